@@ -0,0 +1,53 @@
+//! Example neuro plugin: counts lines, words and characters
+//!
+//! The call arguments arrive as JSON on stdin and the result goes to stdout.
+//! Reading `path` only works when the config grants the plugin that
+//! directory, e.g. `"grants": {"word_count": {"read": ["."]}}`.
+
+use serde::Deserialize;
+use std::io::Read;
+use std::process::ExitCode;
+
+#[derive(Deserialize)]
+struct Args {
+    path: Option<String>,
+    text: Option<String>,
+}
+
+fn main() -> ExitCode {
+    let mut input = String::new();
+    if let Err(e) = std::io::stdin().read_to_string(&mut input) {
+        eprintln!("could not read the arguments: {}", e);
+        return ExitCode::FAILURE;
+    }
+    let args: Args = match serde_json::from_str(&input) {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("invalid arguments: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let text = match (args.path, args.text) {
+        (Some(path), _) => match std::fs::read_to_string(&path) {
+            Ok(text) => text,
+            Err(e) => {
+                eprintln!("could not read {}: {} (is its directory granted?)", path, e);
+                return ExitCode::FAILURE;
+            }
+        },
+        (None, Some(text)) => text,
+        (None, None) => {
+            eprintln!("pass either `path` or `text`");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    println!(
+        "{} lines, {} words, {} characters",
+        text.lines().count(),
+        text.split_whitespace().count(),
+        text.chars().count()
+    );
+    ExitCode::SUCCESS
+}
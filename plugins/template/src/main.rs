@@ -0,0 +1,38 @@
+//! {{project-name}}: a neuro WASM plugin
+//!
+//! Arguments arrive as JSON on stdin (matching `parameters` in plugin.json),
+//! the result is printed on stdout, and a non-zero exit reports the stderr
+//! text to the model as an error.
+
+use serde::Deserialize;
+use std::io::Read;
+use std::process::ExitCode;
+
+#[derive(Deserialize)]
+struct Args {
+    input: String,
+}
+
+fn run(args: Args) -> Result<String, String> {
+    Ok(format!("received: {}", args.input))
+}
+
+fn main() -> ExitCode {
+    let mut input = String::new();
+    let result = std::io::stdin()
+        .read_to_string(&mut input)
+        .map_err(|e| e.to_string())
+        .and_then(|_| serde_json::from_str::<Args>(&input).map_err(|e| format!("invalid arguments: {}", e)))
+        .and_then(run);
+
+    match result {
+        Ok(output) => {
+            println!("{}", output);
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
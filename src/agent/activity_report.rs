@@ -0,0 +1,279 @@
+//! Activity report (`neuro report --since 7d`)
+//!
+//! Sums up a period of work from the session database and its audit log
+//! (`command_executions`): questions asked, files modified, commits and test
+//! commands that went from failing to passing, with a rough estimate of the
+//! time saved. The report is Markdown, ready for a standup or a status mail.
+//!
+//! Test runs and commits reach the audit log through [`audit`], called by the
+//! shell tool and the `/test` and `/commit` commands.
+
+use crate::db::{CommandExecution, Database, DatabaseError, SessionStore};
+use crate::log_warn;
+use crate::tools::BuildRunner;
+use chrono::{DateTime, Duration, Utc};
+use std::collections::{BTreeSet, HashMap};
+use thiserror::Error;
+use uuid::Uuid;
+
+/// Minutes a question answered saves, for the time estimate
+pub const MINUTES_PER_QUESTION: u32 = 5;
+/// Minutes a file modified by the agent saves
+pub const MINUTES_PER_FILE: u32 = 10;
+/// Minutes a commit prepared by the agent saves
+pub const MINUTES_PER_COMMIT: u32 = 3;
+/// Minutes a test fixed saves
+pub const MINUTES_PER_TEST_FIX: u32 = 20;
+
+/// Files and commits listed before the report only gives a count
+const MAX_LISTED: usize = 20;
+
+#[derive(Debug, Error)]
+pub enum ReportError {
+    #[error("invalid period '{0}': use a number and d, h, w or m (7d, 24h, 2w, 30m)")]
+    InvalidPeriod(String),
+
+    #[error(transparent)]
+    Database(#[from] DatabaseError),
+}
+
+/// Length of a period such as `7d`, `24h`, `2w` or `30m`
+pub fn parse_since(text: &str) -> Result<Duration, ReportError> {
+    let invalid = || ReportError::InvalidPeriod(text.to_string());
+    let text = text.trim();
+    let split = text.find(|c: char| !c.is_ascii_digit()).ok_or_else(invalid)?;
+    let amount: i64 = text[..split].parse().map_err(|_| invalid())?;
+    let duration = match &text[split..] {
+        "m" | "min" => Duration::minutes(amount),
+        "h" => Duration::hours(amount),
+        "d" => Duration::days(amount),
+        "w" => Duration::weeks(amount),
+        _ => return Err(invalid()),
+    };
+    if amount == 0 {
+        return Err(invalid());
+    }
+    Ok(duration)
+}
+
+/// The command runs tests (`cargo test`, `pytest`, `/test`...)
+pub fn is_test_command(command: &str) -> bool {
+    command.starts_with("run_tests")
+        || segments(command).any(|segment| BuildRunner::of_command(segment).is_some_and(|runner| runner.tests))
+}
+
+/// The command creates a git commit
+pub fn is_commit_command(command: &str) -> bool {
+    segments(command).any(|segment| {
+        let mut words = segment.split_whitespace();
+        words.next() == Some("git") && words.any(|word| word == "commit")
+    })
+}
+
+/// Commands chained with `&&`, `||` or `;`
+fn segments(command: &str) -> impl Iterator<Item = &str> {
+    command.split(['&', '|', ';']).map(str::trim).filter(|segment| !segment.is_empty())
+}
+
+/// Add a test run or commit to the audit log of the session
+pub async fn audit(store: &SessionStore, command: &str, exit_code: i32) {
+    // Only the outcome is kept: the output already went to the model or the chat
+    let execution = CommandExecution::new(Uuid::new_v4().to_string(), store.session_id.clone(), command)
+        .with_result(exit_code, String::new(), String::new());
+    if let Err(e) = store.db.create_command_execution(&execution).await {
+        log_warn!("⚠️ Could not write audit entry for '{}': {}", command, e);
+    }
+}
+
+/// What the agent did during a period
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ActivityReport {
+    pub since: DateTime<Utc>,
+    pub until: DateTime<Utc>,
+    pub sessions: usize,
+    pub questions: i64,
+    /// Files the tools wrote, without repeats
+    pub files_modified: Vec<String>,
+    /// Commit commands that succeeded
+    pub commits: Vec<String>,
+    /// Test commands that passed after failing in the same session
+    pub tests_fixed: Vec<String>,
+    /// Audited commands that failed
+    pub failed_commands: usize,
+}
+
+impl ActivityReport {
+    /// Gather the activity from `since` until now
+    pub async fn collect(db: &Database, since: DateTime<Utc>) -> Result<Self, ReportError> {
+        let from = since.to_rfc3339();
+        let sessions = db.sessions_active_since(&from).await?;
+        let questions = db.count_questions_since(&from).await?;
+        let mut files = BTreeSet::new();
+        for session in &sessions {
+            let written = db.get_session_files(&session.id).await?;
+            files.extend(written.into_iter().filter(|file| file.is_written()).map(|file| file.path));
+        }
+        let executions = db.command_executions_since(&from).await?;
+        Ok(Self::from_records(
+            since,
+            Utc::now(),
+            sessions.len(),
+            questions,
+            files.into_iter().collect(),
+            &executions,
+        ))
+    }
+
+    /// Report over records already read; `executions` oldest first
+    pub fn from_records(
+        since: DateTime<Utc>,
+        until: DateTime<Utc>,
+        sessions: usize,
+        questions: i64,
+        files_modified: Vec<String>,
+        executions: &[CommandExecution],
+    ) -> Self {
+        let mut commits = Vec::new();
+        let mut tests_fixed = Vec::new();
+        let mut failed_commands = 0;
+        // Last outcome of each test command, per session
+        let mut last_test: HashMap<(&str, &str), bool> = HashMap::new();
+
+        for execution in executions {
+            let passed = execution.exit_code == Some(0);
+            if !passed {
+                failed_commands += 1;
+            }
+            if is_commit_command(&execution.command) && passed {
+                commits.push(execution.command.clone());
+            }
+            if is_test_command(&execution.command) {
+                let key = (execution.session_id.as_str(), execution.command.as_str());
+                if last_test.insert(key, passed) == Some(false) && passed {
+                    tests_fixed.push(execution.command.clone());
+                }
+            }
+        }
+
+        Self {
+            since,
+            until,
+            sessions,
+            questions,
+            files_modified,
+            commits,
+            tests_fixed,
+            failed_commands,
+        }
+    }
+
+    /// Rough minutes saved, from the `MINUTES_PER_*` rates
+    pub fn minutes_saved(&self) -> u32 {
+        self.questions.max(0) as u32 * MINUTES_PER_QUESTION
+            + self.files_modified.len() as u32 * MINUTES_PER_FILE
+            + self.commits.len() as u32 * MINUTES_PER_COMMIT
+            + self.tests_fixed.len() as u32 * MINUTES_PER_TEST_FIX
+    }
+
+    pub fn to_markdown(&self) -> String {
+        let minutes = self.minutes_saved();
+        let mut output = format!(
+            "# Actividad con Neuro\n\n{} → {}\n\n",
+            self.since.format("%Y-%m-%d %H:%M"),
+            self.until.format("%Y-%m-%d %H:%M")
+        );
+        output.push_str("| | |\n|---|---:|\n");
+        output.push_str(&format!("| Sesiones | {} |\n", self.sessions));
+        output.push_str(&format!("| Preguntas | {} |\n", self.questions));
+        output.push_str(&format!("| Archivos modificados | {} |\n", self.files_modified.len()));
+        output.push_str(&format!("| Commits | {} |\n", self.commits.len()));
+        output.push_str(&format!("| Tests arreglados | {} |\n", self.tests_fixed.len()));
+        output.push_str(&format!("| Comandos fallidos | {} |\n", self.failed_commands));
+        output.push_str(&format!(
+            "| Tiempo ahorrado (estimado) | {}h {:02}min |\n",
+            minutes / 60,
+            minutes % 60
+        ));
+
+        list(&mut output, "Archivos modificados", &self.files_modified);
+        list(&mut output, "Commits", &self.commits);
+        list(&mut output, "Tests arreglados", &self.tests_fixed);
+
+        output.push_str(&format!(
+            "\n_Estimación: {} min por pregunta, {} por archivo, {} por commit y {} por test arreglado._\n",
+            MINUTES_PER_QUESTION, MINUTES_PER_FILE, MINUTES_PER_COMMIT, MINUTES_PER_TEST_FIX
+        ));
+        output
+    }
+}
+
+fn list(output: &mut String, title: &str, items: &[String]) {
+    if items.is_empty() {
+        return;
+    }
+    output.push_str(&format!("\n## {}\n\n", title));
+    for item in items.iter().take(MAX_LISTED) {
+        output.push_str(&format!("- `{}`\n", item));
+    }
+    if items.len() > MAX_LISTED {
+        output.push_str(&format!("- … y {} más\n", items.len() - MAX_LISTED));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(session: &str, command: &str, exit_code: i32) -> CommandExecution {
+        CommandExecution::new(Uuid::new_v4().to_string(), session, command).with_result(
+            exit_code,
+            String::new(),
+            String::new(),
+        )
+    }
+
+    #[test]
+    fn test_parse_since_and_commands() {
+        assert_eq!(parse_since("7d").unwrap(), Duration::days(7));
+        assert_eq!(parse_since("24h").unwrap(), Duration::hours(24));
+        assert_eq!(parse_since("2w").unwrap(), Duration::weeks(2));
+        assert!(parse_since("0d").is_err());
+        assert!(parse_since("d").is_err());
+        assert!(parse_since("7y").is_err());
+
+        assert!(is_test_command("RUST_LOG=1 cargo test -p core"));
+        assert!(is_test_command("cd web && npm test"));
+        assert!(!is_test_command("cargo build"));
+        assert!(is_commit_command("git add -A && git commit -m \"fix\""));
+        assert!(!is_commit_command("git log --grep commit"));
+    }
+
+    #[test]
+    fn test_report_counts_fixes_and_commits() {
+        let executions = vec![
+            run("s1", "cargo test", 101),
+            run("s2", "cargo test", 0),
+            run("s1", "cargo test", 0),
+            run("s1", "git commit -m \"fix login\"", 0),
+            run("s1", "git commit -m \"again\"", 1),
+        ];
+        let now = Utc::now();
+        let report = ActivityReport::from_records(
+            now - Duration::days(7),
+            now,
+            2,
+            4,
+            vec!["src/login.rs".to_string()],
+            &executions,
+        );
+        assert_eq!(report.tests_fixed, vec!["cargo test"]);
+        assert_eq!(report.commits.len(), 1);
+        assert_eq!(report.failed_commands, 2);
+        assert_eq!(report.minutes_saved(), 4 * 5 + 10 + 3 + 20);
+
+        let markdown = report.to_markdown();
+        assert!(markdown.contains("| Tests arreglados | 1 |"));
+        assert!(markdown.contains("| Tiempo ahorrado (estimado) | 0h 53min |"));
+        assert!(markdown.contains("- `src/login.rs`"));
+    }
+}
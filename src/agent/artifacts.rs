@@ -0,0 +1,385 @@
+//! Artifacts: large outputs kept out of the chat
+//!
+//! A 600-line test run, a diff, a report, generated docs or an export pasted
+//! into the chat bury the conversation. Outputs longer than
+//! [`ARTIFACT_MIN_LINES`] are stored whole in the `artifacts` table with a
+//! kind, metadata and an expiry from the [`ArtifactsConfig`] retention policy;
+//! the chat shows a summary of at most [`SUMMARY_LINES`] lines, written by the
+//! fast model and headed by the pass/fail counts read from the output, with a
+//! link to `/artifacts open <id>`, which shows the full text in a pager.
+//!
+//! `@artifact:<id>` in a message attaches the artifact back into the context
+//! of that turn. Expired artifacts are removed by the maintenance scheduler
+//! (see [`maintenance`](super::maintenance)); pinned ones are kept.
+
+use crate::db::{Artifact, Database, DatabaseError};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Outputs with more lines than this become artifacts
+pub const ARTIFACT_MIN_LINES: usize = 60;
+/// Lines of the summary shown in the chat
+pub const SUMMARY_LINES: usize = 5;
+/// Characters of the head and of the tail of the output given to the model
+const EXCERPT_CHARS: usize = 3000;
+
+/// The output is too long to paste into the chat
+pub fn is_long(output: &str) -> bool {
+    output.lines().count() > ARTIFACT_MIN_LINES
+}
+
+/// Characters of an artifact attached to a message with `@artifact:<id>`
+const MAX_ATTACHED_CHARS: usize = 12_000;
+/// Artifacts listed by `/artifacts`
+pub const LIST_LIMIT: i64 = 30;
+
+/// What an artifact holds, which sets how long it is kept
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ArtifactKind {
+    /// Output of a command or tool run
+    Output,
+    Diff,
+    /// Analysis, review or audit
+    Report,
+    /// Generated documentation
+    Doc,
+    /// Data exported for use elsewhere
+    Export,
+    /// CPU profile of a `profile_code` run
+    Profile,
+}
+
+impl ArtifactKind {
+    pub const ALL: [ArtifactKind; 6] = [Self::Output, Self::Diff, Self::Report, Self::Doc, Self::Export, Self::Profile];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Output => "output",
+            Self::Diff => "diff",
+            Self::Report => "report",
+            Self::Doc => "doc",
+            Self::Export => "export",
+            Self::Profile => "profile",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|kind| kind.as_str() == value)
+    }
+
+    /// Kind of the output of `source` (a slash command or a tool)
+    pub fn detect(source: &str, content: &str) -> Self {
+        let command = source.trim_start_matches('/').split_whitespace().next().unwrap_or_default();
+        let head = content.trim_start();
+        if head.starts_with("diff --git") || head.starts_with("--- a/") || command == "diff" {
+            return Self::Diff;
+        }
+        match command {
+            "docs" => Self::Doc,
+            "export" => Self::Export,
+            "analyze" | "code-review" | "review" | "dependencies" | "raptor-diagnose" | "tfplan" => Self::Report,
+            _ => Self::Output,
+        }
+    }
+}
+
+/// Retention policy of artifacts (`[artifacts]` in the config)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArtifactsConfig {
+    /// Days command and tool outputs are kept; 0 keeps them until removed
+    #[serde(default = "default_output_days")]
+    pub output_days: u32,
+
+    /// Days diffs, reports and profiles are kept; 0 keeps them until removed
+    #[serde(default = "default_report_days")]
+    pub report_days: u32,
+
+    /// Days generated docs and exports are kept; 0 keeps them until removed
+    #[serde(default)]
+    pub doc_days: u32,
+
+    /// Total size of the unpinned artifacts, in MB; the oldest go first
+    #[serde(default = "default_max_total_mb")]
+    pub max_total_mb: u64,
+
+    /// Minutes between runs of the maintenance scheduler
+    #[serde(default = "default_maintenance_minutes")]
+    pub maintenance_minutes: u64,
+}
+
+fn default_output_days() -> u32 {
+    7
+}
+
+fn default_report_days() -> u32 {
+    30
+}
+
+fn default_max_total_mb() -> u64 {
+    200
+}
+
+fn default_maintenance_minutes() -> u64 {
+    60
+}
+
+impl Default for ArtifactsConfig {
+    fn default() -> Self {
+        Self {
+            output_days: default_output_days(),
+            report_days: default_report_days(),
+            doc_days: 0,
+            max_total_mb: default_max_total_mb(),
+            maintenance_minutes: default_maintenance_minutes(),
+        }
+    }
+}
+
+impl ArtifactsConfig {
+    /// When an artifact of `kind` stored at `now` expires, `None` for never
+    pub fn expires_at(&self, kind: ArtifactKind, now: DateTime<Utc>) -> Option<String> {
+        let days = match kind {
+            ArtifactKind::Output => self.output_days,
+            ArtifactKind::Diff | ArtifactKind::Report | ArtifactKind::Profile => self.report_days,
+            ArtifactKind::Doc | ArtifactKind::Export => self.doc_days,
+        };
+        (days > 0).then(|| (now + Duration::days(days as i64)).to_rfc3339())
+    }
+
+    pub fn max_total_bytes(&self) -> i64 {
+        self.max_total_mb.saturating_mul(1024 * 1024).min(i64::MAX as u64) as i64
+    }
+}
+
+/// Ids referenced as `@artifact:<id>` in `text`
+pub fn references(text: &str) -> Vec<i64> {
+    let mut ids = Vec::new();
+    for (at, _) in text.match_indices("@artifact:") {
+        let digits: String = text[at + "@artifact:".len()..]
+            .trim_start_matches('#')
+            .chars()
+            .take_while(char::is_ascii_digit)
+            .collect();
+        if let Ok(id) = digits.parse() {
+            if !ids.contains(&id) {
+                ids.push(id);
+            }
+        }
+    }
+    ids
+}
+
+/// `text` with the artifacts it references appended, `None` when it
+/// references none; unknown ids are noted rather than failing the turn
+pub async fn attach_references(db: &Database, text: &str) -> Result<Option<String>, DatabaseError> {
+    let ids = references(text);
+    if ids.is_empty() {
+        return Ok(None);
+    }
+    let mut attached = text.to_string();
+    for id in ids {
+        match db.get_artifact(id).await? {
+            Some(artifact) => attached.push_str(&format!("\n\n{}", attachment(&artifact))),
+            None => attached.push_str(&format!("\n\n[Artifact #{} not found]", id)),
+        }
+    }
+    Ok(Some(attached))
+}
+
+/// An artifact as context for the model, cut at [`MAX_ATTACHED_CHARS`]
+fn attachment(artifact: &Artifact) -> String {
+    let mut content: String = artifact.content.chars().take(MAX_ATTACHED_CHARS).collect();
+    if content.len() < artifact.content.len() {
+        content.push_str("\n[... truncated ...]");
+    }
+    format!(
+        "Artifact #{} ({}, from {}):\n```\n{}\n```",
+        artifact.id, artifact.kind, artifact.source, content
+    )
+}
+
+/// Test counts found in a test runner's output
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TestCounts {
+    pub passed: usize,
+    pub failed: usize,
+    pub ignored: usize,
+}
+
+impl TestCounts {
+    /// Sum the result lines of cargo test, pytest, jest/vitest and go test;
+    /// `None` when the output has none
+    pub fn parse(output: &str) -> Option<Self> {
+        let mut counts = TestCounts::default();
+        let mut found = false;
+        for line in output.lines() {
+            let line = line.trim().trim_matches('=').trim();
+            let lower = line.to_lowercase();
+            let is_result = lower.starts_with("test result:")
+                || lower.starts_with("tests:")
+                || ((lower.contains(" passed") || lower.contains(" failed")) && lower.contains(" in "));
+            if is_result {
+                found |= counts.add_line(&lower);
+            } else if let Some(rest) = lower.strip_prefix("--- ") {
+                // go test -v: one line per test
+                if rest.starts_with("pass:") {
+                    counts.passed += 1;
+                    found = true;
+                } else if rest.starts_with("fail:") {
+                    counts.failed += 1;
+                    found = true;
+                } else if rest.starts_with("skip:") {
+                    counts.ignored += 1;
+                    found = true;
+                }
+            }
+        }
+        found.then_some(counts)
+    }
+
+    /// Add the `<n> passed`, `<n> failed`... of a result line
+    fn add_line(&mut self, line: &str) -> bool {
+        let words: Vec<&str> = line
+            .split(|c: char| c.is_whitespace() || c == ',' || c == ';' || c == '.')
+            .filter(|w| !w.is_empty())
+            .collect();
+        let mut found = false;
+        for pair in words.windows(2) {
+            let Ok(n) = pair[0].parse::<usize>() else { continue };
+            match pair[1] {
+                "passed" => self.passed += n,
+                "failed" | "errors" | "error" => self.failed += n,
+                "ignored" | "skipped" => self.ignored += n,
+                _ => continue,
+            }
+            found = true;
+        }
+        found
+    }
+
+    /// `✅ 598 passed · ❌ 2 failed · ⏭️ 1 ignored`, without the zero counts
+    /// other than passed
+    pub fn headline(&self) -> String {
+        let mut parts = vec![format!("✅ {} passed", self.passed)];
+        if self.failed > 0 {
+            parts.push(format!("❌ {} failed", self.failed));
+        }
+        if self.ignored > 0 {
+            parts.push(format!("⏭️ {} ignored", self.ignored));
+        }
+        parts.join(" · ")
+    }
+}
+
+/// Prompt asking the fast model to summarize `output`, produced by `source`
+pub fn summary_prompt(source: &str, output: &str) -> String {
+    let head: String = output.chars().take(EXCERPT_CHARS).collect();
+    let total = output.chars().count();
+    let excerpt = if total > EXCERPT_CHARS * 2 {
+        let tail: String = output.chars().skip(total - EXCERPT_CHARS).collect();
+        format!("{}\n[... {} lines omitted ...]\n{}", head, output.lines().count(), tail)
+    } else {
+        output.to_string()
+    };
+    format!(
+        "Summarize the output of `{}` in at most {} short lines, without preamble. \
+         Give the outcome first, then each failure or error with its file or test name. \
+         Answer in the language of the output.\n\n```\n{}\n```",
+        source, SUMMARY_LINES, excerpt
+    )
+}
+
+/// The model's summary (or, without one, the last lines of the output) cut
+/// to [`SUMMARY_LINES`], headed by the test counts when there are any
+pub fn summarize(output: &str, model_summary: Option<&str>) -> String {
+    let counts = TestCounts::parse(output);
+    let lines: Vec<&str> = match model_summary.map(str::trim).filter(|s| !s.is_empty()) {
+        Some(summary) => summary.lines().filter(|l| !l.trim().is_empty()).collect(),
+        None => {
+            let tail: Vec<&str> = output.lines().rev().filter(|l| !l.trim().is_empty()).take(SUMMARY_LINES).collect();
+            tail.into_iter().rev().collect()
+        }
+    };
+    let budget = if counts.is_some() { SUMMARY_LINES - 1 } else { SUMMARY_LINES };
+    let mut summary: Vec<String> = counts.iter().map(TestCounts::headline).collect();
+    summary.extend(lines.into_iter().take(budget).map(|l| l.trim_end().to_string()));
+    summary.join("\n")
+}
+
+/// The chat text for a stored artifact
+pub fn link(id: i64, output: &str, summary: &str) -> String {
+    format!(
+        "{}\n\n📎 Salida completa ({} líneas): /artifacts open {} · @artifact:{} la añade al contexto",
+        summary,
+        output.lines().count(),
+        id,
+        id
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_counts() {
+        let cargo = "running 3 tests\ntest result: ok. 3 passed; 0 failed; 1 ignored; 0 measured\n\
+                     test result: FAILED. 10 passed; 2 failed; 0 ignored";
+        assert_eq!(TestCounts::parse(cargo), Some(TestCounts { passed: 13, failed: 2, ignored: 1 }));
+
+        let pytest = "=========== 2 failed, 48 passed, 3 skipped in 1.52s ===========";
+        assert_eq!(TestCounts::parse(pytest), Some(TestCounts { passed: 48, failed: 2, ignored: 3 }));
+
+        let jest = "Tests:       1 failed, 5 passed, 6 total";
+        assert_eq!(TestCounts::parse(jest), Some(TestCounts { passed: 5, failed: 1, ignored: 0 }));
+
+        let go = "--- PASS: TestA (0.00s)\n--- FAIL: TestB (0.01s)";
+        assert_eq!(TestCounts::parse(go), Some(TestCounts { passed: 1, failed: 1, ignored: 0 }));
+
+        assert_eq!(TestCounts::parse("Compiling neuro v0.1.0"), None);
+    }
+
+    #[test]
+    fn test_summarize() {
+        let mut output: String = (0..100).map(|i| format!("test t{} ... ok\n", i)).collect();
+        output.push_str("test result: FAILED. 99 passed; 1 failed; 0 ignored\n");
+        assert!(is_long(&output));
+
+        let summary = summarize(&output, Some("1 test failed\n\n- t42: assertion failed\nmore\nmore\nmore\nmore"));
+        assert_eq!(summary.lines().count(), SUMMARY_LINES);
+        assert!(summary.starts_with("✅ 99 passed · ❌ 1 failed\n1 test failed\n- t42"));
+
+        let fallback = summarize(&output, None);
+        assert!(fallback.ends_with("test result: FAILED. 99 passed; 1 failed; 0 ignored"));
+        assert!(link(7, &output, &fallback).contains("(101 líneas): /artifacts open 7"));
+    }
+
+    #[test]
+    fn test_kinds_and_retention() {
+        assert_eq!(ArtifactKind::detect("/shell git diff", "diff --git a/x b/x"), ArtifactKind::Diff);
+        assert_eq!(ArtifactKind::detect("/analyze src", "..."), ArtifactKind::Report);
+        assert_eq!(ArtifactKind::detect("/shell cargo test", "running"), ArtifactKind::Output);
+
+        let policy = ArtifactsConfig::default();
+        let now = DateTime::parse_from_rfc3339("2026-03-01T00:00:00+00:00").unwrap().with_timezone(&Utc);
+        assert_eq!(policy.expires_at(ArtifactKind::Output, now).as_deref(), Some("2026-03-08T00:00:00+00:00"));
+        assert_eq!(policy.expires_at(ArtifactKind::Doc, now), None);
+        assert_eq!(ArtifactKind::parse("profile"), Some(ArtifactKind::Profile));
+        assert_eq!(policy.expires_at(ArtifactKind::Profile, now), policy.expires_at(ArtifactKind::Report, now));
+    }
+
+    #[tokio::test]
+    async fn test_attach_references() {
+        let db = Database::in_memory().await.unwrap();
+        db.create_session(&crate::db::Session::new("s1", "fast", "heavy")).await.unwrap();
+        let id = db.add_artifact(&Artifact::new("s1", "diff", "git diff", "+fn main() {}", "")).await.unwrap();
+
+        assert_eq!(references("see @artifact:3, @artifact:#4 and @artifact:3"), vec![3, 4]);
+        assert_eq!(attach_references(&db, "no refs").await.unwrap(), None);
+        let text = format!("why does @artifact:{} fail? and @artifact:99", id);
+        let attached = attach_references(&db, &text).await.unwrap().unwrap();
+        assert!(attached.contains(&format!("Artifact #{} (diff, from git diff):\n```\n+fn main() {{}}\n```", id)));
+        assert!(attached.ends_with("[Artifact #99 not found]"));
+    }
+}
@@ -9,6 +9,7 @@ use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::Instant;
 use super::monitoring::{LatencyPercentiles, MetricsCollector};
+use crate::raptor::feedback::NegativePair;
 
 /// Estado del benchmark en relación a la baseline
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -302,6 +303,80 @@ impl std::fmt::Display for BenchmarkSummary {
     }
 }
 
+/// Caso de evaluación de la recuperación: fragmentos que el usuario marcó
+/// como irrelevantes para una consulta (`/sources irrelevant`)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RetrievalCase {
+    pub query: String,
+    pub irrelevant: Vec<String>,
+}
+
+impl RetrievalCase {
+    /// Agrupar los pares negativos por consulta
+    pub fn from_feedback(pairs: &[NegativePair]) -> Vec<Self> {
+        let mut cases: Vec<Self> = Vec::new();
+        for pair in pairs {
+            match cases.iter_mut().find(|case| case.query == pair.query) {
+                Some(case) => case.irrelevant.push(pair.chunk_id.clone()),
+                None => cases.push(Self {
+                    query: pair.query.clone(),
+                    irrelevant: vec![pair.chunk_id.clone()],
+                }),
+            }
+        }
+        cases
+    }
+}
+
+/// Resultado de repetir los casos de recuperación
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RetrievalBenchResult {
+    /// Casos evaluados
+    pub cases: usize,
+    /// Casos que aún recuperan algún fragmento marcado como irrelevante
+    pub leaked: usize,
+}
+
+impl RetrievalBenchResult {
+    /// Proporción de casos con fragmentos irrelevantes (0 = todos resueltos)
+    pub fn leak_rate(&self) -> f64 {
+        if self.cases == 0 {
+            return 0.0;
+        }
+        self.leaked as f64 / self.cases as f64
+    }
+
+    /// Formatear resultado para display
+    pub fn format(&self) -> String {
+        format!(
+            "retrieval_feedback: {}/{} consultas aún recuperan fragmentos irrelevantes ({:.0}%)",
+            self.leaked,
+            self.cases,
+            self.leak_rate() * 100.0
+        )
+    }
+}
+
+/// Repetir los casos con `retrieve`, que devuelve los ids de los fragmentos
+/// recuperados para una consulta
+pub async fn bench_retrieval<F, Fut>(cases: &[RetrievalCase], mut retrieve: F) -> Result<RetrievalBenchResult>
+where
+    F: FnMut(String) -> Fut,
+    Fut: std::future::Future<Output = Result<Vec<String>>>,
+{
+    let mut leaked = 0;
+    for case in cases {
+        let retrieved = retrieve(case.query.clone()).await?;
+        if retrieved.iter().any(|id| case.irrelevant.contains(id)) {
+            leaked += 1;
+        }
+    }
+    Ok(RetrievalBenchResult {
+        cases: cases.len(),
+        leaked,
+    })
+}
+
 /// Baselines predefinidas para operaciones comunes
 pub mod presets {
     use super::BenchmarkBaseline;
@@ -537,4 +612,24 @@ mod tests {
         assert_eq!(cache.target_ms, 1);
         assert_eq!(cache.regression_threshold_percent, 50.0);
     }
+
+    #[tokio::test]
+    async fn test_retrieval_bench_replays_feedback() {
+        let pair = |query: &str, chunk_id: &str| NegativePair {
+            query: query.to_string(),
+            chunk_id: chunk_id.to_string(),
+            source: "README.md".to_string(),
+        };
+        let cases = RetrievalCase::from_feedback(&[pair("ci", "c1"), pair("ci", "c2"), pair("router", "c3")]);
+        assert_eq!(cases.len(), 2);
+        assert_eq!(cases[0].irrelevant, vec!["c1", "c2"]);
+
+        let result = bench_retrieval(&cases, |query| async move {
+            Ok(if query == "ci" { vec!["c4".to_string()] } else { vec!["c3".to_string()] })
+        })
+        .await
+        .unwrap();
+        assert_eq!(result, RetrievalBenchResult { cases: 2, leaked: 1 });
+        assert_eq!(result.leak_rate(), 0.5);
+    }
 }
@@ -0,0 +1,190 @@
+//! Model capability registry
+//!
+//! Not every model supports native tool calls or constrained JSON output. The
+//! registry combines a built-in table of known model families with the
+//! `model_capabilities` overrides from the config; the orchestrators fall back
+//! to XML tool calls and JSON extracted from the text when one is missing.
+
+use crate::config::CapabilityOverride;
+use std::collections::HashMap;
+
+/// Feature a request may depend on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Capability {
+    Tools,
+    JsonMode,
+}
+
+/// Capabilities of a single model
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ModelCapabilities {
+    pub tools: bool,
+    pub json_mode: bool,
+    /// Context window in tokens
+    pub context_window: usize,
+}
+
+impl Default for ModelCapabilities {
+    /// Unknown models: keep the historical assumption of tool support
+    fn default() -> Self {
+        Self {
+            tools: true,
+            json_mode: true,
+            context_window: 8192,
+        }
+    }
+}
+
+impl ModelCapabilities {
+    const fn new(tools: bool, json_mode: bool, context_window: usize) -> Self {
+        Self {
+            tools,
+            json_mode,
+            context_window,
+        }
+    }
+
+    pub fn supports(&self, capability: Capability) -> bool {
+        match capability {
+            Capability::Tools => self.tools,
+            Capability::JsonMode => self.json_mode,
+        }
+    }
+
+    fn apply(&mut self, o: &CapabilityOverride) {
+        if let Some(tools) = o.tools {
+            self.tools = tools;
+        }
+        if let Some(json_mode) = o.json_mode {
+            self.json_mode = json_mode;
+        }
+        if let Some(context_window) = o.context_window {
+            self.context_window = context_window;
+        }
+    }
+}
+
+/// Built-in table, matched by prefix against the model name without its tag.
+/// More specific prefixes must come first.
+const BUILTIN: &[(&str, ModelCapabilities)] = &[
+    ("qwen3", ModelCapabilities::new(true, true, 40_960)),
+    ("qwen2.5-coder", ModelCapabilities::new(true, true, 32_768)),
+    ("qwen2.5vl", ModelCapabilities::new(false, true, 128_000)),
+    ("qwen2.5", ModelCapabilities::new(true, true, 32_768)),
+    ("llama3.2-vision", ModelCapabilities::new(false, true, 128_000)),
+    ("llama3.1", ModelCapabilities::new(true, true, 128_000)),
+    ("llama3.2", ModelCapabilities::new(true, true, 128_000)),
+    ("llama3.3", ModelCapabilities::new(true, true, 128_000)),
+    ("llama3", ModelCapabilities::new(false, true, 8_192)),
+    ("llava", ModelCapabilities::new(false, true, 4_096)),
+    ("mistral-nemo", ModelCapabilities::new(true, true, 128_000)),
+    ("mistral", ModelCapabilities::new(true, true, 32_768)),
+    ("gemma3", ModelCapabilities::new(false, true, 128_000)),
+    ("gemma", ModelCapabilities::new(false, true, 8_192)),
+    ("phi4", ModelCapabilities::new(false, true, 16_384)),
+    ("phi3", ModelCapabilities::new(false, true, 4_096)),
+    ("deepseek-r1", ModelCapabilities::new(false, true, 128_000)),
+    ("codellama", ModelCapabilities::new(false, true, 16_384)),
+    ("gpt-4o", ModelCapabilities::new(true, true, 128_000)),
+    ("gpt-4", ModelCapabilities::new(true, true, 128_000)),
+    ("claude", ModelCapabilities::new(true, false, 200_000)),
+    ("llama-3", ModelCapabilities::new(true, true, 128_000)),
+];
+
+/// Capability lookup: config overrides first, then the built-in table
+#[derive(Debug, Clone, Default)]
+pub struct CapabilityRegistry {
+    overrides: HashMap<String, CapabilityOverride>,
+}
+
+impl CapabilityRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registry with overrides keyed by full model name ("qwen3:8b") or family ("qwen3")
+    pub fn with_overrides(overrides: HashMap<String, CapabilityOverride>) -> Self {
+        Self { overrides }
+    }
+
+    /// Resolve the capabilities of `model`
+    pub fn lookup(&self, model: &str) -> ModelCapabilities {
+        let model = model.to_lowercase();
+        // "library/qwen3:8b" -> "qwen3"
+        let family = model
+            .rsplit('/')
+            .next()
+            .unwrap_or(&model)
+            .split(':')
+            .next()
+            .unwrap_or(&model);
+
+        let mut caps = BUILTIN
+            .iter()
+            .find(|(prefix, _)| family.starts_with(prefix))
+            .map(|(_, caps)| *caps)
+            .unwrap_or_default();
+
+        // Family override first so a full-name override wins over it
+        if let Some(o) = self.overrides.get(family) {
+            caps.apply(o);
+        }
+        if family != model {
+            if let Some(o) = self.overrides.get(&model) {
+                caps.apply(o);
+            }
+        }
+
+        caps
+    }
+
+    pub fn supports(&self, model: &str, capability: Capability) -> bool {
+        self.lookup(model).supports(capability)
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builtin_lookup_by_family() {
+        let registry = CapabilityRegistry::new();
+        assert!(registry.supports("qwen3:8b", Capability::Tools));
+        assert!(registry.supports("qwen2.5-coder:7b", Capability::Tools));
+        assert!(!registry.supports("gemma2:2b", Capability::Tools));
+        assert_eq!(registry.lookup("qwen3:0.6b").context_window, 40_960);
+    }
+
+    #[test]
+    fn test_unknown_model_uses_defaults() {
+        let registry = CapabilityRegistry::new();
+        assert_eq!(registry.lookup("my-finetune:latest"), ModelCapabilities::default());
+    }
+
+    #[test]
+    fn test_overrides_by_family_and_full_name() {
+        let mut overrides = HashMap::new();
+        overrides.insert(
+            "qwen3".to_string(),
+            CapabilityOverride {
+                tools: Some(false),
+                context_window: Some(16_384),
+                ..Default::default()
+            },
+        );
+        overrides.insert(
+            "qwen3:8b".to_string(),
+            CapabilityOverride {
+                tools: Some(true),
+                ..Default::default()
+            },
+        );
+        let registry = CapabilityRegistry::with_overrides(overrides);
+
+        assert!(!registry.supports("qwen3:0.6b", Capability::Tools));
+        assert!(registry.supports("qwen3:8b", Capability::Tools));
+        assert_eq!(registry.lookup("qwen3:8b").context_window, 16_384);
+    }
+}
@@ -0,0 +1,173 @@
+//! Confidence indicator of an answer
+//!
+//! Two signals decide it: how close the chunks retrieved for the question were
+//! (cosine similarity of the RAPTOR hits) and how many of the files the answer
+//! cites exist and have the cited lines (see [`super::grounding`]). The label
+//! under the answer is only a hint for when to double-check; `/why` shows the
+//! numbers behind it.
+
+use super::response::Citation;
+
+/// Best chunk at least this similar: retrieval found the topic
+const STRONG_TOP_SCORE: f32 = 0.6;
+/// ...and the retrieved chunks on average at least this similar
+const STRONG_MEAN_SCORE: f32 = 0.45;
+/// Best chunk below this: retrieval found nothing close
+const WEAK_TOP_SCORE: f32 = 0.4;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfidenceLevel {
+    High,
+    Medium,
+    Low,
+}
+
+impl ConfidenceLevel {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::High => "alta",
+            Self::Medium => "media",
+            Self::Low => "baja",
+        }
+    }
+
+    fn icon(&self) -> &'static str {
+        match self {
+            Self::High => "🟢",
+            Self::Medium => "🟡",
+            Self::Low => "🔴",
+        }
+    }
+}
+
+/// Similarity of the chunks retrieved for the question
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RetrievalScores {
+    pub chunks: usize,
+    pub top: f32,
+    pub mean: f32,
+}
+
+impl RetrievalScores {
+    /// `None` when nothing was retrieved
+    pub fn from_scores(scores: &[f32]) -> Option<Self> {
+        let top = scores.iter().copied().reduce(f32::max)?;
+        Some(Self {
+            chunks: scores.len(),
+            top,
+            mean: scores.iter().sum::<f32>() / scores.len() as f32,
+        })
+    }
+
+    fn is_strong(&self) -> bool {
+        self.top >= STRONG_TOP_SCORE && self.mean >= STRONG_MEAN_SCORE
+    }
+
+    fn is_weak(&self) -> bool {
+        self.top < WEAK_TOP_SCORE
+    }
+}
+
+/// Confidence of one answer and the numbers it came from
+#[derive(Debug, Clone, PartialEq)]
+pub struct AnswerConfidence {
+    pub level: ConfidenceLevel,
+    /// `None` when the answer was not built on retrieved chunks
+    pub retrieval: Option<RetrievalScores>,
+    /// Distinct files the answer cites
+    pub sources: usize,
+    /// Cited files whose citations all checked out
+    pub verified_sources: usize,
+}
+
+impl AnswerConfidence {
+    /// Rate an answer from the similarity of its retrieved chunks and its citations
+    pub fn assess(scores: &[f32], citations: &[Citation]) -> Self {
+        let mut files: Vec<(&str, bool)> = Vec::new();
+        for citation in citations {
+            match files.iter_mut().find(|(path, _)| *path == citation.path) {
+                Some((_, verified)) => *verified &= citation.verified,
+                None => files.push((&citation.path, citation.verified)),
+            }
+        }
+        let sources = files.len();
+        let verified_sources = files.iter().filter(|(_, verified)| *verified).count();
+        let retrieval = RetrievalScores::from_scores(scores);
+
+        let level = if sources == 0 {
+            match retrieval {
+                Some(retrieval) if retrieval.is_strong() => ConfidenceLevel::Medium,
+                _ => ConfidenceLevel::Low,
+            }
+        } else if verified_sources * 2 < sources || retrieval.is_some_and(|r| r.is_weak()) {
+            ConfidenceLevel::Low
+        } else if verified_sources == sources && sources >= 2 {
+            ConfidenceLevel::High
+        } else {
+            ConfidenceLevel::Medium
+        };
+
+        Self { level, retrieval, sources, verified_sources }
+    }
+
+    /// "confianza alta, 4 fuentes"
+    pub fn label(&self) -> String {
+        let sources = match self.sources {
+            0 => "sin fuentes".to_string(),
+            1 => "1 fuente".to_string(),
+            n => format!("{} fuentes", n),
+        };
+        format!("confianza {}, {}", self.level.as_str(), sources)
+    }
+
+    /// `answer` with the indicator under it
+    pub fn annotate(&self, answer: &str) -> String {
+        format!("{}\n\n{} {}", answer.trim_end(), self.level.icon(), self.label())
+    }
+
+    /// The numbers behind the level, for /why
+    pub fn details(&self) -> String {
+        let retrieval = match &self.retrieval {
+            Some(r) => format!("{} fragmentos, máx {:.2}, media {:.2}", r.chunks, r.top, r.mean),
+            None => "sin fragmentos recuperados".to_string(),
+        };
+        format!(
+            "Confianza: {}\n  Fuentes: {} citadas, {} verificadas\n  Similitud: {}",
+            self.level.as_str(),
+            self.sources,
+            self.verified_sources,
+            retrieval
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cite(path: &str, verified: bool) -> Citation {
+        Citation { path: path.to_string(), line: None, verified }
+    }
+
+    #[test]
+    fn test_assess_levels() {
+        let strong = [0.72, 0.61, 0.5, 0.44];
+        let cited = [cite("src/a.rs", true), cite("src/b.rs", true), cite("src/a.rs", true)];
+        let high = AnswerConfidence::assess(&strong, &cited);
+        assert_eq!(high.level, ConfidenceLevel::High);
+        assert_eq!(high.label(), "confianza alta, 2 fuentes");
+        assert_eq!(high.retrieval.unwrap().chunks, 4);
+
+        let none = AnswerConfidence::assess(&[], &[]);
+        assert_eq!(none.label(), "confianza baja, sin fuentes");
+        assert!(none.annotate("Respuesta\n").ends_with("Respuesta\n\n🔴 confianza baja, sin fuentes"));
+        assert_eq!(AnswerConfidence::assess(&strong, &[]).level, ConfidenceLevel::Medium);
+
+        // One bad citation of a file taints it; a weak retrieval sinks the answer
+        let mixed = [cite("src/a.rs", true), cite("src/a.rs", false), cite("src/b.rs", true)];
+        let medium = AnswerConfidence::assess(&strong, &mixed);
+        assert_eq!((medium.level, medium.verified_sources), (ConfidenceLevel::Medium, 1));
+        assert_eq!(AnswerConfidence::assess(&[0.3, 0.2], &cited).level, ConfidenceLevel::Low);
+        assert!(medium.details().contains("2 citadas, 1 verificadas"));
+    }
+}
@@ -0,0 +1,580 @@
+//! Merge conflict resolution
+//!
+//! Parses the conflict markers git leaves in unmerged files into hunks with
+//! both sides (and the merge base, with `merge.conflictStyle=diff3`). Each
+//! hunk gets a resolution — ours, theirs, both, or a version proposed by the
+//! heavy model — and accepted resolutions are written through
+//! [`DiffPreview`]. Files with every hunk resolved are staged.
+
+use super::diff_preview::DiffPreview;
+use regex::Regex;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::LazyLock;
+use thiserror::Error;
+
+/// Lines of surrounding code sent to the model with each hunk
+const CONTEXT_LINES: usize = 6;
+/// Lines shown per side when listing a hunk
+const MAX_SIDE_LINES: usize = 30;
+
+static RESOLUTION_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?s)<<<RESOLUTION (\d+)>>>[ \t]*\r?\n(.*?)\r?\n?<<<END>>>").unwrap()
+});
+
+#[derive(Debug, Error)]
+pub enum ConflictError {
+    #[error("No conflicted files")]
+    NoConflicts,
+    #[error("No conflict resolution in progress (run /resolve)")]
+    NoPending,
+    #[error("Unknown hunk #{0}")]
+    UnknownHunk(usize),
+    #[error("Hunk #{0} has no proposal to accept")]
+    NoProposal(usize),
+    #[error("No accepted resolutions to apply")]
+    NothingAccepted,
+    #[error("Git command failed: {0}")]
+    Git(String),
+    #[error("IO error on {0}: {1}")]
+    Io(PathBuf, std::io::Error),
+    #[error("{0}")]
+    Write(String),
+}
+
+/// Where a resolution comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResolutionSource {
+    Ours,
+    Theirs,
+    /// Ours followed by theirs
+    Both,
+    Model,
+}
+
+impl ResolutionSource {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Ours => "ours",
+            Self::Theirs => "theirs",
+            Self::Both => "both",
+            Self::Model => "model",
+        }
+    }
+}
+
+/// Replacement for a conflict block
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Resolution {
+    pub source: ResolutionSource,
+    pub content: String,
+    /// Model proposals are applied only once accepted
+    pub accepted: bool,
+}
+
+/// One `<<<<<<<` ... `>>>>>>>` block
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConflictHunk {
+    /// Number shown to the user, unique across files
+    pub id: usize,
+    /// Line of the `<<<<<<<` marker (1-indexed)
+    pub start_line: usize,
+    /// Line of the `>>>>>>>` marker
+    pub end_line: usize,
+    pub ours_label: String,
+    pub theirs_label: String,
+    pub ours: String,
+    pub base: Option<String>,
+    pub theirs: String,
+    pub resolution: Option<Resolution>,
+}
+
+impl ConflictHunk {
+    fn is_resolved(&self) -> bool {
+        self.resolution.as_ref().is_some_and(|r| r.accepted)
+    }
+}
+
+/// An unmerged file and its conflict blocks
+#[derive(Debug, Clone)]
+pub struct ConflictFile {
+    pub path: PathBuf,
+    /// Path relative to the repository, for display and `git add`
+    pub display: String,
+    pub content: String,
+    pub hunks: Vec<ConflictHunk>,
+}
+
+impl ConflictFile {
+    /// Content with accepted resolutions in place of their conflict blocks
+    pub fn resolved_content(&self) -> String {
+        let lines = split_lines(&self.content);
+        // Model proposals come without '\r'; give them the file's line endings
+        let crlf = self.content.contains("\r\n");
+        let mut output: Vec<String> = Vec::with_capacity(lines.len());
+        let mut next = 1;
+        for hunk in self.hunks.iter().filter(|h| h.is_resolved()) {
+            output.extend(lines[next - 1..hunk.start_line - 1].iter().map(|line| line.to_string()));
+            if let Some(resolution) = &hunk.resolution {
+                output.extend(split_lines(&resolution.content).into_iter().map(|line| {
+                    if crlf && !line.ends_with('\r') {
+                        format!("{}\r", line)
+                    } else {
+                        line.to_string()
+                    }
+                }));
+            }
+            next = hunk.end_line + 1;
+        }
+        output.extend(lines.get(next - 1..).unwrap_or_default().iter().map(|line| line.to_string()));
+
+        let mut content = output.join("\n");
+        if self.content.ends_with('\n') && !content.is_empty() {
+            content.push('\n');
+        }
+        content
+    }
+
+    /// Code around a hunk, markers excluded
+    fn surrounding(&self, hunk: &ConflictHunk) -> (String, String) {
+        let lines: Vec<&str> = self.content.lines().collect();
+        let before = lines[hunk.start_line.saturating_sub(1 + CONTEXT_LINES)..hunk.start_line - 1].join("\n");
+        let after_end = (hunk.end_line + CONTEXT_LINES).min(lines.len());
+        let after = lines.get(hunk.end_line..after_end).unwrap_or_default().join("\n");
+        (before, after)
+    }
+}
+
+/// Lines split on '\n' only: a '\r' stays with its line, so CRLF files are
+/// written back as they were
+fn split_lines(content: &str) -> Vec<&str> {
+    if content.is_empty() {
+        return Vec::new();
+    }
+    content.strip_suffix('\n').unwrap_or(content).split('\n').collect()
+}
+
+/// Conflict blocks of a file; ids are numbered from 1 in file order
+pub fn parse_hunks(content: &str) -> Vec<ConflictHunk> {
+    let mut hunks = Vec::new();
+    let lines = split_lines(content);
+    let mut i = 0;
+    while i < lines.len() {
+        let Some(ours_label) = marker(lines[i], "<<<<<<<") else {
+            i += 1;
+            continue;
+        };
+        let start = i;
+        let (mut ours, mut base, mut theirs) = (Vec::new(), None::<Vec<&str>>, Vec::new());
+        let mut section = 0;
+        let mut end = None;
+        i += 1;
+        while i < lines.len() {
+            let line = lines[i];
+            if marker(line, "<<<<<<<").is_some() {
+                break;
+            } else if section == 0 && marker(line, "|||||||").is_some() {
+                base = Some(Vec::new());
+                section = 1;
+            } else if section < 2 && line.trim_end() == "=======" {
+                section = 2;
+            } else if let (2, Some(theirs_label)) = (section, marker(line, ">>>>>>>")) {
+                end = Some((i, theirs_label));
+                break;
+            } else {
+                match section {
+                    0 => ours.push(line),
+                    1 => base.get_or_insert_with(Vec::new).push(line),
+                    _ => theirs.push(line),
+                }
+            }
+            i += 1;
+        }
+        // Unterminated markers are left alone
+        let Some((end, theirs_label)) = end else {
+            continue;
+        };
+        hunks.push(ConflictHunk {
+            id: hunks.len() + 1,
+            start_line: start + 1,
+            end_line: end + 1,
+            ours_label: ours_label.to_string(),
+            theirs_label: theirs_label.to_string(),
+            ours: ours.join("\n"),
+            base: base.map(|b| b.join("\n")),
+            theirs: theirs.join("\n"),
+            resolution: None,
+        });
+        i = end + 1;
+    }
+    hunks
+}
+
+/// Label after a conflict marker, `""` when there is none
+fn marker<'a>(line: &'a str, prefix: &str) -> Option<&'a str> {
+    let line = line.strip_suffix('\r').unwrap_or(line);
+    let rest = line.strip_prefix(prefix)?;
+    if rest.is_empty() || rest.starts_with(' ') {
+        Some(rest.trim())
+    } else {
+        None
+    }
+}
+
+/// Files written by [`ConflictSet::apply`]
+#[derive(Debug, Clone, Default)]
+pub struct ApplySummary {
+    /// Files with every conflict resolved, now staged
+    pub staged: Vec<String>,
+    /// Files written that still have unresolved hunks
+    pub partial: Vec<String>,
+    pub hunks: usize,
+}
+
+/// The conflicts being resolved, kept in the agent state between `/resolve` calls
+#[derive(Debug, Clone)]
+pub struct ConflictSet {
+    pub root: PathBuf,
+    pub files: Vec<ConflictFile>,
+}
+
+impl ConflictSet {
+    /// Unmerged files of the repository at `working_dir`
+    pub fn scan(working_dir: &Path) -> Result<Self, ConflictError> {
+        let root = git(working_dir, &["rev-parse", "--show-toplevel"])?.trim().to_string();
+        let root = PathBuf::from(root);
+        let mut files = Vec::new();
+        for name in git(&root, &["diff", "--name-only", "--diff-filter=U"])?.lines() {
+            let path = root.join(name);
+            // Binary or deleted-on-one-side conflicts have no markers to resolve
+            let Ok(content) = std::fs::read_to_string(&path) else {
+                continue;
+            };
+            let hunks = parse_hunks(&content);
+            if !hunks.is_empty() {
+                files.push(ConflictFile { path, display: name.to_string(), content, hunks });
+            }
+        }
+        if files.is_empty() {
+            return Err(ConflictError::NoConflicts);
+        }
+        let mut set = Self { root, files };
+        set.renumber();
+        Ok(set)
+    }
+
+    fn renumber(&mut self) {
+        let mut id = 0;
+        for hunk in self.files.iter_mut().flat_map(|f| f.hunks.iter_mut()) {
+            id += 1;
+            hunk.id = id;
+        }
+    }
+
+    pub fn hunk_count(&self) -> usize {
+        self.files.iter().map(|f| f.hunks.len()).sum()
+    }
+
+    fn hunk_mut(&mut self, id: usize) -> Result<&mut ConflictHunk, ConflictError> {
+        self.files
+            .iter_mut()
+            .flat_map(|f| f.hunks.iter_mut())
+            .find(|h| h.id == id)
+            .ok_or(ConflictError::UnknownHunk(id))
+    }
+
+    /// Resolve a hunk with one side, or both
+    pub fn choose(&mut self, id: usize, source: ResolutionSource) -> Result<(), ConflictError> {
+        if source == ResolutionSource::Model {
+            return self.accept(&[id]);
+        }
+        let hunk = self.hunk_mut(id)?;
+        let content = match source {
+            ResolutionSource::Ours => hunk.ours.clone(),
+            ResolutionSource::Theirs => hunk.theirs.clone(),
+            ResolutionSource::Both => [hunk.ours.as_str(), hunk.theirs.as_str()]
+                .iter()
+                .filter(|s| !s.is_empty())
+                .copied()
+                .collect::<Vec<_>>()
+                .join("\n"),
+            ResolutionSource::Model => unreachable!("handled above"),
+        };
+        hunk.resolution = Some(Resolution { source, content, accepted: true });
+        Ok(())
+    }
+
+    /// Accept model proposals; an empty list accepts all of them
+    pub fn accept(&mut self, ids: &[usize]) -> Result<(), ConflictError> {
+        if ids.is_empty() {
+            for hunk in self.files.iter_mut().flat_map(|f| f.hunks.iter_mut()) {
+                if let Some(resolution) = hunk.resolution.as_mut() {
+                    resolution.accepted = true;
+                }
+            }
+            return Ok(());
+        }
+        for &id in ids {
+            let hunk = self.hunk_mut(id)?;
+            let resolution = hunk.resolution.as_mut().ok_or(ConflictError::NoProposal(id))?;
+            resolution.accepted = true;
+        }
+        Ok(())
+    }
+
+    /// Take the heavy model's `<<<RESOLUTION n>>>` blocks as proposals
+    pub fn record_proposals(&mut self, response: &str) -> usize {
+        let mut recorded = 0;
+        for captures in RESOLUTION_RE.captures_iter(response) {
+            let Ok(id) = captures[1].parse::<usize>() else {
+                continue;
+            };
+            let content = captures[2].to_string();
+            if let Ok(hunk) = self.hunk_mut(id) {
+                hunk.resolution = Some(Resolution { source: ResolutionSource::Model, content, accepted: false });
+                recorded += 1;
+            }
+        }
+        recorded
+    }
+
+    /// Prompt asking the heavy model for one merged version per hunk
+    pub fn prompt(&self) -> String {
+        let mut prompt = String::from(
+            "Resuelve estos conflictos de merge. Para cada conflicto combina la intención de \
+             ambos lados (no elijas uno a ciegas) y conserva el estilo del código que lo rodea. \
+             Responde SOLO con un bloque por conflicto, con el código final sin marcadores:\n\
+             <<<RESOLUTION n>>>\n<código resuelto>\n<<<END>>>\n",
+        );
+        for file in &self.files {
+            for hunk in &file.hunks {
+                let (before, after) = file.surrounding(hunk);
+                prompt.push_str(&format!(
+                    "\n## Conflicto {} — {} (línea {})\nCódigo anterior:\n```\n{}\n```\n\
+                     Lado A ({}):\n```\n{}\n```\n",
+                    hunk.id, file.display, hunk.start_line, before, hunk.ours_label, hunk.ours
+                ));
+                if let Some(base) = &hunk.base {
+                    prompt.push_str(&format!("Versión base:\n```\n{}\n```\n", base));
+                }
+                prompt.push_str(&format!(
+                    "Lado B ({}):\n```\n{}\n```\nCódigo posterior:\n```\n{}\n```\n",
+                    hunk.theirs_label, hunk.theirs, after
+                ));
+            }
+        }
+        prompt
+    }
+
+    /// Query for project context about the conflicted code
+    pub fn context_query(&self) -> String {
+        let files: Vec<&str> = self.files.iter().map(|f| f.display.as_str()).collect();
+        let first = self
+            .files
+            .iter()
+            .flat_map(|f| f.hunks.iter())
+            .map(|h| h.ours.lines().take(3).collect::<Vec<_>>().join(" "))
+            .next()
+            .unwrap_or_default();
+        format!("{} {}", files.join(" "), first)
+    }
+
+    /// Every hunk with both sides and its resolution status
+    pub fn format(&self) -> String {
+        let mut output = format!(
+            "⚔️ {} conflictos en {} archivos\n",
+            self.hunk_count(),
+            self.files.len()
+        );
+        for file in &self.files {
+            output.push_str(&format!("\n📄 {} ({} conflictos)\n", file.display, file.hunks.len()));
+            for hunk in &file.hunks {
+                output.push_str(&format!("\n[#{}] líneas {}-{} · {}\n", hunk.id, hunk.start_line, hunk.end_line, status(hunk)));
+                push_side(&mut output, "<", &hunk.ours_label, &hunk.ours);
+                if let Some(base) = &hunk.base {
+                    push_side(&mut output, "|", "base", base);
+                }
+                push_side(&mut output, ">", &hunk.theirs_label, &hunk.theirs);
+                if let Some(resolution) = hunk.resolution.as_ref().filter(|r| r.source == ResolutionSource::Model) {
+                    push_side(&mut output, "=", "propuesta", &resolution.content);
+                }
+            }
+        }
+        output
+    }
+
+    /// Diffs of the files as they would be written
+    pub fn previews(&self) -> Vec<DiffPreview> {
+        self.files
+            .iter()
+            .filter(|f| f.hunks.iter().any(|h| h.is_resolved()))
+            .map(|f| DiffPreview::new(f.path.clone(), f.content.clone(), f.resolved_content()))
+            .collect()
+    }
+
+    /// Write accepted resolutions, stage fully resolved files and drop them from the set
+    pub fn apply(&mut self) -> Result<ApplySummary, ConflictError> {
+        let previews = self.previews();
+        if previews.is_empty() {
+            return Err(ConflictError::NothingAccepted);
+        }
+        let mut summary = ApplySummary::default();
+        // Nothing is written if any file was edited since it was parsed
+        for preview in &previews {
+            preview.check_unchanged().map_err(|e| ConflictError::Write(e.to_string()))?;
+        }
+        for preview in &previews {
+            preview.apply().map_err(|e| ConflictError::Write(e.to_string()))?;
+        }
+
+        let root = self.root.clone();
+        let mut remaining = Vec::new();
+        for mut file in std::mem::take(&mut self.files) {
+            let resolved = file.hunks.iter().filter(|h| h.is_resolved()).count();
+            summary.hunks += resolved;
+            if resolved == file.hunks.len() {
+                git(&root, &["add", "--", &file.display])?;
+                summary.staged.push(file.display);
+            } else {
+                if resolved > 0 {
+                    file.content = file.resolved_content();
+                    file.hunks = parse_hunks(&file.content);
+                    summary.partial.push(file.display.clone());
+                }
+                remaining.push(file);
+            }
+        }
+        self.files = remaining;
+        self.renumber();
+        Ok(summary)
+    }
+}
+
+fn status(hunk: &ConflictHunk) -> String {
+    match &hunk.resolution {
+        None => "sin resolver".to_string(),
+        Some(r) if r.accepted => format!("✅ {}", r.source.label()),
+        Some(_) => "💡 propuesta pendiente".to_string(),
+    }
+}
+
+fn push_side(output: &mut String, mark: &str, label: &str, content: &str) {
+    let lines: Vec<&str> = content.lines().collect();
+    output.push_str(&format!("  {} {}\n", mark.repeat(3), label));
+    for line in lines.iter().take(MAX_SIDE_LINES) {
+        output.push_str(&format!("  {} {}\n", mark, line));
+    }
+    if lines.len() > MAX_SIDE_LINES {
+        output.push_str(&format!("  {} ... {} líneas más\n", mark, lines.len() - MAX_SIDE_LINES));
+    }
+}
+
+fn git(dir: &Path, args: &[&str]) -> Result<String, ConflictError> {
+    let output = Command::new("git")
+        .current_dir(dir)
+        .args(args)
+        .output()
+        .map_err(|e| ConflictError::Io(dir.to_path_buf(), e))?;
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    } else {
+        Err(ConflictError::Git(String::from_utf8_lossy(&output.stderr).trim().to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = "fn main() {
+<<<<<<< HEAD
+    let port = 8080;
+||||||| base
+    let port = 80;
+=======
+    let port = env_port();
+>>>>>>> feature
+    serve(port);
+<<<<<<< HEAD
+    log(\"a\");
+=======
+    log(\"b\");
+>>>>>>> feature
+}
+";
+
+    fn set() -> ConflictSet {
+        ConflictSet {
+            root: PathBuf::from("/repo"),
+            files: vec![ConflictFile {
+                path: PathBuf::from("/repo/src/main.rs"),
+                display: "src/main.rs".to_string(),
+                content: SOURCE.to_string(),
+                hunks: parse_hunks(SOURCE),
+            }],
+        }
+    }
+
+    #[test]
+    fn test_parse_hunks_with_base() {
+        let hunks = parse_hunks(SOURCE);
+        assert_eq!(hunks.len(), 2);
+        assert_eq!((hunks[0].start_line, hunks[0].end_line), (2, 8));
+        assert_eq!(hunks[0].base.as_deref(), Some("    let port = 80;"));
+        assert_eq!(hunks[0].theirs, "    let port = env_port();");
+        assert_eq!((hunks[1].ours_label.as_str(), hunks[1].theirs_label.as_str()), ("HEAD", "feature"));
+        assert!(hunks[1].base.is_none());
+    }
+
+    #[test]
+    fn test_proposals_need_acceptance() {
+        let mut set = set();
+        let response = "Aquí está:\n<<<RESOLUTION 1>>>\n    let port = env_port().unwrap_or(8080);\n<<<END>>>\n\
+                        <<<RESOLUTION 9>>>\nx\n<<<END>>>";
+        assert_eq!(set.record_proposals(response), 1);
+        assert!(set.previews().is_empty());
+        assert!(matches!(set.accept(&[2]), Err(ConflictError::NoProposal(2))));
+
+        set.accept(&[1]).unwrap();
+        set.choose(2, ResolutionSource::Both).unwrap();
+        assert_eq!(
+            set.files[0].resolved_content(),
+            "fn main() {\n    let port = env_port().unwrap_or(8080);\n    serve(port);\n    log(\"a\");\n    log(\"b\");\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_partial_resolution_keeps_other_markers() {
+        let mut set = set();
+        set.choose(2, ResolutionSource::Theirs).unwrap();
+        let content = set.files[0].resolved_content();
+        assert_eq!(parse_hunks(&content).len(), 1);
+        assert!(content.ends_with("    serve(port);\n    log(\"b\");\n}\n"));
+    }
+
+    #[test]
+    fn test_crlf_files_keep_their_line_endings() {
+        let source = SOURCE.replace('\n', "\r\n");
+        let mut file = ConflictFile {
+            path: PathBuf::from("/repo/src/main.rs"),
+            display: "src/main.rs".to_string(),
+            hunks: parse_hunks(&source),
+            content: source,
+        };
+        assert_eq!(file.hunks.len(), 2);
+        file.hunks[0].resolution = Some(Resolution {
+            content: "    let port = 3000;".to_string(),
+            source: ResolutionSource::Model,
+            accepted: true,
+        });
+        file.hunks[1].resolution = Some(Resolution {
+            content: file.hunks[1].theirs.clone(),
+            source: ResolutionSource::Theirs,
+            accepted: true,
+        });
+        assert_eq!(
+            file.resolved_content(),
+            "fn main() {\r\n    let port = 3000;\r\n    serve(port);\r\n    log(\"b\");\r\n}\r\n"
+        );
+    }
+}
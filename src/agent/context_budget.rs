@@ -0,0 +1,210 @@
+//! Context window budget of the last model request
+//!
+//! Every chat request records how its prompt splits between the system prompt
+//! (with the tool definitions), pinned context (the project's `.neuro/`
+//! instructions), retrieved context (RAPTOR, related files, git, schema,
+//! symbols and dependency docs added by the router) and the conversation,
+//! measured against the window of the model. The status bar shows the total as
+//! a meter and Ctrl+B opens the breakdown.
+//!
+//! Counts are estimates (about 4 characters per token), good enough to see
+//! what fills the window, not to bill by.
+
+use crate::context::manager::estimate_tokens;
+use std::sync::{OnceLock, RwLock};
+
+/// Meter turns yellow from here
+pub const WARN_PERCENT: usize = 75;
+/// Meter turns red from here: the provider may start cutting the prompt
+pub const FULL_PERCENT: usize = 90;
+
+/// How the prompt of one request fills the model's window, in tokens
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ContextBudget {
+    pub model: String,
+    /// Context window of the model, from the capability registry
+    pub window: usize,
+    /// System prompt and tool definitions
+    pub system: usize,
+    /// Project instructions pinned into the system prompt
+    pub pinned: usize,
+    /// Context the router retrieved for the query
+    pub retrieval: usize,
+    /// User message, assistant turns and tool results of the request
+    pub history: usize,
+    /// Messages in the session history
+    pub history_messages: usize,
+    /// Session history cap; past it the oldest messages are dropped
+    pub max_history: usize,
+}
+
+impl ContextBudget {
+    pub fn new(model: impl Into<String>, window: usize) -> Self {
+        Self { model: model.into(), window, ..Self::default() }
+    }
+
+    /// Count `system_prompt` and the tool definitions, with `pinned` (already
+    /// part of the system prompt) split out
+    pub fn with_system(mut self, system_prompt: &str, tools: &[crate::agent::OllamaTool], pinned: Option<&str>) -> Self {
+        let tools = if tools.is_empty() {
+            0
+        } else {
+            serde_json::to_string(tools).map(|json| estimate_tokens(&json)).unwrap_or(0)
+        };
+        self.pinned = pinned.map(estimate_tokens).unwrap_or(0);
+        self.system = (estimate_tokens(system_prompt) + tools).saturating_sub(self.pinned);
+        self
+    }
+
+    /// Count the non-system messages of `conversation`, `retrieved` of them
+    /// being context the router added to the query
+    pub fn with_conversation(mut self, conversation: &[serde_json::Value], retrieved: usize) -> Self {
+        let total: usize = conversation
+            .iter()
+            .filter(|message| message["role"] != "system")
+            .map(|message| {
+                let content = message["content"].as_str().map(estimate_tokens).unwrap_or(0);
+                let calls = message.get("tool_calls").map(|calls| estimate_tokens(&calls.to_string())).unwrap_or(0);
+                content + calls
+            })
+            .sum();
+        self.retrieval = retrieved.min(total);
+        self.history = total - self.retrieval;
+        self
+    }
+
+    /// Session history size against its cap
+    pub fn with_history(mut self, messages: usize, max_history: usize) -> Self {
+        self.history_messages = messages;
+        self.max_history = max_history;
+        self
+    }
+
+    pub fn used(&self) -> usize {
+        self.system + self.pinned + self.retrieval + self.history
+    }
+
+    /// Share of the window in use, 0 when the window is unknown
+    pub fn percent(&self) -> usize {
+        if self.window == 0 {
+            0
+        } else {
+            self.used() * 100 / self.window
+        }
+    }
+
+    /// Tokens past the window, which the provider drops from the start of the prompt
+    pub fn overflow(&self) -> usize {
+        self.used().saturating_sub(self.window)
+    }
+
+    /// Messages left before the session history starts dropping the oldest
+    pub fn messages_until_trim(&self) -> usize {
+        self.max_history.saturating_sub(self.history_messages)
+    }
+
+    /// Meter of `width` cells, e.g. `▰▰▰▱▱▱▱▱`
+    pub fn meter(&self, width: usize) -> String {
+        let filled = (self.percent().min(100) * width).div_ceil(100).min(width);
+        format!("{}{}", "▰".repeat(filled), "▱".repeat(width - filled))
+    }
+
+    /// Breakdown shown in the Ctrl+B popup
+    pub fn breakdown(&self) -> Vec<String> {
+        let share = |tokens: usize| {
+            if self.window == 0 {
+                String::new()
+            } else {
+                format!(" ({}%)", tokens * 100 / self.window)
+            }
+        };
+        let mut lines = vec![
+            format!("Modelo: {} · ventana de {} tokens", self.model, self.window),
+            String::new(),
+            format!("  Prompt de sistema y herramientas  {:>7}{}", self.system, share(self.system)),
+            format!("  Contexto fijado (.neuro/)         {:>7}{}", self.pinned, share(self.pinned)),
+            format!("  Contexto recuperado               {:>7}{}", self.retrieval, share(self.retrieval)),
+            format!("  Conversación y resultados         {:>7}{}", self.history, share(self.history)),
+            format!("  Total                             {:>7}{}", self.used(), share(self.used())),
+            String::new(),
+        ];
+        if self.overflow() > 0 {
+            lines.push(format!(
+                "⚠ {} tokens no caben: el proveedor descarta el inicio del prompt",
+                self.overflow()
+            ));
+        } else {
+            lines.push(format!("Libres: {} tokens", self.window - self.used()));
+        }
+        if self.max_history > 0 {
+            lines.push(match self.messages_until_trim() {
+                0 => format!(
+                    "Historial lleno ({} mensajes): cada mensaje nuevo descarta el más antiguo",
+                    self.max_history
+                ),
+                left => format!(
+                    "Historial: {}/{} mensajes, se recorta en {} más",
+                    self.history_messages, self.max_history, left
+                ),
+            });
+        }
+        lines
+    }
+}
+
+fn last_budget() -> &'static RwLock<Option<ContextBudget>> {
+    static LAST: OnceLock<RwLock<Option<ContextBudget>>> = OnceLock::new();
+    LAST.get_or_init(|| RwLock::new(None))
+}
+
+/// Keep `budget` as the one of the latest request
+pub fn record(budget: ContextBudget) {
+    *last_budget().write().unwrap_or_else(|e| e.into_inner()) = Some(budget);
+}
+
+/// Budget of the latest request, if there was one
+pub fn last() -> Option<ContextBudget> {
+    last_budget().read().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_budget_splits_sections() {
+        let instructions = "Use tabs. ".repeat(40);
+        let system_prompt = format!("{}{}", "You are neuro. ".repeat(80), instructions);
+        let query = "explain the router ".repeat(10);
+        let retrieved = "\n\nContexto del proyecto:\n".to_string() + &"fn route() {} ".repeat(100);
+        let conversation = vec![
+            json!({"role": "system", "content": system_prompt}),
+            json!({"role": "user", "content": format!("{}{}", query, retrieved)}),
+            json!({"role": "tool", "content": "x".repeat(400)}),
+        ];
+
+        let budget = ContextBudget::new("qwen3:8b", 2_000)
+            .with_system(&system_prompt, &[], Some(&instructions))
+            .with_conversation(&conversation, estimate_tokens(&retrieved))
+            .with_history(48, 50);
+
+        assert_eq!(budget.pinned, estimate_tokens(&instructions));
+        assert_eq!(budget.system, estimate_tokens(&system_prompt) - budget.pinned);
+        assert_eq!(budget.retrieval, estimate_tokens(&retrieved));
+        assert_eq!(budget.history, estimate_tokens(&format!("{}{}", query, retrieved)) + 100 - budget.retrieval);
+        assert_eq!(budget.percent(), budget.used() * 100 / 2_000);
+        assert_eq!(budget.messages_until_trim(), 2);
+        assert_eq!(budget.meter(8).chars().count(), 8);
+        assert!(budget.breakdown().iter().any(|line| line.contains("se recorta en 2 más")));
+    }
+
+    #[test]
+    fn test_overflow_is_reported() {
+        let conversation = vec![json!({"role": "user", "content": "x".repeat(8_000)})];
+        let budget = ContextBudget::new("qwen3:0.6b", 1_000).with_conversation(&conversation, 0);
+        assert_eq!(budget.overflow(), 1_000);
+        assert_eq!(budget.meter(4), "▰▰▰▰");
+        assert!(budget.breakdown().iter().any(|line| line.contains("1000 tokens no caben")));
+    }
+}
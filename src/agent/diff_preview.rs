@@ -245,8 +245,25 @@ impl DiffPreview {
         colored
     }
 
-    /// Apply the diff (write new content to file)
+    /// Fail if the file no longer holds `old_content`, i.e. it was edited
+    /// since the preview was made
+    pub fn check_unchanged(&self) -> Result<()> {
+        let current = match std::fs::read_to_string(&self.file_path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(e).with_context(|| format!("Failed to read file: {:?}", self.file_path)),
+        };
+        if current != self.old_content {
+            anyhow::bail!("File changed since the preview: {:?}", self.file_path);
+        }
+        Ok(())
+    }
+
+    /// Apply the diff (write new content to file), unless the file changed
+    /// since the preview
     pub fn apply(&self) -> Result<()> {
+        self.check_unchanged()?;
+
         // Create parent directory if needed
         if let Some(parent) = self.file_path.parent() {
             std::fs::create_dir_all(parent)
@@ -429,6 +446,11 @@ mod tests {
 
         let written = std::fs::read_to_string(&path).unwrap();
         assert_eq!(written, new_content);
+
+        // Edited since the preview: left alone
+        let stale = DiffPreview::new(path.clone(), "old content\n".to_string(), "other".to_string());
+        assert!(stale.apply().is_err());
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), new_content);
     }
 
     #[test]
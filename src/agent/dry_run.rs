@@ -0,0 +1,120 @@
+//! Dry-run mode
+//!
+//! With dry-run on (`dry_run` in the config or `/dryrun on`), the tools that
+//! change something describe the exact action they would take instead of
+//! running it: the path and diff of a file write, the command line of a shell
+//! or git call, the matches a replacement would change. Every action is kept
+//! in a plan that `/dryrun` lists, so what an agent meant to do can be audited
+//! before real execution is turned back on.
+
+use super::review_pass::FileChange;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+/// Actions kept in the plan; older ones are dropped first
+pub const MAX_PLANNED: usize = 200;
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static PLANNED: Mutex<Vec<PlannedAction>> = Mutex::new(Vec::new());
+
+/// An action a tool would have taken
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlannedAction {
+    pub tool: String,
+    /// One line: the path, command or git commands
+    pub summary: String,
+    /// Diff or further details, empty when the summary says it all
+    pub details: String,
+}
+
+/// Turn dry-run mode on or off for the whole process
+pub fn set_enabled(enabled: bool) {
+    ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+pub fn enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// Actions planned since the plan was last cleared, oldest first
+pub fn planned() -> Vec<PlannedAction> {
+    PLANNED.lock().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+pub fn clear() {
+    PLANNED.lock().unwrap_or_else(|e| e.into_inner()).clear();
+}
+
+/// Add an action to the plan and tell the model it did not run
+pub fn describe(tool: &str, summary: impl Into<String>, details: impl Into<String>) -> String {
+    let action = PlannedAction {
+        tool: tool.to_string(),
+        summary: summary.into(),
+        details: details.into(),
+    };
+    let mut output = format!("🧪 Dry run: {} would {}", action.tool, action.summary);
+    if !action.details.is_empty() {
+        output.push_str("\n\n");
+        output.push_str(&action.details);
+    }
+    output.push_str("\n\nNothing was executed: dry-run mode is on. Describe the remaining steps the same way.");
+
+    let mut planned = PLANNED.lock().unwrap_or_else(|e| e.into_inner());
+    if planned.len() >= MAX_PLANNED {
+        planned.remove(0);
+    }
+    planned.push(action);
+    output
+}
+
+/// A file write: the diff against the current content, if any
+pub fn file_write(path: &str, previous: Option<&str>, content: &str, append: bool) -> String {
+    let after = match (previous, append) {
+        (Some(previous), true) => format!("{}{}", previous, content),
+        _ => content.to_string(),
+    };
+    let change = FileChange {
+        path: PathBuf::from(path),
+        before: previous.map(str::to_string),
+        after,
+    };
+    let verb = match (previous, append) {
+        (None, _) => "create",
+        (Some(_), true) => "append to",
+        (Some(_), false) => "overwrite",
+    };
+    describe(
+        "write_file",
+        format!("{} {} ({} bytes)", verb, path, content.len()),
+        format!("```diff\n{}```", change.diff()),
+    )
+}
+
+/// A command line run in `working_dir`
+pub fn command(tool: &str, command: &str, working_dir: &str) -> String {
+    describe(tool, format!("run `{}` in {}", command, working_dir), "")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_describes_and_plans_actions() {
+        clear();
+        let output = file_write("/tmp/a.rs", Some("fn a() {}\n"), "fn b() {}\n", false);
+        assert!(output.starts_with("🧪 Dry run: write_file would overwrite /tmp/a.rs (10 bytes)"));
+        assert!(output.contains("-fn a() {}"));
+        assert!(output.contains("+fn b() {}"));
+        assert!(output.contains("Nothing was executed"));
+
+        command("execute_shell", "rm -rf target", "/repo");
+        let plan = planned();
+        assert_eq!(plan.len(), 2);
+        assert_eq!(plan[1].summary, "run `rm -rf target` in /repo");
+        assert!(file_write("/tmp/new.rs", None, "x", false).contains("would create /tmp/new.rs"));
+        clear();
+        assert!(planned().is_empty());
+    }
+}
@@ -0,0 +1,374 @@
+//! Effort and impact estimate of a proposed change (/estimate)
+//!
+//! Files come from three sources: the symbols the description names (where
+//! they are defined and used, from the symbol index), the code RAPTOR
+//! retrieves for the description, and the files importing any of those
+//! (import graph). Tests near them are listed to update, and the spread of
+//! the change gives a rough S/M/L size. No model call: the report only
+//! reflects what the indexes know, and says so.
+
+use crate::context::ImportGraph;
+use crate::tools::{symbol_mentions, FindReferencesTool, SymbolIndexTool};
+use regex::Regex;
+use std::collections::BTreeSet;
+use std::path::Path;
+use std::sync::LazyLock;
+
+/// Symbols of a description looked up
+const MAX_SYMBOLS: usize = 8;
+/// Usages read per symbol
+const MAX_REFERENCES: usize = 200;
+/// Files kept from retrieval
+pub const MAX_RETRIEVED: usize = 8;
+/// Importers listed per file
+const MAX_IMPORTERS: usize = 10;
+/// A file with this many importers is a hub: changing it ripples
+const HUB_IMPORTERS: usize = 10;
+
+/// Identifiers, with their `Type::` path if any
+static IDENTIFIER_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\b(?:[A-Za-z_][A-Za-z0-9_]*::)*[A-Za-z_][A-Za-z0-9_]*\b").unwrap());
+static CODE_SPAN_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"`([^`\n]+)`").unwrap());
+
+/// Why a file is in the estimate
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Impact {
+    /// Defines a symbol the description names
+    Defines(String),
+    /// Uses a symbol the description names
+    Uses(String),
+    /// Retrieved by RAPTOR for the description
+    Retrieved,
+    /// Imports one of the files above
+    Imports(String),
+}
+
+impl Impact {
+    /// Whether the file is likely edited, not only affected
+    pub fn is_direct(&self) -> bool {
+        !matches!(self, Impact::Imports(_))
+    }
+
+    fn describe(&self) -> String {
+        match self {
+            Impact::Defines(symbol) => format!("define `{}`", symbol),
+            Impact::Uses(symbol) => format!("usa `{}`", symbol),
+            Impact::Retrieved => "relacionado con la descripción (RAPTOR)".to_string(),
+            Impact::Imports(file) => format!("importa `{}`", file),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AffectedFile {
+    /// Relative to the project root
+    pub path: String,
+    pub impact: Impact,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Effort {
+    S,
+    M,
+    L,
+}
+
+impl Effort {
+    fn label(self) -> &'static str {
+        match self {
+            Effort::S => "S (horas)",
+            Effort::M => "M (uno o dos días)",
+            Effort::L => "L (varios días)",
+        }
+    }
+}
+
+/// Files, tests and size of a proposed change
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Estimate {
+    pub description: String,
+    pub files: Vec<AffectedFile>,
+    pub tests: Vec<String>,
+    pub effort: Effort,
+    pub rationale: Vec<String>,
+    pub risks: Vec<String>,
+}
+
+/// Estimate `description` in the project at `root`; `retrieved` are the files
+/// RAPTOR found for it
+pub async fn estimate(
+    symbol_index: &SymbolIndexTool,
+    references: &FindReferencesTool,
+    root: &Path,
+    description: &str,
+    retrieved: &[String],
+) -> Estimate {
+    let mut files: Vec<AffectedFile> = Vec::new();
+    let add = |files: &mut Vec<AffectedFile>, path: String, impact: Impact| {
+        if !files.iter().any(|file| file.path == path) {
+            files.push(AffectedFile { path, impact });
+        }
+    };
+
+    for symbol in candidate_symbols(description) {
+        let Ok(report) = references.find_references(root, &symbol, None, MAX_REFERENCES).await else {
+            continue;
+        };
+        for definition in &report.definitions {
+            add(&mut files, definition.relative_path.clone(), Impact::Defines(symbol.clone()));
+        }
+        for reference in &report.references {
+            add(&mut files, reference.path.clone(), Impact::Uses(symbol.clone()));
+        }
+    }
+    for path in retrieved.iter().take(MAX_RETRIEVED) {
+        add(&mut files, relative(root, path), Impact::Retrieved);
+    }
+
+    let graph = symbol_index.import_graph(root).await.ok();
+    if let Some(graph) = &graph {
+        let direct: Vec<String> = files.iter().map(|file| file.path.clone()).collect();
+        for path in &direct {
+            for importer in graph.importers_of(path).into_iter().take(MAX_IMPORTERS) {
+                add(&mut files, importer.to_string(), Impact::Imports(path.clone()));
+            }
+        }
+    }
+
+    let tests = tests_for(root, &files);
+    let (effort, rationale) = classify(&files, &tests);
+    let risks = graph.as_ref().map(|graph| risks(graph, &files)).unwrap_or_default();
+    Estimate {
+        description: description.trim().to_string(),
+        files,
+        tests,
+        effort,
+        rationale,
+        risks,
+    }
+}
+
+/// Identifiers of a description worth looking up: `@Name` mentions, code
+/// spans and words that can only be code (`snake_case`, `CamelCase`, `a::b`)
+pub fn candidate_symbols(description: &str) -> Vec<String> {
+    let mut symbols: Vec<String> = symbol_mentions(description).into_iter().map(str::to_string).collect();
+    let spans = CODE_SPAN_RE.captures_iter(description).filter_map(|c| c.get(1)).map(|m| m.as_str());
+    for span in spans {
+        if let Some(found) = IDENTIFIER_RE.find(span).filter(|found| found.as_str() == span.trim_end_matches("()")) {
+            symbols.push(found.as_str().to_string());
+        }
+    }
+    for word in IDENTIFIER_RE.find_iter(description).map(|m| m.as_str()) {
+        // An inner capital in a word with lowercase: `HttpClient`, `parseFile`, not `JSON`
+        let camel = word.chars().skip(1).any(char::is_uppercase) && word.chars().any(char::is_lowercase);
+        if word.contains('_') || word.contains("::") || camel {
+            symbols.push(word.to_string());
+        }
+    }
+    let mut seen = BTreeSet::new();
+    symbols.retain(|symbol| seen.insert(symbol.clone()));
+    symbols.truncate(MAX_SYMBOLS);
+    symbols
+}
+
+/// Test files among the affected ones, plus the tests conventionally next to
+/// each edited file (`tests/test_x.py`, `x_test.go`, `x.spec.ts`, inline `mod tests`)
+fn tests_for(root: &Path, files: &[AffectedFile]) -> Vec<String> {
+    let mut tests: Vec<String> = files.iter().filter(|file| is_test(&file.path)).map(|file| file.path.clone()).collect();
+    for file in files.iter().filter(|file| file.impact.is_direct() && !is_test(&file.path)) {
+        let path = Path::new(&file.path);
+        let (Some(stem), Some(ext)) = (path.file_stem().and_then(|s| s.to_str()), path.extension().and_then(|e| e.to_str())) else {
+            continue;
+        };
+        let dir = path.parent().map(|dir| dir.to_string_lossy().to_string()).unwrap_or_default();
+        let join = |dir: &str, name: String| if dir.is_empty() { name } else { format!("{}/{}", dir, name) };
+        let candidates = [
+            join(&dir, format!("{}_test.{}", stem, ext)),
+            join(&dir, format!("test_{}.{}", stem, ext)),
+            join(&dir, format!("{}.test.{}", stem, ext)),
+            join(&dir, format!("{}.spec.{}", stem, ext)),
+            format!("tests/test_{}.{}", stem, ext),
+            format!("tests/{}.{}", stem, ext),
+        ];
+        for candidate in candidates {
+            if root.join(&candidate).is_file() && !tests.contains(&candidate) {
+                tests.push(candidate);
+            }
+        }
+        if ext == "rs" && !tests.contains(&file.path) {
+            let inline = std::fs::read_to_string(root.join(&file.path)).is_ok_and(|source| source.contains("#[cfg(test)]"));
+            if inline {
+                tests.push(file.path.clone());
+            }
+        }
+    }
+    tests
+}
+
+fn is_test(path: &str) -> bool {
+    let name = path.rsplit('/').next().unwrap_or(path);
+    path.starts_with("tests/")
+        || path.contains("/tests/")
+        || path.contains("/__tests__/")
+        || name.starts_with("test_")
+        || ["_test.", ".test.", ".spec.", "Test."].iter().any(|marker| name.contains(marker))
+}
+
+/// Size of the change from how many files it edits, how many depend on
+/// them and how many areas of the project it spans
+fn classify(files: &[AffectedFile], tests: &[String]) -> (Effort, Vec<String>) {
+    let direct: Vec<&AffectedFile> = files.iter().filter(|file| file.impact.is_direct()).collect();
+    let dependents = files.len() - direct.len();
+    let areas: BTreeSet<String> = direct.iter().map(|file| area(&file.path)).collect();
+
+    let points = direct.len() + dependents / 3 + 2 * areas.len().saturating_sub(1);
+    let effort = match points {
+        0..=3 => Effort::S,
+        4..=10 => Effort::M,
+        _ => Effort::L,
+    };
+
+    let mut rationale = vec![format!("{} archivos a modificar", direct.len())];
+    if dependents > 0 {
+        rationale.push(format!("{} archivos los importan y pueden necesitar ajustes", dependents));
+    }
+    if areas.len() > 1 {
+        rationale.push(format!(
+            "abarca {} áreas del proyecto ({})",
+            areas.len(),
+            areas.into_iter().collect::<Vec<_>>().join(", ")
+        ));
+    }
+    if tests.is_empty() && !direct.is_empty() {
+        rationale.push("no hay tests cerca de estos archivos: habrá que escribirlos".to_string());
+    }
+    (effort, rationale)
+}
+
+/// Directory of a file two levels deep (`src/agent`), the unit of "area"
+fn area(path: &str) -> String {
+    let parts: Vec<&str> = path.split('/').collect();
+    match parts.len() {
+        0 | 1 => ".".to_string(),
+        2 => parts[0].to_string(),
+        _ => format!("{}/{}", parts[0], parts[1]),
+    }
+}
+
+/// Edited files many others import, and import cycles through them
+fn risks(graph: &ImportGraph, files: &[AffectedFile]) -> Vec<String> {
+    let direct: Vec<&str> = files.iter().filter(|file| file.impact.is_direct()).map(|file| file.path.as_str()).collect();
+    let mut risks = Vec::new();
+    for path in &direct {
+        let importers = graph.importers_of(path).len();
+        if importers >= HUB_IMPORTERS {
+            risks.push(format!("`{}` lo importan {} archivos: un cambio de interfaz se propaga", path, importers));
+        }
+    }
+    for cycle in graph.cycles() {
+        if cycle.iter().any(|file| direct.contains(&file.as_str())) {
+            risks.push(format!("ciclo de imports: {}", cycle.join(" → ")));
+        }
+    }
+    risks
+}
+
+/// `path` relative to `root`, as the indexes store it
+fn relative(root: &Path, path: &str) -> String {
+    let root = root.canonicalize().unwrap_or_else(|_| root.to_path_buf());
+    let path = Path::new(path);
+    let path = path.strip_prefix(&root).unwrap_or(path);
+    path.to_string_lossy().trim_start_matches("./").replace('\\', "/")
+}
+
+impl Estimate {
+    /// Report to paste into a ticket
+    pub fn to_markdown(&self) -> String {
+        let mut output = format!("# Estimación: {}\n\n", self.description);
+        if self.files.is_empty() {
+            output.push_str(
+                "No se encontró código relacionado. Nombra los símbolos implicados (`Parser`, @parse_file) \
+                 o indexa el proyecto con /reindex para que la búsqueda RAPTOR aporte archivos.\n",
+            );
+            return output;
+        }
+
+        output.push_str(&format!("**Esfuerzo:** {}\n\n## Por qué\n\n", self.effort.label()));
+        for reason in &self.rationale {
+            output.push_str(&format!("- {}\n", reason));
+        }
+
+        output.push_str("\n## Archivos afectados\n\n");
+        for file in &self.files {
+            output.push_str(&format!("- `{}` — {}\n", file.path, file.impact.describe()));
+        }
+
+        output.push_str("\n## Tests a actualizar\n\n");
+        if self.tests.is_empty() {
+            output.push_str("- Ninguno encontrado\n");
+        }
+        for test in &self.tests {
+            output.push_str(&format!("- `{}`\n", test));
+        }
+
+        if !self.risks.is_empty() {
+            output.push_str("\n## Riesgos\n\n");
+            for risk in &self.risks {
+                output.push_str(&format!("- {}\n", risk));
+            }
+        }
+        output.push_str(
+            "\n_Estimación orientativa a partir del índice de símbolos, el grafo de imports y la búsqueda RAPTOR._\n",
+        );
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(path: &str, impact: Impact) -> AffectedFile {
+        AffectedFile {
+            path: path.to_string(),
+            impact,
+        }
+    }
+
+    #[test]
+    fn test_candidate_symbols() {
+        let symbols = candidate_symbols("Make `parse_file` retry on @Tokenizer errors in Parser::parse and the HttpClient");
+        assert_eq!(symbols, vec!["Tokenizer", "parse_file", "Parser::parse", "HttpClient"]);
+        assert!(candidate_symbols("Add a retry when the network fails").is_empty());
+    }
+
+    #[test]
+    fn test_classify_and_report() {
+        let files = vec![
+            file("src/parser.rs", Impact::Defines("Parser".to_string())),
+            file("src/agent/router.rs", Impact::Uses("Parser".to_string())),
+            file("tests/parser_test.rs", Impact::Uses("Parser".to_string())),
+            file("src/main.rs", Impact::Imports("src/parser.rs".to_string())),
+        ];
+        let tests = vec!["tests/parser_test.rs".to_string()];
+        let (effort, rationale) = classify(&files, &tests);
+        // 3 edited files in 3 areas, 1 importer
+        assert_eq!(effort, Effort::M);
+        assert!(rationale[0].starts_with("3 archivos"));
+        assert_eq!(classify(&files[..1], &tests).0, Effort::S);
+
+        let estimate = Estimate {
+            description: "Retry parsing".to_string(),
+            files,
+            tests,
+            effort,
+            rationale,
+            risks: vec![],
+        };
+        let report = estimate.to_markdown();
+        assert!(report.contains("**Esfuerzo:** M (uno o dos días)"));
+        assert!(report.contains("- `src/main.rs` — importa `src/parser.rs`"));
+        assert!(report.contains("## Tests a actualizar\n\n- `tests/parser_test.rs`"));
+        assert!(is_test("src/__tests__/app.spec.ts") && !is_test("src/testing.rs"));
+    }
+}
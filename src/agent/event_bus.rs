@@ -0,0 +1,296 @@
+//! Event bus between the agent and its frontends
+//!
+//! The router, the orchestrator's streams and the RAPTOR indexer publish
+//! [`AgentEvent`]s on one [`EventBus`]; the TUI, [`Engine`](crate::Engine) and
+//! experiment tabs subscribe to it. Each subscriber gets its own bounded
+//! mailbox, filtered by [`Topic`], so a slow subscriber cannot hold up the
+//! others:
+//!
+//! - [`EventBus::publish`] never waits: when a mailbox is full the event is
+//!   dropped for that subscriber and counted. Used for status and progress.
+//! - [`EventBus::send`] waits for room in every mailbox, up to
+//!   [`SEND_TIMEOUT`] each, then drops the event for that subscriber and
+//!   counts it. Used for stream chunks and final responses, which a
+//!   subscriber that is reading must not lose; one that stopped reading
+//!   cannot stall the agent.
+//!
+//! [`EventBus::metrics`] reports the depth, high-water mark and drops of every
+//! mailbox.
+
+use super::events::{AgentEvent, Topic};
+use serde::Serialize;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, Weak};
+use std::time::Duration;
+use tokio::sync::mpsc::{self, error::SendTimeoutError, error::TrySendError};
+
+/// Longest [`EventBus::send`] waits for room in one mailbox
+pub const SEND_TIMEOUT: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Default)]
+struct Counters {
+    delivered: AtomicU64,
+    dropped: AtomicU64,
+    high_water: AtomicUsize,
+}
+
+impl Counters {
+    fn delivered(&self, tx: &mpsc::Sender<AgentEvent>) {
+        self.delivered.fetch_add(1, Ordering::Relaxed);
+        self.high_water.fetch_max(tx.max_capacity() - tx.capacity(), Ordering::Relaxed);
+    }
+
+    fn dropped(&self, name: &str, topic: Topic) {
+        if self.dropped.fetch_add(1, Ordering::Relaxed) == 0 {
+            tracing::warn!("Event mailbox '{}' is full, dropping {:?} events", name, topic);
+        }
+    }
+}
+
+struct Mailbox {
+    id: u64,
+    name: String,
+    topics: Vec<Topic>,
+    tx: mpsc::Sender<AgentEvent>,
+    counters: Arc<Counters>,
+}
+
+impl Mailbox {
+    fn wants(&self, topic: Topic) -> bool {
+        self.topics.contains(&topic)
+    }
+}
+
+#[derive(Default)]
+struct Shared {
+    mailboxes: Mutex<Vec<Mailbox>>,
+    next_id: AtomicU64,
+    published: AtomicU64,
+}
+
+impl Shared {
+    fn mailboxes(&self) -> std::sync::MutexGuard<'_, Vec<Mailbox>> {
+        self.mailboxes.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Typed broadcast of agent events with one bounded mailbox per subscriber
+#[derive(Clone, Default)]
+pub struct EventBus {
+    shared: Arc<Shared>,
+}
+
+impl EventBus {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Open a mailbox of `capacity` events for the given topics
+    pub fn subscribe(&self, name: impl Into<String>, capacity: usize, topics: &[Topic]) -> Subscription {
+        let (tx, rx) = mpsc::channel(capacity.max(1));
+        let id = self.shared.next_id.fetch_add(1, Ordering::Relaxed);
+        self.shared.mailboxes().push(Mailbox {
+            id,
+            name: name.into(),
+            topics: topics.to_vec(),
+            tx,
+            counters: Arc::default(),
+        });
+        Subscription { id, rx, shared: Arc::downgrade(&self.shared) }
+    }
+
+    /// Deliver `event` to every subscriber with room for it, without waiting
+    pub fn publish(&self, event: AgentEvent) {
+        self.shared.published.fetch_add(1, Ordering::Relaxed);
+        let topic = event.topic();
+        self.shared.mailboxes().retain(|mailbox| {
+            if !mailbox.wants(topic) {
+                return true;
+            }
+            match mailbox.tx.try_send(event.clone()) {
+                Ok(()) => {
+                    mailbox.counters.delivered(&mailbox.tx);
+                    true
+                }
+                Err(TrySendError::Full(_)) => {
+                    mailbox.counters.dropped(&mailbox.name, topic);
+                    true
+                }
+                Err(TrySendError::Closed(_)) => false,
+            }
+        });
+    }
+
+    /// Deliver `event` to every subscriber, waiting up to [`SEND_TIMEOUT`]
+    /// while a mailbox is full
+    pub async fn send(&self, event: AgentEvent) {
+        self.shared.published.fetch_add(1, Ordering::Relaxed);
+        let topic = event.topic();
+        let targets: Vec<(String, mpsc::Sender<AgentEvent>, Arc<Counters>)> = self
+            .shared
+            .mailboxes()
+            .iter()
+            .filter(|mailbox| mailbox.wants(topic))
+            .map(|mailbox| (mailbox.name.clone(), mailbox.tx.clone(), Arc::clone(&mailbox.counters)))
+            .collect();
+        for (name, tx, counters) in targets {
+            match tx.send_timeout(event.clone(), SEND_TIMEOUT).await {
+                Ok(()) => counters.delivered(&tx),
+                Err(SendTimeoutError::Timeout(_)) => counters.dropped(&name, topic),
+                // A closed mailbox is removed on the next publish
+                Err(SendTimeoutError::Closed(_)) => {}
+            }
+        }
+    }
+
+    pub fn subscriber_count(&self) -> usize {
+        self.shared.mailboxes().len()
+    }
+
+    /// Counters of the bus and of every open mailbox
+    pub fn metrics(&self) -> BusMetrics {
+        BusMetrics {
+            published: self.shared.published.load(Ordering::Relaxed),
+            mailboxes: self
+                .shared
+                .mailboxes()
+                .iter()
+                .map(|mailbox| MailboxMetrics {
+                    name: mailbox.name.clone(),
+                    capacity: mailbox.tx.max_capacity(),
+                    queued: mailbox.tx.max_capacity() - mailbox.tx.capacity(),
+                    high_water: mailbox.counters.high_water.load(Ordering::Relaxed),
+                    delivered: mailbox.counters.delivered.load(Ordering::Relaxed),
+                    dropped: mailbox.counters.dropped.load(Ordering::Relaxed),
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Receiving end of a mailbox; closes it when dropped
+pub struct Subscription {
+    id: u64,
+    rx: mpsc::Receiver<AgentEvent>,
+    shared: Weak<Shared>,
+}
+
+impl Subscription {
+    /// Next event; `None` once the bus is gone
+    pub async fn recv(&mut self) -> Option<AgentEvent> {
+        self.rx.recv().await
+    }
+
+    pub fn try_recv(&mut self) -> Result<AgentEvent, mpsc::error::TryRecvError> {
+        self.rx.try_recv()
+    }
+
+    /// Drop the events already queued
+    pub fn clear(&mut self) {
+        while self.rx.try_recv().is_ok() {}
+    }
+}
+
+impl Drop for Subscription {
+    fn drop(&mut self) {
+        if let Some(shared) = self.shared.upgrade() {
+            shared.mailboxes().retain(|mailbox| mailbox.id != self.id);
+        }
+    }
+}
+
+/// Counters of one mailbox
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct MailboxMetrics {
+    pub name: String,
+    pub capacity: usize,
+    /// Events waiting to be read
+    pub queued: usize,
+    /// Most events ever waiting at once
+    pub high_water: usize,
+    pub delivered: u64,
+    /// Events lost because the mailbox was full
+    pub dropped: u64,
+}
+
+/// Counters of the bus
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct BusMetrics {
+    pub published: u64,
+    pub mailboxes: Vec<MailboxMetrics>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_subscribers_get_their_topics() {
+        let bus = EventBus::new();
+        let mut ui = bus.subscribe("ui", 8, &Topic::ALL);
+        let mut indexer = bus.subscribe("indexer", 8, &[Topic::Raptor]);
+
+        bus.publish(AgentEvent::Status("Clasificando...".to_string()));
+        bus.publish(AgentEvent::RaptorComplete);
+        bus.send(AgentEvent::Chunk("Hola".to_string())).await;
+
+        assert!(matches!(ui.try_recv(), Ok(AgentEvent::Status(_))));
+        assert!(matches!(ui.try_recv(), Ok(AgentEvent::RaptorComplete)));
+        assert!(matches!(ui.try_recv(), Ok(AgentEvent::Chunk(_))));
+        assert!(matches!(indexer.try_recv(), Ok(AgentEvent::RaptorComplete)));
+        assert!(indexer.try_recv().is_err());
+
+        drop(indexer);
+        assert_eq!(bus.subscriber_count(), 1);
+        drop(bus);
+        assert!(ui.recv().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_full_mailbox_drops_and_counts() {
+        let bus = EventBus::new();
+        let mut slow = bus.subscribe("slow", 2, &Topic::ALL);
+        let mut fast = bus.subscribe("fast", 16, &Topic::ALL);
+
+        for i in 0..5 {
+            bus.publish(AgentEvent::Status(format!("paso {}", i)));
+        }
+
+        let metrics = bus.metrics();
+        assert_eq!(metrics.published, 5);
+        assert_eq!(
+            metrics.mailboxes[0],
+            MailboxMetrics {
+                name: "slow".to_string(),
+                capacity: 2,
+                queued: 2,
+                high_water: 2,
+                delivered: 2,
+                dropped: 3,
+            }
+        );
+        assert_eq!(metrics.mailboxes[1].delivered, 5);
+
+        slow.clear();
+        fast.clear();
+        assert_eq!(bus.metrics().mailboxes[0].queued, 0);
+    }
+
+    #[tokio::test]
+    async fn test_send_gives_up_on_a_stuck_mailbox() {
+        let bus = EventBus::new();
+        let _stuck = bus.subscribe("stuck", 1, &Topic::ALL);
+        let mut ui = bus.subscribe("ui", 8, &Topic::ALL);
+
+        bus.send(AgentEvent::Chunk("a".to_string())).await;
+        tokio::time::timeout(SEND_TIMEOUT * 2, bus.send(AgentEvent::Chunk("b".to_string())))
+            .await
+            .expect("send must not wait forever");
+
+        let metrics = bus.metrics();
+        assert_eq!(metrics.mailboxes[0].dropped, 1);
+        assert_eq!(metrics.mailboxes[1].delivered, 2);
+        assert!(matches!(ui.try_recv(), Ok(AgentEvent::Chunk(_))));
+        assert!(matches!(ui.try_recv(), Ok(AgentEvent::Chunk(_))));
+    }
+}
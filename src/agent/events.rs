@@ -1,17 +1,22 @@
 //! Defines events for communication between the agent core and the UI layer.
 
-use crate::agent::{OrchestratorResponse, PlanningResponse, progress::ProgressUpdate, task_progress::TaskProgressInfo};
+use crate::error::NeuroError;
+use crate::agent::{OrchestratorResponse, PlanningResponse, StructuredResponse, progress::ProgressUpdate, task_progress::TaskProgressInfo};
 
 /// Events sent from background agent tasks to the UI for processing.
 /// This enum lives in the agent module but is designed to be used by the UI,
 /// acting as a public API for agent-to-UI communication.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum AgentEvent {
     /// The final, complete response from a non-streaming operation.
-    Response(Result<OrchestratorResponse, String>),
+    Response(Result<OrchestratorResponse, NeuroError>),
+
+    /// Tool calls, citations, token usage and timing of the turn, sent just
+    /// before its `Response`.
+    TurnDetails(Box<StructuredResponse>),
     
     /// The final, complete response from a planning operation.
-    PlanningResponse(Result<PlanningResponse, String>),
+    PlanningResponse(Result<PlanningResponse, NeuroError>),
     
     /// A high-level status or "thinking" message.
     Status(String),
@@ -22,11 +27,23 @@ pub enum AgentEvent {
     /// A single chunk of a streaming response.
     Chunk(String),
 
+    /// A chunk of the model's reasoning (`<think>`), shown apart from the answer.
+    Thinking(String),
+
     /// The end of a stream.
     StreamEnd,
+
+    /// The stream stalled or broke and could not be resumed; it ends the
+    /// stream like `StreamEnd`, with the answer cut short.
+    StreamAborted {
+        /// Answer text received before the stream was given up
+        partial: String,
+        /// Why, e.g. "no data for 30s"
+        reason: String,
+    },
     
-    /// An error message from an agent task.
-    Error(String),
+    /// An error from an agent task.
+    Error(NeuroError),
     
     /// Progress update for a specific sub-task within a larger plan.
     TaskProgress(TaskProgressInfo),
@@ -44,3 +61,39 @@ pub enum AgentEvent {
     /// Signals that RAPTOR indexing is complete.
     RaptorComplete,
 }
+
+/// What an event is about, for subscribers of the event bus that only want some
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Topic {
+    /// Final responses, turn records and errors
+    Turn,
+    /// Status lines and progress of the running request
+    Progress,
+    /// Chunks of a streamed answer and its end
+    Stream,
+    /// RAPTOR indexing
+    Raptor,
+}
+
+impl Topic {
+    pub const ALL: [Topic; 4] = [Topic::Turn, Topic::Progress, Topic::Stream, Topic::Raptor];
+}
+
+impl AgentEvent {
+    pub fn topic(&self) -> Topic {
+        match self {
+            AgentEvent::Response(_)
+            | AgentEvent::PlanningResponse(_)
+            | AgentEvent::TurnDetails(_)
+            | AgentEvent::Error(_) => Topic::Turn,
+            AgentEvent::Status(_) | AgentEvent::Progress(_) | AgentEvent::TaskProgress(_) => Topic::Progress,
+            AgentEvent::Chunk(_)
+            | AgentEvent::Thinking(_)
+            | AgentEvent::StreamEnd
+            | AgentEvent::StreamAborted { .. } => Topic::Stream,
+            AgentEvent::RaptorStatus(_) | AgentEvent::RaptorProgress { .. } | AgentEvent::RaptorComplete => {
+                Topic::Raptor
+            }
+        }
+    }
+}
@@ -0,0 +1,363 @@
+//! Parallel experiments in git worktrees (experimental)
+//!
+//! "Try approach A and B" runs each approach in its own worktree, on its own
+//! branch created from the current `HEAD`, with its own router and session, so
+//! the main checkout stays untouched while both run. Once they are done the
+//! diffs can be compared and one of them adopted into the main checkout as
+//! staged changes; the worktrees and branches are removed afterwards.
+//!
+//! Worktrees live under the repository's git directory, so they never show up
+//! as untracked files.
+
+use super::orchestrator::OrchestratorResponse;
+use super::router_orchestrator::RouterOrchestrator;
+use crate::agent::{AgentEvent, Subscription, Topic};
+use crate::log_warn;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::Duration;
+use thiserror::Error;
+use tokio::sync::mpsc;
+
+/// Approaches run side by side
+pub const MAX_EXPERIMENTS: usize = 4;
+/// Longest wait for a streamed answer
+const RESPONSE_TIMEOUT: Duration = Duration::from_secs(600);
+
+#[derive(Debug, Error)]
+pub enum ExperimentError {
+    #[error("Not a git repository")]
+    NotAGitRepo,
+    #[error("Give between 2 and {MAX_EXPERIMENTS} approaches separated by '|'")]
+    InvalidApproaches,
+    #[error("Unknown experiment '{0}'")]
+    UnknownExperiment(String),
+    #[error("The main checkout has uncommitted changes; commit or stash them before adopting")]
+    DirtyCheckout,
+    #[error("The changes no longer apply to the current HEAD: {0}")]
+    Conflict(String),
+    #[error("Git command failed: {0}")]
+    Git(String),
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// One approach and the worktree it runs in
+#[derive(Debug, Clone)]
+pub struct Experiment {
+    /// `A`, `B`, ...
+    pub label: String,
+    pub approach: String,
+    pub branch: String,
+    pub path: PathBuf,
+}
+
+/// Changes an experiment made relative to the common base
+#[derive(Debug, Clone, Default)]
+pub struct ExperimentDiff {
+    pub files: Vec<String>,
+    pub insertions: usize,
+    pub deletions: usize,
+    pub patch: String,
+}
+
+/// Experiments started together from the same commit
+#[derive(Debug, Clone)]
+pub struct ExperimentSet {
+    /// Main checkout
+    pub root: PathBuf,
+    /// Commit every worktree starts from
+    pub base: String,
+    pub experiments: Vec<Experiment>,
+}
+
+/// Split "approach A | approach B" into approaches
+pub fn parse_approaches(text: &str) -> Result<Vec<String>, ExperimentError> {
+    let approaches: Vec<String> = text
+        .split('|')
+        .map(|a| a.trim().to_string())
+        .filter(|a| !a.is_empty())
+        .collect();
+    if (2..=MAX_EXPERIMENTS).contains(&approaches.len()) {
+        Ok(approaches)
+    } else {
+        Err(ExperimentError::InvalidApproaches)
+    }
+}
+
+impl ExperimentSet {
+    /// Create a worktree and branch per approach, all from the current `HEAD`
+    pub fn create(working_dir: &Path, approaches: Vec<String>) -> Result<Self, ExperimentError> {
+        let root = PathBuf::from(
+            git(working_dir, &["rev-parse", "--show-toplevel"])
+                .map_err(|_| ExperimentError::NotAGitRepo)?
+                .trim(),
+        );
+        let base = git(&root, &["rev-parse", "HEAD"])?.trim().to_string();
+        let common_dir = PathBuf::from(git(&root, &["rev-parse", "--git-common-dir"])?.trim());
+        let stamp = chrono::Utc::now().format("%Y%m%d-%H%M%S").to_string();
+        let dir = root.join(common_dir).join("neuro-worktrees").join(&stamp);
+
+        let mut set = Self { root, base, experiments: Vec::new() };
+        for (i, approach) in approaches.into_iter().enumerate() {
+            let label = ((b'A' + i as u8) as char).to_string();
+            let branch = format!("neuro/try-{}-{}", stamp, label.to_lowercase());
+            let path = dir.join(&label);
+            let added = git(
+                &set.root,
+                &["worktree", "add", "-b", &branch, &path.to_string_lossy(), &set.base],
+            );
+            if let Err(e) = added {
+                // Leave nothing half-created behind
+                set.discard();
+                return Err(e);
+            }
+            set.experiments.push(Experiment { label, approach, branch, path });
+        }
+        Ok(set)
+    }
+
+    pub fn get(&self, label: &str) -> Result<&Experiment, ExperimentError> {
+        self.experiments
+            .iter()
+            .find(|e| e.label.eq_ignore_ascii_case(label))
+            .ok_or_else(|| ExperimentError::UnknownExperiment(label.to_string()))
+    }
+
+    /// Everything an experiment changed since the base, new files included
+    pub fn diff(&self, experiment: &Experiment) -> Result<ExperimentDiff, ExperimentError> {
+        // Intent-to-add makes untracked files show up in `git diff`
+        git(&experiment.path, &["add", "--intent-to-add", "--all"])?;
+        let mut diff = ExperimentDiff {
+            patch: git(&experiment.path, &["diff", &self.base])?,
+            ..Default::default()
+        };
+        for line in git(&experiment.path, &["diff", "--numstat", &self.base])?.lines() {
+            let mut parts = line.split('\t');
+            let (Some(added), Some(removed), Some(file)) = (parts.next(), parts.next(), parts.next()) else {
+                continue;
+            };
+            // Binary files report "-"
+            diff.insertions += added.parse::<usize>().unwrap_or(0);
+            diff.deletions += removed.parse::<usize>().unwrap_or(0);
+            diff.files.push(file.to_string());
+        }
+        Ok(diff)
+    }
+
+    /// Side-by-side summary of what each experiment changed
+    pub fn compare(&self) -> String {
+        let mut output = format!("🧪 Experimentos desde {}\n", short(&self.base));
+        let diffs: Vec<Option<ExperimentDiff>> = self.experiments.iter().map(|e| self.diff(e).ok()).collect();
+        for (experiment, diff) in self.experiments.iter().zip(&diffs) {
+            output.push_str(&format!("\n[{}] {}\n", experiment.label, experiment.approach));
+            match diff {
+                Some(diff) if diff.files.is_empty() => output.push_str("  sin cambios\n"),
+                Some(diff) => {
+                    output.push_str(&format!(
+                        "  {} archivos, +{} -{}\n",
+                        diff.files.len(),
+                        diff.insertions,
+                        diff.deletions
+                    ));
+                    for file in &diff.files {
+                        let shared = diffs
+                            .iter()
+                            .flatten()
+                            .filter(|d| d.files.contains(file))
+                            .count();
+                        let marker = if shared > 1 { "≈" } else { "•" };
+                        output.push_str(&format!("  {} {}\n", marker, file));
+                    }
+                }
+                None => output.push_str("  diff no disponible\n"),
+            }
+        }
+        output.push_str("\n≈ archivo cambiado por más de un enfoque\n");
+        output
+    }
+
+    /// Bring an experiment's changes into the main checkout as staged changes
+    pub fn adopt(&self, label: &str) -> Result<ExperimentDiff, ExperimentError> {
+        let experiment = self.get(label)?;
+        if !git(&self.root, &["status", "--porcelain", "--untracked-files=no"])?.trim().is_empty() {
+            return Err(ExperimentError::DirtyCheckout);
+        }
+        let diff = self.diff(experiment)?;
+        if diff.files.is_empty() {
+            return Ok(diff);
+        }
+        git(&experiment.path, &["add", "--all"])?;
+        git(
+            &experiment.path,
+            &["-c", "user.name=neuro", "-c", "user.email=neuro@localhost", "commit", "-q", "--no-verify", "-m", &experiment.approach],
+        )?;
+        // Apply the branch's changes as a patch rather than merging, so a
+        // HEAD that moved since /try either takes them cleanly or is left as is
+        let patch = git(&self.root, &["diff", "--binary", &self.base, &experiment.branch])?;
+        let mut file = tempfile::NamedTempFile::new()?;
+        std::io::Write::write_all(&mut file, patch.as_bytes())?;
+        let path = file.path().to_string_lossy().to_string();
+        git(&self.root, &["apply", "--index", "--check", &path])
+            .map_err(|e| match e {
+                ExperimentError::Git(message) => ExperimentError::Conflict(message),
+                e => e,
+            })?;
+        git(&self.root, &["apply", "--index", &path])?;
+        Ok(diff)
+    }
+
+    /// Remove every worktree and branch of the set
+    pub fn discard(&mut self) {
+        for experiment in self.experiments.drain(..) {
+            let path = experiment.path.to_string_lossy().to_string();
+            if let Err(e) = git(&self.root, &["worktree", "remove", "--force", &path]) {
+                log_warn!("⚠️ Could not remove worktree {}: {}", path, e);
+            }
+            if let Err(e) = git(&self.root, &["branch", "-D", &experiment.branch]) {
+                log_warn!("⚠️ Could not delete branch {}: {}", experiment.branch, e);
+            }
+        }
+        let _ = git(&self.root, &["worktree", "prune"]);
+    }
+}
+
+/// Progress of a queued experiment task
+#[derive(Debug, Clone)]
+pub enum ExperimentUpdate {
+    Started { index: usize, prompt: String },
+    Finished { index: usize, result: Result<String, String> },
+}
+
+/// Run `router` on the prompts sent to the returned queue, one at a time
+pub fn spawn_runner(
+    index: usize,
+    router: RouterOrchestrator,
+    updates: mpsc::UnboundedSender<ExperimentUpdate>,
+) -> mpsc::UnboundedSender<String> {
+    let (queue_tx, mut queue_rx) = mpsc::unbounded_channel::<String>();
+    tokio::spawn(async move {
+        let mut events = router
+            .event_bus()
+            .subscribe(format!("experiment-{}", index), 5000, &[Topic::Turn, Topic::Stream]);
+        while let Some(prompt) = queue_rx.recv().await {
+            let _ = updates.send(ExperimentUpdate::Started { index, prompt: prompt.clone() });
+            // Drop events left over from the previous task
+            events.clear();
+            let result = match router.process(&prompt).await {
+                Ok(OrchestratorResponse::Streaming { .. }) => collect_stream(&mut events).await,
+                Ok(response) => response_text(response),
+                Err(e) => Err(e.to_string()),
+            };
+            if updates.send(ExperimentUpdate::Finished { index, result }).is_err() {
+                break;
+            }
+        }
+    });
+    queue_tx
+}
+
+/// Accumulate streamed chunks until the stream ends
+async fn collect_stream(events: &mut Subscription) -> Result<String, String> {
+    let mut text = String::new();
+    loop {
+        match tokio::time::timeout(RESPONSE_TIMEOUT, events.recv()).await {
+            Ok(Some(AgentEvent::Chunk(chunk))) => text.push_str(&chunk),
+            Ok(Some(AgentEvent::StreamEnd)) | Ok(None) => return Ok(text),
+            Ok(Some(AgentEvent::StreamAborted { partial, .. })) => return Ok(partial),
+            Ok(Some(AgentEvent::Error(e))) => return Err(e.to_string()),
+            Ok(Some(AgentEvent::Response(response))) => {
+                return response.map_err(|e| e.to_string()).and_then(response_text)
+            }
+            Ok(Some(_)) => {}
+            Err(_) => return Err("Sin respuesta del modelo".to_string()),
+        }
+    }
+}
+
+fn response_text(response: OrchestratorResponse) -> Result<String, String> {
+    match response {
+        OrchestratorResponse::Immediate { content, .. } => Ok(content),
+        OrchestratorResponse::Text(text) => Ok(text),
+        OrchestratorResponse::ToolResult { tool_name, result, success } => {
+            let icon = if success { "✅" } else { "❌" };
+            Ok(format!("{} {}: {}", icon, tool_name, result))
+        }
+        OrchestratorResponse::Error(e) => Err(e),
+        OrchestratorResponse::NeedsConfirmation { command, .. } => {
+            Err(format!("'{}' necesita confirmación; ejecútalo en la pestaña principal", command))
+        }
+        OrchestratorResponse::Delegated { description, .. }
+        | OrchestratorResponse::TaskStarted { description, .. } => Ok(description),
+        OrchestratorResponse::Streaming { .. } => Ok(String::new()),
+    }
+}
+
+fn short(hash: &str) -> &str {
+    hash.get(..7).unwrap_or(hash)
+}
+
+fn git(dir: &Path, args: &[&str]) -> Result<String, ExperimentError> {
+    let output = Command::new("git").current_dir(dir).args(args).output()?;
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    } else {
+        Err(ExperimentError::Git(String::from_utf8_lossy(&output.stderr).trim().to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_parse_approaches() {
+        assert_eq!(
+            parse_approaches("use a HashMap | use a BTreeMap |").unwrap(),
+            vec!["use a HashMap", "use a BTreeMap"]
+        );
+        assert!(parse_approaches("only one").is_err());
+        assert!(parse_approaches("a|b|c|d|e").is_err());
+    }
+
+    #[test]
+    fn test_worktrees_compare_adopt_and_discard() {
+        let dir = tempdir().unwrap();
+        let repo = dir.path();
+        let run = |args: &[&str]| git(repo, args).unwrap();
+        run(&["init", "-q", "-b", "main"]);
+        run(&["config", "user.name", "Test"]);
+        run(&["config", "user.email", "test@example.com"]);
+        std::fs::write(repo.join("lib.rs"), "fn a() {}\n").unwrap();
+        run(&["add", "."]);
+        run(&["commit", "-q", "-m", "base"]);
+
+        let mut set = ExperimentSet::create(repo, vec!["one".into(), "two".into()]).unwrap();
+        std::fs::write(set.experiments[0].path.join("lib.rs"), "fn a() { 1; }\n").unwrap();
+        std::fs::write(set.experiments[1].path.join("new.rs"), "fn b() {}\n").unwrap();
+
+        let b = set.diff(set.get("b").unwrap()).unwrap();
+        assert_eq!((b.files.clone(), b.insertions), (vec!["new.rs".to_string()], 1));
+        assert!(set.compare().contains("[A] one"));
+        assert!(run(&["status", "--porcelain"]).is_empty());
+
+        set.adopt("a").unwrap();
+        assert_eq!(std::fs::read_to_string(repo.join("lib.rs")).unwrap(), "fn a() { 1; }\n");
+        assert!(run(&["status", "--porcelain"]).starts_with("M  lib.rs"));
+
+        // Once main moved under the same lines, adopting leaves it untouched
+        run(&["commit", "-q", "-m", "adopt a"]);
+        std::fs::write(repo.join("lib.rs"), "fn a() { 2; }\n").unwrap();
+        run(&["commit", "-q", "-am", "moved"]);
+        std::fs::write(set.experiments[1].path.join("lib.rs"), "fn a() { 3; }\n").unwrap();
+        assert!(matches!(set.adopt("b"), Err(ExperimentError::Conflict(_))));
+        assert!(run(&["status", "--porcelain"]).is_empty());
+        assert_eq!(std::fs::read_to_string(repo.join("lib.rs")).unwrap(), "fn a() { 2; }\n");
+
+        let paths: Vec<PathBuf> = set.experiments.iter().map(|e| e.path.clone()).collect();
+        set.discard();
+        assert!(paths.iter().all(|p| !p.exists()));
+        assert!(!run(&["branch"]).contains("neuro/try"));
+    }
+}
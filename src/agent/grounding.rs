@@ -0,0 +1,314 @@
+//! Grounding check of retrieval-backed answers
+//!
+//! After an answer built on retrieved context, the files it cites are read
+//! back and every symbol or string it puts next to a citation (an inline code
+//! span in the same paragraph) is looked up in them. Citations of files that
+//! do not exist, lines past the end of the file and symbols none of the cited
+//! files contain are flagged under the answer; with `grounding.auto_correct`
+//! the model is also asked once to fix those claims.
+//!
+//! The check is lexical and local: no model call, only file reads.
+
+use super::response::Citation;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+
+/// Claims checked per answer
+const MAX_CLAIMS: usize = 30;
+/// Larger cited files are not read
+const MAX_FILE_BYTES: u64 = 2 * 1024 * 1024;
+/// Depth searched for a citation that is not relative to the working directory
+const MAX_SEARCH_DEPTH: usize = 8;
+
+/// Extensions of files an answer may cite
+const CITED_EXTENSIONS: &str = "rs|py|ts|tsx|js|jsx|mjs|go|java|kt|kts|c|h|cc|cpp|hpp|cs|rb|php|swift|scala|sql|sh|toml|json|yaml|yml|md|proto|html|css|vue|svelte";
+
+pub(super) static CITATION_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(&format!(
+        r"(?:^|[\s`(\[])((?:\.{{0,2}}/)?(?:[\w.-]+/)*[\w-][\w.-]*\.(?:{}))(?::(\d+)(?:-\d+)?)?\b",
+        CITED_EXTENSIONS
+    ))
+    .unwrap()
+});
+static CODE_SPAN_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"`([^`\n]{2,80})`").unwrap());
+static FENCE_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?ms)^```.*?^```[ \t]*$").unwrap());
+
+/// Grounding check settings
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GroundingConfig {
+    /// Check the citations of answers built on retrieved context
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+
+    /// Ask the model once to correct the claims that were not found
+    #[serde(default)]
+    pub auto_correct: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl Default for GroundingConfig {
+    fn default() -> Self {
+        Self { enabled: true, auto_correct: false }
+    }
+}
+
+/// Outcome of one claim
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ClaimStatus {
+    Verified,
+    /// The cited file does not exist
+    MissingFile,
+    /// The cited line is past the end of the file
+    LineOutOfRange { lines: usize },
+    /// None of the files cited with it contain the text
+    NotFound,
+}
+
+/// A file citation, or a symbol attributed to the files cited around it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Claim {
+    /// Cited file, or the files cited in the paragraph joined with ", "
+    pub source: String,
+    pub line: Option<usize>,
+    /// Symbol or string attributed to `source`; `None` for the citation itself
+    pub text: Option<String>,
+    pub status: ClaimStatus,
+}
+
+impl Claim {
+    pub fn is_verified(&self) -> bool {
+        self.status == ClaimStatus::Verified
+    }
+
+    /// Line shown under the answer for an unverified claim
+    pub fn describe(&self) -> String {
+        let cited = match self.line {
+            Some(line) => format!("{}:{}", self.source, line),
+            None => self.source.clone(),
+        };
+        match (&self.status, &self.text) {
+            (ClaimStatus::MissingFile, _) => format!("⚠ {}: el archivo citado no existe", cited),
+            (ClaimStatus::LineOutOfRange { lines }, _) => {
+                format!("⚠ {}: el archivo solo tiene {} líneas", cited, lines)
+            }
+            (_, Some(text)) => format!("⚠ `{}` no encontrado en la fuente citada ({})", text, cited),
+            (_, None) => format!("⚠ {}: no encontrado en la fuente citada", cited),
+        }
+    }
+}
+
+/// Claims of one answer
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GroundingReport {
+    pub claims: Vec<Claim>,
+}
+
+impl GroundingReport {
+    pub fn unverified(&self) -> impl Iterator<Item = &Claim> {
+        self.claims.iter().filter(|claim| !claim.is_verified())
+    }
+
+    /// Every claim checked out (or there were none)
+    pub fn is_grounded(&self) -> bool {
+        self.claims.iter().all(Claim::is_verified)
+    }
+
+    /// The file citations, without the symbols attributed to them
+    pub fn citations(&self) -> Vec<Citation> {
+        self.claims
+            .iter()
+            .filter(|claim| claim.text.is_none())
+            .map(|claim| Citation { path: claim.source.clone(), line: claim.line, verified: claim.is_verified() })
+            .collect()
+    }
+
+    /// `answer` with the unverified claims listed under it
+    pub fn annotate(&self, answer: &str) -> String {
+        if self.is_grounded() {
+            return answer.to_string();
+        }
+        let verified = self.claims.iter().filter(|claim| claim.is_verified()).count();
+        let mut out = format!(
+            "{}\n\n---\n🔎 Verificación de fuentes ({}/{} afirmaciones confirmadas):\n",
+            answer.trim_end(),
+            verified,
+            self.claims.len()
+        );
+        for claim in self.unverified() {
+            out.push_str(&claim.describe());
+            out.push('\n');
+        }
+        out.trim_end().to_string()
+    }
+
+    /// Follow-up asking the model to fix the unverified claims of `answer` to `question`
+    pub fn correction_prompt(&self, question: &str, answer: &str) -> String {
+        let problems: Vec<String> = self.unverified().map(Claim::describe).collect();
+        format!(
+            "Your previous answer cites sources that do not back it up. Read the cited files \
+             again with the tools, then rewrite the answer keeping only what the files actually \
+             contain; drop or correct every claim below and cite the right file and line.\n\n\
+             Question: {}\n\nPrevious answer:\n{}\n\nProblems found:\n{}",
+            question,
+            answer,
+            problems.join("\n")
+        )
+    }
+}
+
+/// Check the citations of `answer` against the files under `working_dir`
+pub fn check(answer: &str, working_dir: &Path) -> GroundingReport {
+    let prose = FENCE_RE.replace_all(answer, "");
+    let mut files: HashMap<String, Option<String>> = HashMap::new();
+    let mut claims = Vec::new();
+
+    for paragraph in prose.split("\n\n") {
+        let citations: Vec<(String, Option<usize>)> = CITATION_RE
+            .captures_iter(paragraph)
+            .map(|caps| {
+                let path = caps[1].trim_start_matches("./").to_string();
+                (path, caps.get(2).and_then(|line| line.as_str().parse().ok()))
+            })
+            .collect();
+        if citations.is_empty() {
+            continue;
+        }
+
+        let mut found = Vec::new();
+        for (path, line) in &citations {
+            let content = files
+                .entry(path.clone())
+                .or_insert_with(|| resolve(working_dir, path).and_then(|path| read_cited(&path)));
+            let status = match (content, line) {
+                (None, _) => ClaimStatus::MissingFile,
+                (Some(content), Some(line)) if *line > content.lines().count() => {
+                    ClaimStatus::LineOutOfRange { lines: content.lines().count() }
+                }
+                (Some(_), _) => ClaimStatus::Verified,
+            };
+            if status == ClaimStatus::Verified && !found.contains(path) {
+                found.push(path.clone());
+            }
+            claims.push(Claim { source: path.clone(), line: *line, text: None, status });
+        }
+        if found.is_empty() {
+            continue;
+        }
+
+        for caps in CODE_SPAN_RE.captures_iter(paragraph) {
+            let Some(needle) = symbol_needle(&caps[1]) else {
+                continue;
+            };
+            let verified = found
+                .iter()
+                .any(|path| files[path].as_deref().is_some_and(|content| content.contains(&needle)));
+            claims.push(Claim {
+                source: found.join(", "),
+                line: None,
+                text: Some(caps[1].trim().to_string()),
+                status: if verified { ClaimStatus::Verified } else { ClaimStatus::NotFound },
+            });
+        }
+    }
+
+    claims.dedup();
+    claims.truncate(MAX_CLAIMS);
+    GroundingReport { claims }
+}
+
+/// Path of a cited file: relative to `working_dir`, or else the first file
+/// in the project whose path ends with it (`main.rs` for `src/main.rs`)
+fn resolve(working_dir: &Path, cited: &str) -> Option<PathBuf> {
+    let direct = working_dir.join(cited);
+    if direct.is_file() {
+        return Some(direct);
+    }
+    ignore::WalkBuilder::new(working_dir)
+        .max_depth(Some(MAX_SEARCH_DEPTH))
+        .build()
+        .filter_map(Result::ok)
+        .map(|entry| entry.into_path())
+        .find(|path| path.is_file() && path.ends_with(cited))
+}
+
+fn read_cited(path: &Path) -> Option<String> {
+    let metadata = std::fs::metadata(path).ok()?;
+    if !metadata.is_file() || metadata.len() > MAX_FILE_BYTES {
+        return None;
+    }
+    std::fs::read_to_string(path).ok()
+}
+
+/// Text to look for in the cited files for a code span, `None` for spans
+/// that are not symbols or strings from the code (paths, commands, prose)
+fn symbol_needle(span: &str) -> Option<String> {
+    let span = span.trim();
+    if CITATION_RE.is_match(&format!(" {}", span)) || (span.contains(char::is_whitespace) && !span.contains('(')) {
+        return None;
+    }
+    // `foo()`, `Foo::bar(x)`, `self.config` - the last segment before the arguments
+    let name = span.split('(').next().unwrap_or(span);
+    let name = name.rsplit([':', '.']).next().unwrap_or(name).trim_start_matches(['&', '*', '#']);
+    let name = name.trim_matches(|c: char| !c.is_alphanumeric() && c != '_');
+    (name.len() >= 2).then(|| name.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn project() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("src")).unwrap();
+        std::fs::write(
+            dir.path().join("src/router.rs"),
+            "pub struct RouterOrchestrator;\n\nimpl RouterOrchestrator {\n    pub fn classify(&self) {}\n}\n",
+        )
+        .unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_flags_missing_symbols_and_files() {
+        let dir = project();
+        let answer = "The router lives in `src/router.rs:3`, where `RouterOrchestrator::classify()` \
+                      picks a route and `fallback_route` handles errors.\n\n\
+                      Retries are configured in src/retry.rs.\n\n\
+                      ```rust\nfn not_checked() {}\n```";
+
+        let report = check(answer, dir.path());
+        let unverified: Vec<String> = report.unverified().map(Claim::describe).collect();
+        assert_eq!(
+            unverified,
+            [
+                "⚠ `fallback_route` no encontrado en la fuente citada (src/router.rs)",
+                "⚠ src/retry.rs: el archivo citado no existe",
+            ]
+        );
+        assert_eq!(report.claims.len(), 4);
+        assert_eq!(
+            report.citations().iter().map(|citation| citation.verified).collect::<Vec<_>>(),
+            [true, false]
+        );
+        assert!(report.annotate(answer).contains("🔎 Verificación de fuentes (2/4"));
+        assert!(report.correction_prompt("how does routing work?", answer).contains("fallback_route"));
+    }
+
+    #[test]
+    fn test_grounded_answer_is_left_alone() {
+        let dir = project();
+        let answer = "`classify` is defined in router.rs:4.\n\nNo citations here, just `whatever`.";
+        let report = check(answer, dir.path());
+        assert!(report.is_grounded());
+        assert_eq!(report.annotate(answer), answer);
+
+        let past_end = check("See src/router.rs:40.", dir.path());
+        assert_eq!(past_end.claims[0].status, ClaimStatus::LineOutOfRange { lines: 5 });
+    }
+}
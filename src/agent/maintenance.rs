@@ -0,0 +1,79 @@
+//! Maintenance scheduler
+//!
+//! Housekeeping of the session database that runs in the background at
+//! startup and then every `artifacts.maintenance_minutes`: for now, removing
+//! the artifacts the retention policy no longer keeps.
+
+use super::artifacts::ArtifactsConfig;
+use crate::db::{Database, DatabaseError};
+use crate::{log_info, log_warn};
+use chrono::Utc;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+
+/// What a maintenance run did
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MaintenanceReport {
+    pub artifacts_removed: u64,
+}
+
+/// Periodic housekeeping of the session database
+#[derive(Clone)]
+pub struct MaintenanceScheduler {
+    db: Database,
+    artifacts: ArtifactsConfig,
+}
+
+impl MaintenanceScheduler {
+    pub fn new(db: Database, artifacts: ArtifactsConfig) -> Self {
+        Self { db, artifacts }
+    }
+
+    /// Run every job once
+    pub async fn run_once(&self) -> Result<MaintenanceReport, DatabaseError> {
+        let artifacts_removed = self
+            .db
+            .prune_artifacts(&Utc::now().to_rfc3339(), self.artifacts.max_total_bytes())
+            .await?;
+        Ok(MaintenanceReport { artifacts_removed })
+    }
+
+    /// Run now and then at the configured interval, until the task is aborted
+    pub fn spawn(self) -> JoinHandle<()> {
+        let period = Duration::from_secs(self.artifacts.maintenance_minutes.max(1) * 60);
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(period);
+            loop {
+                interval.tick().await;
+                match self.run_once().await {
+                    Ok(report) if report.artifacts_removed > 0 => {
+                        log_info!("🧹 Maintenance: {} artifacts removed", report.artifacts_removed)
+                    }
+                    Ok(_) => {}
+                    Err(e) => log_warn!("⚠ Maintenance run failed: {}", e),
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{Artifact, Session};
+
+    #[tokio::test]
+    async fn test_run_once_prunes_expired_artifacts() {
+        let db = Database::in_memory().await.unwrap();
+        db.create_session(&Session::new("s1", "fast", "heavy")).await.unwrap();
+        let expired = Artifact::new("s1", "output", "cargo test", "ok", "")
+            .with_expiry(Some("2000-01-01T00:00:00+00:00".to_string()));
+        db.add_artifact(&expired).await.unwrap();
+        let kept = db.add_artifact(&Artifact::new("s1", "doc", "/docs", "# API", "")).await.unwrap();
+
+        let scheduler = MaintenanceScheduler::new(db.clone(), ArtifactsConfig::default());
+        assert_eq!(scheduler.run_once().await.unwrap(), MaintenanceReport { artifacts_removed: 1 });
+        let left: Vec<i64> = db.list_artifacts(None, 10).await.unwrap().iter().map(|a| a.id).collect();
+        assert_eq!(left, vec![kept]);
+    }
+}
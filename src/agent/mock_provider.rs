@@ -0,0 +1,435 @@
+//! Scripted model provider for deterministic orchestrator tests
+//!
+//! The orchestrators talk to Ollama over HTTP, so `MockProvider` serves the
+//! same API (`/api/tags`, `/api/generate`, `/api/chat`) on a local port and
+//! answers every model request with the next scripted [`MockReply`]. Point
+//! `OrchestratorConfig::ollama_url` and the router's model configs at
+//! [`MockProvider::url`] to exercise classification, tool loops, streaming and
+//! error paths without a running Ollama. Warm-up requests (no prompt) are
+//! answered without using a reply. It also implements [`ModelProvider`] for
+//! code that takes a provider directly.
+//!
+//! Available in this crate's tests and, for downstream crates, behind the
+//! `test-utils` feature.
+//!
+//! ```ignore
+//! use neuro::agent::mock_provider::{MockProvider, MockReply};
+//!
+//! # async fn example() {
+//! let mock = MockProvider::start().await;
+//! mock.push(MockReply::tool_call("read_file", serde_json::json!({ "path": "Cargo.toml" })))
+//!     .push(MockReply::text("The crate is called neuro"));
+//! let config = mock.orchestrator_config();
+//! # }
+//! ```
+
+use super::orchestrator::OrchestratorConfig;
+use super::provider::{
+    ModelProvider, OllamaFunctionCall, OllamaToolCall, ProviderError, ProviderResponse,
+};
+use crate::config::{ModelConfig, ModelProvider as ProviderType};
+use async_trait::async_trait;
+use serde_json::{json, Value};
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use tokio::io::{AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::task::JoinHandle;
+
+/// Model name used when the script does not care which model answers
+pub const MOCK_MODEL: &str = "mock:latest";
+
+/// One scripted answer, consumed by the next model request
+#[derive(Debug, Clone)]
+pub enum MockReply {
+    /// Plain text (`response` for `/api/generate`, `message.content` for `/api/chat`)
+    Text(String),
+    /// Text sent as separate chunks when the request streams, joined otherwise
+    Chunks(Vec<String>),
+    /// Assistant message with native tool calls
+    ToolCalls(Vec<OllamaToolCall>),
+    /// HTTP error status with a body
+    Error(u16, String),
+    /// Accept the request and never answer, to exercise timeouts
+    Hang,
+    /// Stream these chunks, then keep the connection open without finishing
+    Stall(Vec<String>),
+}
+
+impl MockReply {
+    pub fn text(text: impl Into<String>) -> Self {
+        Self::Text(text.into())
+    }
+
+    pub fn chunks<S: Into<String>>(chunks: impl IntoIterator<Item = S>) -> Self {
+        Self::Chunks(chunks.into_iter().map(Into::into).collect())
+    }
+
+    /// A single native tool call
+    pub fn tool_call(name: &str, arguments: Value) -> Self {
+        Self::ToolCalls(vec![OllamaToolCall {
+            function: OllamaFunctionCall {
+                name: name.to_string(),
+                arguments,
+            },
+        }])
+    }
+
+    pub fn error(status: u16, body: impl Into<String>) -> Self {
+        Self::Error(status, body.into())
+    }
+
+    pub fn stall<S: Into<String>>(chunks: impl IntoIterator<Item = S>) -> Self {
+        Self::Stall(chunks.into_iter().map(Into::into).collect())
+    }
+
+    fn content(&self) -> String {
+        match self {
+            Self::Text(text) => text.clone(),
+            Self::Chunks(chunks) | Self::Stall(chunks) => chunks.concat(),
+            Self::ToolCalls(_) | Self::Error(..) | Self::Hang => String::new(),
+        }
+    }
+}
+
+/// A model request the mock received, in arrival order
+#[derive(Debug, Clone)]
+pub struct MockRequest {
+    /// Endpoint path, e.g. `/api/chat` (`generate` for direct trait calls)
+    pub path: String,
+    pub body: Value,
+}
+
+impl MockRequest {
+    /// Model named in the request
+    pub fn model(&self) -> &str {
+        self.body["model"].as_str().unwrap_or_default()
+    }
+
+    /// Prompt of `/api/generate` or the last message of `/api/chat`
+    pub fn prompt(&self) -> &str {
+        self.body["prompt"]
+            .as_str()
+            .or_else(|| {
+                self.body["messages"]
+                    .as_array()
+                    .and_then(|messages| messages.last())
+                    .and_then(|message| message["content"].as_str())
+            })
+            .unwrap_or_default()
+    }
+}
+
+#[derive(Default)]
+struct Script {
+    replies: VecDeque<MockReply>,
+    requests: Vec<MockRequest>,
+}
+
+/// Ollama-compatible server answering from a script
+pub struct MockProvider {
+    url: String,
+    model: String,
+    script: Arc<Mutex<Script>>,
+    server: JoinHandle<()>,
+}
+
+impl MockProvider {
+    /// Bind a local port and start serving
+    pub async fn start() -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("bind mock provider");
+        let url = format!("http://{}", listener.local_addr().expect("mock address"));
+        let script = Arc::new(Mutex::new(Script::default()));
+
+        let shared = script.clone();
+        let server = tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                tokio::spawn(serve(stream, shared.clone()));
+            }
+        });
+
+        Self {
+            url,
+            model: MOCK_MODEL.to_string(),
+            script,
+            server,
+        }
+    }
+
+    /// Base URL to use as the Ollama URL
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Queue the next reply
+    pub fn push(&self, reply: MockReply) -> &Self {
+        self.script.lock().unwrap().replies.push_back(reply);
+        self
+    }
+
+    /// Model requests received so far
+    pub fn requests(&self) -> Vec<MockRequest> {
+        self.script.lock().unwrap().requests.clone()
+    }
+
+    /// Replies not consumed yet
+    pub fn remaining(&self) -> usize {
+        self.script.lock().unwrap().replies.len()
+    }
+
+    /// Model config for `model` served by this mock
+    pub fn model_config(&self, model: &str) -> ModelConfig {
+        ModelConfig {
+            provider: ProviderType::Ollama,
+            url: self.url.clone(),
+            model: model.to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// Orchestrator config with both models served by this mock
+    pub fn orchestrator_config(&self) -> OrchestratorConfig {
+        OrchestratorConfig {
+            ollama_url: self.url.clone(),
+            heavy_timeout_secs: 10,
+            ..Default::default()
+        }
+    }
+
+    fn next_reply(&self, request: MockRequest) -> Option<MockReply> {
+        next_reply(&self.script, request)
+    }
+}
+
+impl Drop for MockProvider {
+    fn drop(&mut self) {
+        self.server.abort();
+    }
+}
+
+#[async_trait]
+impl ModelProvider for MockProvider {
+    async fn generate(&self, prompt: &str) -> Result<ProviderResponse, ProviderError> {
+        let request = MockRequest {
+            path: "generate".to_string(),
+            body: json!({ "model": self.model, "prompt": prompt }),
+        };
+        match self.next_reply(request) {
+            None => Err(ProviderError::ModelError("mock script exhausted".to_string())),
+            Some(MockReply::Error(status, body)) => {
+                Err(ProviderError::ModelError(format!("HTTP {}: {}", status, body)))
+            }
+            Some(MockReply::Hang | MockReply::Stall(_)) => std::future::pending().await,
+            Some(reply) => Ok(ProviderResponse {
+                content: reply.content(),
+                model: self.model.clone(),
+                finish_reason: Some("stop".to_string()),
+            }),
+        }
+    }
+
+    async fn validate_connection(&self) -> Result<(), ProviderError> {
+        Ok(())
+    }
+
+    fn model_name(&self) -> &str {
+        &self.model
+    }
+
+    fn provider_type(&self) -> ProviderType {
+        ProviderType::Ollama
+    }
+}
+
+fn next_reply(script: &Mutex<Script>, request: MockRequest) -> Option<MockReply> {
+    let mut script = script.lock().unwrap();
+    script.requests.push(request);
+    script.replies.pop_front()
+}
+
+/// Answer one HTTP/1.1 request and close the connection
+async fn serve(stream: TcpStream, script: Arc<Mutex<Script>>) {
+    let mut stream = BufReader::new(stream);
+    let Some((path, body)) = read_request(&mut stream).await else {
+        return;
+    };
+    let mut stream = stream.into_inner();
+
+    if path == "/api/tags" {
+        let _ = respond(&mut stream, 200, &json!({ "models": [] }).to_string()).await;
+        return;
+    }
+
+    let streaming = body["stream"].as_bool().unwrap_or(false);
+    let chat = path == "/api/chat";
+    let model = body["model"].as_str().unwrap_or(MOCK_MODEL).to_string();
+
+    // A generate request without a prompt only loads the model (warm-up)
+    if path == "/api/generate" && body.get("prompt").is_none() {
+        let loaded = json!({ "model": model, "response": "", "done": true, "done_reason": "load" });
+        let _ = respond(&mut stream, 200, &loaded.to_string()).await;
+        return;
+    }
+    let reply = next_reply(&script, MockRequest { path, body });
+
+    let frame = |content: &str, tool_calls: Option<&[OllamaToolCall]>, done: bool| {
+        if chat {
+            let mut message = json!({ "role": "assistant", "content": content });
+            if let Some(calls) = tool_calls {
+                message["tool_calls"] = json!(calls);
+            }
+            json!({
+                "model": model,
+                "created_at": "1970-01-01T00:00:00Z",
+                "message": message,
+                "done": done,
+            })
+        } else {
+            json!({ "model": model, "response": content, "done": done })
+        }
+        .to_string()
+    };
+
+    let _ = match reply {
+        None => respond(&mut stream, 500, "mock script exhausted").await,
+        Some(MockReply::Error(status, body)) => respond(&mut stream, status, &body).await,
+        Some(MockReply::Hang) => {
+            std::future::pending::<()>().await;
+            Ok(())
+        }
+        Some(MockReply::Stall(chunks)) => {
+            let _ = stream_head(&mut stream, chunks.iter().map(|c| frame(c, None, false))).await;
+            std::future::pending::<()>().await;
+            Ok(())
+        }
+        Some(MockReply::ToolCalls(calls)) => {
+            respond(&mut stream, 200, &frame("", Some(&calls), true)).await
+        }
+        Some(MockReply::Chunks(chunks)) if streaming => {
+            stream_chunks(&mut stream, chunks.iter().map(|c| frame(c, None, false)), frame("", None, true)).await
+        }
+        Some(reply) if streaming => {
+            stream_chunks(&mut stream, std::iter::once(frame(&reply.content(), None, false)), frame("", None, true)).await
+        }
+        Some(reply) => respond(&mut stream, 200, &frame(&reply.content(), None, true)).await,
+    };
+}
+
+async fn read_request(stream: &mut BufReader<TcpStream>) -> Option<(String, Value)> {
+    use tokio::io::AsyncBufReadExt;
+
+    let mut request_line = String::new();
+    stream.read_line(&mut request_line).await.ok()?;
+    let path = request_line.split_whitespace().nth(1)?.to_string();
+
+    let mut content_length = 0;
+    loop {
+        let mut header = String::new();
+        if stream.read_line(&mut header).await.ok()? == 0 {
+            return None;
+        }
+        let header = header.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.eq_ignore_ascii_case("content-length") {
+                content_length = value.trim().parse().ok()?;
+            }
+        }
+    }
+
+    let mut body = vec![0; content_length];
+    stream.read_exact(&mut body).await.ok()?;
+    let body = serde_json::from_slice(&body).unwrap_or(Value::Null);
+    Some((path, body))
+}
+
+async fn respond(stream: &mut TcpStream, status: u16, body: &str) -> std::io::Result<()> {
+    let head = format!(
+        "HTTP/1.1 {} Mock\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        status,
+        body.len()
+    );
+    stream.write_all(head.as_bytes()).await?;
+    stream.write_all(body.as_bytes()).await?;
+    stream.shutdown().await
+}
+
+/// NDJSON stream, one line per write, ended by closing the connection
+async fn stream_chunks(
+    stream: &mut TcpStream,
+    lines: impl Iterator<Item = String>,
+    last: String,
+) -> std::io::Result<()> {
+    stream_head(stream, lines.chain(std::iter::once(last))).await?;
+    stream.shutdown().await
+}
+
+/// Headers and the first NDJSON lines, leaving the stream open
+async fn stream_head(stream: &mut TcpStream, lines: impl Iterator<Item = String>) -> std::io::Result<()> {
+    stream
+        .write_all(b"HTTP/1.1 200 OK\r\nContent-Type: application/x-ndjson\r\nConnection: close\r\n\r\n")
+        .await?;
+    for line in lines {
+        stream.write_all(format!("{}\n", line).as_bytes()).await?;
+        stream.flush().await?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent::provider::OllamaProvider;
+
+    #[tokio::test]
+    async fn test_replies_follow_the_script() {
+        let mock = MockProvider::start().await;
+        mock.push(MockReply::text("first"))
+            .push(MockReply::tool_call("read_file", json!({ "path": "a.rs" })));
+
+        let provider = OllamaProvider::new(mock.model_config("fast"));
+        assert_eq!(provider.generate("hi").await.unwrap().content, "first");
+
+        let message = provider
+            .generate_with_tools(vec![json!({ "role": "user", "content": "read a.rs" })], None)
+            .await
+            .unwrap();
+        let calls = message.tool_calls.unwrap();
+        assert_eq!(calls[0].function.name, "read_file");
+        assert_eq!(calls[0].function.arguments["path"], "a.rs");
+
+        let requests = mock.requests();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[0].path, "/api/generate");
+        assert_eq!(requests[0].prompt(), "hi");
+        assert_eq!(requests[1].model(), "fast");
+        assert_eq!(requests[1].prompt(), "read a.rs");
+    }
+
+    #[tokio::test]
+    async fn test_errors_and_exhausted_script() {
+        let mock = MockProvider::start().await;
+        mock.push(MockReply::error(503, "overloaded"));
+
+        let provider = OllamaProvider::new(mock.model_config("fast"));
+        let error = provider.generate("hi").await.unwrap_err().to_string();
+        assert!(error.contains("503") && error.contains("overloaded"), "{}", error);
+        assert!(provider.generate("again").await.is_err());
+        assert!(provider.validate_connection().await.is_ok());
+        assert_eq!(mock.requests().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_direct_trait_calls() {
+        let mock = MockProvider::start().await;
+        mock.push(MockReply::chunks(["a", "b"]));
+
+        assert_eq!(mock.generate("prompt").await.unwrap().content, "ab");
+        assert!(mock.generate("prompt").await.is_err());
+        assert_eq!(mock.remaining(), 0);
+    }
+}
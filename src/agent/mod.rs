@@ -16,47 +16,103 @@
 //! - [`diff_preview`] - Preview interactivo de cambios antes de aplicar
 //! - [`undo_stack`] - Sistema de deshacer/rehacer operaciones
 //! - [`session`] - Gestión de sesiones de conversación persistentes
+//! - [`session_search`] - Etiquetas y búsqueda de sesiones pasadas (`/tag`, `/sessions`)
+//! - [`html_export`] - Exportación de sesiones a una página HTML autocontenida (`/export html`)
+//! - [`translation`] - Traducción de respuestas al leerlas, sin tocar el historial (`/translate`)
+//! - [`estimation`] - Estimación de impacto y esfuerzo de un cambio propuesto (`/estimate`)
+//! - [`dry_run`] - Modo simulación: las herramientas que modifican describen la acción sin ejecutarla (`/dryrun`)
+//! - [`activity_report`] - Informe de actividad en Markdown (`neuro report --since 7d`)
 //! - [`preloader`] - Pre-carga de contexto para reducir latencia
 //! - [`monitoring`] - Sistema de monitoreo y observability
 //! - [`error_recovery`] - Sistema de recuperación automática de errores
 //! - [`benchmarks`] - Sistema de benchmarking con detección de regresiones
+//! - [`capabilities`] - Registro de capacidades por modelo (tools, JSON, visión, contexto)
+//! - [`event_bus`] - Bus de eventos tipado con un buzón acotado por suscriptor
+//! - [`context_budget`] - Reparto de la ventana de contexto de la última petición al modelo
+//! - [`grounding`] - Verificación de las fuentes citadas en respuestas con contexto recuperado
+//! - [`confidence`] - Indicador de confianza de cada respuesta según la recuperación y sus fuentes
+//! - [`response`] - Registros tipados de un turno: herramientas, citas, tokens y tiempos
+//! - [`turn_budget`] - Presupuesto de tiempo por turno con resultado parcial y `/continue`
+//! - [`artifacts`] - Salidas grandes (ejecuciones, diffs, informes) guardadas aparte con retención
+//! - [`preferences`] - Preferencias del usuario (estilo de código, verbosidad) globales y por proyecto
+//! - [`reasoning`] - Razonamiento de modelos como qwen3 (`<think>`), separado de la respuesta
+//! - [`stream_watchdog`] - Detección de streams atascados, una reconexión y fin con la respuesta parcial
+//! - [`maintenance`] - Tareas de mantenimiento periódicas de la base de datos de sesiones
+//! - [`pre_router`] - Pre-enrutado determinista: comandos, saludos y sí/no sin clasificador
+//! - [`recipes`] - Flujos de varios pasos reutilizables definidos en YAML
+//! - [`templates`] - Plantillas de conversación para tareas recurrentes (`/start`)
+//! - [`workspace`] - Directorio de trabajo de la sesión y raíces del espacio de trabajo
+//! - `mock_provider` - Proveedor con respuestas guionizadas para tests (feature `test-utils`)
 
 mod classification_cache;
 mod classifier;
+pub mod activity_report;
+pub mod artifacts;
 pub mod benchmarks;
+pub mod capabilities;
 pub mod code_review;
+pub mod confidence;
+pub mod context_budget;
+pub mod conflicts;
 pub mod diff_preview;
+pub mod dry_run;
+pub mod experiments;
+pub mod grounding;
+pub mod maintenance;
+pub mod pre_router;
+pub mod preferences;
+pub mod reasoning;
+pub mod recipes;
+pub mod response;
+pub mod review_pass;
+pub mod templates;
+pub mod turn_budget;
 pub mod error_recovery;
 pub mod monitoring;
 pub mod multistep;
 pub mod orchestrator;
+#[cfg(any(test, feature = "test-utils"))]
+pub mod mock_provider;
 pub mod preloader;
 pub mod session;
+pub mod session_search;
+pub mod html_export;
+pub mod translation;
+pub mod estimation;
 pub mod undo_stack;
+pub mod webhooks;
+pub mod workspace;
 mod parallel_executor;
 #[deprecated(since = "2.0.0", note = "Use RouterOrchestrator instead. Will be removed in v2.0 (Feb 2026)")]
 pub mod planning_orchestrator;
 mod progress;
 mod streaming;
+pub mod stream_watchdog;
 mod task_progress;
 pub mod prompts;
 pub mod provider;
 pub mod router;
 pub mod router_orchestrator;
 pub mod slash_commands;
+pub mod event_bus;
 pub mod events;
 mod state;
 
 pub use classification_cache::{ClassificationCache, CacheStats};
 pub use classifier::TaskType;
-pub use events::AgentEvent;
+pub use capabilities::{Capability, CapabilityRegistry, ModelCapabilities};
+pub use event_bus::{BusMetrics, EventBus, MailboxMetrics, Subscription};
+pub use events::{AgentEvent, Topic};
 pub use benchmarks::{
     BenchmarkBaseline, BenchmarkResult, BenchmarkRunner, BenchmarkStatus, BenchmarkSummary,
+    RetrievalBenchResult, RetrievalCase,
 };
 pub use code_review::{
     CodeReviewAnalyzer, CodeSmell, ComplexityIssue, Grade, ReviewReport, Suggestion,
     SuggestionSeverity, UntestedFunction,
 };
+pub use context_budget::ContextBudget;
+pub use conflicts::{ConflictError, ConflictHunk, ConflictSet, ResolutionSource};
 pub use diff_preview::{DiffAction, DiffHunk, DiffPreview, DiffStats};
 pub use error_recovery::{
     ErrorPattern, ErrorRecovery, ErrorType, RecoveryStats, RetryStrategy, RollbackOperation,
@@ -69,9 +125,24 @@ pub use multistep::{
     MultiStepExecutor, PlanStatus, StateSnapshot, StepExecutionResult, StepStatus, TaskPlan,
     TaskStep, Checkpoint,
 };
+pub use artifacts::{ArtifactKind, ArtifactsConfig};
+pub use grounding::{Claim, ClaimStatus, GroundingConfig, GroundingReport};
+pub use confidence::{AnswerConfidence, ConfidenceLevel, RetrievalScores};
+pub use activity_report::{ActivityReport, ReportError};
+pub use maintenance::{MaintenanceReport, MaintenanceScheduler};
+pub use response::{Citation, StructuredResponse, Timing, TokenUsage, ToolCallRecord, TurnRecord};
+pub use turn_budget::{Continuation, TurnBudget};
+pub use pre_router::FastPath;
+pub use preferences::{PreferenceError, PreferenceScope, Preferences, PREFERENCE_KEYS};
+pub use reasoning::{ReasoningConfig, Segment, ThinkSplitter};
+pub use recipes::{Recipe, RecipeBook, RecipeError, RecipeRun, RecipeStep, StepAction};
+pub use templates::{ConversationTemplate, TemplateBook, TemplateError, TemplateMode};
 pub use preloader::{ContextPreloader, EmbeddingCache, PreloaderCacheStats, PreloaderState, RaptorCache};
 pub use session::{Session, SessionContext, SessionInfo, SessionManager, SessionMessage};
+pub use session_search::SessionQuery;
 pub use undo_stack::{Operation, OperationType, UndoStack};
+pub use webhooks::{TaskEvent, WebhookConfig, WebhookEventKind, WebhookKind};
+pub use workspace::WorkspaceError;
 pub use orchestrator::{DualModelOrchestrator, OrchestratorResponse};
 pub use parallel_executor::{ToolRequest, ToolResult, execute_parallel, combine_results};
 #[allow(deprecated)]
@@ -88,5 +159,5 @@ pub use provider::{
 };
 pub use router::{ExecutionPlan, ExecutionStep, IntelligentRouter};
 pub use router_orchestrator::{OperationMode, RouterConfig, RouterDecision, RouterOrchestrator};
-pub use state::{AgentState, Message, MessageRole};
+pub use state::{AgentState, AnswerSources, Message, MessageRole, RouteTrace};
 
@@ -2,17 +2,46 @@
 
 ///! Dual-model orchestrator for routing between fast and heavy models
 
+use super::activity_report;
+use super::artifacts::ArtifactKind;
+use super::dry_run;
+use super::capabilities::{Capability, CapabilityRegistry};
 use super::classifier::{TaskClassifier, TaskType};
-use super::state::{create_shared_state, Message, PendingTask, SharedState};
-use crate::tools::ToolRegistry;
-use std::time::Duration;
+use super::context_budget::{self, ContextBudget};
+use super::event_bus::EventBus;
+use super::events::AgentEvent;
+use super::provider::{OllamaTool, OllamaToolCall};
+use super::response::{ToolCallRecord, TurnRecord};
+use super::review_pass::FileChange;
+use super::state::{create_shared_state, Message, MessageRole, PendingTask, SharedState};
+use super::preferences::Preferences;
+use super::reasoning::{self, Segment, ThinkSplitter};
+use super::stream_watchdog::{self, StreamFailure, StreamHealth};
+use super::turn_budget::{self, Continuation, TurnBudget};
+use crate::context::manager::estimate_tokens;
+use crate::db::{Artifact, CommandExecution, SessionStore};
+use crate::log_warn;
+use crate::security::network::{self, SendChecked};
+use crate::tools::{
+    BenchmarkTool, BuildErrorKb, BuildRunner, DatabaseQueryTool, DeadCodeTool, FileReadTool, FindReferencesTool,
+    HttpAuthProfile, HttpClientTool, KubernetesTool, ListDirectoryTool, MemoryTool, OwnershipTool, PluginsConfig, RefactorTool, ReplaceArgs,
+    SchemaIngestTool, SearchArgs, SymbolIndexTool, ToolRegistry, WasmPluginError, WasmPluginHost,
+};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 use thiserror::Error;
 use tokio::sync::mpsc;
 use tokio::time::timeout;
 use tokio_util::sync::CancellationToken;
 use uuid::Uuid;
 use futures::StreamExt; // ADDED THIS LINE
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+
+/// How long Ollama keeps the heavy model loaded after a request, so the
+/// steps of a plan do not each pay for loading it again
+pub const HEAVY_KEEP_ALIVE: &str = "30m";
 
 /// Orchestrator errors
 #[derive(Error, Debug)]
@@ -32,7 +61,11 @@ pub enum OrchestratorError {
 }
 
 /// Response from the orchestrator
-#[derive(Debug, Clone)]
+///
+/// The typed records of the turn (tool calls, citations, usage, timing) come
+/// with it in [`StructuredResponse`](super::response::StructuredResponse).
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", content = "data", rename_all = "snake_case")]
 pub enum OrchestratorResponse {
     /// Immediate response (from fast model or simple command)
     Immediate { content: String, model: String },
@@ -60,6 +93,25 @@ pub enum OrchestratorResponse {
     TaskStarted { task_id: Uuid, description: String },
 }
 
+impl OrchestratorResponse {
+    /// Answer text of `Immediate` and `Text` responses
+    pub fn text(&self) -> Option<&str> {
+        match self {
+            OrchestratorResponse::Immediate { content, .. } => Some(content),
+            OrchestratorResponse::Text(text) => Some(text),
+            _ => None,
+        }
+    }
+
+    /// Model named by the response
+    pub fn model(&self) -> Option<&str> {
+        match self {
+            OrchestratorResponse::Immediate { model, .. } => Some(model),
+            _ => None,
+        }
+    }
+}
+
 /// Result from a heavy task
 #[derive(Debug, Clone)]
 pub struct HeavyTaskResult {
@@ -69,6 +121,16 @@ pub struct HeavyTaskResult {
     pub model: String,
 }
 
+/// A mutating tool call held back until the user approves it
+#[derive(Debug, Clone)]
+pub struct PendingToolCall {
+    pub tool_name: String,
+    pub args: serde_json::Value,
+    /// What will run, shown in the confirmation dialog
+    pub summary: String,
+    pub risk_level: String,
+}
+
 /// Configuration for the orchestrator
 #[derive(Debug, Clone)]
 pub struct OrchestratorConfig {
@@ -96,6 +158,19 @@ impl Default for OrchestratorConfig {
     }
 }
 
+impl OrchestratorConfig {
+    /// Models, URL and limits of the application config
+    pub fn from_app_config(config: &crate::config::AppConfig) -> Self {
+        Self {
+            ollama_url: config.fast_model.url.clone(),
+            fast_model: config.fast_model.model.clone(),
+            heavy_model: config.heavy_model.model.clone(),
+            heavy_timeout_secs: config.heavy_timeout_secs,
+            max_concurrent_heavy: config.max_concurrent_heavy,
+        }
+    }
+}
+
 /// Dual-model orchestrator
 pub struct DualModelOrchestrator {
     config: OrchestratorConfig,
@@ -110,6 +185,24 @@ pub struct DualModelOrchestrator {
     global_cancel: CancellationToken,
     /// Per-task cancellation tokens
     task_cancels: std::collections::HashMap<Uuid, CancellationToken>,
+    /// What each model supports (tools, JSON mode)
+    capabilities: CapabilityRegistry,
+    /// Session database used for the command audit log
+    session_store: Option<SessionStore>,
+    /// Files written since the last `take_file_changes`, with their previous content
+    file_changes: std::sync::Mutex<std::collections::BTreeMap<PathBuf, Option<String>>>,
+    /// Mutating call waiting for the user's answer in the confirmation dialog
+    pending_confirmation: std::sync::Mutex<Option<PendingToolCall>>,
+    /// Tool calls and model requests since the last `take_turn_record`
+    turn: std::sync::Mutex<TurnRecord>,
+    /// Cancels the model requests and tool runs of the current turn
+    turn_cancel: std::sync::Mutex<CancellationToken>,
+    /// Time budget of the current turn, if any
+    budget: std::sync::Mutex<Option<TurnBudget>>,
+    /// Tool loop the budget stopped, for `/continue`
+    continuation: std::sync::Mutex<Option<Continuation>>,
+    /// Settings last given to `apply_tool_config`, for experiment forks
+    tool_config: Option<crate::config::AppConfig>,
 }
 
 impl DualModelOrchestrator {
@@ -120,14 +213,7 @@ impl DualModelOrchestrator {
 
     /// Create a new orchestrator with custom configuration
     pub async fn with_config(config: OrchestratorConfig) -> Result<Self, OrchestratorError> {
-        // Test connection to Ollama
-        let client = reqwest::Client::new();
-        client
-            .get(format!("{}/api/tags", config.ollama_url))
-            .timeout(Duration::from_secs(5))
-            .send()
-            .await
-            .map_err(|e| OrchestratorError::ConnectionError(e.to_string()))?;
+        Self::check_connection(&config.ollama_url).await?;
 
         let (result_tx, result_rx) = mpsc::channel(32);
 
@@ -140,14 +226,321 @@ impl DualModelOrchestrator {
             result_tx,
             global_cancel: CancellationToken::new(),
             task_cancels: std::collections::HashMap::new(),
+            capabilities: CapabilityRegistry::new(),
+            session_store: None,
+            file_changes: Default::default(),
+            pending_confirmation: Default::default(),
+            turn: Default::default(),
+            turn_cancel: Default::default(),
+            budget: Default::default(),
+            continuation: Default::default(),
+            tool_config: None,
         })
     }
 
+    /// Test the connection to Ollama
+    async fn check_connection(ollama_url: &str) -> Result<(), OrchestratorError> {
+        let client = network::client();
+        client
+            .get(format!("{}/api/tags", ollama_url))
+            .timeout(Duration::from_secs(5))
+            .send_checked("model provider")
+            .await
+            .map_err(|e| OrchestratorError::ConnectionError(e.to_string()))?;
+        Ok(())
+    }
+
+    /// Switch to other models or another server, keeping tools and state.
+    /// The current config stays in place when the new server is unreachable.
+    pub async fn reconnect(&mut self, config: OrchestratorConfig) -> Result<(), OrchestratorError> {
+        Self::check_connection(&config.ollama_url).await?;
+        self.config = config;
+        Ok(())
+    }
+
+    /// Change the heavy task timeout for the following requests
+    pub fn set_heavy_timeout_secs(&mut self, secs: u64) {
+        self.config.heavy_timeout_secs = secs;
+    }
+
+    /// Replace the capability registry (built-in table plus config overrides)
+    pub fn set_capabilities(&mut self, capabilities: CapabilityRegistry) {
+        self.capabilities = capabilities;
+    }
+
+    /// Get the capability registry
+    pub fn capabilities(&self) -> &CapabilityRegistry {
+        &self.capabilities
+    }
+
+    /// Configure the auth profiles available to `http_request`
+    pub fn set_http_profiles(&mut self, profiles: HashMap<String, HttpAuthProfile>) {
+        self.tools.http_client = Arc::new(HttpClientTool::new().with_profiles(profiles));
+    }
+
+    /// Configure the named connections available to `query_database`
+    pub fn set_databases(&mut self, connections: HashMap<String, String>) {
+        let database = DatabaseQueryTool::new().with_connections(connections);
+        self.tools.schema = Arc::new(SchemaIngestTool::new().with_database(database.clone()));
+        self.tools.database = Arc::new(database);
+    }
+
+    /// Enable the opt-in `kubernetes` tool
+    pub fn set_kubernetes_enabled(&mut self, enabled: bool) {
+        self.tools.kubernetes = Arc::new(KubernetesTool::new().with_enabled(enabled));
+    }
+
+    /// Load the WASM plugins; the ones that fail are skipped and returned
+    pub fn load_wasm_plugins(&mut self, config: &PluginsConfig, working_dir: &Path) -> Vec<WasmPluginError> {
+        let (plugins, errors) = WasmPluginHost::load(config, working_dir, &self.tools.tool_names());
+        for plugin in plugins.plugins() {
+            log_info!("🧩 Loaded WASM plugin '{}'", plugin.manifest().name);
+        }
+        self.tools.plugins = Arc::new(plugins);
+        errors
+    }
+
+    /// Apply the tool settings of `config` (HTTP profiles, databases, kubernetes,
+    /// protected paths, WASM plugins) for a session in `working_dir`; plugins
+    /// that fail to load are skipped and returned
+    pub fn apply_tool_config(&mut self, config: &crate::config::AppConfig, working_dir: &Path) -> Vec<WasmPluginError> {
+        self.reload_tool_config(config, working_dir);
+        self.load_wasm_plugins(&config.plugins, working_dir)
+    }
+
+    /// Apply the tool settings of `config` that can change while running
+    /// (everything but the WASM plugins)
+    pub fn reload_tool_config(&mut self, config: &crate::config::AppConfig, working_dir: &Path) {
+        self.set_http_profiles(config.http_profiles.clone());
+        self.set_databases(config.databases.clone());
+        self.set_kubernetes_enabled(config.kubernetes);
+        self.set_protected_paths(working_dir, &config.protected_paths);
+        self.tool_config = Some(config.clone());
+    }
+
+    /// Settings last given to `apply_tool_config` or `reload_tool_config`
+    pub fn tool_config(&self) -> Option<&crate::config::AppConfig> {
+        self.tool_config.as_ref()
+    }
+
+    /// Refuse writes to files matching `patterns` (gitignore syntax, relative to `root`)
+    pub fn set_protected_paths(&mut self, root: &Path, patterns: &[String]) {
+        if patterns.is_empty() {
+            self.tools.protected_paths = None;
+            return;
+        }
+        let mut builder = ignore::gitignore::GitignoreBuilder::new(root);
+        for pattern in patterns {
+            if let Err(e) = builder.add_line(None, pattern) {
+                log_warn!("⚠️ Invalid protected path '{}': {}", pattern, e);
+            }
+        }
+        self.tools.protected_paths = builder
+            .build()
+            .map(Arc::new)
+            .map_err(|e| log_warn!("⚠️ Protected paths disabled: {}", e))
+            .ok();
+    }
+
+    /// Attach the session database so tool calls can be audited and symbols indexed
+    pub fn set_session_store(&mut self, store: SessionStore) {
+        let index = SymbolIndexTool::new().with_database(store.db.clone());
+        let references = FindReferencesTool::new().with_index(index.clone());
+        self.tools.refactor = Arc::new(RefactorTool::new().with_references(references.clone()));
+        self.tools.references = Arc::new(references);
+        self.tools.dead_code = Arc::new(DeadCodeTool::new().with_index(index.clone()));
+        self.tools.symbol_index = Arc::new(index);
+        self.tools.build_errors = Arc::new(BuildErrorKb::new().with_store(store.clone()));
+        self.tools.list_directory = Arc::new(ListDirectoryTool::default().with_database(store.db.clone()));
+        self.tools.memory = Arc::new(MemoryTool::new().with_database(store.db.clone()).with_embeddings());
+        self.tools.owners = Arc::new(OwnershipTool::new().with_database(store.db.clone()));
+        self.tools.benchmarks = Arc::new(BenchmarkTool::new().with_database(store.db.clone()));
+        self.session_store = Some(store);
+    }
+
     /// Get shared state
     pub fn state(&self) -> SharedState {
         self.state.clone()
     }
 
+    /// Get the model configuration
+    pub fn config(&self) -> &OrchestratorConfig {
+        &self.config
+    }
+
+    /// Files written since the previous call, with their content before and now
+    pub fn take_file_changes(&self) -> Vec<FileChange> {
+        let changes = std::mem::take(&mut *self.file_changes.lock().unwrap_or_else(|e| e.into_inner()));
+        changes
+            .into_iter()
+            .filter_map(|(path, before)| {
+                let after = std::fs::read_to_string(&path).ok()?;
+                (before.as_deref() != Some(after.as_str())).then_some(FileChange { path, before, after })
+            })
+            .collect()
+    }
+
+    /// Tool calls, token usage and model time since the previous call
+    pub fn take_turn_record(&self) -> TurnRecord {
+        std::mem::take(&mut *self.turn.lock().unwrap_or_else(|e| e.into_inner()))
+    }
+
+    /// Use `token` for the turn about to start; the caller keeps a clone to
+    /// cancel it without locking the orchestrator
+    pub fn set_cancel_token(&self, token: CancellationToken) {
+        *self.turn_cancel.lock().unwrap_or_else(|e| e.into_inner()) = token;
+    }
+
+    /// Token of the current turn
+    pub fn cancel_token(&self) -> CancellationToken {
+        self.turn_cancel.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// Time budget for the turn about to start; `None` lets it run unbounded
+    pub fn set_turn_budget(&self, budget: Option<TurnBudget>) {
+        *self.budget.lock().unwrap_or_else(|e| e.into_inner()) = budget;
+    }
+
+    fn turn_budget(&self) -> Option<TurnBudget> {
+        *self.budget.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// A turn stopped by its budget can be resumed with [`Self::resume`]
+    pub fn has_continuation(&self) -> bool {
+        self.continuation.lock().unwrap_or_else(|e| e.into_inner()).is_some()
+    }
+
+    /// Resume the tool loop the budget stopped: run the calls left pending and
+    /// let the model finish. `None` when there is nothing to resume.
+    pub async fn resume(&self) -> Result<Option<OrchestratorResponse>, OrchestratorError> {
+        let continuation = self.continuation.lock().unwrap_or_else(|e| e.into_inner()).take();
+        let Some(Continuation { model, conversation, pending }) = continuation else {
+            return Ok(None);
+        };
+        let content = self.native_tool_loop(&model, conversation, pending).await?;
+        self.state.lock().await.add_message(Message::assistant(&content, &model));
+        Ok(Some(OrchestratorResponse::Immediate { content, model }))
+    }
+
+    /// Run `work` unless the turn is cancelled or its budget runs out first.
+    /// Dropping the future aborts its HTTP request and kills the process
+    /// groups of tool subprocesses.
+    async fn cancellable<T>(
+        &self,
+        work: impl std::future::Future<Output = Result<T, OrchestratorError>>,
+    ) -> Result<T, OrchestratorError> {
+        let cancel = self.cancel_token();
+        let budget = self.turn_budget();
+        let deadline = async {
+            match budget {
+                Some(budget) => tokio::time::sleep_until(budget.deadline().into()).await,
+                None => std::future::pending().await,
+            }
+        };
+        tokio::select! {
+            biased;
+            _ = cancel.cancelled() => Err(OrchestratorError::Cancelled),
+            _ = deadline => Err(OrchestratorError::Timeout(budget.map_or(0, |b| b.limit().as_secs()))),
+            result = work => result,
+        }
+    }
+
+    /// Stop the tool loop for the budget: keep the conversation for `/continue`
+    /// and answer with what was done and what remains
+    fn stop_for_budget(
+        &self,
+        budget: &TurnBudget,
+        model: &str,
+        conversation: Vec<serde_json::Value>,
+        draft: Option<&str>,
+        pending: Vec<OllamaToolCall>,
+    ) -> String {
+        tracing::info!("Turn budget of {}s ran out with {} tool call(s) pending", budget.limit().as_secs(), pending.len());
+        let answer = {
+            let turn = self.turn.lock().unwrap_or_else(|e| e.into_inner());
+            turn_budget::partial_answer(budget, draft, &turn.tool_calls, &pending)
+        };
+        *self.continuation.lock().unwrap_or_else(|e| e.into_inner()) = Some(Continuation {
+            model: model.to_string(),
+            conversation,
+            pending,
+        });
+        answer
+    }
+
+    /// Add a model request to the turn record, with the token counts Ollama
+    /// reports (`prompt_eval_count`, `eval_count`) or else estimates; returns
+    /// the answer without the model's reasoning
+    fn record_model_call(
+        &self,
+        model: &str,
+        started: Instant,
+        response: Option<&serde_json::Value>,
+        prompt_estimate: usize,
+        completion: &str,
+    ) -> String {
+        // The reasoning is recorded apart and left out of the completion tokens
+        let (answer, tagged) = reasoning::split(completion);
+        let thinking = response
+            .and_then(|json| json["thinking"].as_str().or(json["message"]["thinking"].as_str()))
+            .map(str::trim)
+            .filter(|thinking| !thinking.is_empty())
+            .map(String::from)
+            .or(tagged);
+        let reasoning_tokens = thinking.as_deref().map(estimate_tokens).unwrap_or(0);
+
+        let reported = response.and_then(|json| {
+            Some((json["prompt_eval_count"].as_u64()? as usize, json["eval_count"].as_u64()? as usize))
+        });
+        let (prompt, completion, estimated) = match reported {
+            Some((prompt, completion)) => (prompt, completion.saturating_sub(reasoning_tokens), false),
+            None => (prompt_estimate, estimate_tokens(&answer), true),
+        };
+        let mut turn = self.turn.lock().unwrap_or_else(|e| e.into_inner());
+        turn.model_call(model, started.elapsed(), prompt, completion, estimated);
+        if let Some(thinking) = &thinking {
+            turn.reasoning(thinking);
+        }
+        answer
+    }
+
+    /// Mutating call waiting for the user's approval, if any
+    pub fn pending_confirmation(&self) -> Option<PendingToolCall> {
+        self.pending_confirmation.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// Remove the call waiting for approval, e.g. when the user declines it
+    pub fn take_pending_confirmation(&self) -> Option<PendingToolCall> {
+        self.pending_confirmation.lock().unwrap_or_else(|e| e.into_inner()).take()
+    }
+
+    /// Run a call the user approved in the confirmation dialog
+    pub async fn execute_confirmed(&self, call: PendingToolCall) -> String {
+        self.run_recorded(&call.tool_name, &call.args, true).await
+    }
+
+    /// Hold a mutating call until the user answers; returns the note the model sees
+    pub fn hold_for_confirmation(
+        &self,
+        tool_name: &str,
+        args: &serde_json::Value,
+        summary: String,
+        risk_level: &str,
+    ) -> String {
+        let message = format!(
+            "Waiting for the user to approve: {}\nNothing was changed yet. Do not call the tool \
+             again; the user is asked to confirm it once this turn ends.",
+            summary
+        );
+        *self.pending_confirmation.lock().unwrap_or_else(|e| e.into_inner()) = Some(PendingToolCall {
+            tool_name: tool_name.to_string(),
+            args: args.clone(),
+            summary,
+            risk_level: risk_level.to_string(),
+        });
+        message
+    }
+
     /// Get tool registry
     pub fn tools(&self) -> &ToolRegistry {
         &self.tools
@@ -158,6 +551,8 @@ impl DualModelOrchestrator {
         &mut self,
         input: &str,
     ) -> Result<OrchestratorResponse, OrchestratorError> {
+        self.tools.file_read.reader().start_turn();
+
         // First, try fast classification
         let task_type = self
             .classifier
@@ -287,11 +682,21 @@ impl DualModelOrchestrator {
             message.to_string()
         };
 
-        // LAYER 1: Native function calling (95% confidence)
-        match self
-            .call_ollama_with_native_tools(&self.config.fast_model.clone(), &effective_message)
-            .await
+        // LAYER 1: Native function calling (95% confidence), skipped for models without it
+        let native_tools = if self
+            .capabilities
+            .supports(&self.config.fast_model, Capability::Tools)
         {
+            self.call_ollama_with_native_tools(&self.config.fast_model.clone(), &effective_message)
+                .await
+        } else {
+            Err(OrchestratorError::ModelError(format!(
+                "{} does not support native tool calls",
+                self.config.fast_model
+            )))
+        };
+
+        match native_tools {
             Ok(response) => {
                 tracing::info!("Layer 1 (native tools) succeeded");
                 
@@ -306,6 +711,8 @@ impl DualModelOrchestrator {
                     model: self.config.fast_model.clone(),
                 });
             }
+            // The user stopped the turn or it ran out of time: no fallback
+            Err(e @ (OrchestratorError::Cancelled | OrchestratorError::Timeout(_))) => return Err(e),
             Err(e) => {
                 tracing::warn!("Layer 1 (native tools) failed: {}, falling back to Layer 2", e);
             }
@@ -508,192 +915,301 @@ impl DualModelOrchestrator {
 
     /// Call heavy model directly with a prompt (public for PlanningOrchestrator)
     pub async fn call_heavy_model_direct(&self, prompt: &str) -> Result<String, OrchestratorError> {
-        let client = reqwest::Client::new();
+        let client = network::client();
 
-        let request_body = serde_json::json!({
+        let mut request_body = serde_json::json!({
             "model": self.config.heavy_model,
             "prompt": prompt,
             "stream": false,
+            "keep_alive": HEAVY_KEEP_ALIVE,
             "options": {
                 "temperature": 0.7,
                 "num_predict": 4096
             }
         });
+        reasoning::apply_options(&mut request_body);
+        // Code written by the heavy model follows the user's preferences too
+        let working_dir = self.state.lock().await.working_dir.clone();
+        let preferences = self.preferences(&working_dir).await.instructions(crate::i18n::current_locale());
+        if !preferences.is_empty() {
+            request_body["system"] = serde_json::Value::String(format!("- {}", preferences.join("\n- ")));
+        }
 
-        let response = client
-            .post(format!("{}/api/generate", self.config.ollama_url))
-            .json(&request_body)
-            .timeout(Duration::from_secs(self.config.heavy_timeout_secs))
-            .send()
-            .await
-            .map_err(|e| OrchestratorError::ModelError(e.to_string()))?;
+        let started = Instant::now();
+        let response_json: serde_json::Value = self
+            .cancellable(async {
+                client
+                    .post(format!("{}/api/generate", self.config.ollama_url))
+                    .json(&request_body)
+                    .timeout(Duration::from_secs(self.config.heavy_timeout_secs))
+                    .send_checked("model provider")
+                    .await
+                    .map_err(|e| OrchestratorError::ModelError(e.to_string()))?
+                    .json()
+                    .await
+                    .map_err(|e| OrchestratorError::ModelError(e.to_string()))
+            })
+            .await?;
 
-        let response_json: serde_json::Value = response
-            .json()
+        let content = response_json["response"].as_str().unwrap_or("");
+        let content = self.record_model_call(&self.config.heavy_model, started, Some(&response_json), estimate_tokens(prompt), content);
+
+        Ok(content)
+    }
+
+    /// Load the heavy model ahead of its first request and keep it loaded for
+    /// [`HEAVY_KEEP_ALIVE`]; a generate request without a prompt only loads it.
+    /// Static so it can run in the background without the orchestrator lock.
+    pub async fn warm_heavy_model(ollama_url: &str, model: &str) -> Result<(), OrchestratorError> {
+        network::client()
+            .post(format!("{}/api/generate", ollama_url))
+            .json(&serde_json::json!({ "model": model, "keep_alive": HEAVY_KEEP_ALIVE }))
+            .send_checked("model provider")
             .await
+            .map_err(|e| OrchestratorError::ModelError(e.to_string()))?
+            .error_for_status()
             .map_err(|e| OrchestratorError::ModelError(e.to_string()))?;
+        Ok(())
+    }
 
-        let content = response_json["response"].as_str().unwrap_or("").to_string();
-
-        Ok(content)
+    pub async fn call_heavy_model_streaming(&self, prompt: &str, events: &EventBus) -> Result<(), OrchestratorError> {
+        Self::stream_heavy_model_static(
+            &self.config.ollama_url,
+            &self.config.heavy_model,
+            self.config.heavy_timeout_secs,
+            prompt,
+            events,
+            &self.cancel_token(),
+        )
+        .await
     }
 
-    pub async fn call_heavy_model_streaming(
-        &self,
+    /// Static version of call_heavy_model_streaming that doesn't require &self
+    /// This allows calling without holding a lock on the orchestrator
+    ///
+    /// A stream that stalls or breaks is reopened once to continue the partial
+    /// answer (see [`stream_watchdog`]); if that fails too, it ends with
+    /// `StreamAborted` instead of `StreamEnd` and the call still succeeds.
+    pub async fn stream_heavy_model_static(
+        ollama_url: &str,
+        model: &str,
+        timeout_secs: u64,
         prompt: &str,
-        tx: mpsc::Sender<crate::agent::AgentEvent>,
+        events: &EventBus,
+        cancel: &CancellationToken,
     ) -> Result<(), OrchestratorError> {
-        let client = reqwest::Client::new();
-
-        let request_body = serde_json::json!({
-            "model": self.config.heavy_model,
-            "prompt": prompt,
-            "stream": true,
-            "options": {
-                "temperature": 0.7,
-                "num_predict": 4096
-            }
-        });
+        use crate::{log_debug, log_error};
 
-        let mut response_stream = client
-            .post(format!("{}/api/generate", self.config.ollama_url))
-            .json(&request_body)
-            .timeout(Duration::from_secs(self.config.heavy_timeout_secs))
-            .send()
-            .await
-            .map_err(|e| OrchestratorError::ModelError(e.to_string()))?
-            .bytes_stream();
+        log_debug!("🌊 [STREAM] Starting static stream: model={}, timeout={}s", model, timeout_secs);
 
         #[derive(Deserialize)]
         struct OllamaStreamResponse {
             response: Option<String>,
+            /// Reasoning, when Ollama parses it out of the response
+            thinking: Option<String>,
             done: bool,
         }
 
-        while let Some(item) = response_stream.next().await {
-            let chunk = item.map_err(|e| OrchestratorError::ModelError(format!("Stream error: {}", e)))?;
-            let data = String::from_utf8_lossy(&chunk);
-
-            for line in data.lines() {
-                if line.is_empty() { continue; }
-                match serde_json::from_str::<OllamaStreamResponse>(line) {
-                    Ok(ollama_response) => {
-                        if let Some(content_chunk) = ollama_response.response {
-                            // Use try_send to avoid blocking - if channel is full, just skip this chunk
-                            let _ = tx.try_send(crate::agent::AgentEvent::Chunk(content_chunk));
+        let mut health = StreamHealth::default();
+        // The reasoning goes out as its own events, never as part of the answer
+        let mut splitter = ThinkSplitter::new();
+
+        loop {
+            let attempt_prompt = match health.reconnects {
+                0 => prompt.to_string(),
+                _ => stream_watchdog::continuation_prompt(prompt, &health.partial),
+            };
+            let response = match Self::open_generate_stream(ollama_url, model, timeout_secs, &attempt_prompt, cancel).await {
+                Ok(response) => response,
+                Err(OrchestratorError::Cancelled) => return Err(OrchestratorError::Cancelled),
+                // A first request that fails is an ordinary error, nothing was streamed yet
+                Err(e) if health.reconnects == 0 => return Err(e),
+                Err(e) => {
+                    let failure = StreamFailure::Broken(e.to_string());
+                    return Self::abort_stream(events, &mut splitter, &health, failure).await;
+                }
+            };
+            let mut response_stream = response.bytes_stream();
+            log_debug!("🌊 [STREAM] Response stream started, processing chunks...");
+
+            let failure = loop {
+                // Dropping the stream closes the connection, so Ollama stops generating
+                let item = tokio::select! {
+                    biased;
+                    _ = cancel.cancelled() => {
+                        log_debug!("🌊 [STREAM] Cancelled after {} chunks", health.chunks);
+                        return Err(OrchestratorError::Cancelled);
+                    }
+                    item = stream_watchdog::next_item(&mut response_stream) => item,
+                };
+                let chunk = match item {
+                    Ok(Some(Ok(chunk))) => chunk,
+                    Ok(Some(Err(e))) => {
+                        log_error!("🌊 [STREAM] Stream error: {}", e);
+                        break StreamFailure::Broken(e.to_string());
+                    }
+                    Err(stalled) => break stalled,
+                    Ok(None) => {
+                        log_debug!("🌊 [STREAM] Stream ended naturally (sent {} chunks total)", health.chunks);
+                        send_segments(events, splitter.finish()).await;
+                        events.send(AgentEvent::StreamEnd).await;
+                        return Ok(());
+                    }
+                };
+                health.received(chunk.len());
+                let data = String::from_utf8_lossy(&chunk);
+
+                for line in data.lines() {
+                    if line.is_empty() { continue; }
+                    match serde_json::from_str::<OllamaStreamResponse>(line) {
+                        Ok(ollama_response) => {
+                            if let Some(thinking) = ollama_response.thinking.filter(|thinking| !thinking.is_empty()) {
+                                events.send(AgentEvent::Thinking(thinking)).await;
+                            }
+                            if let Some(content_chunk) = ollama_response.response {
+                                let segments = splitter.push(&content_chunk);
+                                for segment in &segments {
+                                    if let Segment::Answer(text) = segment {
+                                        health.partial.push_str(text);
+                                    }
+                                }
+                                // Waits while a subscriber's mailbox is full rather than losing text
+                                send_segments(events, segments).await;
+                            }
+                            if ollama_response.done {
+                                log_debug!("🌊 [STREAM] Stream completed successfully ({} chunks)", health.chunks);
+                                send_segments(events, splitter.finish()).await;
+                                events.send(AgentEvent::StreamEnd).await;
+                                return Ok(());
+                            }
                         }
-                        if ollama_response.done {
-                            // Try to send StreamEnd, don't block if channel is full
-                            let _ = tx.try_send(crate::agent::AgentEvent::StreamEnd);
-                            return Ok(());
+                        Err(e) => {
+                            tracing::warn!("Failed to parse stream chunk: {}. Chunk: {}", e, line);
                         }
                     }
-                    Err(e) => {
-                        tracing::warn!("Failed to parse stream chunk: {}. Chunk: {}", e, line);
-                        // Continue to try and parse next line/chunk rather than fail the whole stream
-                    }
                 }
+            };
+
+            if !health.try_reconnect() {
+                return Self::abort_stream(events, &mut splitter, &health, failure).await;
             }
+            log_warn!(
+                "🌊 [STREAM] {} after {} bytes, reconnecting to continue the answer",
+                failure, health.bytes
+            );
+            events.send(AgentEvent::Status(format!("Reconectando con el modelo ({})...", failure))).await;
         }
-
-        // If stream ends without 'done: true', ensure StreamEnd is sent (non-blocking)
-        let _ = tx.try_send(crate::agent::AgentEvent::StreamEnd);
-        Ok(())
     }
 
-    /// Static version of call_heavy_model_streaming that doesn't require &self
-    /// This allows calling without holding a lock on the orchestrator
-    pub async fn stream_heavy_model_static(
+    /// Send a streaming `/api/generate` request
+    async fn open_generate_stream(
         ollama_url: &str,
         model: &str,
         timeout_secs: u64,
         prompt: &str,
-        tx: mpsc::Sender<crate::agent::AgentEvent>,
-    ) -> Result<(), OrchestratorError> {
+        cancel: &CancellationToken,
+    ) -> Result<reqwest::Response, OrchestratorError> {
         use crate::{log_debug, log_error};
 
-        log_debug!("🌊 [STREAM] Starting static stream: model={}, timeout={}s", model, timeout_secs);
-
-        let client = reqwest::Client::new();
-
-        let request_body = serde_json::json!({
+        let mut request_body = serde_json::json!({
             "model": model,
             "prompt": prompt,
             "stream": true,
+            "keep_alive": HEAVY_KEEP_ALIVE,
             "options": {
                 "temperature": 0.7,
                 "num_predict": 4096
             }
         });
+        reasoning::apply_options(&mut request_body);
 
         log_debug!("🌊 [STREAM] Sending request to {}/api/generate", ollama_url);
 
-        let mut response_stream = client
+        let request = network::client()
             .post(format!("{}/api/generate", ollama_url))
             .json(&request_body)
             .timeout(Duration::from_secs(timeout_secs))
-            .send()
-            .await
-            .map_err(|e| {
-                log_error!("🌊 [STREAM] Request failed: {}", e);
-                OrchestratorError::ModelError(e.to_string())
-            })?
-            .bytes_stream();
-
-        log_debug!("🌊 [STREAM] Response stream started, processing chunks...");
+            .send_checked("model provider");
+        let response = tokio::select! {
+            biased;
+            _ = cancel.cancelled() => return Err(OrchestratorError::Cancelled),
+            response = request => response,
+        };
+        response.map_err(|e| {
+            log_error!("🌊 [STREAM] Request failed: {}", e);
+            OrchestratorError::ModelError(e.to_string())
+        })
+    }
 
-        #[derive(Deserialize)]
-        struct OllamaStreamResponse {
-            response: Option<String>,
-            done: bool,
+    /// Give up a stream, keeping what was answered
+    async fn abort_stream(
+        events: &EventBus,
+        splitter: &mut ThinkSplitter,
+        health: &StreamHealth,
+        failure: StreamFailure,
+    ) -> Result<(), OrchestratorError> {
+        log_warn!(
+            "🌊 [STREAM] Aborted: {} ({} bytes, {} reconnects)",
+            failure, health.bytes, health.reconnects
+        );
+        let segments = splitter.finish();
+        let mut partial = health.partial.clone();
+        for segment in &segments {
+            if let Segment::Answer(text) = segment {
+                partial.push_str(text);
+            }
         }
+        send_segments(events, segments).await;
+        events
+            .send(AgentEvent::StreamAborted {
+                partial,
+                reason: failure.to_string(),
+            })
+            .await;
+        Ok(())
+    }
 
-        let mut chunk_count = 0;
-
-        while let Some(item) = response_stream.next().await {
-            let chunk = item.map_err(|e| {
-                log_error!("🌊 [STREAM] Stream error: {}", e);
-                OrchestratorError::ModelError(format!("Stream error: {}", e))
-            })?;
-            let data = String::from_utf8_lossy(&chunk);
-
-            for line in data.lines() {
-                if line.is_empty() { continue; }
-                match serde_json::from_str::<OllamaStreamResponse>(line) {
-                    Ok(ollama_response) => {
-                        if let Some(content_chunk) = ollama_response.response {
-                            chunk_count += 1;
-                            if let Err(e) = tx.try_send(crate::agent::AgentEvent::Chunk(content_chunk)) {
-                                log_error!("🌊 [STREAM] Failed to send chunk {}: {:?}", chunk_count, e);
-                            }
-                        }
-                        if ollama_response.done {
-                            log_debug!("🌊 [STREAM] Stream completed successfully (sent {} chunks)", chunk_count);
-                            if let Err(e) = tx.try_send(crate::agent::AgentEvent::StreamEnd) {
-                                log_error!("🌊 [STREAM] CRITICAL: Failed to send StreamEnd: {:?}", e);
-                            }
-                            return Ok(());
-                        }
-                    }
-                    Err(e) => {
-                        tracing::warn!("Failed to parse stream chunk: {}. Chunk: {}", e, line);
-                    }
-                }
+    /// Call a named model directly with a prompt (reviews with a configured model)
+    pub async fn call_model_direct(&self, model: &str, prompt: &str) -> Result<String, OrchestratorError> {
+        let client = network::client();
+
+        let mut request_body = serde_json::json!({
+            "model": model,
+            "prompt": prompt,
+            "stream": false,
+            "options": {
+                "temperature": 0.1,
+                "num_predict": 1024
             }
-        }
+        });
+        reasoning::apply_options(&mut request_body);
+
+        let started = Instant::now();
+        let response_json: serde_json::Value = self
+            .cancellable(async {
+                client
+                    .post(format!("{}/api/generate", self.config.ollama_url))
+                    .json(&request_body)
+                    .timeout(Duration::from_secs(self.config.heavy_timeout_secs))
+                    .send_checked("model provider")
+                    .await
+                    .map_err(|e| OrchestratorError::ModelError(e.to_string()))?
+                    .json()
+                    .await
+                    .map_err(|e| OrchestratorError::ModelError(e.to_string()))
+            })
+            .await?;
 
-        log_debug!("🌊 [STREAM] Stream ended naturally (sent {} chunks total)", chunk_count);
-        if let Err(e) = tx.try_send(crate::agent::AgentEvent::StreamEnd) {
-            log_error!("🌊 [STREAM] CRITICAL: Failed to send final StreamEnd: {:?}", e);
-        }
-        Ok(())
+        let content = response_json["response"].as_str().unwrap_or("");
+        let content = self.record_model_call(model, started, Some(&response_json), estimate_tokens(prompt), content);
+        Ok(content)
     }
 
     /// Call fast model directly with a prompt (for quick summaries)
     pub async fn call_fast_model_direct(&self, prompt: &str) -> Result<String, OrchestratorError> {
-        let client = reqwest::Client::new();
+        let client = network::client();
 
-        let request_body = serde_json::json!({
+        let mut request_body = serde_json::json!({
             "model": self.config.fast_model,
             "prompt": prompt,
             "stream": false,
@@ -702,21 +1218,26 @@ impl DualModelOrchestrator {
                 "num_predict": 256
             }
         });
+        reasoning::apply_options(&mut request_body);
+
+        let started = Instant::now();
+        let response_json: serde_json::Value = self
+            .cancellable(async {
+                client
+                    .post(format!("{}/api/generate", self.config.ollama_url))
+                    .json(&request_body)
+                    .timeout(Duration::from_secs(90))
+                    .send_checked("model provider")
+                    .await
+                    .map_err(|e| OrchestratorError::ModelError(e.to_string()))?
+                    .json()
+                    .await
+                    .map_err(|e| OrchestratorError::ModelError(e.to_string()))
+            })
+            .await?;
 
-        let response = client
-            .post(format!("{}/api/generate", self.config.ollama_url))
-            .json(&request_body)
-            .timeout(Duration::from_secs(90))
-            .send()
-            .await
-            .map_err(|e| OrchestratorError::ModelError(e.to_string()))?;
-
-        let response_json: serde_json::Value = response
-            .json()
-            .await
-            .map_err(|e| OrchestratorError::ModelError(e.to_string()))?;
-
-        let content = response_json["response"].as_str().unwrap_or("").to_string();
+        let content = response_json["response"].as_str().unwrap_or("");
+        let content = self.record_model_call(&self.config.fast_model, started, Some(&response_json), estimate_tokens(prompt), content);
 
         Ok(content)
     }
@@ -727,7 +1248,7 @@ impl DualModelOrchestrator {
         model: &str,
         user_message: &str,
     ) -> Result<String, OrchestratorError> {
-        let client = reqwest::Client::new();
+        let client = network::client();
         let working_dir = {
             let state = self.state.lock().await;
             state.working_dir.clone()
@@ -750,7 +1271,9 @@ impl DualModelOrchestrator {
         let max_iterations = 10;
 
         for _iteration in 0..max_iterations {
-            let request_body = serde_json::json!({
+            let prompt_tokens = self.record_context_budget(model, &system_prompt, &[], None, &conversation).await;
+            let started = Instant::now();
+            let mut request_body = serde_json::json!({
                 "model": model,
                 "messages": conversation,
                 "stream": false,
@@ -759,24 +1282,25 @@ impl DualModelOrchestrator {
                     "num_predict": 4096
                 }
             });
-
-            let response = client
-                .post(format!("{}/api/chat", self.config.ollama_url))
-                .json(&request_body)
-                .timeout(Duration::from_secs(300))
-                .send()
-                .await
-                .map_err(|e| OrchestratorError::ModelError(e.to_string()))?;
-
-            let response_json: serde_json::Value = response
-                .json()
-                .await
-                .map_err(|e| OrchestratorError::ModelError(e.to_string()))?;
-
-            let content = response_json["message"]["content"]
-                .as_str()
-                .unwrap_or("")
-                .to_string();
+            reasoning::apply_options(&mut request_body);
+
+            let response_json: serde_json::Value = self
+                .cancellable(async {
+                    client
+                        .post(format!("{}/api/chat", self.config.ollama_url))
+                        .json(&request_body)
+                        .timeout(Duration::from_secs(300))
+                        .send_checked("model provider")
+                        .await
+                        .map_err(|e| OrchestratorError::ModelError(e.to_string()))?
+                        .json()
+                        .await
+                        .map_err(|e| OrchestratorError::ModelError(e.to_string()))
+                })
+                .await?;
+
+            let content = response_json["message"]["content"].as_str().unwrap_or("");
+            let content = self.record_model_call(model, started, Some(&response_json), prompt_tokens, content);
 
             // Check if the model wants to use a tool
             if let Some((tool_name, tool_args)) = self.parse_tool_call_from_response(&content) {
@@ -818,50 +1342,131 @@ impl DualModelOrchestrator {
         model: &str,
         user_message: &str,
     ) -> Result<String, OrchestratorError> {
-        use crate::agent::provider::OllamaProvider;
-        use crate::agent::{build_minimal_system_prompt, PromptConfig};
-        use crate::config::{ModelConfig, ModelProvider as ProviderType};
-        use crate::i18n::current_locale;
-
-        let working_dir = {
-            let state = self.state.lock().await;
-            state.working_dir.clone()
-        };
+        // A new question replaces any turn left for /continue
+        self.continuation.lock().unwrap_or_else(|e| e.into_inner()).take();
 
-        // Create provider
-        let provider_config = ModelConfig {
-            provider: ProviderType::Ollama,
-            url: self.config.ollama_url.clone(),
-            model: model.to_string(),
-            api_key: None,
-            temperature: 0.7,
-            top_p: 0.95,
-            max_tokens: Some(4096),
-        };
-        let provider = OllamaProvider::new(provider_config);
-
-        // Get tools schema
-        let tools_schema = self.tools.get_ollama_tools_schema().await;
-
-        // Build minimal system prompt
-        let prompt_config = PromptConfig::new(working_dir, current_locale());
-        let system_prompt = build_minimal_system_prompt(&prompt_config);
-
-        // Initialize conversation
-        let mut conversation = vec![
+        let conversation = vec![
             serde_json::json!({
                 "role": "system",
-                "content": system_prompt
+                "content": self.native_system_prompt(user_message).await
             }),
             serde_json::json!({
                 "role": "user",
                 "content": user_message
             }),
         ];
+        self.native_tool_loop(model, conversation, Vec::new()).await
+    }
+
+    /// The user's preferences for `working_dir`, empty without a session database
+    async fn preferences(&self, working_dir: &str) -> Preferences {
+        let Some(store) = &self.session_store else {
+            return Preferences::default();
+        };
+        Preferences::load(&store.db, working_dir).await.unwrap_or_else(|e| {
+            log_warn!("⚠️ Failed to load preferences: {}", e);
+            Preferences::default()
+        })
+    }
+
+    /// System prompt of the native tool loop, with the project's instructions
+    /// and the remembered facts and example answers closest to `query`
+    async fn native_system_prompt(&self, query: &str) -> String {
+        use crate::agent::{build_minimal_system_prompt, PromptConfig};
+        use crate::i18n::current_locale;
 
+        let (working_dir, read_only_roots) = {
+            let state = self.state.lock().await;
+            (state.working_dir.clone(), state.read_only_roots.clone())
+        };
+        let mut prompt_config = PromptConfig::new(working_dir, current_locale());
+        prompt_config.read_only_roots = read_only_roots.iter().map(|root| root.display().to_string()).collect();
+        prompt_config.project_instructions = crate::config::ProjectConfig::discover(Path::new(&prompt_config.working_dir))
+            .ok()
+            .and_then(|project| project.instructions());
+        prompt_config.memories = self.tools.memory.prompt_facts(&prompt_config.working_dir, query).await;
+        prompt_config.examples = self.tools.memory.prompt_examples(&prompt_config.working_dir, query).await;
+        prompt_config.preferences = self.preferences(&prompt_config.working_dir).await.instructions(prompt_config.locale);
+        build_minimal_system_prompt(&prompt_config)
+    }
+
+    /// Native function calling loop: run the `pending` tool calls, ask the
+    /// model, and repeat until it answers without tools. When the turn budget
+    /// winds down no new calls start and a partial answer is returned instead.
+    async fn native_tool_loop(
+        &self,
+        model: &str,
+        mut conversation: Vec<serde_json::Value>,
+        mut pending: Vec<OllamaToolCall>,
+    ) -> Result<String, OrchestratorError> {
+        use crate::agent::provider::OllamaProvider;
+        use crate::config::{ModelConfig, ModelProvider as ProviderType};
+
+        let (working_dir, disabled_tools) = {
+            let state = self.state.lock().await;
+            (state.working_dir.clone(), state.disabled_tools.clone())
+        };
+
+        // Create provider
+        let provider_config = ModelConfig {
+            provider: ProviderType::Ollama,
+            url: self.config.ollama_url.clone(),
+            model: model.to_string(),
+            api_key: None,
+            temperature: 0.7,
+            top_p: 0.95,
+            max_tokens: Some(4096),
+        };
+        let provider = OllamaProvider::new(provider_config);
+
+        // Get tools schema, without the tools turned off for this project
+        let mut tools_schema = self.tools.get_ollama_tools_schema().await;
+        tools_schema.retain(|tool| !disabled_tools.contains(&tool.function.name));
+
+        let system_prompt = conversation
+            .first()
+            .and_then(|message| message["content"].as_str())
+            .unwrap_or_default()
+            .to_string();
+        let pinned = crate::config::ProjectConfig::discover(Path::new(&working_dir))
+            .ok()
+            .and_then(|project| project.instructions());
+
+        let budget = self.turn_budget();
+        let mut draft: Option<String> = None;
         let max_iterations = 10;
 
         for iteration in 0..max_iterations {
+            // Execute tool calls SEQUENTIALLY (respecting dependencies), except
+            // runs of consecutive reads, which the file reader bounds itself
+            let calls = std::mem::take(&mut pending);
+            let mut started_calls = 0;
+            for batch in calls.chunk_by(|a, b| {
+                a.function.name == FileReadTool::NAME && b.function.name == FileReadTool::NAME
+            }) {
+                if let Some(budget) = budget.filter(TurnBudget::winding_down) {
+                    let left = calls[started_calls..].to_vec();
+                    return Ok(self.stop_for_budget(&budget, model, conversation, draft.as_deref(), left));
+                }
+                started_calls += batch.len();
+
+                let results = futures::future::join_all(batch.iter().map(|tool_call| {
+                    let tool_name = &tool_call.function.name;
+                    let tool_args = &tool_call.function.arguments;
+                    tracing::info!("Executing tool: {} with args: {:?}", tool_name, tool_args);
+                    self.execute_tool(tool_name, tool_args)
+                }))
+                .await;
+
+                // Add tool results as tool messages, in call order
+                for tool_result in results {
+                    conversation.push(serde_json::json!({
+                        "role": "tool",
+                        "content": tool_result
+                    }));
+                }
+            }
+
             tracing::debug!(
                 "Native function calling iteration {}/{} for model: {}",
                 iteration + 1,
@@ -869,44 +1474,55 @@ impl DualModelOrchestrator {
                 model
             );
 
+            let prompt_tokens = self
+                .record_context_budget(model, &system_prompt, &tools_schema, pinned.as_deref(), &conversation)
+                .await;
+
             // Call model with tools
-            let message = provider
-                .generate_with_tools(conversation.clone(), Some(tools_schema.clone()))
+            let started = Instant::now();
+            let mut message = match self
+                .cancellable(async {
+                    provider
+                        .generate_with_tools(conversation.clone(), Some(tools_schema.clone()))
+                        .await
+                        .map_err(|e| OrchestratorError::ModelError(e.to_string()))
+                })
                 .await
-                .map_err(|e| OrchestratorError::ModelError(e.to_string()))?;
-
-            // Check for tool calls
-            if let Some(tool_calls) = &message.tool_calls {
-                if !tool_calls.is_empty() {
-                    tracing::info!("Model requested {} tool call(s)", tool_calls.len());
-
-                    // Add assistant message to conversation
-                    conversation.push(serde_json::json!({
-                        "role": "assistant",
-                        "content": message.content.clone().unwrap_or_default(),
-                        "tool_calls": tool_calls
-                    }));
-
-                    // Execute tool calls SEQUENTIALLY (respecting dependencies)
-                    for tool_call in tool_calls {
-                        let tool_name = &tool_call.function.name;
-                        let tool_args = &tool_call.function.arguments;
-
-                        tracing::info!("Executing tool: {} with args: {:?}", tool_name, tool_args);
-
-                        // Execute the tool
-                        let tool_result = self.execute_tool(tool_name, tool_args).await;
+            {
+                Ok(message) => message,
+                Err(OrchestratorError::Timeout(secs)) => {
+                    return match budget {
+                        Some(budget) => Ok(self.stop_for_budget(&budget, model, conversation, draft.as_deref(), Vec::new())),
+                        None => Err(OrchestratorError::Timeout(secs)),
+                    };
+                }
+                Err(e) => return Err(e),
+            };
+            let completion = match &message.tool_calls {
+                Some(calls) => serde_json::to_string(calls).unwrap_or_default(),
+                None => message.content.clone().unwrap_or_default(),
+            };
+            let answer = self.record_model_call(model, started, None, prompt_tokens, &completion);
+            if let Some(thinking) = message.thinking.as_deref().filter(|thinking| !thinking.trim().is_empty()) {
+                self.turn.lock().unwrap_or_else(|e| e.into_inner()).reasoning(thinking.trim());
+            }
+            if message.tool_calls.is_none() && message.content.is_some() {
+                message.content = Some(answer);
+            }
 
-                        // Add tool result as a tool message
-                        conversation.push(serde_json::json!({
-                            "role": "tool",
-                            "content": tool_result
-                        }));
-                    }
+            // Check for tool calls; they run at the top of the next iteration
+            if let Some(tool_calls) = message.tool_calls.filter(|calls| !calls.is_empty()) {
+                tracing::info!("Model requested {} tool call(s)", tool_calls.len());
 
-                    // Continue loop to get model's response with tool results
-                    continue;
-                }
+                // Add assistant message to conversation
+                conversation.push(serde_json::json!({
+                    "role": "assistant",
+                    "content": message.content.clone().unwrap_or_default(),
+                    "tool_calls": tool_calls
+                }));
+                draft = message.content.filter(|content| !content.trim().is_empty()).or(draft);
+                pending = tool_calls;
+                continue;
             }
 
             // No tool calls, this is the final response
@@ -924,6 +1540,30 @@ impl DualModelOrchestrator {
         ))
     }
 
+    /// Record how the request about to be sent fills the window of `model`;
+    /// returns the estimated prompt size in tokens
+    async fn record_context_budget(
+        &self,
+        model: &str,
+        system_prompt: &str,
+        tools: &[OllamaTool],
+        pinned: Option<&str>,
+        conversation: &[serde_json::Value],
+    ) -> usize {
+        let (retrieved, messages, max_history) = {
+            let state = self.state.lock().await;
+            let messages = state.messages.iter().filter(|m| m.role != MessageRole::System).count();
+            (state.retrieval_tokens, messages, state.max_history)
+        };
+        let budget = ContextBudget::new(model, self.capabilities.lookup(model).context_window)
+            .with_system(system_prompt, tools, pinned)
+            .with_conversation(conversation, retrieved)
+            .with_history(messages, max_history);
+        let used = budget.used();
+        context_budget::record(budget);
+        used
+    }
+
     /// Proactive tool execution - pre-execute obvious tools before LLM call
     ///
     /// This method analyzes the user query and determines if it clearly requires
@@ -1223,7 +1863,9 @@ User: "qué archivos hay en src" or "what's in src"
             let cmd = if lower.contains("status") || lower.contains("estado") {
                 "git status"
             } else if lower.contains("diff") || lower.contains("cambios") {
-                "git diff"
+                // Summarized: lockfile, generated and binary changes collapsed
+                let result = self.execute_tool("git_diff", &serde_json::json!({})).await;
+                return Ok(result);
             } else if lower.contains("log")
                 || lower.contains("historial")
                 || lower.contains("commits")
@@ -1468,9 +2110,9 @@ User: "qué archivos hay en src" or "what's in src"
         model: &str,
         prompt: &str,
     ) -> Result<String, OrchestratorError> {
-        let client = reqwest::Client::new();
+        let client = network::client();
 
-        let request_body = serde_json::json!({
+        let mut request_body = serde_json::json!({
             "model": model,
             "messages": [
                 {
@@ -1480,24 +2122,24 @@ User: "qué archivos hay en src" or "what's in src"
             ],
             "stream": false
         });
+        reasoning::apply_options(&mut request_body);
+
+        let response_json: serde_json::Value = self
+            .cancellable(async {
+                client
+                    .post(format!("{}/api/chat", self.config.ollama_url))
+                    .json(&request_body)
+                    .timeout(Duration::from_secs(60))
+                    .send_checked("model provider")
+                    .await
+                    .map_err(|e| OrchestratorError::ModelError(e.to_string()))?
+                    .json()
+                    .await
+                    .map_err(|e| OrchestratorError::ModelError(e.to_string()))
+            })
+            .await?;
 
-        let response = client
-            .post(format!("{}/api/chat", self.config.ollama_url))
-            .json(&request_body)
-            .timeout(Duration::from_secs(60))
-            .send()
-            .await
-            .map_err(|e| OrchestratorError::ModelError(e.to_string()))?;
-
-        let response_json: serde_json::Value = response
-            .json()
-            .await
-            .map_err(|e| OrchestratorError::ModelError(e.to_string()))?;
-
-        let content = response_json["message"]["content"]
-            .as_str()
-            .unwrap_or("")
-            .to_string();
+        let (content, _) = reasoning::split(response_json["message"]["content"].as_str().unwrap_or(""));
 
         Ok(content)
     }
@@ -1553,9 +2195,9 @@ User: "explícame qué es Rust"
 Assistant: {{"action": "respond", "response_text": "Rust es un lenguaje..."}}
 
 HERRAMIENTAS DISPONIBLES:
-- read_file: Lee archivos (path, start_line, end_line)
+- read_file: Lee archivos (path, start_line, end_line, symbol, around, context_lines)
 - write_file: Escribe archivos (path, content, append, create_dirs)
-- list_directory: Lista directorios (path, recursive, max_depth)
+- list_directory: Lista directorios (path, recursive, max_depth, summary)
 - execute_shell: Ejecuta comandos (command, working_dir, timeout_secs)
 - run_linter: Ejecuta linter (path, check_only)
 
@@ -1571,16 +2213,101 @@ Directorio de trabajo actual: {}
         )
     }
 
+    /// Record the errors of a build or test run and append the fixes of the
+    /// ones seen before, so the model can reuse them
+    async fn note_known_errors(
+        &self,
+        output: &mut String,
+        root: &Path,
+        runner: BuildRunner,
+        run_output: &str,
+        success: bool,
+    ) {
+        match self
+            .tools
+            .build_errors
+            .observe(root, runner, run_output, success)
+            .await
+        {
+            Ok(Some(notes)) => {
+                output.push_str("\n\n");
+                output.push_str(&notes);
+            }
+            Ok(None) => {}
+            Err(e) => log_warn!("⚠️ Could not record build errors: {}", e),
+        }
+    }
+
+    /// Keep a profile as a `profile` artifact under the retention policy; its id
+    async fn store_profile(&self, store: &SessionStore, report: &crate::tools::ProfileReport) -> Option<i64> {
+        let kind = ArtifactKind::Profile;
+        let retention = self.tool_config.as_ref().map(|c| c.artifacts.clone()).unwrap_or_default();
+        let summary = format!("{}, {} samples", report.profiler, report.total_samples);
+        let artifact = Artifact::new(&store.session_id, kind.as_str(), format!("profile_code {}", report.command), report.profile.as_str(), summary)
+            .with_metadata(serde_json::json!({
+                "lines": report.profile.lines().count(),
+                "profiler": report.profiler,
+            }))
+            .with_expiry(retention.expires_at(kind, chrono::Utc::now()));
+        store
+            .db
+            .add_artifact(&artifact)
+            .await
+            .map_err(|e| log_warn!("⚠️ Could not store the profile: {}", e))
+            .ok()
+    }
+
     /// Execute a tool by name (public for PlanningOrchestrator)
     pub async fn execute_tool(&self, tool_name: &str, args: &serde_json::Value) -> String {
+        // `user_command` marks calls the user typed (/proc, /profile), which
+        // Plan mode does not apply to; a model's call cannot claim it
+        let mut args = args.clone();
+        if let Some(object) = args.as_object_mut() {
+            object.remove("user_command");
+        }
+        self.run_recorded(tool_name, &args, false).await
+    }
+
+    /// Run a tool and add the call to the turn record
+    async fn run_recorded(&self, tool_name: &str, args: &serde_json::Value, approved: bool) -> String {
+        let started = Instant::now();
+        let result = match self.cancellable(async { Ok(self.run_tool(tool_name, args, approved).await) }).await {
+            Ok(result) => result,
+            Err(OrchestratorError::Timeout(_)) => format!("Tool '{}' stopped: the turn ran out of time", tool_name),
+            Err(_) => format!("Tool '{}' cancelled by user", tool_name),
+        };
+        let record = ToolCallRecord::new(tool_name, args, &result, started.elapsed());
+        self.turn.lock().unwrap_or_else(|e| e.into_inner()).tool_calls.push(record);
+        result
+    }
+
+    /// Execute a tool; `approved` is only true for calls the user confirmed
+    async fn run_tool(&self, tool_name: &str, args: &serde_json::Value, approved: bool) -> String {
         use crate::tools::{
-            FileReadArgs, FileWriteArgs, LinterArgs, ListDirectoryArgs, ShellExecuteArgs,
+            classify_statement, BenchmarkArgs, DataPreviewArgs, DatabaseQueryArgs, DeadCodeArgs, DevServerArgs, DevServerError, DockerComposeArgs, DockerError, FileReadArgs, FileWriteArgs,
+            FindReferencesArgs, GitAction, GitDiffArgs, GitWorkflowArgs,
+            HttpMethod, HttpRequestArgs, resolve_save_path, KubeVerb, KubernetesArgs, KubernetesError, LinterArgs, ListDirectoryArgs,
+            ListDirectoryOutput, LogSource, MemoryArgs, OwnershipArgs, ProfileArgs, ProfilerError, SchemaIngestArgs, ShellExecuteArgs, StatementKind, SymbolIndexArgs, TailLogsArgs,
+            TerraformPlanArgs,
         };
         use rig::tool::Tool;
 
-        let working_dir = {
+        if self.state.lock().await.disabled_tools.contains(tool_name) {
+            return format!("Tool '{}' is disabled for this project (/tools enable {})", tool_name, tool_name);
+        }
+
+        let (working_dir, env, build_mode, read_only_roots): (String, HashMap<String, String>, bool, Vec<PathBuf>) = {
             let state = self.state.lock().await;
-            state.working_dir.clone()
+            (
+                state.working_dir.clone(),
+                state
+                    .env_vars
+                    .iter()
+                    .map(|(k, v)| (k.clone(), v.clone()))
+                    .collect(),
+                state.build_mode,
+                state.read_only_roots.clone(),
+            )
         };
 
         match tool_name {
@@ -1596,9 +2323,26 @@ Directorio de trabajo actual: {}
                     path: full_path.clone(),
                     start_line: args["start_line"].as_u64().map(|n| n as usize),
                     end_line: args["end_line"].as_u64().map(|n| n as usize),
+                    symbol: args["symbol"].as_str().map(String::from),
+                    around: args["around"].as_str().map(String::from),
+                    context_lines: args["context_lines"].as_u64().map(|n| n as usize),
+                    expand_scope: args["expand_scope"].as_bool(),
                 };
 
                 match self.tools.file_read.call(tool_args).await {
+                    Ok(result) if result.scope.is_some() => format!(
+                        "File: {} ({} lines, showing {}: {}{})\n\n{}",
+                        full_path,
+                        result.total_lines,
+                        result.lines_read,
+                        result.scope.unwrap_or_default(),
+                        if result.truncated { ", truncated: read budget for this turn reached" } else { "" },
+                        result.content
+                    ),
+                    Ok(result) if result.truncated => format!(
+                        "File: {} ({} lines, showing {}, truncated: read budget for this turn reached)\n\n{}",
+                        full_path, result.total_lines, result.lines_read, result.content
+                    ),
                     Ok(result) => {
                         if result.total_lines > 100 {
                             format!(
@@ -1621,8 +2365,26 @@ Directorio de trabajo actual: {}
                     format!("{}/{}", working_dir, path)
                 };
 
+                if let Some(error) = self.tools.check_writable(&full_path, &read_only_roots) {
+                    return error;
+                }
+                if dry_run::enabled() {
+                    let previous = std::fs::read_to_string(&full_path).ok();
+                    return dry_run::file_write(
+                        &full_path,
+                        previous.as_deref(),
+                        args["content"].as_str().unwrap_or(""),
+                        args["append"].as_bool().unwrap_or(false),
+                    );
+                }
+
+                // Learned before the write so the new file does not count
+                let root = Path::new(&working_dir);
+                self.tools.conventions.conventions(root).await;
+                let previous = std::fs::read_to_string(&full_path).ok();
+
                 let tool_args = FileWriteArgs {
-                    path: full_path,
+                    path: full_path.clone(),
                     content: args["content"].as_str().unwrap_or("").to_string(),
                     append: args["append"].as_bool().unwrap_or(false),
                     create_dirs: args["create_dirs"].as_bool().unwrap_or(true),
@@ -1630,10 +2392,26 @@ Directorio de trabajo actual: {}
 
                 match self.tools.file_write.call(tool_args).await {
                     Ok(result) => {
-                        format!(
+                        self.file_changes
+                            .lock()
+                            .unwrap_or_else(|e| e.into_inner())
+                            .entry(PathBuf::from(&full_path))
+                            .or_insert_with(|| previous.clone());
+                        let mut output = format!(
                             "✅ File written: {} ({} bytes)",
                             result.path, result.bytes_written
-                        )
+                        );
+                        // Deviations go back to the model for a revision pass
+                        if let Some(feedback) = self
+                            .tools
+                            .conventions
+                            .review(root, Path::new(&full_path), previous.as_deref())
+                            .await
+                        {
+                            output.push_str("\n\n");
+                            output.push_str(&feedback);
+                        }
+                        output
                     }
                     Err(e) => format!("Error writing file: {}", e),
                 }
@@ -1649,13 +2427,22 @@ Directorio de trabajo actual: {}
                     format!("{}/{}", working_dir, path)
                 };
 
+                let recursive = args["recursive"].as_bool().unwrap_or(false);
+                // The project root of a big repo is summarized unless asked otherwise
+                let summary = args["summary"]
+                    .as_bool()
+                    .or((full_path == working_dir && !recursive).then_some(true));
                 let tool_args = ListDirectoryArgs {
                     path: full_path,
-                    recursive: args["recursive"].as_bool().unwrap_or(false),
+                    recursive,
                     max_depth: args["max_depth"].as_u64().unwrap_or(3) as usize,
+                    summary,
                 };
 
                 match self.tools.list_directory.call(tool_args).await {
+                    Ok(ListDirectoryOutput { summary: Some(summary), .. }) => {
+                        summary.format(Path::new(&working_dir))
+                    }
                     Ok(result) => {
                         let mut output =
                             format!("Directory listing ({} entries):\n\n", result.count);
@@ -1673,17 +2460,108 @@ Directorio de trabajo actual: {}
                 }
             }
 
+            "git_diff" => {
+                let tool_args = GitDiffArgs {
+                    path: working_dir.clone(),
+                    commit: args["commit"].as_str().map(String::from),
+                    staged: args["staged"].as_bool(),
+                    file: args["file"].as_str().map(String::from),
+                };
+
+                match self.tools.git.diff(tool_args).await {
+                    Ok(diff) if diff.files.is_empty() => "No changes".to_string(),
+                    Ok(diff) => diff.format(),
+                    Err(e) => format!("Error getting diff: {}", e),
+                }
+            }
+
+            "git_workflow" => {
+                let action = args["action"].as_str().unwrap_or("").to_string();
+                let mutating = GitAction::parse(&action).is_ok_and(|a| a.is_mutating());
+                let dry_run = dry_run::enabled() && mutating;
+                let tool_args = GitWorkflowArgs {
+                    path: working_dir.clone(),
+                    action: action.clone(),
+                    name: args["name"].as_str().map(String::from),
+                    base: args["base"].as_str().map(String::from),
+                    plan: serde_json::from_value(args["plan"].clone()).unwrap_or_default(),
+                    // Only a call the user approved runs; the model's confirm asks for that
+                    confirm: approved && !dry_run,
+                };
+                let requested = args["confirm"].as_bool().unwrap_or(false);
+                let blocked = !build_mode && requested;
+                if mutating {
+                    if let Some(error) = ToolRegistry::check_read_only_root(&working_dir, &read_only_roots) {
+                        return error;
+                    }
+                }
+
+                let result = self.tools.git.workflow(tool_args).await;
+                if let (true, Ok(output)) = (dry_run, &result) {
+                    return dry_run::command(tool_name, &output.commands.join(" && "), &working_dir);
+                }
+                if let Ok(output) = &result {
+                    if build_mode && requested && mutating && !output.executed {
+                        let summary = output.commands.join(" && ");
+                        return self.hold_for_confirmation(tool_name, args, summary, "git:write");
+                    }
+                }
+                let attempted = match &result {
+                    Ok(output) => output.executed,
+                    Err(_) => approved,
+                };
+                if let (Some(store), true) = (&self.session_store, attempted) {
+                    let execution = match &result {
+                        Ok(output) => CommandExecution::new(
+                            Uuid::new_v4().to_string(),
+                            store.session_id.clone(),
+                            output.commands.join(" && "),
+                        )
+                        .with_result(0, output.output.clone(), String::new()),
+                        Err(e) => CommandExecution::new(
+                            Uuid::new_v4().to_string(),
+                            store.session_id.clone(),
+                            format!("git_workflow {}", action),
+                        )
+                        .with_result(1, String::new(), e.to_string()),
+                    }
+                    .mark_dangerous("git:write")
+                    .with_confirmation("user");
+                    if let Err(e) = store.db.create_command_execution(&execution).await {
+                        log_warn!("⚠️ Could not write git audit entry: {}", e);
+                    }
+                }
+
+                match result {
+                    Ok(output) if blocked => format!(
+                        "{}(Plan mode: switch to build mode to run it.)",
+                        output.render()
+                    ),
+                    Ok(output) => output.render(),
+                    Err(e) => format!("Error running git workflow: {}", e),
+                }
+            }
+
             "execute_shell" => {
                 let command = args["command"].as_str().unwrap_or("");
                 let cmd_working_dir = args["working_dir"]
                     .as_str()
                     .map(|s| s.to_string())
                     .unwrap_or_else(|| working_dir.clone());
+                // Commands are not sandboxed: a read-only root is only kept out
+                // of reach as their working directory
+                if let Some(error) = ToolRegistry::check_read_only_root(&cmd_working_dir, &read_only_roots) {
+                    return error;
+                }
+                if dry_run::enabled() {
+                    return dry_run::command(tool_name, command, &cmd_working_dir);
+                }
 
                 let tool_args = ShellExecuteArgs {
                     command: command.to_string(),
-                    working_dir: Some(cmd_working_dir),
+                    working_dir: Some(cmd_working_dir.clone()),
                     timeout_secs: args["timeout_secs"].as_u64().unwrap_or(60),
+                    env,
                 };
 
                 match self.tools.shell_execute.call(tool_args).await {
@@ -1697,6 +2575,22 @@ Directorio de trabajo actual: {}
                         if !result.stderr.is_empty() {
                             output.push_str(&format!("\nstderr:\n{}", result.stderr));
                         }
+                        if let Some(runner) = BuildRunner::of_command(command) {
+                            let combined = format!("{}\n{}", result.stdout, result.stderr);
+                            self.note_known_errors(
+                                &mut output,
+                                Path::new(&cmd_working_dir),
+                                runner,
+                                &combined,
+                                result.exit_code == 0,
+                            )
+                            .await;
+                        }
+                        if let Some(store) = &self.session_store {
+                            if activity_report::is_test_command(command) || activity_report::is_commit_command(command) {
+                                activity_report::audit(store, command, result.exit_code).await;
+                            }
+                        }
                         output
                     }
                     Err(e) => format!("Error executing command: {}", e),
@@ -1714,7 +2608,7 @@ Directorio de trabajo actual: {}
                 };
 
                 let tool_args = LinterArgs {
-                    project_path: full_path,
+                    project_path: full_path.clone(),
                     mode: crate::tools::LinterMode::Clippy,
                     extra_args: vec![],
                     auto_fix: args["auto_fix"].as_bool().unwrap_or(false),
@@ -1743,6 +2637,18 @@ Directorio de trabajo actual: {}
                                 ));
                             }
                         }
+                        let runner = BuildRunner {
+                            tool: "cargo",
+                            tests: false,
+                        };
+                        self.note_known_errors(
+                            &mut output,
+                            Path::new(&full_path),
+                            runner,
+                            &result.raw_output,
+                            result.success,
+                        )
+                        .await;
                         output
                     }
                     Err(e) => format!("Error running linter: {}", e),
@@ -1815,7 +2721,634 @@ Directorio de trabajo actual: {}
                 )
             }
 
-            _ => format!("Unknown tool: {}", tool_name),
+            "http_request" => {
+                let method = match args["method"].as_str().unwrap_or("GET").to_uppercase().as_str() {
+                    "POST" => HttpMethod::Post,
+                    "PUT" => HttpMethod::Put,
+                    "PATCH" => HttpMethod::Patch,
+                    "DELETE" => HttpMethod::Delete,
+                    "HEAD" => HttpMethod::Head,
+                    "OPTIONS" => HttpMethod::Options,
+                    _ => HttpMethod::Get,
+                };
+                let headers = args["headers"].as_object().map(|map| {
+                    map.iter()
+                        .filter_map(|(k, v)| v.as_str().map(|v| (k.clone(), v.to_string())))
+                        .collect()
+                });
+
+                let save_to = match args["save_to"].as_str() {
+                    None => None,
+                    Some(_) if !build_mode => {
+                        return "❌ save_to writes a file and is only available in Build mode"
+                            .to_string();
+                    }
+                    Some(path) => match resolve_save_path(Path::new(&working_dir), path) {
+                        Ok(path) => Some(path.to_string_lossy().into_owned()),
+                        Err(e) => return format!("Error making HTTP request: {}", e),
+                    },
+                };
+
+                let mut tool_args = HttpRequestArgs {
+                    url: args["url"].as_str().unwrap_or("").to_string(),
+                    method,
+                    headers,
+                    body: args["body"].as_str().map(|s| s.to_string()),
+                    json: args.get("json").filter(|v| !v.is_null()).cloned(),
+                    timeout_secs: args["timeout_secs"].as_u64(),
+                    follow_redirects: None,
+                    profile: args["profile"].as_str().map(|s| s.to_string()),
+                    max_retries: args["max_retries"].as_u64().map(|n| n as u32),
+                    max_response_bytes: args["max_response_bytes"].as_u64().map(|n| n as usize),
+                    save_to,
+                };
+                tool_args.expand_env(&env);
+                // Anything but a GET may change something on the server
+                if dry_run::enabled() && (tool_args.method != HttpMethod::Get || tool_args.save_to.is_some()) {
+                    let mut summary = format!(
+                        "send {} {}",
+                        args["method"].as_str().unwrap_or("GET").to_uppercase(),
+                        tool_args.url
+                    );
+                    if let Some(path) = &tool_args.save_to {
+                        summary.push_str(&format!(" and save the body to {}", path));
+                    }
+                    let details = tool_args
+                        .json
+                        .as_ref()
+                        .map(|json| json.to_string())
+                        .or(tool_args.body.clone())
+                        .unwrap_or_default();
+                    return dry_run::describe(tool_name, summary, details);
+                }
+
+                match self.tools.http_client.request(tool_args).await {
+                    Ok(response) => {
+                        let mut output = format!(
+                            "HTTP {} {} ({} ms",
+                            response.status, response.status_text, response.elapsed_ms
+                        );
+                        if response.attempts > 1 {
+                            output.push_str(&format!(", {} attempts", response.attempts));
+                        }
+                        output.push_str(")\n");
+                        if response.truncated {
+                            output.push_str("⚠️ Response truncated at the size limit\n");
+                        }
+                        if let Some(path) = &response.saved_to {
+                            output.push_str(&format!("💾 Body saved to {}\n", path));
+                        }
+                        if let Some(name) = args["scratch"].as_str() {
+                            let chunks = crate::raptor::persistence::GLOBAL_STORE
+                                .lock()
+                                .map(|mut store| store.insert_scratch(name, &response.body))
+                                .unwrap_or(0);
+                            output.push_str(&format!(
+                                "🧠 {} chunks stored in RAPTOR scratch '{}'\n",
+                                chunks, name
+                            ));
+                        }
+                        output.push_str(&format!("\n{}", response.body));
+                        output
+                    }
+                    Err(e) => format!("Error making HTTP request: {}", e),
+                }
+            }
+
+            "tail_logs" => {
+                let source = LogSource::parse(args["source"].as_str().unwrap_or(""))
+                    .relative_to(&working_dir);
+                let tool_args = TailLogsArgs {
+                    source: source.to_string(),
+                    follow_secs: args["follow_secs"].as_u64().unwrap_or(5),
+                    lines: args["lines"].as_u64().unwrap_or(500) as usize,
+                };
+
+                match self.tools.log_tail.call(tool_args).await {
+                    Ok(digest) => digest,
+                    Err(e) => format!("Error reading logs: {}", e),
+                }
+            }
+
+            "query_database" => {
+                let tool_args = DatabaseQueryArgs {
+                    connection: args["connection"].as_str().unwrap_or("").to_string(),
+                    query: args["query"].as_str().unwrap_or("").to_string(),
+                    max_rows: args["max_rows"].as_u64().unwrap_or(100) as usize,
+                    confirm: approved,
+                    build_mode,
+                };
+                if build_mode && classify_statement(&tool_args.query) != StatementKind::Read {
+                    let summary = format!("{}: {}", tool_args.connection, tool_args.query.trim());
+                    if dry_run::enabled() {
+                        return dry_run::describe(tool_name, format!("run on {}", summary), "");
+                    }
+                    if !approved {
+                        return self.hold_for_confirmation(tool_name, args, summary, "db:write");
+                    }
+                }
+
+                match self.tools.database.call(tool_args).await {
+                    Ok(output) => output.to_markdown(),
+                    Err(e) => format!("Error querying database: {}", e),
+                }
+            }
+
+            "docker_compose" => {
+                let tool_args = DockerComposeArgs {
+                    action: args["action"].as_str().unwrap_or("inspect").to_string(),
+                    services: args["services"]
+                        .as_array()
+                        .map(|s| s.iter().filter_map(|v| v.as_str().map(String::from)).collect())
+                        .unwrap_or_default(),
+                    tail: args["tail"].as_u64().unwrap_or(200) as usize,
+                    confirm: approved,
+                    project_dir: working_dir.clone(),
+                    build_mode,
+                };
+                if matches!(tool_args.check(), Err(DockerError::RequiresConfirmation(_))) {
+                    let summary = format!("docker compose {} {}", tool_args.action, tool_args.services.join(" "));
+                    if dry_run::enabled() {
+                        return dry_run::command(tool_name, summary.trim_end(), &working_dir);
+                    }
+                    return self.hold_for_confirmation(tool_name, args, summary.trim_end().to_string(), "docker:write");
+                }
+
+                match self.tools.docker.call(tool_args).await {
+                    Ok(output) => output,
+                    Err(e) => format!("Error running docker compose: {}", e),
+                }
+            }
+
+            "dev_server" => {
+                let tool_args = DevServerArgs {
+                    action: args["action"].as_str().unwrap_or("list").to_string(),
+                    name: args["name"].as_str().map(String::from),
+                    command: args["command"].as_str().map(String::from),
+                    lines: args["lines"].as_u64().map(|n| n as usize),
+                    filter: args["filter"].as_str().map(String::from),
+                    working_dir: working_dir.clone(),
+                    // `/proc` holds calls the user typed, so Plan mode does not apply to them
+                    build_mode: build_mode || (approved && args["user_command"].as_bool().unwrap_or(false)),
+                    confirm: approved,
+                };
+                let summary = tool_args.summary();
+                if matches!(tool_args.check(), Err(DevServerError::RequiresConfirmation(_))) {
+                    if dry_run::enabled() {
+                        return dry_run::describe(tool_name, summary, "");
+                    }
+                    return self.hold_for_confirmation(tool_name, args, summary, "process:write");
+                }
+                let mutating = tool_args.check().is_ok_and(|action| action.is_mutating());
+
+                let result = self.tools.dev_server.call(tool_args).await;
+                if let (Some(store), true) = (&self.session_store, mutating) {
+                    let execution = CommandExecution::new(
+                        Uuid::new_v4().to_string(),
+                        store.session_id.clone(),
+                        format!("dev_server {}", summary),
+                    );
+                    let execution = match &result {
+                        Ok(output) => execution.with_result(0, output.clone(), String::new()),
+                        Err(e) => execution.with_result(1, String::new(), e.to_string()),
+                    }
+                    .mark_dangerous("process:write")
+                    .with_confirmation("user");
+                    if let Err(e) = store.db.create_command_execution(&execution).await {
+                        log_warn!("⚠️ Could not write dev server audit entry: {}", e);
+                    }
+                }
+
+                match result {
+                    Ok(output) => output,
+                    Err(e) => format!("Error managing dev server: {}", e),
+                }
+            }
+
+            "profile_code" => {
+                let user_command = approved && args["user_command"].as_bool().unwrap_or(false);
+                let tool_args = ProfileArgs {
+                    command: args["command"].as_str().unwrap_or("").to_string(),
+                    profiler: args["profiler"].as_str().map(String::from),
+                    top: args["top"].as_u64().map(|n| n as usize),
+                    working_dir: working_dir.clone(),
+                    // `/profile` holds runs the user typed, so Plan mode does not apply to them
+                    build_mode: build_mode || user_command,
+                    confirm: approved,
+                };
+                let summary = tool_args.summary();
+                if matches!(tool_args.check(), Err(ProfilerError::RequiresConfirmation)) {
+                    if dry_run::enabled() {
+                        return dry_run::describe(tool_name, summary, "");
+                    }
+                    return self.hold_for_confirmation(tool_name, args, summary, "process:write");
+                }
+
+                let mut result = self.tools.profiler.run(&tool_args).await;
+                if let (Some(store), Ok(report)) = (&self.session_store, &mut result) {
+                    report.artifact = self.store_profile(store, report).await;
+                }
+                if let (Some(store), true) = (&self.session_store, approved) {
+                    let execution = CommandExecution::new(
+                        Uuid::new_v4().to_string(),
+                        store.session_id.clone(),
+                        format!("profile_code {}", summary),
+                    );
+                    let execution = match &result {
+                        Ok(report) => execution.with_result(0, report.to_markdown(), String::new()),
+                        Err(e) => execution.with_result(1, String::new(), e.to_string()),
+                    }
+                    .mark_dangerous("process:write")
+                    .with_confirmation("user");
+                    if let Err(e) = store.db.create_command_execution(&execution).await {
+                        log_warn!("⚠️ Could not write profiler audit entry: {}", e);
+                    }
+                }
+
+                let mut report = match result {
+                    Ok(report) => report,
+                    Err(e) => return format!("Error profiling: {}", e),
+                };
+                // Native profiles name functions without a file: find them in the symbol index
+                let root = Path::new(&working_dir);
+                for hotspot in report.hotspots.iter_mut().filter(|h| h.location.is_none()) {
+                    let name = hotspot.symbol_name();
+                    if name.is_empty() {
+                        continue;
+                    }
+                    if let Ok(found) = self.tools.symbol_index.find(root, &name, 1).await {
+                        if let Some(symbol) = found.into_iter().find(|s| s.symbol.symbol_name == name) {
+                            hotspot.location = Some(format!("{}:{}", symbol.relative_path, symbol.symbol.line_start));
+                        }
+                    }
+                }
+
+                let mut output = report.to_markdown();
+                // The model reads the hotspots itself when it asked for them; a
+                // `/profile` run gets the heavy model's suggestions here
+                if user_command && !report.hotspots.is_empty() {
+                    match self.call_heavy_model_direct(&report.optimization_prompt(root)).await {
+                        Ok(suggestions) => {
+                            output.push_str(&format!("\n💡 Optimization suggestions:\n\n{}", suggestions.trim()))
+                        }
+                        Err(e) => log_warn!("⚠️ Could not get optimization suggestions: {}", e),
+                    }
+                }
+                output
+            }
+
+            "run_benchmarks" => {
+                let tool_args = BenchmarkArgs {
+                    filter: args["filter"].as_str().map(String::from),
+                    framework: args["framework"].as_str().map(String::from),
+                    baseline: args["baseline"].as_str().map(String::from),
+                    threshold: args["threshold"].as_f64(),
+                    run_baseline: args["run_baseline"].as_bool().unwrap_or(false),
+                    project_dir: working_dir.clone(),
+                    build_mode,
+                };
+                if build_mode && dry_run::enabled() {
+                    return dry_run::describe(tool_name, "run the project's benchmarks and record their timings", "");
+                }
+
+                match self.tools.benchmarks.call(tool_args).await {
+                    Ok(report) => report,
+                    Err(e) => format!("Error running benchmarks: {}", e),
+                }
+            }
+
+            "kubernetes" => {
+                let tool_args = KubernetesArgs {
+                    verb: args["verb"].as_str().unwrap_or("list").to_string(),
+                    resource: args["resource"].as_str().unwrap_or("pods").to_string(),
+                    name: args["name"].as_str().map(String::from),
+                    namespace: args["namespace"].as_str().map(String::from),
+                    container: args["container"].as_str().map(String::from),
+                    tail_lines: args["tail_lines"].as_i64(),
+                    replicas: args["replicas"].as_i64().map(|n| n as i32),
+                    confirm: approved,
+                    build_mode,
+                };
+                let mutating = KubeVerb::parse(&tool_args.verb).is_ok_and(|v| v.is_mutating());
+                let audit = tool_args.audit_command();
+                // A valid mutating request only fails the gate until the user approves it
+                if matches!(tool_args.check(), Err(KubernetesError::RequiresConfirmation(_))) {
+                    if dry_run::enabled() {
+                        return dry_run::describe(tool_name, format!("run `{}`", audit), "");
+                    }
+                    return self.hold_for_confirmation(tool_name, args, audit, "k8s:write");
+                }
+
+                let result = self.tools.kubernetes.call(tool_args).await;
+                if let Some(store) = &self.session_store {
+                    let mut execution = CommandExecution::new(
+                        Uuid::new_v4().to_string(),
+                        store.session_id.clone(),
+                        audit,
+                    );
+                    execution = match &result {
+                        Ok(output) => execution.with_result(0, output.clone(), String::new()),
+                        Err(e) => execution.with_result(1, String::new(), e.to_string()),
+                    };
+                    execution = if mutating {
+                        execution.mark_dangerous("k8s:write")
+                    } else {
+                        execution.with_risk_level("k8s:read")
+                    };
+                    if approved {
+                        execution = execution.with_confirmation("user");
+                    }
+                    if let Err(e) = store.db.create_command_execution(&execution).await {
+                        log_warn!("⚠️ Could not write k8s audit entry: {}", e);
+                    }
+                }
+
+                match result {
+                    Ok(output) => output,
+                    Err(e) => format!("Error calling kubernetes: {}", e),
+                }
+            }
+
+            "terraform_plan" => {
+                let tool_args = TerraformPlanArgs {
+                    dir: args["dir"].as_str().map(String::from),
+                    plan_file: args["plan_file"].as_str().map(String::from),
+                    question: args["question"].as_str().map(String::from),
+                };
+
+                match self
+                    .tools
+                    .terraform
+                    .summarize(&tool_args, std::path::Path::new(&working_dir))
+                    .await
+                {
+                    Ok(summary) => summary.render(tool_args.question.as_deref()),
+                    Err(e) => format!("Error summarizing terraform plan: {}", e),
+                }
+            }
+
+            "preview_data" => {
+                let path = args["path"].as_str().unwrap_or("");
+                let tool_args = DataPreviewArgs {
+                    path: if path.starts_with('/') {
+                        path.to_string()
+                    } else {
+                        format!("{}/{}", working_dir, path)
+                    },
+                    rows: args["rows"].as_u64().unwrap_or(10) as usize,
+                };
+
+                match self.tools.data_preview.call(tool_args).await {
+                    Ok(preview) => preview,
+                    Err(e) => format!("Error previewing data: {}", e),
+                }
+            }
+
+            "ingest_schema" => {
+                let tool_args = SchemaIngestArgs {
+                    connection: args["connection"].as_str().map(String::from),
+                    path: args["path"].as_str().map(String::from),
+                    project_dir: working_dir.clone(),
+                };
+
+                match self.tools.schema.call(tool_args).await {
+                    Ok(summary) => summary,
+                    Err(e) => format!("Error ingesting schema: {}", e),
+                }
+            }
+
+            "symbol_index" => {
+                let tool_args = SymbolIndexArgs {
+                    action: serde_json::from_value(args["action"].clone()).unwrap_or_default(),
+                    query: args["query"].as_str().map(String::from),
+                    path: args["path"].as_str().map(String::from),
+                    line: args["line"].as_u64().map(|n| n as usize),
+                    limit: args["limit"].as_u64().map(|n| n as usize),
+                    project_dir: working_dir.clone(),
+                };
+
+                match self.tools.symbol_index.call(tool_args).await {
+                    Ok(symbols) => symbols,
+                    Err(e) => format!("Error looking up symbols: {}", e),
+                }
+            }
+
+            "memory" => {
+                let tool_args = MemoryArgs {
+                    action: serde_json::from_value(args["action"].clone()).unwrap_or_default(),
+                    content: args["content"].as_str().map(String::from),
+                    query: args["query"].as_str().map(String::from),
+                    id: args["id"].as_i64(),
+                    project_dir: working_dir.clone(),
+                };
+
+                match self.tools.memory.call(tool_args).await {
+                    Ok(memories) => memories,
+                    Err(e) => format!("Error using memory: {}", e),
+                }
+            }
+
+            "find_references" => {
+                let tool_args = FindReferencesArgs {
+                    symbol: args["symbol"].as_str().unwrap_or("").to_string(),
+                    path: args["path"].as_str().map(String::from),
+                    limit: args["limit"].as_u64().map(|n| n as usize),
+                    project_dir: working_dir.clone(),
+                };
+
+                match self.tools.references.call(tool_args).await {
+                    Ok(references) => references,
+                    Err(e) => format!("Error finding references: {}", e),
+                }
+            }
+
+            "find_dead_code" => {
+                let tool_args = DeadCodeArgs {
+                    path: args["path"].as_str().map(String::from),
+                    include_public: args["include_public"].as_bool().unwrap_or(false),
+                    limit: args["limit"].as_u64().map(|n| n as usize),
+                    project_dir: working_dir.clone(),
+                };
+
+                match self.tools.dead_code.call(tool_args).await {
+                    Ok(report) => report,
+                    Err(e) => format!("Error finding dead code: {}", e),
+                }
+            }
+
+            "code_owners" => {
+                let tool_args = OwnershipArgs {
+                    path: args["path"].as_str().map(String::from),
+                    limit: args["limit"].as_u64().map(|n| n as usize),
+                    project_dir: working_dir.clone(),
+                };
+
+                match self.tools.owners.call(tool_args).await {
+                    Ok(owners) => owners,
+                    Err(e) => format!("Error computing code owners: {}", e),
+                }
+            }
+
+            "search_in_files" | "search_files" => {
+                let path = args["path"].as_str().unwrap_or(".");
+                let full_path = if path.starts_with('/') {
+                    path.to_string()
+                } else {
+                    format!("{}/{}", working_dir, path)
+                };
+
+                let tool_args = SearchArgs {
+                    path: full_path,
+                    pattern: args["pattern"].as_str().unwrap_or("").to_string(),
+                    mode: serde_json::from_value(args["mode"].clone()).unwrap_or_default(),
+                    case_insensitive: args["case_insensitive"].as_bool(),
+                    file_pattern: args["file_pattern"].as_str().map(String::from),
+                    max_results: args["max_results"].as_u64().map(|n| n as usize),
+                    offset: args["offset"].as_u64().map(|n| n as usize),
+                    context_lines: args["context_lines"].as_u64().map(|n| n as usize),
+                    max_depth: args["max_depth"].as_u64().map(|n| n as usize),
+                };
+
+                match self.tools.search_files.call(tool_args).await {
+                    Ok(matches) => matches,
+                    Err(e) => format!("Error searching files: {}", e),
+                }
+            }
+
+            "replace_in_files" => {
+                let path = args["path"].as_str().unwrap_or(".");
+                let full_path = if path.starts_with('/') {
+                    path.to_string()
+                } else {
+                    format!("{}/{}", working_dir, path)
+                };
+
+                let tool_args = ReplaceArgs {
+                    path: full_path,
+                    pattern: args["pattern"].as_str().unwrap_or("").to_string(),
+                    replacement: args["replacement"].as_str().unwrap_or("").to_string(),
+                    mode: serde_json::from_value(args["mode"].clone()).unwrap_or_default(),
+                    case_insensitive: args["case_insensitive"].as_bool().unwrap_or(false),
+                    file_pattern: args["file_pattern"].as_str().map(String::from),
+                    apply: args["apply"].as_bool().unwrap_or(false),
+                    matches: serde_json::from_value(args["matches"].clone()).ok(),
+                };
+                if !tool_args.apply {
+                    return match self.tools.replace.call(tool_args).await {
+                        Ok(preview) => preview,
+                        Err(e) => format!("Error replacing in files: {}", e),
+                    };
+                }
+
+                let mut plan = match self.tools.replace.plan(&tool_args).await {
+                    Ok(plan) if plan.edits.is_empty() => return plan.format(),
+                    Ok(plan) => plan,
+                    Err(e) => return format!("Error replacing in files: {}", e),
+                };
+                if let Some(ids) = &tool_args.matches {
+                    if let Err(e) = plan.select_only(ids) {
+                        return format!("Error replacing in files: {}", e);
+                    }
+                }
+                // One denied file fails the whole plan before anything is written
+                let denied: Vec<String> = plan
+                    .files()
+                    .into_iter()
+                    .filter_map(|file| self.tools.check_writable(&file.to_string_lossy(), &read_only_roots))
+                    .collect();
+                if !denied.is_empty() {
+                    return format!("{}\nNothing was replaced.", denied.join("\n"));
+                }
+
+                let summary = format!(
+                    "replace `{}` with `{}` ({} matches in {} files under {})",
+                    plan.pattern,
+                    plan.replacement,
+                    plan.selected().len(),
+                    plan.files().len(),
+                    plan.root.display()
+                );
+                // The preview lists every match with its replacement
+                if dry_run::enabled() {
+                    return dry_run::describe(tool_name, summary, plan.format());
+                }
+                if !approved {
+                    return self.hold_for_confirmation(tool_name, args, summary, "fs:write");
+                }
+
+                match self.tools.replace.apply(&plan).await {
+                    Ok(batch) => {
+                        let mut changes = self.file_changes.lock().unwrap_or_else(|e| e.into_inner());
+                        for (file, before) in batch.changes() {
+                            changes
+                                .entry(file.to_path_buf())
+                                .or_insert_with(|| Some(before.to_string()));
+                        }
+                        batch.format(&plan.root)
+                    }
+                    Err(e) => format!("Error replacing in files: {}", e),
+                }
+            }
+
+            "project_context" => {
+                let mut context_tool = self.tools.project_context.lock().await;
+                if let Err(e) = context_tool.analyze(&working_dir).await {
+                    return format!("Error analyzing project: {}", e);
+                }
+                match context_tool.generate_summary() {
+                    Some(summary) => {
+                        let mut output = format!(
+                            "{}\nStack: {}\n{}\nKey files: {}",
+                            summary.brief,
+                            summary.tech_stack,
+                            summary.structure,
+                            summary.key_files.join(", ")
+                        );
+                        if let Some(runtimes) = summary.runtimes {
+                            output.push_str(&format!("\nRuntimes: {}", runtimes));
+                        }
+                        if let Some(containers) = summary.containers {
+                            output.push_str(&format!("\n\nContainers:\n{}", containers));
+                        }
+                        output
+                    }
+                    None => "No project context available".to_string(),
+                }
+            }
+
+            _ => match self.tools.plugins.get(tool_name) {
+                Some(plugin) => {
+                    // A write grant lets the plugin change files: gate it like write_file
+                    let write_dirs = plugin.write_dirs();
+                    if !write_dirs.is_empty() {
+                        let dirs = plugin.grant().write.join(", ");
+                        if !build_mode {
+                            return format!(
+                                "❌ Plugin {} can write to {} and is only available in Build mode",
+                                tool_name, dirs
+                            );
+                        }
+                        if let Some(error) = write_dirs
+                            .iter()
+                            .find_map(|dir| self.tools.check_writable_dir(dir, &read_only_roots))
+                        {
+                            return format!("{}\nPlugin {} was not run.", error, tool_name);
+                        }
+                        let summary = format!("run plugin {} with write access to {}", tool_name, dirs);
+                        if dry_run::enabled() {
+                            return dry_run::describe(tool_name, summary, format!("Arguments: {}", args));
+                        }
+                        if !approved {
+                            return self.hold_for_confirmation(tool_name, args, summary, "fs:write");
+                        }
+                    }
+                    match plugin.call(args.clone()).await {
+                        Ok(output) => output,
+                        Err(e) => format!("Error calling plugin {}: {}", tool_name, e),
+                    }
+                }
+                None => format!("Unknown tool: {}", tool_name),
+            },
         }
     }
 
@@ -1825,9 +3358,9 @@ Directorio de trabajo actual: {}
         model: &str,
         prompt: &str,
     ) -> Result<String, OrchestratorError> {
-        let client = reqwest::Client::new();
+        let client = network::client();
 
-        let request_body = serde_json::json!({
+        let mut request_body = serde_json::json!({
             "model": model,
             "messages": [
                 {"role": "user", "content": prompt}
@@ -1838,12 +3371,13 @@ Directorio de trabajo actual: {}
                 "num_predict": 4096
             }
         });
+        reasoning::apply_options(&mut request_body);
 
         let response = client
             .post(format!("{}/api/chat", ollama_url))
             .json(&request_body)
             .timeout(Duration::from_secs(300))
-            .send()
+            .send_checked("model provider")
             .await
             .map_err(|e| OrchestratorError::ModelError(e.to_string()))?;
 
@@ -1854,7 +3388,7 @@ Directorio de trabajo actual: {}
 
         response_json["message"]["content"]
             .as_str()
-            .map(|s| s.to_string())
+            .map(|s| reasoning::split(s).0)
             .ok_or_else(|| OrchestratorError::ModelError("Invalid response format".to_string()))
     }
 
@@ -2039,9 +3573,22 @@ Models:
     }
 }
 
+/// Send the reasoning and answer parts of a streamed chunk as their own events
+async fn send_segments(events: &EventBus, segments: Vec<Segment>) {
+    for segment in segments {
+        events
+            .send(match segment {
+                Segment::Thinking(text) => AgentEvent::Thinking(text),
+                Segment::Answer(text) => AgentEvent::Chunk(text),
+            })
+            .await;
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::agent::mock_provider::{MockProvider, MockReply};
 
     #[test]
     fn test_config_default() {
@@ -2050,4 +3597,304 @@ mod tests {
         assert_eq!(config.heavy_model, "qwen3:8b");
         assert_eq!(config.heavy_timeout_secs, 1200);
     }
+
+    #[tokio::test]
+    async fn test_native_tool_loop_with_mock_provider() {
+        let mock = MockProvider::start().await;
+        mock.push(MockReply::tool_call(FileReadTool::NAME, serde_json::json!({ "path": "Cargo.toml" })))
+            .push(MockReply::text("The package is called neuro"));
+
+        let mut orchestrator = DualModelOrchestrator::with_config(mock.orchestrator_config())
+            .await
+            .unwrap();
+        let response = orchestrator.process("what is the package name?").await.unwrap();
+
+        match response {
+            OrchestratorResponse::Immediate { content, model } => {
+                assert_eq!(content, "The package is called neuro");
+                assert_eq!(model, "qwen3:0.6b");
+            }
+            other => panic!("unexpected response: {:?}", other),
+        }
+        let requests = mock.requests();
+        assert_eq!(requests.len(), 2);
+        assert!(requests.iter().all(|r| r.path == "/api/chat" && r.model() == "qwen3:0.6b"));
+        // The second turn carries the tool result back to the model
+        assert!(requests[1].prompt().contains("name = \"neuro\""));
+    }
+
+    #[tokio::test]
+    async fn test_turn_budget_stops_tools_and_continues() {
+        let mock = MockProvider::start().await;
+        mock.push(MockReply::tool_call(FileReadTool::NAME, serde_json::json!({ "path": "Cargo.toml" })));
+
+        let mut orchestrator = DualModelOrchestrator::with_config(mock.orchestrator_config())
+            .await
+            .unwrap();
+        // Winding down: the model may answer, but the tool it asks for must wait
+        let limit = Duration::from_secs(120);
+        orchestrator.set_turn_budget(Some(TurnBudget::starting_at(Instant::now() - Duration::from_secs(115), limit)));
+        let response = orchestrator.process("what is the package name?").await.unwrap();
+
+        let OrchestratorResponse::Immediate { content, .. } = response else {
+            panic!("unexpected response: {:?}", response);
+        };
+        assert!(content.starts_with("⏱️ Se agotó el tiempo del turno (120s)"));
+        assert!(content.contains("- read_file {\"path\":\"Cargo.toml\"}"));
+        assert!(orchestrator.take_turn_record().tool_calls.is_empty());
+        assert!(orchestrator.has_continuation());
+
+        mock.push(MockReply::text("The package is called neuro"));
+        orchestrator.set_turn_budget(Some(TurnBudget::new(limit)));
+        match orchestrator.resume().await.unwrap() {
+            Some(OrchestratorResponse::Immediate { content, .. }) => assert_eq!(content, "The package is called neuro"),
+            other => panic!("unexpected response: {:?}", other),
+        }
+        assert!(!orchestrator.has_continuation());
+        let requests = mock.requests();
+        assert_eq!(requests.len(), 2);
+        assert!(requests[1].prompt().contains("name = \"neuro\""));
+    }
+
+    #[tokio::test]
+    async fn test_replace_checks_every_file_and_waits_for_approval() {
+        let mock = MockProvider::start().await;
+        let mut orchestrator = DualModelOrchestrator::with_config(mock.orchestrator_config())
+            .await
+            .unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let a = dir.path().join("a.txt");
+        std::fs::write(&a, "old\n").unwrap();
+        std::fs::create_dir(dir.path().join("vendor")).unwrap();
+        std::fs::write(dir.path().join("vendor/b.txt"), "old\n").unwrap();
+        let args = serde_json::json!({ "path": dir.path(), "pattern": "old", "replacement": "new", "apply": true });
+
+        orchestrator.set_protected_paths(dir.path(), &["vendor/".to_string()]);
+        let result = orchestrator.run_tool("replace_in_files", &args, false).await;
+        assert!(result.contains("vendor/b.txt is a protected path"), "{}", result);
+        assert!(result.ends_with("Nothing was replaced."));
+        assert!(orchestrator.pending_confirmation().is_none());
+
+        orchestrator.set_protected_paths(dir.path(), &[]);
+        let result = orchestrator.run_tool("replace_in_files", &args, false).await;
+        assert!(result.starts_with("Waiting for the user to approve"), "{}", result);
+        assert_eq!(std::fs::read_to_string(&a).unwrap(), "old\n");
+
+        let call = orchestrator.take_pending_confirmation().unwrap();
+        let result = orchestrator.execute_confirmed(call).await;
+        assert!(result.starts_with("✅ Replaced 2 matches in 2 files"), "{}", result);
+        let changes = orchestrator.take_file_changes();
+        assert_eq!(changes.len(), 2);
+        assert!(changes.iter().all(|c| c.before.as_deref() == Some("old\n") && c.after == "new\n"));
+    }
+
+    #[tokio::test]
+    async fn test_commands_do_not_run_in_read_only_roots() {
+        let mock = MockProvider::start().await;
+        let orchestrator = DualModelOrchestrator::with_config(mock.orchestrator_config())
+            .await
+            .unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let vendor = dir.path().canonicalize().unwrap().join("vendor");
+        std::fs::create_dir(&vendor).unwrap();
+        orchestrator.state().lock().await.read_only_roots = vec![vendor.clone()];
+
+        let args = serde_json::json!({ "command": "touch made.txt", "working_dir": vendor });
+        let result = orchestrator.run_tool("execute_shell", &args, true).await;
+        assert!(result.contains("is in the read-only root"), "{}", result);
+        assert!(!vendor.join("made.txt").exists());
+    }
+
+    #[tokio::test]
+    async fn test_model_calls_cannot_pass_as_user_commands() {
+        let mock = MockProvider::start().await;
+        let orchestrator = DualModelOrchestrator::with_config(mock.orchestrator_config())
+            .await
+            .unwrap();
+        orchestrator.state().lock().await.build_mode = true;
+        let args = serde_json::json!({ "action": "start", "command": "sleep 30", "user_command": true });
+
+        let result = orchestrator.execute_tool("dev_server", &args).await;
+        assert!(result.starts_with("Waiting for the user to approve"), "{}", result);
+        let call = orchestrator.take_pending_confirmation().unwrap();
+        assert!(call.args.get("user_command").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_plugins_with_a_write_grant_are_gated() {
+        use crate::tools::{PluginGrant, PluginsConfig};
+
+        let mock = MockProvider::start().await;
+        let mut orchestrator = DualModelOrchestrator::with_config(mock.orchestrator_config())
+            .await
+            .unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("out")).unwrap();
+        std::fs::write(dir.path().join("out/keep.txt"), "x").unwrap();
+        // Module::from_file also accepts the text format
+        std::fs::write(
+            dir.path().join("writer.wasm"),
+            r#"(module (memory (export "memory") 1) (func (export "_start")))"#,
+        )
+        .unwrap();
+        std::fs::write(dir.path().join("writer.json"), r#"{"name": "writer", "description": "test plugin"}"#).unwrap();
+        let config = PluginsConfig {
+            dirs: vec![dir.path().to_string_lossy().to_string()],
+            grants: HashMap::from([(
+                "writer".to_string(),
+                PluginGrant { write: vec!["out".to_string()], ..PluginGrant::default() },
+            )]),
+        };
+        assert!(orchestrator.load_wasm_plugins(&config, dir.path()).is_empty());
+        let args = serde_json::json!({});
+
+        let result = orchestrator.run_tool("writer", &args, false).await;
+        assert!(result.contains("only available in Build mode"), "{}", result);
+
+        orchestrator.state().lock().await.build_mode = true;
+        orchestrator.set_protected_paths(dir.path(), &["keep.txt".to_string()]);
+        let result = orchestrator.run_tool("writer", &args, false).await;
+        assert!(result.contains("out/keep.txt is a protected path"), "{}", result);
+
+        orchestrator.set_protected_paths(dir.path(), &[]);
+        let result = orchestrator.run_tool("writer", &args, false).await;
+        assert!(result.starts_with("Waiting for the user to approve: run plugin writer"), "{}", result);
+        let call = orchestrator.take_pending_confirmation().unwrap();
+        assert_eq!(call.risk_level, "fs:write");
+        assert_eq!(orchestrator.execute_confirmed(call).await, "");
+    }
+
+    #[tokio::test]
+    async fn test_streaming_chunks_with_mock_provider() {
+        let mock = MockProvider::start().await;
+        mock.push(MockReply::chunks(["Hel", "lo"]));
+
+        let bus = EventBus::new();
+        let mut rx = bus.subscribe("test", 8, &[crate::agent::Topic::Stream]);
+        DualModelOrchestrator::stream_heavy_model_static(mock.url(), "qwen3:8b", 10, "greet", &bus, &CancellationToken::new())
+            .await
+            .unwrap();
+        drop(bus);
+
+        let mut events = Vec::new();
+        while let Some(event) = rx.recv().await {
+            events.push(match event {
+                crate::agent::AgentEvent::Chunk(chunk) => chunk,
+                crate::agent::AgentEvent::StreamEnd => "<end>".to_string(),
+                other => panic!("unexpected event: {:?}", other),
+            });
+        }
+        assert_eq!(events, ["Hel", "lo", "<end>"]);
+        assert_eq!(mock.requests()[0].body["stream"], true);
+    }
+
+    #[tokio::test]
+    async fn test_stalled_stream_reconnects_then_aborts() {
+        use crate::agent::AgentEvent;
+
+        let mock = MockProvider::start().await;
+        mock.push(MockReply::stall(["Hel"]))
+            .push(MockReply::chunks(["lo"]))
+            .push(MockReply::stall(["Bye"]))
+            .push(MockReply::stall([" now"]));
+        stream_watchdog::set_stall_timeout(Some(Duration::from_millis(300)));
+
+        let bus = EventBus::new();
+        let mut rx = bus.subscribe("test", 16, &[crate::agent::Topic::Stream]);
+        let cancel = CancellationToken::new();
+        // One stall is recovered by continuing the answer
+        DualModelOrchestrator::stream_heavy_model_static(mock.url(), "qwen3:8b", 10, "greet", &bus, &cancel)
+            .await
+            .unwrap();
+        // A second one in the same stream is given up, keeping the partial answer
+        DualModelOrchestrator::stream_heavy_model_static(mock.url(), "qwen3:8b", 10, "leave", &bus, &cancel)
+            .await
+            .unwrap();
+        stream_watchdog::set_stall_secs(stream_watchdog::DEFAULT_STALL_SECS);
+        drop(bus);
+
+        let mut events = Vec::new();
+        while let Some(event) = rx.recv().await {
+            events.push(match event {
+                AgentEvent::Chunk(chunk) => chunk,
+                AgentEvent::StreamEnd => "<end>".to_string(),
+                AgentEvent::StreamAborted { partial, reason } => format!("<aborted {:?}: {}>", partial, reason),
+                other => panic!("unexpected event: {:?}", other),
+            });
+        }
+        assert_eq!(events, ["Hel", "lo", "<end>", "Bye", " now", "<aborted \"Bye now\": no data for 300ms>"]);
+        let requests = mock.requests();
+        assert!(requests[1].prompt().starts_with("greet"));
+        assert!(requests[1].prompt().contains("Hel\n"));
+    }
+
+    #[tokio::test]
+    async fn test_streaming_separates_reasoning() {
+        let mock = MockProvider::start().await;
+        mock.push(MockReply::chunks(["<thi", "nk>The user greets</th", "ink>\n\nHola"]));
+
+        let bus = EventBus::new();
+        let mut rx = bus.subscribe("test", 8, &[crate::agent::Topic::Stream]);
+        DualModelOrchestrator::stream_heavy_model_static(mock.url(), "qwen3:8b", 10, "greet", &bus, &CancellationToken::new())
+            .await
+            .unwrap();
+        drop(bus);
+
+        let (mut answer, mut thinking) = (String::new(), String::new());
+        while let Some(event) = rx.recv().await {
+            match event {
+                crate::agent::AgentEvent::Chunk(chunk) => answer.push_str(&chunk),
+                crate::agent::AgentEvent::Thinking(chunk) => thinking.push_str(&chunk),
+                _ => {}
+            }
+        }
+        assert_eq!(thinking, "The user greets");
+        assert_eq!(answer, "\n\nHola");
+    }
+
+    #[tokio::test]
+    async fn test_reasoning_kept_out_of_the_answer() {
+        let mock = MockProvider::start().await;
+        mock.push(MockReply::text("<think>Short question</think>\n\n4"));
+
+        let orchestrator = DualModelOrchestrator::with_config(mock.orchestrator_config())
+            .await
+            .unwrap();
+        assert_eq!(orchestrator.call_heavy_model_direct("2+2?").await.unwrap(), "4");
+        assert_eq!(orchestrator.take_turn_record().reasoning.as_deref(), Some("Short question"));
+    }
+
+    #[tokio::test]
+    async fn test_heavy_model_error_with_mock_provider() {
+        let mock = MockProvider::start().await;
+        mock.push(MockReply::error(500, "model not found"));
+
+        let orchestrator = DualModelOrchestrator::with_config(mock.orchestrator_config())
+            .await
+            .unwrap();
+        assert!(orchestrator.call_heavy_model_direct("explain").await.is_err());
+        assert_eq!(mock.requests()[0].model(), "qwen3:8b");
+    }
+
+    #[tokio::test]
+    async fn test_cancelled_turn_skips_model_and_tools() {
+        let mock = MockProvider::start().await;
+        mock.push(MockReply::text("too late"));
+
+        let orchestrator = DualModelOrchestrator::with_config(mock.orchestrator_config())
+            .await
+            .unwrap();
+        let cancel = CancellationToken::new();
+        orchestrator.set_cancel_token(cancel.clone());
+        cancel.cancel();
+
+        assert!(matches!(
+            orchestrator.call_heavy_model_direct("explain").await,
+            Err(OrchestratorError::Cancelled)
+        ));
+        let result = orchestrator.execute_tool(FileReadTool::NAME, &serde_json::json!({ "path": "Cargo.toml" })).await;
+        assert!(result.contains("cancelled"));
+        assert!(mock.requests().is_empty());
+        assert!(!orchestrator.take_turn_record().tool_calls[0].success);
+    }
 }
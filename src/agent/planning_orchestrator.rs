@@ -11,15 +11,17 @@
 //!
 //! **Target Removal:** v2.0 (Feb 2026)
 
-use anyhow::Result;
 use std::path::PathBuf;
 use std::sync::Arc;
 use tokio::sync::Mutex as AsyncMutex;
 
 use super::orchestrator::{DualModelOrchestrator, OrchestratorResponse};
 use super::state::SharedState;
+use crate::error::NeuroError;
 use crate::tools::ToolRegistry;
 
+type Result<T> = std::result::Result<T, NeuroError>;
+
 /// # DEPRECATED: PlanningOrchestrator
 ///
 /// Use `RouterOrchestrator` instead.
@@ -54,6 +56,7 @@ impl PlanningOrchestrator {
     pub async fn initialize_raptor_with_progress(
         &mut self,
         _progress_tx: Option<tokio::sync::mpsc::Sender<crate::agent::TaskProgressInfo>>,
+        _cancel: tokio_util::sync::CancellationToken,
     ) -> Result<bool> {
         unreachable!("PlanningOrchestrator::initialize_raptor_with_progress called on deprecated stub")
     }
@@ -0,0 +1,123 @@
+//! Deterministic pre-router
+//!
+//! Runs before the LLM classifier. Slash commands, greetings, thanks, yes/no
+//! answers and the orchestrator's one-word commands ("clear", "ayuda") are
+//! recognized by exact match, so the most common interactions skip
+//! classification and RAPTOR retrieval. Anything else returns `None` and goes
+//! through the classifier as before.
+
+use super::classifier::{TaskClassifier, TaskType};
+use crate::i18n::Locale;
+
+/// How a trivial input is handled without classification
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FastPath {
+    /// `/command ...`, run by the slash command registry
+    SlashCommand,
+    /// Greeting, answered without asking a model
+    Greeting,
+    /// Thanks, answered without asking a model
+    Thanks,
+    /// Yes or no: answers the pending confirmation, or else the model's last question
+    Answer(bool),
+    /// One of the orchestrator's built-in commands (`clear`, `help`, `status`...)
+    BuiltIn,
+}
+
+const GREETINGS: &[&str] = &[
+    "hola", "holi", "buenas", "buen dia", "buen día", "buenos dias", "buenos días", "buenas tardes",
+    "buenas noches", "que tal", "qué tal", "hola que tal", "hola qué tal", "hi", "hello", "hey",
+    "hi there", "hello there", "good morning", "good afternoon", "good evening",
+];
+
+const THANKS: &[&str] = &[
+    "gracias", "muchas gracias", "ok gracias", "perfecto gracias", "genial gracias", "mil gracias",
+    "thanks", "thank you", "thanks a lot", "thx", "ty",
+];
+
+const YES: &[&str] = &[
+    "si", "sí", "s", "dale", "claro", "adelante", "hazlo", "confirmo", "de acuerdo", "ok", "okay",
+    "yes", "y", "yep", "sure", "go ahead", "do it",
+];
+
+const NO: &[&str] = &["no", "n", "nop", "mejor no", "cancela", "cancelar", "nope", "cancel", "don't"];
+
+/// Fast path of `input`, or `None` when it needs the classifier
+pub fn fast_path(input: &str) -> Option<FastPath> {
+    let trimmed = input.trim();
+    if trimmed.starts_with('/') {
+        return Some(FastPath::SlashCommand);
+    }
+    let normalized = normalize(trimmed);
+    if normalized.is_empty() {
+        return None;
+    }
+    let text = normalized.as_str();
+    if GREETINGS.contains(&text) {
+        return Some(FastPath::Greeting);
+    }
+    if THANKS.contains(&text) {
+        return Some(FastPath::Thanks);
+    }
+    if YES.contains(&text) {
+        return Some(FastPath::Answer(true));
+    }
+    if NO.contains(&text) {
+        return Some(FastPath::Answer(false));
+    }
+    // Only the bare command: "list the files that use X" still needs the classifier
+    let bare = if trimmed == "?" { trimmed } else { text };
+    if !bare.contains(' ')
+        && matches!(TaskClassifier::new().classify_fast(bare), Some(TaskType::SimpleCommand { .. }))
+    {
+        return Some(FastPath::BuiltIn);
+    }
+    None
+}
+
+/// Reply to a greeting or thanks in the session's language
+pub fn canned_reply(path: FastPath, locale: Locale) -> Option<&'static str> {
+    match (path, locale) {
+        (FastPath::Greeting, Locale::Spanish) => Some("¡Hola! ¿En qué te ayudo con este proyecto?"),
+        (FastPath::Greeting, Locale::English) => Some("Hi! How can I help with this project?"),
+        (FastPath::Thanks, Locale::Spanish) => Some("¡De nada! Aquí estoy si necesitas algo más."),
+        (FastPath::Thanks, Locale::English) => Some("You're welcome! I'm here if you need anything else."),
+        _ => None,
+    }
+}
+
+/// Lowercase, without surrounding punctuation or emoji and with single spaces
+fn normalize(input: &str) -> String {
+    input
+        .trim_matches(|c: char| !c.is_alphanumeric())
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exact_matches_take_the_fast_path() {
+        assert_eq!(fast_path("/reindex"), Some(FastPath::SlashCommand));
+        assert_eq!(fast_path("  ¡Hola! 👋"), Some(FastPath::Greeting));
+        assert_eq!(fast_path("Buenos  días"), Some(FastPath::Greeting));
+        assert_eq!(fast_path("Thanks!"), Some(FastPath::Thanks));
+        assert_eq!(fast_path("Sí."), Some(FastPath::Answer(true)));
+        assert_eq!(fast_path("no"), Some(FastPath::Answer(false)));
+        assert_eq!(fast_path("clear"), Some(FastPath::BuiltIn));
+        assert_eq!(fast_path("?"), Some(FastPath::BuiltIn));
+    }
+
+    #[test]
+    fn test_everything_else_goes_to_the_classifier() {
+        assert_eq!(fast_path("hola, ¿qué hace main.rs?"), None);
+        assert_eq!(fast_path("no entiendo el error de compilación"), None);
+        assert_eq!(fast_path("list the files that use tokio"), None);
+        assert_eq!(fast_path("  "), None);
+        assert!(canned_reply(FastPath::Answer(true), Locale::Spanish).is_none());
+    }
+}
@@ -0,0 +1,182 @@
+//! User preferences
+//!
+//! How the user wants code and answers written (indentation, naming, test
+//! framework, verbosity, comment language, other style rules) is stored in
+//! `user_preferences`, globally and per project, and given to the model on
+//! every turn so it need not be repeated each session. A project value
+//! overrides the global one. `/preferences` shows and edits them.
+
+use crate::db::{Database, DatabaseError};
+use crate::i18n::Locale;
+use std::collections::BTreeMap;
+use thiserror::Error;
+
+/// Preferences the user can set, with what each one holds
+pub const PREFERENCE_KEYS: &[(&str, &str)] = &[
+    ("indent", "Indentación: \"4 spaces\", \"2 spaces\", \"tabs\""),
+    ("naming", "Convenciones de nombres: \"snake_case\", \"camelCase\"..."),
+    ("test_framework", "Framework de tests: \"pytest\", \"jest\", \"cargo test\"..."),
+    ("verbosity", "Longitud de las respuestas: brief, normal o detailed"),
+    ("comment_language", "Idioma de los comentarios del código: \"english\", \"español\"..."),
+    ("code_style", "Otras reglas de estilo: \"use thiserror for errors\"..."),
+];
+
+const VERBOSITY: &[&str] = &["brief", "normal", "detailed"];
+/// Longest value stored, in characters
+const MAX_VALUE_CHARS: usize = 300;
+
+#[derive(Error, Debug)]
+pub enum PreferenceError {
+    #[error("Unknown preference '{0}'")]
+    UnknownKey(String),
+    #[error("Invalid value '{value}' for {key}: expected {expected}")]
+    InvalidValue {
+        key: String,
+        value: String,
+        expected: String,
+    },
+}
+
+/// Where an effective preference comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PreferenceScope {
+    Global,
+    Project,
+}
+
+impl PreferenceScope {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Global => "global",
+            Self::Project => "proyecto",
+        }
+    }
+}
+
+/// Effective preferences of a project: its own values over the global ones
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Preferences {
+    values: BTreeMap<String, (String, PreferenceScope)>,
+}
+
+impl Preferences {
+    /// Merge the stored `(key, value)` pairs of both scopes
+    pub fn merged(global: Vec<(String, String)>, project: Vec<(String, String)>) -> Self {
+        let mut values = BTreeMap::new();
+        for (key, value) in global {
+            values.insert(key, (value, PreferenceScope::Global));
+        }
+        for (key, value) in project {
+            values.insert(key, (value, PreferenceScope::Project));
+        }
+        Self { values }
+    }
+
+    /// Global and project preferences of `working_dir`
+    pub async fn load(db: &Database, working_dir: &str) -> Result<Self, DatabaseError> {
+        let global = db.get_preferences(None).await?;
+        let project = db.get_preferences(Some(&Database::project_key(working_dir))).await?;
+        Ok(Self::merged(global, project))
+    }
+
+    pub fn get(&self, key: &str) -> Option<(&str, PreferenceScope)> {
+        self.values.get(key).map(|(value, scope)| (value.as_str(), *scope))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// One instruction per preference, in the order of [`PREFERENCE_KEYS`]
+    pub fn instructions(&self, locale: Locale) -> Vec<String> {
+        PREFERENCE_KEYS
+            .iter()
+            .filter_map(|(key, _)| Some(instruction(key, self.get(key)?.0, locale)))
+            .collect()
+    }
+}
+
+fn instruction(key: &str, value: &str, locale: Locale) -> String {
+    let spanish = locale == Locale::Spanish;
+    match (key, value) {
+        ("verbosity", "brief") if spanish => "Respuestas breves: el código y una o dos frases".to_string(),
+        ("verbosity", "brief") => "Keep answers brief: the code and one or two sentences".to_string(),
+        ("verbosity", "detailed") if spanish => "Respuestas detalladas: explica el porqué de cada paso".to_string(),
+        ("verbosity", "detailed") => "Give detailed answers: explain the reasoning behind each step".to_string(),
+        ("verbosity", _) if spanish => "Respuestas de longitud normal".to_string(),
+        ("verbosity", _) => "Answers of normal length".to_string(),
+        ("indent", _) if spanish => format!("Indentación: {}", value),
+        ("indent", _) => format!("Indentation: {}", value),
+        ("naming", _) if spanish => format!("Nombres: {}", value),
+        ("naming", _) => format!("Naming: {}", value),
+        ("test_framework", _) if spanish => format!("Escribe los tests con {}", value),
+        ("test_framework", _) => format!("Write tests with {}", value),
+        ("comment_language", _) if spanish => format!("Comentarios del código en {}", value),
+        ("comment_language", _) => format!("Write code comments in {}", value),
+        _ => value.to_string(),
+    }
+}
+
+/// Check a preference before storing it; returns the value to store
+pub fn validate(key: &str, value: &str) -> Result<String, PreferenceError> {
+    if !PREFERENCE_KEYS.iter().any(|(known, _)| *known == key) {
+        return Err(PreferenceError::UnknownKey(key.to_string()));
+    }
+    let value = value.split_whitespace().collect::<Vec<_>>().join(" ");
+    let invalid = |expected: &str| PreferenceError::InvalidValue {
+        key: key.to_string(),
+        value: value.clone(),
+        expected: expected.to_string(),
+    };
+    if value.is_empty() {
+        return Err(invalid("a value"));
+    }
+    if value.chars().count() > MAX_VALUE_CHARS {
+        return Err(invalid("at most 300 characters"));
+    }
+    if key == "verbosity" {
+        let level = value.to_lowercase();
+        return if VERBOSITY.contains(&level.as_str()) {
+            Ok(level)
+        } else {
+            Err(invalid(&VERBOSITY.join(", ")))
+        };
+    }
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pairs(values: &[(&str, &str)]) -> Vec<(String, String)> {
+        values.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+    }
+
+    #[test]
+    fn test_project_overrides_global() {
+        let preferences = Preferences::merged(
+            pairs(&[("naming", "snake_case"), ("verbosity", "detailed")]),
+            pairs(&[("verbosity", "brief"), ("code_style", "use thiserror for errors")]),
+        );
+
+        assert_eq!(preferences.get("naming"), Some(("snake_case", PreferenceScope::Global)));
+        assert_eq!(preferences.get("verbosity"), Some(("brief", PreferenceScope::Project)));
+        assert_eq!(
+            preferences.instructions(Locale::English),
+            vec![
+                "Naming: snake_case",
+                "Keep answers brief: the code and one or two sentences",
+                "use thiserror for errors",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_validate() {
+        assert_eq!(validate("verbosity", " Brief ").unwrap(), "brief");
+        assert_eq!(validate("indent", "4   spaces").unwrap(), "4 spaces");
+        assert!(matches!(validate("verbosity", "chatty"), Err(PreferenceError::InvalidValue { .. })));
+        assert!(matches!(validate("theme", "dark"), Err(PreferenceError::UnknownKey(_))));
+    }
+}
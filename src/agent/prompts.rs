@@ -14,6 +14,16 @@ pub struct PromptConfig {
     pub working_dir: String,
     pub locale: Locale,
     pub include_safety_guidelines: bool,
+    /// Instructions from the project's `.neuro/prompts/`
+    pub project_instructions: Option<String>,
+    /// Secondary roots the tools may read (by absolute path) but not write
+    pub read_only_roots: Vec<String>,
+    /// Remembered facts relevant to the query (`memory` tool, `/remember`)
+    pub memories: Vec<String>,
+    /// The user's preferences for code and answers (`/preferences`)
+    pub preferences: Vec<String>,
+    /// Example questions and answers to imitate (`/examples`)
+    pub examples: Vec<(String, String)>,
 }
 
 impl PromptConfig {
@@ -22,6 +32,11 @@ impl PromptConfig {
             working_dir,
             locale,
             include_safety_guidelines: true,
+            project_instructions: None,
+            read_only_roots: Vec::new(),
+            memories: Vec::new(),
+            preferences: Vec::new(),
+            examples: Vec::new(),
         }
     }
 }
@@ -34,9 +49,68 @@ impl PromptConfig {
 /// - Proactive: anticipate information needs
 /// - Clear on uncertainty handling
 pub fn build_minimal_system_prompt(config: &PromptConfig) -> String {
-    match config.locale {
+    let prompt = match config.locale {
         Locale::Spanish => build_minimal_system_prompt_es(&config.working_dir),
         Locale::English => build_minimal_system_prompt_en(&config.working_dir),
+    };
+    let prompt = if config.read_only_roots.is_empty() {
+        prompt
+    } else {
+        let heading = match config.locale {
+            Locale::Spanish => "DIRECTORIOS DE SOLO LECTURA (lee con rutas absolutas, no escribas en ellos)",
+            Locale::English => "READ-ONLY ROOTS (read with absolute paths, never write to them)",
+        };
+        format!("{}\n\n{}:\n- {}", prompt, heading, config.read_only_roots.join("\n- "))
+    };
+    let prompt = if config.memories.is_empty() {
+        prompt
+    } else {
+        let heading = match config.locale {
+            Locale::Spanish => "DATOS RECORDADOS DE ESTE PROYECTO (respétalos)",
+            Locale::English => "REMEMBERED FACTS ABOUT THIS PROJECT (follow them)",
+        };
+        format!("{}\n\n{}:\n- {}", prompt, heading, config.memories.join("\n- "))
+    };
+    let prompt = if config.preferences.is_empty() {
+        prompt
+    } else {
+        let heading = match config.locale {
+            Locale::Spanish => "PREFERENCIAS DEL USUARIO (aplícalas al código y a las respuestas)",
+            Locale::English => "USER PREFERENCES (apply them to code and answers)",
+        };
+        format!("{}\n\n{}:\n- {}", prompt, heading, config.preferences.join("\n- "))
+    };
+    let prompt = if config.examples.is_empty() {
+        prompt
+    } else {
+        let (heading, question, answer) = match config.locale {
+            Locale::Spanish => (
+                "EJEMPLOS DE RESPUESTAS DE ESTE PROYECTO (imita su estilo y convenciones, no su contenido)",
+                "Pregunta",
+                "Respuesta",
+            ),
+            Locale::English => (
+                "EXAMPLE ANSWERS FOR THIS PROJECT (follow their style and conventions, not their content)",
+                "Question",
+                "Answer",
+            ),
+        };
+        let examples: Vec<String> = config
+            .examples
+            .iter()
+            .map(|(prompt, response)| format!("{}: {}\n{}:\n{}", question, prompt, answer, response))
+            .collect();
+        format!("{}\n\n{}:\n\n{}", prompt, heading, examples.join("\n\n"))
+    };
+    match &config.project_instructions {
+        Some(instructions) => {
+            let heading = match config.locale {
+                Locale::Spanish => "INSTRUCCIONES DEL PROYECTO",
+                Locale::English => "PROJECT INSTRUCTIONS",
+            };
+            format!("{}\n\n{}:\n{}", prompt, heading, instructions)
+        }
+        None => prompt,
     }
 }
 
@@ -68,7 +142,7 @@ FLUJO DE TRABAJO PARA ANÁLISIS DE CÓDIGO:
    - Usar run_linter para análisis de calidad de código
 
 HERRAMIENTAS DISPONIBLES:
-- read_file: Lee archivos (puede especificar start_line/end_line)
+- read_file: Lee archivos (start_line/end_line, symbol="Tipo::funcion" o around="texto")
 - write_file: Escribe/modifica archivos
 - list_directory: Lista contenido de directorios (usa recursive=true para profundidad)
 - search_files: Busca texto/patrones en archivos
@@ -120,7 +194,7 @@ WORKFLOW FOR CODE ANALYSIS:
    - Use run_linter for code quality analysis
 
 AVAILABLE TOOLS:
-- read_file: Read files (can specify start_line/end_line)
+- read_file: Read files (start_line/end_line, symbol="Type::function" or around="text")
 - write_file: Write/modify files
 - list_directory: List directory contents (use recursive=true for depth)
 - search_files: Search text/patterns in files
@@ -235,6 +309,33 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_memories_are_listed() {
+        let mut config = PromptConfig::new("/tmp".to_string(), Locale::English);
+        config.memories = vec!["we deploy with just, not make".to_string()];
+        let prompt = build_minimal_system_prompt(&config);
+
+        assert!(prompt.contains("REMEMBERED FACTS ABOUT THIS PROJECT (follow them):\n- we deploy with just, not make"));
+    }
+
+    #[test]
+    fn test_preferences_are_listed() {
+        let mut config = PromptConfig::new("/tmp".to_string(), Locale::Spanish);
+        config.preferences = vec!["Nombres: snake_case".to_string()];
+        let prompt = build_minimal_system_prompt(&config);
+
+        assert!(prompt.contains("PREFERENCIAS DEL USUARIO (aplícalas al código y a las respuestas):\n- Nombres: snake_case"));
+    }
+
+    #[test]
+    fn test_examples_are_listed() {
+        let mut config = PromptConfig::new("/tmp".to_string(), Locale::English);
+        config.examples = vec![("add an endpoint".to_string(), "Use an axum Router".to_string())];
+        let prompt = build_minimal_system_prompt(&config);
+
+        assert!(prompt.contains("not their content):\n\nQuestion: add an endpoint\nAnswer:\nUse an axum Router"));
+    }
+
     #[test]
     fn test_proactive_validation_compact() {
         let prompt =
@@ -59,7 +59,10 @@
 //! # }
 //! ```
 
+use super::reasoning;
 use crate::config::{ModelConfig, ModelProvider as ProviderType};
+use crate::security::network::{self, SendChecked};
+use crate::security::NetworkError;
 use async_trait::async_trait;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
@@ -90,6 +93,18 @@ pub enum ProviderError {
     
     #[error("JSON error: {0}")]
     JsonError(#[from] serde_json::Error),
+
+    #[error("{0}")]
+    Blocked(NetworkError),
+}
+
+impl From<NetworkError> for ProviderError {
+    fn from(err: NetworkError) -> Self {
+        match err {
+            NetworkError::Request(e) => ProviderError::ConnectionError(e.to_string()),
+            blocked => ProviderError::Blocked(blocked),
+        }
+    }
 }
 
 /// Response from a model provider
@@ -118,6 +133,7 @@ pub trait ModelProvider: Send + Sync {
 
 /// Create a model provider from configuration
 pub fn create_provider(config: ModelConfig) -> Result<Box<dyn ModelProvider>, ProviderError> {
+    network::check(&config.url, "model provider")?;
     match config.provider {
         ProviderType::Ollama => Ok(Box::new(OllamaProvider::new(config))),
         ProviderType::OpenAI => Ok(Box::new(OpenAIProvider::new(config)?)),
@@ -137,7 +153,7 @@ pub struct OllamaProvider {
 
 impl OllamaProvider {
     pub fn new(config: ModelConfig) -> Self {
-        let client = Client::builder()
+        let client = network::client_builder()
             .timeout(Duration::from_secs(300))
             .build()
             .unwrap_or_default();
@@ -151,6 +167,9 @@ struct OllamaRequest {
     model: String,
     prompt: String,
     stream: bool,
+    /// `false` turns the thinking of reasoning models off
+    #[serde(skip_serializing_if = "Option::is_none")]
+    think: Option<bool>,
     #[serde(skip_serializing_if = "Option::is_none")]
     options: Option<OllamaOptions>,
 }
@@ -211,6 +230,12 @@ struct OllamaChatRequest {
     #[serde(skip_serializing_if = "Option::is_none")]
     tools: Option<Vec<OllamaTool>>,
     stream: bool,
+    /// "json" to constrain the output to valid JSON
+    #[serde(skip_serializing_if = "Option::is_none")]
+    format: Option<String>,
+    /// `false` turns the thinking of reasoning models off
+    #[serde(skip_serializing_if = "Option::is_none")]
+    think: Option<bool>,
     #[serde(skip_serializing_if = "Option::is_none")]
     options: Option<OllamaOptions>,
 }
@@ -232,6 +257,9 @@ pub struct OllamaMessage {
     pub content: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub tool_calls: Option<Vec<OllamaToolCall>>,
+    /// Reasoning of a thinking model, when Ollama returns it apart from the content
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub thinking: Option<String>,
 }
 
 #[async_trait]
@@ -243,6 +271,7 @@ impl ModelProvider for OllamaProvider {
             model: self.config.model.clone(),
             prompt: prompt.to_string(),
             stream: false,
+            think: reasoning::think_option(),
             options: Some(OllamaOptions {
                 temperature: self.config.temperature,
                 top_p: self.config.top_p,
@@ -254,9 +283,8 @@ impl ModelProvider for OllamaProvider {
             .post(&url)
             .json(&request)
             .timeout(Duration::from_secs(60))  // Add 60-second timeout for regular generation
-            .send()
-            .await
-            .map_err(|e| ProviderError::ConnectionError(e.to_string()))?;
+            .send_checked("model provider")
+            .await?;
         
         if !response.status().is_success() {
             return Err(ProviderError::ModelError(
@@ -278,9 +306,8 @@ impl ModelProvider for OllamaProvider {
         
         let response = self.client
             .get(&url)
-            .send()
-            .await
-            .map_err(|e| ProviderError::ConnectionError(e.to_string()))?;
+            .send_checked("model provider")
+            .await?;
         
         if !response.status().is_success() {
             return Err(ProviderError::ConnectionError(
@@ -310,13 +337,32 @@ impl OllamaProvider {
         messages: Vec<serde_json::Value>,
         tools: Option<Vec<OllamaTool>>,
     ) -> Result<OllamaMessage, ProviderError> {
-        let url = format!("{}/api/chat", self.config.url);
+        self.chat(messages, tools, None).await
+    }
 
+    /// Generate with Ollama's JSON mode, which constrains the output to valid JSON.
+    /// Only use with models whose capabilities include `json_mode`.
+    pub async fn generate_json(
+        &self,
+        messages: Vec<serde_json::Value>,
+    ) -> Result<OllamaMessage, ProviderError> {
+        self.chat(messages, None, Some("json".to_string())).await
+    }
+
+    async fn chat(
+        &self,
+        messages: Vec<serde_json::Value>,
+        tools: Option<Vec<OllamaTool>>,
+        format: Option<String>,
+    ) -> Result<OllamaMessage, ProviderError> {
+        let url = format!("{}/api/chat", self.config.url);
         let request = OllamaChatRequest {
             model: self.config.model.clone(),
             messages,
             tools,
             stream: false,
+            format,
+            think: reasoning::think_option(),
             options: Some(OllamaOptions {
                 temperature: self.config.temperature,
                 top_p: self.config.top_p,
@@ -329,9 +375,8 @@ impl OllamaProvider {
             .post(&url)
             .json(&request)
             .timeout(Duration::from_secs(60))  // Add 60-second timeout for tool calls
-            .send()
-            .await
-            .map_err(|e| ProviderError::ConnectionError(e.to_string()))?;
+            .send_checked("model provider")
+            .await?;
 
         if !response.status().is_success() {
             let status = response.status();
@@ -369,7 +414,7 @@ impl OpenAIProvider {
         let api_key = config.resolve_api_key()
             .ok_or_else(|| ProviderError::AuthError("OpenAI API key not found".to_string()))?;
         
-        let client = Client::builder()
+        let client = network::client_builder()
             .timeout(Duration::from_secs(300))
             .build()
             .unwrap_or_default();
@@ -432,9 +477,8 @@ impl ModelProvider for OpenAIProvider {
             .header("Authorization", format!("Bearer {}", self.api_key))
             .header("Content-Type", "application/json")
             .json(&request)
-            .send()
-            .await
-            .map_err(|e| ProviderError::ConnectionError(e.to_string()))?;
+            .send_checked("model provider")
+            .await?;
         
         if !response.status().is_success() {
             return Err(ProviderError::ModelError(
@@ -460,9 +504,8 @@ impl ModelProvider for OpenAIProvider {
         let response = self.client
             .get(&url)
             .header("Authorization", format!("Bearer {}", self.api_key))
-            .send()
-            .await
-            .map_err(|e| ProviderError::ConnectionError(e.to_string()))?;
+            .send_checked("model provider")
+            .await?;
         
         if response.status() == 401 {
             return Err(ProviderError::AuthError("Invalid API key".to_string()));
@@ -501,7 +544,7 @@ impl AnthropicProvider {
         let api_key = config.resolve_api_key()
             .ok_or_else(|| ProviderError::AuthError("Anthropic API key not found".to_string()))?;
         
-        let client = Client::builder()
+        let client = network::client_builder()
             .timeout(Duration::from_secs(300))
             .build()
             .unwrap_or_default();
@@ -559,9 +602,8 @@ impl ModelProvider for AnthropicProvider {
             .header("anthropic-version", "2023-06-01")
             .header("Content-Type", "application/json")
             .json(&request)
-            .send()
-            .await
-            .map_err(|e| ProviderError::ConnectionError(e.to_string()))?;
+            .send_checked("model provider")
+            .await?;
         
         if !response.status().is_success() {
             return Err(ProviderError::ModelError(
@@ -600,9 +642,8 @@ impl ModelProvider for AnthropicProvider {
             .header("anthropic-version", "2023-06-01")
             .header("Content-Type", "application/json")
             .json(&test_request)
-            .send()
-            .await
-            .map_err(|e| ProviderError::ConnectionError(e.to_string()))?;
+            .send_checked("model provider")
+            .await?;
         
         if response.status() == 401 {
             return Err(ProviderError::AuthError("Invalid API key".to_string()));
@@ -641,7 +682,7 @@ impl GroqProvider {
         let api_key = config.resolve_api_key()
             .ok_or_else(|| ProviderError::AuthError("Groq API key not found".to_string()))?;
         
-        let client = Client::builder()
+        let client = network::client_builder()
             .timeout(Duration::from_secs(300))
             .build()
             .unwrap_or_default();
@@ -673,9 +714,8 @@ impl ModelProvider for GroqProvider {
             .header("Authorization", format!("Bearer {}", self.api_key))
             .header("Content-Type", "application/json")
             .json(&request)
-            .send()
-            .await
-            .map_err(|e| ProviderError::ConnectionError(e.to_string()))?;
+            .send_checked("model provider")
+            .await?;
         
         if !response.status().is_success() {
             return Err(ProviderError::ModelError(
@@ -701,9 +741,8 @@ impl ModelProvider for GroqProvider {
         let response = self.client
             .get(&url)
             .header("Authorization", format!("Bearer {}", self.api_key))
-            .send()
-            .await
-            .map_err(|e| ProviderError::ConnectionError(e.to_string()))?;
+            .send_checked("model provider")
+            .await?;
         
         if response.status() == 401 {
             return Err(ProviderError::AuthError("Invalid API key".to_string()));
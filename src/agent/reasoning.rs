@@ -0,0 +1,191 @@
+//! Reasoning models
+//!
+//! Models like qwen3 or deepseek-r1 think out loud before answering, inside
+//! `<think>…</think>` or, when Ollama parses it, in a separate `thinking` field.
+//! The reasoning is split from the answer as it streams: the UI shows it apart,
+//! collapsed by default, and it never enters the history or the token budgets.
+
+use serde::{Deserialize, Serialize};
+use std::sync::{OnceLock, RwLock};
+
+const OPEN_TAG: &str = "<think>";
+const CLOSE_TAG: &str = "</think>";
+
+/// Reasoning settings (`[reasoning]` in the config)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReasoningConfig {
+    /// Let reasoning models think; `false` asks Ollama to skip it (`think: false`)
+    #[serde(default = "default_think")]
+    pub think: bool,
+
+    /// Show the reasoning of each answer expanded instead of collapsed
+    #[serde(default)]
+    pub expanded: bool,
+}
+
+fn default_think() -> bool {
+    true
+}
+
+impl Default for ReasoningConfig {
+    fn default() -> Self {
+        Self {
+            think: default_think(),
+            expanded: false,
+        }
+    }
+}
+
+fn config_lock() -> &'static RwLock<ReasoningConfig> {
+    static CONFIG: OnceLock<RwLock<ReasoningConfig>> = OnceLock::new();
+    CONFIG.get_or_init(|| RwLock::new(ReasoningConfig::default()))
+}
+
+/// Set the process-wide reasoning settings
+pub fn set_config(config: ReasoningConfig) {
+    *config_lock().write().unwrap_or_else(|e| e.into_inner()) = config;
+}
+
+/// Whether the reasoning of answers starts expanded in the UI
+pub fn expanded() -> bool {
+    config_lock().read().unwrap_or_else(|e| e.into_inner()).expanded
+}
+
+/// `think` option for Ollama requests: `Some(false)` when thinking is turned off.
+/// Nothing is sent otherwise, since models that cannot think reject `think: true`.
+pub fn think_option() -> Option<bool> {
+    (!config_lock().read().unwrap_or_else(|e| e.into_inner()).think).then_some(false)
+}
+
+/// Add the `think` option to the body of an Ollama request
+pub fn apply_options(body: &mut serde_json::Value) {
+    if let Some(think) = think_option() {
+        body["think"] = serde_json::Value::Bool(think);
+    }
+}
+
+/// Part of a model's output
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Segment {
+    Thinking(String),
+    Answer(String),
+}
+
+/// Splits streamed output into reasoning and answer, also when a tag is cut
+/// between two chunks
+#[derive(Debug, Default)]
+pub struct ThinkSplitter {
+    in_think: bool,
+    pending: String,
+}
+
+impl ThinkSplitter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Segments complete so far; the start of a possible tag is held back
+    pub fn push(&mut self, chunk: &str) -> Vec<Segment> {
+        self.pending.push_str(chunk);
+        let mut segments = Vec::new();
+        loop {
+            let tag = if self.in_think { CLOSE_TAG } else { OPEN_TAG };
+            if let Some(pos) = self.pending.find(tag) {
+                let text: String = self.pending.drain(..pos).collect();
+                self.pending.drain(..tag.len());
+                self.emit(&mut segments, text);
+                self.in_think = !self.in_think;
+                continue;
+            }
+            let held = partial_tag_len(&self.pending, tag);
+            let text: String = self.pending.drain(..self.pending.len() - held).collect();
+            self.emit(&mut segments, text);
+            return segments;
+        }
+    }
+
+    /// Whatever was held back, at the end of the stream
+    pub fn finish(&mut self) -> Vec<Segment> {
+        let mut segments = Vec::new();
+        let text = std::mem::take(&mut self.pending);
+        self.emit(&mut segments, text);
+        segments
+    }
+
+    fn emit(&self, segments: &mut Vec<Segment>, text: String) {
+        if text.is_empty() {
+            return;
+        }
+        segments.push(if self.in_think {
+            Segment::Thinking(text)
+        } else {
+            Segment::Answer(text)
+        });
+    }
+}
+
+/// Length of the longest end of `text` that is the start of `tag`
+fn partial_tag_len(text: &str, tag: &str) -> usize {
+    (1..tag.len())
+        .rev()
+        .find(|&n| n <= text.len() && text.is_char_boundary(text.len() - n) && tag.starts_with(&text[text.len() - n..]))
+        .unwrap_or(0)
+}
+
+/// Answer and reasoning of a complete output
+pub fn split(text: &str) -> (String, Option<String>) {
+    let mut splitter = ThinkSplitter::new();
+    let mut segments = splitter.push(text);
+    segments.extend(splitter.finish());
+
+    let (mut answer, mut thinking) = (String::new(), String::new());
+    for segment in segments {
+        match segment {
+            Segment::Answer(text) => answer.push_str(&text),
+            Segment::Thinking(text) => thinking.push_str(&text),
+        }
+    }
+    let thinking = thinking.trim();
+    (answer.trim_start().to_string(), (!thinking.is_empty()).then(|| thinking.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_complete_output() {
+        let (answer, thinking) = split("<think>\nThe user greets me.\n</think>\n\nHola!");
+        assert_eq!(answer, "Hola!");
+        assert_eq!(thinking.as_deref(), Some("The user greets me."));
+
+        assert_eq!(split("Sin razonamiento"), ("Sin razonamiento".to_string(), None));
+        assert_eq!(split("<think>\n\n</think>\n\nok"), ("ok".to_string(), None));
+    }
+
+    #[test]
+    fn test_splitter_handles_tags_cut_between_chunks() {
+        let mut splitter = ThinkSplitter::new();
+        let mut segments = Vec::new();
+        for chunk in ["<thi", "nk>pl", "an</th", "ink>Do", "ne <b>"] {
+            segments.extend(splitter.push(chunk));
+        }
+        segments.extend(splitter.finish());
+        assert_eq!(
+            segments,
+            vec![
+                Segment::Thinking("pl".to_string()),
+                Segment::Thinking("an".to_string()),
+                Segment::Answer("Do".to_string()),
+                Segment::Answer("ne <b>".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_apply_options() {
+        let mut body = serde_json::json!({ "model": "qwen3:8b" });
+        apply_options(&mut body);
+        assert!(body.get("think").is_none());
+    }
+}
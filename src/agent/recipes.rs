@@ -0,0 +1,717 @@
+//! Recipes: reusable multi-step workflows
+//!
+//! A recipe is a YAML file in `.neuro/recipes/` (shared with the project) or
+//! `~/.config/neuro/recipes/` (personal; a project recipe with the same name
+//! wins). Each step runs a slash command, a tool or a prompt to the heavy
+//! model; text may use `{{variable}}`, `{{steps.<id>.output}}` and
+//! `{{last_output}}`. A step can be conditional on the output of an earlier
+//! one and can ask for confirmation before it runs:
+//!
+//! ```yaml
+//! name: prepare-release
+//! description: Test, summarize and tag a release
+//! variables:
+//!   version:
+//!     description: Version to release
+//! steps:
+//!   - id: tests
+//!     command: /test
+//!   - prompt: "Write release notes for {{version}}. Test results:\n{{steps.tests.output}}"
+//!     when: { step: tests, success: true }
+//!   - tool: git_workflow
+//!     args: { action: "create_branch", name: "release-{{version}}" }
+//!     confirm: true
+//! ```
+//!
+//! [`RecipeRun`] keeps the progress of a run between turns, so `/recipe
+//! continue` picks up after a confirmation pause.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+use thiserror::Error;
+
+/// Project recipes, relative to the working directory
+pub const PROJECT_RECIPES_DIR: &str = ".neuro/recipes";
+/// Characters of a step output kept in the run log
+const MAX_LOGGED_OUTPUT_CHARS: usize = 1500;
+/// Slash commands that only read the project, the only ones started early
+const PREFETCH_COMMANDS: &[&str] = &["search", "analyze", "callgraph", "deadcode", "owners", "context"];
+
+#[derive(Debug, Error)]
+pub enum RecipeError {
+    #[error("Unknown recipe '{0}' (see /recipe list)")]
+    NotFound(String),
+    #[error("Invalid recipe {0}: {1}")]
+    Parse(PathBuf, serde_yaml::Error),
+    #[error("Step {0} must have exactly one of command, tool or prompt")]
+    InvalidStep(usize),
+    #[error("Step {0}: recipes cannot run /recipe")]
+    NestedRecipe(usize),
+    #[error("Step {0}: condition refers to unknown step '{1}'")]
+    UnknownStep(usize, String),
+    #[error("Missing value for variable '{0}' (pass {0}=...)")]
+    MissingVariable(String),
+    #[error("Unknown variable '{0}'")]
+    UnknownVariable(String),
+    #[error("Expected name=value, got '{0}'")]
+    InvalidArgument(String),
+    #[error("No recipe is running")]
+    NotRunning,
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// A variable the recipe expects
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RecipeVariable {
+    /// Used when the run does not set the variable; required otherwise
+    #[serde(default)]
+    pub default: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+}
+
+/// Run a step only when an earlier step's outcome matches
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct StepCondition {
+    /// `id` of the earlier step
+    pub step: String,
+    #[serde(default)]
+    pub contains: Option<String>,
+    #[serde(default)]
+    pub not_contains: Option<String>,
+    #[serde(default)]
+    pub success: Option<bool>,
+}
+
+/// One step of a recipe; exactly one of `command`, `tool` and `prompt` is set
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RecipeStep {
+    /// Name other steps use in conditions and `{{steps.<id>.output}}`
+    #[serde(default)]
+    pub id: Option<String>,
+    /// Slash command, e.g. `/test`
+    #[serde(default)]
+    pub command: Option<String>,
+    /// Tool name, called with `args`
+    #[serde(default)]
+    pub tool: Option<String>,
+    #[serde(default)]
+    pub args: Option<serde_json::Value>,
+    /// Prompt for the heavy model
+    #[serde(default)]
+    pub prompt: Option<String>,
+    #[serde(default)]
+    pub when: Option<StepCondition>,
+    /// Pause for `/recipe continue` before running
+    #[serde(default)]
+    pub confirm: bool,
+}
+
+/// What a step does, with its text already interpolated
+#[derive(Debug, Clone, PartialEq)]
+pub enum StepAction {
+    Command(String),
+    Tool { name: String, args: serde_json::Value },
+    Prompt(String),
+}
+
+impl StepAction {
+    pub fn describe(&self) -> String {
+        match self {
+            Self::Command(command) => command.clone(),
+            Self::Tool { name, args } => format!("tool {} {}", name, args),
+            Self::Prompt(prompt) => {
+                let first_line = prompt.lines().next().unwrap_or_default();
+                format!("prompt \"{}\"", truncate(first_line, 80))
+            }
+        }
+    }
+}
+
+/// A workflow loaded from YAML
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Recipe {
+    /// Defaults to the file name
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub variables: BTreeMap<String, RecipeVariable>,
+    pub steps: Vec<RecipeStep>,
+}
+
+impl Recipe {
+    pub fn from_yaml(path: &Path, yaml: &str) -> Result<Self, RecipeError> {
+        let mut recipe: Recipe =
+            serde_yaml::from_str(yaml).map_err(|e| RecipeError::Parse(path.to_path_buf(), e))?;
+        if recipe.name.is_empty() {
+            recipe.name = recipe_name(path);
+        }
+        recipe.validate()?;
+        Ok(recipe)
+    }
+
+    fn validate(&self) -> Result<(), RecipeError> {
+        let mut seen = Vec::new();
+        for (index, step) in self.steps.iter().enumerate() {
+            let number = index + 1;
+            let actions = [step.command.is_some(), step.tool.is_some(), step.prompt.is_some()];
+            if actions.iter().filter(|set| **set).count() != 1 {
+                return Err(RecipeError::InvalidStep(number));
+            }
+            if step
+                .command
+                .as_deref()
+                .is_some_and(|c| c.trim_start().starts_with("/recipe"))
+            {
+                return Err(RecipeError::NestedRecipe(number));
+            }
+            if let Some(condition) = &step.when {
+                if !seen.contains(&condition.step.as_str()) {
+                    return Err(RecipeError::UnknownStep(number, condition.step.clone()));
+                }
+            }
+            if let Some(id) = &step.id {
+                seen.push(id.as_str());
+            }
+        }
+        Ok(())
+    }
+
+    /// Variables for a run from `name=value` arguments and the defaults
+    pub fn bind(&self, args: &[&str]) -> Result<BTreeMap<String, String>, RecipeError> {
+        let mut values = BTreeMap::new();
+        for arg in args {
+            let (name, value) = arg
+                .split_once('=')
+                .ok_or_else(|| RecipeError::InvalidArgument(arg.to_string()))?;
+            if !self.variables.contains_key(name) {
+                return Err(RecipeError::UnknownVariable(name.to_string()));
+            }
+            values.insert(name.to_string(), value.to_string());
+        }
+        for (name, variable) in &self.variables {
+            if !values.contains_key(name) {
+                let default = variable
+                    .default
+                    .clone()
+                    .ok_or_else(|| RecipeError::MissingVariable(name.clone()))?;
+                values.insert(name.clone(), default);
+            }
+        }
+        Ok(values)
+    }
+
+    /// What a run would do, without running anything
+    pub fn dry_run(&self, variables: &BTreeMap<String, String>) -> String {
+        let mut run = RecipeRun::new(self.clone(), variables.clone(), false);
+        // Outputs are not known yet: show where they go
+        for id in self.steps.iter().filter_map(|s| s.id.as_ref()) {
+            let output = format!("<salida de {}>", id);
+            run.outcomes.insert(id.clone(), StepOutcome { output, success: true });
+        }
+        let mut out = format!("🧪 Simulación de la receta {} ({} pasos)\n", self.name, self.steps.len());
+        for (name, value) in variables {
+            out.push_str(&format!("  {} = {}\n", name, value));
+        }
+        for (index, step) in self.steps.iter().enumerate() {
+            out.push_str(&format!("{}. {}", index + 1, run.action(step).describe()));
+            if let Some(condition) = &step.when {
+                out.push_str(&format!("  [si {}]", describe_condition(condition)));
+            }
+            if step.confirm {
+                out.push_str("  [pide confirmación]");
+            }
+            out.push('\n');
+        }
+        out
+    }
+}
+
+fn describe_condition(condition: &StepCondition) -> String {
+    let mut parts = Vec::new();
+    if let Some(success) = condition.success {
+        parts.push(if success { "tuvo éxito" } else { "falló" }.to_string());
+    }
+    if let Some(text) = &condition.contains {
+        parts.push(format!("contiene \"{}\"", text));
+    }
+    if let Some(text) = &condition.not_contains {
+        parts.push(format!("no contiene \"{}\"", text));
+    }
+    format!("{} {}", condition.step, parts.join(" y "))
+}
+
+fn recipe_name(path: &Path) -> String {
+    path.file_stem().unwrap_or_default().to_string_lossy().to_string()
+}
+
+/// Recipes of the project and of the user
+#[derive(Debug, Clone)]
+pub struct RecipeBook {
+    dirs: Vec<PathBuf>,
+}
+
+impl RecipeBook {
+    /// Project recipes first, so they win over personal ones
+    pub fn new(working_dir: &Path) -> Self {
+        // `.neuro/` may sit at the repository root, above the working directory
+        let project_dir = crate::config::ProjectConfig::discover(working_dir)
+            .ok()
+            .and_then(|project| project.subdir("recipes"))
+            .unwrap_or_else(|| working_dir.join(PROJECT_RECIPES_DIR));
+        let mut dirs = vec![project_dir];
+        if let Some(config_dir) = crate::config::AppConfig::config_dir() {
+            dirs.push(config_dir.join("recipes"));
+        }
+        Self { dirs }
+    }
+
+    /// Look only in `dirs`, earlier ones first
+    pub fn with_dirs(dirs: Vec<PathBuf>) -> Self {
+        Self { dirs }
+    }
+
+    fn files(&self) -> Vec<PathBuf> {
+        let mut files = Vec::new();
+        for dir in &self.dirs {
+            let Ok(entries) = std::fs::read_dir(dir) else { continue };
+            let mut found: Vec<PathBuf> = entries
+                .filter_map(|e| e.ok().map(|e| e.path()))
+                .filter(|p| matches!(p.extension().and_then(|e| e.to_str()), Some("yaml" | "yml")))
+                .collect();
+            found.sort();
+            files.extend(found);
+        }
+        files
+    }
+
+    /// All recipes that parse, by name; invalid files are returned apart
+    pub fn list(&self) -> (Vec<Recipe>, Vec<RecipeError>) {
+        let mut recipes: Vec<Recipe> = Vec::new();
+        let mut errors = Vec::new();
+        for path in self.files() {
+            match std::fs::read_to_string(&path)
+                .map_err(RecipeError::from)
+                .and_then(|yaml| Recipe::from_yaml(&path, &yaml))
+            {
+                Ok(recipe) if recipes.iter().any(|r| r.name == recipe.name) => {}
+                Ok(recipe) => recipes.push(recipe),
+                Err(e) => errors.push(e),
+            }
+        }
+        recipes.sort_by(|a, b| a.name.cmp(&b.name));
+        (recipes, errors)
+    }
+
+    pub fn load(&self, name: &str) -> Result<Recipe, RecipeError> {
+        for path in self.files() {
+            let parsed = std::fs::read_to_string(&path)
+                .map_err(RecipeError::from)
+                .and_then(|yaml| Recipe::from_yaml(&path, &yaml));
+            match parsed {
+                Ok(recipe) if recipe.name == name => return Ok(recipe),
+                // A broken file named like the recipe is reported, others are ignored
+                Err(e) if recipe_name(&path) == name => return Err(e),
+                _ => {}
+            }
+        }
+        Err(RecipeError::NotFound(name.to_string()))
+    }
+}
+
+/// Result of a finished step
+#[derive(Debug, Clone)]
+pub struct StepOutcome {
+    pub output: String,
+    pub success: bool,
+}
+
+/// Progress of a recipe across turns
+#[derive(Debug, Clone)]
+pub struct RecipeRun {
+    pub recipe: Recipe,
+    pub variables: BTreeMap<String, String>,
+    /// Ask before every step, not only the ones marked `confirm`
+    pub step_by_step: bool,
+    /// Index of the next step
+    pub next: usize,
+    /// The user approved the next step with `/recipe continue`
+    pub approved: bool,
+    outcomes: HashMap<String, StepOutcome>,
+    last_output: String,
+    /// One entry per step that ran or was skipped
+    pub log: Vec<String>,
+    /// Steps that ran and failed
+    pub failed_steps: usize,
+    pub started: Instant,
+}
+
+impl RecipeRun {
+    pub fn new(recipe: Recipe, variables: BTreeMap<String, String>, step_by_step: bool) -> Self {
+        Self {
+            recipe,
+            variables,
+            step_by_step,
+            next: 0,
+            approved: false,
+            outcomes: HashMap::new(),
+            last_output: String::new(),
+            log: Vec::new(),
+            failed_steps: 0,
+            started: Instant::now(),
+        }
+    }
+
+    /// Next step to run, skipping (and logging) the ones whose condition fails
+    pub fn next_step(&mut self) -> Option<RecipeStep> {
+        while let Some(step) = self.recipe.steps.get(self.next).cloned() {
+            match &step.when {
+                Some(condition) if !self.condition_met(condition) => {
+                    self.log.push(format!(
+                        "⏭️ {}. {} (omitido: no {})",
+                        self.next + 1,
+                        self.action(&step).describe(),
+                        describe_condition(condition)
+                    ));
+                    self.next += 1;
+                }
+                _ => return Some(step),
+            }
+        }
+        None
+    }
+
+    /// The next step has to wait for `/recipe continue`
+    pub fn needs_confirmation(&self, step: &RecipeStep) -> bool {
+        (step.confirm || self.step_by_step) && !self.approved
+    }
+
+    /// Skip the next step without running it
+    pub fn skip(&mut self) {
+        if let Some(step) = self.next_step() {
+            self.log.push(format!("⏭️ {}. {} (omitido)", self.next + 1, self.action(&step).describe()));
+            self.next += 1;
+            self.approved = false;
+        }
+    }
+
+    /// Store the outcome of the next step and move past it
+    pub fn record(&mut self, step: &RecipeStep, output: String, success: bool) {
+        let icon = if success { "✅" } else { "❌" };
+        self.log.push(format!(
+            "{} {}. {}\n{}",
+            icon,
+            self.next + 1,
+            self.action(step).describe(),
+            truncate(output.trim(), MAX_LOGGED_OUTPUT_CHARS)
+        ));
+        if !success {
+            self.failed_steps += 1;
+        }
+        if let Some(id) = &step.id {
+            self.outcomes.insert(id.clone(), StepOutcome { output: output.clone(), success });
+        }
+        self.last_output = output;
+        self.next += 1;
+        self.approved = false;
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.next >= self.recipe.steps.len()
+    }
+
+    /// Some step still to run prompts the heavy model
+    pub fn uses_heavy_model(&self) -> bool {
+        self.recipe.steps.iter().skip(self.next).any(|step| step.prompt.is_some())
+    }
+
+    /// Command of the step after `current` (the next step), when it can start
+    /// while `current` still runs: it is one of the read-only
+    /// [`PREFETCH_COMMANDS`], does not read `current`'s output, its condition
+    /// is already met and it needs no confirmation. Returns its index and
+    /// interpolated command.
+    pub fn prefetchable(&self, current: &RecipeStep) -> Option<(usize, String)> {
+        let index = self.next + 1;
+        let step = self.recipe.steps.get(index)?;
+        let command = step.command.as_deref()?;
+        if !is_read_only(command) || step.confirm || self.step_by_step || reads_output_of(command, current) {
+            return None;
+        }
+        if let Some(condition) = &step.when {
+            if current.id.as_ref() == Some(&condition.step) || !self.condition_met(condition) {
+                return None;
+            }
+        }
+        Some((index, self.interpolate(command)))
+    }
+
+    fn condition_met(&self, condition: &StepCondition) -> bool {
+        let Some(outcome) = self.outcomes.get(&condition.step) else {
+            // The step was skipped, so nothing about it holds
+            return false;
+        };
+        condition.success.is_none_or(|success| success == outcome.success)
+            && condition.contains.as_deref().is_none_or(|text| outcome.output.contains(text))
+            && condition.not_contains.as_deref().is_none_or(|text| !outcome.output.contains(text))
+    }
+
+    /// Replace `{{variable}}`, `{{steps.<id>.output}}` and `{{last_output}}`
+    pub fn interpolate(&self, text: &str) -> String {
+        let mut out = String::with_capacity(text.len());
+        let mut rest = text;
+        while let Some(start) = rest.find("{{") {
+            out.push_str(&rest[..start]);
+            let Some(end) = rest[start..].find("}}") else {
+                rest = &rest[start..];
+                break;
+            };
+            let key = rest[start + 2..start + end].trim();
+            match self.lookup(key) {
+                Some(value) => out.push_str(&value),
+                None => out.push_str(&rest[start..start + end + 2]),
+            }
+            rest = &rest[start + end + 2..];
+        }
+        out.push_str(rest);
+        out
+    }
+
+    fn lookup(&self, key: &str) -> Option<String> {
+        if key == "last_output" {
+            return Some(self.last_output.clone());
+        }
+        if let Some(id) = key.strip_prefix("steps.").and_then(|k| k.strip_suffix(".output")) {
+            return Some(self.outcomes.get(id).map(|o| o.output.clone()).unwrap_or_default());
+        }
+        self.variables.get(key).cloned()
+    }
+
+    /// The step's action with its text interpolated
+    pub fn action(&self, step: &RecipeStep) -> StepAction {
+        if let Some(command) = &step.command {
+            StepAction::Command(self.interpolate(command))
+        } else if let Some(name) = &step.tool {
+            let args = step.args.clone().unwrap_or_else(|| serde_json::json!({}));
+            StepAction::Tool {
+                name: name.clone(),
+                args: self.interpolate_json(args),
+            }
+        } else {
+            StepAction::Prompt(self.interpolate(step.prompt.as_deref().unwrap_or_default()))
+        }
+    }
+
+    fn interpolate_json(&self, value: serde_json::Value) -> serde_json::Value {
+        use serde_json::Value;
+        match value {
+            Value::String(text) => Value::String(self.interpolate(&text)),
+            Value::Array(items) => Value::Array(items.into_iter().map(|v| self.interpolate_json(v)).collect()),
+            Value::Object(map) => Value::Object(
+                map.into_iter().map(|(k, v)| (k, self.interpolate_json(v))).collect(),
+            ),
+            other => other,
+        }
+    }
+
+    /// Prompt shown when the run pauses before a step
+    pub fn pause_message(&self, step: &RecipeStep) -> String {
+        format!(
+            "⏸️ Receta {} · paso {}/{}: {}\n/recipe continue para ejecutarlo · /recipe skip para omitirlo · /recipe abort para detener",
+            self.recipe.name,
+            self.next + 1,
+            self.recipe.steps.len(),
+            self.action(step).describe()
+        )
+    }
+
+    /// Log of the run so far
+    pub fn report(&self) -> String {
+        let mut out = format!("📜 Receta {}\n", self.recipe.name);
+        for entry in &self.log {
+            out.push_str(entry);
+            out.push('\n');
+        }
+        if self.is_finished() {
+            out.push_str("🏁 Receta completada");
+        }
+        out
+    }
+}
+
+/// `command` runs one of the [`PREFETCH_COMMANDS`]
+fn is_read_only(command: &str) -> bool {
+    command
+        .trim_start()
+        .strip_prefix('/')
+        .and_then(|command| command.split_whitespace().next())
+        .is_some_and(|name| PREFETCH_COMMANDS.contains(&name))
+}
+
+/// `text` interpolates the output of `step`
+fn reads_output_of(text: &str, step: &RecipeStep) -> bool {
+    text.contains("last_output")
+        || step.id.as_ref().is_some_and(|id| text.contains(&format!("steps.{}.output", id)))
+}
+
+fn truncate(text: &str, max_chars: usize) -> String {
+    if text.chars().count() <= max_chars {
+        text.to_string()
+    } else {
+        format!("{}…", text.chars().take(max_chars).collect::<String>())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RELEASE: &str = r#"
+name: prepare-release
+description: Test and tag
+variables:
+  version: {}
+  branch:
+    default: main
+steps:
+  - id: tests
+    command: /test
+  - prompt: "Notes for {{version}} on {{branch}}:\n{{steps.tests.output}}"
+    when: { step: tests, success: true, not_contains: "FAILED" }
+  - tool: git_workflow
+    args: { action: "create_branch", name: "release-{{version}}" }
+    confirm: true
+"#;
+
+    fn recipe() -> Recipe {
+        Recipe::from_yaml(Path::new("prepare-release.yaml"), RELEASE).unwrap()
+    }
+
+    #[test]
+    fn test_bind_and_dry_run() {
+        let recipe = recipe();
+        assert!(matches!(recipe.bind(&[]), Err(RecipeError::MissingVariable(v)) if v == "version"));
+        assert!(matches!(recipe.bind(&["nope=1"]), Err(RecipeError::UnknownVariable(_))));
+
+        let vars = recipe.bind(&["version=1.2.0"]).unwrap();
+        assert_eq!(vars["branch"], "main");
+        let plan = recipe.dry_run(&vars);
+        assert!(plan.contains("1. /test"));
+        assert!(plan.contains("[si tests tuvo éxito y no contiene \"FAILED\"]"));
+        assert!(plan.contains(r#"tool git_workflow {"action":"create_branch","name":"release-1.2.0"}"#));
+        assert!(plan.contains("[pide confirmación]"));
+    }
+
+    #[test]
+    fn test_run_follows_conditions_and_confirmation() {
+        let recipe = recipe();
+        let vars = recipe.bind(&["version=2.0"]).unwrap();
+        let mut run = RecipeRun::new(recipe, vars, false);
+
+        let step = run.next_step().unwrap();
+        assert!(!run.needs_confirmation(&step));
+        run.record(&step, "test result: FAILED. 1 failed".to_string(), false);
+
+        // The notes step is skipped because the tests failed
+        let step = run.next_step().unwrap();
+        assert_eq!(step.tool.as_deref(), Some("git_workflow"));
+        assert!(run.needs_confirmation(&step));
+        assert!(run.log[1].contains("omitido"));
+
+        run.approved = true;
+        assert!(!run.needs_confirmation(&step));
+        run.record(&step, "ok".to_string(), true);
+        assert!(run.is_finished());
+        assert!(run.report().ends_with("🏁 Receta completada"));
+    }
+
+    #[test]
+    fn test_interpolation() {
+        let mut run = RecipeRun::new(recipe(), BTreeMap::from([("version".into(), "3".into())]), false);
+        let step = run.next_step().unwrap();
+        run.record(&step, "all passed".to_string(), true);
+        assert_eq!(
+            run.interpolate("v{{ version }} {{steps.tests.output}} {{last_output}} {{unknown}} {{"),
+            "v3 all passed all passed {{unknown}} {{"
+        );
+    }
+
+    #[test]
+    fn test_prefetchable_next_step() {
+        let yaml = r#"
+steps:
+  - id: notes
+    prompt: "Summarize the changes"
+  - command: /search TODO
+  - command: "/analyze {{last_output}}"
+  - prompt: "Review"
+  - command: /test
+    confirm: true
+"#;
+        let mut run = RecipeRun::new(Recipe::from_yaml(Path::new("r.yaml"), yaml).unwrap(), BTreeMap::new(), false);
+        assert!(run.uses_heavy_model());
+
+        // The search does not need the notes, so it can run while they generate
+        let step = run.next_step().unwrap();
+        assert_eq!(run.prefetchable(&step), Some((1, "/search TODO".to_string())));
+        run.record(&step, "notes".to_string(), true);
+
+        // The analysis reads the search results; prompts and confirmed steps never start early
+        let step = run.next_step().unwrap();
+        assert_eq!(run.prefetchable(&step), None);
+        run.record(&step, "3 TODOs".to_string(), true);
+        let step = run.next_step().unwrap();
+        assert_eq!(run.prefetchable(&step), None);
+        run.record(&step, "ok".to_string(), true);
+        let step = run.next_step().unwrap();
+        assert_eq!(run.prefetchable(&step), None);
+        run.record(&step, "ok".to_string(), true);
+        assert!(!run.uses_heavy_model());
+
+        // Only commands that read the project start early
+        assert!(is_read_only(" /search TODO"));
+        assert!(!is_read_only("/shell rm -rf target"));
+        assert!(!is_read_only("/commit-push-pr"));
+        assert!(!is_read_only("search TODO"));
+    }
+
+    #[test]
+    fn test_invalid_recipes_are_rejected() {
+        let path = Path::new("bad.yaml");
+        assert!(matches!(
+            Recipe::from_yaml(path, "steps:\n  - command: /test\n    prompt: hi\n"),
+            Err(RecipeError::InvalidStep(1))
+        ));
+        assert!(matches!(
+            Recipe::from_yaml(path, "steps:\n  - command: /recipe run other\n"),
+            Err(RecipeError::NestedRecipe(1))
+        ));
+        assert!(matches!(
+            Recipe::from_yaml(path, "steps:\n  - prompt: hi\n    when: { step: later }\n"),
+            Err(RecipeError::UnknownStep(1, _))
+        ));
+        // The name defaults to the file name
+        assert_eq!(Recipe::from_yaml(path, "steps: []\n").unwrap().name, "bad");
+    }
+
+    #[test]
+    fn test_project_recipes_win() {
+        let project = tempfile::tempdir().unwrap();
+        let user = tempfile::tempdir().unwrap();
+        std::fs::write(project.path().join("triage.yaml"), "description: project\nsteps: []\n").unwrap();
+        std::fs::write(user.path().join("triage.yml"), "description: user\nsteps: []\n").unwrap();
+        std::fs::write(user.path().join("broken.yaml"), "steps: [").unwrap();
+
+        let book = RecipeBook::with_dirs(vec![project.path().to_path_buf(), user.path().to_path_buf()]);
+        let (recipes, errors) = book.list();
+        assert_eq!(recipes.len(), 1);
+        assert_eq!(recipes[0].description, "project");
+        assert_eq!(errors.len(), 1);
+        assert_eq!(book.load("triage").unwrap().description, "project");
+        assert!(matches!(book.load("missing"), Err(RecipeError::NotFound(_))));
+    }
+}
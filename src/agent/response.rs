@@ -0,0 +1,250 @@
+//! Typed records of a turn
+//!
+//! [`OrchestratorResponse`] stays the plain answer every caller already
+//! matches on. [`StructuredResponse`] adds what happened while producing it:
+//! each tool call with its arguments, result and duration, the files the
+//! answer cites, token usage and timing, so the TUI and JSON output can show
+//! them without parsing the answer text.
+
+use super::orchestrator::OrchestratorResponse;
+use serde::Serialize;
+use std::time::Duration;
+
+/// Tool results longer than this are cut in the record
+const MAX_RECORDED_RESULT: usize = 4_000;
+
+/// One tool invocation of the turn
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ToolCallRecord {
+    pub tool: String,
+    pub args: serde_json::Value,
+    /// Output the model saw, cut at a few thousand characters
+    pub result: String,
+    pub success: bool,
+    pub duration_ms: u64,
+}
+
+impl ToolCallRecord {
+    pub fn new(tool: &str, args: &serde_json::Value, result: &str, duration: Duration) -> Self {
+        let mut recorded = result.to_string();
+        if recorded.len() > MAX_RECORDED_RESULT {
+            let cut = (0..=MAX_RECORDED_RESULT).rev().find(|&i| recorded.is_char_boundary(i)).unwrap_or(0);
+            recorded.truncate(cut);
+            recorded.push_str("\n…");
+        }
+        Self {
+            tool: tool.to_string(),
+            args: args.clone(),
+            success: !is_failure(result),
+            result: recorded,
+            duration_ms: duration.as_millis() as u64,
+        }
+    }
+}
+
+/// Tools report failures in their output rather than as errors
+fn is_failure(result: &str) -> bool {
+    let head = result.trim_start();
+    head.starts_with("Error") || head.starts_with("Tool '") || head.starts_with("Unknown tool")
+}
+
+/// A file the answer cites
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Citation {
+    pub path: String,
+    pub line: Option<usize>,
+    /// The file exists and has the cited line
+    pub verified: bool,
+}
+
+/// Tokens sent to and generated by the models during the turn
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct TokenUsage {
+    pub prompt_tokens: usize,
+    pub completion_tokens: usize,
+    /// Some counts are estimates (about 4 characters per token) because the
+    /// provider did not report them
+    pub estimated: bool,
+}
+
+impl TokenUsage {
+    pub fn total(&self) -> usize {
+        self.prompt_tokens + self.completion_tokens
+    }
+
+    pub fn add(&mut self, prompt_tokens: usize, completion_tokens: usize, estimated: bool) {
+        self.prompt_tokens += prompt_tokens;
+        self.completion_tokens += completion_tokens;
+        self.estimated |= estimated;
+    }
+}
+
+/// Where the time of the turn went, in milliseconds
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct Timing {
+    pub total_ms: u64,
+    /// Waiting for model responses
+    pub model_ms: u64,
+    /// Running tools; calls run in parallel count once each
+    pub tools_ms: u64,
+}
+
+/// Tool calls, usage and model time collected by the orchestrator during a turn
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TurnRecord {
+    /// Last model asked
+    pub model: Option<String>,
+    pub tool_calls: Vec<ToolCallRecord>,
+    pub usage: TokenUsage,
+    pub model_time: Duration,
+    /// Reasoning of the models asked (`<think>`), kept out of the answer
+    pub reasoning: Option<String>,
+}
+
+impl TurnRecord {
+    /// Note a model request and how long its answer took
+    pub fn model_call(&mut self, model: &str, elapsed: Duration, prompt_tokens: usize, completion_tokens: usize, estimated: bool) {
+        self.model = Some(model.to_string());
+        self.model_time += elapsed;
+        self.usage.add(prompt_tokens, completion_tokens, estimated);
+    }
+
+    /// Note the reasoning behind a model's answer
+    pub fn reasoning(&mut self, thinking: &str) {
+        let reasoning = self.reasoning.get_or_insert_with(String::new);
+        if !reasoning.is_empty() {
+            reasoning.push_str("\n\n");
+        }
+        reasoning.push_str(thinking);
+    }
+}
+
+/// Answer of a turn with its typed records
+#[derive(Debug, Clone, Serialize)]
+pub struct StructuredResponse {
+    pub response: OrchestratorResponse,
+    /// Model that produced the answer, when a model was asked
+    pub model: Option<String>,
+    pub tool_calls: Vec<ToolCallRecord>,
+    pub citations: Vec<Citation>,
+    pub usage: TokenUsage,
+    pub timing: Timing,
+    /// Reasoning behind the answer, shown collapsed and never added to the history
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reasoning: Option<String>,
+}
+
+impl StructuredResponse {
+    /// Response with nothing recorded, e.g. for slash commands
+    pub fn plain(response: OrchestratorResponse) -> Self {
+        Self {
+            model: response.model().map(String::from),
+            response,
+            tool_calls: Vec::new(),
+            citations: Vec::new(),
+            usage: TokenUsage::default(),
+            timing: Timing::default(),
+            reasoning: None,
+        }
+    }
+
+    pub fn from_turn(response: OrchestratorResponse, turn: TurnRecord, citations: Vec<Citation>, total: Duration) -> Self {
+        let tools_ms = turn.tool_calls.iter().map(|call| call.duration_ms).sum();
+        Self {
+            model: response.model().map(String::from).or(turn.model),
+            response,
+            tool_calls: turn.tool_calls,
+            citations,
+            usage: turn.usage,
+            timing: Timing {
+                total_ms: total.as_millis() as u64,
+                model_ms: turn.model_time.as_millis() as u64,
+                tools_ms,
+            },
+            reasoning: turn.reasoning,
+        }
+    }
+
+    /// One-line summary shown under the answer, `None` when nothing was recorded
+    pub fn summary(&self) -> Option<String> {
+        let mut parts = Vec::new();
+        if !self.tool_calls.is_empty() {
+            let failed = self.tool_calls.iter().filter(|call| !call.success).count();
+            parts.push(match failed {
+                0 => format!("🔧 {} herramientas", self.tool_calls.len()),
+                _ => format!("🔧 {} herramientas ({} con error)", self.tool_calls.len(), failed),
+            });
+        }
+        if !self.citations.is_empty() {
+            let verified = self.citations.iter().filter(|citation| citation.verified).count();
+            parts.push(format!("📎 {}/{} fuentes", verified, self.citations.len()));
+        }
+        if self.usage.total() > 0 {
+            let approx = if self.usage.estimated { "~" } else { "" };
+            parts.push(format!("{}{} tokens", approx, self.usage.total()));
+        }
+        if self.timing.total_ms > 0 {
+            parts.push(format!("{:.1}s", self.timing.total_ms as f64 / 1000.0));
+        }
+        (!parts.is_empty()).then(|| parts.join(" · "))
+    }
+}
+
+impl From<StructuredResponse> for OrchestratorResponse {
+    fn from(structured: StructuredResponse) -> Self {
+        structured.response
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_structured_response_from_turn() {
+        let mut turn = TurnRecord::default();
+        turn.model_call("qwen3:8b", Duration::from_millis(900), 1_200, 150, true);
+        turn.tool_calls.push(ToolCallRecord::new(
+            "read_file",
+            &json!({"path": "src/main.rs"}),
+            "File: src/main.rs\n\nfn main() {}",
+            Duration::from_millis(12),
+        ));
+        turn.tool_calls.push(ToolCallRecord::new(
+            "read_file",
+            &json!({"path": "src/nope.rs"}),
+            "Error reading file: not found",
+            Duration::from_millis(3),
+        ));
+        let citations = vec![Citation { path: "src/main.rs".to_string(), line: Some(1), verified: true }];
+
+        let structured = StructuredResponse::from_turn(
+            OrchestratorResponse::Text("`main` is in src/main.rs:1".to_string()),
+            turn,
+            citations,
+            Duration::from_millis(1_500),
+        );
+
+        assert_eq!(structured.model.as_deref(), Some("qwen3:8b"));
+        assert_eq!(structured.timing, Timing { total_ms: 1_500, model_ms: 900, tools_ms: 15 });
+        assert!(!structured.tool_calls[1].success);
+        assert_eq!(
+            structured.summary().unwrap(),
+            "🔧 2 herramientas (1 con error) · 📎 1/1 fuentes · ~1350 tokens · 1.5s"
+        );
+
+        let value = serde_json::to_value(&structured).unwrap();
+        assert_eq!(value["response"]["type"], "text");
+        assert_eq!(value["tool_calls"][0]["args"]["path"], "src/main.rs");
+        assert_eq!(value["usage"]["prompt_tokens"], 1_200);
+    }
+
+    #[test]
+    fn test_long_results_are_cut() {
+        let record = ToolCallRecord::new("search_files", &json!({}), &"ñ".repeat(5_000), Duration::ZERO);
+        assert!(record.result.len() <= MAX_RECORDED_RESULT + "\n…".len());
+        assert!(record.success);
+        assert!(StructuredResponse::plain(OrchestratorResponse::Text("hola".to_string())).summary().is_none());
+    }
+}
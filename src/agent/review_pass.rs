@@ -0,0 +1,213 @@
+//! Second-model review of Build changes
+//!
+//! After the heavy model has written files in Build mode, a second model (the
+//! fast model unless `review.model` names another) reads the diff next to the
+//! original request and the convention checks of the changed files. Blocking
+//! findings send the heavy model back for one revision; whatever remains is
+//! shown with the final diff.
+
+use super::diff_preview::DiffPreview;
+use serde::Deserialize;
+use std::path::PathBuf;
+
+/// Diff characters sent to the reviewer
+const MAX_REVIEW_DIFF_CHARS: usize = 12_000;
+/// Diff characters shown to the user
+const MAX_SHOWN_DIFF_CHARS: usize = 6_000;
+
+/// A file written during a Build turn
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileChange {
+    pub path: PathBuf,
+    /// Content before the turn, `None` for a new file
+    pub before: Option<String>,
+    pub after: String,
+}
+
+impl FileChange {
+    pub fn diff(&self) -> String {
+        DiffPreview::new(
+            self.path.clone(),
+            self.before.clone().unwrap_or_default(),
+            self.after.clone(),
+        )
+        .generate_unified_diff()
+    }
+}
+
+/// Unified diff of every change, cut at `max_chars`
+pub fn render_diff(changes: &[FileChange], max_chars: usize) -> String {
+    let diff: String = changes.iter().map(|c| c.diff()).collect();
+    if diff.len() <= max_chars {
+        return diff;
+    }
+    let cut = (0..=max_chars).rev().find(|&i| diff.is_char_boundary(i)).unwrap_or(0);
+    format!("{}\n... (diff truncated)\n", &diff[..cut])
+}
+
+/// Add a revision's changes, keeping the content from before the first write
+pub fn merge_changes(changes: &mut Vec<FileChange>, revised: Vec<FileChange>) {
+    for change in revised {
+        match changes.iter_mut().find(|c| c.path == change.path) {
+            Some(existing) => existing.after = change.after,
+            None => changes.push(change),
+        }
+    }
+    changes.retain(|c| c.before.as_deref() != Some(c.after.as_str()));
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    /// Must be fixed before the change is shown
+    Blocking,
+    #[serde(other)]
+    Suggestion,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct ReviewFinding {
+    pub severity: Severity,
+    #[serde(default)]
+    pub file: Option<String>,
+    pub message: String,
+}
+
+/// The reviewer's verdict
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+pub struct Review {
+    #[serde(default)]
+    pub findings: Vec<ReviewFinding>,
+}
+
+impl Review {
+    /// Parse the reviewer's JSON; an unreadable answer counts as no findings
+    pub fn parse(response: &str) -> Self {
+        let (Some(start), Some(end)) = (response.find('{'), response.rfind('}')) else {
+            return Self::default();
+        };
+        if end < start {
+            return Self::default();
+        }
+        serde_json::from_str(&response[start..=end]).unwrap_or_default()
+    }
+
+    pub fn has_blocking(&self) -> bool {
+        self.findings.iter().any(|f| f.severity == Severity::Blocking)
+    }
+
+    pub fn render(&self) -> String {
+        self.findings
+            .iter()
+            .map(|f| {
+                let icon = match f.severity {
+                    Severity::Blocking => "⛔",
+                    Severity::Suggestion => "💡",
+                };
+                match &f.file {
+                    Some(file) => format!("{} {}: {}\n", icon, file, f.message),
+                    None => format!("{} {}\n", icon, f.message),
+                }
+            })
+            .collect()
+    }
+}
+
+/// Prompt for the reviewer model
+pub fn review_prompt(request: &str, changes: &[FileChange], convention_notes: &[String]) -> String {
+    let mut prompt = format!(
+        "You review a change another model made for this request:\n{}\n\n\
+         Diff:\n```diff\n{}```\n",
+        request,
+        render_diff(changes, MAX_REVIEW_DIFF_CHARS)
+    );
+    if !convention_notes.is_empty() {
+        prompt.push_str(&format!(
+            "\nProject convention checks:\n{}\n",
+            convention_notes.join("\n")
+        ));
+    }
+    prompt.push_str(
+        "\nCheck that the diff does what was asked, does not break existing code and follows \
+         the project conventions. Only correctness bugs, missing parts of the request and \
+         convention violations are blocking; style preferences are suggestions.\n\
+         Answer with JSON only: {\"findings\": [{\"severity\": \"blocking\" | \"suggestion\", \
+         \"file\": \"path\", \"message\": \"...\"}]} and an empty list if the change is fine.",
+    );
+    prompt
+}
+
+/// Prompt sending the heavy model back to fix the blocking findings
+pub fn revision_prompt(request: &str, review: &Review) -> String {
+    let blocking: String = review
+        .findings
+        .iter()
+        .filter(|f| f.severity == Severity::Blocking)
+        .map(|f| format!("- {}{}\n", f.file.as_ref().map(|p| format!("{}: ", p)).unwrap_or_default(), f.message))
+        .collect();
+    format!(
+        "A reviewer found blocking problems in the changes you just made for this request:\n{}\n\n\
+         Problems:\n{}\nFix them by editing the files again with write_file. Do not undo the \
+         parts of the change that are correct.",
+        request, blocking
+    )
+}
+
+/// Review notes and final diff appended to the Build answer
+pub fn render_outcome(changes: &[FileChange], first: &Review, last: Option<&Review>) -> String {
+    let mut output = String::from("\n\n---\n🔎 Revisión");
+    match last {
+        Some(last) => {
+            output.push_str(": se corrigió una ronda de problemas bloqueantes\n");
+            output.push_str(&first.render());
+            if !last.findings.is_empty() {
+                output.push_str("\nTras la corrección:\n");
+                output.push_str(&last.render());
+            }
+        }
+        None if first.findings.is_empty() => output.push_str(": sin observaciones\n"),
+        None => {
+            output.push_str(":\n");
+            output.push_str(&first.render());
+        }
+    }
+    output.push_str(&format!(
+        "\n```diff\n{}```",
+        render_diff(changes, MAX_SHOWN_DIFF_CHARS)
+    ));
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_review_tolerates_prose_and_unknown_severity() {
+        let review = Review::parse(
+            "Here is my review:\n{\"findings\": [{\"severity\": \"blocking\", \"file\": \"src/a.rs\", \
+             \"message\": \"unwrap on user input\"}, {\"severity\": \"nit\", \"message\": \"rename x\"}]}",
+        );
+        assert!(review.has_blocking());
+        assert_eq!(review.findings[1].severity, Severity::Suggestion);
+        assert!(review.render().starts_with("⛔ src/a.rs: unwrap on user input\n"));
+
+        assert_eq!(Review::parse("looks good"), Review::default());
+        assert!(!Review::parse("{\"findings\": []}").has_blocking());
+    }
+
+    #[test]
+    fn test_prompts_carry_request_diff_and_findings() {
+        let changes = vec![FileChange {
+            path: PathBuf::from("src/lib.rs"),
+            before: Some("fn a() {}\n".into()),
+            after: "fn a() { todo!() }\n".into(),
+        }];
+        let prompt = review_prompt("implement a", &changes, &["src/lib.rs: naming".into()]);
+        assert!(prompt.contains("implement a") && prompt.contains("+fn a() { todo!() }"));
+        assert!(prompt.contains("Project convention checks"));
+
+        let review = Review::parse("{\"findings\": [{\"severity\": \"blocking\", \"message\": \"still a todo\"}]}");
+        assert!(revision_prompt("implement a", &review).contains("- still a todo\n"));
+    }
+}
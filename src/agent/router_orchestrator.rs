@@ -5,30 +5,56 @@
 
 #![allow(deprecated)]
 
+use super::artifacts::{self, ArtifactKind, ArtifactsConfig};
+use super::capabilities::{Capability, CapabilityRegistry};
 use super::classification_cache::ClassificationCache;
-use super::orchestrator::{DualModelOrchestrator, OrchestratorResponse};
+use super::event_bus::EventBus;
+use super::events::AgentEvent;
+use super::orchestrator::{DualModelOrchestrator, OrchestratorError, OrchestratorResponse};
 use super::progress::{ProgressUpdate, ProgressStage};
+use super::confidence::AnswerConfidence;
+use super::grounding::{self, GroundingConfig};
+use super::review_pass;
+use super::estimation;
+use super::html_export;
+use super::translation;
+use super::session_search::{self, SessionQuery};
+use super::recipes::{RecipeRun, StepAction};
+use super::response::{Citation, StructuredResponse, TurnRecord};
 use super::task_progress::{TaskProgressInfo, TaskProgressStatus};
-use super::slash_commands::{SlashCommandRegistry, CommandContext};
-use super::state::SharedState;
+use super::turn_budget::TurnBudget;
+use super::slash_commands::{self, SlashCommandRegistry, CommandContext, CommandResult};
+use super::pre_router::{self, FastPath};
+use super::state::{AnswerSources, Message, RouteTrace, SharedState};
+use super::webhooks::{self, TaskEvent, WebhookEventKind};
+use super::workspace;
 use crate::agent::provider::OllamaProvider;
+use crate::context::manager::estimate_tokens;
 use crate::context::related_files::RelatedFilesDetector;
-use crate::i18n::Locale;
-use crate::raptor::builder::{has_full_index, has_quick_index, quick_index_sync, RaptorBuildProgress};
+use crate::db::{SessionStore, SessionSummary};
+use crate::error::NeuroError;
+use crate::i18n::{t, Locale, Text};
+use crate::raptor::builder::{has_full_index, has_quick_index, is_rebuilding, quick_index_sync, RaptorBuildProgress};
+use crate::raptor::federation::{self, FederatedRoot, Federation};
+use crate::raptor::feedback;
+use crate::raptor::intent;
 use crate::raptor::integration::RaptorContextService;
 use crate::raptor::persistence::GLOBAL_STORE;
+use crate::tools::{extract_examples, CodeExample, ExampleRunner, RuntimeMatrix, ToolListing};
 use crate::{log_debug, log_info, log_warn, log_error};
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
-use std::path::Path;
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use rig::tool::Tool;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use tokio::sync::Mutex as AsyncMutex;
 use tokio::sync::mpsc::Sender;
 use tokio::sync::oneshot;
 use tokio::time::timeout;
+use tokio_util::sync::CancellationToken;
 
 /// Mensaje de estado del router para la UI
 #[derive(Debug, Clone)]
@@ -75,6 +101,43 @@ pub enum RouterDecision {
     },
 }
 
+impl RouterDecision {
+    /// The same decision for another query
+    fn with_query(self, query: String) -> Self {
+        match self {
+            Self::DirectResponse { confidence, .. } => Self::DirectResponse { query, confidence },
+            Self::ToolExecution { mode, needs_raptor, confidence, .. } => {
+                Self::ToolExecution { query, mode, needs_raptor, confidence }
+            }
+            Self::FullPipeline { confidence, .. } => Self::FullPipeline { query, confidence },
+            Self::RepositoryAnalysis { .. } => Self::RepositoryAnalysis { query },
+        }
+    }
+
+    /// Route and mode, as /why shows them
+    fn describe(&self) -> String {
+        match self {
+            Self::DirectResponse { .. } => "DirectResponse (sin herramientas)".to_string(),
+            Self::ToolExecution { mode, needs_raptor, .. } => format!(
+                "ToolExecution ({:?}, {})",
+                mode,
+                if *needs_raptor { "con RAPTOR" } else { "sin RAPTOR" }
+            ),
+            Self::FullPipeline { .. } => "FullPipeline (planificación en varios pasos)".to_string(),
+            Self::RepositoryAnalysis { .. } => "RepositoryAnalysis (análisis del repositorio)".to_string(),
+        }
+    }
+
+    fn confidence(&self) -> Option<f64> {
+        match self {
+            Self::DirectResponse { confidence, .. }
+            | Self::ToolExecution { confidence, .. }
+            | Self::FullPipeline { confidence, .. } => Some(*confidence),
+            Self::RepositoryAnalysis { .. } => None,
+        }
+    }
+}
+
 /// Classification response from fast model
 #[derive(Debug, Clone, Serialize, Deserialize)]
 struct ClassificationResponse {
@@ -95,10 +158,28 @@ pub struct RouterConfig {
     pub classification_timeout_secs: u64,
         /// Execution timeout for delegated tasks (seconds)
         pub execution_timeout_secs: u64,
+    /// Time budget of a chat turn (seconds); see [`TurnBudget`]
+    pub turn_budget_secs: u64,
     pub min_confidence: f64,
     pub working_dir: String,
     pub locale: Locale,
     pub debug: bool,
+    /// Model capabilities (built-in table plus config overrides)
+    pub capabilities: CapabilityRegistry,
+    /// Compile and run self-contained examples in answers and label them
+    pub verify_examples: bool,
+    /// Second-model review of Build changes
+    pub review: crate::config::ReviewConfig,
+    /// Citation check of answers built on retrieved context
+    pub grounding: GroundingConfig,
+    /// Retention of the artifacts stored for long outputs
+    pub artifacts: ArtifactsConfig,
+    /// Directories /cd may move into besides `working_dir`
+    pub roots: Vec<PathBuf>,
+    /// Secondary roots the tools may read but not write
+    pub read_only_roots: Vec<PathBuf>,
+    /// Indexed sibling roots queried together with `working_dir`
+    pub federation: Vec<FederatedRoot>,
 }
 
 impl Default for RouterConfig {
@@ -118,6 +199,39 @@ impl Default for RouterConfig {
             locale: Locale::Spanish,
             debug: false,
             execution_timeout_secs: 120,
+            turn_budget_secs: 120,
+            capabilities: CapabilityRegistry::new(),
+            verify_examples: false,
+            review: crate::config::ReviewConfig::default(),
+            grounding: GroundingConfig::default(),
+            artifacts: ArtifactsConfig::default(),
+            roots: Vec::new(),
+            read_only_roots: Vec::new(),
+            federation: Vec::new(),
+        }
+    }
+}
+
+impl RouterConfig {
+    /// Router settings of `config` for a session started in `working_dir`
+    pub fn from_app_config(config: &crate::config::AppConfig, working_dir: &Path) -> Self {
+        Self {
+            fast_model_config: config.fast_model.clone(),
+            heavy_model_config: config.heavy_model.clone(),
+            execution_timeout_secs: config.heavy_timeout_secs,
+            turn_budget_secs: config.turn_budget_secs,
+            working_dir: working_dir.to_string_lossy().to_string(),
+            locale: Locale::from_config(config.language.as_deref()),
+            debug: config.debug,
+            capabilities: CapabilityRegistry::with_overrides(config.model_capabilities.clone()),
+            verify_examples: config.verify_examples,
+            review: config.review.clone(),
+            grounding: config.grounding.clone(),
+            artifacts: config.artifacts.clone(),
+            roots: config.roots.iter().map(PathBuf::from).collect(),
+            read_only_roots: config.read_only_roots.iter().map(PathBuf::from).collect(),
+            federation: config.federation.clone(),
+            ..Self::default()
         }
     }
 }
@@ -134,15 +248,18 @@ pub struct RouterOrchestrator {
     related_files_detector: Arc<RelatedFilesDetector>,
     git_context: Arc<AsyncMutex<crate::context::GitContext>>,
     incremental_updater: Arc<crate::raptor::incremental::IncrementalUpdater>,
-    event_tx: Arc<AsyncMutex<Option<Sender<crate::agent::AgentEvent>>>>, // Thread-safe channel for unified events
+    /// Status, progress, streamed answers and RAPTOR events for the UI and other subscribers
+    events: EventBus,
+    session_store: Arc<AsyncMutex<Option<SessionStore>>>,
 }
 
 impl RouterOrchestrator {
     /// Create new router orchestrator with configuration
     pub async fn new(
         config: RouterConfig,
-        orchestrator: DualModelOrchestrator,
+        mut orchestrator: DualModelOrchestrator,
     ) -> Result<Self> {
+        orchestrator.set_capabilities(config.capabilities.clone());
         let state = orchestrator.state();
         let orchestrator_arc = Arc::new(AsyncMutex::new(orchestrator));
         
@@ -152,6 +269,16 @@ impl RouterOrchestrator {
         // Canonicalize the working directory to avoid path mismatches (relative vs absolute)
         project_root = std::fs::canonicalize(&project_root).unwrap_or(project_root.clone());
 
+        // The session starts here and may /cd inside its roots
+        {
+            let mut state = state.lock().await;
+            state.working_dir = config.working_dir.clone();
+            state.roots = std::iter::once(project_root.clone())
+                .chain(workspace::resolve_roots(&project_root, &config.roots))
+                .collect();
+            state.read_only_roots = workspace::resolve_roots(&project_root, &config.read_only_roots);
+        }
+
         let related_files_detector = Arc::new(RelatedFilesDetector::new(project_root.clone()));
         
         // Initialize git context
@@ -163,57 +290,226 @@ impl RouterOrchestrator {
             orchestrator_arc.clone(),
         ));
         
+        // Custom commands shared in `.neuro/commands/` and personal ones
+        let mut slash_commands = SlashCommandRegistry::new();
+        for note in slash_commands.register_custom(&project_root) {
+            log_warn!("⚠️ {}", note);
+        }
+
+        // Indexes of the sibling roots queried together with this one
+        let federation = if config.federation.is_empty() {
+            Federation::new()
+        } else {
+            let (root, roots) = (project_root.clone(), config.federation.clone());
+            tokio::task::spawn_blocking(move || Federation::load(&root, &roots))
+                .await
+                .unwrap_or_default()
+        };
+        for (label, path) in federation.unindexed() {
+            log_warn!("⚠️ Federated root [{}] has no index yet: run /reindex in {}", label, path.display());
+        }
+
         Ok(Self {
             config,
             orchestrator: orchestrator_arc.clone(),
             raptor_service: Some(Arc::new(AsyncMutex::new(
-                RaptorContextService::new(orchestrator_arc),
+                RaptorContextService::new(orchestrator_arc).with_federation(federation),
             ))),
             full_index_ready: Arc::new(AtomicBool::new(false)),
             state,
-            slash_commands: SlashCommandRegistry::new(),
+            slash_commands,
             classification_cache: Arc::new(AsyncMutex::new(ClassificationCache::new())),
             related_files_detector,
             git_context,
             incremental_updater,
-            event_tx: Arc::new(AsyncMutex::new(None)), // Initialize thread-safe channel
+            events: EventBus::new(),
+            session_store: Arc::new(AsyncMutex::new(None)),
         })
     }
 
-    /// Set unified event channel for sending updates to UI (async version)
-    pub async fn set_event_channel_async(&self, tx: Sender<crate::agent::AgentEvent>) {
-        let mut event_tx = self.event_tx.lock().await;
-        *event_tx = Some(tx);
+    /// Attach the session database, restore its `/env` variables and the
+    /// project's disabled tools, and enable auditing
+    pub async fn attach_session(&self, store: SessionStore) -> Result<()> {
+        let project = crate::db::Database::project_key(&self.config.working_dir);
+        let vars = store.db.get_session_env(&store.session_id).await?;
+        let disabled_tools = store.db.get_disabled_tools(&project).await?;
+        let feedback = store.db.list_retrieval_feedback(&project).await?;
+        feedback::set_pairs(feedback.into_iter().map(Into::into).collect());
+        {
+            let mut state = self.state.lock().await;
+            state.env_vars.extend(vars);
+            state.disabled_tools.extend(disabled_tools);
+        }
+        self.orchestrator.lock().await.set_session_store(store.clone());
+        *self.session_store.lock().await = Some(store);
+        Ok(())
+    }
+
+    /// The tools offered to the model and the ones turned off for this project
+    pub async fn tool_states(&self) -> (Vec<ToolListing>, BTreeSet<String>) {
+        let listing = self.orchestrator.lock().await.tools().tool_listing().await;
+        (listing, self.state.lock().await.disabled_tools.clone())
     }
 
-    /// Set unified event channel for sending updates to UI (sync version with try_lock)
-    pub fn set_event_channel(&self, tx: Sender<crate::agent::AgentEvent>) {
-        if let Ok(mut event_tx) = self.event_tx.try_lock() {
-            *event_tx = Some(tx);
-        }
+    /// Turn a tool on or off for this project, as `/tools enable|disable` does
+    pub async fn set_tool_enabled(&self, tool: &str, enabled: bool) -> Result<()> {
+        let session = self.session_store.lock().await.clone();
+        slash_commands::set_tool_enabled(&self.state, session.as_ref(), &self.config.working_dir, tool, enabled).await
     }
 
-    /// Send status update to UI if channel is available
-    fn send_status(&self, message: String) {
-        if let Ok(event_tx) = self.event_tx.try_lock() {
-            if let Some(tx) = &*event_tx {
-                let _ = tx.try_send(crate::agent::AgentEvent::Status(message));
+    /// Router for another checkout of the project (an experiment worktree),
+    /// with its own state and, if this router has a session, its own session
+    pub async fn fork(&self, working_dir: &Path, title: &str) -> Result<Self> {
+        let (orchestrator_config, tool_config) = {
+            let parent = self.orchestrator.lock().await;
+            (parent.config().clone(), parent.tool_config().cloned())
+        };
+        let mut orchestrator = DualModelOrchestrator::with_config(orchestrator_config).await?;
+        // Protected paths and plugins resolve against the worktree
+        if let Some(tool_config) = &tool_config {
+            for error in orchestrator.apply_tool_config(tool_config, working_dir) {
+                log_warn!("⚠️ Skipping WASM plugin: {}", error);
+            }
+        }
+        let config = RouterConfig {
+            working_dir: working_dir.to_string_lossy().to_string(),
+            ..self.config.clone()
+        };
+        let router = Self::new(config, orchestrator).await?;
+        let (env_vars, disabled_tools) = {
+            let state = self.state.lock().await;
+            (state.env_vars.clone(), state.disabled_tools.clone())
+        };
+        {
+            let mut state = router.state.lock().await;
+            state.working_dir = router.config.working_dir.clone();
+            state.env_vars = env_vars;
+            state.disabled_tools = disabled_tools;
+        }
+
+        let parent = self.session_store.lock().await.clone();
+        if let Some(parent) = parent {
+            let session = crate::db::Session::new(
+                uuid::Uuid::new_v4().to_string(),
+                &self.config.fast_model_config.model,
+                &self.config.heavy_model_config.model,
+            )
+            .with_title(title)
+            .with_working_dir(&router.config.working_dir);
+            parent.db.create_session(&session).await?;
+            // The experiment's session keeps the /env variables it inherited
+            for (key, value) in &router.state.lock().await.env_vars {
+                parent.db.set_session_env(&session.id, key, value).await?;
             }
+            router
+                .attach_session(SessionStore { db: parent.db, session_id: session.id })
+                .await?;
+        }
+        Ok(router)
+    }
+
+    /// Follow a /cd of the session: point the git context, related files and
+    /// incremental index at the new working directory
+    pub async fn sync_working_dir(&mut self) {
+        let working_dir = self.state.lock().await.working_dir.clone();
+        if working_dir == self.config.working_dir {
+            return;
+        }
+        log_info!("📂 Working directory: {} → {}", self.config.working_dir, working_dir);
+        let root = PathBuf::from(&working_dir);
+        self.config.working_dir = working_dir;
+        self.related_files_detector = Arc::new(RelatedFilesDetector::new(root.clone()));
+        *self.git_context.lock().await = crate::context::GitContext::new(root.clone());
+        self.incremental_updater = Arc::new(crate::raptor::incremental::IncrementalUpdater::new(
+            root,
+            self.orchestrator.clone(),
+        ));
+    }
+
+    /// Apply the settings of a reloaded config that take effect without
+    /// reconnecting, then report the changes as a Status event
+    pub async fn apply_config(&mut self, reload: &crate::config::ConfigReload) {
+        let config = &reload.config;
+        for (current, new) in [
+            (&mut self.config.fast_model_config, &config.fast_model),
+            (&mut self.config.heavy_model_config, &config.heavy_model),
+        ] {
+            current.temperature = new.temperature;
+            current.top_p = new.top_p;
+            current.max_tokens = new.max_tokens;
+        }
+        self.config.execution_timeout_secs = config.heavy_timeout_secs;
+        self.config.turn_budget_secs = config.turn_budget_secs;
+        crate::agent::stream_watchdog::set_stall_secs(config.stream_stall_secs);
+        self.config.locale = Locale::from_config(config.language.as_deref());
+        crate::i18n::set_locale(self.config.locale);
+        crate::security::privacy::set_privacy_level(config.privacy);
+        crate::security::network::set_network_policy(config.network.clone());
+        crate::agent::webhooks::set_webhooks(config.webhooks.clone());
+        crate::agent::reasoning::set_config(config.reasoning.clone());
+        crate::raptor::intent::set_config(config.intents.clone());
+        // Only an edit of the setting overrides a /dryrun of this session
+        if reload.changes.live.iter().any(|change| change.starts_with("dry_run:")) {
+            crate::agent::dry_run::set_enabled(config.dry_run);
+        }
+        crate::crash::set_config(config);
+        self.config.debug = config.debug;
+        self.config.verify_examples = config.verify_examples;
+        self.config.review = config.review.clone();
+        self.config.grounding = config.grounding.clone();
+        self.config.capabilities = CapabilityRegistry::with_overrides(config.model_capabilities.clone());
+
+        {
+            let mut orchestrator = self.orchestrator.lock().await;
+            orchestrator.set_heavy_timeout_secs(config.heavy_timeout_secs);
+            orchestrator.set_capabilities(self.config.capabilities.clone());
+            orchestrator.reload_tool_config(config, Path::new(&self.config.working_dir));
         }
+        self.send_status(reload.changes.summary());
     }
 
-    /// Send detailed progress update to UI with stage and timing
+    /// Switch to the provider, models and URL of `config`
+    pub async fn reconnect(&mut self, config: &crate::config::AppConfig) -> Result<()> {
+        let orchestrator_config = super::orchestrator::OrchestratorConfig::from_app_config(config);
+        self.orchestrator.lock().await.reconnect(orchestrator_config).await?;
+        self.config.fast_model_config = config.fast_model.clone();
+        self.config.heavy_model_config = config.heavy_model.clone();
+        // Decisions of the previous classifier model
+        self.classification_cache.lock().await.clear();
+        self.send_status(format!(
+            "Reconnected: {} / {}",
+            config.fast_model.model, config.heavy_model.model
+        ));
+        Ok(())
+    }
+
+    /// Bus the router publishes its events on; subscribe to receive them
+    pub fn event_bus(&self) -> EventBus {
+        self.events.clone()
+    }
+
+    /// Send status update to the subscribers
+    fn send_status(&self, message: String) {
+        self.events.publish(AgentEvent::Status(message));
+    }
+
+    /// Send detailed progress update to the subscribers with stage and timing
     fn send_progress(&self, stage: ProgressStage, message: String, elapsed_ms: u64) {
-        if let Ok(event_tx) = self.event_tx.try_lock() {
-            if let Some(ref tx) = &*event_tx {
-                let update = ProgressUpdate {
-                    stage,
-                    message,
-                    elapsed_ms,
-                };
-                let _ = tx.try_send(crate::agent::AgentEvent::Progress(update));
+        self.events.publish(AgentEvent::Progress(ProgressUpdate { stage, message, elapsed_ms }));
+    }
+
+    /// Publish `message` every 5 seconds until `stop` fires, so the UI sees a long call is alive
+    fn spawn_heartbeat(&self, message: &'static str, mut stop: oneshot::Receiver<()>) {
+        let events = self.events.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(Duration::from_secs(5));
+            loop {
+                tokio::select! {
+                    _ = interval.tick() => events.publish(AgentEvent::Status(message.to_string())),
+                    _ = &mut stop => break,
+                }
             }
-        }
+        });
     }
 
     /// Initialize RAPTOR index (quick sync + full async)
@@ -271,42 +567,74 @@ impl RouterOrchestrator {
         Ok(())
     }
 
-    /// Rebuild RAPTOR index (for !reindex command)
+    /// Rebuild RAPTOR index (for !reindex command). The current index keeps
+    /// answering queries until the new one replaces it, and stays if the build fails.
+    /// A second call while one runs is refused.
     pub async fn rebuild_raptor(&self) -> Result<String> {
         log_debug!("🔧 [REINDEX] rebuild_raptor() called");
 
-        if let Some(raptor_service) = &self.raptor_service {
-            // Clear existing index
-            log_debug!("🔧 [REINDEX] Clearing existing index");
-            match raptor_service.lock().await.clear().await {
-                Ok(_) => log_debug!("🔧 [REINDEX] Index cleared successfully"),
-                Err(e) => log_warn!("🔧 [REINDEX] Failed to clear index: {}", e),
-            }
+        if self.raptor_service.is_none() {
+            log_warn!("🔧 [REINDEX] RAPTOR service not available");
+            return Ok("⚠️ Servicio RAPTOR no disponible".to_string());
+        }
+        if is_rebuilding() {
+            log_debug!("🔧 [REINDEX] Rebuild already running, request ignored");
+            return Ok("⏳ Ya hay una reindexación en curso; el índice se actualizará al terminar".to_string());
+        }
 
-            // Reset full_index_ready flag
-            self.full_index_ready.store(false, Ordering::SeqCst);
+        log_debug!("🔧 [REINDEX] Starting staged rebuild");
+        match RaptorContextService::rebuild_tree_with_progress(&self.config.working_dir, Some(2000), Some(0.6), None).await {
+            Ok(chunks) => {
+                self.full_index_ready.store(true, Ordering::SeqCst);
+                log_info!("✓ [REINDEX] RAPTOR index rebuilt successfully");
+                Ok(format!("✓ Índice RAPTOR reconstruido exitosamente ({} chunks)", chunks))
+            }
+            Err(e) => {
+                log_error!("❌ [REINDEX] Failed to rebuild RAPTOR index: {}", e);
+                Ok(format!("❌ Error al reconstruir índice RAPTOR: {}\nSe mantiene el índice anterior", e))
+            }
+        }
+    }
 
-            // Rebuild index
-            log_debug!("🔧 [REINDEX] Starting full rebuild");
-            let working_dir = &self.config.working_dir;
+    /// Load the heavy model in the background so the next heavy request does
+    /// not wait for it
+    async fn spawn_heavy_warmup(&self) {
+        let (ollama_url, model) = {
+            let orchestrator = self.orchestrator.lock().await;
+            (orchestrator.config().ollama_url.clone(), orchestrator.config().heavy_model.clone())
+        };
+        tokio::spawn(async move {
+            if let Err(e) = DualModelOrchestrator::warm_heavy_model(&ollama_url, &model).await {
+                log_warn!("⚠️ Could not warm up {}: {}", model, e);
+            }
+        });
+    }
 
-            // Perform full rebuild
-            let mut service_guard = raptor_service.lock().await;
-            match service_guard.build_tree_with_progress(working_dir, Some(2000), Some(0.6), None).await {
+    /// Rebuild the full RAPTOR index of the working directory without waiting for it
+    fn spawn_raptor_rebuild(&self) {
+        let Some(service) = self.raptor_service.clone() else {
+            return;
+        };
+        let working_dir = self.config.working_dir.clone();
+        let full_index_ready = self.full_index_ready.clone();
+        tokio::spawn(async move {
+            let mut service_guard = service.lock().await;
+            match service_guard.build_tree_with_progress(&working_dir, Some(2000), Some(0.6), None).await {
                 Ok(_) => {
-                    self.full_index_ready.store(true, Ordering::SeqCst);
-                    log_info!("✓ [REINDEX] RAPTOR index rebuilt successfully");
-                    Ok("✓ Índice RAPTOR reconstruido exitosamente".to_string())
-                }
-                Err(e) => {
-                    log_error!("❌ [REINDEX] Failed to rebuild RAPTOR index: {}", e);
-                    Ok(format!("❌ Error al reconstruir índice RAPTOR: {}", e))
+                    full_index_ready.store(true, Ordering::SeqCst);
+                    log_info!("✓ RAPTOR: Índice completo listo");
                 }
+                Err(e) => log_error!("⚠ [RAPTOR] Error en índice completo: {}", e),
             }
-        } else {
-            log_warn!("🔧 [REINDEX] RAPTOR service not available");
-            Ok("⚠️ Servicio RAPTOR no disponible".to_string())
-        }
+        });
+    }
+
+    /// RAPTOR context for `query`, without asking the model
+    pub async fn retrieval_context(&self, query: &str) -> Result<String> {
+        let Some(service) = &self.raptor_service else {
+            return Ok(String::new());
+        };
+        service.lock().await.get_planning_context(query).await
     }
 
     /// Check if RAPTOR full index is ready
@@ -324,9 +652,10 @@ impl RouterOrchestrator {
         
         if result.updated {
             Ok(format!(
-                "✓ Actualización incremental: {} archivos modificados, {} eliminados ({}ms)",
+                "✓ Actualización incremental: {} archivos modificados, {} eliminados, {} símbolos cambiados ({}ms)",
                 result.files_modified,
                 result.files_deleted,
+                result.changed_symbols.len(),
                 result.duration_ms
             ))
         } else {
@@ -346,10 +675,12 @@ impl RouterOrchestrator {
         )
     }
 
-    /// Initialize RAPTOR with progress reporting (synchronous, waits for completion)
+    /// Initialize RAPTOR with progress reporting (synchronous, waits for completion);
+    /// cancelling `cancel` stops the full build, which fails with "RAPTOR build cancelled"
     pub async fn initialize_raptor_with_progress(
         &self, 
-        progress_tx: Option<Sender<TaskProgressInfo>>
+        progress_tx: Option<Sender<TaskProgressInfo>>,
+        cancel: CancellationToken,
     ) -> Result<bool> {
         let working_dir = Path::new(&self.config.working_dir);
         
@@ -425,13 +756,21 @@ impl RouterOrchestrator {
             }
             
             let mut service_guard = raptor_service.lock().await;
-            match service_guard.build_tree_with_progress(
+            // Dropping the build stops it between embedding batches; the cache
+            // is only saved once the tree is complete
+            let build = service_guard.build_tree_with_progress(
                 &self.config.working_dir, 
                 Some(2000), 
                 Some(0.6), 
 
                 Some(raptor_tx)
-            ).await {
+            );
+            let result = tokio::select! {
+                biased;
+                _ = cancel.cancelled() => Err(anyhow::anyhow!("RAPTOR build cancelled")),
+                result = build => result,
+            };
+            match result {
                 Ok(_) => {
                     self.full_index_ready.store(true, Ordering::SeqCst);
                     
@@ -465,17 +804,13 @@ impl RouterOrchestrator {
 
     /// Classify user query using fast model with caching
     pub async fn classify(&self, user_query: &str) -> Result<RouterDecision> {
+        Ok(self.classify_explained(user_query).await?.0)
+    }
+
+    /// Like [`Self::classify`], with the reason for the decision (/why)
+    async fn classify_explained(&self, user_query: &str) -> Result<(RouterDecision, String)> {
         // Send progress update (non-blocking)
-        if let Ok(event_tx) = self.event_tx.try_lock() {
-            if let Some(tx) = &*event_tx {
-                let update = ProgressUpdate {
-                    stage: ProgressStage::Classifying,
-                    message: "🔍 Clasificando consulta...".to_string(),
-                    elapsed_ms: 0,
-                };
-                let _ = tx.try_send(crate::agent::AgentEvent::Progress(update));
-            }
-        }
+        self.send_progress(ProgressStage::Classifying, "🔍 Clasificando consulta...".to_string(), 0);
 
         self.send_status("Clasificando consulta...".to_string());
 
@@ -486,7 +821,7 @@ impl RouterOrchestrator {
                 if self.config.debug {
                     log_info!("✓ [CACHE HIT] Usando clasificación cacheada");
                 }
-                return Ok(cached_decision);
+                return Ok((cached_decision, "misma consulta que antes: clasificación en caché".to_string()));
             }
         }
 
@@ -524,7 +859,7 @@ impl RouterOrchestrator {
                 cache.insert(user_query, decision.clone());
             }
 
-            return Ok(decision);
+            return Ok((decision, "regla: la consulta pide explicar el proyecto".to_string()));
         }
         
         let classification_prompt = build_router_classification_prompt(user_query, &self.config.locale);
@@ -549,7 +884,20 @@ impl RouterOrchestrator {
         
         let timeout_duration = Duration::from_secs(self.config.classification_timeout_secs);
         
-        let response = timeout(timeout_duration, provider.generate_with_tools(messages, None))
+        // JSON mode when the model supports it; otherwise the JSON is extracted from the text below
+        let json_mode = self
+            .config
+            .capabilities
+            .supports(&self.config.fast_model_config.model, Capability::JsonMode);
+        let generation = async {
+            if json_mode {
+                provider.generate_json(messages).await
+            } else {
+                provider.generate_with_tools(messages, None).await
+            }
+        };
+
+        let response = timeout(timeout_duration, generation)
             .await
             .context("Classification timeout")?
             .context("Classification generation failed")?;
@@ -646,7 +994,11 @@ impl RouterOrchestrator {
                 cache.insert(user_query, fallback_decision.clone());
             }
             
-            return Ok(fallback_decision);
+            let reason = format!(
+                "confianza {:.2} por debajo del mínimo {:.2}, se usa Ask: {}",
+                classification.confidence, self.config.min_confidence, classification.reasoning
+            );
+            return Ok((fallback_decision, reason));
         }
 
         // Cache the decision before returning
@@ -655,7 +1007,7 @@ impl RouterOrchestrator {
             cache.insert(user_query, decision.clone());
         }
 
-        Ok(decision)
+        Ok((decision, classification.reasoning))
     }
 
     /// Check if input is a slash command and handle it
@@ -714,6 +1066,7 @@ impl RouterOrchestrator {
             tools: Arc::new(orchestrator.tools().clone()),
             state: self.state.clone(),
             working_dir: self.config.working_dir.clone(),
+            session: self.session_store.lock().await.clone(),
         };
         drop(orchestrator); // Release lock
 
@@ -729,6 +1082,199 @@ impl RouterOrchestrator {
 
                 // Handle special commands
                 if let Some(action) = result.metadata.get("action") {
+                    // The command gathered context for the model: send it the prompt
+                    if action.as_str() == "ask" {
+                        if let Some(prompt) = result.metadata.get("prompt") {
+                            self.send_status("Consultando al modelo...".to_string());
+                            let response = {
+                                let mut orchestrator = self.orchestrator.lock().await;
+                                orchestrator.process(prompt).await
+                            };
+                            return match response {
+                                Ok(OrchestratorResponse::Text(text)) => Ok(Some(OrchestratorResponse::Text(
+                                    format!("{}\n\n{}", result.output, text),
+                                ))),
+                                Ok(other) => Ok(Some(other)),
+                                Err(e) => Err(NeuroError::from(e).into()),
+                            };
+                        }
+                    }
+
+                    // The command needs the user's approval: hold it for the confirmation dialog
+                    if action.as_str() == "confirm" {
+                        if let (Some(tool), Some(args), Some(summary)) = (
+                            result.metadata.get("tool"),
+                            result.metadata.get("args"),
+                            result.metadata.get("summary"),
+                        ) {
+                            let args = serde_json::from_str(args).unwrap_or_default();
+                            let risk = result.metadata.get("risk").map_or("write", String::as_str);
+                            self.orchestrator
+                                .lock()
+                                .await
+                                .hold_for_confirmation(tool, &args, summary.clone(), risk);
+                            return Ok(Some(OrchestratorResponse::Text(result.output)));
+                        }
+                    }
+
+                    // Conflicts listed: propose a merged version of every hunk
+                    if action.as_str() == "resolve" {
+                        if let Some(prompt) = result.metadata.get("prompt") {
+                            let context = match (&self.raptor_service, result.metadata.get("query")) {
+                                (Some(service), Some(query)) if has_quick_index() || has_full_index() => {
+                                    self.send_status("Buscando contexto...".to_string());
+                                    let mut service_guard = service.lock().await;
+                                    service_guard
+                                        .get_planning_context(query)
+                                        .await
+                                        .unwrap_or_default()
+                                        .chars()
+                                        .take(4000)
+                                        .collect::<String>()
+                                }
+                                _ => String::new(),
+                            };
+                            let prompt = if context.is_empty() {
+                                prompt.clone()
+                            } else {
+                                format!("{}\n\nContexto del proyecto:\n{}", prompt, context)
+                            };
+
+                            self.send_status("Proponiendo resoluciones...".to_string());
+                            let response = {
+                                let orchestrator = self.orchestrator.lock().await;
+                                orchestrator.call_heavy_model_direct(&prompt).await
+                            };
+                            let mut state = self.state.lock().await;
+                            let proposals = match (response, state.conflicts.as_mut()) {
+                                (Ok(text), Some(set)) => {
+                                    let recorded = set.record_proposals(&text);
+                                    format!(
+                                        "{}\n💡 {} de {} conflictos con propuesta. Revisa con /resolve accept \
+                                         [ids|all] o elige /resolve ours|theirs|both <ids>, luego /resolve apply",
+                                        set.format(),
+                                        recorded,
+                                        set.hunk_count()
+                                    )
+                                }
+                                (Ok(_), None) => result.output.clone(),
+                                (Err(e), _) => format!(
+                                    "{}\n⚠️ El modelo no pudo proponer resoluciones: {}\n\
+                                     Elige con /resolve ours|theirs|both <ids>",
+                                    result.output, e
+                                ),
+                            };
+                            let context_note = if context.is_empty() {
+                                String::new()
+                            } else {
+                                let excerpt: String = context.chars().take(600).collect();
+                                format!("\n\n📚 Contexto relevante:\n{}...", excerpt)
+                            };
+                            return Ok(Some(OrchestratorResponse::Text(format!("{}{}", proposals, context_note))));
+                        }
+                    }
+
+                    // A project was created in the workspace: index its symbols now and
+                    // rebuild RAPTOR in the background so it shows up in retrieval
+                    if action.as_str() == "index" {
+                        if let Some(path) = result.metadata.get("path") {
+                            self.send_status("Indexando el nuevo proyecto...".to_string());
+                            let mut output = result.output.clone();
+                            let symbol_index = self.orchestrator.lock().await.tools().symbol_index.clone();
+                            if symbol_index.has_database() {
+                                match symbol_index.index_project(Path::new(path)).await {
+                                    Ok(report) => output.push_str(&format!(
+                                        "🔎 {} símbolos indexados en {} archivos\n",
+                                        report.symbols, report.files_indexed
+                                    )),
+                                    Err(e) => log_warn!("⚠ [NEW] Symbol index failed for {}: {}", path, e),
+                                }
+                            }
+                            self.spawn_raptor_rebuild();
+                            output.push_str("🔄 Actualizando el índice RAPTOR en segundo plano");
+                            return Ok(Some(OrchestratorResponse::Text(output)));
+                        }
+                    }
+
+                    // Proposed change: affected files, tests and effort from the indexes
+                    if action.as_str() == "estimate" {
+                        if let Some(description) = result.metadata.get("description") {
+                            let retrieved = match &self.raptor_service {
+                                Some(service) if has_quick_index() || has_full_index() => {
+                                    self.send_status("Buscando código relacionado...".to_string());
+                                    let mut service_guard = service.lock().await;
+                                    service_guard
+                                        .relevant_files(description, estimation::MAX_RETRIEVED)
+                                        .await
+                                        .unwrap_or_else(|e| {
+                                            log_warn!("⚠ [ESTIMATE] RAPTOR retrieval failed: {}", e);
+                                            Vec::new()
+                                        })
+                                }
+                                _ => Vec::new(),
+                            };
+                            let (symbol_index, references) = {
+                                let orchestrator = self.orchestrator.lock().await;
+                                let tools = orchestrator.tools();
+                                (tools.symbol_index.clone(), tools.references.clone())
+                            };
+                            self.send_status("Estimando el impacto...".to_string());
+                            let estimate = estimation::estimate(
+                                &symbol_index,
+                                &references,
+                                Path::new(&self.config.working_dir),
+                                description,
+                                &retrieved,
+                            )
+                            .await;
+                            return Ok(Some(OrchestratorResponse::Text(estimate.to_markdown())));
+                        }
+                    }
+
+                    // A turn stopped by its time budget: run what was left and let the model finish
+                    if action.as_str() == "continue" {
+                        self.send_status("Retomando el turno interrumpido...".to_string());
+                        let resumed = self.orchestrator.lock().await.resume().await;
+                        return match resumed {
+                            Ok(Some(response)) => Ok(Some(response)),
+                            Ok(None) => Ok(Some(OrchestratorResponse::Text(
+                                "No hay ningún turno interrumpido que continuar".to_string(),
+                            ))),
+                            Err(e) => Err(NeuroError::from(e).into()),
+                        };
+                    }
+
+                    // A conversation template: run its prompt in the template's mode,
+                    // without asking the classifier
+                    if action.as_str() == "start" {
+                        if let Some(prompt) = result.metadata.get("prompt") {
+                            let mode = match result.metadata.get("mode").map(String::as_str) {
+                                Some("build") => OperationMode::Build,
+                                Some("plan") => OperationMode::Plan,
+                                _ => OperationMode::Ask,
+                            };
+                            let decision = RouterDecision::ToolExecution {
+                                query: prompt.clone(),
+                                mode,
+                                needs_raptor: true,
+                                confidence: 1.0,
+                            };
+                            let response = self.run_decision(decision, Instant::now()).await?;
+                            return Ok(Some(match response {
+                                OrchestratorResponse::Text(text) => {
+                                    OrchestratorResponse::Text(format!("{}\n\n{}", result.output, text))
+                                }
+                                other => other,
+                            }));
+                        }
+                    }
+
+                    // A recipe was started or resumed: run its steps until a pause or the end
+                    if action.as_str() == "recipe" {
+                        let report = self.advance_recipe(&cmd_ctx).await;
+                        return Ok(Some(OrchestratorResponse::Text(report)));
+                    }
+
                     if action.as_str() == "reindex" {
                         if self.config.debug {
                             log_debug!("🔧 [SLASH] Found reindex action in metadata");
@@ -770,11 +1316,18 @@ impl RouterOrchestrator {
                     }
                 }
 
+                // A long output (a test run, a build log) goes to an artifact
+                let output = if input.trim_start().starts_with("/artifacts") {
+                    result.output
+                } else {
+                    self.link_long_output(input.trim(), result.output).await
+                };
+
                 // Return result with debug info if enabled
                 let final_output = if debug_output.is_empty() {
-                    result.output
+                    output
                 } else {
-                    format!("{}\n\n{}", debug_output.trim(), result.output)
+                    format!("{}\n\n{}", debug_output.trim(), output)
                 };
 
                 Ok(Some(OrchestratorResponse::Text(final_output)))
@@ -789,6 +1342,283 @@ impl RouterOrchestrator {
         }
     }
 
+    /// Store an output too long for the chat as an artifact, with the kind
+    /// and expiry of its source, and return its summary with a link to it;
+    /// short outputs, or any without a session database, are returned as they are
+    async fn link_long_output(&self, source: &str, output: String) -> String {
+        if !artifacts::is_long(&output) {
+            return output;
+        }
+        let Some(store) = self.session_store.lock().await.clone() else {
+            return output;
+        };
+        self.send_status("Resumiendo la salida...".to_string());
+        let model_summary = {
+            let orchestrator = self.orchestrator.lock().await;
+            orchestrator
+                .call_fast_model_direct(&artifacts::summary_prompt(source, &output))
+                .await
+                .map_err(|e| log_warn!("⚠ [ARTIFACT] Summary failed, using the last lines: {}", e))
+                .ok()
+        };
+        let summary = artifacts::summarize(&output, model_summary.as_deref());
+        let kind = ArtifactKind::detect(source, &output);
+        let artifact = crate::db::Artifact::new(&store.session_id, kind.as_str(), source, output.as_str(), summary.as_str())
+            .with_expiry(self.config.artifacts.expires_at(kind, chrono::Utc::now()));
+        match store.db.add_artifact(&artifact).await {
+            Ok(id) => artifacts::link(id, &output, &summary),
+            Err(e) => {
+                log_warn!("⚠ [ARTIFACT] Failed to store the output of {}: {}", source, e);
+                output
+            }
+        }
+    }
+
+    /// `text` translated into `language` by the fast model, for the reader
+    /// only: neither the history nor the session database see it
+    pub async fn translate(&self, text: &str, language: &str) -> Result<String> {
+        let (prose, blocks) = translation::protect_code(text);
+        let translated = self
+            .orchestrator
+            .lock()
+            .await
+            .call_model_direct(&self.config.fast_model_config.model, &translation::prompt(&prose, language))
+            .await?;
+        Ok(translation::restore_code(&translated, &blocks))
+    }
+
+    /// Remember the files the tools read or wrote this turn, for the session browser
+    async fn record_session_files(&self, turn: &TurnRecord) {
+        let Some(store) = self.session_store.lock().await.clone() else {
+            return;
+        };
+        for call in turn.tool_calls.iter().filter(|call| call.success) {
+            let written = match call.tool.as_str() {
+                "write_file" => true,
+                "read_file" => false,
+                _ => continue,
+            };
+            let Some(path) = call.args["path"].as_str() else {
+                continue;
+            };
+            if let Err(e) = store.db.record_session_file(&store.session_id, path, written).await {
+                log_warn!("⚠ [SESSION] Failed to record {}: {}", path, e);
+            }
+        }
+    }
+
+    /// Past sessions matching `query`, most recent first, with their summaries
+    pub async fn search_sessions(&self, query: &SessionQuery) -> Result<Vec<SessionSummary>> {
+        let Some(store) = self.session_store.lock().await.clone() else {
+            return Ok(Vec::new());
+        };
+        let sessions = store
+            .db
+            .search_sessions(&query.text, &query.tags, session_search::SEARCH_LIMIT)
+            .await?;
+        let mut summaries = Vec::with_capacity(sessions.len());
+        for session in sessions {
+            summaries.push(store.db.get_session_summary(session).await?);
+        }
+        Ok(summaries)
+    }
+
+    /// A past session as Markdown, for the pager and exports
+    pub async fn session_transcript(&self, session_id: &str) -> Result<Option<String>> {
+        let Some(store) = self.session_store.lock().await.clone() else {
+            return Ok(None);
+        };
+        let session = match store.db.get_session(session_id).await {
+            Ok(session) => session,
+            Err(crate::db::DatabaseError::NotFound(_)) => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+        let messages = store.db.get_session_messages(session_id).await?;
+        let summary = store.db.get_session_summary(session).await?;
+        Ok(Some(session_search::transcript(&summary, &messages)))
+    }
+
+    /// A past session as a standalone HTML page, with citations linked to the
+    /// repository of the directory it ran in
+    pub async fn session_html(&self, session_id: &str) -> Result<Option<String>> {
+        let Some(store) = self.session_store.lock().await.clone() else {
+            return Ok(None);
+        };
+        let session = match store.db.get_session(session_id).await {
+            Ok(session) => session,
+            Err(crate::db::DatabaseError::NotFound(_)) => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+        let dir = PathBuf::from(session.working_dir.clone().unwrap_or_else(|| self.config.working_dir.clone()));
+        let messages = store.db.get_session_messages(session_id).await?;
+        let summary = store.db.get_session_summary(session).await?;
+        let links = html_export::RepoLinks::detect(&dir).await;
+        Ok(Some(html_export::render(&summary, &messages, links.as_ref())))
+    }
+
+    /// Continue a past session: its stored conversation becomes the history
+    /// the models see and new messages are stored in it. Returns its store.
+    pub async fn resume_session(&self, session_id: &str) -> Result<SessionStore> {
+        let current = self
+            .session_store
+            .lock()
+            .await
+            .clone()
+            .ok_or_else(|| anyhow::anyhow!("no session database"))?;
+        current.db.get_session(session_id).await?;
+        let messages = current.db.get_session_messages(session_id).await?;
+        let store = SessionStore {
+            db: current.db,
+            session_id: session_id.to_string(),
+        };
+        self.attach_session(store.clone()).await?;
+
+        let mut state = self.state.lock().await;
+        state.messages.clear();
+        state.last_route = None;
+        state.last_sources = None;
+        state.last_confidence = None;
+        for message in messages {
+            match message.role.as_str() {
+                "user" => state.add_message(Message::user(message.content)),
+                "assistant" => state.add_message(Message::assistant(message.content, message.model.unwrap_or_default())),
+                _ => {}
+            }
+        }
+        Ok(store)
+    }
+
+    /// The artifact `id` of the session database, for the pager
+    pub async fn artifact(&self, id: i64) -> Result<Option<crate::db::Artifact>> {
+        let Some(store) = self.session_store.lock().await.clone() else {
+            return Ok(None);
+        };
+        Ok(store.db.get_artifact(id).await?)
+    }
+
+    /// Run the steps of the recipe in progress until one needs confirmation or
+    /// the recipe ends; returns the run log.
+    ///
+    /// While the heavy model answers a step, the next step's command (and so
+    /// its context and prompt) is already prepared when it does not depend on
+    /// that answer.
+    async fn advance_recipe(&self, cmd_ctx: &CommandContext) -> String {
+        // Load the heavy model while the first steps run
+        if self.state.lock().await.recipe.as_ref().is_some_and(RecipeRun::uses_heavy_model) {
+            self.spawn_heavy_warmup().await;
+        }
+        // Result of the next step's command, run alongside the previous step
+        let mut prefetched: Option<(usize, Result<CommandResult>)> = None;
+        loop {
+            let (step, action, index, prefetch) = {
+                let mut state = self.state.lock().await;
+                let Some(run) = state.recipe.as_mut() else {
+                    return "No hay ninguna receta en curso".to_string();
+                };
+                let Some(step) = run.next_step() else {
+                    let report = run.report();
+                    let summary = format!(
+                        "{} pasos, {} fallidos",
+                        run.recipe.steps.len(),
+                        run.failed_steps
+                    );
+                    webhooks::notify(
+                        TaskEvent::new(
+                            WebhookEventKind::PlanFinished,
+                            run.failed_steps == 0,
+                            format!("Receta {}", run.recipe.name),
+                            run.started.elapsed(),
+                        )
+                        .with_summary(summary),
+                    );
+                    state.recipe = None;
+                    return report;
+                };
+                if run.needs_confirmation(&step) {
+                    return format!("{}{}", run.report(), run.pause_message(&step));
+                }
+                let action = run.action(&step);
+                let prefetch = run.prefetchable(&step);
+                self.send_status(format!(
+                    "Receta {} · paso {}/{}",
+                    run.recipe.name,
+                    run.next + 1,
+                    run.recipe.steps.len()
+                ));
+                (step, action, run.next, prefetch)
+            };
+
+            // Only polled next to a heavy model call
+            let next_command = async move {
+                let (next, command) = prefetch?;
+                log_debug!("[RECIPE] Preparing step {} during step {}", next + 1, index + 1);
+                Some((next, self.slash_commands.execute(&command, cmd_ctx).await))
+            };
+
+            let mut held = false;
+            let ready = match prefetched.take() {
+                Some((prefetched_index, result)) if prefetched_index == index => Some(result),
+                _ => None,
+            };
+            let (output, success) = match action {
+                StepAction::Command(command) => {
+                    let result = match ready {
+                        Some(result) => result,
+                        None => self.slash_commands.execute(&command, cmd_ctx).await,
+                    };
+                    match result {
+                        Ok(result) => match result.metadata.get("prompt") {
+                            Some(prompt) if result.metadata.get("action").is_some_and(|a| a == "ask") => {
+                                let (response, next) = tokio::join!(
+                                    async { self.orchestrator.lock().await.call_heavy_model_direct(prompt).await },
+                                    next_command
+                                );
+                                prefetched = next;
+                                match response {
+                                    Ok(text) => (format!("{}\n\n{}", result.output, text), result.success),
+                                    Err(e) => (format!("{}\nError: {}", result.output, e), false),
+                                }
+                            }
+                            _ => (result.output, result.success),
+                        },
+                        Err(e) => (format!("Error: {}", e), false),
+                    }
+                }
+                StepAction::Tool { name, args } => {
+                    let orchestrator = self.orchestrator.lock().await;
+                    let output = orchestrator.execute_tool(&name, &args).await;
+                    // Mutating tools wait for the confirmation dialog like any other call
+                    held = orchestrator.pending_confirmation().is_some();
+                    let success = !output.starts_with("Error");
+                    (output, success)
+                }
+                StepAction::Prompt(prompt) => {
+                    let (response, next) = tokio::join!(
+                        async { self.orchestrator.lock().await.call_heavy_model_direct(&prompt).await },
+                        next_command
+                    );
+                    prefetched = next;
+                    match response {
+                        Ok(text) => (text, true),
+                        Err(e) => (format!("Error: {}", e), false),
+                    }
+                }
+            };
+
+            let mut state = self.state.lock().await;
+            let Some(run) = state.recipe.as_mut() else {
+                return "Receta detenida".to_string();
+            };
+            run.record(&step, output, success);
+            if held {
+                return format!(
+                    "{}⏸️ Confirma el cambio en el diálogo y sigue con /recipe continue",
+                    run.report()
+                );
+            }
+        }
+    }
+
     /// Get available slash command names for autocomplete
     pub fn get_slash_command_names(&self) -> Vec<String> {
         self.slash_commands.command_names()
@@ -796,13 +1626,309 @@ impl RouterOrchestrator {
 
     /// Process user query with routing
     pub async fn process(&self, user_query: &str) -> Result<OrchestratorResponse> {
+        Ok(self.process_structured(user_query, CancellationToken::new()).await?.response)
+    }
+
+    /// Process user query with routing, returning the tool calls, citations,
+    /// token usage and timing of the turn with the answer.
+    ///
+    /// Cancelling `cancel` ends the turn with [`OrchestratorError::Cancelled`]:
+    /// model requests are aborted, tool subprocesses killed and a streamed
+    /// analysis stopped.
+    pub async fn process_structured(&self, user_query: &str, cancel: CancellationToken) -> Result<StructuredResponse> {
+        let started = Instant::now();
+        // A yes/no answers the call waiting for confirmation (see
+        // take_fast_path); any other query supersedes it
+        let answers_pending = matches!(pre_router::fast_path(user_query), Some(FastPath::Answer(_)));
+        {
+            // Only this turn's writes are reviewed
+            let orchestrator = self.orchestrator.lock().await;
+            if !answers_pending {
+                orchestrator.take_pending_confirmation();
+            }
+            orchestrator.take_file_changes();
+            orchestrator.take_turn_record();
+            orchestrator.set_cancel_token(cancel.clone());
+            orchestrator.set_turn_budget(Some(self.turn_budget()));
+        }
+        let mut citations = None;
+        let response = tokio::select! {
+            biased;
+            _ = cancel.cancelled() => return Err(OrchestratorError::Cancelled.into()),
+            response = self.process_turn(user_query, &mut citations) => response?,
+        };
+        let turn = {
+            let orchestrator = self.orchestrator.lock().await;
+            orchestrator.set_turn_budget(None);
+            orchestrator.take_turn_record()
+        };
+
+        let is_command = user_query.trim_start().starts_with('/');
+        let citations = match (citations, response.text()) {
+            (Some(citations), _) => citations,
+            (None, Some(answer)) if !is_command => {
+                let working_dir = PathBuf::from(self.state.lock().await.working_dir.clone());
+                grounding::check(answer, &working_dir).citations()
+            }
+            _ => Vec::new(),
+        };
+        self.record_session_files(&turn).await;
+        if !citations.is_empty() {
+            let mut paths: Vec<String> = Vec::new();
+            for citation in &citations {
+                if !paths.contains(&citation.path) {
+                    paths.push(citation.path.clone());
+                }
+            }
+            self.state.lock().await.last_sources = Some(AnswerSources {
+                query: user_query.to_string(),
+                paths,
+            });
+        }
+        let response = if is_command { response } else { self.annotate_confidence(response, &citations).await };
+        Ok(StructuredResponse::from_turn(response, turn, citations, started.elapsed()))
+    }
+
+    /// Label an answer built on retrieved context, or citing files, with its
+    /// confidence; /why shows the numbers behind it
+    async fn annotate_confidence(&self, response: OrchestratorResponse, citations: &[Citation]) -> OrchestratorResponse {
+        if response.text().is_none() {
+            return response;
+        }
+        let confidence = {
+            let mut state = self.state.lock().await;
+            if state.retrieval_tokens == 0 && citations.is_empty() {
+                state.last_confidence = None;
+                return response;
+            }
+            let confidence = AnswerConfidence::assess(&state.retrieval_scores, citations);
+            state.last_confidence = Some(confidence.clone());
+            confidence
+        };
+        match response {
+            OrchestratorResponse::Text(text) => OrchestratorResponse::Text(confidence.annotate(&text)),
+            OrchestratorResponse::Immediate { content, model } => {
+                OrchestratorResponse::Immediate { content: confidence.annotate(&content), model }
+            }
+            other => other,
+        }
+    }
+
+    /// Budget of a turn starting now
+    fn turn_budget(&self) -> TurnBudget {
+        TurnBudget::new(Duration::from_secs(self.config.turn_budget_secs))
+    }
+
+    /// Route the query and run the checks on the answer; `citations` is set
+    /// when the grounding check ran
+    async fn process_turn(&self, user_query: &str, citations: &mut Option<Vec<Citation>>) -> Result<OrchestratorResponse> {
+        let mut response = self.route(user_query).await?;
+        // Every Build path ends here, including timeouts after partial writes
+        if self.config.review.enabled && self.state.lock().await.build_mode {
+            response = self.review_build_changes(user_query, response).await;
+        }
+        if let Some(call) = self.orchestrator.lock().await.pending_confirmation() {
+            return Ok(OrchestratorResponse::NeedsConfirmation {
+                command: call.summary,
+                risk_level: call.risk_level,
+            });
+        }
+        let is_command = user_query.trim_start().starts_with('/');
+        if self.config.grounding.enabled && !is_command && self.state.lock().await.retrieval_tokens > 0 {
+            response = self.check_grounding(user_query, response, citations).await;
+        }
+        if !is_command {
+            response = self.check_runtime_versions(response).await;
+        }
+        if !self.config.verify_examples || is_command {
+            return Ok(response);
+        }
+        Ok(self.verify_examples(response).await)
+    }
+
+    /// Answer an input the pre-router recognized; `None` sends it on to the classifier
+    async fn take_fast_path(&self, path: FastPath, user_query: &str) -> Result<Option<OrchestratorResponse>> {
+        if path == FastPath::SlashCommand {
+            return self.handle_slash_command(user_query).await;
+        }
+        {
+            let mut state = self.state.lock().await;
+            // A yes/no continues the previous turn in its mode
+            if !matches!(path, FastPath::Answer(_)) {
+                state.build_mode = false;
+            }
+            state.retrieval_tokens = 0;
+            state.retrieval_scores.clear();
+        }
+
+        if let Some(reply) = pre_router::canned_reply(path, self.config.locale) {
+            let mut state = self.state.lock().await;
+            state.add_message(Message::user(user_query));
+            state.add_message(Message::assistant(reply, "pre-router"));
+            return Ok(Some(OrchestratorResponse::Text(reply.to_string())));
+        }
+
+        // A yes/no answers the confirmation dialog when one is waiting
+        if let FastPath::Answer(approved) = path {
+            let (pending, cancel) = {
+                let orchestrator = self.orchestrator.lock().await;
+                (orchestrator.pending_confirmation().is_some(), orchestrator.cancel_token())
+            };
+            if pending && approved {
+                return self.confirm_pending(cancel).await.map(Some);
+            }
+            if pending {
+                self.cancel_pending().await;
+                return Ok(Some(OrchestratorResponse::Text(t(Text::Cancelled).to_string())));
+            }
+        }
+
+        // Built-in commands and answers to the model's last question go
+        // straight to the orchestrator, which has the conversation
+        self.send_status("Generando respuesta...".to_string());
+        let response = {
+            let mut orchestrator = self.orchestrator.lock().await;
+            orchestrator.process(user_query).await.map_err(NeuroError::from)?
+        };
+        Ok(Some(response))
+    }
+
+    /// Run the tool call the user approved in the confirmation dialog, until
+    /// `cancel` is cancelled
+    pub async fn confirm_pending(&self, cancel: CancellationToken) -> Result<OrchestratorResponse> {
+        let orchestrator = self.orchestrator.lock().await;
+        let Some(call) = orchestrator.take_pending_confirmation() else {
+            return Ok(OrchestratorResponse::Error("No hay ninguna acción pendiente de confirmar".to_string()));
+        };
+        orchestrator.set_cancel_token(cancel);
+        orchestrator.set_turn_budget(Some(self.turn_budget()));
+        let tool_name = call.tool_name.clone();
+        let result = orchestrator.execute_confirmed(call).await;
+        Ok(OrchestratorResponse::ToolResult {
+            success: !result.starts_with("Error"),
+            tool_name,
+            result,
+        })
+    }
+
+    /// Drop the tool call the user declined
+    pub async fn cancel_pending(&self) {
+        self.orchestrator.lock().await.take_pending_confirmation();
+    }
+
+    /// Flag the claims of a retrieval-backed answer its cited files do not back,
+    /// after one corrective follow-up when `grounding.auto_correct` is on
+    async fn check_grounding(
+        &self,
+        request: &str,
+        response: OrchestratorResponse,
+        citations: &mut Option<Vec<Citation>>,
+    ) -> OrchestratorResponse {
+        let (answer, model) = match &response {
+            OrchestratorResponse::Text(text) => (text.clone(), None),
+            OrchestratorResponse::Immediate { content, model } => (content.clone(), Some(model.clone())),
+            _ => return response,
+        };
+        let working_dir = PathBuf::from(self.state.lock().await.working_dir.clone());
+        let mut report = grounding::check(&answer, &working_dir);
+        *citations = Some(report.citations());
+        if report.is_grounded() {
+            return response;
+        }
+
+        let mut answer = answer;
+        if self.config.grounding.auto_correct {
+            self.send_status("🔎 Corrigiendo afirmaciones sin respaldo en las fuentes...".to_string());
+            let prompt = report.correction_prompt(request, &answer);
+            let corrected = self.orchestrator.lock().await.process(&prompt).await;
+            match corrected {
+                Ok(OrchestratorResponse::Text(text)) | Ok(OrchestratorResponse::Immediate { content: text, .. }) => {
+                    report = grounding::check(&text, &working_dir);
+                    *citations = Some(report.citations());
+                    answer = text;
+                }
+                Ok(_) => {}
+                Err(e) => log_warn!("[GROUNDING] Corrective follow-up failed: {}", e),
+            }
+        }
+
+        let annotated = report.annotate(&answer);
+        match model {
+            Some(model) => OrchestratorResponse::Immediate { content: annotated, model },
+            None => OrchestratorResponse::Text(annotated),
+        }
+    }
+
+    /// Warn under an answer whose instructions target runtime versions other than
+    /// the installed ones (`FROM node:18` with Node 20 installed)
+    async fn check_runtime_versions(&self, response: OrchestratorResponse) -> OrchestratorResponse {
+        let answer = match &response {
+            OrchestratorResponse::Text(text) => text,
+            OrchestratorResponse::Immediate { content, .. } => content,
+            _ => return response,
+        };
+        if !RuntimeMatrix::mentions_version(answer) {
+            return response;
+        }
+        let working_dir = PathBuf::from(self.state.lock().await.working_dir.clone());
+        let environment = self.orchestrator.lock().await.tools().environment.clone();
+        let mismatches = environment.runtime_matrix(&working_dir).await.mismatches(answer);
+        if mismatches.is_empty() {
+            return response;
+        }
+
+        let annotate = |answer: String| {
+            let mut out = format!("{}\n\n---\n🧰 Versiones del entorno:\n", answer.trim_end());
+            for mismatch in &mismatches {
+                out.push_str(&mismatch.describe());
+                out.push('\n');
+            }
+            out.trim_end().to_string()
+        };
+        match response {
+            OrchestratorResponse::Text(text) => OrchestratorResponse::Text(annotate(text)),
+            OrchestratorResponse::Immediate { content, model } => {
+                OrchestratorResponse::Immediate { content: annotate(content), model }
+            }
+            other => other,
+        }
+    }
+
+    /// Label the self-contained code examples of a text answer after running them
+    async fn verify_examples(&self, response: OrchestratorResponse) -> OrchestratorResponse {
+        let answer = match &response {
+            OrchestratorResponse::Text(text) => text,
+            OrchestratorResponse::Immediate { content, .. } => content,
+            _ => return response,
+        };
+        if !extract_examples(answer).iter().any(CodeExample::is_self_contained) {
+            return response;
+        }
+        self.send_status("🧪 Verificando ejemplos de código...".to_string());
+
+        let runner = ExampleRunner::new();
+        match response {
+            OrchestratorResponse::Text(text) => {
+                OrchestratorResponse::Text(runner.verify_answer(&text).await.unwrap_or(text))
+            }
+            OrchestratorResponse::Immediate { content, model } => {
+                let content = runner.verify_answer(&content).await.unwrap_or(content);
+                OrchestratorResponse::Immediate { content, model }
+            }
+            other => other,
+        }
+    }
+
+    async fn route(&self, user_query: &str) -> Result<OrchestratorResponse> {
         log_debug!("🔧 [PROCESS] process() called with query: '{}'", user_query);
         let start_time = std::time::Instant::now();
         
-        // Check for slash commands first
-        if let Some(response) = self.handle_slash_command(user_query).await? {
-            log_debug!("🔧 [PROCESS] Slash command handled, returning response");
-            return Ok(response);
+        // Slash commands, greetings and yes/no answers skip the classifier
+        if let Some(path) = pre_router::fast_path(user_query) {
+            if let Some(response) = self.take_fast_path(path, user_query).await? {
+                log_debug!("🔧 [PROCESS] Fast path {:?} handled, returning response", path);
+                return Ok(response);
+            }
         }
 
         // Classify query
@@ -811,7 +1937,43 @@ impl RouterOrchestrator {
             "🔍 Analizando consulta...".to_string(),
             start_time.elapsed().as_millis() as u64,
         );
-        let decision = self.classify(user_query).await?;
+        let (decision, reason) = self.classify_explained(user_query).await?;
+        self.state.lock().await.last_route = Some(RouteTrace {
+            query: user_query.to_string(),
+            route: decision.describe(),
+            confidence: decision.confidence(),
+            reason,
+            filter: intent::infer(user_query),
+        });
+        // `@artifact:<id>` attaches a stored output; the classifier only sees the message
+        let decision = match self.attach_artifacts(user_query).await {
+            Some(query) => decision.with_query(query),
+            None => decision,
+        };
+        self.run_decision(decision, start_time).await
+    }
+
+    /// `user_query` with the artifacts it references, `None` when there are none
+    async fn attach_artifacts(&self, user_query: &str) -> Option<String> {
+        let store = self.session_store.lock().await.clone()?;
+        artifacts::attach_references(&store.db, user_query)
+            .await
+            .map_err(|e| log_warn!("⚠ [ARTIFACT] Failed to attach artifacts: {}", e))
+            .ok()
+            .flatten()
+    }
+
+    /// Run a routing decision, from the classifier or a template's fixed mode
+    async fn run_decision(&self, decision: RouterDecision, start_time: Instant) -> Result<OrchestratorResponse> {
+        {
+            let mut state = self.state.lock().await;
+            state.build_mode = matches!(
+                decision,
+                RouterDecision::ToolExecution { mode: OperationMode::Build, .. }
+            );
+            state.retrieval_tokens = 0;
+            state.retrieval_scores.clear();
+        }
 
         match decision {
             RouterDecision::DirectResponse { query, confidence } => {
@@ -826,7 +1988,7 @@ impl RouterOrchestrator {
                 // Use orchestrator directly without tools
                 let response = {
                     let mut orchestrator = self.orchestrator.lock().await;
-                    orchestrator.process(&query).await.map_err(|e| anyhow::anyhow!("{:?}", e))?
+                    orchestrator.process(&query).await.map_err(NeuroError::from)?
                 };
                 self.send_progress(
                     ProgressStage::Complete,
@@ -842,14 +2004,16 @@ impl RouterOrchestrator {
                 }
                 self.send_status("🔍 Analizando repositorio...".to_string());
 
-                let event_tx = self.event_tx.lock().await.clone().ok_or_else(|| anyhow::anyhow!("Event sender not set"))?;
+                let events = self.events.clone();
                 let orchestrator_arc = Arc::clone(&self.orchestrator);
                 let raptor_service_arc = self.raptor_service.clone();
                 let config_clone = self.config.clone();
                 let related_files_detector_arc = Arc::clone(&self.related_files_detector);
                 let git_context_arc = Arc::clone(&self.git_context);
+                let cancel = self.orchestrator.lock().await.cancel_token();
+                let stream_cancel = cancel.clone();
 
-                tokio::spawn(async move {
+                let analysis = async move {
                     let mut full_context = String::new();
                     let start_time = std::time::Instant::now();
 
@@ -860,24 +2024,21 @@ impl RouterOrchestrator {
                     };
 
                     // --- Step 1: List root directory ---
-                    let _ = event_tx.try_send(crate::agent::AgentEvent::Progress(ProgressUpdate {
+                    events.publish(AgentEvent::Progress(ProgressUpdate {
                         stage: ProgressStage::ExecutingTool { tool_name: "list_directory".to_string() },
                         message: "1/5: Listando directorio raíz...".to_string(),
                         elapsed_ms: start_time.elapsed().as_millis() as u64,
                     }));
                     match tools.list_directory.call(crate::tools::ListDirectoryArgs {
-                        path: ".".to_string(),
+                        path: config_clone.working_dir.clone(),
                         recursive: false,
                         max_depth: 1,
+                        summary: Some(true),
                     }).await {
                         Ok(result) => {
                             full_context.push_str("Estructura del Directorio Raíz:\n");
-                            for entry in result.entries.iter().take(20) { // Limit output
-                                let icon = if entry.is_dir { "📁" } else { "📄" };
-                                full_context.push_str(&format!("{} {}\n", icon, entry.name));
-                            }
-                            if result.count > 20 {
-                                full_context.push_str(&format!("... y {} más.\n", result.count - 20));
+                            if let Some(summary) = result.summary {
+                                full_context.push_str(&summary.format(Path::new(&config_clone.working_dir)));
                             }
                             full_context.push_str("\n---\n");
                         }
@@ -885,7 +2046,7 @@ impl RouterOrchestrator {
                     }
 
                     // --- Step 2: Read README.md ---
-                    let _ = event_tx.try_send(crate::agent::AgentEvent::Progress(ProgressUpdate {
+                    events.publish(AgentEvent::Progress(ProgressUpdate {
                         stage: ProgressStage::ExecutingTool { tool_name: "read_file".to_string() },
                         message: "2/5: Leyendo README.md...".to_string(),
                         elapsed_ms: start_time.elapsed().as_millis() as u64,
@@ -895,6 +2056,7 @@ impl RouterOrchestrator {
                             path: "README.md".to_string(),
                             start_line: None,
                             end_line: Some(100), // Limit to first 100 lines
+                            ..Default::default()
                         }).await {
                             Ok(result) => {
                                 full_context.push_str("Contenido de README.md (primeras 100 líneas):\n");
@@ -906,7 +2068,7 @@ impl RouterOrchestrator {
                     }
 
                     // --- Step 3: Read Cargo.toml ---
-                    let _ = event_tx.try_send(crate::agent::AgentEvent::Progress(ProgressUpdate {
+                    events.publish(AgentEvent::Progress(ProgressUpdate {
                         stage: ProgressStage::ExecutingTool { tool_name: "read_file".to_string() },
                         message: "3/5: Leyendo Cargo.toml...".to_string(),
                         elapsed_ms: start_time.elapsed().as_millis() as u64,
@@ -916,6 +2078,7 @@ impl RouterOrchestrator {
                             path: "Cargo.toml".to_string(),
                             start_line: None,
                             end_line: None,
+                            ..Default::default()
                         }).await {
                             Ok(result) => {
                                 full_context.push_str("Contenido de Cargo.toml:\n");
@@ -927,7 +2090,7 @@ impl RouterOrchestrator {
                     }
 
                     // --- Step 4: List src directory ---
-                    let _ = event_tx.try_send(crate::agent::AgentEvent::Progress(ProgressUpdate {
+                    events.publish(AgentEvent::Progress(ProgressUpdate {
                         stage: ProgressStage::ExecutingTool { tool_name: "list_directory".to_string() },
                         message: "4/5: Listando directorio 'src'...".to_string(),
                         elapsed_ms: start_time.elapsed().as_millis() as u64,
@@ -937,6 +2100,7 @@ impl RouterOrchestrator {
                             path: "src".to_string(),
                             recursive: true,
                             max_depth: 5,
+                            summary: None,
                         }).await {
                             Ok(result) => {
                                 full_context.push_str("Estructura del Directorio 'src':\n");
@@ -953,7 +2117,7 @@ impl RouterOrchestrator {
                     }
                     
                     // --- Step 5: Get RAPTOR context ---
-                    let _ = event_tx.try_send(crate::agent::AgentEvent::Progress(ProgressUpdate {
+                    events.publish(AgentEvent::Progress(ProgressUpdate {
                         stage: ProgressStage::SearchingContext { chunks: 0 }, // Placeholder chunks
                         message: "5/5: Obteniendo contexto del índice (RAPTOR)...".to_string(),
                         elapsed_ms: start_time.elapsed().as_millis() as u64,
@@ -971,6 +2135,7 @@ impl RouterOrchestrator {
                     }
 
                     // --- Step 6: Related files context ---
+                    refresh_import_graph(orchestrator_arc.clone(), related_files_detector_arc.clone(), config_clone.working_dir.clone());
                     let (_detected_files, related_context) = tokio::time::timeout(
                         Duration::from_secs(5), // 5 second timeout for related files
                         related_files_detector_arc.enrich_with_query_context(&query, &config_clone)
@@ -998,7 +2163,7 @@ impl RouterOrchestrator {
 
 
                     // --- Final Summarization (Streaming) ---
-                    let _ = event_tx.try_send(crate::agent::AgentEvent::Progress(ProgressUpdate {
+                    events.publish(AgentEvent::Progress(ProgressUpdate {
                         stage: ProgressStage::Generating,
                         message: "Generando resumen final (streaming)...".to_string(),
                         elapsed_ms: start_time.elapsed().as_millis() as u64,
@@ -1024,24 +2189,33 @@ impl RouterOrchestrator {
                         &heavy_model,
                         timeout_secs,
                         &final_prompt,
-                        event_tx.clone()
+                        &events,
+                        &stream_cancel,
                     ).await;
 
                     match streaming_result {
                         Ok(_) => {
-                            let _ = event_tx.try_send(crate::agent::AgentEvent::Progress(ProgressUpdate {
+                            events.publish(AgentEvent::Progress(ProgressUpdate {
                                 stage: ProgressStage::Complete,
                                 message: "✓ Análisis completado".to_string(),
                                 elapsed_ms: start_time.elapsed().as_millis() as u64,
                             }));
                             // CRITICAL: Always send StreamEnd when streaming completes successfully
-                            let _ = event_tx.try_send(crate::agent::AgentEvent::StreamEnd);
+                            events.send(AgentEvent::StreamEnd).await;
                         }
                         Err(e) => {
-                            let _ = event_tx.try_send(crate::agent::AgentEvent::Error(format!("Error during streaming: {}", e)));
-                            let _ = event_tx.try_send(crate::agent::AgentEvent::StreamEnd);
+                            events.send(AgentEvent::Error(NeuroError::from(e))).await;
+                            events.send(AgentEvent::StreamEnd).await;
                         }
                     }
+                };
+                tokio::spawn(async move {
+                    // The turn is over for the caller, so nothing is reported on cancellation
+                    tokio::select! {
+                        biased;
+                        _ = cancel.cancelled() => log_debug!("[Analysis] Cancelled"),
+                        _ = analysis => {}
+                    }
                 });
 
                 // Immediately return Streaming response
@@ -1055,6 +2229,7 @@ impl RouterOrchestrator {
 
                 // Step 1: Detect files mentioned in query and get related files
                 // TEMPORARY: Skip context enrichment to isolate the freezing issue
+                refresh_import_graph(self.orchestrator.clone(), self.related_files_detector.clone(), self.config.working_dir.clone());
                 let (detected_files, related_context) = tokio::time::timeout(
                     Duration::from_secs(5), // 5 second timeout for related files
                     self.related_files_detector.enrich_with_query_context(&query, &self.config)
@@ -1083,7 +2258,9 @@ impl RouterOrchestrator {
                         }
                         if let Some(service) = &self.raptor_service {
                             let mut service_guard = service.lock().await;
-                            match service_guard.get_planning_context(&query).await {
+                            let context = service_guard.get_planning_context(&query).await;
+                            self.state.lock().await.retrieval_scores = service_guard.last_scores().to_vec();
+                            match context {
                                 Ok(context) if !context.is_empty() => {
                                     // Limit RAPTOR context to prevent model confusion
                                     let original_len = context.len();
@@ -1114,6 +2291,22 @@ impl RouterOrchestrator {
                     query.clone()
                 };
 
+                // Step 2b: Results from the federated roots of the workspace, labeled by root
+                if needs_raptor {
+                    if let Some(service) = &self.raptor_service {
+                        let working_dir = Path::new(&self.config.working_dir);
+                        let label = federation::label_for(&working_dir.canonicalize().unwrap_or_else(|_| working_dir.to_path_buf()));
+                        match service.lock().await.federated_context(&query, &label, 8).await {
+                            Ok(Some(context)) => enriched_query.push_str(&format!(
+                                "\n\nResultados del espacio de trabajo, etiquetados con su raíz:\n{}",
+                                context
+                            )),
+                            Ok(None) => {}
+                            Err(e) => log_warn!("⚠ [RAPTOR] Federated query failed: {}", e),
+                        }
+                    }
+                }
+
                 // Step 3: Append related files context if any were detected
                 if !related_context.is_empty() {
                     enriched_query.push_str(&related_context);
@@ -1128,6 +2321,34 @@ impl RouterOrchestrator {
                     enriched_query.push_str(&git_context);
                 }
 
+                // Step 5: Append the ingested database schema for SQL/ORM queries
+                if let Some(schema) = crate::tools::schema_context_for(&query) {
+                    enriched_query.push_str(&format!("\n\nEsquema de la base de datos:\n{}", schema));
+                }
+
+                // Step 6: Resolve @symbol mentions against the symbol index
+                let symbol_index = self.orchestrator.lock().await.tools().symbol_index.clone();
+                if let Ok(Some(symbols)) = tokio::time::timeout(
+                    Duration::from_secs(5),
+                    symbol_index.mention_context(Path::new(&self.config.working_dir), &query),
+                )
+                .await
+                {
+                    enriched_query.push_str(&format!("\n\nSímbolos mencionados:\n{}", symbols));
+                }
+
+                // Step 7: Fetched docs of the dependencies the query names
+                if let Some(docs) = crate::tools::dependency_docs_context_for(&query) {
+                    enriched_query.push_str(&format!(
+                        "\n\nDocumentación de dependencias (cita la fuente al usarla):\n{}",
+                        docs
+                    ));
+                }
+
+                // Counted apart from the query in the context meter
+                self.state.lock().await.retrieval_tokens =
+                    estimate_tokens(&enriched_query).saturating_sub(estimate_tokens(&query));
+
                 self.send_progress(
                     ProgressStage::ExecutingTool { tool_name: format!("mode_{:?}", mode) },
                     "⚙️ Ejecutando herramientas...".to_string(),
@@ -1143,26 +2364,7 @@ impl RouterOrchestrator {
 
                         // Heartbeat: periodically send status updates while the operation is running
                         let (hb_tx, hb_rx) = oneshot::channel::<()>();
-                        {
-                            let event_tx = self.event_tx.lock().await;
-                            if let Some(ref tx) = &*event_tx {
-                                let tx_clone = tx.clone();
-                                tokio::spawn(async move {
-                                    let mut interval = tokio::time::interval(Duration::from_secs(5));
-                                    let mut hb_rx = hb_rx;
-                                    loop {
-                                        tokio::select! {
-                                            _ = interval.tick() => {
-                                                let _ = tx_clone.try_send(crate::agent::AgentEvent::Status("Procesando (read-only)...".to_string()));
-                                            }
-                                            _ = &mut hb_rx => {
-                                                break;
-                                            }
-                                        }
-                                    }
-                                });
-                            }
-                        }
+                        self.spawn_heartbeat("Procesando (read-only)...", hb_rx);
 
                         let timeout_result = {
                             let mut orchestrator = self.orchestrator.lock().await;
@@ -1176,17 +2378,12 @@ impl RouterOrchestrator {
                             }
                             Ok(Err(e)) => {
                                 let _ = hb_tx.send(());
-                                Err(anyhow::anyhow!("{:?}", e))
+                                Err(NeuroError::from(e).into())
                             }
                             Err(_) => {
                                 // timeout - attempt a single retry with repository-aware context
                                 let _ = hb_tx.send(());
-                                {
-                                    let event_tx = self.event_tx.lock().await;
-                                    if let Some(tx) = &*event_tx {
-                                        let _ = tx.try_send(crate::agent::AgentEvent::Status("⏱️ Timeout: attempting fallback with repo context...".to_string()));
-                                    }
-                                }
+                                self.send_status("⏱️ Timeout: attempting fallback with repo context...".to_string());
 
                                 if let Ok(repo_ctx) = self.collect_repo_context(&enriched_query).await {
                                     if !repo_ctx.is_empty() {
@@ -1198,26 +2395,7 @@ impl RouterOrchestrator {
                                         // short retry timeout
                                         let retry_timeout = Duration::from_secs((self.config.execution_timeout_secs / 4).max(10));
                                         let (hb2_tx, hb2_rx) = oneshot::channel::<()>();
-                                        {
-                                            let event_tx = self.event_tx.lock().await;
-                                            if let Some(ref tx) = &*event_tx {
-                                                let tx_clone = tx.clone();
-                                                tokio::spawn(async move {
-                                                    let mut interval = tokio::time::interval(Duration::from_secs(5));
-                                                    let mut hb_rx = hb2_rx;
-                                                    loop {
-                                                        tokio::select! {
-                                                            _ = interval.tick() => {
-                                                                let _ = tx_clone.try_send(crate::agent::AgentEvent::Status("Procesando (retry with repo context)...".to_string()));
-                                                            }
-                                                            _ = &mut hb_rx => {
-                                                                break;
-                                                            }
-                                                        }
-                                                    }
-                                                });
-                                            }
-                                        }
+                                        self.spawn_heartbeat("Procesando (retry with repo context)...", hb2_rx);
 
                                         let timeout_result = {
                                             let mut orch = self.orchestrator.lock().await;
@@ -1231,7 +2409,7 @@ impl RouterOrchestrator {
                                             }
                                             Ok(Err(e)) => {
                                                 let _ = hb2_tx.send(());
-                                                return Err(anyhow::anyhow!("{:?}", e));
+                                                return Err(NeuroError::from(e).into());
                                             }
                                             Err(_) => {
                                                 let _ = hb2_tx.send(());
@@ -1250,26 +2428,7 @@ impl RouterOrchestrator {
                         let timeout_dur = Duration::from_secs(self.config.execution_timeout_secs);
 
                         let (hb_tx, hb_rx) = oneshot::channel::<()>();
-                        {
-                            let event_tx = self.event_tx.lock().await;
-                            if let Some(ref tx) = &*event_tx {
-                                let tx_clone = tx.clone();
-                                tokio::spawn(async move {
-                                    let mut interval = tokio::time::interval(Duration::from_secs(5));
-                                    let mut hb_rx = hb_rx;
-                                    loop {
-                                        tokio::select! {
-                                            _ = interval.tick() => {
-                                                let _ = tx_clone.try_send(crate::agent::AgentEvent::Status("Procesando (build)...".to_string()));
-                                            }
-                                            _ = &mut hb_rx => {
-                                                break;
-                                            }
-                                        }
-                                    }
-                                });
-                            }
-                        }
+                        self.spawn_heartbeat("Procesando (build)...", hb_rx);
 
                         let timeout_result = {
                             let mut orchestrator = self.orchestrator.lock().await;
@@ -1283,7 +2442,7 @@ impl RouterOrchestrator {
                             }
                             Ok(Err(e)) => {
                                 let _ = hb_tx.send(());
-                                Err(anyhow::anyhow!("{:?}", e))
+                                Err(NeuroError::from(e).into())
                             }
                             Err(_) => {
                                 let _ = hb_tx.send(());
@@ -1307,7 +2466,7 @@ impl RouterOrchestrator {
                         
                         let response = {
                             let mut orchestrator = self.orchestrator.lock().await;
-                            orchestrator.process(&plan_prompt).await.map_err(|e| anyhow::anyhow!("{:?}", e))?
+                            orchestrator.process(&plan_prompt).await.map_err(NeuroError::from)?
                         };
                         Ok(response)
                     }
@@ -1322,13 +2481,95 @@ impl RouterOrchestrator {
                 // Use full orchestrator with all capabilities
                 let response = {
                     let mut orchestrator = self.orchestrator.lock().await;
-                    orchestrator.process(&query).await.map_err(|e| anyhow::anyhow!("{:?}", e))?
+                    orchestrator.process(&query).await.map_err(NeuroError::from)?
                 };
                 Ok(response)
             }
         }
     }
 
+    /// Have a second model review the files a Build turn wrote; blocking
+    /// findings get one revision by the heavy model before the diff is shown
+    async fn review_build_changes(&self, request: &str, response: OrchestratorResponse) -> OrchestratorResponse {
+        let mut changes = self.orchestrator.lock().await.take_file_changes();
+        if changes.is_empty() {
+            return response;
+        }
+
+        self.send_status("🔎 Revisando los cambios...".to_string());
+        let first = self.review_changes(request, &changes).await;
+        let mut last = None;
+        let mut response = response;
+        if first.has_blocking() {
+            self.send_status("🔁 Corrigiendo problemas de la revisión...".to_string());
+            let revision = {
+                let mut orchestrator = self.orchestrator.lock().await;
+                let revised = timeout(
+                    Duration::from_secs(self.config.execution_timeout_secs),
+                    orchestrator.process(&review_pass::revision_prompt(request, &first)),
+                )
+                .await;
+                review_pass::merge_changes(&mut changes, orchestrator.take_file_changes());
+                revised
+            };
+            match revision {
+                Ok(Ok(revised)) => response = revised,
+                Ok(Err(e)) => log_warn!("⚠️ Review revision failed: {}", e),
+                Err(_) => log_warn!("⚠️ Review revision timed out"),
+            }
+            self.send_status("🔎 Revisando la corrección...".to_string());
+            last = Some(self.review_changes(request, &changes).await);
+        }
+
+        let outcome = review_pass::render_outcome(&changes, &first, last.as_ref());
+        match response {
+            OrchestratorResponse::Text(text) => OrchestratorResponse::Text(text + &outcome),
+            OrchestratorResponse::Immediate { content, model } => OrchestratorResponse::Immediate {
+                content: content + &outcome,
+                model,
+            },
+            OrchestratorResponse::ToolResult { tool_name, result, success } => OrchestratorResponse::ToolResult {
+                tool_name,
+                result: result + &outcome,
+                success,
+            },
+            OrchestratorResponse::Error(error) => OrchestratorResponse::Error(error + &outcome),
+            other => other,
+        }
+    }
+
+    /// One review of `changes`; a failed reviewer call counts as no findings
+    async fn review_changes(&self, request: &str, changes: &[review_pass::FileChange]) -> review_pass::Review {
+        let root = Path::new(&self.config.working_dir);
+        let orchestrator = self.orchestrator.lock().await;
+        let mut convention_notes = Vec::new();
+        for change in changes {
+            if let Some(note) = orchestrator
+                .tools()
+                .conventions
+                .review(root, &change.path, change.before.as_deref())
+                .await
+            {
+                convention_notes.push(note);
+            }
+        }
+
+        let prompt = review_pass::review_prompt(request, changes, &convention_notes);
+        let model = self
+            .config
+            .review
+            .model
+            .clone()
+            .unwrap_or_else(|| self.config.fast_model_config.model.clone());
+        match orchestrator.call_model_direct(&model, &prompt).await {
+            Ok(answer) => review_pass::Review::parse(&answer),
+            Err(e) => {
+                log_warn!("⚠️ Review by {} failed: {}", model, e);
+                review_pass::Review::default()
+            }
+        }
+    }
+
     /// Get shared state
     pub fn get_state(&self) -> SharedState {
         self.state.clone()
@@ -1371,10 +2612,11 @@ impl RouterOrchestrator {
         let search_args = crate::tools::SearchArgs {
             path: self.config.working_dir.clone(),
             pattern: user_query.to_string(),
-            is_regex: Some(false),
+            mode: crate::tools::SearchMode::Literal,
             case_insensitive: Some(true),
             file_pattern: None,
             max_results: Some(50),
+            offset: None,
             context_lines: Some(3),
             max_depth: Some(8),
         };
@@ -1384,7 +2626,7 @@ impl RouterOrchestrator {
                 use std::collections::HashMap;
                 let mut by_file: HashMap<String, Vec<crate::tools::SearchResult>> = HashMap::new();
                 for r in search_out.results.into_iter() {
-                    let key = r.file.to_string_lossy().to_string();
+                    let key = r.path.to_string_lossy().to_string();
                     by_file.entry(key).or_default().push(r);
                 }
 
@@ -1392,7 +2634,7 @@ impl RouterOrchestrator {
                     snippets.push(format!("Archivo: {}", file));
                     for r in results.into_iter().take(3) {
                         for b in r.context_before { snippets.push(format!("  {}", b)); }
-                        snippets.push(format!("  {}: {}", r.line_number, r.line_content));
+                        snippets.push(format!("  {}: {}", r.line, r.preview));
                         for a in r.context_after { snippets.push(format!("  {}", a)); }
                         snippets.push(String::from(""));
                     }
@@ -1407,6 +2649,7 @@ impl RouterOrchestrator {
                 path: self.config.working_dir.clone(),
                 recursive: true,
                 max_depth: 4,
+                summary: None,
             }).await {
                 // Filter likely-useful files
                 let mut candidates: Vec<_> = list_out
@@ -1433,6 +2676,8 @@ impl RouterOrchestrator {
                         path: path.clone(),
                         start_line: None,
                         end_line: Some(200),
+                        expand_scope: Some(false),
+                        ..Default::default()
                     }).await {
                         snippets.push(format!("Archivo: {}\n{}\n---", path, read_out.content));
                     }
@@ -1451,6 +2696,37 @@ impl RouterOrchestrator {
     }
 }
 
+/// Refresh the import graph of the related files detector from the symbol index,
+/// in the background and only after source files changed. Only with a session
+/// database: the index is incremental there, a full parse is not.
+fn refresh_import_graph(
+    orchestrator: Arc<AsyncMutex<DualModelOrchestrator>>,
+    detector: Arc<RelatedFilesDetector>,
+    working_dir: String,
+) {
+    tokio::spawn(async move {
+        let symbol_index = orchestrator.lock().await.tools().symbol_index.clone();
+        if !symbol_index.has_database() {
+            return;
+        }
+        let scan = tokio::task::spawn_blocking({
+            let detector = detector.clone();
+            move || detector.begin_import_graph_refresh()
+        });
+        let Ok(Some(sources)) = scan.await else {
+            return;
+        };
+        let built = match symbol_index.import_graph(Path::new(&working_dir)).await {
+            Ok(graph) => Some((graph, sources)),
+            Err(e) => {
+                log_warn!("[RelatedFiles] Import graph unavailable: {}", e);
+                None
+            }
+        };
+        detector.finish_import_graph_refresh(built);
+    });
+}
+
 
 /// Build router classification prompt
 fn build_router_classification_prompt(user_query: &str, locale: &Locale) -> String {
@@ -1558,6 +2834,7 @@ Respond exactly in this JSON format:
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::agent::mock_provider::{MockProvider, MockReply};
     use std::path::PathBuf;
     
     /// Test that RelatedFilesDetector is properly initialized
@@ -1880,4 +3157,139 @@ mod tests {
             }
         }
     }
+
+    async fn mock_router(mock: &MockProvider) -> RouterOrchestrator {
+        let config = RouterConfig {
+            fast_model_config: mock.model_config("qwen3:0.6b"),
+            heavy_model_config: mock.model_config("qwen3:8b"),
+            classification_timeout_secs: 5,
+            ..Default::default()
+        };
+        let orchestrator = DualModelOrchestrator::with_config(mock.orchestrator_config())
+            .await
+            .unwrap();
+        RouterOrchestrator::new(config, orchestrator).await.unwrap()
+    }
+
+    fn classification(route: &str, confidence: f64, mode: &str) -> MockReply {
+        MockReply::text(
+            serde_json::json!({
+                "route": route,
+                "confidence": confidence,
+                "reasoning": "scripted",
+                "mode": mode,
+                "needs_raptor": false,
+            })
+            .to_string(),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_classify_with_mock_provider() {
+        let mock = MockProvider::start().await;
+        mock.push(classification("ToolExecution", 0.95, "Build"));
+        let router = mock_router(&mock).await;
+
+        match router.classify("add a --verbose flag to the CLI").await.unwrap() {
+            RouterDecision::ToolExecution { mode, needs_raptor, .. } => {
+                assert_eq!(mode, OperationMode::Build);
+                assert!(!needs_raptor);
+            }
+            other => panic!("unexpected decision: {:?}", other),
+        }
+        // Cached: a second classification does not reach the model
+        router.classify("add a --verbose flag to the CLI").await.unwrap();
+        let requests = mock.requests();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].model(), "qwen3:0.6b");
+    }
+
+    #[tokio::test]
+    async fn test_low_confidence_falls_back_to_ask() {
+        let mock = MockProvider::start().await;
+        mock.push(classification("FullPipeline", 0.3, "Build"));
+        let router = mock_router(&mock).await;
+
+        match router.classify("do the thing").await.unwrap() {
+            RouterDecision::ToolExecution { mode, needs_raptor, confidence, .. } => {
+                assert_eq!(mode, OperationMode::Ask);
+                assert!(needs_raptor);
+                assert_eq!(confidence, 0.3);
+            }
+            other => panic!("unexpected decision: {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_classification_error_with_mock_provider() {
+        let mock = MockProvider::start().await;
+        mock.push(MockReply::error(500, "model not loaded"));
+        let router = mock_router(&mock).await;
+
+        assert!(router.process("what time is it in Tokyo?").await.is_err());
+        assert_eq!(mock.remaining(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_trivial_inputs_skip_classification() {
+        let mock = MockProvider::start().await;
+        mock.push(MockReply::text("Creando el archivo"));
+        let router = mock_router(&mock).await;
+
+        match router.process("¡Hola!").await.unwrap() {
+            OrchestratorResponse::Text(reply) => assert!(reply.starts_with("¡Hola!")),
+            other => panic!("unexpected response: {:?}", other),
+        }
+        assert!(mock.requests().is_empty());
+
+        // Nothing to confirm: the answer goes to the model without classification
+        match router.process("sí").await.unwrap() {
+            OrchestratorResponse::Immediate { content, .. } => assert_eq!(content, "Creando el archivo"),
+            other => panic!("unexpected response: {:?}", other),
+        }
+        let requests = mock.requests();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].prompt(), "sí");
+    }
+
+    #[tokio::test]
+    async fn test_yes_no_answers_the_pending_confirmation() {
+        let mock = MockProvider::start().await;
+        let router = mock_router(&mock).await;
+        router.state.lock().await.build_mode = true;
+        let args = serde_json::json!({ "path": "Cargo.toml" });
+
+        router.orchestrator.lock().await.hold_for_confirmation("read_file", &args, "read Cargo.toml".into(), "low");
+        match router.process("sí").await.unwrap() {
+            OrchestratorResponse::ToolResult { tool_name, .. } => assert_eq!(tool_name, "read_file"),
+            other => panic!("unexpected response: {:?}", other),
+        }
+        assert!(router.state.lock().await.build_mode);
+
+        router.orchestrator.lock().await.hold_for_confirmation("read_file", &args, "read Cargo.toml".into(), "low");
+        match router.process("no").await.unwrap() {
+            OrchestratorResponse::Text(reply) => assert_eq!(reply, t(Text::Cancelled)),
+            other => panic!("unexpected response: {:?}", other),
+        }
+        assert!(router.orchestrator.lock().await.pending_confirmation().is_none());
+        assert!(mock.requests().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_direct_response_with_mock_provider() {
+        let mock = MockProvider::start().await;
+        mock.push(classification("DirectResponse", 0.9, "Ask"))
+            .push(MockReply::text("Rust is a systems programming language"));
+        let router = mock_router(&mock).await;
+
+        match router.process("what is rust?").await.unwrap() {
+            OrchestratorResponse::Immediate { content, .. } => {
+                assert_eq!(content, "Rust is a systems programming language");
+            }
+            other => panic!("unexpected response: {:?}", other),
+        }
+        let requests = mock.requests();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[1].prompt(), "what is rust?");
+    }
 }
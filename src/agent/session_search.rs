@@ -0,0 +1,160 @@
+//! Search over past sessions
+//!
+//! Sessions are tagged with `/tag` and browsed with `/sessions`, which
+//! searches titles, messages, tags and touched files. A query is free text
+//! plus `#tag` (or `tag:tag`) words, every one of which a session must have.
+
+use crate::db::{DbMessage, SessionSummary};
+
+/// Sessions listed per search
+pub const SEARCH_LIMIT: i64 = 50;
+
+/// Longest tag accepted
+const MAX_TAG_LEN: usize = 40;
+
+/// Characters of the first question shown as the description of a session
+const DESCRIPTION_CHARS: usize = 80;
+
+/// A search of the session browser
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SessionQuery {
+    pub text: String,
+    pub tags: Vec<String>,
+}
+
+impl SessionQuery {
+    /// Split `input` into free text and `#tag` / `tag:tag` words
+    pub fn parse(input: &str) -> Self {
+        let mut words = Vec::new();
+        let mut tags = Vec::new();
+        for word in input.split_whitespace() {
+            let tag = word.strip_prefix('#').or_else(|| word.strip_prefix("tag:"));
+            match tag.and_then(normalize_tag) {
+                Some(tag) if !tags.contains(&tag) => tags.push(tag),
+                Some(_) => {}
+                None => words.push(word),
+            }
+        }
+        Self {
+            text: words.join(" "),
+            tags,
+        }
+    }
+}
+
+/// Lowercased tag without a leading `#`; `None` when empty, too long or with
+/// characters other than letters, digits, `-`, `_`, `.` and `/`
+pub fn normalize_tag(tag: &str) -> Option<String> {
+    let tag = tag.trim().trim_start_matches('#').to_lowercase();
+    let valid = !tag.is_empty()
+        && tag.chars().count() <= MAX_TAG_LEN
+        && tag.chars().all(|c| c.is_alphanumeric() || matches!(c, '-' | '_' | '.' | '/'));
+    valid.then_some(tag)
+}
+
+/// Title of a session, or its first question when it has none
+pub fn describe(summary: &SessionSummary) -> String {
+    if let Some(title) = summary.session.title.as_deref().filter(|title| !title.trim().is_empty()) {
+        return title.to_string();
+    }
+    match summary.first_question.as_deref().and_then(|question| question.lines().next()) {
+        Some(question) if question.chars().count() > DESCRIPTION_CHARS => {
+            format!("{}…", question.chars().take(DESCRIPTION_CHARS).collect::<String>())
+        }
+        Some(question) => question.to_string(),
+        None => "(sin mensajes guardados)".to_string(),
+    }
+}
+
+/// Details of a session: date, models, tags and files, one per line
+pub fn details(summary: &SessionSummary) -> Vec<String> {
+    let session = &summary.session;
+    let mut lines = vec![
+        format!("Sesión {}", session.id),
+        format!("Creada: {}", session.created_at.get(..16).unwrap_or(&session.created_at).replace('T', " ")),
+        format!("Modelos: {} (rápido) · {} (pesado)", session.fast_model, session.heavy_model),
+        format!("Mensajes: {} · Tokens: {}", summary.message_count, session.total_tokens),
+    ];
+    if let Some(dir) = &session.working_dir {
+        lines.push(format!("Directorio: {}", dir));
+    }
+    if !summary.tags.is_empty() {
+        lines.push(format!(
+            "Etiquetas: {}",
+            summary.tags.iter().map(|tag| format!("#{}", tag)).collect::<Vec<_>>().join(" ")
+        ));
+    }
+    if !summary.files.is_empty() {
+        lines.push(String::new());
+        lines.push("Archivos:".to_string());
+        for file in &summary.files {
+            let mark = if file.is_written() { "✏️" } else { "👁" };
+            lines.push(format!("  {} {}", mark, file.path));
+        }
+    }
+    lines
+}
+
+/// The session as Markdown: its details and every stored message
+pub fn transcript(summary: &SessionSummary, messages: &[DbMessage]) -> String {
+    let mut output = format!("# {}\n\n", describe(summary));
+    for line in details(summary) {
+        output.push_str(&line);
+        output.push('\n');
+    }
+    for message in messages {
+        let sender = match (message.role.as_str(), &message.tool_name) {
+            ("user", _) => "Tú".to_string(),
+            ("assistant", _) => "Neuro".to_string(),
+            ("tool", Some(tool)) => format!("Herramienta {}", tool),
+            (role, _) => role.to_string(),
+        };
+        output.push_str(&format!("\n## {}\n\n{}\n", sender, message.content));
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{Session, SessionFile};
+
+    fn summary() -> SessionSummary {
+        SessionSummary {
+            session: Session::new("s1", "qwen3:8b", "qwen3:32b"),
+            tags: vec!["bug-123".to_string()],
+            message_count: 2,
+            first_question: Some("¿Por qué falla el login?\nDetalles".to_string()),
+            files: vec![SessionFile { path: "src/login.rs".to_string(), written: 1 }],
+        }
+    }
+
+    #[test]
+    fn test_parse_query() {
+        let query = SessionQuery::parse("login #Bug-123 tag:auth #bug-123 timeout");
+        assert_eq!(query.text, "login timeout");
+        assert_eq!(query.tags, vec!["bug-123", "auth"]);
+        assert_eq!(SessionQuery::parse("#"), SessionQuery { text: "#".to_string(), tags: vec![] });
+        assert_eq!(normalize_tag("#Release/1.2"), Some("release/1.2".to_string()));
+        assert_eq!(normalize_tag("two words"), None);
+    }
+
+    #[test]
+    fn test_describe_and_transcript() {
+        let mut summary = summary();
+        assert_eq!(describe(&summary), "¿Por qué falla el login?");
+        assert!(details(&summary).contains(&"  ✏️ src/login.rs".to_string()));
+
+        let messages = vec![
+            DbMessage::new("m1", "s1", "user", "¿Por qué falla el login?"),
+            DbMessage::new("m2", "s1", "assistant", "El token caduca."),
+        ];
+        let markdown = transcript(&summary, &messages);
+        assert!(markdown.starts_with("# ¿Por qué falla el login?\n"));
+        assert!(markdown.contains("Etiquetas: #bug-123"));
+        assert!(markdown.ends_with("## Neuro\n\nEl token caduca.\n"));
+
+        summary.session.title = Some("Login".to_string());
+        assert_eq!(describe(&summary), "Login");
+    }
+}
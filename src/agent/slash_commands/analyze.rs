@@ -1,10 +1,110 @@
 //! Analyze Command - Deep code analysis and explanation
 
 use super::{CommandCategory, CommandContext, CommandResult, SlashCommand};
+use crate::context::ImportGraph;
+use crate::tools::format_trends;
 use anyhow::Result;
+use std::path::Path;
+
+/// Snapshots shown by `/analyze trends` without a count
+const DEFAULT_TREND_SNAPSHOTS: usize = 10;
 
 pub struct AnalyzeCommand;
 
+impl AnalyzeCommand {
+    /// `/analyze imports [path]`: project import graph, or the imports of one file
+    async fn analyze_imports(&self, file: Option<&str>, ctx: &CommandContext) -> Result<CommandResult> {
+        let graph = match ctx.tools.symbol_index.import_graph(Path::new(&ctx.working_dir)).await {
+            Ok(graph) => graph,
+            Err(e) => return Ok(CommandResult::error(format!("Import graph failed: {}", e))),
+        };
+
+        let output = match file {
+            Some(file) => {
+                let file = file.trim_start_matches("./");
+                if !graph.contains(file) {
+                    return Ok(CommandResult::error(format!("{} is not an indexed source file", file)));
+                }
+                format_file_imports(&graph, file)
+            }
+            None => format_graph_summary(&graph),
+        };
+        Ok(CommandResult::success(output))
+    }
+
+    /// `/analyze report`: complexity and maintainability of the whole project
+    async fn analyze_report(&self, ctx: &CommandContext) -> Result<CommandResult> {
+        match ctx.tools.symbol_index.complexity_report(Path::new(&ctx.working_dir)).await {
+            Ok(report) => Ok(CommandResult::success(report.format())),
+            Err(e) => Ok(CommandResult::error(format!("Complexity report failed: {}", e))),
+        }
+    }
+
+    /// `/analyze trends [n]`: last `n` complexity snapshots and their direction
+    async fn analyze_trends(&self, limit: Option<&str>, ctx: &CommandContext) -> Result<CommandResult> {
+        let limit = match limit.map(str::parse::<usize>) {
+            None => DEFAULT_TREND_SNAPSHOTS,
+            Some(Ok(n)) if n > 0 => n,
+            Some(_) => return Ok(CommandResult::error("Usage: /analyze trends [n]")),
+        };
+        match ctx.tools.symbol_index.complexity_trends(Path::new(&ctx.working_dir), limit).await {
+            Ok(snapshots) => Ok(CommandResult::success(format_trends(&snapshots))),
+            Err(e) => Ok(CommandResult::error(format!("Complexity trends failed: {}", e))),
+        }
+    }
+}
+
+fn format_list(items: &[&str]) -> String {
+    if items.is_empty() {
+        return "_none_\n".to_string();
+    }
+    items.iter().map(|item| format!("- {}\n", item)).collect()
+}
+
+fn format_file_imports(graph: &ImportGraph, file: &str) -> String {
+    let mut output = format!("# Imports: {}\n\n", file);
+    output.push_str("## Imports\n");
+    output.push_str(&format_list(&graph.imports_of(file)));
+    output.push_str("\n## Imported by\n");
+    output.push_str(&format_list(&graph.importers_of(file)));
+    output.push_str("\n## External\n");
+    output.push_str(&format_list(&graph.external_imports_of(file)));
+
+    for cycle in graph.cycles().iter().filter(|c| c.iter().any(|f| f == file)) {
+        output.push_str(&format!("\n⚠️ Import cycle: {}\n", cycle.join(" → ")));
+    }
+    output
+}
+
+fn format_graph_summary(graph: &ImportGraph) -> String {
+    let internal = graph.edges().iter().filter(|e| e.to.is_some()).count();
+    let mut output = format!(
+        "# Import graph\n\n**Files:** {}\n**Internal imports:** {}\n**External imports:** {}\n",
+        graph.file_count(),
+        internal,
+        graph.edges().len() - internal
+    );
+
+    let most_imported = graph.most_imported(10);
+    if !most_imported.is_empty() {
+        output.push_str("\n## Most imported\n");
+        for (file, count) in most_imported {
+            output.push_str(&format!("- {} ({} importers)\n", file, count));
+        }
+    }
+
+    let cycles = graph.cycles();
+    if cycles.is_empty() {
+        output.push_str("\n✅ No import cycles\n");
+    } else {
+        output.push_str(&format!("\n## Import cycles ({})\n", cycles.len()));
+        for cycle in cycles {
+            output.push_str(&format!("- {}\n", cycle.join(" → ")));
+        }
+    }
+    output
+}
+
 #[async_trait::async_trait]
 impl SlashCommand for AnalyzeCommand {
     fn name(&self) -> &str {
@@ -16,7 +116,7 @@ impl SlashCommand for AnalyzeCommand {
     }
     
     fn usage(&self) -> &str {
-        "/analyze <path> [type] - Types: overview, complexity, functions, dependencies"
+        "/analyze <path> [type] - Types: overview, complexity, functions, dependencies | /analyze imports [path] - Import graph and cycles | /analyze report - Project complexity | /analyze trends [n] - Complexity over time"
     }
     
     fn category(&self) -> CommandCategory {
@@ -32,6 +132,16 @@ impl SlashCommand for AnalyzeCommand {
             return Ok(CommandResult::error("Usage: /analyze <path>"));
         }
         
+        if parts[0] == "imports" {
+            return self.analyze_imports(parts.get(1).copied(), ctx).await;
+        }
+        if parts[0] == "report" {
+            return self.analyze_report(ctx).await;
+        }
+        if parts[0] == "trends" {
+            return self.analyze_trends(parts.get(1).copied(), ctx).await;
+        }
+
         let path = parts[0];
         
         let analyzer_args = AnalyzeFileArgs {
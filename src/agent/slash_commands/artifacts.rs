@@ -0,0 +1,154 @@
+//! Artifacts Command - Large outputs kept out of the chat
+//!
+//! Lists, pins and removes artifacts; the TUI opens `/artifacts open <id>` in
+//! a pager, elsewhere the text is printed. `@artifact:<id>` in a message
+//! attaches one to the context of that turn.
+
+use super::{CommandCategory, CommandContext, CommandResult, SlashCommand};
+use crate::agent::artifacts::{ArtifactKind, LIST_LIMIT};
+use anyhow::Result;
+
+pub struct ArtifactsCommand;
+
+/// `<id>` or `#<id>`
+fn parse_id(arg: &str) -> Option<i64> {
+    arg.trim_start_matches('#').parse().ok()
+}
+
+#[async_trait::async_trait]
+impl SlashCommand for ArtifactsCommand {
+    fn name(&self) -> &str {
+        "artifacts"
+    }
+
+    fn description(&self) -> &str {
+        "List, open, pin or remove large outputs (runs, diffs, reports, docs, exports)"
+    }
+
+    fn usage(&self) -> &str {
+        "/artifacts [list] [output|diff|report|doc|export|profile] | /artifacts open|pin|unpin|rm|attach <id>"
+    }
+
+    fn category(&self) -> CommandCategory {
+        CommandCategory::Context
+    }
+
+    fn validate_args(&self, args: &str) -> Result<()> {
+        match args.split_whitespace().collect::<Vec<_>>().as_slice() {
+            [] | ["list"] => Ok(()),
+            ["list", kind] | [kind] if ArtifactKind::parse(kind).is_some() => Ok(()),
+            ["open" | "pin" | "unpin" | "rm" | "attach", id] if parse_id(id).is_some() => Ok(()),
+            _ => anyhow::bail!("Usage: {}", self.usage()),
+        }
+    }
+
+    async fn execute(&self, args: &str, ctx: &CommandContext) -> Result<CommandResult> {
+        let Some(session) = &ctx.session else {
+            return Ok(CommandResult::error("Los artefactos necesitan una sesión con base de datos"));
+        };
+        let db = &session.db;
+        let parts: Vec<&str> = args.split_whitespace().collect();
+
+        if let [action, id] = parts.as_slice() {
+            if let Some(id) = parse_id(id) {
+                let missing = || CommandResult::error(format!("No hay un artefacto #{}", id));
+                return Ok(match *action {
+                    "open" => match db.get_artifact(id).await? {
+                        Some(artifact) => CommandResult::success(format!(
+                            "📎 #{} [{}] {}\n\n{}",
+                            artifact.id, artifact.kind, artifact.source, artifact.content
+                        ))
+                        .with_metadata("id", id.to_string()),
+                        None => missing(),
+                    },
+                    "pin" | "unpin" if db.pin_artifact(id, *action == "pin").await? => {
+                        let state = if *action == "pin" { "fijado: no caduca" } else { "sin fijar" };
+                        CommandResult::success(format!("📌 Artefacto #{} {}", id, state))
+                    }
+                    "rm" if db.delete_artifact(id).await? => {
+                        CommandResult::success(format!("🗑️ Artefacto #{} eliminado", id))
+                    }
+                    "attach" if db.get_artifact(id).await?.is_some() => CommandResult::success(format!(
+                        "Escribe @artifact:{} en tu mensaje para añadirlo al contexto de ese turno",
+                        id
+                    )),
+                    _ => missing(),
+                });
+            }
+        }
+
+        let kind = parts.iter().find_map(|part| ArtifactKind::parse(part));
+        let artifacts = db.list_artifacts(kind.map(|kind| kind.as_str()), LIST_LIMIT).await?;
+        if artifacts.is_empty() {
+            return Ok(CommandResult::success("No hay artefactos guardados"));
+        }
+        let mut output = String::from("📎 Artefactos:\n\n");
+        for artifact in &artifacts {
+            let expiry = match (&artifact.expires_at, artifact.is_pinned()) {
+                (_, true) => "📌".to_string(),
+                (Some(expires_at), false) => format!("caduca {}", expires_at.get(..10).unwrap_or(expires_at)),
+                (None, false) => "sin caducidad".to_string(),
+            };
+            output.push_str(&format!(
+                "  #{} [{}] {} ({} líneas, {} KB, {}) - {}\n",
+                artifact.id,
+                artifact.kind,
+                artifact.source,
+                artifact.lines(),
+                artifact.size_bytes.div_ceil(1024),
+                expiry,
+                artifact.summary.lines().next().unwrap_or_default()
+            ));
+        }
+        output.push_str(
+            "\nUsa /artifacts open <id> para verlo completo, /artifacts pin <id> para conservarlo \
+             y @artifact:<id> para añadirlo al contexto.",
+        );
+        Ok(CommandResult::success(output))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent::state::create_shared_state;
+    use crate::db::{Artifact, Database, Session, SessionStore};
+    use crate::tools::registry::ToolRegistry;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_list_open_pin_remove() {
+        let db = Database::in_memory().await.unwrap();
+        db.create_session(&Session::new("s1", "fast", "heavy")).await.unwrap();
+        let id = db
+            .add_artifact(&Artifact::new("s1", "output", "/shell cargo test", "line 1\nline 2", "✅ 2 passed"))
+            .await
+            .unwrap();
+        db.add_artifact(&Artifact::new("s1", "diff", "git diff", "+x", "1 file")).await.unwrap();
+        let ctx = CommandContext {
+            tools: Arc::new(ToolRegistry::new()),
+            state: create_shared_state(),
+            working_dir: ".".to_string(),
+            session: Some(SessionStore {
+                db,
+                session_id: "s1".to_string(),
+            }),
+        };
+
+        assert!(ArtifactsCommand.validate_args("open x").is_err());
+        assert!(ArtifactsCommand.validate_args("open #1").is_ok());
+        assert!(ArtifactsCommand.validate_args("list diff").is_ok());
+        assert!(ArtifactsCommand.validate_args("list images").is_err());
+
+        let listed = ArtifactsCommand.execute("output", &ctx).await.unwrap();
+        assert!(listed.output.contains("[output] /shell cargo test (2 líneas, 1 KB, sin caducidad) - ✅ 2 passed"));
+        assert!(!listed.output.contains("git diff"));
+
+        let opened = ArtifactsCommand.execute(&format!("open {}", id), &ctx).await.unwrap();
+        assert!(opened.output.ends_with("line 1\nline 2"));
+        assert!(ArtifactsCommand.execute(&format!("pin {}", id), &ctx).await.unwrap().success);
+        assert!(ArtifactsCommand.execute("list", &ctx).await.unwrap().output.contains("📌"));
+        assert!(ArtifactsCommand.execute(&format!("rm {}", id), &ctx).await.unwrap().success);
+        assert!(!ArtifactsCommand.execute(&format!("open {}", id), &ctx).await.unwrap().success);
+    }
+}
@@ -0,0 +1,151 @@
+//! Callgraph Command - Who calls a function, and what it calls
+
+use super::{CommandCategory, CommandContext, CommandResult, SlashCommand};
+use crate::context::CallGraph;
+use anyhow::Result;
+use std::path::Path;
+
+/// Levels of indirect callers shown without `--depth`
+const DEFAULT_DEPTH: usize = 3;
+
+/// Functions listed by the project summary
+const MOST_CALLED_SHOWN: usize = 10;
+
+pub struct CallGraphCommand;
+
+/// `<function> [--depth N]` → (function, depth)
+fn parse_args(args: &str) -> Result<(Option<&str>, usize), String> {
+    let mut function = None;
+    let mut depth = DEFAULT_DEPTH;
+    let mut words = args.split_whitespace();
+    while let Some(word) = words.next() {
+        match word {
+            "--depth" => {
+                depth = words
+                    .next()
+                    .and_then(|n| n.parse().ok())
+                    .ok_or_else(|| "--depth needs a number".to_string())?;
+            }
+            _ if function.is_none() => function = Some(word),
+            _ => return Err(format!("Unexpected argument: {}", word)),
+        }
+    }
+    Ok((function, depth))
+}
+
+fn format_function(graph: &CallGraph, function: &str, depth: usize) -> String {
+    let mut output = format!("# Call graph: {}\n\n", function);
+
+    output.push_str("## Defined in\n");
+    let definitions = graph.definitions_of(function);
+    if definitions.is_empty() {
+        output.push_str("_not defined in the project_\n");
+    }
+    for definition in definitions {
+        output.push_str(&format!("- {}:{} `{}`\n", definition.file, definition.line, definition.name));
+    }
+
+    output.push_str("\n## Called by\n");
+    let callers = graph.callers_of(function);
+    if callers.is_empty() {
+        output.push_str("_none_\n");
+    }
+    for site in callers {
+        output.push_str(&format!(
+            "- {}:{} in {}\n",
+            site.file,
+            site.line,
+            site.caller.as_deref().map_or("top-level code".to_string(), |c| format!("`{}`", c))
+        ));
+    }
+
+    output.push_str("\n## Calls\n");
+    let callees = graph.callees_of(function);
+    if callees.is_empty() {
+        output.push_str("_none_\n");
+    }
+    for site in callees {
+        output.push_str(&format!("- `{}` ({}:{})\n", site.callee, site.file, site.line));
+    }
+
+    let indirect: Vec<_> = graph
+        .transitive_callers(function, depth)
+        .into_iter()
+        .filter(|(distance, _)| *distance > 1)
+        .collect();
+    if !indirect.is_empty() {
+        output.push_str(&format!("\n## Indirect callers (up to {} levels)\n", depth));
+        for (distance, caller) in indirect {
+            output.push_str(&format!("- `{}` ({} calls away)\n", caller, distance));
+        }
+    }
+    output
+}
+
+fn format_summary(graph: &CallGraph) -> String {
+    let mut output = format!(
+        "# Call graph\n\n**Functions:** {}\n**Call sites:** {}\n",
+        graph.function_count(),
+        graph.sites().len()
+    );
+    let most_called = graph.most_called(MOST_CALLED_SHOWN);
+    if !most_called.is_empty() {
+        output.push_str("\n## Most called\n");
+        for (function, count) in most_called {
+            output.push_str(&format!("- `{}` ({} call sites)\n", function, count));
+        }
+    }
+    output.push_str("\n💡 /callgraph <function> shows who calls it\n");
+    output
+}
+
+#[async_trait::async_trait]
+impl SlashCommand for CallGraphCommand {
+    fn name(&self) -> &str {
+        "callgraph"
+    }
+
+    fn description(&self) -> &str {
+        "Show who calls a function and what it calls"
+    }
+
+    fn usage(&self) -> &str {
+        "/callgraph [function] [--depth N] - Callers and callees of function, with indirect callers up to N levels (default: most called functions)"
+    }
+
+    fn category(&self) -> CommandCategory {
+        CommandCategory::Code
+    }
+
+    async fn execute(&self, args: &str, ctx: &CommandContext) -> Result<CommandResult> {
+        let (function, depth) = match parse_args(args) {
+            Ok(parsed) => parsed,
+            Err(e) => return Ok(CommandResult::error(format!("{}\nUsage: {}", e, self.usage()))),
+        };
+
+        let graph = match ctx.tools.symbol_index.call_graph(Path::new(&ctx.working_dir)).await {
+            Ok(graph) => graph,
+            Err(e) => return Ok(CommandResult::error(format!("Call graph failed: {}", e))),
+        };
+
+        Ok(match function {
+            Some(function) => CommandResult::success(format_function(&graph, function, depth))
+                .with_metadata("function", function),
+            None => CommandResult::success(format_summary(&graph)),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_args() {
+        assert_eq!(parse_args(""), Ok((None, DEFAULT_DEPTH)));
+        assert_eq!(parse_args("Config::load --depth 5"), Ok((Some("Config::load"), 5)));
+        assert_eq!(parse_args("--depth 1 load"), Ok((Some("load"), 1)));
+        assert!(parse_args("load --depth").is_err());
+        assert!(parse_args("load save").is_err());
+    }
+}
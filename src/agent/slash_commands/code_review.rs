@@ -2,8 +2,12 @@
 
 use super::{CommandCategory, CommandContext, CommandResult, SlashCommand};
 use anyhow::Result;
+use crate::tools::Owner;
 use rig::tool::Tool;
 
+/// Reviewers suggested in reports
+pub(super) const SUGGESTED_REVIEWERS: usize = 3;
+
 pub struct CodeReviewCommand;
 
 #[async_trait::async_trait]
@@ -76,9 +80,31 @@ impl SlashCommand for CodeReviewCommand {
             }
         }
         
+        // 3. Who to ask for review
+        let reviewers = ctx
+            .tools
+            .owners
+            .suggest_reviewers(std::path::Path::new(&ctx.working_dir), &[path.to_string()], SUGGESTED_REVIEWERS)
+            .await;
+        match reviewers {
+            Ok(reviewers) if !reviewers.is_empty() => {
+                review_results.push(format!("\n👥 **Suggested reviewers:** {}", format_reviewers(&reviewers)));
+            }
+            _ => {}
+        }
+        
         let output = format!("# Code Review Report: {}\n\n{}\n\n---\n✅ Review complete", 
                            path, review_results.join("\n"));
         
         Ok(CommandResult::success(output).with_metadata("path", path))
     }
 }
+
+/// `Name <email> (46%), ...`
+pub(super) fn format_reviewers(reviewers: &[Owner]) -> String {
+    reviewers
+        .iter()
+        .map(|owner| format!("{} <{}> ({:.0}%)", owner.name, owner.email, owner.share * 100.0))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
@@ -1,8 +1,11 @@
 //! Commit Commands - Git workflow automation
 
+use super::code_review::{format_reviewers, SUGGESTED_REVIEWERS};
 use super::{CommandCategory, CommandContext, CommandResult, SlashCommand};
+use crate::agent::{activity_report, dry_run};
 use anyhow::Result;
-use crate::tools::{GitCommitArgs, GitStatusArgs, GitAddArgs};
+use crate::tools::{commit_message, DiffFileKind, DiffOutput, GitAddArgs, GitCommitArgs, GitDiffArgs};
+use std::path::Path;
 
 pub struct CommitCommand;
 
@@ -17,7 +20,7 @@ impl SlashCommand for CommitCommand {
     }
     
     fn usage(&self) -> &str {
-        "/commit [message] - Commit staged changes (auto-generate message if empty)"
+        "/commit [message] - Commit staged changes (auto-generate a message naming the changed functions if empty)"
     }
     
     fn category(&self) -> CommandCategory {
@@ -25,6 +28,31 @@ impl SlashCommand for CommitCommand {
     }
     
     async fn execute(&self, args: &str, ctx: &CommandContext) -> Result<CommandResult> {
+        // Describe the commit without staging or making it (/dryrun)
+        if dry_run::enabled() {
+            let diff = ctx
+                .tools
+                .git
+                .diff(GitDiffArgs {
+                    path: ctx.working_dir.clone(),
+                    commit: None,
+                    staged: Some(false),
+                    file: None,
+                })
+                .await;
+            let message = match (&diff, args.is_empty()) {
+                (Ok(diff), true) => symbol_message(diff, ctx).await,
+                (Err(_), true) => "chore: update files".to_string(),
+                (_, false) => args.to_string(),
+            };
+            let output = dry_run::describe(
+                "commit",
+                format!("run `git add . && git commit -m {:?}` in {}", message, ctx.working_dir),
+                diff.map(|diff| diff.format()).unwrap_or_default(),
+            );
+            return Ok(CommandResult::success(output).with_metadata("commit_message", &message));
+        }
+
         // First, add all changes
         let add_args = GitAddArgs {
             path: ctx.working_dir.clone(),
@@ -36,16 +64,16 @@ impl SlashCommand for CommitCommand {
         }
         
         let message = if args.is_empty() {
-            // Auto-generate commit message from git status
-            let status_args = GitStatusArgs {
+            // Auto-generate commit message from the staged diff
+            let diff_args = GitDiffArgs {
                 path: ctx.working_dir.clone(),
+                commit: None,
+                staged: Some(true),
+                file: None,
             };
             
-            match ctx.tools.git.status(status_args).await {
-                Ok(status) => {
-                    let staged_count = status.staged.len();
-                    format!("chore: update {} file{}", staged_count, if staged_count != 1 { "s" } else { "" })
-                }
+            match ctx.tools.git.diff(diff_args).await {
+                Ok(diff) => symbol_message(&diff, ctx).await,
                 Err(_) => "chore: update files".to_string(),
             }
         } else {
@@ -59,6 +87,9 @@ impl SlashCommand for CommitCommand {
         
         match ctx.tools.git.commit(commit_args).await {
             Ok(commit_info) => {
+                if let Some(session) = &ctx.session {
+                    activity_report::audit(session, &format!("git commit -m {:?}", message), 0).await;
+                }
                 let result_message = format!(
                     "✅ Committed: {}\n  Hash: {}\n  Author: {}\n  Files changed: {}",
                     commit_info.short_hash, commit_info.hash, commit_info.author, commit_info.files_changed
@@ -103,6 +134,21 @@ impl SlashCommand for CommitPushPrCommand {
             Err(e) => return Ok(CommandResult::error(format!("Commit failed: {}", e))),
         }
         
+        // Reviewers for the files just committed (nothing was committed under /dryrun)
+        if !dry_run::enabled() {
+            let reviewers = ctx
+                .tools
+                .owners
+                .commit_reviewers(Path::new(&ctx.working_dir), "HEAD", SUGGESTED_REVIEWERS)
+                .await;
+            match reviewers {
+                Ok(reviewers) if !reviewers.is_empty() => {
+                    steps.push(format!("👥 Suggested reviewers: {}", format_reviewers(&reviewers)));
+                }
+                _ => {}
+            }
+        }
+        
         // 2. Push (nota: GitTool no tiene método push, esto es un stub)
         steps.push("ℹ️  Push: Run `git push` manually or implement push in GitTool".to_string());
         
@@ -112,3 +158,47 @@ impl SlashCommand for CommitPushPrCommand {
         Ok(CommandResult::success(format!("# Git Workflow\n\n{}", steps.join("\n"))))
     }
 }
+
+/// Commit message describing each hunk by the function, method or type it
+/// changes, with a bullet per file; the file-level message when no symbol
+/// changed or the files could not be parsed
+async fn symbol_message(diff: &DiffOutput, ctx: &CommandContext) -> String {
+    ctx.tools
+        .symbol_index
+        .diff_symbols(Path::new(&ctx.working_dir), diff)
+        .await
+        .ok()
+        .and_then(|summaries| commit_message(diff, &summaries))
+        .unwrap_or_else(|| auto_message(diff))
+}
+
+/// Files shown by name in a generated commit message
+const MAX_NAMED_FILES: usize = 3;
+
+/// Commit message naming the code files changed; lockfile, generated and
+/// binary changes only count when nothing else changed
+fn auto_message(diff: &DiffOutput) -> String {
+    let names: Vec<String> = diff
+        .code_files()
+        .map(|f| {
+            Path::new(&f.path)
+                .file_name()
+                .map_or_else(|| f.path.clone(), |n| n.to_string_lossy().to_string())
+        })
+        .collect();
+
+    if names.is_empty() {
+        return if diff.files.iter().any(|f| f.kind == DiffFileKind::Lockfile) {
+            "chore: update dependencies".to_string()
+        } else {
+            let count = diff.files.len();
+            format!("chore: update {} file{}", count, if count != 1 { "s" } else { "" })
+        };
+    }
+
+    let mut message = format!("chore: update {}", names[..names.len().min(MAX_NAMED_FILES)].join(", "));
+    if names.len() > MAX_NAMED_FILES {
+        message.push_str(&format!(" and {} more", names.len() - MAX_NAMED_FILES));
+    }
+    message
+}
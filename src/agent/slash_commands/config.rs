@@ -0,0 +1,98 @@
+//! Config Command - Show the config layers and check them for conflicts
+
+use super::{CommandCategory, CommandContext, CommandResult, SlashCommand, SlashCommandRegistry};
+use crate::agent::recipes::RecipeBook;
+use crate::config::project::{LOCAL_CONFIG_FILE, PROJECT_DIR, SHARED_CONFIG_FILE};
+use crate::config::{AppConfig, DoctorFinding, ProjectConfig, Severity};
+use anyhow::Result;
+use std::path::Path;
+
+pub struct ConfigCommand;
+
+#[async_trait::async_trait]
+impl SlashCommand for ConfigCommand {
+    fn name(&self) -> &str {
+        "config"
+    }
+
+    fn description(&self) -> &str {
+        "Show the personal and project (.neuro/) config layers"
+    }
+
+    fn usage(&self) -> &str {
+        "/config - Show where each layer comes from\n\
+         /config doctor - Check shared and personal settings for conflicts"
+    }
+
+    fn category(&self) -> CommandCategory {
+        CommandCategory::System
+    }
+
+    async fn execute(&self, args: &str, ctx: &CommandContext) -> Result<CommandResult> {
+        let working_dir = Path::new(&ctx.working_dir);
+        let personal_path = AppConfig::active_path(None);
+        let project = ProjectConfig::discover(working_dir);
+
+        match args.trim() {
+            "" => {
+                let mut output = String::from("⚙️ Capas de configuración (de menor a mayor prioridad):\n");
+                output.push_str(&format!(
+                    "  1. Personal: {}\n",
+                    personal_path.as_ref().map_or("valores por defecto".to_string(), |p| p.display().to_string())
+                ));
+                match &project {
+                    Ok(project) => match project.dir() {
+                        Some(dir) => {
+                            for (index, file) in [SHARED_CONFIG_FILE, LOCAL_CONFIG_FILE].iter().enumerate() {
+                                let path = dir.join(file);
+                                let state = if path.exists() { "" } else { " (no existe)" };
+                                output.push_str(&format!("  {}. {}{}\n", index + 2, path.display(), state));
+                            }
+                            for name in ["recipes", "commands", "prompts"] {
+                                let count = std::fs::read_dir(dir.join(name)).map_or(0, |entries| entries.count());
+                                output.push_str(&format!("     {}/: {} archivos\n", name, count));
+                            }
+                        }
+                        None => output.push_str(&format!("  Sin directorio {}/ en este proyecto\n", PROJECT_DIR)),
+                    },
+                    Err(e) => output.push_str(&format!("  ❌ {}/: {}\n", PROJECT_DIR, e)),
+                }
+                output.push_str("  4. Variables de entorno (NEURO_*)\n\nRevisa conflictos con /config doctor");
+                Ok(CommandResult::success(output))
+            }
+            "doctor" => {
+                let personal = match personal_path.as_ref().map(AppConfig::from_file) {
+                    Some(Ok(config)) => config,
+                    Some(Err(e)) => return Ok(CommandResult::error(format!("Config: {}", e))),
+                    None => AppConfig::default(),
+                };
+                let mut findings = match &project {
+                    Ok(project) => project.doctor(&personal),
+                    Err(e) => vec![DoctorFinding::new(
+                        Severity::Error,
+                        format!("{}/ could not be read: {}", PROJECT_DIR, e),
+                    )],
+                };
+                let (_, recipe_errors) = RecipeBook::new(working_dir).list();
+                findings.extend(recipe_errors.into_iter().map(|e| DoctorFinding::new(Severity::Error, e.to_string())));
+                findings.extend(
+                    SlashCommandRegistry::new()
+                        .register_custom(working_dir)
+                        .into_iter()
+                        .map(|note| DoctorFinding::new(Severity::Warning, note)),
+                );
+
+                let problems = findings.iter().filter(|f| f.severity != Severity::Info).count();
+                let mut output = String::from("🩺 /config doctor\n");
+                for finding in &findings {
+                    output.push_str(&format!("{} {}\n", finding.severity.icon(), finding.message));
+                }
+                if problems == 0 {
+                    output.push_str("✅ Sin conflictos entre la configuración compartida y la personal");
+                }
+                Ok(CommandResult::success(output))
+            }
+            _ => Ok(CommandResult::error(format!("Usage: {}", self.usage()))),
+        }
+    }
+}
@@ -27,7 +27,7 @@ impl SlashCommand for ContextCommand {
         let mut context_tool = ctx.tools.project_context.lock().await;
         match context_tool.analyze(&ctx.working_dir).await {
             Ok(result) => {
-                let output = format!(
+                let mut output = format!(
                     "📊 Project Context\n\n\
                     **Name:** {}\n\
                     **Type:** {:?}\n\
@@ -40,6 +40,12 @@ impl SlashCommand for ContextCommand {
                     result.file_count,
                     result.dependencies_count
                 );
+                if let Some(runtimes) = &result.runtimes {
+                    output.push_str(&format!("\n**Runtimes:** {}", runtimes.compact()));
+                }
+                if let Some(containers) = &result.containers {
+                    output.push_str(&format!("\n\n🐳 Containers\n\n{}", containers.summary()));
+                }
                 Ok(CommandResult::success(output))
             }
             Err(e) => Ok(CommandResult::error(format!("Failed to get context: {}", e))),
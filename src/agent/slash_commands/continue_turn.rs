@@ -0,0 +1,30 @@
+//! Continue Command - Resume a turn stopped by its time budget
+
+use super::{CommandCategory, CommandContext, CommandResult, SlashCommand};
+use anyhow::Result;
+
+pub struct ContinueCommand;
+
+#[async_trait::async_trait]
+impl SlashCommand for ContinueCommand {
+    fn name(&self) -> &str {
+        "continue"
+    }
+
+    fn description(&self) -> &str {
+        "Resume the turn that ran out of time, starting with its pending tool calls"
+    }
+
+    fn usage(&self) -> &str {
+        "/continue - Resume the interrupted turn"
+    }
+
+    fn category(&self) -> CommandCategory {
+        CommandCategory::System
+    }
+
+    async fn execute(&self, _args: &str, _ctx: &CommandContext) -> Result<CommandResult> {
+        // The RouterOrchestrator holds the interrupted conversation and resumes it
+        Ok(CommandResult::success("").with_metadata("action", "continue"))
+    }
+}
@@ -0,0 +1,89 @@
+//! Custom Commands - Prompt templates from `.neuro/commands/*.md`
+//!
+//! The file name is the command name and the text is the prompt sent to the
+//! model, with `$ARGUMENTS` replaced by what follows the command. A first line
+//! starting with `#` is used as the description.
+
+use super::{CommandContext, CommandResult, SlashCommand};
+use anyhow::Result;
+use std::path::{Path, PathBuf};
+
+/// Placeholder for the command arguments in a template
+pub const ARGUMENTS_PLACEHOLDER: &str = "$ARGUMENTS";
+
+pub struct CustomCommand {
+    name: String,
+    description: String,
+    template: String,
+    source: PathBuf,
+}
+
+impl CustomCommand {
+    pub fn from_file(path: &Path) -> std::io::Result<Self> {
+        let text = std::fs::read_to_string(path)?;
+        let name = path.file_stem().unwrap_or_default().to_string_lossy().to_string();
+        let (description, template) = match text.split_once('\n') {
+            Some((first, rest)) if first.starts_with('#') => {
+                (first.trim_start_matches('#').trim().to_string(), rest.trim().to_string())
+            }
+            _ => (format!("Custom command ({})", path.display()), text.trim().to_string()),
+        };
+        Ok(Self {
+            name,
+            description,
+            template,
+            source: path.to_path_buf(),
+        })
+    }
+
+    /// Commands in `dir`, by file name
+    pub fn load_dir(dir: &Path) -> Vec<Self> {
+        let Ok(entries) = std::fs::read_dir(dir) else {
+            return Vec::new();
+        };
+        let mut paths: Vec<PathBuf> = entries
+            .filter_map(|e| e.ok().map(|e| e.path()))
+            .filter(|p| p.extension().is_some_and(|e| e == "md"))
+            .collect();
+        paths.sort();
+        paths
+            .iter()
+            .filter_map(|path| {
+                Self::from_file(path)
+                    .map_err(|e| tracing::warn!("Skipping custom command {:?}: {}", path, e))
+                    .ok()
+            })
+            .collect()
+    }
+
+    pub fn source(&self) -> &Path {
+        &self.source
+    }
+
+    pub fn render(&self, args: &str) -> String {
+        if self.template.contains(ARGUMENTS_PLACEHOLDER) {
+            self.template.replace(ARGUMENTS_PLACEHOLDER, args)
+        } else if args.is_empty() {
+            self.template.clone()
+        } else {
+            format!("{}\n\n{}", self.template, args)
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl SlashCommand for CustomCommand {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn description(&self) -> &str {
+        &self.description
+    }
+
+    async fn execute(&self, args: &str, _ctx: &CommandContext) -> Result<CommandResult> {
+        Ok(CommandResult::success(format!("📝 /{}", self.name))
+            .with_metadata("action", "ask")
+            .with_metadata("prompt", self.render(args)))
+    }
+}
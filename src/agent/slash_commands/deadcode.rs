@@ -0,0 +1,80 @@
+//! Deadcode Command - Functions and types nothing refers to
+
+use super::{CommandCategory, CommandContext, CommandResult, SlashCommand};
+use anyhow::Result;
+use std::path::Path;
+
+/// Symbols listed by the command
+const MAX_SHOWN: usize = 200;
+
+pub struct DeadCodeCommand;
+
+/// `[path] [--public]` → (path, include public symbols)
+fn parse_args(args: &str) -> Result<(Option<&str>, bool), String> {
+    let mut path = None;
+    let mut include_public = false;
+    for word in args.split_whitespace() {
+        match word {
+            "--public" => include_public = true,
+            _ if word.starts_with("--") => return Err(format!("Unknown option: {}", word)),
+            _ if path.is_none() => path = Some(word),
+            _ => return Err(format!("Unexpected argument: {}", word)),
+        }
+    }
+    Ok((path, include_public))
+}
+
+#[async_trait::async_trait]
+impl SlashCommand for DeadCodeCommand {
+    fn name(&self) -> &str {
+        "deadcode"
+    }
+
+    fn description(&self) -> &str {
+        "List functions and types that are never referenced"
+    }
+
+    fn usage(&self) -> &str {
+        "/deadcode [path] [--public] - Unreferenced functions and types below path, by language (--public also lists public ones)"
+    }
+
+    fn category(&self) -> CommandCategory {
+        CommandCategory::Code
+    }
+
+    async fn execute(&self, args: &str, ctx: &CommandContext) -> Result<CommandResult> {
+        let (path, include_public) = match parse_args(args) {
+            Ok(parsed) => parsed,
+            Err(e) => return Ok(CommandResult::error(format!("{}\nUsage: {}", e, self.usage()))),
+        };
+
+        let report = match ctx
+            .tools
+            .dead_code
+            .find_dead_code(Path::new(&ctx.working_dir), path, include_public, MAX_SHOWN)
+            .await
+        {
+            Ok(report) => report,
+            Err(e) => return Ok(CommandResult::error(format!("Dead code search failed: {}", e))),
+        };
+
+        let mut output = format!("# Dead code\n\n{}", report.format());
+        if !report.dead.is_empty() {
+            output.push_str("\n💡 Matching is by name: check with /callgraph or find_references before deleting\n");
+        }
+        Ok(CommandResult::success(output).with_metadata("dead", report.dead.len().to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_args() {
+        assert_eq!(parse_args(""), Ok((None, false)));
+        assert_eq!(parse_args("src/tools --public"), Ok((Some("src/tools"), true)));
+        assert!(parse_args("--all").is_err());
+        assert!(parse_args("src lib").is_err());
+    }
+}
@@ -2,7 +2,8 @@
 
 use super::{CommandCategory, CommandContext, CommandResult, SlashCommand};
 use anyhow::Result;
-use crate::tools::AnalyzeDepsArgs;
+use crate::tools::{AnalyzeDepsArgs, DEFAULT_TOP_DEPENDENCIES};
+use std::path::Path;
 
 pub struct DependenciesCommand;
 
@@ -17,7 +18,7 @@ impl SlashCommand for DependenciesCommand {
     }
     
     fn usage(&self) -> &str {
-        "/deps [path] - Analyze dependencies in project"
+        "/deps [path] - Analyze dependencies in project | /deps docs [n] - Fetch docs of the n most used dependencies"
     }
     
     fn category(&self) -> CommandCategory {
@@ -25,6 +26,25 @@ impl SlashCommand for DependenciesCommand {
     }
     
     async fn execute(&self, args: &str, ctx: &CommandContext) -> Result<CommandResult> {
+        let mut parts = args.split_whitespace();
+        if parts.next() == Some("docs") {
+            let limit = match parts.next().map(str::parse::<usize>) {
+                None => DEFAULT_TOP_DEPENDENCIES,
+                Some(Ok(n)) if n > 0 => n,
+                Some(_) => return Ok(CommandResult::error("Usage: /deps docs [n]")),
+            };
+            return match ctx
+                .tools
+                .dependency_docs
+                .fetch_top(Path::new(&ctx.working_dir), limit)
+                .await
+            {
+                Ok(report) => Ok(CommandResult::success(report.format())
+                    .with_metadata("stored", report.stored.len().to_string())),
+                Err(e) => Ok(CommandResult::error(format!("Dependency docs failed: {}", e))),
+            };
+        }
+
         let path = if args.is_empty() {
             ctx.working_dir.clone()
         } else {
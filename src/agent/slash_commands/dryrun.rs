@@ -0,0 +1,97 @@
+//! Dry-run Command - Describe mutating actions instead of running them
+//!
+//! `/dryrun on` makes write_file, the shell, git and replacements describe
+//! what they would do (see `crate::agent::dry_run`); `/dryrun` lists the
+//! actions planned so far and `/dryrun clear` starts a new plan.
+
+use super::{CommandCategory, CommandContext, CommandResult, SlashCommand};
+use crate::agent::dry_run;
+use anyhow::Result;
+
+pub struct DryRunCommand;
+
+#[async_trait::async_trait]
+impl SlashCommand for DryRunCommand {
+    fn name(&self) -> &str {
+        "dryrun"
+    }
+
+    fn description(&self) -> &str {
+        "Let mutating tools describe their actions without executing them"
+    }
+
+    fn usage(&self) -> &str {
+        "/dryrun [on | off | clear]"
+    }
+
+    fn category(&self) -> CommandCategory {
+        CommandCategory::System
+    }
+
+    fn validate_args(&self, args: &str) -> Result<()> {
+        match args.trim() {
+            "" | "on" | "off" | "clear" => Ok(()),
+            _ => anyhow::bail!("Usage: {}", self.usage()),
+        }
+    }
+
+    async fn execute(&self, args: &str, _ctx: &CommandContext) -> Result<CommandResult> {
+        match args.trim() {
+            "on" => {
+                dry_run::clear();
+                dry_run::set_enabled(true);
+                return Ok(CommandResult::success(
+                    "🧪 Simulación activada: escribir archivos, la shell, git y los reemplazos solo describirán lo que harían. Revisa el plan con /dryrun",
+                )
+                .with_metadata("dry_run", "on"));
+            }
+            "off" => {
+                dry_run::set_enabled(false);
+                let planned = dry_run::planned().len();
+                return Ok(CommandResult::success(format!(
+                    "▶️ Simulación desactivada: las herramientas vuelven a ejecutar ({} acciones quedaron en el plan)",
+                    planned
+                ))
+                .with_metadata("dry_run", "off"));
+            }
+            "clear" => {
+                dry_run::clear();
+                return Ok(CommandResult::success("🧹 Plan de simulación vacío"));
+            }
+            _ => {}
+        }
+
+        let planned = dry_run::planned();
+        let mut output = format!(
+            "🧪 Simulación {}\n",
+            if dry_run::enabled() { "activada" } else { "desactivada" }
+        );
+        if planned.is_empty() {
+            output.push_str("\nNinguna acción planeada todavía.");
+        } else {
+            output.push_str(&format!("\nAcciones planeadas ({}):\n", planned.len()));
+            for (i, action) in planned.iter().enumerate() {
+                output.push_str(&format!("\n{}. {} {}\n", i + 1, action.tool, action.summary));
+                if !action.details.is_empty() {
+                    output.push_str(&action.details);
+                    output.push('\n');
+                }
+            }
+        }
+        output.push_str("\nUsa /dryrun on|off para cambiar el modo y /dryrun clear para vaciar el plan.");
+        Ok(CommandResult::success(output))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_args() {
+        assert!(DryRunCommand.validate_args("").is_ok());
+        assert!(DryRunCommand.validate_args(" on ").is_ok());
+        assert!(DryRunCommand.validate_args("clear").is_ok());
+        assert!(DryRunCommand.validate_args("maybe").is_err());
+    }
+}
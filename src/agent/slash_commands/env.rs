@@ -0,0 +1,169 @@
+//! Env Command - Session-scoped environment variables for shell and http tools
+//!
+//! Variables are kept out of the global process env and stored with the
+//! session; `/try` experiments start with a copy of the chat's variables.
+
+use super::{CommandCategory, CommandContext, CommandResult, SlashCommand};
+use anyhow::Result;
+
+pub struct EnvCommand;
+
+/// Same rules as a POSIX shell variable name
+fn is_valid_key(key: &str) -> bool {
+    let mut chars = key.chars();
+    matches!(chars.next(), Some(c) if c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Names that usually hold credentials
+fn is_secret_key(key: &str) -> bool {
+    const MARKERS: [&str; 7] = ["TOKEN", "SECRET", "PASSWORD", "PASSWD", "KEY", "AUTH", "CREDENTIAL"];
+    let upper = key.to_ascii_uppercase();
+    MARKERS.iter().any(|marker| upper.contains(marker))
+}
+
+/// Value as shown to the user: secrets keep only their last 4 characters
+fn display_value(key: &str, value: &str) -> String {
+    if !is_secret_key(key) {
+        return value.to_string();
+    }
+    let count = value.chars().count();
+    if count <= 8 {
+        "********".to_string()
+    } else {
+        let tail: String = value.chars().skip(count - 4).collect();
+        format!("********{}", tail)
+    }
+}
+
+#[async_trait::async_trait]
+impl SlashCommand for EnvCommand {
+    fn name(&self) -> &str {
+        "env"
+    }
+
+    fn description(&self) -> &str {
+        "Manage session environment variables for shell and http tools"
+    }
+
+    fn usage(&self) -> &str {
+        "/env [list] | /env set KEY=value | /env unset KEY"
+    }
+
+    fn category(&self) -> CommandCategory {
+        CommandCategory::System
+    }
+
+    fn validate_args(&self, args: &str) -> Result<()> {
+        let (action, rest) = args.split_once(char::is_whitespace).unwrap_or((args, ""));
+        let rest = rest.trim();
+
+        match action {
+            "" | "list" => Ok(()),
+            "set" => match rest.split_once('=') {
+                Some((key, _)) if is_valid_key(key) => Ok(()),
+                _ => anyhow::bail!("Usage: /env set KEY=value"),
+            },
+            "unset" if is_valid_key(rest) => Ok(()),
+            "unset" => anyhow::bail!("Usage: /env unset KEY"),
+            _ => anyhow::bail!("Usage: {}", self.usage()),
+        }
+    }
+
+    async fn execute(&self, args: &str, ctx: &CommandContext) -> Result<CommandResult> {
+        let (action, rest) = args.split_once(char::is_whitespace).unwrap_or((args, ""));
+        let rest = rest.trim();
+
+        match action {
+            "set" => {
+                let (key, value) = rest.split_once('=').unwrap_or((rest, ""));
+                if let Some(session) = &ctx.session {
+                    session.db.set_session_env(&session.session_id, key, value).await?;
+                }
+                ctx.state
+                    .lock()
+                    .await
+                    .env_vars
+                    .insert(key.to_string(), value.to_string());
+
+                Ok(CommandResult::success(format!("✅ {}={}", key, display_value(key, value)))
+                    .with_metadata("key", key))
+            }
+            "unset" => {
+                if let Some(session) = &ctx.session {
+                    session.db.unset_session_env(&session.session_id, rest).await?;
+                }
+                let removed = ctx.state.lock().await.env_vars.remove(rest).is_some();
+
+                if removed {
+                    Ok(CommandResult::success(format!("🗑️ {} eliminada", rest)).with_metadata("key", rest))
+                } else {
+                    Ok(CommandResult::error(format!("{} no está definida en esta sesión", rest)))
+                }
+            }
+            _ => {
+                let state = ctx.state.lock().await;
+                if state.env_vars.is_empty() {
+                    return Ok(CommandResult::success(
+                        "No hay variables de sesión. Usa /env set KEY=value",
+                    ));
+                }
+
+                let mut output = String::from("🌱 Variables de sesión:\n\n");
+                for (key, value) in &state.env_vars {
+                    output.push_str(&format!("  {}={}\n", key, display_value(key, value)));
+                }
+                Ok(CommandResult::success(output))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent::state::create_shared_state;
+    use crate::db::{Database, Session, SessionStore};
+    use crate::tools::registry::ToolRegistry;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_env_set_list_unset_persists() {
+        let db = Database::in_memory().await.unwrap();
+        db.create_session(&Session::new("s1", "fast", "heavy"))
+            .await
+            .unwrap();
+        let ctx = CommandContext {
+            tools: Arc::new(ToolRegistry::new()),
+            state: create_shared_state(),
+            working_dir: ".".to_string(),
+            session: Some(SessionStore {
+                db: db.clone(),
+                session_id: "s1".to_string(),
+            }),
+        };
+
+        assert!(EnvCommand.validate_args("set 1BAD=x").is_err());
+        assert!(EnvCommand.validate_args("set API_BASE=http://localhost:3000").is_ok());
+
+        EnvCommand
+            .execute("set API_BASE=http://localhost:3000", &ctx)
+            .await
+            .unwrap();
+        let listed = EnvCommand.execute("list", &ctx).await.unwrap();
+        assert!(listed.output.contains("API_BASE=http://localhost:3000"));
+        assert_eq!(db.get_session_env("s1").await.unwrap().len(), 1);
+
+        let removed = EnvCommand.execute("unset API_BASE", &ctx).await.unwrap();
+        assert!(removed.success);
+        assert!(ctx.state.lock().await.env_vars.is_empty());
+        assert!(db.get_session_env("s1").await.unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_secret_values_are_masked() {
+        assert_eq!(display_value("API_BASE", "http://localhost:3000"), "http://localhost:3000");
+        assert_eq!(display_value("GITHUB_TOKEN", "ghp_abcdefghijkl"), "********ijkl");
+        assert_eq!(display_value("db_password", "hunter2"), "********");
+    }
+}
@@ -0,0 +1,42 @@
+//! Estimate Command - Impact and effort of a proposed change
+//!
+//! The report itself is built by the router, which owns the RAPTOR service
+//! (see `crate::agent::estimation`); this command validates the description
+//! and hands it over with the "estimate" action.
+
+use super::{CommandCategory, CommandContext, CommandResult, SlashCommand};
+use anyhow::Result;
+
+pub struct EstimateCommand;
+
+#[async_trait::async_trait]
+impl SlashCommand for EstimateCommand {
+    fn name(&self) -> &str {
+        "estimate"
+    }
+
+    fn description(&self) -> &str {
+        "List the files and tests a change would touch and classify its effort (S/M/L)"
+    }
+
+    fn usage(&self) -> &str {
+        "/estimate <description>"
+    }
+
+    fn category(&self) -> CommandCategory {
+        CommandCategory::Code
+    }
+
+    fn validate_args(&self, args: &str) -> Result<()> {
+        if args.trim().is_empty() {
+            anyhow::bail!("Describe the change to estimate: {}", self.usage());
+        }
+        Ok(())
+    }
+
+    async fn execute(&self, args: &str, _ctx: &CommandContext) -> Result<CommandResult> {
+        Ok(CommandResult::success(String::new())
+            .with_metadata("action", "estimate")
+            .with_metadata("description", args.trim()))
+    }
+}
@@ -0,0 +1,190 @@
+//! Examples Command - Example answers that show the model the expected style
+//!
+//! An example is a question and the answer (or diff) the user wants for it.
+//! The ones closest to each question are given to the model as few-shot
+//! examples; `/examples save` keeps the last answer of the conversation.
+
+use super::{CommandCategory, CommandContext, CommandResult, SlashCommand};
+use crate::agent::state::MessageRole;
+use crate::tools::MemoryError;
+use anyhow::Result;
+use std::path::Path;
+
+/// Characters of an answer shown by the list
+const PREVIEW_CHARS: usize = 80;
+
+pub struct ExamplesCommand;
+
+/// `<question> => <answer>`, the answer being `@file` to read it from a file
+fn parse_add(args: &str, working_dir: &str) -> Result<(String, String), String> {
+    let (prompt, response) = args
+        .split_once("=>")
+        .ok_or_else(|| "Expected <question> => <answer>".to_string())?;
+    let response = response.trim();
+    let response = match response.strip_prefix('@') {
+        Some(file) => std::fs::read_to_string(Path::new(working_dir).join(file))
+            .map_err(|e| format!("Cannot read {}: {}", file, e))?,
+        None => response.to_string(),
+    };
+    Ok((prompt.trim().to_string(), response))
+}
+
+fn preview(text: &str) -> String {
+    let line = text.lines().find(|l| !l.trim().is_empty()).unwrap_or("").trim();
+    if line.chars().count() > PREVIEW_CHARS || text.trim().lines().count() > 1 {
+        format!("{}…", line.chars().take(PREVIEW_CHARS).collect::<String>())
+    } else {
+        line.to_string()
+    }
+}
+
+#[async_trait::async_trait]
+impl SlashCommand for ExamplesCommand {
+    fn name(&self) -> &str {
+        "examples"
+    }
+
+    fn description(&self) -> &str {
+        "Curate example answers the model imitates in this project"
+    }
+
+    fn usage(&self) -> &str {
+        "/examples [list] | /examples add <question> => <answer|@file> | /examples save | /examples remove <id>"
+    }
+
+    fn category(&self) -> CommandCategory {
+        CommandCategory::Context
+    }
+
+    fn validate_args(&self, args: &str) -> Result<()> {
+        let (action, rest) = args.split_once(char::is_whitespace).unwrap_or((args, ""));
+        match action {
+            "" | "list" | "save" => Ok(()),
+            "add" if rest.contains("=>") => Ok(()),
+            "add" => anyhow::bail!("Usage: /examples add <question> => <answer|@file>"),
+            "remove" if rest.trim().trim_start_matches('#').parse::<i64>().is_ok() => Ok(()),
+            "remove" => anyhow::bail!("Usage: /examples remove <id>"),
+            _ => anyhow::bail!("Usage: {}", self.usage()),
+        }
+    }
+
+    async fn execute(&self, args: &str, ctx: &CommandContext) -> Result<CommandResult> {
+        let memory = &ctx.tools.memory;
+        if !memory.has_database() {
+            return Ok(CommandResult::error("Los ejemplos necesitan una sesión con base de datos"));
+        }
+        let (action, rest) = args.split_once(char::is_whitespace).unwrap_or((args, ""));
+
+        let (prompt, response) = match action {
+            "add" => match parse_add(rest, &ctx.working_dir) {
+                Ok(pair) => pair,
+                Err(e) => return Ok(CommandResult::error(e)),
+            },
+            "save" => {
+                // The last question typed (not a command) and the answer to it
+                let state = ctx.state.lock().await;
+                let question = state
+                    .messages
+                    .iter()
+                    .rposition(|m| m.role == MessageRole::User && !m.content.trim_start().starts_with('/'));
+                let pair = question.and_then(|i| {
+                    let answer = state.messages[i + 1..].iter().find(|m| m.role == MessageRole::Assistant)?;
+                    Some((state.messages[i].content.clone(), answer.content.clone()))
+                });
+                match pair {
+                    Some(pair) => pair,
+                    None => {
+                        return Ok(CommandResult::error(
+                            "No hay ninguna respuesta que guardar: haz primero una pregunta",
+                        ))
+                    }
+                }
+            }
+            "remove" => {
+                let id: i64 = rest.trim().trim_start_matches('#').parse()?;
+                return match memory.remove_example(&ctx.working_dir, id).await {
+                    Ok(()) => Ok(CommandResult::success(format!("🗑️ Ejemplo #{} eliminado", id))),
+                    Err(MemoryError::ExampleNotFound(_)) => Ok(CommandResult::error(format!(
+                        "No hay un ejemplo #{} en este proyecto",
+                        id
+                    ))),
+                    Err(e) => Err(e.into()),
+                };
+            }
+            _ => {
+                let examples = memory.examples(&ctx.working_dir).await?;
+                if examples.is_empty() {
+                    return Ok(CommandResult::success(
+                        "No hay ejemplos en este proyecto. Usa /examples add <pregunta> => <respuesta> \
+                         o /examples save tras una buena respuesta",
+                    ));
+                }
+                let mut output = String::from("📝 Ejemplos de respuesta del proyecto:\n\n");
+                for example in &examples {
+                    output.push_str(&format!(
+                        "  #{} {}\n      → {} (usado {} {})\n",
+                        example.id,
+                        example.prompt,
+                        preview(&example.response),
+                        example.uses,
+                        if example.uses == 1 { "vez" } else { "veces" }
+                    ));
+                }
+                output.push_str("\nLos más parecidos a cada pregunta se dan al modelo como ejemplo.");
+                return Ok(CommandResult::success(output));
+            }
+        };
+
+        match memory.add_example(&ctx.working_dir, &prompt, &response).await {
+            Ok(id) => Ok(CommandResult::success(format!("📝 Ejemplo #{} guardado: {}", id, prompt.trim()))
+                .with_metadata("id", id.to_string())),
+            Err(e @ (MemoryError::ExampleTooLong(_) | MemoryError::MissingArgument(_))) => {
+                Ok(CommandResult::error(e.to_string()))
+            }
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent::state::{create_shared_state, Message};
+    use crate::db::Database;
+    use crate::tools::registry::ToolRegistry;
+    use crate::tools::MemoryTool;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_add_save_list_remove() {
+        let mut tools = ToolRegistry::new();
+        tools.memory = Arc::new(MemoryTool::new().with_database(Database::in_memory().await.unwrap()));
+        let ctx = CommandContext {
+            tools: Arc::new(tools),
+            state: create_shared_state(),
+            working_dir: ".".to_string(),
+            session: None,
+        };
+
+        assert!(ExamplesCommand.validate_args("add no arrow").is_err());
+        assert!(ExamplesCommand.validate_args("remove #2").is_ok());
+        assert!(!ExamplesCommand.execute("save", &ctx).await.unwrap().success);
+
+        let added = ExamplesCommand.execute("add add an endpoint => Use an axum Router", &ctx).await.unwrap();
+        assert!(added.success);
+        {
+            let mut state = ctx.state.lock().await;
+            state.add_message(Message::user("how do we log errors?"));
+            state.add_message(Message::assistant("With `log_warn!`, never println", "qwen3:8b"));
+            state.add_message(Message::user("/examples save"));
+        }
+        assert!(ExamplesCommand.execute("save", &ctx).await.unwrap().success);
+
+        let listed = ExamplesCommand.execute("", &ctx).await.unwrap();
+        assert!(listed.output.contains("#1 add an endpoint\n      → Use an axum Router (usado 0 veces)"));
+        assert!(listed.output.contains("#2 how do we log errors?"));
+
+        assert!(ExamplesCommand.execute("remove 1", &ctx).await.unwrap().success);
+        assert!(!ExamplesCommand.execute("remove 1", &ctx).await.unwrap().success);
+    }
+}
@@ -0,0 +1,121 @@
+//! Export Command - Write the current session to a file
+//!
+//! HTML gives a standalone page to share an investigation, with highlighted
+//! code and citations linked to the repository; Markdown is the plain
+//! transcript also exported from the session browser.
+
+use super::{CommandCategory, CommandContext, CommandResult, SlashCommand};
+use crate::agent::html_export::{self, RepoLinks};
+use crate::agent::session_search;
+use anyhow::Result;
+use std::path::{Path, PathBuf};
+
+pub struct ExportCommand;
+
+#[async_trait::async_trait]
+impl SlashCommand for ExportCommand {
+    fn name(&self) -> &str {
+        "export"
+    }
+
+    fn description(&self) -> &str {
+        "Export the current session as a shareable HTML page or Markdown"
+    }
+
+    fn usage(&self) -> &str {
+        "/export [html|md] [<path>]"
+    }
+
+    fn category(&self) -> CommandCategory {
+        CommandCategory::Context
+    }
+
+    fn validate_args(&self, args: &str) -> Result<()> {
+        match args.split_whitespace().collect::<Vec<_>>().as_slice() {
+            [] | ["html" | "md"] | ["html" | "md", _] => Ok(()),
+            [format] | [format, _] => anyhow::bail!("Unknown format '{}': use html or md", format),
+            _ => anyhow::bail!("Usage: {}", self.usage()),
+        }
+    }
+
+    async fn execute(&self, args: &str, ctx: &CommandContext) -> Result<CommandResult> {
+        let Some(session) = &ctx.session else {
+            return Ok(CommandResult::error("La exportación necesita una sesión con base de datos"));
+        };
+        let parts: Vec<&str> = args.split_whitespace().collect();
+        let format = parts.first().copied().unwrap_or("html");
+        let (db, id) = (&session.db, session.session_id.as_str());
+
+        let summary = db.get_session_summary(db.get_session(id).await?).await?;
+        let messages = db.get_session_messages(id).await?;
+        if messages.is_empty() {
+            return Ok(CommandResult::error("La sesión aún no tiene mensajes que exportar"));
+        }
+
+        let working_dir = Path::new(&ctx.working_dir);
+        let (content, linked) = if format == "md" {
+            (session_search::transcript(&summary, &messages), false)
+        } else {
+            let links = RepoLinks::detect(working_dir).await;
+            (html_export::render(&summary, &messages, links.as_ref()), links.is_some())
+        };
+        let path = match parts.get(1) {
+            Some(path) => working_dir.join(path),
+            None => default_path(working_dir, id, format),
+        };
+        std::fs::write(&path, content)?;
+
+        let mut output = format!("📄 Sesión exportada a {}", path.display());
+        if format == "html" && !linked {
+            output.push_str("\n\nSin remoto `origin`: las citas de archivos quedan sin enlace");
+        }
+        Ok(CommandResult::success(output).with_metadata("path", path.display().to_string()))
+    }
+}
+
+/// `neuro-session-<id>.<format>` in the working directory, as the session browser names exports
+fn default_path(working_dir: &Path, session_id: &str, format: &str) -> PathBuf {
+    working_dir.join(format!(
+        "neuro-session-{}.{}",
+        session_id.chars().take(8).collect::<String>(),
+        format
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent::state::create_shared_state;
+    use crate::db::{Database, DbMessage, Session, SessionStore};
+    use crate::tools::registry::ToolRegistry;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_export_session() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = Database::in_memory().await.unwrap();
+        db.create_session(&Session::new("s1", "fast", "heavy")).await.unwrap();
+        let ctx = CommandContext {
+            tools: Arc::new(ToolRegistry::new()),
+            state: create_shared_state(),
+            working_dir: dir.path().to_string_lossy().to_string(),
+            session: Some(SessionStore {
+                db: db.clone(),
+                session_id: "s1".to_string(),
+            }),
+        };
+
+        assert!(ExportCommand.validate_args("html out.html").is_ok());
+        assert!(ExportCommand.validate_args("pdf").is_err());
+        assert!(!ExportCommand.execute("", &ctx).await.unwrap().success);
+
+        db.create_message(&DbMessage::new("m1", "s1", "user", "¿Qué hace `main`?")).await.unwrap();
+        let result = ExportCommand.execute("", &ctx).await.unwrap();
+        assert!(result.success);
+        let html = std::fs::read_to_string(dir.path().join("neuro-session-s1.html")).unwrap();
+        assert!(html.contains("<code>main</code>"));
+
+        assert!(ExportCommand.execute("md notes.md", &ctx).await.unwrap().success);
+        assert!(std::fs::read_to_string(dir.path().join("notes.md")).unwrap().starts_with("# ¿Qué hace"));
+    }
+}
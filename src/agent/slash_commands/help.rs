@@ -39,23 +39,40 @@ impl SlashCommand for HelpCommand {
         output.push_str("## 📝 Code\n");
         output.push_str("- `/code-review [path]` - Automated code review\n");
         output.push_str("- `/analyze <path>` - Deep code analysis\n");
-        output.push_str("- `/refactor <op> <path>` - Refactoring operations\n");
+        output.push_str("- `/callgraph [function]` - Who calls a function and what it calls\n");
+        output.push_str("- `/deadcode [path] [--public]` - Functions and types that are never referenced\n");
+        output.push_str("- `/refactor <op> <path>` - Refactoring operations (`rename_symbol` renames through the AST)\n");
         output.push_str("- `/format <path>` - Format code\n");
-        output.push_str("- `/deps [action]` - Manage dependencies\n\n");
+        output.push_str("- `/deps [action]` - Manage dependencies\n");
+        output.push_str("- `/estimate <description>` - Affected files, tests to update and effort (S/M/L) of a change\n\n");
         
         // Testing
         output.push_str("## 🧪 Testing\n");
-        output.push_str("- `/test [pattern]` - Run tests\n\n");
+        output.push_str("- `/test [pattern]` - Run tests\n");
+        output.push_str("- `/test --detect-flaky [--runs N] [--parallel N]` - Rerun tests and report flaky ones\n");
+        output.push_str("- `/profile [--with flamegraph|py-spy|node] <command>` - Profile a command and suggest optimizations for its hotspots\n\n");
         
         // Git
         output.push_str("## 🔀 Git\n");
         output.push_str("- `/commit [message]` - Commit changes\n");
-        output.push_str("- `/commit-push-pr` - Full git workflow\n\n");
+        output.push_str("- `/commit-push-pr` - Full git workflow\n");
+        output.push_str("- `/owners [path]` - Who knows a file or directory best, to ask for review\n\n");
         
         // Context
         output.push_str("## 🔍 Context & Search\n");
         output.push_str("- `/search <query>` - Semantic code search\n");
         output.push_str("- `/context` - Show project info\n");
+        output.push_str("- `/remember <fact>` - Remember a fact for future sessions\n");
+        output.push_str("- `/memories [forget <id>]` - Remembered facts of this project\n");
+        output.push_str("- `/examples [add|save|remove]` - Example answers the model imitates\n");
+        output.push_str("- `/artifacts [list [kind]] | open|pin|unpin|rm <id>` - Large outputs: runs, diffs, reports, docs, exports (`@artifact:<id>` attaches one)\n");
+        output.push_str("- `/why [rules | <query>]` - How the last request was routed and which chunks retrieval preferred\n");
+        output.push_str("- `/sources [irrelevant <n>]` - Sources of the last answer; irrelevant ones rank lower for similar questions\n");
+        output.push_str("- `/tag [<tag>...] | rm <tag>` - Tag the current session\n");
+        output.push_str("- `/export [html|md] [<path>]` - Export this session; HTML is a standalone page with links to the repository\n");
+        output.push_str("- `/sessions [text] [#tag]` - Browse, open, resume or export past sessions (TUI)\n");
+        output.push_str("- `/retry [diff]` - Regenerate the last answer and show what changed (TUI)\n");
+        output.push_str("- `/translate <lang> | off` - Show the last answer in another language; history keeps the original (TUI)\n");
         output.push_str("- `/docs [path]` - Generate documentation\n\n");
         
         // System
@@ -63,7 +80,18 @@ impl SlashCommand for HelpCommand {
         output.push_str("- `/shell <cmd>` - Execute shell command\n");
         output.push_str("- `/plan <task>` - Generate execution plan\n");
         output.push_str("- `/mode <ask|build|plan>` - Change mode\n");
+        output.push_str("- `/dryrun [on|off|clear]` - Mutating tools describe their actions instead of running them\n");
+        output.push_str("- `/new <template> <name>` - Scaffold a project\n");
+        output.push_str("- `/recipe run <name>` - Run a multi-step recipe\n");
+        output.push_str("- `/start <template> [text]` - Start from a conversation template\n");
+        output.push_str("- `/config [doctor]` - Config layers and conflicts\n");
+        output.push_str("- `/network` - Network policy and blocked requests\n");
+        output.push_str("- `/preferences [set|unset ...]` - Code style and answer preferences\n");
+        output.push_str("- `/tools [enable|disable <name>]` - Tools available to the model\n");
+        output.push_str("- `/cd <path>` - Change the working directory\n");
+        output.push_str("- `/roots [add|remove <path>]` - Workspace and read-only roots\n");
         output.push_str("- `/reindex` - Rebuild code index\n");
+        output.push_str("- `/continue` - Resume a turn that ran out of time\n");
         output.push_str("- `/help [cmd]` - Show this help\n\n");
         
         output.push_str("---\n💡 Tip: Use Tab for autocompletion");
@@ -0,0 +1,77 @@
+//! Logs Command - Tail a log source and optionally ask the model to diagnose it
+
+use super::{CommandCategory, CommandContext, CommandResult, SlashCommand};
+use crate::tools::{LogSource, TailLogsArgs, DEFAULT_DIGEST_CHARS};
+use anyhow::Result;
+
+pub struct LogsCommand;
+
+#[async_trait::async_trait]
+impl SlashCommand for LogsCommand {
+    fn name(&self) -> &str {
+        "logs"
+    }
+
+    fn description(&self) -> &str {
+        "Tail logs (file, journal:<unit>, docker:<container>) and condense them"
+    }
+
+    fn usage(&self) -> &str {
+        "/logs <source> [seconds] [--diagnose] - Follow a log source, --diagnose asks the model"
+    }
+
+    fn category(&self) -> CommandCategory {
+        CommandCategory::System
+    }
+
+    fn validate_args(&self, args: &str) -> Result<()> {
+        if args.trim().is_empty() {
+            anyhow::bail!("Usage: {}", self.usage());
+        }
+        Ok(())
+    }
+
+    async fn execute(&self, args: &str, ctx: &CommandContext) -> Result<CommandResult> {
+        let mut source = None;
+        let mut follow_secs = 5;
+        let mut diagnose = false;
+        for part in args.split_whitespace() {
+            if part == "--diagnose" {
+                diagnose = true;
+            } else if let (Some(_), Ok(secs)) = (source.as_ref(), part.parse::<u64>()) {
+                follow_secs = secs;
+            } else if source.is_none() {
+                source = Some(part.to_string());
+            }
+        }
+        let Some(source) = source else {
+            return Ok(CommandResult::error(format!("Usage: {}", self.usage())));
+        };
+
+        let source = LogSource::parse(&source).relative_to(&ctx.working_dir);
+        let tail_args = TailLogsArgs {
+            source: source.to_string(),
+            follow_secs,
+            lines: 500,
+        };
+
+        let digest = match ctx.tools.log_tail.digest(&tail_args).await {
+            Ok(digest) => digest.render(DEFAULT_DIGEST_CHARS),
+            Err(e) => return Ok(CommandResult::error(format!("Error leyendo logs: {}", e))),
+        };
+
+        let mut result =
+            CommandResult::success(digest.clone()).with_metadata("source", source.to_string());
+        if diagnose {
+            result = result.with_metadata("action", "ask").with_metadata(
+                "prompt",
+                format!(
+                    "Analiza estos logs de {} y diagnostica la causa probable de los errores. \
+                     Sugiere pasos concretos para corregirlos.\n\n{}",
+                    source, digest
+                ),
+            );
+        }
+        Ok(result)
+    }
+}
@@ -0,0 +1,147 @@
+//! Memory Commands - Facts remembered for the project across sessions
+//!
+//! `/remember` stores a fact the model gets in future system prompts when it is
+//! relevant to the question; `/memories` lists and forgets them.
+
+use super::{CommandCategory, CommandContext, CommandResult, SlashCommand};
+use crate::tools::MemoryError;
+use anyhow::Result;
+
+pub struct RememberCommand;
+
+#[async_trait::async_trait]
+impl SlashCommand for RememberCommand {
+    fn name(&self) -> &str {
+        "remember"
+    }
+
+    fn description(&self) -> &str {
+        "Remember a fact about this project for future sessions"
+    }
+
+    fn usage(&self) -> &str {
+        "/remember <fact>  (e.g. /remember we deploy with just, not make)"
+    }
+
+    fn category(&self) -> CommandCategory {
+        CommandCategory::Context
+    }
+
+    fn validate_args(&self, args: &str) -> Result<()> {
+        if args.trim().is_empty() {
+            anyhow::bail!("Usage: {}", self.usage());
+        }
+        Ok(())
+    }
+
+    async fn execute(&self, args: &str, ctx: &CommandContext) -> Result<CommandResult> {
+        if !ctx.tools.memory.has_database() {
+            return Ok(CommandResult::error("La memoria necesita una sesión con base de datos"));
+        }
+        let id = ctx.tools.memory.remember(&ctx.working_dir, args).await?;
+        Ok(CommandResult::success(format!("🧠 Recordado (#{}): {}", id, args.trim()))
+            .with_metadata("id", id.to_string()))
+    }
+}
+
+pub struct MemoriesCommand;
+
+#[async_trait::async_trait]
+impl SlashCommand for MemoriesCommand {
+    fn name(&self) -> &str {
+        "memories"
+    }
+
+    fn description(&self) -> &str {
+        "List or forget the facts remembered for this project"
+    }
+
+    fn usage(&self) -> &str {
+        "/memories [list] | /memories forget <id>"
+    }
+
+    fn category(&self) -> CommandCategory {
+        CommandCategory::Context
+    }
+
+    fn validate_args(&self, args: &str) -> Result<()> {
+        let (action, rest) = args.split_once(char::is_whitespace).unwrap_or((args, ""));
+        match action {
+            "" | "list" => Ok(()),
+            "forget" if rest.trim().trim_start_matches('#').parse::<i64>().is_ok() => Ok(()),
+            "forget" => anyhow::bail!("Usage: /memories forget <id>"),
+            _ => anyhow::bail!("Usage: {}", self.usage()),
+        }
+    }
+
+    async fn execute(&self, args: &str, ctx: &CommandContext) -> Result<CommandResult> {
+        if !ctx.tools.memory.has_database() {
+            return Ok(CommandResult::error("La memoria necesita una sesión con base de datos"));
+        }
+        let (action, rest) = args.split_once(char::is_whitespace).unwrap_or((args, ""));
+
+        if action == "forget" {
+            let id: i64 = rest.trim().trim_start_matches('#').parse()?;
+            return match ctx.tools.memory.forget(&ctx.working_dir, id).await {
+                Ok(()) => Ok(CommandResult::success(format!("🗑️ Memoria #{} olvidada", id))),
+                Err(MemoryError::NotFound(_)) => Ok(CommandResult::error(format!(
+                    "No hay una memoria #{} en este proyecto",
+                    id
+                ))),
+                Err(e) => Err(e.into()),
+            };
+        }
+
+        let memories = ctx.tools.memory.list(&ctx.working_dir).await?;
+        if memories.is_empty() {
+            return Ok(CommandResult::success(
+                "No hay memorias en este proyecto. Usa /remember <dato>",
+            ));
+        }
+        let mut output = String::from("🧠 Memorias del proyecto:\n\n");
+        for memory in &memories {
+            output.push_str(&format!("  #{} {}\n", memory.id, memory.content));
+        }
+        output.push_str("\nUsa /memories forget <id> para olvidar una.");
+        Ok(CommandResult::success(output))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent::state::create_shared_state;
+    use crate::db::Database;
+    use crate::tools::registry::ToolRegistry;
+    use crate::tools::MemoryTool;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_remember_list_forget() {
+        let mut tools = ToolRegistry::new();
+        tools.memory = Arc::new(MemoryTool::new().with_database(Database::in_memory().await.unwrap()));
+        let ctx = CommandContext {
+            tools: Arc::new(tools),
+            state: create_shared_state(),
+            working_dir: ".".to_string(),
+            session: None,
+        };
+
+        assert!(RememberCommand.validate_args("  ").is_err());
+        assert!(MemoriesCommand.validate_args("forget x").is_err());
+        assert!(MemoriesCommand.validate_args("forget #1").is_ok());
+
+        let remembered = RememberCommand
+            .execute("we deploy with just, not make", &ctx)
+            .await
+            .unwrap();
+        assert!(remembered.success);
+        let listed = MemoriesCommand.execute("", &ctx).await.unwrap();
+        assert!(listed.output.contains("#1 we deploy with just, not make"));
+
+        assert!(MemoriesCommand.execute("forget 1", &ctx).await.unwrap().success);
+        assert!(!MemoriesCommand.execute("forget 1", &ctx).await.unwrap().success);
+        let listed = MemoriesCommand.execute("list", &ctx).await.unwrap();
+        assert!(listed.output.starts_with("No hay memorias"));
+    }
+}
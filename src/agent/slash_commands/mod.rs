@@ -4,14 +4,19 @@
 //! Inspired by Claude Code's plugin architecture but adapted for neuro-agent
 
 use crate::agent::state::SharedState;
+use crate::config::{AppConfig, ProjectConfig};
+use crate::db::SessionStore;
 use crate::tools::registry::ToolRegistry;
 use anyhow::Result;
 use std::collections::HashMap;
+use std::path::Path;
 use std::sync::Arc;
 
 // Command modules
 mod code_review;
 mod analyze;
+mod callgraph;
+mod deadcode;
 mod refactor;
 mod format;
 mod test;
@@ -19,17 +24,48 @@ mod docs;
 mod commit;
 mod dependencies;
 mod search;
+mod replace;
+mod resolve;
 mod context;
 mod plan;
 mod shell;
 mod reindex;
+mod continue_turn;
 mod mode;
 mod help;
 mod raptor_diagnose;
+mod env;
+mod memory;
+mod examples;
+mod artifacts;
+mod preferences;
+mod logs;
+mod proc;
+mod tfplan;
+mod preview;
+mod schema;
+mod scaffold;
+mod recipe;
+mod start;
+mod config;
+mod network;
+mod tools;
+mod workspace;
+mod why;
+mod sources;
+mod tag;
+mod export;
+mod estimate;
+mod owners;
+mod profile;
+mod dryrun;
+mod custom;
 
 // Re-exports
 pub use code_review::CodeReviewCommand;
 pub use analyze::AnalyzeCommand;
+pub use callgraph::CallGraphCommand;
+pub use deadcode::DeadCodeCommand;
 pub use refactor::RefactorCommand;
 pub use format::FormatCommand;
 pub use test::TestCommand;
@@ -37,13 +73,42 @@ pub use docs::DocsCommand;
 pub use commit::{CommitCommand, CommitPushPrCommand};
 pub use dependencies::DependenciesCommand;
 pub use search::SearchCommand;
+pub use replace::ReplaceCommand;
+pub use resolve::ResolveCommand;
 pub use context::ContextCommand;
 pub use plan::PlanCommand;
 pub use shell::ShellCommand;
 pub use reindex::ReindexCommand;
+pub use continue_turn::ContinueCommand;
 pub use mode::ModeCommand;
 pub use help::HelpCommand;
 pub use raptor_diagnose::RaptorDiagnoseCommand;
+pub use env::EnvCommand;
+pub use memory::{MemoriesCommand, RememberCommand};
+pub use examples::ExamplesCommand;
+pub use artifacts::ArtifactsCommand;
+pub use preferences::PreferencesCommand;
+pub use logs::LogsCommand;
+pub use proc::ProcCommand;
+pub use tfplan::TfPlanCommand;
+pub use preview::PreviewCommand;
+pub use schema::SchemaCommand;
+pub use scaffold::ScaffoldCommand;
+pub use recipe::RecipeCommand;
+pub use start::StartCommand;
+pub use config::ConfigCommand;
+pub use network::NetworkCommand;
+pub use tools::{set_tool_enabled, ToolsCommand};
+pub use workspace::{CdCommand, RootsCommand};
+pub use why::WhyCommand;
+pub use sources::SourcesCommand;
+pub use tag::TagCommand;
+pub use export::ExportCommand;
+pub use estimate::EstimateCommand;
+pub use owners::OwnersCommand;
+pub use profile::ProfileCommand;
+pub use dryrun::DryRunCommand;
+pub use custom::CustomCommand;
 
 /// Context passed to slash commands during execution
 #[derive(Clone)]
@@ -51,6 +116,8 @@ pub struct CommandContext {
     pub tools: Arc<ToolRegistry>,
     pub state: SharedState,
     pub working_dir: String,
+    /// Current session in the database, if one is attached
+    pub session: Option<SessionStore>,
 }
 
 /// Trait that all slash commands must implement
@@ -151,18 +218,50 @@ impl SlashCommandRegistry {
         // Register all commands
         registry.register(Box::new(CodeReviewCommand));
         registry.register(Box::new(AnalyzeCommand));
+        registry.register(Box::new(CallGraphCommand));
+        registry.register(Box::new(DeadCodeCommand));
         registry.register(Box::new(RefactorCommand));
         registry.register(Box::new(FormatCommand));
         registry.register(Box::new(TestCommand));
+        registry.register(Box::new(ProfileCommand));
         registry.register(Box::new(DocsCommand));
         registry.register(Box::new(CommitCommand));
         registry.register(Box::new(CommitPushPrCommand));
+        registry.register(Box::new(ResolveCommand));
         registry.register(Box::new(DependenciesCommand));
         registry.register(Box::new(SearchCommand));
+        registry.register(Box::new(ReplaceCommand));
         registry.register(Box::new(ContextCommand));
+        registry.register(Box::new(PreviewCommand));
+        registry.register(Box::new(SchemaCommand));
         registry.register(Box::new(PlanCommand));
         registry.register(Box::new(ShellCommand));
+        registry.register(Box::new(EnvCommand));
+        registry.register(Box::new(RememberCommand));
+        registry.register(Box::new(MemoriesCommand));
+        registry.register(Box::new(ExamplesCommand));
+        registry.register(Box::new(ArtifactsCommand));
+        registry.register(Box::new(WhyCommand));
+        registry.register(Box::new(SourcesCommand));
+        registry.register(Box::new(TagCommand));
+        registry.register(Box::new(ExportCommand));
+        registry.register(Box::new(EstimateCommand));
+        registry.register(Box::new(OwnersCommand));
+        registry.register(Box::new(DryRunCommand));
+        registry.register(Box::new(PreferencesCommand));
+        registry.register(Box::new(LogsCommand));
+        registry.register(Box::new(ProcCommand));
+        registry.register(Box::new(TfPlanCommand));
+        registry.register(Box::new(ScaffoldCommand));
+        registry.register(Box::new(RecipeCommand));
+        registry.register(Box::new(StartCommand));
+        registry.register(Box::new(ConfigCommand));
+        registry.register(Box::new(NetworkCommand));
+        registry.register(Box::new(ToolsCommand));
+        registry.register(Box::new(CdCommand));
+        registry.register(Box::new(RootsCommand));
         registry.register(Box::new(ReindexCommand));
+        registry.register(Box::new(ContinueCommand));
         registry.register(Box::new(RaptorDiagnoseCommand));
         registry.register(Box::new(ModeCommand));
         registry.register(Box::new(HelpCommand));
@@ -175,6 +274,39 @@ impl SlashCommandRegistry {
         self.commands.insert(command.name().to_string(), command);
     }
     
+    /// Register the custom commands of the project (`.neuro/commands/`) and of
+    /// the user (`~/.config/neuro/commands/`); returns why any were left out
+    pub fn register_custom(&mut self, working_dir: &Path) -> Vec<String> {
+        let mut dirs = Vec::new();
+        if let Some(dir) = ProjectConfig::discover(working_dir).ok().and_then(|p| p.subdir("commands")) {
+            dirs.push(dir);
+        }
+        if let Some(dir) = AppConfig::config_dir() {
+            dirs.push(dir.join("commands"));
+        }
+
+        let built_in: Vec<String> = self.commands.keys().cloned().collect();
+        let mut notes = Vec::new();
+        for dir in dirs {
+            for command in CustomCommand::load_dir(&dir) {
+                let name = command.name().to_string();
+                if built_in.contains(&name) {
+                    notes.push(format!(
+                        "/{} in {} clashes with a built-in command and was ignored",
+                        name,
+                        command.source().display()
+                    ));
+                } else if self.commands.contains_key(&name) {
+                    // Project commands come first and win over personal ones
+                    notes.push(format!("/{} in {} is shadowed by the project command", name, command.source().display()));
+                } else {
+                    self.register(Box::new(command));
+                }
+            }
+        }
+        notes
+    }
+
     /// Get a command by name
     pub fn get(&self, name: &str) -> Option<&Box<dyn SlashCommand>> {
         self.commands.get(name)
@@ -262,6 +394,7 @@ mod tests {
             tools: Arc::new(ToolRegistry::new()),
             state: create_shared_state(),
             working_dir: std::env::current_dir().unwrap().to_string_lossy().to_string(),
+            session: None,
         };
 
         let result = registry.execute("/raptor-diagnose", &ctx).await;
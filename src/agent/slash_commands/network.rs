@@ -0,0 +1,64 @@
+//! Network Command - Show the outbound network policy and what it blocked
+
+use super::{CommandCategory, CommandContext, CommandResult, SlashCommand};
+use crate::security::network;
+use crate::security::privacy;
+use anyhow::Result;
+
+pub struct NetworkCommand;
+
+#[async_trait::async_trait]
+impl SlashCommand for NetworkCommand {
+    fn name(&self) -> &str {
+        "network"
+    }
+
+    fn description(&self) -> &str {
+        "Show the outbound network policy and blocked requests"
+    }
+
+    fn usage(&self) -> &str {
+        "/network - Show the privacy mode, allowed hosts and blocked requests"
+    }
+
+    fn category(&self) -> CommandCategory {
+        CommandCategory::System
+    }
+
+    async fn execute(&self, _args: &str, _ctx: &CommandContext) -> Result<CommandResult> {
+        let policy = network::network_policy();
+        let mut output = String::from("🌐 Red saliente (sin telemetría)\n");
+        output.push_str(&format!("  Privacidad: {}\n", privacy::privacy_level()));
+        output.push_str(&format!(
+            "  Modo: {}\n",
+            if policy.strict {
+                "estricto (solo hosts permitidos)"
+            } else {
+                "abierto (los hosts no listados se registran)"
+            }
+        ));
+        if policy.allowed_hosts.is_empty() {
+            output.push_str("  Hosts permitidos: solo localhost\n");
+        } else {
+            output.push_str(&format!("  Hosts permitidos: localhost, {}\n", policy.allowed_hosts.join(", ")));
+        }
+
+        let blocked = network::blocked_requests();
+        if blocked.is_empty() {
+            output.push_str("\n✅ Ninguna petición bloqueada");
+        } else {
+            output.push_str(&format!("\n⛔ Peticiones bloqueadas ({}):\n", network::blocked_count()));
+            for request in blocked.iter().rev() {
+                let at: chrono::DateTime<chrono::Local> = request.at.into();
+                output.push_str(&format!(
+                    "  {} {} → {}\n    {}\n",
+                    at.format("%H:%M:%S"),
+                    request.what,
+                    request.url,
+                    request.reason
+                ));
+            }
+        }
+        Ok(CommandResult::success(output))
+    }
+}
@@ -0,0 +1,58 @@
+//! Owners Command - Who knows a file or directory best, from git history
+
+use super::{CommandCategory, CommandContext, CommandResult, SlashCommand};
+use anyhow::Result;
+use std::path::Path;
+
+/// Owners listed
+const OWNERS_SHOWN: usize = 5;
+
+pub struct OwnersCommand;
+
+#[async_trait::async_trait]
+impl SlashCommand for OwnersCommand {
+    fn name(&self) -> &str {
+        "owners"
+    }
+
+    fn description(&self) -> &str {
+        "Show who changed a file or directory most, weighted towards recent commits"
+    }
+
+    fn usage(&self) -> &str {
+        "/owners [path] - Owners of path (default: the whole project)"
+    }
+
+    fn category(&self) -> CommandCategory {
+        CommandCategory::Git
+    }
+
+    async fn execute(&self, args: &str, ctx: &CommandContext) -> Result<CommandResult> {
+        let path = Some(args.trim()).filter(|path| !path.is_empty());
+        let scope = path.unwrap_or("el proyecto");
+
+        let owners = match ctx.tools.owners.owners(Path::new(&ctx.working_dir), path, OWNERS_SHOWN).await {
+            Ok(owners) => owners,
+            Err(e) => return Ok(CommandResult::error(format!("Owners: {}", e))),
+        };
+        if owners.is_empty() {
+            return Ok(CommandResult::success(format!("Sin historial de commits para {}", scope)));
+        }
+
+        let mut output = format!("👥 Dueños de {} (historial reciente):\n\n", scope);
+        for (i, owner) in owners.iter().enumerate() {
+            output.push_str(&format!(
+                "{}. {} <{}> — {:.0}% de los cambios recientes · {} commits · último {}\n",
+                i + 1,
+                owner.name,
+                owner.email,
+                owner.share * 100.0,
+                owner.commits,
+                owner.last_date()
+            ));
+        }
+        output.push_str(&format!("\n💡 Para revisar cambios en {}, pregunta a {}", scope, owners[0].name));
+
+        Ok(CommandResult::success(output).with_metadata("path", path.unwrap_or(".")))
+    }
+}
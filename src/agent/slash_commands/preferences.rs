@@ -0,0 +1,143 @@
+//! Preferences Command - How the user wants code and answers written
+//!
+//! Values are global unless `--project` is given; project values override the
+//! global ones for sessions started in the same working directory.
+
+use super::{CommandCategory, CommandContext, CommandResult, SlashCommand};
+use crate::agent::preferences::{self, Preferences, PREFERENCE_KEYS};
+use crate::db::Database;
+use anyhow::Result;
+
+pub struct PreferencesCommand;
+
+/// `[--project] rest`: whether the project scope was asked for, and the rest
+fn split_scope(args: &str) -> (bool, &str) {
+    match args.trim().strip_prefix("--project") {
+        Some(rest) if rest.is_empty() || rest.starts_with(char::is_whitespace) => (true, rest.trim()),
+        _ => (false, args.trim()),
+    }
+}
+
+#[async_trait::async_trait]
+impl SlashCommand for PreferencesCommand {
+    fn name(&self) -> &str {
+        "preferences"
+    }
+
+    fn description(&self) -> &str {
+        "Show and edit your code style and answer preferences"
+    }
+
+    fn usage(&self) -> &str {
+        "/preferences | /preferences set [--project] <key> <value> | /preferences unset [--project] <key>"
+    }
+
+    fn category(&self) -> CommandCategory {
+        CommandCategory::System
+    }
+
+    fn validate_args(&self, args: &str) -> Result<()> {
+        let (action, rest) = args.split_once(char::is_whitespace).unwrap_or((args, ""));
+        let (_, rest) = split_scope(rest);
+        match action {
+            "" | "list" => Ok(()),
+            "set" => match rest.split_once(char::is_whitespace) {
+                Some((key, value)) => {
+                    preferences::validate(key, value)?;
+                    Ok(())
+                }
+                None => anyhow::bail!("Usage: /preferences set [--project] <key> <value>"),
+            },
+            "unset" if PREFERENCE_KEYS.iter().any(|(key, _)| *key == rest) => Ok(()),
+            "unset" => anyhow::bail!("Usage: /preferences unset [--project] <key>"),
+            _ => anyhow::bail!("Usage: {}", self.usage()),
+        }
+    }
+
+    async fn execute(&self, args: &str, ctx: &CommandContext) -> Result<CommandResult> {
+        let Some(session) = &ctx.session else {
+            return Ok(CommandResult::error("Las preferencias necesitan una sesión con base de datos"));
+        };
+        let (action, rest) = args.split_once(char::is_whitespace).unwrap_or((args, ""));
+        let (project_scope, rest) = split_scope(rest);
+        let project_key = Database::project_key(&ctx.working_dir);
+        let project = project_scope.then_some(project_key.as_str());
+        let scope = if project_scope { "proyecto" } else { "global" };
+
+        match action {
+            "set" => {
+                let (key, value) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+                let value = preferences::validate(key, value)?;
+                session.db.set_preference(project, key, &value).await?;
+                Ok(CommandResult::success(format!("✅ {} = {} ({})", key, value, scope))
+                    .with_metadata("key", key))
+            }
+            "unset" => {
+                if session.db.unset_preference(project, rest).await? {
+                    Ok(CommandResult::success(format!("🗑️ {} eliminada ({})", rest, scope))
+                        .with_metadata("key", rest))
+                } else {
+                    Ok(CommandResult::error(format!("{} no está definida ({})", rest, scope)))
+                }
+            }
+            _ => {
+                let current = Preferences::load(&session.db, &ctx.working_dir).await?;
+                let mut output = String::from("🎛️ Preferencias\n\n");
+                for (key, help) in PREFERENCE_KEYS {
+                    match current.get(key) {
+                        Some((value, origin)) => {
+                            output.push_str(&format!("  {:<17} {} [{}]\n", key, value, origin.label()))
+                        }
+                        None => output.push_str(&format!("  {:<17} — {}\n", key, help)),
+                    }
+                }
+                output.push_str(
+                    "\nUsa /preferences set [--project] <clave> <valor> para cambiarlas; \
+                     las del proyecto tienen prioridad sobre las globales.",
+                );
+                Ok(CommandResult::success(output))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent::state::create_shared_state;
+    use crate::db::{Session, SessionStore};
+    use crate::tools::registry::ToolRegistry;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_set_list_unset() {
+        let db = Database::in_memory().await.unwrap();
+        db.create_session(&Session::new("s1", "fast", "heavy")).await.unwrap();
+        let ctx = CommandContext {
+            tools: Arc::new(ToolRegistry::new()),
+            state: create_shared_state(),
+            working_dir: ".".to_string(),
+            session: Some(SessionStore {
+                db: db.clone(),
+                session_id: "s1".to_string(),
+            }),
+        };
+
+        assert!(PreferencesCommand.validate_args("set verbosity chatty").is_err());
+        assert!(PreferencesCommand.validate_args("set --project naming snake_case").is_ok());
+        assert!(PreferencesCommand.validate_args("unset theme").is_err());
+
+        PreferencesCommand.execute("set naming camelCase", &ctx).await.unwrap();
+        PreferencesCommand
+            .execute("set --project naming snake_case", &ctx)
+            .await
+            .unwrap();
+        let listed = PreferencesCommand.execute("", &ctx).await.unwrap();
+        assert!(listed.output.contains("snake_case [proyecto]"));
+
+        let removed = PreferencesCommand.execute("unset --project naming", &ctx).await.unwrap();
+        assert!(removed.success);
+        let listed = PreferencesCommand.execute("list", &ctx).await.unwrap();
+        assert!(listed.output.contains("camelCase [global]"));
+    }
+}
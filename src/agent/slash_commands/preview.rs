@@ -0,0 +1,55 @@
+//! Preview Command - Show the schema, first rows and stats of a data file
+
+use super::{CommandCategory, CommandContext, CommandResult, SlashCommand};
+use crate::tools::DataPreviewArgs;
+use anyhow::Result;
+use std::path::Path;
+
+pub struct PreviewCommand;
+
+#[async_trait::async_trait]
+impl SlashCommand for PreviewCommand {
+    fn name(&self) -> &str {
+        "preview"
+    }
+
+    fn description(&self) -> &str {
+        "Preview a CSV, TSV, JSONL or Parquet file with column stats"
+    }
+
+    fn usage(&self) -> &str {
+        "/preview <file> [rows] - Show schema, first rows, null % and min/max"
+    }
+
+    fn category(&self) -> CommandCategory {
+        CommandCategory::Context
+    }
+
+    fn validate_args(&self, args: &str) -> Result<()> {
+        if args.trim().is_empty() {
+            anyhow::bail!("Usage: {}", self.usage());
+        }
+        Ok(())
+    }
+
+    async fn execute(&self, args: &str, ctx: &CommandContext) -> Result<CommandResult> {
+        let mut parts = args.split_whitespace();
+        let Some(file) = parts.next() else {
+            return Ok(CommandResult::error(format!("Usage: {}", self.usage())));
+        };
+        let rows = parts.next().and_then(|r| r.parse().ok()).unwrap_or(10);
+
+        let preview_args = DataPreviewArgs {
+            path: Path::new(&ctx.working_dir)
+                .join(file)
+                .to_string_lossy()
+                .to_string(),
+            rows,
+        };
+        match ctx.tools.data_preview.preview(&preview_args).await {
+            Ok(preview) => Ok(CommandResult::success(preview.to_markdown())
+                .with_metadata("path", preview.path.clone())),
+            Err(e) => Ok(CommandResult::error(format!("Error leyendo datos: {}", e))),
+        }
+    }
+}
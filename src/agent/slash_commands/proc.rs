@@ -0,0 +1,91 @@
+//! Proc Command - Manage dev servers running in the background
+
+use super::{CommandCategory, CommandContext, CommandResult, SlashCommand};
+use crate::tools::{default_process_name, DevServerTool, ProcessInfo, DEFAULT_DEV_SERVER_LINES};
+use anyhow::Result;
+use rig::tool::Tool;
+use std::path::Path;
+
+pub struct ProcCommand;
+
+#[async_trait::async_trait]
+impl SlashCommand for ProcCommand {
+    fn name(&self) -> &str {
+        "proc"
+    }
+
+    fn description(&self) -> &str {
+        "Run dev servers in the background and read their output"
+    }
+
+    fn usage(&self) -> &str {
+        "/proc [list] | start <command> | logs [name] [lines] | stop [name] | restart [name]"
+    }
+
+    fn category(&self) -> CommandCategory {
+        CommandCategory::System
+    }
+
+    async fn execute(&self, args: &str, ctx: &CommandContext) -> Result<CommandResult> {
+        let args = args.trim();
+        let (action, rest) = args.split_once(char::is_whitespace).unwrap_or((args, ""));
+        let rest = rest.trim();
+        let manager = &ctx.tools.dev_server;
+
+        match action {
+            "" | "list" => {
+                let processes = manager.list().await;
+                if processes.is_empty() {
+                    return Ok(CommandResult::success(
+                        "No hay procesos en segundo plano. Inicia uno con /proc start <comando>",
+                    ));
+                }
+                let lines: Vec<String> = processes.iter().map(ProcessInfo::render).collect();
+                Ok(CommandResult::success(format!("⚙️ Procesos:\n{}", lines.join("\n"))))
+            }
+            "start" => {
+                if rest.is_empty() {
+                    return Ok(CommandResult::error(format!("Usage: {}", self.usage())));
+                }
+                let name = default_process_name(rest);
+                match manager.start(&name, rest, Path::new(&ctx.working_dir)).await {
+                    Ok(info) => Ok(CommandResult::success(format!(
+                        "▶️ Iniciado {}\nConsulta su salida con /proc logs {}",
+                        info.render(),
+                        name
+                    ))),
+                    Err(e) => Ok(CommandResult::error(format!("Error iniciando el proceso: {}", e))),
+                }
+            }
+            "logs" | "output" => {
+                let mut name = None;
+                let mut lines = DEFAULT_DEV_SERVER_LINES;
+                for part in rest.split_whitespace() {
+                    match part.parse::<usize>() {
+                        Ok(n) => lines = n,
+                        Err(_) => name = Some(part),
+                    }
+                }
+                match manager.output(name, lines, None).await {
+                    Ok(output) => Ok(CommandResult::success(output)),
+                    Err(e) => Ok(CommandResult::error(format!("Error: {}", e))),
+                }
+            }
+            "stop" | "restart" => {
+                // Stopping or restarting a server is confirmed like the agent's own calls
+                let mut call = serde_json::json!({ "action": action, "user_command": true });
+                if !rest.is_empty() {
+                    call["name"] = serde_json::Value::String(rest.to_string());
+                }
+                let summary = format!("{} {}", action, rest).trim_end().to_string();
+                Ok(CommandResult::success(format!("¿{}? Confirma para continuar", summary))
+                    .with_metadata("action", "confirm")
+                    .with_metadata("tool", DevServerTool::NAME)
+                    .with_metadata("args", call.to_string())
+                    .with_metadata("summary", format!("dev_server {}", summary))
+                    .with_metadata("risk", "process:write"))
+            }
+            _ => Ok(CommandResult::error(format!("Usage: {}", self.usage()))),
+        }
+    }
+}
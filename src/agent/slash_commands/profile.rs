@@ -0,0 +1,144 @@
+//! Profile Command - Profile a command and get optimization suggestions for its hotspots
+
+use super::{CommandCategory, CommandContext, CommandResult, SlashCommand};
+use crate::tools::{ProfileArgs, Profiler, ProfilerTool};
+use anyhow::Result;
+use rig::tool::Tool;
+
+pub struct ProfileCommand;
+
+/// Options of `/profile`
+#[derive(Debug, PartialEq)]
+struct ProfileOptions {
+    profiler: Option<Profiler>,
+    top: Option<usize>,
+    command: String,
+}
+
+impl ProfileOptions {
+    /// `--with <profiler>` and `--top N` before the command; the rest is the command
+    fn parse(args: &str) -> Result<Self, String> {
+        let mut options = Self {
+            profiler: None,
+            top: None,
+            command: String::new(),
+        };
+        let mut rest = args.trim();
+        loop {
+            let (word, tail) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+            let tail = tail.trim_start();
+            match word {
+                "--with" | "-w" => {
+                    let (name, after) = tail.split_once(char::is_whitespace).unwrap_or((tail, ""));
+                    let profiler = Profiler::parse(name)
+                        .ok_or_else(|| format!("Unknown profiler '{}' (flamegraph, py-spy or node)", name))?;
+                    options.profiler = Some(profiler);
+                    rest = after.trim_start();
+                }
+                "--top" | "-n" => {
+                    let (n, after) = tail.split_once(char::is_whitespace).unwrap_or((tail, ""));
+                    let n = n
+                        .parse::<usize>()
+                        .ok()
+                        .filter(|n| *n > 0)
+                        .ok_or_else(|| "--top needs a positive number".to_string())?;
+                    options.top = Some(n);
+                    rest = after.trim_start();
+                }
+                _ => break,
+            }
+        }
+        if rest.is_empty() {
+            return Err("Missing the command to profile".to_string());
+        }
+        options.command = rest.to_string();
+        Ok(options)
+    }
+}
+
+#[async_trait::async_trait]
+impl SlashCommand for ProfileCommand {
+    fn name(&self) -> &str {
+        "profile"
+    }
+
+    fn description(&self) -> &str {
+        "Profile a command and get optimization suggestions for its hotspots"
+    }
+
+    fn usage(&self) -> &str {
+        "/profile [--with flamegraph|py-spy|node] [--top N] <command> - e.g. /profile cargo run --release -- input.txt"
+    }
+
+    fn category(&self) -> CommandCategory {
+        CommandCategory::Testing
+    }
+
+    async fn execute(&self, args: &str, ctx: &CommandContext) -> Result<CommandResult> {
+        let options = match ProfileOptions::parse(args) {
+            Ok(options) => options,
+            Err(e) => return Ok(CommandResult::error(format!("{}\nUsage: {}", e, self.usage()))),
+        };
+        let Some(profiler) = options.profiler.or_else(|| Profiler::detect(&options.command)) else {
+            return Ok(CommandResult::error(format!(
+                "No sé qué perfilador usar para `{}`: indícalo con --with flamegraph|py-spy|node",
+                options.command
+            )));
+        };
+        if !ctx.tools.environment.command_exists(profiler.program()).await {
+            return Ok(CommandResult::error(format!(
+                "{} no está instalado: {}",
+                profiler.label(),
+                profiler.install_hint()
+            )));
+        }
+
+        // Running the target is confirmed like the agent's own calls
+        let mut call = serde_json::json!({
+            "command": options.command,
+            "profiler": profiler.slug(),
+            "user_command": true,
+        });
+        if let Some(top) = options.top {
+            call["top"] = serde_json::json!(top);
+        }
+        let summary = ProfileArgs {
+            command: options.command.clone(),
+            profiler: Some(profiler.slug().to_string()),
+            ..Default::default()
+        }
+        .summary();
+        Ok(CommandResult::success(format!(
+            "🔥 Se ejecutará `{}` con {}; el perfil se guarda en /artifacts y sus puntos \
+             calientes se envían al modelo para sugerir optimizaciones.\n¿Ejecutar? Confirma para continuar",
+            options.command,
+            profiler.label()
+        ))
+        .with_metadata("action", "confirm")
+        .with_metadata("tool", ProfilerTool::NAME)
+        .with_metadata("args", call.to_string())
+        .with_metadata("summary", format!("profile_code {}", summary))
+        .with_metadata("risk", "process:write"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_profile_options() {
+        let options = ProfileOptions::parse("--with py-spy --top 5 python -m app --top 3").unwrap();
+        assert_eq!(
+            options,
+            ProfileOptions {
+                profiler: Some(Profiler::PySpy),
+                top: Some(5),
+                command: "python -m app --top 3".to_string(),
+            }
+        );
+        assert_eq!(ProfileOptions::parse("node server.js").unwrap().profiler, None);
+        assert!(ProfileOptions::parse("--with gprof ./app").is_err());
+        assert!(ProfileOptions::parse("--top 3").is_err());
+    }
+}
@@ -0,0 +1,135 @@
+//! Recipe Command - Run reusable multi-step workflows from YAML files
+
+use super::{CommandCategory, CommandContext, CommandResult, SlashCommand};
+use crate::agent::recipes::{RecipeBook, RecipeError, RecipeRun};
+use anyhow::Result;
+use std::path::Path;
+
+pub struct RecipeCommand;
+
+#[async_trait::async_trait]
+impl SlashCommand for RecipeCommand {
+    fn name(&self) -> &str {
+        "recipe"
+    }
+
+    fn description(&self) -> &str {
+        "Run multi-step workflows from .neuro/recipes or ~/.config/neuro/recipes"
+    }
+
+    fn usage(&self) -> &str {
+        "/recipe list | show <name> | run <name> [var=value...] [--dry-run] [--step]\n\
+         /recipe continue | skip | abort | status"
+    }
+
+    fn category(&self) -> CommandCategory {
+        CommandCategory::System
+    }
+
+    async fn execute(&self, args: &str, ctx: &CommandContext) -> Result<CommandResult> {
+        let parts: Vec<&str> = args.split_whitespace().collect();
+        let book = RecipeBook::new(Path::new(&ctx.working_dir));
+
+        match parts.as_slice() {
+            [] | ["list"] => {
+                let (recipes, errors) = book.list();
+                if recipes.is_empty() && errors.is_empty() {
+                    return Ok(CommandResult::success(
+                        "No hay recetas. Crea un archivo YAML en .neuro/recipes/ o ~/.config/neuro/recipes/",
+                    ));
+                }
+                let mut output = String::from("📜 Recetas disponibles:\n");
+                for recipe in &recipes {
+                    output.push_str(&format!(
+                        "  {} ({} pasos) - {}\n",
+                        recipe.name,
+                        recipe.steps.len(),
+                        recipe.description
+                    ));
+                }
+                for error in &errors {
+                    output.push_str(&format!("  ⚠️ {}\n", error));
+                }
+                output.push_str("\nUso: /recipe run <nombre> [var=valor...] [--dry-run] [--step]");
+                Ok(CommandResult::success(output))
+            }
+            ["show", name] => Ok(match book.load(name) {
+                Ok(recipe) => {
+                    let mut output = format!("📜 {}\n{}\n", recipe.name, recipe.description);
+                    for (name, variable) in &recipe.variables {
+                        output.push_str(&format!(
+                            "  {}{} - {}\n",
+                            name,
+                            variable
+                                .default
+                                .as_ref()
+                                .map(|d| format!(" (por defecto: {})", d))
+                                .unwrap_or_default(),
+                            variable.description.as_deref().unwrap_or_default()
+                        ));
+                    }
+                    output.push_str(&serde_yaml::to_string(&recipe.steps).unwrap_or_default());
+                    CommandResult::success(output)
+                }
+                Err(e) => CommandResult::error(format!("Recipe: {}", e)),
+            }),
+            ["run", name, rest @ ..] => {
+                let dry_run = rest.contains(&"--dry-run");
+                let step_by_step = rest.contains(&"--step");
+                let values: Vec<&str> = rest.iter().copied().filter(|a| !a.starts_with("--")).collect();
+
+                let recipe = match book.load(name) {
+                    Ok(recipe) => recipe,
+                    Err(e) => return Ok(CommandResult::error(format!("Recipe: {}", e))),
+                };
+                let variables = match recipe.bind(&values) {
+                    Ok(variables) => variables,
+                    Err(e) => return Ok(CommandResult::error(format!("Recipe: {}", e))),
+                };
+                if dry_run {
+                    return Ok(CommandResult::success(recipe.dry_run(&variables)));
+                }
+
+                let mut state = ctx.state.lock().await;
+                if let Some(run) = &state.recipe {
+                    return Ok(CommandResult::error(format!(
+                        "La receta {} sigue en curso: /recipe continue, skip o abort",
+                        run.recipe.name
+                    )));
+                }
+                state.recipe = Some(RecipeRun::new(recipe, variables, step_by_step));
+                // The router runs the steps, it owns the tools and the models
+                Ok(CommandResult::success(String::new()).with_metadata("action", "recipe"))
+            }
+            [action @ ("continue" | "skip" | "abort" | "status")] => {
+                let mut state = ctx.state.lock().await;
+                let Some(run) = state.recipe.as_mut() else {
+                    return Ok(CommandResult::error(format!("Recipe: {}", RecipeError::NotRunning)));
+                };
+                match *action {
+                    "continue" => {
+                        run.approved = true;
+                        Ok(CommandResult::success(String::new()).with_metadata("action", "recipe"))
+                    }
+                    "skip" => {
+                        run.skip();
+                        Ok(CommandResult::success(String::new()).with_metadata("action", "recipe"))
+                    }
+                    "abort" => {
+                        let report = run.report();
+                        state.recipe = None;
+                        Ok(CommandResult::success(format!("{}⏹️ Receta detenida", report)))
+                    }
+                    _ => {
+                        let mut output = run.report();
+                        if let Some(step) = run.next_step() {
+                            output.push_str(&run.pause_message(&step));
+                        }
+                        Ok(CommandResult::success(output))
+                    }
+                }
+            }
+            _ => Ok(CommandResult::error(format!("Usage: {}", self.usage()))),
+        }
+    }
+}
@@ -1,10 +1,78 @@
 //! Refactor Command - Code refactoring operations
 
 use super::{CommandCategory, CommandContext, CommandResult, SlashCommand};
+use crate::tools::{RefactorArgs, RefactorOperation};
 use anyhow::Result;
+use std::collections::BTreeSet;
+use std::path::Path;
 
 pub struct RefactorCommand;
 
+impl RefactorCommand {
+    /// `rename_symbol <path> <symbol> <new_name> [--apply]`: a diff preview of
+    /// the AST rename, written only with `--apply`
+    async fn rename_symbol(&self, args: &[&str], ctx: &CommandContext) -> Result<CommandResult> {
+        let apply = args.contains(&"--apply");
+        let args: Vec<&str> = args.iter().copied().filter(|a| *a != "--apply").collect();
+        let [path, symbol, new_name] = args[..] else {
+            return Ok(CommandResult::error(
+                "Usage: /refactor rename_symbol <path> <symbol> <new_name> [--apply]",
+            ));
+        };
+
+        let root = Path::new(&ctx.working_dir).join(path);
+        let args = |dry_run| RefactorArgs {
+            operation: RefactorOperation::RenameSymbol {
+                symbol: symbol.to_string(),
+                new_name: new_name.to_string(),
+            },
+            path: root.to_string_lossy().to_string(),
+            dry_run: Some(dry_run),
+        };
+        let mut result = match ctx.tools.refactor.refactor(args(true)).await {
+            Ok(result) => result,
+            Err(e) => return Ok(CommandResult::error(format!("Rename failed: {}", e))),
+        };
+
+        if apply {
+            // One read-only or protected file refuses the whole rename
+            let read_only_roots = ctx.state.lock().await.read_only_roots.clone();
+            let files: BTreeSet<&str> = result.changes.iter().map(|c| c.file.as_str()).collect();
+            let denied: Vec<String> = files
+                .into_iter()
+                .filter_map(|file| ctx.tools.check_writable(&root.join(file).to_string_lossy(), &read_only_roots))
+                .collect();
+            if !denied.is_empty() {
+                return Ok(CommandResult::error(format!(
+                    "Rename refused, nothing was written:\n{}",
+                    denied.join("\n")
+                )));
+            }
+            result = match ctx.tools.refactor.refactor(args(false)).await {
+                Ok(result) => result,
+                Err(e) => return Ok(CommandResult::error(format!("Rename failed: {}", e))),
+            };
+        }
+
+        let mut output = format!(
+            "# Rename {} → {}\n\n{} changes in {} files\n\n```diff\n{}```\n",
+            symbol,
+            new_name,
+            result.total_changes,
+            result.files_modified,
+            result.diff()
+        );
+        output.push_str(if apply {
+            "\n✅ Changes written.\n"
+        } else {
+            "\n💡 Preview only. Add --apply to write the changes.\n"
+        });
+        Ok(CommandResult::success(output)
+            .with_metadata("operation", "rename_symbol")
+            .with_metadata("symbol", symbol))
+    }
+}
+
 #[async_trait::async_trait]
 impl SlashCommand for RefactorCommand {
     fn name(&self) -> &str {
@@ -16,7 +84,7 @@ impl SlashCommand for RefactorCommand {
     }
     
     fn usage(&self) -> &str {
-        "/refactor <operation> <path> - Operations: extract, rename, inline, simplify"
+        "/refactor <operation> <path> - Operations: extract, rename, rename_symbol, inline, simplify"
     }
     
     fn category(&self) -> CommandCategory {
@@ -28,15 +96,19 @@ impl SlashCommand for RefactorCommand {
         
         if parts.len() < 2 {
             return Ok(CommandResult::error(
-                "Usage: /refactor <operation> <path> [additional_args]\nOperations: rename, extract, inline"
+                "Usage: /refactor <operation> <path> [additional_args]\nOperations: rename, rename_symbol, extract, inline"
             ));
         }
         
         let operation = parts[0];
         let path = parts[1];
+
+        if operation == "rename_symbol" {
+            return self.rename_symbol(&parts[1..], ctx).await;
+        }
         
         // Use RefactorTool to perform the operation
-        use crate::tools::{RefactorArgs, RefactorOperation, RefactorScope, ExtractType};
+        use crate::tools::{RefactorScope, ExtractType};
         
         let refactor_op = match operation {
             "extract" => {
@@ -70,7 +142,7 @@ impl SlashCommand for RefactorCommand {
             }
             _ => {
                 return Ok(CommandResult::error(
-                    "Unknown operation. Available: extract, rename, inline"
+                    "Unknown operation. Available: extract, rename, rename_symbol, inline"
                 ));
             }
         };
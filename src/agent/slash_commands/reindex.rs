@@ -27,7 +27,8 @@ impl SlashCommand for ReindexCommand {
         // Note: Actual reindexing will be handled by RouterOrchestrator
         // This command just signals the intent
         Ok(CommandResult::success(
-            "🔄 Reindexing initiated...\n\nThis will rebuild the RAPTOR index for better semantic search."
+            "🔄 Reindexing initiated...\n\nThis will rebuild the RAPTOR index for better semantic search. \
+             The current index keeps answering queries until the new one is ready."
         ).with_metadata("action", "reindex"))
     }
 }
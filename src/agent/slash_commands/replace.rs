@@ -0,0 +1,177 @@
+//! Replace Command - Review and apply a project-wide replace
+
+use super::{CommandCategory, CommandContext, CommandResult, SlashCommand};
+use crate::tools::{ReplaceArgs, ReplaceError, SearchMode};
+use anyhow::Result;
+use std::path::Path;
+
+pub struct ReplaceCommand;
+
+#[async_trait::async_trait]
+impl SlashCommand for ReplaceCommand {
+    fn name(&self) -> &str {
+        "replace"
+    }
+
+    fn description(&self) -> &str {
+        "Project-wide replace with a per-match checklist and undo"
+    }
+
+    fn usage(&self) -> &str {
+        "/replace <pattern> => <replacement> [--regex|--word] [-i] [--files <glob>]\n\
+         /replace toggle|only <1,3-5> | all | none | show | apply | cancel | undo"
+    }
+
+    fn category(&self) -> CommandCategory {
+        CommandCategory::Code
+    }
+
+    async fn execute(&self, args: &str, ctx: &CommandContext) -> Result<CommandResult> {
+        let replace = &ctx.tools.replace;
+        let (action, rest) = args
+            .trim()
+            .split_once(char::is_whitespace)
+            .unwrap_or((args.trim(), ""));
+
+        let result = match action {
+            "" | "show" => replace.with_pending(|plan| Ok(plan.format())),
+            "all" => replace.with_pending(|plan| {
+                plan.select_all();
+                Ok(plan.format())
+            }),
+            "none" => replace.with_pending(|plan| {
+                plan.select_none();
+                Ok(plan.format())
+            }),
+            "toggle" | "only" => match parse_selection(rest) {
+                Some(ids) => replace.with_pending(|plan| {
+                    if action == "toggle" {
+                        plan.toggle(&ids)?;
+                    } else {
+                        plan.select_only(&ids)?;
+                    }
+                    Ok(plan.format())
+                }),
+                None => Err(ReplaceError::InvalidSelection(rest.to_string())),
+            },
+            "cancel" => {
+                replace.set_pending(None);
+                Ok("Reemplazo descartado".to_string())
+            }
+            "apply" => replace.apply_pending().await.map(|batch| {
+                let files: Vec<String> = batch
+                    .files()
+                    .map(|p| {
+                        p.strip_prefix(&ctx.working_dir)
+                            .unwrap_or(p)
+                            .display()
+                            .to_string()
+                    })
+                    .collect();
+                format!(
+                    "✅ {} reemplazos en {} archivos: {}\nDeshacer: /replace undo",
+                    batch.replacements,
+                    files.len(),
+                    files.join(", ")
+                )
+            }),
+            "undo" => replace.undo_last().await.map(|batch| {
+                format!(
+                    "↩️ Deshecho el reemplazo de '{}' ({} archivos restaurados)",
+                    batch.pattern,
+                    batch.files().count()
+                )
+            }),
+            _ => {
+                let Some(replace_args) = parse_replace(args, &ctx.working_dir) else {
+                    return Ok(CommandResult::error(format!("Uso: {}", self.usage())));
+                };
+                match replace.plan(&replace_args).await {
+                    Ok(plan) => {
+                        let mut text = plan.format();
+                        if !plan.edits.is_empty() {
+                            text.push_str(
+                                "Revisa la lista y usa /replace apply (toggle/only/all/none para elegir)",
+                            );
+                            replace.set_pending(Some(plan));
+                        }
+                        Ok(text)
+                    }
+                    Err(e) => Err(e),
+                }
+            }
+        };
+
+        Ok(match result {
+            Ok(text) => CommandResult::success(text),
+            Err(e) => CommandResult::error(format!("Replace: {}", e)),
+        })
+    }
+}
+
+/// `<pattern> => <replacement> [flags]`, flags after the first ` -`
+fn parse_replace(args: &str, working_dir: &str) -> Option<ReplaceArgs> {
+    let (pattern, rest) = args.split_once(" => ")?;
+    let (replacement, flags) = match rest.find(" -") {
+        Some(i) => (&rest[..i], &rest[i..]),
+        None => (rest, ""),
+    };
+
+    let mut replace_args = ReplaceArgs {
+        path: Path::new(working_dir).to_string_lossy().to_string(),
+        pattern: pattern.trim().to_string(),
+        replacement: replacement.trim().to_string(),
+        mode: SearchMode::Literal,
+        case_insensitive: false,
+        file_pattern: None,
+        apply: false,
+        matches: None,
+    };
+    let mut tokens = flags.split_whitespace();
+    while let Some(token) = tokens.next() {
+        match token {
+            "--regex" => replace_args.mode = SearchMode::Regex,
+            "--word" => replace_args.mode = SearchMode::Word,
+            "-i" => replace_args.case_insensitive = true,
+            "--files" => replace_args.file_pattern = Some(tokens.next()?.to_string()),
+            _ => return None,
+        }
+    }
+    (!replace_args.pattern.is_empty()).then_some(replace_args)
+}
+
+/// `1,3-5` → `[1, 3, 4, 5]`
+pub(super) fn parse_selection(text: &str) -> Option<Vec<usize>> {
+    let mut ids = Vec::new();
+    for part in text.split([',', ' ']).filter(|p| !p.is_empty()) {
+        match part.split_once('-') {
+            Some((from, to)) => ids.extend(from.parse::<usize>().ok()?..=to.parse().ok()?),
+            None => ids.push(part.parse().ok()?),
+        }
+    }
+    (!ids.is_empty()).then_some(ids)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_replace() {
+        let args = parse_replace(r"get_(\w+) => fetch_$1 --regex --files *.rs", "/p").unwrap();
+        assert_eq!(args.pattern, r"get_(\w+)");
+        assert_eq!(args.replacement, "fetch_$1");
+        assert_eq!(args.mode, SearchMode::Regex);
+        assert_eq!(args.file_pattern.as_deref(), Some("*.rs"));
+
+        assert!(parse_replace("no separator", "/p").is_none());
+        assert!(parse_replace("a => b --bogus", "/p").is_none());
+    }
+
+    #[test]
+    fn test_parse_selection() {
+        assert_eq!(parse_selection("1,3-5"), Some(vec![1, 3, 4, 5]));
+        assert_eq!(parse_selection("2 7"), Some(vec![2, 7]));
+        assert_eq!(parse_selection("x"), None);
+    }
+}
@@ -0,0 +1,121 @@
+//! Resolve Command - Walk through merge conflicts with model-proposed resolutions
+
+use super::replace::parse_selection;
+use super::{CommandCategory, CommandContext, CommandResult, SlashCommand};
+use crate::agent::conflicts::{ConflictError, ConflictSet, ResolutionSource};
+use anyhow::Result;
+use std::path::Path;
+
+pub struct ResolveCommand;
+
+#[async_trait::async_trait]
+impl SlashCommand for ResolveCommand {
+    fn name(&self) -> &str {
+        "resolve"
+    }
+
+    fn description(&self) -> &str {
+        "Resolve merge conflicts hunk by hunk with model proposals"
+    }
+
+    fn usage(&self) -> &str {
+        "/resolve - List conflicts and ask the model for a merged version of each hunk\n\
+         /resolve show | diff | accept [1,3-5|all] | ours|theirs|both <1,3-5> | apply | cancel"
+    }
+
+    fn category(&self) -> CommandCategory {
+        CommandCategory::Git
+    }
+
+    async fn execute(&self, args: &str, ctx: &CommandContext) -> Result<CommandResult> {
+        let (action, rest) = args
+            .trim()
+            .split_once(char::is_whitespace)
+            .unwrap_or((args.trim(), ""));
+        let mut state = ctx.state.lock().await;
+
+        if action.is_empty() {
+            let set = match ConflictSet::scan(Path::new(&ctx.working_dir)) {
+                Ok(set) => set,
+                Err(e) => return Ok(CommandResult::error(format!("Resolve: {}", e))),
+            };
+            let result = CommandResult::success(set.format())
+                .with_metadata("action", "resolve")
+                .with_metadata("prompt", set.prompt())
+                .with_metadata("query", set.context_query())
+                .with_metadata("hunks", set.hunk_count().to_string());
+            state.conflicts = Some(set);
+            return Ok(result);
+        }
+        if action == "cancel" {
+            state.conflicts = None;
+            return Ok(CommandResult::success("Resolución de conflictos descartada"));
+        }
+
+        let Some(set) = state.conflicts.as_mut() else {
+            return Ok(CommandResult::error(format!("Resolve: {}", ConflictError::NoPending)));
+        };
+        let result = match action {
+            "show" => Ok(set.format()),
+            "diff" => Ok(render_previews(set)),
+            "accept" => {
+                let ids = if rest.is_empty() || rest == "all" {
+                    Some(Vec::new())
+                } else {
+                    parse_selection(rest)
+                };
+                match ids {
+                    Some(ids) => set.accept(&ids).map(|_| render_previews(set)),
+                    None => return Ok(CommandResult::error(format!("Uso: {}", self.usage()))),
+                }
+            }
+            "ours" | "theirs" | "both" => {
+                let source = match action {
+                    "ours" => ResolutionSource::Ours,
+                    "theirs" => ResolutionSource::Theirs,
+                    _ => ResolutionSource::Both,
+                };
+                let Some(ids) = parse_selection(rest) else {
+                    return Ok(CommandResult::error(format!("Uso: {}", self.usage())));
+                };
+                ids.into_iter()
+                    .try_for_each(|id| set.choose(id, source))
+                    .map(|_| render_previews(set))
+            }
+            "apply" => set.apply().map(|summary| {
+                let mut text = format!("✅ {} conflictos resueltos", summary.hunks);
+                if !summary.staged.is_empty() {
+                    text.push_str(&format!("\nPreparados (git add): {}", summary.staged.join(", ")));
+                }
+                if !summary.partial.is_empty() {
+                    text.push_str(&format!("\nCon conflictos pendientes: {}", summary.partial.join(", ")));
+                }
+                text
+            }),
+            _ => return Ok(CommandResult::error(format!("Uso: {}", self.usage()))),
+        };
+        if state.conflicts.as_ref().is_some_and(|s| s.files.is_empty()) {
+            state.conflicts = None;
+        }
+
+        Ok(match result {
+            Ok(text) => CommandResult::success(text),
+            Err(e) => CommandResult::error(format!("Resolve: {}", e)),
+        })
+    }
+}
+
+/// Diffs of the accepted resolutions, as `/resolve apply` would write them
+fn render_previews(set: &ConflictSet) -> String {
+    let previews = set.previews();
+    if previews.is_empty() {
+        return "Ninguna resolución aceptada todavía".to_string();
+    }
+    let mut output = String::new();
+    for preview in &previews {
+        output.push_str(&preview.generate_colored_diff());
+        output.push_str(&format!("{}\n\n", preview.stats().format()));
+    }
+    output.push_str("Aplica con /resolve apply");
+    output
+}
@@ -0,0 +1,68 @@
+//! New Command - Scaffold a project from a template, build it and index it
+
+use super::{CommandCategory, CommandContext, CommandResult, SlashCommand};
+use crate::tools::ProjectTemplates;
+use anyhow::Result;
+use std::path::Path;
+
+pub struct ScaffoldCommand;
+
+#[async_trait::async_trait]
+impl SlashCommand for ScaffoldCommand {
+    fn name(&self) -> &str {
+        "new"
+    }
+
+    fn description(&self) -> &str {
+        "Create a project from a built-in or user template"
+    }
+
+    fn usage(&self) -> &str {
+        "/new [list] | <template> <name> - Templates: rust-bin, rust-lib, ts-node, python-pkg, ~/.config/neuro/templates/*"
+    }
+
+    fn category(&self) -> CommandCategory {
+        CommandCategory::System
+    }
+
+    async fn execute(&self, args: &str, ctx: &CommandContext) -> Result<CommandResult> {
+        let templates = ProjectTemplates::new();
+        let parts: Vec<&str> = args.split_whitespace().collect();
+
+        let (template_name, name) = match parts.as_slice() {
+            [] | ["list"] => {
+                let list = match templates.list() {
+                    Ok(list) => list,
+                    Err(e) => return Ok(CommandResult::error(format!("Error: {}", e))),
+                };
+                let mut output = String::from("📦 Plantillas disponibles:\n");
+                for template in list {
+                    let origin = if template.is_built_in() { "" } else { " (usuario)" };
+                    output.push_str(&format!(
+                        "  {}{} - {}\n",
+                        template.name, origin, template.description
+                    ));
+                }
+                output.push_str("\nUso: /new <plantilla> <nombre>");
+                return Ok(CommandResult::success(output));
+            }
+            [template, name] => (*template, *name),
+            _ => return Ok(CommandResult::error(format!("Usage: {}", self.usage()))),
+        };
+
+        let template = match templates.get(template_name) {
+            Ok(template) => template,
+            Err(e) => return Ok(CommandResult::error(format!("Error: {}", e))),
+        };
+        let mut report = match templates.scaffold(&template, name, Path::new(&ctx.working_dir)) {
+            Ok(report) => report,
+            Err(e) => return Ok(CommandResult::error(format!("Error creando el proyecto: {}", e))),
+        };
+        templates.verify(&template, &mut report).await;
+
+        // The router indexes the new project once the files are in place
+        Ok(CommandResult::success(report.render())
+            .with_metadata("action", "index")
+            .with_metadata("path", report.root.to_string_lossy()))
+    }
+}
@@ -0,0 +1,59 @@
+//! Schema Command - Ingest the database schema into the project context
+
+use super::{CommandCategory, CommandContext, CommandResult, SlashCommand};
+use crate::tools::SchemaIngestArgs;
+use anyhow::Result;
+use std::path::Path;
+
+pub struct SchemaCommand;
+
+#[async_trait::async_trait]
+impl SlashCommand for SchemaCommand {
+    fn name(&self) -> &str {
+        "schema"
+    }
+
+    fn description(&self) -> &str {
+        "Ingest the database schema (tables, columns, relations)"
+    }
+
+    fn usage(&self) -> &str {
+        "/schema [connection|path] - Introspect a database or read schema.sql/migrations/Prisma/Diesel"
+    }
+
+    fn category(&self) -> CommandCategory {
+        CommandCategory::Context
+    }
+
+    async fn execute(&self, args: &str, ctx: &CommandContext) -> Result<CommandResult> {
+        let target = args.trim();
+        let mut schema_args = SchemaIngestArgs {
+            project_dir: ctx.working_dir.clone(),
+            ..Default::default()
+        };
+        // An existing path is read as schema files; anything else is a connection
+        if !target.is_empty() {
+            if Path::new(&ctx.working_dir).join(target).exists() {
+                schema_args.path = Some(target.to_string());
+            } else {
+                schema_args.connection = Some(target.to_string());
+            }
+        }
+
+        match ctx.tools.schema.ingest(&schema_args).await {
+            Ok(schema) => {
+                let chunks = schema.store();
+                Ok(CommandResult::success(format!(
+                    "{}\n✓ Esquema guardado en el contexto ({} fragmentos)",
+                    schema.to_markdown(),
+                    chunks
+                ))
+                .with_metadata("tables", schema.tables.len().to_string()))
+            }
+            Err(e) => Ok(CommandResult::error(format!(
+                "Error leyendo el esquema: {}",
+                e
+            ))),
+        }
+    }
+}
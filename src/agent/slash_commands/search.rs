@@ -2,7 +2,8 @@
 
 use super::{CommandCategory, CommandContext, CommandResult, SlashCommand};
 use anyhow::Result;
-use crate::tools::SearchArgs;
+use crate::tools::{SearchArgs, SearchMode};
+use std::path::Path;
 
 pub struct SearchCommand;
 
@@ -17,7 +18,7 @@ impl SlashCommand for SearchCommand {
     }
     
     fn usage(&self) -> &str {
-        "/search <query> [--regex] - Search code (use --regex for regex search)"
+        "/search <query> [--regex|--word] [--page N] - Search code (literal by default)"
     }
     
     fn category(&self) -> CommandCategory {
@@ -29,31 +30,41 @@ impl SlashCommand for SearchCommand {
             return Ok(CommandResult::error("Usage: /search <query>"));
         }
         
-        let (query, use_regex) = if args.contains("--regex") {
-            (args.replace("--regex", "").trim().to_string(), true)
-        } else {
-            (args.to_string(), false)
-        };
+        let mut mode = SearchMode::Literal;
+        let mut page = 1usize;
+        let mut words = Vec::new();
+        let mut tokens = args.split_whitespace();
+        while let Some(token) = tokens.next() {
+            match token {
+                "--regex" => mode = SearchMode::Regex,
+                "--word" => mode = SearchMode::Word,
+                "--page" => page = tokens.next().and_then(|n| n.parse().ok()).unwrap_or(1).max(1),
+                _ => words.push(token),
+            }
+        }
+        let query = words.join(" ");
+        if query.is_empty() {
+            return Ok(CommandResult::error("Usage: /search <query>"));
+        }
         
         let search_args = SearchArgs {
             path: ctx.working_dir.clone(),
             pattern: query.clone(),
-            is_regex: Some(use_regex),
+            mode,
             case_insensitive: Some(true),
             file_pattern: None,
-            max_results: Some(50),
-            context_lines: Some(2),
+            max_results: Some(PAGE_SIZE),
+            offset: Some((page - 1) * PAGE_SIZE),
+            context_lines: Some(0),
             max_depth: None,
         };
         
         match ctx.tools.search_files.search(search_args).await {
             Ok(output) => {
-                let message = format!(
-                    "🔍 Search Results: '{}'\n  Files matched: {}\n  Total matches: {}",
-                    query, 
-                    output.results.len(),
-                    output.results.iter().map(|r| r.line_number).count()
-                );
+                let mut message = format!("🔍 {}", output.format(Path::new(&ctx.working_dir)));
+                if output.next_offset.is_some() {
+                    message.push_str(&format!("Siguiente página: /search {} --page {}", args_without_page(args), page + 1));
+                }
                 
                 Ok(CommandResult::success(message).with_metadata("query", &query))
             }
@@ -61,3 +72,20 @@ impl SlashCommand for SearchCommand {
         }
     }
 }
+
+/// Matches shown per page
+const PAGE_SIZE: usize = 30;
+
+/// The command arguments without `--page N`, to build the next-page hint
+fn args_without_page(args: &str) -> String {
+    let mut kept = Vec::new();
+    let mut tokens = args.split_whitespace();
+    while let Some(token) = tokens.next() {
+        if token == "--page" {
+            tokens.next();
+        } else {
+            kept.push(token);
+        }
+    }
+    kept.join(" ")
+}
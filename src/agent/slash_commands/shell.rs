@@ -32,11 +32,20 @@ impl SlashCommand for ShellCommand {
     }
     
     async fn execute(&self, args: &str, ctx: &CommandContext) -> Result<CommandResult> {
+        let env = {
+            let state = ctx.state.lock().await;
+            state
+                .env_vars
+                .iter()
+                .map(|(k, v)| (k.clone(), v.clone()))
+                .collect()
+        };
+
         let shell_args = ShellArgs {
             command: args.to_string(),
             args: None,
             working_dir: Some(ctx.working_dir.clone()),
-            env: None,
+            env: Some(env),
             timeout_secs: Some(300),
             capture_stderr: Some(true),
             shell: None,
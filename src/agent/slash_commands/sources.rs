@@ -0,0 +1,125 @@
+//! Sources Command - Files cited by the last answer
+//!
+//! Lists the sources of the last answer; `/sources irrelevant <n>` records
+//! that the chunks of source `n` did not help with that question, so they
+//! rank lower for similar ones (see `crate::raptor::feedback`).
+
+use super::{CommandCategory, CommandContext, CommandResult, SlashCommand};
+use crate::db::Database;
+use crate::raptor::feedback::{self, NegativePair};
+use crate::raptor::persistence::GLOBAL_STORE;
+use anyhow::Result;
+
+pub struct SourcesCommand;
+
+#[async_trait::async_trait]
+impl SlashCommand for SourcesCommand {
+    fn name(&self) -> &str {
+        "sources"
+    }
+
+    fn description(&self) -> &str {
+        "List the sources of the last answer and mark irrelevant ones"
+    }
+
+    fn usage(&self) -> &str {
+        "/sources [irrelevant <n>]"
+    }
+
+    fn category(&self) -> CommandCategory {
+        CommandCategory::Context
+    }
+
+    fn validate_args(&self, args: &str) -> Result<()> {
+        match args.split_whitespace().collect::<Vec<_>>().as_slice() {
+            [] => Ok(()),
+            ["irrelevant", n] if n.parse::<usize>().is_ok_and(|n| n > 0) => Ok(()),
+            _ => anyhow::bail!("Usage: {}", self.usage()),
+        }
+    }
+
+    async fn execute(&self, args: &str, ctx: &CommandContext) -> Result<CommandResult> {
+        let Some(sources) = ctx.state.lock().await.last_sources.clone() else {
+            return Ok(CommandResult::success("La última respuesta no citó ninguna fuente"));
+        };
+
+        let Some(n) = args.split_whitespace().nth(1).and_then(|n| n.parse::<usize>().ok()) else {
+            let mut output = format!("📎 Fuentes de \"{}\":\n\n", sources.query);
+            for (i, path) in sources.paths.iter().enumerate() {
+                output.push_str(&format!("  {}. {}\n", i + 1, path));
+            }
+            output.push_str("\nUsa /sources irrelevant <n> si una no tenía que ver con la pregunta.");
+            return Ok(CommandResult::success(output));
+        };
+        let Some(path) = sources.paths.get(n - 1) else {
+            return Ok(CommandResult::error(format!("La última respuesta solo citó {} fuentes", sources.paths.len())));
+        };
+
+        let chunk_ids = {
+            let store = GLOBAL_STORE.lock().unwrap_or_else(|e| e.into_inner());
+            feedback::chunks_of(&store, path)
+        };
+        if chunk_ids.is_empty() {
+            return Ok(CommandResult::error(format!("{} no está en el índice; usa /reindex", path)));
+        }
+        let pairs: Vec<NegativePair> = chunk_ids
+            .into_iter()
+            .map(|chunk_id| NegativePair {
+                query: sources.query.clone(),
+                chunk_id,
+                source: path.clone(),
+            })
+            .collect();
+
+        let persisted = match &ctx.session {
+            Some(session) => {
+                let project = Database::project_key(&ctx.working_dir);
+                for pair in &pairs {
+                    session
+                        .db
+                        .add_retrieval_feedback(&project, &pair.query, &pair.chunk_id, &pair.source)
+                        .await?;
+                }
+                ""
+            }
+            None => " (solo en esta sesión: no hay base de datos)",
+        };
+        let count = pairs.len();
+        feedback::add_pairs(pairs);
+        Ok(CommandResult::success(format!(
+            "👎 {} marcado como irrelevante para \"{}\" ({} fragmentos){}: bajará en consultas parecidas",
+            path, sources.query, count, persisted
+        ))
+        .with_metadata("source", path.as_str()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent::state::{create_shared_state, AnswerSources};
+    use crate::tools::registry::ToolRegistry;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_list_and_validate() {
+        let ctx = CommandContext {
+            tools: Arc::new(ToolRegistry::new()),
+            state: create_shared_state(),
+            working_dir: ".".to_string(),
+            session: None,
+        };
+        assert!(SourcesCommand.validate_args("irrelevant 2").is_ok());
+        assert!(SourcesCommand.validate_args("irrelevant 0").is_err());
+        assert!(SourcesCommand.validate_args("relevant 1").is_err());
+        assert!(SourcesCommand.execute("", &ctx).await.unwrap().output.contains("ninguna fuente"));
+
+        ctx.state.lock().await.last_sources = Some(AnswerSources {
+            query: "why does the CI fail".to_string(),
+            paths: vec!["README.md".to_string(), ".github/workflows/ci.yml".to_string()],
+        });
+        let listed = SourcesCommand.execute("", &ctx).await.unwrap();
+        assert!(listed.output.contains("  2. .github/workflows/ci.yml"));
+        assert!(!SourcesCommand.execute("irrelevant 3", &ctx).await.unwrap().success);
+    }
+}
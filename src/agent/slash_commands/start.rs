@@ -0,0 +1,158 @@
+//! Start Command - Launch a conversation template
+//!
+//! A template sets the mode, pins files into the prompt and turns tools off for
+//! the session before sending its prompt; `/start save` turns the current setup
+//! into a template of the project.
+
+use super::{CommandCategory, CommandContext, CommandResult, SlashCommand};
+use crate::agent::state::MessageRole;
+use crate::agent::templates::{ConversationTemplate, TemplateBook, TemplateMode};
+use anyhow::Result;
+use std::path::Path;
+
+pub struct StartCommand;
+
+#[async_trait::async_trait]
+impl SlashCommand for StartCommand {
+    fn name(&self) -> &str {
+        "start"
+    }
+
+    fn description(&self) -> &str {
+        "Start a conversation from a template (.neuro/templates or built-in)"
+    }
+
+    fn usage(&self) -> &str {
+        "/start [list] | /start <template> [text] | /start save <name> [description]"
+    }
+
+    fn category(&self) -> CommandCategory {
+        CommandCategory::System
+    }
+
+    fn validate_args(&self, args: &str) -> Result<()> {
+        match args.split_whitespace().collect::<Vec<_>>().as_slice() {
+            ["save"] => anyhow::bail!("Usage: /start save <name> [description]"),
+            _ => Ok(()),
+        }
+    }
+
+    async fn execute(&self, args: &str, ctx: &CommandContext) -> Result<CommandResult> {
+        let book = TemplateBook::new(Path::new(&ctx.working_dir));
+        let (name, rest) = args.trim().split_once(char::is_whitespace).unwrap_or((args.trim(), ""));
+
+        match name {
+            "" | "list" => {
+                let (templates, errors) = book.list();
+                let mut output = String::from("🚀 Plantillas de conversación:\n");
+                for template in &templates {
+                    output.push_str(&format!(
+                        "  {:<16} [{}] {}\n",
+                        template.name,
+                        template.mode.label(),
+                        template.description
+                    ));
+                }
+                for error in &errors {
+                    output.push_str(&format!("  ⚠️ {}\n", error));
+                }
+                output.push_str(
+                    "\nUso: /start <plantilla> [texto]; /start save <nombre> guarda la configuración actual",
+                );
+                Ok(CommandResult::success(output))
+            }
+            "save" => {
+                let (name, description) = rest.trim().split_once(char::is_whitespace).unwrap_or((rest.trim(), ""));
+                let state = ctx.state.lock().await;
+                let Some(prompt) = state
+                    .messages
+                    .iter()
+                    .rev()
+                    .find(|m| m.role == MessageRole::User && !m.content.trim_start().starts_with('/'))
+                    .map(|m| m.content.clone())
+                else {
+                    return Ok(CommandResult::error(
+                        "No hay ningún mensaje que guardar: escribe primero el prompt de la plantilla",
+                    ));
+                };
+                let template = ConversationTemplate {
+                    name: name.to_string(),
+                    description: description.trim().to_string(),
+                    mode: if state.build_mode { TemplateMode::Build } else { TemplateMode::Ask },
+                    prompt,
+                    files: state.pinned_files.clone(),
+                    disable_tools: state.disabled_tools.iter().cloned().collect(),
+                };
+                drop(state);
+                let path = book.save(&template)?;
+                Ok(CommandResult::success(format!(
+                    "💾 Plantilla {} guardada en {}\nÚsala con /start {}",
+                    template.name,
+                    path.display(),
+                    template.name
+                ))
+                .with_metadata("path", path.to_string_lossy()))
+            }
+            _ => {
+                let template = book.load(name)?;
+                let prompt = template.render(rest, Path::new(&ctx.working_dir));
+                let mut state = ctx.state.lock().await;
+                // Only for this session: /tools keeps the per-project list
+                state.disabled_tools.extend(template.disable_tools.iter().cloned());
+                state.pinned_files = template.files.clone();
+                drop(state);
+
+                let mut output = format!("🚀 {} [{}]", template.name, template.mode.label());
+                if !template.files.is_empty() {
+                    output.push_str(&format!("\n📌 Archivos fijados: {}", template.files.join(", ")));
+                }
+                if !template.disable_tools.is_empty() {
+                    output.push_str(&format!(
+                        "\n🔒 Herramientas desactivadas: {}",
+                        template.disable_tools.join(", ")
+                    ));
+                }
+                Ok(CommandResult::success(output)
+                    .with_metadata("action", "start")
+                    .with_metadata("mode", template.mode.label())
+                    .with_metadata("prompt", prompt))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent::state::{create_shared_state, Message};
+    use crate::tools::registry::ToolRegistry;
+    use std::sync::Arc;
+    use tempfile::tempdir;
+
+    #[tokio::test]
+    async fn test_start_and_save() {
+        let dir = tempdir().unwrap();
+        let ctx = CommandContext {
+            tools: Arc::new(ToolRegistry::new()),
+            state: create_shared_state(),
+            working_dir: dir.path().to_string_lossy().to_string(),
+            session: None,
+        };
+
+        let started = StartCommand.execute("review-changes focus on errors", &ctx).await.unwrap();
+        assert_eq!(started.metadata.get("action").map(String::as_str), Some("start"));
+        assert_eq!(started.metadata.get("mode").map(String::as_str), Some("ask"));
+        assert!(started.metadata["prompt"].ends_with("focus on errors"));
+        assert!(ctx.state.lock().await.disabled_tools.contains("write_file"));
+
+        assert!(!StartCommand.execute("save nightly", &ctx).await.unwrap().success);
+        ctx.state.lock().await.add_message(Message::user("Run the test suite and fix failures"));
+        let saved = StartCommand.execute("save nightly Nightly fixes", &ctx).await.unwrap();
+        assert!(saved.success);
+        assert!(dir.path().join(".neuro/templates/nightly.yaml").exists());
+
+        let listed = StartCommand.execute("", &ctx).await.unwrap();
+        assert!(listed.output.contains("nightly"));
+        assert!(StartCommand.execute("missing", &ctx).await.is_err());
+    }
+}
@@ -0,0 +1,106 @@
+//! Tag Command - Label the current session
+//!
+//! Tags make a session easy to find later from the session browser
+//! (`/sessions #bug-123`).
+
+use super::{CommandCategory, CommandContext, CommandResult, SlashCommand};
+use crate::agent::session_search::normalize_tag;
+use anyhow::Result;
+
+pub struct TagCommand;
+
+#[async_trait::async_trait]
+impl SlashCommand for TagCommand {
+    fn name(&self) -> &str {
+        "tag"
+    }
+
+    fn description(&self) -> &str {
+        "Tag the current session to find it later with /sessions"
+    }
+
+    fn usage(&self) -> &str {
+        "/tag [<tag>...] | /tag rm <tag>"
+    }
+
+    fn category(&self) -> CommandCategory {
+        CommandCategory::Context
+    }
+
+    fn validate_args(&self, args: &str) -> Result<()> {
+        let tags = match args.split_whitespace().collect::<Vec<_>>().as_slice() {
+            ["rm", tag] => vec![*tag],
+            ["rm", ..] => anyhow::bail!("Usage: {}", self.usage()),
+            tags => tags.to_vec(),
+        };
+        match tags.iter().find(|tag| normalize_tag(tag).is_none()) {
+            Some(tag) => anyhow::bail!("Invalid tag '{}': use letters, digits, '-', '_', '.' or '/'", tag),
+            None => Ok(()),
+        }
+    }
+
+    async fn execute(&self, args: &str, ctx: &CommandContext) -> Result<CommandResult> {
+        let Some(session) = &ctx.session else {
+            return Ok(CommandResult::error("Las etiquetas necesitan una sesión con base de datos"));
+        };
+        let (db, id) = (&session.db, session.session_id.as_str());
+        let parts: Vec<&str> = args.split_whitespace().collect();
+
+        if let ["rm", tag] = parts.as_slice() {
+            let tag = normalize_tag(tag).unwrap_or_default();
+            return Ok(if db.untag_session(id, &tag).await? {
+                CommandResult::success(format!("🏷️ Etiqueta #{} quitada", tag))
+            } else {
+                CommandResult::error(format!("La sesión no tiene la etiqueta #{}", tag))
+            });
+        }
+
+        for tag in parts.iter().filter_map(|tag| normalize_tag(tag)) {
+            db.tag_session(id, &tag).await?;
+        }
+        let tags = db.get_session_tags(id).await?;
+        if tags.is_empty() {
+            return Ok(CommandResult::success("La sesión no tiene etiquetas. Usa /tag <etiqueta> para añadir una"));
+        }
+        let tags = tags.iter().map(|tag| format!("#{}", tag)).collect::<Vec<_>>().join(" ");
+        Ok(CommandResult::success(format!(
+            "🏷️ Etiquetas de la sesión: {}\n\nBúscalas con /sessions #etiqueta",
+            tags
+        ))
+        .with_metadata("tags", tags))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent::state::create_shared_state;
+    use crate::db::{Database, Session, SessionStore};
+    use crate::tools::registry::ToolRegistry;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_tag_and_untag() {
+        let db = Database::in_memory().await.unwrap();
+        db.create_session(&Session::new("s1", "fast", "heavy")).await.unwrap();
+        let ctx = CommandContext {
+            tools: Arc::new(ToolRegistry::new()),
+            state: create_shared_state(),
+            working_dir: ".".to_string(),
+            session: Some(SessionStore {
+                db: db.clone(),
+                session_id: "s1".to_string(),
+            }),
+        };
+
+        assert!(TagCommand.validate_args("bug-123 #auth").is_ok());
+        assert!(TagCommand.validate_args("bug?").is_err());
+        assert!(TagCommand.validate_args("rm a b").is_err());
+
+        let tagged = TagCommand.execute("Bug-123 #auth", &ctx).await.unwrap();
+        assert!(tagged.output.contains("#auth #bug-123"));
+        assert!(TagCommand.execute("rm auth", &ctx).await.unwrap().success);
+        assert!(!TagCommand.execute("rm auth", &ctx).await.unwrap().success);
+        assert_eq!(db.get_session_tags("s1").await.unwrap(), vec!["bug-123"]);
+    }
+}
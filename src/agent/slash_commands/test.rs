@@ -1,11 +1,59 @@
-//! Test Command - Run tests
+//! Test Command - Run tests, or look for flaky ones by running them repeatedly
 
 use super::{CommandCategory, CommandContext, CommandResult, SlashCommand};
+use crate::agent::activity_report;
 use anyhow::Result;
-use crate::tools::TestArgs;
+use crate::tools::{detect_flaky, record_flakiness, TestArgs, DEFAULT_FLAKY_RUNS};
+use crate::log_warn;
+
+/// Flaky tests of the project history shown with a detection
+const FLAKY_HISTORY: i32 = 5;
 
 pub struct TestCommand;
 
+/// Options of `/test --detect-flaky`
+#[derive(Debug, PartialEq)]
+struct FlakyOptions {
+    runs: usize,
+    parallelism: usize,
+    filter: Option<String>,
+}
+
+impl FlakyOptions {
+    /// `--runs N`, `--parallel N` and the pattern, in any order
+    fn parse(args: &str) -> Result<Self, String> {
+        let mut options = Self {
+            runs: DEFAULT_FLAKY_RUNS,
+            parallelism: 1,
+            filter: None,
+        };
+        let mut pattern = Vec::new();
+        let mut words = args.split_whitespace();
+        while let Some(word) = words.next() {
+            match word {
+                "--detect-flaky" => {}
+                "--runs" | "-n" | "--parallel" | "-j" => {
+                    let value = words
+                        .next()
+                        .and_then(|n| n.parse::<usize>().ok())
+                        .filter(|n| *n > 0)
+                        .ok_or_else(|| format!("{} needs a positive number", word))?;
+                    if matches!(word, "--runs" | "-n") {
+                        options.runs = value;
+                    } else {
+                        options.parallelism = value;
+                    }
+                }
+                _ => pattern.push(word),
+            }
+        }
+        if !pattern.is_empty() {
+            options.filter = Some(pattern.join(" "));
+        }
+        Ok(options)
+    }
+}
+
 #[async_trait::async_trait]
 impl SlashCommand for TestCommand {
     fn name(&self) -> &str {
@@ -17,7 +65,9 @@ impl SlashCommand for TestCommand {
     }
     
     fn usage(&self) -> &str {
-        "/test [pattern] - Run tests matching pattern (optional)"
+        "/test [pattern] - Run tests matching pattern (optional)\n\
+         /test --detect-flaky [--runs N] [--parallel N] [pattern] - Run them N times and report flaky tests\n\
+         /test --triage - Plan how to fix the worst flaky tests of the last detection"
     }
     
     fn category(&self) -> CommandCategory {
@@ -25,6 +75,19 @@ impl SlashCommand for TestCommand {
     }
     
     async fn execute(&self, args: &str, ctx: &CommandContext) -> Result<CommandResult> {
+        if args.trim() == "--triage" {
+            return Ok(match ctx.state.lock().await.flaky_triage.clone() {
+                Some(prompt) => CommandResult::success("📋 Plan de triage de tests inestables")
+                    .with_metadata("action", "start")
+                    .with_metadata("mode", "plan")
+                    .with_metadata("prompt", prompt),
+                None => CommandResult::error("No flaky tests to triage: run /test --detect-flaky first"),
+            });
+        }
+        if args.split_whitespace().any(|word| word == "--detect-flaky") {
+            return self.detect_flaky(args, ctx).await;
+        }
+
         let filter = if args.is_empty() {
             None
         } else {
@@ -47,6 +110,12 @@ impl SlashCommand for TestCommand {
                     "🧪 Test Results:\n  Passed: {}\n  Failed: {}\n  Skipped: {}",
                     output.summary.passed, output.summary.failed, output.summary.skipped
                 );
+                // Kept for `neuro report`, which counts fixes per command
+                if let Some(session) = &ctx.session {
+                    let command = format!("run_tests {}", args.trim());
+                    let exit_code = if output.summary.failed == 0 { 0 } else { 1 };
+                    activity_report::audit(session, command.trim_end(), exit_code).await;
+                }
                 
                 Ok(CommandResult::success(message).with_metadata("pattern", args))
             }
@@ -54,3 +123,62 @@ impl SlashCommand for TestCommand {
         }
     }
 }
+
+impl TestCommand {
+    /// Run the selection several times and report the tests whose outcome changed
+    async fn detect_flaky(&self, args: &str, ctx: &CommandContext) -> Result<CommandResult> {
+        let options = match FlakyOptions::parse(args) {
+            Ok(options) => options,
+            Err(e) => return Ok(CommandResult::error(format!("{}\nUsage: {}", e, self.usage()))),
+        };
+        let test_args = TestArgs {
+            path: ctx.working_dir.clone(),
+            filter: options.filter.clone(),
+            framework: None,
+            verbose: Some(false),
+            coverage: Some(false),
+            watch: Some(false),
+            parallel: Some(false),
+        };
+        let report = detect_flaky(&ctx.tools.test_runner, &test_args, options.runs, options.parallelism).await;
+        if report.tests.is_empty() {
+            let reason = report.errors.first().map_or("no test results", String::as_str);
+            return Ok(CommandResult::error(format!("Flaky test detection failed: {}", reason)));
+        }
+
+        let history = match &ctx.session {
+            Some(session) => record_flakiness(&session.db, &ctx.working_dir, &report, FLAKY_HISTORY)
+                .await
+                .unwrap_or_else(|e| {
+                    log_warn!("⚠️ Failed to record test runs: {}", e);
+                    Vec::new()
+                }),
+            None => Vec::new(),
+        };
+        ctx.state.lock().await.flaky_triage = report.triage_prompt();
+
+        Ok(CommandResult::success(report.to_markdown(&history))
+            .with_metadata("flaky", report.flaky().len().to_string())
+            .with_metadata("runs", report.runs.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_flaky_options() {
+        let options = FlakyOptions::parse("--detect-flaky --runs 20 -j 4 net::tests").unwrap();
+        assert_eq!(
+            options,
+            FlakyOptions {
+                runs: 20,
+                parallelism: 4,
+                filter: Some("net::tests".to_string()),
+            }
+        );
+        assert_eq!(FlakyOptions::parse("--detect-flaky").unwrap().runs, DEFAULT_FLAKY_RUNS);
+        assert!(FlakyOptions::parse("--detect-flaky --runs 0").is_err());
+    }
+}
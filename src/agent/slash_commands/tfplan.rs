@@ -0,0 +1,68 @@
+//! Terraform Plan Command - Summarize a plan and optionally ask the model about it
+
+use super::{CommandCategory, CommandContext, CommandResult, SlashCommand};
+use crate::tools::TerraformPlanArgs;
+use anyhow::Result;
+use std::path::Path;
+
+pub struct TfPlanCommand;
+
+#[async_trait::async_trait]
+impl SlashCommand for TfPlanCommand {
+    fn name(&self) -> &str {
+        "tfplan"
+    }
+
+    fn description(&self) -> &str {
+        "Summarize a Terraform plan (never applies it)"
+    }
+
+    fn usage(&self) -> &str {
+        "/tfplan [dir|plan-file] [question] - Plan and summarize, a question asks the model"
+    }
+
+    fn category(&self) -> CommandCategory {
+        CommandCategory::System
+    }
+
+    async fn execute(&self, args: &str, ctx: &CommandContext) -> Result<CommandResult> {
+        let working_dir = Path::new(&ctx.working_dir);
+        let mut parts = args.split_whitespace().peekable();
+
+        // The first argument is a path only if it exists; otherwise everything is the question
+        let mut plan_args = TerraformPlanArgs::default();
+        if let Some(first) = parts.peek() {
+            let candidate = working_dir.join(first);
+            if candidate.is_dir() {
+                plan_args.dir = Some(first.to_string());
+                parts.next();
+            } else if candidate.is_file() {
+                plan_args.plan_file = Some(first.to_string());
+                parts.next();
+            }
+        }
+        let question = parts.collect::<Vec<_>>().join(" ");
+        if !question.is_empty() {
+            plan_args.question = Some(question.clone());
+        }
+
+        let summary = match ctx.tools.terraform.summarize(&plan_args, working_dir).await {
+            Ok(summary) => summary.render(plan_args.question.as_deref()),
+            Err(e) => return Ok(CommandResult::error(format!("Error en terraform: {}", e))),
+        };
+
+        let mut result = CommandResult::success(summary.clone());
+        if !question.is_empty() {
+            result = result.with_metadata("action", "ask").with_metadata(
+                "prompt",
+                format!(
+                    "Este es el resumen de un plan de Terraform. Responde a la pregunta \
+                     explicando los cambios y sus riesgos, sin sugerir aplicarlo \
+                     automáticamente.\n\nPregunta: {}\n\n{}",
+                    question, summary
+                ),
+            );
+        }
+        Ok(result)
+    }
+}
@@ -0,0 +1,145 @@
+//! Tools Command - List the model's tools and turn them on or off per project
+//!
+//! The same switches as the Settings screen; the state is stored per working
+//! directory and restored for every session started there.
+
+use super::{CommandCategory, CommandContext, CommandResult, SlashCommand};
+use crate::agent::state::SharedState;
+use crate::db::{Database, SessionStore};
+use crate::tools::ToolOrigin;
+use anyhow::Result;
+
+/// Turn `tool` on or off for the session and, with a session attached, for the project
+pub async fn set_tool_enabled(
+    state: &SharedState,
+    session: Option<&SessionStore>,
+    working_dir: &str,
+    tool: &str,
+    enabled: bool,
+) -> Result<()> {
+    if let Some(session) = session {
+        let project = Database::project_key(working_dir);
+        session.db.set_project_tool(&project, tool, enabled).await?;
+    }
+    let mut state = state.lock().await;
+    if enabled {
+        state.disabled_tools.remove(tool);
+    } else {
+        state.disabled_tools.insert(tool.to_string());
+    }
+    Ok(())
+}
+
+pub struct ToolsCommand;
+
+#[async_trait::async_trait]
+impl SlashCommand for ToolsCommand {
+    fn name(&self) -> &str {
+        "tools"
+    }
+
+    fn description(&self) -> &str {
+        "List the tools and enable or disable them for this project"
+    }
+
+    fn usage(&self) -> &str {
+        "/tools [list] | /tools enable <name> | /tools disable <name>"
+    }
+
+    fn category(&self) -> CommandCategory {
+        CommandCategory::System
+    }
+
+    fn validate_args(&self, args: &str) -> Result<()> {
+        let (action, rest) = args.split_once(char::is_whitespace).unwrap_or((args, ""));
+        match action {
+            "" | "list" => Ok(()),
+            "enable" | "disable" if !rest.trim().is_empty() => Ok(()),
+            _ => anyhow::bail!("Usage: {}", self.usage()),
+        }
+    }
+
+    async fn execute(&self, args: &str, ctx: &CommandContext) -> Result<CommandResult> {
+        let (action, rest) = args.split_once(char::is_whitespace).unwrap_or((args, ""));
+        let name = rest.trim();
+        let listing = ctx.tools.tool_listing().await;
+
+        match action {
+            "enable" | "disable" => {
+                if !listing.iter().any(|tool| tool.name == name) {
+                    return Ok(CommandResult::error(format!(
+                        "No hay ninguna herramienta '{}' (/tools list)",
+                        name
+                    )));
+                }
+                let enabled = action == "enable";
+                set_tool_enabled(&ctx.state, ctx.session.as_ref(), &ctx.working_dir, name, enabled).await?;
+                let message = if enabled {
+                    format!("✅ {} activada en este proyecto", name)
+                } else {
+                    format!("⛔ {} desactivada en este proyecto", name)
+                };
+                Ok(CommandResult::success(message).with_metadata("tool", name))
+            }
+            _ => {
+                let disabled = ctx.state.lock().await.disabled_tools.clone();
+                let mut output = format!(
+                    "🛠 Herramientas ({} activas de {}):\n\n",
+                    listing.iter().filter(|tool| !disabled.contains(&tool.name)).count(),
+                    listing.len()
+                );
+                for tool in &listing {
+                    let icon = if disabled.contains(&tool.name) { "⛔" } else { "✅" };
+                    let origin = match tool.origin {
+                        ToolOrigin::BuiltIn => String::new(),
+                        origin => format!(" [{}]", origin.label()),
+                    };
+                    let summary = tool.description.split(['.', '\n']).next().unwrap_or_default();
+                    output.push_str(&format!("  {} {}{} - {}\n", icon, tool.name, origin, summary.trim()));
+                }
+                output.push_str("\n/tools enable|disable <nombre> cambia una herramienta para este proyecto");
+                Ok(CommandResult::success(output))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent::state::create_shared_state;
+    use crate::db::Session;
+    use crate::tools::registry::ToolRegistry;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_disable_persists_and_lists() {
+        let db = Database::in_memory().await.unwrap();
+        db.create_session(&Session::new("s1", "fast", "heavy")).await.unwrap();
+        let ctx = CommandContext {
+            tools: Arc::new(ToolRegistry::new()),
+            state: create_shared_state(),
+            working_dir: ".".to_string(),
+            session: Some(SessionStore {
+                db: db.clone(),
+                session_id: "s1".to_string(),
+            }),
+        };
+
+        assert!(ToolsCommand.validate_args("disable").is_err());
+        let unknown = ToolsCommand.execute("disable no_such_tool", &ctx).await.unwrap();
+        assert!(!unknown.success);
+
+        ToolsCommand.execute("disable execute_shell", &ctx).await.unwrap();
+        assert!(ctx.state.lock().await.disabled_tools.contains("execute_shell"));
+        let project = Database::project_key(".");
+        assert_eq!(db.get_disabled_tools(&project).await.unwrap(), vec!["execute_shell".to_string()]);
+
+        let listed = ToolsCommand.execute("list", &ctx).await.unwrap();
+        assert!(listed.output.contains("⛔ execute_shell"));
+        assert!(listed.output.contains("✅ read_file"));
+
+        ToolsCommand.execute("enable execute_shell", &ctx).await.unwrap();
+        assert!(db.get_disabled_tools(&project).await.unwrap().is_empty());
+    }
+}
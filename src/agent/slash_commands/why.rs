@@ -0,0 +1,133 @@
+//! Why Command - Explain how the last request was routed
+//!
+//! Shows the route the classifier chose and why, plus the kinds of chunks
+//! retrieval preferred for the query (see `crate::raptor::intent`), and the
+//! numbers behind the confidence shown under the answer.
+//! `/why rules` lists the intent rules in use, `/why <query>` previews the
+//! filters a query would get.
+
+use super::{CommandCategory, CommandContext, CommandResult, SlashCommand};
+use crate::raptor::intent;
+use anyhow::Result;
+
+pub struct WhyCommand;
+
+#[async_trait::async_trait]
+impl SlashCommand for WhyCommand {
+    fn name(&self) -> &str {
+        "why"
+    }
+
+    fn description(&self) -> &str {
+        "Explain the route and retrieval filters of the last request"
+    }
+
+    fn usage(&self) -> &str {
+        "/why [rules | <query>]"
+    }
+
+    fn category(&self) -> CommandCategory {
+        CommandCategory::Context
+    }
+
+    async fn execute(&self, args: &str, ctx: &CommandContext) -> Result<CommandResult> {
+        let args = args.trim();
+        let config = intent::config();
+
+        if args == "rules" {
+            let mut output = format!(
+                "🧭 Reglas de intención ({}, ×{}):\n\n",
+                if config.enabled { "activas" } else { "desactivadas" },
+                config.boost
+            );
+            for rule in &config.rules {
+                let kinds = |kinds: &[intent::ChunkKind]| {
+                    kinds.iter().map(|kind| kind.as_str()).collect::<Vec<_>>().join(", ")
+                };
+                output.push_str(&format!("  {}: {}", rule.name, rule.keywords.join(", ")));
+                if !rule.prefer.is_empty() {
+                    output.push_str(&format!(" → prefiere {}", kinds(&rule.prefer)));
+                }
+                if !rule.avoid.is_empty() {
+                    output.push_str(&format!(" · evita {}", kinds(&rule.avoid)));
+                }
+                output.push('\n');
+            }
+            output.push_str("\nCámbialas en la sección [intents] de la configuración.");
+            return Ok(CommandResult::success(output));
+        }
+
+        if !args.is_empty() {
+            return Ok(CommandResult::success(format!(
+                "🧭 Filtros para \"{}\": {}",
+                args,
+                config.infer(args).describe()
+            )));
+        }
+
+        let state = ctx.state.lock().await;
+        let Some(trace) = &state.last_route else {
+            return Ok(CommandResult::success("Todavía no se ha clasificado ninguna consulta"));
+        };
+        let confidence = trace
+            .confidence
+            .map(|confidence| format!(" (confianza {:.2})", confidence))
+            .unwrap_or_default();
+        let answer = state
+            .last_confidence
+            .as_ref()
+            .map(|confidence| format!("  {}\n", confidence.details()))
+            .unwrap_or_default();
+        Ok(CommandResult::success(format!(
+            "🧭 Última consulta: \"{}\"\n\n  Ruta: {}{}\n  Motivo: {}\n  Recuperación: {}\n{}\nUsa /why rules para ver las reglas de intención.",
+            trace.query,
+            trace.route,
+            confidence,
+            trace.reason,
+            trace.filter.describe(),
+            answer
+        ))
+        .with_metadata("route", &trace.route))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent::confidence::AnswerConfidence;
+    use crate::agent::state::{create_shared_state, RouteTrace};
+    use crate::tools::registry::ToolRegistry;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_explains_last_route() {
+        let ctx = CommandContext {
+            tools: Arc::new(ToolRegistry::new()),
+            state: create_shared_state(),
+            working_dir: ".".to_string(),
+            session: None,
+        };
+        let empty = WhyCommand.execute("", &ctx).await.unwrap();
+        assert!(empty.output.contains("ninguna consulta"));
+
+        ctx.state.lock().await.last_route = Some(RouteTrace {
+            query: "why does the CI fail".to_string(),
+            route: "ToolExecution (Ask, con RAPTOR)".to_string(),
+            confidence: Some(0.9),
+            reason: "needs the workflow files".to_string(),
+            filter: intent::IntentConfig::default().infer("why does the CI fail"),
+        });
+        let output = WhyCommand.execute("", &ctx).await.unwrap().output;
+        assert!(output.contains("Ruta: ToolExecution (Ask, con RAPTOR) (confianza 0.90)"));
+        assert!(output.contains("prefiere config, ci"));
+        assert!(output.contains("ci por \"ci\""));
+
+        ctx.state.lock().await.last_confidence = Some(AnswerConfidence::assess(&[0.7, 0.5], &[]));
+        let output = WhyCommand.execute("", &ctx).await.unwrap().output;
+        assert!(output.contains("Confianza: media\n  Fuentes: 0 citadas, 0 verificadas\n  Similitud: 2 fragmentos, máx 0.70, media 0.60"));
+
+        let preview = WhyCommand.execute("fix the flaky tests", &ctx).await.unwrap().output;
+        assert!(preview.contains("prefiere test"));
+        assert!(WhyCommand.execute("rules", &ctx).await.unwrap().output.contains("[intents]"));
+    }
+}
@@ -0,0 +1,116 @@
+//! Workspace Commands - Change the session's working directory and its roots
+
+use super::{CommandCategory, CommandContext, CommandResult, SlashCommand};
+use crate::agent::workspace;
+use anyhow::Result;
+use std::path::Path;
+
+pub struct CdCommand;
+
+#[async_trait::async_trait]
+impl SlashCommand for CdCommand {
+    fn name(&self) -> &str {
+        "cd"
+    }
+
+    fn description(&self) -> &str {
+        "Change the session's working directory"
+    }
+
+    fn usage(&self) -> &str {
+        "/cd - Show the working directory\n\
+         /cd <path> - Move to <path> (inside the workspace roots)"
+    }
+
+    fn category(&self) -> CommandCategory {
+        CommandCategory::System
+    }
+
+    async fn execute(&self, args: &str, ctx: &CommandContext) -> Result<CommandResult> {
+        let mut state = ctx.state.lock().await;
+        if args.trim().is_empty() {
+            return Ok(CommandResult::success(format!("📂 {}", state.working_dir)));
+        }
+
+        match workspace::change_dir(Path::new(&state.working_dir), args, &state.roots) {
+            Ok(target) => {
+                state.working_dir = target.to_string_lossy().to_string();
+                Ok(CommandResult::success(format!(
+                    "📂 {}\nLas herramientas y el contexto git usan el nuevo directorio; /reindex reconstruye el índice RAPTOR para él",
+                    state.working_dir
+                )))
+            }
+            Err(e) => Ok(CommandResult::error(e.to_string())),
+        }
+    }
+}
+
+pub struct RootsCommand;
+
+#[async_trait::async_trait]
+impl SlashCommand for RootsCommand {
+    fn name(&self) -> &str {
+        "roots"
+    }
+
+    fn description(&self) -> &str {
+        "List the workspace roots and add read-only ones"
+    }
+
+    fn usage(&self) -> &str {
+        "/roots - List the roots\n\
+         /roots add <path> - Add a secondary root the tools may read but not write\n\
+         /roots remove <path> - Remove a secondary root"
+    }
+
+    fn category(&self) -> CommandCategory {
+        CommandCategory::System
+    }
+
+    async fn execute(&self, args: &str, ctx: &CommandContext) -> Result<CommandResult> {
+        let mut state = ctx.state.lock().await;
+        let (subcommand, path) = args.trim().split_once(' ').unwrap_or((args.trim(), ""));
+        let path = path.trim();
+
+        match subcommand {
+            "" => {
+                let mut output = format!("📂 Directorio de trabajo: {}\n\nRaíces (lectura y escritura, /cd):\n", state.working_dir);
+                for root in &state.roots {
+                    output.push_str(&format!("  {}\n", root.display()));
+                }
+                if state.read_only_roots.is_empty() {
+                    output.push_str("\nSin raíces de solo lectura (/roots add <ruta>)");
+                } else {
+                    output.push_str("\nRaíces de solo lectura:\n");
+                    for root in &state.read_only_roots {
+                        output.push_str(&format!("  🔒 {}\n", root.display()));
+                    }
+                }
+                Ok(CommandResult::success(output))
+            }
+            "add" if !path.is_empty() => match workspace::resolve_dir(Path::new(&state.working_dir), path) {
+                Ok(root) if state.read_only_roots.contains(&root) => {
+                    Ok(CommandResult::success(format!("🔒 {} ya es una raíz de solo lectura", root.display())))
+                }
+                Ok(root) => {
+                    let output = format!("🔒 {} añadida como raíz de solo lectura", root.display());
+                    state.read_only_roots.push(root);
+                    Ok(CommandResult::success(output))
+                }
+                Err(e) => Ok(CommandResult::error(e.to_string())),
+            },
+            "remove" if !path.is_empty() => {
+                let root = workspace::resolve_dir(Path::new(&state.working_dir), path)
+                    .unwrap_or_else(|_| Path::new(path).to_path_buf());
+                let before = state.read_only_roots.len();
+                state.read_only_roots.retain(|r| r != &root);
+                if state.read_only_roots.len() < before {
+                    Ok(CommandResult::success(format!("Raíz {} eliminada", root.display())))
+                } else {
+                    Ok(CommandResult::error(format!("{} no es una raíz de solo lectura", root.display())))
+                }
+            }
+            _ => Ok(CommandResult::error(format!("Usage: {}", self.usage()))),
+        }
+    }
+}
@@ -1,9 +1,14 @@
 //! Agent state management with shared context
 
+use super::confidence::AnswerConfidence;
+use super::conflicts::ConflictSet;
+use super::recipes::RecipeRun;
+use crate::raptor::intent::RetrievalFilter;
 use crate::tools::TaskPlan;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::PathBuf;
 use std::sync::Arc;
 use tokio::sync::Mutex;
 use uuid::Uuid;
@@ -172,6 +177,26 @@ impl StreamingState {
     }
 }
 
+/// How the router handled the last request, shown by /why
+#[derive(Debug, Clone)]
+pub struct RouteTrace {
+    pub query: String,
+    /// Route taken, e.g. "ToolExecution (Ask, con RAPTOR)"
+    pub route: String,
+    pub confidence: Option<f64>,
+    /// Why: the classifier's explanation, or the rule or cache that decided
+    pub reason: String,
+    /// Kinds of chunks retrieval preferred for the query
+    pub filter: RetrievalFilter,
+}
+
+/// Files cited by the last answer, which /sources can mark irrelevant
+#[derive(Debug, Clone, Default)]
+pub struct AnswerSources {
+    pub query: String,
+    pub paths: Vec<String>,
+}
+
 /// Shared agent state
 #[derive(Debug)]
 pub struct AgentState {
@@ -185,12 +210,40 @@ pub struct AgentState {
     pub active_plans: HashMap<Uuid, TaskPlan>,
     /// Streaming state
     pub streaming: StreamingState,
-    /// Working directory
+    /// Working directory, changed with /cd
     pub working_dir: String,
+    /// Directories /cd may move into
+    pub roots: Vec<PathBuf>,
+    /// Secondary roots the tools may read but not write
+    pub read_only_roots: Vec<PathBuf>,
     /// Maximum history messages to keep in context
     pub max_history: usize,
     /// Total tokens used in this session
     pub total_tokens: u64,
+    /// Session-scoped environment variables for shell and http tools (/env)
+    pub env_vars: BTreeMap<String, String>,
+    /// The current request runs in Build mode, so tools may modify data
+    pub build_mode: bool,
+    /// Merge conflicts being resolved with /resolve
+    pub conflicts: Option<ConflictSet>,
+    /// Triage prompt for the flaky tests found by the last /test --detect-flaky
+    pub flaky_triage: Option<String>,
+    /// Recipe started with /recipe run, between steps
+    pub recipe: Option<RecipeRun>,
+    /// Tools turned off for this project (/tools, Settings)
+    pub disabled_tools: BTreeSet<String>,
+    /// Files pinned into the prompt by the conversation template in use (/start)
+    pub pinned_files: Vec<String>,
+    /// Tokens of retrieved context the router added to the current request
+    pub retrieval_tokens: usize,
+    /// Routing of the last classified request (/why)
+    pub last_route: Option<RouteTrace>,
+    /// Sources cited by the last answer (/sources)
+    pub last_sources: Option<AnswerSources>,
+    /// Similarity of the chunks retrieved for the current request
+    pub retrieval_scores: Vec<f32>,
+    /// Confidence shown under the last answer (/why)
+    pub last_confidence: Option<AnswerConfidence>,
 }
 
 impl Default for AgentState {
@@ -210,8 +263,22 @@ impl AgentState {
             working_dir: std::env::current_dir()
                 .map(|p| p.to_string_lossy().to_string())
                 .unwrap_or_else(|_| ".".to_string()),
+            roots: Vec::new(),
+            read_only_roots: Vec::new(),
             max_history: 50,
             total_tokens: 0,
+            env_vars: BTreeMap::new(),
+            build_mode: false,
+            conflicts: None,
+            flaky_triage: None,
+            recipe: None,
+            disabled_tools: BTreeSet::new(),
+            pinned_files: Vec::new(),
+            retrieval_tokens: 0,
+            last_route: None,
+            last_sources: None,
+            retrieval_scores: Vec::new(),
+            last_confidence: None,
         }
     }
 
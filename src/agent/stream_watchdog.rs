@@ -0,0 +1,136 @@
+//! Watchdog for streamed model answers
+//!
+//! A stream that sends no bytes for [`stall_timeout`] (`stream_stall_secs` in
+//! the config) is stalled. The provider layer then reconnects once, asking the
+//! model to continue the partial answer, and if that stalls or breaks too the
+//! turn ends with [`AgentEvent::StreamAborted`](super::AgentEvent), which keeps
+//! the text received so far, instead of hanging until the UI gives up.
+
+use futures::{Stream, StreamExt};
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+/// Seconds without bytes before a stream counts as stalled
+pub const DEFAULT_STALL_SECS: u64 = 30;
+
+/// Reconnections tried before the stream is given up
+pub const MAX_RECONNECTS: u32 = 1;
+
+/// Stall timeout in milliseconds, 0 when the watchdog is off
+static STALL_MS: AtomicU64 = AtomicU64::new(DEFAULT_STALL_SECS * 1000);
+
+/// Set the stall timeout for the whole process; `None` turns the watchdog off
+pub fn set_stall_timeout(timeout: Option<Duration>) {
+    let millis = timeout.map_or(0, |timeout| timeout.as_millis().max(1) as u64);
+    STALL_MS.store(millis, Ordering::Relaxed);
+}
+
+/// From `stream_stall_secs`, where 0 turns the watchdog off
+pub fn set_stall_secs(secs: u64) {
+    set_stall_timeout((secs > 0).then(|| Duration::from_secs(secs)));
+}
+
+pub fn stall_timeout() -> Option<Duration> {
+    match STALL_MS.load(Ordering::Relaxed) {
+        0 => None,
+        millis => Some(Duration::from_millis(millis)),
+    }
+}
+
+/// Why a stream was given up
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StreamFailure {
+    /// No bytes for this long
+    Stalled(Duration),
+    /// The connection failed or could not be reopened
+    Broken(String),
+}
+
+impl fmt::Display for StreamFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StreamFailure::Stalled(after) => write!(f, "no data for {:?}", after),
+            StreamFailure::Broken(error) => write!(f, "connection lost: {}", error),
+        }
+    }
+}
+
+/// What a stream delivered so far, across reconnections
+#[derive(Debug, Clone, Default)]
+pub struct StreamHealth {
+    pub bytes: usize,
+    pub chunks: usize,
+    pub reconnects: u32,
+    /// Answer text sent on, kept for the continuation and the abort event
+    pub partial: String,
+}
+
+impl StreamHealth {
+    pub fn received(&mut self, bytes: usize) {
+        self.bytes += bytes;
+        self.chunks += 1;
+    }
+
+    /// Count a reconnection, if one is left
+    pub fn try_reconnect(&mut self) -> bool {
+        if self.reconnects >= MAX_RECONNECTS {
+            return false;
+        }
+        self.reconnects += 1;
+        true
+    }
+}
+
+/// The next item of `stream`, or a stall when nothing comes in time
+pub async fn next_item<S: Stream + Unpin>(stream: &mut S) -> Result<Option<S::Item>, StreamFailure> {
+    next_within(stream, stall_timeout()).await
+}
+
+async fn next_within<S: Stream + Unpin>(
+    stream: &mut S,
+    limit: Option<Duration>,
+) -> Result<Option<S::Item>, StreamFailure> {
+    match limit {
+        Some(limit) => tokio::time::timeout(limit, stream.next())
+            .await
+            .map_err(|_| StreamFailure::Stalled(limit)),
+        None => Ok(stream.next().await),
+    }
+}
+
+/// Prompt asking the model to carry on from `partial` without repeating it
+pub fn continuation_prompt(prompt: &str, partial: &str) -> String {
+    if partial.trim().is_empty() {
+        return prompt.to_string();
+    }
+    format!(
+        "{}\n\n---\nYour previous answer was cut off. This is what was already sent:\n\n{}\n\n---\n\
+         Continue exactly where it stops. Do not repeat any of it and do not comment on the interruption.",
+        prompt, partial
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_stall_and_reconnect_budget() {
+        let limit = Some(Duration::from_millis(20));
+        let mut pending = futures::stream::pending::<u8>();
+        assert_eq!(
+            next_within(&mut pending, limit).await,
+            Err(StreamFailure::Stalled(Duration::from_millis(20)))
+        );
+        let mut ready = futures::stream::iter([1u8]);
+        assert_eq!(next_within(&mut ready, limit).await, Ok(Some(1)));
+        assert_eq!(StreamFailure::Stalled(Duration::from_secs(30)).to_string(), "no data for 30s");
+
+        let mut health = StreamHealth::default();
+        assert!(health.try_reconnect());
+        assert!(!health.try_reconnect());
+        assert_eq!(continuation_prompt("explain", "  "), "explain");
+        assert!(continuation_prompt("explain", "Ownership means").contains("Ownership means"));
+    }
+}
@@ -2,7 +2,9 @@
 //!
 //! Provides streaming response capabilities for real-time token generation.
 
+use super::reasoning::{self, Segment, ThinkSplitter};
 use super::router_orchestrator::{RouterConfig, RouterOrchestrator};
+use crate::security::network::{self, SendChecked};
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
 use tokio::sync::mpsc::Sender;
@@ -23,6 +25,8 @@ struct OllamaStreamRequest {
     tools: Option<Vec<serde_json::Value>>,
     stream: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
+    think: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     options: Option<OllamaOptions>,
 }
 
@@ -82,6 +86,7 @@ impl RouterOrchestrator {
             messages,
             tools: None, // For now, no tools in streaming mode
             stream: true, // Enable streaming
+            think: reasoning::think_option(),
             options: Some(OllamaOptions {
                 temperature: 0.7,
                 top_p: 0.95,
@@ -90,13 +95,13 @@ impl RouterOrchestrator {
         };
         
         // Create HTTP client
-        let client = reqwest::Client::new();
+        let client = network::client();
         
         // Send streaming request
         let response = client
             .post(&url)
             .json(&request)
-            .send()
+            .send_checked("model provider")
             .await?;
         
         if !response.status().is_success() {
@@ -113,6 +118,8 @@ impl RouterOrchestrator {
         let mut full_response = String::new();
         let mut stream = response.bytes_stream();
         let mut buffer = String::new();
+        // Only the answer is sent; `<think>` reasoning is dropped
+        let mut splitter = ThinkSplitter::new();
         
         while let Some(chunk_result) = stream.next().await {
             let chunk_bytes = chunk_result?;
@@ -131,7 +138,17 @@ impl RouterOrchestrator {
                 // Parse JSON chunk
                 match serde_json::from_str::<OllamaStreamChunk>(line) {
                     Ok(stream_chunk) => {
-                        let content = stream_chunk.message.content;
+                        let mut segments = splitter.push(&stream_chunk.message.content);
+                        if stream_chunk.done {
+                            segments.extend(splitter.finish());
+                        }
+                        let content: String = segments
+                            .into_iter()
+                            .filter_map(|segment| match segment {
+                                Segment::Answer(text) => Some(text),
+                                Segment::Thinking(_) => None,
+                            })
+                            .collect();
                         
                         if !content.is_empty() {
                             full_response.push_str(&content);
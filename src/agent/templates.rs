@@ -0,0 +1,340 @@
+//! Conversation templates: saved starters for recurring tasks
+//!
+//! A template bundles a prompt, the mode it runs in, files pinned into the
+//! prompt and tools it must not use. Templates are YAML files in
+//! `.neuro/templates/` (shared with the project) or `~/.config/neuro/templates/`
+//! (personal); the built-in ones are always available and a file with the same
+//! name replaces them:
+//!
+//! ```yaml
+//! name: standup
+//! description: Daily standup review
+//! mode: ask
+//! prompt: "Summarize what changed since yesterday. {{input}}"
+//! files: [CHANGELOG.md]
+//! disable_tools: [write_file, execute_shell]
+//! ```
+//!
+//! `/start <name> [text]` launches one, with the text in place of `{{input}}`;
+//! `/start save <name>` stores the current setup as a project template.
+
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+/// Project templates, relative to the working directory
+pub const PROJECT_TEMPLATES_DIR: &str = ".neuro/templates";
+/// Characters of each pinned file included in the prompt
+const MAX_PINNED_CHARS: usize = 8000;
+
+/// Templates shipped with neuro
+const BUILT_IN: &[&str] = &[
+    r#"
+name: review-changes
+description: Revisar mis cambios sin commitear
+mode: ask
+prompt: |
+  Revisa mis cambios sin commitear (git status y git diff). Señala bugs, riesgos
+  y lo que falta (tests, docs, manejo de errores), agrupado por archivo y del más
+  grave al menos grave. {{input}}
+disable_tools: [write_file, replace_in_files]
+"#,
+    r#"
+name: explain-repo
+description: Explicar este repositorio
+mode: ask
+prompt: |
+  Explica este repositorio: para qué sirve, su arquitectura, los módulos
+  principales y cómo se compila, se prueba y se ejecuta. {{input}}
+files: [README.md]
+disable_tools: [write_file, replace_in_files, execute_shell]
+"#,
+    r#"
+name: standup
+description: Resumen para la daily
+mode: ask
+prompt: |
+  Prepara mi resumen para la daily a partir de los commits desde ayer
+  (git log --since=yesterday) y de los cambios sin commitear: hecho, en curso y
+  posibles bloqueos, en viñetas cortas. {{input}}
+disable_tools: [write_file, replace_in_files]
+"#,
+];
+
+#[derive(Debug, Error)]
+pub enum TemplateError {
+    #[error("Unknown template '{0}' (see /start list)")]
+    NotFound(String),
+    #[error("Invalid template {0}: {1}")]
+    Parse(PathBuf, serde_yaml::Error),
+    #[error("Template {0} has an empty prompt")]
+    EmptyPrompt(String),
+    #[error("Invalid template name '{0}': use letters, digits, '-' and '_'")]
+    InvalidName(String),
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// How a template's prompt is run
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TemplateMode {
+    /// Read-only: tools may look but not modify
+    #[default]
+    Ask,
+    /// Tools may write files and run commands
+    Build,
+    /// Produce a plan without running it
+    Plan,
+}
+
+impl TemplateMode {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Ask => "ask",
+            Self::Build => "build",
+            Self::Plan => "plan",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "ask" => Some(Self::Ask),
+            "build" => Some(Self::Build),
+            "plan" => Some(Self::Plan),
+            _ => None,
+        }
+    }
+}
+
+/// A conversation starter loaded from YAML
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ConversationTemplate {
+    /// Defaults to the file name
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub mode: TemplateMode,
+    /// May use `{{input}}`, the text after the template name
+    pub prompt: String,
+    /// Files read into the prompt, relative to the working directory
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub files: Vec<String>,
+    /// Tools turned off for the session while the template runs
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub disable_tools: Vec<String>,
+}
+
+impl ConversationTemplate {
+    pub fn from_yaml(path: &Path, yaml: &str) -> Result<Self, TemplateError> {
+        let mut template: ConversationTemplate =
+            serde_yaml::from_str(yaml).map_err(|e| TemplateError::Parse(path.to_path_buf(), e))?;
+        if template.name.is_empty() {
+            template.name = template_name(path);
+        }
+        if template.prompt.trim().is_empty() {
+            return Err(TemplateError::EmptyPrompt(template.name));
+        }
+        Ok(template)
+    }
+
+    /// The prompt with `input` in place of `{{input}}` (or after it) and the
+    /// pinned files of `working_dir` attached
+    pub fn render(&self, input: &str, working_dir: &Path) -> String {
+        let input = input.trim();
+        let mut prompt = if self.prompt.contains("{{input}}") {
+            self.prompt.replace("{{input}}", input)
+        } else if input.is_empty() {
+            self.prompt.clone()
+        } else {
+            format!("{}\n\n{}", self.prompt.trim_end(), input)
+        };
+        prompt = prompt.trim().to_string();
+
+        let pinned: Vec<String> = self
+            .files
+            .iter()
+            .filter_map(|file| {
+                let content = std::fs::read_to_string(working_dir.join(file))
+                    .map_err(|e| tracing::warn!("Template {}: cannot read {}: {}", self.name, file, e))
+                    .ok()?;
+                let excerpt: String = content.chars().take(MAX_PINNED_CHARS).collect();
+                let cut = if excerpt.len() < content.len() { "\n[...]" } else { "" };
+                Some(format!("### {}\n```\n{}{}\n```", file, excerpt.trim_end(), cut))
+            })
+            .collect();
+        if !pinned.is_empty() {
+            prompt.push_str("\n\nArchivos fijados:\n");
+            prompt.push_str(&pinned.join("\n\n"));
+        }
+        prompt
+    }
+}
+
+fn template_name(path: &Path) -> String {
+    path.file_stem().unwrap_or_default().to_string_lossy().to_string()
+}
+
+fn is_valid_name(name: &str) -> bool {
+    !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+/// The templates shipped with neuro
+pub fn built_in() -> Vec<ConversationTemplate> {
+    BUILT_IN
+        .iter()
+        .filter_map(|yaml| ConversationTemplate::from_yaml(Path::new("built-in"), yaml).ok())
+        .collect()
+}
+
+/// Templates of the project, of the user and built in
+#[derive(Debug, Clone)]
+pub struct TemplateBook {
+    dirs: Vec<PathBuf>,
+}
+
+impl TemplateBook {
+    /// Project templates first, so they win over personal and built-in ones
+    pub fn new(working_dir: &Path) -> Self {
+        // `.neuro/` may sit at the repository root, above the working directory
+        let project_dir = crate::config::ProjectConfig::discover(working_dir)
+            .ok()
+            .and_then(|project| project.subdir("templates"))
+            .unwrap_or_else(|| working_dir.join(PROJECT_TEMPLATES_DIR));
+        let mut dirs = vec![project_dir];
+        if let Some(config_dir) = crate::config::AppConfig::config_dir() {
+            dirs.push(config_dir.join("templates"));
+        }
+        Self { dirs }
+    }
+
+    /// Look only in `dirs` (and the built-in templates), earlier ones first
+    pub fn with_dirs(dirs: Vec<PathBuf>) -> Self {
+        Self { dirs }
+    }
+
+    fn files(&self) -> Vec<PathBuf> {
+        let mut files = Vec::new();
+        for dir in &self.dirs {
+            let Ok(entries) = std::fs::read_dir(dir) else { continue };
+            let mut found: Vec<PathBuf> = entries
+                .filter_map(|e| e.ok().map(|e| e.path()))
+                .filter(|p| matches!(p.extension().and_then(|e| e.to_str()), Some("yaml" | "yml")))
+                .collect();
+            found.sort();
+            files.extend(found);
+        }
+        files
+    }
+
+    /// All templates that parse, by name; invalid files are returned apart
+    pub fn list(&self) -> (Vec<ConversationTemplate>, Vec<TemplateError>) {
+        let mut templates: Vec<ConversationTemplate> = Vec::new();
+        let mut errors = Vec::new();
+        for path in self.files() {
+            match std::fs::read_to_string(&path)
+                .map_err(TemplateError::from)
+                .and_then(|yaml| ConversationTemplate::from_yaml(&path, &yaml))
+            {
+                Ok(template) if templates.iter().any(|t| t.name == template.name) => {}
+                Ok(template) => templates.push(template),
+                Err(e) => errors.push(e),
+            }
+        }
+        for template in built_in() {
+            if !templates.iter().any(|t| t.name == template.name) {
+                templates.push(template);
+            }
+        }
+        templates.sort_by(|a, b| a.name.cmp(&b.name));
+        (templates, errors)
+    }
+
+    pub fn load(&self, name: &str) -> Result<ConversationTemplate, TemplateError> {
+        for path in self.files() {
+            let parsed = std::fs::read_to_string(&path)
+                .map_err(TemplateError::from)
+                .and_then(|yaml| ConversationTemplate::from_yaml(&path, &yaml));
+            match parsed {
+                Ok(template) if template.name == name => return Ok(template),
+                // A broken file named like the template is reported, others are ignored
+                Err(e) if template_name(&path) == name => return Err(e),
+                _ => {}
+            }
+        }
+        built_in()
+            .into_iter()
+            .find(|template| template.name == name)
+            .ok_or_else(|| TemplateError::NotFound(name.to_string()))
+    }
+
+    /// Write `template` to the first directory (the project's); returns its path
+    pub fn save(&self, template: &ConversationTemplate) -> Result<PathBuf, TemplateError> {
+        if !is_valid_name(&template.name) {
+            return Err(TemplateError::InvalidName(template.name.clone()));
+        }
+        if template.prompt.trim().is_empty() {
+            return Err(TemplateError::EmptyPrompt(template.name.clone()));
+        }
+        let dir = self.dirs.first().cloned().unwrap_or_else(|| PathBuf::from(PROJECT_TEMPLATES_DIR));
+        std::fs::create_dir_all(&dir)?;
+        let path = dir.join(format!("{}.yaml", template.name));
+        let yaml = serde_yaml::to_string(template)
+            .map_err(|e| TemplateError::Parse(path.clone(), e))?;
+        std::fs::write(&path, yaml)?;
+        Ok(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_built_in_templates_parse() {
+        let names: Vec<String> = built_in().into_iter().map(|t| t.name).collect();
+        assert_eq!(names, vec!["review-changes", "explain-repo", "standup"]);
+    }
+
+    #[test]
+    fn test_render_fills_input_and_pins_files() {
+        let dir = tempdir().unwrap();
+        std::fs::write(dir.path().join("README.md"), "# Demo\n").unwrap();
+        let template = ConversationTemplate {
+            name: "explain".to_string(),
+            prompt: "Explain this repo. {{input}}".to_string(),
+            files: vec!["README.md".to_string(), "missing.md".to_string()],
+            ..Default::default()
+        };
+
+        let prompt = template.render("Focus on the CLI", dir.path());
+        assert!(prompt.starts_with("Explain this repo. Focus on the CLI"));
+        assert!(prompt.contains("### README.md\n```\n# Demo\n```"));
+        assert!(!prompt.contains("missing.md"));
+    }
+
+    #[test]
+    fn test_saved_template_overrides_built_in() {
+        let dir = tempdir().unwrap();
+        let book = TemplateBook::with_dirs(vec![dir.path().join("templates")]);
+        let template = ConversationTemplate {
+            name: "standup".to_string(),
+            mode: TemplateMode::Plan,
+            prompt: "Plan my day".to_string(),
+            disable_tools: vec!["execute_shell".to_string()],
+            ..Default::default()
+        };
+
+        book.save(&template).unwrap();
+        assert_eq!(book.load("standup").unwrap(), template);
+        assert_eq!(book.list().0.len(), 3);
+        assert!(matches!(book.load("nope"), Err(TemplateError::NotFound(_))));
+        assert!(matches!(
+            book.save(&ConversationTemplate { name: "../x".to_string(), ..template }),
+            Err(TemplateError::InvalidName(_))
+        ));
+    }
+}
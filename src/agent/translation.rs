@@ -0,0 +1,188 @@
+//! Read-time translation of answers (/translate)
+//!
+//! The fast model rewrites an answer in another language for whoever reads
+//! it; the original stays in the history the models see and in the session
+//! database. Fenced code blocks are taken out before the call and put back
+//! afterwards, so code and commands come back exactly as they were.
+
+/// Languages known by code or name; anything else is passed to the model as typed
+const LANGUAGES: &[(&[&str], &str)] = &[
+    (&["en", "english", "inglés", "ingles"], "English"),
+    (&["es", "spanish", "español", "espanol", "castellano"], "Spanish"),
+    (&["pt", "pt-br", "portuguese", "portugués", "portugues"], "Portuguese"),
+    (&["fr", "french", "français", "francés", "frances"], "French"),
+    (&["de", "german", "deutsch", "alemán", "aleman"], "German"),
+    (&["it", "italian", "italiano"], "Italian"),
+    (&["nl", "dutch", "nederlands"], "Dutch"),
+    (&["pl", "polish", "polski"], "Polish"),
+    (&["ru", "russian", "ruso"], "Russian"),
+    (&["uk", "ukrainian"], "Ukrainian"),
+    (&["tr", "turkish"], "Turkish"),
+    (&["ja", "japanese", "japonés", "japones"], "Japanese"),
+    (&["zh", "chinese", "chino"], "Chinese"),
+    (&["ko", "korean", "coreano"], "Korean"),
+];
+
+/// Longest language name accepted as typed
+const MAX_LANGUAGE_LEN: usize = 30;
+
+/// Marker of the n-th code block while the prose is translated
+fn placeholder(index: usize) -> String {
+    format!("⟦CODE{}⟧", index)
+}
+
+/// Name of the language `input` refers to (`en`, `inglés`, `English`);
+/// `None` when it cannot be a language name
+pub fn language_name(input: &str) -> Option<String> {
+    let input = input.trim();
+    let key = input.to_lowercase();
+    if let Some((_, name)) = LANGUAGES.iter().find(|(aliases, _)| aliases.contains(&key.as_str())) {
+        return Some(name.to_string());
+    }
+    let valid = !input.is_empty()
+        && input.chars().count() <= MAX_LANGUAGE_LEN
+        && input.chars().all(|c| c.is_alphabetic() || c == ' ' || c == '-');
+    valid.then(|| {
+        let mut chars = input.chars();
+        chars
+            .next()
+            .map(|first| first.to_uppercase().chain(chars).collect())
+            .unwrap_or_default()
+    })
+}
+
+/// `text` with its fenced code blocks replaced by placeholders, and the blocks
+pub fn protect_code(text: &str) -> (String, Vec<String>) {
+    let mut prose = Vec::new();
+    let mut blocks: Vec<String> = Vec::new();
+    let mut block: Option<Vec<&str>> = None;
+    for line in text.lines() {
+        let fence = line.trim_start().starts_with("```");
+        match &mut block {
+            Some(lines) => {
+                lines.push(line);
+                if fence {
+                    prose.push(placeholder(blocks.len()));
+                    blocks.push(lines.join("\n"));
+                    block = None;
+                }
+            }
+            None if fence => block = Some(vec![line]),
+            None => prose.push(line.to_string()),
+        }
+    }
+    // An unclosed fence runs to the end of the answer
+    if let Some(lines) = block {
+        prose.push(placeholder(blocks.len()));
+        blocks.push(lines.join("\n"));
+    }
+    (prose.join("\n"), blocks)
+}
+
+/// Put the code blocks back; blocks whose placeholder the model dropped go at the end
+pub fn restore_code(translated: &str, blocks: &[String]) -> String {
+    let mut text = translated.trim().to_string();
+    for (index, block) in blocks.iter().enumerate() {
+        let marker = placeholder(index);
+        if text.contains(&marker) {
+            text = text.replacen(&marker, block, 1);
+        } else {
+            text.push_str("\n\n");
+            text.push_str(block);
+        }
+    }
+    text
+}
+
+/// Prompt for the fast model to translate `prose` (code already protected)
+pub fn prompt(prose: &str, language: &str) -> String {
+    format!(
+        "Translate the following answer into {}. Keep the Markdown formatting, file paths, \
+         identifiers, inline code and every marker like ⟦CODE0⟧ exactly as they are. \
+         Reply with the translation only, with no introduction or notes.\n\n---\n{}",
+        language, prose
+    )
+}
+
+/// Translations of one message, kept with it so switching back and forth
+/// does not call the model again
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Translations {
+    /// (language, text), in the order they were made
+    entries: Vec<(String, String)>,
+    /// Language shown instead of the original, if any
+    shown: Option<String>,
+}
+
+impl Translations {
+    pub fn get(&self, language: &str) -> Option<&str> {
+        self.entries
+            .iter()
+            .find(|(lang, _)| lang == language)
+            .map(|(_, text)| text.as_str())
+    }
+
+    /// Store a translation and show it
+    pub fn insert(&mut self, language: &str, text: String) {
+        self.entries.retain(|(lang, _)| lang != language);
+        self.entries.push((language.to_string(), text));
+        self.shown = Some(language.to_string());
+    }
+
+    /// Show a cached translation, or the original with `None`; false when
+    /// there is no translation to that language yet
+    pub fn show(&mut self, language: Option<&str>) -> bool {
+        match language {
+            Some(language) if self.get(language).is_none() => false,
+            language => {
+                self.shown = language.map(str::to_string);
+                true
+            }
+        }
+    }
+
+    /// Language and text of the translation shown instead of the original
+    pub fn shown(&self) -> Option<(&str, &str)> {
+        let language = self.shown.as_deref()?;
+        Some((language, self.get(language)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_language_names() {
+        assert_eq!(language_name("EN").as_deref(), Some("English"));
+        assert_eq!(language_name("inglés").as_deref(), Some("English"));
+        assert_eq!(language_name("catalán").as_deref(), Some("Catalán"));
+        assert_eq!(language_name("en; rm -rf"), None);
+        assert_eq!(language_name(""), None);
+    }
+
+    #[test]
+    fn test_code_survives_translation() {
+        let answer = "Usa esto:\n```sh\ncargo test -- --nocapture\n```\nY listo.";
+        let (prose, blocks) = protect_code(answer);
+        assert_eq!(prose, "Usa esto:\n⟦CODE0⟧\nY listo.");
+        assert_eq!(blocks, vec!["```sh\ncargo test -- --nocapture\n```"]);
+        assert_eq!(
+            restore_code("Use this:\n⟦CODE0⟧\nAnd done.\n", &blocks),
+            "Use this:\n```sh\ncargo test -- --nocapture\n```\nAnd done."
+        );
+        assert!(restore_code("Use this.", &blocks).ends_with("\n\n```sh\ncargo test -- --nocapture\n```"));
+    }
+
+    #[test]
+    fn test_translation_cache() {
+        let mut translations = Translations::default();
+        assert!(!translations.show(Some("English")));
+        translations.insert("English", "Hello".to_string());
+        assert_eq!(translations.shown(), Some(("English", "Hello")));
+        assert!(translations.show(None));
+        assert_eq!(translations.shown(), None);
+        assert!(translations.show(Some("English")));
+        assert_eq!(translations.get("English"), Some("Hello"));
+    }
+}
@@ -0,0 +1,150 @@
+//! Time budget of a turn
+//!
+//! A turn gets `turn_budget_secs` (120 s by default). In its last stretch no
+//! new tool calls start; when time runs out the work done so far is returned
+//! with what remains, and the conversation is kept as a [`Continuation`] that
+//! `/continue` resumes instead of throwing everything away.
+
+use super::provider::OllamaToolCall;
+use super::response::ToolCallRecord;
+use std::time::{Duration, Instant};
+
+/// Shortest wind-down, for small budgets
+const MIN_WIND_DOWN: Duration = Duration::from_secs(10);
+
+/// Deadline of a turn and the stretch before it in which no tool calls start
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TurnBudget {
+    started: Instant,
+    limit: Duration,
+    wind_down: Duration,
+}
+
+impl TurnBudget {
+    /// Budget of `limit` from now; tools stop in its last sixth (at least 10 s)
+    pub fn new(limit: Duration) -> Self {
+        Self::starting_at(Instant::now(), limit)
+    }
+
+    pub fn starting_at(started: Instant, limit: Duration) -> Self {
+        Self {
+            started,
+            limit,
+            wind_down: (limit / 6).max(MIN_WIND_DOWN).min(limit),
+        }
+    }
+
+    pub fn limit(&self) -> Duration {
+        self.limit
+    }
+
+    pub fn deadline(&self) -> Instant {
+        self.started + self.limit
+    }
+
+    pub fn remaining(&self) -> Duration {
+        self.deadline().saturating_duration_since(Instant::now())
+    }
+
+    /// Close to the deadline: finish what runs, start nothing new
+    pub fn winding_down(&self) -> bool {
+        self.remaining() <= self.wind_down
+    }
+
+    pub fn expired(&self) -> bool {
+        self.remaining().is_zero()
+    }
+}
+
+/// Tool loop stopped by the budget, resumable with `/continue`
+#[derive(Debug, Clone)]
+pub struct Continuation {
+    pub model: String,
+    /// Messages so far, ending with the model's last turn or tool results
+    pub conversation: Vec<serde_json::Value>,
+    /// Tool calls the model asked for that never started
+    pub pending: Vec<OllamaToolCall>,
+}
+
+/// Answer of a turn the budget stopped: the model's draft, the tool calls that
+/// ran and those that remain
+pub fn partial_answer(
+    budget: &TurnBudget,
+    draft: Option<&str>,
+    done: &[ToolCallRecord],
+    pending: &[OllamaToolCall],
+) -> String {
+    let mut out = format!(
+        "⏱️ Se agotó el tiempo del turno ({}s). Resultado parcial:\n",
+        budget.limit().as_secs()
+    );
+    if let Some(draft) = draft.map(str::trim).filter(|draft| !draft.is_empty()) {
+        out.push('\n');
+        out.push_str(draft);
+        out.push('\n');
+    }
+    if !done.is_empty() {
+        out.push_str("\n**Completado:**\n");
+        for call in done {
+            let mark = if call.success { "✓" } else { "✗" };
+            out.push_str(&format!("- {} {} {}\n", mark, call.tool, compact_args(&call.args)));
+        }
+    }
+    out.push_str("\n**Pendiente:**\n");
+    if pending.is_empty() {
+        out.push_str("- Terminar la respuesta con los resultados anteriores\n");
+    }
+    for call in pending {
+        out.push_str(&format!("- {} {}\n", call.function.name, compact_args(&call.function.arguments)));
+    }
+    out.push_str("\nUsa /continue para retomar donde se quedó.");
+    out
+}
+
+/// Arguments on one short line
+fn compact_args(args: &serde_json::Value) -> String {
+    let text = match args {
+        serde_json::Value::Object(map) if map.is_empty() => return String::new(),
+        serde_json::Value::Null => return String::new(),
+        other => other.to_string(),
+    };
+    match text.char_indices().nth(80) {
+        Some((cut, _)) => format!("{}…", &text[..cut]),
+        None => text,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent::provider::OllamaFunctionCall;
+    use serde_json::json;
+
+    #[test]
+    fn test_budget_winds_down_before_deadline() {
+        let budget = TurnBudget::starting_at(Instant::now() - Duration::from_secs(105), Duration::from_secs(120));
+        assert!(budget.winding_down());
+        assert!(!budget.expired());
+
+        let fresh = TurnBudget::new(Duration::from_secs(120));
+        assert!(!fresh.winding_down());
+        // Small budgets keep a usable wind-down without exceeding the limit
+        assert!(TurnBudget::new(Duration::from_secs(5)).winding_down());
+    }
+
+    #[test]
+    fn test_partial_answer_lists_done_and_pending() {
+        let budget = TurnBudget::new(Duration::from_secs(120));
+        let done = [ToolCallRecord::new("read_file", &json!({"path": "src/main.rs"}), "fn main() {}", Duration::ZERO)];
+        let pending = [OllamaToolCall {
+            function: OllamaFunctionCall { name: "search_files".to_string(), arguments: json!({"pattern": "TODO"}) },
+        }];
+
+        let answer = partial_answer(&budget, Some("Encontré el punto de entrada."), &done, &pending);
+        assert!(answer.starts_with("⏱️ Se agotó el tiempo del turno (120s)"));
+        assert!(answer.contains("Encontré el punto de entrada."));
+        assert!(answer.contains("- ✓ read_file {\"path\":\"src/main.rs\"}"));
+        assert!(answer.contains("- search_files {\"pattern\":\"TODO\"}"));
+        assert!(answer.ends_with("/continue para retomar donde se quedó."));
+    }
+}
@@ -0,0 +1,244 @@
+//! Completion webhooks
+//!
+//! Long tasks (index builds, recipes, batch runs) post a notification to the
+//! configured webhooks when they finish or fail, so a neuro running on a
+//! server can be left alone. Slack and Discord get their message shape; any
+//! other endpoint gets a JSON object with every field.
+//!
+//! Webhooks are process-wide, like the locale and the network policy, and are
+//! sent through the policy-checked client without blocking the caller.
+
+use crate::security::network::{self, SendChecked};
+use crate::{log_info, log_warn};
+use serde::{Deserialize, Serialize};
+use std::sync::{OnceLock, RwLock};
+use std::time::Duration;
+
+/// Payload shape of a webhook
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WebhookKind {
+    /// `{"text": ...}`
+    Slack,
+    /// `{"content": ...}`
+    Discord,
+    /// Every field of the event plus the rendered text
+    #[default]
+    Generic,
+}
+
+/// Tasks that fire webhooks
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum WebhookEventKind {
+    IndexBuilt,
+    PlanFinished,
+    BatchFinished,
+}
+
+impl std::fmt::Display for WebhookEventKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::IndexBuilt => "index-built",
+            Self::PlanFinished => "plan-finished",
+            Self::BatchFinished => "batch-finished",
+        })
+    }
+}
+
+/// One webhook of the config
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WebhookConfig {
+    pub url: String,
+
+    #[serde(default)]
+    pub kind: WebhookKind,
+
+    /// Events that fire this webhook; all of them when empty
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub events: Vec<WebhookEventKind>,
+
+    /// Message text with `{icon}`, `{event}`, `{status}`, `{title}`,
+    /// `{summary}`, `{duration}` and `{project}` placeholders
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub template: Option<String>,
+
+    /// Tasks shorter than this are not reported
+    #[serde(default = "default_min_duration_secs")]
+    pub min_duration_secs: u64,
+}
+
+fn default_min_duration_secs() -> u64 {
+    30
+}
+
+const DEFAULT_TEMPLATE: &str = "{icon} {title} {status} in {duration} ({project})\n{summary}";
+
+/// A finished task
+#[derive(Debug, Clone)]
+pub struct TaskEvent {
+    pub kind: WebhookEventKind,
+    pub success: bool,
+    pub title: String,
+    pub summary: String,
+    pub duration: Duration,
+}
+
+impl TaskEvent {
+    pub fn new(kind: WebhookEventKind, success: bool, title: impl Into<String>, duration: Duration) -> Self {
+        Self {
+            kind,
+            success,
+            title: title.into(),
+            summary: String::new(),
+            duration,
+        }
+    }
+
+    pub fn with_summary(mut self, summary: impl Into<String>) -> Self {
+        self.summary = summary.into();
+        self
+    }
+
+    fn status(&self) -> &'static str {
+        if self.success {
+            "completed"
+        } else {
+            "failed"
+        }
+    }
+
+    /// `template` with the placeholders filled in
+    pub fn render(&self, template: &str, project: &str) -> String {
+        template
+            .replace("{icon}", if self.success { "✅" } else { "❌" })
+            .replace("{event}", &self.kind.to_string())
+            .replace("{status}", self.status())
+            .replace("{title}", &self.title)
+            .replace("{summary}", &self.summary)
+            .replace("{duration}", &format_duration(self.duration))
+            .replace("{project}", project)
+            .trim()
+            .to_string()
+    }
+
+    /// Request body for `webhook`
+    pub fn payload(&self, webhook: &WebhookConfig, project: &str) -> serde_json::Value {
+        let text = self.render(webhook.template.as_deref().unwrap_or(DEFAULT_TEMPLATE), project);
+        match webhook.kind {
+            WebhookKind::Slack => serde_json::json!({ "text": text }),
+            WebhookKind::Discord => serde_json::json!({ "content": text }),
+            WebhookKind::Generic => serde_json::json!({
+                "event": self.kind,
+                "status": self.status(),
+                "success": self.success,
+                "title": self.title,
+                "summary": self.summary,
+                "duration_secs": self.duration.as_secs_f64(),
+                "project": project,
+                "text": text,
+            }),
+        }
+    }
+}
+
+fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    match secs {
+        0..=59 => format!("{}s", secs),
+        60..=3599 => format!("{}m {}s", secs / 60, secs % 60),
+        _ => format!("{}h {}m", secs / 3600, (secs % 3600) / 60),
+    }
+}
+
+fn webhooks_lock() -> &'static RwLock<Vec<WebhookConfig>> {
+    static WEBHOOKS: OnceLock<RwLock<Vec<WebhookConfig>>> = OnceLock::new();
+    WEBHOOKS.get_or_init(|| RwLock::new(Vec::new()))
+}
+
+/// Set the process-wide webhooks
+pub fn set_webhooks(webhooks: Vec<WebhookConfig>) {
+    *webhooks_lock().write().unwrap_or_else(|e| e.into_inner()) = webhooks;
+}
+
+/// Post `event` to every webhook that wants it, in the background
+pub fn notify(event: TaskEvent) {
+    if let Ok(runtime) = tokio::runtime::Handle::try_current() {
+        runtime.spawn(deliver(event));
+    }
+}
+
+/// Post `event` to every webhook that wants it and wait for the answers;
+/// for callers that exit right after, where a background send would be lost
+pub async fn deliver(event: TaskEvent) {
+    let targets: Vec<WebhookConfig> = webhooks_lock()
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .iter()
+        .filter(|w| w.events.is_empty() || w.events.contains(&event.kind))
+        .filter(|w| event.duration.as_secs() >= w.min_duration_secs)
+        .cloned()
+        .collect();
+    if targets.is_empty() {
+        return;
+    }
+    let project = std::env::current_dir()
+        .ok()
+        .and_then(|dir| dir.file_name().map(|name| name.to_string_lossy().to_string()))
+        .unwrap_or_default();
+
+    let sends = targets.into_iter().map(|webhook| {
+        let payload = event.payload(&webhook, &project);
+        let kind = event.kind;
+        async move {
+            let result = network::client()
+                .post(&webhook.url)
+                .json(&payload)
+                .timeout(Duration::from_secs(10))
+                .send_checked("webhook")
+                .await;
+            match result {
+                Ok(response) if response.status().is_success() => {
+                    log_info!("🔔 [WEBHOOK] {} sent", kind);
+                }
+                Ok(response) => log_warn!("⚠️ [WEBHOOK] {} rejected: HTTP {}", kind, response.status()),
+                Err(e) => log_warn!("⚠️ [WEBHOOK] {} failed: {}", kind, e),
+            }
+        }
+    });
+    futures::future::join_all(sends).await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event() -> TaskEvent {
+        TaskEvent::new(WebhookEventKind::IndexBuilt, true, "RAPTOR index", Duration::from_secs(125))
+            .with_summary("1204 chunks")
+    }
+
+    #[test]
+    fn test_payload_shapes() {
+        let mut webhook = WebhookConfig {
+            url: "https://hooks.slack.com/services/x".to_string(),
+            kind: WebhookKind::Slack,
+            events: Vec::new(),
+            template: None,
+            min_duration_secs: 0,
+        };
+        let slack = event().payload(&webhook, "neuro");
+        assert_eq!(slack["text"], "✅ RAPTOR index completed in 2m 5s (neuro)\n1204 chunks");
+
+        webhook.kind = WebhookKind::Generic;
+        let generic = event().payload(&webhook, "neuro");
+        assert_eq!(generic["event"], "index-built");
+        assert_eq!(generic["duration_secs"], 125.0);
+    }
+
+    #[test]
+    fn test_custom_template() {
+        let text = event().render("[{event}] {status}: {title} ({duration})", "neuro");
+        assert_eq!(text, "[index-built] completed: RAPTOR index (2m 5s)");
+    }
+}
@@ -0,0 +1,129 @@
+//! Session working directory and workspace roots
+//!
+//! A session starts in the directory given on the command line and may move
+//! with `/cd`, but only inside its roots: the startup directory and the
+//! `roots` of the config. Secondary roots added with `/roots add` (or listed
+//! in `read_only_roots`) are readable by the tools but never written by the
+//! file tools, and neither shell commands nor git writes run inside them.
+//! Shell commands are not sandboxed though: one run elsewhere can still write
+//! to a read-only root by path.
+
+use crate::log_warn;
+use std::path::{Component, Path, PathBuf};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum WorkspaceError {
+    #[error("No such directory: {0}")]
+    NotFound(PathBuf),
+    #[error("Not a directory: {0}")]
+    NotADirectory(PathBuf),
+    #[error("{0} is outside the workspace roots (add it to `roots` in the config)")]
+    OutsideRoots(PathBuf),
+}
+
+/// Canonical form of `input`, relative to `base`, with `~` expanded
+pub fn resolve_dir(base: &Path, input: &str) -> Result<PathBuf, WorkspaceError> {
+    let input = input.trim();
+    let path = match input.strip_prefix('~') {
+        Some(rest) if rest.is_empty() || rest.starts_with('/') => {
+            let home = dirs::home_dir().unwrap_or_default();
+            home.join(rest.trim_start_matches('/'))
+        }
+        _ => base.join(input),
+    };
+    let canonical = std::fs::canonicalize(&path).map_err(|_| WorkspaceError::NotFound(path.clone()))?;
+    if !canonical.is_dir() {
+        return Err(WorkspaceError::NotADirectory(canonical));
+    }
+    Ok(canonical)
+}
+
+/// Canonical form of a path that may not exist yet: the longest existing
+/// ancestor is canonicalized and the rest is normalized lexically
+pub fn normalize(path: &Path) -> PathBuf {
+    let mut existing = path;
+    let mut rest = Vec::new();
+    loop {
+        if let Ok(canonical) = std::fs::canonicalize(existing) {
+            let mut out = canonical;
+            for component in rest.iter().rev() {
+                match component {
+                    Component::ParentDir => {
+                        out.pop();
+                    }
+                    Component::CurDir => {}
+                    other => out.push(other),
+                }
+            }
+            return out;
+        }
+        match (existing.parent(), existing.components().next_back()) {
+            (Some(parent), Some(last)) => {
+                rest.push(last);
+                existing = parent;
+            }
+            _ => return path.to_path_buf(),
+        }
+    }
+}
+
+/// The root among `roots` that contains `path`, if any
+pub fn containing_root<'a>(path: &Path, roots: &'a [PathBuf]) -> Option<&'a PathBuf> {
+    let path = normalize(path);
+    roots.iter().find(|root| path.starts_with(root))
+}
+
+/// Canonical forms of the configured `entries`, relative to `base`;
+/// missing directories are skipped with a warning
+pub fn resolve_roots(base: &Path, entries: &[PathBuf]) -> Vec<PathBuf> {
+    entries
+        .iter()
+        .filter_map(|entry| {
+            resolve_dir(base, &entry.to_string_lossy())
+                .map_err(|e| log_warn!("⚠️ Ignoring workspace root: {}", e))
+                .ok()
+        })
+        .collect()
+}
+
+/// Move from `current` to `input`, which must resolve inside one of `roots`
+pub fn change_dir(current: &Path, input: &str, roots: &[PathBuf]) -> Result<PathBuf, WorkspaceError> {
+    let target = resolve_dir(current, input)?;
+    if containing_root(&target, roots).is_none() {
+        return Err(WorkspaceError::OutsideRoots(target));
+    }
+    Ok(target)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_change_dir_is_confined_to_roots() {
+        let dir = TempDir::new().unwrap();
+        let root = std::fs::canonicalize(dir.path()).unwrap();
+        std::fs::create_dir_all(root.join("project/crates/core")).unwrap();
+        std::fs::create_dir_all(root.join("other")).unwrap();
+        let roots = vec![root.join("project")];
+
+        let core = change_dir(&root.join("project"), "crates/core", &roots).unwrap();
+        assert_eq!(core, root.join("project/crates/core"));
+        assert_eq!(change_dir(&core, "../..", &roots).unwrap(), root.join("project"));
+        assert!(matches!(change_dir(&core, "../../../other", &roots), Err(WorkspaceError::OutsideRoots(_))));
+        assert!(matches!(change_dir(&core, "missing", &roots), Err(WorkspaceError::NotFound(_))));
+    }
+
+    #[test]
+    fn test_containing_root_handles_new_files() {
+        let dir = TempDir::new().unwrap();
+        let root = std::fs::canonicalize(dir.path()).unwrap();
+        std::fs::create_dir_all(root.join("vendor/lib")).unwrap();
+        let roots = vec![root.join("vendor")];
+
+        assert!(containing_root(&root.join("vendor/lib/new.rs"), &roots).is_some());
+        assert!(containing_root(&root.join("vendor/lib/../../app/new.rs"), &roots).is_none());
+    }
+}
@@ -0,0 +1,388 @@
+//! Language detection from file content
+//!
+//! Extensions lie or are missing: scripts without one, `Dockerfile`s and
+//! `Makefile`s, C++ in `.h` headers, Qt translations saved as `.ts`. The
+//! content is checked first (shebang, editor modeline, heuristics for
+//! ambiguous extensions, sniffing for files the name says nothing about) and
+//! the file name and extension are the fallback.
+//!
+//! Languages are named as the indexer shows them ("Rust", "C++", "Shell",
+//! "Docker"...); [`super::SupportedLanguage::parse_language`] accepts the ones
+//! with a grammar.
+
+use std::io::Read;
+use std::path::Path;
+
+/// Bytes of a file the heuristics look at
+pub const HEAD_BYTES: usize = 8 * 1024;
+
+/// Interpreters, modeline names and aliases, lowercase
+const ALIASES: &[(&str, &str)] = &[
+    ("bash", "Shell"),
+    ("sh", "Shell"),
+    ("zsh", "Shell"),
+    ("dash", "Shell"),
+    ("ksh", "Shell"),
+    ("shell", "Shell"),
+    ("python", "Python"),
+    ("pypy", "Python"),
+    ("node", "JavaScript"),
+    ("nodejs", "JavaScript"),
+    ("bun", "JavaScript"),
+    ("javascript", "JavaScript"),
+    ("js", "JavaScript"),
+    ("deno", "TypeScript"),
+    ("ts-node", "TypeScript"),
+    ("tsx", "TypeScript"),
+    ("typescript", "TypeScript"),
+    ("ruby", "Ruby"),
+    ("perl", "Perl"),
+    ("php", "PHP"),
+    ("lua", "Lua"),
+    ("rscript", "R"),
+    ("pwsh", "PowerShell"),
+    ("powershell", "PowerShell"),
+    ("groovy", "Groovy"),
+    ("rust-script", "Rust"),
+    ("rust", "Rust"),
+    ("make", "Make"),
+    ("dockerfile", "Docker"),
+    ("c", "C"),
+    ("cpp", "C++"),
+    ("go", "Go"),
+    ("java", "Java"),
+    ("yaml", "YAML"),
+    ("json", "JSON"),
+    ("toml", "TOML"),
+    ("sql", "SQL"),
+];
+
+/// Language of a file: what its content says, then its name
+pub fn detect(path: &Path, content: &str) -> Option<&'static str> {
+    from_content(path, content).or_else(|| from_path(path))
+}
+
+/// What the content says about the language, whatever the file is called
+pub fn from_content(path: &Path, content: &str) -> Option<&'static str> {
+    let head = head(content);
+    if let Some(language) = shebang(head).or_else(|| modeline(head)) {
+        return Some(language);
+    }
+    let extension = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(str::to_lowercase)
+        .unwrap_or_default();
+    if let Some(language) = disambiguate(&extension, head) {
+        return Some(language);
+    }
+    match from_path(path) {
+        None | Some("Text") => sniff(head),
+        Some(_) => None,
+    }
+}
+
+/// Language from the file name and extension alone
+pub fn from_path(path: &Path) -> Option<&'static str> {
+    let name = path.file_name()?.to_str()?;
+    let language = match name {
+        "Cargo.toml" | "Cargo.lock" => Some("Rust"),
+        "package.json" | "package-lock.json" => Some("JavaScript"),
+        "tsconfig.json" => Some("TypeScript"),
+        "pyproject.toml" | "setup.py" | "requirements.txt" | "Pipfile" => Some("Python"),
+        "Makefile" | "makefile" | "GNUmakefile" => Some("Make"),
+        "Dockerfile" | "Containerfile" => Some("Docker"),
+        "docker-compose.yml" | "docker-compose.yaml" => Some("Docker"),
+        "Rakefile" | "Gemfile" | "Guardfile" | "Capfile" | "Vagrantfile" => Some("Ruby"),
+        "Jenkinsfile" => Some("Groovy"),
+        "CMakeLists.txt" => Some("CMake"),
+        ".gitignore" | ".gitattributes" => Some("Git"),
+        ".bashrc" | ".bash_profile" | ".zshrc" | ".profile" => Some("Shell"),
+        _ if name.starts_with("Dockerfile.") || name.ends_with(".Dockerfile") => Some("Docker"),
+        _ => None,
+    };
+    if language.is_some() {
+        return language;
+    }
+
+    let extension = path.extension()?.to_str()?.to_lowercase();
+    Some(match extension.as_str() {
+        "rs" => "Rust",
+        "py" | "pyi" => "Python",
+        "js" | "mjs" | "cjs" => "JavaScript",
+        "ts" | "mts" | "cts" => "TypeScript",
+        "tsx" | "jsx" => "React",
+        "go" => "Go",
+        "java" => "Java",
+        "kt" | "kts" => "Kotlin",
+        "c" | "h" => "C",
+        // Arduino sketches and template implementation headers are C++ too
+        "cpp" | "cc" | "cxx" | "hpp" | "hh" | "hxx" | "ino" | "ipp" | "tpp" | "inl" => "C++",
+        "cs" => "C#",
+        "rb" | "rake" | "gemspec" => "Ruby",
+        "php" | "phtml" => "PHP",
+        "swift" => "Swift",
+        "scala" => "Scala",
+        "lua" => "Lua",
+        "r" => "R",
+        "pl" | "pm" => "Perl",
+        "sql" => "SQL",
+        "sh" | "bash" | "zsh" => "Shell",
+        "ps1" => "PowerShell",
+        "mk" => "Make",
+        "groovy" | "gradle" => "Groovy",
+        "html" | "htm" => "HTML",
+        "css" | "scss" | "sass" | "less" => "CSS",
+        "json" => "JSON",
+        "yaml" | "yml" => "YAML",
+        "toml" => "TOML",
+        "xml" => "XML",
+        "md" | "markdown" => "Markdown",
+        "txt" => "Text",
+        "vue" => "Vue",
+        "svelte" => "Svelte",
+        "elm" => "Elm",
+        "ex" | "exs" => "Elixir",
+        "erl" | "hrl" => "Erlang",
+        "hs" => "Haskell",
+        "ml" | "mli" => "OCaml",
+        "clj" | "cljs" | "cljc" => "Clojure",
+        "nim" => "Nim",
+        "zig" => "Zig",
+        "v" => "V",
+        "dart" => "Dart",
+        "proto" => "Protobuf",
+        "graphql" | "gql" => "GraphQL",
+        _ => return None,
+    })
+}
+
+/// The first [`HEAD_BYTES`] of a file, lossily decoded
+pub fn read_head(path: &Path) -> std::io::Result<String> {
+    let mut bytes = Vec::with_capacity(HEAD_BYTES);
+    std::fs::File::open(path)?
+        .take(HEAD_BYTES as u64)
+        .read_to_end(&mut bytes)?;
+    Ok(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+fn head(content: &str) -> &str {
+    if content.len() <= HEAD_BYTES {
+        return content;
+    }
+    let mut end = HEAD_BYTES;
+    while !content.is_char_boundary(end) {
+        end -= 1;
+    }
+    &content[..end]
+}
+
+fn alias(name: &str) -> Option<&'static str> {
+    let name = name.to_lowercase();
+    ALIASES
+        .iter()
+        .find(|(alias, _)| *alias == name)
+        .map(|(_, language)| *language)
+}
+
+/// `#!/usr/bin/env -S python3 -u`, `#!/bin/bash`...
+fn shebang(head: &str) -> Option<&'static str> {
+    let line = head.lines().next()?.strip_prefix("#!")?;
+    let mut words = line.split_whitespace();
+    let mut interpreter = words.next()?.rsplit('/').next()?;
+    if interpreter == "env" {
+        // Skip env's options and variable assignments
+        interpreter = words.find(|word| !word.starts_with('-') && !word.contains('='))?;
+    }
+    // python3.12 → python
+    alias(interpreter.trim_end_matches(|c: char| c.is_ascii_digit() || c == '.'))
+}
+
+/// `vim: set ft=python:` or `-*- mode: ruby -*-` in the first or last lines
+fn modeline(head: &str) -> Option<&'static str> {
+    let lines: Vec<&str> = head.lines().collect();
+    let edges = lines.iter().take(5).chain(lines.iter().rev().take(5));
+    for line in edges {
+        if let Some(rest) = line
+            .split("vim:")
+            .nth(1)
+            .or_else(|| line.split("vi:").nth(1))
+        {
+            let setting = rest.split([' ', ':', '\t']).find_map(|part| {
+                part.strip_prefix("ft=")
+                    .or_else(|| part.strip_prefix("filetype="))
+            });
+            if let Some(language) = setting.and_then(alias) {
+                return Some(language);
+            }
+        }
+        if let Some(rest) = line.split("-*-").nth(1) {
+            let mode = rest.split(';').map(str::trim).find_map(|part| {
+                part.strip_prefix("mode:")
+                    .or((!part.contains(':')).then_some(part))
+            });
+            if let Some(language) = mode.map(str::trim).and_then(alias) {
+                return Some(language);
+            }
+        }
+    }
+    None
+}
+
+/// Extensions shared by several languages, decided by their content
+fn disambiguate(extension: &str, head: &str) -> Option<&'static str> {
+    let cpp = [
+        "class ",
+        "namespace ",
+        "template<",
+        "template <",
+        "public:",
+        "private:",
+    ];
+    match extension {
+        "h" if has_line(head, &cpp) || head.contains("std::") => Some("C++"),
+        "m" if has_line(head, &["@interface", "@implementation", "#import"]) => Some("Objective-C"),
+        "m" if has_line(head, &["function ", "%"]) => Some("MATLAB"),
+        "pl" if has_line(head, &[":- "]) && !has_line(head, &["use strict", "my $", "sub "]) => {
+            Some("Prolog")
+        }
+        "ts" if head.trim_start().starts_with("<?xml") || head.contains("<TS") => Some("XML"),
+        "v" if has_line(head, &["module "]) && head.contains("endmodule") => Some("Verilog"),
+        "inc" if head.trim_start().starts_with("<?php") => Some("PHP"),
+        _ => None,
+    }
+}
+
+/// Some line starts with one of `prefixes`, ignoring indentation
+fn has_line(head: &str, prefixes: &[&str]) -> bool {
+    head.lines()
+        .map(str::trim_start)
+        .any(|line| prefixes.iter().any(|prefix| line.starts_with(prefix)))
+}
+
+/// Guess from the content of a file whose name says nothing
+fn sniff(head: &str) -> Option<&'static str> {
+    let start = head.trim_start();
+    let lower = start.chars().take(16).collect::<String>().to_lowercase();
+    if start.starts_with("<?php") {
+        return Some("PHP");
+    }
+    if start.starts_with("<?xml") {
+        return Some("XML");
+    }
+    if lower.starts_with("<!doctype html") || lower.starts_with("<html") {
+        return Some("HTML");
+    }
+
+    let mut lines = head
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'));
+    if lines
+        .next()
+        .is_some_and(|first| first.starts_with("FROM ") || first.starts_with("ARG "))
+        && lines.any(|line| {
+            ["RUN ", "CMD ", "COPY ", "ENTRYPOINT ", "FROM "]
+                .iter()
+                .any(|i| line.starts_with(i))
+        })
+    {
+        return Some("Docker");
+    }
+
+    // A target followed by a recipe indented with a tab (not a Python block)
+    let raw: Vec<&str> = head.lines().collect();
+    let is_target = |line: &str| {
+        let line = line.trim_end();
+        !line.starts_with(['\t', ' ', '#'])
+            && line.split_once(':').is_some_and(|(target, rest)| {
+                !target.is_empty()
+                    && !target.contains(' ')
+                    && !["else", "try", "finally"].contains(&target)
+                    && !rest.starts_with('=')
+            })
+    };
+    if raw.iter().any(|line| line.starts_with(".PHONY:"))
+        || raw
+            .windows(2)
+            .any(|pair| is_target(pair[0]) && pair[1].starts_with('\t'))
+    {
+        return Some("Make");
+    }
+
+    if (start.starts_with('{') || start.starts_with('['))
+        && serde_json::from_str::<serde_json::Value>(head).is_ok()
+    {
+        return Some("JSON");
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_content_beats_name() {
+        let script = Path::new("deploy");
+        assert_eq!(
+            detect(script, "#!/usr/bin/env -S python3.12 -u\nprint(1)\n"),
+            Some("Python")
+        );
+        assert_eq!(
+            detect(Path::new("run.txt"), "#!/bin/bash\necho hi\n"),
+            Some("Shell")
+        );
+        assert_eq!(
+            detect(
+                Path::new("tool.js"),
+                "// vim: set ft=typescript:\nlet a: number = 1;"
+            ),
+            Some("TypeScript")
+        );
+        assert_eq!(
+            detect(Path::new("x"), "# -*- mode: ruby -*-\nputs 1\n"),
+            Some("Ruby")
+        );
+
+        assert_eq!(
+            detect(Path::new("widget.h"), "namespace ui {\nclass Widget {};\n}"),
+            Some("C++")
+        );
+        assert_eq!(
+            detect(Path::new("api.h"), "int add(int a, int b);"),
+            Some("C")
+        );
+        assert_eq!(
+            detect(
+                Path::new("app_es.ts"),
+                "<?xml version=\"1.0\"?>\n<TS version=\"2.1\">"
+            ),
+            Some("XML")
+        );
+        assert_eq!(detect(Path::new("main.rs"), "fn main() {}"), Some("Rust"));
+        assert_eq!(from_content(Path::new("main.rs"), "fn main() {}"), None);
+    }
+
+    #[test]
+    fn test_names_and_sniffing() {
+        assert_eq!(detect(Path::new("Dockerfile"), ""), Some("Docker"));
+        assert_eq!(detect(Path::new("Dockerfile.dev"), ""), Some("Docker"));
+        assert_eq!(detect(Path::new("GNUmakefile"), ""), Some("Make"));
+
+        assert_eq!(
+            detect(
+                Path::new("build/image"),
+                "# base\nFROM rust:1.80\nRUN cargo build\n"
+            ),
+            Some("Docker")
+        );
+        assert_eq!(
+            detect(Path::new("tasks"), "all: build\n\nbuild:\n\tcargo build\n"),
+            Some("Make")
+        );
+        assert_eq!(detect(Path::new("data"), "{\"a\": [1, 2]}"), Some("JSON"));
+        assert_eq!(detect(Path::new("notes"), "just some words"), None);
+        assert_eq!(detect(Path::new("firmware/blink.ino"), "void loop() {}"), Some("C++"));
+    }
+}
@@ -1,10 +1,20 @@
 //! AST Parsing Module
 //!
 //! Provides multi-language AST parsing using tree-sitter for accurate code analysis.
+//! Symbols, imports and calls are extracted with per-language query files (see [`queries`]).
+//! The language of a file comes from its content when it says something (see [`detect`]).
+
+pub mod detect;
+pub mod queries;
 
 use anyhow::{Context, Result};
-use std::collections::HashMap;
-use tree_sitter::{Language, Node, Parser, Tree};
+use lru::LruCache;
+use std::collections::{HashMap, HashSet};
+use std::num::NonZeroUsize;
+use std::path::{Path, PathBuf};
+use tree_sitter::{InputEdit, Language, Node, Parser, Point, Tree};
+
+use queries::{run_query, LanguageQueries};
 
 /// Supported languages for AST parsing
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -13,6 +23,13 @@ pub enum SupportedLanguage {
     Python,
     TypeScript,
     JavaScript,
+    C,
+    Cpp,
+    Ruby,
+    Php,
+    Go,
+    Java,
+    Kotlin,
 }
 
 impl SupportedLanguage {
@@ -22,16 +39,53 @@ impl SupportedLanguage {
             "python" | "py" => Some(Self::Python),
             "typescript" | "ts" => Some(Self::TypeScript),
             "javascript" | "js" => Some(Self::JavaScript),
+            "c" => Some(Self::C),
+            // Headers are parsed as C++: its grammar accepts nearly all C declarations.
+            // Arduino sketches (`.ino`) are C++ with an implicit `#include <Arduino.h>`
+            "c++" | "cpp" | "cc" | "cxx" | "h" | "hpp" | "hh" | "hxx" | "ipp" | "tpp" | "inl"
+            | "ino" => Some(Self::Cpp),
+            "ruby" | "rb" | "rake" | "gemspec" | "ru" => Some(Self::Ruby),
+            "php" | "phtml" => Some(Self::Php),
+            "go" | "golang" => Some(Self::Go),
+            "java" => Some(Self::Java),
+            "kotlin" | "kt" | "kts" => Some(Self::Kotlin),
+            _ => None,
+        }
+    }
+
+    /// Detect the language of a file from its extension or well-known name
+    pub fn from_path(path: &Path) -> Option<Self> {
+        if let Some(ext) = path.extension().and_then(|e| e.to_str()) {
+            return Self::parse_language(ext);
+        }
+        match path.file_name()?.to_str()? {
+            "Rakefile" | "Gemfile" | "Guardfile" | "Capfile" | "Vagrantfile" => Some(Self::Ruby),
             _ => None,
         }
     }
 
+    /// Detect the language of a file from its content (shebang, modeline, ambiguous
+    /// extensions), then from its name
+    pub fn detect(path: &Path, content: &str) -> Option<Self> {
+        match detect::from_content(path, content) {
+            Some(language) => Self::parse_language(language),
+            None => Self::from_path(path),
+        }
+    }
+
     pub fn as_str(&self) -> &'static str {
         match self {
             Self::Rust => "rust",
             Self::Python => "python",
             Self::TypeScript => "typescript",
             Self::JavaScript => "javascript",
+            Self::C => "c",
+            Self::Cpp => "cpp",
+            Self::Ruby => "ruby",
+            Self::Php => "php",
+            Self::Go => "go",
+            Self::Java => "java",
+            Self::Kotlin => "kotlin",
         }
     }
 
@@ -41,6 +95,13 @@ impl SupportedLanguage {
             Self::Python => tree_sitter_python::LANGUAGE.into(),
             Self::TypeScript => tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into(),
             Self::JavaScript => tree_sitter_javascript::LANGUAGE.into(),
+            Self::C => tree_sitter_c::LANGUAGE.into(),
+            Self::Cpp => tree_sitter_cpp::LANGUAGE.into(),
+            Self::Ruby => tree_sitter_ruby::LANGUAGE.into(),
+            Self::Php => tree_sitter_php::LANGUAGE_PHP.into(),
+            Self::Go => tree_sitter_go::LANGUAGE.into(),
+            Self::Java => tree_sitter_java::LANGUAGE.into(),
+            Self::Kotlin => tree_sitter_kotlin_ng::LANGUAGE.into(),
         }
     }
 }
@@ -75,6 +136,23 @@ impl SymbolKind {
             Self::Module => "module",
         }
     }
+
+    /// Kind from a query capture suffix: `@definition.function` → `function`
+    pub fn from_capture(suffix: &str) -> Option<Self> {
+        match suffix {
+            "function" => Some(Self::Function),
+            "method" => Some(Self::Method),
+            "struct" => Some(Self::Struct),
+            "class" => Some(Self::Class),
+            "enum" => Some(Self::Enum),
+            "trait" => Some(Self::Trait),
+            "interface" => Some(Self::Interface),
+            "constant" => Some(Self::Constant),
+            "variable" => Some(Self::Variable),
+            "module" => Some(Self::Module),
+            _ => None,
+        }
+    }
 }
 
 /// Visibility of a symbol
@@ -108,8 +186,10 @@ pub struct Range {
 
 impl Range {
     fn from_node(node: &Node) -> Self {
-        let start = node.start_position();
-        let end = node.end_position();
+        Self::from_points(node.start_position(), node.end_position())
+    }
+
+    fn from_points(start: Point, end: Point) -> Self {
         Self {
             start_line: start.row + 1, // 1-indexed
             start_col: start.column,
@@ -117,6 +197,11 @@ impl Range {
             end_col: end.column,
         }
     }
+
+    /// Whether both ranges share at least one line
+    pub fn overlaps_lines(&self, other: &Range) -> bool {
+        self.start_line <= other.end_line && other.start_line <= self.end_line
+    }
 }
 
 /// Parameter of a function/method
@@ -127,6 +212,16 @@ pub struct Parameter {
     pub default_value: Option<String>,
 }
 
+/// Field of a struct or variant of an enum
+#[derive(Debug, Clone)]
+pub struct Field {
+    pub name: String,
+    /// `None` for enum variants
+    pub type_annotation: Option<String>,
+    pub docstring: Option<String>,
+    pub line: usize,
+}
+
 /// Symbol extracted from AST
 #[derive(Debug, Clone)]
 pub struct AstSymbol {
@@ -158,30 +253,110 @@ pub struct FunctionCall {
     pub line: usize,
 }
 
+/// Caller → callee edge of a call graph
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CallEdge {
+    /// Innermost function or method around the call; `None` for top-level code
+    pub caller: Option<String>,
+    pub callee: String,
+    pub line: usize,
+}
+
+/// How an identifier occurrence uses a symbol
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ReferenceKind {
+    Definition,
+    Import,
+    Call,
+    Reference,
+}
+
+impl ReferenceKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Definition => "definition",
+            Self::Import => "import",
+            Self::Call => "call",
+            Self::Reference => "reference",
+        }
+    }
+}
+
+/// Occurrence of an identifier outside comments and strings
+#[derive(Debug, Clone)]
+pub struct SymbolReference {
+    pub kind: ReferenceKind,
+    pub line: usize,
+    pub column: usize,
+}
+
+/// Result of [`AstParser::parse_file`]
+#[derive(Debug, Clone)]
+pub struct FileParse {
+    pub tree: Tree,
+    /// Regions that changed since the previous parse of the file, `None` when it
+    /// was parsed from scratch
+    pub changed_ranges: Option<Vec<Range>>,
+}
+
+impl FileParse {
+    /// Whether a range may differ from the previous parse
+    pub fn touches(&self, range: &Range) -> bool {
+        self.changed_ranges
+            .as_ref()
+            .is_none_or(|changed| changed.iter().any(|c| c.overlaps_lines(range)))
+    }
+}
+
+/// Files whose last parse is kept; the least recently parsed are dropped first
+const PARSE_CACHE_FILES: usize = 256;
+
+/// Last parse of a file, kept to re-parse it incrementally
+struct ParsedFile {
+    language: SupportedLanguage,
+    source: String,
+    tree: Tree,
+}
+
 /// Multi-language AST parser
 pub struct AstParser {
     parsers: HashMap<SupportedLanguage, Parser>,
+    queries: HashMap<SupportedLanguage, LanguageQueries>,
+    trees: LruCache<PathBuf, ParsedFile>,
 }
 
 impl AstParser {
     /// Create a new AST parser with support for multiple languages
     pub fn new() -> Result<Self> {
         let mut parsers = HashMap::new();
+        let mut queries = HashMap::new();
 
         for lang in [
             SupportedLanguage::Rust,
             SupportedLanguage::Python,
             SupportedLanguage::TypeScript,
             SupportedLanguage::JavaScript,
+            SupportedLanguage::C,
+            SupportedLanguage::Cpp,
+            SupportedLanguage::Ruby,
+            SupportedLanguage::Php,
+            SupportedLanguage::Go,
+            SupportedLanguage::Java,
+            SupportedLanguage::Kotlin,
         ] {
             let mut parser = Parser::new();
             parser
                 .set_language(&lang.tree_sitter_language())
                 .context(format!("Failed to set language for {}", lang.as_str()))?;
             parsers.insert(lang, parser);
+            queries.insert(lang, LanguageQueries::load(lang)?);
         }
 
-        Ok(Self { parsers })
+        Ok(Self {
+            parsers,
+            queries,
+            trees: LruCache::new(NonZeroUsize::new(PARSE_CACHE_FILES).unwrap()),
+        })
     }
 
     /// Parse source code into an AST
@@ -194,218 +369,292 @@ impl AstParser {
         parser.parse(code, None).context("Failed to parse code")
     }
 
-    /// Extract all symbols from the AST
-    pub fn extract_symbols(
-        &self,
-        tree: &Tree,
+    /// Parse a file, reusing its previous tree when it was parsed before.
+    ///
+    /// The difference with the cached source becomes tree-sitter edits (see
+    /// [`compute_edits`]), so only the edited regions are re-parsed.
+    pub fn parse_file(
+        &mut self,
+        path: &Path,
         language: SupportedLanguage,
         source: &str,
-    ) -> Vec<AstSymbol> {
-        match language {
-            SupportedLanguage::Rust => self.extract_rust_symbols(tree, source),
-            SupportedLanguage::Python => self.extract_python_symbols(tree, source),
-            SupportedLanguage::TypeScript => self.extract_typescript_symbols(tree, source),
-            SupportedLanguage::JavaScript => self.extract_javascript_symbols(tree, source),
-        }
+    ) -> Result<FileParse> {
+        let previous = self.trees.pop(path).filter(|f| f.language == language);
+        let parse = match previous {
+            Some(previous) => {
+                let edits = compute_edits(&previous.source, source);
+                self.parse_incremental(language, &previous.tree, &edits, source)?
+            }
+            None => FileParse {
+                tree: self.parse(language, source)?,
+                changed_ranges: None,
+            },
+        };
+
+        self.trees.put(
+            path.to_path_buf(),
+            ParsedFile {
+                language,
+                source: source.to_string(),
+                tree: parse.tree.clone(),
+            },
+        );
+        Ok(parse)
     }
 
-    /// Extract Rust symbols from AST
-    fn extract_rust_symbols(&self, tree: &Tree, source: &str) -> Vec<AstSymbol> {
-        let mut symbols = Vec::new();
-        let mut cursor = tree.walk();
+    /// Parse `new_source` reusing `old_tree`, the tree of the source before
+    /// `edits`. The edits are applied in order, each in the coordinates the
+    /// previous ones leave, as [`compute_edits`] returns them.
+    pub fn parse_incremental(
+        &mut self,
+        language: SupportedLanguage,
+        old_tree: &Tree,
+        edits: &[InputEdit],
+        new_source: &str,
+    ) -> Result<FileParse> {
+        if edits.is_empty() {
+            return Ok(FileParse {
+                tree: old_tree.clone(),
+                changed_ranges: Some(Vec::new()),
+            });
+        }
+        let parser = self
+            .parsers
+            .get_mut(&language)
+            .context("Unsupported language")?;
 
-        fn traverse(
-            node: &Node,
-            source: &str,
-            symbols: &mut Vec<AstSymbol>,
-            cursor: &mut tree_sitter::TreeCursor,
-        ) {
-            match node.kind() {
-                "function_item" => {
-                    if let Some(symbol) = extract_rust_function(node, source) {
-                        symbols.push(symbol);
-                    }
-                }
-                "struct_item" => {
-                    if let Some(symbol) = extract_rust_struct(node, source) {
-                        symbols.push(symbol);
-                    }
-                }
-                "enum_item" => {
-                    if let Some(symbol) = extract_rust_enum(node, source) {
-                        symbols.push(symbol);
-                    }
-                }
-                "trait_item" => {
-                    if let Some(symbol) = extract_rust_trait(node, source) {
-                        symbols.push(symbol);
-                    }
-                }
-                "impl_item" => {
-                    // Traverse impl block to find methods
-                    if cursor.goto_first_child() {
-                        loop {
-                            let child = cursor.node();
-                            traverse(&child, source, symbols, cursor);
-                            if !cursor.goto_next_sibling() {
-                                break;
-                            }
-                        }
-                        cursor.goto_parent();
-                    }
-                }
-                _ => {
-                    // Recursively traverse children
-                    if cursor.goto_first_child() {
-                        loop {
-                            let child = cursor.node();
-                            traverse(&child, source, symbols, cursor);
-                            if !cursor.goto_next_sibling() {
-                                break;
-                            }
-                        }
-                        cursor.goto_parent();
-                    }
-                }
-            }
+        let mut edited = old_tree.clone();
+        for edit in edits {
+            edited.edit(edit);
         }
+        let tree = parser
+            .parse(new_source, Some(&edited))
+            .context("Failed to parse code")?;
+
+        // Syntactic changes plus the edited text itself (a renamed identifier
+        // keeps the same structure)
+        let mut changed: Vec<Range> = edited
+            .changed_ranges(&tree)
+            .map(|r| Range::from_points(r.start_point, r.end_point))
+            .collect();
+        changed.extend(
+            edits
+                .iter()
+                .map(|edit| Range::from_points(edit.start_position, edit.new_end_position)),
+        );
+        Ok(FileParse {
+            tree,
+            changed_ranges: Some(changed),
+        })
+    }
 
-        let root = tree.root_node();
-        traverse(&root, source, &mut symbols, &mut cursor);
-        symbols
+    /// Drop the cached tree of a file (deleted or renamed)
+    pub fn forget_file(&mut self, path: &Path) {
+        self.trees.pop(path);
     }
 
-    /// Extract Python symbols from AST
-    fn extract_python_symbols(&self, tree: &Tree, source: &str) -> Vec<AstSymbol> {
-        let mut symbols = Vec::new();
-        let mut cursor = tree.walk();
+    /// Number of files with a cached tree
+    pub fn cached_files(&self) -> usize {
+        self.trees.len()
+    }
 
-        fn traverse(
-            node: &Node,
-            source: &str,
-            symbols: &mut Vec<AstSymbol>,
-            cursor: &mut tree_sitter::TreeCursor,
-        ) {
-            match node.kind() {
-                "function_definition" => {
-                    if let Some(symbol) = extract_python_function(node, source) {
-                        symbols.push(symbol);
-                    }
-                }
-                "class_definition" => {
-                    if let Some(symbol) = extract_python_class(node, source) {
-                        symbols.push(symbol);
-                    }
-                    // Also traverse class methods
-                    if cursor.goto_first_child() {
-                        loop {
-                            let child = cursor.node();
-                            traverse(&child, source, symbols, cursor);
-                            if !cursor.goto_next_sibling() {
-                                break;
-                            }
-                        }
-                        cursor.goto_parent();
+    /// Extract all symbols from the AST
+    pub fn extract_symbols(
+        &self,
+        tree: &Tree,
+        language: SupportedLanguage,
+        source: &str,
+    ) -> Vec<AstSymbol> {
+        let Some(queries) = self.queries.get(&language) else {
+            return Vec::new();
+        };
+
+        run_query(&queries.symbols, tree.root_node(), source, "definition")
+            .iter()
+            .filter_map(|hit| {
+                let (tag, node) = hit.tagged("definition")?;
+                let kind = SymbolKind::from_capture(tag)?;
+                let name = get_node_text(&hit.node("name")?, source);
+                Some(match language {
+                    SupportedLanguage::Rust => rust_symbol(name, kind, &node, source),
+                    SupportedLanguage::Python => python_symbol(name, kind, &node, source),
+                    SupportedLanguage::TypeScript | SupportedLanguage::JavaScript => {
+                        ts_symbol(name, kind, &node, source)
                     }
-                }
-                _ => {
-                    if cursor.goto_first_child() {
-                        loop {
-                            let child = cursor.node();
-                            traverse(&child, source, symbols, cursor);
-                            if !cursor.goto_next_sibling() {
-                                break;
-                            }
-                        }
-                        cursor.goto_parent();
+                    SupportedLanguage::C | SupportedLanguage::Cpp => {
+                        c_symbol(name, kind, &node, source)
                     }
-                }
-            }
-        }
-
-        let root = tree.root_node();
-        traverse(&root, source, &mut symbols, &mut cursor);
-        symbols
+                    SupportedLanguage::Ruby => ruby_symbol(name, kind, &node, source),
+                    SupportedLanguage::Php => php_symbol(name, kind, &node, source),
+                    SupportedLanguage::Go => go_symbol(name, kind, &node, source),
+                    SupportedLanguage::Java => java_symbol(name, kind, &node, source),
+                    SupportedLanguage::Kotlin => kotlin_symbol(name, kind, &node, source),
+                })
+            })
+            .collect()
     }
 
-    /// Extract TypeScript symbols from AST
-    fn extract_typescript_symbols(&self, tree: &Tree, source: &str) -> Vec<AstSymbol> {
-        let mut symbols = Vec::new();
-        let mut cursor = tree.walk();
-
-        fn traverse(
-            node: &Node,
-            source: &str,
-            symbols: &mut Vec<AstSymbol>,
-            cursor: &mut tree_sitter::TreeCursor,
-        ) {
-            match node.kind() {
-                "function_declaration" | "method_definition" => {
-                    if let Some(symbol) = extract_ts_function(node, source) {
-                        symbols.push(symbol);
-                    }
-                }
-                "class_declaration" => {
-                    if let Some(symbol) = extract_ts_class(node, source) {
-                        symbols.push(symbol);
-                    }
-                    // Traverse class methods
-                    if cursor.goto_first_child() {
-                        loop {
-                            let child = cursor.node();
-                            traverse(&child, source, symbols, cursor);
-                            if !cursor.goto_next_sibling() {
-                                break;
-                            }
-                        }
-                        cursor.goto_parent();
-                    }
-                }
-                "interface_declaration" => {
-                    if let Some(symbol) = extract_ts_interface(node, source) {
-                        symbols.push(symbol);
-                    }
-                }
-                _ => {
-                    if cursor.goto_first_child() {
-                        loop {
-                            let child = cursor.node();
-                            traverse(&child, source, symbols, cursor);
-                            if !cursor.goto_next_sibling() {
-                                break;
-                            }
-                        }
-                        cursor.goto_parent();
-                    }
-                }
+    /// Extract imports from the AST
+    pub fn extract_imports(
+        &self,
+        tree: &Tree,
+        language: SupportedLanguage,
+        source: &str,
+    ) -> Vec<Import> {
+        let Some(queries) = self.queries.get(&language) else {
+            return Vec::new();
+        };
+
+        // Patterns matching one clause at a time (`use a::{b, c}`) are merged per statement
+        let mut imports: Vec<(usize, Import)> = Vec::new();
+        for hit in run_query(&queries.imports, tree.root_node(), source, "import") {
+            let Some((tag, node)) = hit.tagged("import") else {
+                continue;
+            };
+            let module = hit
+                .node("module")
+                .map(|m| clean_module_path(&get_node_text(&m, source)))
+                .unwrap_or_else(|| get_node_text(&node, source));
+            let items = hit.nodes("item").map(|i| get_node_text(&i, source));
+
+            if let Some((_, import)) = imports
+                .iter_mut()
+                .find(|(id, import)| *id == node.id() && import.module == module)
+            {
+                import.items.extend(items);
+                continue;
             }
+            imports.push((
+                node.id(),
+                Import {
+                    module,
+                    items: items.collect(),
+                    is_wildcard: tag == "wildcard",
+                    line: node.start_position().row + 1,
+                },
+            ));
         }
+        imports.into_iter().map(|(_, import)| import).collect()
+    }
 
-        let root = tree.root_node();
-        traverse(&root, source, &mut symbols, &mut cursor);
-        symbols
+    /// Extract function call sites from the AST
+    pub fn extract_calls(
+        &self,
+        tree: &Tree,
+        language: SupportedLanguage,
+        source: &str,
+    ) -> Vec<FunctionCall> {
+        let Some(queries) = self.queries.get(&language) else {
+            return Vec::new();
+        };
+
+        run_query(&queries.calls, tree.root_node(), source, "call")
+            .iter()
+            .filter_map(|hit| {
+                let name = hit.node("name")?;
+                Some(FunctionCall {
+                    function_name: get_node_text(&name, source),
+                    line: name.start_position().row + 1,
+                })
+            })
+            .collect()
     }
 
-    /// Extract JavaScript symbols from AST
-    fn extract_javascript_symbols(&self, tree: &Tree, source: &str) -> Vec<AstSymbol> {
-        // JavaScript parsing is similar to TypeScript
-        self.extract_typescript_symbols(tree, source)
+    /// Calls of a file attributed to the innermost function or method around them
+    pub fn extract_call_graph(
+        &self,
+        tree: &Tree,
+        language: SupportedLanguage,
+        source: &str,
+    ) -> Vec<CallEdge> {
+        let functions: Vec<AstSymbol> = self
+            .extract_symbols(tree, language, source)
+            .into_iter()
+            .filter(|s| matches!(s.kind, SymbolKind::Function | SymbolKind::Method))
+            .collect();
+
+        self.extract_calls(tree, language, source)
+            .into_iter()
+            .map(|call| {
+                let caller = functions
+                    .iter()
+                    .filter(|f| f.range.start_line <= call.line && call.line <= f.range.end_line)
+                    .min_by_key(|f| f.range.end_line - f.range.start_line)
+                    .map(|f| f.name.clone());
+                CallEdge {
+                    caller,
+                    callee: call.function_name,
+                    line: call.line,
+                }
+            })
+            .collect()
     }
 
-    /// Extract imports from the AST
-    pub fn extract_imports(
+    /// Find the identifiers named `name`, classified with the symbol, call and
+    /// import queries of the language. Comments and string literals are skipped.
+    pub fn find_references(
         &self,
         tree: &Tree,
         language: SupportedLanguage,
         source: &str,
-    ) -> Vec<Import> {
-        match language {
-            SupportedLanguage::Rust => extract_rust_imports(tree, source),
-            SupportedLanguage::Python => extract_python_imports(tree, source),
-            SupportedLanguage::TypeScript | SupportedLanguage::JavaScript => {
-                extract_ts_imports(tree, source)
+        name: &str,
+    ) -> Vec<SymbolReference> {
+        let Some(queries) = self.queries.get(&language) else {
+            return Vec::new();
+        };
+        let root = tree.root_node();
+
+        let definitions: HashSet<usize> = run_query(&queries.symbols, root, source, "definition")
+            .iter()
+            .filter_map(|hit| hit.node("name"))
+            .map(|node| node.id())
+            .collect();
+        let calls: HashSet<usize> = run_query(&queries.calls, root, source, "call")
+            .iter()
+            .filter_map(|hit| hit.node("name"))
+            .map(|node| node.id())
+            .collect();
+        let imports: Vec<std::ops::Range<usize>> =
+            run_query(&queries.imports, root, source, "import")
+                .iter()
+                .filter_map(|hit| hit.tagged("import"))
+                .map(|(_, node)| node.byte_range())
+                .collect();
+
+        let mut references = Vec::new();
+        let mut stack = vec![root];
+        while let Some(node) = stack.pop() {
+            let kind = node.kind();
+            if kind.contains("comment") || kind.contains("string") {
+                continue;
             }
+            if node.child_count() > 0 {
+                let mut cursor = node.walk();
+                // Reversed so occurrences come out in source order
+                let children: Vec<Node> = node.children(&mut cursor).collect();
+                stack.extend(children.into_iter().rev());
+                continue;
+            }
+            if !node.is_named() || &source[node.byte_range()] != name {
+                continue;
+            }
+
+            let kind = if definitions.contains(&node.id()) {
+                ReferenceKind::Definition
+            } else if calls.contains(&node.id()) {
+                ReferenceKind::Call
+            } else if imports.iter().any(|r| r.contains(&node.start_byte())) {
+                ReferenceKind::Import
+            } else {
+                ReferenceKind::Reference
+            };
+            references.push(SymbolReference {
+                kind,
+                line: node.start_position().row + 1,
+                column: node.start_position().column + 1,
+            });
         }
+        references
     }
 
     /// Calculate cyclomatic complexity of a function
@@ -418,12 +667,25 @@ impl AstParser {
             cursor: &mut tree_sitter::TreeCursor,
             count: &mut usize,
         ) {
+            // Named nodes only: Ruby's `if`/`while` keywords share the statement's kind
             match node.kind() {
                 "if_expression" | "if_statement" | "while_statement" | "while_expression"
                 | "for_statement" | "for_expression" | "match_expression" | "match_arm"
-                | "binary_expression" => {
+                | "binary_expression" | "do_statement" | "for_range_loop" | "case_statement"
+                | "conditional_expression" | "catch_clause" | "if" | "unless" | "elsif"
+                | "while" | "until" | "for" | "when" | "rescue" | "if_modifier"
+                | "unless_modifier" | "while_modifier" | "until_modifier" | "conditional"
+                | "foreach_statement" | "else_if_clause" | "match_conditional_expression"
+                | "expression_case" | "type_case" | "communication_case"
+                | "enhanced_for_statement" | "ternary_expression" | "catch_block"
+                | "do_while_statement"
+                    if node.is_named() =>
+                {
                     *count += 1;
                 }
+                // `default:` and Kotlin's `else ->` are not decisions
+                "switch_label" if node.named_child_count() > 0 => *count += 1,
+                "when_entry" if node.child_by_field_name("condition").is_some() => *count += 1,
                 _ => {}
             }
 
@@ -442,93 +704,63 @@ impl AstParser {
         count_decision_points(node, &mut cursor, &mut complexity);
         complexity
     }
-}
-
-// Helper functions for extracting Rust symbols
-fn extract_rust_function(node: &Node, source: &str) -> Option<AstSymbol> {
-    let name_node = node.child_by_field_name("name")?;
-    let name = get_node_text(&name_node, source);
-
-    let is_async = node.children(&mut node.walk()).any(|n| n.kind() == "async");
-    let is_test = has_test_attribute(node, source);
-
-    let visibility = extract_rust_visibility(node, source);
-    let params = extract_rust_parameters(node, source);
-    let return_type = extract_rust_return_type(node, source);
-    let docstring = extract_rust_docstring(node, source);
-
-    Some(AstSymbol {
-        name,
-        kind: SymbolKind::Function,
-        range: Range::from_node(node),
-        visibility,
-        params,
-        return_type,
-        docstring,
-        decorators: Vec::new(),
-        is_async,
-        is_test,
-    })
-}
-
-fn extract_rust_struct(node: &Node, source: &str) -> Option<AstSymbol> {
-    let name_node = node.child_by_field_name("name")?;
-    let name = get_node_text(&name_node, source);
-    let visibility = extract_rust_visibility(node, source);
-    let docstring = extract_rust_docstring(node, source);
-
-    Some(AstSymbol {
-        name,
-        kind: SymbolKind::Struct,
-        range: Range::from_node(node),
-        visibility,
-        params: Vec::new(),
-        return_type: None,
-        docstring,
-        decorators: Vec::new(),
-        is_async: false,
-        is_test: false,
-    })
-}
-
-fn extract_rust_enum(node: &Node, source: &str) -> Option<AstSymbol> {
-    let name_node = node.child_by_field_name("name")?;
-    let name = get_node_text(&name_node, source);
-    let visibility = extract_rust_visibility(node, source);
-    let docstring = extract_rust_docstring(node, source);
 
-    Some(AstSymbol {
-        name,
-        kind: SymbolKind::Enum,
-        range: Range::from_node(node),
-        visibility,
-        params: Vec::new(),
-        return_type: None,
-        docstring,
-        decorators: Vec::new(),
-        is_async: false,
-        is_test: false,
-    })
+    /// Deepest nesting of control-flow blocks inside a node (`else if` chains count once)
+    pub fn nesting_depth(&self, node: &Node) -> usize {
+        fn depth(node: &Node) -> usize {
+            let nests = matches!(
+                node.kind(),
+                "if_expression" | "if_statement" | "while_statement" | "while_expression"
+                    | "for_statement" | "for_expression" | "loop_expression" | "match_expression"
+                    | "switch_statement" | "try_statement" | "do_statement" | "for_range_loop"
+                    | "foreach_statement" | "with_statement" | "for_in_statement" | "if"
+                    | "unless" | "while" | "until" | "for" | "case" | "begin"
+                    | "expression_switch_statement" | "type_switch_statement" | "select_statement"
+                    | "enhanced_for_statement" | "switch_expression" | "try_with_resources_statement"
+                    | "when_expression" | "try_expression" | "do_while_statement"
+            ) && node.is_named()
+                // Go, Java and Kotlin have no else clause node: `else if` is an `if` that is
+                // the last child of its parent `if`
+                && node.parent().is_none_or(|p| {
+                    p.kind() != "else_clause"
+                        && !(p.kind() == node.kind()
+                            && matches!(p.kind(), "if_statement" | "if_expression")
+                            && p.named_child(p.named_child_count().saturating_sub(1)) == Some(*node))
+                });
+
+            let mut cursor = node.walk();
+            let deepest = node
+                .children(&mut cursor)
+                .map(|child| depth(&child))
+                .max()
+                .unwrap_or(0);
+            deepest + nests as usize
+        }
+        depth(node)
+    }
 }
 
-fn extract_rust_trait(node: &Node, source: &str) -> Option<AstSymbol> {
-    let name_node = node.child_by_field_name("name")?;
-    let name = get_node_text(&name_node, source);
-    let visibility = extract_rust_visibility(node, source);
-    let docstring = extract_rust_docstring(node, source);
+// Helper functions for Rust
+fn rust_symbol(name: String, kind: SymbolKind, node: &Node, source: &str) -> AstSymbol {
+    let is_function = matches!(kind, SymbolKind::Function | SymbolKind::Method);
+    let is_async = is_function
+        && node.children(&mut node.walk()).any(|n| {
+            n.kind() == "async"
+                || (n.kind() == "function_modifiers" && get_node_text(&n, source).contains("async"))
+        });
 
-    Some(AstSymbol {
+    AstSymbol {
         name,
-        kind: SymbolKind::Trait,
+        kind,
         range: Range::from_node(node),
-        visibility,
-        params: Vec::new(),
-        return_type: None,
-        docstring,
+        visibility: extract_rust_visibility(node, source),
+        params: extract_rust_parameters(node, source),
+        return_type: extract_rust_return_type(node, source),
+        docstring: extract_rust_docstring(node, source),
         decorators: Vec::new(),
-        is_async: false,
-        is_test: false,
-    })
+        is_async,
+        is_test: is_function && has_test_attribute(node, source),
+    }
 }
 
 fn extract_rust_visibility(node: &Node, source: &str) -> Visibility {
@@ -570,15 +802,65 @@ fn extract_rust_return_type(node: &Node, source: &str) -> Option<String> {
         .map(|t| get_node_text(&t, source))
 }
 
-fn extract_rust_docstring(node: &Node, _source: &str) -> Option<String> {
-    // Look for doc comments (///) before the node
-    let start_line = node.start_position().row;
-    if start_line == 0 {
-        return None;
+/// Doc comments of a Rust item: the `///` and `/** */` comments above it, with
+/// attributes and plain comments allowed in between, then for an inline module
+/// the `//!` and `/*! */` comments opening its body
+fn extract_rust_docstring(node: &Node, source: &str) -> Option<String> {
+    let mut lines = Vec::new();
+    let mut sibling = node.prev_sibling();
+    while let Some(current) = sibling {
+        match current.kind() {
+            "line_comment" | "block_comment" => {
+                let text = get_node_text(&current, source);
+                if is_rust_outer_doc(&text) {
+                    lines.push(text.trim_end().to_string());
+                } else if is_rust_inner_doc(&text) {
+                    break;
+                }
+            }
+            "attribute_item" => {}
+            _ => break,
+        }
+        sibling = current.prev_sibling();
     }
+    lines.reverse();
 
-    // Simple implementation - could be enhanced
-    None
+    if node.kind() == "mod_item" {
+        if let Some(body) = node.child_by_field_name("body") {
+            lines.extend(rust_inner_docs(&body, source));
+        }
+    }
+    (!lines.is_empty()).then(|| lines.join("\n"))
+}
+
+/// `///` and `/** */`, but not `////` or `/***/` separators
+fn is_rust_outer_doc(text: &str) -> bool {
+    (text.starts_with("///") && !text.starts_with("////"))
+        || (text.starts_with("/**") && !text.starts_with("/***") && text != "/**/")
+}
+
+fn is_rust_inner_doc(text: &str) -> bool {
+    text.starts_with("//!") || text.starts_with("/*!")
+}
+
+/// `//!` and `/*! */` comments at the start of a file or module body
+fn rust_inner_docs(container: &Node, source: &str) -> Vec<String> {
+    let mut lines = Vec::new();
+    for child in container.children(&mut container.walk()) {
+        match child.kind() {
+            "{" => {}
+            "line_comment" | "block_comment" => {
+                let text = get_node_text(&child, source);
+                if is_rust_inner_doc(&text) {
+                    lines.push(text.trim_end().to_string());
+                } else if is_rust_outer_doc(&text) {
+                    break;
+                }
+            }
+            _ => break,
+        }
+    }
+    lines
 }
 
 fn has_test_attribute(node: &Node, source: &str) -> bool {
@@ -594,30 +876,29 @@ fn has_test_attribute(node: &Node, source: &str) -> bool {
 }
 
 // Helper functions for Python
-fn extract_python_function(node: &Node, source: &str) -> Option<AstSymbol> {
-    let name_node = node.child_by_field_name("name")?;
-    let name = get_node_text(&name_node, source);
-
-    let is_async = node.children(&mut node.walk()).any(|n| n.kind() == "async");
-
-    let decorators = extract_python_decorators(node, source);
-    let is_test = decorators.iter().any(|d| d.contains("test"));
-
-    let params = extract_python_parameters(node, source);
-    let return_type = extract_python_return_type(node, source);
-
-    Some(AstSymbol {
+fn python_symbol(name: String, kind: SymbolKind, node: &Node, source: &str) -> AstSymbol {
+    // Decorators live on the wrapping `decorated_definition`
+    let decorators = node
+        .parent()
+        .filter(|p| p.kind() == "decorated_definition")
+        .map(|p| extract_python_decorators(&p, source))
+        .unwrap_or_default();
+    let is_function = matches!(kind, SymbolKind::Function | SymbolKind::Method);
+    let is_async = is_function && node.children(&mut node.walk()).any(|n| n.kind() == "async");
+    let is_test = is_function && decorators.iter().any(|d| d.contains("test"));
+
+    AstSymbol {
         name,
-        kind: SymbolKind::Function,
+        kind,
         range: Range::from_node(node),
         visibility: Visibility::Public, // Python doesn't have strict visibility
-        params,
-        return_type,
+        params: extract_python_parameters(node, source),
+        return_type: extract_python_return_type(node, source),
         docstring: extract_python_docstring(node, source),
         decorators,
         is_async,
         is_test,
-    })
+    }
 }
 
 fn extract_python_parameters(node: &Node, source: &str) -> Vec<Parameter> {
@@ -667,24 +948,6 @@ fn extract_python_return_type(node: &Node, source: &str) -> Option<String> {
         .map(|t| get_node_text(&t, source))
 }
 
-fn extract_python_class(node: &Node, source: &str) -> Option<AstSymbol> {
-    let name_node = node.child_by_field_name("name")?;
-    let name = get_node_text(&name_node, source);
-
-    Some(AstSymbol {
-        name,
-        kind: SymbolKind::Class,
-        range: Range::from_node(node),
-        visibility: Visibility::Public,
-        params: Vec::new(),
-        return_type: None,
-        docstring: extract_python_docstring(node, source),
-        decorators: extract_python_decorators(node, source),
-        is_async: false,
-        is_test: false,
-    })
-}
-
 fn extract_python_decorators(node: &Node, source: &str) -> Vec<String> {
     let mut decorators = Vec::new();
     for child in node.children(&mut node.walk()) {
@@ -712,34 +975,37 @@ fn extract_python_docstring(node: &Node, source: &str) -> Option<String> {
 }
 
 // Helper functions for TypeScript/JavaScript
-fn extract_ts_function(node: &Node, source: &str) -> Option<AstSymbol> {
-    let name_node = node.child_by_field_name("name")?;
-    let name = get_node_text(&name_node, source);
-
-    let is_async = node.children(&mut node.walk()).any(|n| n.kind() == "async");
-    
-    let visibility = extract_ts_visibility(node, source);
-    let params = extract_ts_parameters(node, source);
-    let return_type = extract_ts_return_type(node, source);
-    let docstring = extract_ts_jsdoc(node, source);
-    let decorators = extract_ts_decorators(node, source);
-
-    Some(AstSymbol {
+fn ts_symbol(name: String, kind: SymbolKind, node: &Node, source: &str) -> AstSymbol {
+    // `const f = (x) => ...`: signature details come from the assigned function
+    let function = if node.kind() == "lexical_declaration" {
+        node.named_children(&mut node.walk())
+            .find(|c| c.kind() == "variable_declarator")
+            .and_then(|d| d.child_by_field_name("value"))
+            .unwrap_or(*node)
+    } else {
+        *node
+    };
+    let is_async = function
+        .children(&mut function.walk())
+        .any(|n| n.kind() == "async");
+    // JSDoc of `export function ...` sits above the export statement
+    let exported = node
+        .parent()
+        .filter(|p| p.kind() == "export_statement")
+        .unwrap_or(*node);
+
+    AstSymbol {
         name,
-        kind: if node.kind() == "method_definition" {
-            SymbolKind::Method
-        } else {
-            SymbolKind::Function
-        },
+        kind,
         range: Range::from_node(node),
-        visibility,
-        params,
-        return_type,
-        docstring,
-        decorators,
+        visibility: extract_ts_visibility(node, source),
+        params: extract_ts_parameters(&function, source),
+        return_type: extract_ts_return_type(&function, source),
+        docstring: extract_ts_jsdoc(&exported, source),
+        decorators: extract_ts_decorators(node, source),
         is_async,
         is_test: false,
-    })
+    }
 }
 
 fn extract_ts_visibility(node: &Node, source: &str) -> Visibility {
@@ -819,178 +1085,927 @@ fn extract_ts_decorators(node: &Node, source: &str) -> Vec<String> {
     decorators
 }
 
-fn extract_ts_class(node: &Node, source: &str) -> Option<AstSymbol> {
-    let name_node = node.child_by_field_name("name")?;
-    let name = get_node_text(&name_node, source);
-
-    Some(AstSymbol {
+// Helper functions for C/C++
+fn c_symbol(name: String, kind: SymbolKind, node: &Node, source: &str) -> AstSymbol {
+    let function_declarator = (node.kind() == "function_definition")
+        .then(|| node.child_by_field_name("declarator"))
+        .flatten()
+        .and_then(find_c_function_declarator);
+
+    // `typedef struct Name { ... } Name;` spans the typedef, templates their header
+    let outer = node
+        .parent()
+        .filter(|p| p.kind() == "type_definition")
+        .unwrap_or_else(|| c_template_wrapper(node));
+
+    let visibility = match node.parent().map(|p| p.kind()) {
+        Some("field_declaration_list") => extract_cpp_access(node, source),
+        Some("field_declaration") => {
+            extract_cpp_access(&node.parent().unwrap_or(*node), source)
+        }
+        _ if node.children(&mut node.walk()).any(|c| {
+            c.kind() == "storage_class_specifier" && get_node_text(&c, source) == "static"
+        }) =>
+        {
+            Visibility::Private
+        }
+        _ => Visibility::Public,
+    };
+    let is_test = function_declarator.is_some()
+        && (name.starts_with("TEST") || name.starts_with("test_"));
+
+    AstSymbol {
+        params: function_declarator
+            .map(|f| extract_c_parameters(&f, source))
+            .unwrap_or_default(),
+        return_type: function_declarator.and_then(|f| extract_c_return_type(node, &f, source)),
         name,
-        kind: SymbolKind::Class,
-        range: Range::from_node(node),
-        visibility: Visibility::Public,
-        params: Vec::new(),
-        return_type: None,
-        docstring: None,
+        kind,
+        range: Range::from_node(&outer),
+        visibility,
+        docstring: extract_c_doc_comment(&outer, source),
         decorators: Vec::new(),
         is_async: false,
-        is_test: false,
-    })
+        is_test,
+    }
 }
 
-fn extract_ts_interface(node: &Node, source: &str) -> Option<AstSymbol> {
-    let name_node = node.child_by_field_name("name")?;
-    let name = get_node_text(&name_node, source);
+/// Everything before the declarator minus specifiers: `static const char *` → `const char *`.
+/// Constructors and destructors have no return type.
+fn extract_c_return_type(node: &Node, function_declarator: &Node, source: &str) -> Option<String> {
+    const SPECIFIERS: &[&str] = &[
+        "static", "inline", "extern", "virtual", "explicit", "constexpr", "friend",
+    ];
+    node.child_by_field_name("type")?;
+    let prefix = &source[node.start_byte()..function_declarator.start_byte()];
+    let return_type = prefix
+        .split_whitespace()
+        .filter(|word| !SPECIFIERS.contains(word))
+        .collect::<Vec<_>>()
+        .join(" ");
+    (!return_type.is_empty()).then_some(return_type)
+}
 
-    Some(AstSymbol {
-        name,
-        kind: SymbolKind::Interface,
-        range: Range::from_node(node),
-        visibility: Visibility::Public,
-        params: Vec::new(),
-        return_type: None,
-        docstring: None,
+/// The `template_declaration` wrapping a node, or the node itself
+fn c_template_wrapper<'a>(node: &Node<'a>) -> Node<'a> {
+    node.parent()
+        .filter(|p| p.kind() == "template_declaration")
+        .unwrap_or(*node)
+}
+
+/// Find the `function_declarator` under pointer/reference/parenthesized declarators
+fn find_c_function_declarator(node: Node) -> Option<Node> {
+    if node.kind() == "function_declarator" {
+        return Some(node);
+    }
+    let inner = node.child_by_field_name("declarator").or_else(|| {
+        node.named_children(&mut node.walk())
+            .find(|c| c.kind().ends_with("declarator"))
+    })?;
+    find_c_function_declarator(inner)
+}
+
+/// The identifier a (possibly pointer/reference/array) declarator declares
+fn c_declarator_identifier(node: Node) -> Option<Node> {
+    match node.kind() {
+        "identifier" | "field_identifier" | "qualified_identifier" | "destructor_name"
+        | "operator_name" => Some(node),
+        _ => {
+            let inner = node.child_by_field_name("declarator").or_else(|| {
+                node.named_children(&mut node.walk()).find(|c| {
+                    c.kind().ends_with("declarator") || c.kind().ends_with("identifier")
+                })
+            })?;
+            c_declarator_identifier(inner)
+        }
+    }
+}
+
+fn extract_c_parameters(function_declarator: &Node, source: &str) -> Vec<Parameter> {
+    let mut params = Vec::new();
+    let Some(params_node) = function_declarator.child_by_field_name("parameters") else {
+        return params;
+    };
+
+    for child in params_node.children(&mut params_node.walk()) {
+        if !matches!(
+            child.kind(),
+            "parameter_declaration" | "optional_parameter_declaration"
+        ) {
+            continue;
+        }
+        // Unnamed parameters (`void`, `int`) carry no useful name
+        let Some(ident) = child
+            .child_by_field_name("declarator")
+            .and_then(c_declarator_identifier)
+        else {
+            continue;
+        };
+        // The type is everything before the name: `const char *`, `std::vector<int> &`
+        let type_annotation = source[child.start_byte()..ident.start_byte()].trim();
+        params.push(Parameter {
+            name: get_node_text(&ident, source),
+            type_annotation: (!type_annotation.is_empty()).then(|| type_annotation.to_string()),
+            default_value: child
+                .child_by_field_name("default_value")
+                .map(|v| get_node_text(&v, source)),
+        });
+    }
+    params
+}
+
+/// Access of a class member: the closest preceding `public:`/`private:`/`protected:`
+/// label, or the default for the enclosing `class` (private) or `struct` (public)
+fn extract_cpp_access(node: &Node, source: &str) -> Visibility {
+    let mut sibling = node.prev_sibling();
+    while let Some(current) = sibling {
+        if current.kind() == "access_specifier" {
+            return match get_node_text(&current, source).trim() {
+                "private" => Visibility::Private,
+                "protected" => Visibility::Protected,
+                _ => Visibility::Public,
+            };
+        }
+        sibling = current.prev_sibling();
+    }
+
+    let is_class = node
+        .parent()
+        .and_then(|body| body.parent())
+        .is_some_and(|owner| owner.kind() == "class_specifier");
+    if is_class {
+        Visibility::Private
+    } else {
+        Visibility::Public
+    }
+}
+
+/// Doxygen-style comments (`/** */`, `/*! */`, `///`, `//!`) right above a node
+fn extract_c_doc_comment(node: &Node, source: &str) -> Option<String> {
+    let mut lines = Vec::new();
+    let mut expected_end_row = node.start_position().row;
+    let mut sibling = node.prev_sibling();
+
+    while let Some(current) = sibling {
+        if current.kind() != "comment" || current.end_position().row + 1 < expected_end_row {
+            break;
+        }
+        let text = get_node_text(&current, source);
+        if !["/**", "/*!", "///", "//!"].iter().any(|p| text.starts_with(p)) {
+            break;
+        }
+        lines.push(text);
+        expected_end_row = current.start_position().row;
+        sibling = current.prev_sibling();
+    }
+
+    if lines.is_empty() {
+        return None;
+    }
+    lines.reverse();
+    Some(lines.join("\n"))
+}
+
+// Helper functions for Ruby
+fn ruby_symbol(name: String, kind: SymbolKind, node: &Node, source: &str) -> AstSymbol {
+    let is_method = matches!(node.kind(), "method" | "singleton_method");
+    let name = match node.child_by_field_name("object") {
+        Some(object) if node.kind() == "singleton_method" => {
+            format!("{}.{}", get_node_text(&object, source), name)
+        }
+        _ => name,
+    };
+    let is_test = is_method && name.starts_with("test_");
+
+    AstSymbol {
+        name,
+        kind,
+        range: Range::from_node(node),
+        visibility: if is_method {
+            extract_ruby_visibility(node, source)
+        } else {
+            Visibility::Public
+        },
+        params: extract_ruby_parameters(node, source),
+        return_type: None,
+        docstring: extract_hash_comment(node, source),
         decorators: Vec::new(),
         is_async: false,
-        is_test: false,
-    })
+        is_test,
+    }
 }
 
-// Import extraction helpers
-fn extract_rust_imports(tree: &Tree, source: &str) -> Vec<Import> {
-    let mut imports = Vec::new();
-    let mut cursor = tree.walk();
+/// `private def foo`, or the last bare `private`/`protected`/`public` above the method
+fn extract_ruby_visibility(node: &Node, source: &str) -> Visibility {
+    fn from_keyword(keyword: &str) -> Option<Visibility> {
+        match keyword {
+            "private" => Some(Visibility::Private),
+            "protected" => Some(Visibility::Protected),
+            "public" => Some(Visibility::Public),
+            _ => None,
+        }
+    }
 
-    fn traverse(
-        node: &Node,
-        source: &str,
-        imports: &mut Vec<Import>,
-        cursor: &mut tree_sitter::TreeCursor,
-    ) {
-        if node.kind() == "use_declaration" {
-            if let Some(import) = parse_rust_use(node, source) {
-                imports.push(import);
+    if let Some(call) = node
+        .parent()
+        .filter(|p| p.kind() == "argument_list")
+        .and_then(|p| p.parent())
+    {
+        if let Some(visibility) = call
+            .child_by_field_name("method")
+            .and_then(|m| from_keyword(&get_node_text(&m, source)))
+        {
+            return visibility;
+        }
+    }
+
+    let mut sibling = node.prev_sibling();
+    while let Some(current) = sibling {
+        if current.kind() == "identifier" {
+            if let Some(visibility) = from_keyword(&get_node_text(&current, source)) {
+                return visibility;
             }
         }
+        sibling = current.prev_sibling();
+    }
+    Visibility::Public
+}
 
-        if cursor.goto_first_child() {
-            loop {
-                let child = cursor.node();
-                traverse(&child, source, imports, cursor);
-                if !cursor.goto_next_sibling() {
-                    break;
+fn extract_ruby_parameters(node: &Node, source: &str) -> Vec<Parameter> {
+    let mut params = Vec::new();
+    if let Some(params_node) = node.child_by_field_name("parameters") {
+        for child in params_node.named_children(&mut params_node.walk()) {
+            let (name, default_value) = match child.kind() {
+                "identifier" => (get_node_text(&child, source), None),
+                "optional_parameter" | "keyword_parameter" => {
+                    let Some(name_node) = child.child_by_field_name("name") else {
+                        continue;
+                    };
+                    (
+                        get_node_text(&name_node, source),
+                        child
+                            .child_by_field_name("value")
+                            .map(|v| get_node_text(&v, source)),
+                    )
                 }
+                // `*args`, `**opts`, `&block` keep their sigil
+                "splat_parameter" | "hash_splat_parameter" | "block_parameter" => {
+                    (get_node_text(&child, source), None)
+                }
+                _ => continue,
+            };
+            params.push(Parameter {
+                name,
+                type_annotation: None,
+                default_value,
+            });
+        }
+    }
+    params
+}
+
+/// Consecutive `#` comments right above a node. Read from the source lines: the
+/// grammar attaches a comment before a body's first statement outside the body.
+fn extract_hash_comment(node: &Node, source: &str) -> Option<String> {
+    let lines: Vec<&str> = source.lines().take(node.start_position().row).collect();
+    let comments: Vec<&str> = lines
+        .iter()
+        .rev()
+        .map(|l| l.trim())
+        .take_while(|l| l.starts_with('#') && !l.starts_with("#!"))
+        .collect();
+
+    if comments.is_empty() {
+        return None;
+    }
+    Some(comments.into_iter().rev().collect::<Vec<_>>().join("\n"))
+}
+
+// Helper functions for PHP
+fn php_symbol(name: String, kind: SymbolKind, node: &Node, source: &str) -> AstSymbol {
+    let visibility = node
+        .children(&mut node.walk())
+        .find(|c| c.kind() == "visibility_modifier")
+        .map(|m| match get_node_text(&m, source).as_str() {
+            "private" => Visibility::Private,
+            "protected" => Visibility::Protected,
+            _ => Visibility::Public,
+        })
+        .unwrap_or(Visibility::Public);
+    let is_test = kind == SymbolKind::Method && name.starts_with("test");
+
+    AstSymbol {
+        name,
+        kind,
+        range: Range::from_node(node),
+        visibility,
+        params: extract_php_parameters(node, source),
+        return_type: node
+            .child_by_field_name("return_type")
+            .map(|t| get_node_text(&t, source)),
+        docstring: extract_php_docblock(node, source),
+        decorators: extract_php_attributes(node, source),
+        is_async: false,
+        is_test,
+    }
+}
+
+fn extract_php_parameters(node: &Node, source: &str) -> Vec<Parameter> {
+    let mut params = Vec::new();
+    if let Some(params_node) = node.child_by_field_name("parameters") {
+        for child in params_node.named_children(&mut params_node.walk()) {
+            if !matches!(
+                child.kind(),
+                "simple_parameter" | "property_promotion_parameter" | "variadic_parameter"
+            ) {
+                continue;
+            }
+            if let Some(name_node) = child.child_by_field_name("name") {
+                params.push(Parameter {
+                    name: get_node_text(&name_node, source),
+                    type_annotation: child
+                        .child_by_field_name("type")
+                        .map(|t| get_node_text(&t, source)),
+                    default_value: child
+                        .child_by_field_name("default_value")
+                        .map(|v| get_node_text(&v, source)),
+                });
             }
-            cursor.goto_parent();
         }
     }
+    params
+}
 
-    let root = tree.root_node();
-    traverse(&root, source, &mut imports, &mut cursor);
-    imports
+fn extract_php_attributes(node: &Node, source: &str) -> Vec<String> {
+    node.child_by_field_name("attributes")
+        .map(|attrs| {
+            attrs
+                .named_children(&mut attrs.walk())
+                .map(|a| get_node_text(&a, source))
+                .collect()
+        })
+        .unwrap_or_default()
 }
 
-fn parse_rust_use(node: &Node, source: &str) -> Option<Import> {
-    let text = get_node_text(node, source);
-    let is_wildcard = text.contains("::*");
-    Some(Import {
-        module: text,
-        items: Vec::new(),
-        is_wildcard,
-        line: node.start_position().row + 1,
-    })
+fn extract_php_docblock(node: &Node, source: &str) -> Option<String> {
+    let comment = node.prev_sibling().filter(|s| s.kind() == "comment")?;
+    let text = get_node_text(&comment, source);
+    text.starts_with("/**").then_some(text)
 }
 
-fn extract_python_imports(tree: &Tree, source: &str) -> Vec<Import> {
-    let mut imports = Vec::new();
-    let mut cursor = tree.walk();
+// Helper functions for Go
+fn go_symbol(name: String, kind: SymbolKind, node: &Node, source: &str) -> AstSymbol {
+    // Exported names start with an upper-case letter
+    let visibility = if name.starts_with(|c: char| c.is_uppercase()) {
+        Visibility::Public
+    } else {
+        Visibility::Private
+    };
+    let params = extract_go_parameters(node, source);
+    let is_test = kind == SymbolKind::Function
+        && ["Test", "Benchmark", "Fuzz"].iter().any(|p| name.starts_with(p))
+        && params
+            .iter()
+            .any(|p| p.type_annotation.as_deref().is_some_and(|t| t.contains("testing.")));
+
+    AstSymbol {
+        name,
+        kind,
+        range: Range::from_node(node),
+        visibility,
+        params,
+        return_type: node
+            .child_by_field_name("result")
+            .map(|t| get_node_text(&t, source)),
+        docstring: extract_go_doc_comment(node, source),
+        decorators: Vec::new(),
+        is_async: false,
+        is_test,
+    }
+}
 
-    fn traverse(
-        node: &Node,
-        source: &str,
-        imports: &mut Vec<Import>,
-        cursor: &mut tree_sitter::TreeCursor,
-    ) {
-        match node.kind() {
-            "import_statement" | "import_from_statement" => {
-                if let Some(import) = parse_python_import(node, source) {
-                    imports.push(import);
+/// One parameter per name: `a, b int` declares two
+fn extract_go_parameters(node: &Node, source: &str) -> Vec<Parameter> {
+    let mut params = Vec::new();
+    if let Some(params_node) = node.child_by_field_name("parameters") {
+        for child in params_node.named_children(&mut params_node.walk()) {
+            let type_annotation = child.child_by_field_name("type").map(|t| {
+                let ty = get_node_text(&t, source);
+                match child.kind() {
+                    "variadic_parameter_declaration" => format!("...{}", ty),
+                    _ => ty,
+                }
+            });
+            let names: Vec<String> = child
+                .children_by_field_name("name", &mut child.walk())
+                .map(|n| get_node_text(&n, source))
+                .collect();
+            if names.is_empty() {
+                // Unnamed parameter: `func(int, string)`
+                if child.kind().ends_with("parameter_declaration") {
+                    params.push(Parameter {
+                        name: "_".to_string(),
+                        type_annotation,
+                        default_value: None,
+                    });
                 }
+                continue;
+            }
+            for name in names {
+                params.push(Parameter {
+                    name,
+                    type_annotation: type_annotation.clone(),
+                    default_value: None,
+                });
             }
-            _ => {}
         }
+    }
+    params
+}
 
-        if cursor.goto_first_child() {
-            loop {
-                let child = cursor.node();
-                traverse(&child, source, imports, cursor);
-                if !cursor.goto_next_sibling() {
-                    break;
-                }
+/// Consecutive `//` comments right above a node, the Go doc comment convention
+fn extract_go_doc_comment(node: &Node, source: &str) -> Option<String> {
+    let lines: Vec<&str> = source.lines().take(node.start_position().row).collect();
+    let comments: Vec<&str> = lines
+        .iter()
+        .rev()
+        .map(|l| l.trim())
+        .take_while(|l| l.starts_with("//"))
+        .collect();
+
+    if comments.is_empty() {
+        return None;
+    }
+    Some(comments.into_iter().rev().collect::<Vec<_>>().join("\n"))
+}
+
+// Helper functions for Java
+fn java_symbol(name: String, kind: SymbolKind, node: &Node, source: &str) -> AstSymbol {
+    let modifiers = node
+        .children(&mut node.walk())
+        .find(|c| c.kind() == "modifiers");
+    let keyword = |k: &str| {
+        modifiers.is_some_and(|m| m.children(&mut m.walk()).any(|c| c.kind() == k))
+    };
+    let visibility = if keyword("public") {
+        Visibility::Public
+    } else if keyword("private") {
+        Visibility::Private
+    } else if keyword("protected") {
+        Visibility::Protected
+    } else if node.parent().is_some_and(|p| p.kind() == "interface_body") {
+        // Interface members are implicitly public
+        Visibility::Public
+    } else {
+        Visibility::Internal
+    };
+    let decorators = extract_annotations(modifiers, source);
+    let is_test = kind == SymbolKind::Method && decorators.iter().any(|d| is_test_annotation(d));
+
+    AstSymbol {
+        name,
+        kind,
+        range: Range::from_node(node),
+        visibility,
+        params: extract_java_parameters(node, source),
+        return_type: (node.kind() == "method_declaration")
+            .then(|| node.child_by_field_name("type"))
+            .flatten()
+            .map(|t| get_node_text(&t, source)),
+        docstring: extract_javadoc(node, source),
+        decorators,
+        is_async: false,
+        is_test,
+    }
+}
+
+fn extract_java_parameters(node: &Node, source: &str) -> Vec<Parameter> {
+    let mut params = Vec::new();
+    if let Some(params_node) = node.child_by_field_name("parameters") {
+        for child in params_node.named_children(&mut params_node.walk()) {
+            let (name, type_annotation) = match child.kind() {
+                "formal_parameter" => (
+                    child.child_by_field_name("name"),
+                    child
+                        .child_by_field_name("type")
+                        .map(|t| get_node_text(&t, source)),
+                ),
+                // `String... names`
+                "spread_parameter" => (
+                    child
+                        .named_children(&mut child.walk())
+                        .find(|c| c.kind() == "variable_declarator")
+                        .and_then(|d| d.child_by_field_name("name")),
+                    child
+                        .named_children(&mut child.walk())
+                        .find(|c| c.kind().ends_with("type") || c.kind() == "type_identifier")
+                        .map(|t| format!("{}...", get_node_text(&t, source))),
+                ),
+                _ => continue,
+            };
+            if let Some(name) = name {
+                params.push(Parameter {
+                    name: get_node_text(&name, source),
+                    type_annotation,
+                    default_value: None,
+                });
             }
-            cursor.goto_parent();
         }
     }
+    params
+}
 
-    let root = tree.root_node();
-    traverse(&root, source, &mut imports, &mut cursor);
-    imports
+/// `/** ... */` right above a declaration (Javadoc and KDoc)
+fn extract_javadoc(node: &Node, source: &str) -> Option<String> {
+    let comment = node
+        .prev_sibling()
+        .filter(|s| s.kind() == "block_comment")?;
+    let text = get_node_text(&comment, source);
+    text.starts_with("/**").then_some(text)
 }
 
-fn parse_python_import(node: &Node, source: &str) -> Option<Import> {
-    let text = get_node_text(node, source);
-    let is_wildcard = text.contains("*");
-    Some(Import {
-        module: text,
-        items: Vec::new(),
-        is_wildcard,
-        line: node.start_position().row + 1,
-    })
+/// Annotations of a `modifiers` node, as written: `@Override`, `@Test(timeout = 10)`
+fn extract_annotations(modifiers: Option<Node>, source: &str) -> Vec<String> {
+    modifiers
+        .map(|m| {
+            m.named_children(&mut m.walk())
+                .filter(|c| matches!(c.kind(), "annotation" | "marker_annotation"))
+                .map(|a| get_node_text(&a, source))
+                .collect()
+        })
+        .unwrap_or_default()
 }
 
-fn extract_ts_imports(tree: &Tree, source: &str) -> Vec<Import> {
-    let mut imports = Vec::new();
-    let mut cursor = tree.walk();
+/// `@Test`, `@ParameterizedTest`, `@org.junit.Test`...
+fn is_test_annotation(annotation: &str) -> bool {
+    let name = annotation.split('(').next().unwrap_or(annotation);
+    name.ends_with("Test")
+}
 
-    fn traverse(
-        node: &Node,
-        source: &str,
-        imports: &mut Vec<Import>,
-        cursor: &mut tree_sitter::TreeCursor,
-    ) {
-        if node.kind() == "import_statement" {
-            if let Some(import) = parse_ts_import(node, source) {
-                imports.push(import);
-            }
-        }
+// Helper functions for Kotlin
+fn kotlin_symbol(name: String, kind: SymbolKind, node: &Node, source: &str) -> AstSymbol {
+    let modifiers = node
+        .children(&mut node.walk())
+        .find(|c| c.kind() == "modifiers");
+    let modifier = |kind: &str| {
+        modifiers.and_then(|m| {
+            m.named_children(&mut m.walk())
+                .find(|c| c.kind() == kind)
+                .map(|c| get_node_text(&c, source))
+        })
+    };
+    let visibility = match modifier("visibility_modifier").as_deref() {
+        Some("private") => Visibility::Private,
+        Some("protected") => Visibility::Protected,
+        Some("internal") => Visibility::Internal,
+        _ => Visibility::Public,
+    };
+    let decorators = extract_annotations(modifiers, source);
+    let is_function = matches!(kind, SymbolKind::Function | SymbolKind::Method);
+    let is_async = is_function
+        && modifiers.is_some_and(|m| {
+            m.named_children(&mut m.walk()).any(|c| {
+                c.kind() == "function_modifier" && get_node_text(&c, source) == "suspend"
+            })
+        });
+    let is_test = is_function && decorators.iter().any(|d| is_test_annotation(d));
+
+    AstSymbol {
+        name,
+        kind,
+        range: Range::from_node(node),
+        visibility,
+        params: extract_kotlin_parameters(node, source),
+        return_type: is_function
+            .then(|| extract_kotlin_return_type(node, source))
+            .flatten(),
+        docstring: extract_javadoc(node, source),
+        decorators,
+        is_async,
+        is_test,
+    }
+}
 
-        if cursor.goto_first_child() {
-            loop {
-                let child = cursor.node();
-                traverse(&child, source, imports, cursor);
-                if !cursor.goto_next_sibling() {
-                    break;
+/// Parameters of `function_value_parameters`: `vararg` comes as a modifier node before
+/// its parameter and a default value as the expression after it
+fn extract_kotlin_parameters(node: &Node, source: &str) -> Vec<Parameter> {
+    let mut params: Vec<Parameter> = Vec::new();
+    let Some(params_node) = node
+        .named_children(&mut node.walk())
+        .find(|c| c.kind() == "function_value_parameters")
+    else {
+        return params;
+    };
+
+    let mut vararg = false;
+    let mut after_parameter = false;
+    for child in params_node.named_children(&mut params_node.walk()) {
+        match child.kind() {
+            "parameter_modifiers" => {
+                vararg = get_node_text(&child, source).contains("vararg");
+                after_parameter = false;
+            }
+            "parameter" => {
+                let mut cursor = child.walk();
+                let mut parts = child.named_children(&mut cursor);
+                let Some(name) = parts.find(|c| c.kind() == "identifier") else {
+                    continue;
+                };
+                let type_annotation = parts.next().map(|t| {
+                    let ty = get_node_text(&t, source);
+                    if vararg {
+                        format!("vararg {}", ty)
+                    } else {
+                        ty
+                    }
+                });
+                params.push(Parameter {
+                    name: get_node_text(&name, source),
+                    type_annotation,
+                    default_value: None,
+                });
+                vararg = false;
+                after_parameter = true;
+            }
+            kind if after_parameter && !kind.contains("comment") => {
+                if let Some(param) = params.last_mut() {
+                    param.default_value = Some(get_node_text(&child, source));
                 }
+                after_parameter = false;
             }
-            cursor.goto_parent();
+            _ => {}
+        }
+    }
+    params
+}
+
+/// The type after the parameter list: `fun total(): Int` → `Int`
+fn extract_kotlin_return_type(node: &Node, source: &str) -> Option<String> {
+    let mut cursor = node.walk();
+    let mut children = node.named_children(&mut cursor);
+    children.find(|c| c.kind() == "function_value_parameters")?;
+    children
+        .next()
+        .filter(|c| !matches!(c.kind(), "function_body" | "type_constraints"))
+        .map(|t| get_node_text(&t, source))
+}
+
+/// The edits turning `old` into `new`: one per hunk of changed lines, trimmed to
+/// the bytes that differ. They are in order, each in the coordinates the previous
+/// ones leave, so [`AstParser::parse_incremental`] can apply them one by one;
+/// changes far apart stay separate and the code between them is not re-parsed.
+pub fn compute_edits(old: &str, new: &str) -> Vec<InputEdit> {
+    let old_lines: Vec<&str> = old.split_inclusive('\n').collect();
+    let new_lines: Vec<&str> = new.split_inclusive('\n').collect();
+    let mut edits = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    // Byte offset of new line `j`: everything before it is already edited
+    let mut offset = 0;
+
+    while i < old_lines.len() || j < new_lines.len() {
+        if i < old_lines.len() && j < new_lines.len() && old_lines[i] == new_lines[j] {
+            offset += new_lines[j].len();
+            i += 1;
+            j += 1;
+            continue;
+        }
+
+        let (old_end, new_end) = resync(&old_lines, &new_lines, i, j);
+        let old_hunk = old_lines[i..old_end].concat();
+        let new_hunk = new_lines[j..new_end].concat();
+        if let Some(edit) = compute_edit(&old_hunk, &new_hunk) {
+            // Hunks start at a line start, so only the first row is shifted
+            let shift = |point: Point| Point::new(j + point.row, point.column);
+            edits.push(InputEdit {
+                start_byte: offset + edit.start_byte,
+                old_end_byte: offset + edit.old_end_byte,
+                new_end_byte: offset + edit.new_end_byte,
+                start_position: shift(edit.start_position),
+                old_end_position: shift(edit.old_end_position),
+                new_end_position: shift(edit.new_end_position),
+            });
         }
+        offset += new_hunk.len();
+        i = old_end;
+        j = new_end;
     }
+    edits
+}
 
-    let root = tree.root_node();
-    traverse(&root, source, &mut imports, &mut cursor);
-    imports
+/// Lines searched ahead for the end of a changed hunk
+const RESYNC_WINDOW: usize = 64;
+
+/// Nearest lines at or after `i` and `j` where both texts agree again; the ends
+/// of both texts when none is found within [`RESYNC_WINDOW`] lines
+fn resync(old: &[&str], new: &[&str], i: usize, j: usize) -> (usize, usize) {
+    for distance in 1..=2 * RESYNC_WINDOW {
+        for skipped_old in distance.saturating_sub(RESYNC_WINDOW)..=distance.min(RESYNC_WINDOW) {
+            let (a, b) = (i + skipped_old, j + distance - skipped_old);
+            if a < old.len() && b < new.len() && old[a] == new[b] {
+                return (a, b);
+            }
+        }
+    }
+    (old.len(), new.len())
 }
 
-fn parse_ts_import(node: &Node, source: &str) -> Option<Import> {
-    let text = get_node_text(node, source);
-    let is_wildcard = text.contains("*");
-    Some(Import {
-        module: text,
-        items: Vec::new(),
-        is_wildcard,
-        line: node.start_position().row + 1,
+/// The single edit turning `old` into `new`: the common prefix and suffix are kept
+/// and the bytes between them replaced. `None` when both are equal.
+pub fn compute_edit(old: &str, new: &str) -> Option<InputEdit> {
+    if old == new {
+        return None;
+    }
+
+    let mut start = old
+        .bytes()
+        .zip(new.bytes())
+        .take_while(|(a, b)| a == b)
+        .count();
+    while !old.is_char_boundary(start) || !new.is_char_boundary(start) {
+        start -= 1;
+    }
+
+    let mut suffix = old.as_bytes()[start..]
+        .iter()
+        .rev()
+        .zip(new.as_bytes()[start..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    while !old.is_char_boundary(old.len() - suffix) || !new.is_char_boundary(new.len() - suffix) {
+        suffix -= 1;
+    }
+
+    let old_end = old.len() - suffix;
+    let new_end = new.len() - suffix;
+    Some(InputEdit {
+        start_byte: start,
+        old_end_byte: old_end,
+        new_end_byte: new_end,
+        start_position: point_at(old, start),
+        old_end_position: point_at(old, old_end),
+        new_end_position: point_at(new, new_end),
     })
 }
 
+/// Row and byte column of a byte offset
+fn point_at(text: &str, byte: usize) -> Point {
+    let before = &text[..byte];
+    let row = before.matches('\n').count();
+    let column = byte - before.rfind('\n').map_or(0, |i| i + 1);
+    Point::new(row, column)
+}
+
+/// Name of a symbol with the types and modules enclosing it:
+/// `RouterOrchestrator::process` for a method of an `impl` block, `Invoice.total`
+/// for a method of a class
+pub fn qualified_name(tree: &Tree, language: SupportedLanguage, source: &str, symbol: &AstSymbol) -> String {
+    let Some(node) = symbol_node(tree, symbol) else {
+        return symbol.name.clone();
+    };
+
+    let mut names = vec![symbol.name.clone()];
+    let mut current = node.parent();
+    while let Some(parent) = current {
+        names.extend(owner_name(&parent, source));
+        current = parent.parent();
+    }
+    names.reverse();
+    let separator = match language {
+        SupportedLanguage::Rust | SupportedLanguage::C | SupportedLanguage::Cpp => "::",
+        _ => ".",
+    };
+    names.join(separator)
+}
+
+/// Name a node gives the definitions nested in it: the type of an `impl` block,
+/// the name of a class, trait, interface, namespace or module
+fn owner_name(node: &Node, source: &str) -> Option<String> {
+    const OWNERS: &[&str] = &["class", "interface", "trait", "struct", "object", "module", "mod_item", "namespace"];
+    let field = match node.kind() {
+        "impl_item" => "type",
+        kind if OWNERS.iter().any(|owner| kind.contains(owner)) => "name",
+        _ => return None,
+    };
+    let name = get_node_text(&node.child_by_field_name(field)?, source);
+    // `Parser<'a>` → `Parser`
+    Some(name.split('<').next().unwrap_or(&name).trim().to_string())
+}
+
+/// Node a symbol was extracted from
+fn symbol_node<'t>(tree: &'t Tree, symbol: &AstSymbol) -> Option<Node<'t>> {
+    let range = &symbol.range;
+    tree.root_node().descendant_for_point_range(
+        Point::new(range.start_line - 1, range.start_col),
+        Point::new(range.end_line - 1, range.end_col),
+    )
+}
+
+/// Fields of a struct or variants of an enum, with their doc comments; only
+/// Rust for now
+pub fn extract_fields(tree: &Tree, language: SupportedLanguage, source: &str, symbol: &AstSymbol) -> Vec<Field> {
+    if language != SupportedLanguage::Rust {
+        return Vec::new();
+    }
+    let Some(body) = symbol_node(tree, symbol).and_then(|node| node.child_by_field_name("body")) else {
+        return Vec::new();
+    };
+
+    let mut fields = Vec::new();
+    for child in body.named_children(&mut body.walk()) {
+        if !matches!(child.kind(), "field_declaration" | "enum_variant") {
+            continue;
+        }
+        let Some(name) = child.child_by_field_name("name") else {
+            continue;
+        };
+        fields.push(Field {
+            name: get_node_text(&name, source),
+            type_annotation: child.child_by_field_name("type").map(|t| get_node_text(&t, source)),
+            docstring: extract_rust_docstring(&child, source),
+            line: child.start_position().row + 1,
+        });
+    }
+    fields
+}
+
+/// Doc comments of the file itself: the `//!` block opening a Rust file
+pub fn module_docstring(tree: &Tree, language: SupportedLanguage, source: &str) -> Option<String> {
+    match language {
+        SupportedLanguage::Rust => {
+            let lines = rust_inner_docs(&tree.root_node(), source);
+            (!lines.is_empty()).then(|| lines.join("\n"))
+        }
+        _ => None,
+    }
+}
+
+/// Text of a doc comment without its markers: `/// Loads the config` →
+/// `Loads the config`
+pub fn doc_comment_text(raw: &str) -> String {
+    const MARKERS: &[&str] = &["///", "//!", "//", "/**", "/*!", "/*", "*", "#"];
+    raw.lines()
+        .map(|line| {
+            let line = line.trim();
+            let line = line.strip_suffix("*/").unwrap_or(line).trim_end();
+            let line = MARKERS.iter().find_map(|m| line.strip_prefix(m)).unwrap_or(line);
+            line.strip_prefix(' ').unwrap_or(line)
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+        .trim()
+        .to_string()
+}
+
+/// How many times each identifier appears in a file, outside comments and
+/// strings. The type of a Rust `impl` block is not counted: implementing
+/// methods for a type does not use it.
+pub fn identifier_counts(tree: &Tree, source: &str) -> HashMap<String, usize> {
+    let mut counts = HashMap::new();
+    let mut stack = vec![tree.root_node()];
+    while let Some(node) = stack.pop() {
+        let kind = node.kind();
+        if kind.contains("comment") || kind.contains("string") {
+            continue;
+        }
+        if node.child_count() > 0 {
+            let impl_type = (kind == "impl_item")
+                .then(|| node.child_by_field_name("type"))
+                .flatten()
+                .map(|t| t.id());
+            let mut cursor = node.walk();
+            stack.extend(node.children(&mut cursor).filter(|c| Some(c.id()) != impl_type));
+            continue;
+        }
+        if node.is_named() {
+            *counts.entry(get_node_text(&node, source)).or_insert(0) += 1;
+        }
+    }
+    counts
+}
+
+/// Whether a symbol is declared by a trait or implements one (`impl Display for
+/// X`): those are called through the trait, not by name
+pub fn is_trait_member(tree: &Tree, symbol: &AstSymbol) -> bool {
+    let mut current = symbol_node(tree, symbol).and_then(|node| node.parent());
+    while let Some(node) = current {
+        match node.kind() {
+            "trait_item" => return true,
+            "impl_item" => return node.child_by_field_name("trait").is_some(),
+            "declaration_list" => current = node.parent(),
+            _ => return false,
+        }
+    }
+    false
+}
+
+/// `"stdio.h"`, `<vector>`, `'json'` → the bare path
+fn clean_module_path(text: &str) -> String {
+    text.trim()
+        .trim_matches(|c| matches!(c, '"' | '\'' | '<' | '>'))
+        .to_string()
+}
+
+
 // Utility function to get node text
 fn get_node_text(node: &Node, source: &str) -> String {
     let start = node.start_byte();
@@ -1026,6 +2041,62 @@ mod tests {
         assert_eq!(symbols[1].kind, SymbolKind::Struct);
     }
 
+    #[test]
+    fn test_rust_docstrings() {
+        let code = r#"//! Invoices and their lines
+//! of items
+
+/// A bill sent to a customer
+// not part of the docs
+#[derive(Debug)]
+pub struct Invoice {
+    /// Amount in cents
+    pub total: u64,
+    pub paid: bool,
+}
+
+//// separator, not a doc
+fn undocumented() {}
+
+/** Anything that can be billed */
+pub trait Billable {
+    /// Build the invoice
+    fn bill(&self) -> Invoice;
+}
+
+/// Tax rules
+mod tax {
+    //! Rates per country
+}
+"#;
+
+        let mut parser = AstParser::new().unwrap();
+        let tree = parser.parse(SupportedLanguage::Rust, code).unwrap();
+        let symbols = parser.extract_symbols(&tree, SupportedLanguage::Rust, code);
+        let doc = |name: &str| {
+            let symbol = symbols.iter().find(|s| s.name == name).unwrap();
+            symbol.docstring.as_deref().map(doc_comment_text)
+        };
+
+        assert_eq!(doc("Invoice").as_deref(), Some("A bill sent to a customer"));
+        assert_eq!(doc("undocumented"), None);
+        assert_eq!(doc("Billable").as_deref(), Some("Anything that can be billed"));
+        assert_eq!(doc("bill").as_deref(), Some("Build the invoice"));
+        assert_eq!(doc("tax").as_deref(), Some("Tax rules\nRates per country"));
+        assert_eq!(
+            module_docstring(&tree, SupportedLanguage::Rust, code).map(|d| doc_comment_text(&d)).as_deref(),
+            Some("Invoices and their lines\nof items")
+        );
+
+        let invoice = symbols.iter().find(|s| s.name == "Invoice").unwrap();
+        let fields = extract_fields(&tree, SupportedLanguage::Rust, code, invoice);
+        assert_eq!(fields.len(), 2);
+        assert_eq!(fields[0].name, "total");
+        assert_eq!(fields[0].type_annotation.as_deref(), Some("u64"));
+        assert_eq!(fields[0].docstring.as_deref(), Some("/// Amount in cents"));
+        assert_eq!(fields[1].docstring, None);
+    }
+
     #[test]
     fn test_python_parsing() {
         let code = r#"
@@ -1044,4 +2115,759 @@ class Calculator:
 
         assert!(symbols.len() >= 2);
     }
+
+    #[test]
+    fn test_c_parsing() {
+        let code = r#"
+#include <stdio.h>
+#include "config.h"
+
+typedef struct {
+    int x;
+} Point;
+
+/** Print a greeting */
+static const char *greet(const char *name, int times) {
+    for (int i = 0; i < times; i++) {
+        if (name) printf("%s\n", name);
+    }
+    return name;
+}
+"#;
+
+        let mut parser = AstParser::new().unwrap();
+        let tree = parser.parse(SupportedLanguage::C, code).unwrap();
+        let symbols = parser.extract_symbols(&tree, SupportedLanguage::C, code);
+
+        assert_eq!(symbols.len(), 2);
+        assert_eq!(symbols[0].name, "Point");
+        assert_eq!(symbols[0].kind, SymbolKind::Struct);
+        let greet = &symbols[1];
+        assert_eq!(greet.name, "greet");
+        assert_eq!(greet.visibility, Visibility::Private);
+        assert_eq!(greet.return_type.as_deref(), Some("const char *"));
+        assert_eq!(greet.docstring.as_deref(), Some("/** Print a greeting */"));
+        assert_eq!(greet.params.len(), 2);
+        assert_eq!(greet.params[0].name, "name");
+        assert_eq!(greet.params[0].type_annotation.as_deref(), Some("const char *"));
+
+        let imports = parser.extract_imports(&tree, SupportedLanguage::C, code);
+        let modules: Vec<_> = imports.iter().map(|i| i.module.as_str()).collect();
+        assert_eq!(modules, vec!["stdio.h", "config.h"]);
+    }
+
+    #[test]
+    fn test_cpp_parsing() {
+        let code = r#"
+#include <vector>
+using namespace std;
+
+namespace geo {
+class Shape {
+public:
+    virtual double area() const { return 0.0; }
+private:
+    void reset() {}
+};
+
+template <typename T>
+T clamp(T v, T lo, T hi = T()) {
+    return v < lo ? lo : (v > hi ? hi : v);
+}
+}
+
+double geo::Shape::perimeter() const { return 1.0; }
+"#;
+
+        let mut parser = AstParser::new().unwrap();
+        let tree = parser.parse(SupportedLanguage::Cpp, code).unwrap();
+        let symbols = parser.extract_symbols(&tree, SupportedLanguage::Cpp, code);
+        let find = |name: &str| symbols.iter().find(|s| s.name == name).unwrap();
+
+        assert_eq!(find("geo").kind, SymbolKind::Module);
+        assert_eq!(find("Shape").kind, SymbolKind::Class);
+        assert_eq!(find("area").kind, SymbolKind::Method);
+        assert_eq!(find("area").visibility, Visibility::Public);
+        assert_eq!(find("reset").visibility, Visibility::Private);
+        assert_eq!(find("geo::Shape::perimeter").kind, SymbolKind::Method);
+
+        let clamp = find("clamp");
+        assert_eq!(clamp.kind, SymbolKind::Function);
+        assert!(code.lines().nth(clamp.range.start_line - 1).unwrap().starts_with("template"));
+        assert_eq!(clamp.params[2].default_value.as_deref(), Some("T()"));
+
+        let imports = parser.extract_imports(&tree, SupportedLanguage::Cpp, code);
+        assert_eq!(imports.len(), 2);
+        assert!(imports[1].is_wildcard);
+
+        let function = tree
+            .root_node()
+            .descendant_for_point_range(
+                tree_sitter::Point::new(14, 0),
+                tree_sitter::Point::new(14, 1),
+            )
+            .unwrap();
+        assert!(parser.calculate_complexity(&function, code) >= 3);
+    }
+
+    #[test]
+    fn test_ruby_parsing() {
+        let code = r#"
+require 'json'
+require_relative "../lib/helpers"
+
+module Billing
+  # Computes invoice totals
+  class Invoice < ApplicationRecord
+    def total(tax = 0.21, *items, currency:)
+      items.sum * (1 + tax) unless items.empty?
+    end
+
+    def self.build(attrs)
+      new(attrs)
+    end
+
+    private
+
+    def recalculate
+    end
+  end
+end
+"#;
+
+        let mut parser = AstParser::new().unwrap();
+        let tree = parser.parse(SupportedLanguage::Ruby, code).unwrap();
+        let symbols = parser.extract_symbols(&tree, SupportedLanguage::Ruby, code);
+        let find = |name: &str| symbols.iter().find(|s| s.name == name).unwrap();
+
+        assert_eq!(find("Billing").kind, SymbolKind::Module);
+        let invoice = find("Invoice");
+        assert_eq!(invoice.kind, SymbolKind::Class);
+        assert_eq!(invoice.docstring.as_deref(), Some("# Computes invoice totals"));
+        let total = find("total");
+        assert_eq!(total.kind, SymbolKind::Method);
+        assert_eq!(total.params.len(), 3);
+        assert_eq!(total.params[0].default_value.as_deref(), Some("0.21"));
+        assert_eq!(total.params[1].name, "*items");
+        assert_eq!(find("self.build").visibility, Visibility::Public);
+        assert_eq!(find("recalculate").visibility, Visibility::Private);
+
+        let imports = parser.extract_imports(&tree, SupportedLanguage::Ruby, code);
+        let modules: Vec<_> = imports.iter().map(|i| i.module.as_str()).collect();
+        assert_eq!(modules, vec!["json", "../lib/helpers"]);
+    }
+
+    #[test]
+    fn test_php_parsing() {
+        let code = r#"<?php
+namespace App\Http\Controllers;
+
+use Illuminate\Http\Request;
+use App\Models\{User, Post};
+require_once 'helpers.php';
+
+/** Handles users */
+class UserController extends Controller
+{
+    public function show(Request $request, int $id = 1): View
+    {
+        return view('user', ['user' => User::find($id)]);
+    }
+
+    private function audit(string ...$events): void {}
+}
+
+interface Auditable {}
+trait Loggable {}
+"#;
+
+        let mut parser = AstParser::new().unwrap();
+        let tree = parser.parse(SupportedLanguage::Php, code).unwrap();
+        let symbols = parser.extract_symbols(&tree, SupportedLanguage::Php, code);
+        let find = |name: &str| symbols.iter().find(|s| s.name == name).unwrap();
+
+        assert_eq!(find("App\\Http\\Controllers").kind, SymbolKind::Module);
+        let controller = find("UserController");
+        assert_eq!(controller.kind, SymbolKind::Class);
+        assert_eq!(controller.docstring.as_deref(), Some("/** Handles users */"));
+        let show = find("show");
+        assert_eq!(show.kind, SymbolKind::Method);
+        assert_eq!(show.return_type.as_deref(), Some("View"));
+        assert_eq!(show.params[1].name, "$id");
+        assert_eq!(show.params[1].type_annotation.as_deref(), Some("int"));
+        assert_eq!(find("audit").visibility, Visibility::Private);
+        assert_eq!(find("Auditable").kind, SymbolKind::Interface);
+        assert_eq!(find("Loggable").kind, SymbolKind::Trait);
+
+        let imports = parser.extract_imports(&tree, SupportedLanguage::Php, code);
+        assert_eq!(imports.len(), 3);
+        assert_eq!(imports[0].module, "Illuminate\\Http\\Request");
+        assert_eq!(imports[1].module, "App\\Models");
+        assert_eq!(imports[1].items, vec!["User", "Post"]);
+        assert_eq!(imports[2].module, "helpers.php");
+    }
+
+    #[test]
+    fn test_go_parsing() {
+        let code = r#"package billing
+
+import (
+    "fmt"
+    str "strings"
+    "github.com/acme/shop/internal/tax"
+)
+
+const MaxItems = 10
+
+// Invoice is a bill sent to a customer
+type Invoice struct {
+    Items []int
+}
+
+type Totaler interface {
+    Total() int
+}
+
+// Total sums the items
+func (i *Invoice) Total(rate float64, extra ...int) (int, error) {
+    sum := 0
+    for _, item := range i.Items {
+        if item > 0 && rate > 0 {
+            sum += item
+        } else if item < 0 {
+            return 0, fmt.Errorf("negative item %d", item)
+        }
+    }
+    switch {
+    case sum > MaxItems:
+        return tax.Apply(sum), nil
+    }
+    return sum, nil
+}
+
+func newInvoice(a, b int) *Invoice { return &Invoice{} }
+"#;
+
+        let mut parser = AstParser::new().unwrap();
+        let tree = parser.parse(SupportedLanguage::Go, code).unwrap();
+        let symbols = parser.extract_symbols(&tree, SupportedLanguage::Go, code);
+        let find = |name: &str| symbols.iter().find(|s| s.name == name).unwrap();
+
+        assert_eq!(find("MaxItems").kind, SymbolKind::Constant);
+        let invoice = find("Invoice");
+        assert_eq!(invoice.kind, SymbolKind::Struct);
+        assert_eq!(invoice.docstring.as_deref(), Some("// Invoice is a bill sent to a customer"));
+        assert_eq!(find("Totaler").kind, SymbolKind::Interface);
+        let total = find("Total");
+        assert_eq!(total.kind, SymbolKind::Method);
+        assert_eq!(total.visibility, Visibility::Public);
+        assert_eq!(total.return_type.as_deref(), Some("(int, error)"));
+        assert_eq!(total.params[1].name, "extra");
+        assert_eq!(total.params[1].type_annotation.as_deref(), Some("...int"));
+        let new_invoice = find("newInvoice");
+        assert_eq!(new_invoice.visibility, Visibility::Private);
+        assert_eq!(new_invoice.params.len(), 2);
+
+        let imports = parser.extract_imports(&tree, SupportedLanguage::Go, code);
+        let modules: Vec<_> = imports.iter().map(|i| i.module.as_str()).collect();
+        assert_eq!(modules, vec!["fmt", "strings", "github.com/acme/shop/internal/tax"]);
+
+        let calls: Vec<_> = parser
+            .extract_calls(&tree, SupportedLanguage::Go, code)
+            .into_iter()
+            .map(|c| c.function_name)
+            .collect();
+        assert_eq!(calls, vec!["Errorf", "Apply"]);
+
+        let root = tree.root_node();
+        let method = root
+            .named_children(&mut root.walk())
+            .find(|n| n.kind() == "method_declaration")
+            .unwrap();
+        assert!(parser.calculate_complexity(&method, code) >= 5);
+        // for > if; the `else if` and the switch do not nest deeper
+        assert_eq!(parser.nesting_depth(&method), 2);
+    }
+
+    #[test]
+    fn test_java_parsing() {
+        let code = r#"package com.acme.shop;
+
+import java.util.List;
+import java.util.concurrent.*;
+import static org.junit.Assert.assertEquals;
+
+/** An invoice */
+@Entity
+public class Invoice implements Totaler {
+    public static final int MAX_ITEMS = 10;
+
+    @Override
+    public int total(double rate, int... extra) {
+        int sum = 0;
+        for (int item : items) {
+            if (item > 0 && rate > 0) {
+                sum += item;
+            } else if (item < 0) {
+                throw new IllegalStateException("negative");
+            }
+        }
+        return Rates.apply(sum);
+    }
+
+    void reset() {}
+}
+
+interface Totaler {
+    int total(double rate, int... extra);
+}
+
+class InvoiceTest {
+    @Test
+    void totalIsSum() {
+        assertEquals(1, new Invoice().total(1.0));
+    }
+}
+"#;
+
+        let mut parser = AstParser::new().unwrap();
+        let tree = parser.parse(SupportedLanguage::Java, code).unwrap();
+        let symbols = parser.extract_symbols(&tree, SupportedLanguage::Java, code);
+        let find = |name: &str| symbols.iter().find(|s| s.name == name).unwrap();
+
+        let invoice = find("Invoice");
+        assert_eq!(invoice.kind, SymbolKind::Class);
+        assert_eq!(invoice.decorators, vec!["@Entity"]);
+        assert_eq!(invoice.docstring.as_deref(), Some("/** An invoice */"));
+        assert_eq!(find("MAX_ITEMS").kind, SymbolKind::Constant);
+        let total = symbols
+            .iter()
+            .find(|s| s.name == "total" && s.visibility == Visibility::Public)
+            .unwrap();
+        assert_eq!(total.kind, SymbolKind::Method);
+        assert_eq!(total.decorators, vec!["@Override"]);
+        assert_eq!(total.return_type.as_deref(), Some("int"));
+        assert_eq!(total.params[1].name, "extra");
+        assert_eq!(total.params[1].type_annotation.as_deref(), Some("int..."));
+        assert_eq!(find("reset").visibility, Visibility::Internal);
+        assert_eq!(find("Totaler").kind, SymbolKind::Interface);
+        assert!(find("totalIsSum").is_test);
+
+        let imports = parser.extract_imports(&tree, SupportedLanguage::Java, code);
+        let modules: Vec<_> = imports.iter().map(|i| i.module.as_str()).collect();
+        assert_eq!(
+            modules,
+            vec!["java.util.List", "java.util.concurrent", "org.junit.Assert.assertEquals"]
+        );
+        assert!(imports[1].is_wildcard);
+
+        let calls: Vec<_> = parser
+            .extract_calls(&tree, SupportedLanguage::Java, code)
+            .into_iter()
+            .map(|c| c.function_name)
+            .collect();
+        assert_eq!(
+            calls,
+            vec!["IllegalStateException", "apply", "assertEquals", "total", "Invoice"]
+        );
+
+        let range = &total.range;
+        let method = tree
+            .root_node()
+            .descendant_for_point_range(
+                tree_sitter::Point::new(range.start_line - 1, range.start_col),
+                tree_sitter::Point::new(range.end_line - 1, range.end_col),
+            )
+            .unwrap();
+        assert_eq!(method.kind(), "method_declaration");
+        assert!(parser.calculate_complexity(&method, code) >= 4);
+        // for > if; the `else if` does not nest deeper
+        assert_eq!(parser.nesting_depth(&method), 2);
+    }
+
+    #[test]
+    fn test_kotlin_parsing() {
+        let code = r#"package com.acme.shop
+
+import com.acme.shop.tax.Rates
+import kotlinx.coroutines.*
+import org.junit.Test as JTest
+
+const val MAX_ITEMS = 10
+
+/** An invoice */
+@Serializable
+data class Invoice(val items: List<Int>) : Totaler {
+    @Throws(IllegalStateException::class)
+    override fun total(rate: Double, vararg extra: Int): Int {
+        var sum = 0
+        for (item in items) {
+            if (item > 0 && rate > 0) {
+                sum += item
+            } else if (item < 0) {
+                throw IllegalStateException("negative")
+            }
+        }
+        return when {
+            sum > MAX_ITEMS -> Rates.apply(sum)
+            else -> sum
+        }
+    }
+
+    private suspend fun load(id: String = "a"): String? = fetch(id)
+}
+
+interface Totaler {
+    fun total(rate: Double, vararg extra: Int): Int
+}
+
+enum class Status {
+    OPEN,
+    PAID,
+}
+
+fun topLevel(a: Int, b: Int) = a + b
+"#;
+
+        let mut parser = AstParser::new().unwrap();
+        let tree = parser.parse(SupportedLanguage::Kotlin, code).unwrap();
+        let symbols = parser.extract_symbols(&tree, SupportedLanguage::Kotlin, code);
+        let find = |name: &str| symbols.iter().find(|s| s.name == name).unwrap();
+
+        assert_eq!(find("MAX_ITEMS").kind, SymbolKind::Constant);
+        let invoice = find("Invoice");
+        assert_eq!(invoice.kind, SymbolKind::Class);
+        assert_eq!(invoice.decorators, vec!["@Serializable"]);
+        assert_eq!(invoice.docstring.as_deref(), Some("/** An invoice */"));
+        let total = find("total");
+        assert_eq!(total.kind, SymbolKind::Method);
+        assert_eq!(total.decorators, vec!["@Throws(IllegalStateException::class)"]);
+        assert_eq!(total.return_type.as_deref(), Some("Int"));
+        assert_eq!(total.params[1].type_annotation.as_deref(), Some("vararg Int"));
+        let load = find("load");
+        assert!(load.is_async);
+        assert_eq!(load.visibility, Visibility::Private);
+        assert_eq!(load.params[0].default_value.as_deref(), Some("\"a\""));
+        assert_eq!(load.return_type.as_deref(), Some("String?"));
+        assert_eq!(find("Totaler").kind, SymbolKind::Interface);
+        assert_eq!(find("Status").kind, SymbolKind::Enum);
+        assert_eq!(find("topLevel").kind, SymbolKind::Function);
+
+        let imports = parser.extract_imports(&tree, SupportedLanguage::Kotlin, code);
+        let modules: Vec<_> = imports.iter().map(|i| i.module.as_str()).collect();
+        assert_eq!(
+            modules,
+            vec!["com.acme.shop.tax.Rates", "kotlinx.coroutines", "org.junit.Test"]
+        );
+        assert!(imports[1].is_wildcard);
+
+        let calls: Vec<_> = parser
+            .extract_calls(&tree, SupportedLanguage::Kotlin, code)
+            .into_iter()
+            .map(|c| c.function_name)
+            .collect();
+        assert_eq!(calls, vec!["IllegalStateException", "apply", "fetch"]);
+
+        let range = &total.range;
+        let method = tree
+            .root_node()
+            .descendant_for_point_range(
+                tree_sitter::Point::new(range.start_line - 1, range.start_col),
+                tree_sitter::Point::new(range.end_line - 1, range.end_col),
+            )
+            .unwrap();
+        assert_eq!(method.kind(), "function_declaration");
+        assert!(parser.calculate_complexity(&method, code) >= 5);
+        assert_eq!(parser.nesting_depth(&method), 2);
+    }
+
+    #[test]
+    fn test_language_from_path() {
+        assert_eq!(
+            SupportedLanguage::from_path(Path::new("app/models/user.rb")),
+            Some(SupportedLanguage::Ruby)
+        );
+        assert_eq!(
+            SupportedLanguage::from_path(Path::new("Rakefile")),
+            Some(SupportedLanguage::Ruby)
+        );
+        assert_eq!(
+            SupportedLanguage::from_path(Path::new("routes/web.php")),
+            Some(SupportedLanguage::Php)
+        );
+        assert_eq!(
+            SupportedLanguage::from_path(Path::new("firmware/blink/blink.ino")),
+            Some(SupportedLanguage::Cpp)
+        );
+        assert_eq!(
+            SupportedLanguage::from_path(Path::new("include/ring_buffer.tpp")),
+            Some(SupportedLanguage::Cpp)
+        );
+        assert_eq!(SupportedLanguage::from_path(Path::new("README")), None);
+    }
+
+    #[test]
+    fn test_typescript_queries() {
+        let code = r#"
+import { readFile, writeFile } from 'fs/promises';
+import * as path from 'path';
+
+export interface Config { root: string }
+
+/** Loads the config */
+export const loadConfig = async (file: string): Promise<Config> => {
+    const raw = await readFile(path.join(file), 'utf8');
+    return JSON.parse(raw);
+};
+
+class Loader {
+    load(file: string) { return loadConfig(file); }
+}
+"#;
+
+        let mut parser = AstParser::new().unwrap();
+        let tree = parser.parse(SupportedLanguage::TypeScript, code).unwrap();
+
+        let symbols = parser.extract_symbols(&tree, SupportedLanguage::TypeScript, code);
+        let names: Vec<_> = symbols.iter().map(|s| (s.name.as_str(), s.kind.clone())).collect();
+        assert_eq!(
+            names,
+            vec![
+                ("Config", SymbolKind::Interface),
+                ("loadConfig", SymbolKind::Function),
+                ("Loader", SymbolKind::Class),
+                ("load", SymbolKind::Method),
+            ]
+        );
+        let load_config = &symbols[1];
+        assert!(load_config.is_async);
+        assert_eq!(load_config.params[0].name, "file");
+        assert_eq!(load_config.docstring.as_deref(), Some("/** Loads the config */"));
+
+        let imports = parser.extract_imports(&tree, SupportedLanguage::TypeScript, code);
+        assert_eq!(imports.len(), 2);
+        assert_eq!(imports[0].module, "fs/promises");
+        assert_eq!(imports[0].items, vec!["readFile", "writeFile"]);
+        assert!(imports[1].is_wildcard);
+
+        let calls: Vec<_> = parser
+            .extract_calls(&tree, SupportedLanguage::TypeScript, code)
+            .into_iter()
+            .map(|c| c.function_name)
+            .collect();
+        assert_eq!(calls, vec!["readFile", "join", "parse", "loadConfig"]);
+    }
+
+    #[test]
+    fn test_extract_calls() {
+        let code = r#"
+fn main() {
+    let config = Config::load("neuro.toml");
+    let name = config.name();
+    println!("{}", name);
+    run(config);
+}
+"#;
+
+        let mut parser = AstParser::new().unwrap();
+        let tree = parser.parse(SupportedLanguage::Rust, code).unwrap();
+        let calls = parser.extract_calls(&tree, SupportedLanguage::Rust, code);
+
+        let names: Vec<_> = calls.iter().map(|c| c.function_name.as_str()).collect();
+        assert_eq!(names, vec!["load", "name", "println", "run"]);
+        assert_eq!(calls[0].line, 3);
+
+
+        let code = "use std::collections::{HashMap, HashSet};\nuse super::*;\n";
+        let tree = parser.parse(SupportedLanguage::Rust, code).unwrap();
+        let imports = parser.extract_imports(&tree, SupportedLanguage::Rust, code);
+        assert_eq!(imports.len(), 2);
+        assert_eq!(imports[0].module, "std::collections");
+        assert_eq!(imports[0].items, vec!["HashMap", "HashSet"]);
+        assert!(imports[1].is_wildcard);
+    }
+
+    #[test]
+    fn test_extract_call_graph() {
+        let code = r#"
+fn main() {
+    let config = load();
+    run(config, || validate());
+}
+
+impl Runner {
+    fn run(&self) {
+        self.step();
+    }
+}
+
+static READY: Lazy<bool> = Lazy::new(init);
+"#;
+
+        let mut parser = AstParser::new().unwrap();
+        let tree = parser.parse(SupportedLanguage::Rust, code).unwrap();
+        let edges: Vec<_> = parser
+            .extract_call_graph(&tree, SupportedLanguage::Rust, code)
+            .into_iter()
+            .map(|e| (e.caller, e.callee, e.line))
+            .collect();
+
+        let main = Some("main".to_string());
+        assert_eq!(
+            edges,
+            vec![
+                (main.clone(), "load".to_string(), 3),
+                (main.clone(), "run".to_string(), 4),
+                (main, "validate".to_string(), 4),
+                (Some("run".to_string()), "step".to_string(), 9),
+                (None, "new".to_string(), 13),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_find_references() {
+        let code = r#"
+use crate::config::load_config;
+
+/// Wraps load_config
+fn load_config_or_default() -> Config {
+    let loader = load_config;
+    // load_config may fail
+    load_config().unwrap_or(loader("load_config"))
+}
+
+fn load_config() -> Config {}
+"#;
+
+        let mut parser = AstParser::new().unwrap();
+        let tree = parser.parse(SupportedLanguage::Rust, code).unwrap();
+        let refs = parser.find_references(&tree, SupportedLanguage::Rust, code, "load_config");
+
+        let found: Vec<_> = refs.iter().map(|r| (r.line, r.kind)).collect();
+        assert_eq!(
+            found,
+            vec![
+                (2, ReferenceKind::Import),
+                (6, ReferenceKind::Reference),
+                (8, ReferenceKind::Call),
+                (11, ReferenceKind::Definition),
+            ]
+        );
+        assert_eq!(refs[0].column, 20);
+    }
+
+    #[test]
+    fn test_incremental_parse() {
+        let path = Path::new("src/lib.rs");
+        let original = "fn alpha() -> u32 {\n    1\n}\n\nfn beta() {}\n";
+        let edited = "fn alpha() -> u32 {\n    1\n}\n\nfn beta_renamed() {}\n";
+
+        let mut parser = AstParser::new().unwrap();
+        let first = parser.parse_file(path, SupportedLanguage::Rust, original).unwrap();
+        assert!(first.changed_ranges.is_none());
+
+        let unchanged = parser.parse_file(path, SupportedLanguage::Rust, original).unwrap();
+        assert_eq!(unchanged.changed_ranges.as_deref().map(|r| r.len()), Some(0));
+
+        let second = parser.parse_file(path, SupportedLanguage::Rust, edited).unwrap();
+        let symbols = parser.extract_symbols(&second.tree, SupportedLanguage::Rust, edited);
+        assert_eq!(symbols[1].name, "beta_renamed");
+        assert!(!second.touches(&symbols[0].range));
+        assert!(second.touches(&symbols[1].range));
+        assert_eq!(parser.cached_files(), 1);
+        for i in 0..PARSE_CACHE_FILES {
+            let other = PathBuf::from(format!("src/m{}.rs", i));
+            parser.parse_file(&other, SupportedLanguage::Rust, "fn m() {}\n").unwrap();
+        }
+        assert_eq!(parser.cached_files(), PARSE_CACHE_FILES);
+        assert!(!parser.trees.contains(path));
+
+        // Same tree as a parse from scratch
+        let fresh = parser.parse(SupportedLanguage::Rust, edited).unwrap();
+        assert_eq!(
+            second.tree.root_node().to_sexp(),
+            fresh.root_node().to_sexp()
+        );
+
+        let edit = compute_edit("héllo wörld", "héllo world").unwrap();
+        assert_eq!((edit.start_byte, edit.old_end_byte, edit.new_end_byte), (8, 10, 9));
+        assert!(compute_edit("same", "same").is_none());
+    }
+
+    #[test]
+    fn test_qualified_name() {
+        let mut parser = AstParser::new().unwrap();
+        let rust = "struct Parser<'a>(&'a str);\n\nimpl<'a> Parser<'a> {\n    fn parse(&self) {}\n}\n\nfn main() {}\n";
+        let tree = parser.parse(SupportedLanguage::Rust, rust).unwrap();
+        let names: Vec<String> = parser
+            .extract_symbols(&tree, SupportedLanguage::Rust, rust)
+            .iter()
+            .map(|s| qualified_name(&tree, SupportedLanguage::Rust, rust, s))
+            .collect();
+        assert_eq!(names, vec!["Parser", "Parser::parse", "main"]);
+
+        let python = "class Invoice:\n    def total(self):\n        return 0\n";
+        let tree = parser.parse(SupportedLanguage::Python, python).unwrap();
+        let symbols = parser.extract_symbols(&tree, SupportedLanguage::Python, python);
+        let total = symbols.iter().find(|s| s.name == "total").unwrap();
+        assert_eq!(qualified_name(&tree, SupportedLanguage::Python, python, total), "Invoice.total");
+    }
+
+    #[test]
+    fn test_parse_incremental_edits() {
+        let body: String = (0..40).map(|i| format!("fn f{}() -> u32 {{\n    {}\n}}\n\n", i, i)).collect();
+        let original = format!("use std::fmt;\n\n{}fn last() {{}}\n", body);
+        let edited = format!(
+            "use std::io;\n\n{}fn last_one() {{\n    f1();\n}}\n",
+            body.replace("    17\n", "    170\n")
+        );
+
+        // Three far-apart hunks, each cut to the bytes that differ
+        let edits = compute_edits(&original, &edited);
+        assert_eq!(edits.len(), 3);
+        assert_eq!(&edited[edits[0].start_byte..edits[0].new_end_byte], "io");
+        assert_eq!(edits[1].start_position.row, 71);
+        let mut replayed = original.clone();
+        for edit in &edits {
+            replayed.replace_range(edit.start_byte..edit.old_end_byte, &edited[edit.start_byte..edit.new_end_byte]);
+        }
+        assert_eq!(replayed, edited);
+        assert!(compute_edits(&edited, &edited).is_empty());
+
+        let mut parser = AstParser::new().unwrap();
+        let old_tree = parser.parse(SupportedLanguage::Rust, &original).unwrap();
+        let parse = parser
+            .parse_incremental(SupportedLanguage::Rust, &old_tree, &edits, &edited)
+            .unwrap();
+        let fresh = parser.parse(SupportedLanguage::Rust, &edited).unwrap();
+        assert_eq!(parse.tree.root_node().to_sexp(), fresh.root_node().to_sexp());
+
+        let symbols = parser.extract_symbols(&parse.tree, SupportedLanguage::Rust, &edited);
+        let touched: Vec<&str> = symbols
+            .iter()
+            .filter(|s| parse.touches(&s.range))
+            .map(|s| s.name.as_str())
+            .collect();
+        assert_eq!(touched, vec!["f17", "last_one"]);
+    }
+
+    #[test]
+    fn test_identifier_counts_and_trait_members() {
+        let mut parser = AstParser::new().unwrap();
+        let code = "struct Config;\n\nimpl Config {\n    fn load() -> Config { Config }\n}\n\nimpl std::fmt::Display for Config {\n    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result { Ok(()) }\n}\n\n// Config\nfn main() { let s = \"load\"; }\n";
+        let tree = parser.parse(SupportedLanguage::Rust, code).unwrap();
+        let counts = identifier_counts(&tree, code);
+        // Definition, return type and value; not the two impl headers nor the comment
+        assert_eq!(counts["Config"], 3);
+        assert_eq!(counts["load"], 1);
+
+        let symbols = parser.extract_symbols(&tree, SupportedLanguage::Rust, code);
+        let members: Vec<&str> = symbols
+            .iter()
+            .filter(|s| is_trait_member(&tree, s))
+            .map(|s| s.name.as_str())
+            .collect();
+        assert_eq!(members, vec!["fmt"]);
+    }
 }
@@ -0,0 +1,207 @@
+//! Tree-sitter queries for symbol, import and call extraction
+//!
+//! Every language has `symbols.scm`, `imports.scm` and `calls.scm` under
+//! `src/ast/queries/<language>/`, embedded in the binary. A file with the same
+//! relative path under `~/.config/neuro/queries/` replaces the embedded one, so
+//! coverage can be extended without rebuilding.
+//!
+//! Capture conventions:
+//! - symbols: `@definition.<kind>` on the definition (`function`, `method`, `class`,
+//!   `struct`, `enum`, `trait`, `interface`, `module`, `constant`) and `@name` on its name
+//! - imports: `@import` (or `@import.wildcard`) on the statement, `@module` on the
+//!   imported path and `@item` on each imported name
+//! - calls: `@call` on the call expression and `@name` on the called function
+//!
+//! When several patterns match the same node, the one written first in the file wins.
+
+use super::SupportedLanguage;
+use crate::config::AppConfig;
+use crate::log_warn;
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use streaming_iterator::StreamingIterator;
+use tree_sitter::{Node, Query, QueryCursor};
+
+/// Kind of query file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QueryKind {
+    Symbols,
+    Imports,
+    Calls,
+}
+
+impl QueryKind {
+    pub fn file_name(&self) -> &'static str {
+        match self {
+            Self::Symbols => "symbols.scm",
+            Self::Imports => "imports.scm",
+            Self::Calls => "calls.scm",
+        }
+    }
+}
+
+macro_rules! embedded_queries {
+    ($lang:literal) => {
+        [
+            include_str!(concat!("queries/", $lang, "/symbols.scm")),
+            include_str!(concat!("queries/", $lang, "/imports.scm")),
+            include_str!(concat!("queries/", $lang, "/calls.scm")),
+        ]
+    };
+}
+
+/// Query sources shipped with the binary, in `QueryKind` order
+fn embedded(language: SupportedLanguage) -> [&'static str; 3] {
+    match language {
+        SupportedLanguage::Rust => embedded_queries!("rust"),
+        SupportedLanguage::Python => embedded_queries!("python"),
+        SupportedLanguage::TypeScript => embedded_queries!("typescript"),
+        SupportedLanguage::JavaScript => embedded_queries!("javascript"),
+        SupportedLanguage::C => embedded_queries!("c"),
+        SupportedLanguage::Cpp => embedded_queries!("cpp"),
+        SupportedLanguage::Ruby => embedded_queries!("ruby"),
+        SupportedLanguage::Php => embedded_queries!("php"),
+        SupportedLanguage::Go => embedded_queries!("go"),
+        SupportedLanguage::Java => embedded_queries!("java"),
+        SupportedLanguage::Kotlin => embedded_queries!("kotlin"),
+    }
+}
+
+/// User override for a query file: `~/.config/neuro/queries/<language>/<kind>.scm`
+pub fn override_path(language: SupportedLanguage, kind: QueryKind) -> Option<PathBuf> {
+    AppConfig::config_dir().map(|dir| {
+        dir.join("queries")
+            .join(language.as_str())
+            .join(kind.file_name())
+    })
+}
+
+/// Compiled queries of one language
+pub struct LanguageQueries {
+    pub symbols: Query,
+    pub imports: Query,
+    pub calls: Query,
+}
+
+impl LanguageQueries {
+    /// Compile the queries of a language, preferring user overrides that compile
+    pub fn load(language: SupportedLanguage) -> Result<Self> {
+        let [symbols, imports, calls] = embedded(language);
+        Ok(Self {
+            symbols: compile(language, QueryKind::Symbols, symbols)?,
+            imports: compile(language, QueryKind::Imports, imports)?,
+            calls: compile(language, QueryKind::Calls, calls)?,
+        })
+    }
+
+    pub fn get(&self, kind: QueryKind) -> &Query {
+        match kind {
+            QueryKind::Symbols => &self.symbols,
+            QueryKind::Imports => &self.imports,
+            QueryKind::Calls => &self.calls,
+        }
+    }
+}
+
+fn compile(language: SupportedLanguage, kind: QueryKind, embedded: &str) -> Result<Query> {
+    let ts_language = language.tree_sitter_language();
+
+    if let Some(path) = override_path(language, kind).filter(|p| p.is_file()) {
+        // A broken override must not disable parsing: fall back to the embedded query
+        match std::fs::read_to_string(&path)
+            .map_err(anyhow::Error::from)
+            .and_then(|source| Query::new(&ts_language, &source).map_err(anyhow::Error::from))
+        {
+            Ok(query) => return Ok(query),
+            Err(e) => log_warn!("Ignoring query override {}: {}", path.display(), e),
+        }
+    }
+
+    Query::new(&ts_language, embedded).with_context(|| {
+        format!(
+            "Invalid embedded query {}/{}",
+            language.as_str(),
+            kind.file_name()
+        )
+    })
+}
+
+/// One query match with its captures by name
+pub struct QueryHit<'tree> {
+    pub pattern_index: usize,
+    captures: Vec<(String, Node<'tree>)>,
+}
+
+impl<'tree> QueryHit<'tree> {
+    /// First node captured as `name`
+    pub fn node(&self, name: &str) -> Option<Node<'tree>> {
+        self.nodes(name).next()
+    }
+
+    /// All nodes captured as `name`
+    pub fn nodes<'a>(&'a self, name: &'a str) -> impl Iterator<Item = Node<'tree>> + 'a {
+        self.captures
+            .iter()
+            .filter(move |(capture, _)| capture == name)
+            .map(|(_, node)| *node)
+    }
+
+    /// The capture named `tag` or `tag.<suffix>`, with its suffix (`""` when bare)
+    pub fn tagged(&self, tag: &str) -> Option<(&str, Node<'tree>)> {
+        self.captures.iter().find_map(|(capture, node)| {
+            let suffix = capture.strip_prefix(tag)?;
+            (suffix.is_empty() || suffix.starts_with('.'))
+                .then(|| (suffix.trim_start_matches('.'), *node))
+        })
+    }
+}
+
+/// Run a query and keep, for every node tagged by several patterns, only the
+/// matches of the first pattern. Hits are ordered by position of the tagged node.
+pub fn run_query<'tree>(
+    query: &Query,
+    root: Node<'tree>,
+    source: &str,
+    tag: &str,
+) -> Vec<QueryHit<'tree>> {
+    let names = query.capture_names();
+    let mut cursor = QueryCursor::new();
+    let mut matches = cursor.matches(query, root, source.as_bytes());
+
+    let mut hits = Vec::new();
+    while let Some(m) = matches.next() {
+        hits.push(QueryHit {
+            pattern_index: m.pattern_index,
+            captures: m
+                .captures
+                .iter()
+                .map(|c| (names[c.index as usize].to_string(), c.node))
+                .collect(),
+        });
+    }
+
+    let mut first_pattern: HashMap<usize, usize> = HashMap::new();
+    for hit in &hits {
+        if let Some((_, node)) = hit.tagged(tag) {
+            first_pattern
+                .entry(node.id())
+                .and_modify(|p| *p = (*p).min(hit.pattern_index))
+                .or_insert(hit.pattern_index);
+        }
+    }
+
+    let mut hits: Vec<QueryHit> = hits
+        .into_iter()
+        .filter(|hit| {
+            hit.tagged(tag)
+                .is_some_and(|(_, node)| first_pattern[&node.id()] == hit.pattern_index)
+        })
+        .collect();
+    // Outer nodes first when two start at the same byte
+    hits.sort_by_key(|hit| {
+        hit.tagged(tag)
+            .map(|(_, node)| (node.start_byte(), std::cmp::Reverse(node.end_byte())))
+    });
+    hits
+}
@@ -0,0 +1 @@
+(preproc_include path: (_) @module) @import
@@ -0,0 +1,7 @@
+(call_expression
+  function: [
+    (identifier) @name
+    (field_expression field: (field_identifier) @name)
+    (qualified_identifier name: (identifier) @name)
+    (template_function name: (identifier) @name)
+  ]) @call
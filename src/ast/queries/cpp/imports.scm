@@ -0,0 +1,7 @@
+(preproc_include path: (_) @module) @import
+
+; using namespace std;
+(using_declaration "namespace" (_) @module) @import.wildcard
+
+; using std::string;
+(using_declaration (_) @module) @import
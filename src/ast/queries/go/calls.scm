@@ -0,0 +1,5 @@
+(call_expression
+  function: [
+    (identifier) @name
+    (selector_expression field: (field_identifier) @name)
+  ]) @call
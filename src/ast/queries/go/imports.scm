@@ -0,0 +1,2 @@
+; import "fmt", import ( str "strings" ), import . "math"
+(import_spec path: (_) @module) @import
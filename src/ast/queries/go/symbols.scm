@@ -0,0 +1,8 @@
+(function_declaration name: (identifier) @name) @definition.function
+(method_declaration name: (field_identifier) @name) @definition.method
+
+(type_spec name: (type_identifier) @name type: (struct_type)) @definition.struct
+(type_spec name: (type_identifier) @name type: (interface_type)) @definition.interface
+
+; Package-level constants only, not the ones declared in a function body
+(source_file (const_declaration (const_spec name: (identifier) @name) @definition.constant))
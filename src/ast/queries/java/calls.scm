@@ -0,0 +1,5 @@
+(method_invocation name: (identifier) @name) @call
+(object_creation_expression type: [
+  (type_identifier) @name
+  (generic_type (type_identifier) @name)
+]) @call
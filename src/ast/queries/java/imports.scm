@@ -0,0 +1,5 @@
+; import java.util.*; import static org.junit.Assert.*;
+(import_declaration (scoped_identifier) @module (asterisk)) @import.wildcard
+
+; import java.util.List; import static java.lang.Math.max;
+(import_declaration [(scoped_identifier) (identifier)] @module) @import
@@ -0,0 +1,7 @@
+(call_expression
+  function: [
+    (identifier) @name
+    (member_expression property: (property_identifier) @name)
+  ]) @call
+
+(new_expression constructor: (identifier) @name) @call
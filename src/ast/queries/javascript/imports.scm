@@ -0,0 +1,17 @@
+; import * as fs from 'fs'
+(import_statement
+  (import_clause (namespace_import))
+  source: (string (string_fragment) @module)) @import.wildcard
+
+; import { a, b as c } from './mod'
+(import_statement
+  (import_clause (named_imports (import_specifier name: (_) @item)))
+  source: (string (string_fragment) @module)) @import
+
+(import_statement source: (string (string_fragment) @module)) @import
+
+; const x = require('mod')
+(call_expression
+  function: (identifier) @_require
+  arguments: (arguments . (string (string_fragment) @module))
+  (#eq? @_require "require")) @import
@@ -0,0 +1,12 @@
+(method_definition name: (_) @name) @definition.method
+
+(function_declaration name: (identifier) @name) @definition.function
+(generator_function_declaration name: (identifier) @name) @definition.function
+
+; const handler = async (req) => { ... }
+(lexical_declaration
+  (variable_declarator
+    name: (identifier) @name
+    value: [(arrow_function) (function_expression)])) @definition.function
+
+(class_declaration name: (identifier) @name) @definition.class
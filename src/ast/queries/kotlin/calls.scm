@@ -0,0 +1,4 @@
+(call_expression . [
+  (identifier) @name
+  (navigation_expression (identifier) @name .)
+]) @call
@@ -0,0 +1,5 @@
+; import kotlinx.coroutines.*
+(import (qualified_identifier) @module "*") @import.wildcard
+
+; import org.junit.Test, import org.junit.Test as JTest
+(import (qualified_identifier) @module) @import
@@ -0,0 +1,13 @@
+; Interfaces and enums are class declarations too: the first pattern wins
+(class_declaration "interface" name: (identifier) @name) @definition.interface
+(class_declaration name: (identifier) @name (enum_class_body)) @definition.enum
+(class_declaration name: (identifier) @name) @definition.class
+(object_declaration name: (identifier) @name) @definition.class
+
+; A function in a class or object body is a method
+(class_body (function_declaration name: (identifier) @name) @definition.method)
+(function_declaration name: (identifier) @name) @definition.function
+
+(property_declaration
+  (modifiers (property_modifier))
+  (variable_declaration (identifier) @name)) @definition.constant
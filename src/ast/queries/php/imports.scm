@@ -0,0 +1,14 @@
+; use App\Models\{User, Post};
+(namespace_use_declaration
+  (namespace_name) @module
+  body: (namespace_use_group
+    (namespace_use_clause . [(name) (qualified_name)] @item))) @import
+
+; use Illuminate\Http\Request, Foo\Bar as Baz;
+(namespace_use_declaration
+  (namespace_use_clause . [(name) (qualified_name)] @module)) @import
+
+(require_expression (_) @module) @import
+(require_once_expression (_) @module) @import
+(include_expression (_) @module) @import
+(include_once_expression (_) @module) @import
@@ -0,0 +1,8 @@
+(method_declaration name: (name) @name) @definition.method
+(function_definition name: (name) @name) @definition.function
+
+(class_declaration name: (name) @name) @definition.class
+(interface_declaration name: (name) @name) @definition.interface
+(trait_declaration name: (name) @name) @definition.trait
+(enum_declaration name: (name) @name) @definition.enum
+(namespace_definition name: (namespace_name) @name) @definition.module
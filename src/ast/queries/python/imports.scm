@@ -0,0 +1,10 @@
+(import_from_statement module_name: (_) @module (wildcard_import)) @import.wildcard
+
+; from a import b, c as d
+(import_from_statement
+  module_name: (_) @module
+  name: [(dotted_name) @item (aliased_import name: (dotted_name) @item)]) @import
+
+; import a, b as c
+(import_statement
+  name: [(dotted_name) @module (aliased_import name: (dotted_name) @module)]) @import
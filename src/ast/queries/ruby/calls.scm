@@ -0,0 +1 @@
+(call method: (identifier) @name) @call
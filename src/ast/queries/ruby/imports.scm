@@ -0,0 +1,6 @@
+; require 'json', require_relative '../lib/foo', load 'tasks.rb'
+(call
+  !receiver
+  method: (identifier) @_method
+  arguments: (argument_list . (string (string_content) @module))
+  (#any-of? @_method "require" "require_relative" "load")) @import
@@ -0,0 +1,18 @@
+; Methods first: `def` in a class, module or `class << self` body, also
+; behind a visibility call (`private def foo`)
+(class body: (body_statement (method name: (_) @name) @definition.method))
+(module body: (body_statement (method name: (_) @name) @definition.method))
+(singleton_class body: (body_statement (method name: (_) @name) @definition.method))
+
+(class
+  body: (body_statement
+    (call arguments: (argument_list (method name: (_) @name) @definition.method))))
+(module
+  body: (body_statement
+    (call arguments: (argument_list (method name: (_) @name) @definition.method))))
+
+(singleton_method name: (_) @name) @definition.method
+(method name: (_) @name) @definition.function
+
+(class name: (_) @name) @definition.class
+(module name: (_) @name) @definition.module
@@ -0,0 +1,9 @@
+(call_expression
+  function: [
+    (identifier) @name
+    (field_expression field: (field_identifier) @name)
+    (scoped_identifier name: (identifier) @name)
+    (generic_function function: (identifier) @name)
+  ]) @call
+
+(macro_invocation macro: (identifier) @name) @call
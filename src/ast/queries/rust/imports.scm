@@ -0,0 +1,9 @@
+(use_declaration argument: (use_wildcard) @module) @import.wildcard
+
+; use a::b::{c, d};
+(use_declaration
+  argument: (scoped_use_list
+    path: (_) @module
+    list: (use_list [(identifier) (self) (scoped_identifier)] @item))) @import
+
+(use_declaration argument: (_) @module) @import
@@ -0,0 +1,24 @@
+; Methods first: a function inside an impl or trait block is a method
+(impl_item
+  body: (declaration_list
+    (function_item name: (identifier) @name) @definition.method))
+
+(trait_item
+  body: (declaration_list
+    (function_item name: (identifier) @name) @definition.method))
+
+; Required trait methods have no body
+(trait_item
+  body: (declaration_list
+    (function_signature_item name: (identifier) @name) @definition.method))
+
+(function_item name: (identifier) @name) @definition.function
+
+(struct_item name: (type_identifier) @name) @definition.struct
+(union_item name: (type_identifier) @name) @definition.struct
+(enum_item name: (type_identifier) @name) @definition.enum
+(trait_item name: (type_identifier) @name) @definition.trait
+(mod_item name: (identifier) @name body: (declaration_list)) @definition.module
+(const_item name: (identifier) @name) @definition.constant
+(static_item name: (identifier) @name) @definition.constant
+(macro_definition name: (identifier) @name) @definition.function
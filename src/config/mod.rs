@@ -48,8 +48,34 @@
 //! - OPENAI_API_KEY
 //! - ANTHROPIC_API_KEY
 //! - GROQ_API_KEY
+//!
+//! ## Hot Reload
+//!
+//! [`ConfigWatcher`] watches the file returned by [`AppConfig::active_path`]
+//! and reports each saved change; see [`reload`] for what applies live.
+//!
+//! ## Project Configuration
+//!
+//! A `.neuro/` directory committed to the repository layers shared and local
+//! overrides on top of the personal config; see [`project`].
 
+pub mod project;
+pub mod reload;
+
+pub use project::{DoctorFinding, ProjectConfig, Severity};
+pub use reload::{ConfigChanges, ConfigReload, ConfigWatcher};
+
+use crate::agent::artifacts::ArtifactsConfig;
+use crate::agent::grounding::GroundingConfig;
+use crate::agent::reasoning::ReasoningConfig;
+use crate::agent::webhooks::WebhookConfig;
+use crate::raptor::federation::FederatedRoot;
+use crate::raptor::intent::IntentConfig;
+use crate::security::{NetworkPolicy, PrivacyLevel};
+use crate::tools::{HttpAuthProfile, PluginsConfig};
+use crate::ui::AccessibilityConfig;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use thiserror::Error;
 
@@ -232,6 +258,16 @@ pub struct AppConfig {
     /// Timeout for heavy tasks in seconds
     #[serde(default = "default_heavy_timeout")]
     pub heavy_timeout_secs: u64,
+
+    /// Time budget of one chat turn in seconds; near the end no new tool calls
+    /// start and the partial result is returned, resumable with /continue
+    #[serde(default = "default_turn_budget")]
+    pub turn_budget_secs: u64,
+
+    /// Seconds a streamed answer may go without data before it is resumed once
+    /// and then ended with what arrived; 0 turns the watchdog off
+    #[serde(default = "default_stream_stall")]
+    pub stream_stall_secs: u64,
     
     /// Maximum concurrent heavy tasks
     #[serde(default = "default_max_concurrent")]
@@ -250,6 +286,99 @@ pub struct AppConfig {
     #[serde(default)]
     pub debug: bool,
 
+    /// Chat messages kept in memory by the TUI; older ones are archived to the session database
+    #[serde(default = "default_max_history_messages")]
+    pub max_history_messages: usize,
+
+    /// Per-model capability overrides, keyed by model name (e.g. "qwen3:8b") or family ("qwen3")
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub model_capabilities: HashMap<String, CapabilityOverride>,
+
+    /// Named auth profiles for the `http_request` tool, each bound to its `hosts`;
+    /// secrets may name an env var
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub http_profiles: HashMap<String, HttpAuthProfile>,
+
+    /// Named connection strings for `query_database` (sqlite:, postgres://, mysql://)
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub databases: HashMap<String, String>,
+
+    /// Enable the `kubernetes` tool (uses the current kubeconfig context)
+    #[serde(default)]
+    pub kubernetes: bool,
+
+    /// Fetch the docs of the top dependencies (docs.rs, npm, PyPI) at startup
+    #[serde(default)]
+    pub dependency_docs: bool,
+
+    /// Compile and run small self-contained Rust/Python examples before showing an answer.
+    /// The examples are model-written code running on this machine: they run under
+    /// bubblewrap with a cleared environment, no network, a read-only filesystem and
+    /// only their temp project writable; without `bwrap` they are only compiled.
+    #[serde(default)]
+    pub verify_examples: bool,
+
+    /// Second-model review of the changes made in Build mode
+    #[serde(default)]
+    pub review: ReviewConfig,
+
+    /// Check that files cited by answers built on retrieved context contain what they claim
+    #[serde(default)]
+    pub grounding: GroundingConfig,
+
+    /// How long large outputs kept out of the chat (/artifacts) are kept
+    #[serde(default)]
+    pub artifacts: ArtifactsConfig,
+
+    /// Thinking of reasoning models (qwen3, deepseek-r1): on or off, shown expanded or collapsed
+    #[serde(default)]
+    pub reasoning: ReasoningConfig,
+
+    /// Rules mapping words of a query to the kinds of chunks retrieval prefers (`[intents]`, see /why)
+    #[serde(default)]
+    pub intents: IntentConfig,
+
+    /// Mutating tools describe what they would do instead of doing it (/dryrun)
+    #[serde(default)]
+    pub dry_run: bool,
+
+    /// What may leave the machine: "local-only", "redacted" or "full"
+    #[serde(default)]
+    pub privacy: PrivacyLevel,
+
+    /// Hosts outbound requests may reach; in strict mode every other host is blocked
+    #[serde(default)]
+    pub network: NetworkPolicy,
+
+    /// Gitignore-style patterns, relative to the working directory, the agent may not write to
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub protected_paths: Vec<String>,
+
+    /// Directories /cd may move into besides the startup one, absolute or relative to it
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub roots: Vec<String>,
+
+    /// Secondary roots whose files the agent may read but never write
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub read_only_roots: Vec<String>,
+
+    /// Sibling directories with their own RAPTOR index, queried together with
+    /// the working directory; each result is labeled with its root
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub federation: Vec<FederatedRoot>,
+
+    /// Speech hook, focus indicators and plain output for assistive technology
+    #[serde(default)]
+    pub accessibility: AccessibilityConfig,
+
+    /// Slack, Discord or generic HTTP endpoints notified when long tasks finish
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub webhooks: Vec<WebhookConfig>,
+
+    /// WASM plugin directories and the capabilities granted to each plugin
+    #[serde(default)]
+    pub plugins: PluginsConfig,
+
     /// Experimental features
     #[serde(default)]
     pub experimental: ExperimentalConfig,
@@ -259,6 +388,34 @@ pub struct AppConfig {
     pub min_ollama_version: Option<String>,
 }
 
+/// Overrides for the built-in model capability table; unset fields keep the built-in value
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CapabilityOverride {
+    /// Native tool / function calling
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tools: Option<bool>,
+
+    /// Constrained JSON output
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub json_mode: Option<bool>,
+
+    /// Context window in tokens
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub context_window: Option<usize>,
+}
+
+/// Review of Build changes by a second model before they are shown
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReviewConfig {
+    /// Review every Build turn that changed files
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Reviewer model (default: the fast model)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+}
+
 /// Experimental features configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ExperimentalConfig {
@@ -273,6 +430,10 @@ pub struct ExperimentalConfig {
     /// Enable proactive validation of tool calls
     #[serde(default)]
     pub proactive_validation: bool,
+
+    /// `/try`: run alternative approaches side by side in git worktrees
+    #[serde(default)]
+    pub parallel_worktrees: bool,
 }
 
 impl Default for ExperimentalConfig {
@@ -281,6 +442,7 @@ impl Default for ExperimentalConfig {
             native_function_calling: true,
             fallback_to_xml: true,
             proactive_validation: true,
+            parallel_worktrees: false,
         }
     }
 }
@@ -293,10 +455,22 @@ fn default_heavy_timeout() -> u64 {
     1200
 }
 
+fn default_turn_budget() -> u64 {
+    120
+}
+
+fn default_stream_stall() -> u64 {
+    crate::agent::stream_watchdog::DEFAULT_STALL_SECS
+}
+
 fn default_max_concurrent() -> usize {
     2
 }
 
+fn default_max_history_messages() -> usize {
+    500
+}
+
 impl Default for AppConfig {
     fn default() -> Self {
         Self {
@@ -313,10 +487,34 @@ impl Default for AppConfig {
                 ..Default::default()
             },
             heavy_timeout_secs: default_heavy_timeout(),
+            turn_budget_secs: default_turn_budget(),
+            stream_stall_secs: default_stream_stall(),
             max_concurrent_heavy: default_max_concurrent(),
             use_router_orchestrator: default_use_router(),
             language: None, // Will use system locale by default
             debug: false,
+            max_history_messages: default_max_history_messages(),
+            model_capabilities: HashMap::new(),
+            http_profiles: HashMap::new(),
+            databases: HashMap::new(),
+            kubernetes: false,
+            dependency_docs: false,
+            verify_examples: false,
+            review: ReviewConfig::default(),
+            grounding: GroundingConfig::default(),
+            artifacts: ArtifactsConfig::default(),
+            reasoning: ReasoningConfig::default(),
+            intents: IntentConfig::default(),
+            dry_run: false,
+            privacy: PrivacyLevel::Full,
+            network: NetworkPolicy::default(),
+            protected_paths: Vec::new(),
+            roots: Vec::new(),
+            read_only_roots: Vec::new(),
+            federation: Vec::new(),
+            accessibility: AccessibilityConfig::default(),
+            webhooks: Vec::new(),
+            plugins: PluginsConfig::default(),
             experimental: ExperimentalConfig::default(),
             min_ollama_version: Some("0.3.0".to_string()),
         }
@@ -343,30 +541,17 @@ impl AppConfig {
     /// 2. ~/.config/neuro/config.{NEURO_ENV}.json
     /// 3. Defaults
     pub fn load(explicit_path: Option<&Path>) -> Result<Self, ConfigError> {
-        // Try explicit path first
         if let Some(path) = explicit_path {
-            if path.exists() {
-                tracing::info!("Loading config from: {:?}", path);
-                return Self::from_file(path);
-            } else {
+            if !path.exists() {
                 return Err(ConfigError::ValidationError(
                     format!("Config file not found: {:?}", path)
                 ));
             }
         }
-        
-        // Try standard location with environment
-        let env = std::env::var("NEURO_ENV").unwrap_or_else(|_| "production".to_string());
-        
-        if let Some(config_dir) = dirs::config_dir() {
-            let config_path = config_dir
-                .join("neuro")
-                .join(format!("config.{}.json", env));
-            
-            if config_path.exists() {
-                tracing::info!("Loading config from: {:?}", config_path);
-                return Self::from_file(&config_path);
-            }
+
+        if let Some(path) = Self::active_path(explicit_path) {
+            tracing::info!("Loading config from: {:?}", path);
+            return Self::from_file(&path);
         }
         
         // Fallback to defaults with env overrides
@@ -376,6 +561,19 @@ impl AppConfig {
         config.validate()?;
         Ok(config)
     }
+
+    /// File `load` reads for this priority, or `None` when it falls back to defaults
+    pub fn active_path(explicit_path: Option<&Path>) -> Option<PathBuf> {
+        if let Some(path) = explicit_path {
+            return path.exists().then(|| path.to_path_buf());
+        }
+
+        // Standard location with environment
+        let env = std::env::var("NEURO_ENV").unwrap_or_else(|_| "production".to_string());
+        Self::config_dir()
+            .map(|dir| dir.join(format!("config.{}.json", env)))
+            .filter(|path| path.exists())
+    }
     
     /// Apply environment variable overrides
     fn apply_env_overrides(&mut self) {
@@ -419,13 +617,60 @@ impl AppConfig {
                 "heavy_timeout_secs must be greater than 0".to_string()
             ));
         }
+
+        if self.turn_budget_secs == 0 {
+            return Err(ConfigError::ValidationError(
+                "turn_budget_secs must be greater than 0".to_string()
+            ));
+        }
         
         if self.max_concurrent_heavy == 0 {
             return Err(ConfigError::ValidationError(
                 "max_concurrent_heavy must be greater than 0".to_string()
             ));
         }
-        
+
+        if self.max_history_messages == 0 {
+            return Err(ConfigError::ValidationError(
+                "max_history_messages must be greater than 0".to_string()
+            ));
+        }
+
+        if self.privacy == PrivacyLevel::LocalOnly {
+            for (name, model) in [("fast_model", &self.fast_model), ("heavy_model", &self.heavy_model)] {
+                if model.provider != ModelProvider::Ollama || !crate::security::privacy::is_local_url(&model.url) {
+                    return Err(ConfigError::ValidationError(format!(
+                        "privacy is local-only but {} uses {} at {}",
+                        name, model.provider, model.url
+                    )));
+                }
+            }
+        }
+
+        if self.network.strict {
+            for (name, model) in [("fast_model", &self.fast_model), ("heavy_model", &self.heavy_model)] {
+                let host = reqwest::Url::parse(&model.url)
+                    .ok()
+                    .and_then(|u| u.host_str().map(String::from))
+                    .unwrap_or_default();
+                if !self.network.allows(&host) {
+                    return Err(ConfigError::ValidationError(format!(
+                        "network.strict is on but {} host {} is not in network.allowed_hosts",
+                        name, host
+                    )));
+                }
+            }
+        }
+
+        for webhook in &self.webhooks {
+            if reqwest::Url::parse(&webhook.url).is_err() {
+                return Err(ConfigError::ValidationError(format!(
+                    "invalid webhook url: {}",
+                    webhook.url
+                )));
+            }
+        }
+
         Ok(())
     }
     
@@ -476,6 +721,32 @@ mod tests {
         assert!("invalid".parse::<ModelProvider>().is_err());
     }
 
+    #[test]
+    fn test_local_only_refuses_cloud_providers() {
+        let mut config = AppConfig::default();
+        config.privacy = PrivacyLevel::LocalOnly;
+        assert!(config.validate().is_ok());
+
+        config.heavy_model.provider = ModelProvider::OpenAI;
+        config.heavy_model.url = "https://api.openai.com/v1".to_string();
+        config.heavy_model.api_key = Some("OPENAI_API_KEY".to_string());
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_strict_network_requires_listed_model_hosts() {
+        let mut config = AppConfig::default();
+        config.network.strict = true;
+        assert!(config.validate().is_ok());
+
+        config.heavy_model.provider = ModelProvider::OpenAI;
+        config.heavy_model.url = "https://api.openai.com/v1".to_string();
+        assert!(config.validate().is_err());
+
+        config.network.allowed_hosts.push("api.openai.com".to_string());
+        assert!(config.validate().is_ok());
+    }
+
     #[test]
     fn test_serialize_config() {
         let config = AppConfig::default();
@@ -0,0 +1,298 @@
+//! Hot reload of the active config file
+//!
+//! [`ConfigWatcher`] watches the file the configuration was loaded from and,
+//! on every save that parses and validates, queues a [`ConfigReload`] with the
+//! new config and what changed since the previous version. Changes are split
+//! by what it takes to apply them:
+//! - `live`: temperatures, timeouts, language and tool toggles, applied to the
+//!   running session right away
+//! - `reconnect`: provider, model, URL or API key, applied after the user
+//!   accepts reconnecting
+//! - `restart`: settings only read at startup
+
+use super::{AppConfig, ConfigError, ModelConfig, ProjectConfig};
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tokio::sync::mpsc;
+
+/// What changed between two versions of the config, as `field: old → new` lines
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ConfigChanges {
+    /// Applied without interrupting the session
+    pub live: Vec<String>,
+    /// Need a reconnection to the model provider
+    pub reconnect: Vec<String>,
+    /// Only take effect at the next start
+    pub restart: Vec<String>,
+}
+
+impl ConfigChanges {
+    /// Compare two configs field by field
+    pub fn between(old: &AppConfig, new: &AppConfig) -> Self {
+        let mut changes = Self::default();
+        changes.model("fast_model", &old.fast_model, &new.fast_model);
+        changes.model("heavy_model", &old.heavy_model, &new.heavy_model);
+
+        let live = &mut changes.live;
+        value(live, "heavy_timeout_secs", &old.heavy_timeout_secs, &new.heavy_timeout_secs);
+        value(live, "turn_budget_secs", &old.turn_budget_secs, &new.turn_budget_secs);
+        value(live, "stream_stall_secs", &old.stream_stall_secs, &new.stream_stall_secs);
+        value(live, "language", &old.language, &new.language);
+        value(live, "debug", &old.debug, &new.debug);
+        value(live, "kubernetes", &old.kubernetes, &new.kubernetes);
+        value(live, "verify_examples", &old.verify_examples, &new.verify_examples);
+        value(live, "review.enabled", &old.review.enabled, &new.review.enabled);
+        value(live, "review.model", &old.review.model, &new.review.model);
+        value(live, "grounding", &old.grounding, &new.grounding);
+        value(live, "reasoning", &old.reasoning, &new.reasoning);
+        value(live, "intents", &old.intents, &new.intents);
+        value(live, "dry_run", &old.dry_run, &new.dry_run);
+        opaque(live, "model_capabilities", &old.model_capabilities, &new.model_capabilities);
+        opaque(live, "http_profiles", &old.http_profiles, &new.http_profiles);
+        opaque(live, "databases", &old.databases, &new.databases);
+        value(live, "protected_paths", &old.protected_paths, &new.protected_paths);
+        value(live, "privacy", &old.privacy, &new.privacy);
+        value(live, "network", &old.network, &new.network);
+        value(live, "accessibility", &old.accessibility, &new.accessibility);
+        // Slack and Discord URLs are the secret
+        opaque(live, "webhooks", &old.webhooks, &new.webhooks);
+
+        let restart = &mut changes.restart;
+        value(restart, "max_concurrent_heavy", &old.max_concurrent_heavy, &new.max_concurrent_heavy);
+        value(restart, "use_router_orchestrator", &old.use_router_orchestrator, &new.use_router_orchestrator);
+        value(restart, "max_history_messages", &old.max_history_messages, &new.max_history_messages);
+        value(restart, "roots", &old.roots, &new.roots);
+        value(restart, "read_only_roots", &old.read_only_roots, &new.read_only_roots);
+        value(restart, "federation", &old.federation, &new.federation);
+        value(restart, "plugins", &old.plugins, &new.plugins);
+        value(restart, "artifacts", &old.artifacts, &new.artifacts);
+        value(restart, "dependency_docs", &old.dependency_docs, &new.dependency_docs);
+        value(restart, "min_ollama_version", &old.min_ollama_version, &new.min_ollama_version);
+        opaque(restart, "experimental", &old.experimental, &new.experimental);
+        changes
+    }
+
+    fn model(&mut self, name: &str, old: &ModelConfig, new: &ModelConfig) {
+        let field = |f: &str| format!("{}.{}", name, f);
+        value(&mut self.reconnect, &field("provider"), &old.provider, &new.provider);
+        value(&mut self.reconnect, &field("url"), &old.url, &new.url);
+        value(&mut self.reconnect, &field("model"), &old.model, &new.model);
+        // Never show the key itself
+        opaque(&mut self.reconnect, &field("api_key"), &old.api_key, &new.api_key);
+        value(&mut self.live, &field("temperature"), &old.temperature, &new.temperature);
+        value(&mut self.live, &field("top_p"), &old.top_p, &new.top_p);
+        value(&mut self.live, &field("max_tokens"), &old.max_tokens, &new.max_tokens);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.live.is_empty() && self.reconnect.is_empty() && self.restart.is_empty()
+    }
+
+    /// One-line description for the status bar
+    pub fn summary(&self) -> String {
+        let mut parts = Vec::new();
+        if !self.live.is_empty() {
+            parts.push(format!("applied {}", self.live.join(", ")));
+        }
+        if !self.reconnect.is_empty() {
+            parts.push(format!("reconnect needed for {}", self.reconnect.join(", ")));
+        }
+        if !self.restart.is_empty() {
+            parts.push(format!("next restart: {}", self.restart.join(", ")));
+        }
+        format!("Config reloaded: {}", parts.join("; "))
+    }
+}
+
+/// Record `name: old → new` when the values differ
+fn value<T: PartialEq + std::fmt::Debug>(out: &mut Vec<String>, name: &str, old: &T, new: &T) {
+    if old != new {
+        out.push(format!("{}: {:?} → {:?}", name, old, new));
+    }
+}
+
+/// Record only the field name, for values that are large or secret
+fn opaque<T: Serialize>(out: &mut Vec<String>, name: &str, old: &T, new: &T) {
+    if serde_json::to_value(old).ok() != serde_json::to_value(new).ok() {
+        out.push(name.to_string());
+    }
+}
+
+/// A new version of the config file
+#[derive(Debug, Clone)]
+pub struct ConfigReload {
+    pub config: AppConfig,
+    pub changes: ConfigChanges,
+}
+
+impl ConfigReload {
+    /// Fold a later reload into one still waiting for confirmation: the later
+    /// config wins and a reconnect field changed twice keeps its latest change
+    pub fn merge(&mut self, later: ConfigReload) {
+        self.config = later.config;
+        for change in later.changes.reconnect {
+            let field = change.split(':').next().unwrap_or_default().to_string();
+            self.changes.reconnect.retain(|c| c.split(':').next() != Some(field.as_str()));
+            self.changes.reconnect.push(change);
+        }
+    }
+}
+
+/// Watches the active config file and queues a [`ConfigReload`] per effective change
+pub struct ConfigWatcher {
+    path: PathBuf,
+    rx: mpsc::UnboundedReceiver<ConfigReload>,
+    _watcher: RecommendedWatcher,
+}
+
+impl ConfigWatcher {
+    /// Watch `path`, comparing each save against `current` (the config as read
+    /// from the file, before command-line overrides)
+    pub fn spawn(path: &Path, current: AppConfig) -> Result<Self, ConfigError> {
+        Self::spawn_with_project(path, current, ProjectConfig::default())
+    }
+
+    /// Like [`spawn`](Self::spawn), with the `.neuro/` overrides of `project`
+    /// applied to every saved version
+    pub fn spawn_with_project(path: &Path, current: AppConfig, project: ProjectConfig) -> Result<Self, ConfigError> {
+        let path = std::fs::canonicalize(path)?;
+        // Editors save by renaming a temp file over the original, so watch the directory
+        let dir = path
+            .parent()
+            .ok_or_else(|| ConfigError::ValidationError(format!("No parent directory: {:?}", path)))?
+            .to_path_buf();
+        let (tx, rx) = mpsc::unbounded_channel();
+        let last = Arc::new(Mutex::new(current));
+
+        let target = path.clone();
+        let mut watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+            let Ok(event) = event else { return };
+            if event.kind.is_access() || !event.paths.iter().any(|p| p.file_name() == target.file_name()) {
+                return;
+            }
+            let config = match AppConfig::from_file(&target).and_then(|config| project.apply(&config)) {
+                Ok(config) => config,
+                Err(e) => {
+                    // Usually a save in progress; the next event will pick up the full file
+                    tracing::warn!("Ignoring config change in {:?}: {}", target, e);
+                    return;
+                }
+            };
+            let mut last = last.lock().unwrap_or_else(|e| e.into_inner());
+            let changes = ConfigChanges::between(&last, &config);
+            if changes.is_empty() {
+                return;
+            }
+            *last = config.clone();
+            let _ = tx.send(ConfigReload { config, changes });
+        })
+        .map_err(|e| ConfigError::IoError(std::io::Error::other(e)))?;
+        watcher
+            .watch(&dir, RecursiveMode::NonRecursive)
+            .map_err(|e| ConfigError::IoError(std::io::Error::other(e)))?;
+
+        tracing::info!("Watching config file: {:?}", path);
+        Ok(Self { path, rx, _watcher: watcher })
+    }
+
+    /// Watched file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Next queued reload, if any
+    pub fn try_recv(&mut self) -> Option<ConfigReload> {
+        self.rx.try_recv().ok()
+    }
+
+    /// Wait for the next reload
+    pub async fn recv(&mut self) -> Option<ConfigReload> {
+        self.rx.recv().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_changes_are_classified() {
+        let old = AppConfig::default();
+        let mut new = old.clone();
+        new.heavy_model.temperature = 0.5;
+        new.heavy_timeout_secs = 600;
+        new.language = Some("en".to_string());
+        new.fast_model.model = "llama3.2:1b".to_string();
+        new.heavy_model.api_key = Some("sk-secret".to_string());
+        new.max_concurrent_heavy = 4;
+        new.webhooks = vec![crate::agent::WebhookConfig {
+            url: "https://hooks.slack.com/services/T0/B0/secret-path".to_string(),
+            kind: crate::agent::WebhookKind::Slack,
+            events: Vec::new(),
+            template: None,
+            min_duration_secs: 0,
+        }];
+
+        let changes = ConfigChanges::between(&old, &new);
+        assert_eq!(
+            changes.live,
+            [
+                "heavy_model.temperature: 0.3 → 0.5",
+                "heavy_timeout_secs: 1200 → 600",
+                "language: None → Some(\"en\")",
+                "webhooks",
+            ]
+        );
+        assert_eq!(
+            changes.reconnect,
+            ["fast_model.model: \"qwen3:0.6b\" → \"llama3.2:1b\"", "heavy_model.api_key"]
+        );
+        assert_eq!(changes.restart, ["max_concurrent_heavy: 2 → 4"]);
+        assert!(!changes.summary().contains("sk-secret"));
+        assert!(!changes.summary().contains("secret-path"));
+        assert!(ConfigChanges::between(&old, &old).is_empty());
+    }
+
+    #[test]
+    fn test_queued_reloads_merge() {
+        let old = AppConfig::default();
+        let mut first = old.clone();
+        first.fast_model.model = "llama3.2:1b".to_string();
+        let mut second = first.clone();
+        second.fast_model.model = "phi3".to_string();
+        second.heavy_model.api_key = Some("sk-secret".to_string());
+
+        let mut pending = ConfigReload { changes: ConfigChanges::between(&old, &first), config: first };
+        pending.merge(ConfigReload { changes: ConfigChanges::between(&old, &second), config: second });
+        assert_eq!(pending.config.fast_model.model, "phi3");
+        assert_eq!(
+            pending.changes.reconnect,
+            ["fast_model.model: \"qwen3:0.6b\" → \"phi3\"", "heavy_model.api_key"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_watcher_reports_saved_changes() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.test.json");
+        let config = AppConfig::default();
+        config.save_to_file(&path).unwrap();
+        let mut watcher = ConfigWatcher::spawn(&path, AppConfig::from_file(&path).unwrap()).unwrap();
+
+        // A broken save is skipped, the fixed one is reported
+        std::fs::write(&path, "{ not json").unwrap();
+        let mut updated = config.clone();
+        updated.fast_model.temperature = 0.9;
+        updated.save_to_file(&path).unwrap();
+
+        let reload = tokio::time::timeout(Duration::from_secs(5), watcher.recv())
+            .await
+            .expect("reload event")
+            .unwrap();
+        assert_eq!(reload.config.fast_model.temperature, 0.9);
+        assert_eq!(reload.changes.live, ["fast_model.temperature: 0.2 → 0.9"]);
+    }
+}
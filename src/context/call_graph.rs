@@ -0,0 +1,214 @@
+//! Call graph between the functions of a project
+//!
+//! Call sites come from [`AstParser::extract_call_graph`] and are linked to
+//! definitions by name: without type information a call to `load` matches
+//! every function or method named `load`. That over-approximates methods
+//! shared by several types, which is what "who calls X?" wants to see anyway.
+//! Qualified names (`Config::load`, `config.load`, `self.build`) are compared
+//! by their last segment.
+
+use crate::ast::{AstParser, AstSymbol, CallEdge, SymbolKind};
+use std::collections::{BTreeMap, BTreeSet, HashSet, VecDeque};
+use std::path::Path;
+
+/// One call of a project file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CallSite {
+    /// Calling file, relative to the project root
+    pub file: String,
+    /// Function or method making the call; `None` for top-level code
+    pub caller: Option<String>,
+    pub callee: String,
+    pub line: usize,
+}
+
+/// A function or method of the project
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FunctionDefinition {
+    pub file: String,
+    pub name: String,
+    pub line: usize,
+}
+
+/// Function-level call graph of a project
+#[derive(Debug, Clone, Default)]
+pub struct CallGraph {
+    sites: Vec<CallSite>,
+    definitions: Vec<FunctionDefinition>,
+}
+
+impl CallGraph {
+    /// Add the calls and function definitions of one file
+    pub fn add_file(&mut self, file: &str, edges: Vec<CallEdge>, symbols: &[AstSymbol]) {
+        self.definitions.extend(
+            symbols
+                .iter()
+                .filter(|s| matches!(s.kind, SymbolKind::Function | SymbolKind::Method))
+                .map(|s| FunctionDefinition {
+                    file: file.to_string(),
+                    name: s.name.clone(),
+                    line: s.range.start_line,
+                }),
+        );
+        self.sites.extend(edges.into_iter().map(|edge| CallSite {
+            file: file.to_string(),
+            caller: edge.caller,
+            callee: edge.callee,
+            line: edge.line,
+        }));
+    }
+
+    /// Parse the source files of a project and build its graph
+    pub fn build(root: &Path, parser: &mut AstParser) -> Self {
+        let mut graph = Self::default();
+        for (path, language) in crate::tools::source_files(root) {
+            let relative = path
+                .strip_prefix(root)
+                .unwrap_or(&path)
+                .to_string_lossy()
+                .to_string();
+            let Ok(source) = std::fs::read_to_string(&path) else {
+                continue;
+            };
+            let Ok(parse) = parser.parse_file(&path, language, &source) else {
+                continue;
+            };
+            let symbols = parser.extract_symbols(&parse.tree, language, &source);
+            let edges = parser.extract_call_graph(&parse.tree, language, &source);
+            graph.add_file(&relative, edges, &symbols);
+        }
+        graph
+    }
+
+    pub fn sites(&self) -> &[CallSite] {
+        &self.sites
+    }
+
+    /// Functions and methods named `name`
+    pub fn definitions_of(&self, name: &str) -> Vec<&FunctionDefinition> {
+        let name = short_name(name);
+        self.definitions
+            .iter()
+            .filter(|d| short_name(&d.name) == name)
+            .collect()
+    }
+
+    /// Calls of `name`, by file and line
+    pub fn callers_of(&self, name: &str) -> Vec<&CallSite> {
+        let name = short_name(name);
+        let mut sites: Vec<&CallSite> = self.sites.iter().filter(|s| s.callee == name).collect();
+        sites.sort_by(|a, b| a.file.cmp(&b.file).then(a.line.cmp(&b.line)));
+        sites
+    }
+
+    /// Calls made by `name`, by file and line
+    pub fn callees_of(&self, name: &str) -> Vec<&CallSite> {
+        let name = short_name(name);
+        let mut sites: Vec<&CallSite> = self
+            .sites
+            .iter()
+            .filter(|s| s.caller.as_deref().is_some_and(|c| short_name(c) == name))
+            .collect();
+        sites.sort_by(|a, b| a.file.cmp(&b.file).then(a.line.cmp(&b.line)));
+        sites
+    }
+
+    /// Functions reaching `name` through up to `depth` calls, with their distance,
+    /// nearest first
+    pub fn transitive_callers(&self, name: &str, depth: usize) -> Vec<(usize, &str)> {
+        let mut seen: HashSet<&str> = HashSet::from([short_name(name)]);
+        let mut queue = VecDeque::from([(0, short_name(name))]);
+        let mut found = Vec::new();
+
+        while let Some((distance, current)) = queue.pop_front() {
+            if distance == depth {
+                continue;
+            }
+            let callers: BTreeSet<&str> = self
+                .sites
+                .iter()
+                .filter(|s| s.callee == current)
+                .filter_map(|s| s.caller.as_deref())
+                .collect();
+            for caller in callers {
+                if seen.insert(short_name(caller)) {
+                    found.push((distance + 1, caller));
+                    queue.push_back((distance + 1, short_name(caller)));
+                }
+            }
+        }
+        found
+    }
+
+    /// Project functions with the most call sites
+    pub fn most_called(&self, limit: usize) -> Vec<(&str, usize)> {
+        let defined: HashSet<&str> = self.definitions.iter().map(|d| short_name(&d.name)).collect();
+        let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+        for site in self.sites.iter().filter(|s| defined.contains(s.callee.as_str())) {
+            *counts.entry(site.callee.as_str()).or_default() += 1;
+        }
+        let mut ranked: Vec<(&str, usize)> = counts.into_iter().collect();
+        ranked.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+        ranked.truncate(limit);
+        ranked
+    }
+
+    pub fn function_count(&self) -> usize {
+        self.definitions.len()
+    }
+}
+
+/// `Config::load`, `config.load`, `Invoice#total`, `$obj->save` → the last segment
+pub fn short_name(name: &str) -> &str {
+    name.rsplit([':', '.', '#', '>'])
+        .next()
+        .unwrap_or(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ast::SupportedLanguage;
+
+    fn graph(files: &[(&str, &str)]) -> CallGraph {
+        let mut parser = AstParser::new().unwrap();
+        let mut graph = CallGraph::default();
+        for (file, code) in files {
+            let tree = parser.parse(SupportedLanguage::Rust, code).unwrap();
+            let symbols = parser.extract_symbols(&tree, SupportedLanguage::Rust, code);
+            let edges = parser.extract_call_graph(&tree, SupportedLanguage::Rust, code);
+            graph.add_file(file, edges, &symbols);
+        }
+        graph
+    }
+
+    #[test]
+    fn test_who_calls() {
+        let g = graph(&[
+            (
+                "src/config.rs",
+                "pub fn load() -> Config {\n    parse(read())\n}\n\nfn parse(s: String) -> Config {}\n",
+            ),
+            (
+                "src/main.rs",
+                "fn main() {\n    start();\n}\n\nfn start() {\n    let c = config::load();\n    run(c);\n}\n",
+            ),
+        ]);
+
+        let callers: Vec<_> = g
+            .callers_of("Config::load")
+            .iter()
+            .map(|s| (s.file.as_str(), s.caller.as_deref(), s.line))
+            .collect();
+        assert_eq!(callers, vec![("src/main.rs", Some("start"), 6)]);
+        assert_eq!(g.definitions_of("load")[0].file, "src/config.rs");
+
+        let callees: Vec<_> = g.callees_of("load").iter().map(|s| s.callee.as_str()).collect();
+        assert_eq!(callees, vec!["parse", "read"]);
+
+        assert_eq!(g.transitive_callers("parse", 3), vec![(1, "load"), (2, "start"), (3, "main")]);
+        assert_eq!(g.transitive_callers("parse", 1), vec![(1, "load")]);
+        assert_eq!(g.most_called(2), vec![("load", 1), ("parse", 1)]);
+        assert_eq!(short_name("$repo->save"), "save");
+    }
+}
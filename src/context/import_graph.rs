@@ -0,0 +1,677 @@
+//! Import graph between project files
+//!
+//! Imports extracted by the AST parser are stored per file as raw targets
+//! (`crate::agent::router`, `./utils`, `app.models.User`) and resolved against
+//! the set of indexed files when the graph is loaded, so adding or removing a
+//! file re-links its importers without re-parsing them.
+//!
+//! Resolution is best effort and per language:
+//! - Rust: `crate::`, `self::` and `super::` paths, longest existing module prefix
+//! - Python: dotted and relative (`.mod`) imports, from the root or `src/`
+//! - TypeScript/JavaScript: relative specifiers with extension and `index` lookup
+//! - C/C++: quoted includes next to the file, at the root, `include/` or `src/`
+//! - Ruby: `require_relative` paths and `lib/` requires
+//! - PHP: PSR-4 style namespaces and `require`/`include` paths
+//! - Go: package directories, after the module path of the import
+//! - Java/Kotlin: class files under any source root, or the package directory
+//!
+//! Anything that does not resolve to a project file is an external import.
+
+use crate::ast::{AstParser, Import, SupportedLanguage};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::path::Path;
+
+/// Raw import targets of one import statement: one per imported item when the
+/// items may be modules themselves (`use a::{b, c}`, `from a import b`)
+pub fn import_targets(import: &Import, language: SupportedLanguage) -> Vec<String> {
+    let module = import.module.trim_end_matches("::*").to_string();
+    let separator = match language {
+        SupportedLanguage::Rust => "::",
+        SupportedLanguage::Python => ".",
+        SupportedLanguage::Php => "\\",
+        _ => return vec![module],
+    };
+    if import.items.is_empty() || import.is_wildcard {
+        return vec![module];
+    }
+
+    import
+        .items
+        .iter()
+        .map(|item| match item.as_str() {
+            "self" => module.clone(),
+            _ if module.ends_with('.') => format!("{}{}", module, item),
+            _ => format!("{}{}{}", module, separator, item),
+        })
+        .collect()
+}
+
+/// One import of a file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImportEdge {
+    /// Importing file, relative to the project root
+    pub from: String,
+    /// Raw target as written in the source
+    pub target: String,
+    /// Imported project file, `None` for external imports
+    pub to: Option<String>,
+    pub line: usize,
+}
+
+/// File-level import graph of a project
+#[derive(Debug, Clone, Default)]
+pub struct ImportGraph {
+    edges: Vec<ImportEdge>,
+    files: BTreeSet<String>,
+}
+
+impl ImportGraph {
+    /// Resolve raw imports `(file, language, target, line)` against the project files
+    pub fn resolve(
+        imports: impl IntoIterator<Item = (String, SupportedLanguage, String, usize)>,
+        files: impl IntoIterator<Item = String>,
+    ) -> Self {
+        let files: BTreeSet<String> = files.into_iter().collect();
+        let lookup: HashSet<&str> = files.iter().map(String::as_str).collect();
+
+        let edges = imports
+            .into_iter()
+            .map(|(from, language, target, line)| {
+                let to = resolve_import(&lookup, &from, language, &target).filter(|to| *to != from);
+                ImportEdge {
+                    from,
+                    target,
+                    to,
+                    line,
+                }
+            })
+            .collect();
+        Self { edges, files }
+    }
+
+    /// Parse the source files of a project and build its graph without a database
+    pub fn build(root: &Path, parser: &mut AstParser) -> Self {
+        let mut imports = Vec::new();
+        let mut files = Vec::new();
+
+        for (path, language) in crate::tools::source_files(root) {
+            let relative = path
+                .strip_prefix(root)
+                .unwrap_or(&path)
+                .to_string_lossy()
+                .to_string();
+            files.push(relative.clone());
+
+            let Ok(source) = std::fs::read_to_string(&path) else {
+                continue;
+            };
+            let Ok(parse) = parser.parse_file(&path, language, &source) else {
+                continue;
+            };
+            for import in parser.extract_imports(&parse.tree, language, &source) {
+                for target in import_targets(&import, language) {
+                    imports.push((relative.clone(), language, target, import.line));
+                }
+            }
+        }
+
+        Self::resolve(imports, files)
+    }
+
+    pub fn edges(&self) -> &[ImportEdge] {
+        &self.edges
+    }
+
+    pub fn file_count(&self) -> usize {
+        self.files.len()
+    }
+
+    pub fn contains(&self, file: &str) -> bool {
+        self.files.contains(file)
+    }
+
+    /// Project files imported by `file`
+    pub fn imports_of(&self, file: &str) -> Vec<&str> {
+        let targets: BTreeSet<&str> = self
+            .edges
+            .iter()
+            .filter(|e| e.from == file)
+            .filter_map(|e| e.to.as_deref())
+            .collect();
+        targets.into_iter().collect()
+    }
+
+    /// External modules imported by `file`
+    pub fn external_imports_of(&self, file: &str) -> Vec<&str> {
+        let targets: BTreeSet<&str> = self
+            .edges
+            .iter()
+            .filter(|e| e.from == file && e.to.is_none())
+            .map(|e| e.target.as_str())
+            .collect();
+        targets.into_iter().collect()
+    }
+
+    /// Project files importing `file`
+    pub fn importers_of(&self, file: &str) -> Vec<&str> {
+        let sources: BTreeSet<&str> = self
+            .edges
+            .iter()
+            .filter(|e| e.to.as_deref() == Some(file))
+            .map(|e| e.from.as_str())
+            .collect();
+        sources.into_iter().collect()
+    }
+
+    /// Groups of files importing each other, directly or transitively
+    pub fn cycles(&self) -> Vec<Vec<String>> {
+        let mut adjacency: BTreeMap<&str, BTreeSet<&str>> = BTreeMap::new();
+        for edge in &self.edges {
+            if let Some(to) = &edge.to {
+                adjacency.entry(&edge.from).or_default().insert(to);
+            }
+        }
+
+        let mut cycles: Vec<Vec<String>> = strongly_connected(&adjacency)
+            .into_iter()
+            .filter(|component| component.len() > 1)
+            .map(|component| {
+                let mut files: Vec<String> = component.into_iter().map(String::from).collect();
+                files.sort();
+                files
+            })
+            .collect();
+        cycles.sort();
+        cycles
+    }
+
+    /// Files with the most importers
+    pub fn most_imported(&self, limit: usize) -> Vec<(&str, usize)> {
+        let mut counts: HashMap<&str, BTreeSet<&str>> = HashMap::new();
+        for edge in &self.edges {
+            if let Some(to) = &edge.to {
+                counts.entry(to).or_default().insert(&edge.from);
+            }
+        }
+        let mut ranked: Vec<(&str, usize)> =
+            counts.into_iter().map(|(file, from)| (file, from.len())).collect();
+        ranked.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+        ranked.truncate(limit);
+        ranked
+    }
+}
+
+/// Tarjan's algorithm over the internal edges
+fn strongly_connected<'a>(adjacency: &BTreeMap<&'a str, BTreeSet<&'a str>>) -> Vec<Vec<&'a str>> {
+    struct State<'a> {
+        index: usize,
+        indices: HashMap<&'a str, usize>,
+        lowlink: HashMap<&'a str, usize>,
+        stack: Vec<&'a str>,
+        on_stack: HashSet<&'a str>,
+        components: Vec<Vec<&'a str>>,
+    }
+
+    fn visit<'a>(node: &'a str, adjacency: &BTreeMap<&'a str, BTreeSet<&'a str>>, state: &mut State<'a>) {
+        state.indices.insert(node, state.index);
+        state.lowlink.insert(node, state.index);
+        state.index += 1;
+        state.stack.push(node);
+        state.on_stack.insert(node);
+
+        for &next in adjacency.get(node).into_iter().flatten() {
+            if !state.indices.contains_key(next) {
+                visit(next, adjacency, state);
+                let low = state.lowlink[node].min(state.lowlink[next]);
+                state.lowlink.insert(node, low);
+            } else if state.on_stack.contains(next) {
+                let low = state.lowlink[node].min(state.indices[next]);
+                state.lowlink.insert(node, low);
+            }
+        }
+
+        if state.lowlink[node] == state.indices[node] {
+            let mut component = Vec::new();
+            while let Some(member) = state.stack.pop() {
+                state.on_stack.remove(member);
+                component.push(member);
+                if member == node {
+                    break;
+                }
+            }
+            state.components.push(component);
+        }
+    }
+
+    let mut state = State {
+        index: 0,
+        indices: HashMap::new(),
+        lowlink: HashMap::new(),
+        stack: Vec::new(),
+        on_stack: HashSet::new(),
+        components: Vec::new(),
+    };
+    for &node in adjacency.keys() {
+        if !state.indices.contains_key(node) {
+            visit(node, adjacency, &mut state);
+        }
+    }
+    state.components
+}
+
+/// Project file imported by `target` from `from`, `None` for external imports
+pub fn resolve_import(
+    files: &HashSet<&str>,
+    from: &str,
+    language: SupportedLanguage,
+    target: &str,
+) -> Option<String> {
+    match language {
+        SupportedLanguage::Rust => resolve_rust(files, from, target),
+        SupportedLanguage::Python => resolve_python(files, from, target),
+        SupportedLanguage::TypeScript | SupportedLanguage::JavaScript => {
+            resolve_script(files, from, target)
+        }
+        SupportedLanguage::C | SupportedLanguage::Cpp => resolve_include(files, from, target),
+        SupportedLanguage::Ruby => resolve_ruby(files, from, target),
+        SupportedLanguage::Php => resolve_php(files, from, target),
+        SupportedLanguage::Go => resolve_go(files, from, target),
+        SupportedLanguage::Java | SupportedLanguage::Kotlin => resolve_jvm(files, target),
+    }
+}
+
+/// Directory of a relative file path (`""` at the root)
+fn parent_dir(file: &str) -> &str {
+    file.rsplit_once('/').map(|(dir, _)| dir).unwrap_or("")
+}
+
+/// Join `relative` onto `dir`, resolving `.` and `..`; `None` when it leaves the root
+fn join_path(dir: &str, relative: &str) -> Option<String> {
+    let mut parts: Vec<&str> = dir.split('/').filter(|p| !p.is_empty()).collect();
+    for part in relative.split('/') {
+        match part {
+            "" | "." => {}
+            ".." => {
+                parts.pop()?;
+            }
+            part => parts.push(part),
+        }
+    }
+    Some(parts.join("/"))
+}
+
+fn first_existing(files: &HashSet<&str>, candidates: impl IntoIterator<Item = String>) -> Option<String> {
+    candidates.into_iter().find(|c| files.contains(c.as_str()))
+}
+
+/// Directory holding the submodules of a Rust file
+fn rust_module_dir(file: &str) -> String {
+    let dir = parent_dir(file);
+    match file.rsplit('/').next().unwrap_or(file) {
+        "mod.rs" | "lib.rs" | "main.rs" => dir.to_string(),
+        name => join_path(dir, name.trim_end_matches(".rs")).unwrap_or_default(),
+    }
+}
+
+/// File defining the Rust module whose submodules live in `dir`
+fn rust_module_file(files: &HashSet<&str>, dir: &str) -> Option<String> {
+    let candidates = [
+        format!("{}.rs", dir),
+        join_path(dir, "mod.rs")?,
+        join_path(dir, "lib.rs")?,
+        join_path(dir, "main.rs")?,
+    ];
+    first_existing(files, candidates)
+}
+
+fn resolve_rust(files: &HashSet<&str>, from: &str, target: &str) -> Option<String> {
+    let mut segments: Vec<&str> = target.split("::").filter(|s| !s.is_empty()).collect();
+    let first = segments.first().copied()?;
+    let base = match first {
+        "crate" => {
+            // Nearest enclosing crate root
+            let mut dir = parent_dir(from).to_string();
+            loop {
+                if rust_module_file(files, &dir)
+                    .is_some_and(|f| f.ends_with("lib.rs") || f.ends_with("main.rs"))
+                    || dir.is_empty()
+                {
+                    break;
+                }
+                dir = parent_dir(&dir).to_string();
+            }
+            segments.remove(0);
+            dir
+        }
+        "self" | "super" => {
+            let mut dir = rust_module_dir(from);
+            while let Some(&segment) = segments.first() {
+                match segment {
+                    "self" => {}
+                    "super" => dir = parent_dir(&dir).to_string(),
+                    _ => break,
+                }
+                segments.remove(0);
+            }
+            dir
+        }
+        // 2015-style paths and external crates: only local when such a module exists
+        _ => rust_module_dir(from),
+    };
+
+    // Longest module prefix that exists: `a::b::Item` lives in `a/b.rs`
+    for len in (1..=segments.len()).rev() {
+        let dir = join_path(&base, &segments[..len].join("/"))?;
+        let candidates = [format!("{}.rs", dir), join_path(&dir, "mod.rs")?];
+        if let Some(found) = first_existing(files, candidates) {
+            return Some(found);
+        }
+    }
+    // `use super::*` or an item of the base module itself
+    if matches!(first, "crate" | "self" | "super") {
+        return rust_module_file(files, &base);
+    }
+    None
+}
+
+fn resolve_python(files: &HashSet<&str>, from: &str, target: &str) -> Option<String> {
+    let dots = target.chars().take_while(|c| *c == '.').count();
+    let segments: Vec<&str> = target[dots..].split('.').filter(|s| !s.is_empty()).collect();
+
+    let bases: Vec<String> = if dots > 0 {
+        let mut dir = parent_dir(from).to_string();
+        for _ in 1..dots {
+            dir = parent_dir(&dir).to_string();
+        }
+        vec![dir]
+    } else {
+        vec![String::new(), "src".to_string()]
+    };
+
+    for base in &bases {
+        for len in (1..=segments.len()).rev() {
+            let dir = join_path(base, &segments[..len].join("/"))?;
+            let candidates = [format!("{}.py", dir), join_path(&dir, "__init__.py")?];
+            if let Some(found) = first_existing(files, candidates) {
+                return Some(found);
+            }
+        }
+        if dots > 0 && segments.is_empty() {
+            return first_existing(files, [join_path(base, "__init__.py")?]);
+        }
+    }
+    None
+}
+
+const SCRIPT_EXTENSIONS: &[&str] = &["ts", "tsx", "js", "jsx", "mjs", "cjs", "d.ts"];
+
+fn resolve_script(files: &HashSet<&str>, from: &str, target: &str) -> Option<String> {
+    // Bare specifiers are packages
+    if !target.starts_with('.') {
+        return None;
+    }
+    let path = join_path(parent_dir(from), target)?;
+
+    let mut candidates = vec![path.clone()];
+    for ext in SCRIPT_EXTENSIONS {
+        candidates.push(format!("{}.{}", path, ext));
+    }
+    for ext in SCRIPT_EXTENSIONS {
+        candidates.push(join_path(&path, &format!("index.{}", ext))?);
+    }
+    first_existing(files, candidates)
+}
+
+fn resolve_include(files: &HashSet<&str>, from: &str, target: &str) -> Option<String> {
+    let candidates = [parent_dir(from), "", "include", "src"]
+        .into_iter()
+        .filter_map(|dir| join_path(dir, target));
+    first_existing(files, candidates)
+}
+
+fn resolve_ruby(files: &HashSet<&str>, from: &str, target: &str) -> Option<String> {
+    let candidates = [parent_dir(from), "lib", ""]
+        .into_iter()
+        .filter_map(|dir| join_path(dir, target))
+        .flat_map(|path| [format!("{}.rb", path), path]);
+    first_existing(files, candidates)
+}
+
+fn resolve_php(files: &HashSet<&str>, from: &str, target: &str) -> Option<String> {
+    // require/include: the quoted path in the expression (`__DIR__ . '/config.php'`)
+    if target.contains(".php") || target.contains('/') {
+        let path = target
+            .rsplit(['\'', '"'])
+            .find(|part| part.contains(".php"))
+            .unwrap_or(target)
+            .trim_start_matches('/');
+        return first_existing(
+            files,
+            [parent_dir(from), ""]
+                .into_iter()
+                .filter_map(|dir| join_path(dir, path)),
+        );
+    }
+
+    // PSR-4: `App\Models\User` in app/Models/User.php, src/Models/User.php, ...
+    let segments: Vec<&str> = target
+        .trim_start_matches('\\')
+        .split('\\')
+        .filter(|s| !s.is_empty())
+        .collect();
+    let (first, rest) = segments.split_first()?;
+    let rest = rest.join("/");
+    let lowered = first.to_lowercase();
+    let mut candidates = vec![format!("{}.php", segments.join("/"))];
+    if !rest.is_empty() {
+        candidates.push(format!("{}/{}.php", lowered, rest));
+        for dir in ["src", "app", "lib"] {
+            candidates.push(format!("{}/{}.php", dir, rest));
+        }
+    }
+    first_existing(files, candidates)
+}
+
+/// A Go import names a package directory; its first source file stands for it
+fn resolve_go(files: &HashSet<&str>, from: &str, target: &str) -> Option<String> {
+    let package_file = |dir: &str| {
+        files
+            .iter()
+            .filter(|f| parent_dir(f) == dir && f.ends_with(".go") && !f.ends_with("_test.go"))
+            .min()
+            .map(|f| f.to_string())
+    };
+    if target.starts_with('.') {
+        return package_file(&join_path(parent_dir(from), target)?);
+    }
+
+    // `github.com/acme/shop/internal/tax`: the go.mod module path is unknown here,
+    // so the longest suffix naming a project directory wins
+    let segments: Vec<&str> = target.split('/').collect();
+    (1..segments.len()).find_map(|i| package_file(&segments[i..].join("/")))
+}
+
+/// `com.acme.shop.Invoice` lives in `com/acme/shop/Invoice.{java,kt}` under a source
+/// root (`src/main/java/`, `app/src/main/kotlin/`...) unknown here, so any file with
+/// that path suffix matches. Static members and top-level functions name their file
+/// one segment up; wildcard imports name a package, whose first file stands for it.
+fn resolve_jvm(files: &HashSet<&str>, target: &str) -> Option<String> {
+    let path = target.replace('.', "/");
+    let class_file = |path: &str| {
+        files
+            .iter()
+            .filter(|f| {
+                [".java", ".kt"].iter().any(|ext| {
+                    let file = format!("{}{}", path, ext);
+                    **f == file || f.ends_with(&format!("/{}", file))
+                })
+            })
+            .min()
+            .map(|f| f.to_string())
+    };
+    if let Some(file) = class_file(&path) {
+        return Some(file);
+    }
+    if let Some((outer, _)) = path.rsplit_once('/') {
+        if let Some(file) = class_file(outer) {
+            return Some(file);
+        }
+    }
+
+    files
+        .iter()
+        .filter(|f| {
+            let dir = parent_dir(f);
+            (dir == path || dir.ends_with(&format!("/{}", path)))
+                && (f.ends_with(".java") || f.ends_with(".kt"))
+        })
+        .min()
+        .map(|f| f.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn graph(imports: &[(&str, SupportedLanguage, &str)], files: &[&str]) -> ImportGraph {
+        ImportGraph::resolve(
+            imports
+                .iter()
+                .map(|(from, lang, target)| (from.to_string(), *lang, target.to_string(), 1)),
+            files.iter().map(|f| f.to_string()),
+        )
+    }
+
+    #[test]
+    fn test_resolve_rust() {
+        let files = [
+            "src/main.rs",
+            "src/agent/mod.rs",
+            "src/agent/router.rs",
+            "src/agent/router/fast_path.rs",
+            "src/tools/mod.rs",
+            "src/tools/search.rs",
+        ];
+        let g = graph(
+            &[
+                ("src/main.rs", SupportedLanguage::Rust, "crate::agent::router::Router"),
+                ("src/main.rs", SupportedLanguage::Rust, "std::sync::Arc"),
+                ("src/agent/router.rs", SupportedLanguage::Rust, "self::fast_path"),
+                ("src/agent/router.rs", SupportedLanguage::Rust, "super::super::tools::search"),
+                ("src/tools/search.rs", SupportedLanguage::Rust, "super"),
+            ],
+            &files,
+        );
+
+        assert_eq!(g.imports_of("src/main.rs"), vec!["src/agent/router.rs"]);
+        assert_eq!(g.external_imports_of("src/main.rs"), vec!["std::sync::Arc"]);
+        assert_eq!(
+            g.imports_of("src/agent/router.rs"),
+            vec!["src/agent/router/fast_path.rs", "src/tools/search.rs"]
+        );
+        assert_eq!(g.imports_of("src/tools/search.rs"), vec!["src/tools/mod.rs"]);
+        assert_eq!(g.importers_of("src/agent/router.rs"), vec!["src/main.rs"]);
+    }
+
+    #[test]
+    fn test_resolve_other_languages() {
+        let files = [
+            "app/models/user.py",
+            "app/models/__init__.py",
+            "app/views.py",
+            "web/src/api/index.ts",
+            "web/src/app.tsx",
+            "native/include/util.h",
+            "native/main.c",
+            "lib/store.rb",
+            "bin/run.rb",
+            "app/Models/User.php",
+            "routes.php",
+            "cmd/shop/main.go",
+            "internal/tax/tax.go",
+            "internal/tax/rates.go",
+            "internal/tax/rates_test.go",
+            "src/main/java/com/acme/shop/Invoice.java",
+            "src/main/kotlin/com/acme/tax/Rates.kt",
+        ];
+        let g = graph(
+            &[
+                ("app/views.py", SupportedLanguage::Python, "app.models.user.User"),
+                ("app/views.py", SupportedLanguage::Python, ".models"),
+                ("web/src/app.tsx", SupportedLanguage::TypeScript, "./api"),
+                ("web/src/app.tsx", SupportedLanguage::TypeScript, "react"),
+                ("native/main.c", SupportedLanguage::C, "include/util.h"),
+                ("bin/run.rb", SupportedLanguage::Ruby, "../lib/store"),
+                ("routes.php", SupportedLanguage::Php, "App\\Models\\User"),
+                ("cmd/shop/main.go", SupportedLanguage::Go, "github.com/acme/shop/internal/tax"),
+                ("cmd/shop/main.go", SupportedLanguage::Go, "fmt"),
+                ("src/main/kotlin/com/acme/tax/Rates.kt", SupportedLanguage::Kotlin, "com.acme.shop"),
+                ("src/main/java/com/acme/shop/Invoice.java", SupportedLanguage::Java, "com.acme.tax.Rates.apply"),
+                ("src/main/java/com/acme/shop/Invoice.java", SupportedLanguage::Java, "java.util.List"),
+            ],
+            &files,
+        );
+
+        assert_eq!(
+            g.imports_of("app/views.py"),
+            vec!["app/models/__init__.py", "app/models/user.py"]
+        );
+        assert_eq!(g.imports_of("web/src/app.tsx"), vec!["web/src/api/index.ts"]);
+        assert_eq!(g.external_imports_of("web/src/app.tsx"), vec!["react"]);
+        assert_eq!(g.imports_of("native/main.c"), vec!["native/include/util.h"]);
+        assert_eq!(g.imports_of("bin/run.rb"), vec!["lib/store.rb"]);
+        assert_eq!(g.imports_of("routes.php"), vec!["app/Models/User.php"]);
+        assert_eq!(g.imports_of("cmd/shop/main.go"), vec!["internal/tax/rates.go"]);
+        assert_eq!(g.external_imports_of("cmd/shop/main.go"), vec!["fmt"]);
+        assert_eq!(
+            g.imports_of("src/main/kotlin/com/acme/tax/Rates.kt"),
+            vec!["src/main/java/com/acme/shop/Invoice.java"]
+        );
+        assert_eq!(
+            g.imports_of("src/main/java/com/acme/shop/Invoice.java"),
+            vec!["src/main/kotlin/com/acme/tax/Rates.kt"]
+        );
+        assert_eq!(
+            g.external_imports_of("src/main/java/com/acme/shop/Invoice.java"),
+            vec!["java.util.List"]
+        );
+    }
+
+    #[test]
+    fn test_cycles() {
+        let g = graph(
+            &[
+                ("a.ts", SupportedLanguage::TypeScript, "./b"),
+                ("b.ts", SupportedLanguage::TypeScript, "./c"),
+                ("c.ts", SupportedLanguage::TypeScript, "./a"),
+                ("d.ts", SupportedLanguage::TypeScript, "./a"),
+            ],
+            &["a.ts", "b.ts", "c.ts", "d.ts"],
+        );
+
+        assert_eq!(g.cycles(), vec![vec!["a.ts", "b.ts", "c.ts"]]);
+        assert_eq!(g.most_imported(1), vec![("a.ts", 2)]);
+    }
+
+    #[test]
+    fn test_import_targets() {
+        let import = Import {
+            module: "crate::tools".to_string(),
+            items: vec!["self".to_string(), "search".to_string()],
+            is_wildcard: false,
+            line: 1,
+        };
+        assert_eq!(
+            import_targets(&import, SupportedLanguage::Rust),
+            vec!["crate::tools", "crate::tools::search"]
+        );
+
+        let import = Import {
+            module: "super::*".to_string(),
+            items: Vec::new(),
+            is_wildcard: true,
+            line: 1,
+        };
+        assert_eq!(import_targets(&import, SupportedLanguage::Rust), vec!["super"]);
+    }
+}
@@ -144,6 +144,6 @@ impl std::fmt::Display for LLMContext {
 }
 
 /// Estimate tokens (rough approximation: 1 token ≈ 4 characters)
-fn estimate_tokens(text: &str) -> usize {
+pub fn estimate_tokens(text: &str) -> usize {
     (text.len() / 4).max(1)
 }
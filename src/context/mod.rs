@@ -1,10 +1,14 @@
 //! Context module exports
 
 pub mod cache;
+pub mod call_graph;
 pub mod git_context;
+pub mod import_graph;
 pub mod manager;
 pub mod related_files;
 
+pub use call_graph::{CallGraph, CallSite, FunctionDefinition};
 pub use git_context::{GitChangedFile, GitChangeType, GitContext};
+pub use import_graph::{ImportEdge, ImportGraph};
 pub use manager::{ContextManager, LLMContext, Priority};
 pub use related_files::{RelatedFile, RelatedFilesDetector, RelationType};
@@ -5,7 +5,8 @@
 //!
 //! # Features
 //!
-//! - Import/dependency detection using tree-sitter AST
+//! - Import/dependency detection using tree-sitter AST (and the project
+//!   [`ImportGraph`] when one is set: importers are related too)
 //! - Test file discovery (finds tests for a given source file)
 //! - Documentation linkage
 //! - Cargo.toml dependencies
@@ -17,11 +18,14 @@
 //! - Incremental updates on file changes
 //! - Fast AST parsing with tree-sitter
 
+use super::import_graph::ImportGraph;
+use crate::raptor::incremental::FileTracker;
 use anyhow::Result;
 use lru::LruCache;
 use regex::Regex;
 use std::num::NonZeroUsize;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
 use walkdir::WalkDir;
 
@@ -30,6 +34,8 @@ use walkdir::WalkDir;
 pub enum RelationType {
     /// Direct import/use statement
     Import,
+    /// File importing this one
+    ImportedBy,
     /// Test file for this source file
     Test,
     /// Documentation file
@@ -55,6 +61,11 @@ type RelatedFilesCache = LruCache<PathBuf, Vec<RelatedFile>>;
 pub struct RelatedFilesDetector {
     cache: Arc<Mutex<RelatedFilesCache>>,
     project_root: PathBuf,
+    import_graph: Arc<Mutex<Option<ImportGraph>>>,
+    /// Source files the import graph was built from
+    import_graph_sources: Mutex<Option<FileTracker>>,
+    /// An import graph refresh is running
+    import_graph_refreshing: AtomicBool,
 }
 
 impl RelatedFilesDetector {
@@ -65,9 +76,46 @@ impl RelatedFilesDetector {
                 NonZeroUsize::new(200).unwrap()
             ))),
             project_root,
+            import_graph: Arc::new(Mutex::new(None)),
+            import_graph_sources: Mutex::new(None),
+            import_graph_refreshing: AtomicBool::new(false),
         }
     }
 
+    /// Use a project import graph for import relations (replaces the cached results)
+    pub fn set_import_graph(&self, graph: ImportGraph) {
+        *self.import_graph.lock().unwrap() = Some(graph);
+        self.clear_cache();
+    }
+
+    /// Start refreshing the import graph: the source files as they are now,
+    /// when they changed since the graph was built and no refresh is running
+    pub fn begin_import_graph_refresh(&self) -> Option<FileTracker> {
+        if self.import_graph_refreshing.swap(true, Ordering::AcqRel) {
+            return None;
+        }
+        let mut sources = FileTracker::new(self.project_root.clone());
+        let current = sources.scan().is_ok()
+            && self.import_graph_sources.lock().unwrap().as_ref().is_some_and(|last| {
+                sources.get_modified(last).is_empty() && sources.get_deleted(last).is_empty()
+            });
+        if current {
+            self.import_graph_refreshing.store(false, Ordering::Release);
+            return None;
+        }
+        Some(sources)
+    }
+
+    /// End a refresh started with [`Self::begin_import_graph_refresh`], with
+    /// the graph built from `sources` unless building it failed
+    pub fn finish_import_graph_refresh(&self, built: Option<(ImportGraph, FileTracker)>) {
+        if let Some((graph, sources)) = built {
+            self.set_import_graph(graph);
+            *self.import_graph_sources.lock().unwrap() = Some(sources);
+        }
+        self.import_graph_refreshing.store(false, Ordering::Release);
+    }
+
     /// Find all files related to the given file
     pub async fn find_related(&self, file_path: &Path) -> Result<Vec<RelatedFile>> {
         // Check cache first
@@ -108,6 +156,10 @@ impl RelatedFilesDetector {
 
     /// Find files imported/used by this file using tree-sitter
     fn find_imports(&self, file_path: &Path) -> Result<Vec<RelatedFile>> {
+        if let Some(related) = self.find_graph_imports(file_path) {
+            return Ok(related);
+        }
+
         let extension = file_path.extension().and_then(|s| s.to_str());
         
         match extension {
@@ -118,6 +170,36 @@ impl RelatedFilesDetector {
         }
     }
 
+    /// Imports and importers from the import graph, `None` when the file is not in it
+    fn find_graph_imports(&self, file_path: &Path) -> Option<Vec<RelatedFile>> {
+        let guard = self.import_graph.lock().unwrap();
+        let graph = guard.as_ref()?;
+
+        let root = self.project_root.canonicalize().ok()?;
+        let absolute = if file_path.is_absolute() {
+            file_path.to_path_buf()
+        } else {
+            self.project_root.join(file_path)
+        };
+        let relative = absolute.canonicalize().ok()?;
+        let relative = relative.strip_prefix(&root).ok()?.to_string_lossy().to_string();
+        if !graph.contains(&relative) {
+            return None;
+        }
+
+        let imports = graph.imports_of(&relative).into_iter().map(|file| RelatedFile {
+            path: self.project_root.join(file),
+            relation_type: RelationType::Import,
+            confidence: 0.9,
+        });
+        let importers = graph.importers_of(&relative).into_iter().map(|file| RelatedFile {
+            path: self.project_root.join(file),
+            relation_type: RelationType::ImportedBy,
+            confidence: 0.7,
+        });
+        Some(imports.chain(importers).collect())
+    }
+
     /// Find Rust imports using regex (fast but less accurate than AST)
     fn find_rust_imports(&self, file_path: &Path) -> Result<Vec<RelatedFile>> {
         let source = std::fs::read_to_string(file_path)?;
@@ -491,6 +573,55 @@ mod tests {
         assert_eq!(size3, 0);
     }
 
+    #[test]
+    fn test_import_graph_relations() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        for file in ["src/main.rs", "src/config.rs", "src/app.rs"] {
+            std::fs::write(root.join(file), "").unwrap();
+        }
+
+        let graph = ImportGraph::resolve(
+            [
+                ("src/main.rs", "crate::config::Config"),
+                ("src/app.rs", "crate::config"),
+            ]
+            .map(|(from, target)| {
+                (from.to_string(), crate::ast::SupportedLanguage::Rust, target.to_string(), 1)
+            }),
+            ["src/main.rs", "src/config.rs", "src/app.rs"].map(String::from),
+        );
+        let detector = RelatedFilesDetector::new(root.to_path_buf());
+        detector.set_import_graph(graph);
+
+        let related = detector.find_imports(&root.join("src/config.rs")).unwrap();
+        assert_eq!(related.len(), 2);
+        assert!(related.iter().all(|r| r.relation_type == RelationType::ImportedBy));
+
+        let related = detector.find_imports(&root.join("src/main.rs")).unwrap();
+        assert_eq!(related[0].relation_type, RelationType::Import);
+        assert!(related[0].path.ends_with("src/config.rs"));
+    }
+
+    #[test]
+    fn test_import_graph_refreshes_only_after_changes() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        std::fs::write(root.join("main.rs"), "").unwrap();
+        let detector = RelatedFilesDetector::new(root.to_path_buf());
+
+        let sources = detector.begin_import_graph_refresh().unwrap();
+        assert!(detector.begin_import_graph_refresh().is_none());
+        detector.finish_import_graph_refresh(Some((ImportGraph::default(), sources)));
+        assert!(detector.begin_import_graph_refresh().is_none());
+
+        std::fs::write(root.join("lib.rs"), "").unwrap();
+        assert!(detector.begin_import_graph_refresh().is_some());
+        detector.finish_import_graph_refresh(None);
+        assert!(detector.begin_import_graph_refresh().is_some());
+    }
+
     #[test]
     fn test_module_to_file_path() {
         let temp_dir = TempDir::new().unwrap();
@@ -0,0 +1,234 @@
+//! Crash safety for the TUI
+//!
+//! A panic or a termination signal while the TUI is up would leave the
+//! terminal in raw mode, on the alternate screen and with mouse capture on.
+//! The panic hook and signal handlers installed here put it back first, then
+//! write a crash report to `<data dir>/neuro/crashes/` with the backtrace, the
+//! last log events and a summary of the config without secrets, and print
+//! where it is.
+
+use crate::config::AppConfig;
+use crate::log_error;
+use chrono::Local;
+use std::collections::VecDeque;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, OnceLock, RwLock};
+
+/// Log events kept for the crash report
+const RECENT_EVENTS: usize = 50;
+
+/// Config keys left out of the summary because they hold credentials or URLs with tokens
+const SECRET_KEYS: &[&str] = &["api_key", "http_profiles", "databases", "webhooks"];
+
+static TUI_ACTIVE: AtomicBool = AtomicBool::new(false);
+
+fn recent_events() -> &'static Mutex<VecDeque<String>> {
+    static EVENTS: OnceLock<Mutex<VecDeque<String>>> = OnceLock::new();
+    EVENTS.get_or_init(|| Mutex::new(VecDeque::with_capacity(RECENT_EVENTS)))
+}
+
+fn config_summary_lock() -> &'static RwLock<String> {
+    static SUMMARY: OnceLock<RwLock<String>> = OnceLock::new();
+    SUMMARY.get_or_init(|| RwLock::new(String::new()))
+}
+
+/// The TUI took over the terminal (or gave it back)
+pub fn set_tui_active(active: bool) {
+    TUI_ACTIVE.store(active, Ordering::SeqCst);
+}
+
+/// Keep `event` for the next crash report
+pub fn record_event(event: String) {
+    let Ok(mut events) = recent_events().lock() else {
+        return;
+    };
+    if events.len() == RECENT_EVENTS {
+        events.pop_front();
+    }
+    events.push_back(event);
+}
+
+/// Config described in crash reports
+pub fn set_config(config: &AppConfig) {
+    *config_summary_lock().write().unwrap_or_else(|e| e.into_inner()) = config_summary(config);
+}
+
+/// Pretty JSON of `config` without credentials, with secrets left in other fields masked
+pub fn config_summary(config: &AppConfig) -> String {
+    let mut value = serde_json::to_value(config).unwrap_or_default();
+    strip_secrets(&mut value);
+    crate::security::privacy::redact(&serde_json::to_string_pretty(&value).unwrap_or_default())
+}
+
+fn strip_secrets(value: &mut serde_json::Value) {
+    match value {
+        serde_json::Value::Object(map) => {
+            for key in SECRET_KEYS {
+                if let Some(entry) = map.get_mut(*key) {
+                    *entry = serde_json::Value::String("<omitted>".to_string());
+                }
+            }
+            map.values_mut().for_each(strip_secrets);
+        }
+        serde_json::Value::Array(items) => items.iter_mut().for_each(strip_secrets),
+        _ => {}
+    }
+}
+
+/// Leave raw mode, the alternate screen and mouse capture, if the TUI had them
+pub fn restore_terminal() {
+    #[cfg(feature = "tui")]
+    if TUI_ACTIVE.swap(false, Ordering::SeqCst) {
+        use crossterm::{cursor::Show, event::DisableMouseCapture, execute, terminal};
+        let _ = terminal::disable_raw_mode();
+        let _ = execute!(std::io::stdout(), terminal::LeaveAlternateScreen, DisableMouseCapture, Show);
+    }
+}
+
+/// Install the panic hook; the previous hook still prints the panic message,
+/// now on a restored terminal
+pub fn install_panic_hook() {
+    let previous = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        let thread = std::thread::current();
+        let thread_name = thread.name().unwrap_or("unnamed").to_string();
+        // A panicking background task does not take the TUI down, so the
+        // terminal is only given back when the UI thread itself panics
+        let fatal = thread_name == "main";
+        if fatal {
+            restore_terminal();
+        }
+
+        let payload = info
+            .payload()
+            .downcast_ref::<&str>()
+            .map(|s| s.to_string())
+            .or_else(|| info.payload().downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "<non-string panic payload>".to_string());
+        let location = info
+            .location()
+            .map(|l| format!("{}:{}:{}", l.file(), l.line(), l.column()))
+            .unwrap_or_default();
+        let reason = format!("panic in thread '{}' at {}: {}", thread_name, location, payload);
+        let backtrace = std::backtrace::Backtrace::force_capture().to_string();
+
+        let report = write_report(&reason, &backtrace);
+        previous(info);
+        match report {
+            Ok(path) if fatal => eprintln!("\n💥 neuro crashed. Crash report: {}", path.display()),
+            Ok(path) => log_error!("💥 {} (crash report: {})", reason, path.display()),
+            Err(e) => eprintln!("\n💥 neuro crashed and the crash report could not be written: {}", e),
+        }
+    }));
+}
+
+/// Restore the terminal and exit on SIGTERM, SIGHUP and SIGQUIT
+#[cfg(unix)]
+pub fn install_signal_handlers() {
+    use tokio::signal::unix::{signal, SignalKind};
+
+    for (kind, name, number) in [
+        (SignalKind::terminate(), "SIGTERM", 15),
+        (SignalKind::hangup(), "SIGHUP", 1),
+        (SignalKind::quit(), "SIGQUIT", 3),
+    ] {
+        let Ok(mut stream) = signal(kind) else {
+            continue;
+        };
+        tokio::spawn(async move {
+            if stream.recv().await.is_some() {
+                restore_terminal();
+                match write_report(&format!("terminated by {}", name), "") {
+                    Ok(path) => eprintln!("neuro terminated by {}. Report: {}", name, path.display()),
+                    Err(_) => eprintln!("neuro terminated by {}", name),
+                }
+                std::process::exit(128 + number);
+            }
+        });
+    }
+}
+
+#[cfg(not(unix))]
+pub fn install_signal_handlers() {}
+
+fn crash_dir() -> PathBuf {
+    dirs::data_dir()
+        .map(|dir| dir.join("neuro"))
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("crashes")
+}
+
+fn write_report(reason: &str, backtrace: &str) -> std::io::Result<PathBuf> {
+    // The panic may have happened while a lock was held, so nothing here waits on one
+    let events: Vec<String> = recent_events()
+        .try_lock()
+        .map(|events| events.iter().cloned().collect())
+        .unwrap_or_default();
+    let config = config_summary_lock()
+        .try_read()
+        .map(|summary| summary.clone())
+        .unwrap_or_default();
+
+    let dir = crash_dir();
+    std::fs::create_dir_all(&dir)?;
+    let path = dir.join(format!("crash-{}.txt", Local::now().format("%Y%m%d-%H%M%S")));
+    std::fs::write(&path, render_report(reason, backtrace, &events, &config))?;
+    Ok(path)
+}
+
+fn render_report(reason: &str, backtrace: &str, events: &[String], config: &str) -> String {
+    let mut out = format!(
+        "neuro {} crash report\n{}\nos: {} {}\n\n{}\n",
+        env!("CARGO_PKG_VERSION"),
+        Local::now().format("%Y-%m-%d %H:%M:%S"),
+        std::env::consts::OS,
+        std::env::consts::ARCH,
+        reason
+    );
+    if !backtrace.is_empty() {
+        out.push_str(&format!("\n== Backtrace ==\n{}\n", backtrace.trim_end()));
+    }
+    out.push_str(&format!("\n== Last {} events ==\n", events.len()));
+    for event in events {
+        out.push_str(event);
+        out.push('\n');
+    }
+    if !config.is_empty() {
+        out.push_str(&format!("\n== Config ==\n{}\n", config));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_config_summary_leaves_out_secrets() {
+        let mut config = AppConfig::default();
+        config.heavy_model.api_key = Some("sk-live-1234567890abcdef".to_string());
+        config.webhooks.push(crate::agent::WebhookConfig {
+            url: "https://hooks.slack.com/services/T000/B000/XXXX".to_string(),
+            kind: crate::agent::WebhookKind::Slack,
+            events: Vec::new(),
+            template: None,
+            min_duration_secs: 0,
+        });
+
+        let summary = config_summary(&config);
+        assert!(!summary.contains("sk-live"));
+        assert!(!summary.contains("hooks.slack.com"));
+        assert!(summary.contains(&config.fast_model.model));
+    }
+
+    #[test]
+    fn test_report_sections() {
+        let events = vec!["[INFO] turn started".to_string()];
+        let report = render_report("panic in thread 'main' at src/ui/x.rs:1:1: boom", "0: frame", &events, "{}");
+        assert!(report.contains("boom"));
+        assert!(report.contains("== Backtrace ==\n0: frame"));
+        assert!(report.contains("== Last 1 events ==\n[INFO] turn started"));
+        assert!(report.contains("== Config ==\n{}"));
+    }
+}
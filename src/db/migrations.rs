@@ -28,6 +28,26 @@ CREATE TABLE IF NOT EXISTS messages (
     FOREIGN KEY (session_id) REFERENCES sessions(id) ON DELETE CASCADE
 );
 
+-- Labels of sessions (/tag), searched by the session browser (/sessions)
+CREATE TABLE IF NOT EXISTS session_tags (
+    session_id TEXT NOT NULL,
+    tag TEXT NOT NULL,
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+
+    PRIMARY KEY (session_id, tag),
+    FOREIGN KEY (session_id) REFERENCES sessions(id) ON DELETE CASCADE
+);
+
+-- Files the tools read or wrote during a session
+CREATE TABLE IF NOT EXISTS session_files (
+    session_id TEXT NOT NULL,
+    path TEXT NOT NULL,
+    written INTEGER NOT NULL DEFAULT 0,
+
+    PRIMARY KEY (session_id, path),
+    FOREIGN KEY (session_id) REFERENCES sessions(id) ON DELETE CASCADE
+);
+
 -- Command executions for auditing
 CREATE TABLE IF NOT EXISTS command_executions (
     id TEXT PRIMARY KEY,
@@ -90,11 +110,103 @@ CREATE TABLE IF NOT EXISTS app_config (
     updated_at TEXT NOT NULL DEFAULT (datetime('now'))
 );
 
+-- Session-scoped environment variables for tools (/env)
+CREATE TABLE IF NOT EXISTS session_env (
+    session_id TEXT NOT NULL,
+    key TEXT NOT NULL,
+    value TEXT NOT NULL,
+    updated_at TEXT NOT NULL DEFAULT (datetime('now')),
+
+    PRIMARY KEY (session_id, key),
+    FOREIGN KEY (session_id) REFERENCES sessions(id) ON DELETE CASCADE
+);
+
+-- Tools enabled or disabled per project (/tools, Settings)
+CREATE TABLE IF NOT EXISTS project_tools (
+    project TEXT NOT NULL,
+    tool TEXT NOT NULL,
+    enabled INTEGER NOT NULL DEFAULT 1,
+    updated_at TEXT NOT NULL DEFAULT (datetime('now')),
+
+    PRIMARY KEY (project, tool)
+);
+
+-- User preferences (/preferences): scope '' is global, otherwise a project key
+CREATE TABLE IF NOT EXISTS user_preferences (
+    scope TEXT NOT NULL,
+    key TEXT NOT NULL,
+    value TEXT NOT NULL,
+    updated_at TEXT NOT NULL DEFAULT (datetime('now')),
+
+    PRIMARY KEY (scope, key)
+);
+
+-- Facts remembered across sessions (/remember, memory tool)
+CREATE TABLE IF NOT EXISTS memories (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    project TEXT NOT NULL,
+    content TEXT NOT NULL,
+    embedding BLOB,
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+
+    UNIQUE(project, content)
+);
+
+-- Example answers curated to show the model the expected style (/examples);
+-- uses counts the turns they were given to the model
+CREATE TABLE IF NOT EXISTS style_examples (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    project TEXT NOT NULL,
+    prompt TEXT NOT NULL,
+    response TEXT NOT NULL,
+    embedding BLOB,
+    uses INTEGER NOT NULL DEFAULT 0,
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+
+    UNIQUE(project, prompt)
+);
+
+-- Chunks marked irrelevant for a query from the sources of an answer (/sources)
+CREATE TABLE IF NOT EXISTS retrieval_feedback (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    project TEXT NOT NULL,
+    query TEXT NOT NULL,
+    chunk_id TEXT NOT NULL,
+    source TEXT NOT NULL,
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+
+    UNIQUE(project, query, chunk_id)
+);
+
+-- Large outputs (tool runs, diffs, reports, docs, exports) kept out of the
+-- chat (/artifacts); expires_at NULL keeps them until removed
+CREATE TABLE IF NOT EXISTS artifacts (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    session_id TEXT NOT NULL,
+    kind TEXT NOT NULL DEFAULT 'output',
+    source TEXT NOT NULL,
+    content TEXT NOT NULL,
+    summary TEXT NOT NULL,
+    metadata TEXT NOT NULL DEFAULT '{}',
+    size_bytes INTEGER NOT NULL DEFAULT 0,
+    pinned INTEGER NOT NULL DEFAULT 0,
+    expires_at TEXT,
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+
+    FOREIGN KEY (session_id) REFERENCES sessions(id) ON DELETE CASCADE
+);
+
 -- Create indexes for better query performance
 CREATE INDEX IF NOT EXISTS idx_messages_session ON messages(session_id);
 CREATE INDEX IF NOT EXISTS idx_messages_created ON messages(created_at);
 CREATE INDEX IF NOT EXISTS idx_commands_session ON command_executions(session_id);
 CREATE INDEX IF NOT EXISTS idx_sessions_updated ON sessions(updated_at DESC);
+CREATE INDEX IF NOT EXISTS idx_session_tags_tag ON session_tags(tag);
+CREATE INDEX IF NOT EXISTS idx_memories_project ON memories(project);
+CREATE INDEX IF NOT EXISTS idx_style_examples_project ON style_examples(project);
+CREATE INDEX IF NOT EXISTS idx_retrieval_feedback_project ON retrieval_feedback(project);
+CREATE INDEX IF NOT EXISTS idx_artifacts_session ON artifacts(session_id);
+CREATE INDEX IF NOT EXISTS idx_artifacts_expires ON artifacts(expires_at);
 
 -- ========================================================================
 -- PROJECT CONTEXT CACHE TABLES
@@ -201,6 +313,99 @@ CREATE TABLE IF NOT EXISTS project_analysis (
     UNIQUE(project_id, analysis_type)
 );
 
+-- Per-file complexity metrics, refreshed with the symbol index
+CREATE TABLE IF NOT EXISTS file_metrics (
+    file_id INTEGER PRIMARY KEY,
+    project_id TEXT NOT NULL,
+    metrics_json TEXT NOT NULL,
+
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE,
+    FOREIGN KEY (file_id) REFERENCES indexed_files(id) ON DELETE CASCADE
+);
+
+-- Project complexity history, one row per index run that changed files
+CREATE TABLE IF NOT EXISTS complexity_snapshots (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    project_id TEXT NOT NULL,
+    taken_at TEXT NOT NULL,
+    files INTEGER NOT NULL,
+    functions INTEGER NOT NULL,
+    avg_complexity REAL NOT NULL,
+    max_complexity INTEGER NOT NULL,
+    complex_functions INTEGER NOT NULL,
+    todo_count INTEGER NOT NULL,
+    maintainability REAL NOT NULL,
+    report_json TEXT NOT NULL,
+
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
+
+-- Build and test errors by normalized signature, with their last resolution
+CREATE TABLE IF NOT EXISTS build_errors (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    project_root TEXT NOT NULL,
+    signature TEXT NOT NULL,
+    tool TEXT NOT NULL,
+    code TEXT,
+    message TEXT NOT NULL,
+    occurrences INTEGER NOT NULL DEFAULT 1,
+    first_seen TEXT NOT NULL,
+    last_seen TEXT NOT NULL,
+    last_session_id TEXT NOT NULL,
+    is_open INTEGER NOT NULL DEFAULT 1,
+    resolved_at TEXT,
+    resolution_session_id TEXT,
+    resolution_commit TEXT,
+    resolution_files TEXT,
+
+    UNIQUE(project_root, signature)
+);
+
+-- Outcomes of every test across /test --detect-flaky runs, with the most
+-- frequent failure
+CREATE TABLE IF NOT EXISTS test_flakiness (
+    project_root TEXT NOT NULL,
+    test TEXT NOT NULL,
+    runs INTEGER NOT NULL,
+    failures INTEGER NOT NULL,
+    signature TEXT,
+    message TEXT,
+    first_seen TEXT NOT NULL,
+    last_seen TEXT NOT NULL,
+
+    PRIMARY KEY (project_root, test)
+);
+
+-- Benchmark timings per commit, `dirty` when the working tree had changes
+CREATE TABLE IF NOT EXISTS benchmark_results (
+    project_root TEXT NOT NULL,
+    commit_sha TEXT NOT NULL,
+    dirty INTEGER NOT NULL DEFAULT 0,
+    framework TEXT NOT NULL,
+    benchmark TEXT NOT NULL,
+    mean_ns REAL NOT NULL,
+    stddev_ns REAL NOT NULL,
+    recorded_at TEXT NOT NULL,
+
+    PRIMARY KEY (project_root, commit_sha, dirty, benchmark)
+);
+
+-- Summarized directory trees, dropped when a file below them changes
+CREATE TABLE IF NOT EXISTS tree_summaries (
+    path TEXT PRIMARY KEY,
+    fingerprint TEXT NOT NULL,
+    summary TEXT NOT NULL,
+    created_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+-- Recency-weighted ownership of a repository, as of its `head` commit
+CREATE TABLE IF NOT EXISTS code_ownership (
+    repo TEXT PRIMARY KEY,
+    head TEXT NOT NULL,
+    ownership TEXT NOT NULL,
+    updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
 -- Documentation cache
 CREATE TABLE IF NOT EXISTS documentation_cache (
     id INTEGER PRIMARY KEY AUTOINCREMENT,
@@ -317,4 +522,6 @@ CREATE INDEX IF NOT EXISTS idx_code_embeddings_symbol ON code_embeddings(symbol_
 CREATE INDEX IF NOT EXISTS idx_code_embeddings_type ON code_embeddings(chunk_type);
 CREATE INDEX IF NOT EXISTS idx_llm_contexts_project_type ON llm_contexts(project_id, context_type);
 CREATE INDEX IF NOT EXISTS idx_analysis_cache_key ON analysis_cache(project_id, cache_key);
+CREATE INDEX IF NOT EXISTS idx_complexity_snapshots_project ON complexity_snapshots(project_id, taken_at);
+CREATE INDEX IF NOT EXISTS idx_build_errors_open ON build_errors(project_root, tool, is_open);
 "#;
@@ -5,7 +5,9 @@ mod models;
 mod repository;
 
 pub use models::{
-    CodeDependency, CodeRelationship, CodeSymbol, CommandExecution, DbMessage, DocumentationCache,
-    IndexedFile, Project, ProjectAnalysisRecord, SearchIndexEntry, SecurityConfig, Session,
+    Artifact, BenchmarkResult, BuildError, CodeDependency, CodeRelationship, CodeSymbol, CommandExecution, ComplexitySnapshot, DbMessage,
+    DocumentationCache, IndexedDependency, IndexedFile, IndexedSymbol, Memory, Project,
+    ProjectAnalysisRecord, RetrievalFeedback, SearchIndexEntry, SecurityConfig, Session, SessionFile,
+    SessionSummary, StyleExample, TestFlakiness,
 };
-pub use repository::{Database, DatabaseError};
+pub use repository::{Database, DatabaseError, SessionStore};
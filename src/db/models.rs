@@ -153,6 +153,12 @@ impl CommandExecution {
         self
     }
 
+    /// Tag the execution without flagging it as dangerous
+    pub fn with_risk_level(mut self, risk_level: impl Into<String>) -> Self {
+        self.risk_level = Some(risk_level.into());
+        self
+    }
+
     pub fn with_confirmation(mut self, confirmed_by: impl Into<String>) -> Self {
         self.confirmed_by = Some(confirmed_by.into());
         self
@@ -307,6 +313,14 @@ impl CodeSymbol {
     }
 }
 
+/// Code symbol with the path of its file, as returned by symbol lookups
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct IndexedSymbol {
+    #[sqlx(flatten)]
+    pub symbol: CodeSymbol,
+    pub relative_path: String,
+}
+
 /// Code dependency record
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
 pub struct CodeDependency {
@@ -319,6 +333,229 @@ pub struct CodeDependency {
     pub line_number: i64,
 }
 
+/// Import of an indexed file, as returned by import graph queries
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct IndexedDependency {
+    #[sqlx(flatten)]
+    pub dependency: CodeDependency,
+    pub relative_path: String,
+    pub language: Option<String>,
+}
+
+/// Project complexity at one point in time
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct ComplexitySnapshot {
+    pub id: i64,
+    pub project_id: String,
+    pub taken_at: String,
+    pub files: i64,
+    pub functions: i64,
+    pub avg_complexity: f64,
+    pub max_complexity: i64,
+    pub complex_functions: i64,
+    pub todo_count: i64,
+    pub maintainability: f64,
+    pub report_json: String,
+}
+
+impl ComplexitySnapshot {
+    pub fn report<T: serde::de::DeserializeOwned>(&self) -> Result<T, serde_json::Error> {
+        serde_json::from_str(&self.report_json)
+    }
+}
+
+/// A build or test error seen in a project, keyed by normalized signature
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct BuildError {
+    pub id: i64,
+    pub project_root: String,
+    pub signature: String,
+    /// `cargo`, `pytest`, `tsc`...
+    pub tool: String,
+    pub code: Option<String>,
+    pub message: String,
+    pub occurrences: i64,
+    pub first_seen: String,
+    pub last_seen: String,
+    pub last_session_id: String,
+    pub is_open: i32,
+    pub resolved_at: Option<String>,
+    pub resolution_session_id: Option<String>,
+    /// `<short hash> <subject>` of HEAD when the error went away
+    pub resolution_commit: Option<String>,
+    /// Uncommitted files (newline separated) when the error went away
+    pub resolution_files: Option<String>,
+}
+
+/// Pass/fail history of a test across flaky-test detections
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct TestFlakiness {
+    pub project_root: String,
+    pub test: String,
+    pub runs: i64,
+    pub failures: i64,
+    /// Normalized message of the most frequent failure of the last detection
+    pub signature: Option<String>,
+    pub message: Option<String>,
+    pub first_seen: String,
+    pub last_seen: String,
+}
+
+impl TestFlakiness {
+    /// Passed at least once and failed at least once
+    pub fn is_flaky(&self) -> bool {
+        self.failures > 0 && self.failures < self.runs
+    }
+}
+
+/// Timing of a benchmark at a commit
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, sqlx::FromRow)]
+pub struct BenchmarkResult {
+    pub project_root: String,
+    pub commit_sha: String,
+    /// 1 when run with uncommitted changes, so not the commit's own timing
+    pub dirty: i32,
+    /// criterion or pytest-benchmark
+    pub framework: String,
+    pub benchmark: String,
+    pub mean_ns: f64,
+    pub stddev_ns: f64,
+    pub recorded_at: String,
+}
+
+/// A file the tools read or wrote during a session
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, sqlx::FromRow)]
+pub struct SessionFile {
+    pub path: String,
+    pub written: i64, // SQLite uses INTEGER for boolean
+}
+
+impl SessionFile {
+    pub fn is_written(&self) -> bool {
+        self.written != 0
+    }
+}
+
+/// A past session as the session browser lists it (`/sessions`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionSummary {
+    pub session: Session,
+    pub tags: Vec<String>,
+    pub message_count: i64,
+    /// First question of the session, which describes it when it has no title
+    pub first_question: Option<String>,
+    pub files: Vec<SessionFile>,
+}
+
+/// A fact remembered for a project (`/remember`, `memory` tool)
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct Memory {
+    pub id: i64,
+    /// Working directory key, see [`Database::project_key`](super::Database::project_key)
+    pub project: String,
+    pub content: String,
+    /// Embedding of `content`, `None` when no embedding model was available
+    pub embedding: Option<Vec<u8>>,
+    pub created_at: String,
+}
+
+/// An example question and the answer the user wants for it (`/examples`)
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct StyleExample {
+    pub id: i64,
+    /// Working directory key, see [`Database::project_key`](super::Database::project_key)
+    pub project: String,
+    pub prompt: String,
+    /// The answer or diff to imitate
+    pub response: String,
+    /// Embedding of `prompt`, `None` when no embedding model was available
+    pub embedding: Option<Vec<u8>>,
+    /// Turns the example was given to the model
+    pub uses: i64,
+    pub created_at: String,
+}
+
+/// A chunk marked irrelevant for a query (`/sources irrelevant`)
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct RetrievalFeedback {
+    pub id: i64,
+    /// Working directory key, see [`Database::project_key`](super::Database::project_key)
+    pub project: String,
+    pub query: String,
+    pub chunk_id: String,
+    /// File the chunk was read from, as the answer cited it
+    pub source: String,
+    pub created_at: String,
+}
+
+/// A large output kept out of the chat and linked from its summary (`/artifacts`)
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct Artifact {
+    pub id: i64,
+    pub session_id: String,
+    /// output, diff, report, doc, export or profile
+    pub kind: String,
+    /// What produced it, e.g. the shell command
+    pub source: String,
+    pub content: String,
+    pub summary: String,
+    /// JSON object: line count and whatever the producer adds
+    pub metadata: String,
+    pub size_bytes: i64,
+    /// Pinned artifacts are never removed by the retention policy
+    pub pinned: i64, // SQLite uses INTEGER for boolean
+    /// RFC 3339; `None` keeps the artifact until it is removed
+    pub expires_at: Option<String>,
+    pub created_at: String,
+}
+
+impl Artifact {
+    pub fn new(
+        session_id: impl Into<String>,
+        kind: impl Into<String>,
+        source: impl Into<String>,
+        content: impl Into<String>,
+        summary: impl Into<String>,
+    ) -> Self {
+        let content = content.into();
+        Self {
+            id: 0,
+            session_id: session_id.into(),
+            kind: kind.into(),
+            source: source.into(),
+            size_bytes: content.len() as i64,
+            metadata: serde_json::json!({ "lines": content.lines().count() }).to_string(),
+            content,
+            summary: summary.into(),
+            pinned: 0,
+            expires_at: None,
+            created_at: Utc::now().to_rfc3339(),
+        }
+    }
+
+    pub fn with_metadata(mut self, metadata: serde_json::Value) -> Self {
+        self.metadata = metadata.to_string();
+        self
+    }
+
+    pub fn with_expiry(mut self, expires_at: Option<String>) -> Self {
+        self.expires_at = expires_at;
+        self
+    }
+
+    pub fn is_pinned(&self) -> bool {
+        self.pinned != 0
+    }
+
+    /// Line count of the content, from the metadata when recorded
+    pub fn lines(&self) -> usize {
+        serde_json::from_str::<serde_json::Value>(&self.metadata)
+            .ok()
+            .and_then(|metadata| metadata["lines"].as_u64())
+            .map_or_else(|| self.content.lines().count(), |lines| lines as usize)
+    }
+}
+
 /// Code relationship record
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
 pub struct CodeRelationship {
@@ -2,10 +2,12 @@
 
 use super::migrations::INIT_SCHEMA;
 use super::models::{
-    CodeDependency, CodeSymbol, CommandExecution, DbMessage, IndexedFile, Project,
-    ProjectAnalysisRecord, SecurityConfig, Session,
+    Artifact, BenchmarkResult, BuildError, CodeDependency, CodeSymbol, CommandExecution, ComplexitySnapshot, DbMessage,
+    IndexedDependency, IndexedFile, IndexedSymbol, Memory, Project, ProjectAnalysisRecord, RetrievalFeedback,
+    SecurityConfig, Session, SessionFile, SessionSummary, StyleExample, TestFlakiness,
 };
-use sqlx::sqlite::{SqliteConnectOptions, SqlitePool, SqlitePoolOptions};
+use sqlx::sqlite::{SqliteArguments, SqliteConnectOptions, SqlitePool, SqlitePoolOptions};
+use sqlx::Sqlite;
 use std::path::Path;
 use std::str::FromStr;
 use thiserror::Error;
@@ -23,6 +25,7 @@ pub enum DatabaseError {
 }
 
 /// Database connection and operations
+#[derive(Clone)]
 pub struct Database {
     pool: SqlitePool,
 }
@@ -157,6 +160,176 @@ impl Database {
         Ok(())
     }
 
+    // ========================================================================
+    // Session tags, files and search
+    // ========================================================================
+
+    /// Tag a session; `false` when it already had the tag
+    pub async fn tag_session(&self, session_id: &str, tag: &str) -> Result<bool, DatabaseError> {
+        let result = sqlx::query("INSERT OR IGNORE INTO session_tags (session_id, tag) VALUES (?, ?)")
+            .bind(session_id)
+            .bind(tag)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Remove a tag from a session; `false` when it did not have it
+    pub async fn untag_session(&self, session_id: &str, tag: &str) -> Result<bool, DatabaseError> {
+        let result = sqlx::query("DELETE FROM session_tags WHERE session_id = ? AND tag = ?")
+            .bind(session_id)
+            .bind(tag)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Tags of a session, sorted
+    pub async fn get_session_tags(&self, session_id: &str) -> Result<Vec<String>, DatabaseError> {
+        Ok(sqlx::query_scalar::<_, String>(
+            "SELECT tag FROM session_tags WHERE session_id = ? ORDER BY tag",
+        )
+        .bind(session_id)
+        .fetch_all(&self.pool)
+        .await?)
+    }
+
+    /// Note a file the tools read or wrote; a file once written stays written
+    pub async fn record_session_file(
+        &self,
+        session_id: &str,
+        path: &str,
+        written: bool,
+    ) -> Result<(), DatabaseError> {
+        sqlx::query(
+            r#"
+            INSERT INTO session_files (session_id, path, written)
+            VALUES (?, ?, ?)
+            ON CONFLICT(session_id, path) DO UPDATE SET
+                written = MAX(session_files.written, excluded.written)
+            "#,
+        )
+        .bind(session_id)
+        .bind(path)
+        .bind(written as i64)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Files touched during a session, written ones first
+    pub async fn get_session_files(&self, session_id: &str) -> Result<Vec<SessionFile>, DatabaseError> {
+        Ok(sqlx::query_as::<_, SessionFile>(
+            "SELECT path, written FROM session_files WHERE session_id = ? ORDER BY written DESC, path",
+        )
+        .bind(session_id)
+        .fetch_all(&self.pool)
+        .await?)
+    }
+
+    /// Sessions, most recent first, whose title, messages, tags or files
+    /// contain `text` and that have every tag in `tags`
+    pub async fn search_sessions(
+        &self,
+        text: &str,
+        tags: &[String],
+        limit: i64,
+    ) -> Result<Vec<Session>, DatabaseError> {
+        let mut pattern = String::from("%");
+        for c in text.chars() {
+            if matches!(c, '%' | '_' | '\\') {
+                pattern.push('\\');
+            }
+            pattern.push(c);
+        }
+        pattern.push('%');
+        let tags_json = serde_json::to_string(tags).unwrap_or_else(|_| "[]".to_string());
+
+        Ok(sqlx::query_as::<_, Session>(
+            r#"
+            SELECT s.* FROM sessions s
+            WHERE (
+                ? = ''
+                OR s.title LIKE ? ESCAPE '\'
+                OR EXISTS (SELECT 1 FROM messages m WHERE m.session_id = s.id AND m.content LIKE ? ESCAPE '\')
+                OR EXISTS (SELECT 1 FROM session_tags t WHERE t.session_id = s.id AND t.tag LIKE ? ESCAPE '\')
+                OR EXISTS (SELECT 1 FROM session_files f WHERE f.session_id = s.id AND f.path LIKE ? ESCAPE '\')
+            )
+            AND (
+                SELECT COUNT(*) FROM session_tags t
+                WHERE t.session_id = s.id AND t.tag IN (SELECT value FROM json_each(?))
+            ) = ?
+            ORDER BY s.updated_at DESC, s.created_at DESC
+            LIMIT ?
+            "#,
+        )
+        .bind(text)
+        .bind(&pattern)
+        .bind(&pattern)
+        .bind(&pattern)
+        .bind(&pattern)
+        .bind(tags_json)
+        .bind(tags.len() as i64)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?)
+    }
+
+    /// Sessions created, updated or with messages since `since` (RFC 3339), oldest first
+    pub async fn sessions_active_since(&self, since: &str) -> Result<Vec<Session>, DatabaseError> {
+        Ok(sqlx::query_as::<_, Session>(
+            r#"
+            SELECT * FROM sessions s
+            WHERE s.updated_at >= ?
+               OR EXISTS (SELECT 1 FROM messages m WHERE m.session_id = s.id AND m.created_at >= ?)
+            ORDER BY s.created_at ASC
+            "#,
+        )
+        .bind(since)
+        .bind(since)
+        .fetch_all(&self.pool)
+        .await?)
+    }
+
+    /// User messages stored since `since` (RFC 3339)
+    pub async fn count_questions_since(&self, since: &str) -> Result<i64, DatabaseError> {
+        Ok(sqlx::query_scalar::<_, i64>(
+            "SELECT COUNT(*) FROM messages WHERE role = 'user' AND created_at >= ?",
+        )
+        .bind(since)
+        .fetch_one(&self.pool)
+        .await?)
+    }
+
+    /// Tags, size, first question and files of a session
+    pub async fn get_session_summary(&self, session: Session) -> Result<SessionSummary, DatabaseError> {
+        let tags = self.get_session_tags(&session.id).await?;
+        let files = self.get_session_files(&session.id).await?;
+        let message_count = self.count_session_messages(&session.id).await?;
+        let first_question = sqlx::query_scalar::<_, String>(
+            r#"
+            SELECT content FROM messages
+            WHERE session_id = ? AND role = 'user'
+            ORDER BY created_at ASC, rowid ASC
+            LIMIT 1
+            "#,
+        )
+        .bind(&session.id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(SessionSummary {
+            session,
+            tags,
+            message_count,
+            first_question,
+            files,
+        })
+    }
+
     // ========================================================================
     // Message operations
     // ========================================================================
@@ -218,6 +391,448 @@ impl Database {
         .await?)
     }
 
+    /// Number of messages stored for a session
+    pub async fn count_session_messages(&self, session_id: &str) -> Result<i64, DatabaseError> {
+        Ok(sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM messages WHERE session_id = ?")
+            .bind(session_id)
+            .fetch_one(&self.pool)
+            .await?)
+    }
+
+    /// Get a page of a session's messages in chronological order
+    pub async fn get_message_page(
+        &self,
+        session_id: &str,
+        offset: i64,
+        limit: i64,
+    ) -> Result<Vec<DbMessage>, DatabaseError> {
+        Ok(sqlx::query_as::<_, DbMessage>(
+            r#"
+            SELECT * FROM messages
+            WHERE session_id = ?
+            ORDER BY created_at ASC, rowid ASC
+            LIMIT ? OFFSET ?
+            "#,
+        )
+        .bind(session_id)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await?)
+    }
+
+    // ========================================================================
+    // Session environment operations
+    // ========================================================================
+
+    /// Set (or replace) a session environment variable
+    pub async fn set_session_env(
+        &self,
+        session_id: &str,
+        key: &str,
+        value: &str,
+    ) -> Result<(), DatabaseError> {
+        sqlx::query(
+            r#"
+            INSERT INTO session_env (session_id, key, value)
+            VALUES (?, ?, ?)
+            ON CONFLICT(session_id, key) DO UPDATE SET
+                value = excluded.value,
+                updated_at = datetime('now')
+            "#,
+        )
+        .bind(session_id)
+        .bind(key)
+        .bind(value)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Remove a session environment variable
+    pub async fn unset_session_env(&self, session_id: &str, key: &str) -> Result<(), DatabaseError> {
+        sqlx::query("DELETE FROM session_env WHERE session_id = ? AND key = ?")
+            .bind(session_id)
+            .bind(key)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Get all environment variables of a session, sorted by key
+    pub async fn get_session_env(
+        &self,
+        session_id: &str,
+    ) -> Result<Vec<(String, String)>, DatabaseError> {
+        Ok(sqlx::query_as::<_, (String, String)>(
+            "SELECT key, value FROM session_env WHERE session_id = ? ORDER BY key",
+        )
+        .bind(session_id)
+        .fetch_all(&self.pool)
+        .await?)
+    }
+
+    /// Key under which a working directory's project settings are stored
+    pub fn project_key(working_dir: &str) -> String {
+        std::fs::canonicalize(working_dir)
+            .map(|p| p.to_string_lossy().into_owned())
+            .unwrap_or_else(|_| working_dir.to_string())
+    }
+
+    /// Enable or disable a tool for a project
+    pub async fn set_project_tool(
+        &self,
+        project: &str,
+        tool: &str,
+        enabled: bool,
+    ) -> Result<(), DatabaseError> {
+        sqlx::query(
+            r#"
+            INSERT INTO project_tools (project, tool, enabled)
+            VALUES (?, ?, ?)
+            ON CONFLICT(project, tool) DO UPDATE SET
+                enabled = excluded.enabled,
+                updated_at = datetime('now')
+            "#,
+        )
+        .bind(project)
+        .bind(tool)
+        .bind(enabled)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Tools disabled for a project, sorted by name
+    pub async fn get_disabled_tools(&self, project: &str) -> Result<Vec<String>, DatabaseError> {
+        Ok(sqlx::query_scalar::<_, String>(
+            "SELECT tool FROM project_tools WHERE project = ? AND enabled = 0 ORDER BY tool",
+        )
+        .bind(project)
+        .fetch_all(&self.pool)
+        .await?)
+    }
+
+    // ========================================================================
+    // User preference operations
+    // ========================================================================
+
+    /// Set a preference for a project, or globally with `None`
+    pub async fn set_preference(
+        &self,
+        project: Option<&str>,
+        key: &str,
+        value: &str,
+    ) -> Result<(), DatabaseError> {
+        sqlx::query(
+            r#"
+            INSERT INTO user_preferences (scope, key, value)
+            VALUES (?, ?, ?)
+            ON CONFLICT(scope, key) DO UPDATE SET
+                value = excluded.value,
+                updated_at = datetime('now')
+            "#,
+        )
+        .bind(project.unwrap_or(""))
+        .bind(key)
+        .bind(value)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Remove a preference of a project, or a global one with `None`; `false` when it was not set
+    pub async fn unset_preference(&self, project: Option<&str>, key: &str) -> Result<bool, DatabaseError> {
+        let result = sqlx::query("DELETE FROM user_preferences WHERE scope = ? AND key = ?")
+            .bind(project.unwrap_or(""))
+            .bind(key)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Preferences of a project, or the global ones with `None`, sorted by key
+    pub async fn get_preferences(
+        &self,
+        project: Option<&str>,
+    ) -> Result<Vec<(String, String)>, DatabaseError> {
+        Ok(sqlx::query_as::<_, (String, String)>(
+            "SELECT key, value FROM user_preferences WHERE scope = ? ORDER BY key",
+        )
+        .bind(project.unwrap_or(""))
+        .fetch_all(&self.pool)
+        .await?)
+    }
+
+    // ========================================================================
+    // Memory operations
+    // ========================================================================
+
+    /// Remember a fact for a project; returns its id (the existing one for a repeated fact)
+    pub async fn add_memory(
+        &self,
+        project: &str,
+        content: &str,
+        embedding: Option<&[u8]>,
+    ) -> Result<i64, DatabaseError> {
+        sqlx::query(
+            r#"
+            INSERT INTO memories (project, content, embedding)
+            VALUES (?, ?, ?)
+            ON CONFLICT(project, content) DO UPDATE SET
+                embedding = COALESCE(excluded.embedding, memories.embedding)
+            "#,
+        )
+        .bind(project)
+        .bind(content)
+        .bind(embedding)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(sqlx::query_scalar::<_, i64>(
+            "SELECT id FROM memories WHERE project = ? AND content = ?",
+        )
+        .bind(project)
+        .bind(content)
+        .fetch_one(&self.pool)
+        .await?)
+    }
+
+    /// Facts remembered for a project, oldest first
+    pub async fn list_memories(&self, project: &str) -> Result<Vec<Memory>, DatabaseError> {
+        Ok(sqlx::query_as::<_, Memory>(
+            "SELECT * FROM memories WHERE project = ? ORDER BY id",
+        )
+        .bind(project)
+        .fetch_all(&self.pool)
+        .await?)
+    }
+
+    /// Forget a fact of a project; `false` when there was no such id
+    pub async fn forget_memory(&self, project: &str, id: i64) -> Result<bool, DatabaseError> {
+        let result = sqlx::query("DELETE FROM memories WHERE project = ? AND id = ?")
+            .bind(project)
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    // ========================================================================
+    // Style example operations
+    // ========================================================================
+
+    /// Store an example answer for a project; a repeated prompt replaces its
+    /// answer. Returns the id.
+    pub async fn add_style_example(
+        &self,
+        project: &str,
+        prompt: &str,
+        response: &str,
+        embedding: Option<&[u8]>,
+    ) -> Result<i64, DatabaseError> {
+        sqlx::query(
+            r#"
+            INSERT INTO style_examples (project, prompt, response, embedding)
+            VALUES (?, ?, ?, ?)
+            ON CONFLICT(project, prompt) DO UPDATE SET
+                response = excluded.response,
+                embedding = COALESCE(excluded.embedding, style_examples.embedding)
+            "#,
+        )
+        .bind(project)
+        .bind(prompt)
+        .bind(response)
+        .bind(embedding)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(sqlx::query_scalar::<_, i64>(
+            "SELECT id FROM style_examples WHERE project = ? AND prompt = ?",
+        )
+        .bind(project)
+        .bind(prompt)
+        .fetch_one(&self.pool)
+        .await?)
+    }
+
+    /// Example answers of a project, oldest first
+    pub async fn list_style_examples(&self, project: &str) -> Result<Vec<StyleExample>, DatabaseError> {
+        Ok(sqlx::query_as::<_, StyleExample>(
+            "SELECT * FROM style_examples WHERE project = ? ORDER BY id",
+        )
+        .bind(project)
+        .fetch_all(&self.pool)
+        .await?)
+    }
+
+    /// Remove an example of a project; `false` when there was no such id
+    pub async fn remove_style_example(&self, project: &str, id: i64) -> Result<bool, DatabaseError> {
+        let result = sqlx::query("DELETE FROM style_examples WHERE project = ? AND id = ?")
+            .bind(project)
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Count one more use of each example in `ids`
+    pub async fn record_style_example_uses(&self, ids: &[i64]) -> Result<(), DatabaseError> {
+        for id in ids {
+            sqlx::query("UPDATE style_examples SET uses = uses + 1 WHERE id = ?")
+                .bind(id)
+                .execute(&self.pool)
+                .await?;
+        }
+        Ok(())
+    }
+
+    // ========================================================================
+    // Retrieval feedback operations
+    // ========================================================================
+
+    /// Record that `chunk_id` was irrelevant for `query`; repeating it is a no-op
+    pub async fn add_retrieval_feedback(
+        &self,
+        project: &str,
+        query: &str,
+        chunk_id: &str,
+        source: &str,
+    ) -> Result<(), DatabaseError> {
+        sqlx::query(
+            r#"
+            INSERT OR IGNORE INTO retrieval_feedback (project, query, chunk_id, source)
+            VALUES (?, ?, ?, ?)
+            "#,
+        )
+        .bind(project)
+        .bind(query)
+        .bind(chunk_id)
+        .bind(source)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Negative pairs recorded for a project, oldest first
+    pub async fn list_retrieval_feedback(&self, project: &str) -> Result<Vec<RetrievalFeedback>, DatabaseError> {
+        Ok(sqlx::query_as::<_, RetrievalFeedback>(
+            "SELECT * FROM retrieval_feedback WHERE project = ? ORDER BY id",
+        )
+        .bind(project)
+        .fetch_all(&self.pool)
+        .await?)
+    }
+
+    // ========================================================================
+    // Artifact operations
+    // ========================================================================
+
+    /// Store an artifact; returns its id
+    pub async fn add_artifact(&self, artifact: &Artifact) -> Result<i64, DatabaseError> {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO artifacts
+                (session_id, kind, source, content, summary, metadata, size_bytes, pinned, expires_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(&artifact.session_id)
+        .bind(&artifact.kind)
+        .bind(&artifact.source)
+        .bind(&artifact.content)
+        .bind(&artifact.summary)
+        .bind(&artifact.metadata)
+        .bind(artifact.size_bytes)
+        .bind(artifact.pinned)
+        .bind(&artifact.expires_at)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.last_insert_rowid())
+    }
+
+    pub async fn get_artifact(&self, id: i64) -> Result<Option<Artifact>, DatabaseError> {
+        Ok(sqlx::query_as::<_, Artifact>("SELECT * FROM artifacts WHERE id = ?")
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?)
+    }
+
+    /// The latest `limit` artifacts, of one kind when given, newest first
+    pub async fn list_artifacts(&self, kind: Option<&str>, limit: i64) -> Result<Vec<Artifact>, DatabaseError> {
+        Ok(sqlx::query_as::<_, Artifact>(
+            "SELECT * FROM artifacts WHERE ? IS NULL OR kind = ? ORDER BY id DESC LIMIT ?",
+        )
+        .bind(kind)
+        .bind(kind)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?)
+    }
+
+    /// Pin or unpin an artifact; `false` when there was no such id
+    pub async fn pin_artifact(&self, id: i64, pinned: bool) -> Result<bool, DatabaseError> {
+        let result = sqlx::query("UPDATE artifacts SET pinned = ? WHERE id = ?")
+            .bind(pinned as i64)
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Remove an artifact; `false` when there was no such id
+    pub async fn delete_artifact(&self, id: i64) -> Result<bool, DatabaseError> {
+        let result = sqlx::query("DELETE FROM artifacts WHERE id = ?")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Remove the unpinned artifacts expired at `now` (RFC 3339), then the
+    /// oldest unpinned ones until all of them fit in `max_total_bytes`;
+    /// returns how many were removed
+    pub async fn prune_artifacts(&self, now: &str, max_total_bytes: i64) -> Result<u64, DatabaseError> {
+        let mut removed = sqlx::query(
+            "DELETE FROM artifacts WHERE pinned = 0 AND expires_at IS NOT NULL AND expires_at <= ?",
+        )
+        .bind(now)
+        .execute(&self.pool)
+        .await?
+        .rows_affected();
+
+        let sizes: Vec<(i64, i64, i64)> =
+            sqlx::query_as("SELECT id, size_bytes, pinned FROM artifacts ORDER BY id")
+                .fetch_all(&self.pool)
+                .await?;
+        let mut total: i64 = sizes.iter().map(|(_, size, _)| size).sum();
+        for (id, size, pinned) in sizes {
+            if total <= max_total_bytes {
+                break;
+            }
+            if pinned != 0 {
+                continue;
+            }
+            removed += sqlx::query("DELETE FROM artifacts WHERE id = ?")
+                .bind(id)
+                .execute(&self.pool)
+                .await?
+                .rows_affected();
+            total -= size;
+        }
+        Ok(removed)
+    }
+
     // ========================================================================
     // Command execution operations
     // ========================================================================
@@ -266,6 +881,16 @@ impl Database {
         .await?)
     }
 
+    /// Audit log entries since `since` (RFC 3339), oldest first
+    pub async fn command_executions_since(&self, since: &str) -> Result<Vec<CommandExecution>, DatabaseError> {
+        Ok(sqlx::query_as::<_, CommandExecution>(
+            "SELECT * FROM command_executions WHERE executed_at >= ? ORDER BY executed_at ASC, rowid ASC",
+        )
+        .bind(since)
+        .fetch_all(&self.pool)
+        .await?)
+    }
+
     /// Update retry count for a command
     pub async fn update_command_retry(
         &self,
@@ -426,156 +1051,656 @@ impl Database {
             .execute(&self.pool)
             .await?;
 
-            Ok(existing_id)
-        } else {
-            // Insert new
+            Ok(existing_id)
+        } else {
+            // Insert new
+            sqlx::query(
+                r#"
+                INSERT INTO indexed_files
+                (project_id, relative_path, absolute_path, file_hash, file_size, line_count,
+                 language, file_type, last_modified, indexed_at, is_valid)
+                VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+                "#,
+            )
+            .bind(&file.project_id)
+            .bind(&file.relative_path)
+            .bind(&file.absolute_path)
+            .bind(&file.file_hash)
+            .bind(file.file_size)
+            .bind(file.line_count)
+            .bind(&file.language)
+            .bind(&file.file_type)
+            .bind(&file.last_modified)
+            .bind(&file.indexed_at)
+            .bind(file.is_valid)
+            .execute(&self.pool)
+            .await?;
+
+            // Get last insert ID
+            let id: (i64,) = sqlx::query_as("SELECT last_insert_rowid()")
+                .fetch_one(&self.pool)
+                .await?;
+
+            Ok(id.0)
+        }
+    }
+
+    /// Get files for project
+    pub async fn get_project_files(
+        &self,
+        project_id: &str,
+    ) -> Result<Vec<IndexedFile>, DatabaseError> {
+        Ok(sqlx::query_as::<_, IndexedFile>(
+            "SELECT * FROM indexed_files WHERE project_id = ? AND is_valid = 1 ORDER BY relative_path"
+        )
+        .bind(project_id)
+        .fetch_all(&self.pool)
+        .await?)
+    }
+
+    /// Get an indexed file by its path relative to the project root
+    pub async fn get_indexed_file(
+        &self,
+        project_id: &str,
+        relative_path: &str,
+    ) -> Result<Option<IndexedFile>, DatabaseError> {
+        Ok(sqlx::query_as::<_, IndexedFile>(
+            "SELECT * FROM indexed_files WHERE project_id = ? AND relative_path = ?",
+        )
+        .bind(project_id)
+        .bind(relative_path)
+        .fetch_optional(&self.pool)
+        .await?)
+    }
+
+    /// Remove an indexed file together with its symbols
+    pub async fn remove_indexed_file(&self, file_id: i64) -> Result<(), DatabaseError> {
+        let mut tx = self.pool.begin().await?;
+        for sql in [
+            "DELETE FROM search_index WHERE entity_type = 'symbol' AND entity_id IN \
+             (SELECT id FROM code_symbols WHERE file_id = ?)",
+            "DELETE FROM code_symbols WHERE file_id = ?",
+            "DELETE FROM code_dependencies WHERE source_file_id = ?",
+            "DELETE FROM file_metrics WHERE file_id = ?",
+            "DELETE FROM indexed_files WHERE id = ?",
+        ] {
+            sqlx::query(sql).bind(file_id).execute(&mut *tx).await?;
+        }
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    /// Insert code symbol
+    pub async fn insert_code_symbol(&self, symbol: &CodeSymbol) -> Result<i64, DatabaseError> {
+        let result = insert_code_symbol_query(symbol).execute(&self.pool).await?;
+        Ok(result.last_insert_rowid())
+    }
+
+    /// Replace all symbols of a file (and their search entries) in one transaction
+    pub async fn replace_file_symbols(
+        &self,
+        file_id: i64,
+        symbols: &[CodeSymbol],
+    ) -> Result<(), DatabaseError> {
+        let mut tx = self.pool.begin().await?;
+        sqlx::query(
+            "DELETE FROM search_index WHERE entity_type = 'symbol' AND entity_id IN \
+             (SELECT id FROM code_symbols WHERE file_id = ?)",
+        )
+        .bind(file_id)
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query("DELETE FROM code_symbols WHERE file_id = ?")
+            .bind(file_id)
+            .execute(&mut *tx)
+            .await?;
+
+        for symbol in symbols {
+            let id = insert_code_symbol_query(symbol)
+                .execute(&mut *tx)
+                .await?
+                .last_insert_rowid();
+            sqlx::query(
+                "INSERT INTO search_index (project_id, entity_type, entity_id, search_text) \
+                 VALUES (?, 'symbol', ?, ?)",
+            )
+            .bind(&symbol.project_id)
+            .bind(id)
+            .bind(symbol.symbol_name.to_lowercase())
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    /// Get symbols for file
+    pub async fn get_file_symbols(&self, file_id: i64) -> Result<Vec<CodeSymbol>, DatabaseError> {
+        Ok(sqlx::query_as::<_, CodeSymbol>(
+            "SELECT * FROM code_symbols WHERE file_id = ? ORDER BY line_start",
+        )
+        .bind(file_id)
+        .fetch_all(&self.pool)
+        .await?)
+    }
+
+    /// Get all symbols for a project
+    pub async fn get_all_symbols(
+        &self,
+        project_id: &str,
+    ) -> Result<Vec<CodeSymbol>, DatabaseError> {
+        Ok(sqlx::query_as::<_, CodeSymbol>(
+            "SELECT * FROM code_symbols WHERE project_id = ? ORDER BY symbol_name",
+        )
+        .bind(project_id)
+        .fetch_all(&self.pool)
+        .await?)
+    }
+
+    /// Search symbols by name
+    pub async fn search_symbols(
+        &self,
+        project_id: &str,
+        query: &str,
+        limit: i32,
+    ) -> Result<Vec<CodeSymbol>, DatabaseError> {
+        let search_pattern = format!("%{}%", query.to_lowercase());
+        Ok(sqlx::query_as::<_, CodeSymbol>(
+            r#"
+            SELECT DISTINCT cs.* FROM code_symbols cs
+            JOIN search_index si ON si.entity_type = 'symbol' AND si.entity_id = cs.id
+            WHERE cs.project_id = ? AND si.search_text LIKE ?
+            ORDER BY cs.symbol_name
+            LIMIT ?
+            "#,
+        )
+        .bind(project_id)
+        .bind(&search_pattern)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?)
+    }
+
+    /// Symbols whose name contains the characters of `query` in order
+    /// (case-insensitive), as candidates for fuzzy ranking
+    pub async fn find_symbol_candidates(
+        &self,
+        project_id: &str,
+        query: &str,
+        limit: i32,
+    ) -> Result<Vec<IndexedSymbol>, DatabaseError> {
+        let mut pattern = String::from("%");
+        for c in query.chars() {
+            if matches!(c, '%' | '_' | '\\') {
+                pattern.push('\\');
+            }
+            pattern.push(c);
+            pattern.push('%');
+        }
+
+        Ok(sqlx::query_as::<_, IndexedSymbol>(
+            r#"
+            SELECT cs.*, f.relative_path FROM code_symbols cs
+            JOIN indexed_files f ON f.id = cs.file_id
+            WHERE cs.project_id = ? AND cs.symbol_name LIKE ? ESCAPE '\'
+            ORDER BY length(cs.symbol_name)
+            LIMIT ?
+            "#,
+        )
+        .bind(project_id)
+        .bind(&pattern)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?)
+    }
+
+    /// Symbols of a file, in source order
+    pub async fn get_symbols_in_file(
+        &self,
+        project_id: &str,
+        relative_path: &str,
+    ) -> Result<Vec<IndexedSymbol>, DatabaseError> {
+        Ok(sqlx::query_as::<_, IndexedSymbol>(
+            r#"
+            SELECT cs.*, f.relative_path FROM code_symbols cs
+            JOIN indexed_files f ON f.id = cs.file_id
+            WHERE cs.project_id = ? AND f.relative_path = ?
+            ORDER BY cs.line_start, cs.line_end DESC
+            "#,
+        )
+        .bind(project_id)
+        .bind(relative_path)
+        .fetch_all(&self.pool)
+        .await?)
+    }
+
+    /// Innermost symbol spanning a line of a file
+    pub async fn get_symbol_at_line(
+        &self,
+        project_id: &str,
+        relative_path: &str,
+        line: i64,
+    ) -> Result<Option<IndexedSymbol>, DatabaseError> {
+        Ok(sqlx::query_as::<_, IndexedSymbol>(
+            r#"
+            SELECT cs.*, f.relative_path FROM code_symbols cs
+            JOIN indexed_files f ON f.id = cs.file_id
+            WHERE cs.project_id = ? AND f.relative_path = ?
+              AND cs.line_start <= ? AND cs.line_end >= ?
+            ORDER BY cs.line_end - cs.line_start
+            LIMIT 1
+            "#,
+        )
+        .bind(project_id)
+        .bind(relative_path)
+        .bind(line)
+        .bind(line)
+        .fetch_optional(&self.pool)
+        .await?)
+    }
+
+    /// Insert dependency
+    pub async fn insert_dependency(&self, dep: &CodeDependency) -> Result<(), DatabaseError> {
+        sqlx::query(
+            r#"
+            INSERT INTO code_dependencies
+            (project_id, source_file_id, target_module, import_type, is_external, line_number)
+            VALUES (?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(&dep.project_id)
+        .bind(dep.source_file_id)
+        .bind(&dep.target_module)
+        .bind(&dep.import_type)
+        .bind(dep.is_external)
+        .bind(dep.line_number)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Replace the imports of a file in one transaction
+    pub async fn replace_file_dependencies(
+        &self,
+        file_id: i64,
+        dependencies: &[CodeDependency],
+    ) -> Result<(), DatabaseError> {
+        let mut tx = self.pool.begin().await?;
+        sqlx::query("DELETE FROM code_dependencies WHERE source_file_id = ?")
+            .bind(file_id)
+            .execute(&mut *tx)
+            .await?;
+
+        for dep in dependencies {
             sqlx::query(
                 r#"
-                INSERT INTO indexed_files
-                (project_id, relative_path, absolute_path, file_hash, file_size, line_count,
-                 language, file_type, last_modified, indexed_at, is_valid)
-                VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+                INSERT INTO code_dependencies
+                (project_id, source_file_id, target_module, import_type, is_external, line_number)
+                VALUES (?, ?, ?, ?, ?, ?)
                 "#,
             )
-            .bind(&file.project_id)
-            .bind(&file.relative_path)
-            .bind(&file.absolute_path)
-            .bind(&file.file_hash)
-            .bind(file.file_size)
-            .bind(file.line_count)
-            .bind(&file.language)
-            .bind(&file.file_type)
-            .bind(&file.last_modified)
-            .bind(&file.indexed_at)
-            .bind(file.is_valid)
-            .execute(&self.pool)
+            .bind(&dep.project_id)
+            .bind(file_id)
+            .bind(&dep.target_module)
+            .bind(&dep.import_type)
+            .bind(dep.is_external)
+            .bind(dep.line_number)
+            .execute(&mut *tx)
             .await?;
-
-            // Get last insert ID
-            let id: (i64,) = sqlx::query_as("SELECT last_insert_rowid()")
-                .fetch_one(&self.pool)
-                .await?;
-
-            Ok(id.0)
         }
+        tx.commit().await?;
+
+        Ok(())
     }
 
-    /// Get files for project
-    pub async fn get_project_files(
+    /// Imports of every indexed file of a project, with the importing file
+    pub async fn get_file_dependencies(
         &self,
         project_id: &str,
-    ) -> Result<Vec<IndexedFile>, DatabaseError> {
-        Ok(sqlx::query_as::<_, IndexedFile>(
-            "SELECT * FROM indexed_files WHERE project_id = ? AND is_valid = 1 ORDER BY relative_path"
+    ) -> Result<Vec<IndexedDependency>, DatabaseError> {
+        Ok(sqlx::query_as::<_, IndexedDependency>(
+            r#"
+            SELECT d.*, f.relative_path, f.language
+            FROM code_dependencies d
+            JOIN indexed_files f ON f.id = d.source_file_id
+            WHERE d.project_id = ?
+            ORDER BY f.relative_path, d.line_number
+            "#,
         )
         .bind(project_id)
         .fetch_all(&self.pool)
         .await?)
     }
 
-    /// Insert code symbol
-    pub async fn insert_code_symbol(&self, symbol: &CodeSymbol) -> Result<i64, DatabaseError> {
+    /// Store the complexity metrics of a file (JSON)
+    pub async fn upsert_file_metrics(
+        &self,
+        file_id: i64,
+        project_id: &str,
+        metrics_json: &str,
+    ) -> Result<(), DatabaseError> {
         sqlx::query(
             r#"
-            INSERT INTO code_symbols
-            (file_id, project_id, symbol_name, symbol_type, visibility, line_start, line_end,
-             signature, documentation, complexity, params_json, return_type, is_async, is_test, parent_symbol_id)
-            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
-            "#,
-        )
-        .bind(symbol.file_id)
-        .bind(&symbol.project_id)
-        .bind(&symbol.symbol_name)
-        .bind(&symbol.symbol_type)
-        .bind(&symbol.visibility)
-        .bind(symbol.line_start)
-        .bind(symbol.line_end)
-        .bind(&symbol.signature)
-        .bind(&symbol.documentation)
-        .bind(symbol.complexity)
-        .bind(&symbol.params_json)
-        .bind(&symbol.return_type)
-        .bind(symbol.is_async)
-        .bind(symbol.is_test)
-        .bind(symbol.parent_symbol_id)
+            INSERT INTO file_metrics (file_id, project_id, metrics_json)
+            VALUES (?, ?, ?)
+            ON CONFLICT(file_id) DO UPDATE SET metrics_json = excluded.metrics_json
+            "#,
+        )
+        .bind(file_id)
+        .bind(project_id)
+        .bind(metrics_json)
         .execute(&self.pool)
         .await?;
 
-        let id: (i64,) = sqlx::query_as("SELECT last_insert_rowid()")
-            .fetch_one(&self.pool)
-            .await?;
+        Ok(())
+    }
 
-        Ok(id.0)
+    /// Complexity metrics (JSON) of every indexed file of a project
+    pub async fn get_file_metrics(&self, project_id: &str) -> Result<Vec<String>, DatabaseError> {
+        let rows: Vec<(String,)> =
+            sqlx::query_as("SELECT metrics_json FROM file_metrics WHERE project_id = ?")
+                .bind(project_id)
+                .fetch_all(&self.pool)
+                .await?;
+        Ok(rows.into_iter().map(|(json,)| json).collect())
     }
 
-    /// Get symbols for file
-    pub async fn get_file_symbols(&self, file_id: i64) -> Result<Vec<CodeSymbol>, DatabaseError> {
-        Ok(sqlx::query_as::<_, CodeSymbol>(
-            "SELECT * FROM code_symbols WHERE file_id = ? ORDER BY line_start",
+    /// Record a complexity snapshot
+    pub async fn insert_complexity_snapshot(
+        &self,
+        snapshot: &ComplexitySnapshot,
+    ) -> Result<i64, DatabaseError> {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO complexity_snapshots
+            (project_id, taken_at, files, functions, avg_complexity, max_complexity,
+             complex_functions, todo_count, maintainability, report_json)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
         )
-        .bind(file_id)
-        .fetch_all(&self.pool)
-        .await?)
+        .bind(&snapshot.project_id)
+        .bind(&snapshot.taken_at)
+        .bind(snapshot.files)
+        .bind(snapshot.functions)
+        .bind(snapshot.avg_complexity)
+        .bind(snapshot.max_complexity)
+        .bind(snapshot.complex_functions)
+        .bind(snapshot.todo_count)
+        .bind(snapshot.maintainability)
+        .bind(&snapshot.report_json)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.last_insert_rowid())
     }
 
-    /// Get all symbols for a project
-    pub async fn get_all_symbols(
+    /// Latest complexity snapshots of a project, oldest first
+    pub async fn get_complexity_snapshots(
         &self,
         project_id: &str,
-    ) -> Result<Vec<CodeSymbol>, DatabaseError> {
-        Ok(sqlx::query_as::<_, CodeSymbol>(
-            "SELECT * FROM code_symbols WHERE project_id = ? ORDER BY symbol_name",
+        limit: i32,
+    ) -> Result<Vec<ComplexitySnapshot>, DatabaseError> {
+        let mut snapshots = sqlx::query_as::<_, ComplexitySnapshot>(
+            "SELECT * FROM complexity_snapshots WHERE project_id = ? ORDER BY id DESC LIMIT ?",
         )
         .bind(project_id)
+        .bind(limit)
         .fetch_all(&self.pool)
+        .await?;
+        snapshots.reverse();
+        Ok(snapshots)
+    }
+
+    /// Build error of a project by signature
+    pub async fn get_build_error(
+        &self,
+        project_root: &str,
+        signature: &str,
+    ) -> Result<Option<BuildError>, DatabaseError> {
+        Ok(sqlx::query_as::<_, BuildError>(
+            "SELECT * FROM build_errors WHERE project_root = ? AND signature = ?",
+        )
+        .bind(project_root)
+        .bind(signature)
+        .fetch_optional(&self.pool)
         .await?)
     }
 
-    /// Search symbols by name
-    pub async fn search_symbols(
+    /// Record an occurrence of a build error, reopening it if it was resolved
+    pub async fn record_build_error(&self, error: &BuildError) -> Result<(), DatabaseError> {
+        sqlx::query(
+            r#"
+            INSERT INTO build_errors
+            (project_root, signature, tool, code, message, occurrences, first_seen, last_seen,
+             last_session_id, is_open)
+            VALUES (?, ?, ?, ?, ?, 1, ?, ?, ?, 1)
+            ON CONFLICT(project_root, signature) DO UPDATE SET
+                occurrences = occurrences + 1,
+                message = excluded.message,
+                last_seen = excluded.last_seen,
+                last_session_id = excluded.last_session_id,
+                is_open = 1
+            "#,
+        )
+        .bind(&error.project_root)
+        .bind(&error.signature)
+        .bind(&error.tool)
+        .bind(&error.code)
+        .bind(&error.message)
+        .bind(&error.first_seen)
+        .bind(&error.last_seen)
+        .bind(&error.last_session_id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Mark the open errors of a tool as fixed, returning how many were open
+    pub async fn resolve_build_errors(
         &self,
-        project_id: &str,
-        query: &str,
+        project_root: &str,
+        tool: &str,
+        session_id: &str,
+        commit: Option<&str>,
+        files: Option<&str>,
+    ) -> Result<u64, DatabaseError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE build_errors
+            SET is_open = 0, resolved_at = datetime('now'), resolution_session_id = ?,
+                resolution_commit = ?, resolution_files = ?
+            WHERE project_root = ? AND tool = ? AND is_open = 1
+            "#,
+        )
+        .bind(session_id)
+        .bind(commit)
+        .bind(files)
+        .bind(project_root)
+        .bind(tool)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Build errors of a project, most recurrent first
+    pub async fn get_build_errors(
+        &self,
+        project_root: &str,
         limit: i32,
-    ) -> Result<Vec<CodeSymbol>, DatabaseError> {
-        let search_pattern = format!("%{}%", query.to_lowercase());
-        Ok(sqlx::query_as::<_, CodeSymbol>(
+    ) -> Result<Vec<BuildError>, DatabaseError> {
+        Ok(sqlx::query_as::<_, BuildError>(
+            "SELECT * FROM build_errors WHERE project_root = ? \
+             ORDER BY occurrences DESC, last_seen DESC LIMIT ?",
+        )
+        .bind(project_root)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?)
+    }
+
+    /// Add the runs and failures of a test in one detection
+    pub async fn record_test_runs(&self, record: &TestFlakiness) -> Result<(), DatabaseError> {
+        sqlx::query(
             r#"
-            SELECT DISTINCT cs.* FROM code_symbols cs
-            JOIN search_index si ON si.entity_type = 'symbol' AND si.entity_id = cs.id
-            WHERE cs.project_id = ? AND si.search_text LIKE ?
-            ORDER BY cs.symbol_name
-            LIMIT ?
+            INSERT INTO test_flakiness
+            (project_root, test, runs, failures, signature, message, first_seen, last_seen)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+            ON CONFLICT(project_root, test) DO UPDATE SET
+                runs = runs + excluded.runs,
+                failures = failures + excluded.failures,
+                signature = COALESCE(excluded.signature, signature),
+                message = COALESCE(excluded.message, message),
+                last_seen = excluded.last_seen
             "#,
         )
-        .bind(project_id)
-        .bind(&search_pattern)
+        .bind(&record.project_root)
+        .bind(&record.test)
+        .bind(record.runs)
+        .bind(record.failures)
+        .bind(&record.signature)
+        .bind(&record.message)
+        .bind(&record.first_seen)
+        .bind(&record.last_seen)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Tests of a project that have both passed and failed, those failing
+    /// closest to half of their runs first
+    pub async fn get_flaky_tests(
+        &self,
+        project_root: &str,
+        limit: i32,
+    ) -> Result<Vec<TestFlakiness>, DatabaseError> {
+        Ok(sqlx::query_as::<_, TestFlakiness>(
+            "SELECT * FROM test_flakiness \
+             WHERE project_root = ? AND failures > 0 AND failures < runs \
+             ORDER BY MIN(failures, runs - failures) * 1.0 / runs DESC, runs DESC LIMIT ?",
+        )
+        .bind(project_root)
         .bind(limit)
         .fetch_all(&self.pool)
         .await?)
     }
 
-    /// Insert dependency
-    pub async fn insert_dependency(&self, dep: &CodeDependency) -> Result<(), DatabaseError> {
+    /// Store the timing of a benchmark, replacing an earlier run at the same commit
+    pub async fn save_benchmark_result(&self, result: &BenchmarkResult) -> Result<(), DatabaseError> {
         sqlx::query(
             r#"
-            INSERT INTO code_dependencies
-            (project_id, source_file_id, target_module, import_type, is_external, line_number)
-            VALUES (?, ?, ?, ?, ?, ?)
+            INSERT OR REPLACE INTO benchmark_results
+            (project_root, commit_sha, dirty, framework, benchmark, mean_ns, stddev_ns, recorded_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?)
             "#,
         )
-        .bind(&dep.project_id)
-        .bind(dep.source_file_id)
-        .bind(&dep.target_module)
-        .bind(&dep.import_type)
-        .bind(dep.is_external)
-        .bind(dep.line_number)
+        .bind(&result.project_root)
+        .bind(&result.commit_sha)
+        .bind(result.dirty)
+        .bind(&result.framework)
+        .bind(&result.benchmark)
+        .bind(result.mean_ns)
+        .bind(result.stddev_ns)
+        .bind(&result.recorded_at)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Benchmark timings recorded at a commit
+    pub async fn get_benchmark_results(
+        &self,
+        project_root: &str,
+        commit_sha: &str,
+        dirty: i32,
+    ) -> Result<Vec<BenchmarkResult>, DatabaseError> {
+        Ok(sqlx::query_as::<_, BenchmarkResult>(
+            "SELECT * FROM benchmark_results \
+             WHERE project_root = ? AND commit_sha = ? AND dirty = ? ORDER BY benchmark",
+        )
+        .bind(project_root)
+        .bind(commit_sha)
+        .bind(dirty)
+        .fetch_all(&self.pool)
+        .await?)
+    }
+
+    /// Cached tree summary of a directory, as `(fingerprint, summary JSON)`
+    pub async fn get_tree_summary(
+        &self,
+        path: &str,
+    ) -> Result<Option<(String, String)>, DatabaseError> {
+        Ok(sqlx::query_as::<_, (String, String)>(
+            "SELECT fingerprint, summary FROM tree_summaries WHERE path = ?",
+        )
+        .bind(path)
+        .fetch_optional(&self.pool)
+        .await?)
+    }
+
+    /// Store the tree summary of a directory
+    pub async fn save_tree_summary(
+        &self,
+        path: &str,
+        fingerprint: &str,
+        summary: &str,
+    ) -> Result<(), DatabaseError> {
+        sqlx::query(
+            "INSERT OR REPLACE INTO tree_summaries (path, fingerprint, summary) VALUES (?, ?, ?)",
+        )
+        .bind(path)
+        .bind(fingerprint)
+        .bind(summary)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Cached ownership of a repository, as `(head commit, ownership JSON)`
+    pub async fn get_code_ownership(&self, repo: &str) -> Result<Option<(String, String)>, DatabaseError> {
+        Ok(sqlx::query_as::<_, (String, String)>(
+            "SELECT head, ownership FROM code_ownership WHERE repo = ?",
+        )
+        .bind(repo)
+        .fetch_optional(&self.pool)
+        .await?)
+    }
+
+    /// Store the ownership of a repository computed up to `head`
+    pub async fn save_code_ownership(&self, repo: &str, head: &str, ownership: &str) -> Result<(), DatabaseError> {
+        sqlx::query(
+            "INSERT OR REPLACE INTO code_ownership (repo, head, ownership, updated_at) \
+             VALUES (?, ?, ?, datetime('now'))",
+        )
+        .bind(repo)
+        .bind(head)
+        .bind(ownership)
         .execute(&self.pool)
         .await?;
 
         Ok(())
     }
 
+    /// Drop the tree summaries of `changed` and of every directory above it
+    pub async fn invalidate_tree_summaries(&self, changed: &str) -> Result<u64, DatabaseError> {
+        let result = sqlx::query(
+            "DELETE FROM tree_summaries \
+             WHERE path = ?1 OR substr(?1, 1, length(path) + 1) = path || '/'",
+        )
+        .bind(changed)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
     /// Get project dependencies
     pub async fn get_project_dependencies(
         &self,
@@ -670,6 +1795,41 @@ impl Database {
     }
 }
 
+fn insert_code_symbol_query(
+    symbol: &CodeSymbol,
+) -> sqlx::query::Query<'_, Sqlite, SqliteArguments<'_>> {
+    sqlx::query(
+        r#"
+        INSERT INTO code_symbols
+        (file_id, project_id, symbol_name, symbol_type, visibility, line_start, line_end,
+         signature, documentation, complexity, params_json, return_type, is_async, is_test, parent_symbol_id)
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+        "#,
+    )
+    .bind(symbol.file_id)
+    .bind(&symbol.project_id)
+    .bind(&symbol.symbol_name)
+    .bind(&symbol.symbol_type)
+    .bind(&symbol.visibility)
+    .bind(symbol.line_start)
+    .bind(symbol.line_end)
+    .bind(&symbol.signature)
+    .bind(&symbol.documentation)
+    .bind(symbol.complexity)
+    .bind(&symbol.params_json)
+    .bind(&symbol.return_type)
+    .bind(symbol.is_async)
+    .bind(symbol.is_test)
+    .bind(symbol.parent_symbol_id)
+}
+
+/// Database handle bound to a single session
+#[derive(Clone)]
+pub struct SessionStore {
+    pub db: Database,
+    pub session_id: String,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -701,6 +1861,222 @@ mod tests {
         assert_eq!(messages[0].content, "Hello!");
     }
 
+    #[tokio::test]
+    async fn test_get_message_page() {
+        let db = Database::in_memory().await.unwrap();
+
+        let session = Session::new("paged", "qwen3:0.6b", "qwen3:8b");
+        db.create_session(&session).await.unwrap();
+
+        for i in 0..5 {
+            let message = DbMessage::new(format!("msg-{}", i), "paged", "user", format!("m{}", i));
+            db.create_message(&message).await.unwrap();
+        }
+
+        let page = db.get_message_page("paged", 1, 2).await.unwrap();
+        let contents: Vec<_> = page.iter().map(|m| m.content.as_str()).collect();
+        assert_eq!(contents, vec!["m1", "m2"]);
+    }
+
+    #[tokio::test]
+    async fn test_session_env() {
+        let db = Database::in_memory().await.unwrap();
+
+        let session = Session::new("env-session", "qwen3:0.6b", "qwen3:8b");
+        db.create_session(&session).await.unwrap();
+
+        db.set_session_env("env-session", "API_BASE", "http://localhost:3000").await.unwrap();
+        db.set_session_env("env-session", "API_BASE", "http://localhost:8080").await.unwrap();
+        db.set_session_env("env-session", "DEBUG", "1").await.unwrap();
+        db.unset_session_env("env-session", "DEBUG").await.unwrap();
+
+        let env = db.get_session_env("env-session").await.unwrap();
+        assert_eq!(env, vec![("API_BASE".to_string(), "http://localhost:8080".to_string())]);
+    }
+
+    #[tokio::test]
+    async fn test_preferences() {
+        let db = Database::in_memory().await.unwrap();
+
+        db.set_preference(None, "naming", "snake_case").await.unwrap();
+        db.set_preference(Some("/srv/app"), "naming", "camelCase").await.unwrap();
+        db.set_preference(Some("/srv/app"), "verbosity", "brief").await.unwrap();
+        assert!(db.unset_preference(Some("/srv/app"), "verbosity").await.unwrap());
+        assert!(!db.unset_preference(None, "verbosity").await.unwrap());
+
+        assert_eq!(
+            db.get_preferences(None).await.unwrap(),
+            vec![("naming".to_string(), "snake_case".to_string())]
+        );
+        assert_eq!(
+            db.get_preferences(Some("/srv/app")).await.unwrap(),
+            vec![("naming".to_string(), "camelCase".to_string())]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_memories() {
+        let db = Database::in_memory().await.unwrap();
+
+        let first = db.add_memory("/srv/app", "we deploy with just, not make", None).await.unwrap();
+        let again = db.add_memory("/srv/app", "we deploy with just, not make", Some(&[1, 2])).await.unwrap();
+        let second = db.add_memory("/srv/app", "staging runs on port 8080", None).await.unwrap();
+        db.add_memory("/srv/other", "uses poetry", None).await.unwrap();
+        assert_eq!(first, again);
+
+        let memories = db.list_memories("/srv/app").await.unwrap();
+        assert_eq!(memories.len(), 2);
+        assert_eq!(memories[0].embedding.as_deref(), Some(&[1u8, 2][..]));
+
+        assert!(db.forget_memory("/srv/app", second).await.unwrap());
+        assert!(!db.forget_memory("/srv/other", first).await.unwrap());
+        assert_eq!(db.list_memories("/srv/app").await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_style_examples() {
+        let db = Database::in_memory().await.unwrap();
+
+        let first = db.add_style_example("/srv/app", "add an endpoint", "Use axum", None).await.unwrap();
+        let again = db.add_style_example("/srv/app", "add an endpoint", "Use axum with a Router", None).await.unwrap();
+        db.add_style_example("/srv/other", "add an endpoint", "Use flask", None).await.unwrap();
+        assert_eq!(first, again);
+
+        db.record_style_example_uses(&[first, first]).await.unwrap();
+        let examples = db.list_style_examples("/srv/app").await.unwrap();
+        assert_eq!(examples.len(), 1);
+        assert_eq!((examples[0].response.as_str(), examples[0].uses), ("Use axum with a Router", 2));
+
+        assert!(!db.remove_style_example("/srv/other", first).await.unwrap());
+        assert!(db.remove_style_example("/srv/app", first).await.unwrap());
+        assert!(db.list_style_examples("/srv/app").await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_session_tags_files_and_search() {
+        let db = Database::in_memory().await.unwrap();
+        db.create_session(&Session::new("s1", "qwen3:8b", "qwen3:32b").with_title("Login bug"))
+            .await
+            .unwrap();
+        db.create_session(&Session::new("s2", "qwen3:8b", "qwen3:32b")).await.unwrap();
+        db.create_message(&DbMessage::new("m1", "s2", "user", "why does the 100% CI job fail?"))
+            .await
+            .unwrap();
+
+        assert!(db.tag_session("s1", "bug-123").await.unwrap());
+        assert!(!db.tag_session("s1", "bug-123").await.unwrap());
+        db.tag_session("s1", "auth").await.unwrap();
+        db.tag_session("s2", "ci").await.unwrap();
+        db.record_session_file("s1", "src/login.rs", false).await.unwrap();
+        db.record_session_file("s1", "src/login.rs", true).await.unwrap();
+        db.record_session_file("s1", "README.md", false).await.unwrap();
+
+        let ids = |sessions: Vec<Session>| sessions.into_iter().map(|s| s.id).collect::<Vec<_>>();
+        assert_eq!(ids(db.search_sessions("login", &[], 10).await.unwrap()), vec!["s1"]);
+        assert_eq!(ids(db.search_sessions("100%", &[], 10).await.unwrap()), vec!["s2"]);
+        assert_eq!(ids(db.search_sessions("", &["bug-123".to_string()], 10).await.unwrap()), vec!["s1"]);
+        assert!(db
+            .search_sessions("", &["bug-123".to_string(), "ci".to_string()], 10)
+            .await
+            .unwrap()
+            .is_empty());
+        assert_eq!(db.search_sessions("", &[], 10).await.unwrap().len(), 2);
+
+        let summary = db.get_session_summary(db.get_session("s1").await.unwrap()).await.unwrap();
+        assert_eq!(summary.tags, vec!["auth", "bug-123"]);
+        assert_eq!(summary.files[0].path, "src/login.rs");
+        assert!(summary.files[0].is_written());
+        assert!(!summary.files[1].is_written());
+        let summary = db.get_session_summary(db.get_session("s2").await.unwrap()).await.unwrap();
+        assert_eq!(summary.message_count, 1);
+        assert_eq!(summary.first_question.as_deref(), Some("why does the 100% CI job fail?"));
+
+        assert!(db.untag_session("s1", "auth").await.unwrap());
+        assert_eq!(db.get_session_tags("s1").await.unwrap(), vec!["bug-123"]);
+    }
+
+    #[tokio::test]
+    async fn test_activity_since() {
+        let db = Database::in_memory().await.unwrap();
+        let old = "2020-01-01T00:00:00+00:00";
+        let mut stale = Session::new("old", "fast", "heavy");
+        stale.created_at = old.to_string();
+        stale.updated_at = old.to_string();
+        db.create_session(&stale).await.unwrap();
+        db.create_session(&Session::new("new", "fast", "heavy")).await.unwrap();
+
+        let mut question = DbMessage::new("m1", "old", "user", "old question");
+        question.created_at = old.to_string();
+        db.create_message(&question).await.unwrap();
+        db.create_message(&DbMessage::new("m2", "new", "user", "new question")).await.unwrap();
+        db.create_message(&DbMessage::new("m3", "new", "assistant", "answer")).await.unwrap();
+
+        let mut run = CommandExecution::new("c1", "old", "cargo test");
+        run.executed_at = old.to_string();
+        db.create_command_execution(&run).await.unwrap();
+        db.create_command_execution(&CommandExecution::new("c2", "new", "cargo test")).await.unwrap();
+
+        let since = "2024-01-01T00:00:00+00:00";
+        let sessions = db.sessions_active_since(since).await.unwrap();
+        assert_eq!(sessions.into_iter().map(|s| s.id).collect::<Vec<_>>(), vec!["new"]);
+        assert_eq!(db.count_questions_since(since).await.unwrap(), 1);
+        let runs = db.command_executions_since(since).await.unwrap();
+        assert_eq!(runs.len(), 1);
+        assert_eq!(runs[0].id, "c2");
+    }
+
+    #[tokio::test]
+    async fn test_retrieval_feedback() {
+        let db = Database::in_memory().await.unwrap();
+
+        db.add_retrieval_feedback("/srv/app", "why does the CI fail", "c1", "README.md").await.unwrap();
+        db.add_retrieval_feedback("/srv/app", "why does the CI fail", "c1", "README.md").await.unwrap();
+        db.add_retrieval_feedback("/srv/app", "why does the CI fail", "c2", "README.md").await.unwrap();
+        db.add_retrieval_feedback("/srv/other", "build", "c9", "Makefile").await.unwrap();
+
+        let pairs = db.list_retrieval_feedback("/srv/app").await.unwrap();
+        let chunks: Vec<&str> = pairs.iter().map(|pair| pair.chunk_id.as_str()).collect();
+        assert_eq!(chunks, vec!["c1", "c2"]);
+    }
+
+    #[tokio::test]
+    async fn test_artifacts() {
+        let db = Database::in_memory().await.unwrap();
+        db.create_session(&Session::new("s1", "fast", "heavy")).await.unwrap();
+
+        let old = Artifact::new("s1", "output", "cargo test", "a\nb", "2 passed")
+            .with_expiry(Some("2026-01-01T00:00:00+00:00".to_string()));
+        let old = db.add_artifact(&old).await.unwrap();
+        let diff = db.add_artifact(&Artifact::new("s1", "diff", "git diff", "+x", "1 file")).await.unwrap();
+        let big = db.add_artifact(&Artifact::new("s1", "report", "/analyze", "x".repeat(100), "")).await.unwrap();
+
+        let artifact = db.get_artifact(old).await.unwrap().unwrap();
+        assert_eq!((artifact.source.as_str(), artifact.lines()), ("cargo test", 2));
+        assert_eq!(db.list_artifacts(None, 10).await.unwrap()[0].id, big);
+        assert_eq!(db.list_artifacts(Some("diff"), 10).await.unwrap().len(), 1);
+
+        // Expired, then over the size cap: the oldest unpinned goes first
+        assert!(db.pin_artifact(diff, true).await.unwrap());
+        assert_eq!(db.prune_artifacts("2026-06-01T00:00:00+00:00", 50).await.unwrap(), 2);
+        let left: Vec<i64> = db.list_artifacts(None, 10).await.unwrap().iter().map(|a| a.id).collect();
+        assert_eq!(left, vec![diff]);
+        assert!(db.delete_artifact(diff).await.unwrap());
+        assert!(!db.delete_artifact(diff).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_project_tools() {
+        let db = Database::in_memory().await.unwrap();
+
+        db.set_project_tool("/srv/app", "execute_shell", false).await.unwrap();
+        db.set_project_tool("/srv/app", "http_request", false).await.unwrap();
+        db.set_project_tool("/srv/app", "http_request", true).await.unwrap();
+        db.set_project_tool("/srv/other", "git_tool", false).await.unwrap();
+
+        let disabled = db.get_disabled_tools("/srv/app").await.unwrap();
+        assert_eq!(disabled, vec!["execute_shell".to_string()]);
+    }
+
     #[tokio::test]
     async fn test_security_config() {
         let db = Database::in_memory().await.unwrap();
@@ -0,0 +1,391 @@
+//! Embedding API
+//!
+//! [`Engine`] is the entry point for using neuro as a library: it loads the
+//! configuration (with the project's `.neuro/` overrides), connects the router
+//! to the fast and heavy models, builds the RAPTOR index and answers queries,
+//! reporting progress and streamed text through callbacks. It needs no
+//! terminal, so the `tui` feature (ratatui, crossterm and the `neuro` binary)
+//! can be left out:
+//!
+//! ```toml
+//! [dependencies]
+//! neuro = { git = "https://github.com/madkoding/neuro-agent", default-features = false }
+//! ```
+//!
+//! ```rust,no_run
+//! use neuro::{Engine, EngineEvent};
+//!
+//! # async fn example() -> Result<(), neuro::NeuroError> {
+//! let engine = Engine::builder().working_dir("path/to/project").build().await?;
+//! engine.index(|progress| eprintln!("{}", progress.description)).await?;
+//!
+//! let reply = engine
+//!     .chat_streaming("¿Dónde se clasifican las consultas?", |event| {
+//!         if let EngineEvent::Chunk(text) = event {
+//!             print!("{}", text);
+//!         }
+//!     })
+//!     .await?;
+//! println!("\n({})", reply.model.unwrap_or_default());
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! An engine handles one request at a time; concurrent calls wait their turn.
+//! [`Engine::interrupt`] stops the running one from another task.
+
+use crate::agent::orchestrator::{OrchestratorConfig, OrchestratorError};
+use crate::agent::{
+    AgentEvent, DualModelOrchestrator, OrchestratorResponse, ProgressUpdate, RouterConfig, RouterOrchestrator,
+    StructuredResponse, Subscription, TaskProgressInfo, Topic,
+};
+use crate::config::{AppConfig, ProjectConfig};
+use crate::db::{Database, Session, SessionStore};
+use crate::error::NeuroError;
+use std::path::{Path, PathBuf};
+use tokio::sync::{mpsc, Mutex};
+use tokio_util::sync::CancellationToken;
+
+/// Events queued between the router and the running request
+const EVENT_BUFFER: usize = 256;
+/// Index progress updates queued for the `index` callback
+const PROGRESS_BUFFER: usize = 64;
+
+/// What a request reports while it runs
+#[derive(Debug, Clone)]
+pub enum EngineEvent {
+    /// Short status line ("Analizando repositorio...")
+    Status(String),
+    /// Stage of the request with elapsed time
+    Progress(ProgressUpdate),
+    /// Next piece of a streamed answer
+    Chunk(String),
+    /// Next piece of the model's reasoning, which is not part of the answer
+    Thinking(String),
+}
+
+/// Final answer of a request
+#[derive(Debug, Clone)]
+pub struct Reply {
+    /// Answer text; for streamed answers, every chunk joined
+    pub text: String,
+    /// Model that answered, when known
+    pub model: Option<String>,
+    /// A tool call waits for [`Engine::confirm`] or [`Engine::cancel`]
+    pub needs_confirmation: bool,
+    /// Response as returned by the router, with the tool calls, citations,
+    /// token usage and timing of the turn
+    pub structured: StructuredResponse,
+}
+
+impl Reply {
+    fn from_structured(structured: StructuredResponse) -> Result<Self, NeuroError> {
+        let model = structured.model.clone();
+        let (text, needs_confirmation) = match &structured.response {
+            OrchestratorResponse::Immediate { content, .. } => (content.clone(), false),
+            OrchestratorResponse::Text(text) => (text.clone(), false),
+            OrchestratorResponse::ToolResult { result, .. } => (result.clone(), false),
+            OrchestratorResponse::NeedsConfirmation { command, risk_level } => {
+                (format!("{} (riesgo: {})", command, risk_level), true)
+            }
+            OrchestratorResponse::Delegated { description, .. }
+            | OrchestratorResponse::TaskStarted { description, .. } => (description.clone(), false),
+            OrchestratorResponse::Streaming { .. } => (String::new(), false),
+            OrchestratorResponse::Error(message) => return Err(NeuroError::Internal(message.clone())),
+        };
+        Ok(Self { text, model, needs_confirmation, structured })
+    }
+}
+
+/// Builder of an [`Engine`]
+#[derive(Default)]
+pub struct EngineBuilder {
+    config: Option<AppConfig>,
+    config_path: Option<PathBuf>,
+    working_dir: Option<PathBuf>,
+    database: Option<Database>,
+}
+
+impl EngineBuilder {
+    /// Use `config` instead of loading the config file
+    pub fn config(mut self, config: AppConfig) -> Self {
+        self.config = Some(config);
+        self
+    }
+
+    /// Load the config from `path` instead of the default locations
+    pub fn config_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.config_path = Some(path.into());
+        self
+    }
+
+    /// Project the engine works on (default: the current directory)
+    pub fn working_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.working_dir = Some(dir.into());
+        self
+    }
+
+    /// Keep a session in `database`: per-project `/env` variables and tool switches
+    pub fn database(mut self, database: Database) -> Self {
+        self.database = Some(database);
+        self
+    }
+
+    /// Load and validate the config, connect to the model provider and set up the router
+    pub async fn build(self) -> Result<Engine, NeuroError> {
+        let working_dir = match self.working_dir {
+            Some(dir) => dir,
+            None => std::env::current_dir()?,
+        };
+        let config = match self.config {
+            Some(config) => config,
+            None => AppConfig::load(self.config_path.as_deref())?,
+        };
+        let config = ProjectConfig::discover(&working_dir)?.apply(&config)?;
+        config.validate()?;
+
+        // Process-wide policies, as the binary sets them at startup
+        crate::security::privacy::set_privacy_level(config.privacy);
+        crate::security::network::set_network_policy(config.network.clone());
+        crate::agent::webhooks::set_webhooks(config.webhooks.clone());
+        crate::agent::reasoning::set_config(config.reasoning.clone());
+        crate::raptor::intent::set_config(config.intents.clone());
+        crate::agent::dry_run::set_enabled(config.dry_run);
+        crate::agent::stream_watchdog::set_stall_secs(config.stream_stall_secs);
+        if config.language.is_some() {
+            crate::i18n::init_locale_with(crate::i18n::Locale::from_config(config.language.as_deref()));
+        }
+
+        let mut orchestrator =
+            DualModelOrchestrator::with_config(OrchestratorConfig::from_app_config(&config)).await?;
+        for error in orchestrator.apply_tool_config(&config, &working_dir) {
+            tracing::warn!("Skipping WASM plugin: {}", error);
+        }
+        let router = RouterOrchestrator::new(RouterConfig::from_app_config(&config, &working_dir), orchestrator).await?;
+
+        if let Some(db) = self.database {
+            let session = Session::new(
+                uuid::Uuid::new_v4().to_string(),
+                &config.fast_model.model,
+                &config.heavy_model.model,
+            )
+            .with_working_dir(working_dir.to_string_lossy());
+            db.create_session(&session)
+                .await
+                .map_err(|e| NeuroError::Internal(e.to_string()))?;
+            router.attach_session(SessionStore { db, session_id: session.id }).await?;
+        }
+
+        let events = router
+            .event_bus()
+            .subscribe("engine", EVENT_BUFFER, &[Topic::Turn, Topic::Progress, Topic::Stream]);
+
+        Ok(Engine {
+            router,
+            config,
+            working_dir,
+            events: Mutex::new(events),
+            cancel: Default::default(),
+        })
+    }
+}
+
+/// neuro as a library: config, router, index and chat behind one handle
+pub struct Engine {
+    router: RouterOrchestrator,
+    config: AppConfig,
+    working_dir: PathBuf,
+    /// Held for the whole of a request, which also serializes requests
+    events: Mutex<Subscription>,
+    /// Token of the running request or index build
+    cancel: std::sync::Mutex<CancellationToken>,
+}
+
+impl Engine {
+    pub fn builder() -> EngineBuilder {
+        EngineBuilder::default()
+    }
+
+    /// Effective config, with the project overrides applied
+    pub fn config(&self) -> &AppConfig {
+        &self.config
+    }
+
+    pub fn working_dir(&self) -> &Path {
+        &self.working_dir
+    }
+
+    /// The router underneath, for what the facade does not cover
+    pub fn router(&self) -> &RouterOrchestrator {
+        &self.router
+    }
+
+    /// Build the RAPTOR index of the working directory, waiting for it to
+    /// finish; `false` when there was nothing to index
+    pub async fn index(&self, mut on_progress: impl FnMut(&TaskProgressInfo)) -> Result<bool, NeuroError> {
+        let (tx, mut rx) = mpsc::channel(PROGRESS_BUFFER);
+        let build = self.router.initialize_raptor_with_progress(Some(tx), self.start_cancellable());
+        let report = async {
+            while let Some(progress) = rx.recv().await {
+                on_progress(&progress);
+            }
+        };
+        let (built, ()) = tokio::join!(build, report);
+        Ok(built?)
+    }
+
+    /// Whether the full RAPTOR index is ready
+    pub fn is_indexed(&self) -> bool {
+        self.router.is_raptor_ready()
+    }
+
+    /// Context the index retrieves for `question`, without asking the model
+    pub async fn query(&self, question: &str) -> Result<String, NeuroError> {
+        Ok(self.router.retrieval_context(question).await?)
+    }
+
+    /// Answer `message` (a question, a task or a slash command)
+    pub async fn chat(&self, message: &str) -> Result<Reply, NeuroError> {
+        self.chat_streaming(message, |_| {}).await
+    }
+
+    /// Answer `message`, reporting status, progress and streamed text to `on_event`
+    pub async fn chat_streaming(
+        &self,
+        message: &str,
+        mut on_event: impl FnMut(EngineEvent),
+    ) -> Result<Reply, NeuroError> {
+        let mut events = self.events.lock().await;
+        // Leftovers of background work (e.g. an index rebuild) between requests
+        events.clear();
+
+        let cancel = self.start_cancellable();
+        let request = self.router.process_structured(message, cancel.clone());
+        tokio::pin!(request);
+        let response = loop {
+            tokio::select! {
+                response = &mut request => break response?,
+                Some(event) = events.recv() => {
+                    if let Some(error) = forward(event, &mut on_event, &mut String::new()) {
+                        return Err(error);
+                    }
+                }
+            }
+        };
+
+        let mut reply = Reply::from_structured(response)?;
+        if matches!(reply.structured.response, OrchestratorResponse::Streaming { .. }) {
+            // The answer keeps coming as events until the end of the stream
+            loop {
+                let event = tokio::select! {
+                    biased;
+                    _ = cancel.cancelled() => return Err(OrchestratorError::Cancelled.into()),
+                    event = events.recv() => event,
+                };
+                let Some(event) = event else { break };
+                if matches!(event, AgentEvent::StreamEnd) {
+                    break;
+                }
+                if let AgentEvent::StreamAborted { reason, .. } = event {
+                    // The chunks already carried the partial answer
+                    on_event(EngineEvent::Status(format!("La respuesta se cortó: {}", reason)));
+                    break;
+                }
+                if let Some(error) = forward(event, &mut on_event, &mut reply.text) {
+                    return Err(error);
+                }
+            }
+            reply.model = Some(self.config.heavy_model.model.clone());
+        } else {
+            while let Ok(event) = events.try_recv() {
+                forward(event, &mut on_event, &mut String::new());
+            }
+        }
+        Ok(reply)
+    }
+
+    /// Run the tool call a previous reply asked to confirm
+    pub async fn confirm(&self) -> Result<Reply, NeuroError> {
+        let cancel = self.start_cancellable();
+        Reply::from_structured(StructuredResponse::plain(self.router.confirm_pending(cancel).await?))
+    }
+
+    /// Drop the tool call a previous reply asked to confirm
+    pub async fn cancel(&self) {
+        self.router.cancel_pending().await;
+    }
+
+    /// Stop the running request or index build: model requests are aborted,
+    /// tool subprocesses killed and the call fails with a cancellation error.
+    /// Safe to call from another task while the request runs.
+    pub fn interrupt(&self) {
+        self.cancel.lock().unwrap_or_else(|e| e.into_inner()).cancel();
+    }
+
+    /// Fresh token for the work about to start
+    fn start_cancellable(&self) -> CancellationToken {
+        let token = CancellationToken::new();
+        *self.cancel.lock().unwrap_or_else(|e| e.into_inner()) = token.clone();
+        token
+    }
+}
+
+/// Pass `event` on to the caller, collecting streamed text in `text`; errors are returned
+fn forward(event: AgentEvent, on_event: &mut impl FnMut(EngineEvent), text: &mut String) -> Option<NeuroError> {
+    match event {
+        AgentEvent::Status(status) | AgentEvent::RaptorStatus(status) => on_event(EngineEvent::Status(status)),
+        AgentEvent::Progress(update) => on_event(EngineEvent::Progress(update)),
+        AgentEvent::Chunk(chunk) => {
+            text.push_str(&chunk);
+            on_event(EngineEvent::Chunk(chunk));
+        }
+        AgentEvent::Thinking(thinking) => on_event(EngineEvent::Thinking(thinking)),
+        AgentEvent::Error(error) => return Some(error),
+        AgentEvent::Response(Err(error)) => return Some(error),
+        _ => {}
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reply_from_response() {
+        let reply = Reply::from_structured(StructuredResponse::plain(OrchestratorResponse::Immediate {
+            content: "hola".to_string(),
+            model: "qwen3:8b".to_string(),
+        }))
+        .unwrap();
+        assert_eq!((reply.text.as_str(), reply.model.as_deref()), ("hola", Some("qwen3:8b")));
+
+        let confirm = Reply::from_structured(StructuredResponse::plain(OrchestratorResponse::NeedsConfirmation {
+            command: "rm -rf target".to_string(),
+            risk_level: "alto".to_string(),
+        }))
+        .unwrap();
+        assert!(confirm.needs_confirmation);
+
+        assert!(Reply::from_structured(StructuredResponse::plain(OrchestratorResponse::Error("timeout".to_string()))).is_err());
+    }
+
+    #[test]
+    fn test_forward_collects_chunks() {
+        let mut seen = Vec::new();
+        let mut text = String::new();
+        for event in [
+            AgentEvent::Status("Analizando...".to_string()),
+            AgentEvent::Chunk("Hola ".to_string()),
+            AgentEvent::Chunk("mundo".to_string()),
+            AgentEvent::StreamEnd,
+        ] {
+            assert!(forward(event, &mut |event| seen.push(event), &mut text).is_none());
+        }
+        assert_eq!(text, "Hola mundo");
+        assert_eq!(seen.len(), 3);
+
+        let error = forward(AgentEvent::Error(NeuroError::Tool("boom".to_string())), &mut |_| {}, &mut text);
+        assert_eq!(error, Some(NeuroError::Tool("boom".to_string())));
+    }
+}
@@ -0,0 +1,274 @@
+//! Typed error taxonomy shared by the orchestrators, the TUI and the JSON APIs
+//!
+//! Subsystems keep their own error enums; at the boundary towards the user
+//! they are folded into [`NeuroError`], which knows its category, a stable
+//! machine-readable code and, when possible, a hint on how to fix it.
+
+use crate::agent::orchestrator::OrchestratorError;
+use crate::agent::provider::ProviderError;
+use crate::config::ConfigError;
+use serde_json::{json, Value};
+use thiserror::Error;
+
+/// Broad family an error belongs to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorCategory {
+    Provider,
+    Tool,
+    Index,
+    Config,
+    Io,
+    Internal,
+}
+
+impl ErrorCategory {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ErrorCategory::Provider => "provider",
+            ErrorCategory::Tool => "tool",
+            ErrorCategory::Index => "index",
+            ErrorCategory::Config => "config",
+            ErrorCategory::Io => "io",
+            ErrorCategory::Internal => "internal",
+        }
+    }
+}
+
+/// User-facing error
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum NeuroError {
+    #[error("Model provider unreachable: {0}")]
+    ProviderUnreachable(String),
+
+    #[error("Model provider rejected the credentials: {0}")]
+    ProviderAuth(String),
+
+    #[error("Model provider timed out: {0}")]
+    ProviderTimeout(String),
+
+    #[error("Model provider error: {0}")]
+    Provider(String),
+
+    #[error("Tool error: {0}")]
+    Tool(String),
+
+    #[error("Index error: {0}")]
+    Index(String),
+
+    #[error("Configuration error: {0}")]
+    Config(String),
+
+    #[error("I/O error: {0}")]
+    Io(String),
+
+    #[error("{0}")]
+    Internal(String),
+}
+
+impl NeuroError {
+    pub fn category(&self) -> ErrorCategory {
+        match self {
+            NeuroError::ProviderUnreachable(_)
+            | NeuroError::ProviderAuth(_)
+            | NeuroError::ProviderTimeout(_)
+            | NeuroError::Provider(_) => ErrorCategory::Provider,
+            NeuroError::Tool(_) => ErrorCategory::Tool,
+            NeuroError::Index(_) => ErrorCategory::Index,
+            NeuroError::Config(_) => ErrorCategory::Config,
+            NeuroError::Io(_) => ErrorCategory::Io,
+            NeuroError::Internal(_) => ErrorCategory::Internal,
+        }
+    }
+
+    /// Stable code for JSON consumers
+    pub fn code(&self) -> &'static str {
+        match self {
+            NeuroError::ProviderUnreachable(_) => "provider_unreachable",
+            NeuroError::ProviderAuth(_) => "provider_auth",
+            NeuroError::ProviderTimeout(_) => "provider_timeout",
+            NeuroError::Provider(_) => "provider_error",
+            NeuroError::Tool(_) => "tool_failed",
+            NeuroError::Index(_) => "index_error",
+            NeuroError::Config(_) => "config_invalid",
+            NeuroError::Io(_) => "io_error",
+            NeuroError::Internal(_) => "internal",
+        }
+    }
+
+    /// What the user can do about it, shown below the error in the TUI
+    pub fn hint(&self) -> Option<&'static str> {
+        match self {
+            NeuroError::ProviderUnreachable(_) => {
+                Some("¿Ollama está corriendo? Ejecuta `ollama serve` o revisa la URL del proveedor")
+            }
+            NeuroError::ProviderAuth(_) => {
+                Some("Revisa la API key del proveedor en la configuración o en las variables de entorno")
+            }
+            NeuroError::ProviderTimeout(_) => {
+                Some("El modelo tardó demasiado: prueba un modelo más pequeño o aumenta heavy_timeout_secs")
+            }
+            NeuroError::Provider(_) => {
+                Some("Verifica que el modelo esté descargado (`ollama pull <modelo>`)")
+            }
+            NeuroError::Index(_) => Some("Reconstruye el índice con /reindex"),
+            NeuroError::Config(_) => Some("Revisa ~/.config/neuro/config.production.json"),
+            NeuroError::Tool(_) | NeuroError::Io(_) | NeuroError::Internal(_) => None,
+        }
+    }
+
+    /// Machine-readable representation (`code`, `category`, `message`, `hint`)
+    pub fn to_json(&self) -> Value {
+        json!({
+            "code": self.code(),
+            "category": self.category().as_str(),
+            "message": self.to_string(),
+            "hint": self.hint(),
+        })
+    }
+
+    /// Classify an `anyhow` error by looking for a known error type in its chain
+    pub fn from_anyhow(err: &anyhow::Error) -> Self {
+        for cause in err.chain() {
+            if let Some(e) = cause.downcast_ref::<NeuroError>() {
+                return e.clone();
+            }
+            if let Some(e) = cause.downcast_ref::<OrchestratorError>() {
+                return Self::from_orchestrator(e);
+            }
+            if let Some(e) = cause.downcast_ref::<ProviderError>() {
+                return Self::from_provider(e);
+            }
+            if let Some(e) = cause.downcast_ref::<reqwest::Error>() {
+                return Self::from_reqwest(e);
+            }
+            if let Some(e) = cause.downcast_ref::<ConfigError>() {
+                return NeuroError::Config(e.to_string());
+            }
+            if let Some(e) = cause.downcast_ref::<std::io::Error>() {
+                return NeuroError::Io(e.to_string());
+            }
+        }
+
+        // Model clients often flatten transport errors into strings
+        let message = format!("{:#}", err);
+        let lower = message.to_lowercase();
+        if lower.contains("connection refused")
+            || lower.contains("error sending request")
+            || lower.contains("tcp connect")
+        {
+            NeuroError::ProviderUnreachable(message)
+        } else {
+            NeuroError::Internal(message)
+        }
+    }
+
+    fn from_orchestrator(err: &OrchestratorError) -> Self {
+        match err {
+            OrchestratorError::ConnectionError(msg) => NeuroError::ProviderUnreachable(msg.clone()),
+            OrchestratorError::ModelError(msg) => NeuroError::Provider(msg.clone()),
+            OrchestratorError::Timeout(secs) => NeuroError::ProviderTimeout(format!("{}s", secs)),
+            OrchestratorError::ToolError(msg) => NeuroError::Tool(msg.clone()),
+            OrchestratorError::Cancelled | OrchestratorError::ClassificationError(_) => {
+                NeuroError::Internal(err.to_string())
+            }
+        }
+    }
+
+    fn from_provider(err: &ProviderError) -> Self {
+        match err {
+            ProviderError::ConnectionError(msg) => NeuroError::ProviderUnreachable(msg.clone()),
+            ProviderError::AuthError(msg) => NeuroError::ProviderAuth(msg.clone()),
+            ProviderError::Timeout => NeuroError::ProviderTimeout(err.to_string()),
+            ProviderError::HttpError(e) => Self::from_reqwest(e),
+            ProviderError::Blocked(_) => NeuroError::Config(err.to_string()),
+            ProviderError::ModelError(_)
+            | ProviderError::InvalidResponse(_)
+            | ProviderError::JsonError(_) => NeuroError::Provider(err.to_string()),
+        }
+    }
+
+    fn from_reqwest(err: &reqwest::Error) -> Self {
+        if err.is_connect() {
+            NeuroError::ProviderUnreachable(err.to_string())
+        } else if err.is_timeout() {
+            NeuroError::ProviderTimeout(err.to_string())
+        } else if matches!(err.status(), Some(s) if s.as_u16() == 401 || s.as_u16() == 403) {
+            NeuroError::ProviderAuth(err.to_string())
+        } else {
+            NeuroError::Provider(err.to_string())
+        }
+    }
+}
+
+impl From<OrchestratorError> for NeuroError {
+    fn from(err: OrchestratorError) -> Self {
+        Self::from_orchestrator(&err)
+    }
+}
+
+impl From<ProviderError> for NeuroError {
+    fn from(err: ProviderError) -> Self {
+        Self::from_provider(&err)
+    }
+}
+
+impl From<ConfigError> for NeuroError {
+    fn from(err: ConfigError) -> Self {
+        NeuroError::Config(err.to_string())
+    }
+}
+
+impl From<std::io::Error> for NeuroError {
+    fn from(err: std::io::Error) -> Self {
+        NeuroError::Io(err.to_string())
+    }
+}
+
+impl From<anyhow::Error> for NeuroError {
+    fn from(err: anyhow::Error) -> Self {
+        Self::from_anyhow(&err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_orchestrator_errors_are_categorized() {
+        let err = NeuroError::from(OrchestratorError::ConnectionError("refused".into()));
+        assert_eq!(err.category(), ErrorCategory::Provider);
+        assert_eq!(err.code(), "provider_unreachable");
+        assert!(err.hint().unwrap().contains("ollama serve"));
+
+        let err = NeuroError::from(OrchestratorError::ToolError("boom".into()));
+        assert_eq!(err.category(), ErrorCategory::Tool);
+    }
+
+    #[test]
+    fn test_from_anyhow_finds_typed_cause() {
+        let err = anyhow::Error::from(ProviderError::AuthError("bad key".into()))
+            .context("calling model");
+        assert_eq!(NeuroError::from_anyhow(&err).code(), "provider_auth");
+
+        let err = anyhow::Error::from(NeuroError::Index("missing".into())).context("query");
+        assert_eq!(NeuroError::from_anyhow(&err), NeuroError::Index("missing".into()));
+    }
+
+    #[test]
+    fn test_from_anyhow_falls_back_on_message() {
+        let err = anyhow::anyhow!("error sending request for url (http://localhost:11434)");
+        assert_eq!(NeuroError::from(err).code(), "provider_unreachable");
+
+        let err = anyhow::anyhow!("something else");
+        assert_eq!(NeuroError::from(err).category(), ErrorCategory::Internal);
+    }
+
+    #[test]
+    fn test_to_json() {
+        let value = NeuroError::Config("missing model".into()).to_json();
+        assert_eq!(value["code"], "config_invalid");
+        assert_eq!(value["category"], "config");
+        assert!(value["message"].as_str().unwrap().contains("missing model"));
+    }
+}
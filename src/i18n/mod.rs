@@ -27,6 +27,15 @@ impl Locale {
         }
     }
 
+    /// Locale for the `language` config value, falling back to the system locale
+    pub fn from_config(language: Option<&str>) -> Self {
+        match language {
+            Some("es" | "español" | "spanish") => Locale::Spanish,
+            Some("en" | "english" | "inglés") => Locale::English,
+            _ => Locale::detect(),
+        }
+    }
+
     /// Get the locale code for LLM prompts
     pub fn code(&self) -> &'static str {
         match self {
@@ -17,12 +17,33 @@
 //! - [`agent`] - Orquestación de modelos y routing inteligente
 //! - [`tools`] - Herramientas para análisis, búsqueda, refactoring, etc.
 //! - [`raptor`] - Sistema RAPTOR para RAG mejorado
-//! - [`ui`] - Interfaz TUI moderna con ratatui
+//! - [`engine`] - Fachada [`Engine`] para usar neuro como biblioteca
+//! - [`ui`] - Interfaz TUI moderna con ratatui (feature `tui`)
 //! - [`db`] - Persistencia de sesiones e índices
 //!
+//! # Features
+//!
+//! - `tui` (por defecto): interfaz de terminal y binario `neuro`. Con
+//!   `default-features = false` la crate no depende de ratatui ni crossterm y
+//!   se usa a través de [`Engine`].
+//!
 //! # Ejemplo de Uso
 //!
 //! ```rust,no_run
+//! use neuro::Engine;
+//!
+//! # async fn example() -> Result<(), neuro::NeuroError> {
+//! let engine = Engine::builder().working_dir(".").build().await?;
+//! engine.index(|_| {}).await?;
+//! let reply = engine.chat("¿qué hace el router?").await?;
+//! println!("{}", reply.text);
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! El orquestador también se puede usar directamente:
+//!
+//! ```rust,no_run
 //! use neuro::agent::orchestrator::{DualModelOrchestrator, OrchestratorConfig};
 //!
 //! # async fn example() -> anyhow::Result<()> {
@@ -44,8 +65,11 @@ pub mod agent;
 pub mod ast;
 pub mod config;
 pub mod context;
+pub mod crash;
 pub mod db;
 pub mod embedding;
+pub mod engine;
+pub mod error;
 pub mod i18n;
 pub mod logging;
 pub mod mcp;
@@ -60,9 +84,12 @@ pub mod raptor;
 pub use agent::orchestrator::DualModelOrchestrator;
 pub use context::ContextManager;
 pub use db::Database;
+pub use engine::{Engine, EngineBuilder, EngineEvent, Reply};
+pub use error::NeuroError;
 pub use i18n::{current_locale, init_locale, t, Locale, Text};
 pub use mcp::NeuroMcpServer;
 pub use raptor::retriever::TreeRetriever;
 pub use raptor::summarizer::SummaryNode;
 pub use security::CommandScanner;
+#[cfg(feature = "tui")]
 pub use ui::ModernApp;
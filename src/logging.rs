@@ -83,6 +83,9 @@ pub fn log(level: &str, message: &str) {
         message
     );
 
+    // Kept for the crash report
+    crate::crash::record_event(format!("[{}] {}: {}", timestamp, level, message));
+
     // Write to file (always enabled)
     let mut log_file = LOG_FILE.lock().unwrap();
     if let Some(ref mut f) = *log_file {
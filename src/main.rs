@@ -5,20 +5,38 @@
 
 #![allow(deprecated)]
 
-use clap::Parser;
+use clap::{CommandFactory, Parser};
 use directories::ProjectDirs;
 use neuro::{
-    agent::{DualModelOrchestrator, RouterOrchestrator, RouterConfig},
-    db::Database,
+    agent::{
+        ActivityReport, AgentEvent, DualModelOrchestrator, EventBus, RouterOrchestrator, RouterConfig, TokenUsage, Topic,
+    },
+    config::{ConfigWatcher, ProjectConfig},
+    db::{Database, Session, SessionStore},
     i18n::{init_locale, init_locale_with, Locale},
-    ui::ModernApp,
+    tools::{DependencyDocsTool, DEFAULT_TOP_DEPENDENCIES},
+    ui::{history_archive::HistoryArchive, AccessibilityConfig, ModernApp, SimpleRepl},
     log_error, log_info, logging,
 };
+use std::io::Write;
 use std::path::PathBuf;
 use std::sync::Arc;
 use tokio::sync::Mutex;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
+/// Events buffered between the model stream and stdout (`raptor query --stream`)
+const STREAM_BUFFER: usize = 256;
+
+/// How one-shot modes print their result
+#[derive(clap::ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+enum OutputFormat {
+    /// Human-readable text
+    #[default]
+    Text,
+    /// A single JSON object on stdout
+    Json,
+}
+
 /// Neuro - AI Programming Assistant CLI
 
 #[derive(clap::Subcommand, Debug)]
@@ -28,6 +46,26 @@ enum Command {
         #[command(subcommand)]
         cmd: RaptorCmd,
     },
+    /// Summarize recent activity as Markdown: questions, files, commits, tests fixed
+    Report {
+        /// Period to cover: 7d, 24h, 2w...
+        #[arg(long, default_value = "7d")]
+        since: String,
+        /// Write the report to this file instead of stdout
+        #[arg(long)]
+        output: Option<PathBuf>,
+    },
+    /// Print the shell completion script
+    Completions {
+        /// Shell to complete for
+        shell: clap_complete::Shell,
+    },
+    /// Print the man page, or write one page per subcommand to a directory
+    Man {
+        /// Directory for neuro.1, neuro-raptor.1, ...
+        #[arg(long)]
+        out_dir: Option<PathBuf>,
+    },
 }
 
 #[derive(clap::Subcommand, Debug)]
@@ -56,7 +94,12 @@ enum RaptorCmd {
         /// Number of chunks to expand for context
         #[arg(long, default_value_t = 5)]
         expand_k: usize,
-
+        /// Print the answer as the model writes it instead of all at the end
+        #[arg(long, conflicts_with = "output")]
+        stream: bool,
+        /// Print summaries, chunks, scores, the answer and token usage as JSON
+        #[arg(long, value_enum, default_value_t)]
+        output: OutputFormat,
     },
 }
 
@@ -98,10 +141,22 @@ struct Args {
     #[arg(long)]
     debug: bool,
 
-    /// Skip TUI and run in simple mode
+    /// Skip the TUI: read requests from stdin and print answers to stdout (SSH, CI)
     #[arg(long)]
     simple: bool,
 
+    /// Answer a single request, print it to stdout and exit (non-zero on error)
+    #[arg(short, long, value_name = "QUESTION", conflicts_with = "simple")]
+    prompt: Option<String>,
+
+    /// With --prompt, also print the tool calls of the turn and their output
+    #[arg(long, requires = "prompt")]
+    show_tools: bool,
+
+    /// With --prompt, print the answer, tool calls and token usage as JSON
+    #[arg(long, value_enum, default_value_t, requires = "prompt")]
+    output: OutputFormat,
+
     #[command(subcommand)]
     command: Option<Command>,
 }
@@ -110,6 +165,25 @@ struct Args {
 async fn main() -> anyhow::Result<()> {
     let args = Args::parse();
 
+    // Generated from the clap definitions, before any startup work
+    match &args.command {
+        Some(Command::Completions { shell }) => {
+            clap_complete::generate(*shell, &mut Args::command(), "neuro", &mut std::io::stdout());
+            return Ok(());
+        }
+        Some(Command::Man { out_dir }) => {
+            match out_dir {
+                Some(dir) => {
+                    std::fs::create_dir_all(dir)?;
+                    write_man_pages(Args::command(), dir)?;
+                }
+                None => clap_mangen::Man::new(Args::command()).render(&mut std::io::stdout())?,
+            }
+            return Ok(());
+        }
+        _ => {}
+    }
+
     // Get database path
     let db_path = args.db_path.unwrap_or_else(|| {
         ProjectDirs::from("com", "neuro", "neuro")
@@ -119,25 +193,61 @@ async fn main() -> anyhow::Result<()> {
 
     // Initialize database
     tracing::info!("Initializing database at {:?}", db_path);
-    let _db = Database::new(&db_path).await?;
+    let db = Database::new(&db_path).await?;
+
+    // Reports only read the session database: no model connection needed
+    if let Some(Command::Report { since, output }) = &args.command {
+        let period = neuro::agent::activity_report::parse_since(since)?;
+        let report = ActivityReport::collect(&db, chrono::Utc::now() - period).await?;
+        match output {
+            Some(path) => std::fs::write(path, report.to_markdown())?,
+            None => print!("{}", report.to_markdown()),
+        }
+        return Ok(());
+    }
 
     // Load configuration
     let mut app_config = neuro::config::AppConfig::load(args.config.as_deref())?;
+
+    // Get working directory
+    let working_dir = args
+        .dir
+        .clone()
+        .unwrap_or_else(|| std::env::current_dir().unwrap_or_else(|_| PathBuf::from(".")));
+
+    // Shared `.neuro/` overrides committed to the project, then the local ones
+    let project_config = ProjectConfig::discover(&working_dir)?;
+    app_config = project_config.apply(&app_config)?;
     
     // Initialize logging (now that we have config)
-    init_logging(args.verbose, !args.simple, app_config.debug);
+    init_logging(args.verbose, !args.simple && args.prompt.is_none(), app_config.debug);
+    for setting in project_config.ignored_settings() {
+        neuro::log_warn!("⚠️ Project config {} ignored: only the personal config may set it", setting);
+    }
     
     // Initialize locale based on configuration
-    if let Some(ref lang) = app_config.language {
-        let locale = match lang.as_str() {
-            "es" | "español" | "spanish" => Locale::Spanish,
-            "en" | "english" | "inglés" => Locale::English,
-            _ => Locale::detect(),
-        };
-        init_locale_with(locale);
+    if app_config.language.is_some() {
+        init_locale_with(Locale::from_config(app_config.language.as_deref()));
     } else {
         init_locale();
     }
+
+    // What may leave the machine, enforced by the providers and the HTTP tool
+    neuro::security::privacy::set_privacy_level(app_config.privacy);
+    neuro::security::network::set_network_policy(app_config.network.clone());
+    neuro::agent::webhooks::set_webhooks(app_config.webhooks.clone());
+    neuro::agent::reasoning::set_config(app_config.reasoning.clone());
+    neuro::raptor::intent::set_config(app_config.intents.clone());
+    neuro::agent::dry_run::set_enabled(app_config.dry_run);
+    neuro::agent::stream_watchdog::set_stall_secs(app_config.stream_stall_secs);
+
+    // Give the terminal back and leave a crash report on panics and signals
+    neuro::crash::set_config(&app_config);
+    neuro::crash::install_panic_hook();
+    neuro::crash::install_signal_handlers();
+
+    // Config as saved in the file, before CLI overrides, for hot reload
+    let file_config = app_config.clone();
     
     // Apply CLI overrides (for backward compatibility)
     if let Some(url) = args.ollama_url {
@@ -158,6 +268,9 @@ async fn main() -> anyhow::Result<()> {
     // Validate configuration
     app_config.validate()?;
 
+    // Background housekeeping (artifact retention)
+    neuro::agent::MaintenanceScheduler::new(db.clone(), app_config.artifacts.clone()).spawn();
+
     // Initialize orchestrator (using old OrchestratorConfig for now - will refactor later)
     tracing::info!(
         "Connecting to {} at {}",
@@ -165,13 +278,7 @@ async fn main() -> anyhow::Result<()> {
         app_config.fast_model.url
     );
     
-    let config = neuro::agent::orchestrator::OrchestratorConfig {
-        ollama_url: app_config.fast_model.url.clone(),
-        fast_model: app_config.fast_model.model.clone(),
-        heavy_model: app_config.heavy_model.model.clone(),
-        heavy_timeout_secs: app_config.heavy_timeout_secs,
-        max_concurrent_heavy: app_config.max_concurrent_heavy,
-    };
+    let config = neuro::agent::orchestrator::OrchestratorConfig::from_app_config(&app_config);
 
     // Test connection first
     let _test_orch = match DualModelOrchestrator::with_config(config.clone()).await {
@@ -188,11 +295,6 @@ async fn main() -> anyhow::Result<()> {
         }
     };
 
-    // Get working directory
-    let working_dir = args
-        .dir
-        .unwrap_or_else(|| std::env::current_dir().unwrap_or_else(|_| PathBuf::from(".")));
-
     // If a subcommand was provided, handle it and exit
     if let Some(cmd) = args.command {
         // Create orchestrator for subcommands
@@ -200,6 +302,9 @@ async fn main() -> anyhow::Result<()> {
         let dual_arc = Arc::new(Mutex::new(dual_orchestrator));
         
         match cmd {
+            Command::Completions { .. } | Command::Man { .. } | Command::Report { .. } => {
+                unreachable!("handled before startup")
+            }
             Command::Raptor { cmd } => match cmd {
                 RaptorCmd::Build {
                     path,
@@ -223,6 +328,8 @@ async fn main() -> anyhow::Result<()> {
                     text,
                     top_k,
                     expand_k,
+                    stream,
+                    output,
                 } => {
                     log_info!("Query: {}", text);
                     // Build retriever and run query
@@ -240,17 +347,19 @@ async fn main() -> anyhow::Result<()> {
                         .retrieve_with_context(&text, top_k, expand_k)
                         .await?;
 
-                    println!("Top summaries:");
-                    for (id, score, summary) in summaries.iter() {
-                        println!("- {} (score: {:.3})", id, score);
-                        println!("  summary: {}", summary);
-                    }
-
-                    if !chunks.is_empty() {
-                        println!("Top chunks (fallback):");
-                        for (id, score, chunk) in chunks.iter() {
+                    if output == OutputFormat::Text {
+                        println!("Top summaries:");
+                        for (id, score, summary) in summaries.iter() {
                             println!("- {} (score: {:.3})", id, score);
-                            println!("  chunk: {}", chunk);
+                            println!("  summary: {}", summary);
+                        }
+
+                        if !chunks.is_empty() {
+                            println!("Top chunks (fallback):");
+                            for (id, score, chunk) in chunks.iter() {
+                                println!("- {} (score: {:.3})", id, score);
+                                println!("  chunk: {}", chunk);
+                            }
                         }
                     }
 
@@ -266,12 +375,26 @@ async fn main() -> anyhow::Result<()> {
                     }
 
                     let prompt = format!("Usando este contexto:\n{}\nRESPONDE: {}", context, text);
-                    let answer = dual_arc
-                        .lock()
-                        .await
-                        .call_heavy_model_direct(&prompt)
-                        .await?;
-                    println!("Respuesta: {}", answer);
+                    if stream {
+                        print!("Respuesta: ");
+                        stream_answer(&*dual_arc.lock().await, &prompt).await?;
+                        return Ok(());
+                    }
+                    let dual = dual_arc.lock().await;
+                    let answer = dual.call_heavy_model_direct(&prompt).await?;
+                    if output == OutputFormat::Json {
+                        let result = QueryOutput {
+                            query: &text,
+                            summaries: summaries.iter().map(ScoredText::from).collect(),
+                            chunks: chunks.iter().map(ScoredText::from).collect(),
+                            answer: &answer,
+                            model: &config.heavy_model,
+                            usage: dual.take_turn_record().usage,
+                        };
+                        println!("{}", serde_json::to_string_pretty(&result)?);
+                    } else {
+                        println!("Respuesta: {}", answer);
+                    }
                     return Ok(());
                 }
             },
@@ -291,32 +414,160 @@ async fn main() -> anyhow::Result<()> {
     // Use RouterOrchestrator (the ONLY supported orchestrator)
     tracing::info!("Using RouterOrchestrator (optimized for small models)");
     
-    let router_config = RouterConfig {
-        fast_model_config: app_config.fast_model.clone(),
-        heavy_model_config: app_config.heavy_model.clone(),
-        classification_timeout_secs: 30,
-        execution_timeout_secs: app_config.heavy_timeout_secs,
-        min_confidence: 0.8,
-        working_dir: working_dir.to_string_lossy().to_string(),
-        locale: init_locale(),
-        debug: app_config.debug,
-    };
+    let router_config = RouterConfig::from_app_config(&app_config, &working_dir);
     
     // Create new DualModelOrchestrator for RouterOrchestrator
-    let dual_for_router = DualModelOrchestrator::with_config(config).await?;
+    let mut dual_for_router = DualModelOrchestrator::with_config(config).await?;
+    for error in dual_for_router.apply_tool_config(&app_config, &working_dir) {
+        tracing::warn!("Skipping WASM plugin: {}", error);
+    }
     let router = RouterOrchestrator::new(router_config, dual_for_router).await?;
     
     // Initialize RAPTOR index
     router.initialize_raptor().await?;
+
+    if app_config.dependency_docs {
+        let docs_dir = working_dir.clone();
+        tokio::spawn(async move {
+            match DependencyDocsTool::new()
+                .fetch_top(&docs_dir, DEFAULT_TOP_DEPENDENCIES)
+                .await
+            {
+                Ok(report) => log_info!("{}", report.format()),
+                Err(e) => log_error!("Dependency docs unavailable: {}", e),
+            }
+        });
+    }
     
-    if args.simple {
-        eprintln!("Simple mode not yet supported with RouterOrchestrator");
-        return Ok(());
+    // Session that receives the chat history archived by the TUI or the REPL
+    let session = Session::new(
+        uuid::Uuid::new_v4().to_string(),
+        &app_config.fast_model.model,
+        &app_config.heavy_model.model,
+    )
+    .with_working_dir(working_dir.to_string_lossy());
+    let archive = match db.create_session(&session).await {
+        Ok(()) => {
+            let store = SessionStore {
+                db: db.clone(),
+                session_id: session.id.clone(),
+            };
+            if let Err(e) = router.attach_session(store).await {
+                tracing::warn!("Session environment unavailable: {}", e);
+            }
+            Some(HistoryArchive::new(db, session.id, app_config.max_history_messages))
+        }
+        Err(e) => {
+            tracing::warn!("Chat history archival disabled: {}", e);
+            None
+        }
+    };
+
+    if let Some(prompt) = &args.prompt {
+        SimpleRepl::new(router)
+            .with_archive(archive)
+            .show_tools(args.show_tools)
+            .json_output(args.output == OutputFormat::Json)
+            .run_once(prompt)
+            .await
+    } else if args.simple {
+        SimpleRepl::new(router).with_archive(archive).run().await
     } else {
-        run_modern_tui_with_router(router).await
+        // Apply saved changes of the config file without restarting
+        let config_watcher = neuro::config::AppConfig::active_path(args.config.as_deref()).and_then(|path| {
+            ConfigWatcher::spawn_with_project(&path, file_config, project_config)
+                .map_err(|e| tracing::warn!("Config hot reload disabled: {}", e))
+                .ok()
+        });
+
+        run_modern_tui_with_router(
+            router,
+            archive,
+            config_watcher,
+            app_config.experimental.parallel_worktrees,
+            &app_config.accessibility,
+        )
+        .await
     }
 }
 
+/// `raptor query --output json`
+#[derive(serde::Serialize)]
+struct QueryOutput<'a> {
+    query: &'a str,
+    summaries: Vec<ScoredText<'a>>,
+    chunks: Vec<ScoredText<'a>>,
+    answer: &'a str,
+    model: &'a str,
+    usage: TokenUsage,
+}
+
+/// A retrieved summary or chunk with its similarity to the query
+#[derive(serde::Serialize)]
+struct ScoredText<'a> {
+    id: &'a str,
+    score: f32,
+    text: &'a str,
+}
+
+impl<'a> From<&'a (String, f32, String)> for ScoredText<'a> {
+    fn from((id, score, text): &'a (String, f32, String)) -> Self {
+        Self { id, score: *score, text }
+    }
+}
+
+/// Print a heavy model answer to stdout chunk by chunk, through the same
+/// streaming path as the TUI
+async fn stream_answer(orchestrator: &DualModelOrchestrator, prompt: &str) -> anyhow::Result<()> {
+    let events = EventBus::new();
+    let mut stream = events.subscribe("cli", STREAM_BUFFER, &[Topic::Stream]);
+    let mut stdout = std::io::stdout();
+    let mut print = |event: AgentEvent| -> std::io::Result<()> {
+        match event {
+            AgentEvent::Chunk(chunk) => {
+                stdout.write_all(chunk.as_bytes())?;
+                // Line buffering would hold tokens back until the next newline
+                stdout.flush()
+            }
+            AgentEvent::StreamAborted { reason, .. } => {
+                eprintln!("\n⚠ La respuesta se cortó: {}", reason);
+                Ok(())
+            }
+            _ => Ok(()),
+        }
+    };
+
+    let mut call = std::pin::pin!(orchestrator.call_heavy_model_streaming(prompt, &events));
+    let result = loop {
+        tokio::select! {
+            result = &mut call => break result,
+            Some(event) = stream.recv() => print(event)?,
+        }
+    };
+    while let Ok(event) = stream.try_recv() {
+        print(event)?;
+    }
+    println!();
+    Ok(result?)
+}
+
+/// Write the man page of `cmd` and, recursively, of its subcommands to `dir`
+fn write_man_pages(cmd: clap::Command, dir: &std::path::Path) -> std::io::Result<()> {
+    let mut cmd = cmd;
+    cmd.build();
+    write_man_page(&cmd, cmd.get_name(), dir)
+}
+
+fn write_man_page(cmd: &clap::Command, name: &str, dir: &std::path::Path) -> std::io::Result<()> {
+    let mut page = Vec::new();
+    clap_mangen::Man::new(cmd.clone().name(name.to_string())).render(&mut page)?;
+    std::fs::write(dir.join(format!("{}.1", name)), page)?;
+    for sub in cmd.get_subcommands().filter(|sub| sub.get_name() != "help") {
+        write_man_page(sub, &format!("{}-{}", name, sub.get_name()), dir)?;
+    }
+    Ok(())
+}
+
 /// Initialize logging
 fn init_logging(verbose: bool, tui_mode: bool, debug_mode: bool) {
     // In TUI mode, use file logging to avoid interfering with the interface
@@ -342,18 +593,35 @@ fn init_logging(verbose: bool, tui_mode: bool, debug_mode: bool) {
         .with(
             tracing_subscriber::EnvFilter::try_from_default_env().unwrap_or_else(|_| filter.into()),
         )
-        .with(tracing_subscriber::fmt::layer().with_target(false))
+        // stdout is left to the answers of --simple, --prompt and raptor query
+        .with(tracing_subscriber::fmt::layer().with_target(false).with_writer(std::io::stderr))
         .init();
 }
 
 /// Run the modern TUI mode with RouterOrchestrator
-async fn run_modern_tui_with_router(router: RouterOrchestrator) -> anyhow::Result<()> {
+async fn run_modern_tui_with_router(
+    router: RouterOrchestrator,
+    archive: Option<HistoryArchive>,
+    config_watcher: Option<ConfigWatcher>,
+    parallel_worktrees: bool,
+    accessibility: &AccessibilityConfig,
+) -> anyhow::Result<()> {
     // Initialize locale
     let locale = init_locale();
     tracing::info!("Using locale: {}", locale.display_name());
 
     // Create and run modern app with router
     let mut app = ModernApp::new_with_router(router).await?;
+    app.enable_accessibility(accessibility);
+    if let Some(archive) = archive {
+        app.enable_history_archive(archive);
+    }
+    if let Some(watcher) = config_watcher {
+        app.enable_config_reload(watcher);
+    }
+    if parallel_worktrees {
+        app.enable_experiments();
+    }
     app.run().await?;
 
     Ok(())
@@ -3,6 +3,7 @@
 //! Provides a basic MCP server implementation that exposes Neuro's capabilities
 //! to MCP clients like Claude Desktop, Cody, and other compatible tools.
 
+use crate::error::NeuroError;
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
@@ -46,6 +47,20 @@ struct McpResponse {
 struct McpError {
     code: i32,
     message: String,
+    /// Machine-readable details (`code`, `category`, `hint`) for Neuro errors
+    #[serde(skip_serializing_if = "Option::is_none")]
+    data: Option<Value>,
+}
+
+impl From<&NeuroError> for McpError {
+    fn from(err: &NeuroError) -> Self {
+        Self {
+            // JSON-RPC reserves -32000..-32099 for implementation-defined server errors
+            code: -32000,
+            message: err.to_string(),
+            data: Some(err.to_json()),
+        }
+    }
 }
 
 pub struct NeuroMcpServer {
@@ -156,6 +171,7 @@ impl NeuroMcpServer {
                         error: Some(McpError {
                             code: -32700,
                             message: format!("Parse error: {}", e),
+                            data: None,
                         }),
                     };
                     let response_json = serde_json::to_string(&error_response)?;
@@ -181,6 +197,7 @@ impl NeuroMcpServer {
                 error: Some(McpError {
                     code: -32601,
                     message: format!("Method not found: {}", request.method),
+                    data: None,
                 }),
             },
         }
@@ -232,11 +249,23 @@ impl NeuroMcpServer {
                     error: Some(McpError {
                         code: -32602,
                         message: "Missing params".to_string(),
+                        data: None,
                     }),
                 }
             }
         };
 
+        let name = params.get("name").and_then(Value::as_str).unwrap_or_default();
+        if !self.tools.iter().any(|tool| tool.name == name) {
+            let err = NeuroError::Tool(format!("Unknown tool: {}", name));
+            return McpResponse {
+                jsonrpc: "2.0".to_string(),
+                id: request.id,
+                result: None,
+                error: Some(McpError::from(&err)),
+            };
+        }
+
         // In a real implementation, this would call the actual tool implementations
         // For now, return a placeholder response
         McpResponse {
@@ -263,3 +292,25 @@ impl Default for NeuroMcpServer {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_unknown_tool_returns_error_code() {
+        let server = NeuroMcpServer::new();
+        let request: McpRequest = serde_json::from_value(json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "tools/call",
+            "params": {"name": "format_disk", "arguments": {}}
+        }))
+        .unwrap();
+
+        let response = serde_json::to_value(server.handle_request(request).await).unwrap();
+        assert_eq!(response["error"]["code"], -32000);
+        assert_eq!(response["error"]["data"]["code"], "tool_failed");
+        assert_eq!(response["error"]["data"]["category"], "tool");
+    }
+}
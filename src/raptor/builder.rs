@@ -1,10 +1,13 @@
 use crate::agent::orchestrator::DualModelOrchestrator;
-use crate::log_info;
+use crate::{log_info, log_warn};
 use crate::embedding::EmbeddingEngine;
 use crate::raptor::chunker::chunk_text;
-use crate::raptor::persistence::{load_cache_if_valid, save_cache, GLOBAL_STORE};
+use crate::raptor::persistence::{
+    load_cache_if_valid, save_cache, swap_global_store, TreeNode, TreeStore, GLOBAL_STORE,
+};
 use anyhow::Result;
 use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::time::SystemTime;
 use tokio::sync::mpsc::Sender;
@@ -90,7 +93,8 @@ pub fn quick_index_sync(path: &Path, max_chars: usize, overlap: usize) -> Result
             let ext = path.extension().and_then(|s| s.to_str()).unwrap_or("");
             matches!(
                 ext,
-                "rs" | "py" | "js" | "ts" | "tsx" | "jsx" | "go" | "java" | "c" | "cpp" | "h" | "hpp" 
+                "rs" | "py" | "js" | "ts" | "tsx" | "jsx" | "go" | "java" | "c" | "cpp" | "h" | "hpp"
+                | "cc" | "cxx" | "hh" | "hxx" | "ino" | "ipp" | "tpp" | "inl"
                 | "md" | "toml" | "yaml" | "yml" | "json" | "txt" | "sh" | "bash" | "zsh"
                 | "rb" | "php" | "swift" | "kt" | "scala" | "r" | "lua" | "sql" | "html" | "css" | "scss"
             )
@@ -119,7 +123,7 @@ pub fn quick_index_sync(path: &Path, max_chars: usize, overlap: usize) -> Result
                 let chunk_id = Uuid::new_v4().to_string();
                 {
                     let mut store = GLOBAL_STORE.lock().unwrap();
-                    store.insert_chunk(chunk_id, chunk);
+                    store.insert_file_chunk(chunk_id, &file_path.to_string_lossy(), chunk);
                     let mtime = get_file_mtime(file_path);
                     store
                         .indexed_files
@@ -206,6 +210,52 @@ mod quick_index_tests {
     }
 }
 
+/// Files read by a full build: code, docs and config, at most 500
+fn full_index_files(path: &Path) -> Vec<walkdir::DirEntry> {
+    WalkDir::new(path)
+        .into_iter()
+        .filter_entry(|e| {
+            let name = e.file_name().to_str().unwrap_or("");
+            !name.starts_with('.') && !SKIP_DIRS.contains(&name)
+        })
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .filter(|e| {
+            let path = e.path();
+            let ext = path.extension().and_then(|s| s.to_str()).unwrap_or("");
+            matches!(
+                ext,
+                "rs" | "py"
+                    | "js"
+                    | "ts"
+                    | "go"
+                    | "java"
+                    | "c"
+                    | "cpp"
+                    | "h"
+                    | "hpp"
+                    | "cc"
+                    | "cxx"
+                    | "hh"
+                    | "hxx"
+                    | "ino"
+                    | "ipp"
+                    | "tpp"
+                    | "inl"
+                    | "rb"
+                    | "php"
+                    | "md"
+                    | "toml"
+                    | "yaml"
+                    | "yml"
+                    | "json"
+                    | "txt"
+            )
+        })
+        .take(500)
+        .collect()
+}
+
 /// Check if quick index has been done (chunks exist)
 pub fn has_quick_index() -> bool {
     let store = GLOBAL_STORE.lock().unwrap();
@@ -292,38 +342,7 @@ pub async fn build_tree_with_progress(
             store.indexed_files.clone()
         };
 
-        let files: Vec<_> = WalkDir::new(path)
-            .into_iter()
-            .filter_entry(|e| {
-                let name = e.file_name().to_str().unwrap_or("");
-                !name.starts_with('.') && !SKIP_DIRS.contains(&name)
-            })
-            .filter_map(|e| e.ok())
-            .filter(|e| e.file_type().is_file())
-            .filter(|e| {
-                let path = e.path();
-                let ext = path.extension().and_then(|s| s.to_str()).unwrap_or("");
-                matches!(
-                    ext,
-                    "rs" | "py"
-                        | "js"
-                        | "ts"
-                        | "go"
-                        | "java"
-                        | "c"
-                        | "cpp"
-                        | "h"
-                        | "hpp"
-                        | "md"
-                        | "toml"
-                        | "yaml"
-                        | "yml"
-                        | "json"
-                        | "txt"
-                )
-            })
-            .take(500)
-            .collect();
+        let files = full_index_files(path);
 
         let files_to_index: Vec<_> = files
             .iter()
@@ -388,7 +407,7 @@ pub async fn build_tree_with_progress(
                     let chunk_id = Uuid::new_v4().to_string();
                     {
                         let mut store = GLOBAL_STORE.lock().unwrap();
-                        store.insert_chunk(chunk_id.clone(), chunk.clone());
+                        store.insert_file_chunk(chunk_id.clone(), &file_path.to_string_lossy(), chunk.clone());
                     }
                     new_chunks.push((chunk_id.clone(), chunk.clone()));
                 }
@@ -423,41 +442,14 @@ pub async fn build_tree_with_progress(
             .await;
     }
 
-    // Batch embed chunks - smaller batch for lower RAM usage
-    let batch_size = 64; // Reduced from 256 for lower memory
-    let mut i = 0usize;
-    while i < chunk_texts.len() {
-        // Yield to let other tasks run - low priority background indexing
-        yield_low_priority().await;
-        let end = std::cmp::min(i + batch_size, chunk_texts.len());
-
-        if let Some(ref tx) = progress_tx {
-            let _ = tx
-                .send(RaptorBuildProgress {
-                    stage: "Embeddings".to_string(),
-                    current: end,
-                    total: total_chunks,
-                    detail: format!("{}/{}", end, total_chunks),
-                })
-                .await;
+    embed_chunks(&embedder, &chunk_texts, progress_tx.as_ref(), |batch| {
+        let mut store = GLOBAL_STORE.lock().unwrap();
+        for (id, emb) in batch {
+            store.insert_chunk_embedding(id, emb);
         }
+    })
+    .await?;
 
-        let slice = &chunk_texts[i..end];
-        let text_refs: Vec<&str> = slice.iter().map(|(_, t)| t.as_str()).collect();
-        let emb_batch = embedder.embed_batch(text_refs).await?;
-        
-        // Store embeddings immediately to free memory
-        {
-            let mut store = GLOBAL_STORE.lock().unwrap();
-            for (j, emb) in emb_batch.into_iter().enumerate() {
-                let id = slice[j].0.clone();
-                store.insert_chunk_embedding(id, emb);
-            }
-        }
-        
-        i = end;
-    }
-    
     // Clear chunk_texts to free memory
     drop(chunk_texts);
 
@@ -496,15 +488,177 @@ pub async fn build_tree_with_progress(
     Ok("hierarchical-tree".to_string())
 }
 
+/// Set while a staged rebuild or an incremental update writes the index
+static REBUILDING: AtomicBool = AtomicBool::new(false);
+
+/// One writer of the index at a time: held for a whole staged rebuild or
+/// incremental update, released when dropped (also when the future holding
+/// it is cancelled)
+pub struct RebuildGuard(());
+
+impl RebuildGuard {
+    /// `None` while another rebuild or update holds it
+    pub fn acquire() -> Option<Self> {
+        (!REBUILDING.swap(true, Ordering::SeqCst)).then_some(Self(()))
+    }
+}
+
+impl Drop for RebuildGuard {
+    fn drop(&mut self) {
+        REBUILDING.store(false, Ordering::SeqCst);
+    }
+}
+
+/// A staged rebuild or incremental update is writing the index
+pub fn is_rebuilding() -> bool {
+    REBUILDING.load(Ordering::SeqCst)
+}
+
+/// Rebuild the index of `path` from scratch into a staging store, then swap it
+/// into [`GLOBAL_STORE`] in one step. Queries keep reading the previous index
+/// while it builds; if the build fails or is cancelled (the future dropped) the
+/// previous index stays in place untouched. Only one rebuild runs at a time.
+/// Returns the number of chunks.
+pub async fn rebuild_tree_staged(
+    path: &Path,
+    max_chars: usize,
+    overlap: usize,
+    threshold: f32,
+    progress_tx: Option<Sender<RaptorBuildProgress>>,
+) -> Result<usize> {
+    let Some(_guard) = RebuildGuard::acquire() else {
+        anyhow::bail!("the index is already being rebuilt or updated");
+    };
+    let path_str = path.to_string_lossy().to_string();
+    let embedder = EmbeddingEngine::new().await?;
+    let mut staging = TreeStore::new();
+
+    let files = full_index_files(path);
+    let mut chunk_texts: Vec<(String, String)> = Vec::new();
+    for (file_idx, entry) in files.iter().enumerate() {
+        yield_low_priority().await;
+        let file_path = entry.path();
+
+        if let Some(ref tx) = progress_tx {
+            let _ = tx
+                .send(RaptorBuildProgress {
+                    stage: "Leyendo".to_string(),
+                    current: file_idx + 1,
+                    total: files.len(),
+                    detail: file_path.file_name().and_then(|n| n.to_str()).unwrap_or("unknown").to_string(),
+                })
+                .await;
+        }
+
+        let Ok(text) = std::fs::read_to_string(file_path) else {
+            continue;
+        };
+        for chunk in chunk_text(&text, max_chars, overlap) {
+            let chunk_id = Uuid::new_v4().to_string();
+            staging.insert_file_chunk(chunk_id.clone(), &file_path.to_string_lossy(), chunk.clone());
+            chunk_texts.push((chunk_id, chunk));
+        }
+        staging
+            .indexed_files
+            .insert(file_path.to_string_lossy().to_string(), get_file_mtime(file_path));
+    }
+
+    let total_chunks = chunk_texts.len();
+    if total_chunks == 0 && has_quick_index() {
+        anyhow::bail!("no chunks read from {}, keeping the current index", path_str);
+    }
+
+    embed_chunks(&embedder, &chunk_texts, progress_tx.as_ref(), |batch| {
+        for (id, emb) in batch {
+            staging.insert_chunk_embedding(id, emb);
+        }
+    })
+    .await?;
+    drop(chunk_texts);
+
+    if let Some(ref tx) = progress_tx {
+        let _ = tx
+            .send(RaptorBuildProgress {
+                stage: "Clustering".to_string(),
+                current: 0,
+                total: total_chunks,
+                detail: "Construyendo jerarquía...".to_string(),
+            })
+            .await;
+    }
+    let embeddings: Vec<(String, Vec<f32>)> = staging
+        .chunk_embeddings
+        .iter()
+        .map(|(id, emb)| (id.clone(), emb.clone()))
+        .collect();
+    if !embeddings.is_empty() {
+        let (nodes, root) = cluster_tree(embeddings, threshold, progress_tx.as_ref()).await;
+        staging.set_tree(nodes, root);
+    }
+
+    staging.indexing_complete = true;
+    // The previous index is freed here, outside the store lock
+    drop(swap_global_store(staging));
+    if let Err(e) = save_cache(&path_str) {
+        log_warn!("⚠ [RAPTOR] Could not save the rebuilt index cache: {}", e);
+    }
+
+    if let Some(ref tx) = progress_tx {
+        let _ = tx
+            .send(RaptorBuildProgress {
+                stage: "Completado".to_string(),
+                current: 1,
+                total: 1,
+                detail: format!("Índice listo: {} chunks", total_chunks),
+            })
+            .await;
+    }
+    Ok(total_chunks)
+}
+
+/// Embed `chunks` in batches, handing each batch of (chunk id, embedding) to
+/// `store` as soon as it is ready to free memory
+async fn embed_chunks(
+    embedder: &EmbeddingEngine,
+    chunks: &[(String, String)],
+    progress_tx: Option<&Sender<RaptorBuildProgress>>,
+    mut store: impl FnMut(Vec<(String, Vec<f32>)>),
+) -> Result<()> {
+    let total_chunks = chunks.len();
+    // Batch embed chunks - smaller batch for lower RAM usage
+    let batch_size = 64; // Reduced from 256 for lower memory
+    let mut i = 0usize;
+    while i < chunks.len() {
+        // Yield to let other tasks run - low priority background indexing
+        yield_low_priority().await;
+        let end = std::cmp::min(i + batch_size, chunks.len());
+
+        if let Some(tx) = progress_tx {
+            let _ = tx
+                .send(RaptorBuildProgress {
+                    stage: "Embeddings".to_string(),
+                    current: end,
+                    total: total_chunks,
+                    detail: format!("{}/{}", end, total_chunks),
+                })
+                .await;
+        }
+
+        let slice = &chunks[i..end];
+        let text_refs: Vec<&str> = slice.iter().map(|(_, t)| t.as_str()).collect();
+        let emb_batch = embedder.embed_batch(text_refs).await?;
+        store(slice.iter().map(|(id, _)| id.clone()).zip(emb_batch).collect());
+
+        i = end;
+    }
+    Ok(())
+}
+
 /// Build hierarchical tree structure from chunk embeddings
 async fn build_hierarchical_tree(
     threshold: f32,
     progress_tx: Option<&Sender<RaptorBuildProgress>>,
 ) -> Result<()> {
-    use crate::raptor::clustering::{cluster_by_threshold_with_centroids, calculate_centroid};
-    use crate::raptor::persistence::TreeNode;
-    use uuid::Uuid;
-
     // Get all chunk embeddings
     let embeddings: Vec<(String, Vec<f32>)> = {
         let store = GLOBAL_STORE.lock().unwrap();
@@ -517,6 +671,20 @@ async fn build_hierarchical_tree(
         return Ok(());
     }
 
+    let (nodes, root) = cluster_tree(embeddings, threshold, progress_tx).await;
+    GLOBAL_STORE.lock().unwrap().set_tree(nodes, root);
+    Ok(())
+}
+
+/// Cluster chunk embeddings bottom-up into tree nodes; returns the nodes and
+/// the id of the root
+async fn cluster_tree(
+    embeddings: Vec<(String, Vec<f32>)>,
+    threshold: f32,
+    progress_tx: Option<&Sender<RaptorBuildProgress>>,
+) -> (Vec<TreeNode>, Option<String>) {
+    use crate::raptor::clustering::{cluster_by_threshold_with_centroids, calculate_centroid};
+
     let mut current_level: Vec<(String, Vec<f32>)> = embeddings.clone();
     let mut level = 0;
     let mut all_nodes: Vec<TreeNode> = Vec::new();
@@ -575,27 +743,8 @@ async fn build_hierarchical_tree(
         yield_low_priority().await;
     }
 
-    // Store tree in global store
-    {
-        let mut store = GLOBAL_STORE.lock().unwrap();
-        store.tree_nodes.clear();
-        for node in all_nodes {
-            // Set parent references
-            for child_id in &node.children {
-                if let Some(child) = store.tree_nodes.get_mut(child_id) {
-                    child.parent_id = Some(node.id.clone());
-                }
-            }
-            store.tree_nodes.insert(node.id.clone(), node);
-        }
-        
-        // Set root
-        if !current_level.is_empty() {
-            store.tree_root = Some(current_level[0].0.clone());
-        }
-    }
-
-    Ok(())
+    let root = current_level.into_iter().next().map(|(id, _)| id);
+    (all_nodes, root)
 }
 
 /// Build the RAPTOR tree for all files under `path` (legacy, no progress)
@@ -616,6 +765,16 @@ mod tests {
     use std::io::Write;
     use tempfile::tempdir;
 
+    #[test]
+    fn test_one_rebuild_at_a_time() {
+        let guard = RebuildGuard::acquire().unwrap();
+        assert!(is_rebuilding());
+        assert!(RebuildGuard::acquire().is_none());
+        drop(guard);
+        assert!(!is_rebuilding());
+        assert!(RebuildGuard::acquire().is_some());
+    }
+
     #[tokio::test]
     #[ignore] // Heavy test: loads embedding model. Run with: cargo test -- --ignored
     async fn test_build_tree_small() {
@@ -0,0 +1,231 @@
+//! Queries federated across several indexed roots
+//!
+//! In a workspace of sibling services each directory keeps its own RAPTOR
+//! index (built by running neuro there). The roots listed under `federation`
+//! in the config are queried together with the working directory: their
+//! scores are multiplied by the root's weight, merged, and every result is
+//! labeled with its root so cross-service questions ("where is this API
+//! consumed?") can be answered.
+
+use crate::agent::workspace;
+use crate::log_warn;
+use crate::raptor::persistence::TreeStore;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Characters of each result included in the context
+const HIT_CHARS: usize = 800;
+
+/// Indexed root queried together with the working directory
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FederatedRoot {
+    /// Directory whose index is queried, absolute or relative to the working directory
+    pub path: String,
+
+    /// Name its results are labeled with; the directory name by default
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+
+    /// Multiplies the similarity of its results; above 1 favours this root
+    #[serde(default = "default_weight")]
+    pub weight: f32,
+}
+
+fn default_weight() -> f32 {
+    1.0
+}
+
+/// Result of a federated query
+#[derive(Debug, Clone, PartialEq)]
+pub struct FederatedHit {
+    /// Label of the root it comes from
+    pub root: String,
+    pub chunk_id: String,
+    /// Similarity times the weight of the root
+    pub score: f32,
+    pub text: String,
+}
+
+/// A root and its loaded index, `None` when it has not been indexed
+struct RootIndex {
+    label: String,
+    path: PathBuf,
+    weight: f32,
+    store: Option<TreeStore>,
+}
+
+/// Indexes of the federated roots
+#[derive(Default)]
+pub struct Federation {
+    roots: Vec<RootIndex>,
+}
+
+impl Federation {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Load the cached index of each root, resolved against `base`
+    pub fn load(base: &Path, roots: &[FederatedRoot]) -> Self {
+        let mut federation = Self::new();
+        for root in roots {
+            let path = match workspace::resolve_dir(base, &root.path) {
+                Ok(path) => path,
+                Err(e) => {
+                    log_warn!("⚠️ Ignoring federated root: {}", e);
+                    continue;
+                }
+            };
+            let label = root.label.clone().unwrap_or_else(|| label_for(&path));
+            let cache = TreeStore::cache_path_for(&path.to_string_lossy());
+            let store = TreeStore::load_from(cache).ok().filter(|store| !store.chunk_embeddings.is_empty());
+            federation.roots.push(RootIndex { label, path, weight: root.weight, store });
+        }
+        federation
+    }
+
+    /// Add a root whose index is already loaded, e.g. the working directory's own
+    pub fn with_store(mut self, label: impl Into<String>, path: PathBuf, weight: f32, store: TreeStore) -> Self {
+        self.roots.push(RootIndex { label: label.into(), path, weight, store: Some(store) });
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.roots.is_empty()
+    }
+
+    /// Labels of the roots that still need an index, with their directory
+    pub fn unindexed(&self) -> Vec<(&str, &Path)> {
+        self.roots
+            .iter()
+            .filter(|root| root.store.is_none())
+            .map(|root| (root.label.as_str(), root.path.as_path()))
+            .collect()
+    }
+
+    /// One line per root: label, weight and size of its index
+    pub fn describe(&self) -> String {
+        self.roots
+            .iter()
+            .map(|root| match &root.store {
+                Some(store) => format!(
+                    "  [{}] {} (peso {}, {} fragmentos)",
+                    root.label,
+                    root.path.display(),
+                    root.weight,
+                    store.chunk_map.len()
+                ),
+                None => format!(
+                    "  [{}] {} (peso {}, sin índice: abre neuro en ese directorio y usa /reindex)",
+                    root.label,
+                    root.path.display(),
+                    root.weight
+                ),
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Best `top_k` chunks across every indexed root, by weighted similarity
+    pub fn query(&self, q_emb: &[f32], top_k: usize) -> Vec<FederatedHit> {
+        self.query_with(None, q_emb, top_k)
+    }
+
+    /// Like [`Federation::query`], ranking the working directory's own index
+    /// (`label`, weight 1) among the roots
+    pub fn query_with(&self, primary: Option<(&str, &TreeStore)>, q_emb: &[f32], top_k: usize) -> Vec<FederatedHit> {
+        let roots = self
+            .roots
+            .iter()
+            .filter_map(|root| Some((root.label.as_str(), root.weight, root.store.as_ref()?)));
+        let mut hits: Vec<FederatedHit> = primary
+            .map(|(label, store)| (label, 1.0, store))
+            .into_iter()
+            .chain(roots)
+            .flat_map(|(label, weight, store)| root_hits(label, weight, store, q_emb, top_k))
+            .collect();
+        hits.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
+        hits.truncate(top_k);
+        hits
+    }
+}
+
+fn root_hits(label: &str, weight: f32, store: &TreeStore, q_emb: &[f32], top_k: usize) -> Vec<FederatedHit> {
+    store
+        .query_top_k_chunks(q_emb, top_k)
+        .into_iter()
+        .filter_map(|(id, score)| {
+            Some(FederatedHit {
+                root: label.to_string(),
+                text: store.get_chunk(&id)?.clone(),
+                chunk_id: id,
+                score: score * weight,
+            })
+        })
+        .collect()
+}
+
+/// Default label of a root: its directory name
+pub fn label_for(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| path.display().to_string())
+}
+
+/// Context section listing each result under its root
+pub fn format_hits(hits: &[FederatedHit]) -> String {
+    hits.iter()
+        .map(|hit| format!("[{}] {}", hit.root, hit.text.chars().take(HIT_CHARS).collect::<String>()))
+        .collect::<Vec<_>>()
+        .join("\n---\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn store(chunks: &[(&str, &str, Vec<f32>)]) -> TreeStore {
+        let mut store = TreeStore::new();
+        for (id, text, embedding) in chunks {
+            store.insert_chunk(id.to_string(), text.to_string());
+            store.insert_chunk_embedding(id.to_string(), embedding.clone());
+        }
+        store
+    }
+
+    #[test]
+    fn test_query_merges_roots_by_weighted_score() {
+        let api = store(&[("a1", "fn get_user()", vec![1.0, 0.0]), ("a2", "fn health()", vec![0.0, 1.0])]);
+        let web = store(&[("w1", "fetch('/users')", vec![0.9, 0.1])]);
+        let federation = Federation::new()
+            .with_store("api", PathBuf::from("/ws/api"), 1.0, api)
+            .with_store("web", PathBuf::from("/ws/web"), 2.0, web);
+
+        let hits = federation.query(&[1.0, 0.0], 2);
+        let labels: Vec<(&str, &str)> = hits.iter().map(|hit| (hit.root.as_str(), hit.chunk_id.as_str())).collect();
+        assert_eq!(labels, vec![("web", "w1"), ("api", "a1")]);
+        assert!(hits[0].score > 1.0);
+        assert_eq!(format_hits(&hits[..1]), "[web] fetch('/users')");
+
+        let gateway = store(&[("g1", "route /users", vec![0.6, 0.8])]);
+        let hits = federation.query_with(Some(("gateway", &gateway)), &[0.0, 1.0], 1);
+        assert_eq!(hits[0].root, "api");
+        assert_eq!(hits[0].chunk_id, "a2");
+    }
+
+    #[test]
+    fn test_load_labels_roots_and_flags_missing_indexes() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("billing")).unwrap();
+        let roots = vec![
+            FederatedRoot { path: "billing".to_string(), label: None, weight: 1.5 },
+            FederatedRoot { path: "missing".to_string(), label: Some("x".to_string()), weight: 1.0 },
+        ];
+
+        let federation = Federation::load(dir.path(), &roots);
+        let unindexed: Vec<&str> = federation.unindexed().iter().map(|(label, _)| *label).collect();
+        assert_eq!(unindexed, vec!["billing"]);
+        assert!(federation.describe().contains("[billing]"));
+        assert!(federation.query(&[1.0], 5).is_empty());
+    }
+}
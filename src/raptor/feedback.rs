@@ -0,0 +1,157 @@
+//! Relevance feedback on retrieved chunks
+//!
+//! Marking a source of an answer as irrelevant (`/sources irrelevant <n>`)
+//! records a negative (query, chunk) pair for each chunk of that file. Pairs
+//! are kept per project in the database and loaded when a session is
+//! attached; retrieval divides the score of a chunk marked irrelevant for a
+//! similar query, and the bench harness replays them as retrieval cases.
+
+use crate::raptor::persistence::TreeStore;
+use std::collections::BTreeSet;
+use std::sync::{OnceLock, RwLock};
+
+/// Score multiplier of a chunk marked irrelevant for a similar query
+pub const DOWN_WEIGHT: f32 = 0.5;
+
+/// Share of words two queries must have in common to be similar
+pub const SIMILARITY: f32 = 0.5;
+
+/// A chunk the user marked irrelevant for a query
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NegativePair {
+    pub query: String,
+    pub chunk_id: String,
+    /// File of the chunk, as the answer cited it
+    pub source: String,
+}
+
+impl From<crate::db::RetrievalFeedback> for NegativePair {
+    fn from(feedback: crate::db::RetrievalFeedback) -> Self {
+        Self {
+            query: feedback.query,
+            chunk_id: feedback.chunk_id,
+            source: feedback.source,
+        }
+    }
+}
+
+fn pairs_lock() -> &'static RwLock<Vec<NegativePair>> {
+    static PAIRS: OnceLock<RwLock<Vec<NegativePair>>> = OnceLock::new();
+    PAIRS.get_or_init(|| RwLock::new(Vec::new()))
+}
+
+/// Replace the pairs in use, e.g. with the ones of the project just opened
+pub fn set_pairs(pairs: Vec<NegativePair>) {
+    *pairs_lock().write().unwrap_or_else(|e| e.into_inner()) = pairs;
+}
+
+/// Add pairs just recorded
+pub fn add_pairs(new: impl IntoIterator<Item = NegativePair>) {
+    let mut pairs = pairs_lock().write().unwrap_or_else(|e| e.into_inner());
+    for pair in new {
+        if !pairs.contains(&pair) {
+            pairs.push(pair);
+        }
+    }
+}
+
+/// The pairs in use
+pub fn pairs() -> Vec<NegativePair> {
+    pairs_lock().read().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+/// Lowercased words of at least three characters
+fn words(text: &str) -> BTreeSet<String> {
+    text.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| word.chars().count() >= 3)
+        .map(str::to_string)
+        .collect()
+}
+
+/// Words in common over words in either query, from 0 to 1
+pub fn similarity(a: &str, b: &str) -> f32 {
+    let (a, b) = (words(a), words(b));
+    let union = a.union(&b).count();
+    if union == 0 {
+        return 0.0;
+    }
+    a.intersection(&b).count() as f32 / union as f32
+}
+
+/// Score multiplier of `chunk_id` for `query` under `pairs`
+pub fn weight(pairs: &[NegativePair], query: &str, chunk_id: &str) -> f32 {
+    let marked = pairs
+        .iter()
+        .any(|pair| pair.chunk_id == chunk_id && similarity(&pair.query, query) >= SIMILARITY);
+    if marked {
+        DOWN_WEIGHT
+    } else {
+        1.0
+    }
+}
+
+/// Down-weight the `(chunk_id, score, text)` matches marked irrelevant for a
+/// query like `query` and sort them again
+pub fn rerank(query: &str, mut chunks: Vec<(String, f32, String)>) -> Vec<(String, f32, String)> {
+    let pairs = pairs();
+    if pairs.is_empty() {
+        return chunks;
+    }
+    for (id, score, _) in chunks.iter_mut() {
+        *score *= weight(&pairs, query, id);
+    }
+    chunks.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+    chunks
+}
+
+/// Chunks read from `source`, a path as cited in an answer (usually relative)
+pub fn chunks_of(store: &TreeStore, source: &str) -> Vec<String> {
+    let source = source.trim_start_matches("./").replace('\\', "/");
+    let mut ids: Vec<String> = store
+        .chunk_files
+        .iter()
+        .filter(|(_, file)| {
+            let file = file.replace('\\', "/");
+            file == source || file.ends_with(&format!("/{}", source))
+        })
+        .map(|(id, _)| id.clone())
+        .collect();
+    ids.sort();
+    ids
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pair(query: &str, chunk_id: &str) -> NegativePair {
+        NegativePair {
+            query: query.to_string(),
+            chunk_id: chunk_id.to_string(),
+            source: "README.md".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_weight_applies_to_similar_queries() {
+        let pairs = vec![pair("why does the CI fail", "c1")];
+        assert_eq!(weight(&pairs, "why does the CI fail on main", "c1"), DOWN_WEIGHT);
+        assert_eq!(weight(&pairs, "why does the CI fail", "c2"), 1.0);
+        assert_eq!(weight(&pairs, "how is the router configured", "c1"), 1.0);
+        assert_eq!(similarity("", "ci"), 0.0);
+    }
+
+    #[test]
+    fn test_chunks_of_matches_cited_paths() {
+        let mut store = TreeStore::new();
+        store.insert_file_chunk("a".to_string(), "/repo/src/main.rs", "fn main()".to_string());
+        store.insert_file_chunk("b".to_string(), "/repo/src/main.rs", "fn run()".to_string());
+        store.insert_file_chunk("c".to_string(), "/repo/tests/main.rs", "#[test]".to_string());
+
+        assert_eq!(chunks_of(&store, "src/main.rs"), vec!["a", "b"]);
+        assert_eq!(chunks_of(&store, "./tests/main.rs"), vec!["c"]);
+        assert!(chunks_of(&store, "main.rs").len() == 3);
+        assert!(chunks_of(&store, "lib.rs").is_empty());
+    }
+}
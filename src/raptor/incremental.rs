@@ -10,9 +10,10 @@ use std::sync::Arc;
 use std::time::SystemTime;
 use tokio::sync::Mutex as AsyncMutex;
 
-use super::builder::{build_tree, RaptorBuildProgress};
+use super::builder::{build_tree, RaptorBuildProgress, RebuildGuard};
 use super::persistence::GLOBAL_STORE;
 use crate::agent::orchestrator::DualModelOrchestrator;
+use crate::ast::{AstParser, SupportedLanguage};
 
 /// Track file modification times for incremental updates
 #[derive(Debug, Clone)]
@@ -55,6 +56,7 @@ impl FileTracker {
                 if matches!(
                     ext_str.as_ref(),
                     "rs" | "py" | "js" | "ts" | "tsx" | "jsx" | "go" | "java" | "c" | "cpp" | "h" | "hpp"
+                        | "cc" | "cxx" | "hh" | "hxx" | "ino" | "ipp" | "tpp" | "inl" | "rb" | "php"
                 ) {
                     if let Ok(metadata) = std::fs::metadata(path) {
                         if let Ok(modified) = metadata.modified() {
@@ -120,6 +122,8 @@ pub struct IncrementalUpdater {
     current_tracker: Arc<AsyncMutex<FileTracker>>,
     /// Orchestrator for embeddings
     orchestrator: Arc<AsyncMutex<DualModelOrchestrator>>,
+    /// Parse trees of changed files, re-parsed incrementally on later edits
+    ast_parser: AsyncMutex<Option<AstParser>>,
 }
 
 impl IncrementalUpdater {
@@ -134,6 +138,7 @@ impl IncrementalUpdater {
             project_root,
             current_tracker,
             orchestrator,
+            ast_parser: AsyncMutex::new(AstParser::new().ok()),
         }
     }
 
@@ -162,11 +167,15 @@ impl IncrementalUpdater {
         let modified_files = new_tracker.get_modified(&previous_tracker);
         let deleted_files = new_tracker.get_deleted(&previous_tracker);
 
-        if modified_files.is_empty() && deleted_files.is_empty() {
+        // During a staged rebuild the changes wait for the next check, so they
+        // are not written into the store about to be replaced
+        let guard = RebuildGuard::acquire();
+        if guard.is_none() || (modified_files.is_empty() && deleted_files.is_empty()) {
             return Ok(UpdateResult {
                 updated: false,
                 files_modified: 0,
                 files_deleted: 0,
+                changed_symbols: Vec::new(),
                 duration_ms: 0,
             });
         }
@@ -191,6 +200,7 @@ impl IncrementalUpdater {
 
         // Remove deleted files from index
         self.remove_files(&deleted_files).await?;
+        let changed_symbols = self.reparse_files(&modified_files, &deleted_files).await;
 
         // Re-index modified files
         self.reindex_files(&modified_files, progress_tx).await?;
@@ -207,10 +217,49 @@ impl IncrementalUpdater {
             updated: true,
             files_modified: modified_files.len(),
             files_deleted: deleted_files.len(),
+            changed_symbols,
             duration_ms: duration.as_millis() as u64,
         })
     }
 
+    /// Re-parse modified files and list the symbols their edits touched, as
+    /// `path:name`. Files seen before are re-parsed from their cached tree with
+    /// one byte-level edit per changed hunk, so a small change to a large file
+    /// only re-parses the regions around it.
+    async fn reparse_files(&self, modified: &[PathBuf], deleted: &[PathBuf]) -> Vec<String> {
+        let mut guard = self.ast_parser.lock().await;
+        let Some(parser) = guard.as_mut() else {
+            return Vec::new();
+        };
+
+        for path in deleted {
+            parser.forget_file(path);
+        }
+
+        let mut changed = Vec::new();
+        for path in modified {
+            let Some(language) = SupportedLanguage::from_path(path) else {
+                continue;
+            };
+            let Ok(source) = std::fs::read_to_string(path) else {
+                continue;
+            };
+            let Ok(parse) = parser.parse_file(path, language, &source) else {
+                continue;
+            };
+
+            let relative = path.strip_prefix(&self.project_root).unwrap_or(path);
+            changed.extend(
+                parser
+                    .extract_symbols(&parse.tree, language, &source)
+                    .into_iter()
+                    .filter(|symbol| parse.touches(&symbol.range))
+                    .map(|symbol| format!("{}:{}", relative.display(), symbol.name)),
+            );
+        }
+        changed
+    }
+
     /// Remove deleted files from RAPTOR index
     async fn remove_files(&self, files: &[PathBuf]) -> Result<()> {
         if files.is_empty() {
@@ -287,6 +336,10 @@ impl IncrementalUpdater {
         &self,
         _progress_tx: Option<tokio::sync::mpsc::Sender<RaptorBuildProgress>>,
     ) -> Result<()> {
+        let Some(_guard) = RebuildGuard::acquire() else {
+            anyhow::bail!("the index is already being rebuilt or updated");
+        };
+
         // Clear the entire index
         {
             let mut store = GLOBAL_STORE.lock().unwrap();
@@ -341,6 +394,8 @@ pub struct UpdateResult {
     pub files_modified: usize,
     /// Number of files deleted
     pub files_deleted: usize,
+    /// Symbols touched by the edits, as `path:name`
+    pub changed_symbols: Vec<String>,
     /// Duration in milliseconds
     pub duration_ms: u64,
 }
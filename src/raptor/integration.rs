@@ -10,15 +10,19 @@
 
 use crate::agent::orchestrator::DualModelOrchestrator;
 use crate::agent::planning_orchestrator::PlanningOrchestrator;
+use crate::agent::webhooks::{self, TaskEvent, WebhookEventKind};
 use crate::embedding::EmbeddingEngine;
-use crate::raptor::builder::RaptorBuildProgress;
+use crate::raptor::builder::{self, RaptorBuildProgress};
+use crate::raptor::federation::{self, Federation};
+use crate::raptor::feedback;
+use crate::raptor::intent;
 use crate::raptor::persistence::GLOBAL_STORE;
 use crate::raptor::retriever::TreeRetriever;
 use crate::tools::{BuildTreeArgs, RaptorTool, RaptorToolCalls};
 use crate::log_info;
 use anyhow::Result;
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use tokio::sync::mpsc::Sender;
 use tokio::sync::Mutex as AsyncMutex;
 
@@ -26,6 +30,10 @@ use tokio::sync::Mutex as AsyncMutex;
 pub struct RaptorContextService {
     tool: Arc<RaptorTool>,
     embedder: Option<Arc<EmbeddingEngine>>,
+    /// Índices de otras raíces del espacio de trabajo consultados junto al propio
+    federation: Federation,
+    /// Similitud de los fragmentos recuperados por la última consulta de contexto
+    last_scores: Vec<f32>,
 }
 
 impl RaptorContextService {
@@ -34,9 +42,44 @@ impl RaptorContextService {
         Self {
             tool: Arc::new(RaptorTool::new(orchestrator)),
             embedder: None,
+            federation: Federation::new(),
+            last_scores: Vec::new(),
         }
     }
 
+    /// Consultar también los índices de `federation` (raíces hermanas)
+    pub fn with_federation(mut self, federation: Federation) -> Self {
+        self.federation = federation;
+        self
+    }
+
+    /// Raíces federadas configuradas
+    pub fn federation(&self) -> &Federation {
+        &self.federation
+    }
+
+    /// Similitud (coseno) de los fragmentos que recuperó el último
+    /// `get_planning_context`, de mayor a menor; vacío si no recuperó nada
+    pub fn last_scores(&self) -> &[f32] {
+        &self.last_scores
+    }
+
+    /// Fragmentos más relevantes de todas las raíces, cada uno etiquetado con la
+    /// suya; `None` sin raíces federadas. `label` nombra el directorio de trabajo.
+    pub async fn federated_context(&mut self, query: &str, label: &str, top_k: usize) -> Result<Option<String>> {
+        if self.federation.is_empty() {
+            return Ok(None);
+        }
+        self.initialize_embedder().await?;
+        let q_emb = self.embedder.as_ref().unwrap().embed_text(query).await?;
+        let hits = {
+            let store = GLOBAL_STORE.lock().unwrap();
+            let primary = (!store.chunk_embeddings.is_empty()).then_some((label, &*store));
+            self.federation.query_with(primary, &q_emb, top_k)
+        };
+        Ok((!hits.is_empty()).then(|| federation::format_hits(&hits)))
+    }
+
     /// Inicializar embedder (lazy loading para ahorrar memoria)
     pub async fn initialize_embedder(&mut self) -> Result<()> {
         if self.embedder.is_none() {
@@ -66,6 +109,58 @@ impl RaptorContextService {
         max_chars: Option<usize>,
         threshold: Option<f32>,
         progress_tx: Option<Sender<RaptorBuildProgress>>,
+    ) -> Result<String> {
+        let started = Instant::now();
+        let result = self.build_tree_inner(path, max_chars, threshold, progress_tx).await;
+
+        let summary = match &result {
+            Ok(output) => output.lines().next().unwrap_or_default().to_string(),
+            Err(e) => e.to_string(),
+        };
+        webhooks::notify(
+            TaskEvent::new(WebhookEventKind::IndexBuilt, result.is_ok(), format!("RAPTOR index of {}", path), started.elapsed())
+                .with_summary(summary),
+        );
+        result
+    }
+
+    /// Reconstruir el índice de `path` desde cero sin dejar de servir el actual:
+    /// se construye aparte y sustituye al anterior solo al completarse. Si falla,
+    /// el índice anterior sigue en uso. No toma el servicio, así que las
+    /// consultas no esperan a la reconstrucción.
+    pub async fn rebuild_tree_with_progress(
+        path: &str,
+        max_chars: Option<usize>,
+        threshold: Option<f32>,
+        progress_tx: Option<Sender<RaptorBuildProgress>>,
+    ) -> Result<usize> {
+        let started = Instant::now();
+        let result = builder::rebuild_tree_staged(
+            std::path::Path::new(path),
+            max_chars.unwrap_or(500),
+            50,
+            threshold.unwrap_or(0.7),
+            progress_tx,
+        )
+        .await;
+
+        let summary = match &result {
+            Ok(chunks) => format!("{} chunks", chunks),
+            Err(e) => e.to_string(),
+        };
+        webhooks::notify(
+            TaskEvent::new(WebhookEventKind::IndexBuilt, result.is_ok(), format!("RAPTOR index of {}", path), started.elapsed())
+                .with_summary(summary),
+        );
+        result
+    }
+
+    async fn build_tree_inner(
+        &mut self,
+        path: &str,
+        max_chars: Option<usize>,
+        threshold: Option<f32>,
+        progress_tx: Option<Sender<RaptorBuildProgress>>,
     ) -> Result<String> {
         self.initialize_embedder().await?;
         let args = BuildTreeArgs {
@@ -83,11 +178,37 @@ impl RaptorContextService {
         self.tool.query_raptor_tree(query, top_k).await
     }
 
+    /// Archivos de los chunks más relevantes para `query`, en orden de relevancia
+    pub async fn relevant_files(&mut self, query: &str, limit: usize) -> Result<Vec<String>> {
+        self.initialize_embedder().await?;
+        let store = {
+            let store_guard = GLOBAL_STORE.lock().unwrap();
+            store_guard.clone()
+        };
+        if store.chunk_map.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let retriever = TreeRetriever::new(self.embedder.as_ref().unwrap(), &store);
+        let (_, chunks) = retriever.retrieve_with_context(query, 12, 24).await?;
+        let mut files: Vec<String> = Vec::new();
+        for (id, _, _) in &chunks {
+            if let Some(file) = store.chunk_file(id) {
+                if !files.iter().any(|f| f == file) {
+                    files.push(file.to_string());
+                }
+            }
+        }
+        files.truncate(limit);
+        Ok(files)
+    }
+
     /// Obtener contexto enriquecido para el planning orchestrator
     ///
     /// Este método busca en el árbol RAPTOR y formatea los resultados
     /// de manera que puedan ser usados directamente por el planning orchestrator
     pub async fn get_planning_context(&mut self, task_description: &str) -> Result<String> {
+        self.last_scores.clear();
         self.initialize_embedder().await?;
 
         // Verificar si hay árbol construido
@@ -115,6 +236,14 @@ impl RaptorContextService {
         let (summaries, chunks) = retriever
             .retrieve_with_context(task_description, top_k, expand_k)
             .await?;
+        // Antes de reordenar: los impulsos por intención no son similitud
+        self.last_scores = chunks.iter().map(|(_, score, _)| *score).collect();
+        self.last_scores.sort_by(|a, b| b.total_cmp(a));
+        self.last_scores.truncate(top_k);
+        // Test, config, CI... chunks the query asks about rank first
+        let chunks = intent::infer(task_description).rerank(chunks, &store_clone);
+        // ...and the ones marked irrelevant for a similar query sink
+        let chunks = feedback::rerank(task_description, chunks);
 
         // Si no hay suficiente contexto, devolver diagnóstico
         if summaries.is_empty() && chunks.is_empty() {
@@ -226,25 +355,6 @@ impl RaptorContextService {
         Self::build_fallback_context_from_chunks(&store, limit)
     }
 
-    #[tokio::test]
-    #[ignore] // HEAVY: Requires embedding model (~500MB). Run manually: cargo test -- --ignored
-    async fn test_get_planning_context_comprehensive() {
-        let embedder = EmbeddingEngine::new().await.unwrap();
-
-        // Prepare a store with some chunks
-        {
-            let mut store = GLOBAL_STORE.lock().unwrap();
-            store.insert_chunk("c1".to_string(), "Contenido extenso sobre la arquitectura del proyecto: módulos, rutas, pruebas, y más...".to_string());
-            store.insert_chunk("c2".to_string(), "Notas de diseño: uso de RAPTOR, estrategia de indexado, y consideraciones".to_string());
-        }
-
-        let mut service = RaptorContextService::new(Arc::new(tokio::sync::Mutex::new(DualModelOrchestrator::with_config(crate::agent::orchestrator::OrchestratorConfig::default()).await.unwrap())));
-        service.initialize_embedder().await.unwrap();
-
-        let ctx = service.get_planning_context("explicar la arquitectura del proyecto").await.unwrap();
-        assert!(ctx.len() > 0, "Context should not be empty for prepared store");
-    }
-
     /// Limpiar árbol
     pub async fn clear(&self) -> Result<String> {
         self.tool.clear_raptor().await
@@ -422,6 +532,23 @@ mod tests {
         assert!(ctx.contains("helper function"));
     }
 
+    #[tokio::test]
+    #[ignore] // HEAVY: Requires embedding model (~500MB). Run manually: cargo test -- --ignored
+    async fn test_get_planning_context_comprehensive() {
+        // Prepare a store with some chunks
+        {
+            let mut store = GLOBAL_STORE.lock().unwrap();
+            store.insert_chunk("c1".to_string(), "Contenido extenso sobre la arquitectura del proyecto: módulos, rutas, pruebas, y más...".to_string());
+            store.insert_chunk("c2".to_string(), "Notas de diseño: uso de RAPTOR, estrategia de indexado, y consideraciones".to_string());
+        }
+
+        let mut service = RaptorContextService::new(Arc::new(AsyncMutex::new(DualModelOrchestrator::with_config(OrchestratorConfig::default()).await.unwrap())));
+        service.initialize_embedder().await.unwrap();
+
+        let ctx = service.get_planning_context("explicar la arquitectura del proyecto").await.unwrap();
+        assert!(!ctx.is_empty(), "Context should not be empty for prepared store");
+    }
+
     #[tokio::test]
     #[ignore] // Heavy test: loads embedding model and LLM. Run with: cargo test -- --ignored
     async fn test_raptor_service_integration() {
@@ -0,0 +1,374 @@
+//! Retrieval filters inferred from the intent of a query
+//!
+//! Each indexed chunk remembers the file it was read from, and the path says
+//! what kind of code it is: tests, config, CI workflows, docs, frontend or
+//! backend. Rules map words of the query to the kinds it prefers ("why does
+//! the CI fail" prefers workflows and config), and retrieval multiplies the
+//! score of matching chunks by the configured boost. The rules live under
+//! `[intents]` in the config and /why shows which ones applied.
+
+use crate::raptor::persistence::TreeStore;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::fmt;
+use std::sync::{OnceLock, RwLock};
+
+/// What a chunk is, judged by the path of its file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChunkKind {
+    Test,
+    Config,
+    Ci,
+    Docs,
+    Frontend,
+    Backend,
+    /// Production code: anything that is not tests, config, CI or docs
+    Source,
+}
+
+impl ChunkKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Test => "test",
+            Self::Config => "config",
+            Self::Ci => "ci",
+            Self::Docs => "docs",
+            Self::Frontend => "frontend",
+            Self::Backend => "backend",
+            Self::Source => "source",
+        }
+    }
+}
+
+impl fmt::Display for ChunkKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Kinds of a file; a file may have several, e.g. a frontend test
+pub fn kinds_of(path: &str) -> BTreeSet<ChunkKind> {
+    let path = path.replace('\\', "/").to_lowercase();
+    let name = path.rsplit('/').next().unwrap_or(&path);
+    let ext = name.rsplit_once('.').map(|(_, ext)| ext).unwrap_or("");
+    let in_dir = |dirs: &[&str]| {
+        dirs.iter()
+            .any(|dir| path.contains(&format!("/{}/", dir)) || path.starts_with(&format!("{}/", dir)))
+    };
+
+    let mut kinds = BTreeSet::new();
+    if in_dir(&[".github/workflows", ".circleci", ".gitlab", ".buildkite"])
+        || matches!(name, ".gitlab-ci.yml" | "jenkinsfile" | "azure-pipelines.yml" | ".travis.yml")
+    {
+        kinds.insert(ChunkKind::Ci);
+    }
+    if in_dir(&["tests", "test", "__tests__", "spec"])
+        || name.starts_with("test_")
+        || [".test.", ".spec.", "_test.", "_spec."].iter().any(|part| name.contains(part))
+    {
+        kinds.insert(ChunkKind::Test);
+    }
+    if matches!(ext, "toml" | "yaml" | "yml" | "json" | "ini" | "cfg" | "conf" | "lock")
+        || matches!(name, "dockerfile" | "makefile" | "docker-compose.yml")
+        || name.starts_with(".env")
+    {
+        kinds.insert(ChunkKind::Config);
+    }
+    if matches!(ext, "md" | "rst" | "adoc" | "txt") || in_dir(&["docs", "doc"]) {
+        kinds.insert(ChunkKind::Docs);
+    }
+    if matches!(ext, "tsx" | "jsx" | "vue" | "svelte" | "css" | "scss" | "sass" | "less" | "html")
+        || in_dir(&["frontend", "web", "ui", "client", "components", "pages"])
+    {
+        kinds.insert(ChunkKind::Frontend);
+    }
+    if ext == "sql" || in_dir(&["backend", "server", "api", "handlers", "routes", "migrations"]) {
+        kinds.insert(ChunkKind::Backend);
+    }
+    if !kinds
+        .iter()
+        .any(|kind| matches!(kind, ChunkKind::Test | ChunkKind::Config | ChunkKind::Ci | ChunkKind::Docs))
+    {
+        kinds.insert(ChunkKind::Source);
+    }
+    kinds
+}
+
+/// Words of a query that make retrieval prefer, or avoid, some kinds of chunks
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IntentRule {
+    /// Shown by /why when the rule applies
+    pub name: String,
+    /// Whole words or phrases, matched case-insensitively
+    pub keywords: Vec<String>,
+    #[serde(default)]
+    pub prefer: Vec<ChunkKind>,
+    #[serde(default)]
+    pub avoid: Vec<ChunkKind>,
+}
+
+impl IntentRule {
+    fn new(name: &str, keywords: &[&str], prefer: &[ChunkKind], avoid: &[ChunkKind]) -> Self {
+        Self {
+            name: name.to_string(),
+            keywords: keywords.iter().map(|keyword| keyword.to_string()).collect(),
+            prefer: prefer.to_vec(),
+            avoid: avoid.to_vec(),
+        }
+    }
+
+    /// First keyword found in `words`, a query lowercased with its words between spaces
+    fn matched(&self, words: &str) -> Option<&str> {
+        self.keywords
+            .iter()
+            .find(|keyword| words.contains(&format!(" {} ", keyword.to_lowercase())))
+            .map(String::as_str)
+    }
+}
+
+/// Intent filter settings (`[intents]` in the config)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IntentConfig {
+    /// Infer filters from queries at all
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+
+    /// Score multiplier of preferred chunks; avoided chunks are divided by it
+    #[serde(default = "default_boost")]
+    pub boost: f32,
+
+    /// Replace the built-in rules
+    #[serde(default = "default_rules")]
+    pub rules: Vec<IntentRule>,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+fn default_boost() -> f32 {
+    1.3
+}
+
+fn default_rules() -> Vec<IntentRule> {
+    use ChunkKind::*;
+    vec![
+        IntentRule::new(
+            "ci",
+            &["ci", "pipeline", "workflow", "github actions", "integración continua", "continuous integration"],
+            &[Ci, Config],
+            &[],
+        ),
+        IntentRule::new(
+            "tests",
+            &["test", "tests", "testing", "prueba", "pruebas", "flaky", "assert", "coverage", "cobertura"],
+            &[Test],
+            &[],
+        ),
+        IntentRule::new(
+            "config",
+            &["config", "configuración", "configuration", "settings", "ajustes", "env", "docker", "dependencias", "dependencies"],
+            &[Config],
+            &[],
+        ),
+        IntentRule::new(
+            "docs",
+            &["doc", "docs", "readme", "documentación", "documentation"],
+            &[Docs],
+            &[],
+        ),
+        IntentRule::new(
+            "frontend",
+            &["frontend", "ui", "css", "componente", "component", "react", "vista", "página", "page"],
+            &[Frontend],
+            &[],
+        ),
+        IntentRule::new(
+            "backend",
+            &["backend", "api", "endpoint", "servidor", "server", "base de datos", "database", "sql", "migración", "migration"],
+            &[Backend],
+            &[],
+        ),
+        IntentRule::new(
+            "producción",
+            &["producción", "production", "prod", "implementación", "implementation"],
+            &[Source],
+            &[Test, Docs],
+        ),
+    ]
+}
+
+impl Default for IntentConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_enabled(),
+            boost: default_boost(),
+            rules: default_rules(),
+        }
+    }
+}
+
+impl IntentConfig {
+    /// Filter the rules infer for `query`
+    pub fn infer(&self, query: &str) -> RetrievalFilter {
+        let mut filter = RetrievalFilter {
+            boost: self.boost,
+            ..RetrievalFilter::default()
+        };
+        if !self.enabled {
+            return filter;
+        }
+        let words: String = query
+            .to_lowercase()
+            .chars()
+            .map(|c| if c.is_alphanumeric() { c } else { ' ' })
+            .collect();
+        let words = format!(" {} ", words.split_whitespace().collect::<Vec<_>>().join(" "));
+        for rule in &self.rules {
+            if let Some(keyword) = rule.matched(&words) {
+                filter.prefer.extend(rule.prefer.iter().copied());
+                filter.avoid.extend(rule.avoid.iter().copied());
+                filter.matched.push((rule.name.clone(), keyword.to_string()));
+            }
+        }
+        // A kind both preferred and avoided by different rules is left alone
+        let both: Vec<ChunkKind> = filter.prefer.intersection(&filter.avoid).copied().collect();
+        for kind in both {
+            filter.prefer.remove(&kind);
+            filter.avoid.remove(&kind);
+        }
+        filter
+    }
+}
+
+fn config_lock() -> &'static RwLock<IntentConfig> {
+    static CONFIG: OnceLock<RwLock<IntentConfig>> = OnceLock::new();
+    CONFIG.get_or_init(|| RwLock::new(IntentConfig::default()))
+}
+
+/// Set the process-wide intent rules
+pub fn set_config(config: IntentConfig) {
+    *config_lock().write().unwrap_or_else(|e| e.into_inner()) = config;
+}
+
+/// The process-wide intent rules
+pub fn config() -> IntentConfig {
+    config_lock().read().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+/// Filter the configured rules infer for `query`
+pub fn infer(query: &str) -> RetrievalFilter {
+    config_lock().read().unwrap_or_else(|e| e.into_inner()).infer(query)
+}
+
+/// Kinds of chunks a query prefers and avoids, with the rules that decided it
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RetrievalFilter {
+    pub prefer: BTreeSet<ChunkKind>,
+    pub avoid: BTreeSet<ChunkKind>,
+    /// Name of each rule that applied and the keyword that triggered it
+    pub matched: Vec<(String, String)>,
+    boost: f32,
+}
+
+impl RetrievalFilter {
+    pub fn is_empty(&self) -> bool {
+        self.prefer.is_empty() && self.avoid.is_empty()
+    }
+
+    /// Score multiplier of a chunk of `kinds`; 1 when the filter says nothing about it
+    pub fn weight(&self, kinds: &BTreeSet<ChunkKind>) -> f32 {
+        if kinds.iter().any(|kind| self.prefer.contains(kind)) {
+            self.boost
+        } else if kinds.iter().any(|kind| self.avoid.contains(kind)) {
+            1.0 / self.boost
+        } else {
+            1.0
+        }
+    }
+
+    /// Reweigh `(chunk_id, score, text)` matches by the kind of their file and
+    /// sort them again; chunks of unknown origin keep their score
+    pub fn rerank(&self, mut chunks: Vec<(String, f32, String)>, store: &TreeStore) -> Vec<(String, f32, String)> {
+        if self.is_empty() {
+            return chunks;
+        }
+        for (id, score, _) in chunks.iter_mut() {
+            if let Some(file) = store.chunk_file(id) {
+                *score *= self.weight(&kinds_of(file));
+            }
+        }
+        chunks.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+        chunks
+    }
+
+    /// Preferred and avoided kinds and the rules behind them, for /why
+    pub fn describe(&self) -> String {
+        if self.is_empty() {
+            return "sin filtros: ninguna regla coincide con la consulta".to_string();
+        }
+        let join = |kinds: &BTreeSet<ChunkKind>| kinds.iter().map(ChunkKind::as_str).collect::<Vec<_>>().join(", ");
+        let mut parts = Vec::new();
+        if !self.prefer.is_empty() {
+            parts.push(format!("prefiere {} (×{})", join(&self.prefer), self.boost));
+        }
+        if !self.avoid.is_empty() {
+            parts.push(format!("evita {}", join(&self.avoid)));
+        }
+        let rules: Vec<String> = self
+            .matched
+            .iter()
+            .map(|(rule, keyword)| format!("{} por \"{}\"", rule, keyword))
+            .collect();
+        format!("{} · reglas: {}", parts.join(" · "), rules.join(", "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ChunkKind::*;
+
+    #[test]
+    fn test_kinds_of_paths() {
+        assert_eq!(kinds_of("/repo/.github/workflows/ci.yml"), BTreeSet::from([Ci, Config]));
+        assert_eq!(kinds_of("/repo/tests/router_test.rs"), BTreeSet::from([Test]));
+        assert_eq!(kinds_of("/repo/web/src/App.test.tsx"), BTreeSet::from([Test, Frontend]));
+        assert_eq!(kinds_of("/repo/server/api/users.rs"), BTreeSet::from([Backend, Source]));
+        assert_eq!(kinds_of("/repo/README.md"), BTreeSet::from([Docs]));
+        assert_eq!(kinds_of("/repo/src/latest.rs"), BTreeSet::from([Source]));
+    }
+
+    #[test]
+    fn test_infer_from_query() {
+        let config = IntentConfig::default();
+        let filter = config.infer("Why does the CI fail?");
+        assert_eq!(filter.prefer, BTreeSet::from([Ci, Config]));
+        assert_eq!(filter.matched, vec![("ci".to_string(), "ci".to_string())]);
+
+        let filter = config.infer("arregla la implementación, no las pruebas");
+        assert_eq!(filter.prefer, BTreeSet::from([Source]));
+        assert_eq!(filter.avoid, BTreeSet::from([Docs]));
+
+        // Whole words only: "city" does not mention the CI
+        assert!(config.infer("list every city").is_empty());
+        assert!(IntentConfig { enabled: false, ..config }.infer("fix the CI").is_empty());
+    }
+
+    #[test]
+    fn test_rerank_prefers_matching_chunks() {
+        let mut store = TreeStore::new();
+        store.insert_file_chunk("a".to_string(), "/repo/src/build.rs", "fn build()".to_string());
+        store.insert_file_chunk("b".to_string(), "/repo/.github/workflows/ci.yml", "run: cargo test".to_string());
+        let chunks = vec![
+            ("a".to_string(), 0.8, "fn build()".to_string()),
+            ("b".to_string(), 0.7, "run: cargo test".to_string()),
+            ("c".to_string(), 0.75, "scratch".to_string()),
+        ];
+
+        let ranked = IntentConfig::default().infer("why does the CI fail").rerank(chunks, &store);
+        let ids: Vec<&str> = ranked.iter().map(|(id, _, _)| id.as_str()).collect();
+        assert_eq!(ids, vec!["b", "a", "c"]);
+    }
+}
@@ -1,7 +1,10 @@
 pub mod builder;
 pub mod chunker;
 pub mod clustering;
+pub mod federation;
+pub mod feedback;
 pub mod incremental;
+pub mod intent;
 pub mod integration;
 pub mod persistence;
 pub mod retriever;
@@ -10,7 +13,10 @@ pub mod summarizer;
 pub use builder::*;
 pub use chunker::*;
 pub use clustering::*;
+pub use federation::*;
+pub use feedback::NegativePair;
 pub use incremental::*;
+pub use intent::{ChunkKind, IntentConfig, IntentRule, RetrievalFilter};
 pub use integration::*;
 pub use persistence::*;
 pub use retriever::*;
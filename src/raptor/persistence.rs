@@ -71,8 +71,17 @@ pub struct TreeStore {
     pub indexed_files: HashMap<String, u64>, // file_path -> modified_time
     #[serde(default)]
     pub indexing_complete: bool,
+
+    // File each indexed chunk was read from, for intent filters
+    #[serde(default)]
+    pub chunk_files: HashMap<String, String>, // chunk_id -> file_path
 }
 
+/// Chunk id prefix of the scratch namespace
+pub const SCRATCH_PREFIX: &str = "scratch:";
+/// Chunk id prefix of the docs namespace (ingested reference material)
+pub const DOCS_PREFIX: &str = "docs:";
+
 /// Memory limits configuration
 #[derive(Debug, Clone, Copy)]
 pub struct MemoryLimits {
@@ -126,6 +135,7 @@ impl TreeStore {
             created_at: 0,
             indexed_files: HashMap::new(),
             indexing_complete: false,
+            chunk_files: HashMap::new(),
         }
     }
 
@@ -141,6 +151,7 @@ impl TreeStore {
         self.created_at = 0;
         self.indexed_files.clear();
         self.indexing_complete = false;
+        self.chunk_files.clear();
         // Shrink to free memory
         self.nodes.shrink_to_fit();
         self.chunk_map.shrink_to_fit();
@@ -148,6 +159,35 @@ impl TreeStore {
         self.chunk_embeddings.shrink_to_fit();
         self.tree_nodes.shrink_to_fit();
         self.indexed_files.shrink_to_fit();
+        self.chunk_files.shrink_to_fit();
+    }
+
+    /// Replace the tree with `nodes`, linking each child to its parent
+    pub fn set_tree(&mut self, nodes: Vec<TreeNode>, root: Option<String>) {
+        self.tree_nodes.clear();
+        for node in nodes {
+            for child_id in &node.children {
+                if let Some(child) = self.tree_nodes.get_mut(child_id) {
+                    child.parent_id = Some(node.id.clone());
+                }
+            }
+            self.tree_nodes.insert(node.id.clone(), node);
+        }
+        if root.is_some() {
+            self.tree_root = root;
+        }
+    }
+
+    /// Copy the scratch and docs chunks of `other`, which are not rebuilt from
+    /// the project files
+    fn carry_namespaces(&mut self, other: &TreeStore) {
+        let namespaced = |id: &String| id.starts_with(SCRATCH_PREFIX) || id.starts_with(DOCS_PREFIX);
+        for (id, content) in other.chunk_map.iter().filter(|(id, _)| namespaced(id)) {
+            self.chunk_map.insert(id.clone(), content.clone());
+        }
+        for (id, emb) in other.chunk_embeddings.iter().filter(|(id, _)| namespaced(id)) {
+            self.chunk_embeddings.insert(id.clone(), emb.clone());
+        }
     }
 
     /// Check if store is at capacity
@@ -174,6 +214,19 @@ impl TreeStore {
         self.chunk_map.insert(chunk_id, content);
     }
 
+    /// Insert a chunk read from `file_path`, remembering where it came from
+    pub fn insert_file_chunk(&mut self, chunk_id: String, file_path: &str, content: String) {
+        self.insert_chunk(chunk_id.clone(), content);
+        if self.chunk_map.contains_key(&chunk_id) {
+            self.chunk_files.insert(chunk_id, file_path.to_string());
+        }
+    }
+
+    /// File a chunk was read from; `None` for namespaced or older chunks
+    pub fn chunk_file(&self, id: &str) -> Option<&str> {
+        self.chunk_files.get(id).map(String::as_str)
+    }
+
     pub fn get_node(&self, id: &str) -> Option<&super::summarizer::SummaryNode> {
         self.nodes.get(id)
     }
@@ -182,6 +235,70 @@ impl TreeStore {
         self.chunk_map.get(id)
     }
 
+    /// Store `text` under the scratch namespace `name`, replacing its previous
+    /// content. Scratch chunks (ids `scratch:<name>:<n>`) hold tool output such
+    /// as HTTP responses so later queries can use it as context.
+    /// Returns the number of chunks stored.
+    pub fn insert_scratch(&mut self, name: &str, text: &str) -> usize {
+        self.insert_namespaced(SCRATCH_PREFIX, name, text)
+    }
+
+    /// Chunks of the scratch namespace `name`, in order
+    pub fn scratch_chunks(&self, name: &str) -> Vec<&String> {
+        self.namespaced_chunks(SCRATCH_PREFIX, name)
+    }
+
+    /// Store `text` under the docs namespace `name` (ids `docs:<name>:<n>`),
+    /// replacing its previous content. Used for reference material such as
+    /// database schemas. Returns the number of chunks stored.
+    pub fn insert_docs(&mut self, name: &str, text: &str) -> usize {
+        self.insert_namespaced(DOCS_PREFIX, name, text)
+    }
+
+    /// Chunks of the docs namespace `name`, in order
+    pub fn docs_chunks(&self, name: &str) -> Vec<&String> {
+        self.namespaced_chunks(DOCS_PREFIX, name)
+    }
+
+    /// Names of the docs namespaces holding chunks, sorted
+    pub fn docs_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self
+            .chunk_map
+            .keys()
+            .filter_map(|id| id.strip_prefix(DOCS_PREFIX)?.rsplit_once(':'))
+            .map(|(name, _)| name.to_string())
+            .collect();
+        names.sort();
+        names.dedup();
+        names
+    }
+
+    fn insert_namespaced(&mut self, namespace: &str, name: &str, text: &str) -> usize {
+        let prefix = format!("{}{}:", namespace, name);
+        self.chunk_map.retain(|id, _| !id.starts_with(&prefix));
+        self.chunk_embeddings.retain(|id, _| !id.starts_with(&prefix));
+
+        let before = self.chunk_map.len();
+        for (i, chunk) in super::chunker::chunk_text(text, 1500, 200).into_iter().enumerate() {
+            self.insert_chunk(format!("{}{}", prefix, i), chunk);
+        }
+        self.chunk_map.len() - before
+    }
+
+    fn namespaced_chunks(&self, namespace: &str, name: &str) -> Vec<&String> {
+        let prefix = format!("{}{}:", namespace, name);
+        let mut chunks: Vec<(usize, &String)> = self
+            .chunk_map
+            .iter()
+            .filter_map(|(id, content)| {
+                let index = id.strip_prefix(&prefix)?.parse().ok()?;
+                Some((index, content))
+            })
+            .collect();
+        chunks.sort_by_key(|(index, _)| *index);
+        chunks.into_iter().map(|(_, content)| content).collect()
+    }
+
     /// Insert a precomputed embedding for a summary node
     pub fn insert_summary_embedding(&mut self, node_id: String, emb: Vec<f32>) {
         let limits = get_dynamic_limits();
@@ -365,6 +482,15 @@ lazy_static::lazy_static! {
     pub static ref GLOBAL_STORE: Mutex<TreeStore> = Mutex::new(TreeStore::new());
 }
 
+/// Make `staged`, a store built off to the side, the global one in a single
+/// step, keeping the scratch and docs namespaces of the current store. Returns
+/// the replaced store.
+pub fn swap_global_store(mut staged: TreeStore) -> TreeStore {
+    let mut global = GLOBAL_STORE.lock().unwrap();
+    staged.carry_namespaces(&global);
+    std::mem::replace(&mut *global, staged)
+}
+
 /// Try to load RAPTOR cache from disk
 pub fn load_cache_if_valid(project_path: &str) -> bool {
     let cache_path = TreeStore::cache_path_for(project_path);
@@ -390,3 +516,60 @@ pub fn save_cache(project_path: &str) -> Result<()> {
     store.set_metadata(project_path);
     store.save_to(cache_path)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scratch_namespace_replaces_previous_content() {
+        let mut store = TreeStore::new();
+        store.insert_chunk("c1".to_string(), "fn main() {}".to_string());
+
+        assert_eq!(store.insert_scratch("api", "first response"), 1);
+        assert_eq!(store.insert_scratch("api", "second response"), 1);
+
+        assert_eq!(store.scratch_chunks("api"), vec!["second response"]);
+        assert!(store.scratch_chunks("other").is_empty());
+        assert_eq!(store.chunk_map.len(), 2);
+    }
+
+    #[test]
+    fn test_docs_namespace_is_separate_from_scratch() {
+        let mut store = TreeStore::new();
+        store.insert_scratch("schema", "scratch text");
+        store.insert_docs("schema", "### users");
+
+        assert_eq!(store.docs_chunks("schema"), vec!["### users"]);
+        assert_eq!(store.scratch_chunks("schema"), vec!["scratch text"]);
+
+        store.insert_docs("dep:serde", "# serde");
+        assert_eq!(store.docs_names(), vec!["dep:serde", "schema"]);
+    }
+
+    #[test]
+    fn test_rebuilt_store_keeps_namespaces() {
+        let mut live = TreeStore::new();
+        live.insert_file_chunk("old".to_string(), "src/lib.rs", "fn old() {}".to_string());
+        live.insert_scratch("api", "cached response");
+        live.insert_docs("schema", "### users");
+
+        let mut staged = TreeStore::new();
+        staged.insert_file_chunk("new".to_string(), "src/lib.rs", "fn new() {}".to_string());
+        staged.set_tree(
+            vec![
+                TreeNode::new_leaf("leaf".to_string(), "new".to_string(), vec![1.0]),
+                TreeNode::new_internal("root".to_string(), vec!["leaf".to_string()], vec![1.0], 1),
+            ],
+            Some("root".to_string()),
+        );
+        staged.carry_namespaces(&live);
+
+        assert!(staged.get_chunk("old").is_none());
+        assert_eq!(staged.chunk_file("new"), Some("src/lib.rs"));
+        assert_eq!(staged.scratch_chunks("api"), vec!["cached response"]);
+        assert_eq!(staged.docs_chunks("schema"), vec!["### users"]);
+        assert_eq!(staged.tree_nodes["leaf"].parent_id.as_deref(), Some("root"));
+        assert_eq!(staged.tree_root.as_deref(), Some("root"));
+    }
+}
@@ -2,7 +2,7 @@
 //!
 //! Divides code into semantic chunks (functions, structs, modules) for embedding generation.
 
-use crate::ast::{AstParser, AstSymbol, Range, SupportedLanguage, SymbolKind};
+use crate::ast::{detect, AstParser, AstSymbol, Range, SupportedLanguage, SymbolKind};
 use anyhow::{Context, Result};
 use std::path::{Path, PathBuf};
 use uuid::Uuid;
@@ -197,20 +197,39 @@ impl CodeChunker {
         content: &str,
         language: &str,
     ) -> Result<Vec<CodeChunk>> {
-        let supported_lang = SupportedLanguage::parse_language(language);
+        // What the content says (shebang, modeline, ambiguous extension) beats the caller's guess
+        let detected = detect::from_content(file_path, content).map(str::to_lowercase);
+        let language = detected.as_deref().unwrap_or(language);
+        let supported_lang = match detected {
+            Some(_) => SupportedLanguage::parse_language(language),
+            None => SupportedLanguage::parse_language(language)
+                .or_else(|| SupportedLanguage::from_path(file_path)),
+        };
 
         // If language not supported, fall back to simple chunking
         let Some(lang) = supported_lang else {
             return Ok(self.chunk_by_lines(file_path, content, language));
         };
 
-        // Parse with AST
+        // Parse with AST, incrementally when the file was chunked before
         let tree = self
             .ast_parser
-            .parse(lang, content)
-            .context("Failed to parse file")?;
-
-        let symbols = self.ast_parser.extract_symbols(&tree, lang, content);
+            .parse_file(file_path, lang, content)
+            .context("Failed to parse file")?
+            .tree;
+
+        let mut symbols = self.ast_parser.extract_symbols(&tree, lang, content);
+
+        // A module/namespace that wraps other symbols would duplicate their text
+        let ranges: Vec<Range> = symbols.iter().map(|s| s.range.clone()).collect();
+        symbols.retain(|s| {
+            s.kind != SymbolKind::Module
+                || !ranges.iter().any(|r| {
+                    *r != s.range
+                        && r.start_line >= s.range.start_line
+                        && r.end_line <= s.range.end_line
+                })
+        });
 
         // If no symbols found, fall back to file-level chunk
         if symbols.is_empty() {
@@ -357,6 +376,44 @@ class Calculator:
         assert!(chunks.len() >= 2);
     }
 
+    #[test]
+    fn test_chunk_cpp_file() {
+        let code = r#"
+#include <string>
+
+namespace app {
+class Greeter {
+public:
+    std::string greet(const std::string &name) const {
+        return "Hello, " + name;
+    }
+};
+}
+"#;
+
+        let mut chunker = CodeChunker::new().unwrap();
+        let chunks = chunker
+            .chunk_file(Path::new("greeter.hpp"), code, "cpp")
+            .unwrap();
+
+        // The namespace wraps the class, so only the class and method are chunked
+        assert_eq!(chunks.len(), 2);
+        assert_eq!(chunks[0].chunk_type, ChunkType::Class);
+        assert_eq!(chunks[1].symbol_name.as_deref(), Some("greet"));
+    }
+
+    #[test]
+    fn test_chunk_script_by_shebang() {
+        let code = "#!/usr/bin/env python3\n\ndef main():\n    print('deploy')\n";
+
+        let mut chunker = CodeChunker::new().unwrap();
+        let chunks = chunker.chunk_file(Path::new("deploy"), code, "text").unwrap();
+
+        assert_eq!(chunks.len(), 1);
+        assert_eq!(chunks[0].chunk_type, ChunkType::Function);
+        assert_eq!(chunks[0].language, "python");
+    }
+
     #[test]
     fn test_chunk_by_lines() {
         let code = "Line 1\nLine 2\nLine 3";
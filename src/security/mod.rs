@@ -1,7 +1,12 @@
-//! Security module for command scanning and password management
+//! Security module for command scanning, password management, privacy modes
+//! and the outbound network policy
 
+pub mod network;
 mod password;
+pub mod privacy;
 mod scanner;
 
+pub use network::{NetworkError, NetworkPolicy, SendChecked};
 pub use password::PasswordManager;
+pub use privacy::{PrivacyError, PrivacyLevel};
 pub use scanner::{CommandScanner, RiskLevel};
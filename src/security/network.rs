@@ -0,0 +1,273 @@
+//! Outbound network policy
+//!
+//! Every HTTP request the agent makes (model providers, `http_request`,
+//! dependency docs) is built from [`client`] or [`client_builder`] and sent
+//! with [`SendChecked::send_checked`], which checks the destination against
+//! the privacy mode and the host allow-list first, including every redirect,
+//! and masks secrets in the body when the privacy mode is `redacted`.
+//! Nothing else talks to the network: there is no telemetry.
+//!
+//! In strict mode a host that is not in `allowed_hosts` is blocked; otherwise
+//! it is allowed and logged. Loopback hosts are always allowed. Blocked
+//! requests are logged and kept for `/network`.
+
+use super::privacy::{self, PrivacyError};
+use crate::{log_info, log_warn};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::future::Future;
+use std::net::IpAddr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Mutex, OnceLock, RwLock};
+use std::time::{Duration, SystemTime};
+use thiserror::Error;
+
+/// Blocked requests kept for `/network`
+const MAX_BLOCKED_KEPT: usize = 50;
+/// Redirects followed by the shared client
+const MAX_REDIRECTS: usize = 10;
+
+static NOT_ALLOWED: AtomicUsize = AtomicUsize::new(0);
+
+/// Which hosts the agent may reach
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NetworkPolicy {
+    /// Block every host not in `allowed_hosts`
+    #[serde(default)]
+    pub strict: bool,
+
+    /// Host names, optionally `*.domain` for its subdomains
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allowed_hosts: Vec<String>,
+}
+
+impl NetworkPolicy {
+    /// Whether `host` is loopback or matches an allow-list entry
+    pub fn allows(&self, host: &str) -> bool {
+        let host = host.trim_start_matches('[').trim_end_matches(']').to_lowercase();
+        if host == "localhost" || host.parse::<IpAddr>().is_ok_and(|ip| ip.is_loopback()) {
+            return true;
+        }
+        self.allowed_hosts.iter().any(|entry| host_matches(&host, entry))
+    }
+}
+
+/// Whether `host` is `entry`, or one of its subdomains when `entry` is `*.domain`
+pub fn host_matches(host: &str, entry: &str) -> bool {
+    let host = host.trim_start_matches('[').trim_end_matches(']').to_lowercase();
+    let entry = entry.trim().to_lowercase();
+    match entry.strip_prefix("*.") {
+        Some(domain) => host.ends_with(&format!(".{}", domain)),
+        None => host == entry,
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum NetworkError {
+    #[error("{0}")]
+    Privacy(#[from] PrivacyError),
+    #[error("Blocked by network policy: {what} tried to reach {host}, which is not in network.allowed_hosts")]
+    NotAllowed { what: String, host: String },
+    #[error("Request error: {0}")]
+    Request(#[from] reqwest::Error),
+}
+
+/// An outbound request that was refused
+#[derive(Debug, Clone)]
+pub struct BlockedRequest {
+    pub at: SystemTime,
+    pub what: String,
+    pub url: String,
+    pub reason: String,
+}
+
+fn policy_lock() -> &'static RwLock<NetworkPolicy> {
+    static POLICY: OnceLock<RwLock<NetworkPolicy>> = OnceLock::new();
+    POLICY.get_or_init(|| RwLock::new(NetworkPolicy::default()))
+}
+
+fn blocked_log() -> &'static Mutex<VecDeque<BlockedRequest>> {
+    static BLOCKED: OnceLock<Mutex<VecDeque<BlockedRequest>>> = OnceLock::new();
+    BLOCKED.get_or_init(|| Mutex::new(VecDeque::new()))
+}
+
+/// Set the process-wide policy
+pub fn set_network_policy(policy: NetworkPolicy) {
+    *policy_lock().write().unwrap_or_else(|e| e.into_inner()) = policy;
+}
+
+/// Current process-wide policy
+pub fn network_policy() -> NetworkPolicy {
+    policy_lock().read().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+/// Latest blocked requests, oldest first
+pub fn blocked_requests() -> Vec<BlockedRequest> {
+    blocked_log().lock().unwrap_or_else(|e| e.into_inner()).iter().cloned().collect()
+}
+
+/// Requests blocked by the privacy mode or the allow-list since startup
+pub fn blocked_count() -> usize {
+    privacy::blocked_attempts() + NOT_ALLOWED.load(Ordering::SeqCst)
+}
+
+fn record(what: &str, url: &str, reason: String) {
+    let mut log = blocked_log().lock().unwrap_or_else(|e| e.into_inner());
+    if log.len() == MAX_BLOCKED_KEPT {
+        log.pop_front();
+    }
+    log.push_back(BlockedRequest {
+        at: SystemTime::now(),
+        what: what.to_string(),
+        url: url.to_string(),
+        reason,
+    });
+}
+
+/// Refuse a request to `url` that the privacy mode or the policy does not allow
+pub fn check(url: &str, what: &str) -> Result<(), NetworkError> {
+    if let Err(e) = privacy::check_outbound(url, what) {
+        record(what, url, e.to_string());
+        return Err(e.into());
+    }
+    let host = reqwest::Url::parse(url)
+        .ok()
+        .and_then(|u| u.host_str().map(String::from))
+        .unwrap_or_default();
+    let policy = policy_lock().read().unwrap_or_else(|e| e.into_inner());
+    if policy.allows(&host) {
+        return Ok(());
+    }
+    if !policy.strict {
+        log_info!("🌐 [NETWORK] {} → {} (not in allowed_hosts)", what, host);
+        return Ok(());
+    }
+    drop(policy);
+    NOT_ALLOWED.fetch_add(1, Ordering::SeqCst);
+    log_warn!("🛡️ [NETWORK] Blocked {} → {}", what, url);
+    let error = NetworkError::NotAllowed {
+        what: what.to_string(),
+        host,
+    };
+    record(what, url, error.to_string());
+    Err(error)
+}
+
+/// Builder for clients that re-check every redirect against the policy
+pub fn client_builder() -> reqwest::ClientBuilder {
+    reqwest::Client::builder()
+        .user_agent(concat!("neuro-agent/", env!("CARGO_PKG_VERSION")))
+        .redirect(redirect_policy(|_| true))
+}
+
+/// Redirect policy of [`client_builder`], also refusing targets `allowed` rejects
+pub fn redirect_policy(
+    allowed: impl Fn(&reqwest::Url) -> bool + Send + Sync + 'static,
+) -> reqwest::redirect::Policy {
+    reqwest::redirect::Policy::custom(move |attempt| {
+        if attempt.previous().len() >= MAX_REDIRECTS {
+            attempt.error("too many redirects")
+        } else if let Err(e) = check(attempt.url().as_str(), "redirect") {
+            attempt.error(e)
+        } else if !allowed(attempt.url()) {
+            let url = attempt.url().to_string();
+            attempt.error(format!("redirect to {} is not allowed for this request", url))
+        } else {
+            attempt.follow()
+        }
+    })
+}
+
+/// The shared client, for requests without their own timeout or redirect settings
+pub fn client() -> reqwest::Client {
+    static CLIENT: OnceLock<reqwest::Client> = OnceLock::new();
+    CLIENT
+        .get_or_init(|| {
+            client_builder()
+                .connect_timeout(Duration::from_secs(10))
+                .build()
+                .unwrap_or_default()
+        })
+        .clone()
+}
+
+/// `send` for requests that must pass the network policy
+pub trait SendChecked {
+    /// Check the destination and redact the body, then send; `what` names
+    /// the caller in the log
+    fn send_checked(self, what: &str) -> impl Future<Output = Result<reqwest::Response, NetworkError>> + Send;
+}
+
+impl SendChecked for reqwest::RequestBuilder {
+    fn send_checked(self, what: &str) -> impl Future<Output = Result<reqwest::Response, NetworkError>> + Send {
+        let what = what.to_string();
+        async move {
+            let (client, request) = self.build_split();
+            let mut request = request?;
+            check(request.url().as_str(), &what)?;
+            redact_body(&mut request);
+            Ok(client.execute(request).await?)
+        }
+    }
+}
+
+/// Mask secrets in the body of a request leaving the machine in `redacted` mode
+fn redact_body(request: &mut reqwest::Request) {
+    if privacy::privacy_level() == privacy::PrivacyLevel::Redacted
+        && !privacy::is_local_url(request.url().as_str())
+    {
+        mask_body(request);
+    }
+}
+
+fn mask_body(request: &mut reqwest::Request) {
+    // Streamed bodies have no bytes to rewrite; every caller sends a buffered one
+    let Some(bytes) = request.body().and_then(|body| body.as_bytes()) else {
+        return;
+    };
+    let redacted = match serde_json::from_slice::<serde_json::Value>(bytes) {
+        Ok(json) => serde_json::to_vec(&privacy::redact_json(&json)).ok(),
+        Err(_) => std::str::from_utf8(bytes).ok().map(|text| privacy::redact(text).into_bytes()),
+    };
+    if let Some(redacted) = redacted {
+        *request.body_mut() = Some(redacted.into());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_allow_list_matching() {
+        let policy = NetworkPolicy {
+            strict: true,
+            allowed_hosts: vec!["api.openai.com".to_string(), "*.docs.rs".to_string()],
+        };
+        assert!(policy.allows("api.openai.com"));
+        assert!(policy.allows("static.docs.rs"));
+        assert!(!policy.allows("docs.rs"));
+        assert!(!policy.allows("evil-api.openai.com"));
+        assert!(policy.allows("localhost"));
+        assert!(policy.allows("127.0.0.1"));
+        assert!(policy.allows("[::1]"));
+        assert!(!policy.allows("telemetry.example.com"));
+    }
+
+    #[test]
+    fn test_mask_body() {
+        let client = reqwest::Client::new();
+        let mut chat = client
+            .post("https://api.example.com/api/chat")
+            .json(&serde_json::json!({"messages": [{"content": "key sk-abcdefghijklmnopqrstuv"}], "stream": false}))
+            .build()
+            .unwrap();
+        mask_body(&mut chat);
+        let body = String::from_utf8(chat.body().unwrap().as_bytes().unwrap().to_vec()).unwrap();
+        assert_eq!(body, r#"{"messages":[{"content":"key [REDACTED]"}],"stream":false}"#);
+
+        let mut text = client.post("https://hooks.example.com").body("password=hunter2").build().unwrap();
+        mask_body(&mut text);
+        assert_eq!(text.body().unwrap().as_bytes().unwrap(), b"password=[REDACTED]");
+    }
+}
@@ -1,5 +1,7 @@
 //! Code analyzer - Analyze code structure and complexity
 
+use crate::ast::{self, AstParser, SupportedLanguage};
+use crate::context::call_graph::short_name;
 use serde::{Deserialize, Serialize};
 use std::path::{Path, PathBuf};
 use tokio::fs;
@@ -117,7 +119,7 @@ impl CodeAnalyzerTool {
             .await
             .map_err(|e| AnalyzerError::IoError(e.to_string()))?;
 
-        let language = detect_language(&path);
+        let language = detect_language(&path, &content);
         let metrics = calculate_metrics(&content, &language);
         let symbols = extract_symbols(&content, &language);
         let imports = extract_imports(&content, &language);
@@ -143,7 +145,7 @@ impl CodeAnalyzerTool {
             .await
             .map_err(|e| AnalyzerError::IoError(e.to_string()))?;
 
-        let language = detect_language(&path);
+        let language = detect_language(&path, &content);
         let symbols = extract_symbols(&content, &language);
 
         let symbol = symbols
@@ -158,14 +160,41 @@ impl CodeAnalyzerTool {
             .unwrap_or_default();
 
         let complexity = calculate_cyclomatic_complexity(&symbol_content);
-        let calls = extract_function_calls(&symbol_content, &language);
         let variables = extract_local_variables(&symbol_content, &language);
 
+        // The AST call graph where the language has a grammar, call-like patterns otherwise
+        let (calls, called_by) = match extract_call_graph(&content, &language) {
+            Some(edges) => {
+                let mut calls: Vec<String> = edges
+                    .iter()
+                    .filter(|e| {
+                        e.caller.as_deref() == Some(symbol.name.as_str())
+                            && (symbol.line_start..=symbol.line_end).contains(&e.line)
+                    })
+                    .map(|e| e.callee.clone())
+                    .collect();
+                calls.sort();
+                calls.dedup();
+                let short = short_name(&symbol.name);
+                let called_by = edges
+                    .iter()
+                    .filter(|e| e.callee == short)
+                    .map(|e| match &e.caller {
+                        Some(caller) => format!("{} (line {})", caller, e.line),
+                        None => format!("top level (line {})", e.line),
+                    })
+                    .collect();
+                (calls, called_by)
+            }
+            None => (extract_function_calls(&symbol_content, &language), Vec::new()),
+        };
+
         Ok(SymbolAnalysis {
             symbol,
             content: symbol_content,
             complexity,
             function_calls: calls,
+            called_by,
             local_variables: variables,
         })
     }
@@ -286,6 +315,9 @@ pub struct SymbolAnalysis {
     pub content: String,
     pub complexity: usize,
     pub function_calls: Vec<String>,
+    /// Calls of the symbol in its own file: `main (line 12)`
+    #[serde(default)]
+    pub called_by: Vec<String>,
     pub local_variables: Vec<String>,
 }
 
@@ -322,28 +354,11 @@ pub enum AnalyzerError {
     ParseError(String),
 }
 
-fn detect_language(path: &Path) -> String {
-    let ext = path
-        .extension()
-        .map(|e| e.to_string_lossy().to_lowercase())
-        .unwrap_or_default();
-
-    match ext.as_str() {
-        "rs" => "Rust",
-        "py" => "Python",
-        "js" | "mjs" => "JavaScript",
-        "ts" | "mts" => "TypeScript",
-        "tsx" | "jsx" => "React",
-        "go" => "Go",
-        "java" => "Java",
-        "c" | "h" => "C",
-        "cpp" | "cc" | "hpp" => "C++",
-        "cs" => "C#",
-        "rb" => "Ruby",
-        "php" => "PHP",
-        _ => "Unknown",
-    }
-    .to_string()
+/// Language from the content (shebang, modeline, ambiguous extension), then the file name
+fn detect_language(path: &Path, content: &str) -> String {
+    ast::detect::detect(path, content)
+        .unwrap_or("Unknown")
+        .to_string()
 }
 
 fn calculate_metrics(content: &str, language: &str) -> CodeMetrics {
@@ -367,7 +382,8 @@ fn calculate_metrics(content: &str, language: &str) -> CodeMetrics {
 
         // Check for comments
         let is_comment = match language {
-            "Rust" | "JavaScript" | "TypeScript" | "Java" | "C" | "C++" | "Go" | "C#" => {
+            "Rust" | "JavaScript" | "TypeScript" | "Java" | "Kotlin" | "C" | "C++" | "Go"
+            | "C#" => {
                 if trimmed.starts_with("/*") {
                     in_multiline_comment = true;
                 }
@@ -445,6 +461,10 @@ fn calculate_metrics(content: &str, language: &str) -> CodeMetrics {
 }
 
 fn extract_symbols(content: &str, language: &str) -> Vec<CodeSymbol> {
+    if matches!(language, "C" | "C++" | "Ruby" | "PHP" | "Go" | "Java" | "Kotlin") {
+        return extract_ast_symbols(content, language);
+    }
+
     let mut symbols = Vec::new();
     let lines: Vec<&str> = content.lines().collect();
 
@@ -524,8 +544,72 @@ fn extract_symbols(content: &str, language: &str) -> Vec<CodeSymbol> {
     symbols
 }
 
+/// Symbols from the tree-sitter AST, for languages where line heuristics are
+/// unreliable (C/C++ return types and templates, Ruby `end` blocks, PHP modifiers,
+/// Go receivers, Java and Kotlin annotations)
+fn extract_ast_symbols(content: &str, language: &str) -> Vec<CodeSymbol> {
+    let Some(lang) = SupportedLanguage::parse_language(language) else {
+        return Vec::new();
+    };
+    let Ok(mut parser) = AstParser::new() else {
+        return Vec::new();
+    };
+    let Ok(tree) = parser.parse(lang, content) else {
+        return Vec::new();
+    };
+
+    let lines: Vec<&str> = content.lines().collect();
+    parser
+        .extract_symbols(&tree, lang, content)
+        .into_iter()
+        .map(|symbol| {
+            let body = lines
+                .get(symbol.range.start_line - 1..symbol.range.end_line.min(lines.len()))
+                .map(|l| l.join("\n"))
+                .unwrap_or_default();
+            CodeSymbol {
+                symbol_type: match symbol.kind {
+                    ast::SymbolKind::Function => SymbolType::Function,
+                    ast::SymbolKind::Method => SymbolType::Method,
+                    ast::SymbolKind::Struct => SymbolType::Struct,
+                    ast::SymbolKind::Class => SymbolType::Class,
+                    ast::SymbolKind::Enum => SymbolType::Enum,
+                    ast::SymbolKind::Trait => SymbolType::Trait,
+                    ast::SymbolKind::Interface => SymbolType::Interface,
+                    ast::SymbolKind::Constant => SymbolType::Constant,
+                    ast::SymbolKind::Variable => SymbolType::Variable,
+                    ast::SymbolKind::Module => SymbolType::Module,
+                },
+                visibility: match symbol.visibility {
+                    ast::Visibility::Public => Visibility::Public,
+                    ast::Visibility::Private => Visibility::Private,
+                    ast::Visibility::Protected => Visibility::Protected,
+                    ast::Visibility::Internal => Visibility::Internal,
+                },
+                params: symbol
+                    .params
+                    .iter()
+                    .map(|p| match &p.type_annotation {
+                        Some(ty) => format!("{} {}", ty, p.name),
+                        None => p.name.clone(),
+                    })
+                    .collect(),
+                line_start: symbol.range.start_line,
+                line_end: symbol.range.end_line,
+                complexity: calculate_cyclomatic_complexity(&body),
+                return_type: symbol.return_type,
+                name: symbol.name,
+            }
+        })
+        .collect()
+}
+
 fn extract_imports(content: &str, language: &str) -> Vec<ImportInfo> {
     let mut imports = Vec::new();
+    // Inside a Go `import ( ... )` block
+    let mut in_import_block = false;
+    // Java/Kotlin `package` of the file
+    let mut package: Option<String> = None;
 
     for (i, line) in content.lines().enumerate() {
         let trimmed = line.trim();
@@ -574,6 +658,108 @@ fn extract_imports(content: &str, language: &str) -> Vec<ImportInfo> {
                     });
                 }
             }
+            "Ruby" => {
+                if trimmed.starts_with("require ") || trimmed.starts_with("require_relative ") {
+                    let is_external = trimmed.starts_with("require ");
+                    let module = trimmed
+                        .split_whitespace()
+                        .nth(1)
+                        .unwrap_or_default()
+                        .trim_matches(|c| matches!(c, '\'' | '"' | '(' | ')'))
+                        .to_string();
+                    imports.push(ImportInfo {
+                        module,
+                        items: vec![],
+                        line: line_num,
+                        is_external,
+                    });
+                }
+            }
+            "PHP" => {
+                if trimmed.starts_with("use ") && trimmed.contains('\\') {
+                    let module = trimmed
+                        .trim_start_matches("use ")
+                        .trim_end_matches(';')
+                        .to_string();
+                    // Composer packages vs the project's own `App\` namespace
+                    let is_external = !module.starts_with("App\\");
+                    imports.push(ImportInfo {
+                        module,
+                        items: vec![],
+                        line: line_num,
+                        is_external,
+                    });
+                }
+            }
+            "Go" => {
+                if trimmed.starts_with("import (") {
+                    in_import_block = true;
+                } else if in_import_block && trimmed.starts_with(')') {
+                    in_import_block = false;
+                } else if (in_import_block || trimmed.starts_with("import ")) && trimmed.ends_with('"') {
+                    let module = trimmed.split('"').nth(1).unwrap_or_default().to_string();
+                    // Project packages share the go.mod module path, unknown here:
+                    // only relative imports are known to be local
+                    let is_external = !module.starts_with('.');
+                    imports.push(ImportInfo {
+                        module,
+                        items: vec![],
+                        line: line_num,
+                        is_external,
+                    });
+                }
+            }
+            "Java" | "Kotlin" => {
+                if trimmed.starts_with("package ") {
+                    package = Some(
+                        trimmed
+                            .trim_start_matches("package ")
+                            .trim_end_matches(';')
+                            .trim()
+                            .to_string(),
+                    );
+                } else if trimmed.starts_with("import ") {
+                    let path = trimmed
+                        .trim_start_matches("import ")
+                        .trim_start_matches("static ")
+                        .trim_end_matches(';');
+                    let module = path
+                        .split(" as ")
+                        .next()
+                        .unwrap_or_default()
+                        .trim()
+                        .to_string();
+                    // Project imports share the top of the file's package (`com.acme`)
+                    let is_external = package.as_deref().is_none_or(|package| {
+                        let root: Vec<&str> = package.split('.').take(2).collect();
+                        !module.split('.').take(root.len()).eq(root.iter().copied())
+                    });
+                    imports.push(ImportInfo {
+                        module,
+                        items: vec![],
+                        line: line_num,
+                        is_external,
+                    });
+                }
+            }
+            "C" | "C++" => {
+                if trimmed.starts_with("#include") {
+                    let target = trimmed.trim_start_matches("#include").trim();
+                    // <...> headers come from the system or a dependency, "..." from the project
+                    let is_external = target.starts_with('<');
+                    let module = target
+                        .get(1..)
+                        .and_then(|t| t.split(['>', '"']).next())
+                        .unwrap_or_default()
+                        .to_string();
+                    imports.push(ImportInfo {
+                        module,
+                        items: vec![],
+                        line: line_num,
+                        is_external,
+                    });
+                }
+            }
             _ => {}
         }
     }
@@ -648,6 +834,26 @@ fn check_issues(content: &str, language: &str, symbols: &[CodeSymbol]) -> Vec<Co
                 }
             }
         }
+        "C" | "C++" => {
+            const UNBOUNDED: &[&str] = &["gets(", "strcpy(", "strcat(", "sprintf("];
+            for (i, line) in content.lines().enumerate() {
+                if let Some(call) = UNBOUNDED.iter().find(|c| {
+                    line.match_indices(*c).any(|(pos, _)| {
+                        !line[..pos].ends_with(|ch: char| ch.is_alphanumeric() || ch == '_')
+                    })
+                }) {
+                    issues.push(CodeIssue {
+                        severity: IssueSeverity::Warning,
+                        message: format!(
+                            "{}) does not check buffer bounds; use a size-limited variant",
+                            call
+                        ),
+                        line: Some(i + 1),
+                        rule: "unbounded-copy".to_string(),
+                    });
+                }
+            }
+        }
         _ => {}
     }
 
@@ -671,6 +877,14 @@ fn calculate_cyclomatic_complexity(content: &str) -> usize {
     complexity
 }
 
+/// Caller → callee edges of a file, `None` when its language has no grammar
+fn extract_call_graph(content: &str, language: &str) -> Option<Vec<ast::CallEdge>> {
+    let lang = SupportedLanguage::parse_language(language)?;
+    let mut parser = AstParser::new().ok()?;
+    let tree = parser.parse(lang, content).ok()?;
+    Some(parser.extract_call_graph(&tree, lang, content))
+}
+
 fn extract_function_calls(content: &str, _language: &str) -> Vec<String> {
     let mut calls = Vec::new();
     let re = regex::Regex::new(r"([a-zA-Z_][a-zA-Z0-9_]*)\s*\(").unwrap();
@@ -1041,9 +1255,98 @@ mod tests {
 
     #[test]
     fn test_language_detection() {
-        assert_eq!(detect_language(Path::new("test.rs")), "Rust");
-        assert_eq!(detect_language(Path::new("test.py")), "Python");
-        assert_eq!(detect_language(Path::new("test.ts")), "TypeScript");
+        assert_eq!(detect_language(Path::new("test.rs"), ""), "Rust");
+        assert_eq!(detect_language(Path::new("test.py"), ""), "Python");
+        assert_eq!(detect_language(Path::new("test.ts"), ""), "TypeScript");
+        assert_eq!(detect_language(Path::new("engine.cxx"), ""), "C++");
+        assert_eq!(detect_language(Path::new("bin/release"), "#!/bin/sh\nset -e\n"), "Shell");
+        assert_eq!(detect_language(Path::new("widget.h"), "class Widget {};"), "C++");
+    }
+
+    #[test]
+    fn test_cpp_symbols_and_includes() {
+        let code = r#"#include <vector>
+#include "engine.h" // local
+
+class Engine {
+public:
+    int step(int dt) {
+        if (dt > 0 && running) {
+            return dt;
+        }
+        return 0;
+    }
+};
+
+static void reset(char *buf) {
+    strcpy(buf, "");
+}
+"#;
+        let symbols = extract_symbols(code, "C++");
+        let step = symbols.iter().find(|s| s.name == "step").unwrap();
+        assert_eq!(step.symbol_type, SymbolType::Method);
+        assert_eq!(step.params, vec!["int dt"]);
+        assert_eq!(step.complexity, 3);
+        let reset = symbols.iter().find(|s| s.name == "reset").unwrap();
+        assert_eq!(reset.visibility, Visibility::Private);
+        assert_eq!(reset.return_type.as_deref(), Some("void"));
+
+        let imports = extract_imports(code, "C++");
+        assert_eq!(imports[0].module, "vector");
+        assert!(imports[0].is_external);
+        assert_eq!(imports[1].module, "engine.h");
+        assert!(!imports[1].is_external);
+
+        let issues = check_issues(code, "C++", &symbols);
+        assert!(issues.iter().any(|i| i.rule == "unbounded-copy" && i.line == Some(15)));
+    }
+
+    #[test]
+    fn test_ruby_symbols_and_requires() {
+        let code = "require 'json'\nrequire_relative 'support/helpers'\n\nclass Report\n  def render(format = :html)\n    if format == :json\n      to_json\n    end\n  end\nend\n";
+        let symbols = extract_symbols(code, "Ruby");
+        assert_eq!(symbols.len(), 2);
+        assert_eq!(symbols[0].symbol_type, SymbolType::Class);
+        assert_eq!(symbols[1].name, "render");
+        assert_eq!(symbols[1].line_end, 9);
+
+        let imports = extract_imports(code, "Ruby");
+        assert_eq!(imports[0].module, "json");
+        assert!(imports[0].is_external);
+        assert!(!imports[1].is_external);
+    }
+
+    #[test]
+    fn test_go_symbols_and_imports() {
+        let code = "package report\n\nimport (\n    \"fmt\"\n    \"./render\"\n)\n\nfunc (r *Report) Render(format string) string {\n    if format == \"json\" {\n        return fmt.Sprint(r)\n    }\n    return \"\"\n}\n";
+        let symbols = extract_symbols(code, "Go");
+        assert_eq!(symbols.len(), 1);
+        assert_eq!(symbols[0].symbol_type, SymbolType::Method);
+        assert_eq!(symbols[0].visibility, Visibility::Public);
+        assert_eq!(symbols[0].line_end, 13);
+
+        let imports = extract_imports(code, "Go");
+        assert_eq!(imports.len(), 2);
+        assert_eq!(imports[0].module, "fmt");
+        assert!(!imports[1].is_external);
+    }
+
+    #[test]
+    fn test_kotlin_symbols_and_imports() {
+        let code = "package com.acme.report\n\nimport com.acme.render.Html\nimport kotlinx.coroutines.*\n\n@Service\nclass Report {\n    suspend fun render(format: String): String {\n        if (format == \"json\") {\n            return Html.escape(format)\n        }\n        return \"\"\n    }\n}\n";
+        let symbols = extract_symbols(code, "Kotlin");
+        assert_eq!(symbols.len(), 2);
+        assert_eq!(symbols[0].symbol_type, SymbolType::Class);
+        assert_eq!(symbols[1].symbol_type, SymbolType::Method);
+        assert_eq!(symbols[1].params, vec!["String format"]);
+        assert_eq!(symbols[1].line_end, 13);
+
+        let imports = extract_imports(code, "Kotlin");
+        assert_eq!(imports.len(), 2);
+        assert_eq!(imports[0].module, "com.acme.render.Html");
+        assert!(!imports[0].is_external);
+        assert_eq!(imports[1].module, "kotlinx.coroutines.*");
+        assert!(imports[1].is_external);
     }
 
     #[test]
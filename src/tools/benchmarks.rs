@@ -0,0 +1,663 @@
+//! Benchmark runs with regression comparison
+//!
+//! Runs the project's benchmarks (criterion through `cargo bench`, read from
+//! the estimates it writes under `target/criterion`, or pytest-benchmark
+//! through its JSON report) and records the mean time of every benchmark per
+//! commit in `benchmark_results`. Runs with uncommitted changes are kept
+//! apart, so the timing of a commit is always its own. A run is compared with
+//! the newest recorded commit of a baseline branch (`main` or `master` unless
+//! given) at or before the point the current branch forked from it, so changes
+//! merged into the baseline since are not blamed on the branch. With
+//! `run_baseline`, a baseline without results is benchmarked in a temporary
+//! git worktree. A benchmark regresses when it got slower by more than the
+//! threshold and by more than the noise (standard deviation) of both runs.
+
+use super::command::output_in_group;
+use super::git::{run_git_command, GitError};
+use crate::db::{BenchmarkResult, Database, DatabaseError};
+use crate::log_warn;
+use rig::tool::Tool;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use thiserror::Error;
+use tokio::process::Command;
+
+/// Slowdown, in percent, reported as a regression unless told otherwise
+pub const DEFAULT_REGRESSION_THRESHOLD: f64 = 5.0;
+/// Baseline branch commits searched for recorded results
+const BASELINE_COMMITS: usize = 50;
+/// Benchmark runs are stopped after this long
+const BENCH_TIMEOUT_SECS: u64 = 1800;
+
+#[derive(Error, Debug)]
+pub enum BenchmarkError {
+    #[error("No benchmarks found: expected criterion in Cargo.toml or pytest-benchmark in the Python dependencies")]
+    NoFramework,
+    #[error("Unknown benchmark framework '{0}' (expected criterion or pytest-benchmark)")]
+    UnknownFramework(String),
+    #[error("Running benchmarks is only allowed in Build mode")]
+    RequiresBuildMode,
+    #[error("No baseline branch: pass baseline=<branch> (neither main nor master exists)")]
+    NoBaselineBranch,
+    #[error("Benchmarks failed: {0}")]
+    Failed(String),
+    #[error("Benchmarks timed out after {0} seconds")]
+    Timeout(u64),
+    #[error("Git error: {0}")]
+    Git(#[from] GitError),
+    #[error("Database error: {0}")]
+    Database(#[from] DatabaseError),
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// Benchmark harness of a project
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BenchFramework {
+    Criterion,
+    PytestBenchmark,
+}
+
+impl BenchFramework {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "criterion" | "cargo" | "rust" => Some(Self::Criterion),
+            "pytest-benchmark" | "pytest_benchmark" | "pytest" | "python" => Some(Self::PytestBenchmark),
+            _ => None,
+        }
+    }
+
+    /// Harness the project depends on, criterion first
+    pub fn detect(root: &Path) -> Option<Self> {
+        let mentions = |file: &str, needles: &[&str]| {
+            std::fs::read_to_string(root.join(file)).is_ok_and(|text| needles.iter().any(|n| text.contains(n)))
+        };
+        if mentions("Cargo.toml", &["criterion"]) || (root.join("Cargo.toml").exists() && root.join("benches").is_dir()) {
+            return Some(Self::Criterion);
+        }
+        let python = ["pyproject.toml", "setup.cfg", "setup.py", "requirements.txt", "requirements-dev.txt"];
+        if python.iter().any(|file| mentions(file, &["pytest-benchmark", "pytest_benchmark"])) {
+            return Some(Self::PytestBenchmark);
+        }
+        None
+    }
+
+    pub fn slug(&self) -> &'static str {
+        match self {
+            Self::Criterion => "criterion",
+            Self::PytestBenchmark => "pytest-benchmark",
+        }
+    }
+}
+
+/// Mean time of one benchmark in a run
+#[derive(Debug, Clone, PartialEq)]
+pub struct BenchTiming {
+    pub name: String,
+    pub mean_ns: f64,
+    pub stddev_ns: f64,
+}
+
+impl From<&BenchmarkResult> for BenchTiming {
+    fn from(result: &BenchmarkResult) -> Self {
+        Self {
+            name: result.benchmark.clone(),
+            mean_ns: result.mean_ns,
+            stddev_ns: result.stddev_ns,
+        }
+    }
+}
+
+/// Timing of a criterion benchmark, from the `benchmark.json` and
+/// `estimates.json` of its `new/` directory
+pub fn parse_criterion(benchmark: &str, estimates: &str) -> Option<BenchTiming> {
+    let benchmark: serde_json::Value = serde_json::from_str(benchmark).ok()?;
+    let estimates: serde_json::Value = serde_json::from_str(estimates).ok()?;
+    Some(BenchTiming {
+        name: benchmark["full_id"].as_str().or_else(|| benchmark["title"].as_str())?.to_string(),
+        mean_ns: estimates["mean"]["point_estimate"].as_f64()?,
+        stddev_ns: estimates["std_dev"]["point_estimate"].as_f64().unwrap_or(0.0),
+    })
+}
+
+/// Timings of a `--benchmark-json` report, which is in seconds
+pub fn parse_pytest_benchmark(json: &str) -> Vec<BenchTiming> {
+    let Ok(report) = serde_json::from_str::<serde_json::Value>(json) else {
+        return Vec::new();
+    };
+    report["benchmarks"]
+        .as_array()
+        .map(|benchmarks| {
+            benchmarks
+                .iter()
+                .filter_map(|bench| {
+                    Some(BenchTiming {
+                        name: bench["fullname"].as_str().or_else(|| bench["name"].as_str())?.to_string(),
+                        mean_ns: bench["stats"]["mean"].as_f64()? * 1e9,
+                        stddev_ns: bench["stats"]["stddev"].as_f64().unwrap_or(0.0) * 1e9,
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Criterion results written since `since` below `dir`
+fn read_criterion(dir: &Path, since: SystemTime) -> Vec<BenchTiming> {
+    let mut timings: Vec<BenchTiming> = walkdir::WalkDir::new(dir)
+        .into_iter()
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_name() == "benchmark.json")
+        .filter(|entry| entry.path().parent().is_some_and(|parent| parent.ends_with("new")))
+        .filter(|entry| entry.metadata().ok().and_then(|m| m.modified().ok()).is_some_and(|time| time >= since))
+        .filter_map(|entry| {
+            let benchmark = std::fs::read_to_string(entry.path()).ok()?;
+            let estimates = std::fs::read_to_string(entry.path().with_file_name("estimates.json")).ok()?;
+            parse_criterion(&benchmark, &estimates)
+        })
+        .collect();
+    timings.sort_by(|a, b| a.name.cmp(&b.name));
+    timings
+}
+
+/// A benchmark of the current run next to its baseline timing
+#[derive(Debug, Clone, PartialEq)]
+pub struct BenchComparison {
+    pub current: BenchTiming,
+    pub baseline: Option<BenchTiming>,
+}
+
+impl BenchComparison {
+    /// Change of the mean in percent, positive when slower
+    pub fn change(&self) -> Option<f64> {
+        let baseline = self.baseline.as_ref().filter(|b| b.mean_ns > 0.0)?;
+        Some((self.current.mean_ns - baseline.mean_ns) / baseline.mean_ns * 100.0)
+    }
+
+    /// Whether the difference is larger than the noise of both runs
+    fn beyond_noise(&self) -> bool {
+        self.baseline.as_ref().is_some_and(|baseline| {
+            (self.current.mean_ns - baseline.mean_ns).abs() > self.current.stddev_ns + baseline.stddev_ns
+        })
+    }
+
+    pub fn is_regression(&self, threshold: f64) -> bool {
+        self.change().is_some_and(|change| change > threshold) && self.beyond_noise()
+    }
+
+    pub fn is_improvement(&self, threshold: f64) -> bool {
+        self.change().is_some_and(|change| change < -threshold) && self.beyond_noise()
+    }
+}
+
+/// Commit the current run was compared with
+#[derive(Debug, Clone, PartialEq)]
+pub struct Baseline {
+    pub branch: String,
+    pub commit: String,
+}
+
+/// A benchmark run compared with its baseline
+#[derive(Debug, Clone)]
+pub struct BenchmarkReport {
+    pub framework: BenchFramework,
+    pub commit: String,
+    pub dirty: bool,
+    /// `None` when the baseline branch has no recorded results
+    pub baseline: Option<Baseline>,
+    pub branch: String,
+    pub threshold: f64,
+    pub comparisons: Vec<BenchComparison>,
+    /// Benchmarks of the baseline the current run did not have
+    pub missing: Vec<String>,
+    /// Why the run was not recorded, if it was not
+    pub unrecorded: Option<String>,
+}
+
+impl BenchmarkReport {
+    pub fn compare(current: Vec<BenchTiming>, baseline: &[BenchTiming]) -> (Vec<BenchComparison>, Vec<String>) {
+        let missing = baseline
+            .iter()
+            .filter(|b| !current.iter().any(|c| c.name == b.name))
+            .map(|b| b.name.clone())
+            .collect();
+        let comparisons = current
+            .into_iter()
+            .map(|current| BenchComparison {
+                baseline: baseline.iter().find(|b| b.name == current.name).cloned(),
+                current,
+            })
+            .collect();
+        (comparisons, missing)
+    }
+
+    pub fn regressions(&self) -> Vec<&BenchComparison> {
+        self.comparisons.iter().filter(|c| c.is_regression(self.threshold)).collect()
+    }
+
+    pub fn to_markdown(&self) -> String {
+        let dirty = if self.dirty { " with uncommitted changes" } else { "" };
+        let mut output = format!(
+            "⏱️ {} benchmarks at {}{}",
+            self.framework.slug(),
+            short(&self.commit),
+            dirty
+        );
+        match &self.baseline {
+            Some(baseline) => output.push_str(&format!(
+                " vs {} at {} (regression threshold {}%)\n\n",
+                baseline.branch,
+                short(&baseline.commit),
+                self.threshold
+            )),
+            None => output.push_str(&format!(
+                "\nNo recorded results on {} to compare with: run the benchmarks there, or pass run_baseline=true\n\n",
+                self.branch
+            )),
+        }
+
+        let line = |c: &BenchComparison| match (&c.baseline, c.change()) {
+            (Some(baseline), Some(change)) => format!(
+                "- {}: {} → {} ({:+.1}%)\n",
+                c.current.name,
+                format_ns(baseline.mean_ns),
+                format_ns(c.current.mean_ns),
+                change
+            ),
+            _ => format!("- {}: {} (new)\n", c.current.name, format_ns(c.current.mean_ns)),
+        };
+        if self.baseline.is_some() {
+            let regressions = self.regressions();
+            if regressions.is_empty() {
+                output.push_str("✅ No regressions\n");
+            } else {
+                output.push_str(&format!("🔴 Regressions ({}):\n", regressions.len()));
+                regressions.iter().for_each(|c| output.push_str(&line(c)));
+            }
+            let improvements: Vec<_> = self.comparisons.iter().filter(|c| c.is_improvement(self.threshold)).collect();
+            if !improvements.is_empty() {
+                output.push_str(&format!("🟢 Improvements ({}):\n", improvements.len()));
+                improvements.iter().for_each(|c| output.push_str(&line(c)));
+            }
+            output.push('\n');
+        }
+
+        output.push_str("All benchmarks:\n");
+        self.comparisons.iter().for_each(|c| output.push_str(&line(c)));
+        if !self.missing.is_empty() {
+            output.push_str(&format!("\nOnly in the baseline: {}\n", self.missing.join(", ")));
+        }
+        if let Some(reason) = &self.unrecorded {
+            output.push_str(&format!("\n⚠️ Results not recorded: {}\n", reason));
+        }
+        output
+    }
+}
+
+fn short(commit: &str) -> &str {
+    &commit[..commit.len().min(8)]
+}
+
+/// `850 ns`, `12.40 µs`, `3.10 ms`, `1.25 s`
+pub fn format_ns(ns: f64) -> String {
+    if ns < 1e3 {
+        format!("{:.0} ns", ns)
+    } else if ns < 1e6 {
+        format!("{:.2} µs", ns / 1e3)
+    } else if ns < 1e9 {
+        format!("{:.2} ms", ns / 1e6)
+    } else {
+        format!("{:.2} s", ns / 1e9)
+    }
+}
+
+/// Arguments for run_benchmarks
+#[derive(Debug, Clone, Default, Deserialize, Serialize, JsonSchema)]
+pub struct BenchmarkArgs {
+    /// Only benchmarks matching this (criterion filter, or pytest -k expression)
+    #[serde(default)]
+    pub filter: Option<String>,
+    /// criterion or pytest-benchmark (default: detected from the project)
+    #[serde(default)]
+    pub framework: Option<String>,
+    /// Branch to compare with (default: main, or master)
+    #[serde(default)]
+    pub baseline: Option<String>,
+    /// Slowdown in percent reported as a regression (default 5)
+    #[serde(default)]
+    pub threshold: Option<f64>,
+    /// Benchmark the baseline in a temporary git worktree when it has no recorded results
+    #[serde(default)]
+    pub run_baseline: bool,
+    /// Project directory, filled in by the orchestrator
+    #[serde(skip)]
+    #[schemars(skip)]
+    pub project_dir: String,
+    /// Whether the agent is in Build mode, filled in by the orchestrator
+    #[serde(skip)]
+    #[schemars(skip)]
+    pub build_mode: bool,
+}
+
+/// Runs the project's benchmarks and compares them with a baseline branch
+#[derive(Clone, Default)]
+pub struct BenchmarkTool {
+    db: Option<Database>,
+}
+
+impl BenchmarkTool {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record results in `db`, so later runs have a baseline
+    pub fn with_database(mut self, db: Database) -> Self {
+        self.db = Some(db);
+        self
+    }
+
+    /// Run the benchmarks, record them and compare them with the baseline branch
+    pub async fn run(&self, args: &BenchmarkArgs) -> Result<BenchmarkReport, BenchmarkError> {
+        if !args.build_mode {
+            return Err(BenchmarkError::RequiresBuildMode);
+        }
+        let root = PathBuf::from(if args.project_dir.is_empty() { "." } else { &args.project_dir }).canonicalize()?;
+        let framework = match args.framework.as_deref().filter(|f| !f.trim().is_empty()) {
+            Some(name) => BenchFramework::parse(name).ok_or_else(|| BenchmarkError::UnknownFramework(name.to_string()))?,
+            None => BenchFramework::detect(&root).ok_or(BenchmarkError::NoFramework)?,
+        };
+        let threshold = args.threshold.filter(|t| *t >= 0.0).unwrap_or(DEFAULT_REGRESSION_THRESHOLD);
+        let filter = args.filter.as_deref().map(str::trim).filter(|f| !f.is_empty());
+        let project = root.to_string_lossy().to_string();
+
+        let head = run_git_command(&root, &["rev-parse", "HEAD"])?.trim().to_string();
+        let dirty = !run_git_command(&root, &["status", "--porcelain", "--untracked-files=no"])?.trim().is_empty();
+        let branch = match args.baseline.as_deref().map(str::trim).filter(|b| !b.is_empty()) {
+            Some(branch) => branch.to_string(),
+            None => ["main", "master"]
+                .into_iter()
+                .find(|branch| run_git_command(&root, &["rev-parse", "--verify", "--quiet", branch]).is_ok())
+                .ok_or(BenchmarkError::NoBaselineBranch)?
+                .to_string(),
+        };
+        let fork = run_git_command(&root, &["merge-base", "HEAD", &branch])?.trim().to_string();
+
+        let current = run_benchmarks(framework, &root, filter, None).await?;
+        // A clean run of a baseline commit is not its own baseline
+        let skip = (!dirty).then_some(head.as_str());
+        let mut baseline = self.baseline(&project, &root, &fork, skip).await?;
+        if baseline.is_none() && args.run_baseline {
+            baseline = Some(self.benchmark_baseline(framework, &project, &root, &fork, filter).await?);
+        }
+
+        let unrecorded = match &self.db {
+            Some(db) => {
+                let recorded_at = chrono::Utc::now().to_rfc3339();
+                let mut error = None;
+                for timing in &current {
+                    let result = to_record(&project, &head, dirty, framework, timing, &recorded_at);
+                    if let Err(e) = db.save_benchmark_result(&result).await {
+                        error = Some(e.to_string());
+                        break;
+                    }
+                }
+                error
+            }
+            None => Some("no session database".to_string()),
+        };
+
+        let (commit, baseline_timings) = baseline.unzip();
+        let (comparisons, missing) = BenchmarkReport::compare(current, &baseline_timings.unwrap_or_default());
+        Ok(BenchmarkReport {
+            framework,
+            commit: head,
+            dirty,
+            baseline: commit.map(|commit| Baseline {
+                branch: branch.clone(),
+                commit,
+            }),
+            branch,
+            threshold,
+            comparisons,
+            missing,
+            unrecorded,
+        })
+    }
+
+    /// Newest recorded clean results of the baseline branch, at or before
+    /// `fork` (where HEAD forked off it)
+    async fn baseline(
+        &self,
+        project: &str,
+        root: &PathBuf,
+        fork: &str,
+        skip: Option<&str>,
+    ) -> Result<Option<(String, Vec<BenchTiming>)>, BenchmarkError> {
+        let Some(db) = &self.db else { return Ok(None) };
+        let limit = format!("-{}", BASELINE_COMMITS);
+        let commits = run_git_command(root, &["rev-list", "--first-parent", &limit, fork])?;
+        for commit in commits.lines().map(str::trim).filter(|c| Some(*c) != skip) {
+            let results = db.get_benchmark_results(project, commit, 0).await?;
+            if !results.is_empty() {
+                return Ok(Some((commit.to_string(), results.iter().map(BenchTiming::from).collect())));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Benchmark `commit` in a temporary worktree and record it
+    async fn benchmark_baseline(
+        &self,
+        framework: BenchFramework,
+        project: &str,
+        root: &PathBuf,
+        commit: &str,
+        filter: Option<&str>,
+    ) -> Result<(String, Vec<BenchTiming>), BenchmarkError> {
+        let top = PathBuf::from(run_git_command(root, &["rev-parse", "--show-toplevel"])?.trim()).canonicalize()?;
+        let prefix = root.strip_prefix(&top).unwrap_or(Path::new("")).to_path_buf();
+        let temp = tempfile::tempdir()?;
+        let worktree = temp.path().join("baseline");
+        let worktree_arg = worktree.to_string_lossy().to_string();
+        run_git_command(root, &["worktree", "add", "--detach", &worktree_arg, commit])?;
+
+        // Sharing the target directory reuses the dependencies already built
+        let target = match framework {
+            BenchFramework::Criterion => Some(root.join("target")).filter(|target| target.is_dir()),
+            BenchFramework::PytestBenchmark => None,
+        };
+        let timings = run_benchmarks(framework, &worktree.join(prefix), filter, target.as_deref()).await;
+        if let Err(e) = run_git_command(root, &["worktree", "remove", "--force", &worktree_arg]) {
+            log_warn!("⚠️ Failed to remove the baseline worktree: {}", e);
+        }
+        let timings = timings?;
+
+        if let Some(db) = &self.db {
+            let recorded_at = chrono::Utc::now().to_rfc3339();
+            for timing in &timings {
+                db.save_benchmark_result(&to_record(project, commit, false, framework, timing, &recorded_at))
+                    .await?;
+            }
+        }
+        Ok((commit.to_string(), timings))
+    }
+}
+
+fn to_record(
+    project: &str,
+    commit: &str,
+    dirty: bool,
+    framework: BenchFramework,
+    timing: &BenchTiming,
+    recorded_at: &str,
+) -> BenchmarkResult {
+    BenchmarkResult {
+        project_root: project.to_string(),
+        commit_sha: commit.to_string(),
+        dirty: dirty as i32,
+        framework: framework.slug().to_string(),
+        benchmark: timing.name.clone(),
+        mean_ns: timing.mean_ns,
+        stddev_ns: timing.stddev_ns,
+        recorded_at: recorded_at.to_string(),
+    }
+}
+
+/// Run the benchmarks of the project in `dir` and read their timings
+async fn run_benchmarks(
+    framework: BenchFramework,
+    dir: &Path,
+    filter: Option<&str>,
+    target: Option<&Path>,
+) -> Result<Vec<BenchTiming>, BenchmarkError> {
+    let started = SystemTime::now();
+    let report = tempfile::NamedTempFile::new()?;
+    let mut command = match framework {
+        BenchFramework::Criterion => {
+            let mut command = Command::new("cargo");
+            command.arg("bench");
+            if let Some(filter) = filter {
+                command.args(["--", filter]);
+            }
+            if let Some(target) = target {
+                command.env("CARGO_TARGET_DIR", target);
+            }
+            command
+        }
+        BenchFramework::PytestBenchmark => {
+            let venv = dir.join(".venv/bin/python");
+            let mut command = Command::new(if venv.exists() { venv.as_path() } else { Path::new("python3") });
+            command
+                .args(["-m", "pytest", "--benchmark-only", "-q"])
+                .arg(format!("--benchmark-json={}", report.path().display()));
+            if let Some(filter) = filter {
+                command.args(["-k", filter]);
+            }
+            command
+        }
+    };
+
+    let output = tokio::time::timeout(
+        Duration::from_secs(BENCH_TIMEOUT_SECS),
+        output_in_group(command.current_dir(dir)),
+    )
+    .await
+    .map_err(|_| BenchmarkError::Timeout(BENCH_TIMEOUT_SECS))??;
+
+    let timings = match framework {
+        BenchFramework::Criterion => {
+            let target = match target {
+                Some(target) => Some(target.to_path_buf()),
+                None => dir.ancestors().map(|dir| dir.join("target")).find(|target| target.is_dir()),
+            };
+            target.map_or_else(Vec::new, |target| read_criterion(&target.join("criterion"), started))
+        }
+        BenchFramework::PytestBenchmark => parse_pytest_benchmark(&std::fs::read_to_string(report.path())?),
+    };
+    if timings.is_empty() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let tail: Vec<&str> = stderr.lines().rev().take(10).collect();
+        let tail: Vec<&str> = tail.into_iter().rev().collect();
+        let reason = if output.status.success() { "no benchmark results".to_string() } else { tail.join("\n") };
+        return Err(BenchmarkError::Failed(reason));
+    }
+    Ok(timings)
+}
+
+impl Tool for BenchmarkTool {
+    const NAME: &'static str = "run_benchmarks";
+
+    type Args = BenchmarkArgs;
+    type Output = String;
+    type Error = BenchmarkError;
+
+    async fn definition(&self, _prompt: String) -> rig::completion::ToolDefinition {
+        rig::completion::ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: "Run the project's benchmarks (criterion or pytest-benchmark), record the \
+                          timings for the current commit and compare them with a baseline branch, \
+                          listing regressions above a threshold. Use it to answer whether a change \
+                          made anything slower. Needs Build mode."
+                .to_string(),
+            parameters: serde_json::to_value(schemars::schema_for!(BenchmarkArgs)).unwrap_or_default(),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        // sqlx futures are not `Sync`, which `Tool::call` requires: run on a task
+        let tool = self.clone();
+        tokio::spawn(async move { tool.run(&args).await.map(|report| report.to_markdown()) })
+            .await
+            .map_err(|e| BenchmarkError::Failed(e.to_string()))?
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_results() {
+        let benchmark = r#"{"group_id":"parse","function_id":"large","value_str":null,"full_id":"parse/large","directory_name":"parse/large","title":"parse/large"}"#;
+        let estimates = r#"{"mean":{"confidence_interval":{"confidence_level":0.95,"lower_bound":1180.0,"upper_bound":1220.0},"point_estimate":1200.0,"standard_error":10.0},"std_dev":{"point_estimate":40.0,"standard_error":3.0}}"#;
+        let timing = parse_criterion(benchmark, estimates).unwrap();
+        assert_eq!((timing.name.as_str(), timing.mean_ns, timing.stddev_ns), ("parse/large", 1200.0, 40.0));
+
+        let pytest = r#"{"benchmarks":[{"name":"test_sort","fullname":"tests/test_perf.py::test_sort","stats":{"mean":0.0025,"stddev":0.0001}}]}"#;
+        let timings = parse_pytest_benchmark(pytest);
+        assert_eq!(timings.len(), 1);
+        assert_eq!(timings[0].name, "tests/test_perf.py::test_sort");
+        assert!((timings[0].mean_ns - 2.5e6).abs() < 1e-3);
+        assert!(parse_pytest_benchmark("not json").is_empty());
+        assert_eq!(format_ns(2.5e6), "2.50 ms");
+    }
+
+    #[test]
+    fn test_regressions() {
+        let timing = |name: &str, mean_ns: f64, stddev_ns: f64| BenchTiming {
+            name: name.to_string(),
+            mean_ns,
+            stddev_ns,
+        };
+        let baseline = [
+            timing("parse", 1000.0, 10.0),
+            timing("render", 1000.0, 200.0),
+            timing("sort", 1000.0, 10.0),
+            timing("removed", 50.0, 1.0),
+        ];
+        let current = vec![
+            // 20% slower, well past the noise
+            timing("parse", 1200.0, 10.0),
+            // 20% slower, but within the noise
+            timing("render", 1200.0, 200.0),
+            timing("sort", 800.0, 10.0),
+            timing("added", 10.0, 1.0),
+        ];
+        let (comparisons, missing) = BenchmarkReport::compare(current, &baseline);
+        let report = BenchmarkReport {
+            framework: BenchFramework::Criterion,
+            commit: "0123456789abcdef".to_string(),
+            dirty: true,
+            baseline: Some(Baseline {
+                branch: "main".to_string(),
+                commit: "fedcba9876543210".to_string(),
+            }),
+            branch: "main".to_string(),
+            threshold: DEFAULT_REGRESSION_THRESHOLD,
+            comparisons,
+            missing,
+            unrecorded: None,
+        };
+        let regressions: Vec<_> = report.regressions().iter().map(|c| c.current.name.as_str()).collect();
+        assert_eq!(regressions, vec!["parse"]);
+        assert_eq!(report.missing, vec!["removed"]);
+
+        let markdown = report.to_markdown();
+        assert!(markdown.starts_with("⏱️ criterion benchmarks at 01234567 with uncommitted changes vs main at fedcba98"));
+        assert!(markdown.contains("🔴 Regressions (1):\n- parse: 1.00 µs → 1.20 µs (+20.0%)\n"));
+        assert!(markdown.contains("🟢 Improvements (1):\n- sort: 1.00 µs → 800 ns (-20.0%)\n"));
+        assert!(markdown.contains("- added: 10 ns (new)\n"));
+    }
+}
@@ -0,0 +1,408 @@
+//! Build error knowledge base
+//!
+//! Errors parsed from build, lint and test runs are stored in `build_errors` by a
+//! signature that ignores paths, line numbers and addresses, so the same failure
+//! is recognised across runs and sessions. When a run of the same tool succeeds,
+//! the open errors are marked resolved with the session, HEAD commit and edited
+//! files. A recurring error is annotated with that resolution, which both shows
+//! it to the user and gives it to the model as context.
+
+use crate::db::{BuildError, Database, DatabaseError, SessionStore};
+use chrono::Utc;
+use regex::Regex;
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+use std::path::Path;
+use std::sync::LazyLock;
+use tokio::process::Command;
+
+/// Errors taken from one run
+const MAX_ERRORS_PER_RUN: usize = 20;
+/// Uncommitted files recorded with a resolution
+const MAX_RESOLUTION_FILES: usize = 10;
+
+static ANSI_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\x1b\[[0-9;]*m").unwrap());
+/// `error[E0425]: ...`, `src/main.rs:3:5: error: ...` (rustc, gcc, clang)
+static COMPILER_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(?:\S+:\d+(?::\d+)?: )?(?:fatal )?error(?:\[(E\d{4})\])?: (.+)$").unwrap()
+});
+/// `src/app.ts(3,5): error TS2304: ...`
+static TSC_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"error (TS\d+): (.+)$").unwrap());
+/// `ValueError: ...`, pytest's `E   KeyError: ...`
+static EXCEPTION_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(?:E\s+)?((?:\w+\.)*\w*(?:Error|Exception)): (.+)$").unwrap()
+});
+/// `test config::tests::load ... FAILED`, `FAILED tests/test_x.py::test_y - ...`
+static TEST_FAILED_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(?:test (\S+) \.\.\. FAILED|FAILED (\S+?)(?: - .*)?)$").unwrap()
+});
+static PATH_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?:/[\w.@-]+){2,}").unwrap());
+static HEX_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\b0x[0-9a-fA-F]+\b").unwrap());
+static NUMBER_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\b\d+\b").unwrap());
+
+/// Summary lines that repeat the real errors
+const NOISE: &[&str] = &[
+    "aborting due to",
+    "could not compile",
+    "test failed, to rerun",
+    "build failed",
+];
+
+/// The build or test tool behind a run
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BuildRunner {
+    /// `cargo`, `pytest`, `tsc`, `npm`...
+    pub tool: &'static str,
+    /// The run executes tests, not only a build
+    pub tests: bool,
+}
+
+impl BuildRunner {
+    /// Runner of a shell command, `None` when it is not a build or test command
+    pub fn of_command(command: &str) -> Option<Self> {
+        let mut words = command
+            .split_whitespace()
+            .skip_while(|w| w.contains('=') && !w.starts_with('-'));
+        let program = words.next()?.rsplit('/').next()?;
+        let rest: Vec<&str> = words.collect();
+        let has = |word: &str| rest.contains(&word);
+        let runner = |tool, tests| Some(Self { tool, tests });
+
+        match program {
+            "cargo" => match rest.first().copied()? {
+                "test" | "nextest" => runner("cargo", true),
+                "build" | "check" | "clippy" | "run" => runner("cargo", false),
+                _ => None,
+            },
+            "pytest" => runner("pytest", true),
+            "python" | "python3" if rest.starts_with(&["-m", "pytest"]) => runner("pytest", true),
+            "tsc" => runner("tsc", false),
+            "npx" if has("tsc") => runner("tsc", false),
+            "npm" | "yarn" | "pnpm" if has("test") => runner("npm", true),
+            "npm" | "yarn" | "pnpm" if has("build") => runner("npm", false),
+            "go" if has("test") => runner("go", true),
+            "go" if has("build") || has("vet") => runner("go", false),
+            "make" => runner("make", has("test") || has("check")),
+            "gcc" | "g++" | "clang" | "clang++" | "cc" | "c++" => runner("cc", false),
+            _ => None,
+        }
+    }
+
+    /// Key stored with the errors of this runner: test failures are kept apart,
+    /// so a build that succeeds does not resolve them
+    fn key(&self, test_failure: bool) -> String {
+        if test_failure {
+            format!("{} test", self.tool)
+        } else {
+            self.tool.to_string()
+        }
+    }
+}
+
+/// An error extracted from tool output
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParsedError {
+    /// Error code or exception type
+    pub code: Option<String>,
+    pub message: String,
+    pub test_failure: bool,
+}
+
+impl ParsedError {
+    /// Stable identity of the error: paths, numbers and addresses are ignored
+    pub fn signature(&self, tool: &str) -> String {
+        let normalized = normalize(&self.message);
+        let key = format!("{}|{}|{}", tool, self.code.as_deref().unwrap_or(""), normalized);
+        format!("{:x}", Sha256::digest(key.as_bytes()))[..16].to_string()
+    }
+
+    fn label(&self) -> String {
+        match &self.code {
+            Some(code) => format!("{}: {}", code, self.message),
+            None => self.message.clone(),
+        }
+    }
+}
+
+/// Message without the parts that change between runs
+pub(super) fn normalize(message: &str) -> String {
+    let message = PATH_RE.replace_all(message, "<path>");
+    let message = HEX_RE.replace_all(&message, "<hex>");
+    let message = NUMBER_RE.replace_all(&message, "N");
+    message.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Errors in the output of a build, lint or test run, without duplicates
+pub fn parse_errors(output: &str) -> Vec<ParsedError> {
+    let mut seen = HashSet::new();
+    let mut errors = Vec::new();
+
+    for line in output.lines() {
+        let line = ANSI_RE.replace_all(line, "");
+        let line = line.trim();
+        if NOISE.iter().any(|noise| line.contains(noise)) {
+            continue;
+        }
+
+        let error = if let Some(caps) = TEST_FAILED_RE.captures(line) {
+            let name = caps.get(1).or_else(|| caps.get(2)).map_or("", |m| m.as_str());
+            ParsedError {
+                code: None,
+                message: format!("test failed: {}", name),
+                test_failure: true,
+            }
+        } else if let Some(caps) = TSC_RE.captures(line) {
+            ParsedError {
+                code: Some(caps[1].to_string()),
+                message: caps[2].trim().to_string(),
+                test_failure: false,
+            }
+        } else if let Some(caps) = COMPILER_RE.captures(line) {
+            ParsedError {
+                code: caps.get(1).map(|m| m.as_str().to_string()),
+                message: caps[2].trim().to_string(),
+                test_failure: false,
+            }
+        } else if let Some(caps) = EXCEPTION_RE.captures(line) {
+            ParsedError {
+                code: Some(caps[1].to_string()),
+                message: caps[2].trim().to_string(),
+                test_failure: false,
+            }
+        } else {
+            continue;
+        };
+
+        if seen.insert((error.code.clone(), normalize(&error.message))) {
+            errors.push(error);
+        }
+        if errors.len() >= MAX_ERRORS_PER_RUN {
+            break;
+        }
+    }
+    errors
+}
+
+/// Recurrence note for an error seen before this run
+fn format_known(error: &ParsedError, previous: &BuildError) -> String {
+    let times = match previous.occurrences {
+        1 => "once".to_string(),
+        n => format!("{} times", n),
+    };
+    let fix = match &previous.resolution_session_id {
+        Some(session) => {
+            let mut fix = format!(
+                "previous fix: session {}",
+                session.get(..8).unwrap_or(session)
+            );
+            if let Some(at) = &previous.resolved_at {
+                fix.push_str(&format!(" ({})", at));
+            }
+            if let Some(commit) = &previous.resolution_commit {
+                fix.push_str(&format!(", commit {}", commit));
+            }
+            if let Some(files) = previous.resolution_files.as_deref().filter(|f| !f.is_empty()) {
+                fix.push_str(&format!(", edited {}", files.lines().collect::<Vec<_>>().join(", ")));
+            }
+            fix
+        }
+        None => "not fixed yet".to_string(),
+    };
+    format!("- `{}` — seen {} before, {}", error.label(), times, fix)
+}
+
+/// Records build and test errors of the current session
+#[derive(Clone, Default)]
+pub struct BuildErrorKb {
+    db: Option<Database>,
+    session_id: String,
+}
+
+impl BuildErrorKb {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Persist errors in the session database
+    pub fn with_store(mut self, store: SessionStore) -> Self {
+        self.db = Some(store.db);
+        self.session_id = store.session_id;
+        self
+    }
+
+    /// Record the outcome of a run in `root`. Returns the notes about errors
+    /// seen before, or `None` when nothing recurred.
+    pub async fn observe(
+        &self,
+        root: &Path,
+        runner: BuildRunner,
+        output: &str,
+        success: bool,
+    ) -> Result<Option<String>, DatabaseError> {
+        let Some(db) = &self.db else {
+            return Ok(None);
+        };
+        let root = root
+            .canonicalize()
+            .unwrap_or_else(|_| root.to_path_buf())
+            .to_string_lossy()
+            .to_string();
+        let errors = parse_errors(output);
+
+        if success && errors.iter().all(|e| !e.test_failure) {
+            let (commit, files) = git_state(Path::new(&root)).await;
+            db.resolve_build_errors(&root, runner.tool, &self.session_id, commit.as_deref(), files.as_deref())
+                .await?;
+            if runner.tests {
+                db.resolve_build_errors(
+                    &root,
+                    &runner.key(true),
+                    &self.session_id,
+                    commit.as_deref(),
+                    files.as_deref(),
+                )
+                .await?;
+            }
+            return Ok(None);
+        }
+
+        let now = Utc::now().to_rfc3339();
+        let mut notes = Vec::new();
+        for error in &errors {
+            let tool = runner.key(error.test_failure);
+            let signature = error.signature(&tool);
+            if let Some(previous) = db.get_build_error(&root, &signature).await? {
+                notes.push(format_known(error, &previous));
+            }
+            db.record_build_error(&BuildError {
+                id: 0,
+                project_root: root.clone(),
+                signature,
+                tool,
+                code: error.code.clone(),
+                message: error.message.clone(),
+                occurrences: 1,
+                first_seen: now.clone(),
+                last_seen: now.clone(),
+                last_session_id: self.session_id.clone(),
+                is_open: 1,
+                resolved_at: None,
+                resolution_session_id: None,
+                resolution_commit: None,
+                resolution_files: None,
+            })
+            .await?;
+        }
+
+        Ok((!notes.is_empty()).then(|| {
+            format!(
+                "🔁 Known errors (reuse the previous fix if it applies):\n{}",
+                notes.join("\n")
+            )
+        }))
+    }
+}
+
+/// HEAD commit (`<hash> <subject>`) and uncommitted files of a repository
+async fn git_state(root: &Path) -> (Option<String>, Option<String>) {
+    let git = |args: &'static [&'static str]| async move {
+        let output = Command::new("git")
+            .args(args)
+            .current_dir(root)
+            .output()
+            .await
+            .ok()?;
+        output
+            .status
+            .success()
+            .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+    };
+
+    let commit = git(&["log", "-1", "--format=%h %s"])
+        .await
+        .filter(|c| !c.is_empty());
+    let files = git(&["status", "--porcelain"]).await.map(|status| {
+        status
+            .lines()
+            .filter_map(|line| line.get(3..))
+            .take(MAX_RESOLUTION_FILES)
+            .collect::<Vec<_>>()
+            .join("\n")
+    });
+    (commit, files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_runner_of_command() {
+        let cargo_test = BuildRunner::of_command("RUST_LOG=debug cargo test -p core");
+        assert_eq!(cargo_test, Some(BuildRunner { tool: "cargo", tests: true }));
+        assert_eq!(BuildRunner::of_command("python3 -m pytest -x").unwrap().tool, "pytest");
+        assert!(!BuildRunner::of_command("npm run build").unwrap().tests);
+        assert_eq!(BuildRunner::of_command("ls -la"), None);
+        assert_eq!(BuildRunner::of_command("cargo fmt"), None);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let output = "\x1b[31merror[E0425]\x1b[0m: cannot find value `x` in this scope\n \
+                      --> src/main.rs:5:13\n\
+                      src/lib.rs:10:5: error[E0425]: cannot find value `x` in this scope\n\
+                      error: aborting due to 1 previous error\n\
+                      src/app.ts(3,5): error TS2304: Cannot find name 'foo'.\n\
+                      E   KeyError: 'missing'\n\
+                      test config::tests::load ... FAILED\n";
+        let errors = parse_errors(output);
+        let labels: Vec<_> = errors.iter().map(|e| e.label()).collect();
+        assert_eq!(
+            labels,
+            vec![
+                "E0425: cannot find value `x` in this scope",
+                "TS2304: Cannot find name 'foo'.",
+                "KeyError: 'missing'",
+                "test failed: config::tests::load",
+            ]
+        );
+        assert!(errors[3].test_failure);
+
+        // Same error with other numbers and paths has the same signature
+        let a = &parse_errors("error: linking failed at /tmp/build-1/out.o offset 0x1f (12 bytes)")[0];
+        let b = &parse_errors("error: linking failed at /tmp/build-7/out.o offset 0x2a (48 bytes)")[0];
+        assert_eq!(a.signature("cargo"), b.signature("cargo"));
+        assert_ne!(a.signature("cargo"), a.signature("cc"));
+    }
+
+    #[tokio::test]
+    async fn test_recurrence_and_resolution() {
+        let dir = tempdir().unwrap();
+        let db = Database::in_memory().await.unwrap();
+        let store = |session: &str| SessionStore {
+            db: db.clone(),
+            session_id: session.to_string(),
+        };
+        let runner = BuildRunner::of_command("cargo build").unwrap();
+        let failure = "error[E0308]: mismatched types\n";
+
+        let first = BuildErrorKb::new().with_store(store("session-one-0001"));
+        assert_eq!(first.observe(dir.path(), runner, failure, false).await.unwrap(), None);
+        let note = first.observe(dir.path(), runner, failure, false).await.unwrap().unwrap();
+        assert!(note.contains("`E0308: mismatched types` — seen once before, not fixed yet"));
+
+        // A passing test run resolves build errors too
+        let tests = BuildRunner::of_command("cargo test").unwrap();
+        first.observe(dir.path(), tests, "test result: ok", true).await.unwrap();
+
+        let second = BuildErrorKb::new().with_store(store("session-two-0002"));
+        let note = second.observe(dir.path(), runner, failure, false).await.unwrap().unwrap();
+        assert!(note.contains("seen 2 times before, previous fix: session session-"));
+
+        let root = dir.path().canonicalize().unwrap();
+        let errors = db.get_build_errors(&root.to_string_lossy(), 10).await.unwrap();
+        assert_eq!((errors[0].occurrences, errors[0].is_open), (3, 1));
+        assert_eq!(errors[0].resolution_session_id.as_deref(), Some("session-one-0001"));
+    }
+}
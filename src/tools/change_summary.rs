@@ -0,0 +1,241 @@
+//! Symbol-level summary of a diff
+//!
+//! Each hunk is matched against the symbols of its file before and after the
+//! change: the changed lines are attributed to the innermost function, method
+//! or type containing them, so a commit message can say "update
+//! `RouterOrchestrator::process`" instead of naming the file. A symbol found
+//! only after the change was added, one found only before was removed.
+
+use super::git::{DiffHunk, DiffOutput, FileDiff};
+use crate::ast::Range;
+use serde::Serialize;
+
+/// Symbols named in the subject of a generated commit message
+const MAX_SUBJECT_SYMBOLS: usize = 3;
+
+/// What happened to a symbol
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum SymbolChange {
+    Added,
+    Modified,
+    Removed,
+}
+
+impl SymbolChange {
+    pub fn verb(&self) -> &'static str {
+        match self {
+            Self::Added => "add",
+            Self::Modified => "update",
+            Self::Removed => "remove",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ChangedSymbol {
+    /// Qualified name: `RouterOrchestrator::process`
+    pub name: String,
+    pub change: SymbolChange,
+}
+
+/// Symbol-level changes of one file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FileChangeSummary {
+    pub path: String,
+    /// In the order they appear in the file, removed ones last
+    pub symbols: Vec<ChangedSymbol>,
+    /// Some changed lines are outside every symbol (imports, top-level code)
+    pub outside_symbols: bool,
+}
+
+impl FileChangeSummary {
+    /// `add Parser::reset; update Parser::parse`
+    pub fn describe(&self) -> String {
+        let mut parts: Vec<String> = Vec::new();
+        for change in [SymbolChange::Added, SymbolChange::Modified, SymbolChange::Removed] {
+            let names: Vec<&str> = self
+                .symbols
+                .iter()
+                .filter(|s| s.change == change)
+                .map(|s| s.name.as_str())
+                .collect();
+            if !names.is_empty() {
+                parts.push(format!("{} {}", change.verb(), names.join(", ")));
+            }
+        }
+        if self.outside_symbols {
+            parts.push(if parts.is_empty() { "update top-level code" } else { "top-level code" }.to_string());
+        }
+        parts.join("; ")
+    }
+}
+
+/// Lines a hunk removes (old numbering) and adds (new numbering)
+fn changed_lines(hunk: &DiffHunk) -> (Vec<usize>, Vec<usize>) {
+    let (mut old_line, mut new_line) = (hunk.old_start, hunk.new_start);
+    let (mut removed, mut added) = (Vec::new(), Vec::new());
+    for line in hunk.content.lines() {
+        match line.chars().next() {
+            Some('-') => {
+                removed.push(old_line);
+                old_line += 1;
+            }
+            Some('+') => {
+                added.push(new_line);
+                new_line += 1;
+            }
+            Some('\\') => {}
+            _ => {
+                old_line += 1;
+                new_line += 1;
+            }
+        }
+    }
+    (removed, added)
+}
+
+/// Innermost symbol containing `line`
+fn innermost(symbols: &[(String, Range)], line: usize) -> Option<&str> {
+    symbols
+        .iter()
+        .filter(|(_, range)| range.start_line <= line && line <= range.end_line)
+        .min_by_key(|(_, range)| range.end_line - range.start_line)
+        .map(|(name, _)| name.as_str())
+}
+
+/// Attribute the hunks of `file` to its symbols before and after the change,
+/// each a qualified name with its range
+pub fn summarize_file(file: &FileDiff, before: &[(String, Range)], after: &[(String, Range)]) -> FileChangeSummary {
+    let mut touched_after: Vec<&str> = Vec::new();
+    let mut touched_before: Vec<&str> = Vec::new();
+    let mut outside_symbols = false;
+
+    for hunk in &file.hunks {
+        let (removed, added) = changed_lines(hunk);
+        for (lines, symbols, touched) in [(&removed, before, &mut touched_before), (&added, after, &mut touched_after)] {
+            for &line in lines {
+                match innermost(symbols, line) {
+                    Some(name) if !touched.contains(&name) => touched.push(name),
+                    Some(_) => {}
+                    None => outside_symbols = true,
+                }
+            }
+        }
+    }
+
+    let existed = |name: &str| before.iter().any(|(n, _)| n == name);
+    let exists = |name: &str| after.iter().any(|(n, _)| n == name);
+    let mut symbols: Vec<ChangedSymbol> = touched_after
+        .iter()
+        .map(|&name| ChangedSymbol {
+            name: name.to_string(),
+            change: if existed(name) { SymbolChange::Modified } else { SymbolChange::Added },
+        })
+        .collect();
+    for &name in &touched_before {
+        if !symbols.iter().any(|s| s.name == name) {
+            symbols.push(ChangedSymbol {
+                name: name.to_string(),
+                change: if exists(name) { SymbolChange::Modified } else { SymbolChange::Removed },
+            });
+        }
+    }
+
+    FileChangeSummary {
+        path: file.path.clone(),
+        symbols,
+        outside_symbols,
+    }
+}
+
+/// Commit message with the changed symbols in the subject and one bullet per
+/// file; `None` when no symbol changed
+pub fn commit_message(diff: &DiffOutput, summaries: &[FileChangeSummary]) -> Option<String> {
+    let changed: Vec<&ChangedSymbol> = summaries.iter().flat_map(|s| &s.symbols).collect();
+    if changed.is_empty() {
+        return None;
+    }
+
+    let kind = if changed.iter().any(|s| s.change == SymbolChange::Added) {
+        "feat"
+    } else if changed.iter().all(|s| s.change == SymbolChange::Removed) {
+        "refactor"
+    } else {
+        "chore"
+    };
+    let mut budget = MAX_SUBJECT_SYMBOLS;
+    let mut parts = Vec::new();
+    for change in [SymbolChange::Added, SymbolChange::Modified, SymbolChange::Removed] {
+        let names: Vec<&str> = changed
+            .iter()
+            .filter(|s| s.change == change)
+            .take(budget)
+            .map(|s| s.name.as_str())
+            .collect();
+        budget -= names.len();
+        if !names.is_empty() {
+            parts.push(format!("{} {}", change.verb(), names.join(", ")));
+        }
+    }
+    let mut subject = parts.join("; ");
+    let named = MAX_SUBJECT_SYMBOLS - budget;
+    if changed.len() > named {
+        subject.push_str(&format!(" and {} more", changed.len() - named));
+    }
+
+    let mut message = format!("{}: {}\n", kind, subject);
+    for file in &diff.files {
+        let description = match summaries.iter().find(|s| s.path == file.path) {
+            Some(summary) if !summary.symbols.is_empty() || summary.outside_symbols => summary.describe(),
+            _ => file.summary.clone().unwrap_or_else(|| "update".to_string()),
+        };
+        message.push_str(&format!("\n- {}: {}", file.path, description));
+    }
+    Some(message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tools::DiffFileKind;
+
+    fn range(start_line: usize, end_line: usize) -> Range {
+        Range { start_line, start_col: 0, end_line, end_col: 0 }
+    }
+
+    fn file(path: &str, hunks: Vec<DiffHunk>) -> FileDiff {
+        FileDiff { path: path.to_string(), additions: 0, deletions: 0, hunks, kind: DiffFileKind::Code, summary: None }
+    }
+
+    #[test]
+    fn test_summarize_hunks_by_symbol() {
+        // `process` edited, `reset` added after it, `legacy` removed
+        let hunks = vec![
+            DiffHunk { old_start: 4, old_lines: 3, new_start: 4, new_lines: 3, content: "     let a = 1;\n-    old();\n+    new();\n".to_string() },
+            DiffHunk {
+                old_start: 10,
+                old_lines: 3,
+                new_start: 10,
+                new_lines: 3,
+                content: "-fn legacy() {\n-}\n+fn reset() {\n+}\n }\n".to_string(),
+            },
+        ];
+        let before = vec![("Router".to_string(), range(1, 12)), ("Router::process".to_string(), range(3, 7)), ("legacy".to_string(), range(10, 11))];
+        let after = vec![("Router".to_string(), range(1, 12)), ("Router::process".to_string(), range(3, 7)), ("Router::reset".to_string(), range(10, 11))];
+        let summary = summarize_file(&file("src/router.rs", hunks), &before, &after);
+        assert_eq!(summary.describe(), "add Router::reset; update Router::process; remove legacy");
+
+        let diff = DiffOutput {
+            files: vec![file("src/router.rs", Vec::new()), file("README.md", Vec::new())],
+            total_additions: 0,
+            total_deletions: 0,
+        };
+        assert_eq!(
+            commit_message(&diff, &[summary]).unwrap(),
+            "feat: add Router::reset; update Router::process; remove legacy\n\n\
+             - src/router.rs: add Router::reset; update Router::process; remove legacy\n\
+             - README.md: update"
+        );
+        assert!(commit_message(&diff, &[]).is_none());
+    }
+}
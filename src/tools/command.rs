@@ -4,10 +4,11 @@ use crate::security::{CommandScanner, RiskLevel};
 use rig::tool::Tool;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use std::process::Stdio;
+use std::collections::HashMap;
+use std::process::{Output, Stdio};
 use std::time::Duration;
 use thiserror::Error;
-use tokio::process::Command;
+use tokio::process::{Child, Command};
 use tokio::time::timeout;
 
 // ============================================================================
@@ -42,6 +43,8 @@ pub struct ShellExecuteTool {
     working_dir: Option<String>,
     /// Whether to skip security checks (dangerous!)
     skip_security: bool,
+    /// Extra environment variables for the child process
+    env: HashMap<String, String>,
 }
 
 impl Default for ShellExecuteTool {
@@ -57,6 +60,7 @@ impl ShellExecuteTool {
             timeout_secs: 1200,
             working_dir: None,
             skip_security: false,
+            env: HashMap::new(),
         }
     }
 
@@ -97,19 +101,13 @@ impl ShellExecuteTool {
     async fn run_command(&self, command: &str) -> Result<CommandOutput, CommandError> {
         let mut cmd = Command::new("sh");
         cmd.arg("-c").arg(command);
-        cmd.stdout(Stdio::piped());
-        cmd.stderr(Stdio::piped());
 
         if let Some(ref dir) = self.working_dir {
             cmd.current_dir(dir);
         }
+        cmd.envs(&self.env);
 
-        let future = async {
-            let output = cmd.output().await?;
-            Ok::<_, std::io::Error>(output)
-        };
-
-        let output = timeout(Duration::from_secs(self.timeout_secs), future)
+        let output = timeout(Duration::from_secs(self.timeout_secs), output_in_group(&mut cmd))
             .await
             .map_err(|_| CommandError::Timeout(self.timeout_secs))??;
 
@@ -137,6 +135,10 @@ pub struct ShellExecuteArgs {
     /// Timeout in seconds (max 1200)
     #[serde(default = "default_timeout")]
     pub timeout_secs: u64,
+    /// Session environment variables, set by the agent rather than the model
+    #[serde(skip)]
+    #[schemars(skip)]
+    pub env: HashMap<String, String>,
 }
 
 fn default_timeout() -> u64 {
@@ -189,16 +191,81 @@ impl Tool for ShellExecuteTool {
             timeout_secs: args.timeout_secs.min(1200),
             working_dir: args.working_dir.or_else(|| self.working_dir.clone()),
             skip_security: self.skip_security,
+            env: args.env,
         };
 
         executor.run_command(&args.command).await
     }
 }
 
+// ============================================================================
+// Process groups
+// ============================================================================
+
+/// Kills a child's process group when dropped. `kill_on_drop` only reaches the
+/// direct child, so a cancelled `sh -c` or `cargo test` would leave what it
+/// started running.
+#[derive(Debug)]
+pub(crate) struct ProcessGroup(Option<u32>);
+
+impl ProcessGroup {
+    /// The child exited on its own: leave the group alone
+    pub(crate) fn release(mut self) {
+        self.0 = None;
+    }
+}
+
+impl Drop for ProcessGroup {
+    fn drop(&mut self) {
+        #[cfg(unix)]
+        if let Some(pid) = self.0 {
+            let _ = std::process::Command::new("kill")
+                .args(["-KILL", "--", &format!("-{}", pid)])
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .status();
+        }
+    }
+}
+
+/// Spawn `cmd` as the leader of a new process group
+pub(crate) fn spawn_in_group(cmd: &mut Command) -> std::io::Result<(Child, ProcessGroup)> {
+    #[cfg(unix)]
+    cmd.process_group(0);
+    cmd.kill_on_drop(true);
+    let child = cmd.spawn()?;
+    let group = ProcessGroup(child.id());
+    Ok((child, group))
+}
+
+/// `Command::output` for a child in its own process group: dropping the
+/// future (cancelled turn, timeout) kills the whole group
+pub(crate) async fn output_in_group(cmd: &mut Command) -> std::io::Result<Output> {
+    cmd.stdin(Stdio::null()).stdout(Stdio::piped()).stderr(Stdio::piped());
+    let (child, group) = spawn_in_group(cmd)?;
+    let output = child.wait_with_output().await;
+    group.release();
+    output
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_timeout_kills_the_process_group() {
+        let dir = tempfile::tempdir().unwrap();
+        let marker = dir.path().join("marker");
+        let mut cmd = Command::new("sh");
+        cmd.arg("-c").arg(format!("(sleep 1; touch {}) & wait", marker.display()));
+
+        let run = timeout(Duration::from_millis(200), output_in_group(&mut cmd)).await;
+        assert!(run.is_err());
+        tokio::time::sleep(Duration::from_millis(1500)).await;
+        assert!(!marker.exists());
+    }
+
     #[tokio::test]
     async fn test_safe_command() {
         let tool = ShellExecuteTool::new();
@@ -207,6 +274,7 @@ mod tests {
                 command: "echo 'Hello, World!'".to_string(),
                 working_dir: None,
                 timeout_secs: 10,
+                env: HashMap::new(),
             })
             .await
             .unwrap();
@@ -223,6 +291,7 @@ mod tests {
                 command: "exit 1".to_string(),
                 working_dir: None,
                 timeout_secs: 10,
+                env: HashMap::new(),
             })
             .await
             .unwrap();
@@ -0,0 +1,395 @@
+//! Project complexity report
+//!
+//! Per-function metrics (cyclomatic complexity, length, nesting) and per-file
+//! TODO counts are computed when the symbol index re-parses a file and stored in
+//! `file_metrics`. Every index run that changed files aggregates them into a
+//! [`ComplexityReport`] saved in `complexity_snapshots`, which `/analyze trends`
+//! compares over time.
+
+use crate::ast::{AstParser, AstSymbol, SymbolKind};
+use crate::db::ComplexitySnapshot;
+use chrono::Utc;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::sync::LazyLock;
+use tree_sitter::{Point, Tree};
+
+/// Functions above this cyclomatic complexity are flagged
+pub const COMPLEX_THRESHOLD: usize = 10;
+/// Functions longer than this many lines are flagged
+pub const LONG_THRESHOLD: usize = 50;
+/// Functions nesting deeper than this are flagged
+pub const DEEP_THRESHOLD: usize = 4;
+/// Functions listed as hotspots in a report
+const MAX_HOTSPOTS: usize = 10;
+
+static TODO_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\b(TODO|FIXME|HACK|XXX)\b").unwrap());
+
+/// Metrics of one function or method
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FunctionMetrics {
+    pub name: String,
+    pub line: usize,
+    pub complexity: usize,
+    pub length: usize,
+    pub nesting: usize,
+}
+
+/// Metrics of one source file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileMetrics {
+    pub path: String,
+    pub lines: usize,
+    pub todos: usize,
+    pub functions: Vec<FunctionMetrics>,
+}
+
+impl FileMetrics {
+    /// Metrics of a parsed file from its extracted symbols
+    pub fn compute(
+        path: &str,
+        source: &str,
+        tree: &Tree,
+        symbols: &[AstSymbol],
+        parser: &AstParser,
+    ) -> Self {
+        let functions = symbols
+            .iter()
+            .filter(|s| matches!(s.kind, SymbolKind::Function | SymbolKind::Method))
+            .filter_map(|symbol| {
+                let node = tree.root_node().descendant_for_point_range(
+                    Point::new(symbol.range.start_line - 1, symbol.range.start_col),
+                    Point::new(symbol.range.end_line - 1, symbol.range.end_col),
+                )?;
+                Some(FunctionMetrics {
+                    name: symbol.name.clone(),
+                    line: symbol.range.start_line,
+                    complexity: parser.calculate_complexity(&node, source),
+                    length: symbol.range.end_line - symbol.range.start_line + 1,
+                    nesting: parser.nesting_depth(&node),
+                })
+            })
+            .collect();
+
+        Self {
+            path: path.to_string(),
+            lines: source.lines().count(),
+            todos: source.lines().filter(|l| TODO_RE.is_match(l)).count(),
+            functions,
+        }
+    }
+}
+
+/// A function worth refactoring first
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Hotspot {
+    pub path: String,
+    #[serde(flatten)]
+    pub function: FunctionMetrics,
+}
+
+/// Project-wide complexity and maintainability
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ComplexityReport {
+    pub files: usize,
+    pub lines: usize,
+    pub functions: usize,
+    pub avg_complexity: f64,
+    pub max_complexity: usize,
+    pub avg_function_length: f64,
+    pub max_nesting: usize,
+    pub complex_functions: usize,
+    pub long_functions: usize,
+    pub deep_functions: usize,
+    pub todos: usize,
+    /// TODO/FIXME markers per 1000 lines
+    pub todo_density: f64,
+    /// Heuristic 0-100 score, higher is easier to maintain
+    pub maintainability: f64,
+    /// Most complex functions, worst first
+    pub hotspots: Vec<Hotspot>,
+}
+
+impl ComplexityReport {
+    pub fn from_files(files: &[FileMetrics]) -> Self {
+        let functions: Vec<(&str, &FunctionMetrics)> = files
+            .iter()
+            .flat_map(|f| f.functions.iter().map(move |func| (f.path.as_str(), func)))
+            .collect();
+        let lines: usize = files.iter().map(|f| f.lines).sum();
+        let todos: usize = files.iter().map(|f| f.todos).sum();
+        let count = functions.len();
+
+        let mean = |value: fn(&FunctionMetrics) -> usize| {
+            if count == 0 {
+                0.0
+            } else {
+                functions.iter().map(|(_, f)| value(f)).sum::<usize>() as f64 / count as f64
+            }
+        };
+        let share = |flagged: usize| {
+            if count == 0 {
+                0.0
+            } else {
+                flagged as f64 / count as f64
+            }
+        };
+
+        let complex_functions = functions
+            .iter()
+            .filter(|(_, f)| f.complexity > COMPLEX_THRESHOLD)
+            .count();
+        let long_functions = functions
+            .iter()
+            .filter(|(_, f)| f.length > LONG_THRESHOLD)
+            .count();
+        let deep_functions = functions
+            .iter()
+            .filter(|(_, f)| f.nesting > DEEP_THRESHOLD)
+            .count();
+        let avg_complexity = mean(|f| f.complexity);
+        let todo_density = if lines == 0 {
+            0.0
+        } else {
+            todos as f64 * 1000.0 / lines as f64
+        };
+
+        // Penalties for the usual maintainability smells, not the classic
+        // Halstead-based index: it needs operator counts we do not extract
+        let maintainability = (100.0
+            - 4.0 * (avg_complexity - 1.0).max(0.0)
+            - 30.0 * share(complex_functions)
+            - 20.0 * share(long_functions)
+            - 15.0 * share(deep_functions)
+            - todo_density.min(10.0))
+        .clamp(0.0, 100.0);
+
+        let mut hotspots: Vec<Hotspot> = functions
+            .iter()
+            .map(|(path, f)| Hotspot {
+                path: path.to_string(),
+                function: (*f).clone(),
+            })
+            .collect();
+        hotspots.sort_by(|a, b| {
+            b.function
+                .complexity
+                .cmp(&a.function.complexity)
+                .then_with(|| b.function.length.cmp(&a.function.length))
+                .then_with(|| a.path.cmp(&b.path))
+        });
+        hotspots.truncate(MAX_HOTSPOTS);
+
+        Self {
+            files: files.len(),
+            lines,
+            functions: count,
+            avg_complexity,
+            max_complexity: functions.iter().map(|(_, f)| f.complexity).max().unwrap_or(0),
+            avg_function_length: mean(|f| f.length),
+            max_nesting: functions.iter().map(|(_, f)| f.nesting).max().unwrap_or(0),
+            complex_functions,
+            long_functions,
+            deep_functions,
+            todos,
+            todo_density,
+            maintainability,
+            hotspots,
+        }
+    }
+
+    /// Database row for this report
+    pub fn to_snapshot(&self, project_id: &str) -> ComplexitySnapshot {
+        ComplexitySnapshot {
+            id: 0,
+            project_id: project_id.to_string(),
+            taken_at: Utc::now().to_rfc3339(),
+            files: self.files as i64,
+            functions: self.functions as i64,
+            avg_complexity: self.avg_complexity,
+            max_complexity: self.max_complexity as i64,
+            complex_functions: self.complex_functions as i64,
+            todo_count: self.todos as i64,
+            maintainability: self.maintainability,
+            report_json: serde_json::to_string(self).unwrap_or_default(),
+        }
+    }
+
+    /// Markdown report
+    pub fn format(&self) -> String {
+        let mut output = format!(
+            "# Complexity report\n\n\
+             **Files:** {} ({} lines)\n\
+             **Functions:** {}\n\
+             **Cyclomatic complexity:** avg {:.1}, max {}\n\
+             **Function length:** avg {:.1} lines\n\
+             **Max nesting:** {}\n\
+             **Flagged:** {} complex (>{}), {} long (>{} lines), {} deeply nested (>{})\n\
+             **TODO/FIXME:** {} ({:.1} per 1000 lines)\n\
+             **Maintainability:** {:.0}/100\n",
+            self.files,
+            self.lines,
+            self.functions,
+            self.avg_complexity,
+            self.max_complexity,
+            self.avg_function_length,
+            self.max_nesting,
+            self.complex_functions,
+            COMPLEX_THRESHOLD,
+            self.long_functions,
+            LONG_THRESHOLD,
+            self.deep_functions,
+            DEEP_THRESHOLD,
+            self.todos,
+            self.todo_density,
+            self.maintainability
+        );
+
+        if !self.hotspots.is_empty() {
+            output.push_str("\n## Hotspots\n");
+            for hotspot in &self.hotspots {
+                output.push_str(&format!(
+                    "- {}:{} `{}` — complexity {}, {} lines, nesting {}\n",
+                    hotspot.path,
+                    hotspot.function.line,
+                    hotspot.function.name,
+                    hotspot.function.complexity,
+                    hotspot.function.length,
+                    hotspot.function.nesting
+                ));
+            }
+        }
+        output
+    }
+}
+
+/// Direction of a metric between two snapshots
+fn arrow(delta: f64) -> &'static str {
+    if delta > 0.05 {
+        "↑"
+    } else if delta < -0.05 {
+        "↓"
+    } else {
+        "="
+    }
+}
+
+/// Markdown table of snapshots (oldest first) with the overall direction
+pub fn format_trends(snapshots: &[ComplexitySnapshot]) -> String {
+    let (Some(first), Some(last)) = (snapshots.first(), snapshots.last()) else {
+        return "No complexity history yet: it is recorded each time the index changes".to_string();
+    };
+
+    let mut output = String::from(
+        "# Complexity trends\n\n\
+         | Date | Files | Functions | Avg CC | Max CC | Complex | TODOs | Maintainability |\n\
+         |------|-------|-----------|--------|--------|---------|-------|-----------------|\n",
+    );
+    let mut previous: Option<&ComplexitySnapshot> = None;
+    for snapshot in snapshots {
+        let change = |now: f64, before: fn(&ComplexitySnapshot) -> f64| {
+            previous
+                .map(|p| format!(" {}", arrow(now - before(p))))
+                .unwrap_or_default()
+        };
+        output.push_str(&format!(
+            "| {} | {} | {} | {:.2}{} | {} | {}{} | {}{} | {:.0}{} |\n",
+            snapshot.taken_at.get(..16).unwrap_or(&snapshot.taken_at).replace('T', " "),
+            snapshot.files,
+            snapshot.functions,
+            snapshot.avg_complexity,
+            change(snapshot.avg_complexity, |p| p.avg_complexity),
+            snapshot.max_complexity,
+            snapshot.complex_functions,
+            change(snapshot.complex_functions as f64, |p| p.complex_functions as f64),
+            snapshot.todo_count,
+            change(snapshot.todo_count as f64, |p| p.todo_count as f64),
+            snapshot.maintainability,
+            change(snapshot.maintainability, |p| p.maintainability),
+        ));
+        previous = Some(snapshot);
+    }
+
+    let delta = last.maintainability - first.maintainability;
+    let verdict = if snapshots.len() < 2 {
+        "Only one snapshot so far: trends appear after the next change".to_string()
+    } else if delta > 1.0 {
+        format!("📈 Improving: maintainability {:+.1} over {} snapshots", delta, snapshots.len())
+    } else if delta < -1.0 {
+        format!("📉 Regressing: maintainability {:+.1} over {} snapshots", delta, snapshots.len())
+    } else {
+        format!("➡️ Stable: maintainability {:+.1} over {} snapshots", delta, snapshots.len())
+    };
+    output.push_str(&format!("\n{}\n", verdict));
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ast::SupportedLanguage;
+
+    fn metrics(path: &str, code: &str) -> FileMetrics {
+        let mut parser = AstParser::new().unwrap();
+        let tree = parser.parse(SupportedLanguage::Rust, code).unwrap();
+        let symbols = parser.extract_symbols(&tree, SupportedLanguage::Rust, code);
+        FileMetrics::compute(path, code, &tree, &symbols, &parser)
+    }
+
+    #[test]
+    fn test_file_metrics() {
+        let code = r#"
+// TODO: split this up
+fn classify(n: i32) -> &'static str {
+    if n > 0 {
+        for _ in 0..n {
+            if n % 2 == 0 {
+                return "even";
+            }
+        }
+        "odd"
+    } else if n < 0 {
+        "negative"
+    } else {
+        "zero"
+    }
+}
+
+struct Point;
+
+fn simple() {}
+"#;
+        let file = metrics("src/lib.rs", code);
+        assert_eq!(file.todos, 1);
+        assert_eq!(file.functions.len(), 2);
+
+        let classify = &file.functions[0];
+        assert_eq!((classify.name.as_str(), classify.line, classify.length), ("classify", 3, 14));
+        assert_eq!(classify.nesting, 3);
+        assert!(classify.complexity >= 5);
+        assert_eq!((file.functions[1].complexity, file.functions[1].nesting), (1, 0));
+    }
+
+    #[test]
+    fn test_report_and_trends() {
+        let file = metrics("src/lib.rs", "fn a() {}\nfn b(x: bool) { if x { } }\n");
+        let report = ComplexityReport::from_files(std::slice::from_ref(&file));
+        assert_eq!(report.functions, 2);
+        assert_eq!(report.max_complexity, 2);
+        assert_eq!(report.hotspots[0].function.name, "b");
+        assert!(report.maintainability > 90.0);
+        assert!(report.format().contains("src/lib.rs:2 `b`"));
+
+        let mut worse = file.clone();
+        worse.todos = 5;
+        worse.functions[0].complexity = 25;
+        let worse = ComplexityReport::from_files(&[worse]);
+        assert!(worse.maintainability < report.maintainability);
+
+        let trends = format_trends(&[report.to_snapshot("p"), worse.to_snapshot("p")]);
+        assert!(trends.contains("📉 Regressing"));
+        assert!(trends.contains("| 13.50 ↑ |"));
+        assert!(format_trends(&[]).starts_with("No complexity history"));
+    }
+}
@@ -1,5 +1,7 @@
 //! Project context tool - Maintain context about the current project
 
+use super::docker::{ContainerTopology, DockerComposeTool};
+use super::environment::{EnvironmentTool, RuntimeMatrix};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
@@ -23,6 +25,12 @@ pub struct ProjectContext {
     pub important_files: Vec<ImportantFile>,
     pub directories: DirectoryStructure,
     pub git_info: Option<GitInfo>,
+    /// Dockerfiles and compose services of the dev environment
+    #[serde(default)]
+    pub containers: Option<ContainerTopology>,
+    /// Installed runtimes and active toolchains, as seen from the project root
+    #[serde(default)]
+    pub runtimes: Option<RuntimeMatrix>,
 }
 
 /// Primary language of the project
@@ -102,6 +110,9 @@ pub struct ContextSummary {
     pub tech_stack: String,
     pub key_files: Vec<String>,
     pub structure: String,
+    pub containers: Option<String>,
+    /// One line: `Rust 1.82.0 (rustup stable), Node 20.11.1 (nvm)`
+    pub runtimes: Option<String>,
 }
 
 /// Project context tool
@@ -143,6 +154,8 @@ impl ProjectContextTool {
         let git_info = self.get_git_info(&root).await.ok();
         let dependencies_count = self.count_dependencies(&root, &language).await.unwrap_or(0);
         let file_count = self.count_files(&root).await.unwrap_or(0);
+        let containers = DockerComposeTool::new().inspect(&root).await.ok();
+        let runtimes = EnvironmentTool::new().runtime_matrix(&root).await;
 
         let context = ProjectContext {
             name,
@@ -160,6 +173,8 @@ impl ProjectContextTool {
             important_files,
             directories,
             git_info,
+            containers,
+            runtimes: Some(runtimes),
         };
 
         self.context = Some(context.clone());
@@ -830,6 +845,8 @@ impl ProjectContextTool {
             tech_stack,
             key_files,
             structure,
+            containers: context.containers.as_ref().map(|c| c.summary()),
+            runtimes: context.runtimes.as_ref().map(|r| r.compact()),
         })
     }
 }
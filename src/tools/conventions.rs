@@ -0,0 +1,535 @@
+//! Project convention checker
+//!
+//! Learns how a project is written (case style of function, type and constant
+//! names, error-handling idioms, which logging calls it uses) from its own source
+//! files, then flags the lines of agent-written code that deviate. The orchestrator
+//! appends the findings to the `write_file` result so the model revises the file
+//! before presenting the change.
+
+use super::symbol_index::{relative_path, source_files};
+use crate::ast::{AstParser, SupportedLanguage, SymbolKind};
+use regex::Regex;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, LazyLock};
+use tokio::sync::Mutex;
+
+/// Files sampled when learning conventions
+const MAX_LEARN_FILES: usize = 2000;
+/// Names of a kind needed before its case style counts as a convention
+const MIN_NAME_SAMPLES: usize = 10;
+/// Share of names needed for a case style to be the convention
+const NAMING_SHARE: f64 = 0.8;
+/// Logging calls needed before a logging style counts as a convention
+const MIN_LOG_SAMPLES: usize = 10;
+/// Share of logging calls needed for a family to be the convention
+const DOMINANT_LOG_SHARE: f64 = 0.6;
+/// Families below this share are considered foreign to the project
+const RARE_LOG_SHARE: f64 = 0.1;
+/// `.unwrap()`/`.expect()` per 1000 non-test lines under which they are avoided
+const UNWRAP_DENSITY: f64 = 1.0;
+/// Non-test lines needed before the unwrap density is meaningful
+const MIN_UNWRAP_LINES: usize = 500;
+/// Violations reported for one file
+const MAX_VIOLATIONS: usize = 20;
+
+static UNWRAP_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\.(?:unwrap|expect)\(").unwrap());
+static EXCEPT_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^\s*except\b").unwrap());
+static BARE_EXCEPT_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^\s*except\s*:").unwrap());
+
+/// Logging call families of a language: `(name, pattern)`
+static LOG_FAMILIES: LazyLock<HashMap<SupportedLanguage, Vec<(&'static str, Regex)>>> =
+    LazyLock::new(|| {
+        let family = |name, pattern| (name, Regex::new(pattern).unwrap());
+        let console = vec![
+            family("console.*", r"\bconsole\.(?:log|debug|info|warn|error)\("),
+            family("logger calls", r"\b(?:logger|log)\.(?:trace|debug|info|warn|error)\("),
+        ];
+        HashMap::from([
+            (
+                SupportedLanguage::Rust,
+                vec![
+                    family("println!/eprintln!", r"\b(?:e?println|e?print|dbg)!\s*\("),
+                    family("log/tracing macros", r"\b(?:trace|debug|info|warn|error)!\s*\("),
+                    family("log_*! macros", r"\blog_(?:trace|debug|info|warn|error)!\s*\("),
+                ],
+            ),
+            (
+                SupportedLanguage::Python,
+                vec![
+                    family("print()", r"(?:^|[^\w.])print\("),
+                    family(
+                        "logging calls",
+                        r"\b(?:logging|logger|log|LOGGER)\.(?:debug|info|warning|error|exception|critical)\(",
+                    ),
+                ],
+            ),
+            (SupportedLanguage::JavaScript, console.clone()),
+            (SupportedLanguage::TypeScript, console),
+        ])
+    });
+
+/// Case style of an identifier
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CaseStyle {
+    Snake,
+    Camel,
+    Pascal,
+    ScreamingSnake,
+}
+
+impl CaseStyle {
+    /// Style of a name, `None` when it fits several (`run`, `ID`) or none
+    pub fn of(name: &str) -> Option<Self> {
+        let name = name.trim_matches('_');
+        let has_lower = name.chars().any(|c| c.is_ascii_lowercase());
+        let has_upper = name.chars().any(|c| c.is_ascii_uppercase());
+        let has_underscore = name.contains('_');
+        let first_upper = name.chars().next()?.is_ascii_uppercase();
+
+        match (has_lower, has_upper, has_underscore) {
+            (true, false, true) => Some(Self::Snake),
+            (false, true, true) => Some(Self::ScreamingSnake),
+            (true, true, false) if first_upper => Some(Self::Pascal),
+            (true, true, false) => Some(Self::Camel),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Snake => "snake_case",
+            Self::Camel => "camelCase",
+            Self::Pascal => "PascalCase",
+            Self::ScreamingSnake => "SCREAMING_SNAKE_CASE",
+        }
+    }
+}
+
+/// Names checked for a consistent case style
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum NameKind {
+    Function,
+    Type,
+    Constant,
+}
+
+impl NameKind {
+    fn of(kind: &SymbolKind) -> Option<Self> {
+        match kind {
+            SymbolKind::Function | SymbolKind::Method => Some(Self::Function),
+            SymbolKind::Struct
+            | SymbolKind::Class
+            | SymbolKind::Enum
+            | SymbolKind::Trait
+            | SymbolKind::Interface => Some(Self::Type),
+            SymbolKind::Constant => Some(Self::Constant),
+            _ => None,
+        }
+    }
+
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Function => "function",
+            Self::Type => "type",
+            Self::Constant => "constant",
+        }
+    }
+}
+
+/// A deviation from the project conventions
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConventionViolation {
+    pub line: usize,
+    /// `naming`, `error-handling` or `logging`
+    pub rule: &'static str,
+    pub message: String,
+}
+
+/// Conventions learned from a project's sources
+#[derive(Debug, Clone, Default)]
+pub struct ProjectConventions {
+    names: HashMap<(SupportedLanguage, NameKind), HashMap<CaseStyle, usize>>,
+    logging: HashMap<SupportedLanguage, HashMap<&'static str, usize>>,
+    rust_lines: usize,
+    rust_unwraps: usize,
+    python_excepts: usize,
+    python_bare_excepts: usize,
+    pub files: usize,
+}
+
+impl ProjectConventions {
+    /// Learn from the source files below `root`
+    pub fn learn(root: &Path) -> Self {
+        let mut conventions = Self::default();
+        let Ok(mut parser) = AstParser::new() else {
+            return conventions;
+        };
+        for (path, language) in source_files(root).into_iter().take(MAX_LEARN_FILES) {
+            let Ok(source) = std::fs::read_to_string(&path) else {
+                continue;
+            };
+            conventions.add_file(&mut parser, &relative_path(root, &path), language, &source);
+        }
+        conventions
+    }
+
+    /// Account for one file
+    pub fn add_file(
+        &mut self,
+        parser: &mut AstParser,
+        path: &str,
+        language: SupportedLanguage,
+        source: &str,
+    ) {
+        self.files += 1;
+        if let Ok(tree) = parser.parse(language, source) {
+            for symbol in parser.extract_symbols(&tree, language, source) {
+                let (Some(kind), Some(style)) = (NameKind::of(&symbol.kind), CaseStyle::of(&symbol.name))
+                else {
+                    continue;
+                };
+                *self
+                    .names
+                    .entry((language, kind))
+                    .or_default()
+                    .entry(style)
+                    .or_default() += 1;
+            }
+        }
+
+        // Idioms of test code (unwraps, prints) say nothing about the project
+        if is_test_file(path) {
+            return;
+        }
+        for (_, line) in non_test_lines(language, source) {
+            if let Some(families) = LOG_FAMILIES.get(&language) {
+                for (name, pattern) in families {
+                    let count = pattern.find_iter(line).count();
+                    if count > 0 {
+                        *self
+                            .logging
+                            .entry(language)
+                            .or_default()
+                            .entry(name)
+                            .or_default() += count;
+                    }
+                }
+            }
+            match language {
+                SupportedLanguage::Rust => {
+                    self.rust_lines += 1;
+                    self.rust_unwraps += UNWRAP_RE.find_iter(line).count();
+                }
+                SupportedLanguage::Python if EXCEPT_RE.is_match(line) => {
+                    self.python_excepts += 1;
+                    self.python_bare_excepts += BARE_EXCEPT_RE.is_match(line) as usize;
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Dominant case style of a kind of name, with its share and sample size
+    fn naming(&self, language: SupportedLanguage, kind: NameKind) -> Option<(CaseStyle, f64, usize)> {
+        let counts = self.names.get(&(language, kind))?;
+        let total: usize = counts.values().sum();
+        let (style, count) = counts.iter().max_by_key(|(_, count)| **count)?;
+        let share = *count as f64 / total as f64;
+        (total >= MIN_NAME_SAMPLES && share >= NAMING_SHARE).then_some((*style, share, total))
+    }
+
+    /// Dominant logging family of a language and the share of each family
+    fn logging(&self, language: SupportedLanguage) -> Option<(&'static str, HashMap<&'static str, f64>)> {
+        let counts = self.logging.get(&language)?;
+        let total: usize = counts.values().sum();
+        if total < MIN_LOG_SAMPLES {
+            return None;
+        }
+        let shares: HashMap<&'static str, f64> = counts
+            .iter()
+            .map(|(name, count)| (*name, *count as f64 / total as f64))
+            .collect();
+        let (dominant, share) = shares
+            .iter()
+            .max_by(|a, b| a.1.total_cmp(b.1))
+            .map(|(name, share)| (*name, *share))?;
+        (share >= DOMINANT_LOG_SHARE).then_some((dominant, shares))
+    }
+
+    /// The project keeps `.unwrap()`/`.expect()` out of non-test Rust code
+    fn avoids_unwrap(&self) -> bool {
+        self.rust_lines >= MIN_UNWRAP_LINES
+            && (self.rust_unwraps as f64 * 1000.0 / self.rust_lines as f64) < UNWRAP_DENSITY
+    }
+
+    /// Deviations in the lines of `new` that are not in `old`
+    pub fn check(
+        &self,
+        parser: &mut AstParser,
+        path: &str,
+        language: SupportedLanguage,
+        old: Option<&str>,
+        new: &str,
+    ) -> Vec<ConventionViolation> {
+        let existing: HashSet<&str> = old
+            .map(|old| old.lines().map(str::trim).collect())
+            .unwrap_or_default();
+        let lines: Vec<&str> = new.lines().collect();
+        let is_new = |line: usize| {
+            lines
+                .get(line - 1)
+                .is_some_and(|l| !existing.contains(l.trim()))
+        };
+        let mut violations = Vec::new();
+
+        if let Ok(tree) = parser.parse(language, new) {
+            for symbol in parser.extract_symbols(&tree, language, new) {
+                let line = symbol.range.start_line;
+                let (Some(kind), Some(style)) = (NameKind::of(&symbol.kind), CaseStyle::of(&symbol.name))
+                else {
+                    continue;
+                };
+                let Some((expected, share, total)) = self.naming(language, kind) else {
+                    continue;
+                };
+                if style != expected && is_new(line) {
+                    violations.push(ConventionViolation {
+                        line,
+                        rule: "naming",
+                        message: format!(
+                            "{} `{}` is {}, the project names {}s in {} ({:.0}% of {})",
+                            kind.as_str(),
+                            symbol.name,
+                            style.as_str(),
+                            kind.as_str(),
+                            expected.as_str(),
+                            share * 100.0,
+                            total
+                        ),
+                    });
+                }
+            }
+        }
+
+        if !is_test_file(path) {
+            let logging = self.logging(language);
+            for (number, line) in non_test_lines(language, new) {
+                if !is_new(number) {
+                    continue;
+                }
+                if let (Some((dominant, shares)), Some(families)) =
+                    (&logging, LOG_FAMILIES.get(&language))
+                {
+                    for (name, pattern) in families {
+                        let share = shares.get(name).copied().unwrap_or(0.0);
+                        if name != dominant && share < RARE_LOG_SHARE && pattern.is_match(line) {
+                            violations.push(ConventionViolation {
+                                line: number,
+                                rule: "logging",
+                                message: format!(
+                                    "uses {}, the project logs with {} ({:.0}% of logging calls)",
+                                    name,
+                                    dominant,
+                                    shares[dominant] * 100.0
+                                ),
+                            });
+                        }
+                    }
+                }
+                if language == SupportedLanguage::Rust
+                    && self.avoids_unwrap()
+                    && UNWRAP_RE.is_match(line)
+                {
+                    violations.push(ConventionViolation {
+                        line: number,
+                        rule: "error-handling",
+                        message: "`.unwrap()`/`.expect()` outside tests, the project propagates \
+                                  errors with `?` instead"
+                            .to_string(),
+                    });
+                }
+                if language == SupportedLanguage::Python
+                    && self.python_excepts >= MIN_LOG_SAMPLES
+                    && self.python_bare_excepts == 0
+                    && BARE_EXCEPT_RE.is_match(line)
+                {
+                    violations.push(ConventionViolation {
+                        line: number,
+                        rule: "error-handling",
+                        message: "bare `except:`, the project always names the exception type"
+                            .to_string(),
+                    });
+                }
+            }
+        }
+
+        violations.sort_by_key(|v| v.line);
+        violations.truncate(MAX_VIOLATIONS);
+        violations
+    }
+}
+
+/// Feedback for the model about the deviations of a written file
+pub fn format_violations(path: &str, violations: &[ConventionViolation]) -> String {
+    let mut output = format!(
+        "⚠️ {} deviates from the project conventions:\n",
+        path
+    );
+    for violation in violations {
+        output.push_str(&format!(
+            "- line {} [{}]: {}\n",
+            violation.line, violation.rule, violation.message
+        ));
+    }
+    output.push_str("Revise the file to follow these conventions before presenting the change.");
+    output
+}
+
+/// Checks agent-written files against the conventions of their project,
+/// learning them once per project root
+#[derive(Clone, Default)]
+pub struct ConventionChecker {
+    learned: Arc<Mutex<HashMap<PathBuf, Arc<ProjectConventions>>>>,
+}
+
+impl ConventionChecker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Conventions of a project, learned on first use
+    pub async fn conventions(&self, root: &Path) -> Arc<ProjectConventions> {
+        let root = root.canonicalize().unwrap_or_else(|_| root.to_path_buf());
+        let mut learned = self.learned.lock().await;
+        if let Some(conventions) = learned.get(&root) {
+            return conventions.clone();
+        }
+
+        let learn_root = root.clone();
+        let conventions = tokio::task::spawn_blocking(move || ProjectConventions::learn(&learn_root))
+            .await
+            .map(Arc::new)
+            .unwrap_or_default();
+        learned.insert(root, conventions.clone());
+        conventions
+    }
+
+    /// Feedback for a file written below `root`, `None` when it follows the
+    /// conventions or is not a supported source file. Learn the conventions
+    /// before writing the file, or it counts towards them.
+    pub async fn review(&self, root: &Path, path: &Path, old: Option<&str>) -> Option<String> {
+        let language = SupportedLanguage::from_path(path)?;
+        let new = tokio::fs::read_to_string(path).await.ok()?;
+        let conventions = self.conventions(root).await;
+
+        let root = root.canonicalize().unwrap_or_else(|_| root.to_path_buf());
+        let relative = relative_path(&root, &path.canonicalize().unwrap_or_else(|_| path.to_path_buf()));
+        let old = old.map(str::to_string);
+        let checked = relative.clone();
+        let violations = tokio::task::spawn_blocking(move || {
+            let mut parser = AstParser::new().ok()?;
+            Some(conventions.check(&mut parser, &checked, language, old.as_deref(), &new))
+        })
+        .await
+        .ok()??;
+
+        (!violations.is_empty()).then(|| format_violations(&relative, &violations))
+    }
+}
+
+/// Test files by path: `tests/`, `test_*.py`, `*.test.ts`, `*_test.rs`...
+fn is_test_file(path: &str) -> bool {
+    let name = path.rsplit('/').next().unwrap_or(path);
+    path.starts_with("tests/")
+        || path.contains("/tests/")
+        || path.contains("__tests__/")
+        || name.starts_with("test_")
+        || name.contains("_test.")
+        || name.contains(".test.")
+        || name.contains(".spec.")
+}
+
+/// Numbered lines of a file outside Rust `#[cfg(test)]` modules
+fn non_test_lines(
+    language: SupportedLanguage,
+    source: &str,
+) -> impl Iterator<Item = (usize, &str)> {
+    let mut in_tests = false;
+    source
+        .lines()
+        .enumerate()
+        .map(|(index, line)| (index + 1, line))
+        .filter(move |(_, line)| {
+            if language == SupportedLanguage::Rust
+                && line.trim_start().starts_with("#[cfg(test)]")
+            {
+                in_tests = true;
+            }
+            !in_tests
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_case_style() {
+        assert_eq!(CaseStyle::of("parse_file"), Some(CaseStyle::Snake));
+        assert_eq!(CaseStyle::of("parseFile"), Some(CaseStyle::Camel));
+        assert_eq!(CaseStyle::of("AstParser"), Some(CaseStyle::Pascal));
+        assert_eq!(CaseStyle::of("MAX_FILES"), Some(CaseStyle::ScreamingSnake));
+        assert_eq!(CaseStyle::of("__init__"), None);
+        assert_eq!(CaseStyle::of("run"), None);
+    }
+
+    #[tokio::test]
+    async fn test_review_flags_new_deviations() {
+        let dir = tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("src")).unwrap();
+        let mut module = String::new();
+        for i in 0..12 {
+            module.push_str(&format!(
+                "fn load_part_{i}(path: &str) -> Result<String> {{\n    log_info!(\"loading {{}}\", path);\n    let data = read(path)?;\n    Ok(data)\n}}\n\n"
+            ));
+        }
+        module.push_str(&"// padding\n".repeat(500));
+        std::fs::write(dir.path().join("src/lib.rs"), &module).unwrap();
+
+        let checker = ConventionChecker::new();
+        let conventions = checker.conventions(dir.path()).await;
+        assert_eq!(conventions.files, 1);
+        assert!(conventions.avoids_unwrap());
+
+        let old = "fn keep_me() {\n    println!(\"legacy\");\n}\n";
+        let path = dir.path().join("src/new.rs");
+        std::fs::write(
+            &path,
+            format!(
+                "{}\nfn loadConfig(path: &str) -> String {{\n    println!(\"loading\");\n    read(path).unwrap()\n}}\n\n#[cfg(test)]\nmod tests {{\n    fn helper() {{ read(\"x\").unwrap(); }}\n}}\n",
+                old
+            ),
+        )
+        .unwrap();
+
+        let feedback = checker
+            .review(dir.path(), &path, Some(old))
+            .await
+            .unwrap();
+        assert!(feedback.starts_with("⚠️ src/new.rs deviates"));
+        assert!(feedback.contains("line 5 [naming]: function `loadConfig` is camelCase"));
+        assert!(feedback.contains("line 6 [logging]: uses println!/eprintln!, the project logs with log_*! macros"));
+        assert!(feedback.contains("line 7 [error-handling]"));
+        // Pre-existing lines and test modules are not flagged
+        assert!(!feedback.contains("line 2 "));
+        assert!(!feedback.contains("line 11 "));
+
+        let clean = dir.path().join("src/clean.rs");
+        std::fs::write(&clean, "fn load_all() -> Result<()> {\n    log_info!(\"all\");\n    Ok(())\n}\n").unwrap();
+        assert!(checker.review(dir.path(), &clean, None).await.is_none());
+    }
+}
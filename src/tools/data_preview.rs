@@ -0,0 +1,652 @@
+//! Data preview tool for CSV, TSV, JSONL and Parquet files
+//!
+//! Reads the schema and first rows of a data file and computes simple column
+//! statistics (null %, min/max) over a bounded scan, so questions about a
+//! dataset can be answered from its actual content without loading it all.
+
+use parquet::file::reader::{FileReader, SerializedFileReader};
+use parquet::record::Field;
+use rig::tool::Tool;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+/// Rows shown when the model does not ask for a number
+const DEFAULT_PREVIEW_ROWS: usize = 10;
+/// Hard cap on preview rows
+const MAX_PREVIEW_ROWS: usize = 100;
+/// Rows scanned for statistics; bigger files are sampled from the start
+const MAX_SCAN_ROWS: usize = 100_000;
+/// Files above this size are only previewed, never fully scanned
+const MAX_SCAN_BYTES: u64 = 256 * 1024 * 1024;
+/// Characters kept per cell in the rendered tables
+const MAX_CELL_CHARS: usize = 40;
+
+#[derive(Error, Debug)]
+pub enum DataPreviewError {
+    #[error("File not found: {0}")]
+    NotFound(String),
+    #[error("Unsupported data format: {0} (expected .csv, .tsv, .jsonl/.ndjson or .parquet)")]
+    UnsupportedFormat(String),
+    #[error("CSV error: {0}")]
+    Csv(#[from] csv::Error),
+    #[error("Parquet error: {0}")]
+    Parquet(#[from] parquet::errors::ParquetError),
+    #[error("Invalid JSON on line {0}: {1}")]
+    Json(usize, String),
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Preview task failed: {0}")]
+    Task(String),
+}
+
+/// Supported file formats
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DataFormat {
+    Csv,
+    Tsv,
+    Jsonl,
+    Parquet,
+}
+
+impl DataFormat {
+    pub fn from_path(path: &Path) -> Result<Self, DataPreviewError> {
+        let ext = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .unwrap_or("")
+            .to_lowercase();
+        match ext.as_str() {
+            "csv" => Ok(Self::Csv),
+            "tsv" | "tab" => Ok(Self::Tsv),
+            "jsonl" | "ndjson" => Ok(Self::Jsonl),
+            "parquet" | "pq" => Ok(Self::Parquet),
+            _ => Err(DataPreviewError::UnsupportedFormat(
+                path.display().to_string(),
+            )),
+        }
+    }
+}
+
+/// Schema and statistics of one column
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ColumnStats {
+    pub name: String,
+    /// Declared type (Parquet) or type inferred from the values
+    pub data_type: String,
+    pub nulls: usize,
+    pub non_null: usize,
+    pub min: Option<String>,
+    pub max: Option<String>,
+}
+
+impl ColumnStats {
+    pub fn null_percent(&self) -> f64 {
+        let total = self.nulls + self.non_null;
+        if total == 0 {
+            0.0
+        } else {
+            self.nulls as f64 * 100.0 / total as f64
+        }
+    }
+}
+
+/// Running statistics for a column while scanning
+#[derive(Debug, Default)]
+struct ColumnAccumulator {
+    name: String,
+    declared_type: Option<String>,
+    nulls: usize,
+    non_null: usize,
+    all_int: bool,
+    all_float: bool,
+    all_bool: bool,
+    num_min: Option<f64>,
+    num_max: Option<f64>,
+    str_min: Option<String>,
+    str_max: Option<String>,
+}
+
+impl ColumnAccumulator {
+    fn new(name: &str, declared_type: Option<String>) -> Self {
+        Self {
+            name: name.to_string(),
+            declared_type,
+            all_int: true,
+            all_float: true,
+            all_bool: true,
+            ..Default::default()
+        }
+    }
+
+    fn observe(&mut self, value: Option<&str>) {
+        let Some(value) = value.filter(|v| !v.is_empty()) else {
+            self.nulls += 1;
+            return;
+        };
+        self.non_null += 1;
+
+        self.all_int &= value.parse::<i64>().is_ok();
+        self.all_bool &= matches!(value.to_lowercase().as_str(), "true" | "false");
+        match value.parse::<f64>() {
+            Ok(n) if n.is_finite() => {
+                self.num_min = Some(self.num_min.map_or(n, |m| m.min(n)));
+                self.num_max = Some(self.num_max.map_or(n, |m| m.max(n)));
+            }
+            _ => self.all_float = false,
+        }
+        if self.str_min.as_deref().is_none_or(|m| value < m) {
+            self.str_min = Some(value.to_string());
+        }
+        if self.str_max.as_deref().is_none_or(|m| value > m) {
+            self.str_max = Some(value.to_string());
+        }
+    }
+
+    fn finish(self) -> ColumnStats {
+        let numeric = self.non_null > 0 && self.all_float;
+        let inferred = if self.non_null == 0 {
+            "null"
+        } else if self.all_int {
+            "int"
+        } else if numeric {
+            "float"
+        } else if self.all_bool {
+            "bool"
+        } else {
+            "string"
+        };
+        let (min, max) = if numeric {
+            (
+                self.num_min.map(format_number),
+                self.num_max.map(format_number),
+            )
+        } else {
+            (self.str_min, self.str_max)
+        };
+        ColumnStats {
+            name: self.name,
+            data_type: self.declared_type.unwrap_or_else(|| inferred.to_string()),
+            nulls: self.nulls,
+            non_null: self.non_null,
+            min,
+            max,
+        }
+    }
+}
+
+fn format_number(n: f64) -> String {
+    if n.fract() == 0.0 && n.abs() < 1e15 {
+        format!("{}", n as i64)
+    } else {
+        format!("{}", n)
+    }
+}
+
+/// Collects columns, preview rows and statistics from any reader
+struct PreviewBuilder {
+    columns: Vec<ColumnAccumulator>,
+    rows: Vec<Vec<String>>,
+    preview_rows: usize,
+    scanned: usize,
+}
+
+impl PreviewBuilder {
+    fn new(preview_rows: usize) -> Self {
+        Self {
+            columns: Vec::new(),
+            rows: Vec::new(),
+            preview_rows,
+            scanned: 0,
+        }
+    }
+
+    fn column_index(&mut self, name: &str) -> usize {
+        match self.columns.iter().position(|c| c.name == name) {
+            Some(index) => index,
+            None => self.add_column(name),
+        }
+    }
+
+    /// Append a column by position; a repeated name gets a `_2`, `_3`... suffix
+    fn add_column(&mut self, name: &str) -> usize {
+        let mut unique = name.to_string();
+        let mut suffix = 2;
+        while self.columns.iter().any(|c| c.name == unique) {
+            unique = format!("{}_{}", name, suffix);
+            suffix += 1;
+        }
+        // Columns discovered late were null in every earlier row
+        let mut column = ColumnAccumulator::new(&unique, None);
+        column.nulls = self.scanned;
+        self.columns.push(column);
+        for row in &mut self.rows {
+            row.push(String::new());
+        }
+        self.columns.len() - 1
+    }
+
+    /// Record one row; `values` are aligned with `self.columns`
+    fn push_row(&mut self, mut values: Vec<Option<String>>) {
+        values.resize(self.columns.len(), None);
+        for (column, value) in self.columns.iter_mut().zip(&values) {
+            column.observe(value.as_deref());
+        }
+        if self.rows.len() < self.preview_rows {
+            self.rows
+                .push(values.into_iter().map(Option::unwrap_or_default).collect());
+        }
+        self.scanned += 1;
+    }
+
+    fn full(&self, scan_stats: bool) -> bool {
+        if scan_stats {
+            self.scanned >= MAX_SCAN_ROWS
+        } else {
+            self.scanned >= self.preview_rows
+        }
+    }
+}
+
+/// Schema, first rows and statistics of a data file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DataPreview {
+    pub path: String,
+    pub format: DataFormat,
+    pub file_size: u64,
+    pub columns: Vec<ColumnStats>,
+    pub rows: Vec<Vec<String>>,
+    /// Total rows when known, otherwise the rows scanned
+    pub row_count: usize,
+    /// False when the file was too big to count or scan completely
+    pub row_count_exact: bool,
+    /// Rows the statistics are based on
+    pub scanned_rows: usize,
+}
+
+impl DataPreview {
+    /// Compact markdown for the model and the TUI: schema with stats, then rows
+    pub fn to_markdown(&self) -> String {
+        let mut out = format!(
+            "**{}** ({:?}, {}, {}{} rows, {} columns)\n\n",
+            self.path,
+            self.format,
+            format_size(self.file_size),
+            if self.row_count_exact { "" } else { "≥" },
+            self.row_count,
+            self.columns.len()
+        );
+        if self.scanned_rows < self.row_count || !self.row_count_exact {
+            out.push_str(&format!(
+                "_Stats based on the first {} rows_\n\n",
+                self.scanned_rows
+            ));
+        }
+
+        out.push_str("| column | type | null % | min | max |\n|---|---|---|---|---|\n");
+        for column in &self.columns {
+            out.push_str(&format!(
+                "| {} | {} | {:.1} | {} | {} |\n",
+                cell(&column.name),
+                column.data_type,
+                column.null_percent(),
+                cell(column.min.as_deref().unwrap_or("")),
+                cell(column.max.as_deref().unwrap_or(""))
+            ));
+        }
+
+        if !self.rows.is_empty() {
+            out.push_str(&format!(
+                "\n| {} |\n|{}|\n",
+                self.columns
+                    .iter()
+                    .map(|c| cell(&c.name))
+                    .collect::<Vec<_>>()
+                    .join(" | "),
+                "---|".repeat(self.columns.len())
+            ));
+            for row in &self.rows {
+                out.push_str(&format!(
+                    "| {} |\n",
+                    row.iter().map(|v| cell(v)).collect::<Vec<_>>().join(" | ")
+                ));
+            }
+        }
+        out
+    }
+}
+
+fn cell(value: &str) -> String {
+    let value = value.replace('|', "\\|").replace('\n', " ");
+    if value.chars().count() > MAX_CELL_CHARS {
+        format!(
+            "{}…",
+            value.chars().take(MAX_CELL_CHARS - 1).collect::<String>()
+        )
+    } else {
+        value
+    }
+}
+
+fn format_size(bytes: u64) -> String {
+    match bytes {
+        b if b >= 1024 * 1024 * 1024 => format!("{:.1} GB", b as f64 / 1073741824.0),
+        b if b >= 1024 * 1024 => format!("{:.1} MB", b as f64 / 1048576.0),
+        b if b >= 1024 => format!("{:.1} KB", b as f64 / 1024.0),
+        b => format!("{} B", b),
+    }
+}
+
+/// Arguments for the data preview tool
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct DataPreviewArgs {
+    /// Path to a .csv, .tsv, .jsonl/.ndjson or .parquet file
+    pub path: String,
+    /// Rows to show (default 10, max 100)
+    #[serde(default = "default_preview_rows")]
+    pub rows: usize,
+}
+
+fn default_preview_rows() -> usize {
+    DEFAULT_PREVIEW_ROWS
+}
+
+/// Data preview tool
+#[derive(Debug, Clone, Default)]
+pub struct DataPreviewTool;
+
+impl DataPreviewTool {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Read the file on a blocking thread (the readers are synchronous)
+    pub async fn preview(&self, args: &DataPreviewArgs) -> Result<DataPreview, DataPreviewError> {
+        let path = PathBuf::from(&args.path);
+        let rows = args.rows.clamp(1, MAX_PREVIEW_ROWS);
+        tokio::task::spawn_blocking(move || preview_file(&path, rows))
+            .await
+            .map_err(|e| DataPreviewError::Task(e.to_string()))?
+    }
+}
+
+fn preview_file(path: &Path, preview_rows: usize) -> Result<DataPreview, DataPreviewError> {
+    if !path.is_file() {
+        return Err(DataPreviewError::NotFound(path.display().to_string()));
+    }
+    let format = DataFormat::from_path(path)?;
+    let file_size = std::fs::metadata(path)?.len();
+    let scan_stats = file_size <= MAX_SCAN_BYTES;
+
+    let mut builder = PreviewBuilder::new(preview_rows);
+    let mut known_row_count = None;
+    let mut exhausted = true;
+
+    match format {
+        DataFormat::Csv | DataFormat::Tsv => {
+            let mut reader = csv::ReaderBuilder::new()
+                .delimiter(if format == DataFormat::Tsv {
+                    b'\t'
+                } else {
+                    b','
+                })
+                .flexible(true)
+                .from_path(path)?;
+            // CSV columns are positional, so duplicate headers stay separate
+            for header in reader.headers()?.clone().iter() {
+                builder.add_column(header);
+            }
+            for record in reader.records() {
+                if builder.full(scan_stats) {
+                    exhausted = false;
+                    break;
+                }
+                let record = record?;
+                builder.push_row(record.iter().map(|v| Some(v.to_string())).collect());
+            }
+        }
+        DataFormat::Jsonl => {
+            use std::io::BufRead;
+            let reader = std::io::BufReader::new(std::fs::File::open(path)?);
+            for (line_no, line) in reader.lines().enumerate() {
+                let line = line?;
+                if line.trim().is_empty() {
+                    continue;
+                }
+                if builder.full(scan_stats) {
+                    exhausted = false;
+                    break;
+                }
+                let value: serde_json::Value = serde_json::from_str(&line)
+                    .map_err(|e| DataPreviewError::Json(line_no + 1, e.to_string()))?;
+                let mut values = Vec::new();
+                if let serde_json::Value::Object(map) = value {
+                    for (key, value) in map {
+                        let index = builder.column_index(&key);
+                        if values.len() <= index {
+                            values.resize(index + 1, None);
+                        }
+                        values[index] = match value {
+                            serde_json::Value::Null => None,
+                            serde_json::Value::String(s) => Some(s),
+                            other => Some(other.to_string()),
+                        };
+                    }
+                }
+                builder.push_row(values);
+            }
+        }
+        DataFormat::Parquet => {
+            let reader = SerializedFileReader::new(std::fs::File::open(path)?)?;
+            let metadata = reader.metadata().file_metadata();
+            known_row_count = Some(metadata.num_rows().max(0) as usize);
+            for field in metadata.schema().get_fields() {
+                let declared = if field.is_primitive() {
+                    let info = field.get_basic_info();
+                    match info.logical_type() {
+                        Some(logical) => format!("{:?}", logical),
+                        None => format!("{}", field.get_physical_type()),
+                    }
+                } else {
+                    "group".to_string()
+                };
+                builder
+                    .columns
+                    .push(ColumnAccumulator::new(field.name(), Some(declared)));
+            }
+            for row in reader.get_row_iter(None)? {
+                if builder.full(scan_stats) {
+                    break;
+                }
+                let row = row?;
+                builder.push_row(
+                    row.get_column_iter()
+                        .map(|(_, field)| match field {
+                            Field::Null => None,
+                            Field::Str(s) => Some(s.clone()),
+                            other => Some(other.to_string()),
+                        })
+                        .collect(),
+                );
+            }
+        }
+    }
+
+    let scanned_rows = builder.scanned;
+    let (row_count, row_count_exact) = match known_row_count {
+        Some(count) => (count, true),
+        None => (scanned_rows, exhausted),
+    };
+    Ok(DataPreview {
+        path: path.display().to_string(),
+        format,
+        file_size,
+        columns: builder
+            .columns
+            .into_iter()
+            .map(ColumnAccumulator::finish)
+            .collect(),
+        rows: builder.rows,
+        row_count,
+        row_count_exact,
+        scanned_rows,
+    })
+}
+
+impl Tool for DataPreviewTool {
+    const NAME: &'static str = "preview_data";
+
+    type Args = DataPreviewArgs;
+    type Output = String;
+    type Error = DataPreviewError;
+
+    async fn definition(&self, _prompt: String) -> rig::completion::ToolDefinition {
+        rig::completion::ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: "Preview a CSV, TSV, JSONL or Parquet file: schema, first rows, row \
+                          count and per-column null %, min and max."
+                .to_string(),
+            parameters: serde_json::to_value(schemars::schema_for!(DataPreviewArgs))
+                .unwrap_or_default(),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        self.preview(&args)
+            .await
+            .map(|preview| preview.to_markdown())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use parquet::data_type::{ByteArray, ByteArrayType, Int32Type};
+    use parquet::file::properties::WriterProperties;
+    use parquet::file::writer::SerializedFileWriter;
+    use parquet::schema::parser::parse_message_type;
+    use std::sync::Arc;
+    use tempfile::tempdir;
+
+    fn args(path: &Path) -> DataPreviewArgs {
+        DataPreviewArgs {
+            path: path.to_string_lossy().to_string(),
+            rows: 2,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_csv_preview_and_stats() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("users.csv");
+        std::fs::write(
+            &path,
+            "id,name,score,active\n1,ana,9.5,true\n2,,7,false\n3,luis,,true\n",
+        )
+        .unwrap();
+
+        let preview = DataPreviewTool::new().preview(&args(&path)).await.unwrap();
+        assert_eq!(preview.row_count, 3);
+        assert!(preview.row_count_exact);
+        assert_eq!(preview.rows.len(), 2);
+
+        let id = &preview.columns[0];
+        assert_eq!(id.data_type, "int");
+        assert_eq!(
+            (id.min.as_deref(), id.max.as_deref()),
+            (Some("1"), Some("3"))
+        );
+        let name = &preview.columns[1];
+        assert_eq!(name.data_type, "string");
+        assert!((name.null_percent() - 33.3).abs() < 0.1);
+        assert_eq!(preview.columns[2].data_type, "float");
+        assert_eq!(preview.columns[2].max.as_deref(), Some("9.5"));
+        assert_eq!(preview.columns[3].data_type, "bool");
+
+        let markdown = preview.to_markdown();
+        assert!(markdown.contains("| id | int | 0.0 | 1 | 3 |"));
+        assert!(markdown.contains("| 1 | ana | 9.5 | true |"));
+    }
+
+    #[tokio::test]
+    async fn test_csv_duplicate_headers() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("dup.csv");
+        std::fs::write(&path, "a,a,b\n1,2,3\n").unwrap();
+
+        let preview = DataPreviewTool::new().preview(&args(&path)).await.unwrap();
+        let names: Vec<&str> = preview.columns.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, vec!["a", "a_2", "b"]);
+        assert_eq!(preview.rows[0], vec!["1", "2", "3"]);
+    }
+
+    #[tokio::test]
+    async fn test_jsonl_columns_discovered_late() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("events.jsonl");
+        std::fs::write(
+            &path,
+            "{\"event\":\"login\"}\n{\"event\":\"buy\",\"amount\":20}\n{\"event\":\"buy\",\"amount\":null}\n",
+        )
+        .unwrap();
+
+        let preview = DataPreviewTool::new().preview(&args(&path)).await.unwrap();
+        assert_eq!(preview.columns.len(), 2);
+        let amount = &preview.columns[1];
+        assert_eq!(amount.name, "amount");
+        assert_eq!((amount.nulls, amount.non_null), (2, 1));
+        assert_eq!(preview.rows[0], vec!["login".to_string(), String::new()]);
+    }
+
+    #[tokio::test]
+    async fn test_parquet_preview() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("items.parquet");
+        let schema = Arc::new(
+            parse_message_type(
+                "message schema { REQUIRED INT32 id; OPTIONAL BYTE_ARRAY name (UTF8); }",
+            )
+            .unwrap(),
+        );
+        let file = std::fs::File::create(&path).unwrap();
+        let mut writer =
+            SerializedFileWriter::new(file, schema, Arc::new(WriterProperties::builder().build()))
+                .unwrap();
+        let mut row_group = writer.next_row_group().unwrap();
+        let mut column = row_group.next_column().unwrap().unwrap();
+        column
+            .typed::<Int32Type>()
+            .write_batch(&[10, 20, 30], None, None)
+            .unwrap();
+        column.close().unwrap();
+        let mut column = row_group.next_column().unwrap().unwrap();
+        column
+            .typed::<ByteArrayType>()
+            .write_batch(
+                &[ByteArray::from("a"), ByteArray::from("b")],
+                Some(&[1, 0, 1]),
+                None,
+            )
+            .unwrap();
+        column.close().unwrap();
+        row_group.close().unwrap();
+        writer.close().unwrap();
+
+        let preview = DataPreviewTool::new().preview(&args(&path)).await.unwrap();
+        assert_eq!(preview.row_count, 3);
+        assert_eq!(preview.columns[0].data_type, "INT32");
+        assert_eq!(preview.columns[0].max.as_deref(), Some("30"));
+        assert_eq!(preview.columns[1].nulls, 1);
+        assert_eq!(preview.rows[0], vec!["10".to_string(), "a".to_string()]);
+    }
+
+    #[test]
+    fn test_unsupported_format() {
+        assert!(matches!(
+            DataFormat::from_path(Path::new("data.xlsx")),
+            Err(DataPreviewError::UnsupportedFormat(_))
+        ));
+    }
+}
@@ -0,0 +1,518 @@
+//! Database query tool with read-only safeguards
+//!
+//! Runs SQL against the SQLite, Postgres or MySQL databases named in the
+//! config (`databases`); raw connection strings are refused. Queries run
+//! inside a read-only session by default; statements that modify data or
+//! schema need Build mode and the user's approval in the confirmation dialog.
+
+use futures::TryStreamExt;
+use rig::tool::Tool;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use sqlx::any::AnyRow;
+use sqlx::AnyConnection;
+use sqlx::{Column, Connection, Executor, Row};
+use std::collections::HashMap;
+use std::time::Duration;
+use thiserror::Error;
+
+/// Default number of rows returned
+const DEFAULT_MAX_ROWS: usize = 100;
+/// Hard cap on rows, whatever the model asks for
+const MAX_ROWS_LIMIT: usize = 1000;
+/// Characters kept per cell in the markdown table
+const MAX_CELL_CHARS: usize = 200;
+/// Time allowed for connecting and running the statement
+const QUERY_TIMEOUT_SECS: u64 = 30;
+
+#[derive(Error, Debug)]
+pub enum DatabaseQueryError {
+    #[error("Unknown database connection: {0}")]
+    UnknownConnection(String),
+    #[error("Unsupported connection string (expected sqlite:, postgres:// or mysql://): {0}")]
+    UnsupportedUrl(String),
+    #[error("Connection strings are not accepted; add the database to `databases` in the config and use its name")]
+    RawConnectionString,
+    #[error("{0} statements modify the database and are only allowed in Build mode")]
+    RequiresBuildMode(&'static str),
+    #[error("{0} statement needs the user's approval before it runs")]
+    RequiresConfirmation(&'static str),
+    #[error("Database error: {0}")]
+    Sql(#[from] sqlx::Error),
+    #[error("Query timed out after {0} seconds")]
+    Timeout(u64),
+    #[error("Query task failed: {0}")]
+    Task(String),
+}
+
+/// What a SQL statement does, from least to most dangerous
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum StatementKind {
+    /// SELECT, SHOW, EXPLAIN...
+    Read,
+    /// INSERT, UPDATE, DELETE... (DML)
+    Write,
+    /// CREATE, ALTER, DROP... (DDL) and anything unrecognized
+    Schema,
+}
+
+impl StatementKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            StatementKind::Read => "Read",
+            StatementKind::Write => "DML",
+            StatementKind::Schema => "DDL",
+        }
+    }
+}
+
+/// Classify SQL by the leading keyword of each statement; the most dangerous one wins
+pub fn classify_statement(sql: &str) -> StatementKind {
+    sql.split(';')
+        .map(strip_leading_comments)
+        .filter(|stmt| !stmt.is_empty())
+        .map(classify_single)
+        .max()
+        .unwrap_or(StatementKind::Read)
+}
+
+fn strip_leading_comments(mut sql: &str) -> &str {
+    loop {
+        sql = sql.trim_start();
+        if let Some(rest) = sql.strip_prefix("--") {
+            sql = rest.split_once('\n').map(|(_, rest)| rest).unwrap_or("");
+        } else if let Some(rest) = sql.strip_prefix("/*") {
+            sql = rest.split_once("*/").map(|(_, rest)| rest).unwrap_or("");
+        } else {
+            return sql;
+        }
+    }
+}
+
+fn classify_single(stmt: &str) -> StatementKind {
+    let keyword = stmt
+        .split(|c: char| !c.is_ascii_alphabetic())
+        .next()
+        .unwrap_or("")
+        .to_ascii_uppercase();
+
+    match keyword.as_str() {
+        "SELECT" | "WITH" | "EXPLAIN" | "SHOW" | "DESCRIBE" | "DESC" | "VALUES" | "TABLE" => {
+            StatementKind::Read
+        }
+        // `PRAGMA x = y` changes settings, `PRAGMA table_info(t)` only reads
+        "PRAGMA" if !stmt.contains('=') => StatementKind::Read,
+        "INSERT" | "UPDATE" | "DELETE" | "REPLACE" | "MERGE" | "UPSERT" | "COPY" | "CALL" => {
+            StatementKind::Write
+        }
+        _ => StatementKind::Schema,
+    }
+}
+
+/// Supported database engines
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Dialect {
+    Sqlite,
+    Postgres,
+    MySql,
+}
+
+impl Dialect {
+    pub fn from_url(url: &str) -> Option<Self> {
+        let scheme = url.split(':').next()?.to_ascii_lowercase();
+        match scheme.as_str() {
+            "sqlite" => Some(Dialect::Sqlite),
+            "postgres" | "postgresql" => Some(Dialect::Postgres),
+            "mysql" | "mariadb" => Some(Dialect::MySql),
+            _ => None,
+        }
+    }
+
+    /// Statement that makes the rest of the session read-only
+    fn read_only_statement(&self) -> &'static str {
+        match self {
+            Dialect::Sqlite => "PRAGMA query_only = ON",
+            Dialect::Postgres => "BEGIN READ ONLY",
+            Dialect::MySql => "START TRANSACTION READ ONLY",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct DatabaseQueryArgs {
+    /// Connection name from the `databases` config
+    pub connection: String,
+    /// SQL statement to run
+    pub query: String,
+    /// Maximum rows to return (default 100, max 1000)
+    #[serde(default = "default_max_rows")]
+    pub max_rows: usize,
+    /// Set by the orchestrator once the user approved a statement that modifies data
+    #[serde(skip)]
+    #[schemars(skip)]
+    pub confirm: bool,
+    /// Whether the agent is in Build mode, set by the agent rather than the model
+    #[serde(skip)]
+    #[schemars(skip)]
+    pub build_mode: bool,
+}
+
+fn default_max_rows() -> usize {
+    DEFAULT_MAX_ROWS
+}
+
+/// Result of a query
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueryOutput {
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+    /// More rows were available than returned
+    pub truncated: bool,
+    /// Rows changed by a write statement
+    pub rows_affected: Option<u64>,
+    /// The statement ran in a read-only session
+    pub read_only: bool,
+}
+
+impl QueryOutput {
+    /// Render as a markdown table
+    pub fn to_markdown(&self) -> String {
+        if let Some(affected) = self.rows_affected {
+            return format!("✅ {} rows affected", affected);
+        }
+        if self.columns.is_empty() {
+            return "(no rows)".to_string();
+        }
+
+        let mut out = format!(
+            "| {} |\n",
+            self.columns
+                .iter()
+                .map(|c| escape_cell(c))
+                .collect::<Vec<_>>()
+                .join(" | ")
+        );
+        out.push_str(&format!("|{}\n", " --- |".repeat(self.columns.len())));
+        for row in &self.rows {
+            out.push_str(&format!(
+                "| {} |\n",
+                row.iter()
+                    .map(|c| escape_cell(c))
+                    .collect::<Vec<_>>()
+                    .join(" | ")
+            ));
+        }
+
+        out.push_str(&format!("\n{} rows", self.rows.len()));
+        if self.truncated {
+            out.push_str(" (truncated, raise max_rows or refine the query)");
+        }
+        out
+    }
+}
+
+fn escape_cell(value: &str) -> String {
+    let mut cell: String = value.chars().take(MAX_CELL_CHARS).collect();
+    if value.chars().count() > MAX_CELL_CHARS {
+        cell.push('…');
+    }
+    cell.replace('|', "\\|").replace('\n', " ")
+}
+
+/// Tool for querying project databases
+#[derive(Debug, Clone, Default)]
+pub struct DatabaseQueryTool {
+    connections: HashMap<String, String>,
+}
+
+impl DatabaseQueryTool {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Named connection strings from the `databases` config. A value in
+    /// uppercase is read from the environment variable of that name.
+    pub fn with_connections(mut self, connections: HashMap<String, String>) -> Self {
+        self.connections = connections;
+        self
+    }
+
+    /// Names of the configured connections, sorted
+    pub fn connection_names(&self) -> Vec<String> {
+        let mut names: Vec<_> = self.connections.keys().cloned().collect();
+        names.sort();
+        names
+    }
+
+    /// Engine behind a configured connection name
+    pub fn dialect(&self, connection: &str) -> Result<Dialect, DatabaseQueryError> {
+        let url = self.resolve_url(connection)?;
+        Dialect::from_url(&url).ok_or_else(|| DatabaseQueryError::UnsupportedUrl(connection.to_string()))
+    }
+
+    fn resolve_url(&self, connection: &str) -> Result<String, DatabaseQueryError> {
+        let url = match self.connections.get(connection) {
+            Some(value) if value.chars().all(|c| c.is_ascii_uppercase() || c == '_') => {
+                std::env::var(value).map_err(|_| {
+                    DatabaseQueryError::UnknownConnection(format!(
+                        "{} (env {} not set)",
+                        connection, value
+                    ))
+                })?
+            }
+            Some(value) => value.clone(),
+            // Only the user's connections: a model-written URL could reach any host
+            None if Dialect::from_url(connection).is_some() => {
+                return Err(DatabaseQueryError::RawConnectionString)
+            }
+            None => {
+                return Err(DatabaseQueryError::UnknownConnection(
+                    connection.to_string(),
+                ))
+            }
+        };
+        Ok(url)
+    }
+
+    async fn run(&self, args: DatabaseQueryArgs) -> Result<QueryOutput, DatabaseQueryError> {
+        let kind = classify_statement(&args.query);
+        if kind != StatementKind::Read {
+            if !args.build_mode {
+                return Err(DatabaseQueryError::RequiresBuildMode(kind.label()));
+            }
+            if !args.confirm {
+                return Err(DatabaseQueryError::RequiresConfirmation(kind.label()));
+            }
+        }
+
+        let url = self.resolve_url(&args.connection)?;
+        let dialect = Dialect::from_url(&url)
+            .ok_or_else(|| DatabaseQueryError::UnsupportedUrl(args.connection.clone()))?;
+
+        sqlx::any::install_default_drivers();
+        let mut conn = AnyConnection::connect(&url).await?;
+
+        if kind != StatementKind::Read {
+            let result = conn.execute(args.query.as_str()).await?;
+            return Ok(QueryOutput {
+                columns: Vec::new(),
+                rows: Vec::new(),
+                truncated: false,
+                rows_affected: Some(result.rows_affected()),
+                read_only: false,
+            });
+        }
+
+        conn.execute(dialect.read_only_statement()).await?;
+        let max_rows = args.max_rows.clamp(1, MAX_ROWS_LIMIT);
+        // Stream the rows so a huge result set stops at the limit instead of being loaded
+        let mut rows = Vec::new();
+        let mut truncated = false;
+        let fetched = {
+            let mut stream = sqlx::query(&args.query).fetch(&mut conn);
+            loop {
+                match stream.try_next().await {
+                    Ok(Some(_)) if rows.len() == max_rows => {
+                        truncated = true;
+                        break Ok(());
+                    }
+                    Ok(Some(row)) => rows.push(row),
+                    Ok(None) => break Ok(()),
+                    Err(e) => break Err(e),
+                }
+            }
+        };
+        if dialect != Dialect::Sqlite {
+            let _ = conn.execute("ROLLBACK").await;
+        }
+        fetched?;
+
+        let columns = rows
+            .first()
+            .map(|row| row.columns().iter().map(|c| c.name().to_string()).collect())
+            .unwrap_or_default();
+
+        Ok(QueryOutput {
+            columns,
+            truncated,
+            rows: rows.iter().map(row_values).collect(),
+            rows_affected: None,
+            read_only: true,
+        })
+    }
+}
+
+fn row_values(row: &AnyRow) -> Vec<String> {
+    (0..row.columns().len())
+        .map(|i| {
+            if let Ok(v) = row.try_get::<Option<String>, _>(i) {
+                v.unwrap_or_else(|| "NULL".to_string())
+            } else if let Ok(v) = row.try_get::<Option<i64>, _>(i) {
+                v.map_or_else(|| "NULL".to_string(), |v| v.to_string())
+            } else if let Ok(v) = row.try_get::<Option<f64>, _>(i) {
+                v.map_or_else(|| "NULL".to_string(), |v| v.to_string())
+            } else if let Ok(v) = row.try_get::<Option<bool>, _>(i) {
+                v.map_or_else(|| "NULL".to_string(), |v| v.to_string())
+            } else if let Ok(v) = row.try_get::<Option<Vec<u8>>, _>(i) {
+                v.map_or_else(|| "NULL".to_string(), |v| format!("<{} bytes>", v.len()))
+            } else {
+                "?".to_string()
+            }
+        })
+        .collect()
+}
+
+impl Tool for DatabaseQueryTool {
+    const NAME: &'static str = "query_database";
+
+    type Args = DatabaseQueryArgs;
+    type Output = QueryOutput;
+    type Error = DatabaseQueryError;
+
+    async fn definition(&self, _prompt: String) -> rig::completion::ToolDefinition {
+        let mut description =
+            "Run a SQL query against a project database and return the rows as a \
+                               markdown table. Queries are read-only; INSERT/UPDATE/DELETE and DDL \
+                               need Build mode and run after the user approves them."
+                .to_string();
+        let names = self.connection_names();
+        if !names.is_empty() {
+            description.push_str(&format!(" Configured connections: {}.", names.join(", ")));
+        }
+
+        rig::completion::ToolDefinition {
+            name: Self::NAME.to_string(),
+            description,
+            parameters: serde_json::to_value(schemars::schema_for!(DatabaseQueryArgs))
+                .unwrap_or_default(),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        // sqlx futures are not `Sync`, which `Tool::call` requires: run on a task
+        let tool = self.clone();
+        tokio::spawn(async move {
+            tokio::time::timeout(Duration::from_secs(QUERY_TIMEOUT_SECS), tool.run(args))
+                .await
+                .map_err(|_| DatabaseQueryError::Timeout(QUERY_TIMEOUT_SECS))?
+        })
+        .await
+        .map_err(|e| DatabaseQueryError::Task(e.to_string()))?
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn args(connection: &str, query: &str) -> DatabaseQueryArgs {
+        DatabaseQueryArgs {
+            connection: connection.to_string(),
+            query: query.to_string(),
+            max_rows: DEFAULT_MAX_ROWS,
+            confirm: false,
+            build_mode: false,
+        }
+    }
+
+    #[test]
+    fn test_classify_statement() {
+        assert_eq!(
+            classify_statement("select * from users"),
+            StatementKind::Read
+        );
+        assert_eq!(
+            classify_statement("-- list\n  WITH t AS (SELECT 1) SELECT * FROM t"),
+            StatementKind::Read
+        );
+        assert_eq!(
+            classify_statement("PRAGMA table_info(users)"),
+            StatementKind::Read
+        );
+        assert_eq!(
+            classify_statement("PRAGMA journal_mode = WAL"),
+            StatementKind::Schema
+        );
+        assert_eq!(
+            classify_statement("/* x */ DELETE FROM users"),
+            StatementKind::Write
+        );
+        assert_eq!(
+            classify_statement("SELECT 1; DROP TABLE users"),
+            StatementKind::Schema
+        );
+        assert_eq!(classify_statement("VACUUM"), StatementKind::Schema);
+    }
+
+    #[test]
+    fn test_markdown_escapes_and_truncates() {
+        let output = QueryOutput {
+            columns: vec!["id".into(), "note".into()],
+            rows: vec![vec!["1".into(), "a|b\nc".into()]],
+            truncated: true,
+            rows_affected: None,
+            read_only: true,
+        };
+        let md = output.to_markdown();
+        assert!(md.starts_with("| id | note |\n| --- | --- |\n"));
+        assert!(md.contains("| 1 | a\\|b c |"));
+        assert!(md.contains("1 rows (truncated"));
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_query_and_write_safeguards() {
+        let dir = tempdir().unwrap();
+        let url = format!("sqlite:{}?mode=rwc", dir.path().join("app.db").display());
+        {
+            sqlx::any::install_default_drivers();
+            let mut conn = AnyConnection::connect(&url).await.unwrap();
+            conn.execute("CREATE TABLE users (id INTEGER, name TEXT)")
+                .await
+                .unwrap();
+            conn.execute("INSERT INTO users VALUES (1, 'ana'), (2, NULL), (3, 'luis')")
+                .await
+                .unwrap();
+        }
+
+        let mut connections = HashMap::new();
+        connections.insert("app".to_string(), url);
+        let tool = DatabaseQueryTool::new().with_connections(connections);
+
+        let mut select = args("app", "SELECT id, name FROM users ORDER BY id");
+        select.max_rows = 2;
+        let output = tool.call(select).await.unwrap();
+        assert_eq!(output.columns, vec!["id", "name"]);
+        assert_eq!(output.rows, vec![vec!["1", "ana"], vec!["2", "NULL"]]);
+        assert!(output.truncated);
+
+        let delete = args("app", "DELETE FROM users");
+        assert!(matches!(
+            tool.call(delete.clone()).await,
+            Err(DatabaseQueryError::RequiresBuildMode(_))
+        ));
+        let in_build = DatabaseQueryArgs {
+            build_mode: true,
+            ..delete
+        };
+        assert!(matches!(
+            tool.call(in_build.clone()).await,
+            Err(DatabaseQueryError::RequiresConfirmation(_))
+        ));
+        let confirmed = DatabaseQueryArgs {
+            confirm: true,
+            ..in_build
+        };
+        assert_eq!(tool.call(confirmed).await.unwrap().rows_affected, Some(3));
+
+        assert!(matches!(
+            tool.call(args("missing", "SELECT 1")).await,
+            Err(DatabaseQueryError::UnknownConnection(_))
+        ));
+        let raw = format!("sqlite:{}", dir.path().join("app.db").display());
+        assert!(matches!(
+            tool.call(args(&raw, "SELECT 1")).await,
+            Err(DatabaseQueryError::RawConnectionString)
+        ));
+    }
+}
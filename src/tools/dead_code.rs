@@ -0,0 +1,337 @@
+//! Dead code detection
+//!
+//! Every source file is parsed once with the symbol index parser. A function,
+//! struct, class, enum, trait or interface is reported when its name never
+//! appears outside its own definitions and recursive calls. Names are compared
+//! across the whole project, not per file or language, so a same-named symbol
+//! elsewhere hides a dead one rather than flagging a live one. Entry points
+//! (`main`, tests, decorated functions, trait implementations, dunder methods)
+//! are never reported, and public symbols only on request since other crates
+//! or packages may use them.
+
+use super::symbol_index::{relative_path, source_files, SymbolIndexError, SymbolIndexTool};
+use crate::ast::{
+    identifier_counts, is_trait_member, qualified_name, AstSymbol, SupportedLanguage, SymbolKind, Visibility,
+};
+use crate::context::call_graph::{short_name, CallGraph};
+use rig::tool::Tool;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+
+/// Default cap on reported symbols
+const DEFAULT_LIMIT: usize = 100;
+/// Names called by the runtime or a framework rather than by the project
+const ENTRY_POINTS: &[&str] = &["main", "init", "constructor", "initialize", "setUp", "tearDown"];
+
+/// A symbol nothing refers to
+#[derive(Debug, Clone, Serialize)]
+pub struct DeadSymbol {
+    pub path: String,
+    /// Qualified name: `Config::load`, `Invoice.total`
+    pub name: String,
+    pub kind: &'static str,
+    pub language: &'static str,
+    pub line: usize,
+}
+
+/// Unreferenced symbols of a project
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct DeadCodeReport {
+    pub files: usize,
+    /// Functions and types considered
+    pub checked: usize,
+    pub dead: Vec<DeadSymbol>,
+    /// Unreferenced public symbols left out of `dead`
+    pub skipped_public: usize,
+    /// More symbols than the limit were found
+    pub truncated: bool,
+}
+
+impl DeadCodeReport {
+    /// Symbols grouped by language, then file, with `line kind name` entries
+    pub fn format(&self) -> String {
+        let mut output = format!(
+            "{} unreferenced symbols out of {} functions and types in {} files{}\n",
+            self.dead.len(),
+            self.checked,
+            self.files,
+            if self.truncated { " (truncated)" } else { "" }
+        );
+        if self.skipped_public > 0 {
+            output.push_str(&format!(
+                "{} unreferenced public symbols not listed (they may be used by other packages)\n",
+                self.skipped_public
+            ));
+        }
+
+        let mut by_language: BTreeMap<&str, Vec<&DeadSymbol>> = BTreeMap::new();
+        for symbol in &self.dead {
+            by_language.entry(symbol.language).or_default().push(symbol);
+        }
+        for (language, symbols) in by_language {
+            output.push_str(&format!("\n## {} ({})\n", language, symbols.len()));
+            let mut current = "";
+            for symbol in symbols {
+                if symbol.path != current {
+                    current = &symbol.path;
+                    output.push_str(&format!("{}\n", current));
+                }
+                output.push_str(&format!("  {:<5} {:<9} {}\n", symbol.line, symbol.kind, symbol.name));
+            }
+        }
+        output
+    }
+}
+
+/// Arguments for find_dead_code
+#[derive(Debug, Clone, Default, Deserialize, Serialize, JsonSchema)]
+pub struct DeadCodeArgs {
+    /// Only report symbols below this path, relative to the project root
+    #[serde(default)]
+    pub path: Option<String>,
+    /// Also report public symbols (default false)
+    #[serde(default)]
+    pub include_public: bool,
+    /// Maximum number of symbols (default 100)
+    #[serde(default)]
+    pub limit: Option<usize>,
+    /// Project directory, filled in by the orchestrator
+    #[serde(skip)]
+    #[schemars(skip)]
+    pub project_dir: String,
+}
+
+/// Lists the functions and types of a project nothing refers to
+#[derive(Clone, Default)]
+pub struct DeadCodeTool {
+    index: SymbolIndexTool,
+}
+
+impl DeadCodeTool {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Share the parser cache of a symbol index
+    pub fn with_index(mut self, index: SymbolIndexTool) -> Self {
+        self.index = index;
+        self
+    }
+
+    /// Find the unreferenced symbols below `scope` (the whole project when
+    /// `None`); usages are searched in the whole project
+    pub async fn find_dead_code(
+        &self,
+        root: &Path,
+        scope: Option<&str>,
+        include_public: bool,
+        limit: usize,
+    ) -> Result<DeadCodeReport, SymbolIndexError> {
+        let root = root.canonicalize()?;
+        let scope = scope.map(|s| root.join(s));
+        let mut files = source_files(&root);
+        files.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+        let mut guard = self.index.parser().await?;
+        let Some(parser) = guard.as_mut() else {
+            return Err(SymbolIndexError::Parser(
+                "AST parser unavailable".to_string(),
+            ));
+        };
+
+        let mut report = DeadCodeReport::default();
+        let mut graph = CallGraph::default();
+        let mut occurrences: HashMap<String, usize> = HashMap::new();
+        let mut definitions: HashMap<String, usize> = HashMap::new();
+        let mut candidates = Vec::new();
+
+        for (path, language) in files {
+            let Ok(source) = std::fs::read_to_string(&path) else {
+                continue;
+            };
+            let Ok(parse) = parser.parse_file(&path, language, &source) else {
+                continue;
+            };
+            let relative = relative_path(&root, &path);
+            let symbols = parser.extract_symbols(&parse.tree, language, &source);
+            for (name, count) in identifier_counts(&parse.tree, &source) {
+                *occurrences.entry(name).or_default() += count;
+            }
+            for symbol in &symbols {
+                *definitions.entry(symbol.name.clone()).or_default() += 1;
+            }
+
+            let in_scope = scope.as_ref().is_none_or(|s| path.starts_with(s));
+            if in_scope {
+                report.files += 1;
+                for symbol in &symbols {
+                    let checked = matches!(
+                        symbol.kind,
+                        SymbolKind::Function
+                            | SymbolKind::Method
+                            | SymbolKind::Struct
+                            | SymbolKind::Class
+                            | SymbolKind::Enum
+                            | SymbolKind::Trait
+                            | SymbolKind::Interface
+                    );
+                    if !checked || is_entry_point(&symbol.name) || symbol.is_test || !symbol.decorators.is_empty() {
+                        continue;
+                    }
+                    if is_trait_member(&parse.tree, symbol) {
+                        continue;
+                    }
+                    candidates.push((
+                        relative.clone(),
+                        language,
+                        symbol.clone(),
+                        qualified_name(&parse.tree, language, &source, symbol),
+                    ));
+                }
+            }
+            let edges = parser.extract_call_graph(&parse.tree, language, &source);
+            graph.add_file(&relative, edges, &symbols);
+        }
+        drop(guard);
+
+        report.checked = candidates.len();
+        for (path, language, symbol, name) in candidates {
+            // Calls a function makes to itself do not keep it alive
+            let recursive = graph
+                .callers_of(&symbol.name)
+                .iter()
+                .filter(|site| site.caller.as_deref().is_some_and(|c| short_name(c) == symbol.name))
+                .count();
+            let used = occurrences.get(&symbol.name).copied().unwrap_or(0);
+            let defined = definitions.get(&symbol.name).copied().unwrap_or(0);
+            if used > defined + recursive {
+                continue;
+            }
+            if is_exported(language, &symbol) && !include_public {
+                report.skipped_public += 1;
+                continue;
+            }
+            if report.dead.len() >= limit {
+                report.truncated = true;
+                continue;
+            }
+            report.dead.push(DeadSymbol {
+                path,
+                name,
+                kind: symbol.kind.as_str(),
+                language: language.as_str(),
+                line: symbol.range.start_line,
+            });
+        }
+        Ok(report)
+    }
+}
+
+/// Public API of its module; Python has no visibility, a leading `_` marks
+/// private names by convention
+fn is_exported(language: SupportedLanguage, symbol: &AstSymbol) -> bool {
+    match language {
+        SupportedLanguage::Python => !symbol.name.starts_with('_'),
+        _ => symbol.visibility == Visibility::Public,
+    }
+}
+
+/// `main`, framework hooks and dunder methods (`__init__`, `__str__`)
+fn is_entry_point(name: &str) -> bool {
+    ENTRY_POINTS.contains(&name) || (name.len() > 4 && name.starts_with("__") && name.ends_with("__"))
+}
+
+impl Tool for DeadCodeTool {
+    const NAME: &'static str = "find_dead_code";
+
+    type Args = DeadCodeArgs;
+    type Output = String;
+    type Error = SymbolIndexError;
+
+    async fn definition(&self, _prompt: String) -> rig::completion::ToolDefinition {
+        rig::completion::ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: "Find functions, methods and types that are never referenced in the \
+                          project, grouped by language and file. Entry points, tests and trait \
+                          implementations are skipped; public symbols only with include_public. \
+                          Matching is by name, so confirm with find_references before deleting."
+                .to_string(),
+            parameters: serde_json::to_value(schemars::schema_for!(DeadCodeArgs))
+                .unwrap_or_default(),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        // sqlx futures are not `Sync`, which `Tool::call` requires: run on a task
+        let tool = self.clone();
+        tokio::spawn(async move {
+            let root = PathBuf::from(if args.project_dir.is_empty() {
+                "."
+            } else {
+                &args.project_dir
+            });
+            let report = tool
+                .find_dead_code(
+                    &root,
+                    args.path.as_deref(),
+                    args.include_public,
+                    args.limit.unwrap_or(DEFAULT_LIMIT),
+                )
+                .await?;
+            Ok(report.format())
+        })
+        .await
+        .map_err(|e| SymbolIndexError::Task(e.to_string()))?
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[tokio::test]
+    async fn test_find_dead_code() {
+        let dir = tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("src")).unwrap();
+        std::fs::write(
+            dir.path().join("src/main.rs"),
+            "struct Config;\nstruct Unused;\n\nimpl Config {\n    fn load() -> Config {\n        helper(3);\n        Config\n    }\n}\n\n\
+             impl std::fmt::Display for Unused {\n    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result { Ok(()) }\n}\n\n\
+             fn helper(n: u32) -> u32 {\n    if n == 0 { 0 } else { helper(n - 1) }\n}\n\n\
+             // orphan() is mentioned in a comment only\nfn orphan() {}\n\npub fn exported() {}\n\n\
+             fn main() {\n    Config::load();\n}\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("src/util.py"),
+            "class Cache:\n    def __init__(self):\n        self.items = {}\n\n    def _evict(self):\n        pass\n\ndef _stale():\n    return Cache()\n",
+        )
+        .unwrap();
+
+        let tool = DeadCodeTool::new();
+        let report = tool.find_dead_code(dir.path(), None, false, 100).await.unwrap();
+        let dead: Vec<_> = report
+            .dead
+            .iter()
+            .map(|s| (s.language, s.name.as_str(), s.line))
+            .collect();
+        assert_eq!(
+            dead,
+            vec![
+                ("rust", "Unused", 2),
+                ("rust", "orphan", 20),
+                ("python", "Cache._evict", 5),
+                ("python", "_stale", 8),
+            ]
+        );
+        assert_eq!(report.skipped_public, 1);
+        assert!(report.format().contains("## python (2)\nsrc/util.py\n  5     method    Cache._evict\n"));
+
+        let report = tool.find_dead_code(dir.path(), Some("src/util.py"), true, 1).await.unwrap();
+        assert_eq!(report.files, 1);
+        assert!(report.truncated);
+    }
+}
@@ -0,0 +1,658 @@
+//! Dependency documentation retrieval
+//!
+//! Fetches the documentation of the project's most used direct dependencies
+//! (rustdoc JSON from docs.rs with a crates.io fallback, npm READMEs, PyPI
+//! descriptions) and stores it in the RAPTOR docs namespace as `dep:<name>`.
+//! Queries naming a dependency then get the matching excerpts with their source
+//! URL, so answers about third-party APIs cite real items instead of guessing.
+
+use super::dependencies::{
+    AnalyzeDepsArgs, Dependency, DependencyAnalyzerTool, DependencySource, DepsError, ProjectType,
+};
+use super::symbol_index::source_files;
+use crate::raptor::persistence::GLOBAL_STORE;
+use crate::security::network::{self, NetworkError, SendChecked};
+use serde_json::Value;
+use std::collections::HashSet;
+use std::io::Read;
+use std::path::Path;
+use std::time::Duration;
+use thiserror::Error;
+
+/// Docs namespace prefix of dependency documentation
+pub const DEPENDENCY_DOCS_PREFIX: &str = "dep:";
+/// Dependencies fetched when no count is given
+pub const DEFAULT_TOP_DEPENDENCIES: usize = 10;
+/// Characters of documentation stored per dependency
+const MAX_DOC_CHARS: usize = 40_000;
+/// API items taken from a rustdoc JSON
+const MAX_RUST_ITEMS: usize = 400;
+/// Characters of an item's docs (its first paragraph)
+const MAX_ITEM_DOC_CHARS: usize = 400;
+/// Largest download accepted (rustdoc JSON of big crates is tens of MB)
+const MAX_DOWNLOAD_BYTES: usize = 64 * 1024 * 1024;
+/// Characters of dependency docs added to a prompt
+const MAX_CONTEXT_CHARS: usize = 4000;
+/// Excerpts added per mentioned dependency
+const MAX_CONTEXT_CHUNKS: usize = 2;
+/// Rustdoc item kinds listed with their path
+const RUST_ITEM_KINDS: &[&str] = &[
+    "struct", "enum", "trait", "function", "macro", "type_alias", "constant", "union",
+];
+
+#[derive(Error, Debug)]
+pub enum DependencyDocsError {
+    #[error("Dependency analysis failed: {0}")]
+    Deps(#[from] DepsError),
+    #[error("Unsupported project type: dependency docs need Cargo, npm or PyPI")]
+    UnsupportedProject,
+    #[error("HTTP error: {0}")]
+    Http(#[from] reqwest::Error),
+    #[error("{0}")]
+    Network(#[from] NetworkError),
+    #[error("No documentation found for {0}")]
+    NotFound(String),
+    #[error("Invalid documentation for {0}: {1}")]
+    Invalid(String, String),
+}
+
+/// Package registry a dependency comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Ecosystem {
+    Crates,
+    Npm,
+    PyPi,
+}
+
+impl Ecosystem {
+    fn of(project: &ProjectType) -> Option<Self> {
+        match project {
+            ProjectType::Rust => Some(Self::Crates),
+            ProjectType::Node => Some(Self::Npm),
+            ProjectType::Python => Some(Self::PyPi),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Crates => "Rust",
+            Self::Npm => "npm",
+            Self::PyPi => "PyPI",
+        }
+    }
+
+    /// How the dependency is written in source code
+    fn identifier(&self, name: &str) -> String {
+        match self {
+            Self::Crates | Self::PyPi => name.replace('-', "_").to_lowercase(),
+            Self::Npm => name.to_string(),
+        }
+    }
+}
+
+/// Documentation of one dependency
+#[derive(Debug, Clone)]
+pub struct DependencyDocs {
+    pub name: String,
+    pub version: String,
+    pub ecosystem: Ecosystem,
+    pub source_url: String,
+    /// Markdown: `# name version`, `Source: url`, then the docs
+    pub text: String,
+}
+
+impl DependencyDocs {
+    fn new(name: &str, version: &str, ecosystem: Ecosystem, source_url: String, body: &str) -> Self {
+        let mut text = format!(
+            "# {} {} ({})\nSource: {}\n\n{}",
+            name,
+            version,
+            ecosystem.as_str(),
+            source_url,
+            body.trim()
+        );
+        if let Some((cut, _)) = text.char_indices().nth(MAX_DOC_CHARS) {
+            text.truncate(cut);
+        }
+        Self {
+            name: name.to_string(),
+            version: version.to_string(),
+            ecosystem,
+            source_url,
+            text,
+        }
+    }
+
+    /// Store in the docs namespace; returns the chunks stored
+    pub fn store(&self) -> usize {
+        GLOBAL_STORE
+            .lock()
+            .map(|mut store| {
+                store.insert_docs(&format!("{}{}", DEPENDENCY_DOCS_PREFIX, self.name), &self.text)
+            })
+            .unwrap_or(0)
+    }
+}
+
+/// Outcome of fetching the docs of several dependencies
+#[derive(Debug, Default)]
+pub struct DocsFetchReport {
+    /// `(name, version, chunks stored)`
+    pub stored: Vec<(String, String, usize)>,
+    /// `(name, error)`
+    pub failed: Vec<(String, String)>,
+}
+
+impl DocsFetchReport {
+    pub fn format(&self) -> String {
+        let mut output = format!(
+            "📚 Documentación de dependencias: {} indexadas, {} fallidas\n",
+            self.stored.len(),
+            self.failed.len()
+        );
+        for (name, version, chunks) in &self.stored {
+            output.push_str(&format!("  ✓ {} {} ({} fragmentos)\n", name, version, chunks));
+        }
+        for (name, error) in &self.failed {
+            output.push_str(&format!("  ✗ {}: {}\n", name, error));
+        }
+        output
+    }
+}
+
+/// Fetches and stores the documentation of project dependencies
+#[derive(Debug, Clone)]
+pub struct DependencyDocsTool {
+    client: reqwest::Client,
+}
+
+impl Default for DependencyDocsTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DependencyDocsTool {
+    pub fn new() -> Self {
+        let client = network::client_builder()
+            .timeout(Duration::from_secs(60))
+            .build()
+            .unwrap_or_default();
+        Self { client }
+    }
+
+    /// Fetch and store the docs of the `limit` most used direct dependencies
+    pub async fn fetch_top(
+        &self,
+        root: &Path,
+        limit: usize,
+    ) -> Result<DocsFetchReport, DependencyDocsError> {
+        let analysis = DependencyAnalyzerTool
+            .analyze(AnalyzeDepsArgs {
+                path: root.to_string_lossy().to_string(),
+                check_outdated: Some(false),
+                check_security: Some(false),
+            })
+            .await?;
+        let ecosystem =
+            Ecosystem::of(&analysis.project_type).ok_or(DependencyDocsError::UnsupportedProject)?;
+        let locked = locked_versions(root);
+
+        let mut report = DocsFetchReport::default();
+        for dependency in top_dependencies(root, ecosystem, &analysis.dependencies, limit) {
+            let version = locked
+                .iter()
+                .find(|(name, _)| *name == dependency.name)
+                .map(|(_, version)| version.clone());
+            match self.fetch(ecosystem, &dependency.name, version.as_deref()).await {
+                Ok(docs) => {
+                    let chunks = docs.store();
+                    report.stored.push((docs.name, docs.version, chunks));
+                }
+                Err(e) => report.failed.push((dependency.name.clone(), e.to_string())),
+            }
+        }
+        Ok(report)
+    }
+
+    /// Documentation of one dependency; `version` defaults to the latest
+    pub async fn fetch(
+        &self,
+        ecosystem: Ecosystem,
+        name: &str,
+        version: Option<&str>,
+    ) -> Result<DependencyDocs, DependencyDocsError> {
+        match ecosystem {
+            Ecosystem::Crates => {
+                let version = version.unwrap_or("latest");
+                match self.fetch_rustdoc(name, version).await {
+                    Ok(docs) => Ok(docs),
+                    // Only crates built by docs.rs since rustdoc JSON was enabled have it
+                    Err(_) => self.fetch_crates_io(name, version).await,
+                }
+            }
+            Ecosystem::Npm => {
+                let json = self
+                    .get_json(&format!("https://registry.npmjs.org/{}", name), name)
+                    .await?;
+                npm_docs(name, &json)
+            }
+            Ecosystem::PyPi => {
+                let json = self
+                    .get_json(&format!("https://pypi.org/pypi/{}/json", name), name)
+                    .await?;
+                pypi_docs(name, &json)
+            }
+        }
+    }
+
+    async fn fetch_rustdoc(
+        &self,
+        name: &str,
+        version: &str,
+    ) -> Result<DependencyDocs, DependencyDocsError> {
+        let url = format!("https://docs.rs/crate/{}/{}/json.gz", name, version);
+        let bytes = self.get_bytes(&url, name).await?;
+        let json: Value = tokio::task::spawn_blocking(move || {
+            let mut text = String::new();
+            flate2::read::GzDecoder::new(&bytes[..])
+                .take(MAX_DOWNLOAD_BYTES as u64 * 8)
+                .read_to_string(&mut text)
+                .map_err(|e| e.to_string())?;
+            serde_json::from_str(&text).map_err(|e| e.to_string())
+        })
+        .await
+        .map_err(|e| DependencyDocsError::Invalid(name.to_string(), e.to_string()))?
+        .map_err(|e| DependencyDocsError::Invalid(name.to_string(), e))?;
+        Ok(rustdoc_docs(name, version, &json))
+    }
+
+    async fn fetch_crates_io(
+        &self,
+        name: &str,
+        version: &str,
+    ) -> Result<DependencyDocs, DependencyDocsError> {
+        let json = self
+            .get_json(&format!("https://crates.io/api/v1/crates/{}", name), name)
+            .await?;
+        let krate = &json["crate"];
+        let version = if version == "latest" {
+            krate["max_stable_version"].as_str().unwrap_or(version)
+        } else {
+            version
+        };
+        let mut body = krate["description"].as_str().unwrap_or_default().to_string();
+        for (label, key) in [("Documentation", "documentation"), ("Repository", "repository")] {
+            if let Some(url) = krate[key].as_str() {
+                body.push_str(&format!("\n\n{}: {}", label, url));
+            }
+        }
+        Ok(DependencyDocs::new(
+            name,
+            version,
+            Ecosystem::Crates,
+            format!("https://docs.rs/{}/{}", name, version),
+            &body,
+        ))
+    }
+
+    async fn get_bytes(&self, url: &str, name: &str) -> Result<Vec<u8>, DependencyDocsError> {
+        let response = self.client.get(url).send_checked("dependency docs").await?;
+        if !response.status().is_success() {
+            return Err(DependencyDocsError::NotFound(name.to_string()));
+        }
+        if response
+            .content_length()
+            .is_some_and(|len| len as usize > MAX_DOWNLOAD_BYTES)
+        {
+            return Err(DependencyDocsError::Invalid(
+                name.to_string(),
+                "documentation too large".to_string(),
+            ));
+        }
+        Ok(response.bytes().await?.to_vec())
+    }
+
+    async fn get_json(&self, url: &str, name: &str) -> Result<Value, DependencyDocsError> {
+        let bytes = self.get_bytes(url, name).await?;
+        serde_json::from_slice(&bytes)
+            .map_err(|e| DependencyDocsError::Invalid(name.to_string(), e.to_string()))
+    }
+}
+
+/// Registry dependencies ordered by the number of source files using them
+fn top_dependencies<'a>(
+    root: &Path,
+    ecosystem: Ecosystem,
+    dependencies: &'a [Dependency],
+    limit: usize,
+) -> Vec<&'a Dependency> {
+    let mut candidates: Vec<(usize, &Dependency)> = dependencies
+        .iter()
+        .filter(|d| !d.is_dev && matches!(d.source, DependencySource::Registry(_)))
+        .map(|d| (0, d))
+        .collect();
+
+    for (path, _) in source_files(root) {
+        let Ok(source) = std::fs::read_to_string(&path) else {
+            continue;
+        };
+        for (uses, dependency) in candidates.iter_mut() {
+            if source.contains(&ecosystem.identifier(&dependency.name)) {
+                *uses += 1;
+            }
+        }
+    }
+
+    // Stable sort keeps the manifest order between equally used dependencies
+    candidates.sort_by_key(|(uses, _)| std::cmp::Reverse(*uses));
+    candidates.into_iter().take(limit).map(|(_, d)| d).collect()
+}
+
+/// Exact versions from Cargo.lock, when present
+fn locked_versions(root: &Path) -> Vec<(String, String)> {
+    let Ok(lock) = std::fs::read_to_string(root.join("Cargo.lock")) else {
+        return Vec::new();
+    };
+    let Ok(lock) = lock.parse::<toml::Table>() else {
+        return Vec::new();
+    };
+    lock.get("package")
+        .and_then(|p| p.as_array())
+        .map(|packages| {
+            packages
+                .iter()
+                .filter_map(|p| {
+                    Some((
+                        p.get("name")?.as_str()?.to_string(),
+                        p.get("version")?.as_str()?.to_string(),
+                    ))
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// First paragraph of a doc comment, shortened
+fn first_paragraph(docs: &str) -> String {
+    let paragraph = docs.trim().split("\n\n").next().unwrap_or_default().trim();
+    match paragraph.char_indices().nth(MAX_ITEM_DOC_CHARS) {
+        Some((cut, _)) => format!("{}…", &paragraph[..cut]),
+        None => paragraph.to_string(),
+    }
+}
+
+/// `fn name(a, b)` from a rustdoc function item (`sig` or the older `decl`)
+fn rust_signature(name: &str, function: &Value) -> String {
+    let signature = if function["sig"].is_object() {
+        &function["sig"]
+    } else {
+        &function["decl"]
+    };
+    let inputs: Vec<&str> = signature["inputs"]
+        .as_array()
+        .map(|inputs| inputs.iter().filter_map(|i| i[0].as_str()).collect())
+        .unwrap_or_default();
+    format!("fn {}({})", name, inputs.join(", "))
+}
+
+/// Public API of a crate from its rustdoc JSON: items with their path and
+/// the methods of inherent impls
+pub fn rustdoc_docs(name: &str, version: &str, json: &Value) -> DependencyDocs {
+    let index = json["index"].as_object();
+    let item = |id: &str| index.and_then(|index| index.get(id));
+    let mut entries: Vec<(String, String, String)> = Vec::new();
+
+    if let Some(paths) = json["paths"].as_object() {
+        for (id, summary) in paths {
+            let kind = summary["kind"].as_str().unwrap_or_default();
+            if summary["crate_id"].as_u64() != Some(0) || !RUST_ITEM_KINDS.contains(&kind) {
+                continue;
+            }
+            let Some(item) = item(id) else {
+                continue;
+            };
+            let path: Vec<&str> = summary["path"]
+                .as_array()
+                .map(|p| p.iter().filter_map(Value::as_str).collect())
+                .unwrap_or_default();
+            let mut body = String::new();
+            if kind == "function" {
+                body.push_str(&format!(
+                    "`{}`\n",
+                    rust_signature(path.last().unwrap_or(&""), &item["inner"]["function"])
+                ));
+            }
+            body.push_str(&first_paragraph(item["docs"].as_str().unwrap_or_default()));
+            entries.push((path.join("::"), kind.to_string(), body));
+        }
+    }
+
+    // Methods live in impl blocks, which have no path of their own
+    for implementation in index.into_iter().flat_map(|index| index.values()) {
+        let block = &implementation["inner"]["impl"];
+        if implementation["crate_id"].as_u64() != Some(0) || !block.is_object() || !block["trait"].is_null() {
+            continue;
+        }
+        let owner = &block["for"]["resolved_path"];
+        let Some(owner) = owner["path"].as_str().or_else(|| owner["name"].as_str()) else {
+            continue;
+        };
+        for id in block["items"].as_array().into_iter().flatten() {
+            let id = id.as_str().map(str::to_string).unwrap_or_else(|| id.to_string());
+            let Some(method) = item(&id) else {
+                continue;
+            };
+            let (Some(method_name), true) = (
+                method["name"].as_str(),
+                method["visibility"].as_str() == Some("public") && method["inner"]["function"].is_object(),
+            ) else {
+                continue;
+            };
+            entries.push((
+                format!("{}::{}", owner, method_name),
+                "method".to_string(),
+                format!(
+                    "`{}`\n{}",
+                    rust_signature(method_name, &method["inner"]["function"]),
+                    first_paragraph(method["docs"].as_str().unwrap_or_default())
+                ),
+            ));
+        }
+    }
+
+    entries.sort_by(|a, b| a.0.cmp(&b.0));
+    entries.dedup_by(|a, b| a.0 == b.0);
+    entries.truncate(MAX_RUST_ITEMS);
+
+    let root_docs = json["root"]
+        .as_str()
+        .map(str::to_string)
+        .or_else(|| json["root"].as_u64().map(|id| id.to_string()))
+        .and_then(|root| item(&root)?["docs"].as_str())
+        .map(first_paragraph)
+        .unwrap_or_default();
+    let version = json["crate_version"].as_str().unwrap_or(version);
+
+    let mut body = format!("{}\n", root_docs);
+    for (path, kind, docs) in &entries {
+        body.push_str(&format!("\n### {} ({})\n{}\n", path, kind, docs.trim()));
+    }
+    DependencyDocs::new(
+        name,
+        version,
+        Ecosystem::Crates,
+        format!("https://docs.rs/{}/{}", name, version),
+        &body,
+    )
+}
+
+/// Description and README of an npm package document
+pub fn npm_docs(name: &str, json: &Value) -> Result<DependencyDocs, DependencyDocsError> {
+    let version = json["dist-tags"]["latest"].as_str().unwrap_or("latest");
+    let description = json["description"].as_str().unwrap_or_default();
+    let readme = json["readme"].as_str().unwrap_or_default();
+    if description.is_empty() && readme.is_empty() {
+        return Err(DependencyDocsError::NotFound(name.to_string()));
+    }
+    Ok(DependencyDocs::new(
+        name,
+        version,
+        Ecosystem::Npm,
+        format!("https://www.npmjs.com/package/{}/v/{}", name, version),
+        &format!("{}\n\n{}", description, readme),
+    ))
+}
+
+/// Summary and description of a PyPI project document
+pub fn pypi_docs(name: &str, json: &Value) -> Result<DependencyDocs, DependencyDocsError> {
+    let info = &json["info"];
+    let version = info["version"].as_str().unwrap_or("latest");
+    let summary = info["summary"].as_str().unwrap_or_default();
+    let description = info["description"].as_str().unwrap_or_default();
+    if summary.is_empty() && description.is_empty() {
+        return Err(DependencyDocsError::NotFound(name.to_string()));
+    }
+    Ok(DependencyDocs::new(
+        name,
+        version,
+        Ecosystem::PyPi,
+        format!("https://pypi.org/project/{}/{}/", name, version),
+        &format!("{}\n\n{}", summary, description),
+    ))
+}
+
+/// Stored docs of the dependencies a query mentions, most relevant excerpts
+/// first, each with its source URL
+pub fn dependency_docs_context_for(query: &str) -> Option<String> {
+    let store = GLOBAL_STORE.lock().ok()?;
+    let query = query.to_lowercase();
+    let words: HashSet<&str> = query
+        .split(|c: char| !c.is_alphanumeric() && c != '_' && c != '-')
+        .filter(|w| w.len() >= 3)
+        .collect();
+
+    let mut sections = Vec::new();
+    let mut used = 0;
+    for namespace in store.docs_names() {
+        let Some(dependency) = namespace.strip_prefix(DEPENDENCY_DOCS_PREFIX) else {
+            continue;
+        };
+        let dependency = dependency.to_lowercase();
+        if !words.contains(dependency.as_str()) && !words.contains(dependency.replace('-', "_").as_str()) {
+            continue;
+        }
+        let chunks = store.docs_chunks(&namespace);
+        let Some(header) = chunks.first().and_then(|c| c.lines().next()) else {
+            continue;
+        };
+        let source = chunks
+            .first()
+            .and_then(|c| c.lines().find_map(|l| l.strip_prefix("Source: ")))
+            .unwrap_or_default();
+
+        // Chunks with the most query words; the header chunk wins ties
+        let mut ranked: Vec<(usize, usize)> = chunks
+            .iter()
+            .enumerate()
+            .map(|(i, chunk)| {
+                let chunk = chunk.to_lowercase();
+                let hits = words
+                    .iter()
+                    .filter(|w| **w != dependency && chunk.contains(**w))
+                    .count();
+                (hits, i)
+            })
+            .collect();
+        ranked.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+
+        for (_, i) in ranked.into_iter().take(MAX_CONTEXT_CHUNKS) {
+            let excerpt = chunks[i].trim();
+            if used + excerpt.len() > MAX_CONTEXT_CHARS {
+                break;
+            }
+            used += excerpt.len();
+            let title = if i == 0 { String::new() } else { format!("{} (cont.)\n", header) };
+            sections.push(format!("{}{}\n[Fuente: {}]", title, excerpt, source));
+        }
+    }
+
+    (!sections.is_empty()).then(|| sections.join("\n\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_rustdoc_docs() {
+        let json = json!({
+            "root": 0,
+            "crate_version": "1.2.3",
+            "index": {
+                "0": {"crate_id": 0, "name": "widgets", "docs": "Widgets for everyone.\n\nMore.", "inner": {"module": {}}},
+                "1": {"crate_id": 0, "name": "Widget", "docs": "A widget.", "inner": {"struct": {}}},
+                "2": {"crate_id": 0, "name": "build", "docs": "Builds a widget.",
+                      "inner": {"function": {"sig": {"inputs": [["name", {}], ["size", {}]]}}}},
+                "3": {"crate_id": 0, "inner": {"impl": {"for": {"resolved_path": {"path": "Widget", "id": 1}},
+                      "items": [4, 5], "trait": null}}},
+                "4": {"crate_id": 0, "name": "render", "visibility": "public", "docs": "Renders it.",
+                      "inner": {"function": {"sig": {"inputs": [["self", {}]]}}}},
+                "5": {"crate_id": 0, "name": "secret", "visibility": "default", "inner": {"function": {}}},
+                "9": {"crate_id": 1, "name": "Vec", "docs": "std", "inner": {"struct": {}}}
+            },
+            "paths": {
+                "0": {"crate_id": 0, "path": ["widgets"], "kind": "module"},
+                "1": {"crate_id": 0, "path": ["widgets", "Widget"], "kind": "struct"},
+                "2": {"crate_id": 0, "path": ["widgets", "build"], "kind": "function"},
+                "9": {"crate_id": 1, "path": ["alloc", "vec", "Vec"], "kind": "struct"}
+            }
+        });
+        let docs = rustdoc_docs("widgets", "latest", &json);
+        assert_eq!(docs.version, "1.2.3");
+        assert!(docs.text.starts_with("# widgets 1.2.3 (Rust)\nSource: https://docs.rs/widgets/1.2.3\n\nWidgets for everyone.\n"));
+        assert!(docs.text.contains("### Widget::render (method)\n`fn render(self)`\nRenders it."));
+        assert!(docs.text.contains("### widgets::build (function)\n`fn build(name, size)`\nBuilds a widget."));
+        assert!(docs.text.contains("### widgets::Widget (struct)\nA widget."));
+        assert!(!docs.text.contains("secret") && !docs.text.contains("alloc::vec"));
+    }
+
+    #[test]
+    fn test_registry_docs() {
+        let npm = npm_docs(
+            "left-pad",
+            &json!({"dist-tags": {"latest": "1.3.0"}, "description": "Pad strings", "readme": "## Usage"}),
+        )
+        .unwrap();
+        assert_eq!(npm.source_url, "https://www.npmjs.com/package/left-pad/v/1.3.0");
+        assert!(npm.text.ends_with("Pad strings\n\n## Usage"));
+
+        let pypi = pypi_docs("requests", &json!({"info": {"version": "2.32.0", "summary": "HTTP", "description": ""}})).unwrap();
+        assert_eq!(pypi.version, "2.32.0");
+        assert!(pypi_docs("empty", &json!({"info": {}})).is_err());
+    }
+
+    #[test]
+    fn test_dependency_docs_context() {
+        let mut body = String::from("Sprockets for gears.\n");
+        for i in 0..40 {
+            body.push_str(&format!("\n### sprocketz::Item{} (struct)\nFiller item number {}.\n", i, i));
+        }
+        body.push_str("\n### sprocketz::Chain::tighten (method)\n`fn tighten(self, turns)`\nTightens the chain.\n");
+        let docs = DependencyDocs::new(
+            "sprocketz",
+            "0.4.0",
+            Ecosystem::Crates,
+            "https://docs.rs/sprocketz/0.4.0".to_string(),
+            &body,
+        );
+        assert!(docs.store() > 1);
+
+        let context = dependency_docs_context_for("how do I tighten a chain with sprocketz?").unwrap();
+        assert!(context.contains("fn tighten(self, turns)"));
+        assert!(context.contains("[Fuente: https://docs.rs/sprocketz/0.4.0]"));
+        assert!(dependency_docs_context_for("how do I tighten a chain?").is_none());
+    }
+}
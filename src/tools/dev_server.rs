@@ -0,0 +1,529 @@
+//! Managed dev servers
+//!
+//! Long-running commands such as `npm run dev` or `cargo run` run as named
+//! background processes whose stdout and stderr are kept in a ring buffer, so
+//! the agent can read the recent output ("why did the request 500?") while the
+//! server keeps running. Starting, stopping and restarting go through the
+//! user's confirmation like the other mutating tools; `/proc` drives the same
+//! manager from the chat.
+
+use super::command::{spawn_in_group, ProcessGroup};
+use rig::tool::Tool;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::path::{Path, PathBuf};
+use std::process::{ExitStatus, Stdio};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use thiserror::Error;
+use tokio::io::{AsyncBufReadExt, AsyncRead, BufReader};
+use tokio::process::{Child, Command};
+
+/// Output lines kept per process
+const MAX_BUFFERED_LINES: usize = 2000;
+/// Output lines returned when none are asked for
+pub const DEFAULT_DEV_SERVER_LINES: usize = 100;
+/// Characters of output returned to the model
+const MAX_OUTPUT_CHARS: usize = 8000;
+/// Time a process gets to exit after SIGTERM before it is killed
+const STOP_GRACE_SECS: u64 = 5;
+
+#[derive(Error, Debug)]
+pub enum DevServerError {
+    #[error("Unknown action '{0}' (expected list, output, start, stop or restart)")]
+    UnknownAction(String),
+    #[error("'start' needs the command to run")]
+    MissingCommand,
+    #[error("No managed process named '{0}'")]
+    NotFound(String),
+    #[error("No managed processes (start one with /proc start <command>)")]
+    NoProcesses,
+    #[error("Several processes are managed, name one of: {0}")]
+    Ambiguous(String),
+    #[error("'{0}' is already running (pid {1})")]
+    AlreadyRunning(String, u32),
+    #[error("'{0}' changes running processes and is only allowed in Build mode")]
+    RequiresBuildMode(&'static str),
+    #[error("'{0}' needs the user's approval before it runs")]
+    RequiresConfirmation(&'static str),
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// What to do with the managed processes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DevServerAction {
+    List,
+    Output,
+    Start,
+    Stop,
+    Restart,
+}
+
+impl DevServerAction {
+    pub fn parse(action: &str) -> Result<Self, DevServerError> {
+        match action.trim().to_lowercase().as_str() {
+            "" | "list" | "ps" => Ok(Self::List),
+            "output" | "logs" => Ok(Self::Output),
+            "start" => Ok(Self::Start),
+            "stop" => Ok(Self::Stop),
+            "restart" => Ok(Self::Restart),
+            other => Err(DevServerError::UnknownAction(other.to_string())),
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::List => "list",
+            Self::Output => "output",
+            Self::Start => "start",
+            Self::Stop => "stop",
+            Self::Restart => "restart",
+        }
+    }
+
+    /// Start, stop and restart change what runs on the machine
+    pub fn is_mutating(&self) -> bool {
+        matches!(self, Self::Start | Self::Stop | Self::Restart)
+    }
+}
+
+/// Snapshot of a managed process
+#[derive(Debug, Clone, Serialize)]
+pub struct ProcessInfo {
+    pub name: String,
+    pub command: String,
+    pub pid: Option<u32>,
+    pub running: bool,
+    pub exit_code: Option<i32>,
+    pub uptime: Duration,
+    pub buffered_lines: usize,
+}
+
+impl ProcessInfo {
+    pub fn render(&self) -> String {
+        let status = match (self.running, self.exit_code) {
+            (true, _) => format!("running, pid {}", self.pid.unwrap_or_default()),
+            (false, Some(code)) => format!("exited with code {}", code),
+            (false, None) => "stopped".to_string(),
+        };
+        format!(
+            "{}: {} ({}, up {}s, {} lines buffered)",
+            self.name,
+            self.command,
+            status,
+            self.uptime.as_secs(),
+            self.buffered_lines
+        )
+    }
+}
+
+#[derive(Debug)]
+struct ManagedProcess {
+    command: String,
+    dir: PathBuf,
+    child: Child,
+    pid: Option<u32>,
+    started: Instant,
+    output: Arc<Mutex<VecDeque<String>>>,
+    exit: Option<ExitStatus>,
+    /// Kills what the shell started once the process is dropped
+    _group: ProcessGroup,
+}
+
+impl ManagedProcess {
+    fn spawn(command: &str, dir: &Path) -> Result<Self, DevServerError> {
+        let mut cmd = Command::new("sh");
+        cmd.arg("-c")
+            .arg(command)
+            .current_dir(dir)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+        // Own process group, so stopping also reaches what the shell started
+        let (mut child, group) = spawn_in_group(&mut cmd)?;
+
+        let output = Arc::new(Mutex::new(VecDeque::new()));
+        if let Some(stdout) = child.stdout.take() {
+            pump(stdout, output.clone());
+        }
+        if let Some(stderr) = child.stderr.take() {
+            pump(stderr, output.clone());
+        }
+        Ok(Self {
+            command: command.to_string(),
+            dir: dir.to_path_buf(),
+            pid: child.id(),
+            child,
+            started: Instant::now(),
+            output,
+            exit: None,
+            _group: group,
+        })
+    }
+
+    fn is_running(&mut self) -> bool {
+        if self.exit.is_none() {
+            self.exit = self.child.try_wait().ok().flatten();
+        }
+        self.exit.is_none()
+    }
+
+    async fn terminate(&mut self) {
+        if !self.is_running() {
+            return;
+        }
+        self.signal_group("-TERM").await;
+        match tokio::time::timeout(Duration::from_secs(STOP_GRACE_SECS), self.child.wait()).await {
+            Ok(Ok(status)) => self.exit = Some(status),
+            _ => {
+                self.signal_group("-KILL").await;
+                let _ = self.child.kill().await;
+                self.exit = self.child.try_wait().ok().flatten();
+            }
+        }
+    }
+
+    async fn signal_group(&self, signal: &str) {
+        #[cfg(unix)]
+        if let Some(pid) = self.pid {
+            let _ = Command::new("kill")
+                .args([signal, "--", &format!("-{}", pid)])
+                .status()
+                .await;
+        }
+    }
+
+    fn info(&mut self, name: &str) -> ProcessInfo {
+        let running = self.is_running();
+        ProcessInfo {
+            name: name.to_string(),
+            command: self.command.clone(),
+            pid: self.pid,
+            running,
+            exit_code: self.exit.and_then(|status| status.code()),
+            uptime: self.started.elapsed(),
+            buffered_lines: self.output.lock().unwrap_or_else(|e| e.into_inner()).len(),
+        }
+    }
+}
+
+/// Copy lines from a pipe into the ring buffer until the process closes it
+fn pump<R: AsyncRead + Unpin + Send + 'static>(reader: R, output: Arc<Mutex<VecDeque<String>>>) {
+    tokio::spawn(async move {
+        let mut lines = BufReader::new(reader).lines();
+        while let Ok(Some(line)) = lines.next_line().await {
+            let mut output = output.lock().unwrap_or_else(|e| e.into_inner());
+            if output.len() == MAX_BUFFERED_LINES {
+                output.pop_front();
+            }
+            output.push_back(line);
+        }
+    });
+}
+
+/// Arguments for the dev server tool
+#[derive(Debug, Clone, Default, Deserialize, Serialize, JsonSchema)]
+pub struct DevServerArgs {
+    /// list, output, start, stop or restart
+    #[serde(default)]
+    pub action: String,
+    /// Process name (default: the only managed process, or the program name for start)
+    #[serde(default)]
+    pub name: Option<String>,
+    /// Command to start, e.g. "npm run dev" or "cargo run"
+    #[serde(default)]
+    pub command: Option<String>,
+    /// Output lines to return (default 100)
+    #[serde(default)]
+    pub lines: Option<usize>,
+    /// Only return output lines containing this text (case-insensitive)
+    #[serde(default)]
+    pub filter: Option<String>,
+    /// Directory to start the command in, filled in by the orchestrator
+    #[serde(skip)]
+    #[schemars(skip)]
+    pub working_dir: String,
+    /// Whether the agent is in Build mode, filled in by the orchestrator
+    #[serde(skip)]
+    #[schemars(skip)]
+    pub build_mode: bool,
+    /// Set by the orchestrator once the user approved a start/stop/restart
+    #[serde(skip)]
+    #[schemars(skip)]
+    pub confirm: bool,
+}
+
+impl DevServerArgs {
+    /// Validate the request and apply the Build mode / confirmation gate
+    pub fn check(&self) -> Result<DevServerAction, DevServerError> {
+        let action = DevServerAction::parse(&self.action)?;
+        if action == DevServerAction::Start && self.command.as_deref().unwrap_or("").trim().is_empty() {
+            return Err(DevServerError::MissingCommand);
+        }
+        if action.is_mutating() {
+            if !self.build_mode {
+                return Err(DevServerError::RequiresBuildMode(action.label()));
+            }
+            if !self.confirm {
+                return Err(DevServerError::RequiresConfirmation(action.label()));
+            }
+        }
+        Ok(action)
+    }
+
+    /// One-line description for the confirmation dialog and the audit log
+    pub fn summary(&self) -> String {
+        match (self.action.trim(), &self.command, &self.name) {
+            ("start", Some(command), _) => format!("start: {}", command.trim()),
+            (action, _, Some(name)) => format!("{} {}", action, name),
+            (action, _, None) => action.to_string(),
+        }
+    }
+}
+
+/// Name a process after the program it runs (`npm run dev` -> `npm`)
+pub fn default_process_name(command: &str) -> String {
+    command
+        .split_whitespace()
+        .next()
+        .and_then(|program| Path::new(program).file_name())
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| "server".to_string())
+}
+
+/// Background processes started by the agent or with `/proc`
+#[derive(Debug, Clone, Default)]
+pub struct DevServerTool {
+    processes: Arc<tokio::sync::Mutex<HashMap<String, ManagedProcess>>>,
+}
+
+impl DevServerTool {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start `command` in `dir`; a stopped process of the same name is replaced
+    pub async fn start(&self, name: &str, command: &str, dir: &Path) -> Result<ProcessInfo, DevServerError> {
+        let mut processes = self.processes.lock().await;
+        if let Some(existing) = processes.get_mut(name) {
+            if existing.is_running() {
+                return Err(DevServerError::AlreadyRunning(
+                    name.to_string(),
+                    existing.pid.unwrap_or_default(),
+                ));
+            }
+        }
+        let mut process = ManagedProcess::spawn(command, dir)?;
+        let info = process.info(name);
+        processes.insert(name.to_string(), process);
+        Ok(info)
+    }
+
+    /// Stop a process; its output stays readable until it is started again
+    pub async fn stop(&self, name: Option<&str>) -> Result<ProcessInfo, DevServerError> {
+        let mut processes = self.processes.lock().await;
+        let name = resolve_name(&processes, name)?;
+        let process = processes.get_mut(&name).ok_or(DevServerError::NotFound(name.clone()))?;
+        process.terminate().await;
+        Ok(process.info(&name))
+    }
+
+    /// Stop a process and start its command again with a fresh buffer
+    pub async fn restart(&self, name: Option<&str>) -> Result<ProcessInfo, DevServerError> {
+        let mut processes = self.processes.lock().await;
+        let name = resolve_name(&processes, name)?;
+        let process = processes.get_mut(&name).ok_or(DevServerError::NotFound(name.clone()))?;
+        process.terminate().await;
+        let mut fresh = ManagedProcess::spawn(&process.command, &process.dir)?;
+        let info = fresh.info(&name);
+        *process = fresh;
+        Ok(info)
+    }
+
+    pub async fn list(&self) -> Vec<ProcessInfo> {
+        let mut processes = self.processes.lock().await;
+        let mut infos: Vec<ProcessInfo> = processes
+            .iter_mut()
+            .map(|(name, process)| process.info(name))
+            .collect();
+        infos.sort_by(|a, b| a.name.cmp(&b.name));
+        infos
+    }
+
+    /// Last `lines` lines of output, optionally only those containing `filter`
+    pub async fn output(
+        &self,
+        name: Option<&str>,
+        lines: usize,
+        filter: Option<&str>,
+    ) -> Result<String, DevServerError> {
+        let mut processes = self.processes.lock().await;
+        let name = resolve_name(&processes, name)?;
+        let process = processes.get_mut(&name).ok_or(DevServerError::NotFound(name.clone()))?;
+        let info = process.info(&name);
+
+        let filter = filter.map(str::to_lowercase).filter(|f| !f.is_empty());
+        let output = process.output.lock().unwrap_or_else(|e| e.into_inner());
+        let mut selected: Vec<&str> = output
+            .iter()
+            .rev()
+            .filter(|line| filter.as_ref().is_none_or(|f| line.to_lowercase().contains(f)))
+            .take(lines.max(1))
+            .map(String::as_str)
+            .collect();
+        selected.reverse();
+
+        let mut body = selected.join("\n");
+        if body.len() > MAX_OUTPUT_CHARS {
+            let mut cut = body.len() - MAX_OUTPUT_CHARS;
+            while !body.is_char_boundary(cut) {
+                cut += 1;
+            }
+            body = format!("... (earlier output truncated)\n{}", &body[cut..]);
+        }
+        if body.is_empty() {
+            body = match &filter {
+                Some(f) => format!("(no output lines matching '{}')", f),
+                None => "(no output yet)".to_string(),
+            };
+        }
+        Ok(format!("{}\n\n{}", info.render(), body))
+    }
+
+    /// Run a validated request
+    pub async fn run(&self, args: DevServerArgs) -> Result<String, DevServerError> {
+        let action = args.check()?;
+        let name = args.name.as_deref().filter(|n| !n.trim().is_empty());
+        match action {
+            DevServerAction::List => {
+                let infos = self.list().await;
+                if infos.is_empty() {
+                    return Err(DevServerError::NoProcesses);
+                }
+                Ok(infos.iter().map(ProcessInfo::render).collect::<Vec<_>>().join("\n"))
+            }
+            DevServerAction::Output => {
+                self.output(
+                    name,
+                    args.lines.unwrap_or(DEFAULT_DEV_SERVER_LINES),
+                    args.filter.as_deref(),
+                )
+                .await
+            }
+            DevServerAction::Start => {
+                let command = args.command.as_deref().unwrap_or("").trim();
+                let name = name.map(String::from).unwrap_or_else(|| default_process_name(command));
+                let dir = if args.working_dir.is_empty() { "." } else { &args.working_dir };
+                let info = self.start(&name, command, Path::new(dir)).await?;
+                Ok(format!("✓ Started {}", info.render()))
+            }
+            DevServerAction::Stop => Ok(format!("✓ Stopped {}", self.stop(name).await?.render())),
+            DevServerAction::Restart => {
+                Ok(format!("✓ Restarted {}", self.restart(name).await?.render()))
+            }
+        }
+    }
+}
+
+fn resolve_name(
+    processes: &HashMap<String, ManagedProcess>,
+    name: Option<&str>,
+) -> Result<String, DevServerError> {
+    match name {
+        Some(name) if processes.contains_key(name) => Ok(name.to_string()),
+        Some(name) => Err(DevServerError::NotFound(name.to_string())),
+        None => match processes.len() {
+            0 => Err(DevServerError::NoProcesses),
+            1 => Ok(processes.keys().next().cloned().unwrap_or_default()),
+            _ => {
+                let mut names: Vec<&str> = processes.keys().map(String::as_str).collect();
+                names.sort();
+                Err(DevServerError::Ambiguous(names.join(", ")))
+            }
+        },
+    }
+}
+
+impl Tool for DevServerTool {
+    const NAME: &'static str = "dev_server";
+
+    type Args = DevServerArgs;
+    type Output = String;
+    type Error = DevServerError;
+
+    async fn definition(&self, _prompt: String) -> rig::completion::ToolDefinition {
+        rig::completion::ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: "Manage long-running dev servers (npm run dev, cargo run...) in the \
+                          background: list them and read their recent output, optionally \
+                          filtered (e.g. filter=\"500\" to see why a request failed). start, stop \
+                          and restart need Build mode and run after the user approves them."
+                .to_string(),
+            parameters: serde_json::to_value(schemars::schema_for!(DevServerArgs))
+                .unwrap_or_default(),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        self.run(args).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mutating_actions_are_gated() {
+        let mut args = DevServerArgs {
+            action: "start".to_string(),
+            ..Default::default()
+        };
+        assert!(matches!(args.check(), Err(DevServerError::MissingCommand)));
+        args.command = Some("npm run dev".to_string());
+        assert!(matches!(args.check(), Err(DevServerError::RequiresBuildMode("start"))));
+        args.build_mode = true;
+        assert!(matches!(args.check(), Err(DevServerError::RequiresConfirmation("start"))));
+        args.confirm = true;
+        assert_eq!(args.check().unwrap(), DevServerAction::Start);
+        assert_eq!(args.summary(), "start: npm run dev");
+
+        let output = DevServerArgs {
+            action: "output".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(output.check().unwrap(), DevServerAction::Output);
+        assert_eq!(default_process_name("/usr/bin/cargo run"), "cargo");
+    }
+
+    #[tokio::test]
+    async fn test_output_is_buffered_while_running() {
+        let dir = tempfile::tempdir().unwrap();
+        let tool = DevServerTool::new();
+        tool.start("web", "echo listening; echo 'GET /api 500' >&2; sleep 30", dir.path())
+            .await
+            .unwrap();
+
+        let mut output = String::new();
+        for _ in 0..50 {
+            output = tool.output(None, 10, Some("500")).await.unwrap();
+            if output.contains("GET /api 500") {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        assert!(output.contains("GET /api 500"));
+        // Only the filtered line follows the status header
+        assert!(!output.lines().any(|line| line == "listening"));
+        assert!(tool.list().await[0].running);
+
+        let stopped = tool.stop(Some("web")).await.unwrap();
+        assert!(!stopped.running);
+        assert!(matches!(
+            tool.stop(Some("api")).await,
+            Err(DevServerError::NotFound(_))
+        ));
+    }
+}
@@ -0,0 +1,679 @@
+//! Docker / docker-compose awareness tool
+//!
+//! Parses the Dockerfiles and compose file of a project into a
+//! [`ContainerTopology`] (services, ports, volumes, dependencies) and runs
+//! `docker compose` for the project. `ps` and `logs` are read-only; `up` and
+//! `down` change the running environment and need Build mode plus the user's
+//! approval in the confirmation dialog.
+
+use rig::tool::Tool;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_yaml::Value;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use thiserror::Error;
+use tokio::process::Command;
+use super::command::output_in_group;
+
+/// Compose file names, in the order `docker compose` looks for them
+pub const COMPOSE_FILE_NAMES: &[&str] = &[
+    "compose.yaml",
+    "compose.yml",
+    "docker-compose.yaml",
+    "docker-compose.yml",
+];
+/// Log lines requested per service by `logs`
+const DEFAULT_LOG_TAIL: usize = 200;
+/// Characters of command output returned to the model
+const MAX_OUTPUT_CHARS: usize = 8000;
+/// Time allowed for a compose command (`up` may pull images)
+const COMPOSE_TIMEOUT_SECS: u64 = 300;
+
+#[derive(Error, Debug)]
+pub enum DockerError {
+    #[error("No Dockerfile or compose file found in {0}")]
+    NotFound(String),
+    #[error("Invalid compose file {0}: {1}")]
+    InvalidCompose(String, String),
+    #[error("Unknown action '{0}' (expected inspect, ps, logs, up or down)")]
+    UnknownAction(String),
+    #[error("'{0}' changes the running environment and is only allowed in Build mode")]
+    RequiresBuildMode(&'static str),
+    #[error("'{0}' needs the user's approval before it runs")]
+    RequiresConfirmation(&'static str),
+    #[error("Invalid service name '{0}'")]
+    InvalidService(String),
+    #[error("docker compose timed out after {0} seconds")]
+    Timeout(u64),
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// A service declared in a compose file
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct ComposeService {
+    pub name: String,
+    pub image: Option<String>,
+    /// Build context, with the Dockerfile when it is not the default one
+    pub build: Option<String>,
+    pub ports: Vec<String>,
+    pub volumes: Vec<String>,
+    pub depends_on: Vec<String>,
+    /// Names of the environment variables (values are not kept)
+    pub environment: Vec<String>,
+}
+
+/// What a Dockerfile builds
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct DockerfileInfo {
+    pub path: String,
+    /// Base image of every stage (`FROM`), in order
+    pub base_images: Vec<String>,
+    pub exposed_ports: Vec<String>,
+    pub volumes: Vec<String>,
+    pub workdir: Option<String>,
+    /// Last `ENTRYPOINT`/`CMD`, as written
+    pub command: Option<String>,
+}
+
+impl DockerfileInfo {
+    /// Parse the instructions of a Dockerfile (line continuations are joined)
+    pub fn parse(path: &str, content: &str) -> Self {
+        let mut info = DockerfileInfo {
+            path: path.to_string(),
+            ..Default::default()
+        };
+
+        let joined = content.replace("\\\r\n", " ").replace("\\\n", " ");
+        for line in joined.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (instruction, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+            let rest = rest.trim();
+            match instruction.to_ascii_uppercase().as_str() {
+                "FROM" => {
+                    let image = rest
+                        .split_whitespace()
+                        .find(|part| !part.starts_with("--"))
+                        .unwrap_or(rest);
+                    info.base_images.push(image.to_string());
+                }
+                "EXPOSE" => info
+                    .exposed_ports
+                    .extend(rest.split_whitespace().map(String::from)),
+                "VOLUME" => info.volumes.extend(
+                    rest.trim_start_matches('[')
+                        .trim_end_matches(']')
+                        .split([',', ' '])
+                        .map(|v| v.trim().trim_matches('"'))
+                        .filter(|v| !v.is_empty())
+                        .map(String::from),
+                ),
+                "WORKDIR" => info.workdir = Some(rest.to_string()),
+                "CMD" | "ENTRYPOINT" => info.command = Some(rest.to_string()),
+                _ => {}
+            }
+        }
+        info
+    }
+}
+
+/// Containers that make up the project's dev environment
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct ContainerTopology {
+    pub compose_file: Option<String>,
+    pub services: Vec<ComposeService>,
+    pub dockerfiles: Vec<DockerfileInfo>,
+    /// Top-level named volumes
+    pub volumes: Vec<String>,
+    /// Top-level networks
+    pub networks: Vec<String>,
+}
+
+impl ContainerTopology {
+    pub fn is_empty(&self) -> bool {
+        self.compose_file.is_none() && self.dockerfiles.is_empty()
+    }
+
+    /// Parse the services, volumes and networks of a compose file
+    pub fn parse_compose(path: &str, content: &str) -> Result<Self, DockerError> {
+        let doc: Value = serde_yaml::from_str(content)
+            .map_err(|e| DockerError::InvalidCompose(path.to_string(), e.to_string()))?;
+
+        let services = doc
+            .get("services")
+            .and_then(Value::as_mapping)
+            .map(|services| {
+                services
+                    .iter()
+                    .filter_map(|(name, spec)| Some(parse_service(name.as_str()?, spec)))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Ok(ContainerTopology {
+            compose_file: Some(path.to_string()),
+            services,
+            dockerfiles: Vec::new(),
+            volumes: mapping_keys(doc.get("volumes")),
+            networks: mapping_keys(doc.get("networks")),
+        })
+    }
+
+    /// Short description of the topology for prompts and `/context`
+    pub fn summary(&self) -> String {
+        let mut out = String::new();
+        if let Some(file) = &self.compose_file {
+            out.push_str(&format!("Compose ({}):\n", file));
+            for service in &self.services {
+                let source = service
+                    .image
+                    .clone()
+                    .or_else(|| service.build.as_ref().map(|b| format!("build {}", b)))
+                    .unwrap_or_else(|| "?".to_string());
+                out.push_str(&format!("- {} [{}]", service.name, source));
+                if !service.ports.is_empty() {
+                    out.push_str(&format!(" ports {}", service.ports.join(", ")));
+                }
+                if !service.volumes.is_empty() {
+                    out.push_str(&format!(" volumes {}", service.volumes.join(", ")));
+                }
+                if !service.depends_on.is_empty() {
+                    out.push_str(&format!(" depends on {}", service.depends_on.join(", ")));
+                }
+                out.push('\n');
+            }
+            if !self.volumes.is_empty() {
+                out.push_str(&format!("Named volumes: {}\n", self.volumes.join(", ")));
+            }
+            if !self.networks.is_empty() {
+                out.push_str(&format!("Networks: {}\n", self.networks.join(", ")));
+            }
+        }
+        for dockerfile in &self.dockerfiles {
+            out.push_str(&format!(
+                "{}: FROM {}",
+                dockerfile.path,
+                dockerfile.base_images.join(" -> ")
+            ));
+            if !dockerfile.exposed_ports.is_empty() {
+                out.push_str(&format!(", EXPOSE {}", dockerfile.exposed_ports.join(" ")));
+            }
+            if let Some(command) = &dockerfile.command {
+                out.push_str(&format!(", runs {}", command));
+            }
+            out.push('\n');
+        }
+        out.trim_end().to_string()
+    }
+}
+
+fn parse_service(name: &str, spec: &Value) -> ComposeService {
+    let build = match spec.get("build") {
+        Some(Value::String(context)) => Some(context.clone()),
+        Some(build) => {
+            let context = build.get("context").and_then(Value::as_str).unwrap_or(".");
+            Some(match build.get("dockerfile").and_then(Value::as_str) {
+                Some(dockerfile) => format!("{} ({})", context, dockerfile),
+                None => context.to_string(),
+            })
+        }
+        None => None,
+    };
+
+    let environment = match spec.get("environment") {
+        Some(Value::Sequence(items)) => items
+            .iter()
+            .filter_map(Value::as_str)
+            .map(|item| item.split('=').next().unwrap_or(item).to_string())
+            .collect(),
+        env => mapping_keys(env),
+    };
+
+    let depends_on = match spec.get("depends_on") {
+        Some(Value::Sequence(items)) => items
+            .iter()
+            .filter_map(Value::as_str)
+            .map(String::from)
+            .collect(),
+        deps => mapping_keys(deps),
+    };
+
+    ComposeService {
+        name: name.to_string(),
+        image: spec.get("image").and_then(Value::as_str).map(String::from),
+        build,
+        ports: sequence_entries(spec.get("ports"), &["published", "target"], ":"),
+        volumes: sequence_entries(spec.get("volumes"), &["source", "target"], ":"),
+        depends_on,
+        environment,
+    }
+}
+
+/// Render a list that may use the short (`"8080:80"`) or long (mapping) syntax
+fn sequence_entries(value: Option<&Value>, long_keys: &[&str], separator: &str) -> Vec<String> {
+    let Some(Value::Sequence(items)) = value else {
+        return Vec::new();
+    };
+    items
+        .iter()
+        .filter_map(|item| match item {
+            Value::Mapping(_) => {
+                let parts: Vec<String> = long_keys
+                    .iter()
+                    .filter_map(|key| item.get(*key).and_then(scalar_to_string))
+                    .collect();
+                (!parts.is_empty()).then(|| parts.join(separator))
+            }
+            other => scalar_to_string(other),
+        })
+        .collect()
+}
+
+fn scalar_to_string(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        Value::Bool(b) => Some(b.to_string()),
+        _ => None,
+    }
+}
+
+fn mapping_keys(value: Option<&Value>) -> Vec<String> {
+    value
+        .and_then(Value::as_mapping)
+        .map(|map| {
+            map.keys()
+                .filter_map(|key| key.as_str().map(String::from))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// `docker compose` operations
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ComposeAction {
+    /// Parse the project files only, no docker needed
+    Inspect,
+    Ps,
+    Logs,
+    Up,
+    Down,
+}
+
+impl ComposeAction {
+    pub fn parse(action: &str) -> Result<Self, DockerError> {
+        match action.trim().to_lowercase().as_str() {
+            "" | "inspect" => Ok(Self::Inspect),
+            "ps" | "status" => Ok(Self::Ps),
+            "logs" => Ok(Self::Logs),
+            "up" => Ok(Self::Up),
+            "down" => Ok(Self::Down),
+            other => Err(DockerError::UnknownAction(other.to_string())),
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Inspect => "inspect",
+            Self::Ps => "ps",
+            Self::Logs => "logs",
+            Self::Up => "up",
+            Self::Down => "down",
+        }
+    }
+
+    /// Whether the action starts or stops containers
+    pub fn is_mutating(&self) -> bool {
+        matches!(self, Self::Up | Self::Down)
+    }
+
+    fn compose_args(&self, services: &[String], tail: usize) -> Vec<String> {
+        let mut args: Vec<String> = match self {
+            Self::Inspect => return Vec::new(),
+            Self::Ps => vec!["ps".into()],
+            Self::Logs => vec![
+                "logs".into(),
+                "--no-color".into(),
+                "--tail".into(),
+                tail.to_string(),
+            ],
+            Self::Up => vec!["up".into(), "-d".into()],
+            Self::Down => vec!["down".into()],
+        };
+        if *self != Self::Down {
+            args.extend(services.iter().cloned());
+        }
+        args
+    }
+}
+
+/// Arguments for the docker compose tool
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct DockerComposeArgs {
+    /// inspect (parse Dockerfiles/compose), ps, logs, up or down
+    #[serde(default)]
+    pub action: String,
+    /// Services to target (all when empty; ignored by down)
+    #[serde(default)]
+    pub services: Vec<String>,
+    /// Log lines per service for `logs`
+    #[serde(default = "default_log_tail")]
+    pub tail: usize,
+    /// Set by the orchestrator once the user approved `up`/`down`
+    #[serde(skip)]
+    #[schemars(skip)]
+    pub confirm: bool,
+    /// Project directory, filled in by the orchestrator
+    #[serde(skip)]
+    #[schemars(skip)]
+    pub project_dir: String,
+    /// Whether the agent is in Build mode, filled in by the orchestrator
+    #[serde(skip)]
+    #[schemars(skip)]
+    pub build_mode: bool,
+}
+
+impl DockerComposeArgs {
+    /// Validate the request and apply the Build mode / confirmation gate
+    pub fn check(&self) -> Result<ComposeAction, DockerError> {
+        let action = ComposeAction::parse(&self.action)?;
+        // Service names are passed as positional arguments and must not read as flags
+        if let Some(service) = self.services.iter().find(|s| s.trim().is_empty() || s.starts_with('-')) {
+            return Err(DockerError::InvalidService(service.clone()));
+        }
+        if action.is_mutating() {
+            if !self.build_mode {
+                return Err(DockerError::RequiresBuildMode(action.label()));
+            }
+            if !self.confirm {
+                return Err(DockerError::RequiresConfirmation(action.label()));
+            }
+        }
+        Ok(action)
+    }
+}
+
+fn default_log_tail() -> usize {
+    DEFAULT_LOG_TAIL
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DockerComposeOutput {
+    pub action: String,
+    pub topology: ContainerTopology,
+    /// Output of `docker compose`, empty for `inspect`
+    pub output: String,
+    pub success: bool,
+}
+
+impl DockerComposeOutput {
+    pub fn render(&self) -> String {
+        let mut out = self.topology.summary();
+        if !self.output.is_empty() {
+            out.push_str(&format!(
+                "\n\n$ docker compose {}{}\n{}",
+                self.action,
+                if self.success { "" } else { " (failed)" },
+                self.output
+            ));
+        }
+        out
+    }
+}
+
+/// Docker / compose awareness tool
+#[derive(Debug, Clone, Default)]
+pub struct DockerComposeTool;
+
+impl DockerComposeTool {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Find and parse the compose file and Dockerfiles under `root`
+    pub async fn inspect(&self, root: &Path) -> Result<ContainerTopology, DockerError> {
+        let mut topology = ContainerTopology::default();
+
+        for name in COMPOSE_FILE_NAMES {
+            if let Ok(content) = tokio::fs::read_to_string(root.join(name)).await {
+                topology = ContainerTopology::parse_compose(name, &content)?;
+                break;
+            }
+        }
+
+        for path in self.dockerfile_paths(root, &topology).await {
+            if let Ok(content) = tokio::fs::read_to_string(root.join(&path)).await {
+                topology
+                    .dockerfiles
+                    .push(DockerfileInfo::parse(&path, &content));
+            }
+        }
+
+        if topology.is_empty() {
+            return Err(DockerError::NotFound(root.display().to_string()));
+        }
+        Ok(topology)
+    }
+
+    /// Dockerfiles at the root (`Dockerfile`, `Dockerfile.*`, `*.Dockerfile`)
+    /// plus the ones referenced by compose build contexts
+    async fn dockerfile_paths(&self, root: &Path, topology: &ContainerTopology) -> Vec<String> {
+        let mut paths = Vec::new();
+        if let Ok(mut entries) = tokio::fs::read_dir(root).await {
+            while let Ok(Some(entry)) = entries.next_entry().await {
+                let name = entry.file_name().to_string_lossy().to_string();
+                if name == "Dockerfile"
+                    || name.starts_with("Dockerfile.")
+                    || name.ends_with(".Dockerfile")
+                {
+                    paths.push(name);
+                }
+            }
+        }
+
+        for build in topology.services.iter().filter_map(|s| s.build.as_ref()) {
+            let (context, dockerfile) = match build.split_once(" (") {
+                Some((context, file)) => (context, file.trim_end_matches(')')),
+                None => (build.as_str(), "Dockerfile"),
+            };
+            let path = PathBuf::from(context).join(dockerfile);
+            let path = path
+                .strip_prefix(".")
+                .unwrap_or(&path)
+                .to_string_lossy()
+                .to_string();
+            if !paths.contains(&path) && root.join(&path).is_file() {
+                paths.push(path);
+            }
+        }
+
+        paths.sort();
+        paths
+    }
+
+    pub async fn run(&self, args: DockerComposeArgs) -> Result<DockerComposeOutput, DockerError> {
+        let action = args.check()?;
+
+        let root = PathBuf::from(if args.project_dir.is_empty() {
+            "."
+        } else {
+            &args.project_dir
+        });
+        let topology = self.inspect(&root).await?;
+        if action == ComposeAction::Inspect {
+            return Ok(DockerComposeOutput {
+                action: action.label().to_string(),
+                topology,
+                output: String::new(),
+                success: true,
+            });
+        }
+
+        let mut cmd = Command::new("docker");
+        cmd.arg("compose");
+        if let Some(file) = &topology.compose_file {
+            cmd.args(["-f", file]);
+        }
+        cmd.args(action.compose_args(&args.services, args.tail))
+            .current_dir(&root);
+
+        let output = tokio::time::timeout(Duration::from_secs(COMPOSE_TIMEOUT_SECS), output_in_group(&mut cmd))
+            .await
+            .map_err(|_| DockerError::Timeout(COMPOSE_TIMEOUT_SECS))??;
+
+        let mut text = String::from_utf8_lossy(&output.stdout).to_string();
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if !stderr.trim().is_empty() {
+            text.push_str(&stderr);
+        }
+        if text.chars().count() > MAX_OUTPUT_CHARS {
+            // Keep the tail: the latest log lines are the interesting ones
+            let skip = text.chars().count() - MAX_OUTPUT_CHARS;
+            text = format!("...\n{}", text.chars().skip(skip).collect::<String>());
+        }
+
+        Ok(DockerComposeOutput {
+            action: action.label().to_string(),
+            topology,
+            output: text,
+            success: output.status.success(),
+        })
+    }
+}
+
+impl Tool for DockerComposeTool {
+    const NAME: &'static str = "docker_compose";
+
+    type Args = DockerComposeArgs;
+    type Output = String;
+    type Error = DockerError;
+
+    async fn definition(&self, _prompt: String) -> rig::completion::ToolDefinition {
+        rig::completion::ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: "Inspect the project's Dockerfiles and compose file (services, ports, \
+                          volumes, dependencies) and run docker compose ps/logs. up/down need \
+                          Build mode and run after the user approves them."
+                .to_string(),
+            parameters: serde_json::to_value(schemars::schema_for!(DockerComposeArgs))
+                .unwrap_or_default(),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        self.run(args).await.map(|output| output.render())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    const COMPOSE: &str = r#"
+services:
+  web:
+    build:
+      context: ./web
+      dockerfile: Dockerfile.dev
+    ports:
+      - "8080:80"
+      - target: 443
+        published: 8443
+    depends_on:
+      db:
+        condition: service_healthy
+    environment:
+      - DATABASE_URL=postgres://db/app
+  db:
+    image: postgres:16
+    volumes:
+      - pgdata:/var/lib/postgresql/data
+    environment:
+      POSTGRES_PASSWORD: secret
+volumes:
+  pgdata:
+"#;
+
+    #[test]
+    fn test_parse_compose() {
+        let topology = ContainerTopology::parse_compose("compose.yaml", COMPOSE).unwrap();
+        assert_eq!(topology.services.len(), 2);
+
+        let web = &topology.services[0];
+        assert_eq!(web.name, "web");
+        assert_eq!(web.build.as_deref(), Some("./web (Dockerfile.dev)"));
+        assert_eq!(web.ports, vec!["8080:80", "8443:443"]);
+        assert_eq!(web.depends_on, vec!["db"]);
+        assert_eq!(web.environment, vec!["DATABASE_URL"]);
+
+        let db = &topology.services[1];
+        assert_eq!(db.image.as_deref(), Some("postgres:16"));
+        assert_eq!(db.volumes, vec!["pgdata:/var/lib/postgresql/data"]);
+        assert_eq!(db.environment, vec!["POSTGRES_PASSWORD"]);
+        assert_eq!(topology.volumes, vec!["pgdata"]);
+
+        let summary = topology.summary();
+        assert!(summary.contains("- db [postgres:16]"));
+        assert!(!summary.contains("secret"));
+    }
+
+    #[test]
+    fn test_parse_dockerfile() {
+        let info = DockerfileInfo::parse(
+            "Dockerfile",
+            "FROM --platform=linux/amd64 rust:1.80 AS build\n\
+             WORKDIR /app\n\
+             RUN cargo build \\\n  --release\n\
+             FROM debian:bookworm-slim\n\
+             EXPOSE 8080 9090/udp\n\
+             VOLUME [\"/data\"]\n\
+             CMD [\"./server\"]\n",
+        );
+        assert_eq!(info.base_images, vec!["rust:1.80", "debian:bookworm-slim"]);
+        assert_eq!(info.exposed_ports, vec!["8080", "9090/udp"]);
+        assert_eq!(info.volumes, vec!["/data"]);
+        assert_eq!(info.workdir.as_deref(), Some("/app"));
+        assert_eq!(info.command.as_deref(), Some("[\"./server\"]"));
+    }
+
+    #[tokio::test]
+    async fn test_inspect_and_gating() {
+        let dir = tempdir().unwrap();
+        std::fs::write(dir.path().join("docker-compose.yml"), COMPOSE).unwrap();
+        std::fs::create_dir(dir.path().join("web")).unwrap();
+        std::fs::write(dir.path().join("web/Dockerfile.dev"), "FROM node:20\n").unwrap();
+
+        let tool = DockerComposeTool::new();
+        let topology = tool.inspect(dir.path()).await.unwrap();
+        assert_eq!(topology.compose_file.as_deref(), Some("docker-compose.yml"));
+        assert_eq!(topology.dockerfiles.len(), 1);
+        assert_eq!(topology.dockerfiles[0].path, "web/Dockerfile.dev");
+
+        let mut args = DockerComposeArgs {
+            action: "up".to_string(),
+            services: Vec::new(),
+            tail: DEFAULT_LOG_TAIL,
+            confirm: false,
+            project_dir: dir.path().to_string_lossy().to_string(),
+            build_mode: false,
+        };
+        assert!(matches!(
+            tool.run(args.clone()).await,
+            Err(DockerError::RequiresBuildMode("up"))
+        ));
+        args.build_mode = true;
+        assert!(matches!(
+            tool.run(args.clone()).await,
+            Err(DockerError::RequiresConfirmation("up"))
+        ));
+        args.services = vec!["-v".to_string()];
+        assert!(matches!(
+            tool.run(args).await,
+            Err(DockerError::InvalidService(name)) if name == "-v"
+        ));
+    }
+}
@@ -1,5 +1,6 @@
 //! Documentation generator tool
 
+use crate::ast::{self, AstParser, AstSymbol, SupportedLanguage, SymbolKind};
 use serde::{Deserialize, Serialize};
 use std::path::{Path, PathBuf};
 use std::process::Stdio;
@@ -232,6 +233,11 @@ impl DocumentationTool {
             .map(|n| n.to_string_lossy().to_string())
             .unwrap_or_default();
 
+        if let Some(module) = parse_rust_ast(&name, path, &content) {
+            return Ok(module);
+        }
+
+        // No parser available: doc comments right above `fn` and `struct` lines
         let mut functions = Vec::new();
         let mut classes = Vec::new();
         let mut current_doc = Vec::new();
@@ -364,6 +370,11 @@ impl DocumentationTool {
             .map(|n| n.to_string_lossy().to_string())
             .unwrap_or_default();
 
+        if let Some(module) = parse_rust_ast(&name, path, &content) {
+            return Ok(module);
+        }
+
+        // No parser available: doc comments right above `fn` and `struct` lines
         let mut functions = Vec::new();
         let mut classes = Vec::new();
         let mut current_doc = Vec::new();
@@ -640,6 +651,102 @@ pub struct ProjectInfo {
     pub author: Option<String>,
 }
 
+/// Functions, types with their fields and methods, and the module description
+/// from the AST, with the text of every doc comment
+fn parse_rust_ast(name: &str, path: &Path, content: &str) -> Option<ModuleDoc> {
+    let language = SupportedLanguage::Rust;
+    let mut parser = AstParser::new().ok()?;
+    let tree = parser.parse(language, content).ok()?;
+    let lines: Vec<&str> = content.lines().collect();
+
+    let mut functions = Vec::new();
+    // Keyed by qualified name so methods find their type
+    let mut classes: Vec<(String, ClassDoc)> = Vec::new();
+    let mut methods: Vec<(String, FunctionDoc)> = Vec::new();
+    for symbol in parser.extract_symbols(&tree, language, content) {
+        match symbol.kind {
+            SymbolKind::Function => functions.push(rust_function_doc(&symbol, symbol.name.clone(), &lines)),
+            SymbolKind::Method => {
+                let qualified = ast::qualified_name(&tree, language, content, &symbol);
+                let owner = qualified.rsplit_once("::").map_or("", |(owner, _)| owner).to_string();
+                methods.push((owner, rust_function_doc(&symbol, qualified, &lines)));
+            }
+            SymbolKind::Struct | SymbolKind::Enum | SymbolKind::Trait => {
+                let properties = ast::extract_fields(&tree, language, content, &symbol)
+                    .into_iter()
+                    .map(|field| ParamDoc {
+                        name: field.name,
+                        type_: field.type_annotation,
+                        description: field.docstring.as_deref().map(ast::doc_comment_text).unwrap_or_default(),
+                        default: None,
+                        required: true,
+                    })
+                    .collect();
+                classes.push((
+                    ast::qualified_name(&tree, language, content, &symbol),
+                    ClassDoc {
+                        description: doc_text(&symbol),
+                        name: symbol.name,
+                        methods: vec![],
+                        properties,
+                        parent: None,
+                    },
+                ));
+            }
+            _ => {}
+        }
+    }
+
+    // Methods of types defined elsewhere (`impl Display for Config`) stay
+    // functions under their qualified name
+    for (owner, mut method) in methods {
+        match classes.iter_mut().find(|(qualified, _)| *qualified == owner) {
+            Some((_, class)) => {
+                method.name = method.name.rsplit("::").next().unwrap_or_default().to_string();
+                class.methods.push(method);
+            }
+            None => functions.push(method),
+        }
+    }
+
+    Some(ModuleDoc {
+        name: name.to_string(),
+        path: path.to_string_lossy().to_string(),
+        description: ast::module_docstring(&tree, language, content)
+            .map(|doc| ast::doc_comment_text(&doc))
+            .unwrap_or_default(),
+        functions,
+        classes: classes.into_iter().map(|(_, class)| class).collect(),
+        constants: vec![],
+    })
+}
+
+fn rust_function_doc(symbol: &AstSymbol, name: String, lines: &[&str]) -> FunctionDoc {
+    FunctionDoc {
+        name,
+        signature: extract_rust_signature(lines.get(symbol.range.start_line - 1).map_or("", |l| l.trim())),
+        description: doc_text(symbol),
+        params: symbol
+            .params
+            .iter()
+            .map(|p| ParamDoc {
+                name: p.name.clone(),
+                type_: p.type_annotation.clone(),
+                description: String::new(),
+                default: None,
+                required: true,
+            })
+            .collect(),
+        returns: symbol.return_type.clone(),
+        examples: vec![],
+        raises: vec![],
+    }
+}
+
+fn doc_text(symbol: &AstSymbol) -> String {
+    symbol.docstring.as_deref().map(ast::doc_comment_text).unwrap_or_default()
+}
+
 // Helper functions
 fn extract_rust_signature(line: &str) -> String {
     // Extract full function signature
@@ -787,6 +894,22 @@ mod tests {
         assert_eq!(extract_rust_fn_name("fn another() {"), "another");
     }
 
+    #[test]
+    fn test_rust_ast_docs() {
+        let code = "//! Billing\n\n/// A bill\npub struct Invoice {\n    /// Amount in cents\n    pub total: u64,\n}\n\nimpl Invoice {\n    /// Whether it was paid\n    pub fn is_paid(&self) -> bool { true }\n}\n\n/// Send it\npub fn send(invoice: &Invoice) {}\n";
+        let module = parse_rust_ast("billing", Path::new("src/billing.rs"), code).unwrap();
+        assert_eq!(module.description, "Billing");
+        assert_eq!(module.functions.len(), 1);
+        assert_eq!(module.functions[0].description, "Send it");
+        assert_eq!(module.functions[0].signature, "fn send(invoice: &Invoice)");
+
+        let invoice = &module.classes[0];
+        assert_eq!(invoice.description, "A bill");
+        assert_eq!(invoice.properties[0].description, "Amount in cents");
+        assert_eq!(invoice.methods[0].name, "is_paid");
+        assert_eq!(invoice.methods[0].description, "Whether it was paid");
+    }
+
     #[test]
     fn test_python_fn_extraction() {
         assert_eq!(
@@ -1,8 +1,36 @@
 //! Environment info tool - System and environment information
+//!
+//! Besides the raw system info, [`EnvironmentTool::runtime_matrix`] gathers the
+//! installed runtimes with the toolchain managers that selected them, compact
+//! enough for the project context, and [`RuntimeMatrix::mismatches`] finds the
+//! versions an answer targets that are not the installed ones.
 
+use regex::Regex;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::env;
+use std::path::Path;
+use std::sync::LazyLock;
+
+/// Env vars that change how the runtimes of the matrix behave
+const RUNTIME_ENV_VARS: &[&str] = &[
+    "RUSTUP_TOOLCHAIN",
+    "RUSTFLAGS",
+    "NODE_ENV",
+    "NODE_OPTIONS",
+    "PYTHONPATH",
+    "DOCKER_HOST",
+    "DOCKER_CONTEXT",
+];
+
+/// `Node 18`, `python3.11`, `FROM rust:1.75-slim`, `nvm use 18`, `pyenv install 3.10.4`
+static VERSION_MENTION_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?i)\b(rustc|rust|rustup\s+(?:default|install|override\s+set)|node(?:\.?js)?|nvm\s+(?:install|use)|python|pyenv\s+(?:install|local|global)|docker)(?:\s+version)?[\s:@=-]*v?(\d+(?:\.\d+){0,2})\b",
+    )
+    .unwrap()
+});
+static BARE_VERSION_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\d+(?:\.\d+)+").unwrap());
 
 /// System information
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -40,15 +68,180 @@ pub struct ShellInfo {
     pub path: Vec<String>,
 }
 
+/// Toolchain managers that selected the active runtimes
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ToolchainInfo {
+    /// `rustup show active-toolchain`: `stable-x86_64-unknown-linux-gnu`
+    pub rustup: Option<String>,
+    /// Activated Python virtualenv (`venv .venv`) or conda environment (`conda ml`)
+    pub virtualenv: Option<String>,
+    /// Node selected by nvm: `v20.11.1`
+    pub nvm: Option<String>,
+}
+
 /// Full environment info
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EnvironmentInfo {
     pub system: SystemInfo,
     pub runtime: RuntimeInfo,
+    #[serde(default)]
+    pub toolchains: ToolchainInfo,
     pub shell: ShellInfo,
     pub env_vars: HashMap<String, String>,
 }
 
+/// One installed runtime
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InstalledRuntime {
+    /// `Rust`, `Node`, `Python` or `Docker`
+    pub name: String,
+    /// Bare version: `1.82.0`
+    pub version: String,
+    /// Toolchain manager that selected it: `rustup stable`, `nvm`, `venv .venv`
+    pub toolchain: Option<String>,
+}
+
+/// Installed runtimes, their toolchains and the env vars that affect them
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RuntimeMatrix {
+    pub runtimes: Vec<InstalledRuntime>,
+    pub env_vars: Vec<(String, String)>,
+}
+
+impl RuntimeMatrix {
+    pub fn get(&self, name: &str) -> Option<&InstalledRuntime> {
+        self.runtimes.iter().find(|r| r.name == name)
+    }
+
+    /// Whether `text` names any runtime version, before probing the installed ones
+    pub fn mentions_version(text: &str) -> bool {
+        VERSION_MENTION_RE.is_match(text)
+    }
+
+    /// One line for the project context:
+    /// `Rust 1.82.0 (rustup stable), Node 20.11.1 (nvm), Docker 27.1.1; NODE_ENV=development`
+    pub fn compact(&self) -> String {
+        let runtimes: Vec<String> = self
+            .runtimes
+            .iter()
+            .map(|r| match &r.toolchain {
+                Some(toolchain) => format!("{} {} ({})", r.name, r.version, toolchain),
+                None => format!("{} {}", r.name, r.version),
+            })
+            .collect();
+        let mut line = if runtimes.is_empty() {
+            "no runtimes detected".to_string()
+        } else {
+            runtimes.join(", ")
+        };
+        if !self.env_vars.is_empty() {
+            let vars: Vec<String> = self.env_vars.iter().map(|(k, v)| format!("{}={}", k, v)).collect();
+            line.push_str(&format!("; {}", vars.join(" ")));
+        }
+        line
+    }
+
+    /// Runtime versions `text` targets that differ from the installed ones.
+    /// Versions are compared on the components that matter for compatibility
+    /// (the major for Node and Docker, major.minor for Python and Rust); an older
+    /// Rust is fine since newer toolchains build it.
+    pub fn mismatches(&self, text: &str) -> Vec<VersionMismatch> {
+        let mut mismatches: Vec<VersionMismatch> = Vec::new();
+        for caps in VERSION_MENTION_RE.captures_iter(text) {
+            let Some(name) = runtime_name(&caps[1]) else {
+                continue;
+            };
+            let targeted = caps[2].to_string();
+            let Some(installed) = self.get(name) else {
+                continue;
+            };
+            let (wanted, have) = (version_parts(&targeted), version_parts(&installed.version));
+            if !wanted.first().is_some_and(|major| plausible_major(name, *major)) {
+                continue;
+            }
+            let significant = if matches!(name, "Node" | "Docker") { 1 } else { 2 };
+            let n = wanted.len().min(have.len()).min(significant);
+            let differs = match name {
+                "Rust" => wanted[..n] > have[..n],
+                _ => wanted[..n] != have[..n],
+            };
+            if differs
+                && !mismatches
+                    .iter()
+                    .any(|m| m.runtime == name && m.targeted == targeted)
+            {
+                mismatches.push(VersionMismatch {
+                    runtime: name.to_string(),
+                    targeted,
+                    installed: installed.version.clone(),
+                });
+            }
+        }
+        mismatches
+    }
+}
+
+/// A runtime version an answer targets that is not the installed one
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VersionMismatch {
+    pub runtime: String,
+    pub targeted: String,
+    pub installed: String,
+}
+
+impl VersionMismatch {
+    /// Line shown under the answer
+    pub fn describe(&self) -> String {
+        format!(
+            "⚠ La respuesta usa {} {}, pero la versión instalada es {}",
+            self.runtime, self.targeted, self.installed
+        )
+    }
+}
+
+/// Runtime named by a version mention, `None` for anything else
+fn runtime_name(mention: &str) -> Option<&'static str> {
+    let mention = mention.to_lowercase();
+    let first = mention.split_whitespace().next()?;
+    Some(match first {
+        "rust" | "rustc" | "rustup" => "Rust",
+        "nvm" => "Node",
+        "pyenv" | "python" => "Python",
+        "docker" => "Docker",
+        _ if first.starts_with("node") => "Node",
+        _ => return None,
+    })
+}
+
+fn version_parts(version: &str) -> Vec<u64> {
+    version.split('.').filter_map(|p| p.parse().ok()).collect()
+}
+
+/// Filters prose like "node 2 of the graph" or "Python 101"
+fn plausible_major(runtime: &str, major: u64) -> bool {
+    match runtime {
+        "Rust" => major == 1,
+        "Python" => (2..=3).contains(&major),
+        "Node" => (4..=40).contains(&major),
+        _ => (17..=40).contains(&major),
+    }
+}
+
+/// `rustc 1.82.0 (f6e511eec 2024-10-15)` → `1.82.0`
+fn bare_version(output: &str) -> Option<String> {
+    BARE_VERSION_RE.find(output).map(|m| m.as_str().to_string())
+}
+
+/// `stable-x86_64-unknown-linux-gnu (default)` → `stable`
+fn short_toolchain(toolchain: &str) -> String {
+    let name = toolchain.split_whitespace().next().unwrap_or(toolchain);
+    ["x86_64", "aarch64", "i686", "armv7", "riscv64"]
+        .iter()
+        .find_map(|arch| name.find(&format!("-{}-", arch)))
+        .map(|i| name[..i].to_string())
+        .unwrap_or_else(|| name.to_string())
+}
+
 /// Environment info tool
 #[derive(Debug, Clone)]
 pub struct EnvironmentTool;
@@ -71,6 +264,7 @@ impl EnvironmentTool {
         EnvironmentInfo {
             system: self.get_system_info(),
             runtime: self.get_runtime_info().await,
+            toolchains: self.get_toolchain_info(None).await,
             shell: self.get_shell_info(),
             env_vars: self.get_env_vars(),
         }
@@ -210,6 +404,97 @@ impl EnvironmentTool {
             })
     }
 
+    /// Active toolchains; rustup overrides depend on the directory (`rust-toolchain.toml`)
+    pub async fn get_toolchain_info(&self, dir: Option<&Path>) -> ToolchainInfo {
+        let mut rustup = tokio::process::Command::new("rustup");
+        rustup.args(["show", "active-toolchain"]);
+        if let Some(dir) = dir {
+            rustup.current_dir(dir);
+        }
+        let rustup = rustup
+            .output()
+            .await
+            .ok()
+            .filter(|o| o.status.success())
+            .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string())
+            .filter(|t| !t.is_empty());
+
+        let virtualenv = env::var("VIRTUAL_ENV")
+            .ok()
+            .map(|venv| {
+                let name = Path::new(&venv)
+                    .file_name()
+                    .map(|n| n.to_string_lossy().to_string())
+                    .unwrap_or(venv);
+                format!("venv {}", name)
+            })
+            .or_else(|| {
+                env::var("CONDA_DEFAULT_ENV")
+                    .ok()
+                    .map(|name| format!("conda {}", name))
+            });
+
+        // NVM_BIN is `~/.nvm/versions/node/v20.11.1/bin`
+        let nvm = env::var("NVM_BIN").ok().and_then(|bin| {
+            Path::new(&bin)
+                .parent()?
+                .file_name()
+                .map(|v| v.to_string_lossy().to_string())
+        });
+
+        ToolchainInfo {
+            rustup,
+            virtualenv,
+            nvm,
+        }
+    }
+
+    /// Installed Rust, Node, Python and Docker as seen from `dir`, with the
+    /// toolchains that selected them and the env vars that affect them
+    pub async fn runtime_matrix(&self, dir: &Path) -> RuntimeMatrix {
+        let version = |cmd: &'static str, arg: &'static str| async move {
+            let output = tokio::process::Command::new(cmd)
+                .arg(arg)
+                .current_dir(dir)
+                .output()
+                .await
+                .ok()
+                .filter(|o| o.status.success())?;
+            bare_version(&String::from_utf8_lossy(&output.stdout))
+        };
+        let (rust, node, python3, python, docker, toolchains) = tokio::join!(
+            version("rustc", "--version"),
+            version("node", "--version"),
+            version("python3", "--version"),
+            version("python", "--version"),
+            version("docker", "--version"),
+            self.get_toolchain_info(Some(dir)),
+        );
+
+        let runtimes = [
+            ("Rust", rust, toolchains.rustup.as_deref().map(|t| format!("rustup {}", short_toolchain(t)))),
+            ("Node", node, toolchains.nvm.as_ref().map(|_| "nvm".to_string())),
+            ("Python", python3.or(python), toolchains.virtualenv.clone()),
+            ("Docker", docker, None),
+        ]
+        .into_iter()
+        .filter_map(|(name, version, toolchain)| {
+            Some(InstalledRuntime {
+                name: name.to_string(),
+                version: version?,
+                toolchain,
+            })
+        })
+        .collect();
+
+        let env_vars = RUNTIME_ENV_VARS
+            .iter()
+            .filter_map(|name| Some((name.to_string(), env::var(name).ok()?)))
+            .collect();
+
+        RuntimeMatrix { runtimes, env_vars }
+    }
+
     /// Get shell information
     pub fn get_shell_info(&self) -> ShellInfo {
         let path = env::var("PATH")
@@ -387,6 +672,15 @@ impl EnvironmentTool {
         if let Some(ref v) = info.runtime.docker_version {
             summary.push_str(&format!("- Docker: {}\n", v));
         }
+        if let Some(ref t) = info.toolchains.rustup {
+            summary.push_str(&format!("- Rustup toolchain: {}\n", t));
+        }
+        if let Some(ref t) = info.toolchains.nvm {
+            summary.push_str(&format!("- nvm: {}\n", t));
+        }
+        if let Some(ref t) = info.toolchains.virtualenv {
+            summary.push_str(&format!("- Python env: {}\n", t));
+        }
 
         summary.push_str("\n## Shell\n");
         if let Some(ref shell) = info.shell.shell {
@@ -435,6 +729,53 @@ mod tests {
         assert!(info.pwd.is_some());
     }
 
+    #[test]
+    fn test_runtime_mismatches() {
+        let matrix = RuntimeMatrix {
+            runtimes: vec![
+                InstalledRuntime {
+                    name: "Rust".to_string(),
+                    version: "1.82.0".to_string(),
+                    toolchain: Some("rustup stable".to_string()),
+                },
+                InstalledRuntime {
+                    name: "Node".to_string(),
+                    version: "20.11.1".to_string(),
+                    toolchain: None,
+                },
+                InstalledRuntime {
+                    name: "Python".to_string(),
+                    version: "3.12.1".to_string(),
+                    toolchain: Some("venv .venv".to_string()),
+                },
+            ],
+            env_vars: vec![("NODE_ENV".to_string(), "development".to_string())],
+        };
+        assert_eq!(
+            matrix.compact(),
+            "Rust 1.82.0 (rustup stable), Node 20.11.1, Python 3.12.1 (venv .venv); NODE_ENV=development"
+        );
+
+        let answer = "Use `FROM node:18-alpine` and run `nvm use 18`, then `python3.12 -m venv .venv`.\n\
+                      Needs Rust 1.70 or later; `rustup default 1.85` for let chains. Node 20.10 works too, \
+                      and node 2 of the graph has Python 101.";
+        let found: Vec<(String, String)> = matrix
+            .mismatches(answer)
+            .into_iter()
+            .map(|m| (m.runtime, m.targeted))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                ("Node".to_string(), "18".to_string()),
+                ("Rust".to_string(), "1.85".to_string()),
+            ]
+        );
+
+        assert_eq!(short_toolchain("nightly-2024-05-01-aarch64-apple-darwin (overridden)"), "nightly-2024-05-01");
+        assert_eq!(bare_version("Docker version 27.1.1, build 6312585").as_deref(), Some("27.1.1"));
+    }
+
     #[tokio::test]
     async fn test_command_exists() {
         let tool = EnvironmentTool::new();
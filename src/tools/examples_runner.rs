@@ -0,0 +1,553 @@
+//! Embedded examples runner
+//!
+//! Small self-contained Rust and Python snippets in an answer are compiled and
+//! run in a throwaway temp project before the answer is shown, and each one is
+//! labeled with the result. Rust blocks follow doctest rules: a block without
+//! `fn main` is wrapped in one, `# ` lines are kept, and `ignore`,
+//! `compile_fail`, `no_run` and `should_panic` are honoured. Blocks that need
+//! third-party crates or packages, read stdin, or are too long are left alone.
+//!
+//! The code is model-written, so every step runs with a cleared environment
+//! and the examples themselves run under bubblewrap (`bwrap`): no network, a
+//! read-only view of the filesystem with the home directory hidden, and only
+//! the temp project writable. Where bwrap is unavailable they are only compiled.
+
+use regex::Regex;
+use std::collections::HashMap;
+use std::ffi::{OsStr, OsString};
+use std::path::Path;
+use std::process::Stdio;
+use std::sync::LazyLock;
+use std::time::{Duration, Instant};
+use tokio::process::Command;
+use super::command::output_in_group;
+
+/// Default limit for each compile and run step
+pub const DEFAULT_EXAMPLE_TIMEOUT_SECS: u64 = 10;
+/// Longer blocks are not "small" examples
+const MAX_EXAMPLE_LINES: usize = 60;
+/// Examples verified per answer
+const MAX_EXAMPLES_PER_ANSWER: usize = 5;
+/// Characters of the failure reason shown in the label
+const MAX_REASON_CHARS: usize = 160;
+
+/// Variables kept for the compilers; everything else is cleared
+const TOOLCHAIN_ENV: &[&str] = &["PATH", "HOME", "RUSTUP_HOME", "CARGO_HOME", "RUSTUP_TOOLCHAIN"];
+/// PATH given to the examples themselves
+const SANDBOX_PATH: &str = "/usr/local/bin:/usr/bin:/bin";
+
+/// Whether `bwrap` can sandbox the examples here, checked once
+static SANDBOX: tokio::sync::OnceCell<bool> = tokio::sync::OnceCell::const_new();
+
+/// Crate roots available to a bare `rustc` build
+const RUST_BUILTIN_ROOTS: &[&str] = &["std", "core", "alloc", "self", "super", "crate"];
+
+/// Standard library modules commonly imported by examples
+const PYTHON_STDLIB: &[&str] = &[
+    "abc", "argparse", "array", "ast", "asyncio", "base64", "bisect", "calendar", "collections",
+    "contextlib", "copy", "csv", "dataclasses", "datetime", "decimal", "difflib", "enum",
+    "fractions", "functools", "hashlib", "heapq", "hmac", "html", "io", "itertools", "json",
+    "logging", "math", "operator", "os", "pathlib", "pprint", "queue", "random", "re",
+    "secrets", "shutil", "statistics", "string", "struct", "sys", "tempfile", "textwrap",
+    "threading", "time", "timeit", "types", "typing", "unicodedata", "unittest", "uuid",
+    "warnings", "weakref", "zlib",
+];
+
+static FENCE_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?ms)^```([^\n`]*)\n(.*?)^```[ \t]*$").unwrap());
+/// `serde_json::json!`, `use rand::Rng` - the first segment of a path
+static RUST_PATH_ROOT_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?:^|[^\w:])([a-z_][a-z0-9_]*)::").unwrap());
+static RUST_MOD_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?m)^\s*(?:pub\s+)?mod\s+([a-z_][a-z0-9_]*)").unwrap());
+static RUST_MAIN_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?m)^\s*(?:pub\s+)?fn\s+main\s*\(").unwrap());
+static PYTHON_IMPORT_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?m)^\s*(?:import\s+([\w.]+(?:\s*,\s*[\w.]+)*)|from\s+([\w.]+)\s+import)").unwrap()
+});
+
+/// Language of a runnable example
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExampleLanguage {
+    Rust,
+    Python,
+}
+
+impl ExampleLanguage {
+    fn of_fence(tag: &str) -> Option<Self> {
+        match tag {
+            "rust" | "rs" => Some(Self::Rust),
+            "python" | "py" | "python3" => Some(Self::Python),
+            _ => None,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Rust => "rust",
+            Self::Python => "python",
+        }
+    }
+}
+
+/// What counts as success, from the doctest attributes of the fence
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExampleMode {
+    /// Compile and run, exit successfully
+    Run,
+    /// `no_run`: compile only
+    CompileOnly,
+    /// `should_panic`: compile and run, exit with a failure
+    ExpectFailure,
+}
+
+/// A fenced code block found in an answer
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CodeExample {
+    pub language: ExampleLanguage,
+    pub mode: ExampleMode,
+    pub code: String,
+    /// Byte offset right after the closing fence
+    pub end: usize,
+}
+
+impl CodeExample {
+    /// Whether the example can run without the user's project or dependencies
+    pub fn is_self_contained(&self) -> bool {
+        if self.code.lines().count() > MAX_EXAMPLE_LINES || self.code.trim().is_empty() {
+            return false;
+        }
+        match self.language {
+            ExampleLanguage::Rust => {
+                let local_mods: Vec<&str> = RUST_MOD_RE
+                    .captures_iter(&self.code)
+                    .filter_map(|c| c.get(1).map(|m| m.as_str()))
+                    .collect();
+                !self.code.contains("extern crate")
+                    && !self.code.contains("std::io::stdin")
+                    && RUST_PATH_ROOT_RE
+                        .captures_iter(&self.code)
+                        .filter_map(|c| c.get(1).map(|m| m.as_str()))
+                        .all(|root| RUST_BUILTIN_ROOTS.contains(&root) || local_mods.contains(&root))
+            }
+            ExampleLanguage::Python => {
+                !self.code.contains("input(")
+                    && !self.code.lines().any(|l| l.trim_start().starts_with(">>>"))
+                    && PYTHON_IMPORT_RE.captures_iter(&self.code).all(|c| {
+                        let modules = c.get(1).or_else(|| c.get(2)).map_or("", |m| m.as_str());
+                        modules.split(',').all(|module| {
+                            let top = module.trim().split('.').next().unwrap_or_default();
+                            PYTHON_STDLIB.contains(&top)
+                        })
+                    })
+            }
+        }
+    }
+
+    /// Source as compiled: hidden `# ` lines unhidden, wrapped in `main` if needed
+    fn source(&self) -> String {
+        match self.language {
+            ExampleLanguage::Python => self.code.clone(),
+            ExampleLanguage::Rust => {
+                let body: Vec<&str> = self
+                    .code
+                    .lines()
+                    .map(|line| match line.trim_start() {
+                        "#" => "",
+                        trimmed if trimmed.starts_with("# ") => &trimmed[2..],
+                        _ => line,
+                    })
+                    .collect();
+                let body = body.join("\n");
+                if RUST_MAIN_RE.is_match(&body) {
+                    body
+                } else {
+                    format!("#![allow(unused)]\nfn main() {{\n{}\n}}\n", body)
+                }
+            }
+        }
+    }
+}
+
+/// Result of verifying one example
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verification {
+    Passed { elapsed: Duration },
+    Failed { reason: String },
+    TimedOut { after: Duration },
+    /// The toolchain is missing or the temp project could not be created
+    Skipped,
+}
+
+impl Verification {
+    /// Label shown under the example, `None` when it was not verified
+    pub fn label(&self, example: &CodeExample) -> Option<String> {
+        let language = example.language.name();
+        match self {
+            Self::Passed { .. } if example.mode == ExampleMode::CompileOnly => {
+                Some(format!("✅ Verificado: compila ({})", language))
+            }
+            Self::Passed { .. } if example.mode == ExampleMode::ExpectFailure => {
+                Some(format!("✅ Verificado: falla como se esperaba ({})", language))
+            }
+            Self::Passed { elapsed } => Some(format!(
+                "✅ Verificado: se ejecutó sin errores ({}, {:.1}s)",
+                language,
+                elapsed.as_secs_f64()
+            )),
+            Self::Failed { reason } => Some(format!("❌ No verificado ({}): {}", language, reason)),
+            Self::TimedOut { after } => Some(format!(
+                "⏱️ No verificado ({}): excedió {}s",
+                language,
+                after.as_secs()
+            )),
+            Self::Skipped => None,
+        }
+    }
+}
+
+/// Fenced Rust and Python blocks of an answer, in order
+pub fn extract_examples(text: &str) -> Vec<CodeExample> {
+    FENCE_RE
+        .captures_iter(text)
+        .filter_map(|caps| {
+            let info = caps.get(1)?.as_str().trim();
+            let mut attrs = info.split([',', ' ']).map(str::trim).filter(|a| !a.is_empty());
+            let language = ExampleLanguage::of_fence(attrs.next()?)?;
+            let mut mode = ExampleMode::Run;
+            for attr in attrs {
+                match attr {
+                    "ignore" | "compile_fail" | "text" => return None,
+                    "no_run" => mode = ExampleMode::CompileOnly,
+                    "should_panic" => mode = ExampleMode::ExpectFailure,
+                    _ => {}
+                }
+            }
+            Some(CodeExample {
+                language,
+                mode,
+                code: caps.get(2)?.as_str().to_string(),
+                end: caps.get(0)?.end(),
+            })
+        })
+        .collect()
+}
+
+/// Compiles and runs examples in isolated temp projects
+#[derive(Debug, Clone)]
+pub struct ExampleRunner {
+    timeout: Duration,
+    /// Environment the toolchain variables are taken from
+    host_env: HashMap<OsString, OsString>,
+}
+
+impl Default for ExampleRunner {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ExampleRunner {
+    pub fn new() -> Self {
+        Self::with_timeout(Duration::from_secs(DEFAULT_EXAMPLE_TIMEOUT_SECS))
+    }
+
+    /// Runner with a custom limit for each compile and run step
+    pub fn with_timeout(timeout: Duration) -> Self {
+        Self { timeout, host_env: std::env::vars_os().collect() }
+    }
+
+    /// Take the toolchain variables from `env` instead of the process environment
+    pub fn with_host_env(mut self, env: impl IntoIterator<Item = (OsString, OsString)>) -> Self {
+        self.host_env = env.into_iter().collect();
+        self
+    }
+
+    /// Verify the self-contained examples of an answer and label each one.
+    /// Returns `None` when the answer has nothing to verify.
+    pub async fn verify_answer(&self, answer: &str) -> Option<String> {
+        let examples: Vec<CodeExample> = extract_examples(answer)
+            .into_iter()
+            .filter(CodeExample::is_self_contained)
+            .take(MAX_EXAMPLES_PER_ANSWER)
+            .collect();
+        if examples.is_empty() {
+            return None;
+        }
+
+        let mut labeled = String::with_capacity(answer.len() + examples.len() * 64);
+        let mut last = 0;
+        for example in &examples {
+            labeled.push_str(&answer[last..example.end]);
+            last = example.end;
+            if let Some(label) = self.run(example).await.label(example) {
+                labeled.push('\n');
+                labeled.push_str(&label);
+            }
+        }
+        labeled.push_str(&answer[last..]);
+        Some(labeled)
+    }
+
+    /// Compile and run one example in a fresh temp directory
+    pub async fn run(&self, example: &CodeExample) -> Verification {
+        let Ok(dir) = tempfile::tempdir() else {
+            return Verification::Skipped;
+        };
+        let started = Instant::now();
+        let result = match example.language {
+            ExampleLanguage::Rust => self.run_rust(example, dir.path()).await,
+            ExampleLanguage::Python => self.run_python(example, dir.path()).await,
+        };
+        match result {
+            Ok(()) => Verification::Passed { elapsed: started.elapsed() },
+            Err(verification) => verification,
+        }
+    }
+
+    async fn run_rust(&self, example: &CodeExample, dir: &Path) -> Result<(), Verification> {
+        let source = dir.join("main.rs");
+        let binary = dir.join("example");
+        tokio::fs::write(&source, example.source())
+            .await
+            .map_err(|_| Verification::Skipped)?;
+
+        let mut rustc = toolchain_command("rustc", &self.host_env);
+        rustc
+            .args(["--edition", "2021", "--crate-name", "example", "-o"])
+            .arg(&binary)
+            .arg(&source);
+        let compiled = self.execute(rustc, dir).await?;
+        if !compiled.status.success() {
+            return Err(Verification::Failed { reason: failure_reason(&compiled.stderr) });
+        }
+        if example.mode == ExampleMode::CompileOnly {
+            return Ok(());
+        }
+        let mut binary_command = sandboxed_command(dir, self.host_env.get(OsStr::new("HOME"))).await?;
+        binary_command.arg(&binary);
+        let ran = self.execute(binary_command, dir).await?;
+        check_exit(example.mode, &ran)
+    }
+
+    async fn run_python(&self, example: &CodeExample, dir: &Path) -> Result<(), Verification> {
+        let script = dir.join("example.py");
+        tokio::fs::write(&script, example.source())
+            .await
+            .map_err(|_| Verification::Skipped)?;
+
+        let mut python = if example.mode == ExampleMode::CompileOnly {
+            let mut python = toolchain_command("python3", &self.host_env);
+            python.args(["-m", "py_compile"]);
+            python
+        } else {
+            let mut python = sandboxed_command(dir, self.host_env.get(OsStr::new("HOME"))).await?;
+            python.args(["python3", "-I"]);
+            python
+        };
+        python.arg(&script);
+        let ran = self.execute(python, dir).await?;
+        check_exit(example.mode, &ran)
+    }
+
+    /// Run a command inside the temp project with no stdin and the step timeout
+    async fn execute(&self, mut command: Command, dir: &Path) -> Result<std::process::Output, Verification> {
+        let output = tokio::time::timeout(
+            self.timeout,
+            output_in_group(command.current_dir(dir)),
+        )
+        .await
+        .map_err(|_| Verification::TimedOut { after: self.timeout })?;
+        output.map_err(|_| Verification::Skipped)
+    }
+}
+
+/// Compiler step: only the toolchain variables are inherited
+fn toolchain_command(program: &str, host_env: &HashMap<OsString, OsString>) -> Command {
+    let mut command = Command::new(program);
+    command.env_clear().envs(
+        host_env.iter().filter(|(key, _)| key.to_str().is_some_and(|key| TOOLCHAIN_ENV.contains(&key))),
+    );
+    command
+}
+
+/// Run step for model-written code: no inherited environment, no network, a
+/// read-only root with `host_home` hidden, and only `dir` writable.
+/// The program and its arguments are appended by the caller.
+async fn sandboxed_command(dir: &Path, host_home: Option<&OsString>) -> Result<Command, Verification> {
+    let available = *SANDBOX
+        .get_or_init(|| async {
+            Command::new("bwrap")
+                .args(["--unshare-all", "--ro-bind", "/", "/", "--dev", "/dev", "true"])
+                .stdin(Stdio::null())
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .status()
+                .await
+                .is_ok_and(|status| status.success())
+        })
+        .await;
+    if !available {
+        return Err(Verification::Skipped);
+    }
+
+    let mut command = Command::new("bwrap");
+    command
+        .args(["--unshare-all", "--die-with-parent", "--new-session"])
+        .args(["--ro-bind", "/", "/", "--dev", "/dev", "--proc", "/proc", "--tmpfs", "/tmp"]);
+    if let Some(home) = host_home {
+        command.arg("--tmpfs").arg(home);
+    }
+    // Mounted last so it stays writable inside the tmpfs mounts above
+    command
+        .arg("--bind")
+        .arg(dir)
+        .arg(dir)
+        .arg("--chdir")
+        .arg(dir)
+        .arg("--")
+        .env_clear()
+        .env("PATH", SANDBOX_PATH)
+        .env("HOME", dir)
+        .env("TMPDIR", dir);
+    Ok(command)
+}
+
+fn check_exit(mode: ExampleMode, output: &std::process::Output) -> Result<(), Verification> {
+    match (mode, output.status.success()) {
+        (ExampleMode::ExpectFailure, true) => Err(Verification::Failed {
+            reason: "terminó sin error pero se esperaba un panic".to_string(),
+        }),
+        (ExampleMode::ExpectFailure, false) | (_, true) => Ok(()),
+        (_, false) => Err(Verification::Failed { reason: failure_reason(&output.stderr) }),
+    }
+}
+
+/// The most telling line of a failed run: rustc's first error or Python's exception
+fn failure_reason(stderr: &[u8]) -> String {
+    let stderr = String::from_utf8_lossy(stderr);
+    let lines: Vec<&str> = stderr.lines().map(str::trim).filter(|l| !l.is_empty()).collect();
+    let line = lines
+        .iter()
+        .find(|l| l.starts_with("error"))
+        .or_else(|| lines.iter().find(|l| l.contains("panicked at")))
+        .or_else(|| lines.last())
+        .copied()
+        .unwrap_or("salida con error");
+    line.chars().take(MAX_REASON_CHARS).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn example(language: ExampleLanguage, code: &str) -> CodeExample {
+        CodeExample { language, mode: ExampleMode::Run, code: code.to_string(), end: 0 }
+    }
+
+    #[test]
+    fn test_extract_examples_reads_fence_attributes() {
+        let text = "Intro\n```rust\nlet x = 1;\n```\n```rust,no_run\nfn main() {}\n```\n\
+                    ```rust,ignore\nbroken\n```\n```bash\nls\n```\n```py\nprint(1)\n```\n";
+        let examples = extract_examples(text);
+
+        assert_eq!(examples.len(), 3);
+        assert_eq!(examples[0].code, "let x = 1;\n");
+        assert_eq!(examples[0].mode, ExampleMode::Run);
+        assert_eq!(examples[1].mode, ExampleMode::CompileOnly);
+        assert_eq!(examples[2].language, ExampleLanguage::Python);
+        assert!(text[..examples[0].end].ends_with("let x = 1;\n```"));
+    }
+
+    #[test]
+    fn test_self_contained_rejects_external_dependencies() {
+        let std_only = example(ExampleLanguage::Rust, "use std::collections::HashMap;\nlet m: HashMap<u8, u8> = HashMap::new();");
+        let local_mod = example(ExampleLanguage::Rust, "mod geo { pub fn area() -> u32 { 4 } }\nfn main() { geo::area(); }");
+        let serde = example(ExampleLanguage::Rust, "let v = serde_json::json!({});");
+        let tokio = example(ExampleLanguage::Rust, "#[tokio::main]\nasync fn main() {}");
+
+        assert!(std_only.is_self_contained());
+        assert!(local_mod.is_self_contained());
+        assert!(!serde.is_self_contained());
+        assert!(!tokio.is_self_contained());
+
+        let stdlib = example(ExampleLanguage::Python, "import os, json\nfrom collections import Counter\n");
+        let requests = example(ExampleLanguage::Python, "import requests\n");
+        let reads_stdin = example(ExampleLanguage::Python, "name = input()\n");
+
+        assert!(stdlib.is_self_contained());
+        assert!(!requests.is_self_contained());
+        assert!(!reads_stdin.is_self_contained());
+    }
+
+    #[test]
+    fn test_rust_source_follows_doctest_rules() {
+        let wrapped = example(ExampleLanguage::Rust, "# let hidden = 2;\nassert_eq!(hidden, 2);").source();
+        assert!(wrapped.contains("fn main() {\nlet hidden = 2;"));
+
+        let with_main = example(ExampleLanguage::Rust, "fn main() {}\n").source();
+        assert_eq!(with_main, "fn main() {}");
+    }
+
+    #[test]
+    fn test_failure_reason_prefers_compiler_error() {
+        let stderr = b"warning: unused\nerror[E0425]: cannot find value `y`\n --> main.rs:2:5\n";
+        assert_eq!(failure_reason(stderr), "error[E0425]: cannot find value `y`");
+
+        let traceback = b"Traceback (most recent call last):\n  File \"x.py\"\nZeroDivisionError: division by zero\n";
+        assert_eq!(failure_reason(traceback), "ZeroDivisionError: division by zero");
+    }
+
+    #[tokio::test]
+    async fn test_verify_answer_labels_results() {
+        let answer = "Suma:\n```python\nprint(1 + 1)\n```\nError:\n```python\n1 / 0\n```\nFin";
+        let labeled = ExampleRunner::new().verify_answer(answer).await.unwrap();
+
+        // Without python3 the examples are skipped and the answer is unchanged
+        if labeled == answer {
+            return;
+        }
+        assert!(labeled.contains("print(1 + 1)\n```\n✅ Verificado: se ejecutó sin errores (python"));
+        assert!(labeled.contains("1 / 0\n```\n❌ No verificado (python): ZeroDivisionError"));
+        assert!(labeled.ends_with("\nFin"));
+    }
+
+    #[tokio::test]
+    async fn test_run_rust_example() {
+        let runner = ExampleRunner::with_timeout(Duration::from_secs(60));
+        let ok = example(ExampleLanguage::Rust, "let v = vec![1, 2, 3];\nassert_eq!(v.iter().sum::<i32>(), 6);");
+        let broken = example(ExampleLanguage::Rust, "let x: u8 = \"no\";");
+
+        match runner.run(&ok).await {
+            Verification::Skipped => return,
+            result => assert!(matches!(result, Verification::Passed { .. }), "{:?}", result),
+        }
+        assert!(matches!(runner.run(&broken).await, Verification::Failed { reason } if reason.starts_with("error")));
+    }
+
+    #[tokio::test]
+    async fn test_examples_run_without_host_environment_or_files() {
+        let host_env = std::env::vars_os().chain([("NEURO_EXAMPLE_SECRET".into(), "1".into())]);
+        let runner = ExampleRunner::new().with_host_env(host_env);
+        let outside = tempfile::tempdir().unwrap();
+        let escaped = outside.path().join("escaped");
+        let leak = example(
+            ExampleLanguage::Python,
+            &format!(
+                "import os\nassert 'NEURO_EXAMPLE_SECRET' not in os.environ\ntry:\n    open({:?}, 'w').write('x')\nexcept OSError:\n    pass",
+                escaped.to_string_lossy()
+            ),
+        );
+        match runner.run(&leak).await {
+            Verification::Skipped => {}
+            result => assert!(matches!(result, Verification::Passed { .. }), "{:?}", result),
+        }
+        assert!(!escaped.exists());
+    }
+
+    #[tokio::test]
+    async fn test_verify_answer_without_examples() {
+        let answer = "```rust\nuse rand::Rng;\n```";
+        assert!(ExampleRunner::new().verify_answer(answer).await.is_none());
+    }
+}
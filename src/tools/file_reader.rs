@@ -0,0 +1,238 @@
+//! Budgeted parallel file reading
+//!
+//! All `read_file` calls go through one [`FileReader`] that bounds how many
+//! files are read at once and how many bytes a turn may read in total, so a
+//! request like "analyze these 20 files" reads them concurrently without
+//! flooding the context. Files above [`MMAP_THRESHOLD`] are memory-mapped and
+//! only the requested line range is copied out.
+
+use super::filesystem::FileSystemError;
+use memmap2::Mmap;
+use std::fs::File;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use tokio::sync::Semaphore;
+
+/// Files read at the same time
+pub const DEFAULT_READ_CONCURRENCY: usize = 8;
+/// Bytes all reads of one turn may return together
+pub const DEFAULT_TURN_READ_BUDGET: usize = 4 * 1024 * 1024;
+/// Files at least this large are memory-mapped instead of read
+pub const MMAP_THRESHOLD: u64 = 256 * 1024;
+
+/// Bytes left for the current turn, shared by every read
+#[derive(Debug)]
+struct ReadBudget {
+    limit: usize,
+    used: AtomicUsize,
+}
+
+impl ReadBudget {
+    /// Take up to `wanted` bytes from the budget, returning what was granted
+    fn reserve(&self, wanted: usize) -> usize {
+        let mut granted = 0;
+        let _ = self.used.fetch_update(Ordering::AcqRel, Ordering::Acquire, |used| {
+            granted = wanted.min(self.limit.saturating_sub(used));
+            Some(used + granted)
+        });
+        granted
+    }
+}
+
+/// A line range read from a file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileSlice {
+    pub content: String,
+    pub total_lines: usize,
+    /// First line returned (1-indexed)
+    pub first_line: usize,
+    /// Number of lines returned
+    pub line_count: usize,
+    /// The turn budget ran out before the whole range was read
+    pub truncated: bool,
+}
+
+/// Shared reader with bounded parallelism and a per-turn byte budget
+#[derive(Debug, Clone)]
+pub struct FileReader {
+    permits: Arc<Semaphore>,
+    budget: Arc<ReadBudget>,
+}
+
+impl Default for FileReader {
+    fn default() -> Self {
+        Self::new(DEFAULT_READ_CONCURRENCY, DEFAULT_TURN_READ_BUDGET)
+    }
+}
+
+impl FileReader {
+    pub fn new(concurrency: usize, turn_budget: usize) -> Self {
+        Self {
+            permits: Arc::new(Semaphore::new(concurrency.max(1))),
+            budget: Arc::new(ReadBudget { limit: turn_budget, used: AtomicUsize::new(0) }),
+        }
+    }
+
+    /// Start a new turn with the full byte budget
+    pub fn start_turn(&self) {
+        self.budget.used.store(0, Ordering::Release);
+    }
+
+    /// Bytes the current turn can still read
+    pub fn remaining_budget(&self) -> usize {
+        self.budget.limit.saturating_sub(self.budget.used.load(Ordering::Acquire))
+    }
+
+    /// Read lines `start_line..=end_line` (1-indexed, both optional) of a file
+    pub async fn read_lines(
+        &self,
+        path: &Path,
+        start_line: Option<usize>,
+        end_line: Option<usize>,
+    ) -> Result<FileSlice, FileSystemError> {
+        let _permit = self
+            .permits
+            .acquire()
+            .await
+            .map_err(|e| FileSystemError::Io(std::io::Error::other(e)))?;
+        let path = path.to_path_buf();
+        let budget = self.budget.clone();
+        tokio::task::spawn_blocking(move || read_blocking(&path, start_line, end_line, &budget))
+            .await
+            .map_err(|e| FileSystemError::Io(std::io::Error::other(e)))?
+    }
+
+    /// Read whole files concurrently, results in the order of `paths`
+    pub async fn read_many(&self, paths: &[PathBuf]) -> Vec<Result<FileSlice, FileSystemError>> {
+        futures::future::join_all(paths.iter().map(|path| self.read_lines(path, None, None))).await
+    }
+}
+
+fn read_blocking(
+    path: &Path,
+    start_line: Option<usize>,
+    end_line: Option<usize>,
+    budget: &ReadBudget,
+) -> Result<FileSlice, FileSystemError> {
+    let mut file = File::open(path)?;
+    let mapped;
+    let mut owned = Vec::new();
+    let bytes: &[u8] = if file.metadata()?.len() >= MMAP_THRESHOLD {
+        // SAFETY: the map is read-only and dropped at the end of this call; the
+        // bytes are copied out as lossy UTF-8, so a concurrent writer can at
+        // worst produce garbled text, never an invalid `String`.
+        mapped = unsafe { Mmap::map(&file)? };
+        &mapped
+    } else {
+        file.read_to_end(&mut owned)?;
+        &owned
+    };
+
+    let total_lines = count_lines(bytes);
+    let start = start_line.unwrap_or(1).saturating_sub(1);
+    let end = end_line.unwrap_or(total_lines).min(total_lines);
+    let from = line_offset(bytes, start);
+    let wanted = &bytes[from..line_offset(bytes, end).max(from)];
+
+    let granted = budget.reserve(wanted.len());
+    if granted == 0 && !wanted.is_empty() {
+        return Err(FileSystemError::BudgetExhausted(budget.limit));
+    }
+    let text = String::from_utf8_lossy(&wanted[..granted]);
+    let lines: Vec<&str> = text.lines().collect();
+
+    Ok(FileSlice {
+        content: lines.join("\n"),
+        total_lines,
+        first_line: start + 1,
+        line_count: lines.len(),
+        truncated: granted < wanted.len(),
+    })
+}
+
+/// Line count as `str::lines` sees it
+fn count_lines(bytes: &[u8]) -> usize {
+    let newlines = bytes.iter().filter(|&&b| b == b'\n').count();
+    match bytes.last() {
+        Some(b'\n') | None => newlines,
+        Some(_) => newlines + 1,
+    }
+}
+
+/// Byte offset where line `index` (0-indexed) starts, or the end of the file
+fn line_offset(bytes: &[u8], index: usize) -> usize {
+    if index == 0 {
+        return 0;
+    }
+    bytes
+        .iter()
+        .enumerate()
+        .filter(|(_, &b)| b == b'\n')
+        .nth(index - 1)
+        .map_or(bytes.len(), |(i, _)| i + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[tokio::test]
+    async fn test_read_lines_range() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("a.txt");
+        std::fs::write(&path, "one\ntwo\r\nthree\nfour").unwrap();
+
+        let slice = FileReader::default().read_lines(&path, Some(2), Some(3)).await.unwrap();
+
+        assert_eq!(slice.content, "two\nthree");
+        assert_eq!(slice.total_lines, 4);
+        assert_eq!((slice.first_line, slice.line_count), (2, 2));
+        assert!(!slice.truncated);
+    }
+
+    #[tokio::test]
+    async fn test_large_file_is_mapped_and_sliced() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("big.log");
+        let text: String = (1..=40_000).map(|i| format!("line {}\n", i)).collect();
+        assert!(text.len() as u64 >= MMAP_THRESHOLD);
+        std::fs::write(&path, &text).unwrap();
+
+        let reader = FileReader::default();
+        let slice = reader.read_lines(&path, Some(39_999), None).await.unwrap();
+
+        assert_eq!(slice.content, "line 39999\nline 40000");
+        assert_eq!(slice.total_lines, 40_000);
+        // Only the requested lines count against the budget
+        assert_eq!(reader.remaining_budget(), DEFAULT_TURN_READ_BUDGET - 22);
+    }
+
+    #[tokio::test]
+    async fn test_turn_budget_truncates_then_refuses() {
+        let dir = tempdir().unwrap();
+        let paths: Vec<PathBuf> = (0..3)
+            .map(|i| {
+                let path = dir.path().join(format!("f{}.txt", i));
+                std::fs::write(&path, "0123456789").unwrap();
+                path
+            })
+            .collect();
+        let reader = FileReader::new(2, 25);
+
+        let results = reader.read_many(&paths).await;
+        let read: usize = results.iter().flatten().map(|s| s.content.len()).sum();
+
+        assert_eq!(read, 25);
+        assert_eq!(results.iter().flatten().filter(|s| s.truncated).count(), 1);
+        assert!(matches!(
+            reader.read_lines(&paths[0], None, None).await,
+            Err(FileSystemError::BudgetExhausted(25))
+        ));
+
+        reader.start_turn();
+        assert_eq!(reader.read_lines(&paths[0], None, None).await.unwrap().content, "0123456789");
+    }
+}
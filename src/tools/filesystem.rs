@@ -1,5 +1,9 @@
 //! Filesystem tools for reading, writing, and listing files
 
+use super::file_reader::FileReader;
+use super::read_ranges::{self, SymbolRanges};
+use super::tree_summary::{TreeSummaries, TreeSummary};
+use crate::db::Database;
 use rig::tool::Tool;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -21,6 +25,12 @@ pub enum FileSystemError {
     PermissionDenied(String),
     #[error("Invalid path: {0}")]
     InvalidPath(String),
+    #[error("Read budget for this turn exhausted ({0} bytes); read narrower line ranges")]
+    BudgetExhausted(usize),
+    #[error("Symbol not found: {0}")]
+    SymbolNotFound(String),
+    #[error("Text not found: {0}")]
+    TextNotFound(String),
 }
 
 // ============================================================================
@@ -29,9 +39,19 @@ pub enum FileSystemError {
 
 /// Tool for reading file contents
 #[derive(Debug, Clone, Default)]
-pub struct FileReadTool;
+pub struct FileReadTool {
+    reader: FileReader,
+    symbols: SymbolRanges,
+}
 
-#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+impl FileReadTool {
+    /// Shared reader (parallelism limit and per-turn byte budget)
+    pub fn reader(&self) -> &FileReader {
+        &self.reader
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
 pub struct FileReadArgs {
     /// The absolute path to the file to read
     pub path: String,
@@ -41,6 +61,20 @@ pub struct FileReadArgs {
     /// Optional: end line (1-indexed, inclusive). If not provided, reads to end.
     #[serde(default)]
     pub end_line: Option<usize>,
+    /// Optional: read only the definition of this function, method or type.
+    /// `Type::method` or `Class.method` picks among same-named symbols.
+    #[serde(default)]
+    pub symbol: Option<String>,
+    /// Optional: read the lines around the first line containing this text
+    #[serde(default)]
+    pub around: Option<String>,
+    /// Lines before and after the `around` match (default: 10)
+    #[serde(default)]
+    pub context_lines: Option<usize>,
+    /// Grow a line range to the whole functions or types it cuts through
+    /// (default: true)
+    #[serde(default)]
+    pub expand_scope: Option<bool>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -51,6 +85,12 @@ pub struct FileReadOutput {
     pub total_lines: usize,
     /// Lines actually read (start..=end)
     pub lines_read: String,
+    /// The per-turn read budget cut the range short
+    #[serde(default)]
+    pub truncated: bool,
+    /// How the range was chosen (symbol, match, expanded scope)
+    #[serde(default)]
+    pub scope: Option<String>,
 }
 
 impl Tool for FileReadTool {
@@ -63,7 +103,9 @@ impl Tool for FileReadTool {
     async fn definition(&self, _prompt: String) -> rig::completion::ToolDefinition {
         rig::completion::ToolDefinition {
             name: Self::NAME.to_string(),
-            description: "Read the contents of a file. You can optionally specify line ranges."
+            description: "Read the contents of a file: all of it, a line range, one symbol's \
+                          definition, or the lines around some text. Ranges grow to whole \
+                          functions or types."
                 .to_string(),
             parameters: serde_json::to_value(schemars::schema_for!(FileReadArgs))
                 .unwrap_or_default(),
@@ -77,24 +119,35 @@ impl Tool for FileReadTool {
             return Err(FileSystemError::PathNotFound(args.path));
         }
 
-        let content = fs::read_to_string(path).await?;
-        let lines: Vec<&str> = content.lines().collect();
-        let total_lines = lines.len();
-
-        let start = args.start_line.unwrap_or(1).saturating_sub(1);
-        let end = args.end_line.unwrap_or(total_lines).min(total_lines);
+        // Resolving reads the whole file; only the final range counts
+        // against the turn budget
+        let range = if read_ranges::needs_resolution(&args, path) {
+            let (symbols, file, args) = (self.symbols.clone(), path.to_path_buf(), args.clone());
+            tokio::task::spawn_blocking(move || {
+                let source = String::from_utf8_lossy(&std::fs::read(&file)?).to_string();
+                read_ranges::resolve(&args, &source, &symbols.symbols(&file, &source))
+            })
+            .await
+            .map_err(|e| FileSystemError::Io(std::io::Error::other(e)))??
+        } else {
+            read_ranges::ResolvedRange {
+                start_line: args.start_line,
+                end_line: args.end_line,
+                note: None,
+            }
+        };
 
-        let selected_lines: Vec<&str> = lines
-            .iter()
-            .skip(start)
-            .take(end.saturating_sub(start))
-            .copied()
-            .collect();
+        let slice = self
+            .reader
+            .read_lines(path, range.start_line, range.end_line)
+            .await?;
 
         Ok(FileReadOutput {
-            content: selected_lines.join("\n"),
-            total_lines,
-            lines_read: format!("{}-{}", start + 1, start + selected_lines.len()),
+            content: slice.content,
+            total_lines: slice.total_lines,
+            lines_read: format!("{}-{}", slice.first_line, slice.first_line - 1 + slice.line_count),
+            truncated: slice.truncated,
+            scope: range.note,
         })
     }
 }
@@ -188,8 +241,22 @@ impl Tool for FileWriteTool {
 // ============================================================================
 
 /// Tool for listing directory contents
-#[derive(Debug, Clone, Default)]
-pub struct ListDirectoryTool;
+#[derive(Clone, Default)]
+pub struct ListDirectoryTool {
+    summaries: TreeSummaries,
+}
+
+impl ListDirectoryTool {
+    /// Persist directory summaries in the session database
+    pub fn with_database(mut self, db: Database) -> Self {
+        self.summaries = self.summaries.with_database(db);
+        self
+    }
+
+    pub fn summaries(&self) -> &TreeSummaries {
+        &self.summaries
+    }
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ListDirectoryArgs {
@@ -201,6 +268,10 @@ pub struct ListDirectoryArgs {
     /// Maximum depth for recursive listing (default: 3)
     #[serde(default = "default_max_depth")]
     pub max_depth: usize,
+    /// Summarize instead: file count, size and languages per entry. Use it for
+    /// large directories; the project root is summarized by default
+    #[serde(default)]
+    pub summary: Option<bool>,
 }
 
 fn default_max_depth() -> usize {
@@ -225,6 +296,8 @@ pub struct ListDirectoryOutput {
     pub entries: Vec<DirEntry>,
     /// Total count of entries
     pub count: usize,
+    /// Set instead of `entries` when a summary was requested
+    pub summary: Option<TreeSummary>,
 }
 
 impl Tool for ListDirectoryTool {
@@ -237,7 +310,8 @@ impl Tool for ListDirectoryTool {
     async fn definition(&self, _prompt: String) -> rig::completion::ToolDefinition {
         rig::completion::ToolDefinition {
             name: Self::NAME.to_string(),
-            description: "List the contents of a directory. Can optionally list recursively."
+            description: "List the contents of a directory. Can optionally list recursively, \
+                          or summarize it (file counts, sizes and languages per entry)."
                 .to_string(),
             parameters: serde_json::to_value(schemars::schema_for!(ListDirectoryArgs))
                 .unwrap_or_default(),
@@ -258,11 +332,24 @@ impl Tool for ListDirectoryTool {
             )));
         }
 
+        if args.summary == Some(true) {
+            // sqlx futures are not `Sync`, which `Tool::call` requires: run on a task
+            let (summaries, dir) = (self.summaries.clone(), path.to_path_buf());
+            let summary = tokio::spawn(async move { summaries.summarize(&dir).await })
+                .await
+                .map_err(|e| FileSystemError::Io(std::io::Error::other(e)))??;
+            return Ok(ListDirectoryOutput {
+                entries: Vec::new(),
+                count: summary.entries.len(),
+                summary: Some(summary),
+            });
+        }
+
         let mut entries = Vec::new();
         list_dir_recursive(path, &mut entries, 0, args.max_depth, args.recursive).await?;
 
         let count = entries.len();
-        Ok(ListDirectoryOutput { entries, count })
+        Ok(ListDirectoryOutput { entries, count, summary: None })
     }
 }
 
@@ -327,12 +414,13 @@ mod tests {
         assert!(result.success);
 
         // Read
-        let read_tool = FileReadTool;
+        let read_tool = FileReadTool::default();
         let result = read_tool
             .call(FileReadArgs {
                 path: file_path.to_string_lossy().to_string(),
                 start_line: None,
                 end_line: None,
+                ..Default::default()
             })
             .await
             .unwrap();
@@ -354,12 +442,13 @@ mod tests {
             .unwrap();
         fs::create_dir(dir.path().join("subdir")).await.unwrap();
 
-        let tool = ListDirectoryTool;
+        let tool = ListDirectoryTool::default();
         let result = tool
             .call(ListDirectoryArgs {
                 path: dir.path().to_string_lossy().to_string(),
                 recursive: false,
                 max_depth: 3,
+                summary: None,
             })
             .await
             .unwrap();
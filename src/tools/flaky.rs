@@ -0,0 +1,378 @@
+//! Flaky test detection
+//!
+//! `/test --detect-flaky` runs the same test selection several times, some of
+//! them at once, and compares the outcome of every test across the runs: a
+//! test that both passed and failed is probably flaky. Failures are grouped by
+//! their message with paths, numbers and addresses left out (as in
+//! `build_errors`), so a race that breaks in two ways shows both. The counts
+//! are added to `test_flakiness`, which keeps the history of a project across
+//! detections.
+
+use super::build_errors::normalize;
+use super::test_runner::{TestArgs, TestError, TestOutput, TestRunnerTool, TestStatus};
+use crate::db::{Database, DatabaseError, TestFlakiness};
+use chrono::Utc;
+use futures::stream::{self, StreamExt};
+use std::collections::BTreeMap;
+
+/// Runs of a detection unless `--runs` says otherwise
+pub const DEFAULT_FLAKY_RUNS: usize = 5;
+/// Upper bound of `--runs`
+pub const MAX_FLAKY_RUNS: usize = 50;
+/// Tests put in a triage plan
+pub const TRIAGE_TESTS: usize = 3;
+/// Name of the whole run, for frameworks whose output has no per-test results
+const SUITE: &str = "(suite)";
+/// Characters of a failure message kept in the report
+const MAX_MESSAGE_CHARS: usize = 200;
+
+/// One way a test failed, with how many runs failed like this
+#[derive(Debug, Clone, PartialEq)]
+pub struct FailureSignature {
+    pub signature: String,
+    /// Message of the first run that failed like this
+    pub message: String,
+    pub count: usize,
+}
+
+/// Outcomes of a test across the runs of a detection
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TestVariance {
+    pub name: String,
+    pub passed: usize,
+    pub failed: usize,
+    /// Most frequent first
+    pub failures: Vec<FailureSignature>,
+}
+
+impl TestVariance {
+    pub fn is_flaky(&self) -> bool {
+        self.passed > 0 && self.failed > 0
+    }
+
+    /// 1.0 for a test that fails half the time, 0.0 for a stable one
+    pub fn flakiness(&self) -> f64 {
+        let runs = self.passed + self.failed;
+        if runs == 0 {
+            return 0.0;
+        }
+        2.0 * self.passed.min(self.failed) as f64 / runs as f64
+    }
+
+    fn record(&mut self, failure: Option<String>) {
+        let Some(message) = failure else {
+            self.passed += 1;
+            return;
+        };
+        self.failed += 1;
+        let signature = normalize(&message);
+        match self.failures.iter_mut().find(|f| f.signature == signature) {
+            Some(known) => known.count += 1,
+            None => self.failures.push(FailureSignature {
+                signature,
+                message,
+                count: 1,
+            }),
+        }
+        self.failures.sort_by(|a, b| b.count.cmp(&a.count));
+    }
+}
+
+/// Result of running a test selection several times
+#[derive(Debug, Clone, Default)]
+pub struct FlakyReport {
+    pub runs: usize,
+    pub parallelism: usize,
+    /// Runs that could not be executed at all
+    pub errors: Vec<String>,
+    pub tests: BTreeMap<String, TestVariance>,
+}
+
+impl FlakyReport {
+    fn add(&mut self, output: &TestOutput) {
+        if output.tests.is_empty() {
+            let failure = (!output.summary.success).then(|| {
+                let tail = output.stderr.lines().rev().find(|line| !line.trim().is_empty());
+                tail.map_or_else(|| format!("exit code {}", output.exit_code), str::to_string)
+            });
+            self.variance(SUITE).record(failure);
+            return;
+        }
+        let messages = failure_messages(&output.stdout);
+        for test in &output.tests {
+            let failure = match test.status {
+                TestStatus::Passed => None,
+                TestStatus::Skipped => continue,
+                TestStatus::Failed | TestStatus::Error => Some(
+                    messages
+                        .get(&test.name)
+                        .or(test.message.as_ref())
+                        .cloned()
+                        .unwrap_or_else(|| "failed".to_string()),
+                ),
+            };
+            self.variance(&test.name).record(failure);
+        }
+    }
+
+    fn variance(&mut self, name: &str) -> &mut TestVariance {
+        self.tests.entry(name.to_string()).or_insert_with(|| TestVariance {
+            name: name.to_string(),
+            ..Default::default()
+        })
+    }
+
+    /// Tests that both passed and failed, most flaky first
+    pub fn flaky(&self) -> Vec<&TestVariance> {
+        let mut flaky: Vec<&TestVariance> = self.tests.values().filter(|t| t.is_flaky()).collect();
+        flaky.sort_by(|a, b| b.flakiness().total_cmp(&a.flakiness()).then_with(|| a.name.cmp(&b.name)));
+        flaky
+    }
+
+    /// Tests that failed in every run: broken, not flaky
+    pub fn always_failing(&self) -> Vec<&TestVariance> {
+        self.tests.values().filter(|t| t.passed == 0 && t.failed > 0).collect()
+    }
+
+    /// Report in Markdown, with the project history of flaky tests when there is one
+    pub fn to_markdown(&self, history: &[TestFlakiness]) -> String {
+        let mut output = format!(
+            "# 🎲 Detección de tests inestables\n\n{} ejecuciones ({} en paralelo), {} tests observados\n\n",
+            self.runs,
+            self.parallelism,
+            self.tests.len()
+        );
+        for error in &self.errors {
+            output.push_str(&format!("⚠️ Ejecución fallida: {}\n", error));
+        }
+
+        let flaky = self.flaky();
+        if flaky.is_empty() {
+            output.push_str("✅ Ningún test cambió de resultado entre ejecuciones\n");
+        } else {
+            output.push_str("## Probablemente inestables\n\n");
+            for test in &flaky {
+                output.push_str(&format!(
+                    "- `{}`: falló {} de {} veces\n",
+                    test.name,
+                    test.failed,
+                    test.passed + test.failed
+                ));
+                for failure in &test.failures {
+                    output.push_str(&format!("  - {}× {}\n", failure.count, truncate(&failure.message)));
+                }
+            }
+        }
+
+        let broken = self.always_failing();
+        if !broken.is_empty() {
+            output.push_str("\n## Fallan siempre\n\n");
+            for test in broken {
+                let message = test.failures.first().map(|f| truncate(&f.message)).unwrap_or_default();
+                output.push_str(&format!("- `{}`: {}\n", test.name, message));
+            }
+        }
+
+        if !history.is_empty() {
+            output.push_str("\n## Historial del proyecto\n\n");
+            for test in history {
+                output.push_str(&format!(
+                    "- `{}`: {} fallos en {} ejecuciones (desde {})\n",
+                    test.test,
+                    test.failures,
+                    test.runs,
+                    test.first_seen.get(..10).unwrap_or(&test.first_seen)
+                ));
+            }
+        }
+
+        if !flaky.is_empty() {
+            output.push_str(&format!(
+                "\n💡 /test --triage abre un plan de triage para {}",
+                flaky.iter().take(TRIAGE_TESTS).map(|t| format!("`{}`", t.name)).collect::<Vec<_>>().join(", ")
+            ));
+        }
+        output
+    }
+
+    /// Prompt for a plan to investigate the worst flaky tests, `None` when there are none
+    pub fn triage_prompt(&self) -> Option<String> {
+        let flaky = self.flaky();
+        if flaky.is_empty() {
+            return None;
+        }
+        let mut prompt = format!(
+            "These tests passed and failed across {} identical runs ({} at a time). Plan how to triage them: \
+             find the likely source of nondeterminism in each (shared state, ordering, timing, randomness, \
+             network, filesystem), how to reproduce it reliably, and the fix to try first. \
+             Do not change any code yet.\n",
+            self.runs, self.parallelism
+        );
+        for test in flaky.iter().take(TRIAGE_TESTS) {
+            prompt.push_str(&format!("\n- {}: failed {} of {} runs", test.name, test.failed, test.passed + test.failed));
+            for failure in &test.failures {
+                prompt.push_str(&format!("\n  - {}x: {}", failure.count, truncate(&failure.message)));
+            }
+        }
+        Some(prompt)
+    }
+}
+
+/// Run the tests of `args` `runs` times, up to `parallelism` at once
+pub async fn detect_flaky(runner: &TestRunnerTool, args: &TestArgs, runs: usize, parallelism: usize) -> FlakyReport {
+    let runs = runs.clamp(1, MAX_FLAKY_RUNS);
+    let parallelism = parallelism.clamp(1, runs);
+    let outputs: Vec<Result<TestOutput, TestError>> = stream::iter(0..runs)
+        .map(|_| runner.run(args.clone()))
+        .buffer_unordered(parallelism)
+        .collect()
+        .await;
+
+    let mut report = FlakyReport {
+        runs,
+        parallelism,
+        ..Default::default()
+    };
+    for output in outputs {
+        match output {
+            Ok(output) => report.add(&output),
+            Err(e) => report.errors.push(e.to_string()),
+        }
+    }
+    report
+}
+
+/// Add the outcomes of `report` to the history of `project_root`; the flaky
+/// tests of the project afterwards
+pub async fn record_flakiness(
+    db: &Database,
+    project_root: &str,
+    report: &FlakyReport,
+    limit: i32,
+) -> Result<Vec<TestFlakiness>, DatabaseError> {
+    let now = Utc::now().to_rfc3339();
+    for test in report.tests.values() {
+        let failure = test.failures.first();
+        db.record_test_runs(&TestFlakiness {
+            project_root: project_root.to_string(),
+            test: test.name.clone(),
+            runs: (test.passed + test.failed) as i64,
+            failures: test.failed as i64,
+            signature: failure.map(|f| f.signature.clone()),
+            message: failure.map(|f| f.message.clone()),
+            first_seen: now.clone(),
+            last_seen: now.clone(),
+        })
+        .await?;
+    }
+    db.get_flaky_tests(project_root, limit).await
+}
+
+/// Panic message of every failed test in `cargo test` output, from its
+/// `---- <name> stdout ----` section
+fn failure_messages(stdout: &str) -> BTreeMap<String, String> {
+    let mut messages = BTreeMap::new();
+    let mut lines = stdout.lines();
+    while let Some(line) = lines.next() {
+        let Some(name) = line.strip_prefix("---- ").and_then(|rest| rest.strip_suffix(" stdout ----")) else {
+            continue;
+        };
+        let Some(panic) = lines.by_ref().find(|line| line.contains("panicked at")) else {
+            break;
+        };
+        // `panicked at src/lib.rs:3:5:` followed by the message, or the older
+        // `panicked at 'message', src/lib.rs:3:5`
+        let message = match panic.split_once("panicked at ") {
+            Some((_, at)) if at.ends_with(':') => lines.next().unwrap_or(at).trim().to_string(),
+            Some((_, at)) => at.trim().to_string(),
+            None => panic.trim().to_string(),
+        };
+        messages.insert(name.to_string(), message);
+    }
+    messages
+}
+
+fn truncate(message: &str) -> String {
+    if message.chars().count() <= MAX_MESSAGE_CHARS {
+        return message.to_string();
+    }
+    format!("{}…", message.chars().take(MAX_MESSAGE_CHARS).collect::<String>())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tools::{TestCase, TestFramework, TestSummary};
+
+    fn output(results: &[(&str, TestStatus)], stdout: &str) -> TestOutput {
+        TestOutput {
+            summary: TestSummary {
+                framework: TestFramework::Cargo,
+                total: results.len(),
+                passed: 0,
+                failed: 0,
+                skipped: 0,
+                errors: 0,
+                duration_ms: 0,
+                success: results.iter().all(|(_, status)| *status != TestStatus::Failed),
+            },
+            tests: results
+                .iter()
+                .map(|(name, status)| TestCase {
+                    name: name.to_string(),
+                    status: status.clone(),
+                    duration_ms: None,
+                    message: None,
+                    file: None,
+                    line: None,
+                })
+                .collect(),
+            stdout: stdout.to_string(),
+            stderr: String::new(),
+            exit_code: 0,
+        }
+    }
+
+    #[test]
+    fn test_flaky_and_broken_tests() {
+        let timeout = "---- net::tests::fetch stdout ----\n\
+                       thread 'net::tests::fetch' panicked at src/net.rs:40:9:\n\
+                       request timed out after 3012 ms\n";
+        let refused = "---- net::tests::fetch stdout ----\n\
+                       thread 'net::tests::fetch' panicked at 'connection refused: 127.0.0.1:8080', src/net.rs:41:9\n";
+        let mut report = FlakyReport::default();
+        report.add(&output(&[("net::tests::fetch", TestStatus::Failed), ("db::tests::open", TestStatus::Failed)], timeout));
+        report.add(&output(&[("net::tests::fetch", TestStatus::Passed), ("db::tests::open", TestStatus::Failed)], ""));
+        report.add(&output(&[("net::tests::fetch", TestStatus::Failed), ("db::tests::open", TestStatus::Failed)], refused));
+        report.add(&output(
+            &[("net::tests::fetch", TestStatus::Failed), ("db::tests::open", TestStatus::Failed)],
+            &timeout.replace("3012", "3007"),
+        ));
+
+        let flaky = report.flaky();
+        assert_eq!(flaky.len(), 1);
+        assert_eq!((flaky[0].passed, flaky[0].failed), (1, 3));
+        // Both timeouts share a signature despite the different durations
+        assert_eq!(flaky[0].failures.len(), 2);
+        assert_eq!(flaky[0].failures[0].count, 2);
+        assert_eq!(flaky[0].failures[0].message, "request timed out after 3012 ms");
+        assert!(flaky[0].failures[1].message.starts_with("'connection refused"));
+
+        assert_eq!(report.always_failing().len(), 1);
+        assert!(report.triage_prompt().unwrap().contains("net::tests::fetch: failed 3 of 4 runs"));
+    }
+
+    #[tokio::test]
+    async fn test_history() {
+        let db = Database::in_memory().await.unwrap();
+        let mut report = FlakyReport::default();
+        report.add(&output(&[("a", TestStatus::Passed), ("b", TestStatus::Passed)], ""));
+        report.add(&output(&[("a", TestStatus::Failed), ("b", TestStatus::Passed)], ""));
+        record_flakiness(&db, "/srv/app", &report, 10).await.unwrap();
+
+        let history = record(&db, "/srv/app", &report, 10).await.unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!((history[0].test.as_str(), history[0].runs, history[0].failures), ("a", 4, 2));
+        assert!(db.get_flaky_tests("/srv/other", 10).await.unwrap().is_empty());
+    }
+}
@@ -1,10 +1,12 @@
 //! Code formatter tool - Format code in various languages
 
+use crate::ast::detect;
 use serde::{Deserialize, Serialize};
 use std::path::{Path, PathBuf};
 use std::process::Stdio;
 use tokio::fs;
 use tokio::process::Command;
+use super::command::output_in_group;
 
 /// Supported languages for formatting
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -130,6 +132,38 @@ impl FormatterTool {
         }
     }
 
+    /// Detect language from the file content (shebang, modeline, ambiguous
+    /// extensions), falling back to the extension
+    pub fn detect_file_language(path: &Path) -> Option<FormatLanguage> {
+        let head = detect::read_head(path).unwrap_or_default();
+        match detect::from_content(path, &head) {
+            // A bash script named `.py` must not go through black
+            Some(language) => Self::language_from_name(language),
+            None => Self::detect_language(path),
+        }
+    }
+
+    fn language_from_name(name: &str) -> Option<FormatLanguage> {
+        match name {
+            "Rust" => Some(FormatLanguage::Rust),
+            "Python" => Some(FormatLanguage::Python),
+            "JavaScript" => Some(FormatLanguage::JavaScript),
+            "TypeScript" => Some(FormatLanguage::TypeScript),
+            "Go" => Some(FormatLanguage::Go),
+            "Java" => Some(FormatLanguage::Java),
+            "C++" => Some(FormatLanguage::Cpp),
+            "C" => Some(FormatLanguage::C),
+            "JSON" => Some(FormatLanguage::Json),
+            "YAML" => Some(FormatLanguage::Yaml),
+            "TOML" => Some(FormatLanguage::Toml),
+            "Markdown" => Some(FormatLanguage::Markdown),
+            "HTML" => Some(FormatLanguage::Html),
+            "CSS" => Some(FormatLanguage::Css),
+            "SQL" => Some(FormatLanguage::Sql),
+            _ => None,
+        }
+    }
+
     /// Format a file or directory
     pub async fn format(&self, args: FormatArgs) -> Result<FormatOutput, FormatError> {
         let path = PathBuf::from(&args.path);
@@ -168,7 +202,7 @@ impl FormatterTool {
         let language = args
             .language
             .clone()
-            .or_else(|| Self::detect_language(path));
+            .or_else(|| Self::detect_file_language(path));
 
         let Some(lang) = language else {
             return FormatResult {
@@ -227,7 +261,7 @@ impl FormatterTool {
             .map_err(|e| FormatError::IoError(e.to_string()))?
         {
             let entry_path = entry.path();
-            if entry_path.is_file() && Self::detect_language(&entry_path).is_some() {
+            if entry_path.is_file() && Self::detect_file_language(&entry_path).is_some() {
                 let result = self.format_file(&entry_path, args).await;
                 results.push(result);
             }
@@ -266,7 +300,7 @@ impl FormatterTool {
 
             if entry_path.is_dir() {
                 Box::pin(self.format_dir_recursive(&entry_path, args, results)).await?;
-            } else if entry_path.is_file() && Self::detect_language(&entry_path).is_some() {
+            } else if entry_path.is_file() && Self::detect_file_language(&entry_path).is_some() {
                 let result = self.format_file(&entry_path, args).await;
                 results.push(result);
             }
@@ -292,8 +326,7 @@ impl FormatterTool {
         cmd.stdout(Stdio::piped());
         cmd.stderr(Stdio::piped());
 
-        let output = cmd
-            .output()
+        let output = output_in_group(&mut cmd)
             .await
             .map_err(|e| FormatError::FormatterError(format!("rustfmt: {}", e)))?;
 
@@ -331,8 +364,7 @@ impl FormatterTool {
         cmd.stdout(Stdio::piped());
         cmd.stderr(Stdio::piped());
 
-        let output = cmd
-            .output()
+        let output = output_in_group(&mut cmd)
             .await
             .map_err(|e| FormatError::FormatterError(format!("black: {}", e)))?;
 
@@ -388,8 +420,7 @@ impl FormatterTool {
         cmd.stdout(Stdio::piped());
         cmd.stderr(Stdio::piped());
 
-        let output = cmd
-            .output()
+        let output = output_in_group(&mut cmd)
             .await
             .map_err(|e| FormatError::FormatterError(format!("prettier: {}", e)))?;
 
@@ -417,8 +448,7 @@ impl FormatterTool {
         cmd.stdout(Stdio::piped());
         cmd.stderr(Stdio::piped());
 
-        let output = cmd
-            .output()
+        let output = output_in_group(&mut cmd)
             .await
             .map_err(|e| FormatError::FormatterError(format!("gofmt: {}", e)))?;
 
@@ -539,8 +569,7 @@ impl FormatterTool {
         cmd.stdout(Stdio::piped());
         cmd.stderr(Stdio::piped());
 
-        let output = cmd
-            .output()
+        let output = output_in_group(&mut cmd)
             .await
             .map_err(|e| FormatError::FormatterError(format!("prettier: {}", e)))?;
 
@@ -614,6 +643,22 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_detect_file_language_from_content() {
+        let dir = tempfile::tempdir().unwrap();
+        let script = dir.path().join("build");
+        std::fs::write(&script, "#!/usr/bin/env python3\nprint('ok')\n").unwrap();
+        assert_eq!(
+            FormatterTool::detect_file_language(&script),
+            Some(FormatLanguage::Python)
+        );
+
+        // Shell saved with a Python extension is left alone
+        let misnamed = dir.path().join("setup_env.py");
+        std::fs::write(&misnamed, "#!/bin/bash\nexport A=1\n").unwrap();
+        assert_eq!(FormatterTool::detect_file_language(&misnamed), None);
+    }
+
     #[tokio::test]
     async fn test_format_json_string() {
         let formatter = FormatterTool::new();
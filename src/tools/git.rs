@@ -1,8 +1,39 @@
 //! Git tool - Git operations and history analysis
 
+use regex::Regex;
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
 use std::process::Command;
+use std::sync::LazyLock;
+
+/// Lockfiles, whose diffs are collapsed to the packages they touch
+const LOCKFILES: &[&str] = &[
+    "Cargo.lock",
+    "package-lock.json",
+    "npm-shrinkwrap.json",
+    "yarn.lock",
+    "pnpm-lock.yaml",
+    "bun.lockb",
+    "poetry.lock",
+    "Pipfile.lock",
+    "uv.lock",
+    "Gemfile.lock",
+    "composer.lock",
+    "go.sum",
+    "flake.lock",
+    "mix.lock",
+    "pubspec.lock",
+];
+/// Markers generators leave at the top of their output
+const GENERATED_MARKERS: &[&str] = &["@generated", "DO NOT EDIT", "auto-generated", "autogenerated"];
+/// Packages named in a collapsed lockfile summary
+const MAX_SUMMARY_PACKAGES: usize = 8;
+
+/// `name = "serde"` (Cargo, Poetry, uv) or `"node_modules/serde": {` (npm)
+static PACKAGE_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"^\s*(?:name = "([^"]+)"|"(?:.*/)?node_modules/([^"]+)": \{)"#).unwrap()
+});
 
 /// Git status output
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -53,6 +84,63 @@ pub struct DiffOutput {
     pub total_deletions: usize,
 }
 
+impl DiffOutput {
+    /// Files with real code changes (not lockfiles, generated or binary)
+    pub fn code_files(&self) -> impl Iterator<Item = &FileDiff> {
+        self.files.iter().filter(|f| f.kind == DiffFileKind::Code)
+    }
+
+    /// Per-file stats followed by the hunks of code files; lockfile, generated
+    /// and binary changes are one line each
+    pub fn format(&self) -> String {
+        let collapsed = self.files.len() - self.code_files().count();
+        let mut output = format!(
+            "{} files changed, +{} -{}",
+            self.files.len(),
+            self.total_additions,
+            self.total_deletions
+        );
+        if collapsed > 0 {
+            output.push_str(&format!(" ({} collapsed)", collapsed));
+        }
+        output.push_str("\n\n");
+
+        let width = self.files.iter().map(|f| f.path.len()).max().unwrap_or(0);
+        for file in &self.files {
+            let stats = match file.kind {
+                DiffFileKind::Binary => "binary".to_string(),
+                _ => format!("+{} -{}", file.additions, file.deletions),
+            };
+            output.push_str(&format!(" {:width$} | {}", file.path, stats, width = width));
+            if let Some(summary) = &file.summary {
+                output.push_str(&format!("  ({})", summary));
+            }
+            output.push('\n');
+        }
+
+        for file in self.code_files() {
+            output.push_str(&format!("\n--- {}\n", file.path));
+            for hunk in &file.hunks {
+                output.push_str(&format!(
+                    "@@ -{},{} +{},{} @@\n{}",
+                    hunk.old_start, hunk.old_lines, hunk.new_start, hunk.new_lines, hunk.content
+                ));
+            }
+        }
+        output
+    }
+}
+
+/// What a changed file is, deciding whether its hunks are worth showing
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DiffFileKind {
+    #[default]
+    Code,
+    Lockfile,
+    Generated,
+    Binary,
+}
+
 /// File diff
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FileDiff {
@@ -60,6 +148,11 @@ pub struct FileDiff {
     pub additions: usize,
     pub deletions: usize,
     pub hunks: Vec<DiffHunk>,
+    #[serde(default)]
+    pub kind: DiffFileKind,
+    /// One-line description replacing the hunks of collapsed files
+    #[serde(default)]
+    pub summary: Option<String>,
 }
 
 /// Diff hunk
@@ -203,6 +296,11 @@ impl GitTool {
     pub async fn diff(&self, args: GitDiffArgs) -> Result<DiffOutput, GitError> {
         let path = PathBuf::from(&args.path);
 
+        // A revision that starts with '-' is an option, e.g. `--output=<file>` writes a file
+        if let Some(commit) = args.commit.as_deref().filter(|c| c.starts_with('-')) {
+            return Err(GitError::InvalidArgs(format!("not a revision: {}", commit)));
+        }
+
         if !is_git_repo(&path) {
             return Err(GitError::NotAGitRepo);
         }
@@ -217,6 +315,10 @@ impl GitTool {
             cmd_args.push(commit);
         }
 
+        if let Some(ref file) = args.file {
+            cmd_args.extend(["--", file.as_str()]);
+        }
+
         let output = run_git_command(&path, &cmd_args)?;
 
         parse_diff_output(&output)
@@ -398,14 +500,18 @@ pub enum GitError {
     CommandFailed(String),
     #[error("IO error: {0}")]
     IoError(#[from] std::io::Error),
+    #[error("Unknown git action: {0}")]
+    UnknownAction(String),
+    #[error("Invalid arguments: {0}")]
+    InvalidArgs(String),
 }
 
-fn is_git_repo(path: &PathBuf) -> bool {
+pub(super) fn is_git_repo(path: &PathBuf) -> bool {
     let git_dir = path.join(".git");
     git_dir.exists() || run_git_command(path, &["rev-parse", "--git-dir"]).is_ok()
 }
 
-fn run_git_command(path: &PathBuf, args: &[&str]) -> Result<String, GitError> {
+pub(super) fn run_git_command(path: &PathBuf, args: &[&str]) -> Result<String, GitError> {
     let output = Command::new("git")
         .current_dir(path)
         .args(args)
@@ -482,7 +588,15 @@ fn parse_diff_output(output: &str) -> Result<DiffOutput, GitError> {
                 additions: 0,
                 deletions: 0,
                 hunks: Vec::new(),
+                kind: DiffFileKind::Code,
+                summary: None,
             });
+        } else if current_hunk.is_none()
+            && (line.starts_with("Binary files ") || line == "GIT binary patch")
+        {
+            if let Some(ref mut file) = current_file {
+                file.kind = DiffFileKind::Binary;
+            }
         } else if line.starts_with("@@") {
             // New hunk
             if let Some(ref mut file) = current_file {
@@ -524,6 +638,8 @@ fn parse_diff_output(output: &str) -> Result<DiffOutput, GitError> {
         files.push(file);
     }
 
+    files.iter_mut().for_each(collapse_noise);
+
     Ok(DiffOutput {
         files,
         total_additions,
@@ -531,6 +647,98 @@ fn parse_diff_output(output: &str) -> Result<DiffOutput, GitError> {
     })
 }
 
+/// Classify a file and replace the hunks of lockfiles, generated and binary
+/// files with a one-line summary
+fn collapse_noise(file: &mut FileDiff) {
+    let name = Path::new(&file.path)
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    if file.kind == DiffFileKind::Code {
+        if LOCKFILES.contains(&name.as_str()) {
+            file.kind = DiffFileKind::Lockfile;
+        } else if is_generated(file, &name) {
+            file.kind = DiffFileKind::Generated;
+        }
+    }
+
+    file.summary = match file.kind {
+        DiffFileKind::Code => return,
+        DiffFileKind::Binary => Some("binary file".to_string()),
+        DiffFileKind::Generated => Some("generated file, hunks hidden".to_string()),
+        DiffFileKind::Lockfile => {
+            let packages = changed_packages(file);
+            Some(match packages.len() {
+                0 => "lockfile".to_string(),
+                n => {
+                    let shown: Vec<&str> =
+                        packages.iter().take(MAX_SUMMARY_PACKAGES).map(String::as_str).collect();
+                    let more = n.saturating_sub(MAX_SUMMARY_PACKAGES);
+                    format!(
+                        "lockfile, {} package{}: {}{}",
+                        n,
+                        if n == 1 { "" } else { "s" },
+                        shown.join(", "),
+                        if more > 0 { format!(" and {} more", more) } else { String::new() }
+                    )
+                }
+            })
+        }
+    };
+    file.hunks.clear();
+}
+
+/// Generated by path convention or by a marker in the first lines
+fn is_generated(file: &FileDiff, name: &str) -> bool {
+    let by_name = [".min.js", ".min.css", ".js.map", ".css.map", ".pb.go", "_pb2.py", "_pb2_grpc.py", ".g.dart", ".freezed.dart"]
+        .iter()
+        .any(|suffix| name.ends_with(suffix))
+        || name.contains(".generated.");
+    let by_dir = Path::new(&file.path).components().any(|c| {
+        matches!(c.as_os_str().to_str(), Some("__generated__" | "generated" | "dist"))
+    });
+    let by_marker = file.hunks.first().is_some_and(|hunk| {
+        hunk.new_start <= 3
+            && hunk
+                .content
+                .lines()
+                .take(10)
+                .any(|line| GENERATED_MARKERS.iter().any(|marker| line.contains(marker)))
+    });
+    by_name || by_dir || by_marker
+}
+
+/// Packages whose entries changed in a lockfile diff
+fn changed_packages(file: &FileDiff) -> BTreeSet<String> {
+    let go_sum = file.path.ends_with("go.sum");
+    let mut packages = BTreeSet::new();
+    for hunk in &file.hunks {
+        let mut current: Option<String> = None;
+        for line in hunk.content.lines() {
+            let changed = line.starts_with('+') || line.starts_with('-');
+            let text = line.get(1..).unwrap_or_default();
+            if go_sum {
+                if changed {
+                    if let Some(module) = text.split_whitespace().next() {
+                        packages.insert(module.to_string());
+                    }
+                }
+                continue;
+            }
+            if let Some(caps) = PACKAGE_RE.captures(text) {
+                current = caps.get(1).or_else(|| caps.get(2)).map(|m| m.as_str().to_string());
+            }
+            if changed {
+                if let Some(name) = &current {
+                    packages.insert(name.clone());
+                }
+            }
+        }
+    }
+    packages
+}
+
 fn parse_hunk_header(line: &str) -> (usize, usize, usize, usize) {
     // @@ -1,3 +1,4 @@
     let parts: Vec<&str> = line.split_whitespace().collect();
@@ -602,4 +810,70 @@ mod tests {
         assert_eq!(parse_range("1,3"), (1, 3));
         assert_eq!(parse_range("5"), (5, 1));
     }
+
+    #[test]
+    fn test_diff_collapses_lockfiles_generated_and_binary() {
+        let raw = "\
+diff --git a/src/main.rs b/src/main.rs
+--- a/src/main.rs
++++ b/src/main.rs
+@@ -1,2 +1,2 @@
+ fn main() {
+-    run();
++    run_all();
+diff --git a/Cargo.lock b/Cargo.lock
+--- a/Cargo.lock
++++ b/Cargo.lock
+@@ -10,7 +10,7 @@
+ [[package]]
+ name = \"serde\"
+-version = \"1.0.1\"
++version = \"1.0.2\"
+ [[package]]
+ name = \"tokio\"
+-version = \"1.40.0\"
++version = \"1.41.0\"
+diff --git a/assets/logo.png b/assets/logo.png
+index 1111111..2222222 100644
+Binary files a/assets/logo.png and b/assets/logo.png differ
+diff --git a/src/api.rs b/src/api.rs
+--- a/src/api.rs
++++ b/src/api.rs
+@@ -1,1 +1,2 @@
++// @generated by build.rs, DO NOT EDIT
+ pub const VERSION: u32 = 1;
+";
+        let diff = parse_diff_output(raw).unwrap();
+        let kinds: Vec<DiffFileKind> = diff.files.iter().map(|f| f.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![DiffFileKind::Code, DiffFileKind::Lockfile, DiffFileKind::Binary, DiffFileKind::Generated]
+        );
+        assert_eq!(diff.code_files().count(), 1);
+        assert_eq!(
+            diff.files[1].summary.as_deref(),
+            Some("lockfile, 2 packages: serde, tokio")
+        );
+        assert!(diff.files[1].hunks.is_empty());
+
+        let text = diff.format();
+        assert!(text.starts_with("4 files changed, +4 -3 (3 collapsed)"));
+        assert!(text.contains("assets/logo.png | binary"));
+        assert!(text.contains("+    run_all();"));
+        assert!(!text.contains("1.41.0"));
+    }
+
+    #[tokio::test]
+    async fn test_diff_rejects_options_as_revision() {
+        let dir = tempfile::tempdir().unwrap();
+        let output = dir.path().join("written.txt");
+        let args = GitDiffArgs {
+            path: dir.path().to_string_lossy().into_owned(),
+            commit: Some(format!("--output={}", output.display())),
+            staged: None,
+            file: None,
+        };
+        assert!(matches!(GitTool::new().diff(args).await, Err(GitError::InvalidArgs(_))));
+        assert!(!output.exists());
+    }
 }
@@ -0,0 +1,614 @@
+//! Stash, branch and rebase helpers for [`GitTool`]
+//!
+//! These change the working tree or the history, so every action is
+//! previewed first: without `confirm` the tool only reports the commands it
+//! would run and what they touch, and the orchestrator only sets it once the
+//! user approved that preview. The interactive-rebase planner suggests
+//! reorders and squashes from the commit messages (`fixup!` commits, "wip",
+//! repeated messages) and applies the plan without opening an editor.
+//! Confirmed runs are recorded in the session's command audit log by the
+//! orchestrator.
+
+use super::git::{is_git_repo, run_git_command, GitError, GitTool};
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Changed files listed in a stash preview
+const MAX_PREVIEW_FILES: usize = 20;
+/// Messages of follow-up commits folded into the previous one
+const TRIVIAL_MESSAGES: &[&str] = &[
+    "wip", "fix", "fixup", "typo", "fix typo", "oops", "fmt", "format", "cargo fmt", "lint",
+    "address review", "review fixes",
+];
+
+/// A stash, branch or rebase action
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GitAction {
+    StashSave,
+    StashPop,
+    BranchCreate,
+    BranchSwitch,
+    RebasePlan,
+    Rebase,
+}
+
+impl GitAction {
+    pub fn parse(action: &str) -> Result<Self, GitError> {
+        match action.trim().to_lowercase().replace('-', "_").as_str() {
+            "stash_save" | "stash" | "stash_push" => Ok(Self::StashSave),
+            "stash_pop" => Ok(Self::StashPop),
+            "branch_create" | "create_branch" => Ok(Self::BranchCreate),
+            "branch_switch" | "switch_branch" | "switch" | "checkout" => Ok(Self::BranchSwitch),
+            "rebase_plan" | "plan_rebase" => Ok(Self::RebasePlan),
+            "rebase" => Ok(Self::Rebase),
+            _ => Err(GitError::UnknownAction(action.to_string())),
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::StashSave => "stash_save",
+            Self::StashPop => "stash_pop",
+            Self::BranchCreate => "branch_create",
+            Self::BranchSwitch => "branch_switch",
+            Self::RebasePlan => "rebase_plan",
+            Self::Rebase => "rebase",
+        }
+    }
+
+    /// Everything but planning changes the repository
+    pub fn is_mutating(&self) -> bool {
+        *self != Self::RebasePlan
+    }
+}
+
+/// Arguments for the stash/branch/rebase helpers
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GitWorkflowArgs {
+    pub path: String,
+    /// stash_save, stash_pop, branch_create, branch_switch, rebase_plan or rebase
+    pub action: String,
+    /// Stash message, or the branch to create/switch to
+    #[serde(default)]
+    pub name: Option<String>,
+    /// Start point of a new branch, or the base of a rebase (default: upstream, then main/master)
+    #[serde(default)]
+    pub base: Option<String>,
+    /// Edited rebase plan, one `<action> <hash>` line per commit, oldest first
+    #[serde(default)]
+    pub plan: Vec<String>,
+    /// Set by the orchestrator once the user approved the preview
+    #[serde(default)]
+    pub confirm: bool,
+}
+
+/// What to do with a commit during a rebase
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RebaseAction {
+    Pick,
+    Reword,
+    Squash,
+    Fixup,
+    Drop,
+}
+
+impl RebaseAction {
+    pub fn parse(action: &str) -> Option<Self> {
+        match action {
+            "pick" | "p" => Some(Self::Pick),
+            "reword" | "r" => Some(Self::Reword),
+            "squash" | "s" => Some(Self::Squash),
+            "fixup" | "f" => Some(Self::Fixup),
+            "drop" | "d" => Some(Self::Drop),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Pick => "pick",
+            Self::Reword => "reword",
+            Self::Squash => "squash",
+            Self::Fixup => "fixup",
+            Self::Drop => "drop",
+        }
+    }
+
+    /// Folds the commit into the one before it
+    fn melds(&self) -> bool {
+        matches!(self, Self::Squash | Self::Fixup)
+    }
+}
+
+/// A commit between the rebase base and `HEAD`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RebaseCommit {
+    pub hash: String,
+    pub short_hash: String,
+    pub subject: String,
+}
+
+/// One line of a rebase plan
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RebaseStep {
+    pub action: RebaseAction,
+    pub commit: RebaseCommit,
+    /// Why the planner suggested something other than a plain pick
+    pub reason: Option<String>,
+}
+
+/// Rebase todo list, oldest commit first
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RebasePlan {
+    /// Commit the branch is replayed onto
+    pub base: String,
+    pub steps: Vec<RebaseStep>,
+}
+
+impl RebasePlan {
+    /// Suggest reorders and squashes from the commit messages
+    pub fn suggest(base: impl Into<String>, commits: Vec<RebaseCommit>) -> Self {
+        let mut steps: Vec<RebaseStep> = Vec::new();
+        for commit in commits {
+            let autosquash = commit
+                .subject
+                .strip_prefix("fixup! ")
+                .map(|target| (RebaseAction::Fixup, target))
+                .or_else(|| {
+                    commit
+                        .subject
+                        .strip_prefix("squash! ")
+                        .map(|target| (RebaseAction::Squash, target))
+                });
+
+            if let Some((action, target)) = autosquash {
+                if let Some(index) = steps.iter().rposition(|s| s.commit.subject == target) {
+                    let mut position = index + 1;
+                    while steps.get(position).is_some_and(|s| s.action.melds()) {
+                        position += 1;
+                    }
+                    let reason = format!("moved onto {} ({})", steps[index].commit.short_hash, target);
+                    steps.insert(position, RebaseStep { action, commit, reason: Some(reason) });
+                    continue;
+                }
+            }
+
+            let (action, reason) = match steps.last() {
+                Some(_) if is_trivial(&commit.subject) => (
+                    RebaseAction::Fixup,
+                    Some(format!("small follow-up (\"{}\")", commit.subject)),
+                ),
+                Some(previous) if previous.commit.subject == commit.subject => (
+                    RebaseAction::Squash,
+                    Some("same message as the previous commit".to_string()),
+                ),
+                _ => (RebaseAction::Pick, None),
+            };
+            steps.push(RebaseStep { action, commit, reason });
+        }
+        Self { base: base.into(), steps }
+    }
+
+    /// Plan from edited `<action> <hash>` lines, which must name every commit once
+    pub fn from_lines(
+        base: impl Into<String>,
+        commits: &[RebaseCommit],
+        lines: &[String],
+    ) -> Result<Self, GitError> {
+        let mut steps: Vec<RebaseStep> = Vec::new();
+        for line in lines.iter().map(|l| l.trim()).filter(|l| !l.is_empty()) {
+            let mut parts = line.split_whitespace();
+            let (Some(action), Some(hash)) = (parts.next(), parts.next()) else {
+                return Err(GitError::InvalidArgs(format!("bad plan line '{}'", line)));
+            };
+            let action = RebaseAction::parse(action)
+                .ok_or_else(|| GitError::InvalidArgs(format!("unknown rebase action '{}'", action)))?;
+            let matching: Vec<&RebaseCommit> = commits.iter().filter(|c| c.hash.starts_with(hash)).collect();
+            let [commit] = matching.as_slice() else {
+                return Err(GitError::InvalidArgs(format!(
+                    "'{}' does not name exactly one commit of the branch",
+                    hash
+                )));
+            };
+            if steps.iter().any(|s| s.commit.hash == commit.hash) {
+                return Err(GitError::InvalidArgs(format!("commit {} listed twice", commit.short_hash)));
+            }
+            steps.push(RebaseStep { action, commit: (*commit).clone(), reason: None });
+        }
+
+        if steps.len() != commits.len() {
+            return Err(GitError::InvalidArgs(format!(
+                "the plan lists {} of {} commits; use drop to remove one",
+                steps.len(),
+                commits.len()
+            )));
+        }
+        if steps.iter().find(|s| s.action != RebaseAction::Drop).is_some_and(|s| s.action.melds()) {
+            return Err(GitError::InvalidArgs(
+                "the first kept commit cannot be a squash or fixup".to_string(),
+            ));
+        }
+        Ok(Self { base: base.into(), steps })
+    }
+
+    /// Whether the plan keeps every commit as it is
+    pub fn is_noop(&self) -> bool {
+        self.steps.iter().all(|s| s.action == RebaseAction::Pick && s.reason.is_none())
+    }
+
+    /// Todo file for `git rebase -i`
+    pub fn todo(&self) -> String {
+        self.steps
+            .iter()
+            .map(|s| format!("{} {} {}\n", s.action.as_str(), s.commit.hash, s.commit.subject))
+            .collect()
+    }
+
+    pub fn render(&self) -> String {
+        let mut output = format!("Rebase onto {} ({} commits, oldest first):\n", short(&self.base), self.steps.len());
+        for (i, step) in self.steps.iter().enumerate() {
+            output.push_str(&format!(
+                "{:>3}. {:<6} {} {}",
+                i + 1,
+                step.action.as_str(),
+                step.commit.short_hash,
+                step.commit.subject
+            ));
+            if let Some(reason) = &step.reason {
+                output.push_str(&format!("  ← {}", reason));
+            }
+            output.push('\n');
+        }
+        output
+    }
+}
+
+fn is_trivial(subject: &str) -> bool {
+    let subject = subject.trim().trim_end_matches('.').to_lowercase();
+    TRIVIAL_MESSAGES.contains(&subject.as_str()) || subject.starts_with("wip:") || subject.starts_with("wip ")
+}
+
+fn short(hash: &str) -> &str {
+    hash.get(..7).unwrap_or(hash)
+}
+
+/// What an action would do, or did
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GitWorkflowOutput {
+    pub action: String,
+    /// Commands run (or to be run), for the preview and the audit log
+    pub commands: Vec<String>,
+    /// Files, stash entries and rebase steps the action touches
+    pub details: String,
+    pub executed: bool,
+    /// Git output of an executed action
+    pub output: String,
+}
+
+impl GitWorkflowOutput {
+    pub fn render(&self) -> String {
+        let mut text = String::new();
+        if !self.details.is_empty() {
+            text.push_str(&self.details);
+            if !self.details.ends_with('\n') {
+                text.push('\n');
+            }
+            text.push('\n');
+        }
+        if self.executed {
+            text.push_str(&format!("✓ {} done: {}\n", self.action, self.commands.join(" && ")));
+            if !self.output.trim().is_empty() {
+                text.push_str(self.output.trim_end());
+                text.push('\n');
+            }
+        } else if self.commands.is_empty() {
+            text.push_str("Apply it with action=rebase and confirm=true, which asks the user to \
+                           approve it (pass an edited plan to change it).\n");
+        } else {
+            text.push_str(&format!("Would run: {}\n", self.commands.join(" && ")));
+            text.push_str("Nothing was changed. Call again with confirm=true to ask the user to approve it.\n");
+        }
+        text
+    }
+}
+
+impl GitTool {
+    /// Preview a stash, branch or rebase action, and run it once confirmed
+    pub async fn workflow(&self, args: GitWorkflowArgs) -> Result<GitWorkflowOutput, GitError> {
+        let path = PathBuf::from(&args.path);
+        if !is_git_repo(&path) {
+            return Err(GitError::NotAGitRepo);
+        }
+        let action = GitAction::parse(&args.action)?;
+
+        let mut details = Vec::new();
+        let mut plan = None;
+        let commands: Vec<Vec<String>> = match action {
+            GitAction::StashSave => {
+                let changed = changed_files(&path)?;
+                if changed.is_empty() {
+                    return Err(GitError::InvalidArgs("nothing to stash".to_string()));
+                }
+                details.push(format!("Stashes {} changed files:", changed.len()));
+                details.extend(changed.iter().take(MAX_PREVIEW_FILES).map(|f| format!("  {}", f)));
+                if changed.len() > MAX_PREVIEW_FILES {
+                    details.push(format!("  ... and {} more", changed.len() - MAX_PREVIEW_FILES));
+                }
+                let message = args.name.clone().unwrap_or_else(|| "neuro: stash".to_string());
+                vec![strings(&["stash", "push", "--include-untracked", "-m", &message])]
+            }
+            GitAction::StashPop => {
+                let top = run_git_command(&path, &["stash", "list", "-1"])?;
+                if top.trim().is_empty() {
+                    return Err(GitError::InvalidArgs("no stash entries".to_string()));
+                }
+                details.push(format!("Restores {}", top.trim()));
+                details.push(run_git_command(&path, &["stash", "show", "--stat", "stash@{0}"])?.trim_end().to_string());
+                vec![strings(&["stash", "pop"])]
+            }
+            GitAction::BranchCreate | GitAction::BranchSwitch => {
+                let name = args
+                    .name
+                    .as_deref()
+                    .filter(|n| !n.trim().is_empty())
+                    .ok_or_else(|| GitError::InvalidArgs("branch name required".to_string()))?;
+                run_git_command(&path, &["check-ref-format", "--branch", name])
+                    .map_err(|_| GitError::InvalidArgs(format!("invalid branch name '{}'", name)))?;
+                let exists = branch_exists(&path, name);
+                let dirty = changed_files(&path)?.len();
+                if dirty > 0 {
+                    details.push(format!(
+                        "{} uncommitted files come along (a conflicting switch fails; stash_save first)",
+                        dirty
+                    ));
+                }
+                if action == GitAction::BranchCreate {
+                    if exists {
+                        return Err(GitError::InvalidArgs(format!("branch '{}' already exists", name)));
+                    }
+                    let mut command = strings(&["switch", "-c", name]);
+                    command.extend(args.base.clone());
+                    vec![command]
+                } else {
+                    if !exists {
+                        return Err(GitError::InvalidArgs(format!(
+                            "no branch '{}' (use branch_create)",
+                            name
+                        )));
+                    }
+                    vec![strings(&["switch", name])]
+                }
+            }
+            GitAction::RebasePlan | GitAction::Rebase => {
+                let base = rebase_base(&path, args.base.as_deref())?;
+                let commits = branch_commits(&path, &base)?;
+                if commits.is_empty() {
+                    return Err(GitError::InvalidArgs(format!("no commits after {}", short(&base))));
+                }
+                let rebase_plan = if args.plan.is_empty() {
+                    RebasePlan::suggest(base.clone(), commits)
+                } else {
+                    RebasePlan::from_lines(base.clone(), &commits, &args.plan)?
+                };
+                details.push(rebase_plan.render());
+                if rebase_plan.is_noop() {
+                    details.push("Nothing to reorder or squash.".to_string());
+                }
+                if is_pushed(&path, &rebase_plan) {
+                    details.push("⚠️ Rewrites commits already pushed: a force push will be needed.".to_string());
+                }
+                let dirty = action == GitAction::Rebase && !changed_files(&path)?.is_empty();
+                if dirty {
+                    return Err(GitError::InvalidArgs(
+                        "uncommitted changes: commit or stash_save them before rebasing".to_string(),
+                    ));
+                }
+                let command = strings(&["rebase", "-i", short(&base)]);
+                plan = Some(rebase_plan);
+                if action == GitAction::RebasePlan {
+                    Vec::new()
+                } else {
+                    vec![command]
+                }
+            }
+        };
+
+        let mut output = GitWorkflowOutput {
+            action: action.label().to_string(),
+            commands: commands.iter().map(|c| format!("git {}", c.join(" "))).collect(),
+            details: details.join("\n"),
+            executed: false,
+            output: String::new(),
+        };
+        if !action.is_mutating() || !args.confirm {
+            return Ok(output);
+        }
+
+        output.output = match (&plan, action) {
+            (Some(plan), GitAction::Rebase) => run_rebase(&path, plan)?,
+            _ => {
+                let mut text = String::new();
+                for command in &commands {
+                    let args: Vec<&str> = command.iter().map(String::as_str).collect();
+                    text.push_str(&run_git_command(&path, &args)?);
+                }
+                text
+            }
+        };
+        output.executed = true;
+        Ok(output)
+    }
+}
+
+fn strings(args: &[&str]) -> Vec<String> {
+    args.iter().map(|a| a.to_string()).collect()
+}
+
+/// `git status --porcelain` paths, untracked files included
+fn changed_files(path: &Path) -> Result<Vec<String>, GitError> {
+    Ok(run_git_command(&path.to_path_buf(), &["status", "--porcelain"])?
+        .lines()
+        .filter(|l| l.len() > 3)
+        .map(|l| l[3..].to_string())
+        .collect())
+}
+
+fn branch_exists(path: &Path, name: &str) -> bool {
+    run_git_command(
+        &path.to_path_buf(),
+        &["rev-parse", "--verify", "--quiet", &format!("refs/heads/{}", name)],
+    )
+    .is_ok()
+}
+
+/// Merge base with the given base, the upstream, or main/master
+fn rebase_base(path: &Path, base: Option<&str>) -> Result<String, GitError> {
+    let path = path.to_path_buf();
+    let candidates: Vec<&str> = match base {
+        Some(base) => vec![base],
+        None => vec!["@{upstream}", "main", "master"],
+    };
+    candidates
+        .into_iter()
+        .find_map(|candidate| run_git_command(&path, &["merge-base", "HEAD", candidate]).ok())
+        .map(|hash| hash.trim().to_string())
+        .ok_or_else(|| GitError::InvalidArgs("no base found; pass base=<branch or commit>".to_string()))
+}
+
+/// Commits after `base`, oldest first; merges are refused
+fn branch_commits(path: &Path, base: &str) -> Result<Vec<RebaseCommit>, GitError> {
+    let path = path.to_path_buf();
+    let range = format!("{}..HEAD", base);
+    if !run_git_command(&path, &["rev-list", "--merges", &range])?.trim().is_empty() {
+        return Err(GitError::InvalidArgs("the branch contains merge commits".to_string()));
+    }
+    Ok(run_git_command(&path, &["log", "--reverse", "--format=%H%x1f%h%x1f%s", &range])?
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.splitn(3, '\u{1f}');
+            Some(RebaseCommit {
+                hash: parts.next()?.to_string(),
+                short_hash: parts.next()?.to_string(),
+                subject: parts.next().unwrap_or_default().to_string(),
+            })
+        })
+        .collect())
+}
+
+/// Whether a remote branch already contains the oldest rewritten commit
+fn is_pushed(path: &Path, plan: &RebasePlan) -> bool {
+    plan.steps.first().is_some_and(|step| {
+        run_git_command(&path.to_path_buf(), &["branch", "-r", "--contains", &step.commit.hash])
+            .is_ok_and(|out| !out.trim().is_empty())
+    })
+}
+
+/// Run `git rebase -i` with the plan as todo list and no editor; a failed
+/// rebase is aborted so the history is left as it was
+fn run_rebase(path: &Path, plan: &RebasePlan) -> Result<String, GitError> {
+    let mut todo = tempfile::NamedTempFile::new()?;
+    todo.write_all(plan.todo().as_bytes())?;
+    todo.flush()?;
+    let todo_path = todo.path().to_string_lossy().replace('\'', r"'\''");
+
+    let output = Command::new("git")
+        .current_dir(path)
+        .args(["rebase", "-i", &plan.base])
+        .env("GIT_SEQUENCE_EDITOR", format!("cp '{}'", todo_path))
+        // Squash messages keep git's combined message
+        .env("GIT_EDITOR", "true")
+        .output()?;
+
+    if output.status.success() {
+        return Ok(String::from_utf8_lossy(&output.stderr).to_string());
+    }
+    let _ = run_git_command(&path.to_path_buf(), &["rebase", "--abort"]);
+    Err(GitError::CommandFailed(format!(
+        "{} (rebase aborted, history unchanged)",
+        String::from_utf8_lossy(&output.stderr).trim()
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn commit(hash: &str, subject: &str) -> RebaseCommit {
+        RebaseCommit {
+            hash: format!("{}000000", hash),
+            short_hash: hash.to_string(),
+            subject: subject.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_suggest_moves_fixups_and_folds_followups() {
+        let plan = RebasePlan::suggest(
+            "base",
+            vec![
+                commit("a1", "Add parser"),
+                commit("b2", "Add lexer"),
+                commit("c3", "wip"),
+                commit("d4", "fixup! Add parser"),
+                commit("e5", "Add lexer"),
+            ],
+        );
+        let order: Vec<(&str, &str)> = plan
+            .steps
+            .iter()
+            .map(|s| (s.action.as_str(), s.commit.short_hash.as_str()))
+            .collect();
+
+        assert_eq!(
+            order,
+            vec![("pick", "a1"), ("fixup", "d4"), ("pick", "b2"), ("fixup", "c3"), ("pick", "e5")]
+        );
+        assert!(!plan.is_noop());
+        assert!(plan.todo().starts_with("pick a1000000 Add parser\nfixup d4000000"));
+    }
+
+    #[test]
+    fn test_edited_plan_must_cover_every_commit() {
+        let commits = vec![commit("a1", "one"), commit("b2", "two")];
+        let lines = |l: &[&str]| l.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+
+        assert!(RebasePlan::from_lines("base", &commits, &lines(&["pick b2", "squash a1"])).is_ok());
+        assert!(RebasePlan::from_lines("base", &commits, &lines(&["pick a1"])).is_err());
+        assert!(RebasePlan::from_lines("base", &commits, &lines(&["fixup a1", "pick b2"])).is_err());
+        assert!(RebasePlan::from_lines("base", &commits, &lines(&["pick a1", "pick zz"])).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_rebase_is_previewed_then_applied() {
+        let dir = tempdir().unwrap();
+        let repo = dir.path().to_path_buf();
+        let git = |args: &[&str]| run_git_command(&repo, args).unwrap();
+        git(&["init", "-q", "-b", "main"]);
+        git(&["config", "user.name", "Test"]);
+        git(&["config", "user.email", "test@example.com"]);
+        for (file, subject) in [("a", "base"), ("b", "Add b"), ("c", "Add c"), ("b2", "fixup! Add b")] {
+            std::fs::write(repo.join(file), subject).unwrap();
+            git(&["add", "."]);
+            git(&["commit", "-q", "-m", subject]);
+        }
+        let base = git(&["rev-list", "--max-parents=0", "HEAD"]).trim().to_string();
+        let args = GitWorkflowArgs {
+            path: repo.to_string_lossy().to_string(),
+            action: "rebase".to_string(),
+            base: Some(base),
+            ..Default::default()
+        };
+
+        let preview = GitTool::new().workflow(args.clone()).await.unwrap();
+        assert!(!preview.executed);
+        assert!(preview.render().contains("confirm=true"));
+        assert_eq!(git(&["rev-list", "--count", "HEAD"]).trim(), "4");
+
+        let applied = GitTool::new().workflow(GitWorkflowArgs { confirm: true, ..args }).await.unwrap();
+        assert!(applied.executed);
+        assert_eq!(git(&["log", "--format=%s"]), "Add c\nAdd b\nbase\n");
+    }
+}
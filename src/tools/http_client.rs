@@ -1,11 +1,21 @@
 //! HTTP Client tool - Make HTTP requests
-
+//!
+//! Supports named auth profiles from the config, retries with backoff for
+//! idempotent methods, a cap on the response size and saving the body to a file.
+
+use crate::security::network;
+use crate::security::privacy;
+use crate::security::NetworkError;
+use rig::tool::Tool;
 use serde::{Deserialize, Serialize};
+use serde_json::json;
 use std::collections::HashMap;
+use std::path::{Component, Path, PathBuf};
 use std::time::Duration;
 
 /// HTTP method
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+#[serde(rename_all = "UPPERCASE")]
 pub enum HttpMethod {
     #[default]
     Get,
@@ -17,8 +27,118 @@ pub enum HttpMethod {
     Options,
 }
 
+impl HttpMethod {
+    /// Methods that can be repeated without side effects (RFC 9110)
+    pub fn is_idempotent(&self) -> bool {
+        !matches!(self, HttpMethod::Post | HttpMethod::Patch)
+    }
+}
+
+/// Default number of retries for idempotent requests
+pub const DEFAULT_MAX_RETRIES: u32 = 2;
+/// Default cap on the response body kept in memory
+pub const DEFAULT_MAX_RESPONSE_BYTES: usize = 1024 * 1024;
+/// Delay before the first retry, doubled on each attempt
+const RETRY_BASE_DELAY_MS: u64 = 250;
+
+/// Named credentials for `http_request`, configured under `http_profiles`.
+///
+/// Secret values follow the same rule as model API keys: an all-uppercase
+/// value is read from the environment variable of that name when the request
+/// is made, so the config file never has to hold the secret itself.
+///
+/// `hosts` lists where the credentials may be sent (`*.domain` for its
+/// subdomains); a profile without hosts is never attached to a request.
+#[derive(Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum HttpAuthProfile {
+    Bearer {
+        token: String,
+        #[serde(default)]
+        hosts: Vec<String>,
+    },
+    Basic {
+        username: String,
+        password: String,
+        #[serde(default)]
+        hosts: Vec<String>,
+    },
+    Headers {
+        headers: HashMap<String, String>,
+        #[serde(default)]
+        hosts: Vec<String>,
+    },
+}
+
+impl std::fmt::Debug for HttpAuthProfile {
+    /// Never print secrets
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HttpAuthProfile::Bearer { .. } => write!(f, "Bearer(***)"),
+            HttpAuthProfile::Basic { username, .. } => write!(f, "Basic({}:***)", username),
+            HttpAuthProfile::Headers { headers, .. } => {
+                let mut names: Vec<_> = headers.keys().collect();
+                names.sort();
+                write!(f, "Headers({:?})", names)
+            }
+        }
+    }
+}
+
+impl HttpAuthProfile {
+    /// Hosts the credentials may be sent to
+    pub fn hosts(&self) -> &[String] {
+        match self {
+            HttpAuthProfile::Bearer { hosts, .. }
+            | HttpAuthProfile::Basic { hosts, .. }
+            | HttpAuthProfile::Headers { hosts, .. } => hosts,
+        }
+    }
+
+    /// Whether the credentials may be sent to `url`
+    pub fn allows(&self, url: &reqwest::Url) -> bool {
+        url.host_str()
+            .is_some_and(|host| self.hosts().iter().any(|entry| network::host_matches(host, entry)))
+    }
+
+    /// Headers to add to the request, with secrets resolved
+    pub fn resolve_headers(&self) -> Result<Vec<(String, String)>, HttpError> {
+        use base64::Engine;
+
+        match self {
+            HttpAuthProfile::Bearer { token, .. } => Ok(vec![(
+                "Authorization".to_string(),
+                format!("Bearer {}", resolve_secret(token)?),
+            )]),
+            HttpAuthProfile::Basic { username, password, .. } => {
+                let credentials = format!("{}:{}", resolve_secret(username)?, resolve_secret(password)?);
+                Ok(vec![(
+                    "Authorization".to_string(),
+                    format!(
+                        "Basic {}",
+                        base64::engine::general_purpose::STANDARD.encode(credentials)
+                    ),
+                )])
+            }
+            HttpAuthProfile::Headers { headers, .. } => headers
+                .iter()
+                .map(|(k, v)| Ok((k.clone(), resolve_secret(v)?)))
+                .collect(),
+        }
+    }
+}
+
+fn resolve_secret(value: &str) -> Result<String, HttpError> {
+    if !value.is_empty() && value.chars().all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_') {
+        std::env::var(value)
+            .map_err(|_| HttpError::ClientError(format!("Environment variable {} is not set", value)))
+    } else {
+        Ok(value.to_string())
+    }
+}
+
 /// HTTP request arguments
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct HttpRequestArgs {
     pub url: String,
     #[serde(default)]
@@ -28,6 +148,103 @@ pub struct HttpRequestArgs {
     pub json: Option<serde_json::Value>,
     pub timeout_secs: Option<u64>,
     pub follow_redirects: Option<bool>,
+    /// Name of an auth profile from `http_profiles`
+    #[serde(default)]
+    pub profile: Option<String>,
+    /// Retries for idempotent methods (default: [`DEFAULT_MAX_RETRIES`])
+    #[serde(default)]
+    pub max_retries: Option<u32>,
+    /// Body bytes kept before truncating (default: [`DEFAULT_MAX_RESPONSE_BYTES`])
+    #[serde(default)]
+    pub max_response_bytes: Option<usize>,
+    /// Also write the (possibly truncated) body to this file
+    #[serde(default)]
+    pub save_to: Option<String>,
+}
+
+impl HttpRequestArgs {
+    /// Substitute `$VAR` and `${VAR}` in the url, headers and body with
+    /// session variables. Unknown names are left as written.
+    pub fn expand_env(&mut self, env: &HashMap<String, String>) {
+        if env.is_empty() {
+            return;
+        }
+        self.url = expand_vars(&self.url, env);
+        if let Some(headers) = self.headers.as_mut() {
+            for value in headers.values_mut() {
+                *value = expand_vars(value, env);
+            }
+        }
+        if let Some(body) = self.body.as_mut() {
+            *body = expand_vars(body, env);
+        }
+    }
+}
+
+fn expand_vars(input: &str, env: &HashMap<String, String>) -> String {
+    let mut out = String::with_capacity(input.len());
+    let mut rest = input;
+
+    while let Some(pos) = rest.find('$') {
+        out.push_str(&rest[..pos]);
+        let after = &rest[pos + 1..];
+
+        let (name, consumed) = if let Some(braced) = after.strip_prefix('{') {
+            match braced.find('}') {
+                Some(end) => (&braced[..end], end + 2),
+                None => ("", 0),
+            }
+        } else {
+            let end = after
+                .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+                .unwrap_or(after.len());
+            (&after[..end], end)
+        };
+
+        match env.get(name) {
+            Some(value) if !name.is_empty() => {
+                out.push_str(value);
+                rest = &after[consumed..];
+            }
+            _ => {
+                out.push('$');
+                rest = after;
+            }
+        }
+    }
+
+    out.push_str(rest);
+    out
+}
+
+/// Resolve a `save_to` path against the working directory. Paths that end up
+/// outside of it are rejected, like the agent's other file writes.
+pub fn resolve_save_path(working_dir: &Path, path: &str) -> Result<PathBuf, HttpError> {
+    let root = normalize(working_dir);
+    let resolved = normalize(&root.join(path));
+    if resolved.starts_with(&root) && resolved != root {
+        Ok(resolved)
+    } else {
+        Err(HttpError::IoError(format!(
+            "save_to must be a file inside the working directory: {}",
+            path
+        )))
+    }
+}
+
+/// Lexically resolve `.` and `..` components
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            other => normalized.push(other),
+        }
+    }
+    normalized
 }
 
 /// HTTP response
@@ -41,12 +258,22 @@ pub struct HttpResponse {
     pub elapsed_ms: u64,
     pub url: String,
     pub redirected: bool,
+    /// Requests sent, including retries
+    #[serde(default)]
+    pub attempts: u32,
+    /// The body was cut at `max_response_bytes`
+    #[serde(default)]
+    pub truncated: bool,
+    /// File the body was written to
+    #[serde(default)]
+    pub saved_to: Option<String>,
 }
 
 /// HTTP client tool
 #[derive(Debug, Clone)]
 pub struct HttpClientTool {
     user_agent: String,
+    profiles: HashMap<String, HttpAuthProfile>,
 }
 
 impl Default for HttpClientTool {
@@ -56,24 +283,93 @@ impl Default for HttpClientTool {
 }
 
 impl HttpClientTool {
-    pub const NAME: &'static str = "http_request";
-
     pub fn new() -> Self {
         Self {
             user_agent: format!("neuro-agent/{}", env!("CARGO_PKG_VERSION")),
+            profiles: HashMap::new(),
         }
     }
 
-    /// Make an HTTP request
+    /// Use the auth profiles configured under `http_profiles`
+    pub fn with_profiles(mut self, profiles: HashMap<String, HttpAuthProfile>) -> Self {
+        self.profiles = profiles;
+        self
+    }
+
+    /// Names of the configured auth profiles, sorted
+    pub fn profile_names(&self) -> Vec<String> {
+        let mut names: Vec<_> = self.profiles.keys().cloned().collect();
+        names.sort();
+        names
+    }
+
+    /// Auth profile `name`, if its credentials may be sent to `url`
+    fn profile_for(&self, name: &str, url: &str) -> Result<&HttpAuthProfile, HttpError> {
+        let profile = self
+            .profiles
+            .get(name)
+            .ok_or_else(|| HttpError::ClientError(format!("Unknown auth profile: {}", name)))?;
+        let url = reqwest::Url::parse(url).map_err(|e| HttpError::ClientError(e.to_string()))?;
+        if !profile.allows(&url) {
+            return Err(HttpError::ClientError(format!(
+                "Auth profile {} is not allowed for {}; add the host to the profile's `hosts`",
+                name,
+                url.host_str().unwrap_or_default()
+            )));
+        }
+        Ok(profile)
+    }
+
+    /// Make an HTTP request, retrying idempotent methods on transient failures
     pub async fn request(&self, args: HttpRequestArgs) -> Result<HttpResponse, HttpError> {
-        let client = reqwest::Client::builder()
+        let max_retries = if args.method.is_idempotent() {
+            args.max_retries.unwrap_or(DEFAULT_MAX_RETRIES)
+        } else {
+            0
+        };
+
+        let mut attempt = 0;
+        loop {
+            attempt += 1;
+            let retries_left = attempt <= max_retries;
+
+            match self.send_once(&args).await {
+                Ok(response) if retries_left && is_retryable_status(response.status) => {}
+                Ok(mut response) => {
+                    response.attempts = attempt;
+                    if let Some(path) = &args.save_to {
+                        tokio::fs::write(path, &response.body)
+                            .await
+                            .map_err(|e| HttpError::IoError(e.to_string()))?;
+                        response.saved_to = Some(path.clone());
+                    }
+                    return Ok(response);
+                }
+                Err(HttpError::RequestError(_)) if retries_left => {}
+                Err(e) => return Err(e),
+            }
+
+            let delay = RETRY_BASE_DELAY_MS << (attempt - 1).min(6);
+            tokio::time::sleep(Duration::from_millis(delay)).await;
+        }
+    }
+
+    async fn send_once(&self, args: &HttpRequestArgs) -> Result<HttpResponse, HttpError> {
+        let profile = args.profile.as_ref().map(|name| self.profile_for(name, &args.url)).transpose()?;
+        network::check(&args.url, "http_request")?;
+
+        // The shared builder re-checks every redirect against the network policy
+        let mut builder = network::client_builder()
             .user_agent(&self.user_agent)
-            .timeout(Duration::from_secs(args.timeout_secs.unwrap_or(30)))
-            .redirect(if args.follow_redirects.unwrap_or(true) {
-                reqwest::redirect::Policy::limited(10)
-            } else {
-                reqwest::redirect::Policy::none()
-            })
+            .timeout(Duration::from_secs(args.timeout_secs.unwrap_or(30)));
+        if !args.follow_redirects.unwrap_or(true) {
+            builder = builder.redirect(reqwest::redirect::Policy::none());
+        } else if let Some(profile) = &profile {
+            // Credentials never follow a redirect to a host outside the profile
+            let profile = profile.clone();
+            builder = builder.redirect(network::redirect_policy(move |url| profile.allows(url)));
+        }
+        let client = builder
             .build()
             .map_err(|e| HttpError::ClientError(e.to_string()))?;
 
@@ -87,26 +383,32 @@ impl HttpClientTool {
             HttpMethod::Options => client.request(reqwest::Method::OPTIONS, &args.url),
         };
 
+        // Profile headers first so explicit headers can override them
+        if let Some(profile) = &profile {
+            for (key, value) in profile.resolve_headers()? {
+                request = request.header(key, value);
+            }
+        }
+
         // Add headers
-        if let Some(headers) = args.headers {
+        if let Some(headers) = &args.headers {
             for (key, value) in headers {
-                request = request.header(&key, &value);
+                request = request.header(key, value);
             }
         }
 
-        // Add body
-        if let Some(json) = args.json {
-            request = request.json(&json);
-        } else if let Some(body) = args.body {
-            request = request.body(body);
+        // Add body, with secrets masked in redacted mode
+        if let Some(json) = &args.json {
+            request = request.json(&privacy::redact_json_for(&args.url, json));
+        } else if let Some(body) = &args.body {
+            request = request.body(privacy::redact_for(&args.url, body).into_owned());
         }
 
         let start = std::time::Instant::now();
-        let response = request
+        let mut response = request
             .send()
             .await
             .map_err(|e| HttpError::RequestError(e.to_string()))?;
-        let elapsed_ms = start.elapsed().as_millis() as u64;
 
         let status = response.status().as_u16();
         let status_text = response
@@ -125,14 +427,32 @@ impl HttpClientTool {
             }
         }
 
-        // Get body
-        let body = response
-            .text()
+        // Read the body up to the size limit
+        let limit = args.max_response_bytes.unwrap_or(DEFAULT_MAX_RESPONSE_BYTES);
+        let mut bytes = Vec::new();
+        let mut truncated = false;
+        while let Some(chunk) = response
+            .chunk()
             .await
-            .map_err(|e| HttpError::ResponseError(e.to_string()))?;
+            .map_err(|e| HttpError::ResponseError(e.to_string()))?
+        {
+            let room = limit - bytes.len();
+            if chunk.len() > room {
+                bytes.extend_from_slice(&chunk[..room]);
+                truncated = true;
+                break;
+            }
+            bytes.extend_from_slice(&chunk);
+        }
+        let elapsed_ms = start.elapsed().as_millis() as u64;
+        let body = String::from_utf8_lossy(&bytes).into_owned();
 
-        // Try to parse as JSON
-        let body_json = serde_json::from_str(&body).ok();
+        // Try to parse as JSON (a truncated body never is)
+        let body_json = if truncated {
+            None
+        } else {
+            serde_json::from_str(&body).ok()
+        };
 
         Ok(HttpResponse {
             status,
@@ -143,6 +463,9 @@ impl HttpClientTool {
             elapsed_ms,
             url: final_url,
             redirected,
+            attempts: 1,
+            truncated,
+            saved_to: None,
         })
     }
 
@@ -156,6 +479,7 @@ impl HttpClientTool {
             json: None,
             timeout_secs: None,
             follow_redirects: None,
+            ..Default::default()
         })
         .await
     }
@@ -174,13 +498,15 @@ impl HttpClientTool {
             json: Some(json),
             timeout_secs: None,
             follow_redirects: None,
+            ..Default::default()
         })
         .await
     }
 
     /// Download a file
     pub async fn download(&self, url: &str, path: &str) -> Result<DownloadResult, HttpError> {
-        let client = reqwest::Client::builder()
+        network::check(url, "download")?;
+        let client = network::client_builder()
             .user_agent(&self.user_agent)
             .build()
             .map_err(|e| HttpError::ClientError(e.to_string()))?;
@@ -236,6 +562,11 @@ pub struct DownloadResult {
     pub content_length: Option<u64>,
 }
 
+/// Statuses worth retrying: rate limiting and gateway/availability errors
+fn is_retryable_status(status: u16) -> bool {
+    matches!(status, 429 | 502 | 503 | 504)
+}
+
 /// HTTP client errors
 #[derive(Debug, thiserror::Error)]
 pub enum HttpError {
@@ -249,6 +580,73 @@ pub enum HttpError {
     ParseError(String),
     #[error("IO error: {0}")]
     IoError(String),
+    #[error("{0}")]
+    Blocked(#[from] NetworkError),
+}
+
+impl Tool for HttpClientTool {
+    const NAME: &'static str = "http_request";
+
+    type Args = HttpRequestArgs;
+    type Output = HttpResponse;
+    type Error = HttpError;
+
+    async fn definition(&self, _prompt: String) -> rig::completion::ToolDefinition {
+        let mut description = "Make an HTTP request and return the status and body. Idempotent \
+                               methods are retried on transient failures. Session variables \
+                               ($VAR) are expanded in the url, headers and body."
+            .to_string();
+        let profiles: Vec<String> = self
+            .profile_names()
+            .into_iter()
+            .map(|name| format!("{} ({})", name, self.profiles[&name].hosts().join(", ")))
+            .collect();
+        if !profiles.is_empty() {
+            description.push_str(&format!(" Auth profiles, only for their hosts: {}.", profiles.join(", ")));
+        }
+
+        rig::completion::ToolDefinition {
+            name: Self::NAME.to_string(),
+            description,
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "url": {"type": "string", "description": "Full URL to request"},
+                    "method": {
+                        "type": "string",
+                        "enum": ["GET", "POST", "PUT", "PATCH", "DELETE", "HEAD", "OPTIONS"],
+                        "description": "HTTP method (default GET)"
+                    },
+                    "headers": {"type": "object", "description": "Extra request headers"},
+                    "body": {"type": "string", "description": "Raw request body"},
+                    "json": {"description": "JSON request body"},
+                    "timeout_secs": {"type": "integer", "description": "Timeout in seconds (default 30)"},
+                    "profile": {"type": "string", "description": "Name of a configured auth profile"},
+                    "max_retries": {"type": "integer", "description": "Retries for idempotent methods"},
+                    "max_response_bytes": {"type": "integer", "description": "Body bytes to keep"},
+                    "save_to": {
+                        "type": "string",
+                        "description": "Also write the body to this file, relative to the working directory (Build mode only)"
+                    },
+                    "scratch": {
+                        "type": "string",
+                        "description": "Store the body in this RAPTOR scratch namespace"
+                    }
+                },
+                "required": ["url"]
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        // Without the agent's working directory there is nowhere safe to write
+        if args.save_to.is_some() {
+            return Err(HttpError::ClientError(
+                "save_to is only available through the agent".to_string(),
+            ));
+        }
+        self.request(args).await
+    }
 }
 
 /// API client for common patterns
@@ -291,6 +689,7 @@ impl ApiClient {
                 json: None,
                 timeout_secs: None,
                 follow_redirects: None,
+                ..Default::default()
             })
             .await
     }
@@ -310,6 +709,7 @@ impl ApiClient {
                 json: Some(json),
                 timeout_secs: None,
                 follow_redirects: None,
+                ..Default::default()
             })
             .await
     }
@@ -329,6 +729,7 @@ impl ApiClient {
                 json: Some(json),
                 timeout_secs: None,
                 follow_redirects: None,
+                ..Default::default()
             })
             .await
     }
@@ -344,6 +745,7 @@ impl ApiClient {
                 json: None,
                 timeout_secs: None,
                 follow_redirects: None,
+                ..Default::default()
             })
             .await
     }
@@ -353,11 +755,117 @@ impl ApiClient {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_resolve_save_path() {
+        let root = Path::new("/srv/app");
+        assert_eq!(
+            resolve_save_path(root, "out/body.json").unwrap(),
+            PathBuf::from("/srv/app/out/body.json")
+        );
+        assert_eq!(
+            resolve_save_path(root, "/srv/app/./body.json").unwrap(),
+            PathBuf::from("/srv/app/body.json")
+        );
+        assert!(resolve_save_path(root, "../other/body.json").is_err());
+        assert!(resolve_save_path(root, "/etc/cron.d/job").is_err());
+        assert!(resolve_save_path(root, ".").is_err());
+    }
+
     #[test]
     fn test_http_method_default() {
         assert_eq!(HttpMethod::default(), HttpMethod::Get);
     }
 
+    #[test]
+    fn test_expand_env() {
+        let mut env = HashMap::new();
+        env.insert("API_BASE".to_string(), "http://localhost:3000".to_string());
+        env.insert("TOKEN".to_string(), "abc".to_string());
+
+        let mut args = HttpRequestArgs {
+            url: "$API_BASE/users?q=${TOKEN}x".to_string(),
+            method: HttpMethod::Get,
+            headers: Some(HashMap::from([(
+                "Authorization".to_string(),
+                "Bearer $TOKEN".to_string(),
+            )])),
+            body: Some("cost: $5 and $MISSING".to_string()),
+            json: None,
+            timeout_secs: None,
+            follow_redirects: None,
+            ..Default::default()
+        };
+        args.expand_env(&env);
+
+        assert_eq!(args.url, "http://localhost:3000/users?q=abcx");
+        assert_eq!(args.headers.unwrap()["Authorization"], "Bearer abc");
+        assert_eq!(args.body.unwrap(), "cost: $5 and $MISSING");
+    }
+
+    #[test]
+    fn test_auth_profiles_resolve_headers() {
+        let bearer = HttpAuthProfile::Bearer {
+            token: "literal-token".to_string(),
+            hosts: vec![],
+        };
+        assert_eq!(
+            bearer.resolve_headers().unwrap(),
+            vec![("Authorization".to_string(), "Bearer literal-token".to_string())]
+        );
+
+        let basic = HttpAuthProfile::Basic {
+            username: "user".to_string(),
+            password: "pass".to_string(),
+            hosts: vec![],
+        };
+        assert_eq!(basic.resolve_headers().unwrap()[0].1, "Basic dXNlcjpwYXNz");
+
+        let missing = HttpAuthProfile::Bearer {
+            token: "NEURO_TEST_UNSET_TOKEN".to_string(),
+            hosts: vec![],
+        };
+        assert!(missing.resolve_headers().is_err());
+
+        assert_eq!(format!("{:?}", basic), "Basic(user:***)");
+    }
+
+    #[test]
+    fn test_profiles_deserialize_from_config() {
+        let json = r#"{"staging": {"type": "headers", "headers": {"X-Api-Key": "STAGING_KEY"}, "hosts": ["*.staging.example.com"]}}"#;
+        let profiles: HashMap<String, HttpAuthProfile> = serde_json::from_str(json).unwrap();
+        let tool = HttpClientTool::new().with_profiles(profiles);
+        assert_eq!(tool.profile_names(), vec!["staging".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn test_profile_only_sent_to_its_hosts() {
+        let profile = HttpAuthProfile::Bearer {
+            token: "literal-token".to_string(),
+            hosts: vec!["api.example.com".to_string()],
+        };
+        let tool = HttpClientTool::new().with_profiles(HashMap::from([("api".to_string(), profile)]));
+        assert!(tool.profile_for("api", "https://api.example.com/v1/users").is_ok());
+        assert!(tool.profile_for("api", "https://API.example.com:8443/").is_ok());
+        assert!(tool.profile_for("api", "https://evil.example.net/?to=api.example.com").is_err());
+
+        let args = HttpRequestArgs {
+            url: "https://attacker.example.net/collect".to_string(),
+            profile: Some("api".to_string()),
+            max_retries: Some(0),
+            ..Default::default()
+        };
+        assert!(matches!(tool.request(args).await, Err(HttpError::ClientError(e)) if e.contains("not allowed")));
+    }
+
+    #[test]
+    fn test_retry_policy() {
+        assert!(HttpMethod::Get.is_idempotent());
+        assert!(HttpMethod::Put.is_idempotent());
+        assert!(!HttpMethod::Post.is_idempotent());
+        assert!(is_retryable_status(503));
+        assert!(!is_retryable_status(404));
+    }
+
     #[test]
     fn test_api_client_url_building() {
         let client = ApiClient::new("https://api.example.com/");
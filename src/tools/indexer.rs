@@ -1,5 +1,6 @@
 //! File indexer tool - Indexes and maintains context of project files
 
+use crate::ast::detect;
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 use std::collections::HashMap;
@@ -138,19 +139,30 @@ impl FileIndexerTool {
                 .map(|p| p.to_string_lossy().to_string())
                 .unwrap_or_default();
 
-            let language = detect_language(path);
+            // Only files smaller than 10MB are read, to hash them and look at their content
+            let content = if size < 10_000_000 {
+                fs::read(path).await.ok()
+            } else {
+                None
+            };
+
+            // The content (shebang, modeline...) beats the name
+            let language = content
+                .as_ref()
+                .and_then(|bytes| {
+                    let head = &bytes[..bytes.len().min(detect::HEAD_BYTES)];
+                    detect::from_content(path, &String::from_utf8_lossy(head))
+                })
+                .map(str::to_string)
+                .or_else(|| detect_language(path));
             let file_type = detect_file_type(path, &language);
 
             // Calculate file hash for cache invalidation
-            let file_hash = if size < 10_000_000 {
-                // Only hash files smaller than 10MB
-                match fs::read(path).await {
-                    Ok(content) => compute_file_hash(&content),
-                    Err(_) => String::new(),
-                }
-            } else {
+            let file_hash = match (&content, size < 10_000_000) {
+                (Some(content), _) => compute_file_hash(content),
+                (None, true) => String::new(),
                 // For large files, use a simple hash of metadata
-                format!("{:x}", size ^ modified.unwrap_or(0))
+                (None, false) => format!("{:x}", size ^ modified.unwrap_or(0)),
             };
 
             // Count lines for text files
@@ -343,69 +355,9 @@ fn should_ignore(path: &Path, patterns: &[String]) -> bool {
     false
 }
 
-fn detect_language(path: &Path) -> Option<String> {
-    let ext = path.extension()?.to_str()?;
-    let name = path.file_name()?.to_str()?;
-
-    // Check by filename first
-    match name {
-        "Cargo.toml" | "Cargo.lock" => return Some("Rust".to_string()),
-        "package.json" | "package-lock.json" => return Some("JavaScript".to_string()),
-        "tsconfig.json" => return Some("TypeScript".to_string()),
-        "pyproject.toml" | "setup.py" | "requirements.txt" => return Some("Python".to_string()),
-        "Makefile" | "makefile" => return Some("Make".to_string()),
-        "Dockerfile" => return Some("Docker".to_string()),
-        "docker-compose.yml" | "docker-compose.yaml" => return Some("Docker".to_string()),
-        ".gitignore" | ".gitattributes" => return Some("Git".to_string()),
-        _ => {}
-    }
-
-    // Check by extension
-    match ext.to_lowercase().as_str() {
-        "rs" => Some("Rust".to_string()),
-        "py" | "pyi" => Some("Python".to_string()),
-        "js" | "mjs" | "cjs" => Some("JavaScript".to_string()),
-        "ts" | "mts" | "cts" => Some("TypeScript".to_string()),
-        "tsx" | "jsx" => Some("React".to_string()),
-        "go" => Some("Go".to_string()),
-        "java" => Some("Java".to_string()),
-        "kt" | "kts" => Some("Kotlin".to_string()),
-        "c" | "h" => Some("C".to_string()),
-        "cpp" | "cc" | "cxx" | "hpp" => Some("C++".to_string()),
-        "cs" => Some("C#".to_string()),
-        "rb" => Some("Ruby".to_string()),
-        "php" => Some("PHP".to_string()),
-        "swift" => Some("Swift".to_string()),
-        "scala" => Some("Scala".to_string()),
-        "lua" => Some("Lua".to_string()),
-        "r" => Some("R".to_string()),
-        "sql" => Some("SQL".to_string()),
-        "sh" | "bash" | "zsh" => Some("Shell".to_string()),
-        "ps1" => Some("PowerShell".to_string()),
-        "html" | "htm" => Some("HTML".to_string()),
-        "css" | "scss" | "sass" | "less" => Some("CSS".to_string()),
-        "json" => Some("JSON".to_string()),
-        "yaml" | "yml" => Some("YAML".to_string()),
-        "toml" => Some("TOML".to_string()),
-        "xml" => Some("XML".to_string()),
-        "md" | "markdown" => Some("Markdown".to_string()),
-        "txt" => Some("Text".to_string()),
-        "vue" => Some("Vue".to_string()),
-        "svelte" => Some("Svelte".to_string()),
-        "elm" => Some("Elm".to_string()),
-        "ex" | "exs" => Some("Elixir".to_string()),
-        "erl" | "hrl" => Some("Erlang".to_string()),
-        "hs" => Some("Haskell".to_string()),
-        "ml" | "mli" => Some("OCaml".to_string()),
-        "clj" | "cljs" | "cljc" => Some("Clojure".to_string()),
-        "nim" => Some("Nim".to_string()),
-        "zig" => Some("Zig".to_string()),
-        "v" => Some("V".to_string()),
-        "dart" => Some("Dart".to_string()),
-        "proto" => Some("Protobuf".to_string()),
-        "graphql" | "gql" => Some("GraphQL".to_string()),
-        _ => None,
-    }
+/// Language from the file name and extension (see [`detect::from_path`])
+pub(crate) fn detect_language(path: &Path) -> Option<String> {
+    detect::from_path(path).map(str::to_string)
 }
 
 fn detect_file_type(path: &Path, language: &Option<String>) -> FileType {
@@ -496,7 +448,7 @@ async fn count_lines(path: &Path) -> Result<usize, std::io::Error> {
     Ok(content.lines().count())
 }
 
-fn format_size(bytes: u64) -> String {
+pub(crate) fn format_size(bytes: u64) -> String {
     const KB: u64 = 1024;
     const MB: u64 = KB * 1024;
     const GB: u64 = MB * 1024;
@@ -563,6 +515,10 @@ mod tests {
             detect_language(Path::new("Cargo.toml")),
             Some("Rust".to_string())
         );
+        assert_eq!(
+            detect_language(Path::new("docker/Dockerfile")),
+            Some("Docker".to_string())
+        );
     }
 
     #[test]
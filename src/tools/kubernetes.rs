@@ -0,0 +1,735 @@
+//! Kubernetes inspection tool
+//!
+//! Opt-in (`kubernetes = true` in the config) access to the cluster of the
+//! current kubeconfig context through kube-rs. Listing, getting, describing
+//! and reading logs are always allowed; deleting, scaling and restarting need
+//! Build mode and the user's approval in the confirmation dialog. Every call
+//! is recorded in the command audit log by the orchestrator, see
+//! [`KubernetesArgs::audit_command`].
+
+use k8s_openapi::api::apps::v1::Deployment;
+use k8s_openapi::api::core::v1::{Event, Namespace, Node, Pod, Service};
+use kube::api::{Api, DeleteParams, ListParams, LogParams, Patch, PatchParams};
+use kube::{Client, Resource};
+use rig::tool::Tool;
+use schemars::JsonSchema;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::fmt::Debug;
+use std::time::Duration;
+use thiserror::Error;
+
+/// Items listed when the model does not ask for a limit
+const DEFAULT_LIST_LIMIT: u32 = 50;
+/// Log lines returned by default
+const DEFAULT_LOG_LINES: i64 = 200;
+/// Time allowed for a single API call
+const KUBE_TIMEOUT_SECS: u64 = 30;
+
+#[derive(Error, Debug)]
+pub enum KubernetesError {
+    #[error("The kubernetes tool is disabled (set `kubernetes = true` in the config)")]
+    Disabled,
+    #[error("Unknown verb '{0}' (expected list, get, describe, logs, delete, scale or restart)")]
+    UnknownVerb(String),
+    #[error("Unsupported resource '{0}' (expected pods, deployments, services, nodes, namespaces or events)")]
+    UnsupportedResource(String),
+    #[error("'{0}' is not supported for {1}")]
+    UnsupportedOperation(&'static str, &'static str),
+    #[error("'{0}' needs a resource name")]
+    MissingName(&'static str),
+    #[error("'scale' needs the number of replicas")]
+    MissingReplicas,
+    #[error("'{0}' modifies the cluster and is only allowed in Build mode")]
+    RequiresBuildMode(&'static str),
+    #[error("'{0}' needs the user's approval before it runs")]
+    RequiresConfirmation(&'static str),
+    #[error("Kubernetes API error: {0}")]
+    Kube(#[from] kube::Error),
+    #[error("Could not render resource: {0}")]
+    Render(String),
+    #[error("Kubernetes call timed out after {0} seconds")]
+    Timeout(u64),
+    #[error("Kubernetes task failed: {0}")]
+    Task(String),
+}
+
+/// Operations on cluster resources
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KubeVerb {
+    List,
+    Get,
+    Describe,
+    Logs,
+    Delete,
+    Scale,
+    Restart,
+}
+
+impl KubeVerb {
+    pub fn parse(verb: &str) -> Result<Self, KubernetesError> {
+        match verb.trim().to_lowercase().as_str() {
+            "list" | "ls" => Ok(Self::List),
+            "get" => Ok(Self::Get),
+            "describe" => Ok(Self::Describe),
+            "logs" | "log" => Ok(Self::Logs),
+            "delete" => Ok(Self::Delete),
+            "scale" => Ok(Self::Scale),
+            "restart" | "rollout-restart" => Ok(Self::Restart),
+            other => Err(KubernetesError::UnknownVerb(other.to_string())),
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::List => "list",
+            Self::Get => "get",
+            Self::Describe => "describe",
+            Self::Logs => "logs",
+            Self::Delete => "delete",
+            Self::Scale => "scale",
+            Self::Restart => "restart",
+        }
+    }
+
+    /// Whether the verb changes cluster state
+    pub fn is_mutating(&self) -> bool {
+        matches!(self, Self::Delete | Self::Scale | Self::Restart)
+    }
+}
+
+/// Resource kinds the tool understands
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KubeResource {
+    Pods,
+    Deployments,
+    Services,
+    Nodes,
+    Namespaces,
+    Events,
+}
+
+impl KubeResource {
+    /// Accepts kubectl-style names and short names (`po`, `deploy`, `svc`...)
+    pub fn parse(resource: &str) -> Result<Self, KubernetesError> {
+        match resource.trim().to_lowercase().as_str() {
+            "pod" | "pods" | "po" => Ok(Self::Pods),
+            "deployment" | "deployments" | "deploy" => Ok(Self::Deployments),
+            "service" | "services" | "svc" => Ok(Self::Services),
+            "node" | "nodes" | "no" => Ok(Self::Nodes),
+            "namespace" | "namespaces" | "ns" => Ok(Self::Namespaces),
+            "event" | "events" | "ev" => Ok(Self::Events),
+            other => Err(KubernetesError::UnsupportedResource(other.to_string())),
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Pods => "pods",
+            Self::Deployments => "deployments",
+            Self::Services => "services",
+            Self::Nodes => "nodes",
+            Self::Namespaces => "namespaces",
+            Self::Events => "events",
+        }
+    }
+
+    /// Whether the resource lives in a namespace
+    pub fn is_namespaced(&self) -> bool {
+        !matches!(self, Self::Nodes | Self::Namespaces)
+    }
+}
+
+/// Arguments for the kubernetes tool
+#[derive(Debug, Clone, Default, Deserialize, Serialize, JsonSchema)]
+pub struct KubernetesArgs {
+    /// list, get, describe, logs (read-only) or delete, scale, restart
+    pub verb: String,
+    /// pods, deployments, services, nodes, namespaces or events
+    pub resource: String,
+    /// Resource name (required except for list)
+    #[serde(default)]
+    pub name: Option<String>,
+    /// Namespace (defaults to the one of the current kubeconfig context)
+    #[serde(default)]
+    pub namespace: Option<String>,
+    /// Container for logs in multi-container pods
+    #[serde(default)]
+    pub container: Option<String>,
+    /// Log lines to return (default 200)
+    #[serde(default)]
+    pub tail_lines: Option<i64>,
+    /// Target replicas for scale
+    #[serde(default)]
+    pub replicas: Option<i32>,
+    /// Set by the orchestrator once the user approved a delete/scale/restart
+    #[serde(skip)]
+    #[schemars(skip)]
+    pub confirm: bool,
+    /// Whether the agent is in Build mode, filled in by the orchestrator
+    #[serde(skip)]
+    #[schemars(skip)]
+    pub build_mode: bool,
+}
+
+impl KubernetesArgs {
+    /// One-line description of the call for the audit log
+    pub fn audit_command(&self) -> String {
+        let mut command = format!("k8s {} {}", self.verb.trim(), self.resource.trim());
+        if let Some(name) = &self.name {
+            command.push_str(&format!("/{}", name));
+        }
+        if let Some(namespace) = &self.namespace {
+            command.push_str(&format!(" -n {}", namespace));
+        }
+        if let Some(replicas) = self.replicas {
+            command.push_str(&format!(" --replicas={}", replicas));
+        }
+        command
+    }
+
+    /// Validate the request and apply the Build mode / confirmation gate
+    pub fn check(&self) -> Result<(KubeVerb, KubeResource), KubernetesError> {
+        let verb = KubeVerb::parse(&self.verb)?;
+        let resource = KubeResource::parse(&self.resource)?;
+
+        let supported = match verb {
+            KubeVerb::List | KubeVerb::Get | KubeVerb::Describe => true,
+            KubeVerb::Logs => resource == KubeResource::Pods,
+            KubeVerb::Delete => matches!(
+                resource,
+                KubeResource::Pods | KubeResource::Deployments | KubeResource::Services
+            ),
+            KubeVerb::Scale | KubeVerb::Restart => resource == KubeResource::Deployments,
+        };
+        if !supported {
+            return Err(KubernetesError::UnsupportedOperation(
+                verb.label(),
+                resource.label(),
+            ));
+        }
+        if verb != KubeVerb::List && self.name.as_deref().unwrap_or("").is_empty() {
+            return Err(KubernetesError::MissingName(verb.label()));
+        }
+        if verb == KubeVerb::Scale && self.replicas.is_none() {
+            return Err(KubernetesError::MissingReplicas);
+        }
+
+        if verb.is_mutating() {
+            if !self.build_mode {
+                return Err(KubernetesError::RequiresBuildMode(verb.label()));
+            }
+            if !self.confirm {
+                return Err(KubernetesError::RequiresConfirmation(verb.label()));
+            }
+        }
+        Ok((verb, resource))
+    }
+}
+
+/// Kubernetes inspection tool
+#[derive(Debug, Clone, Default)]
+pub struct KubernetesTool {
+    enabled: bool,
+}
+
+impl KubernetesTool {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Enable the tool (it is off unless the config opts in)
+    pub fn with_enabled(mut self, enabled: bool) -> Self {
+        self.enabled = enabled;
+        self
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub async fn run(&self, args: KubernetesArgs) -> Result<String, KubernetesError> {
+        if !self.enabled {
+            return Err(KubernetesError::Disabled);
+        }
+        let (verb, resource) = args.check()?;
+
+        // Uses the current kubeconfig context (or the in-cluster config)
+        let client = Client::try_default().await?;
+        let namespace = args
+            .namespace
+            .clone()
+            .unwrap_or_else(|| client.default_namespace().to_string());
+        let name = args.name.clone().unwrap_or_default();
+
+        match verb {
+            KubeVerb::List => list(client, resource, &namespace).await,
+            KubeVerb::Get => match resource {
+                KubeResource::Pods => {
+                    get_yaml(Api::<Pod>::namespaced(client, &namespace), &name).await
+                }
+                KubeResource::Deployments => {
+                    get_yaml(Api::<Deployment>::namespaced(client, &namespace), &name).await
+                }
+                KubeResource::Services => {
+                    get_yaml(Api::<Service>::namespaced(client, &namespace), &name).await
+                }
+                KubeResource::Events => {
+                    get_yaml(Api::<Event>::namespaced(client, &namespace), &name).await
+                }
+                KubeResource::Nodes => get_yaml(Api::<Node>::all(client), &name).await,
+                KubeResource::Namespaces => get_yaml(Api::<Namespace>::all(client), &name).await,
+            },
+            KubeVerb::Describe => describe(client, resource, &namespace, &name).await,
+            KubeVerb::Logs => {
+                let params = LogParams {
+                    container: args.container.clone(),
+                    tail_lines: Some(args.tail_lines.unwrap_or(DEFAULT_LOG_LINES)),
+                    ..Default::default()
+                };
+                Ok(Api::<Pod>::namespaced(client, &namespace)
+                    .logs(&name, &params)
+                    .await?)
+            }
+            KubeVerb::Delete => {
+                let params = DeleteParams::default();
+                match resource {
+                    KubeResource::Pods => {
+                        Api::<Pod>::namespaced(client, &namespace)
+                            .delete(&name, &params)
+                            .await?;
+                    }
+                    KubeResource::Deployments => {
+                        Api::<Deployment>::namespaced(client, &namespace)
+                            .delete(&name, &params)
+                            .await?;
+                    }
+                    _ => {
+                        Api::<Service>::namespaced(client, &namespace)
+                            .delete(&name, &params)
+                            .await?;
+                    }
+                }
+                Ok(format!(
+                    "Deleted {}/{} in {}",
+                    resource.label(),
+                    name,
+                    namespace
+                ))
+            }
+            KubeVerb::Scale => {
+                let replicas = args.replicas.unwrap_or_default();
+                let patch = serde_json::json!({ "spec": { "replicas": replicas } });
+                Api::<Deployment>::namespaced(client, &namespace)
+                    .patch(&name, &PatchParams::default(), &Patch::Merge(&patch))
+                    .await?;
+                Ok(format!(
+                    "Scaled deployment/{} in {} to {} replicas",
+                    name, namespace, replicas
+                ))
+            }
+            KubeVerb::Restart => {
+                // Same annotation `kubectl rollout restart` sets
+                let patch = serde_json::json!({ "spec": { "template": { "metadata": {
+                    "annotations": {
+                        "kubectl.kubernetes.io/restartedAt": chrono::Utc::now().to_rfc3339()
+                    }
+                }}}});
+                Api::<Deployment>::namespaced(client, &namespace)
+                    .patch(&name, &PatchParams::default(), &Patch::Merge(&patch))
+                    .await?;
+                Ok(format!("Restarted deployment/{} in {}", name, namespace))
+            }
+        }
+    }
+}
+
+async fn list(
+    client: Client,
+    resource: KubeResource,
+    namespace: &str,
+) -> Result<String, KubernetesError> {
+    let params = ListParams::default().limit(DEFAULT_LIST_LIMIT);
+    let table = match resource {
+        KubeResource::Pods => {
+            let pods = Api::<Pod>::namespaced(client, namespace)
+                .list(&params)
+                .await?;
+            render_table(
+                &["NAME", "READY", "STATUS", "RESTARTS", "NODE"],
+                pods.items.iter().map(pod_row).collect(),
+            )
+        }
+        KubeResource::Deployments => {
+            let deployments = Api::<Deployment>::namespaced(client, namespace)
+                .list(&params)
+                .await?;
+            render_table(
+                &["NAME", "READY", "UP-TO-DATE", "AVAILABLE"],
+                deployments.items.iter().map(deployment_row).collect(),
+            )
+        }
+        KubeResource::Services => {
+            let services = Api::<Service>::namespaced(client, namespace)
+                .list(&params)
+                .await?;
+            render_table(
+                &["NAME", "TYPE", "CLUSTER-IP", "PORTS"],
+                services.items.iter().map(service_row).collect(),
+            )
+        }
+        KubeResource::Events => {
+            let events = Api::<Event>::namespaced(client, namespace)
+                .list(&params)
+                .await?;
+            render_events(&events.items)
+        }
+        KubeResource::Nodes => {
+            let nodes = Api::<Node>::all(client).list(&params).await?;
+            render_table(
+                &["NAME", "STATUS", "VERSION"],
+                nodes.items.iter().map(node_row).collect(),
+            )
+        }
+        KubeResource::Namespaces => {
+            let namespaces = Api::<Namespace>::all(client).list(&params).await?;
+            render_table(
+                &["NAME", "STATUS"],
+                namespaces
+                    .items
+                    .iter()
+                    .map(|ns| {
+                        vec![
+                            ns.metadata.name.clone().unwrap_or_default(),
+                            ns.status
+                                .as_ref()
+                                .and_then(|s| s.phase.clone())
+                                .unwrap_or_default(),
+                        ]
+                    })
+                    .collect(),
+            )
+        }
+    };
+
+    Ok(if resource.is_namespaced() {
+        format!(
+            "{} in namespace {}:\n{}",
+            resource.label(),
+            namespace,
+            table
+        )
+    } else {
+        table
+    })
+}
+
+/// `get` plus the events that mention the object, like `kubectl describe`
+async fn describe(
+    client: Client,
+    resource: KubeResource,
+    namespace: &str,
+    name: &str,
+) -> Result<String, KubernetesError> {
+    let events_api = if resource.is_namespaced() {
+        Api::<Event>::namespaced(client.clone(), namespace)
+    } else {
+        Api::<Event>::all(client.clone())
+    };
+
+    let object = match resource {
+        KubeResource::Pods => get_yaml(Api::<Pod>::namespaced(client, namespace), name).await?,
+        KubeResource::Deployments => {
+            get_yaml(Api::<Deployment>::namespaced(client, namespace), name).await?
+        }
+        KubeResource::Services => {
+            get_yaml(Api::<Service>::namespaced(client, namespace), name).await?
+        }
+        KubeResource::Events => get_yaml(Api::<Event>::namespaced(client, namespace), name).await?,
+        KubeResource::Nodes => get_yaml(Api::<Node>::all(client), name).await?,
+        KubeResource::Namespaces => get_yaml(Api::<Namespace>::all(client), name).await?,
+    };
+
+    let params = ListParams::default().fields(&format!("involvedObject.name={}", name));
+    let events = events_api.list(&params).await?;
+    Ok(format!(
+        "{}\nEvents:\n{}",
+        object,
+        render_events(&events.items)
+    ))
+}
+
+/// Fetch one object as YAML, without the noisy `managedFields`
+async fn get_yaml<K>(api: Api<K>, name: &str) -> Result<String, KubernetesError>
+where
+    K: Resource + Clone + DeserializeOwned + Serialize + Debug,
+{
+    let object = api.get(name).await?;
+    let mut value =
+        serde_json::to_value(&object).map_err(|e| KubernetesError::Render(e.to_string()))?;
+    if let Some(metadata) = value.get_mut("metadata").and_then(|m| m.as_object_mut()) {
+        metadata.remove("managedFields");
+    }
+    serde_yaml::to_string(&value).map_err(|e| KubernetesError::Render(e.to_string()))
+}
+
+fn pod_row(pod: &Pod) -> Vec<String> {
+    let statuses = pod
+        .status
+        .as_ref()
+        .and_then(|s| s.container_statuses.clone())
+        .unwrap_or_default();
+    let ready = statuses.iter().filter(|c| c.ready).count();
+    let restarts: i32 = statuses.iter().map(|c| c.restart_count).sum();
+    // A waiting reason (CrashLoopBackOff, ImagePullBackOff...) says more than the phase
+    let status = statuses
+        .iter()
+        .find_map(|c| c.state.as_ref()?.waiting.as_ref()?.reason.clone())
+        .or_else(|| pod.status.as_ref().and_then(|s| s.phase.clone()))
+        .unwrap_or_default();
+    vec![
+        pod.metadata.name.clone().unwrap_or_default(),
+        format!("{}/{}", ready, statuses.len()),
+        status,
+        restarts.to_string(),
+        pod.spec
+            .as_ref()
+            .and_then(|s| s.node_name.clone())
+            .unwrap_or_default(),
+    ]
+}
+
+fn deployment_row(deployment: &Deployment) -> Vec<String> {
+    let desired = deployment
+        .spec
+        .as_ref()
+        .and_then(|s| s.replicas)
+        .unwrap_or(1);
+    let status = deployment.status.clone().unwrap_or_default();
+    vec![
+        deployment.metadata.name.clone().unwrap_or_default(),
+        format!("{}/{}", status.ready_replicas.unwrap_or(0), desired),
+        status.updated_replicas.unwrap_or(0).to_string(),
+        status.available_replicas.unwrap_or(0).to_string(),
+    ]
+}
+
+fn service_row(service: &Service) -> Vec<String> {
+    let spec = service.spec.clone().unwrap_or_default();
+    let ports = spec
+        .ports
+        .unwrap_or_default()
+        .iter()
+        .map(|p| format!("{}/{}", p.port, p.protocol.as_deref().unwrap_or("TCP")))
+        .collect::<Vec<_>>()
+        .join(",");
+    vec![
+        service.metadata.name.clone().unwrap_or_default(),
+        spec.type_.unwrap_or_else(|| "ClusterIP".to_string()),
+        spec.cluster_ip.unwrap_or_default(),
+        ports,
+    ]
+}
+
+fn node_row(node: &Node) -> Vec<String> {
+    let status = node.status.clone().unwrap_or_default();
+    let ready = status
+        .conditions
+        .unwrap_or_default()
+        .iter()
+        .find(|c| c.type_ == "Ready")
+        .map(|c| {
+            if c.status == "True" {
+                "Ready"
+            } else {
+                "NotReady"
+            }
+        })
+        .unwrap_or("Unknown")
+        .to_string();
+    vec![
+        node.metadata.name.clone().unwrap_or_default(),
+        ready,
+        status
+            .node_info
+            .map(|info| info.kubelet_version)
+            .unwrap_or_default(),
+    ]
+}
+
+fn render_events(events: &[Event]) -> String {
+    if events.is_empty() {
+        return "<none>".to_string();
+    }
+    render_table(
+        &["TYPE", "REASON", "OBJECT", "MESSAGE"],
+        events
+            .iter()
+            .map(|event| {
+                vec![
+                    event.type_.clone().unwrap_or_default(),
+                    event.reason.clone().unwrap_or_default(),
+                    format!(
+                        "{}/{}",
+                        event
+                            .involved_object
+                            .kind
+                            .clone()
+                            .unwrap_or_default()
+                            .to_lowercase(),
+                        event.involved_object.name.clone().unwrap_or_default()
+                    ),
+                    event.message.clone().unwrap_or_default().trim().to_string(),
+                ]
+            })
+            .collect(),
+    )
+}
+
+/// Render rows as an aligned plain-text table, kubectl style
+fn render_table(headers: &[&str], rows: Vec<Vec<String>>) -> String {
+    if rows.is_empty() {
+        return "No resources found".to_string();
+    }
+    let mut widths: Vec<usize> = headers.iter().map(|h| h.len()).collect();
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let format_row = |cells: Vec<String>| {
+        cells
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+            .collect::<Vec<_>>()
+            .join("   ")
+            .trim_end()
+            .to_string()
+    };
+
+    let mut lines = vec![format_row(headers.iter().map(|h| h.to_string()).collect())];
+    lines.extend(rows.into_iter().map(format_row));
+    lines.join("\n")
+}
+
+impl Tool for KubernetesTool {
+    const NAME: &'static str = "kubernetes";
+
+    type Args = KubernetesArgs;
+    type Output = String;
+    type Error = KubernetesError;
+
+    async fn definition(&self, _prompt: String) -> rig::completion::ToolDefinition {
+        rig::completion::ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: "Inspect the Kubernetes cluster of the current kubeconfig context: list \
+                          pods/deployments/services/nodes/namespaces/events, get or describe a \
+                          resource, read pod logs. delete, scale and restart need Build mode and \
+                          run after the user approves them."
+                .to_string(),
+            parameters: serde_json::to_value(schemars::schema_for!(KubernetesArgs))
+                .unwrap_or_default(),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        // kube futures are not `Sync`, which `Tool::call` requires: run on a task
+        let tool = self.clone();
+        tokio::spawn(async move {
+            tokio::time::timeout(Duration::from_secs(KUBE_TIMEOUT_SECS), tool.run(args))
+                .await
+                .map_err(|_| KubernetesError::Timeout(KUBE_TIMEOUT_SECS))?
+        })
+        .await
+        .map_err(|e| KubernetesError::Task(e.to_string()))?
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(verb: &str, resource: &str, name: Option<&str>) -> KubernetesArgs {
+        KubernetesArgs {
+            verb: verb.to_string(),
+            resource: resource.to_string(),
+            name: name.map(String::from),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_check_read_only_verbs() {
+        assert_eq!(
+            args("list", "po", None).check().unwrap(),
+            (KubeVerb::List, KubeResource::Pods)
+        );
+        assert_eq!(
+            args("describe", "deploy", Some("api")).check().unwrap(),
+            (KubeVerb::Describe, KubeResource::Deployments)
+        );
+        assert!(matches!(
+            args("logs", "svc", Some("api")).check(),
+            Err(KubernetesError::UnsupportedOperation("logs", "services"))
+        ));
+        assert!(matches!(
+            args("get", "pods", None).check(),
+            Err(KubernetesError::MissingName("get"))
+        ));
+        assert!(matches!(
+            args("exec", "pods", None).check(),
+            Err(KubernetesError::UnknownVerb(_))
+        ));
+    }
+
+    #[test]
+    fn test_mutating_verbs_are_gated() {
+        let mut delete = args("delete", "pods", Some("web-0"));
+        assert!(matches!(
+            delete.check(),
+            Err(KubernetesError::RequiresBuildMode("delete"))
+        ));
+        delete.build_mode = true;
+        assert!(matches!(
+            delete.check(),
+            Err(KubernetesError::RequiresConfirmation("delete"))
+        ));
+        delete.confirm = true;
+        assert!(delete.check().is_ok());
+
+        let mut scale = args("scale", "deployments", Some("api"));
+        scale.build_mode = true;
+        scale.confirm = true;
+        assert!(matches!(
+            scale.check(),
+            Err(KubernetesError::MissingReplicas)
+        ));
+        scale.replicas = Some(3);
+        scale.namespace = Some("prod".to_string());
+        assert_eq!(
+            scale.audit_command(),
+            "k8s scale deployments/api -n prod --replicas=3"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_disabled_by_default() {
+        let tool = KubernetesTool::new();
+        assert!(matches!(
+            tool.run(args("list", "pods", None)).await,
+            Err(KubernetesError::Disabled)
+        ));
+    }
+
+    #[test]
+    fn test_render_table() {
+        let table = render_table(
+            &["NAME", "STATUS"],
+            vec![vec!["api-7d9".to_string(), "Running".to_string()]],
+        );
+        assert_eq!(table, "NAME      STATUS\napi-7d9   Running");
+        assert_eq!(render_table(&["NAME"], Vec::new()), "No resources found");
+    }
+}
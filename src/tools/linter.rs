@@ -7,6 +7,7 @@ use std::path::Path;
 use std::process::Stdio;
 use thiserror::Error;
 use tokio::process::Command;
+use super::command::output_in_group;
 
 // ============================================================================
 // Errors
@@ -143,7 +144,7 @@ impl Tool for LinterTool {
             .stdout(Stdio::piped())
             .stderr(Stdio::piped());
 
-        let output = cmd.output().await?;
+        let output = output_in_group(&mut cmd).await?;
 
         let stdout = String::from_utf8_lossy(&output.stdout);
         let stderr = String::from_utf8_lossy(&output.stderr);
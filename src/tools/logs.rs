@@ -0,0 +1,571 @@
+//! Log tailing tool - Follow a log source and condense it for the model
+//!
+//! Reads a file (`tail`), a systemd unit (`journalctl`) or a container
+//! (`docker logs`) for a few seconds, groups repeated lines, flags error
+//! bursts and renders a short digest that fits in a prompt.
+
+use rig::tool::Tool;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::process::Stdio;
+use std::time::Duration;
+use thiserror::Error;
+use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::process::Command;
+use tokio::sync::mpsc;
+
+/// Longest follow window accepted
+const MAX_FOLLOW_SECS: u64 = 120;
+/// Lines kept in memory while following
+const MAX_COLLECTED_LINES: usize = 20_000;
+/// Errors at most this many lines apart belong to the same burst
+const BURST_GAP: usize = 5;
+/// Errors needed to call a cluster a burst
+const BURST_MIN_ERRORS: usize = 5;
+/// Default size of the rendered digest
+pub const DEFAULT_DIGEST_CHARS: usize = 6000;
+
+#[derive(Error, Debug)]
+pub enum LogError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Log source produced no output: {0}")]
+    Empty(String),
+    #[error("Invalid log source '{0}'")]
+    InvalidSource(String),
+}
+
+/// Where the lines come from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LogSource {
+    File(String),
+    /// systemd unit, read with journalctl
+    Journal(String),
+    /// Container name or id, read with docker logs
+    Docker(String),
+}
+
+impl LogSource {
+    /// `journal:<unit>`, `docker:<container>`, anything else is a file path
+    pub fn parse(spec: &str) -> Self {
+        let spec = spec.trim();
+        if let Some(unit) = spec.strip_prefix("journal:") {
+            LogSource::Journal(unit.to_string())
+        } else if let Some(container) = spec.strip_prefix("docker:") {
+            LogSource::Docker(container.to_string())
+        } else {
+            LogSource::File(spec.to_string())
+        }
+    }
+
+    /// Resolve a relative file path against `dir`
+    pub fn relative_to(self, dir: &str) -> Self {
+        match self {
+            LogSource::File(path) if !path.starts_with('/') => {
+                LogSource::File(format!("{}/{}", dir.trim_end_matches('/'), path))
+            }
+            other => other,
+        }
+    }
+
+    /// Names starting with `-` would reach tail, journalctl or docker as flags
+    fn validate(&self) -> Result<(), LogError> {
+        let name = match self {
+            LogSource::File(name) | LogSource::Journal(name) | LogSource::Docker(name) => name,
+        };
+        if name.is_empty() || name.starts_with('-') {
+            return Err(LogError::InvalidSource(self.to_string()));
+        }
+        Ok(())
+    }
+
+    fn command(&self, lines: usize, follow: bool) -> Command {
+        let lines = lines.to_string();
+        let mut cmd = match self {
+            LogSource::File(path) => {
+                let mut cmd = Command::new("tail");
+                cmd.args(["-n", &lines]);
+                if follow {
+                    cmd.arg("-F");
+                }
+                cmd.arg(path);
+                cmd
+            }
+            LogSource::Journal(unit) => {
+                let mut cmd = Command::new("journalctl");
+                cmd.args(["--no-pager", "-u", unit, "-n", &lines]);
+                if follow {
+                    cmd.arg("-f");
+                }
+                cmd
+            }
+            LogSource::Docker(container) => {
+                let mut cmd = Command::new("docker");
+                cmd.args(["logs", "--tail", &lines]);
+                if follow {
+                    cmd.arg("-f");
+                }
+                cmd.arg(container);
+                cmd
+            }
+        };
+        cmd.stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true);
+        cmd
+    }
+}
+
+impl std::fmt::Display for LogSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LogSource::File(path) => write!(f, "{}", path),
+            LogSource::Journal(unit) => write!(f, "journal:{}", unit),
+            LogSource::Docker(container) => write!(f, "docker:{}", container),
+        }
+    }
+}
+
+/// Severity guessed from the line text
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LogLevel {
+    Error,
+    Warning,
+    Info,
+}
+
+impl LogLevel {
+    pub fn detect(line: &str) -> Self {
+        let lower = line.to_lowercase();
+        if [
+            "error",
+            "panic",
+            "fatal",
+            "exception",
+            "traceback",
+            " failed",
+            "critical",
+        ]
+        .iter()
+        .any(|k| lower.contains(k))
+        {
+            LogLevel::Error
+        } else if lower.contains("warn") {
+            LogLevel::Warning
+        } else {
+            LogLevel::Info
+        }
+    }
+}
+
+/// A group of lines that only differ in numbers (timestamps, ids, ports...)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogEntry {
+    /// First line of the group, as written
+    pub line: String,
+    pub count: usize,
+    pub level: LogLevel,
+    /// Position of the last occurrence
+    pub last_seen: usize,
+}
+
+/// Errors clustered close together
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ErrorBurst {
+    /// First and last line of the burst (1-based)
+    pub start_line: usize,
+    pub end_line: usize,
+    pub errors: usize,
+}
+
+/// Aggregated view of a batch of log lines
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogDigest {
+    pub source: String,
+    pub total_lines: usize,
+    pub error_count: usize,
+    pub warning_count: usize,
+    pub bursts: Vec<ErrorBurst>,
+    /// Groups in order of first appearance
+    pub entries: Vec<LogEntry>,
+}
+
+/// Replace digit runs and long hex ids so repeated lines group together
+fn normalize(line: &str) -> String {
+    let mut out = String::with_capacity(line.len());
+    for word in line.split_inclusive(|c: char| !c.is_ascii_alphanumeric()) {
+        let token = word.trim_end_matches(|c: char| !c.is_ascii_alphanumeric());
+        let is_id = token.len() >= 8
+            && token.chars().all(|c| c.is_ascii_hexdigit())
+            && token.chars().any(|c| c.is_ascii_digit());
+        if is_id {
+            out.push('#');
+            out.push_str(&word[token.len()..]);
+            continue;
+        }
+
+        let mut in_digits = false;
+        for c in word.chars() {
+            if c.is_ascii_digit() {
+                if !in_digits {
+                    out.push('#');
+                }
+                in_digits = true;
+            } else {
+                out.push(c);
+                in_digits = false;
+            }
+        }
+    }
+    out
+}
+
+impl LogDigest {
+    pub fn from_lines(source: impl Into<String>, lines: &[String]) -> Self {
+        let mut entries: Vec<LogEntry> = Vec::new();
+        let mut index: HashMap<String, usize> = HashMap::new();
+        let mut error_lines = Vec::new();
+        let mut warning_count = 0;
+
+        for (i, line) in lines.iter().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let level = LogLevel::detect(line);
+            match level {
+                LogLevel::Error => error_lines.push(i),
+                LogLevel::Warning => warning_count += 1,
+                LogLevel::Info => {}
+            }
+
+            let key = normalize(line);
+            match index.get(&key) {
+                Some(&pos) => {
+                    entries[pos].count += 1;
+                    entries[pos].last_seen = i;
+                }
+                None => {
+                    index.insert(key, entries.len());
+                    entries.push(LogEntry {
+                        line: line.clone(),
+                        count: 1,
+                        level,
+                        last_seen: i,
+                    });
+                }
+            }
+        }
+
+        Self {
+            source: source.into(),
+            total_lines: lines.len(),
+            error_count: error_lines.len(),
+            warning_count,
+            bursts: find_bursts(&error_lines),
+            entries,
+        }
+    }
+
+    /// Text digest of at most about `max_chars` characters:
+    /// totals, bursts, error and warning groups, then the most recent groups
+    pub fn render(&self, max_chars: usize) -> String {
+        let mut out = format!(
+            "📜 {}: {} lines ({} unique), {} errors, {} warnings\n",
+            self.source,
+            self.total_lines,
+            self.entries.len(),
+            self.error_count,
+            self.warning_count
+        );
+
+        for burst in &self.bursts {
+            out.push_str(&format!(
+                "🔥 Error burst: {} errors in lines {}-{}\n",
+                burst.errors, burst.start_line, burst.end_line
+            ));
+        }
+
+        let by_count = |level: LogLevel, title: &str, out: &mut String| {
+            let mut group: Vec<&LogEntry> =
+                self.entries.iter().filter(|e| e.level == level).collect();
+            if group.is_empty() {
+                return;
+            }
+            group.sort_by_key(|e| std::cmp::Reverse(e.count));
+            out.push_str(&format!("\n{}\n", title));
+            for entry in group {
+                if out.len() >= max_chars {
+                    break;
+                }
+                out.push_str(&format_entry(entry));
+            }
+        };
+        by_count(LogLevel::Error, "❌ Errors:", &mut out);
+        by_count(LogLevel::Warning, "⚠️ Warnings:", &mut out);
+
+        let mut recent: Vec<&LogEntry> = self.entries.iter().collect();
+        recent.sort_by_key(|e| std::cmp::Reverse(e.last_seen));
+        let budget = max_chars.saturating_sub(out.len());
+        let mut tail = Vec::new();
+        let mut used = 0;
+        for entry in recent {
+            let line = format_entry(entry);
+            if used + line.len() > budget {
+                break;
+            }
+            used += line.len();
+            tail.push(line);
+        }
+        if !tail.is_empty() {
+            out.push_str("\n🕒 Recent:\n");
+            for line in tail.into_iter().rev() {
+                out.push_str(&line);
+            }
+        }
+
+        out
+    }
+}
+
+fn format_entry(entry: &LogEntry) -> String {
+    let line: String = entry.line.chars().take(300).collect();
+    if entry.count > 1 {
+        format!("  [x{}] {}\n", entry.count, line)
+    } else {
+        format!("  {}\n", line)
+    }
+}
+
+/// Cluster error positions; clusters with enough errors are bursts
+fn find_bursts(error_lines: &[usize]) -> Vec<ErrorBurst> {
+    let mut bursts = Vec::new();
+    let mut start = 0;
+
+    for i in 1..=error_lines.len() {
+        let split = i == error_lines.len() || error_lines[i] - error_lines[i - 1] > BURST_GAP;
+        if split {
+            if i - start >= BURST_MIN_ERRORS {
+                bursts.push(ErrorBurst {
+                    start_line: error_lines[start] + 1,
+                    end_line: error_lines[i - 1] + 1,
+                    errors: i - start,
+                });
+            }
+            start = i;
+        }
+    }
+
+    bursts
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct TailLogsArgs {
+    /// Log source: a file path, `journal:<unit>` or `docker:<container>`
+    pub source: String,
+    /// Seconds to keep following new lines (0 = only read the last lines, max 120)
+    #[serde(default = "default_follow_secs")]
+    pub follow_secs: u64,
+    /// Existing lines to read before following
+    #[serde(default = "default_lines")]
+    pub lines: usize,
+}
+
+fn default_follow_secs() -> u64 {
+    5
+}
+
+fn default_lines() -> usize {
+    500
+}
+
+/// Tool for tailing and condensing logs
+#[derive(Debug, Clone, Default)]
+pub struct LogTailTool;
+
+impl LogTailTool {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Collect lines from `source`: the last `lines`, then everything written
+    /// during `follow_secs`
+    pub async fn collect(
+        &self,
+        source: &LogSource,
+        lines: usize,
+        follow_secs: u64,
+    ) -> Result<Vec<String>, LogError> {
+        source.validate()?;
+        let follow_secs = follow_secs.min(MAX_FOLLOW_SECS);
+        let mut child = source.command(lines, follow_secs > 0).spawn()?;
+
+        // stdout and stderr both carry log lines (docker logs uses both)
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        if let Some(stdout) = child.stdout.take() {
+            let tx = tx.clone();
+            tokio::spawn(async move {
+                let mut reader = BufReader::new(stdout).lines();
+                while let Ok(Some(line)) = reader.next_line().await {
+                    if tx.send(line).is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+        if let Some(stderr) = child.stderr.take() {
+            let tx = tx.clone();
+            tokio::spawn(async move {
+                let mut reader = BufReader::new(stderr).lines();
+                while let Ok(Some(line)) = reader.next_line().await {
+                    if tx.send(line).is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+        drop(tx);
+
+        // Without follow the command exits on its own; still bound the wait
+        let window = Duration::from_secs(if follow_secs > 0 { follow_secs } else { 10 });
+        let deadline = tokio::time::Instant::now() + window;
+        let mut collected = VecDeque::new();
+        while let Ok(Some(line)) = tokio::time::timeout_at(deadline, rx.recv()).await {
+            if collected.len() == MAX_COLLECTED_LINES {
+                collected.pop_front();
+            }
+            collected.push_back(line);
+        }
+
+        let _ = child.kill().await;
+        Ok(collected.into())
+    }
+
+    /// Collect and condense in one step
+    pub async fn digest(&self, args: &TailLogsArgs) -> Result<LogDigest, LogError> {
+        let source = LogSource::parse(&args.source);
+        let lines = self.collect(&source, args.lines, args.follow_secs).await?;
+        if lines.is_empty() {
+            return Err(LogError::Empty(source.to_string()));
+        }
+        Ok(LogDigest::from_lines(source.to_string(), &lines))
+    }
+}
+
+impl Tool for LogTailTool {
+    const NAME: &'static str = "tail_logs";
+
+    type Args = TailLogsArgs;
+    type Output = String;
+    type Error = LogError;
+
+    async fn definition(&self, _prompt: String) -> rig::completion::ToolDefinition {
+        rig::completion::ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: "Read and follow a log file, journalctl unit (journal:<unit>) or docker \
+                          container (docker:<name>) for a few seconds. Returns a condensed digest \
+                          with repeated lines grouped and error bursts highlighted."
+                .to_string(),
+            parameters: serde_json::to_value(schemars::schema_for!(TailLogsArgs))
+                .unwrap_or_default(),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        Ok(self.digest(&args).await?.render(DEFAULT_DIGEST_CHARS))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(raw: &[&str]) -> Vec<String> {
+        raw.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_source() {
+        assert_eq!(
+            LogSource::parse("journal:nginx"),
+            LogSource::Journal("nginx".into())
+        );
+        assert_eq!(
+            LogSource::parse("docker:api"),
+            LogSource::Docker("api".into())
+        );
+        assert_eq!(
+            LogSource::parse("logs/app.log"),
+            LogSource::File("logs/app.log".into())
+        );
+        assert_eq!(
+            LogSource::parse("logs/app.log").relative_to("/srv/"),
+            LogSource::File("/srv/logs/app.log".into())
+        );
+    }
+
+    #[test]
+    fn test_repeated_lines_are_grouped() {
+        let digest = LogDigest::from_lines(
+            "app.log",
+            &lines(&[
+                "2024-01-01T10:00:01 GET /health 200 3ms",
+                "2024-01-01T10:00:02 GET /health 200 4ms",
+                "2024-01-01T10:00:03 WARN slow query 1200ms",
+                "2024-01-01T10:00:04 GET /health 200 2ms",
+            ]),
+        );
+        assert_eq!(digest.total_lines, 4);
+        assert_eq!(digest.entries.len(), 2);
+        assert_eq!(digest.entries[0].count, 3);
+        assert_eq!(digest.warning_count, 1);
+        assert!(digest
+            .render(DEFAULT_DIGEST_CHARS)
+            .contains("[x3] 2024-01-01T10:00:01 GET /health"));
+    }
+
+    #[test]
+    fn test_error_bursts() {
+        let mut raw = vec!["ok".to_string(); 10];
+        for i in 0..6 {
+            raw.push(format!("ERROR connection refused to db (attempt {})", i));
+        }
+        raw.extend(vec!["ok".to_string(); 20]);
+        raw.push("ERROR single failure".to_string());
+
+        let digest = LogDigest::from_lines("app.log", &raw);
+        assert_eq!(digest.error_count, 7);
+        assert_eq!(
+            digest.bursts,
+            vec![ErrorBurst {
+                start_line: 11,
+                end_line: 16,
+                errors: 6
+            }]
+        );
+        let text = digest.render(DEFAULT_DIGEST_CHARS);
+        assert!(text.contains("🔥 Error burst: 6 errors in lines 11-16"));
+        assert!(text.contains("[x6] ERROR connection refused"));
+    }
+
+    #[tokio::test]
+    async fn test_collect_from_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("app.log");
+        std::fs::write(&path, "one\ntwo\nthree\n").unwrap();
+
+        let tool = LogTailTool::new();
+        let collected = tool
+            .collect(&LogSource::File(path.display().to_string()), 2, 0)
+            .await
+            .unwrap();
+        assert_eq!(collected, vec!["two", "three"]);
+
+        for source in [LogSource::Docker("-v".into()), LogSource::Journal("--all".into())] {
+            assert!(matches!(
+                tool.collect(&source, 10, 0).await,
+                Err(LogError::InvalidSource(_))
+            ));
+        }
+    }
+}
@@ -0,0 +1,473 @@
+//! Project memory
+//!
+//! Facts worth keeping across sessions ("we deploy with just, not make") are
+//! stored per working directory in the `memories` table, by the user with
+//! `/remember` or by the model with the `memory` tool. Each fact is embedded
+//! when saved; the facts closest to a query are added to the system prompt.
+//! Without an embedding model, recall falls back to shared words.
+//!
+//! Example answers (`/examples`) are kept the same way: a question and the
+//! answer or diff the user wants for it. The examples whose question is
+//! closest to a query are given to the model as few-shot examples, as many as
+//! fit in [`EXAMPLE_TOKEN_BUDGET`], and each use is counted so the list shows
+//! which ones steer the answers.
+
+use crate::context::manager::estimate_tokens;
+use crate::db::{Database, DatabaseError, Memory, StyleExample};
+use crate::embedding::{blob_to_embedding, embedding_to_blob, EmbeddingEngine};
+use rig::tool::Tool;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::sync::Arc;
+use thiserror::Error;
+use tokio::sync::OnceCell;
+
+/// Facts added to the system prompt of a turn
+pub const MAX_PROMPT_MEMORIES: usize = 5;
+/// Longest fact stored, in characters
+const MAX_MEMORY_CHARS: usize = 500;
+/// Lowest cosine similarity of a fact recalled by embedding
+const MIN_SIMILARITY: f32 = 0.35;
+/// Example answers added to the system prompt of a turn
+pub const MAX_PROMPT_EXAMPLES: usize = 3;
+/// Tokens the example answers of a turn may take
+pub const EXAMPLE_TOKEN_BUDGET: usize = 1_200;
+/// Longest example answer stored, in characters
+const MAX_EXAMPLE_CHARS: usize = 4_000;
+
+#[derive(Error, Debug)]
+pub enum MemoryError {
+    #[error("Memory unavailable: no database attached")]
+    NoDatabase,
+    #[error("Missing argument: {0}")]
+    MissingArgument(&'static str),
+    #[error("Memory too long ({0} characters): keep it to one short sentence")]
+    TooLong(usize),
+    #[error("No memory with id {0}")]
+    NotFound(i64),
+    #[error("Example answer too long ({0} characters): keep the part that shows the style")]
+    ExampleTooLong(usize),
+    #[error("No example with id {0}")]
+    ExampleNotFound(i64),
+    #[error("Memory task failed: {0}")]
+    Task(String),
+    #[error("Database error: {0}")]
+    Database(#[from] DatabaseError),
+}
+
+/// Operation performed by the tool
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum MemoryAction {
+    /// Store a fact
+    Remember,
+    /// Facts related to a query
+    #[default]
+    Recall,
+    /// All stored facts
+    List,
+    /// Delete a fact by id
+    Forget,
+}
+
+/// Arguments for the memory tool
+#[derive(Debug, Clone, Default, Deserialize, Serialize, JsonSchema)]
+pub struct MemoryArgs {
+    /// remember, recall, list or forget
+    #[serde(default)]
+    pub action: MemoryAction,
+    /// The fact to store, one short sentence (remember)
+    #[serde(default)]
+    pub content: Option<String>,
+    /// What to look for (recall)
+    #[serde(default)]
+    pub query: Option<String>,
+    /// Id of the fact to delete (forget)
+    #[serde(default)]
+    pub id: Option<i64>,
+    /// Project directory, filled in by the orchestrator
+    #[serde(skip)]
+    #[schemars(skip)]
+    pub project_dir: String,
+}
+
+/// Persistent per-project facts backed by the session database
+#[derive(Clone, Default)]
+pub struct MemoryTool {
+    db: Option<Database>,
+    /// Embed facts and queries; off in tests and without a database
+    embeddings: bool,
+    /// Loaded on first use, `None` when the model could not be loaded
+    embedder: Arc<OnceCell<Option<Arc<EmbeddingEngine>>>>,
+}
+
+impl MemoryTool {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Store facts in the given database
+    pub fn with_database(mut self, db: Database) -> Self {
+        self.db = Some(db);
+        self
+    }
+
+    /// Rank facts by embedding similarity (the model is loaded on first use)
+    pub fn with_embeddings(mut self) -> Self {
+        self.embeddings = true;
+        self
+    }
+
+    pub fn has_database(&self) -> bool {
+        self.db.is_some()
+    }
+
+    fn db(&self) -> Result<&Database, MemoryError> {
+        self.db.as_ref().ok_or(MemoryError::NoDatabase)
+    }
+
+    async fn embedder(&self) -> Option<Arc<EmbeddingEngine>> {
+        if !self.embeddings {
+            return None;
+        }
+        self.embedder
+            .get_or_init(|| async {
+                EmbeddingEngine::new()
+                    .await
+                    .map_err(|e| tracing::warn!("Memory recall without embeddings: {}", e))
+                    .ok()
+                    .map(Arc::new)
+            })
+            .await
+            .clone()
+    }
+
+    async fn embed(&self, text: &str) -> Option<Vec<f32>> {
+        let embedder = self.embedder().await?;
+        embedder
+            .embed_text(text)
+            .await
+            .map_err(|e| tracing::warn!("Failed to embed memory: {}", e))
+            .ok()
+    }
+
+    /// Store a fact for the project in `working_dir`; returns its id
+    pub async fn remember(&self, working_dir: &str, content: &str) -> Result<i64, MemoryError> {
+        let content = content.split_whitespace().collect::<Vec<_>>().join(" ");
+        if content.is_empty() {
+            return Err(MemoryError::MissingArgument("content"));
+        }
+        let length = content.chars().count();
+        if length > MAX_MEMORY_CHARS {
+            return Err(MemoryError::TooLong(length));
+        }
+        let db = self.db()?;
+        let blob = self.embed(&content).await.map(|embedding| embedding_to_blob(&embedding));
+        Ok(db
+            .add_memory(&Database::project_key(working_dir), &content, blob.as_deref())
+            .await?)
+    }
+
+    /// Every fact of the project, oldest first
+    pub async fn list(&self, working_dir: &str) -> Result<Vec<Memory>, MemoryError> {
+        Ok(self.db()?.list_memories(&Database::project_key(working_dir)).await?)
+    }
+
+    pub async fn forget(&self, working_dir: &str, id: i64) -> Result<(), MemoryError> {
+        let project = Database::project_key(working_dir);
+        if self.db()?.forget_memory(&project, id).await? {
+            Ok(())
+        } else {
+            Err(MemoryError::NotFound(id))
+        }
+    }
+
+    /// Up to `limit` facts related to `query`, closest first
+    pub async fn recall(
+        &self,
+        working_dir: &str,
+        query: &str,
+        limit: usize,
+    ) -> Result<Vec<Memory>, MemoryError> {
+        let memories = self.list(working_dir).await?;
+        if memories.is_empty() {
+            return Ok(memories);
+        }
+        let query_embedding = self.embed(query).await;
+        let query_words = words(query);
+
+        let mut scored: Vec<(f32, Memory)> = memories
+            .into_iter()
+            .filter_map(|memory| {
+                let score = similarity(&query_embedding, &query_words, memory.embedding.as_deref(), &memory.content);
+                score.map(|score| (score, memory))
+            })
+            .collect();
+        scored.sort_by(|a, b| b.0.total_cmp(&a.0));
+        Ok(scored.into_iter().take(limit).map(|(_, memory)| memory).collect())
+    }
+
+    /// Facts to add to the system prompt of a turn about `query`
+    pub async fn prompt_facts(&self, working_dir: &str, query: &str) -> Vec<String> {
+        if !self.has_database() {
+            return Vec::new();
+        }
+        match self.recall(working_dir, query, MAX_PROMPT_MEMORIES).await {
+            Ok(memories) => memories.into_iter().map(|memory| memory.content).collect(),
+            Err(e) => {
+                tracing::warn!("Failed to recall memories: {}", e);
+                Vec::new()
+            }
+        }
+    }
+
+    /// Store an example answer for `prompt`; returns its id. A repeated prompt
+    /// replaces its answer.
+    pub async fn add_example(&self, working_dir: &str, prompt: &str, response: &str) -> Result<i64, MemoryError> {
+        let prompt = prompt.split_whitespace().collect::<Vec<_>>().join(" ");
+        let response = response.trim();
+        if prompt.is_empty() {
+            return Err(MemoryError::MissingArgument("prompt"));
+        }
+        if response.is_empty() {
+            return Err(MemoryError::MissingArgument("response"));
+        }
+        let length = response.chars().count();
+        if length > MAX_EXAMPLE_CHARS {
+            return Err(MemoryError::ExampleTooLong(length));
+        }
+        let db = self.db()?;
+        let blob = self.embed(&prompt).await.map(|embedding| embedding_to_blob(&embedding));
+        Ok(db
+            .add_style_example(&Database::project_key(working_dir), &prompt, response, blob.as_deref())
+            .await?)
+    }
+
+    /// Every example of the project, oldest first
+    pub async fn examples(&self, working_dir: &str) -> Result<Vec<StyleExample>, MemoryError> {
+        Ok(self.db()?.list_style_examples(&Database::project_key(working_dir)).await?)
+    }
+
+    pub async fn remove_example(&self, working_dir: &str, id: i64) -> Result<(), MemoryError> {
+        let project = Database::project_key(working_dir);
+        if self.db()?.remove_style_example(&project, id).await? {
+            Ok(())
+        } else {
+            Err(MemoryError::ExampleNotFound(id))
+        }
+    }
+
+    /// Examples whose question is closest to `query`, closest first, as many
+    /// as fit in `budget` tokens; each one returned counts as used
+    pub async fn select_examples(
+        &self,
+        working_dir: &str,
+        query: &str,
+        budget: usize,
+    ) -> Result<Vec<StyleExample>, MemoryError> {
+        let examples = self.examples(working_dir).await?;
+        if examples.is_empty() {
+            return Ok(examples);
+        }
+        let query_embedding = self.embed(query).await;
+        let query_words = words(query);
+
+        let mut scored: Vec<(f32, StyleExample)> = examples
+            .into_iter()
+            .filter_map(|example| {
+                let score = similarity(&query_embedding, &query_words, example.embedding.as_deref(), &example.prompt);
+                score.map(|score| (score, example))
+            })
+            .collect();
+        scored.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+        // A long example that does not fit leaves room for a shorter, less similar one
+        let mut left = budget;
+        let mut selected = Vec::new();
+        for (_, example) in scored {
+            let tokens = estimate_tokens(&example.prompt) + estimate_tokens(&example.response);
+            if tokens <= left {
+                left -= tokens;
+                selected.push(example);
+            }
+            if selected.len() == MAX_PROMPT_EXAMPLES {
+                break;
+            }
+        }
+        let ids: Vec<i64> = selected.iter().map(|example| example.id).collect();
+        self.db()?.record_style_example_uses(&ids).await?;
+        Ok(selected)
+    }
+
+    /// `(question, answer)` examples to add to the system prompt of a turn about `query`
+    pub async fn prompt_examples(&self, working_dir: &str, query: &str) -> Vec<(String, String)> {
+        if !self.has_database() {
+            return Vec::new();
+        }
+        match self.select_examples(working_dir, query, EXAMPLE_TOKEN_BUDGET).await {
+            Ok(examples) => examples.into_iter().map(|example| (example.prompt, example.response)).collect(),
+            Err(e) => {
+                tracing::warn!("Failed to select example answers: {}", e);
+                Vec::new()
+            }
+        }
+    }
+
+    async fn run(&self, args: MemoryArgs) -> Result<String, MemoryError> {
+        let dir = if args.project_dir.is_empty() { "." } else { &args.project_dir };
+        match args.action {
+            MemoryAction::Remember => {
+                let content = args.content.ok_or(MemoryError::MissingArgument("content"))?;
+                let id = self.remember(dir, &content).await?;
+                Ok(format!("Remembered #{}: {}", id, content.trim()))
+            }
+            MemoryAction::Recall => {
+                let query = args.query.ok_or(MemoryError::MissingArgument("query"))?;
+                Ok(format_memories(&self.recall(dir, &query, MAX_PROMPT_MEMORIES).await?))
+            }
+            MemoryAction::List => Ok(format_memories(&self.list(dir).await?)),
+            MemoryAction::Forget => {
+                let id = args.id.ok_or(MemoryError::MissingArgument("id"))?;
+                self.forget(dir, id).await?;
+                Ok(format!("Forgot #{}", id))
+            }
+        }
+    }
+}
+
+/// One `#id fact` per line
+pub fn format_memories(memories: &[Memory]) -> String {
+    if memories.is_empty() {
+        return "No memories".to_string();
+    }
+    memories
+        .iter()
+        .map(|memory| format!("#{} {}", memory.id, memory.content))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// How close a stored text is to the query: cosine similarity of the
+/// embeddings when both exist, shared words otherwise
+fn similarity(
+    query_embedding: &Option<Vec<f32>>,
+    query_words: &HashSet<String>,
+    embedding: Option<&[u8]>,
+    text: &str,
+) -> Option<f32> {
+    let stored = embedding.and_then(|blob| blob_to_embedding(blob).ok());
+    match (query_embedding, stored) {
+        (Some(query), Some(stored)) => {
+            Some(EmbeddingEngine::cosine_similarity(query, &stored)).filter(|s| *s >= MIN_SIMILARITY)
+        }
+        _ => word_overlap(query_words, text),
+    }
+}
+
+/// Lowercase words of at least 3 characters
+fn words(text: &str) -> HashSet<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| word.chars().count() >= 3)
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// Share of the fact's words found in the query, `None` when there are none
+fn word_overlap(query: &HashSet<String>, content: &str) -> Option<f32> {
+    let content = words(content);
+    let shared = content.intersection(query).count();
+    (shared > 0).then(|| shared as f32 / content.len() as f32)
+}
+
+impl Tool for MemoryTool {
+    const NAME: &'static str = "memory";
+
+    type Args = MemoryArgs;
+    type Output = String;
+    type Error = MemoryError;
+
+    async fn definition(&self, _prompt: String) -> rig::completion::ToolDefinition {
+        rig::completion::ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: "Long-term memory of this project across sessions: remember a durable fact \
+                          the user states (conventions, commands, preferences), recall facts about a \
+                          topic, list them, or forget one by id."
+                .to_string(),
+            parameters: serde_json::to_value(schemars::schema_for!(MemoryArgs)).unwrap_or_default(),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        // sqlx futures are not `Sync`, which `Tool::call` requires: run on a task
+        let tool = self.clone();
+        tokio::spawn(async move { tool.run(args).await })
+            .await
+            .map_err(|e| MemoryError::Task(e.to_string()))?
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_remember_recall_forget() {
+        let tool = MemoryTool::new().with_database(Database::in_memory().await.unwrap());
+        let deploy = tool.remember("/srv/app", "We deploy with  just, not make").await.unwrap();
+        tool.remember("/srv/app", "Staging runs on port 8080").await.unwrap();
+        tool.remember("/srv/other", "deploy with helm").await.unwrap();
+
+        let recalled = tool.recall("/srv/app", "how do we deploy?", 5).await.unwrap();
+        assert_eq!(recalled.len(), 1);
+        assert_eq!(recalled[0].content, "We deploy with just, not make");
+        assert_eq!(
+            tool.prompt_facts("/srv/app", "what port does staging use").await,
+            vec!["Staging runs on port 8080".to_string()]
+        );
+
+        let listed = tool
+            .call(MemoryArgs { action: MemoryAction::List, project_dir: "/srv/app".to_string(), ..Default::default() })
+            .await
+            .unwrap();
+        assert_eq!(listed.lines().count(), 2);
+
+        tool.forget("/srv/app", deploy).await.unwrap();
+        assert!(matches!(tool.forget("/srv/app", deploy).await, Err(MemoryError::NotFound(_))));
+        assert!(tool.recall("/srv/app", "deploy", 5).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_examples_fit_the_budget() {
+        let tool = MemoryTool::new().with_database(Database::in_memory().await.unwrap());
+        let long = "x".repeat(2_000);
+        let endpoint = tool.add_example("/srv/app", "add an http endpoint", "Use an axum Router").await.unwrap();
+        tool.add_example("/srv/app", "add an http endpoint with auth", &long).await.unwrap();
+        tool.add_example("/srv/app", "write a migration", "sqlx migrate add").await.unwrap();
+        assert!(matches!(
+            tool.add_example("/srv/app", "explain", &"x".repeat(5_000)).await,
+            Err(MemoryError::ExampleTooLong(5_000))
+        ));
+
+        // The long example is closer but does not fit in the budget
+        let selected = tool.select_examples("/srv/app", "new http endpoint with auth", 100).await.unwrap();
+        assert_eq!(selected.len(), 1);
+        assert_eq!(selected[0].id, endpoint);
+        assert_eq!(
+            tool.prompt_examples("/srv/app", "how do I write a migration").await,
+            vec![("write a migration".to_string(), "sqlx migrate add".to_string())]
+        );
+
+        let uses: Vec<i64> = tool.examples("/srv/app").await.unwrap().iter().map(|e| e.uses).collect();
+        assert_eq!(uses, vec![1, 0, 1]);
+        tool.remove_example("/srv/app", endpoint).await.unwrap();
+        assert!(matches!(tool.remove_example("/srv/app", endpoint).await, Err(MemoryError::ExampleNotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn test_without_database() {
+        let tool = MemoryTool::new();
+        assert!(tool.prompt_facts(".", "deploy").await.is_empty());
+        assert!(matches!(tool.remember(".", "uses just").await, Err(MemoryError::NoDatabase)));
+    }
+}
@@ -7,11 +7,23 @@
 //!
 //! ## Análisis de Código
 //! - [`analyzer`] - Análisis de complejidad y métricas
+//! - [`complexity`] - Reporte de complejidad del proyecto con historial
+//! - [`conventions`] - Convenciones del proyecto aplicadas al código generado
+//! - [`build_errors`] - Base de conocimiento de errores de compilación recurrentes
 //! - [`linter`] - Ejecución de linters (Rust, Python, etc.)
 //! - [`dependencies`] - Análisis de dependencias
+//! - [`dependency_docs`] - Documentación de las dependencias principales (docs.rs, npm, PyPI)
+//! - [`examples_runner`] - Verificación de ejemplos Rust/Python de las respuestas
+//! - [`benchmarks`] - Benchmarks (criterion, pytest-benchmark) por commit y regresiones frente a una rama base
+//! - [`flaky`] - Detección de tests inestables con ejecuciones repetidas e historial
+//! - [`profiler`] - Perfilado con cargo flamegraph, py-spy o node --prof y sus puntos calientes
+//! - [`symbol_index`] - Índice de símbolos en SQLite (búsqueda difusa, símbolo en línea)
+//! - [`references`] - Referencias de un símbolo en todo el proyecto
+//! - [`dead_code`] - Funciones y tipos sin referencias, por lenguaje
 //!
 //! ## Modificación de Código
 //! - [`refactor`] - Refactorización automatizada
+//! - [`replace`] - Reemplazo en todo el proyecto con vista previa, selección y deshacer
 //! - [`formatter`] - Formateo de código
 //!
 //! ## Búsqueda
@@ -21,11 +33,30 @@
 //!
 //! ## Control de Versiones
 //! - [`git`] - Operaciones git (status, diff, blame, etc.)
+//! - [`git_workflow`] - Stash, ramas y rebase guiado, con vista previa y confirmación
+//! - [`ownership`] - Dueños del código según el historial git, para sugerir revisores
+//! - [`change_summary`] - Cambios de un diff por símbolo (función, método, tipo) para mensajes de commit
 //!
 //! ## Sistema
 //! - [`filesystem`] - Operaciones de archivos
+//! - [`file_reader`] - Lectura paralela con presupuesto de bytes por turno y mmap
+//! - [`read_ranges`] - Lectura por símbolo, alrededor de un texto o por ámbito completo
+//! - [`tree_summary`] - Resumen de directorios (archivos, tamaños, lenguajes) con caché
 //! - [`shell`] - Ejecución de comandos shell
-//! - [`environment`] - Variables de entorno
+//! - [`environment`] - Variables de entorno y versiones de runtimes instalados
+//! - [`memory`] - Memoria del proyecto entre sesiones (`/remember`, recuperación por embeddings)
+//! - [`logs`] - Seguimiento y resumen de logs (archivos, journalctl, docker)
+//! - [`docker`] - Dockerfiles y docker-compose (servicios, puertos, volúmenes)
+//! - [`dev_server`] - Servidores de desarrollo en segundo plano con su salida reciente
+//! - [`kubernetes`] - Inspección de Kubernetes (opcional, solo lectura por defecto)
+//! - [`terraform`] - Resumen de planes de Terraform (nunca aplica)
+//! - [`scaffold`] - Plantillas de proyectos para `/new` (integradas y del usuario)
+//! - [`wasm_plugin`] - Herramientas propias en WebAssembly, aisladas con wasmtime y WASI
+//!
+//! ## Datos
+//! - [`database`] - Consultas SQL de solo lectura (SQLite, Postgres, MySQL)
+//! - [`data_preview`] - Vista previa y estadísticas de CSV, JSONL y Parquet
+//! - [`schema`] - Ingesta del esquema de la base de datos (introspección, SQL, Prisma, Diesel)
 //!
 //! # Ejemplo de Uso
 //!
@@ -54,28 +85,57 @@
 mod command;
 mod filesystem;
 mod linter;
+mod logs;
 pub mod registry;
 
 // New comprehensive tools
 mod analyzer;
+mod benchmarks;
+mod build_errors;
 mod calculator;
+mod change_summary;
+mod complexity;
 mod context;
+mod conventions;
 mod context_cache;
+mod data_preview;
+mod dead_code;
+mod database;
 mod dependencies;
+mod dependency_docs;
+mod dev_server;
+mod docker;
 mod documentation;
 mod environment;
+mod examples_runner;
+mod file_reader;
+mod flaky;
 mod formatter;
 mod git;
+mod git_workflow;
 mod http_client;
 pub mod incremental_indexer;
 mod indexer;
+mod kubernetes;
+mod memory;
+mod ownership;
 pub mod planner;
+mod profiler;
 mod raptor_tool;
+mod read_ranges;
 mod refactor;
+mod replace;
+mod references;
+mod scaffold;
+mod schema;
 mod search;
 mod shell;
 mod snippets;
+mod symbol_index;
+mod terraform;
 mod test_runner;
+mod tree_summary;
+mod wasm_plugin;
 
 // Re-export existing tools
 pub use calculator::CalculatorTool;
@@ -85,53 +145,123 @@ pub use filesystem::{
     FileWriteTool, ListDirectoryArgs, ListDirectoryOutput, ListDirectoryTool,
 };
 pub use linter::{LinterArgs, LinterDiagnostic, LinterMode, LinterOutput, LinterTool};
-pub use registry::ToolRegistry;
+pub use logs::{
+    ErrorBurst, LogDigest, LogEntry, LogError, LogLevel, LogSource, LogTailTool, TailLogsArgs,
+    DEFAULT_DIGEST_CHARS,
+};
+pub use registry::{ToolListing, ToolOrigin, ToolRegistry};
 
 // Re-export new tools
 pub use analyzer::{
     AnalyzeFileArgs, AnalyzeSymbolArgs, AnalyzerError, CodeAnalysis, CodeAnalyzerTool, CodeIssue, 
     CodeMetrics, CodeSymbol, ImportInfo, SymbolType,
 };
+pub use benchmarks::{
+    format_ns, BenchComparison, BenchFramework, BenchTiming, BenchmarkArgs, BenchmarkError, BenchmarkReport,
+    BenchmarkTool, DEFAULT_REGRESSION_THRESHOLD,
+};
+pub use build_errors::{parse_errors, BuildErrorKb, BuildRunner, ParsedError};
+pub use change_summary::{commit_message, summarize_file, ChangedSymbol, FileChangeSummary, SymbolChange};
+pub use complexity::{
+    format_trends, ComplexityReport, FileMetrics, FunctionMetrics, Hotspot,
+};
+pub use conventions::{
+    format_violations, CaseStyle, ConventionChecker, ConventionViolation, ProjectConventions,
+};
 pub use context::{
     ContextError, ContextSummary, DirectoryStructure, GitInfo, ImportantFile, PrimaryLanguage,
     ProjectContext, ProjectContextTool, ProjectType as ContextProjectType,
 };
 pub use context_cache::{CacheError, CachedProjectContext, ContextCacheTool, ProjectMetrics};
+pub use data_preview::{
+    ColumnStats, DataFormat, DataPreview, DataPreviewArgs, DataPreviewError, DataPreviewTool,
+};
+pub use dead_code::{DeadCodeArgs, DeadCodeReport, DeadCodeTool, DeadSymbol};
+pub use database::{
+    classify_statement, DatabaseQueryArgs, DatabaseQueryError, DatabaseQueryTool, Dialect,
+    QueryOutput, StatementKind,
+};
 pub use dependencies::{
     AnalyzeDepsArgs, Dependency, DependencyAnalysis, DependencyAnalyzerTool, DependencySource, DepsError,
     OutdatedDependency, ProjectType as DepsProjectType, SecurityIssue,
 };
+pub use dependency_docs::{
+    dependency_docs_context_for, DependencyDocs, DependencyDocsError, DependencyDocsTool,
+    DocsFetchReport, Ecosystem, DEFAULT_TOP_DEPENDENCIES,
+};
+pub use dev_server::{
+    default_process_name, DevServerAction, DevServerArgs, DevServerError, DevServerTool,
+    ProcessInfo, DEFAULT_DEV_SERVER_LINES,
+};
+pub use docker::{
+    ComposeAction, ComposeService, ContainerTopology, DockerComposeArgs, DockerComposeOutput,
+    DockerComposeTool, DockerError, DockerfileInfo,
+};
 pub use documentation::{
     ClassDoc, DocError, DocFormat, DocGenArgs, DocOutput, DocumentationTool, FunctionDoc,
     ModuleDoc, ParamDoc, ProjectInfo,
 };
 pub use environment::{
-    DiskUsage, EnvironmentInfo, EnvironmentTool, RuntimeInfo, ShellInfo, SystemInfo,
+    DiskUsage, EnvironmentInfo, EnvironmentTool, InstalledRuntime, RuntimeInfo, RuntimeMatrix,
+    ShellInfo, SystemInfo, ToolchainInfo, VersionMismatch,
+};
+pub use examples_runner::{
+    extract_examples, CodeExample, ExampleLanguage, ExampleMode, ExampleRunner, Verification,
+    DEFAULT_EXAMPLE_TIMEOUT_SECS,
+};
+pub use file_reader::{
+    FileReader, FileSlice, DEFAULT_READ_CONCURRENCY, DEFAULT_TURN_READ_BUDGET, MMAP_THRESHOLD,
+};
+pub use flaky::{
+    detect_flaky, record_flakiness, FailureSignature, FlakyReport, TestVariance, DEFAULT_FLAKY_RUNS,
+    MAX_FLAKY_RUNS, TRIAGE_TESTS,
 };
 pub use formatter::{
     FormatArgs, FormatConfig, FormatError, FormatLanguage, FormatOutput, FormatResult,
     FormatterTool, QuoteStyle,
 };
 pub use git::{
-    BlameLine, BranchInfo, CommitInfo, DiffOutput, FileDiff, GitAddArgs, GitCommitArgs,
-    GitError, GitStatus, GitStatusArgs, GitDiffArgs, GitTool,
+    BlameLine, BranchInfo, CommitInfo, DiffFileKind, DiffOutput, FileDiff, GitAddArgs,
+    GitCommitArgs, GitError, GitStatus, GitStatusArgs, GitDiffArgs, GitTool,
+};
+pub use git_workflow::{
+    GitAction, GitWorkflowArgs, GitWorkflowOutput, RebaseAction, RebaseCommit, RebasePlan,
+    RebaseStep,
 };
 pub use http_client::{
-    ApiClient, DownloadResult, HttpClientTool, HttpError, HttpMethod, HttpRequestArgs, HttpResponse,
+    ApiClient, DownloadResult, HttpAuthProfile, HttpClientTool, HttpError, HttpMethod,
+    HttpRequestArgs, HttpResponse, resolve_save_path,
 };
 pub use incremental_indexer::{IncrementalIndexer, UpdateReport};
 pub use indexer::{
     FileIndexerTool, FileInfo as IndexedFileInfo, IndexerError, LanguageStats, ProjectIndex,
     ProjectSummary,
 };
+pub use kubernetes::{KubeResource, KubeVerb, KubernetesArgs, KubernetesError, KubernetesTool};
+pub use memory::{format_memories, MemoryAction, MemoryArgs, MemoryError, MemoryTool, MAX_PROMPT_MEMORIES};
+pub use ownership::{format_owners, Owner, Ownership, OwnershipArgs, OwnershipTool};
 pub use planner::{PlanStatus, Task, TaskEffort, TaskPlan, TaskPlannerTool, TaskStatus, TaskType};
+pub use profiler::{
+    ProfileArgs, ProfileReport, Profiler, ProfilerError, ProfilerTool, DEFAULT_HOTSPOTS,
+};
 pub use raptor_tool::{BuildTreeArgs, QueryTreeArgs, RaptorTool, RaptorToolCalls};
 pub use refactor::{
     ExtractType, RefactorArgs, RefactorChange, RefactorError, RefactorOperation, RefactorResult,
     RefactorScope, RefactorTool,
 };
+pub use references::{FindReferencesArgs, FindReferencesTool, Reference, ReferenceReport};
+pub use scaffold::{
+    BuildCheck, ProjectTemplate, ProjectTemplates, ScaffoldError, ScaffoldReport, TemplateSource,
+    TEMPLATE_MANIFEST,
+};
+pub use schema::{
+    schema_context_for, ColumnSchema, DatabaseSchema, ForeignKey, SchemaError, SchemaIngestArgs,
+    SchemaIngestTool, TableSchema, SCHEMA_DOCS_NAME,
+};
+pub use replace::{ReplaceArgs, ReplaceBatch, ReplaceEdit, ReplaceError, ReplacePlan, ReplaceTool};
 pub use search::{
-    ReplaceOutput, SearchArgs, SearchError, SearchInFilesTool, SearchOutput, SearchResult,
+    ReplaceOutput, SearchArgs, SearchError, SearchInFilesTool, SearchMode, SearchOutput,
+    SearchResult,
 };
 // pub use semantic_search::{ // Deprecated: Use Raptor instead
 //     SearchResultFormatted, SemanticSearchArgs, SemanticSearchError, SemanticSearchOutput,
@@ -139,10 +269,23 @@ pub use search::{
 // };
 pub use shell::{OutputLine, ShellArgs, ShellError, ShellExecutorTool, ShellResult};
 pub use snippets::{CodeSnippet, Placeholder, SnippetCollection, SnippetError, SnippetTool};
+pub(crate) use symbol_index::source_files;
+pub use symbol_index::{
+    fuzzy_score, symbol_mentions, SymbolIndexArgs, SymbolIndexError, SymbolIndexReport,
+    SymbolIndexTool, SymbolLookup,
+};
+pub use terraform::{
+    PlanAction, PlanRisk, PlanSummary, PlannedChange, TerraformError, TerraformPlanArgs,
+    TerraformPlanTool,
+};
 pub use test_runner::{
     TestArgs, TestCase, TestError, TestFramework, TestOutput, TestRunnerTool, TestStatus,
     TestSummary,
 };
+pub use tree_summary::{TreeEntry, TreeSummaries, TreeSummary};
+pub use wasm_plugin::{
+    PluginGrant, PluginManifest, PluginsConfig, WasmPlugin, WasmPluginError, WasmPluginHost,
+};
 
 /// All available tool names
 pub const AVAILABLE_TOOLS: &[&str] = &[
@@ -151,30 +294,47 @@ pub const AVAILABLE_TOOLS: &[&str] = &[
     "write_file",
     "list_directory",
     "search_files",
+    "replace_in_files",
     "file_indexer",
     // Code operations
     "analyze_code",
     "format_code",
     "refactor_code",
     "lint_code",
+    "symbol_index",
+    "find_references",
+    "find_dead_code",
+    "profile_code",
     // Project operations
     "project_context",
     "analyze_dependencies",
     "generate_documentation",
     "run_tests",
+    "run_benchmarks",
     // Git operations
     "git_status",
     "git_diff",
     "git_log",
     "git_commit",
     "git_blame",
+    "git_workflow",
+    "code_owners",
     // Shell operations
     "execute_shell",
     "environment_info",
+    "tail_logs",
+    "docker_compose",
+    "dev_server",
+    "kubernetes",
+    "terraform_plan",
     // Planning
     "task_planner",
     // HTTP
     "http_request",
+    // Database
+    "query_database",
+    "preview_data",
+    "ingest_schema",
     // Snippets
     "snippets",
     // RAPTOR - Context Management
@@ -182,6 +342,7 @@ pub const AVAILABLE_TOOLS: &[&str] = &[
     "query_raptor_tree",
     "raptor_stats",
     "clear_raptor",
+    "memory",
 ];
 
 /// Tool category
@@ -201,20 +362,21 @@ pub enum ToolCategory {
 /// Get tool category
 pub fn get_tool_category(tool_name: &str) -> ToolCategory {
     match tool_name {
-        "read_file" | "write_file" | "list_directory" | "search_files" | "file_indexer" => {
-            ToolCategory::FileSystem
-        }
-        "analyze_code" | "format_code" | "refactor_code" | "lint_code" => {
-            ToolCategory::CodeAnalysis
-        }
-        "project_context" | "analyze_dependencies" | "generate_documentation" | "run_tests" => {
-            ToolCategory::ProjectManagement
-        }
-        "git_status" | "git_diff" | "git_log" | "git_commit" | "git_blame" => ToolCategory::Git,
-        "execute_shell" | "environment_info" => ToolCategory::Shell,
+        "read_file" | "write_file" | "list_directory" | "search_files" | "replace_in_files"
+        | "file_indexer" | "preview_data" => ToolCategory::FileSystem,
+        "analyze_code" | "format_code" | "refactor_code" | "lint_code" | "symbol_index"
+        | "find_references" | "find_dead_code" | "profile_code" => ToolCategory::CodeAnalysis,
+        "project_context" | "analyze_dependencies" | "generate_documentation" | "run_tests"
+        | "run_benchmarks" | "terraform_plan" | "ingest_schema" => ToolCategory::ProjectManagement,
+        "git_status" | "git_diff" | "git_log" | "git_commit" | "git_blame" | "git_workflow"
+        | "code_owners" => {
+            ToolCategory::Git
+        },
+        "execute_shell" | "environment_info" | "tail_logs" | "docker_compose" | "dev_server"
+        | "kubernetes" => ToolCategory::Shell,
         "http_request" => ToolCategory::Network,
         "task_planner" => ToolCategory::Planning,
-        "build_raptor_tree" | "query_raptor_tree" | "raptor_stats" | "clear_raptor" => {
+        "build_raptor_tree" | "query_raptor_tree" | "raptor_stats" | "clear_raptor" | "memory" => {
             ToolCategory::ContextManagement
         }
         _ => ToolCategory::Utilities,
@@ -0,0 +1,422 @@
+//! Code ownership from git history
+//!
+//! Every commit adds to its author's score on the files it touched, weighted
+//! by size (log-scaled, so a reformat does not outweigh years of fixes) and by
+//! recency: a commit counts double for every [`HALF_LIFE_DAYS`] it is newer.
+//! Scores are stored relative to the Unix epoch, so commits made after the
+//! last computation are simply added: the history is read once per
+//! repository, then only `<cached head>..HEAD` is. Results are cached in
+//! memory and, with a session database, across runs. Rewritten history (a
+//! cached head that is no longer an ancestor) starts over.
+
+use super::git::{run_git_command, GitError};
+use crate::db::Database;
+use crate::log_warn;
+use rig::tool::Tool;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+/// A commit counts double for every half-life it is newer
+const HALF_LIFE_DAYS: f64 = 180.0;
+/// Commits read when a repository is first computed
+const MAX_COMMITS: usize = 5000;
+/// Owners listed by default
+const DEFAULT_LIMIT: usize = 5;
+
+/// Separators of the `git log` records and fields
+const RECORD_SEP: char = '\x1e';
+const FIELD_SEP: char = '\x1f';
+
+/// An author's share of a file or directory
+#[derive(Debug, Clone, PartialEq)]
+pub struct Owner {
+    pub name: String,
+    pub email: String,
+    /// Fraction of the recency-weighted score, 0..=1
+    pub share: f64,
+    pub commits: usize,
+    /// Unix time of the author's last commit there
+    pub last_commit: i64,
+}
+
+impl Owner {
+    /// `YYYY-MM-DD` of the last commit
+    pub fn last_date(&self) -> String {
+        chrono::DateTime::from_timestamp(self.last_commit, 0)
+            .map(|date| date.format("%Y-%m-%d").to_string())
+            .unwrap_or_default()
+    }
+}
+
+/// One author's history on one file
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+struct Contribution {
+    /// Σ size × 2^(time / half-life)
+    score: f64,
+    commits: usize,
+    last_commit: i64,
+}
+
+/// Per-file contributions of every author, up to `head`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Ownership {
+    head: String,
+    /// path → author email → contribution
+    files: HashMap<String, HashMap<String, Contribution>>,
+    /// email → most recent name
+    names: HashMap<String, String>,
+}
+
+impl Ownership {
+    /// Add the commits of `git log --numstat --format=<RECORD_SEP>%at<FIELD_SEP>%aN<FIELD_SEP>%aE`
+    fn apply(&mut self, log: &str) {
+        for record in log.split(RECORD_SEP).filter(|record| !record.trim().is_empty()) {
+            let mut lines = record.lines();
+            let header: Vec<&str> = lines.next().unwrap_or_default().split(FIELD_SEP).collect();
+            let [time, name, email] = header[..] else { continue };
+            let Ok(time) = time.trim().parse::<i64>() else { continue };
+            if name.ends_with("[bot]") {
+                continue;
+            }
+            let email = email.trim().to_lowercase();
+            let recency = (time as f64 / (HALF_LIFE_DAYS * 86_400.0)).exp2();
+
+            for line in lines {
+                let mut fields = line.splitn(3, '\t');
+                let (Some(added), Some(deleted), Some(path)) = (fields.next(), fields.next(), fields.next()) else {
+                    continue;
+                };
+                // Binary files report `-`
+                let changed = added.parse::<f64>().unwrap_or(0.0) + deleted.parse::<f64>().unwrap_or(0.0);
+                let entry = self
+                    .files
+                    .entry(renamed_path(path))
+                    .or_default()
+                    .entry(email.clone())
+                    .or_default();
+                entry.score += (1.0 + changed.ln_1p()) * recency;
+                entry.commits += 1;
+                entry.last_commit = entry.last_commit.max(time);
+            }
+            // `git log` lists newest first: keep the first name seen
+            if !self.names.contains_key(&email) {
+                self.names.insert(email, name.trim().to_string());
+            }
+        }
+    }
+
+    /// Bring the ownership of `repo` up to `head`, reading only the new commits when possible
+    fn update(&mut self, repo: &PathBuf, head: &str) -> Result<(), GitError> {
+        let incremental = !self.head.is_empty()
+            && run_git_command(repo, &["merge-base", "--is-ancestor", &self.head, head]).is_ok();
+        let range = if incremental {
+            format!("{}..{}", self.head, head)
+        } else {
+            *self = Self::default();
+            head.to_string()
+        };
+        let format = format!("--format={}%at{}%aN{}%aE", RECORD_SEP, FIELD_SEP, FIELD_SEP);
+        let max_count = format!("--max-count={}", MAX_COMMITS);
+        let log = run_git_command(repo, &["log", "--no-merges", "--numstat", &format, &max_count, &range])?;
+        self.apply(&log);
+        self.head = head.to_string();
+        Ok(())
+    }
+
+    /// Owners of the files at or below `path` (relative to the repository
+    /// root; empty for the whole repository), largest share first
+    pub fn owners(&self, path: &str, limit: usize) -> Vec<Owner> {
+        self.reviewers(&[path.to_string()], None, limit)
+    }
+
+    /// Who to ask to review a change to `paths` (files or directories),
+    /// leaving out `author`
+    pub fn reviewers(&self, paths: &[String], author: Option<&str>, limit: usize) -> Vec<Owner> {
+        let paths: Vec<&str> = paths.iter().map(|path| path.trim_matches('/')).collect();
+        self.ranked(
+            self.files.iter().filter(|(file, _)| paths.iter().any(|path| covers(path, file))),
+            author,
+            limit,
+        )
+    }
+
+    fn ranked<'a>(
+        &'a self,
+        files: impl Iterator<Item = (&'a String, &'a HashMap<String, Contribution>)>,
+        excluded: Option<&str>,
+        limit: usize,
+    ) -> Vec<Owner> {
+        let excluded = excluded.map(str::to_lowercase);
+        let mut totals: HashMap<&str, Contribution> = HashMap::new();
+        for (_, authors) in files {
+            for (email, contribution) in authors {
+                let total = totals.entry(email.as_str()).or_default();
+                total.score += contribution.score;
+                total.commits += contribution.commits;
+                total.last_commit = total.last_commit.max(contribution.last_commit);
+            }
+        }
+        let sum: f64 = totals.values().map(|total| total.score).sum();
+        let mut owners: Vec<Owner> = totals
+            .into_iter()
+            .filter(|(email, _)| excluded.as_deref() != Some(*email))
+            .map(|(email, total)| Owner {
+                name: self.names.get(email).cloned().unwrap_or_else(|| email.to_string()),
+                email: email.to_string(),
+                share: if sum > 0.0 { total.score / sum } else { 0.0 },
+                commits: total.commits,
+                last_commit: total.last_commit,
+            })
+            .collect();
+        owners.sort_by(|a, b| b.share.total_cmp(&a.share).then_with(|| a.email.cmp(&b.email)));
+        owners.truncate(limit);
+        owners
+    }
+}
+
+/// Whether `file` is `path` or below it; the empty path covers everything
+fn covers(path: &str, file: &str) -> bool {
+    path.is_empty() || file == path || file.strip_prefix(path).is_some_and(|rest| rest.starts_with('/'))
+}
+
+/// New path of a `--numstat` rename (`src/{old => new}/a.rs`, `old.rs => new.rs`)
+fn renamed_path(path: &str) -> String {
+    let Some(arrow) = path.find(" => ") else {
+        return path.to_string();
+    };
+    match (path[..arrow].rfind('{'), path[arrow..].find('}')) {
+        (Some(open), Some(close)) => {
+            let new = &path[arrow + 4..arrow + close];
+            let joined = format!("{}{}{}", &path[..open], new, &path[arrow + close + 1..]);
+            // `{old => }/a.rs` leaves a doubled separator
+            joined.replace("//", "/")
+        }
+        _ => path[arrow + 4..].to_string(),
+    }
+}
+
+/// Arguments for code_owners
+#[derive(Debug, Clone, Default, Deserialize, Serialize, JsonSchema)]
+pub struct OwnershipArgs {
+    /// File or directory, relative to the project root (default: the whole project)
+    #[serde(default)]
+    pub path: Option<String>,
+    /// Maximum number of owners (default 5)
+    #[serde(default)]
+    pub limit: Option<usize>,
+    /// Project directory, filled in by the orchestrator
+    #[serde(skip)]
+    #[schemars(skip)]
+    pub project_dir: String,
+}
+
+/// Who knows a part of the code best, from its git history
+#[derive(Clone, Default)]
+pub struct OwnershipTool {
+    /// Repository root → ownership
+    cache: Arc<Mutex<HashMap<PathBuf, Ownership>>>,
+    db: Option<Database>,
+}
+
+impl OwnershipTool {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Keep computed ownership in `db`, so it survives restarts
+    pub fn with_database(mut self, db: Database) -> Self {
+        self.db = Some(db);
+        self
+    }
+
+    /// Owners of `path` (relative to `root`, `None` for all of it)
+    pub async fn owners(&self, root: &Path, path: Option<&str>, limit: usize) -> Result<Vec<Owner>, GitError> {
+        let (repo, prefix) = repository(root)?;
+        let path = join(&prefix, path.unwrap_or(""));
+        self.with_ownership(&repo, |ownership| ownership.owners(&path, limit)).await
+    }
+
+    /// Reviewers for a change to `paths` (files or directories relative to
+    /// `root`), leaving out the configured git user
+    pub async fn suggest_reviewers(&self, root: &Path, paths: &[String], limit: usize) -> Result<Vec<Owner>, GitError> {
+        let (repo, prefix) = repository(root)?;
+        let paths: Vec<String> = paths.iter().map(|path| join(&prefix, path)).collect();
+        self.reviewers_in(&repo, &paths, limit).await
+    }
+
+    /// Reviewers for the files changed by `commit`
+    pub async fn commit_reviewers(&self, root: &Path, commit: &str, limit: usize) -> Result<Vec<Owner>, GitError> {
+        let (repo, _) = repository(root)?;
+        let changed = run_git_command(&repo, &["show", "--name-only", "--format=", commit])?;
+        let paths: Vec<String> = changed.lines().filter(|line| !line.is_empty()).map(String::from).collect();
+        self.reviewers_in(&repo, &paths, limit).await
+    }
+
+    /// Reviewers for `paths`, relative to the root of `repo`
+    async fn reviewers_in(&self, repo: &PathBuf, paths: &[String], limit: usize) -> Result<Vec<Owner>, GitError> {
+        if paths.is_empty() {
+            return Ok(Vec::new());
+        }
+        let author = run_git_command(repo, &["config", "user.email"]).ok();
+        let author = author.as_deref().map(str::trim);
+        self.with_ownership(repo, |ownership| ownership.reviewers(paths, author, limit)).await
+    }
+
+    /// Run `query` on the ownership of `repo`, refreshed up to its HEAD
+    async fn with_ownership<T>(&self, repo: &PathBuf, query: impl FnOnce(&Ownership) -> T) -> Result<T, GitError> {
+        let head = run_git_command(repo, &["rev-parse", "HEAD"])?.trim().to_string();
+        let cached = self.cache.lock().unwrap_or_else(|e| e.into_inner()).remove(repo);
+        let mut ownership = match cached {
+            Some(ownership) => ownership,
+            None => self.load(repo).await.unwrap_or_default(),
+        };
+        if ownership.head != head {
+            ownership.update(repo, &head)?;
+            self.save(repo, &ownership).await;
+        }
+        let result = query(&ownership);
+        self.cache.lock().unwrap_or_else(|e| e.into_inner()).insert(repo.clone(), ownership);
+        Ok(result)
+    }
+
+    async fn load(&self, repo: &Path) -> Option<Ownership> {
+        let (_, json) = self.db.as_ref()?.get_code_ownership(&repo.to_string_lossy()).await.ok()??;
+        serde_json::from_str(&json).ok()
+    }
+
+    async fn save(&self, repo: &Path, ownership: &Ownership) {
+        let Some(db) = &self.db else { return };
+        let Ok(json) = serde_json::to_string(ownership) else { return };
+        if let Err(e) = db.save_code_ownership(&repo.to_string_lossy(), &ownership.head, &json).await {
+            log_warn!("⚠️ Failed to cache code ownership: {}", e);
+        }
+    }
+}
+
+/// Root of the repository containing `dir`, and `dir` relative to it
+fn repository(dir: &Path) -> Result<(PathBuf, String), GitError> {
+    let dir = dir.canonicalize()?;
+    let top = run_git_command(&dir, &["rev-parse", "--show-toplevel"]).map_err(|_| GitError::NotAGitRepo)?;
+    let repo = PathBuf::from(top.trim()).canonicalize()?;
+    let prefix = dir.strip_prefix(&repo).unwrap_or(Path::new("")).to_string_lossy().replace('\\', "/");
+    Ok((repo, prefix))
+}
+
+/// `path` below `prefix`, in the form `git log` prints
+fn join(prefix: &str, path: &str) -> String {
+    let path = path.trim().trim_start_matches("./").trim_matches('/');
+    let joined = match (prefix.is_empty(), path.is_empty() || path == ".") {
+        (true, _) => path.to_string(),
+        (false, true) => prefix.to_string(),
+        (false, false) => format!("{}/{}", prefix, path),
+    };
+    if joined == "." { String::new() } else { joined }
+}
+
+/// `1. Name <email>: 46% of recent changes, 12 commits, last 2026-09-30` lines
+pub fn format_owners(owners: &[Owner]) -> String {
+    owners
+        .iter()
+        .enumerate()
+        .map(|(i, owner)| {
+            format!(
+                "{}. {} <{}>: {:.0}% of recent changes, {} commits, last {}\n",
+                i + 1,
+                owner.name,
+                owner.email,
+                owner.share * 100.0,
+                owner.commits,
+                owner.last_date()
+            )
+        })
+        .collect()
+}
+
+impl Tool for OwnershipTool {
+    const NAME: &'static str = "code_owners";
+
+    type Args = OwnershipArgs;
+    type Output = String;
+    type Error = GitError;
+
+    async fn definition(&self, _prompt: String) -> rig::completion::ToolDefinition {
+        rig::completion::ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: "Who knows a file or directory best, from its git history weighted towards \
+                          recent commits: share of changes, commit count and last commit per author. \
+                          Use it to suggest reviewers or who to ask about a piece of code."
+                .to_string(),
+            parameters: serde_json::to_value(schemars::schema_for!(OwnershipArgs)).unwrap_or_default(),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        // sqlx futures are not `Sync`, which `Tool::call` requires: run on a task
+        let tool = self.clone();
+        tokio::spawn(async move {
+            let root = PathBuf::from(if args.project_dir.is_empty() { "." } else { &args.project_dir });
+            let path = args.path.as_deref().filter(|path| !path.trim().is_empty());
+            let owners = tool.owners(&root, path, args.limit.unwrap_or(DEFAULT_LIMIT)).await?;
+            let scope = path.unwrap_or("the project");
+            Ok(if owners.is_empty() {
+                format!("No commit history for {}", scope)
+            } else {
+                format!("Owners of {}:\n{}", scope, format_owners(&owners))
+            })
+        })
+        .await
+        .map_err(|e| GitError::CommandFailed(e.to_string()))?
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn commit(time: i64, name: &str, email: &str, files: &[&str]) -> String {
+        let mut record = format!("{}{}{}{}{}{}\n\n", RECORD_SEP, time, FIELD_SEP, name, FIELD_SEP, email);
+        for file in files {
+            record.push_str(&format!("10\t2\t{}\n", file));
+        }
+        record
+    }
+
+    #[test]
+    fn test_recent_commits_weigh_more() {
+        let day = 86_400;
+        let mut ownership = Ownership::default();
+        // Ana wrote the parser long ago; Luis has been changing it lately
+        let log = [
+            commit(1_000 * day, "Luis", "luis@example.com", &["src/parser.rs"]),
+            commit(999 * day, "Luis", "luis@example.com", &["src/parser.rs", "src/lexer.rs"]),
+            commit(100 * day, "Ana", "ANA@example.com", &["src/parser.rs"]),
+            commit(99 * day, "Ana", "ana@example.com", &["src/parser.rs"]),
+            commit(98 * day, "Ana", "ana@example.com", &["src/parser.rs"]),
+            commit(900 * day, "dependabot[bot]", "bot@example.com", &["src/parser.rs"]),
+        ]
+        .concat();
+        ownership.apply(&log);
+
+        let owners = ownership.owners("src", 5);
+        assert_eq!(owners.iter().map(|o| o.email.as_str()).collect::<Vec<_>>(), vec!["luis@example.com", "ana@example.com"]);
+        assert_eq!((owners[1].name.as_str(), owners[1].commits), ("Ana", 3));
+        assert!(owners[0].share > 0.9);
+        assert_eq!(ownership.owners("src/lexer.rs", 5).len(), 1);
+
+        let reviewers = ownership.reviewers(&["src/parser.rs".to_string()], Some("Luis@example.com"), 5);
+        assert_eq!(reviewers.len(), 1);
+        assert_eq!(reviewers[0].email, "ana@example.com");
+    }
+
+    #[test]
+    fn test_paths() {
+        assert_eq!(renamed_path("src/{old => new}/a.rs"), "src/new/a.rs");
+        assert_eq!(renamed_path("src/{ => util}/a.rs"), "src/util/a.rs");
+        assert_eq!(renamed_path("old.rs => new.rs"), "new.rs");
+        assert_eq!(join("crates/core", "./src/"), "crates/core/src");
+        assert_eq!(join("", "."), "");
+    }
+}
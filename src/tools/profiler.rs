@@ -0,0 +1,739 @@
+//! Profiler runs
+//!
+//! Wraps the usual CPU profiler of a runtime around the command that runs the
+//! code: `cargo flamegraph` for Rust (or `flamegraph` for any binary),
+//! `py-spy record` for Python and `node --prof` for Node. Running the target
+//! goes through the user's confirmation like the other tools that start
+//! processes. The profile is stored as a `profile` artifact (`/artifacts`) and
+//! its hottest functions (self time) are extracted as text, with their source
+//! location when the profile has one, so the heavy model can suggest
+//! optimizations tied to the code.
+
+use rig::tool::Tool;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use thiserror::Error;
+use tokio::process::Command;
+use super::command::output_in_group;
+
+/// Hotspots returned when none are asked for
+pub const DEFAULT_HOTSPOTS: usize = 10;
+/// Profiled programs are stopped after this long
+const PROFILE_TIMEOUT_SECS: u64 = 600;
+/// Hotspots whose source is shown to the model
+const EXCERPT_HOTSPOTS: usize = 5;
+/// Lines shown before and after a hotspot's line
+const EXCERPT_CONTEXT: usize = 8;
+
+#[derive(Error, Debug)]
+pub enum ProfilerError {
+    #[error("No command to profile")]
+    MissingCommand,
+    #[error("Unknown profiler '{0}' (expected flamegraph, py-spy or node)")]
+    UnknownProfiler(String),
+    #[error("No profiler known for '{0}': pass profiler=flamegraph, py-spy or node")]
+    Undetected(String),
+    #[error("Profiling runs the target and is only allowed in Build mode")]
+    RequiresBuildMode,
+    #[error("Profiling needs the user's approval before it runs")]
+    RequiresConfirmation,
+    #[error("{0} is not installed ({1})")]
+    NotInstalled(&'static str, &'static str),
+    #[error("{0} wrote no profile: {1}")]
+    NoProfile(&'static str, String),
+    #[error("The profiled command timed out after {0} seconds")]
+    Timeout(u64),
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// CPU profiler wrapped around a command
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Profiler {
+    /// `cargo flamegraph` / `flamegraph` (perf or dtrace), an SVG flame graph
+    Flamegraph,
+    /// `py-spy record --format raw`, folded stacks
+    PySpy,
+    /// `node --prof`, a V8 tick log read with `node --prof-process`
+    NodeProf,
+}
+
+impl Profiler {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "flamegraph" | "cargo-flamegraph" | "perf" | "rust" => Some(Self::Flamegraph),
+            "py-spy" | "pyspy" | "python" => Some(Self::PySpy),
+            "node" | "node-prof" | "v8" => Some(Self::NodeProf),
+            _ => None,
+        }
+    }
+
+    /// Profiler of the runtime the command starts
+    pub fn detect(command: &str) -> Option<Self> {
+        let mut words = command.split_whitespace();
+        let program = words.next()?;
+        let name = Path::new(program).file_name()?.to_str()?;
+        match name {
+            "cargo" if words.next() == Some("run") => Some(Self::Flamegraph),
+            _ if program.contains("target/release/") || program.contains("target/debug/") => Some(Self::Flamegraph),
+            _ if name.starts_with("python") || name.ends_with(".py") => Some(Self::PySpy),
+            "node" => Some(Self::NodeProf),
+            _ if name.ends_with(".js") || name.ends_with(".mjs") || name.ends_with(".cjs") => Some(Self::NodeProf),
+            _ => None,
+        }
+    }
+
+    /// Name used in arguments and file names
+    pub fn slug(&self) -> &'static str {
+        match self {
+            Self::Flamegraph => "flamegraph",
+            Self::PySpy => "py-spy",
+            Self::NodeProf => "node",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Flamegraph => "cargo flamegraph",
+            Self::PySpy => "py-spy",
+            Self::NodeProf => "node --prof",
+        }
+    }
+
+    /// Program that must be on the PATH
+    pub fn program(&self) -> &'static str {
+        match self {
+            Self::Flamegraph => "flamegraph",
+            Self::PySpy => "py-spy",
+            Self::NodeProf => "node",
+        }
+    }
+
+    pub fn install_hint(&self) -> &'static str {
+        match self {
+            Self::Flamegraph => "cargo install flamegraph",
+            Self::PySpy => "pip install py-spy",
+            Self::NodeProf => "install Node.js",
+        }
+    }
+
+    fn extension(&self) -> &'static str {
+        match self {
+            Self::Flamegraph => "svg",
+            Self::PySpy => "txt",
+            Self::NodeProf => "log",
+        }
+    }
+
+    /// Shell command running `command` under the profiler, writing the profile to `output`
+    pub fn wrap(&self, command: &str, output: &Path) -> String {
+        let output = shell_quote(output);
+        let command = command.trim();
+        match self {
+            Self::Flamegraph => match command.strip_prefix("cargo run") {
+                Some(rest) => {
+                    // Release builds are the default of cargo flamegraph, which has no `--release`
+                    let (options, program_args) = match rest.split_once(" -- ") {
+                        Some((options, args)) => (options, Some(args)),
+                        None => (rest, None),
+                    };
+                    let options: Vec<&str> = options.split_whitespace().filter(|o| *o != "--release").collect();
+                    let mut wrapped = format!("cargo flamegraph -o {}", output);
+                    if !options.is_empty() {
+                        wrapped.push_str(&format!(" {}", options.join(" ")));
+                    }
+                    if let Some(args) = program_args {
+                        wrapped.push_str(&format!(" -- {}", args));
+                    }
+                    wrapped
+                }
+                None => format!("flamegraph -o {} -- {}", output, command),
+            },
+            Self::PySpy => format!("py-spy record --format raw -o {} -- {}", output, command),
+            Self::NodeProf => {
+                let script = match command.split_once(char::is_whitespace) {
+                    Some(("node", rest)) => rest.trim_start(),
+                    _ if command == "node" => "",
+                    _ => command,
+                };
+                format!("node --prof --no-logfile-per-isolate --logfile={} {}", output, script)
+            }
+        }
+    }
+}
+
+/// Single-quote a path for `sh -c`
+fn shell_quote(path: &Path) -> String {
+    format!("'{}'", path.to_string_lossy().replace('\'', "'\\''"))
+}
+
+/// A function where the profiled program spends its own time
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Hotspot {
+    /// Function as the profiler names it
+    pub function: String,
+    /// `path:line` when the profile (or the symbol index) has it
+    pub location: Option<String>,
+    /// Samples spent in the function itself, not in its callees
+    pub samples: u64,
+    /// Share of all samples, 0.0-1.0
+    pub share: f64,
+}
+
+impl Hotspot {
+    /// Bare name to look up in the symbol index: no module path, generics,
+    /// closure frames, symbol hash or V8 optimization marker
+    pub fn symbol_name(&self) -> String {
+        let mut depth = 0usize;
+        let without_generics: String = self
+            .function
+            .chars()
+            .filter(|c| match c {
+                '<' => {
+                    depth += 1;
+                    false
+                }
+                '>' => {
+                    depth = depth.saturating_sub(1);
+                    false
+                }
+                _ => depth == 0,
+            })
+            .collect();
+        let is_hash = |s: &str| s.len() == 17 && s.starts_with('h') && s[1..].chars().all(|c| c.is_ascii_hexdigit());
+        without_generics
+            .split("::")
+            .filter(|s| !s.is_empty() && !s.starts_with('{') && !is_hash(s))
+            .last()
+            .unwrap_or_default()
+            .split(['(', ' '])
+            .next()
+            .unwrap_or_default()
+            .trim_start_matches(['*', '~'])
+            .to_string()
+    }
+}
+
+/// Hotspots of a profile run
+#[derive(Debug, Clone, Serialize)]
+pub struct ProfileReport {
+    pub profiler: &'static str,
+    pub command: String,
+    /// The profile as text (SVG, folded stacks or processed tick log), kept
+    /// as an artifact
+    #[serde(skip)]
+    pub profile: String,
+    /// Id of the stored profile artifact, set by the caller
+    pub artifact: Option<i64>,
+    /// Exit code of the profiled command
+    pub exit_code: Option<i32>,
+    pub total_samples: u64,
+    /// Hottest first
+    pub hotspots: Vec<Hotspot>,
+}
+
+impl ProfileReport {
+    pub fn to_markdown(&self) -> String {
+        let mut output = format!(
+            "🔥 Profile of `{}` ({}, {} samples)\n",
+            self.command, self.profiler, self.total_samples
+        );
+        if let Some(id) = self.artifact {
+            output.push_str(&format!("Profile: /artifacts open {}\n", id));
+        }
+        if let Some(code) = self.exit_code.filter(|code| *code != 0) {
+            output.push_str(&format!("⚠️ The command exited with code {}\n", code));
+        }
+        if self.hotspots.is_empty() {
+            output.push_str("\nNo samples in the profile: the command may have ended too quickly\n");
+            return output;
+        }
+
+        output.push_str("\nHotspots (self time):\n");
+        output.push_str(&self.hotspot_list());
+        output
+    }
+
+    /// `1.  34.2%  compute — app/math.py:12`, one line per hotspot
+    fn hotspot_list(&self) -> String {
+        let mut list = String::new();
+        for (i, hotspot) in self.hotspots.iter().enumerate() {
+            list.push_str(&format!("{:>2}. {:>5.1}%  {}", i + 1, hotspot.share * 100.0, hotspot.function));
+            if let Some(location) = &hotspot.location {
+                list.push_str(&format!(" — {}", location));
+            }
+            list.push('\n');
+        }
+        list
+    }
+
+    /// Prompt asking for optimizations of the hotspots, with the source of
+    /// the hottest ones found under `root`
+    pub fn optimization_prompt(&self, root: &Path) -> String {
+        let excerpts: Vec<String> = self
+            .hotspots
+            .iter()
+            .filter_map(|h| {
+                let location = h.location.as_deref()?;
+                Some(format!("{} ({}):\n```\n{}\n```", h.function, location, code_excerpt(root, location)?))
+            })
+            .take(EXCERPT_HOTSPOTS)
+            .collect();
+        let source = if excerpts.is_empty() {
+            "No source was found for them in the project.".to_string()
+        } else {
+            format!("Source of the hottest ones:\n\n{}", excerpts.join("\n\n"))
+        };
+
+        format!(
+            "This is a CPU profile of `{}` taken with {} ({} samples). These are the functions \
+             where the program spends its own time, hottest first:\n\n{}\n{}\n\n\
+             Suggest concrete optimizations for these hotspots. Tie each suggestion to its file \
+             and line, explain why the code is slow there, and say what to measure to confirm \
+             the gain. Skip hotspots in the standard library or dependencies unless the calling \
+             code is what makes them hot.",
+            self.command,
+            self.profiler,
+            self.total_samples,
+            self.hotspot_list(),
+            source
+        )
+    }
+}
+
+/// Numbered lines around `path:line`, for files inside `root` only
+fn code_excerpt(root: &Path, location: &str) -> Option<String> {
+    let location = location.trim_start_matches("file://");
+    let (path, line) = location.rsplit_once(':')?;
+    let line: usize = line.parse().ok()?;
+    let path = Path::new(path);
+    let relative = if path.is_absolute() { path.strip_prefix(root).ok()? } else { path };
+    let content = std::fs::read_to_string(root.join(relative)).ok()?;
+
+    let start = line.saturating_sub(EXCERPT_CONTEXT + 1);
+    let excerpt: Vec<String> = content
+        .lines()
+        .enumerate()
+        .skip(start)
+        .take(EXCERPT_CONTEXT * 2 + 1)
+        .map(|(i, text)| format!("{:>5} | {}", i + 1, text))
+        .collect();
+    (!excerpt.is_empty()).then(|| excerpt.join("\n"))
+}
+
+/// The hottest `limit` functions of per-function self samples
+fn top_hotspots(samples: HashMap<String, u64>, total: u64, limit: usize) -> Vec<Hotspot> {
+    let mut hotspots: Vec<Hotspot> = samples
+        .into_iter()
+        .filter(|(_, n)| *n > 0)
+        .map(|(frame, n)| {
+            // py-spy frames: `compute (app/math.py:12)`
+            let (function, location) = match frame.rsplit_once(" (") {
+                Some((function, location)) if location.ends_with(')') && location.contains(':') => {
+                    (function.to_string(), Some(location.trim_end_matches(')').to_string()))
+                }
+                _ => (frame, None),
+            };
+            Hotspot {
+                function,
+                location,
+                samples: n,
+                share: n as f64 / total.max(1) as f64,
+            }
+        })
+        .collect();
+    hotspots.sort_by(|a, b| b.samples.cmp(&a.samples).then_with(|| a.function.cmp(&b.function)));
+    hotspots.truncate(limit);
+    hotspots
+}
+
+/// Self samples per leaf frame of folded stacks (`a;b;c 12`), and the total
+pub fn parse_folded(text: &str) -> (HashMap<String, u64>, u64) {
+    let mut samples = HashMap::new();
+    let mut total = 0;
+    for line in text.lines() {
+        let Some((stack, count)) = line.trim().rsplit_once(' ') else {
+            continue;
+        };
+        let Ok(count) = count.parse::<u64>() else {
+            continue;
+        };
+        let leaf = stack.rsplit(';').next().unwrap_or(stack).trim();
+        *samples.entry(leaf.to_string()).or_insert(0) += count;
+        total += count;
+    }
+    (samples, total)
+}
+
+/// One frame of a flame graph SVG
+struct SvgFrame {
+    name: String,
+    level: i64,
+    x: u64,
+    width: u64,
+}
+
+/// Self samples per function of an inferno flame graph (the SVG of cargo
+/// flamegraph), and the total. Frames carry their samples in `fg:x`/`fg:w`;
+/// a frame's self time is its width minus its children's.
+pub fn parse_flamegraph_svg(svg: &str) -> (HashMap<String, u64>, u64) {
+    fn attribute(tag: &str, name: &str) -> Option<f64> {
+        let start = tag.find(&format!(" {}=\"", name))? + name.len() + 3;
+        tag[start..].split('"').next()?.trim_end_matches('%').parse().ok()
+    }
+
+    let mut frames = Vec::new();
+    for chunk in svg.split("<title>").skip(1) {
+        let Some((title, rest)) = chunk.split_once("</title>") else {
+            continue;
+        };
+        let rect = rest.split("/>").next().unwrap_or_default();
+        let (Some(y), Some(x), Some(width)) = (attribute(rect, "y"), attribute(rect, "fg:x"), attribute(rect, "fg:w"))
+        else {
+            continue;
+        };
+        let name = title.rsplit_once(" (").map_or(title, |(name, _)| name);
+        frames.push(SvgFrame {
+            name: unescape_xml(name),
+            level: y.round() as i64,
+            x: x as u64,
+            width: width as u64,
+        });
+    }
+    let Some(total) = frames.iter().map(|f| f.width).max() else {
+        return (HashMap::new(), 0);
+    };
+
+    let mut levels: Vec<i64> = frames.iter().map(|f| f.level).collect();
+    levels.sort_unstable();
+    levels.dedup();
+    let mut by_level: HashMap<i64, Vec<(u64, u64)>> = HashMap::new();
+    for frame in &frames {
+        by_level.entry(frame.level).or_default().push((frame.x, frame.width));
+    }
+    for spans in by_level.values_mut() {
+        spans.sort_unstable();
+    }
+    // Children sit above their parent in a flame graph (the root row is the
+    // lowest and widest), below it in an icicle graph
+    let level_width = |level: Option<&i64>| -> u64 {
+        level
+            .and_then(|l| by_level.get(l))
+            .map_or(0, |spans| spans.iter().map(|(_, width)| width).sum())
+    };
+    let upwards = level_width(levels.last()) >= level_width(levels.first());
+
+    let mut samples = HashMap::new();
+    for frame in &frames {
+        let index = levels.binary_search(&frame.level).unwrap_or_default();
+        let child_level = if upwards { index.checked_sub(1) } else { Some(index + 1) };
+        let children: u64 = child_level
+            .and_then(|i| levels.get(i))
+            .and_then(|level| by_level.get(level))
+            .map(|spans| {
+                let first = spans.partition_point(|(x, _)| *x < frame.x);
+                spans[first..]
+                    .iter()
+                    .take_while(|(x, _)| *x < frame.x + frame.width)
+                    .map(|(_, width)| width)
+                    .sum()
+            })
+            .unwrap_or(0);
+        *samples.entry(frame.name.clone()).or_insert(0) += frame.width.saturating_sub(children);
+    }
+    (samples, total)
+}
+
+fn unescape_xml(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&")
+}
+
+/// Ticks per function of the `[JavaScript]` and `[C++]` sections of
+/// `node --prof-process`, and the total ticks
+pub fn parse_node_profile(text: &str) -> (HashMap<String, u64>, u64) {
+    let mut samples = HashMap::new();
+    let mut total = 0;
+    let mut in_section = false;
+    for line in text.lines() {
+        let trimmed = line.trim();
+        if trimmed.starts_with('[') {
+            in_section = matches!(trimmed, "[JavaScript]:" | "[C++]:");
+            continue;
+        }
+        // Statistical profiling result from isolate.log, (1234 ticks, 0 unaccounted, ...)
+        if trimmed.starts_with("Statistical profiling result") {
+            if let Some((ticks, _)) = trimmed.split_once('(').and_then(|(_, rest)| rest.split_once(" ticks")) {
+                total = ticks.trim().parse().unwrap_or(total);
+            }
+            continue;
+        }
+        if !in_section {
+            continue;
+        }
+
+        // `  ticks  total  nonlib   name`
+        let mut rest = trimmed;
+        let mut columns = Vec::new();
+        for _ in 0..3 {
+            let Some((column, tail)) = rest.split_once(char::is_whitespace) else {
+                break;
+            };
+            columns.push(column);
+            rest = tail.trim_start();
+        }
+        let Some(Ok(ticks)) = columns.first().map(|c| c.parse::<u64>()) else {
+            continue;
+        };
+        if columns.len() < 3 || rest.is_empty() {
+            continue;
+        }
+        // `JS: *fib /app/math.js:3:13` → `*fib (/app/math.js:3)`, the py-spy frame shape
+        let name = rest.split_once(": ").map_or(rest, |(_, name)| name);
+        let frame = match name.rsplit_once(' ') {
+            Some((function, location)) if location.contains(':') && location.contains(['/', '\\']) => {
+                let location = location.rsplit_once(':').map_or(location, |(line, _column)| line);
+                format!("{} ({})", function, location)
+            }
+            _ => name.to_string(),
+        };
+        *samples.entry(frame).or_insert(0) += ticks;
+    }
+    if total == 0 {
+        total = samples.values().sum();
+    }
+    (samples, total)
+}
+
+/// Arguments for the profiler tool
+#[derive(Debug, Clone, Default, Deserialize, Serialize, JsonSchema)]
+pub struct ProfileArgs {
+    /// Command that runs the code to profile, e.g. "cargo run --release -- input.txt" or "python app.py"
+    pub command: String,
+    /// flamegraph, py-spy or node (default: detected from the command)
+    #[serde(default)]
+    pub profiler: Option<String>,
+    /// Hotspots to return (default 10)
+    #[serde(default)]
+    pub top: Option<usize>,
+    /// Directory to run the command in, filled in by the orchestrator
+    #[serde(skip)]
+    #[schemars(skip)]
+    pub working_dir: String,
+    /// Whether the agent is in Build mode, filled in by the orchestrator
+    #[serde(skip)]
+    #[schemars(skip)]
+    pub build_mode: bool,
+    /// Set by the orchestrator once the user approved the run
+    #[serde(skip)]
+    #[schemars(skip)]
+    pub confirm: bool,
+}
+
+impl ProfileArgs {
+    /// Pick the profiler and apply the Build mode / confirmation gate
+    pub fn check(&self) -> Result<Profiler, ProfilerError> {
+        let command = self.command.trim();
+        if command.is_empty() {
+            return Err(ProfilerError::MissingCommand);
+        }
+        let profiler = match self.profiler.as_deref().filter(|p| !p.trim().is_empty()) {
+            Some(name) => Profiler::parse(name).ok_or_else(|| ProfilerError::UnknownProfiler(name.to_string()))?,
+            None => Profiler::detect(command).ok_or_else(|| ProfilerError::Undetected(command.to_string()))?,
+        };
+        if !self.build_mode {
+            return Err(ProfilerError::RequiresBuildMode);
+        }
+        if !self.confirm {
+            return Err(ProfilerError::RequiresConfirmation);
+        }
+        Ok(profiler)
+    }
+
+    /// One-line description for the confirmation dialog and the audit log
+    pub fn summary(&self) -> String {
+        let profiler = self
+            .profiler
+            .as_deref()
+            .and_then(Profiler::parse)
+            .or_else(|| Profiler::detect(&self.command))
+            .map_or("a profiler", |p| p.label());
+        format!("run `{}` under {}", self.command.trim(), profiler)
+    }
+}
+
+/// Runs commands under a profiler and extracts their hotspots
+#[derive(Debug, Clone, Default)]
+pub struct ProfilerTool;
+
+impl ProfilerTool {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Profile the command and extract its hotspots
+    pub async fn run(&self, args: &ProfileArgs) -> Result<ProfileReport, ProfilerError> {
+        let profiler = args.check()?;
+        let root = PathBuf::from(&args.working_dir);
+        // Written outside the project; the caller keeps the text as an artifact
+        let dir = tempfile::tempdir()?;
+        let artifact = dir.path().join(format!("profile.{}", profiler.extension()));
+
+        let output = run_shell(&profiler.wrap(&args.command, &artifact), &root).await?;
+        if !artifact.exists() {
+            if output.status.code() == Some(127) {
+                return Err(ProfilerError::NotInstalled(profiler.label(), profiler.install_hint()));
+            }
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            let tail: Vec<&str> = stderr.lines().rev().take(5).collect();
+            let tail: Vec<&str> = tail.into_iter().rev().collect();
+            return Err(ProfilerError::NoProfile(profiler.label(), tail.join("\n")));
+        }
+
+        let (profile, (samples, total)) = match profiler {
+            Profiler::Flamegraph => {
+                let svg = tokio::fs::read_to_string(&artifact).await?;
+                let parsed = parse_flamegraph_svg(&svg);
+                (svg, parsed)
+            }
+            Profiler::PySpy => {
+                let folded = tokio::fs::read_to_string(&artifact).await?;
+                let parsed = parse_folded(&folded);
+                (folded, parsed)
+            }
+            Profiler::NodeProf => {
+                let processed = run_shell(&format!("node --prof-process {}", shell_quote(&artifact)), &root).await?;
+                // The processed text is kept: the tick log alone needs the same node to read
+                let text = String::from_utf8_lossy(&processed.stdout).to_string();
+                let parsed = parse_node_profile(&text);
+                (text, parsed)
+            }
+        };
+
+        Ok(ProfileReport {
+            profiler: profiler.label(),
+            command: args.command.trim().to_string(),
+            profile,
+            artifact: None,
+            exit_code: output.status.code(),
+            total_samples: total,
+            hotspots: top_hotspots(samples, total, args.top.unwrap_or(DEFAULT_HOTSPOTS)),
+        })
+    }
+}
+
+async fn run_shell(command: &str, dir: &Path) -> Result<std::process::Output, ProfilerError> {
+    tokio::time::timeout(
+        Duration::from_secs(PROFILE_TIMEOUT_SECS),
+        output_in_group(Command::new("sh").arg("-c").arg(command).current_dir(dir)),
+    )
+    .await
+    .map_err(|_| ProfilerError::Timeout(PROFILE_TIMEOUT_SECS))?
+    .map_err(ProfilerError::from)
+}
+
+impl Tool for ProfilerTool {
+    const NAME: &'static str = "profile_code";
+
+    type Args = ProfileArgs;
+    type Output = String;
+    type Error = ProfilerError;
+
+    async fn definition(&self, _prompt: String) -> rig::completion::ToolDefinition {
+        rig::completion::ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: "Run a command under a CPU profiler (cargo flamegraph, py-spy or node \
+                          --prof, picked from the command) and return its hottest functions by \
+                          self time with their file:line. Needs Build mode and runs after the \
+                          user approves it."
+                .to_string(),
+            parameters: serde_json::to_value(schemars::schema_for!(ProfileArgs)).unwrap_or_default(),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        self.run(&args).await.map(|report| report.to_markdown())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_and_wrap() {
+        let out = Path::new("/p/.neuro-agent/profiles/a.svg");
+        let cargo = Profiler::detect("cargo run --release --bin api -- input.txt").unwrap();
+        assert_eq!(cargo, Profiler::Flamegraph);
+        assert_eq!(
+            cargo.wrap("cargo run --release --bin api -- input.txt", out),
+            "cargo flamegraph -o '/p/.neuro-agent/profiles/a.svg' --bin api -- input.txt"
+        );
+        assert_eq!(Profiler::detect("python3 -m app.main"), Some(Profiler::PySpy));
+        assert_eq!(
+            Profiler::NodeProf.wrap("node server.js --port 3000", Path::new("out.log")),
+            "node --prof --no-logfile-per-isolate --logfile='out.log' server.js --port 3000"
+        );
+        assert_eq!(Profiler::detect("make bench"), None);
+
+        let mut args = ProfileArgs {
+            command: "python app.py".to_string(),
+            ..Default::default()
+        };
+        assert!(matches!(args.check(), Err(ProfilerError::RequiresBuildMode)));
+        args.build_mode = true;
+        assert!(matches!(args.check(), Err(ProfilerError::RequiresConfirmation)));
+        args.confirm = true;
+        assert_eq!(args.check().unwrap(), Profiler::PySpy);
+        assert_eq!(args.summary(), "run `python app.py` under py-spy");
+    }
+
+    #[test]
+    fn test_parse_profiles() {
+        let folded = "main (app.py:30);load (app.py:10) 20\nmain (app.py:30);compute (app/math.py:12) 70\n\
+                      main (app.py:30);compute (app/math.py:12) 10\n";
+        let (samples, total) = parse_folded(folded);
+        let hotspots = top_hotspots(samples, total, 5);
+        assert_eq!(hotspots[0].function, "compute");
+        assert_eq!(hotspots[0].location.as_deref(), Some("app/math.py:12"));
+        assert_eq!(hotspots[0].samples, 80);
+        assert!((hotspots[0].share - 0.8).abs() < 1e-9);
+
+        // all (100) > main (100) > [parse (60) > tokenize&lt;T&gt; (45), render (30)]
+        let svg = r#"<svg>
+<g><title>all (100 samples, 100%)</title><rect x="0%" y="85" width="100%" height="15" fg:x="0" fg:w="100"/></g>
+<g><title>app::main (100 samples, 100%)</title><rect x="0%" y="69" width="100%" height="15" fg:x="0" fg:w="100"/></g>
+<g><title>app::parse (60 samples, 60%)</title><rect x="0%" y="53" width="60%" height="15" fg:x="0" fg:w="60"/></g>
+<g><title>app::render (30 samples, 30%)</title><rect x="60%" y="53" width="30%" height="15" fg:x="60" fg:w="30"/></g>
+<g><title>app::tokenize&lt;T&gt;::h0123456789abcdef (45 samples, 45%)</title><rect x="0%" y="37" width="45%" height="15" fg:x="0" fg:w="45"/></g>
+</svg>"#;
+        let (samples, total) = parse_flamegraph_svg(svg);
+        assert_eq!(total, 100);
+        let hotspots = top_hotspots(samples, total, 3);
+        let found: Vec<_> = hotspots.iter().map(|h| (h.function.as_str(), h.samples)).collect();
+        assert_eq!(
+            found,
+            vec![("app::tokenize<T>::h0123456789abcdef", 45), ("app::render", 30), ("app::parse", 15)]
+        );
+        assert_eq!(hotspots[0].symbol_name(), "tokenize");
+
+        let node = "Statistical profiling result from isolate.log, (200 ticks, 2 unaccounted, 0 excluded).\n\n \
+                    [Shared libraries]:\n   ticks  total  nonlib   name\n     50   25.0%          /usr/bin/node\n\n \
+                    [JavaScript]:\n   ticks  total  nonlib   name\n    120   60.0%   80.0%  JS: *fib /srv/app/math.js:3:13\n\n \
+                    [C++]:\n   ticks  total  nonlib   name\n     10    5.0%    6.7%  v8::internal::Heap::Scavenge()\n";
+        let (samples, total) = parse_node_profile(node);
+        assert_eq!(total, 200);
+        let hotspots = top_hotspots(samples, total, 5);
+        assert_eq!(hotspots.len(), 2);
+        assert_eq!(hotspots[0].function, "*fib");
+        assert_eq!(hotspots[0].location.as_deref(), Some("/srv/app/math.js:3"));
+        assert_eq!(hotspots[0].symbol_name(), "fib");
+    }
+}
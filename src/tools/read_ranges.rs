@@ -0,0 +1,315 @@
+//! Smart line ranges for `read_file`
+//!
+//! Instead of guessing line numbers the model can ask for a symbol
+//! (`symbol: "Parser::parse"`), for the lines around some text
+//! (`around: "TODO"`), and have a raw range grow to the whole function or
+//! type it cuts through. Scopes come from the tree-sitter symbols of
+//! [`crate::ast`]; files in other languages are read as plain lines.
+
+use super::file_reader::MMAP_THRESHOLD;
+use super::filesystem::{FileReadArgs, FileSystemError};
+use crate::ast::{AstParser, AstSymbol, SupportedLanguage, SymbolKind};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+/// Lines before and after an `around` match
+pub const DEFAULT_AROUND_LINES: usize = 10;
+/// Enclosing scopes longer than this are not expanded into
+pub const MAX_SCOPE_LINES: usize = 200;
+/// Symbol names listed when a lookup fails
+const MAX_SUGGESTIONS: usize = 12;
+
+/// Line range to read, with a note on how it was chosen
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ResolvedRange {
+    pub start_line: Option<usize>,
+    pub end_line: Option<usize>,
+    pub note: Option<String>,
+}
+
+/// Whether the arguments ask for more than a plain line range. Resolving
+/// reads the whole file, so a raw range only grows to its scope in files with
+/// a grammar that are small enough to be read without memory-mapping
+pub fn needs_resolution(args: &FileReadArgs, path: &Path) -> bool {
+    if args.symbol.is_some() || args.around.is_some() {
+        return true;
+    }
+    (args.start_line.is_some() || args.end_line.is_some())
+        && args.expand_scope != Some(false)
+        && SupportedLanguage::from_path(path).is_some()
+        && std::fs::metadata(path).is_ok_and(|m| m.len() < MMAP_THRESHOLD)
+}
+
+/// Turn symbol, `around` and scope-expansion requests into a line range
+pub fn resolve(
+    args: &FileReadArgs,
+    source: &str,
+    symbols: &[AstSymbol],
+) -> Result<ResolvedRange, FileSystemError> {
+    let lines: Vec<&str> = source.lines().collect();
+
+    if let Some(query) = &args.symbol {
+        let candidates = find_symbol(symbols, &lines, query);
+        let Some(symbol) = candidates.first() else {
+            let mut known: Vec<&str> = symbols.iter().map(|s| s.name.as_str()).collect();
+            known.dedup();
+            known.truncate(MAX_SUGGESTIONS);
+            return Err(FileSystemError::SymbolNotFound(if known.is_empty() {
+                query.clone()
+            } else {
+                format!("{} (symbols in this file: {})", query, known.join(", "))
+            }));
+        };
+
+        let start = with_leading_docs(&lines, symbol.range.start_line);
+        let mut note = format!(
+            "{} `{}` (lines {}-{})",
+            symbol.kind.as_str(),
+            symbol.name,
+            start,
+            symbol.range.end_line
+        );
+        if candidates.len() > 1 {
+            let others: Vec<String> = candidates[1..]
+                .iter()
+                .map(|s| s.range.start_line.to_string())
+                .collect();
+            note.push_str(&format!("; also defined at lines {}", others.join(", ")));
+        }
+        return Ok(ResolvedRange {
+            start_line: Some(start),
+            end_line: Some(symbol.range.end_line),
+            note: Some(note),
+        });
+    }
+
+    let (mut start, mut end, mut notes) = match &args.around {
+        Some(needle) => {
+            let line = find_line(&lines, needle)
+                .ok_or_else(|| FileSystemError::TextNotFound(needle.clone()))?;
+            let context = args.context_lines.unwrap_or(DEFAULT_AROUND_LINES);
+            (
+                Some(line.saturating_sub(context).max(1)),
+                Some((line + context).min(lines.len())),
+                vec![format!("match at line {}", line)],
+            )
+        }
+        None => (args.start_line, args.end_line, Vec::new()),
+    };
+
+    if args.expand_scope != Some(false) {
+        if let Some(scope) = start.and_then(|s| innermost_scope(symbols, s).filter(|sc| sc.range.start_line < s)) {
+            start = Some(with_leading_docs(&lines, scope.range.start_line));
+            notes.push(format!("starts at {} `{}`", scope.kind.as_str(), scope.name));
+        }
+        if let Some(scope) = end.and_then(|e| innermost_scope(symbols, e).filter(|sc| sc.range.end_line > e)) {
+            end = Some(scope.range.end_line);
+            notes.push(format!("ends with {} `{}`", scope.kind.as_str(), scope.name));
+        }
+    }
+
+    Ok(ResolvedRange {
+        start_line: start,
+        end_line: end,
+        note: (!notes.is_empty()).then(|| notes.join(", ")),
+    })
+}
+
+/// Definitions matching `name`, `Type::name` or `Type.name`, in file order
+fn find_symbol<'a>(symbols: &'a [AstSymbol], lines: &[&str], query: &str) -> Vec<&'a AstSymbol> {
+    let (qualifier, name) = match query.rsplit_once("::").or_else(|| query.rsplit_once('.')) {
+        Some((qualifier, name)) => (Some(qualifier.rsplit(['.', ':']).next().unwrap_or(qualifier)), name),
+        None => (None, query),
+    };
+
+    let mut named: Vec<&AstSymbol> = symbols.iter().filter(|s| s.name == name).collect();
+    named.sort_by_key(|s| s.range.start_line);
+    named.dedup_by_key(|s| s.range.start_line);
+
+    let Some(qualifier) = qualifier else {
+        return named;
+    };
+    let qualified: Vec<&AstSymbol> = named
+        .iter()
+        .copied()
+        .filter(|s| {
+            symbols.iter().any(|parent| {
+                parent.name == qualifier
+                    && parent.range.start_line < s.range.start_line
+                    && s.range.end_line <= parent.range.end_line
+            }) || (s.kind == SymbolKind::Method
+                && impl_header(lines, s.range.start_line).is_some_and(|header| {
+                    header
+                        .split(|c: char| !c.is_alphanumeric() && c != '_')
+                        .any(|word| word == qualifier)
+                }))
+        })
+        .collect();
+    // A wrong qualifier still finds the symbol
+    if qualified.is_empty() {
+        named
+    } else {
+        qualified
+    }
+}
+
+/// The closest `impl` line above a Rust method
+fn impl_header<'a>(lines: &[&'a str], line: usize) -> Option<&'a str> {
+    lines[..line.saturating_sub(1).min(lines.len())]
+        .iter()
+        .rev()
+        .find(|l| {
+            let l = l.trim_start();
+            l.starts_with("impl") && !l.starts_with("impl_")
+        })
+        .copied()
+}
+
+/// Smallest definition containing `line` that is short enough to read whole
+fn innermost_scope(symbols: &[AstSymbol], line: usize) -> Option<&AstSymbol> {
+    symbols
+        .iter()
+        .filter(|s| s.range.start_line <= line && line <= s.range.end_line)
+        .filter(|s| s.range.end_line - s.range.start_line < MAX_SCOPE_LINES)
+        .min_by_key(|s| s.range.end_line - s.range.start_line)
+}
+
+/// First line (1-indexed) containing `needle`, case-insensitively as a fallback
+fn find_line(lines: &[&str], needle: &str) -> Option<usize> {
+    lines
+        .iter()
+        .position(|l| l.contains(needle))
+        .or_else(|| {
+            let needle = needle.to_lowercase();
+            lines.iter().position(|l| l.to_lowercase().contains(&needle))
+        })
+        .map(|i| i + 1)
+}
+
+/// Move a definition start up over its doc comments, attributes and decorators
+fn with_leading_docs(lines: &[&str], start_line: usize) -> usize {
+    let mut start = start_line;
+    while start > 1 {
+        let previous = lines.get(start - 2).map(|l| l.trim()).unwrap_or_default();
+        let is_doc = previous.starts_with("///")
+            || previous.starts_with("#[")
+            || previous.starts_with('@')
+            || previous.starts_with("/**")
+            || previous.starts_with("* ")
+            || previous == "*"
+            || previous == "*/";
+        if !is_doc {
+            break;
+        }
+        start -= 1;
+    }
+    start
+}
+
+/// Tree-sitter symbols of files, with the parser created on first use
+#[derive(Clone, Default)]
+pub struct SymbolRanges {
+    parser: Arc<Mutex<Option<AstParser>>>,
+}
+
+impl std::fmt::Debug for SymbolRanges {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SymbolRanges").finish_non_exhaustive()
+    }
+}
+
+impl SymbolRanges {
+    /// Symbols of `source`, empty for languages without a grammar. Blocking.
+    pub fn symbols(&self, path: &Path, source: &str) -> Vec<AstSymbol> {
+        let Some(language) = SupportedLanguage::from_path(path) else {
+            return Vec::new();
+        };
+        let mut guard = self.parser.lock().unwrap_or_else(|e| e.into_inner());
+        if guard.is_none() {
+            *guard = AstParser::new().ok();
+        }
+        let Some(parser) = guard.as_mut() else {
+            return Vec::new();
+        };
+        match parser.parse(language, source) {
+            Ok(tree) => parser.extract_symbols(&tree, language, source),
+            Err(_) => Vec::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = "use std::fmt;
+
+pub struct Parser {
+    depth: usize,
+}
+
+impl Parser {
+    /// Parse the input
+    #[inline]
+    pub fn parse(&self, input: &str) -> usize {
+        let trimmed = input.trim();
+        // TODO: handle nesting
+        trimmed.len() + self.depth
+    }
+}
+
+pub fn parse(input: &str) -> usize {
+    input.len()
+}
+";
+
+    fn symbols() -> Vec<AstSymbol> {
+        SymbolRanges::default().symbols(Path::new("lib.rs"), SOURCE)
+    }
+
+    fn args() -> FileReadArgs {
+        FileReadArgs {
+            path: "lib.rs".to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_symbol_read_includes_docs_and_qualifier() {
+        let symbols = symbols();
+
+        let method = resolve(&FileReadArgs { symbol: Some("Parser::parse".into()), ..args() }, SOURCE, &symbols)
+            .unwrap();
+        assert_eq!((method.start_line, method.end_line), (Some(8), Some(14)));
+
+        let free = resolve(&FileReadArgs { symbol: Some("parse".into()), ..args() }, SOURCE, &symbols).unwrap();
+        assert_eq!(free.start_line, Some(8));
+        assert!(free.note.unwrap().contains("also defined at lines 17"));
+
+        let missing = resolve(&FileReadArgs { symbol: Some("lex".into()), ..args() }, SOURCE, &symbols);
+        assert!(matches!(missing, Err(FileSystemError::SymbolNotFound(s)) if s.contains("Parser")));
+    }
+
+    #[test]
+    fn test_around_and_partial_ranges_expand_to_scope() {
+        let symbols = symbols();
+
+        let around = FileReadArgs { around: Some("todo".into()), context_lines: Some(1), ..args() };
+        let range = resolve(&around, SOURCE, &symbols).unwrap();
+        assert_eq!((range.start_line, range.end_line), (Some(8), Some(14)));
+        assert!(range.note.unwrap().starts_with("match at line 12"));
+
+        let partial = FileReadArgs { start_line: Some(11), end_line: Some(18), ..args() };
+        let range = resolve(&partial, SOURCE, &symbols).unwrap();
+        assert_eq!((range.start_line, range.end_line), (Some(8), Some(19)));
+
+        let dir = tempfile::tempdir().unwrap();
+        let (rust, text) = (dir.path().join("lib.rs"), dir.path().join("notes.txt"));
+        std::fs::write(&rust, SOURCE).unwrap();
+        std::fs::write(&text, SOURCE).unwrap();
+        assert!(needs_resolution(&partial, &rust));
+        assert!(!needs_resolution(&partial, &text));
+        let raw = FileReadArgs { expand_scope: Some(false), ..partial };
+        assert!(!needs_resolution(&raw, &rust));
+    }
+}
@@ -1,7 +1,17 @@
 //! Refactor tool - Code refactoring operations
-
+//!
+//! `RenameSymbol` works on the AST instead of text: the declaration is resolved
+//! and the references found with [`FindReferencesTool`], and only those
+//! identifiers change, so comments, strings and longer names that contain the
+//! old one are left alone. The changed files are written together like a
+//! project-wide replace: all or nothing, refusing files edited meanwhile.
+
+use super::references::{FindReferencesTool, Reference};
+use super::replace::{write_all, ReplaceError};
+use crate::ast::ReferenceKind;
 use regex::Regex;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
 use tokio::fs;
 
@@ -13,6 +23,12 @@ pub enum RefactorOperation {
         new_name: String,
         scope: RefactorScope,
     },
+    /// Rename a declaration and its references across the project (AST based)
+    RenameSymbol {
+        /// `name` or `Type::name`
+        symbol: String,
+        new_name: String,
+    },
     Extract {
         code: String,
         name: String,
@@ -87,21 +103,54 @@ pub struct RefactorResult {
     pub errors: Vec<String>,
 }
 
-/// Refactor tool
-#[derive(Debug, Clone)]
-pub struct RefactorTool;
-
-impl Default for RefactorTool {
-    fn default() -> Self {
-        Self::new()
+impl RefactorResult {
+    /// Unified diff of the changed lines, one section per file
+    pub fn diff(&self) -> String {
+        let mut diff = String::new();
+        let mut i = 0;
+        while i < self.changes.len() {
+            let first = &self.changes[i];
+            if i == 0 || self.changes[i - 1].file != first.file {
+                diff.push_str(&format!("--- a/{}\n+++ b/{}\n", first.file, first.file));
+            }
+            // Consecutive lines of a file form one hunk
+            let run = self.changes[i..]
+                .iter()
+                .enumerate()
+                .take_while(|(k, c)| c.file == first.file && c.line == first.line + k)
+                .count();
+            let hunk = &self.changes[i..i + run];
+            diff.push_str(&format!("@@ -{},{} +{},{} @@\n", first.line, run, first.line, run));
+            for change in hunk {
+                diff.push_str(&format!("-{}\n", change.old_text));
+            }
+            for change in hunk {
+                diff.push_str(&format!("+{}\n", change.new_text));
+            }
+            i += run;
+        }
+        diff
     }
 }
 
+/// Refactor tool
+#[derive(Clone, Default)]
+pub struct RefactorTool {
+    /// Resolves declarations and references for `RenameSymbol`
+    references: FindReferencesTool,
+}
+
 impl RefactorTool {
     pub const NAME: &'static str = "refactor_code";
 
     pub fn new() -> Self {
-        Self
+        Self::default()
+    }
+
+    /// Use a references tool (and its symbol index) for `RenameSymbol`
+    pub fn with_references(mut self, references: FindReferencesTool) -> Self {
+        self.references = references;
+        self
     }
 
     /// Execute a refactoring operation
@@ -121,6 +170,13 @@ impl RefactorTool {
                 self.rename_symbol(old_name, new_name, scope, args.dry_run.unwrap_or(false))
                     .await
             }
+            RefactorOperation::RenameSymbol {
+                ref symbol,
+                ref new_name,
+            } => {
+                self.rename_symbol_ast(&path, symbol, new_name, args.dry_run.unwrap_or(false))
+                    .await
+            }
             RefactorOperation::Extract {
                 ref code,
                 ref name,
@@ -240,6 +296,115 @@ impl RefactorTool {
         })
     }
 
+    /// Rename `symbol` and its references below `root`, resolved through the
+    /// AST: identifiers are replaced at their exact position. For `Type::name`
+    /// the references are those [`FindReferencesTool`] narrows to `Type`
+    async fn rename_symbol_ast(
+        &self,
+        root: &Path,
+        symbol: &str,
+        new_name: &str,
+        dry_run: bool,
+    ) -> Result<RefactorResult, RefactorError> {
+        if !is_identifier(new_name) {
+            return Err(RefactorError::InvalidPattern(format!(
+                "'{}' is not a valid identifier",
+                new_name
+            )));
+        }
+        let name = symbol.rsplit("::").next().unwrap_or(symbol);
+
+        let report = self
+            .references
+            .find_references(root, symbol, None, usize::MAX)
+            .await
+            .map_err(|e| RefactorError::Index(e.to_string()))?;
+        let declarations: Vec<&Reference> = report
+            .references
+            .iter()
+            .filter(|r| r.kind == ReferenceKind::Definition.as_str())
+            .collect();
+        if declarations.is_empty() {
+            return Err(RefactorError::DefinitionNotFound(symbol.to_string()));
+        }
+        // References are matched by name: with two declarations they cannot
+        // be told apart
+        if declarations.len() > 1 {
+            let sites: Vec<String> = declarations
+                .iter()
+                .map(|d| format!("{}:{}", d.path, d.line))
+                .collect();
+            return Err(RefactorError::Ambiguous(symbol.to_string(), sites.join(", ")));
+        }
+
+        let mut by_file: BTreeMap<&str, Vec<&Reference>> = BTreeMap::new();
+        for reference in &report.references {
+            by_file.entry(&reference.path).or_default().push(reference);
+        }
+
+        let mut changes = Vec::new();
+        let mut rewritten = Vec::new();
+        for (file, references) in by_file {
+            let path = root.join(file);
+            let content = fs::read_to_string(&path)
+                .await
+                .map_err(|e| RefactorError::IoError(e.to_string()))?;
+            // Split on '\n' only, so `\r\n` and the final newline survive
+            let mut lines: Vec<String> = content.split('\n').map(String::from).collect();
+            let original = lines.clone();
+
+            // Right to left, so earlier columns on the same line stay valid
+            for reference in references.iter().rev() {
+                let Some(line) = reference.line.checked_sub(1).and_then(|i| lines.get_mut(i)) else {
+                    return Err(RefactorError::Index(format!(
+                        "{}:{} is past the end of the file; reindex and try again",
+                        file, reference.line
+                    )));
+                };
+                // Columns are byte offsets; anything else there means the file
+                // changed since the references were found
+                let start = reference.column.saturating_sub(1);
+                if line.get(start..start + name.len()) != Some(name) {
+                    return Err(RefactorError::Conflict(format!("{}:{}", file, reference.line)));
+                }
+                line.replace_range(start..start + name.len(), new_name);
+            }
+
+            for (i, (old, new)) in original.iter().zip(&lines).enumerate() {
+                if old != new {
+                    changes.push(RefactorChange {
+                        file: file.to_string(),
+                        line: i + 1,
+                        old_text: old.trim_end_matches('\r').to_string(),
+                        new_text: new.trim_end_matches('\r').to_string(),
+                    });
+                }
+            }
+            if lines != original {
+                rewritten.push((path, content, lines.join("\n")));
+            }
+        }
+
+        let files_modified = rewritten.len();
+        if !dry_run {
+            tokio::task::spawn_blocking(move || write_all(&rewritten))
+                .await
+                .map_err(|e| RefactorError::IoError(e.to_string()))?
+                .map_err(|e| match e {
+                    ReplaceError::Conflict(path) => RefactorError::Conflict(path.display().to_string()),
+                    e => RefactorError::IoError(e.to_string()),
+                })?;
+        }
+
+        Ok(RefactorResult {
+            success: true,
+            files_modified,
+            total_changes: changes.len(),
+            changes,
+            errors: vec![],
+        })
+    }
+
     async fn rename_in_file(
         &self,
         path: &Path,
@@ -356,6 +521,22 @@ impl RefactorTool {
                 ),
                 format!("{}()", name),
             ),
+            "c" => (
+                format!(
+                    "static void {}(void) {{\n    {}\n}}",
+                    name,
+                    code.replace('\n', "\n    ")
+                ),
+                format!("{}()", name),
+            ),
+            "cpp" | "cc" | "cxx" | "h" | "hpp" | "hh" | "hxx" | "ino" | "ipp" | "tpp" | "inl" => (
+                format!(
+                    "static void {}() {{\n    {}\n}}",
+                    name,
+                    code.replace('\n', "\n    ")
+                ),
+                format!("{}()", name),
+            ),
             _ => return Err(RefactorError::UnsupportedLanguage(ext.to_string())),
         };
 
@@ -374,6 +555,10 @@ impl RefactorTool {
             "rs" => (format!("let {} = {};", name, code), name.to_string()),
             "py" => (format!("{} = {}", name, code), name.to_string()),
             "js" | "ts" => (format!("const {} = {};", name, code), name.to_string()),
+            // `auto` type inference: C++11 and C23
+            "c" | "cpp" | "cc" | "cxx" | "h" | "hpp" | "hh" | "hxx" | "ino" | "ipp" | "tpp" | "inl" => {
+                (format!("auto {} = {};", name, code), name.to_string())
+            }
             _ => return Err(RefactorError::UnsupportedLanguage(ext.to_string())),
         };
 
@@ -400,6 +585,14 @@ impl RefactorTool {
                 format!("const {} = {};", upper_name, code),
                 upper_name.clone(),
             ),
+            "c" => (
+                format!("#define {} ({})", upper_name, code),
+                upper_name.clone(),
+            ),
+            "cpp" | "cc" | "cxx" | "h" | "hpp" | "hh" | "hxx" | "ino" | "ipp" | "tpp" | "inl" => (
+                format!("constexpr auto {} = {};", upper_name, code),
+                upper_name.clone(),
+            ),
             _ => return Err(RefactorError::UnsupportedLanguage(ext.to_string())),
         };
 
@@ -668,8 +861,22 @@ impl RefactorTool {
             }
             "ts" => format!("{}: {}", param_name, param_type),
             "js" => param_name.to_string(),
+            "c" | "cpp" | "cc" | "cxx" | "h" | "hpp" | "hh" | "hxx" | "ino" | "ipp" | "tpp" | "inl" => {
+                format!("{} {}", param_type, param_name)
+            }
             _ => format!("{}: {}", param_name, param_type),
         };
+        let c_signature = regex::Regex::new(&format!(
+            r"^\s*(?:[\w:<>,*&]+\s+)+[*&]*(?:\w+::)*{}\s*\(",
+            regex::escape(function_name)
+        ))
+        .ok()
+        .filter(|_| {
+            matches!(
+                ext,
+                "c" | "cpp" | "cc" | "cxx" | "h" | "hpp" | "hh" | "hxx" | "ino" | "ipp" | "tpp" | "inl"
+            )
+        });
 
         let mut changes = Vec::new();
         let mut new_lines = Vec::new();
@@ -678,6 +885,12 @@ impl RefactorTool {
             if line.contains(&format!("fn {}(", function_name))
                 || line.contains(&format!("def {}(", function_name))
                 || line.contains(&format!("function {}(", function_name))
+                || c_signature.as_ref().is_some_and(|re| {
+                    // C/C++ definitions and prototypes: a type before the name
+                    re.is_match(line)
+                        && !line.trim_start().starts_with("return ")
+                        && !line.trim_start().starts_with("else ")
+                })
             {
                 // Find the closing paren
                 if let Some(paren_pos) = line.rfind(')') {
@@ -802,7 +1015,8 @@ impl RefactorTool {
     async fn collect_source_files(&self, dir: &str) -> Result<Vec<PathBuf>, RefactorError> {
         let mut files = Vec::new();
         let extensions = [
-            "rs", "py", "js", "ts", "jsx", "tsx", "go", "java", "cpp", "c", "h",
+            "rs", "py", "js", "ts", "jsx", "tsx", "go", "java", "cpp", "cc", "cxx", "c", "h",
+            "hpp", "hh", "hxx", "ino", "ipp", "tpp", "inl",
         ];
 
         self.collect_files_recursive(Path::new(dir), &extensions, &mut files)
@@ -866,6 +1080,19 @@ pub enum RefactorError {
     UnsupportedOperation(String),
     #[error("Unsupported language: {0}")]
     UnsupportedLanguage(String),
+    #[error("{0} is declared more than once ({1}); rename within a narrower path")]
+    Ambiguous(String, String),
+    #[error("Symbol index error: {0}")]
+    Index(String),
+    #[error("{0} changed while the refactor was prepared; run it again")]
+    Conflict(String),
+}
+
+/// Letters, digits and `_`, not starting with a digit
+fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars.next().is_some_and(|c| c.is_alphabetic() || c == '_')
+        && chars.all(|c| c.is_alphanumeric() || c == '_')
 }
 
 #[cfg(test)]
@@ -879,5 +1106,95 @@ mod tests {
         let (extracted, call) = tool.extract_to_function("x + y", "add", path).unwrap();
         assert!(extracted.contains("fn add()"));
         assert_eq!(call, "add()");
+
+        let (constant, _) = tool
+            .extract_to_constant("1024", "buffer_size", Path::new("io.c"))
+            .unwrap();
+        assert_eq!(constant, "#define BUFFER_SIZE (1024)");
+    }
+
+    #[tokio::test]
+    async fn test_rename_symbol_ast() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("src")).unwrap();
+        let config = dir.path().join("src/config.rs");
+        let main = dir.path().join("src/main.rs");
+        std::fs::write(&config, "pub fn load() -> u32 {\n    1\n}\n\npub fn load_all() -> u32 {\n    load()\n}\n").unwrap();
+        std::fs::write(&main, "// load is called once\nfn main() {\n    let n = load() + load();\n    println!(\"load\");\n}\n").unwrap();
+
+        let tool = RefactorTool::new();
+        let args = |dry_run| RefactorArgs {
+            operation: RefactorOperation::RenameSymbol { symbol: "load".to_string(), new_name: "read_config".to_string() },
+            path: dir.path().to_string_lossy().to_string(),
+            dry_run: Some(dry_run),
+        };
+
+        let preview = tool.refactor(args(true)).await.unwrap();
+        assert_eq!((preview.files_modified, preview.total_changes), (2, 3));
+        assert!(preview.diff().contains(
+            "--- a/src/main.rs\n+++ b/src/main.rs\n@@ -3,1 +3,1 @@\n-    let n = load() + load();\n+    let n = read_config() + read_config();\n"
+        ));
+        assert!(std::fs::read_to_string(&main).unwrap().contains("load() + load()"));
+
+        tool.refactor(args(false)).await.unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&config).unwrap(),
+            "pub fn read_config() -> u32 {\n    1\n}\n\npub fn load_all() -> u32 {\n    read_config()\n}\n"
+        );
+        assert!(std::fs::read_to_string(&main).unwrap().starts_with("// load is called once\n"));
+
+        let missing = RefactorArgs { operation: RefactorOperation::RenameSymbol { symbol: "save".to_string(), new_name: "store".to_string() }, ..args(true) };
+        assert!(matches!(tool.refactor(missing).await, Err(RefactorError::DefinitionNotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn test_rename_symbol_ast_of_one_type() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("lib.rs");
+        std::fs::write(
+            &file,
+            "struct A;\nimpl A {\n    fn load() {}\n}\nstruct B;\nimpl B {\n    fn load() {}\n}\nfn f() {\n    A::load();\n    B::load();\n}\n",
+        )
+        .unwrap();
+
+        let index = crate::tools::symbol_index::SymbolIndexTool::new()
+            .with_database(crate::db::Database::in_memory().await.unwrap());
+        let tool = RefactorTool::new().with_references(FindReferencesTool::new().with_index(index));
+        let result = tool
+            .refactor(RefactorArgs {
+                operation: RefactorOperation::RenameSymbol { symbol: "A::load".to_string(), new_name: "read".to_string() },
+                path: dir.path().to_string_lossy().to_string(),
+                dry_run: Some(false),
+            })
+            .await
+            .unwrap();
+        assert_eq!(result.total_changes, 2);
+        assert_eq!(
+            std::fs::read_to_string(&file).unwrap(),
+            "struct A;\nimpl A {\n    fn read() {}\n}\nstruct B;\nimpl B {\n    fn load() {}\n}\nfn f() {\n    A::read();\n    B::load();\n}\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_add_parameter_c_family() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("engine.cpp");
+        fs::write(
+            &path,
+            "int Engine::step(int dt) {\n    return step(dt - 1);\n}\nvoid run() { step(1); }",
+        )
+        .await
+        .unwrap();
+
+        let tool = RefactorTool::new();
+        let result = tool
+            .add_parameter("step", "scale", "float", None, &path, true)
+            .await
+            .unwrap();
+        assert_eq!(result.total_changes, 1);
+        assert_eq!(
+            result.changes[0].new_text,
+            "int Engine::step(int dt, float scale) {"
+        );
     }
 }
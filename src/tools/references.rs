@@ -0,0 +1,408 @@
+//! Workspace-wide find references
+//!
+//! Files mentioning the symbol are re-parsed with tree-sitter (sharing the
+//! symbol index parser cache) and every matching identifier outside comments and
+//! strings is classified as definition, import, call or plain reference. The
+//! symbol index supplies the definitions, so `Type::method` can be narrowed to the
+//! right one, and its usages to those qualified with `Type`, inside `Type` or
+//! called on a receiver whose type is not known.
+
+use super::symbol_index::{relative_path, source_files, SymbolIndexError, SymbolIndexTool};
+use crate::db::IndexedSymbol;
+use rig::tool::Tool;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Default cap on reported occurrences
+const DEFAULT_LIMIT: usize = 200;
+
+/// One usage of a symbol
+#[derive(Debug, Clone, Serialize)]
+pub struct Reference {
+    pub path: String,
+    pub line: usize,
+    /// 1-based byte offset in the line
+    pub column: usize,
+    pub kind: &'static str,
+    /// Trimmed source line
+    pub context: String,
+}
+
+/// All usages of a symbol in a project
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ReferenceReport {
+    pub symbol: String,
+    pub definitions: Vec<IndexedSymbol>,
+    pub references: Vec<Reference>,
+    pub files: usize,
+    /// More occurrences than the limit were found
+    pub truncated: bool,
+}
+
+impl ReferenceReport {
+    /// Occurrences grouped by file, with `line:col kind  code` entries
+    pub fn format(&self) -> String {
+        if self.references.is_empty() {
+            return format!("No references to `{}` found", self.symbol);
+        }
+
+        let mut output = format!(
+            "{} references to `{}` in {} files{}\n",
+            self.references.len(),
+            self.symbol,
+            self.files,
+            if self.truncated { " (truncated)" } else { "" }
+        );
+        for definition in &self.definitions {
+            output.push_str(&format!(
+                "Defined at {}:{} ({})\n",
+                definition.relative_path,
+                definition.symbol.line_start,
+                definition.symbol.symbol_type
+            ));
+        }
+
+        let mut current = "";
+        for reference in &self.references {
+            if reference.path != current {
+                current = &reference.path;
+                output.push_str(&format!("\n{}\n", current));
+            }
+            output.push_str(&format!(
+                "  {}:{} {:<10} {}\n",
+                reference.line, reference.column, reference.kind, reference.context
+            ));
+        }
+        output
+    }
+}
+
+/// Arguments for find_references
+#[derive(Debug, Clone, Default, Deserialize, Serialize, JsonSchema)]
+pub struct FindReferencesArgs {
+    /// Symbol to look for: `name` or `Type::name`
+    pub symbol: String,
+    /// Only search below this path, relative to the project root
+    #[serde(default)]
+    pub path: Option<String>,
+    /// Maximum number of occurrences (default 200)
+    #[serde(default)]
+    pub limit: Option<usize>,
+    /// Project directory, filled in by the orchestrator
+    #[serde(skip)]
+    #[schemars(skip)]
+    pub project_dir: String,
+}
+
+/// Lists the usages of a symbol across the project
+#[derive(Clone, Default)]
+pub struct FindReferencesTool {
+    index: SymbolIndexTool,
+}
+
+impl FindReferencesTool {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Use a symbol index (and its parser cache) for definitions
+    pub fn with_index(mut self, index: SymbolIndexTool) -> Self {
+        self.index = index;
+        self
+    }
+
+    /// Find the usages of `symbol` below `scope` (the whole project when `None`)
+    pub async fn find_references(
+        &self,
+        root: &Path,
+        symbol: &str,
+        scope: Option<&str>,
+        limit: usize,
+    ) -> Result<ReferenceReport, SymbolIndexError> {
+        let root = root.canonicalize()?;
+        // Identifiers are single segments: `Parser::parse` matches `parse`
+        let (owner, name) = match symbol.rsplit_once("::") {
+            Some((owner, name)) => (owner.rsplit("::").next(), name),
+            None => (None, symbol),
+        };
+
+        let (definitions, owned) = self.definitions(&root, name, owner).await;
+        // Usages are only narrowed when the index knows where `owner` is
+        let owner = owner.filter(|_| owned);
+        let mut report = ReferenceReport {
+            symbol: symbol.to_string(),
+            definitions,
+            ..Default::default()
+        };
+
+        let search_root = match scope {
+            Some(scope) => {
+                let search_root = root.join(scope).canonicalize()?;
+                if !search_root.starts_with(&root) {
+                    return Err(SymbolIndexError::OutsideProject(scope.to_string()));
+                }
+                search_root
+            }
+            None => root.clone(),
+        };
+        let mut files: Vec<_> = source_files(&search_root);
+        files.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+        let mut guard = self.index.parser().await?;
+        let Some(parser) = guard.as_mut() else {
+            return Err(SymbolIndexError::Parser(
+                "AST parser unavailable".to_string(),
+            ));
+        };
+
+        for (path, language) in files {
+            let Ok(source) = std::fs::read_to_string(&path) else {
+                continue;
+            };
+            // Cheap filter before parsing, like grep would do
+            if !source.contains(name) {
+                continue;
+            }
+
+            let parse = parser
+                .parse_file(&path, language, &source)
+                .map_err(|e| SymbolIndexError::Parser(e.to_string()))?;
+            let mut occurrences = parser.find_references(&parse.tree, language, &source, name);
+            let relative = relative_path(&root, &path);
+            let lines: Vec<&str> = source.lines().collect();
+            if let Some(owner) = owner {
+                let bodies: Vec<(usize, usize)> = self
+                    .index
+                    .file_symbols(&root, &relative)
+                    .await
+                    .unwrap_or_default()
+                    .iter()
+                    .filter(|s| s.symbol.symbol_name == owner)
+                    .map(|s| (s.symbol.line_start, s.symbol.line_end))
+                    .collect();
+                occurrences.retain(|occurrence| {
+                    let inside = bodies
+                        .iter()
+                        .any(|(start, end)| (*start..=*end).contains(&occurrence.line));
+                    let prefix = lines
+                        .get(occurrence.line - 1)
+                        .and_then(|l| l.get(..occurrence.column - 1))
+                        .unwrap_or_default();
+                    belongs_to_owner(prefix, owner, inside)
+                });
+            }
+            if occurrences.is_empty() {
+                continue;
+            }
+
+            report.files += 1;
+            for occurrence in occurrences {
+                if report.references.len() >= limit {
+                    report.truncated = true;
+                    break;
+                }
+                report.references.push(Reference {
+                    path: relative.clone(),
+                    line: occurrence.line,
+                    column: occurrence.column,
+                    kind: occurrence.kind.as_str(),
+                    context: lines
+                        .get(occurrence.line - 1)
+                        .map(|l| l.trim().chars().take(160).collect())
+                        .unwrap_or_default(),
+                });
+            }
+            if report.truncated {
+                break;
+            }
+        }
+
+        Ok(report)
+    }
+
+    /// Indexed definitions named `name`, inside `owner` when given; the flag
+    /// tells whether they could be narrowed to `owner`
+    async fn definitions(
+        &self,
+        root: &Path,
+        name: &str,
+        owner: Option<&str>,
+    ) -> (Vec<IndexedSymbol>, bool) {
+        // Without a database there is no index: occurrences are still reported
+        if self.index.index_project(root).await.is_err() {
+            return (Vec::new(), false);
+        }
+        let Ok(found) = self.index.find(root, name, 50).await else {
+            return (Vec::new(), false);
+        };
+
+        let exact: Vec<IndexedSymbol> = found
+            .into_iter()
+            .filter(|s| s.symbol.symbol_name == name)
+            .collect();
+        let Some(owner) = owner else {
+            return (exact, false);
+        };
+
+        // Keep the definitions nested in a symbol named `owner`
+        let mut owned = Vec::new();
+        for definition in &exact {
+            let Ok(symbols) = self
+                .index
+                .file_symbols(root, &definition.relative_path)
+                .await
+            else {
+                continue;
+            };
+            let nested = symbols.iter().any(|s| {
+                s.symbol.symbol_name == owner
+                    && s.symbol.line_start <= definition.symbol.line_start
+                    && definition.symbol.line_end <= s.symbol.line_end
+            });
+            if nested {
+                owned.push(definition.clone());
+            }
+        }
+        if owned.is_empty() {
+            (exact, false)
+        } else {
+            (owned, true)
+        }
+    }
+}
+
+/// Whether an occurrence preceded by `prefix` on its line can be the member of
+/// `owner`: a path names its type, a bare name only counts inside `owner`, and
+/// a `receiver.name` is kept since the receiver's type is not known here
+fn belongs_to_owner(prefix: &str, owner: &str, inside_owner: bool) -> bool {
+    let prefix = prefix.trim_end();
+    if let Some(path) = prefix.strip_suffix("::") {
+        let qualifier = path
+            .rsplit(|c: char| !(c.is_alphanumeric() || c == '_'))
+            .next()
+            .unwrap_or_default();
+        return qualifier == owner || (qualifier == "Self" && inside_owner);
+    }
+    prefix.ends_with('.') || inside_owner
+}
+
+impl Tool for FindReferencesTool {
+    const NAME: &'static str = "find_references";
+
+    type Args = FindReferencesArgs;
+    type Output = String;
+    type Error = SymbolIndexError;
+
+    async fn definition(&self, _prompt: String) -> rig::completion::ToolDefinition {
+        rig::completion::ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: "List every usage of a symbol across the project (definitions, imports, \
+                          calls and other references) with file:line context. Comments and \
+                          strings are ignored. Use it before renaming or changing a signature."
+                .to_string(),
+            parameters: serde_json::to_value(schemars::schema_for!(FindReferencesArgs))
+                .unwrap_or_default(),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        if args.symbol.trim().is_empty() {
+            return Err(SymbolIndexError::MissingArgument("symbol"));
+        }
+        // sqlx futures are not `Sync`, which `Tool::call` requires: run on a task
+        let tool = self.clone();
+        tokio::spawn(async move {
+            let root = PathBuf::from(if args.project_dir.is_empty() {
+                "."
+            } else {
+                &args.project_dir
+            });
+            let report = tool
+                .find_references(
+                    &root,
+                    args.symbol.trim(),
+                    args.path.as_deref(),
+                    args.limit.unwrap_or(DEFAULT_LIMIT),
+                )
+                .await?;
+            Ok(report.format())
+        })
+        .await
+        .map_err(|e| SymbolIndexError::Task(e.to_string()))?
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::Database;
+    use tempfile::tempdir;
+
+    #[tokio::test]
+    async fn test_find_references() {
+        let dir = tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("src")).unwrap();
+        std::fs::write(
+            dir.path().join("src/config.rs"),
+            "pub struct Config;\n\nimpl Config {\n    pub fn load() -> Self {\n        Config\n    }\n}\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("src/main.rs"),
+            "use crate::config::Config;\n\n// Config is loaded once\nfn main() {\n    let c = Config::load();\n    println!(\"Config\");\n}\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("src/other.rs"),
+            "pub struct Other;\n\nimpl Other {\n    pub fn load() {}\n}\n\nfn f() {\n    Other::load();\n}\n",
+        )
+        .unwrap();
+
+        let index = SymbolIndexTool::new().with_database(Database::in_memory().await.unwrap());
+        let tool = FindReferencesTool::new().with_index(index);
+
+        let report = tool
+            .find_references(dir.path(), "Config", None, 100)
+            .await
+            .unwrap();
+        let found: Vec<_> = report
+            .references
+            .iter()
+            .map(|r| (r.path.as_str(), r.line, r.kind))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                ("src/config.rs", 1, "definition"),
+                ("src/config.rs", 3, "reference"),
+                ("src/config.rs", 5, "reference"),
+                ("src/main.rs", 1, "import"),
+                ("src/main.rs", 5, "reference"),
+            ]
+        );
+        assert_eq!(report.files, 2);
+        assert_eq!(report.definitions.len(), 1);
+
+        let report = tool
+            .find_references(dir.path(), "Config::load", None, 100)
+            .await
+            .unwrap();
+        assert_eq!(report.definitions[0].symbol.symbol_type, "method");
+        assert_eq!(report.references.len(), 2);
+        assert_eq!(report.references[1].kind, "call");
+        assert!(report.format().contains("src/main.rs\n  5:21 call"));
+        assert!(report.references.iter().all(|r| r.path != "src/other.rs"));
+        assert!(matches!(
+            tool.find_references(dir.path(), "Config", Some(".."), 100).await,
+            Err(SymbolIndexError::OutsideProject(_))
+        ));
+
+        let report = tool
+            .find_references(dir.path(), "Config", None, 2)
+            .await
+            .unwrap();
+        assert!(report.truncated);
+        assert_eq!(report.references.len(), 2);
+    }
+}
@@ -1,31 +1,78 @@
 //! Tool registry for managing and sharing tools between agents
 
 use super::{
+    BenchmarkTool,
+    BuildErrorKb,
     CalculatorTool,
     CodeAnalyzerTool,
+    ConventionChecker,
+    DataPreviewTool,
+    DatabaseQueryTool,
+    DeadCodeTool,
     DependencyAnalyzerTool,
+    DependencyDocsTool,
+    DevServerTool,
+    DockerComposeTool,
     DocumentationTool,
     EnvironmentTool,
     // New tools
     FileIndexerTool,
     FileReadTool,
     FileWriteTool,
+    FindReferencesTool,
     FormatterTool,
     GitTool,
     HttpClientTool,
+    KubernetesTool,
     LinterTool,
+    LogTailTool,
     ListDirectoryTool,
+    MemoryTool,
+    OwnershipTool,
+    ProfilerTool,
     ProjectContextTool,
     RefactorTool,
+    ReplaceTool,
+    SchemaIngestTool,
     SearchInFilesTool,
     ShellExecuteTool,
     ShellExecutorTool,
     SnippetTool,
+    SymbolIndexTool,
     TaskPlannerTool,
+    TerraformPlanTool,
     TestRunnerTool,
+    WasmPluginHost,
 };
+use rig::tool::Tool;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
+/// Where a tool comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ToolOrigin {
+    BuiltIn,
+    /// A WASM plugin of the plugin directories
+    Plugin,
+}
+
+impl ToolOrigin {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::BuiltIn => "built-in",
+            Self::Plugin => "plugin",
+        }
+    }
+}
+
+/// A tool the model can call
+#[derive(Debug, Clone)]
+pub struct ToolListing {
+    pub name: String,
+    pub description: String,
+    pub origin: ToolOrigin,
+}
+
 /// Registry that holds all available tools
 /// This can be shared between multiple agents
 #[derive(Clone)]
@@ -42,18 +89,44 @@ pub struct ToolRegistry {
     pub file_indexer: Arc<FileIndexerTool>,
     pub task_planner: Arc<TaskPlannerTool>,
     pub search_files: Arc<SearchInFilesTool>,
+    pub replace: Arc<ReplaceTool>,
     pub git: Arc<GitTool>,
+    pub owners: Arc<OwnershipTool>,
     pub code_analyzer: Arc<CodeAnalyzerTool>,
+    pub profiler: Arc<ProfilerTool>,
     pub dependency_analyzer: Arc<DependencyAnalyzerTool>,
     pub http_client: Arc<HttpClientTool>,
     pub shell_executor: Arc<ShellExecutorTool>,
     pub test_runner: Arc<TestRunnerTool>,
+    pub benchmarks: Arc<BenchmarkTool>,
     pub documentation: Arc<DocumentationTool>,
     pub formatter: Arc<FormatterTool>,
     pub refactor: Arc<RefactorTool>,
     pub snippets: Arc<SnippetTool>,
     pub project_context: Arc<tokio::sync::Mutex<ProjectContextTool>>,
     pub environment: Arc<EnvironmentTool>,
+    pub database: Arc<DatabaseQueryTool>,
+    pub log_tail: Arc<LogTailTool>,
+    pub docker: Arc<DockerComposeTool>,
+    pub dev_server: Arc<DevServerTool>,
+    pub kubernetes: Arc<KubernetesTool>,
+    pub terraform: Arc<TerraformPlanTool>,
+    pub data_preview: Arc<DataPreviewTool>,
+    pub schema: Arc<SchemaIngestTool>,
+    pub symbol_index: Arc<SymbolIndexTool>,
+    pub references: Arc<FindReferencesTool>,
+    pub dead_code: Arc<DeadCodeTool>,
+    pub memory: Arc<MemoryTool>,
+    /// Not a model-facing tool: reviews the files written with `write_file`
+    pub conventions: Arc<ConventionChecker>,
+    /// Not a model-facing tool: remembers build errors and their fixes
+    pub build_errors: Arc<BuildErrorKb>,
+    /// Not a model-facing tool: fetches dependency docs into the docs namespace
+    pub dependency_docs: Arc<DependencyDocsTool>,
+    /// WASM plugins, exposed to the model under their own names
+    pub plugins: Arc<WasmPluginHost>,
+    /// `protected_paths` of the config, matched like a .gitignore
+    pub protected_paths: Option<Arc<ignore::gitignore::Gitignore>>,
 }
 
 impl Default for ToolRegistry {
@@ -67,9 +140,9 @@ impl ToolRegistry {
     pub fn new() -> Self {
         Self {
             // Original tools
-            file_read: Arc::new(FileReadTool),
+            file_read: Arc::new(FileReadTool::default()),
             file_write: Arc::new(FileWriteTool),
-            list_directory: Arc::new(ListDirectoryTool),
+            list_directory: Arc::new(ListDirectoryTool::default()),
             shell_execute: Arc::new(ShellExecuteTool::new()),
             linter: Arc::new(LinterTool),
             calculator: Arc::new(CalculatorTool),
@@ -78,21 +151,80 @@ impl ToolRegistry {
             file_indexer: Arc::new(FileIndexerTool::new()),
             task_planner: Arc::new(TaskPlannerTool::new()),
             search_files: Arc::new(SearchInFilesTool::new()),
+            replace: Arc::new(ReplaceTool::new()),
             git: Arc::new(GitTool::new()),
+            owners: Arc::new(OwnershipTool::new()),
             code_analyzer: Arc::new(CodeAnalyzerTool::new()),
+            profiler: Arc::new(ProfilerTool::new()),
             dependency_analyzer: Arc::new(DependencyAnalyzerTool),
             http_client: Arc::new(HttpClientTool::new()),
             shell_executor: Arc::new(ShellExecutorTool::new()),
             test_runner: Arc::new(TestRunnerTool::new()),
+            benchmarks: Arc::new(BenchmarkTool::new()),
             documentation: Arc::new(DocumentationTool::new()),
             formatter: Arc::new(FormatterTool::new()),
             refactor: Arc::new(RefactorTool::new()),
             snippets: Arc::new(SnippetTool::with_defaults()),
             project_context: Arc::new(tokio::sync::Mutex::new(ProjectContextTool::new())),
             environment: Arc::new(EnvironmentTool::new()),
+            database: Arc::new(DatabaseQueryTool::new()),
+            log_tail: Arc::new(LogTailTool::new()),
+            docker: Arc::new(DockerComposeTool::new()),
+            dev_server: Arc::new(DevServerTool::new()),
+            kubernetes: Arc::new(KubernetesTool::new()),
+            terraform: Arc::new(TerraformPlanTool::new()),
+            data_preview: Arc::new(DataPreviewTool::new()),
+            schema: Arc::new(SchemaIngestTool::new()),
+            symbol_index: Arc::new(SymbolIndexTool::new()),
+            references: Arc::new(FindReferencesTool::new()),
+            dead_code: Arc::new(DeadCodeTool::new()),
+            memory: Arc::new(MemoryTool::new()),
+            conventions: Arc::new(ConventionChecker::new()),
+            build_errors: Arc::new(BuildErrorKb::new()),
+            dependency_docs: Arc::new(DependencyDocsTool::new()),
+            plugins: Arc::new(WasmPluginHost::default()),
+            protected_paths: None,
         }
     }
 
+    /// Error for the model when `path` is in a read-only root or protected by the project config
+    pub fn check_writable(&self, path: &str, read_only_roots: &[PathBuf]) -> Option<String> {
+        if let Some(error) = Self::check_read_only_root(path, read_only_roots) {
+            return Some(error);
+        }
+        let matcher = self.protected_paths.as_ref()?;
+        let relative = Path::new(path).strip_prefix(matcher.path()).ok()?;
+        matcher
+            .matched_path_or_any_parents(relative, false)
+            .is_ignore()
+            .then(|| format!("Error: {} is a protected path (protected_paths in .neuro/config.json); do not modify it", path))
+    }
+
+    /// Error for the model when `path` is in a read-only root
+    pub fn check_read_only_root(path: &str, read_only_roots: &[PathBuf]) -> Option<String> {
+        let root = crate::agent::workspace::containing_root(Path::new(path), read_only_roots)?;
+        Some(format!(
+            "Error: {} is in the read-only root {}; read it, but write only under the working directory",
+            path,
+            root.display()
+        ))
+    }
+
+    /// [`Self::check_writable`] for a directory a tool may write anywhere in:
+    /// the directory itself and everything under it
+    pub fn check_writable_dir(&self, dir: &Path, read_only_roots: &[PathBuf]) -> Option<String> {
+        if let Some(error) = self.check_writable(&dir.to_string_lossy(), read_only_roots) {
+            return Some(error);
+        }
+        self.protected_paths.as_ref()?;
+        // Ignored files too: `.env` is the usual protected path
+        ignore::WalkBuilder::new(dir)
+            .standard_filters(false)
+            .build()
+            .filter_map(Result::ok)
+            .find_map(|entry| self.check_writable(&entry.path().to_string_lossy(), read_only_roots))
+    }
+
     /// Create a new tool registry with a custom shell executor
     pub fn with_shell_executor(shell_execute: ShellExecuteTool) -> Self {
         let mut registry = Self::new();
@@ -102,7 +234,7 @@ impl ToolRegistry {
 
     /// Get a list of all tool names
     pub fn tool_names(&self) -> Vec<&'static str> {
-        vec![
+        let mut names = vec![
             // Original tools
             FileReadTool::NAME,
             FileWriteTool::NAME,
@@ -114,6 +246,7 @@ impl ToolRegistry {
             FileIndexerTool::NAME,
             TaskPlannerTool::NAME,
             SearchInFilesTool::NAME,
+            ReplaceTool::NAME,
             GitTool::NAME,
             CodeAnalyzerTool::NAME,
             DependencyAnalyzerTool::NAME,
@@ -126,7 +259,25 @@ impl ToolRegistry {
             SnippetTool::NAME,
             ProjectContextTool::NAME,
             EnvironmentTool::NAME,
-        ]
+            DatabaseQueryTool::NAME,
+            LogTailTool::NAME,
+            DockerComposeTool::NAME,
+            DevServerTool::NAME,
+            TerraformPlanTool::NAME,
+            DataPreviewTool::NAME,
+            SchemaIngestTool::NAME,
+            SymbolIndexTool::NAME,
+            FindReferencesTool::NAME,
+            DeadCodeTool::NAME,
+            MemoryTool::NAME,
+            OwnershipTool::NAME,
+            ProfilerTool::NAME,
+            BenchmarkTool::NAME,
+        ];
+        if self.kubernetes.is_enabled() {
+            names.push(KubernetesTool::NAME);
+        }
+        names
     }
 
     /// Get tool descriptions for the system prompt
@@ -135,11 +286,11 @@ impl ToolRegistry {
             r#"Available tools (20+):
 
 ## File System Operations
-1. {} - Read file contents, optionally specifying line ranges
+1. {} - Read file contents: a line range, one symbol (symbol="Type::method") or the lines around some text (around="..."); ranges grow to whole functions
 2. {} - Write content to files, can create directories
-3. {} - List directory contents, optionally recursive
+3. {} - List directory contents, optionally recursive; summary=true gives file counts, sizes and languages per entry (default for the project root)
 4. {} - Index project files for context
-5. {} - Search in files using patterns (grep-like)
+5. {} - Search file contents (literal, regex or whole word), paged path:line:column matches
 
 ## Code Analysis & Quality
 6. {} - Analyze code structure, metrics, and issues
@@ -152,20 +303,36 @@ impl ToolRegistry {
 11. {} - Generate documentation
 12. {} - Run tests across frameworks
 13. {} - Get project context and structure
+14. {} - Summarize Terraform plans with risk highlighting (never applies)
 
 ## Git Operations
-14. {} - Git operations (status, diff, log, commit, blame)
+15. {} - Git operations (status, diff, log, commit, blame; stash, branch and rebase planning with preview and confirm)
 
 ## Shell & Environment
-15. {} - Execute shell commands (security-scanned)
-16. {} - Advanced shell execution with streaming
-17. {} - Get environment and system info
+16. {} - Execute shell commands (security-scanned)
+17. {} - Advanced shell execution with streaming
+18. {} - Get environment and system info
+19. {} - Follow and condense logs (file, journalctl, docker)
+20. {} - Inspect Dockerfiles/compose services and run compose ps/logs/up/down
+21. {} - Inspect Kubernetes resources and pod logs (opt-in)
 
 ## Planning & Utilities
-18. {} - Evaluate mathematical expressions
-19. {} - Create and manage task plans
-20. {} - Make HTTP requests
-21. {} - Code snippets and templates"#,
+22. {} - Evaluate mathematical expressions
+23. {} - Create and manage task plans
+24. {} - Make HTTP requests
+25. {} - Code snippets and templates
+26. {} - Read-only SQL queries against project databases
+27. {} - Preview CSV/JSONL/Parquet files with schema and column stats
+28. {} - Ingest the database schema (tables, columns, relations) for SQL/ORM work
+29. {} - Find symbols by name (fuzzy), list a file's symbols or get the symbol at a line
+30. {} - List all usages of a symbol across the project with file:line context
+31. {} - Replace across files: preview numbered matches, then apply the chosen ones (undoable)
+32. {} - Run dev servers in the background and read their recent output
+33. {} - Remember project facts across sessions and recall them by topic
+34. {} - Who knows a file or directory best from git history (suggested reviewers)
+35. {} - Profile a command (cargo flamegraph, py-spy, node --prof) and list its hotspots with file:line
+36. {} - Run benchmarks, record them per commit and list regressions against a baseline branch
+37. {} - Find functions and types never referenced in the project, grouped by language"#,
             FileReadTool::NAME,
             FileWriteTool::NAME,
             ListDirectoryTool::NAME,
@@ -179,14 +346,30 @@ impl ToolRegistry {
             DocumentationTool::NAME,
             TestRunnerTool::NAME,
             ProjectContextTool::NAME,
+            TerraformPlanTool::NAME,
             GitTool::NAME,
             ShellExecuteTool::NAME,
             ShellExecutorTool::NAME,
             EnvironmentTool::NAME,
+            LogTailTool::NAME,
+            DockerComposeTool::NAME,
+            KubernetesTool::NAME,
             CalculatorTool::NAME,
             TaskPlannerTool::NAME,
             HttpClientTool::NAME,
             SnippetTool::NAME,
+            DatabaseQueryTool::NAME,
+            DataPreviewTool::NAME,
+            SchemaIngestTool::NAME,
+            SymbolIndexTool::NAME,
+            FindReferencesTool::NAME,
+            ReplaceTool::NAME,
+            DevServerTool::NAME,
+            MemoryTool::NAME,
+            OwnershipTool::NAME,
+            ProfilerTool::NAME,
+            BenchmarkTool::NAME,
+            DeadCodeTool::NAME,
         )
     }
 
@@ -202,6 +385,8 @@ impl ToolRegistry {
                 ListDirectoryTool::NAME,
                 FileIndexerTool::NAME,
                 SearchInFilesTool::NAME,
+                ReplaceTool::NAME,
+                DataPreviewTool::NAME,
             ],
         );
 
@@ -212,6 +397,10 @@ impl ToolRegistry {
                 LinterTool::NAME,
                 FormatterTool::NAME,
                 RefactorTool::NAME,
+                SymbolIndexTool::NAME,
+                FindReferencesTool::NAME,
+                DeadCodeTool::NAME,
+                ProfilerTool::NAME,
             ],
         );
 
@@ -221,11 +410,14 @@ impl ToolRegistry {
                 DependencyAnalyzerTool::NAME,
                 DocumentationTool::NAME,
                 TestRunnerTool::NAME,
+                BenchmarkTool::NAME,
                 ProjectContextTool::NAME,
+                TerraformPlanTool::NAME,
+                SchemaIngestTool::NAME,
             ],
         );
 
-        categories.insert("git".to_string(), vec![GitTool::NAME]);
+        categories.insert("git".to_string(), vec![GitTool::NAME, OwnershipTool::NAME]);
 
         categories.insert(
             "shell".to_string(),
@@ -233,6 +425,10 @@ impl ToolRegistry {
                 ShellExecuteTool::NAME,
                 ShellExecutorTool::NAME,
                 EnvironmentTool::NAME,
+                LogTailTool::NAME,
+                DockerComposeTool::NAME,
+                DevServerTool::NAME,
+                KubernetesTool::NAME,
             ],
         );
 
@@ -243,6 +439,8 @@ impl ToolRegistry {
                 TaskPlannerTool::NAME,
                 HttpClientTool::NAME,
                 SnippetTool::NAME,
+                DatabaseQueryTool::NAME,
+                MemoryTool::NAME,
             ],
         );
 
@@ -269,7 +467,6 @@ impl ToolRegistry {
     /// Other tools will need manual schema definitions or trait implementation.
     pub async fn get_ollama_tools_schema(&self) -> Vec<crate::agent::provider::OllamaTool> {
         use crate::agent::provider::{OllamaFunction, OllamaTool};
-        use rig::tool::Tool;
 
         let mut tools = Vec::new();
 
@@ -292,15 +489,40 @@ impl ToolRegistry {
         add_tool!(&*self.file_read);
         add_tool!(&*self.file_write);
         add_tool!(&*self.list_directory);
+        add_tool!(&*self.search_files);
+        add_tool!(&*self.replace);
 
         // Code analysis tools (implement Tool trait)
         add_tool!(&*self.linter);
+        add_tool!(&*self.symbol_index);
+        add_tool!(&*self.references);
+        add_tool!(&*self.dead_code);
+        add_tool!(&*self.owners);
+        add_tool!(&*self.profiler);
+        add_tool!(&*self.benchmarks);
 
         // Shell tool (implement Tool trait)
         add_tool!(&*self.shell_execute);
+        add_tool!(&*self.log_tail);
+        add_tool!(&*self.docker);
+        add_tool!(&*self.dev_server);
+        add_tool!(&*self.terraform);
+        if self.kubernetes.is_enabled() {
+            add_tool!(&*self.kubernetes);
+        }
 
         // Utilities (implement Tool trait)
         add_tool!(&*self.calculator);
+        add_tool!(&*self.database);
+        add_tool!(&*self.data_preview);
+        add_tool!(&*self.schema);
+        add_tool!(&*self.http_client);
+        add_tool!(&*self.memory);
+
+        // WASM plugins (implement Tool trait)
+        for plugin in self.plugins.plugins() {
+            add_tool!(&**plugin);
+        }
 
         // MANUAL SCHEMAS: Tools that don't yet implement Tool trait
         
@@ -310,13 +532,29 @@ impl ToolRegistry {
         // for most code assistance tasks.
         
         // TODO: Add manual schemas for remaining tools:
-        // - search_files (text search in files)
         // - code_analyzer, formatter, refactor
         // - dependency_analyzer, documentation, test_runner, git
-        // - http_client, snippets, project_context
+        // - snippets, project_context
 
         tools
     }
+
+    /// The tools offered to the model, with their origin
+    pub async fn tool_listing(&self) -> Vec<ToolListing> {
+        self.get_ollama_tools_schema()
+            .await
+            .into_iter()
+            .map(|tool| ToolListing {
+                origin: if self.plugins.get(&tool.function.name).is_some() {
+                    ToolOrigin::Plugin
+                } else {
+                    ToolOrigin::BuiltIn
+                },
+                description: tool.function.description,
+                name: tool.function.name,
+            })
+            .collect()
+    }
 }
 
 // Implement the NAME constants using the Tool trait
@@ -343,3 +581,35 @@ impl LinterTool {
 impl CalculatorTool {
     pub const NAME: &'static str = "calculator";
 }
+
+impl TerraformPlanTool {
+    pub const NAME: &'static str = "terraform_plan";
+}
+
+impl DataPreviewTool {
+    pub const NAME: &'static str = "preview_data";
+}
+
+impl SchemaIngestTool {
+    pub const NAME: &'static str = "ingest_schema";
+}
+
+impl SymbolIndexTool {
+    pub const NAME: &'static str = "symbol_index";
+}
+
+impl FindReferencesTool {
+    pub const NAME: &'static str = "find_references";
+}
+
+impl DeadCodeTool {
+    pub const NAME: &'static str = "find_dead_code";
+}
+
+impl MemoryTool {
+    pub const NAME: &'static str = "memory";
+}
+
+impl OwnershipTool {
+    pub const NAME: &'static str = "code_owners";
+}
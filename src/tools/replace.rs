@@ -0,0 +1,596 @@
+//! Project-wide search and replace
+//!
+//! A replace is planned before anything is written: every match of the pattern
+//! (literal, whole word, or regex with `$1`/`${name}` capture groups) becomes a
+//! numbered edit showing the line before and after, and all edits start
+//! selected. Applying writes every touched file at once: each file must still
+//! hold the content the plan was built from, is written to a sibling temp file
+//! and renamed into place, and a failure part-way restores the files already
+//! replaced. The applied batch is kept so it can be undone as a unit.
+
+use super::search::{pattern_regex, project_files, SearchError, SearchMode};
+use rig::tool::Tool;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+/// Matches a single plan may hold
+const MAX_EDITS: usize = 500;
+/// Applied batches kept for undo
+const MAX_UNDO_BATCHES: usize = 10;
+/// Characters of a line shown in the checklist
+const MAX_PREVIEW_CHARS: usize = 160;
+
+#[derive(Debug, thiserror::Error)]
+pub enum ReplaceError {
+    #[error(transparent)]
+    Search(#[from] SearchError),
+    #[error("IO error on {0}: {1}")]
+    Io(PathBuf, std::io::Error),
+    #[error("{0} changed since the replace was planned; plan it again")]
+    Conflict(PathBuf),
+    #[error("More than {0} matches; narrow the pattern or use file_pattern")]
+    TooManyMatches(usize),
+    #[error("No matches selected")]
+    NothingSelected,
+    #[error("Invalid selection: {0}")]
+    InvalidSelection(String),
+    #[error("No pending replace")]
+    NoPendingPlan,
+    #[error("No applied replace to undo")]
+    NothingToUndo,
+}
+
+/// Arguments for planning (and optionally applying) a replace
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ReplaceArgs {
+    /// Directory to search (default: project root)
+    #[serde(default = "default_root")]
+    pub path: String,
+    /// Text or regex to replace
+    pub pattern: String,
+    /// Replacement; in regex mode `$1` or `${name}` insert capture groups
+    pub replacement: String,
+    /// literal (default), regex or word (whole-word literal)
+    #[serde(default)]
+    pub mode: SearchMode,
+    /// Ignore case (default: false)
+    #[serde(default)]
+    pub case_insensitive: bool,
+    /// Only files whose name matches, e.g. "*.rs"
+    #[serde(default)]
+    pub file_pattern: Option<String>,
+    /// false (default) only previews the numbered matches; true writes them
+    #[serde(default)]
+    pub apply: bool,
+    /// Match numbers from the preview to apply (default: all)
+    #[serde(default)]
+    pub matches: Option<Vec<usize>>,
+}
+
+fn default_root() -> String {
+    ".".to_string()
+}
+
+/// One planned replacement
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReplaceEdit {
+    /// 1-based number shown in the checklist
+    pub id: usize,
+    pub path: PathBuf,
+    pub line: usize,
+    pub column: usize,
+    /// Byte range of the match in the planned content
+    pub start: usize,
+    pub end: usize,
+    /// Replacement text with capture groups expanded
+    pub replacement: String,
+    pub before: String,
+    pub after: String,
+}
+
+/// Matches found for a replace, with the ones chosen to apply
+#[derive(Debug, Clone)]
+pub struct ReplacePlan {
+    pub root: PathBuf,
+    pub pattern: String,
+    pub replacement: String,
+    pub edits: Vec<ReplaceEdit>,
+    selected: BTreeSet<usize>,
+    /// Content of each file when the plan was made
+    originals: BTreeMap<PathBuf, String>,
+}
+
+impl ReplacePlan {
+    /// Numbers of the edits that will be applied
+    pub fn selected(&self) -> &BTreeSet<usize> {
+        &self.selected
+    }
+
+    pub fn select_all(&mut self) {
+        self.selected = self.edits.iter().map(|e| e.id).collect();
+    }
+
+    pub fn select_none(&mut self) {
+        self.selected.clear();
+    }
+
+    /// Select exactly `ids`
+    pub fn select_only(&mut self, ids: &[usize]) -> Result<(), ReplaceError> {
+        self.check_ids(ids)?;
+        self.selected = ids.iter().copied().collect();
+        Ok(())
+    }
+
+    /// Flip the selection of `ids`
+    pub fn toggle(&mut self, ids: &[usize]) -> Result<(), ReplaceError> {
+        self.check_ids(ids)?;
+        for id in ids {
+            if !self.selected.remove(id) {
+                self.selected.insert(*id);
+            }
+        }
+        Ok(())
+    }
+
+    fn check_ids(&self, ids: &[usize]) -> Result<(), ReplaceError> {
+        match ids.iter().find(|id| **id == 0 || **id > self.edits.len()) {
+            Some(id) => Err(ReplaceError::InvalidSelection(format!(
+                "#{} (matches are 1-{})",
+                id,
+                self.edits.len()
+            ))),
+            None => Ok(()),
+        }
+    }
+
+    /// Files the selected edits write to
+    pub fn files(&self) -> BTreeSet<&Path> {
+        self.edits
+            .iter()
+            .filter(|e| self.selected.contains(&e.id))
+            .map(|e| e.path.as_path())
+            .collect()
+    }
+
+    /// Checklist of the matches: `[x] #3 src/a.rs:12:5` with the line before and after
+    pub fn format(&self) -> String {
+        if self.edits.is_empty() {
+            return format!("No matches for '{}'", self.pattern);
+        }
+        let files: BTreeSet<&PathBuf> = self.edits.iter().map(|e| &e.path).collect();
+        let mut out = format!(
+            "Replace '{}' → '{}': {} matches in {} files, {} selected\n",
+            self.pattern,
+            self.replacement,
+            self.edits.len(),
+            files.len(),
+            self.selected.len()
+        );
+        for edit in &self.edits {
+            let path = edit.path.strip_prefix(&self.root).unwrap_or(&edit.path);
+            out.push_str(&format!(
+                "[{}] #{} {}:{}:{}\n    - {}\n    + {}\n",
+                if self.selected.contains(&edit.id) { "x" } else { " " },
+                edit.id,
+                path.display(),
+                edit.line,
+                edit.column,
+                edit.before,
+                edit.after
+            ));
+        }
+        out
+    }
+}
+
+/// Files written by one applied replace
+#[derive(Debug, Clone)]
+pub struct ReplaceBatch {
+    pub pattern: String,
+    pub replacements: usize,
+    /// Path, content before, content after
+    files: Vec<(PathBuf, String, String)>,
+}
+
+impl ReplaceBatch {
+    pub fn files(&self) -> impl Iterator<Item = &Path> {
+        self.files.iter().map(|(path, _, _)| path.as_path())
+    }
+
+    /// Each written file with its content before the replace
+    pub fn changes(&self) -> impl Iterator<Item = (&Path, &str)> {
+        self.files.iter().map(|(path, before, _)| (path.as_path(), before.as_str()))
+    }
+
+    /// `✅ Replaced 3 matches in 2 files: a.rs, b.rs`, paths relative to `root`
+    pub fn format(&self, root: &Path) -> String {
+        let files: Vec<String> = self
+            .files()
+            .map(|p| p.strip_prefix(root).unwrap_or(p).display().to_string())
+            .collect();
+        format!(
+            "✅ Replaced {} matches in {} files: {}",
+            self.replacements,
+            files.len(),
+            files.join(", ")
+        )
+    }
+}
+
+/// Safe project-wide replace with a pending plan and an undo history
+#[derive(Debug, Clone, Default)]
+pub struct ReplaceTool {
+    pending: Arc<Mutex<Option<ReplacePlan>>>,
+    history: Arc<Mutex<Vec<ReplaceBatch>>>,
+}
+
+impl ReplaceTool {
+    pub const NAME: &'static str = "replace_in_files";
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Find every match and the text it would become; nothing is written
+    pub async fn plan(&self, args: &ReplaceArgs) -> Result<ReplacePlan, ReplaceError> {
+        let args = args.clone();
+        tokio::task::spawn_blocking(move || plan_blocking(&args))
+            .await
+            .map_err(|e| ReplaceError::Io(PathBuf::new(), std::io::Error::other(e)))?
+    }
+
+    /// Keep a plan for `/replace` to review and apply later
+    pub fn set_pending(&self, plan: Option<ReplacePlan>) {
+        *self.pending.lock().unwrap_or_else(|e| e.into_inner()) = plan;
+    }
+
+    /// Run `f` on the pending plan
+    pub fn with_pending<T>(
+        &self,
+        f: impl FnOnce(&mut ReplacePlan) -> Result<T, ReplaceError>,
+    ) -> Result<T, ReplaceError> {
+        let mut pending = self.pending.lock().unwrap_or_else(|e| e.into_inner());
+        f(pending.as_mut().ok_or(ReplaceError::NoPendingPlan)?)
+    }
+
+    /// Apply the pending plan and clear it
+    pub async fn apply_pending(&self) -> Result<ReplaceBatch, ReplaceError> {
+        let plan = self
+            .pending
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .take()
+            .ok_or(ReplaceError::NoPendingPlan)?;
+        match self.apply(&plan).await {
+            // A conflict or write error keeps the plan around to inspect
+            Err(e) => {
+                self.set_pending(Some(plan));
+                Err(e)
+            }
+            ok => ok,
+        }
+    }
+
+    /// Write the selected edits of a plan to all files at once
+    pub async fn apply(&self, plan: &ReplacePlan) -> Result<ReplaceBatch, ReplaceError> {
+        let plan = plan.clone();
+        let batch = tokio::task::spawn_blocking(move || apply_blocking(&plan))
+            .await
+            .map_err(|e| ReplaceError::Io(PathBuf::new(), std::io::Error::other(e)))??;
+
+        let mut history = self.history.lock().unwrap_or_else(|e| e.into_inner());
+        history.push(batch.clone());
+        if history.len() > MAX_UNDO_BATCHES {
+            history.remove(0);
+        }
+        Ok(batch)
+    }
+
+    /// Restore the files of the last applied replace
+    pub async fn undo_last(&self) -> Result<ReplaceBatch, ReplaceError> {
+        let batch = self
+            .history
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .pop()
+            .ok_or(ReplaceError::NothingToUndo)?;
+        let restore = batch.clone();
+        let result = tokio::task::spawn_blocking(move || {
+            let expected: Vec<(PathBuf, String, String)> = restore
+                .files
+                .iter()
+                .map(|(path, before, after)| (path.clone(), after.clone(), before.clone()))
+                .collect();
+            write_all(&expected)
+        })
+        .await
+        .map_err(|e| ReplaceError::Io(PathBuf::new(), std::io::Error::other(e)))?;
+
+        match result {
+            Ok(()) => Ok(batch),
+            Err(e) => {
+                self.history.lock().unwrap_or_else(|e| e.into_inner()).push(batch);
+                Err(e)
+            }
+        }
+    }
+}
+
+fn plan_blocking(args: &ReplaceArgs) -> Result<ReplacePlan, ReplaceError> {
+    let root = PathBuf::from(&args.path);
+    if !root.exists() {
+        return Err(SearchError::PathNotFound(args.path.clone()).into());
+    }
+    let regex = pattern_regex(&args.pattern, args.mode, args.case_insensitive)?;
+
+    let mut edits = Vec::new();
+    let mut originals = BTreeMap::new();
+    for path in project_files(&root, args.file_pattern.as_deref(), None) {
+        // Non-UTF-8 files are never rewritten
+        let Ok(content) = std::fs::read_to_string(&path) else {
+            continue;
+        };
+        let before = edits.len();
+        for caps in regex.captures_iter(&content) {
+            let Some(m) = caps.get(0) else { continue };
+            if m.as_str().is_empty() {
+                continue;
+            }
+            if edits.len() >= MAX_EDITS {
+                return Err(ReplaceError::TooManyMatches(MAX_EDITS));
+            }
+            let mut replacement = String::new();
+            if args.mode == SearchMode::Regex {
+                caps.expand(&args.replacement, &mut replacement);
+            } else {
+                replacement.push_str(&args.replacement);
+            }
+
+            let line_start = content[..m.start()].rfind('\n').map_or(0, |i| i + 1);
+            let line_end = content[m.end()..].find('\n').map_or(content.len(), |i| m.end() + i);
+            let after = format!(
+                "{}{}{}",
+                &content[line_start..m.start()],
+                replacement,
+                &content[m.end()..line_end]
+            );
+            edits.push(ReplaceEdit {
+                id: edits.len() + 1,
+                path: path.clone(),
+                line: content[..m.start()].matches('\n').count() + 1,
+                column: content[line_start..m.start()].chars().count() + 1,
+                start: m.start(),
+                end: m.end(),
+                replacement,
+                before: preview(&content[line_start..line_end]),
+                after: preview(&after),
+            });
+        }
+        if edits.len() > before {
+            originals.insert(path, content);
+        }
+    }
+
+    let mut plan = ReplacePlan {
+        root,
+        pattern: args.pattern.clone(),
+        replacement: args.replacement.clone(),
+        edits,
+        selected: BTreeSet::new(),
+        originals,
+    };
+    plan.select_all();
+    Ok(plan)
+}
+
+fn preview(line: &str) -> String {
+    let line = line.trim();
+    let mut shown: String = line.chars().take(MAX_PREVIEW_CHARS).collect();
+    if shown.len() < line.len() {
+        shown.push('…');
+    }
+    shown.replace('\n', "⏎")
+}
+
+fn apply_blocking(plan: &ReplacePlan) -> Result<ReplaceBatch, ReplaceError> {
+    if plan.selected.is_empty() {
+        return Err(ReplaceError::NothingSelected);
+    }
+    let mut by_file: BTreeMap<&PathBuf, Vec<&ReplaceEdit>> = BTreeMap::new();
+    for edit in plan.edits.iter().filter(|e| plan.selected.contains(&e.id)) {
+        by_file.entry(&edit.path).or_default().push(edit);
+    }
+
+    let mut files = Vec::new();
+    for (path, edits) in by_file {
+        let original = &plan.originals[path];
+        let mut replaced = original.clone();
+        // Back to front so earlier byte ranges stay valid
+        for edit in edits.iter().rev() {
+            replaced.replace_range(edit.start..edit.end, &edit.replacement);
+        }
+        files.push((path.clone(), original.clone(), replaced));
+    }
+    write_all(&files)?;
+
+    Ok(ReplaceBatch {
+        pattern: plan.pattern.clone(),
+        replacements: plan.selected.len(),
+        files,
+    })
+}
+
+/// Replace every `(path, expected, new)` file, all or nothing. Each file must
+/// still hold `expected`; if a write fails, the files already written get
+/// `expected` back.
+pub(crate) fn write_all(files: &[(PathBuf, String, String)]) -> Result<(), ReplaceError> {
+    for (path, expected, _) in files {
+        let current =
+            std::fs::read_to_string(path).map_err(|e| ReplaceError::Io(path.clone(), e))?;
+        if &current != expected {
+            return Err(ReplaceError::Conflict(path.clone()));
+        }
+    }
+
+    for (done, (path, _, new)) in files.iter().enumerate() {
+        if let Err(e) = write_atomic(path, new) {
+            for (written, expected, _) in &files[..done] {
+                let _ = write_atomic(written, expected);
+            }
+            return Err(ReplaceError::Io(path.clone(), e));
+        }
+    }
+    Ok(())
+}
+
+/// Write through a sibling temp file renamed over `path`, keeping permissions
+fn write_atomic(path: &Path, content: &str) -> std::io::Result<()> {
+    let dir = path.parent().unwrap_or(Path::new("."));
+    let mut temp = tempfile::NamedTempFile::new_in(dir)?;
+    temp.write_all(content.as_bytes())?;
+    temp.as_file().sync_all()?;
+    if let Ok(metadata) = std::fs::metadata(path) {
+        temp.as_file().set_permissions(metadata.permissions())?;
+    }
+    temp.persist(path).map_err(|e| e.error)?;
+    Ok(())
+}
+
+impl Tool for ReplaceTool {
+    const NAME: &'static str = "replace_in_files";
+
+    type Args = ReplaceArgs;
+    type Output = String;
+    type Error = ReplaceError;
+
+    async fn definition(&self, _prompt: String) -> rig::completion::ToolDefinition {
+        rig::completion::ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: "Replace text across the project. First call with apply=false to get \
+                          numbered matches with each line before/after, then call again with \
+                          apply=true and optionally matches=[numbers] to write them once the \
+                          user approves. All files are written together and the change can be \
+                          undone with /replace undo. \
+                          Regex mode supports $1 and ${name} in the replacement."
+                .to_string(),
+            parameters: serde_json::to_value(schemars::schema_for!(ReplaceArgs))
+                .unwrap_or_default(),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let mut plan = self.plan(&args).await?;
+        if !args.apply || plan.edits.is_empty() {
+            let mut preview = plan.format();
+            if !plan.edits.is_empty() {
+                preview.push_str(
+                    "Nothing written. Call again with apply=true (and matches=[...] to pick some).\n",
+                );
+            }
+            self.set_pending(Some(plan));
+            return Ok(preview);
+        }
+
+        if let Some(ids) = &args.matches {
+            plan.select_only(ids)?;
+        }
+        let batch = self.apply(&plan).await?;
+        Ok(batch.format(&plan.root))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn args(root: &Path, pattern: &str, replacement: &str, mode: SearchMode) -> ReplaceArgs {
+        ReplaceArgs {
+            path: root.to_string_lossy().to_string(),
+            pattern: pattern.to_string(),
+            replacement: replacement.to_string(),
+            mode,
+            case_insensitive: false,
+            file_pattern: None,
+            apply: false,
+            matches: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_plan_expands_capture_groups() {
+        let dir = tempdir().unwrap();
+        std::fs::write(dir.path().join("a.rs"), "let x = get_user(1);\nlet y = get_item(2);\n").unwrap();
+        let tool = ReplaceTool::new();
+
+        let plan = tool
+            .plan(&args(dir.path(), r"get_(\w+)\((\d+)\)", "fetch_$1($2, None)", SearchMode::Regex))
+            .await
+            .unwrap();
+
+        assert_eq!(plan.edits.len(), 2);
+        assert_eq!(plan.selected().len(), 2);
+        assert_eq!((plan.edits[1].line, plan.edits[1].column), (2, 9));
+        assert_eq!(plan.edits[1].after, "let y = fetch_item(2, None);");
+        assert!(plan.format().contains("[x] #1 a.rs:1:9\n    - let x = get_user(1);\n    + let x = fetch_user(1, None);"));
+    }
+
+    #[tokio::test]
+    async fn test_apply_selected_then_undo() {
+        let dir = tempdir().unwrap();
+        let a = dir.path().join("a.txt");
+        let b = dir.path().join("b.txt");
+        std::fs::write(&a, "foo foo\n").unwrap();
+        std::fs::write(&b, "foo\n").unwrap();
+        let tool = ReplaceTool::new();
+
+        let mut plan = tool.plan(&args(dir.path(), "foo", "bar", SearchMode::Word)).await.unwrap();
+        plan.toggle(&[2]).unwrap();
+        let batch = tool.apply(&plan).await.unwrap();
+
+        assert_eq!(batch.replacements, 2);
+        assert_eq!(batch.format(dir.path()), "✅ Replaced 2 matches in 2 files: a.txt, b.txt");
+        assert_eq!(std::fs::read_to_string(&a).unwrap(), "bar foo\n");
+        assert_eq!(std::fs::read_to_string(&b).unwrap(), "bar\n");
+
+        tool.undo_last().await.unwrap();
+        assert_eq!(std::fs::read_to_string(&a).unwrap(), "foo foo\n");
+        assert_eq!(std::fs::read_to_string(&b).unwrap(), "foo\n");
+        assert!(matches!(tool.undo_last().await, Err(ReplaceError::NothingToUndo)));
+    }
+
+    #[tokio::test]
+    async fn test_apply_refuses_changed_files() {
+        let dir = tempdir().unwrap();
+        let a = dir.path().join("a.txt");
+        let b = dir.path().join("b.txt");
+        std::fs::write(&a, "old\n").unwrap();
+        std::fs::write(&b, "old\n").unwrap();
+        let tool = ReplaceTool::new();
+
+        let plan = tool.plan(&args(dir.path(), "old", "new", SearchMode::Literal)).await.unwrap();
+        std::fs::write(&b, "old, edited meanwhile\n").unwrap();
+
+        assert!(matches!(tool.apply(&plan).await, Err(ReplaceError::Conflict(path)) if path == b));
+        assert_eq!(std::fs::read_to_string(&a).unwrap(), "old\n", "nothing is written on conflict");
+    }
+
+    #[test]
+    fn test_selection_bounds() {
+        let mut plan = ReplacePlan {
+            root: PathBuf::new(),
+            pattern: "x".to_string(),
+            replacement: "y".to_string(),
+            edits: Vec::new(),
+            selected: BTreeSet::new(),
+            originals: BTreeMap::new(),
+        };
+        assert!(matches!(plan.toggle(&[1]), Err(ReplaceError::InvalidSelection(_))));
+        plan.select_all();
+        assert!(plan.selected().is_empty());
+    }
+}
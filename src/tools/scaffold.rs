@@ -0,0 +1,460 @@
+//! Project templates for `/new`
+//!
+//! Built-in skeletons (Rust binary and library, TypeScript node service,
+//! Python package) plus user templates: every directory under
+//! `~/.config/neuro/templates/` is a template whose files are copied with
+//! `{{name}}` and `{{snake_name}}` replaced in both paths and contents. An
+//! optional `template.json` in that directory gives the description and the
+//! command that verifies the new project builds; it is not copied.
+//!
+//! A user template named like a built-in one replaces it.
+
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use thiserror::Error;
+use tokio::process::Command;
+use super::command::output_in_group;
+use walkdir::WalkDir;
+
+/// File describing a user template, kept out of the generated project
+pub const TEMPLATE_MANIFEST: &str = "template.json";
+/// Time the verification build gets
+const BUILD_TIMEOUT_SECS: u64 = 300;
+/// Characters of build output kept in the report
+const MAX_BUILD_OUTPUT_CHARS: usize = 2000;
+
+#[derive(Error, Debug)]
+pub enum ScaffoldError {
+    #[error("Unknown template '{0}' (see /new list)")]
+    UnknownTemplate(String),
+    #[error("Invalid project name '{0}': use letters, digits, '-' and '_'")]
+    InvalidName(String),
+    #[error("{0} already exists")]
+    AlreadyExists(PathBuf),
+    #[error("Invalid template manifest {0}: {1}")]
+    Manifest(PathBuf, serde_json::Error),
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// Where the files of a template come from
+#[derive(Debug, Clone)]
+pub enum TemplateSource {
+    /// `(path, contents)` pairs compiled into the binary
+    BuiltIn(&'static [(&'static str, &'static str)]),
+    /// A directory of the user's templates
+    Directory(PathBuf),
+}
+
+/// A project skeleton
+#[derive(Debug, Clone)]
+pub struct ProjectTemplate {
+    pub name: String,
+    pub description: String,
+    /// Shell command run in the new project to check it builds
+    pub build: Option<String>,
+    pub source: TemplateSource,
+}
+
+impl ProjectTemplate {
+    fn built_in(
+        name: &str,
+        description: &str,
+        build: &str,
+        files: &'static [(&'static str, &'static str)],
+    ) -> Self {
+        Self {
+            name: name.to_string(),
+            description: description.to_string(),
+            build: Some(build.to_string()),
+            source: TemplateSource::BuiltIn(files),
+        }
+    }
+
+    pub fn is_built_in(&self) -> bool {
+        matches!(self.source, TemplateSource::BuiltIn(_))
+    }
+}
+
+#[derive(Deserialize, Default)]
+struct TemplateManifest {
+    #[serde(default)]
+    description: Option<String>,
+    #[serde(default)]
+    build: Option<String>,
+}
+
+/// Result of the verification build
+#[derive(Debug, Clone)]
+pub struct BuildCheck {
+    pub command: String,
+    pub success: bool,
+    /// Tail of stdout and stderr
+    pub output: String,
+}
+
+/// What `scaffold` created
+#[derive(Debug, Clone)]
+pub struct ScaffoldReport {
+    pub template: String,
+    pub root: PathBuf,
+    /// Created files, relative to `root`
+    pub files: Vec<PathBuf>,
+    pub build: Option<BuildCheck>,
+}
+
+impl ScaffoldReport {
+    pub fn render(&self) -> String {
+        let mut out = format!(
+            "📦 Proyecto creado en {} (plantilla {}, {} archivos)\n",
+            self.root.display(),
+            self.template,
+            self.files.len()
+        );
+        for file in &self.files {
+            out.push_str(&format!("  {}\n", file.display()));
+        }
+        match &self.build {
+            Some(check) if check.success => {
+                out.push_str(&format!("✅ `{}` compila correctamente\n", check.command));
+            }
+            Some(check) => {
+                out.push_str(&format!("❌ `{}` falló:\n{}\n", check.command, check.output));
+            }
+            None => out.push_str("ℹ️ La plantilla no define un comando de compilación\n"),
+        }
+        out
+    }
+}
+
+/// Built-in and user project templates
+#[derive(Debug, Clone)]
+pub struct ProjectTemplates {
+    user_dir: Option<PathBuf>,
+}
+
+impl Default for ProjectTemplates {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ProjectTemplates {
+    /// Templates with the user's ones from `~/.config/neuro/templates/`
+    pub fn new() -> Self {
+        Self {
+            user_dir: crate::config::AppConfig::config_dir().map(|dir| dir.join("templates")),
+        }
+    }
+
+    /// Read user templates from `dir` instead
+    pub fn with_user_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.user_dir = Some(dir.into());
+        self
+    }
+
+    /// All templates, sorted by name
+    pub fn list(&self) -> Result<Vec<ProjectTemplate>, ScaffoldError> {
+        let mut templates = built_in_templates();
+        for template in self.user_templates()? {
+            templates.retain(|t| t.name != template.name);
+            templates.push(template);
+        }
+        templates.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(templates)
+    }
+
+    pub fn get(&self, name: &str) -> Result<ProjectTemplate, ScaffoldError> {
+        self.list()?
+            .into_iter()
+            .find(|t| t.name == name)
+            .ok_or_else(|| ScaffoldError::UnknownTemplate(name.to_string()))
+    }
+
+    fn user_templates(&self) -> Result<Vec<ProjectTemplate>, ScaffoldError> {
+        let Some(dir) = self.user_dir.as_ref().filter(|dir| dir.is_dir()) else {
+            return Ok(Vec::new());
+        };
+        let mut templates = Vec::new();
+        for entry in std::fs::read_dir(dir)? {
+            let path = entry?.path();
+            if !path.is_dir() {
+                continue;
+            }
+            let manifest_path = path.join(TEMPLATE_MANIFEST);
+            let manifest = if manifest_path.is_file() {
+                serde_json::from_str(&std::fs::read_to_string(&manifest_path)?)
+                    .map_err(|e| ScaffoldError::Manifest(manifest_path, e))?
+            } else {
+                TemplateManifest::default()
+            };
+            templates.push(ProjectTemplate {
+                name: path.file_name().unwrap_or_default().to_string_lossy().to_string(),
+                description: manifest.description.unwrap_or_else(|| "Plantilla del usuario".to_string()),
+                build: manifest.build,
+                source: TemplateSource::Directory(path),
+            });
+        }
+        Ok(templates)
+    }
+
+    /// Create `parent/name` from the template, without overwriting anything
+    pub fn scaffold(
+        &self,
+        template: &ProjectTemplate,
+        name: &str,
+        parent: &Path,
+    ) -> Result<ScaffoldReport, ScaffoldError> {
+        validate_name(name)?;
+        let root = parent.join(name);
+        if root.exists() {
+            return Err(ScaffoldError::AlreadyExists(root));
+        }
+
+        let files: Vec<(PathBuf, Vec<u8>)> = match &template.source {
+            TemplateSource::BuiltIn(files) => files
+                .iter()
+                .map(|(path, contents)| (PathBuf::from(path), contents.as_bytes().to_vec()))
+                .collect(),
+            TemplateSource::Directory(dir) => read_template_dir(dir)?,
+        };
+
+        let mut created = Vec::new();
+        for (path, contents) in files {
+            let path = PathBuf::from(fill_placeholders(&path.to_string_lossy(), name));
+            // Binary files are copied as they are
+            let contents = match String::from_utf8(contents) {
+                Ok(text) => fill_placeholders(&text, name).into_bytes(),
+                Err(e) => e.into_bytes(),
+            };
+            let target = root.join(&path);
+            if let Some(dir) = target.parent() {
+                std::fs::create_dir_all(dir)?;
+            }
+            std::fs::write(&target, contents)?;
+            created.push(path);
+        }
+        created.sort();
+
+        Ok(ScaffoldReport {
+            template: template.name.clone(),
+            root,
+            files: created,
+            build: None,
+        })
+    }
+
+    /// Run the template's build command in the new project
+    pub async fn verify(&self, template: &ProjectTemplate, report: &mut ScaffoldReport) {
+        let Some(command) = &template.build else {
+            return;
+        };
+        let mut cmd = Command::new("sh");
+        cmd.arg("-c").arg(command).current_dir(&report.root);
+        let run = output_in_group(&mut cmd);
+        let (success, output) =
+            match tokio::time::timeout(Duration::from_secs(BUILD_TIMEOUT_SECS), run).await {
+                Ok(Ok(output)) => {
+                    let text = format!(
+                        "{}{}",
+                        String::from_utf8_lossy(&output.stdout),
+                        String::from_utf8_lossy(&output.stderr)
+                    );
+                    (output.status.success(), tail(&text, MAX_BUILD_OUTPUT_CHARS))
+                }
+                Ok(Err(e)) => (false, e.to_string()),
+                Err(_) => (false, format!("timed out after {}s", BUILD_TIMEOUT_SECS)),
+            };
+        report.build = Some(BuildCheck {
+            command: command.clone(),
+            success,
+            output,
+        });
+    }
+}
+
+fn validate_name(name: &str) -> Result<(), ScaffoldError> {
+    let valid = !name.is_empty()
+        && !name.starts_with(['-', '.'])
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if valid {
+        Ok(())
+    } else {
+        Err(ScaffoldError::InvalidName(name.to_string()))
+    }
+}
+
+/// Replace `{{name}}` and `{{snake_name}}`
+fn fill_placeholders(text: &str, name: &str) -> String {
+    text.replace("{{snake_name}}", &name.replace('-', "_"))
+        .replace("{{name}}", name)
+}
+
+fn read_template_dir(dir: &Path) -> Result<Vec<(PathBuf, Vec<u8>)>, ScaffoldError> {
+    let mut files = Vec::new();
+    for entry in WalkDir::new(dir).into_iter().filter_map(|e| e.ok()) {
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        let relative = entry.path().strip_prefix(dir).unwrap_or(entry.path()).to_path_buf();
+        if relative == Path::new(TEMPLATE_MANIFEST) {
+            continue;
+        }
+        files.push((relative, std::fs::read(entry.path())?));
+    }
+    Ok(files)
+}
+
+fn tail(text: &str, max_chars: usize) -> String {
+    let count = text.chars().count();
+    if count <= max_chars {
+        return text.trim_end().to_string();
+    }
+    let tail: String = text.chars().skip(count - max_chars).collect();
+    format!("...{}", tail.trim_end())
+}
+
+fn built_in_templates() -> Vec<ProjectTemplate> {
+    vec![
+        ProjectTemplate::built_in("rust-bin", "Rust binary (cargo)", "cargo build", RUST_BIN),
+        ProjectTemplate::built_in("rust-lib", "Rust library with a test (cargo)", "cargo build", RUST_LIB),
+        ProjectTemplate::built_in(
+            "ts-node",
+            "TypeScript node service (npm, tsc)",
+            "npm install && npm run build",
+            TS_NODE,
+        ),
+        ProjectTemplate::built_in(
+            "python-pkg",
+            "Python package with pyproject.toml and pytest layout",
+            "python3 -m compileall -q src tests",
+            PYTHON_PKG,
+        ),
+    ]
+}
+
+const RUST_GITIGNORE: &str = "/target\n";
+
+const RUST_BIN: &[(&str, &str)] = &[
+    (
+        "Cargo.toml",
+        "[package]\nname = \"{{name}}\"\nversion = \"0.1.0\"\nedition = \"2021\"\n\n[dependencies]\n",
+    ),
+    ("src/main.rs", "fn main() {\n    println!(\"Hello from {{name}}!\");\n}\n"),
+    (".gitignore", RUST_GITIGNORE),
+];
+
+const RUST_LIB: &[(&str, &str)] = &[
+    (
+        "Cargo.toml",
+        "[package]\nname = \"{{name}}\"\nversion = \"0.1.0\"\nedition = \"2021\"\n\n[dependencies]\n",
+    ),
+    (
+        "src/lib.rs",
+        "//! {{name}}\n\npub fn add(left: u64, right: u64) -> u64 {\n    left + right\n}\n\n\
+         #[cfg(test)]\nmod tests {\n    use super::*;\n\n    #[test]\n    fn it_works() {\n        \
+         assert_eq!(add(2, 2), 4);\n    }\n}\n",
+    ),
+    (".gitignore", RUST_GITIGNORE),
+];
+
+const TS_NODE: &[(&str, &str)] = &[
+    (
+        "package.json",
+        "{\n  \"name\": \"{{name}}\",\n  \"version\": \"0.1.0\",\n  \"private\": true,\n  \
+         \"main\": \"dist/index.js\",\n  \"scripts\": {\n    \"build\": \"tsc\",\n    \
+         \"start\": \"node dist/index.js\"\n  },\n  \"devDependencies\": {\n    \
+         \"@types/node\": \"^20.0.0\",\n    \"typescript\": \"^5.4.0\"\n  }\n}\n",
+    ),
+    (
+        "tsconfig.json",
+        "{\n  \"compilerOptions\": {\n    \"target\": \"ES2022\",\n    \"module\": \"commonjs\",\n    \
+         \"outDir\": \"dist\",\n    \"rootDir\": \"src\",\n    \"strict\": true,\n    \
+         \"esModuleInterop\": true\n  },\n  \"include\": [\"src\"]\n}\n",
+    ),
+    (
+        "src/index.ts",
+        "import { createServer } from \"node:http\";\n\nconst port = Number(process.env.PORT ?? 3000);\n\n\
+         createServer((_req, res) => {\n  res.writeHead(200, { \"Content-Type\": \"application/json\" });\n  \
+         res.end(JSON.stringify({ service: \"{{name}}\", status: \"ok\" }));\n}).listen(port, () => {\n  \
+         console.log(`{{name}} listening on :${port}`);\n});\n",
+    ),
+    (".gitignore", "node_modules/\ndist/\n"),
+];
+
+const PYTHON_PKG: &[(&str, &str)] = &[
+    (
+        "pyproject.toml",
+        "[build-system]\nrequires = [\"setuptools>=61\"]\nbuild-backend = \"setuptools.build_meta\"\n\n\
+         [project]\nname = \"{{name}}\"\nversion = \"0.1.0\"\nrequires-python = \">=3.9\"\n\n\
+         [tool.pytest.ini_options]\npythonpath = [\"src\"]\n",
+    ),
+    (
+        "src/{{snake_name}}/__init__.py",
+        "\"\"\"{{name}}.\"\"\"\n\n__version__ = \"0.1.0\"\n\n\ndef hello() -> str:\n    \
+         return \"Hello from {{name}}!\"\n",
+    ),
+    (
+        "tests/test_{{snake_name}}.py",
+        "from {{snake_name}} import hello\n\n\ndef test_hello():\n    assert \"{{name}}\" in hello()\n",
+    ),
+    (".gitignore", "__pycache__/\n*.egg-info/\n.venv/\n"),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_built_in_template_fills_placeholders() {
+        let dir = tempdir().unwrap();
+        let templates = ProjectTemplates::new().with_user_dir(dir.path().join("none"));
+        let template = templates.get("python-pkg").unwrap();
+
+        let report = templates.scaffold(&template, "my-pkg", dir.path()).unwrap();
+        assert_eq!(report.root, dir.path().join("my-pkg"));
+        assert!(report.files.contains(&PathBuf::from("src/my_pkg/__init__.py")));
+        let test = std::fs::read_to_string(report.root.join("tests/test_my_pkg.py")).unwrap();
+        assert!(test.starts_with("from my_pkg import hello"));
+        assert!(test.contains("\"my-pkg\" in hello()"));
+
+        // Never overwrites
+        assert!(matches!(
+            templates.scaffold(&template, "my-pkg", dir.path()),
+            Err(ScaffoldError::AlreadyExists(_))
+        ));
+        assert!(matches!(
+            templates.scaffold(&template, "../escape", dir.path()),
+            Err(ScaffoldError::InvalidName(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_user_template_replaces_built_in_and_verifies() {
+        let dir = tempdir().unwrap();
+        let user = dir.path().join("templates");
+        std::fs::create_dir_all(user.join("rust-bin/docs")).unwrap();
+        std::fs::write(user.join("rust-bin/docs/{{name}}.md"), "# {{name}}\n").unwrap();
+        std::fs::write(
+            user.join("rust-bin").join(TEMPLATE_MANIFEST),
+            r#"{ "description": "Team binary", "build": "test -f docs/app.md" }"#,
+        )
+        .unwrap();
+
+        let templates = ProjectTemplates::new().with_user_dir(&user);
+        let names: Vec<String> = templates.list().unwrap().into_iter().map(|t| t.name).collect();
+        assert_eq!(names, ["python-pkg", "rust-bin", "rust-lib", "ts-node"]);
+
+        let template = templates.get("rust-bin").unwrap();
+        assert!(!template.is_built_in());
+        assert_eq!(template.description, "Team binary");
+
+        let mut report = templates.scaffold(&template, "app", dir.path()).unwrap();
+        assert_eq!(report.files, [PathBuf::from("docs/app.md")]);
+        templates.verify(&template, &mut report).await;
+        assert!(report.build.as_ref().unwrap().success);
+        assert!(report.render().contains("compila correctamente"));
+    }
+}
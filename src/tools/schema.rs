@@ -0,0 +1,1033 @@
+//! Database schema ingestion
+//!
+//! Builds an entity-relationship summary of the project database, either by
+//! introspecting a configured connection (through the read-only
+//! `query_database` session) or by reading `schema.sql`, SQL migrations, a
+//! Prisma schema or a Diesel `schema.rs`. The summary is stored in the RAPTOR
+//! docs namespace so queries about SQL or ORM code get the real table and
+//! column names as context.
+
+use super::database::{DatabaseQueryArgs, DatabaseQueryError, DatabaseQueryTool, Dialect};
+use crate::raptor::persistence::GLOBAL_STORE;
+use regex::Regex;
+use rig::tool::Tool;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+use thiserror::Error;
+
+/// Docs namespace the schema summary is stored under
+pub const SCHEMA_DOCS_NAME: &str = "schema";
+/// Characters of schema added to a prompt
+const MAX_SCHEMA_CONTEXT_CHARS: usize = 3000;
+/// Words that suggest a query is about the database
+const DATABASE_KEYWORDS: &[&str] = &[
+    "sql",
+    "query",
+    "consulta",
+    "select",
+    "insert",
+    "update",
+    "delete",
+    "join",
+    "table",
+    "tabla",
+    "column",
+    "columna",
+    "schema",
+    "esquema",
+    "migration",
+    "migración",
+    "orm",
+    "diesel",
+    "sqlx",
+    "prisma",
+    "sequelize",
+    "typeorm",
+    "sqlalchemy",
+    "database",
+    "base de datos",
+];
+/// Prisma scalar types; any other field type is an enum or a relation
+const PRISMA_SCALARS: &[&str] = &[
+    "String", "Boolean", "Int", "BigInt", "Float", "Decimal", "DateTime", "Json", "Bytes",
+];
+/// Keywords that end the type part of a SQL column definition
+const COLUMN_CONSTRAINT_KEYWORDS: &[&str] = &[
+    "not",
+    "null",
+    "primary",
+    "references",
+    "default",
+    "unique",
+    "check",
+    "constraint",
+    "generated",
+    "collate",
+    "auto_increment",
+    "autoincrement",
+    "identity",
+    "on",
+];
+
+static DIESEL_TABLE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^\s*(?:\w+\.)?(\w+)\s*\(([^)]*)\)\s*\{").unwrap());
+static DIESEL_COLUMN: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^\s*(\w+)\s*->\s*([^,]+),?\s*$").unwrap());
+static DIESEL_JOINABLE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"joinable!\s*\(\s*(\w+)\s*->\s*(\w+)\s*\(\s*(\w+)\s*\)\s*\)").unwrap()
+});
+static PRISMA_RELATION: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"@relation\([^)]*fields:\s*\[([^\]]+)\][^)]*references:\s*\[([^\]]+)\]").unwrap()
+});
+static PRISMA_MAP: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"@@map\(\s*(?:name:\s*)?"([^"]+)"\s*\)"#).unwrap());
+
+#[derive(Error, Debug)]
+pub enum SchemaError {
+    #[error("No schema source found (configure a database or add schema.sql, migrations, schema.prisma or src/schema.rs)")]
+    NotFound,
+    #[error("Unsupported schema file: {0}")]
+    UnsupportedFile(String),
+    #[error("Database error: {0}")]
+    Database(#[from] DatabaseQueryError),
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ColumnSchema {
+    pub name: String,
+    pub data_type: String,
+    pub nullable: bool,
+    pub primary_key: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ForeignKey {
+    pub column: String,
+    pub ref_table: String,
+    pub ref_column: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TableSchema {
+    pub name: String,
+    pub columns: Vec<ColumnSchema>,
+    pub foreign_keys: Vec<ForeignKey>,
+}
+
+impl TableSchema {
+    fn column_mut(&mut self, name: &str) -> Option<&mut ColumnSchema> {
+        self.columns
+            .iter_mut()
+            .find(|c| c.name.eq_ignore_ascii_case(name))
+    }
+
+    fn add_column(&mut self, column: ColumnSchema) {
+        match self.column_mut(&column.name) {
+            Some(existing) => *existing = column,
+            None => self.columns.push(column),
+        }
+    }
+}
+
+/// Tables, columns and relationships of a database
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DatabaseSchema {
+    /// Where the schema came from (connection name or files)
+    pub source: String,
+    pub tables: Vec<TableSchema>,
+}
+
+impl DatabaseSchema {
+    fn table_mut(&mut self, name: &str) -> &mut TableSchema {
+        let index = match self
+            .tables
+            .iter()
+            .position(|t| t.name.eq_ignore_ascii_case(name))
+        {
+            Some(index) => index,
+            None => {
+                self.tables.push(TableSchema {
+                    name: name.to_string(),
+                    ..Default::default()
+                });
+                self.tables.len() - 1
+            }
+        };
+        &mut self.tables[index]
+    }
+
+    pub fn relationship_count(&self) -> usize {
+        self.tables.iter().map(|t| t.foreign_keys.len()).sum()
+    }
+
+    /// Apply the DDL statements of a SQL file (CREATE/ALTER/DROP TABLE), in order
+    pub fn apply_sql(&mut self, sql: &str) {
+        for statement in split_statements(&strip_sql_comments(sql)) {
+            let words: Vec<&str> = statement.split_whitespace().collect();
+            let lower: Vec<String> = words.iter().map(|w| w.to_lowercase()).collect();
+            let lower: Vec<&str> = lower.iter().map(String::as_str).collect();
+
+            match lower.as_slice() {
+                ["create", rest @ ..] if rest.contains(&"table") => {
+                    let Some(table_pos) = lower.iter().position(|w| *w == "table") else {
+                        continue;
+                    };
+                    let mut name_pos = table_pos + 1;
+                    if lower.get(name_pos..name_pos + 3) == Some(&["if", "not", "exists"]) {
+                        name_pos += 3;
+                    }
+                    let Some(raw_name) = words.get(name_pos) else {
+                        continue;
+                    };
+                    let name = unquote(raw_name.split('(').next().unwrap_or(raw_name));
+                    let (Some(open), Some(close)) = (statement.find('('), statement.rfind(')'))
+                    else {
+                        continue;
+                    };
+                    if close <= open {
+                        continue;
+                    }
+                    let table = self.table_mut(&name);
+                    for item in split_top_level(&statement[open + 1..close], ',') {
+                        apply_table_item(table, item);
+                    }
+                }
+                ["alter", "table", ..] => {
+                    let mut pos = 2;
+                    if lower.get(pos..pos + 2) == Some(&["if", "exists"]) {
+                        pos += 2;
+                    }
+                    if lower.get(pos) == Some(&"only") {
+                        pos += 1;
+                    }
+                    let Some(raw_name) = words.get(pos) else {
+                        continue;
+                    };
+                    let name = unquote(raw_name);
+                    let rest_words = &words[pos + 1..];
+                    let rest_lower = &lower[pos + 1..];
+                    match rest_lower {
+                        ["add", "constraint", ..]
+                        | ["add", "foreign", ..]
+                        | ["add", "primary", ..] => {
+                            let item = rest_words[1..].join(" ");
+                            apply_table_item(self.table_mut(&name), &item);
+                        }
+                        ["add", "column", ..] | ["add", ..] => {
+                            let skip = if rest_lower.get(1) == Some(&"column") {
+                                2
+                            } else {
+                                1
+                            };
+                            let mut item = &rest_words[skip..];
+                            if rest_lower.get(skip..skip + 3) == Some(&["if", "not", "exists"]) {
+                                item = &item[3..];
+                            }
+                            apply_table_item(self.table_mut(&name), &item.join(" "));
+                        }
+                        ["drop", "column", column, ..] | ["drop", column, ..] => {
+                            let column = unquote(column);
+                            let table = self.table_mut(&name);
+                            table
+                                .columns
+                                .retain(|c| !c.name.eq_ignore_ascii_case(&column));
+                            table
+                                .foreign_keys
+                                .retain(|f| !f.column.eq_ignore_ascii_case(&column));
+                        }
+                        ["rename", "to", new_name, ..] => {
+                            let new_name = unquote(new_name);
+                            self.table_mut(&name).name = new_name;
+                        }
+                        _ => {}
+                    }
+                }
+                ["drop", "table", rest @ ..] => {
+                    let name = rest
+                        .iter()
+                        .zip(&words[2..])
+                        .find(|(lower, _)| !matches!(**lower, "if" | "exists"))
+                        .map(|(_, word)| unquote(word.trim_end_matches(',')));
+                    if let Some(name) = name {
+                        self.tables.retain(|t| !t.name.eq_ignore_ascii_case(&name));
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Parse the models of a Prisma schema
+    pub fn from_prisma(text: &str) -> Self {
+        let enums: Vec<&str> = text
+            .lines()
+            .filter_map(|l| l.trim().strip_prefix("enum "))
+            .filter_map(|l| l.split_whitespace().next())
+            .collect();
+
+        // First pass: model blocks, keyed by model name
+        let mut models: Vec<(String, Vec<&str>)> = Vec::new();
+        let mut current: Option<(String, Vec<&str>)> = None;
+        for line in text.lines().map(str::trim) {
+            if let Some(rest) = line.strip_prefix("model ") {
+                let name = rest.split_whitespace().next().unwrap_or("").to_string();
+                current = Some((name, Vec::new()));
+            } else if line.starts_with('}') {
+                if let Some(model) = current.take() {
+                    models.push(model);
+                }
+            } else if let Some((_, lines)) = current.as_mut() {
+                if !line.is_empty() && !line.starts_with("//") {
+                    lines.push(line);
+                }
+            }
+        }
+
+        let table_name = |model: &str| -> String {
+            models
+                .iter()
+                .find(|(name, _)| name == model)
+                .and_then(|(_, lines)| {
+                    lines
+                        .iter()
+                        .find_map(|l| PRISMA_MAP.captures(l).map(|c| c[1].to_string()))
+                })
+                .unwrap_or_else(|| model.to_string())
+        };
+
+        let mut schema = DatabaseSchema::default();
+        for (model, lines) in &models {
+            let mut table = TableSchema {
+                name: table_name(model),
+                ..Default::default()
+            };
+            for line in lines {
+                if let Some(ids) = line.strip_prefix("@@id(") {
+                    for id in ids.trim_matches(|c| "[]) ".contains(c)).split(',') {
+                        if let Some(column) = table.column_mut(id.trim()) {
+                            column.primary_key = true;
+                        }
+                    }
+                    continue;
+                }
+                if line.starts_with("@@") {
+                    continue;
+                }
+                let mut parts = line.split_whitespace();
+                let (Some(field), Some(field_type)) = (parts.next(), parts.next()) else {
+                    continue;
+                };
+                let base = field_type.trim_end_matches(['?', '[', ']']);
+                let is_list = field_type.ends_with("[]");
+                if PRISMA_SCALARS.contains(&base) || enums.contains(&base) {
+                    table.add_column(ColumnSchema {
+                        name: field.to_string(),
+                        data_type: base.to_string(),
+                        nullable: field_type.ends_with('?'),
+                        primary_key: line.contains("@id"),
+                    });
+                } else if !is_list {
+                    if let Some(caps) = PRISMA_RELATION.captures(line) {
+                        for (column, ref_column) in caps[1].split(',').zip(caps[2].split(',')) {
+                            table.foreign_keys.push(ForeignKey {
+                                column: column.trim().to_string(),
+                                ref_table: table_name(base),
+                                ref_column: ref_column.trim().to_string(),
+                            });
+                        }
+                    }
+                }
+            }
+            schema.tables.push(table);
+        }
+        schema
+    }
+
+    /// Parse the `table!` and `joinable!` macros of a Diesel `schema.rs`
+    pub fn from_diesel(text: &str) -> Self {
+        let mut schema = DatabaseSchema::default();
+        let mut current: Option<(TableSchema, Vec<String>)> = None;
+
+        for line in text.lines() {
+            if let Some(caps) = DIESEL_TABLE.captures(line) {
+                let keys = caps[2].split(',').map(|k| k.trim().to_string()).collect();
+                let table = TableSchema {
+                    name: caps[1].to_string(),
+                    ..Default::default()
+                };
+                current = Some((table, keys));
+            } else if let Some((table, keys)) = current.as_mut() {
+                if let Some(caps) = DIESEL_COLUMN.captures(line) {
+                    let raw_type = caps[2].trim();
+                    let (data_type, nullable) = match raw_type
+                        .strip_prefix("Nullable<")
+                        .and_then(|t| t.strip_suffix('>'))
+                    {
+                        Some(inner) => (inner.to_string(), true),
+                        None => (raw_type.to_string(), false),
+                    };
+                    table.columns.push(ColumnSchema {
+                        name: caps[1].to_string(),
+                        data_type,
+                        nullable,
+                        primary_key: keys.contains(&caps[1].to_string()),
+                    });
+                } else if line.trim_start().starts_with('}') {
+                    if let Some((table, _)) = current.take() {
+                        schema.tables.push(table);
+                    }
+                }
+            }
+        }
+
+        for caps in DIESEL_JOINABLE.captures_iter(text) {
+            let ref_table = caps[2].to_string();
+            let ref_column = schema
+                .tables
+                .iter()
+                .find(|t| t.name == ref_table)
+                .and_then(|t| t.columns.iter().find(|c| c.primary_key))
+                .map(|c| c.name.clone())
+                .unwrap_or_else(|| "id".to_string());
+            let child = schema.table_mut(&caps[1]);
+            child.foreign_keys.push(ForeignKey {
+                column: caps[3].to_string(),
+                ref_table,
+                ref_column,
+            });
+        }
+        schema
+    }
+
+    /// Entity-relationship summary: one block per table with its columns and
+    /// outgoing foreign keys
+    pub fn to_markdown(&self) -> String {
+        let mut out = format!(
+            "## Database schema ({})\n{} tables, {} relationships\n",
+            self.source,
+            self.tables.len(),
+            self.relationship_count()
+        );
+        for table in &self.tables {
+            out.push_str(&format!("\n### {}\n", table.name));
+            let columns: Vec<String> = table
+                .columns
+                .iter()
+                .map(|c| {
+                    let mut column = format!("{} {}", c.name, c.data_type);
+                    if c.primary_key {
+                        column.push_str(" PK");
+                    } else if c.nullable {
+                        column.push_str(" NULL");
+                    }
+                    column
+                })
+                .collect();
+            out.push_str(&columns.join(", "));
+            out.push('\n');
+            for fk in &table.foreign_keys {
+                out.push_str(&format!(
+                    "- {}.{} → {}.{}\n",
+                    table.name, fk.column, fk.ref_table, fk.ref_column
+                ));
+            }
+        }
+        out
+    }
+
+    /// Store the summary in the RAPTOR docs namespace; returns the chunks stored
+    pub fn store(&self) -> usize {
+        GLOBAL_STORE
+            .lock()
+            .map(|mut store| store.insert_docs(SCHEMA_DOCS_NAME, &self.to_markdown()))
+            .unwrap_or(0)
+    }
+}
+
+/// Ingested schema to add to a prompt, if the query is about the database
+/// (mentions SQL/ORM terms or a known table)
+pub fn schema_context_for(query: &str) -> Option<String> {
+    let schema = GLOBAL_STORE.lock().ok().map(|store| {
+        store
+            .docs_chunks(SCHEMA_DOCS_NAME)
+            .into_iter()
+            .cloned()
+            .collect::<Vec<_>>()
+    })?;
+    if schema.is_empty() {
+        return None;
+    }
+    // Chunks overlap; the first one holds the header and usually the main tables
+    let text = schema.join("\n");
+
+    let query = query.to_lowercase();
+    let words: Vec<&str> = query
+        .split(|c: char| !c.is_alphanumeric() && c != '_')
+        .collect();
+    let mentions_keyword = DATABASE_KEYWORDS.iter().any(|k| {
+        if k.contains(' ') {
+            query.contains(k)
+        } else {
+            words.contains(k)
+        }
+    });
+    let mentions_table = text
+        .lines()
+        .filter_map(|l| l.strip_prefix("### "))
+        .any(|table| words.contains(&table.to_lowercase().as_str()));
+    if !mentions_keyword && !mentions_table {
+        return None;
+    }
+
+    Some(text.chars().take(MAX_SCHEMA_CONTEXT_CHARS).collect())
+}
+
+/// Add one column definition or table constraint of a CREATE TABLE body
+fn apply_table_item(table: &mut TableSchema, item: &str) {
+    let item = item.trim();
+    let words: Vec<&str> = item.split_whitespace().collect();
+    let Some(first) = words.first() else {
+        return;
+    };
+    let lower = item.to_lowercase();
+
+    match first.to_lowercase().as_str() {
+        "constraint" | "primary" | "foreign" | "unique" | "check" | "key" | "index" | "exclude" => {
+            if let Some(pk) = lower.find("primary key") {
+                for column in paren_list(&item[pk..]) {
+                    if let Some(column) = table.column_mut(&column) {
+                        column.primary_key = true;
+                        column.nullable = false;
+                    }
+                }
+            } else if let Some(fk) = lower.find("foreign key") {
+                let columns = paren_list(&item[fk..]);
+                if let Some(reference) = lower.find("references") {
+                    let (ref_table, ref_columns) = parse_reference(&item[reference + 10..]);
+                    for (i, column) in columns.into_iter().enumerate() {
+                        table.foreign_keys.push(ForeignKey {
+                            column,
+                            ref_table: ref_table.clone(),
+                            ref_column: ref_columns
+                                .get(i)
+                                .cloned()
+                                .unwrap_or_else(|| "id".to_string()),
+                        });
+                    }
+                }
+            }
+        }
+        _ => {
+            let name = unquote(first);
+            let data_type = words[1..]
+                .iter()
+                .take_while(|w| !COLUMN_CONSTRAINT_KEYWORDS.contains(&w.to_lowercase().as_str()))
+                .copied()
+                .collect::<Vec<_>>()
+                .join(" ");
+            let primary_key = lower.contains("primary key");
+            table.add_column(ColumnSchema {
+                name: name.clone(),
+                data_type: if data_type.is_empty() {
+                    "ANY".to_string()
+                } else {
+                    data_type.to_uppercase()
+                },
+                nullable: !primary_key && !lower.contains("not null"),
+                primary_key,
+            });
+            if let Some(reference) = lower.find("references") {
+                let (ref_table, ref_columns) = parse_reference(&item[reference + 10..]);
+                table.foreign_keys.retain(|f| f.column != name);
+                table.foreign_keys.push(ForeignKey {
+                    column: name,
+                    ref_table,
+                    ref_column: ref_columns
+                        .into_iter()
+                        .next()
+                        .unwrap_or_else(|| "id".to_string()),
+                });
+            }
+        }
+    }
+}
+
+/// `users (id)` / `users(id)` / `"users"` → table name and referenced columns
+fn parse_reference(text: &str) -> (String, Vec<String>) {
+    let text = text.trim();
+    let end = text
+        .find(|c: char| c == '(' || c.is_whitespace())
+        .unwrap_or(text.len());
+    let table = unquote(&text[..end]);
+    let rest = text[end..].trim_start();
+    let columns = if rest.starts_with('(') {
+        paren_list(rest)
+    } else {
+        Vec::new()
+    };
+    (table, columns)
+}
+
+/// Identifiers inside the first parenthesized list of `text`
+fn paren_list(text: &str) -> Vec<String> {
+    let (Some(open), Some(close)) = (text.find('('), text.find(')')) else {
+        return Vec::new();
+    };
+    if close <= open {
+        return Vec::new();
+    }
+    text[open + 1..close]
+        .split(',')
+        .map(unquote)
+        .filter(|c| !c.is_empty())
+        .collect()
+}
+
+/// Strip identifier quoting and any schema qualifier (`public."users"` → `users`)
+fn unquote(ident: &str) -> String {
+    let ident = ident.trim().rsplit('.').next().unwrap_or(ident);
+    ident
+        .trim_matches(|c| matches!(c, '"' | '`' | '[' | ']' | '\''))
+        .to_string()
+}
+
+fn strip_sql_comments(sql: &str) -> String {
+    let mut out = String::with_capacity(sql.len());
+    let mut chars = sql.chars().peekable();
+    let mut quote: Option<char> = None;
+    while let Some(c) = chars.next() {
+        match (quote, c) {
+            (Some(q), _) => {
+                out.push(c);
+                if c == q {
+                    quote = None;
+                }
+            }
+            (None, '\'' | '"' | '`') => {
+                quote = Some(c);
+                out.push(c);
+            }
+            (None, '-') if chars.peek() == Some(&'-') => {
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        out.push('\n');
+                        break;
+                    }
+                }
+            }
+            (None, '/') if chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut prev = ' ';
+                for c in chars.by_ref() {
+                    if prev == '*' && c == '/' {
+                        break;
+                    }
+                    prev = c;
+                }
+                out.push(' ');
+            }
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+fn split_statements(sql: &str) -> Vec<&str> {
+    split_top_level(sql, ';')
+        .into_iter()
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .collect()
+}
+
+/// Split on `separator` outside parentheses and quotes
+fn split_top_level(text: &str, separator: char) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut depth = 0i32;
+    let mut quote: Option<char> = None;
+    let mut start = 0;
+    for (i, c) in text.char_indices() {
+        match (quote, c) {
+            (Some(q), _) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '\'' | '"' | '`') => quote = Some(c),
+            (None, '(') => depth += 1,
+            (None, ')') => depth -= 1,
+            (None, c) if c == separator && depth == 0 => {
+                parts.push(&text[start..i]);
+                start = i + c.len_utf8();
+            }
+            _ => {}
+        }
+    }
+    parts.push(&text[start..]);
+    parts
+}
+
+/// Arguments for the schema ingestion tool
+#[derive(Debug, Clone, Default, Deserialize, Serialize, JsonSchema)]
+pub struct SchemaIngestArgs {
+    /// Database connection to introspect (name from the `databases` config)
+    #[serde(default)]
+    pub connection: Option<String>,
+    /// Schema file or migrations directory (schema.sql, *.prisma, schema.rs); auto-detected when omitted
+    #[serde(default)]
+    pub path: Option<String>,
+    /// Project directory, filled in by the orchestrator
+    #[serde(skip)]
+    #[schemars(skip)]
+    pub project_dir: String,
+}
+
+/// Schema ingestion tool
+#[derive(Debug, Clone, Default)]
+pub struct SchemaIngestTool {
+    database: DatabaseQueryTool,
+}
+
+impl SchemaIngestTool {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Use the configured database connections for introspection
+    pub fn with_database(mut self, database: DatabaseQueryTool) -> Self {
+        self.database = database;
+        self
+    }
+
+    /// Build the schema from the requested or detected source
+    pub async fn ingest(&self, args: &SchemaIngestArgs) -> Result<DatabaseSchema, SchemaError> {
+        if let Some(connection) = &args.connection {
+            return self.introspect(connection).await;
+        }
+
+        let root = PathBuf::from(if args.project_dir.is_empty() {
+            "."
+        } else {
+            &args.project_dir
+        });
+        let path = match &args.path {
+            Some(path) => root.join(path),
+            None => detect_schema_source(&root).ok_or(SchemaError::NotFound)?,
+        };
+        let mut schema = read_schema_path(&path).await?;
+        schema.source = path
+            .strip_prefix(&root)
+            .unwrap_or(&path)
+            .display()
+            .to_string();
+        Ok(schema)
+    }
+
+    /// Read tables, columns and keys from a live database (read-only session)
+    pub async fn introspect(&self, connection: &str) -> Result<DatabaseSchema, SchemaError> {
+        let (columns_sql, keys_sql) = match self.database.dialect(connection)? {
+            Dialect::Sqlite => (
+                "SELECT m.name, p.name, p.type, \
+                 CASE WHEN p.\"notnull\" = 1 THEN 'NO' ELSE 'YES' END, \
+                 CASE WHEN p.pk > 0 THEN 'PRI' ELSE '' END \
+                 FROM sqlite_master m JOIN pragma_table_info(m.name) p \
+                 WHERE m.type = 'table' AND m.name NOT LIKE 'sqlite_%' \
+                 ORDER BY m.name, p.cid",
+                "SELECT 'FOREIGN KEY', m.name, f.\"from\", f.\"table\", f.\"to\" \
+                 FROM sqlite_master m JOIN pragma_foreign_key_list(m.name) f \
+                 WHERE m.type = 'table'",
+            ),
+            Dialect::Postgres => (
+                "SELECT table_name::text, column_name::text, data_type::text, \
+                 is_nullable::text, '' FROM information_schema.columns \
+                 WHERE table_schema = current_schema() \
+                 ORDER BY table_name, ordinal_position",
+                "SELECT tc.constraint_type::text, kcu.table_name::text, kcu.column_name::text, \
+                 ccu.table_name::text, ccu.column_name::text \
+                 FROM information_schema.table_constraints tc \
+                 JOIN information_schema.key_column_usage kcu \
+                 ON tc.constraint_name = kcu.constraint_name AND tc.table_schema = kcu.table_schema \
+                 JOIN information_schema.constraint_column_usage ccu \
+                 ON tc.constraint_name = ccu.constraint_name AND tc.table_schema = ccu.table_schema \
+                 WHERE tc.table_schema = current_schema() \
+                 AND tc.constraint_type IN ('PRIMARY KEY', 'FOREIGN KEY')",
+            ),
+            Dialect::MySql => (
+                "SELECT CAST(table_name AS CHAR), CAST(column_name AS CHAR), \
+                 CAST(column_type AS CHAR), CAST(is_nullable AS CHAR), CAST(column_key AS CHAR) \
+                 FROM information_schema.columns WHERE table_schema = DATABASE() \
+                 ORDER BY table_name, ordinal_position",
+                "SELECT 'FOREIGN KEY', CAST(table_name AS CHAR), CAST(column_name AS CHAR), \
+                 CAST(referenced_table_name AS CHAR), CAST(referenced_column_name AS CHAR) \
+                 FROM information_schema.key_column_usage \
+                 WHERE table_schema = DATABASE() AND referenced_table_name IS NOT NULL",
+            ),
+        };
+
+        let mut schema = DatabaseSchema {
+            source: connection.to_string(),
+            ..Default::default()
+        };
+        for row in self.query(connection, columns_sql).await? {
+            if let [table, column, data_type, nullable, key] = row.as_slice() {
+                schema.table_mut(table).add_column(ColumnSchema {
+                    name: column.clone(),
+                    data_type: data_type.to_uppercase(),
+                    nullable: nullable == "YES",
+                    primary_key: key == "PRI",
+                });
+            }
+        }
+        for row in self.query(connection, keys_sql).await? {
+            if let [kind, table, column, ref_table, ref_column] = row.as_slice() {
+                let table = schema.table_mut(table);
+                if kind == "PRIMARY KEY" {
+                    if let Some(column) = table.column_mut(column) {
+                        column.primary_key = true;
+                    }
+                } else {
+                    table.foreign_keys.push(ForeignKey {
+                        column: column.clone(),
+                        ref_table: ref_table.clone(),
+                        ref_column: ref_column.clone(),
+                    });
+                }
+            }
+        }
+        Ok(schema)
+    }
+
+    async fn query(&self, connection: &str, sql: &str) -> Result<Vec<Vec<String>>, SchemaError> {
+        let output = self
+            .database
+            .call(DatabaseQueryArgs {
+                connection: connection.to_string(),
+                query: sql.to_string(),
+                max_rows: usize::MAX,
+                confirm: false,
+                build_mode: false,
+            })
+            .await?;
+        Ok(output.rows)
+    }
+}
+
+/// First schema source found in the project, most authoritative first
+fn detect_schema_source(root: &Path) -> Option<PathBuf> {
+    const CANDIDATES: &[&str] = &[
+        "prisma/schema.prisma",
+        "schema.prisma",
+        "src/schema.rs",
+        "schema.sql",
+        "db/schema.sql",
+        "database/schema.sql",
+        "sql/schema.sql",
+        "migrations",
+        "db/migrations",
+        "database/migrations",
+    ];
+    CANDIDATES
+        .iter()
+        .map(|candidate| root.join(candidate))
+        .find(|path| path.exists())
+}
+
+async fn read_schema_path(path: &Path) -> Result<DatabaseSchema, SchemaError> {
+    if path.is_dir() {
+        let mut schema = DatabaseSchema::default();
+        for file in migration_files(path) {
+            schema.apply_sql(&tokio::fs::read_to_string(&file).await?);
+        }
+        return Ok(schema);
+    }
+
+    let content = tokio::fs::read_to_string(path).await?;
+    match path.extension().and_then(|e| e.to_str()) {
+        Some("prisma") => Ok(DatabaseSchema::from_prisma(&content)),
+        Some("rs") => Ok(DatabaseSchema::from_diesel(&content)),
+        Some("sql") => {
+            let mut schema = DatabaseSchema::default();
+            schema.apply_sql(&content);
+            Ok(schema)
+        }
+        _ => Err(SchemaError::UnsupportedFile(path.display().to_string())),
+    }
+}
+
+/// Up migrations under `dir`, in the order they are applied (sorted by path)
+fn migration_files(dir: &Path) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = walkdir::WalkDir::new(dir)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .map(|e| e.into_path())
+        .filter(|p| p.extension().is_some_and(|ext| ext == "sql"))
+        .filter(|p| {
+            let name = p.file_name().and_then(|n| n.to_str()).unwrap_or("");
+            !name.starts_with("down") && !name.contains(".down.")
+        })
+        .collect();
+    files.sort();
+    files
+}
+
+impl Tool for SchemaIngestTool {
+    const NAME: &'static str = "ingest_schema";
+
+    type Args = SchemaIngestArgs;
+    type Output = String;
+    type Error = SchemaError;
+
+    async fn definition(&self, _prompt: String) -> rig::completion::ToolDefinition {
+        rig::completion::ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: "Build an entity-relationship summary of the project database from a \
+                          configured connection, schema.sql, SQL migrations, a Prisma schema or \
+                          a Diesel schema.rs, and remember it for later SQL/ORM questions."
+                .to_string(),
+            parameters: serde_json::to_value(schemars::schema_for!(SchemaIngestArgs))
+                .unwrap_or_default(),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let schema = self.ingest(&args).await?;
+        schema.store();
+        Ok(schema.to_markdown())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_sql_migrations() {
+        let mut schema = DatabaseSchema::default();
+        schema.apply_sql(
+            "-- users\n\
+             CREATE TABLE IF NOT EXISTS \"users\" (\n\
+               id INTEGER PRIMARY KEY,\n\
+               email VARCHAR(255) NOT NULL UNIQUE,\n\
+               bio TEXT\n\
+             );\n\
+             CREATE TABLE orders (\n\
+               id BIGINT NOT NULL,\n\
+               user_id INTEGER REFERENCES users(id),\n\
+               total NUMERIC(10, 2) NOT NULL DEFAULT 0,\n\
+               PRIMARY KEY (id)\n\
+             );\n\
+             /* old table */ CREATE TABLE legacy (x INT);",
+        );
+        schema.apply_sql(
+            "ALTER TABLE orders ADD COLUMN coupon_id INT;\n\
+             ALTER TABLE orders ADD CONSTRAINT fk_coupon FOREIGN KEY (coupon_id) REFERENCES coupons (code);\n\
+             ALTER TABLE users DROP COLUMN bio;\n\
+             DROP TABLE IF EXISTS legacy;",
+        );
+
+        assert_eq!(schema.tables.len(), 2);
+        let users = &schema.tables[0];
+        assert_eq!(
+            users
+                .columns
+                .iter()
+                .map(|c| c.name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["id", "email"]
+        );
+        assert_eq!(users.columns[1].data_type, "VARCHAR(255)");
+        assert!(!users.columns[1].nullable);
+
+        let orders = &schema.tables[1];
+        assert!(orders.columns[0].primary_key);
+        assert_eq!(orders.columns[2].data_type, "NUMERIC(10, 2)");
+        assert_eq!(orders.foreign_keys.len(), 2);
+        assert_eq!(orders.foreign_keys[1].ref_table, "coupons");
+        assert_eq!(orders.foreign_keys[1].ref_column, "code");
+
+        let markdown = schema.to_markdown();
+        assert!(markdown.contains("### orders\nid BIGINT PK, user_id INTEGER NULL"));
+        assert!(markdown.contains("- orders.user_id → users.id"));
+    }
+
+    #[test]
+    fn test_prisma_schema() {
+        let schema = DatabaseSchema::from_prisma(
+            r#"
+enum Role { USER ADMIN }
+
+model User {
+  id    Int     @id @default(autoincrement())
+  email String  @unique
+  role  Role
+  posts Post[]
+  @@map("users")
+}
+
+model Post {
+  id       Int     @id
+  title    String?
+  author   User    @relation(fields: [authorId], references: [id])
+  authorId Int
+}
+"#,
+        );
+        assert_eq!(schema.tables[0].name, "users");
+        assert_eq!(schema.tables[0].columns.len(), 3);
+        assert_eq!(schema.tables[0].columns[2].data_type, "Role");
+        let post = &schema.tables[1];
+        assert!(post.columns[1].nullable);
+        assert_eq!(
+            post.foreign_keys,
+            vec![ForeignKey {
+                column: "authorId".to_string(),
+                ref_table: "users".to_string(),
+                ref_column: "id".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_diesel_schema() {
+        let schema = DatabaseSchema::from_diesel(
+            "diesel::table! {\n    users (id) {\n        id -> Int4,\n        name -> Nullable<Text>,\n    }\n}\n\n\
+             diesel::table! {\n    posts (id) {\n        id -> Int4,\n        user_id -> Int4,\n    }\n}\n\n\
+             diesel::joinable!(posts -> users (user_id));\n",
+        );
+        assert_eq!(schema.tables.len(), 2);
+        assert!(schema.tables[0].columns[0].primary_key);
+        assert!(schema.tables[0].columns[1].nullable);
+        assert_eq!(schema.tables[1].foreign_keys[0].ref_table, "users");
+    }
+
+    #[tokio::test]
+    async fn test_introspect_sqlite() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("app.db");
+        let url = format!("sqlite:{}?mode=rwc", path.display());
+        {
+            use sqlx::{Connection, Executor};
+            let mut conn = sqlx::SqliteConnection::connect(&url).await.unwrap();
+            conn.execute(
+                "CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT NOT NULL);\
+                 CREATE TABLE posts (id INTEGER PRIMARY KEY, user_id INTEGER REFERENCES users(id));",
+            )
+            .await
+            .unwrap();
+        }
+
+        let tool = SchemaIngestTool::new();
+        let schema = tool
+            .introspect(&format!("sqlite:{}", path.display()))
+            .await
+            .unwrap();
+        assert_eq!(schema.tables.len(), 2);
+        let posts = schema.tables.iter().find(|t| t.name == "posts").unwrap();
+        assert_eq!(posts.foreign_keys[0].ref_table, "users");
+        let users = schema.tables.iter().find(|t| t.name == "users").unwrap();
+        assert!(users.columns[0].primary_key);
+        assert!(!users.columns[1].nullable);
+    }
+}
@@ -1,31 +1,119 @@
 //! Search tool - Search within files using patterns
-
+//!
+//! Files are walked with the `ignore` crate (so `.gitignore`, `.ignore` and
+//! global git excludes are respected) and searched with `grep-searcher`, the
+//! engine behind ripgrep. Matches come back as `path:line:column` citations,
+//! sorted by path so pages stay stable across calls.
+
+use grep_matcher::Matcher;
+use grep_regex::{RegexMatcher, RegexMatcherBuilder};
+use grep_searcher::{BinaryDetection, Searcher, SearcherBuilder, Sink, SinkContext, SinkContextKind, SinkMatch};
+use ignore::WalkBuilder;
 use regex::Regex;
+use rig::tool::Tool;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::path::{Path, PathBuf};
 use tokio::fs;
-use walkdir::WalkDir;
 
-/// Search result
+/// Default page size
+const DEFAULT_PAGE_SIZE: usize = 100;
+/// Matches counted before the search stops, across all pages
+const MAX_SCANNED_MATCHES: usize = 5_000;
+/// Characters of a matching line kept in the preview
+const MAX_PREVIEW_CHARS: usize = 200;
+/// Directories skipped even without an ignore file
+pub(crate) const ALWAYS_IGNORED: &[&str] = &[
+    ".git",
+    "node_modules",
+    "target",
+    "__pycache__",
+    ".venv",
+    "venv",
+    "dist",
+    ".next",
+];
+
+/// How the pattern is matched
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SearchMode {
+    /// Plain text
+    #[default]
+    Literal,
+    /// Regular expression (Rust regex syntax)
+    Regex,
+    /// Plain text matched as a whole word
+    Word,
+}
+
+/// One match, citable as `path:line:column`
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SearchResult {
-    pub file: PathBuf,
-    pub line_number: usize,
-    pub line_content: String,
-    pub match_start: usize,
-    pub match_end: usize,
+    pub path: PathBuf,
+    pub line: usize,
+    /// 1-indexed, in characters
+    pub column: usize,
+    /// Trimmed matching line
+    pub preview: String,
     pub context_before: Vec<String>,
     pub context_after: Vec<String>,
 }
 
+impl SearchResult {
+    /// `path:line:column`, relative to `root` when possible
+    pub fn citation(&self, root: &Path) -> String {
+        let path = self.path.strip_prefix(root).unwrap_or(&self.path);
+        format!("{}:{}:{}", path.display(), self.line, self.column)
+    }
+}
+
 /// Search output
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SearchOutput {
     pub pattern: String,
+    /// Matches found, capped at [`MAX_SCANNED_MATCHES`]
     pub total_matches: usize,
     pub files_searched: usize,
     pub files_with_matches: usize,
+    /// The page of matches starting at `offset`
     pub results: Vec<SearchResult>,
+    pub offset: usize,
+    /// Offset of the next page, if there is one
+    pub next_offset: Option<usize>,
+    /// The search stopped at the match cap, counts are lower bounds
+    pub truncated: bool,
+}
+
+impl SearchOutput {
+    /// Matches as citations, one per line
+    pub fn format(&self, root: &Path) -> String {
+        if self.results.is_empty() {
+            return format!(
+                "No matches for '{}' ({} files searched)",
+                self.pattern, self.files_searched
+            );
+        }
+        let mut out = format!(
+            "{}{} matches for '{}' in {} files (showing {}-{})\n",
+            self.total_matches,
+            if self.truncated { "+" } else { "" },
+            self.pattern,
+            self.files_with_matches,
+            self.offset + 1,
+            self.offset + self.results.len()
+        );
+        for result in &self.results {
+            for line in &result.context_before {
+                out.push_str(&format!("    {}\n", line));
+            }
+            out.push_str(&format!("{}: {}\n", result.citation(root), result.preview));
+            for line in &result.context_after {
+                out.push_str(&format!("    {}\n", line));
+            }
+        }
+        out
+    }
 }
 
 /// Search in files tool
@@ -47,135 +135,20 @@ impl SearchInFilesTool {
             return Err(SearchError::PathNotFound(args.path));
         }
 
-        let pattern = if args.is_regex.unwrap_or(false) {
-            Regex::new(&args.pattern).map_err(|e| SearchError::InvalidRegex(e.to_string()))?
-        } else {
-            // Escape special regex characters for literal search
-            let escaped = regex::escape(&args.pattern);
-            Regex::new(&escaped).unwrap()
-        };
-
-        let case_insensitive = args.case_insensitive.unwrap_or(true);
-        let pattern = if case_insensitive {
-            Regex::new(&format!("(?i){}", pattern.as_str())).unwrap_or(pattern)
-        } else {
-            pattern
+        let pattern = match args.mode {
+            SearchMode::Regex => args.pattern.clone(),
+            SearchMode::Literal | SearchMode::Word => regex::escape(&args.pattern),
         };
-
-        let max_results = args.max_results.unwrap_or(100);
-        let context_lines = args.context_lines.unwrap_or(2);
-        let file_pattern = args.file_pattern.as_deref();
-
-        let ignore_patterns = vec![
-            ".git",
-            "node_modules",
-            "target",
-            "__pycache__",
-            ".venv",
-            "venv",
-            "dist",
-            "build",
-            ".next",
-        ];
-
-        let mut results = Vec::new();
-        let mut files_searched = 0;
-        let mut files_with_matches = 0;
-
-        for entry in WalkDir::new(&root)
-            .max_depth(args.max_depth.unwrap_or(10))
-            .into_iter()
-            .filter_entry(|e| {
-                let path = e.path();
-                !ignore_patterns
-                    .iter()
-                    .any(|p| path.to_string_lossy().contains(p))
-            })
-            .filter_map(|e| e.ok())
-        {
-            let path = entry.path();
-
-            if !path.is_file() {
-                continue;
-            }
-
-            // Check file pattern
-            if let Some(fp) = file_pattern {
-                let file_name = path
-                    .file_name()
-                    .map(|n| n.to_string_lossy().to_string())
-                    .unwrap_or_default();
-                if !matches_glob(&file_name, fp) {
-                    continue;
-                }
-            }
-
-            // Skip binary files
-            if is_binary_file(path) {
-                continue;
-            }
-
-            // Read and search file
-            let content = match fs::read_to_string(path).await {
-                Ok(c) => c,
-                Err(_) => continue,
-            };
-
-            files_searched += 1;
-            let lines: Vec<&str> = content.lines().collect();
-            let mut file_has_match = false;
-
-            for (line_num, line) in lines.iter().enumerate() {
-                if let Some(m) = pattern.find(line) {
-                    file_has_match = true;
-
-                    // Get context
-                    let context_before: Vec<String> = lines
-                        .iter()
-                        .skip(line_num.saturating_sub(context_lines))
-                        .take(context_lines.min(line_num))
-                        .map(|s| s.to_string())
-                        .collect();
-
-                    let context_after: Vec<String> = lines
-                        .iter()
-                        .skip(line_num + 1)
-                        .take(context_lines)
-                        .map(|s| s.to_string())
-                        .collect();
-
-                    results.push(SearchResult {
-                        file: path.to_path_buf(),
-                        line_number: line_num + 1,
-                        line_content: line.to_string(),
-                        match_start: m.start(),
-                        match_end: m.end(),
-                        context_before,
-                        context_after,
-                    });
-
-                    if results.len() >= max_results {
-                        break;
-                    }
-                }
-            }
-
-            if file_has_match {
-                files_with_matches += 1;
-            }
-
-            if results.len() >= max_results {
-                break;
-            }
-        }
-
-        Ok(SearchOutput {
-            pattern: args.pattern,
-            total_matches: results.len(),
-            files_searched,
-            files_with_matches,
-            results,
-        })
+        let matcher = RegexMatcherBuilder::new()
+            .case_insensitive(args.case_insensitive.unwrap_or(true))
+            .word(args.mode == SearchMode::Word)
+            .line_terminator(Some(b'\n'))
+            .build(&pattern)
+            .map_err(|e| SearchError::InvalidRegex(e.to_string()))?;
+
+        tokio::task::spawn_blocking(move || search_blocking(&root, &args, &matcher))
+            .await
+            .map_err(|e| SearchError::IoError(std::io::Error::other(e)))?
     }
 
     /// Search and replace in files
@@ -186,10 +159,15 @@ impl SearchInFilesTool {
         let search_args = SearchArgs {
             path: args.path.clone(),
             pattern: args.pattern.clone(),
-            is_regex: args.is_regex,
+            mode: if args.is_regex.unwrap_or(false) {
+                SearchMode::Regex
+            } else {
+                SearchMode::Literal
+            },
             case_insensitive: args.case_insensitive,
             file_pattern: args.file_pattern.clone(),
             max_results: Some(1000),
+            offset: None,
             context_lines: Some(0),
             max_depth: args.max_depth,
         };
@@ -230,7 +208,7 @@ impl SearchInFilesTool {
 
         for result in &search_results.results {
             files_to_modify
-                .entry(result.file.clone())
+                .entry(result.path.clone())
                 .or_default()
                 .push(result);
         }
@@ -265,19 +243,71 @@ impl SearchInFilesTool {
     }
 }
 
+impl Tool for SearchInFilesTool {
+    const NAME: &'static str = "search_in_files";
+
+    type Args = SearchArgs;
+    type Output = String;
+    type Error = SearchError;
+
+    async fn definition(&self, _prompt: String) -> rig::completion::ToolDefinition {
+        rig::completion::ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: "Search file contents (ripgrep engine, respects .gitignore). Modes: \
+                          literal, regex or word. Returns path:line:column matches with a \
+                          preview, paged with offset/max_results."
+                .to_string(),
+            parameters: serde_json::to_value(schemars::schema_for!(SearchArgs))
+                .unwrap_or_default(),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let root = PathBuf::from(&args.path);
+        let output = self.search(args).await?;
+        let mut text = output.format(&root);
+        if let Some(next) = output.next_offset {
+            text.push_str(&format!("More matches: call again with offset={}\n", next));
+        }
+        Ok(text)
+    }
+}
+
 /// Arguments for searching
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct SearchArgs {
+    /// Directory to search (default: project root)
+    #[serde(default = "default_search_path")]
     pub path: String,
+    /// Text or regex to search for
     pub pattern: String,
-    pub is_regex: Option<bool>,
+    /// literal (default), regex or word (whole-word literal)
+    #[serde(default)]
+    pub mode: SearchMode,
+    /// Ignore case (default: true)
+    #[serde(default)]
     pub case_insensitive: Option<bool>,
+    /// Only files whose name matches, e.g. "*.rs"
+    #[serde(default)]
     pub file_pattern: Option<String>,
+    /// Page size (default: 100)
+    #[serde(default)]
     pub max_results: Option<usize>,
+    /// Matches to skip, from `next_offset` of the previous page
+    #[serde(default)]
+    pub offset: Option<usize>,
+    /// Lines of context around each match (default: 2)
+    #[serde(default)]
     pub context_lines: Option<usize>,
+    /// Maximum directory depth (default: 10)
+    #[serde(default)]
     pub max_depth: Option<usize>,
 }
 
+fn default_search_path() -> String {
+    ".".to_string()
+}
+
 /// Arguments for search and replace
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SearchReplaceArgs {
@@ -315,6 +345,172 @@ pub enum SearchError {
     WriteError(String),
 }
 
+/// Text files under `root` in path order, honouring ignore files, skipping
+/// binaries and, with `file_pattern`, files whose name does not match
+pub(crate) fn project_files<'a>(
+    root: &Path,
+    file_pattern: Option<&'a str>,
+    max_depth: Option<usize>,
+) -> impl Iterator<Item = PathBuf> + 'a {
+    WalkBuilder::new(root)
+        .max_depth(Some(max_depth.unwrap_or(10)))
+        .require_git(false)
+        .sort_by_file_name(|a, b| a.cmp(b))
+        .filter_entry(|entry| {
+            !ALWAYS_IGNORED
+                .iter()
+                .any(|dir| entry.file_name() == std::ffi::OsStr::new(dir))
+        })
+        .build()
+        .filter_map(|e| e.ok())
+        .filter(|entry| entry.file_type().is_some_and(|t| t.is_file()))
+        .map(|entry| entry.into_path())
+        .filter(move |path| {
+            let name_matches = file_pattern.is_none_or(|fp| {
+                let file_name = path
+                    .file_name()
+                    .map(|n| n.to_string_lossy().to_string())
+                    .unwrap_or_default();
+                matches_glob(&file_name, fp)
+            });
+            name_matches && !is_binary_file(path)
+        })
+}
+
+/// The pattern as a `regex` regex, matching what the search tool matches
+pub(crate) fn pattern_regex(
+    pattern: &str,
+    mode: SearchMode,
+    case_insensitive: bool,
+) -> Result<Regex, SearchError> {
+    let body = match mode {
+        SearchMode::Regex => pattern.to_string(),
+        SearchMode::Literal => regex::escape(pattern),
+        SearchMode::Word => format!(r"\b(?:{})\b", regex::escape(pattern)),
+    };
+    regex::RegexBuilder::new(&body)
+        .case_insensitive(case_insensitive)
+        .multi_line(true)
+        .build()
+        .map_err(|e| SearchError::InvalidRegex(e.to_string()))
+}
+
+fn search_blocking(
+    root: &Path,
+    args: &SearchArgs,
+    matcher: &RegexMatcher,
+) -> Result<SearchOutput, SearchError> {
+    let offset = args.offset.unwrap_or(0);
+    let page_size = args.max_results.unwrap_or(DEFAULT_PAGE_SIZE).max(1);
+    let context_lines = args.context_lines.unwrap_or(2);
+    let mut searcher = SearcherBuilder::new()
+        .line_number(true)
+        .before_context(context_lines)
+        .after_context(context_lines)
+        .binary_detection(BinaryDetection::quit(b'\x00'))
+        .build();
+
+    let mut sink = MatchSink {
+        matcher,
+        path: PathBuf::new(),
+        found: 0,
+        offset,
+        page_end: offset + page_size,
+        results: Vec::new(),
+        pending_before: Vec::new(),
+        last_kept: false,
+    };
+    let mut files_searched = 0;
+    let mut files_with_matches = 0;
+
+    for path in project_files(root, args.file_pattern.as_deref(), args.max_depth) {
+        files_searched += 1;
+        let before = sink.found;
+        sink.path = path.clone();
+        sink.pending_before.clear();
+        sink.last_kept = false;
+        // Unreadable files are skipped, like binary ones
+        let _ = searcher.search_path(matcher, &path, &mut sink);
+        if sink.found > before {
+            files_with_matches += 1;
+        }
+        if sink.found >= MAX_SCANNED_MATCHES {
+            break;
+        }
+    }
+
+    let total_matches = sink.found.min(MAX_SCANNED_MATCHES);
+    Ok(SearchOutput {
+        pattern: args.pattern.clone(),
+        total_matches,
+        files_searched,
+        files_with_matches,
+        next_offset: (total_matches > sink.page_end).then_some(sink.page_end),
+        results: sink.results,
+        offset,
+        truncated: sink.found >= MAX_SCANNED_MATCHES,
+    })
+}
+
+/// Collects the matches that fall on the requested page and counts the rest
+struct MatchSink<'m> {
+    matcher: &'m RegexMatcher,
+    path: PathBuf,
+    /// Matches seen so far, across files
+    found: usize,
+    offset: usize,
+    page_end: usize,
+    results: Vec<SearchResult>,
+    pending_before: Vec<String>,
+    /// The latest match is on the page, so after-context belongs to it
+    last_kept: bool,
+}
+
+impl Sink for MatchSink<'_> {
+    type Error = std::io::Error;
+
+    fn matched(&mut self, _searcher: &Searcher, mat: &SinkMatch<'_>) -> Result<bool, Self::Error> {
+        let index = self.found;
+        self.found += 1;
+        let before = std::mem::take(&mut self.pending_before);
+        self.last_kept = index >= self.offset && index < self.page_end;
+        if self.last_kept {
+            let bytes = mat.bytes();
+            let line = String::from_utf8_lossy(bytes);
+            let start = self
+                .matcher
+                .find(bytes)
+                .ok()
+                .flatten()
+                .map_or(0, |m| m.start());
+            let column = String::from_utf8_lossy(&bytes[..start.min(bytes.len())]).chars().count() + 1;
+            self.results.push(SearchResult {
+                path: self.path.clone(),
+                line: mat.line_number().unwrap_or(0) as usize,
+                column,
+                preview: line.trim().chars().take(MAX_PREVIEW_CHARS).collect(),
+                context_before: before,
+                context_after: Vec::new(),
+            });
+        }
+        Ok(self.found < MAX_SCANNED_MATCHES)
+    }
+
+    fn context(&mut self, _searcher: &Searcher, ctx: &SinkContext<'_>) -> Result<bool, Self::Error> {
+        let line = String::from_utf8_lossy(ctx.bytes()).trim_end().to_string();
+        match ctx.kind() {
+            SinkContextKind::Before => self.pending_before.push(line),
+            SinkContextKind::After if self.last_kept => {
+                if let Some(last) = self.results.last_mut() {
+                    last.context_after.push(line);
+                }
+            }
+            _ => {}
+        }
+        Ok(true)
+    }
+}
+
 fn matches_glob(filename: &str, pattern: &str) -> bool {
     if let Some(ext) = pattern.strip_prefix("*.") {
         filename.ends_with(&format!(".{}", ext))
@@ -392,6 +588,67 @@ mod tests {
         assert!(!matches_glob("test.py", "*.rs"));
     }
 
+    fn args(root: &Path, pattern: &str, mode: SearchMode) -> SearchArgs {
+        SearchArgs {
+            path: root.to_string_lossy().to_string(),
+            pattern: pattern.to_string(),
+            mode,
+            case_insensitive: Some(false),
+            file_pattern: None,
+            max_results: None,
+            offset: None,
+            context_lines: Some(0),
+            max_depth: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_search_modes_and_ignore_files() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join(".gitignore"), "generated/\n").unwrap();
+        std::fs::create_dir(dir.path().join("generated")).unwrap();
+        std::fs::write(dir.path().join("generated/out.rs"), "let count = 1;\n").unwrap();
+        std::fs::write(dir.path().join("a.rs"), "fn main() {\n    let count = 1; let counter = a+b;\n}\n").unwrap();
+        let tool = SearchInFilesTool::new();
+
+        let literal = tool.search(args(dir.path(), "a+b", SearchMode::Literal)).await.unwrap();
+        assert_eq!(literal.total_matches, 1);
+        let hit = &literal.results[0];
+        assert_eq!((hit.line, hit.column), (2, 34));
+        assert_eq!(hit.citation(dir.path()), "a.rs:2:34");
+        assert_eq!(hit.preview, "let count = 1; let counter = a+b;");
+
+        let word = tool.search(args(dir.path(), "counter", SearchMode::Word)).await.unwrap();
+        assert_eq!(word.results[0].column, 24);
+        assert!(tool.search(args(dir.path(), "coun", SearchMode::Word)).await.unwrap().results.is_empty());
+
+        let regex = tool.search(args(dir.path(), r"count\w*", SearchMode::Regex)).await.unwrap();
+        assert_eq!(regex.files_searched, 1, "gitignored files are skipped");
+        assert_eq!(regex.total_matches, 1);
+    }
+
+    #[tokio::test]
+    async fn test_search_pagination() {
+        let dir = tempfile::tempdir().unwrap();
+        let content: String = (1..=25).map(|i| format!("item {}\n", i)).collect();
+        std::fs::write(dir.path().join("list.txt"), content).unwrap();
+        let tool = SearchInFilesTool::new();
+
+        let mut page_args = args(dir.path(), "item", SearchMode::Literal);
+        page_args.max_results = Some(10);
+        page_args.offset = Some(20);
+        let page = tool.search(page_args).await.unwrap();
+
+        assert_eq!(page.total_matches, 25);
+        assert_eq!(page.results.len(), 5);
+        assert_eq!(page.results[0].line, 21);
+        assert_eq!(page.next_offset, None);
+
+        let mut first_args = args(dir.path(), "item", SearchMode::Literal);
+        first_args.max_results = Some(10);
+        assert_eq!(tool.search(first_args).await.unwrap().next_offset, Some(10));
+    }
+
     #[test]
     fn test_binary_detection() {
         assert!(is_binary_file(Path::new("image.png")));
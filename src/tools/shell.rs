@@ -6,6 +6,7 @@ use std::path::PathBuf;
 use std::process::Stdio;
 use tokio::io::{AsyncBufReadExt, BufReader};
 use tokio::process::Command;
+use super::command::{output_in_group, spawn_in_group};
 
 /// Shell command arguments
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -110,7 +111,7 @@ impl ShellExecutorTool {
 
         let start = std::time::Instant::now();
 
-        let output = tokio::time::timeout(std::time::Duration::from_secs(timeout), cmd.output())
+        let output = tokio::time::timeout(std::time::Duration::from_secs(timeout), output_in_group(&mut cmd))
             .await
             .map_err(|_| ShellError::Timeout(timeout))?
             .map_err(|e| ShellError::ExecutionError(e.to_string()))?;
@@ -157,9 +158,8 @@ impl ShellExecutorTool {
         cmd.stderr(Stdio::piped());
 
         let start = std::time::Instant::now();
-        let mut child = cmd
-            .spawn()
-            .map_err(|e| ShellError::ExecutionError(e.to_string()))?;
+        let (mut child, group) =
+            spawn_in_group(&mut cmd).map_err(|e| ShellError::ExecutionError(e.to_string()))?;
 
         let stdout = child.stdout.take().expect("stdout");
         let stderr = child.stderr.take().expect("stderr");
@@ -201,6 +201,7 @@ impl ShellExecutorTool {
             .wait()
             .await
             .map_err(|e| ShellError::ExecutionError(e.to_string()))?;
+        group.release();
 
         let elapsed_ms = start.elapsed().as_millis() as u64;
 
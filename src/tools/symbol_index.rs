@@ -0,0 +1,860 @@
+//! Symbol index
+//!
+//! Extracts symbols with the AST parser and stores them in the `code_symbols`
+//! table, re-parsing only files whose content hash changed. The lookups (fuzzy
+//! search by name, symbols of a file, symbol at a line) back the `symbol_index`
+//! tool and `@symbol` mentions in queries. The imports of each file are stored
+//! alongside in `code_dependencies` to build the [`ImportGraph`], and their
+//! complexity metrics in `file_metrics` for the [`ComplexityReport`] history.
+
+use super::change_summary::{summarize_file, FileChangeSummary};
+use super::complexity::{ComplexityReport, FileMetrics};
+use super::git::{run_git_command, DiffOutput};
+use crate::ast::{qualified_name, AstParser, AstSymbol, Range, SupportedLanguage, SymbolKind};
+use crate::context::call_graph::CallGraph;
+use crate::context::import_graph::{import_targets, resolve_import, ImportGraph};
+use crate::db::{
+    CodeDependency, CodeSymbol, ComplexitySnapshot, Database, DatabaseError, IndexedFile,
+    IndexedSymbol, Project,
+};
+use chrono::{DateTime, Utc};
+use regex::Regex;
+use rig::tool::Tool;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, LazyLock};
+use thiserror::Error;
+use tokio::sync::{Mutex, MutexGuard};
+use tree_sitter::Point;
+
+/// Files larger than this are not indexed (generated or vendored code)
+const MAX_FILE_BYTES: u64 = 1024 * 1024;
+/// Candidates fetched from the database before fuzzy ranking
+const MAX_CANDIDATES: i32 = 500;
+/// Lines of source shown for each mentioned symbol
+const MAX_MENTION_LINES: usize = 40;
+/// Directories never indexed
+const IGNORED_DIRS: &[&str] = &[
+    "target",
+    "node_modules",
+    "dist",
+    "build",
+    "vendor",
+    "__pycache__",
+];
+
+/// `@Name` or `@Type::method`, not followed by a path or extension
+static MENTION_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?:^|[\s(\[{,])@([A-Za-z_]\w*(?:::[A-Za-z_]\w*)*)([./]?)").unwrap()
+});
+
+#[derive(Error, Debug)]
+pub enum SymbolIndexError {
+    #[error("Symbol index unavailable: no database attached")]
+    NoDatabase,
+    #[error("Missing argument: {0}")]
+    MissingArgument(&'static str),
+    #[error("Parser error: {0}")]
+    Parser(String),
+    #[error("Symbol lookup task failed: {0}")]
+    Task(String),
+    #[error("Path outside the project: {0}")]
+    OutsideProject(String),
+    #[error("Database error: {0}")]
+    Database(#[from] DatabaseError),
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// Outcome of an indexing run
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct SymbolIndexReport {
+    pub files_indexed: usize,
+    pub files_unchanged: usize,
+    pub files_removed: usize,
+    pub symbols: usize,
+}
+
+/// Lookup performed by the tool
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SymbolLookup {
+    /// Fuzzy search by name
+    #[default]
+    Find,
+    /// List the symbols of a file
+    File,
+    /// Symbol enclosing a line of a file
+    At,
+    /// Refresh the index and report what changed
+    Reindex,
+}
+
+/// Arguments for the symbol index tool
+#[derive(Debug, Clone, Default, Deserialize, Serialize, JsonSchema)]
+pub struct SymbolIndexArgs {
+    /// find (by name), file (symbols of a file), at (symbol at a line) or reindex
+    #[serde(default)]
+    pub action: SymbolLookup,
+    /// Symbol name or fuzzy pattern (find)
+    #[serde(default)]
+    pub query: Option<String>,
+    /// File path relative to the project root (file, at)
+    #[serde(default)]
+    pub path: Option<String>,
+    /// 1-based line number (at)
+    #[serde(default)]
+    pub line: Option<usize>,
+    /// Maximum number of results (find, default 20)
+    #[serde(default)]
+    pub limit: Option<usize>,
+    /// Project directory, filled in by the orchestrator
+    #[serde(skip)]
+    #[schemars(skip)]
+    pub project_dir: String,
+}
+
+/// Symbol index backed by the session database
+#[derive(Clone, Default)]
+pub struct SymbolIndexTool {
+    db: Option<Database>,
+    /// Kept between runs so edited files are re-parsed incrementally
+    parser: Arc<Mutex<Option<AstParser>>>,
+}
+
+impl SymbolIndexTool {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Store symbols in the given database
+    pub fn with_database(mut self, db: Database) -> Self {
+        self.db = Some(db);
+        self
+    }
+
+    /// Whether lookups are backed by a database (and indexing is incremental)
+    pub fn has_database(&self) -> bool {
+        self.db.is_some()
+    }
+
+    fn db(&self) -> Result<&Database, SymbolIndexError> {
+        self.db.as_ref().ok_or(SymbolIndexError::NoDatabase)
+    }
+
+    /// Project record for a root directory, created on first use
+    async fn project(&self, root: &Path) -> Result<Project, SymbolIndexError> {
+        let root_path = root.to_string_lossy();
+        if let Some(project) = self.db()?.get_project_by_path(&root_path).await? {
+            return Ok(project);
+        }
+        let name = root
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| root_path.to_string());
+        let project = Project::new(root_path, name, "unknown");
+        self.db()?.upsert_project(&project).await?;
+        Ok(project)
+    }
+
+    /// Shared AST parser, created on first use
+    pub(crate) async fn parser(
+        &self,
+    ) -> Result<MutexGuard<'_, Option<AstParser>>, SymbolIndexError> {
+        let mut guard = self.parser.lock().await;
+        if guard.is_none() {
+            *guard = Some(AstParser::new().map_err(|e| SymbolIndexError::Parser(e.to_string()))?);
+        }
+        Ok(guard)
+    }
+
+    /// Bring the index of a project up to date: files whose hash changed are
+    /// re-parsed, deleted files are dropped with their symbols
+    pub async fn index_project(&self, root: &Path) -> Result<SymbolIndexReport, SymbolIndexError> {
+        let db = self.db()?;
+        let root = root.canonicalize()?;
+        let mut project = self.project(&root).await?;
+
+        let mut known: HashMap<String, IndexedFile> = db
+            .get_project_files(&project.id)
+            .await?
+            .into_iter()
+            .map(|f| (f.relative_path.clone(), f))
+            .collect();
+
+        let mut guard = self.parser().await?;
+        let Some(parser) = guard.as_mut() else {
+            return Err(SymbolIndexError::Parser(
+                "AST parser unavailable".to_string(),
+            ));
+        };
+
+        let mut report = SymbolIndexReport::default();
+        let mut languages: HashMap<SupportedLanguage, usize> = HashMap::new();
+
+        let files = source_files(&root);
+        let relative_paths: Vec<String> = files
+            .iter()
+            .map(|(path, _)| relative_path(&root, path))
+            .collect();
+        let lookup: HashSet<&str> = relative_paths.iter().map(String::as_str).collect();
+
+        for (path, language) in &files {
+            let (path, language) = (path.as_path(), *language);
+            let Ok(source) = std::fs::read_to_string(path) else {
+                continue;
+            };
+            let relative = relative_path(&root, path);
+            *languages.entry(language).or_default() += 1;
+
+            let hash = format!("{:x}", Sha256::digest(source.as_bytes()));
+            let previous = known.remove(&relative);
+            if previous.as_ref().is_some_and(|f| !f.is_outdated(&hash)) {
+                report.files_unchanged += 1;
+                continue;
+            }
+
+            let parse = parser
+                .parse_file(path, language, &source)
+                .map_err(|e| SymbolIndexError::Parser(e.to_string()))?;
+            let symbols = parser.extract_symbols(&parse.tree, language, &source);
+
+            let file = IndexedFile {
+                id: previous.map(|f| f.id).unwrap_or_default(),
+                project_id: project.id.clone(),
+                relative_path: relative,
+                absolute_path: path.to_string_lossy().to_string(),
+                file_hash: hash,
+                file_size: source.len() as i64,
+                line_count: Some(source.lines().count() as i64),
+                language: Some(language.as_str().to_string()),
+                file_type: Some("source".to_string()),
+                last_modified: modified_at(path),
+                indexed_at: Utc::now().to_rfc3339(),
+                is_valid: 1,
+            };
+            let file_id = db.upsert_indexed_file(&file).await?;
+
+            let records: Vec<CodeSymbol> = symbols
+                .iter()
+                .map(|symbol| {
+                    let node = parse.tree.root_node().descendant_for_point_range(
+                        Point::new(symbol.range.start_line - 1, symbol.range.start_col),
+                        Point::new(symbol.range.end_line - 1, symbol.range.end_col),
+                    );
+                    let complexity = node
+                        .map(|n| parser.calculate_complexity(&n, &source))
+                        .unwrap_or(1);
+                    to_record(symbol, &source, file_id, &project.id, complexity)
+                })
+                .collect();
+            db.replace_file_symbols(file_id, &records).await?;
+
+            let dependencies: Vec<CodeDependency> = parser
+                .extract_imports(&parse.tree, language, &source)
+                .iter()
+                .flat_map(|import| {
+                    import_targets(import, language)
+                        .into_iter()
+                        .map(|target| CodeDependency {
+                            id: 0,
+                            project_id: project.id.clone(),
+                            source_file_id: file_id,
+                            is_external: resolve_import(&lookup, &file.relative_path, language, &target)
+                                .is_none() as i32,
+                            target_module: target,
+                            import_type: if import.is_wildcard { "wildcard" } else { "import" }
+                                .to_string(),
+                            line_number: import.line as i64,
+                        })
+                })
+                .collect();
+            db.replace_file_dependencies(file_id, &dependencies).await?;
+
+            let metrics =
+                FileMetrics::compute(&file.relative_path, &source, &parse.tree, &symbols, parser);
+            db.upsert_file_metrics(
+                file_id,
+                &project.id,
+                &serde_json::to_string(&metrics).unwrap_or_default(),
+            )
+            .await?;
+
+            report.files_indexed += 1;
+            report.symbols += records.len();
+        }
+
+        for (relative, file) in known {
+            db.remove_indexed_file(file.id).await?;
+            parser.forget_file(&root.join(relative));
+            report.files_removed += 1;
+        }
+
+        // One snapshot per index run that changed something
+        if report.files_indexed > 0 || report.files_removed > 0 {
+            let complexity = self.load_complexity(db, &project.id).await?;
+            db.insert_complexity_snapshot(&complexity.to_snapshot(&project.id))
+                .await?;
+        }
+
+        if let Some((language, _)) = languages.iter().max_by_key(|(_, count)| **count) {
+            project.language = language.as_str().to_string();
+        }
+        project.last_indexed_at = Utc::now().to_rfc3339();
+        db.upsert_project(&project).await?;
+
+        Ok(report)
+    }
+
+    /// Symbols matching a name, best matches first
+    pub async fn find(
+        &self,
+        root: &Path,
+        query: &str,
+        limit: usize,
+    ) -> Result<Vec<IndexedSymbol>, SymbolIndexError> {
+        let project = self.project(&root.canonicalize()?).await?;
+        let mut ranked: Vec<(u32, IndexedSymbol)> = self
+            .db()?
+            .find_symbol_candidates(&project.id, query, MAX_CANDIDATES)
+            .await?
+            .into_iter()
+            .filter_map(|s| fuzzy_score(query, &s.symbol.symbol_name).map(|score| (score, s)))
+            .collect();
+
+        ranked.sort_by(|(a, x), (b, y)| {
+            b.cmp(a)
+                .then_with(|| x.relative_path.cmp(&y.relative_path))
+                .then_with(|| x.symbol.line_start.cmp(&y.symbol.line_start))
+        });
+        Ok(ranked.into_iter().take(limit).map(|(_, s)| s).collect())
+    }
+
+    /// Symbols of a file, in source order
+    pub async fn file_symbols(
+        &self,
+        root: &Path,
+        relative_path: &str,
+    ) -> Result<Vec<IndexedSymbol>, SymbolIndexError> {
+        let project = self.project(&root.canonicalize()?).await?;
+        Ok(self
+            .db()?
+            .get_symbols_in_file(&project.id, relative_path)
+            .await?)
+    }
+
+    /// Innermost symbol spanning a line
+    pub async fn symbol_at(
+        &self,
+        root: &Path,
+        relative_path: &str,
+        line: usize,
+    ) -> Result<Option<IndexedSymbol>, SymbolIndexError> {
+        let project = self.project(&root.canonicalize()?).await?;
+        Ok(self
+            .db()?
+            .get_symbol_at_line(&project.id, relative_path, line as i64)
+            .await?)
+    }
+
+    /// Import graph of a project, refreshing the index first. Without a database
+    /// the graph is built by parsing every file.
+    pub async fn import_graph(&self, root: &Path) -> Result<ImportGraph, SymbolIndexError> {
+        let root = root.canonicalize()?;
+        if self.db.is_none() {
+            let mut guard = self.parser().await?;
+            let Some(parser) = guard.as_mut() else {
+                return Err(SymbolIndexError::Parser(
+                    "AST parser unavailable".to_string(),
+                ));
+            };
+            return Ok(ImportGraph::build(&root, parser));
+        }
+
+        self.index_project(&root).await?;
+        let db = self.db()?;
+        let project = self.project(&root).await?;
+        let files = db
+            .get_project_files(&project.id)
+            .await?
+            .into_iter()
+            .map(|f| f.relative_path);
+        let imports = db
+            .get_file_dependencies(&project.id)
+            .await?
+            .into_iter()
+            .filter_map(|d| {
+                let language = SupportedLanguage::parse_language(d.language.as_deref()?)?;
+                Some((
+                    d.relative_path,
+                    language,
+                    d.dependency.target_module,
+                    d.dependency.line_number as usize,
+                ))
+            });
+        Ok(ImportGraph::resolve(imports, files))
+    }
+
+    /// Function-level call graph of a project, parsed from its source files
+    /// (call sites are not stored in the index)
+    pub async fn call_graph(&self, root: &Path) -> Result<CallGraph, SymbolIndexError> {
+        let root = root.canonicalize()?;
+        let mut guard = self.parser().await?;
+        let Some(parser) = guard.as_mut() else {
+            return Err(SymbolIndexError::Parser(
+                "AST parser unavailable".to_string(),
+            ));
+        };
+        Ok(CallGraph::build(&root, parser))
+    }
+
+    /// Symbol-level changes of the code files of `diff`, comparing each file at
+    /// `HEAD` with the working tree
+    pub async fn diff_symbols(&self, root: &Path, diff: &DiffOutput) -> Result<Vec<FileChangeSummary>, SymbolIndexError> {
+        // Diff paths are relative to the top of the repository
+        let top = run_git_command(&root.to_path_buf(), &["rev-parse", "--show-toplevel"])
+            .map(|top| PathBuf::from(top.trim()))
+            .unwrap_or_else(|_| root.to_path_buf());
+        let mut guard = self.parser().await?;
+        let Some(parser) = guard.as_mut() else {
+            return Err(SymbolIndexError::Parser(
+                "AST parser unavailable".to_string(),
+            ));
+        };
+
+        let mut summaries = Vec::new();
+        for file in diff.code_files() {
+            let Some(language) = SupportedLanguage::from_path(Path::new(&file.path)) else {
+                continue;
+            };
+            let before = run_git_command(&top, &["show", &format!("HEAD:{}", file.path)]).ok();
+            let after = std::fs::read_to_string(top.join(&file.path)).ok();
+            let before = before.map(|source| named_symbols(parser, language, &source)).unwrap_or_default();
+            let after = after.map(|source| named_symbols(parser, language, &source)).unwrap_or_default();
+            summaries.push(summarize_file(file, &before, &after));
+        }
+        Ok(summaries)
+    }
+
+    /// Current complexity report of a project, refreshing the index first
+    pub async fn complexity_report(&self, root: &Path) -> Result<ComplexityReport, SymbolIndexError> {
+        let root = root.canonicalize()?;
+        self.index_project(&root).await?;
+        let project = self.project(&root).await?;
+        self.load_complexity(self.db()?, &project.id).await
+    }
+
+    /// Last `limit` complexity snapshots of a project, oldest first
+    pub async fn complexity_trends(
+        &self,
+        root: &Path,
+        limit: usize,
+    ) -> Result<Vec<ComplexitySnapshot>, SymbolIndexError> {
+        let root = root.canonicalize()?;
+        self.index_project(&root).await?;
+        let project = self.project(&root).await?;
+        Ok(self
+            .db()?
+            .get_complexity_snapshots(&project.id, limit as i32)
+            .await?)
+    }
+
+    async fn load_complexity(
+        &self,
+        db: &Database,
+        project_id: &str,
+    ) -> Result<ComplexityReport, SymbolIndexError> {
+        let files: Vec<FileMetrics> = db
+            .get_file_metrics(project_id)
+            .await?
+            .iter()
+            .filter_map(|json| serde_json::from_str(json).ok())
+            .collect();
+        Ok(ComplexityReport::from_files(&files))
+    }
+
+    /// Location and source of the symbols mentioned as `@Name` in a query
+    pub async fn mention_context(&self, root: &Path, query: &str) -> Option<String> {
+        let mentions = symbol_mentions(query);
+        if mentions.is_empty() || self.db.is_none() {
+            return None;
+        }
+        self.index_project(root).await.ok()?;
+
+        let mut sections = Vec::new();
+        for mention in mentions {
+            // `Type::method` is looked up by its last segment
+            let name = mention.rsplit("::").next().unwrap_or(mention);
+            let Ok(found) = self.find(root, name, 1).await else {
+                continue;
+            };
+            let Some(hit) = found.into_iter().find(|s| s.symbol.symbol_name == name) else {
+                continue;
+            };
+            sections.push(format_mention(root, &hit));
+        }
+
+        (!sections.is_empty()).then(|| sections.join("\n\n"))
+    }
+
+    async fn run(&self, args: SymbolIndexArgs) -> Result<String, SymbolIndexError> {
+        let root = PathBuf::from(if args.project_dir.is_empty() {
+            "."
+        } else {
+            &args.project_dir
+        });
+        let report = self.index_project(&root).await?;
+
+        let symbols = match args.action {
+            SymbolLookup::Reindex => {
+                return Ok(format!(
+                    "Indexed {} files ({} unchanged, {} removed), {} symbols updated",
+                    report.files_indexed,
+                    report.files_unchanged,
+                    report.files_removed,
+                    report.symbols
+                ));
+            }
+            SymbolLookup::Find => {
+                let query = args
+                    .query
+                    .ok_or(SymbolIndexError::MissingArgument("query"))?;
+                self.find(&root, &query, args.limit.unwrap_or(20)).await?
+            }
+            SymbolLookup::File => {
+                let path = args.path.ok_or(SymbolIndexError::MissingArgument("path"))?;
+                self.file_symbols(&root, &path).await?
+            }
+            SymbolLookup::At => {
+                let path = args.path.ok_or(SymbolIndexError::MissingArgument("path"))?;
+                let line = args.line.ok_or(SymbolIndexError::MissingArgument("line"))?;
+                self.symbol_at(&root, &path, line)
+                    .await?
+                    .into_iter()
+                    .collect()
+            }
+        };
+
+        if symbols.is_empty() {
+            return Ok("No matching symbols".to_string());
+        }
+        Ok(symbols
+            .iter()
+            .map(|s| match &s.symbol.signature {
+                Some(signature) => format!("{} — {}", format_location(s), signature),
+                None => format_location(s),
+            })
+            .collect::<Vec<_>>()
+            .join("\n"))
+    }
+}
+
+/// `@Name` mentions in a query; `@src/main.rs` and `@file.py` are file mentions
+pub fn symbol_mentions(query: &str) -> Vec<&str> {
+    let mut mentions: Vec<&str> = MENTION_RE
+        .captures_iter(query)
+        .filter(|c| c.get(2).is_none_or(|m| m.as_str().is_empty()))
+        .filter_map(|c| c.get(1).map(|m| m.as_str()))
+        .collect();
+    mentions.dedup();
+    mentions
+}
+
+/// Score of `name` for a fuzzy `query`: exact > prefix > substring > subsequence,
+/// all case-insensitive. `None` when the query characters are not all in order.
+pub fn fuzzy_score(query: &str, name: &str) -> Option<u32> {
+    let query = query.to_lowercase();
+    let lower = name.to_lowercase();
+    if query.is_empty() {
+        return Some(0);
+    }
+    if lower == query {
+        return Some(
+            1000 + if name.eq_ignore_ascii_case(&query) {
+                1
+            } else {
+                0
+            },
+        );
+    }
+    if lower.starts_with(&query) {
+        return Some(800 - (lower.len() - query.len()).min(100) as u32);
+    }
+    if let Some(pos) = lower.find(&query) {
+        return Some(600 - (pos + lower.len() - query.len()).min(100) as u32);
+    }
+
+    // Subsequence: reward matches at word starts (`fs` → `find_symbol`, `FileSystem`)
+    let chars: Vec<char> = name.chars().collect();
+    let mut score = 0u32;
+    let mut position = 0;
+    for q in query.chars() {
+        let found =
+            (position..chars.len()).find(|&i| chars[i].to_lowercase().eq(q.to_lowercase()))?;
+        let word_start = found == 0
+            || chars[found].is_uppercase()
+            || matches!(chars[found - 1], '_' | '-' | ':' | '.');
+        score += if word_start { 20 } else { 5 };
+        position = found + 1;
+    }
+    Some(score.min(400))
+}
+
+/// Definitions of a source with their qualified names; variables are left out
+fn named_symbols(parser: &mut AstParser, language: SupportedLanguage, source: &str) -> Vec<(String, Range)> {
+    let Ok(tree) = parser.parse(language, source) else {
+        return Vec::new();
+    };
+    parser
+        .extract_symbols(&tree, language, source)
+        .into_iter()
+        .filter(|symbol| symbol.kind != SymbolKind::Variable)
+        .map(|symbol| (qualified_name(&tree, language, source, &symbol), symbol.range))
+        .collect()
+}
+
+fn to_record(
+    symbol: &AstSymbol,
+    source: &str,
+    file_id: i64,
+    project_id: &str,
+    complexity: usize,
+) -> CodeSymbol {
+    let signature = source
+        .lines()
+        .nth(symbol.range.start_line - 1)
+        .map(|line| line.trim().chars().take(200).collect::<String>())
+        .filter(|line| !line.is_empty());
+    let params: Vec<&str> = symbol.params.iter().map(|p| p.name.as_str()).collect();
+
+    CodeSymbol {
+        id: 0,
+        file_id,
+        project_id: project_id.to_string(),
+        symbol_name: symbol.name.clone(),
+        symbol_type: symbol.kind.as_str().to_string(),
+        visibility: symbol.visibility.as_str().to_string(),
+        line_start: symbol.range.start_line as i64,
+        line_end: symbol.range.end_line as i64,
+        signature,
+        documentation: symbol.docstring.clone(),
+        complexity: complexity as i64,
+        params_json: serde_json::to_string(&params).ok(),
+        return_type: symbol.return_type.clone(),
+        is_async: symbol.is_async as i32,
+        is_test: symbol.is_test as i32,
+        parent_symbol_id: None,
+    }
+}
+
+/// Supported source files of a project, skipping hidden and build directories
+pub(crate) fn source_files(root: &Path) -> Vec<(PathBuf, SupportedLanguage)> {
+    walkdir::WalkDir::new(root)
+        .into_iter()
+        .filter_entry(|entry| {
+            let name = entry.file_name().to_string_lossy();
+            entry.depth() == 0
+                || !(entry.file_type().is_dir()
+                    && (name.starts_with('.') || IGNORED_DIRS.contains(&name.as_ref())))
+        })
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .filter(|e| e.metadata().is_ok_and(|m| m.len() <= MAX_FILE_BYTES))
+        .filter_map(|e| {
+            let language = SupportedLanguage::from_path(e.path())?;
+            Some((e.into_path(), language))
+        })
+        .collect()
+}
+
+/// Path of a project file relative to its root
+pub(crate) fn relative_path(root: &Path, path: &Path) -> String {
+    path.strip_prefix(root)
+        .unwrap_or(path)
+        .to_string_lossy()
+        .to_string()
+}
+
+fn modified_at(path: &Path) -> String {
+    std::fs::metadata(path)
+        .and_then(|m| m.modified())
+        .map(|t| DateTime::<Utc>::from(t).to_rfc3339())
+        .unwrap_or_else(|_| Utc::now().to_rfc3339())
+}
+
+fn format_location(symbol: &IndexedSymbol) -> String {
+    format!(
+        "{}:{}-{} {} {}",
+        symbol.relative_path,
+        symbol.symbol.line_start,
+        symbol.symbol.line_end,
+        symbol.symbol.symbol_type,
+        symbol.symbol.symbol_name
+    )
+}
+
+fn format_mention(root: &Path, hit: &IndexedSymbol) -> String {
+    let start = hit.symbol.line_start.max(1) as usize;
+    let end = hit.symbol.line_end.max(hit.symbol.line_start) as usize;
+    let snippet = std::fs::read_to_string(root.join(&hit.relative_path))
+        .map(|content| {
+            let lines: Vec<&str> = content
+                .lines()
+                .skip(start - 1)
+                .take((end - start + 1).min(MAX_MENTION_LINES))
+                .collect();
+            let mut snippet = lines.join("\n");
+            if end - start + 1 > MAX_MENTION_LINES {
+                snippet.push_str("\n...");
+            }
+            snippet
+        })
+        .unwrap_or_default();
+
+    format!("{}\n```\n{}\n```", format_location(hit), snippet)
+}
+
+impl Tool for SymbolIndexTool {
+    const NAME: &'static str = "symbol_index";
+
+    type Args = SymbolIndexArgs;
+    type Output = String;
+    type Error = SymbolIndexError;
+
+    async fn definition(&self, _prompt: String) -> rig::completion::ToolDefinition {
+        rig::completion::ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: "Look up code symbols (functions, types, methods) in the project index: \
+                          fuzzy search by name, list the symbols of a file, or find the symbol \
+                          at a given line."
+                .to_string(),
+            parameters: serde_json::to_value(schemars::schema_for!(SymbolIndexArgs))
+                .unwrap_or_default(),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        // sqlx futures are not `Sync`, which `Tool::call` requires: run on a task
+        let tool = self.clone();
+        tokio::spawn(async move { tool.run(args).await })
+            .await
+            .map_err(|e| SymbolIndexError::Task(e.to_string()))?
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_fuzzy_score() {
+        assert!(fuzzy_score("parse", "parse") > fuzzy_score("parse", "parse_file"));
+        assert!(fuzzy_score("parse", "parse_file") > fuzzy_score("parse", "reparse"));
+        assert!(fuzzy_score("pf", "parse_file") > fuzzy_score("pf", "proof"));
+        assert_eq!(fuzzy_score("xyz", "parse_file"), None);
+    }
+
+    #[test]
+    fn test_symbol_mentions() {
+        assert_eq!(
+            symbol_mentions("why does @AstParser::parse_file fail and @run? see @src/main.rs"),
+            vec!["AstParser::parse_file", "run"]
+        );
+        assert!(symbol_mentions("mail me at dev@example.com").is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_index_and_lookup() {
+        let dir = tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("src")).unwrap();
+        std::fs::write(
+            dir.path().join("src/lib.rs"),
+            "pub struct Parser;\n\nimpl Parser {\n    pub fn parse_file(&self) -> bool {\n        true\n    }\n}\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("util.py"),
+            "def parse_args(argv):\n    return argv\n",
+        )
+        .unwrap();
+
+        let tool = SymbolIndexTool::new().with_database(Database::in_memory().await.unwrap());
+        let report = tool.index_project(dir.path()).await.unwrap();
+        assert_eq!(report.files_indexed, 2);
+        assert_eq!(report.symbols, 3);
+
+        let found = tool.find(dir.path(), "parsefile", 5).await.unwrap();
+        assert_eq!(found[0].symbol.symbol_name, "parse_file");
+        assert_eq!(found[0].relative_path, "src/lib.rs");
+
+        let at = tool
+            .symbol_at(dir.path(), "src/lib.rs", 5)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(at.symbol.symbol_name, "parse_file");
+        assert_eq!(at.symbol.symbol_type, "method");
+
+        // Unchanged files are skipped, edits and deletions invalidate
+        std::fs::write(
+            dir.path().join("util.py"),
+            "def parse_argv(argv):\n    return argv\n",
+        )
+        .unwrap();
+        std::fs::remove_file(dir.path().join("src/lib.rs")).unwrap();
+        let report = tool.index_project(dir.path()).await.unwrap();
+        assert_eq!((report.files_indexed, report.files_removed), (1, 1));
+
+        let names: Vec<_> = tool
+            .find(dir.path(), "parse", 10)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|s| s.symbol.symbol_name)
+            .collect();
+        assert_eq!(names, vec!["parse_argv"]);
+        assert!(tool
+            .file_symbols(dir.path(), "src/lib.rs")
+            .await
+            .unwrap()
+            .is_empty());
+
+        // One complexity snapshot per run that changed files
+        let report = tool.complexity_report(dir.path()).await.unwrap();
+        assert_eq!((report.files, report.functions), (1, 1));
+        let snapshots = tool.complexity_trends(dir.path(), 10).await.unwrap();
+        assert_eq!(snapshots.len(), 2);
+        assert_eq!(snapshots[0].files, 2);
+        assert_eq!(snapshots[1].files, 1);
+
+        let context = tool
+            .mention_context(dir.path(), "what does @parse_argv return?")
+            .await
+            .unwrap();
+        assert!(context.contains("util.py:1-2 function parse_argv"));
+        assert!(context.contains("return argv"));
+    }
+
+    #[tokio::test]
+    async fn test_import_graph_persisted() {
+        let dir = tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("src")).unwrap();
+        std::fs::write(dir.path().join("src/main.rs"), "use crate::config::Config;\nuse std::fs;\n").unwrap();
+        std::fs::write(dir.path().join("src/app.rs"), "use crate::main_helpers;\n").unwrap();
+
+        let tool = SymbolIndexTool::new().with_database(Database::in_memory().await.unwrap());
+        let graph = tool.import_graph(dir.path()).await.unwrap();
+        assert!(graph.imports_of("src/main.rs").is_empty());
+        assert_eq!(graph.external_imports_of("src/main.rs"), vec!["crate::config::Config", "std::fs"]);
+
+        // A new file links its existing importers without re-parsing them
+        std::fs::write(dir.path().join("src/config.rs"), "pub struct Config;\n").unwrap();
+        let graph = tool.import_graph(dir.path()).await.unwrap();
+        assert_eq!(graph.imports_of("src/main.rs"), vec!["src/config.rs"]);
+        assert_eq!(graph.importers_of("src/config.rs"), vec!["src/main.rs"]);
+    }
+}
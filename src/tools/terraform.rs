@@ -0,0 +1,463 @@
+//! Terraform plan summarizer
+//!
+//! Runs `terraform plan -json` (or reads a saved plan) and condenses it into
+//! the resources that will be created, updated, replaced or destroyed, with
+//! the risky ones highlighted. The tool only plans and explains: it never
+//! runs `terraform apply`.
+
+use rig::tool::Tool;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::path::Path;
+use std::time::Duration;
+use thiserror::Error;
+use tokio::process::Command;
+use super::command::output_in_group;
+
+/// `terraform plan` refreshes state remotely and can be slow
+const PLAN_TIMEOUT_SECS: u64 = 600;
+/// Resource types whose changes deserve a second look even when not destroyed
+const SENSITIVE_TYPE_MARKERS: &[&str] = &[
+    "iam",
+    "role",
+    "policy",
+    "security_group",
+    "firewall",
+    "network_acl",
+    "kms",
+    "secret",
+    "db_instance",
+    "database",
+    "rds",
+    "bucket",
+    "route53",
+    "dns",
+];
+
+#[derive(Error, Debug)]
+pub enum TerraformError {
+    #[error("terraform plan failed: {0}")]
+    PlanFailed(String),
+    #[error("Could not parse the plan: {0}")]
+    InvalidPlan(String),
+    #[error("terraform timed out after {0} seconds")]
+    Timeout(u64),
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// What Terraform will do to a resource
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PlanAction {
+    Create,
+    Update,
+    Replace,
+    Delete,
+    Read,
+    Noop,
+}
+
+impl PlanAction {
+    /// Parse a `planned_change` action of the streamed `plan -json` output
+    fn from_streamed(action: &str) -> Self {
+        match action {
+            "create" => Self::Create,
+            "update" => Self::Update,
+            "replace" => Self::Replace,
+            "delete" | "remove" | "forget" => Self::Delete,
+            "read" => Self::Read,
+            _ => Self::Noop,
+        }
+    }
+
+    /// Parse the `actions` list of `terraform show -json`
+    fn from_actions(actions: &[&str]) -> Self {
+        match actions {
+            [a, b] if a.contains("delete") || b.contains("delete") => Self::Replace,
+            [action] => Self::from_streamed(action),
+            _ => Self::Noop,
+        }
+    }
+
+    fn symbol(&self) -> &'static str {
+        match self {
+            Self::Create => "+",
+            Self::Update => "~",
+            Self::Replace => "-/+",
+            Self::Delete => "-",
+            Self::Read => "<=",
+            Self::Noop => " ",
+        }
+    }
+}
+
+/// How careful a reviewer should be with a change
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum PlanRisk {
+    Low,
+    Medium,
+    High,
+}
+
+impl PlanRisk {
+    fn icon(&self) -> &'static str {
+        match self {
+            Self::Low => "🟢",
+            Self::Medium => "🟡",
+            Self::High => "🔴",
+        }
+    }
+}
+
+/// One resource touched by the plan
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PlannedChange {
+    pub address: String,
+    pub resource_type: String,
+    pub action: PlanAction,
+}
+
+impl PlannedChange {
+    /// Destroying data is high risk; touching access control, keys or storage is medium
+    pub fn risk(&self) -> PlanRisk {
+        match self.action {
+            PlanAction::Delete | PlanAction::Replace => PlanRisk::High,
+            PlanAction::Create | PlanAction::Update
+                if SENSITIVE_TYPE_MARKERS
+                    .iter()
+                    .any(|marker| self.resource_type.contains(marker)) =>
+            {
+                PlanRisk::Medium
+            }
+            _ => PlanRisk::Low,
+        }
+    }
+}
+
+/// Condensed view of a Terraform plan
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PlanSummary {
+    pub changes: Vec<PlannedChange>,
+    /// Warnings and errors reported while planning
+    pub diagnostics: Vec<String>,
+}
+
+impl PlanSummary {
+    /// Parse the line-delimited output of `terraform plan -json`
+    pub fn from_streamed_json(output: &str) -> Self {
+        let mut summary = PlanSummary::default();
+        for line in output.lines() {
+            let Ok(event) = serde_json::from_str::<Value>(line) else {
+                continue;
+            };
+            match event["type"].as_str() {
+                Some("planned_change") => {
+                    let resource = &event["change"]["resource"];
+                    summary.changes.push(PlannedChange {
+                        address: resource["addr"].as_str().unwrap_or("?").to_string(),
+                        resource_type: resource["resource_type"].as_str().unwrap_or("").to_string(),
+                        action: PlanAction::from_streamed(
+                            event["change"]["action"].as_str().unwrap_or(""),
+                        ),
+                    });
+                }
+                Some("diagnostic") => {
+                    let diagnostic = &event["diagnostic"];
+                    summary.diagnostics.push(format!(
+                        "{}: {}",
+                        diagnostic["severity"].as_str().unwrap_or("warning"),
+                        diagnostic["summary"].as_str().unwrap_or("")
+                    ));
+                }
+                _ => {}
+            }
+        }
+        summary.changes.retain(|c| c.action != PlanAction::Noop);
+        summary
+    }
+
+    /// Parse the document printed by `terraform show -json <planfile>`
+    pub fn from_show_json(output: &str) -> Result<Self, TerraformError> {
+        let plan: Value =
+            serde_json::from_str(output).map_err(|e| TerraformError::InvalidPlan(e.to_string()))?;
+        let resource_changes = plan["resource_changes"]
+            .as_array()
+            .ok_or_else(|| TerraformError::InvalidPlan("missing resource_changes".to_string()))?;
+
+        let changes = resource_changes
+            .iter()
+            .map(|rc| {
+                let actions: Vec<&str> = rc["change"]["actions"]
+                    .as_array()
+                    .map(|a| a.iter().filter_map(Value::as_str).collect())
+                    .unwrap_or_default();
+                PlannedChange {
+                    address: rc["address"].as_str().unwrap_or("?").to_string(),
+                    resource_type: rc["type"].as_str().unwrap_or("").to_string(),
+                    action: PlanAction::from_actions(&actions),
+                }
+            })
+            .filter(|c| c.action != PlanAction::Noop)
+            .collect();
+
+        Ok(PlanSummary {
+            changes,
+            diagnostics: Vec::new(),
+        })
+    }
+
+    pub fn count(&self, action: PlanAction) -> usize {
+        self.changes.iter().filter(|c| c.action == action).count()
+    }
+
+    pub fn max_risk(&self) -> PlanRisk {
+        self.changes
+            .iter()
+            .map(PlannedChange::risk)
+            .max()
+            .unwrap_or(PlanRisk::Low)
+    }
+
+    /// Changes whose address or type mentions a word of the question
+    pub fn relevant_to(&self, question: &str) -> Vec<&PlannedChange> {
+        let words: Vec<String> = question
+            .split(|c: char| !c.is_alphanumeric() && c != '_')
+            .filter(|w| w.len() > 2)
+            .map(str::to_lowercase)
+            .collect();
+        self.changes
+            .iter()
+            .filter(|c| {
+                let haystack = format!("{} {}", c.address, c.resource_type).to_lowercase();
+                words.iter().any(|w| haystack.contains(w.as_str()))
+            })
+            .collect()
+    }
+
+    /// Markdown summary: counts, risky changes first, then everything else
+    pub fn render(&self, question: Option<&str>) -> String {
+        let mut out = format!(
+            "## Terraform plan {}\n\n{} to add, {} to change, {} to replace, {} to destroy\n",
+            self.max_risk().icon(),
+            self.count(PlanAction::Create),
+            self.count(PlanAction::Update),
+            self.count(PlanAction::Replace),
+            self.count(PlanAction::Delete),
+        );
+        if self.changes.is_empty() {
+            out.push_str("\nNo changes. Infrastructure matches the configuration.\n");
+        }
+
+        let mut changes: Vec<&PlannedChange> = self.changes.iter().collect();
+        changes.sort_by_key(|c| std::cmp::Reverse(c.risk()));
+        let risky: Vec<&&PlannedChange> = changes
+            .iter()
+            .filter(|c| c.risk() > PlanRisk::Low)
+            .collect();
+        if !risky.is_empty() {
+            out.push_str("\n### Needs review\n");
+            for change in risky {
+                out.push_str(&format!(
+                    "- {} `{}` {} ({:?})\n",
+                    change.risk().icon(),
+                    change.action.symbol(),
+                    change.address,
+                    change.action
+                ));
+            }
+        }
+
+        let rest: Vec<&&PlannedChange> = changes
+            .iter()
+            .filter(|c| c.risk() == PlanRisk::Low)
+            .collect();
+        if !rest.is_empty() {
+            out.push_str("\n### Other changes\n");
+            for change in rest {
+                out.push_str(&format!(
+                    "- `{}` {}\n",
+                    change.action.symbol(),
+                    change.address
+                ));
+            }
+        }
+
+        if !self.diagnostics.is_empty() {
+            out.push_str("\n### Diagnostics\n");
+            for diagnostic in &self.diagnostics {
+                out.push_str(&format!("- {}\n", diagnostic));
+            }
+        }
+
+        if let Some(question) = question.filter(|q| !q.trim().is_empty()) {
+            out.push_str(&format!("\n### Relevant to: {}\n", question.trim()));
+            let relevant = self.relevant_to(question);
+            if relevant.is_empty() {
+                out.push_str("- No resource matches the question directly\n");
+            }
+            for change in relevant {
+                out.push_str(&format!("- {:?} {}\n", change.action, change.address));
+            }
+        }
+
+        out.push_str("\n_Plan only: nothing was applied._");
+        out
+    }
+}
+
+/// Arguments for the terraform plan tool
+#[derive(Debug, Clone, Default, Deserialize, Serialize, JsonSchema)]
+pub struct TerraformPlanArgs {
+    /// Terraform working directory (defaults to the project directory)
+    #[serde(default)]
+    pub dir: Option<String>,
+    /// Saved plan to read instead of planning: a binary plan file or its `show -json` output
+    #[serde(default)]
+    pub plan_file: Option<String>,
+    /// Question about the plan; matching resources are listed separately
+    #[serde(default)]
+    pub question: Option<String>,
+}
+
+/// Terraform plan summarizer tool
+#[derive(Debug, Clone, Default)]
+pub struct TerraformPlanTool;
+
+impl TerraformPlanTool {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Produce the plan summary, planning only when no saved plan is given
+    pub async fn summarize(
+        &self,
+        args: &TerraformPlanArgs,
+        base_dir: &Path,
+    ) -> Result<PlanSummary, TerraformError> {
+        let dir = args
+            .dir
+            .as_ref()
+            .map(|d| base_dir.join(d))
+            .unwrap_or_else(|| base_dir.to_path_buf());
+
+        match &args.plan_file {
+            Some(plan_file) => {
+                let path = dir.join(plan_file);
+                if path.extension().is_some_and(|ext| ext == "json") {
+                    let content = tokio::fs::read_to_string(&path).await?;
+                    PlanSummary::from_show_json(&content)
+                        .or_else(|_| Ok(PlanSummary::from_streamed_json(&content)))
+                } else {
+                    let output =
+                        run_terraform(&dir, &["show", "-json", &path.to_string_lossy()]).await?;
+                    PlanSummary::from_show_json(&output)
+                }
+            }
+            None => {
+                let output = run_terraform(&dir, &["plan", "-json", "-input=false"]).await?;
+                let summary = PlanSummary::from_streamed_json(&output);
+                if summary.changes.is_empty()
+                    && summary.diagnostics.iter().any(|d| d.starts_with("error"))
+                {
+                    return Err(TerraformError::PlanFailed(summary.diagnostics.join("; ")));
+                }
+                Ok(summary)
+            }
+        }
+    }
+}
+
+/// Run a read-only terraform subcommand and return its stdout
+async fn run_terraform(dir: &Path, args: &[&str]) -> Result<String, TerraformError> {
+    let output = tokio::time::timeout(
+        Duration::from_secs(PLAN_TIMEOUT_SECS),
+        output_in_group(
+            Command::new("terraform")
+                .args(args)
+                .current_dir(dir)
+                .env("TF_IN_AUTOMATION", "1"),
+        ),
+    )
+    .await
+    .map_err(|_| TerraformError::Timeout(PLAN_TIMEOUT_SECS))??;
+
+    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+    // `plan -json` reports errors as diagnostics on stdout; keep them for the summary
+    if !output.status.success() && !stdout.contains("\"type\":\"diagnostic\"") {
+        return Err(TerraformError::PlanFailed(
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ));
+    }
+    Ok(stdout)
+}
+
+impl Tool for TerraformPlanTool {
+    const NAME: &'static str = "terraform_plan";
+
+    type Args = TerraformPlanArgs;
+    type Output = String;
+    type Error = TerraformError;
+
+    async fn definition(&self, _prompt: String) -> rig::completion::ToolDefinition {
+        rig::completion::ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: "Run `terraform plan -json` (or read a saved plan) and summarize the \
+                          resources to add, change, replace and destroy, highlighting risky \
+                          changes. Never applies anything."
+                .to_string(),
+            parameters: serde_json::to_value(schemars::schema_for!(TerraformPlanArgs))
+                .unwrap_or_default(),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let summary = self.summarize(&args, Path::new(".")).await?;
+        Ok(summary.render(args.question.as_deref()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const STREAMED: &str = r#"{"@level":"info","type":"version","terraform":"1.9.0"}
+{"@level":"info","type":"planned_change","change":{"resource":{"addr":"aws_instance.web","resource_type":"aws_instance"},"action":"create"}}
+{"@level":"info","type":"planned_change","change":{"resource":{"addr":"aws_db_instance.main","resource_type":"aws_db_instance"},"action":"replace"}}
+{"@level":"info","type":"planned_change","change":{"resource":{"addr":"aws_iam_role.app","resource_type":"aws_iam_role"},"action":"update"}}
+{"@level":"warn","type":"diagnostic","diagnostic":{"severity":"warning","summary":"Deprecated attribute"}}
+{"@level":"info","type":"change_summary","changes":{"add":2,"change":1,"remove":1}}"#;
+
+    #[test]
+    fn test_streamed_plan() {
+        let summary = PlanSummary::from_streamed_json(STREAMED);
+        assert_eq!(summary.changes.len(), 3);
+        assert_eq!(summary.count(PlanAction::Replace), 1);
+        assert_eq!(summary.changes[1].risk(), PlanRisk::High);
+        assert_eq!(summary.changes[2].risk(), PlanRisk::Medium);
+        assert_eq!(summary.changes[0].risk(), PlanRisk::Low);
+        assert_eq!(summary.max_risk(), PlanRisk::High);
+        assert_eq!(summary.diagnostics, vec!["warning: Deprecated attribute"]);
+
+        let rendered = summary.render(Some("is the db_instance safe?"));
+        assert!(rendered.contains("1 to add, 1 to change, 1 to replace, 0 to destroy"));
+        assert!(rendered.contains("🔴 `-/+` aws_db_instance.main"));
+        assert!(rendered.contains("- Replace aws_db_instance.main"));
+        assert!(rendered.contains("nothing was applied"));
+    }
+
+    #[test]
+    fn test_show_json_plan() {
+        let show = r#"{"format_version":"1.2","resource_changes":[
+            {"address":"aws_s3_bucket.logs","type":"aws_s3_bucket","change":{"actions":["delete"]}},
+            {"address":"aws_instance.web","type":"aws_instance","change":{"actions":["delete","create"]}},
+            {"address":"aws_vpc.main","type":"aws_vpc","change":{"actions":["no-op"]}}
+        ]}"#;
+        let summary = PlanSummary::from_show_json(show).unwrap();
+        assert_eq!(summary.changes.len(), 2);
+        assert_eq!(summary.changes[0].action, PlanAction::Delete);
+        assert_eq!(summary.changes[1].action, PlanAction::Replace);
+        assert!(PlanSummary::from_show_json("{}").is_err());
+    }
+}
@@ -4,6 +4,7 @@ use serde::{Deserialize, Serialize};
 use std::path::{Path, PathBuf};
 use std::process::Stdio;
 use tokio::process::Command;
+use super::command::output_in_group;
 
 /// Test framework
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -171,8 +172,7 @@ impl TestRunnerTool {
         cmd.stderr(Stdio::piped());
 
         let start = std::time::Instant::now();
-        let output = cmd
-            .output()
+        let output = output_in_group(&mut cmd)
             .await
             .map_err(|e| TestError::ExecutionError(e.to_string()))?;
         let duration_ms = start.elapsed().as_millis() as u64;
@@ -213,8 +213,7 @@ impl TestRunnerTool {
         cmd.stderr(Stdio::piped());
 
         let start = std::time::Instant::now();
-        let output = cmd
-            .output()
+        let output = output_in_group(&mut cmd)
             .await
             .map_err(|e| TestError::ExecutionError(e.to_string()))?;
         let duration_ms = start.elapsed().as_millis() as u64;
@@ -257,8 +256,7 @@ impl TestRunnerTool {
         cmd.stderr(Stdio::piped());
 
         let start = std::time::Instant::now();
-        let output = cmd
-            .output()
+        let output = output_in_group(&mut cmd)
             .await
             .map_err(|e| TestError::ExecutionError(e.to_string()))?;
         let duration_ms = start.elapsed().as_millis() as u64;
@@ -290,8 +288,7 @@ impl TestRunnerTool {
         cmd.stderr(Stdio::piped());
 
         let start = std::time::Instant::now();
-        let output = cmd
-            .output()
+        let output = output_in_group(&mut cmd)
             .await
             .map_err(|e| TestError::ExecutionError(e.to_string()))?;
         let duration_ms = start.elapsed().as_millis() as u64;
@@ -339,8 +336,7 @@ impl TestRunnerTool {
         cmd.stderr(Stdio::piped());
 
         let start = std::time::Instant::now();
-        let output = cmd
-            .output()
+        let output = output_in_group(&mut cmd)
             .await
             .map_err(|e| TestError::ExecutionError(e.to_string()))?;
         let duration_ms = start.elapsed().as_millis() as u64;
@@ -371,8 +367,7 @@ impl TestRunnerTool {
         cmd.stderr(Stdio::piped());
 
         let start = std::time::Instant::now();
-        let output = cmd
-            .output()
+        let output = output_in_group(&mut cmd)
             .await
             .map_err(|e| TestError::ExecutionError(e.to_string()))?;
         let duration_ms = start.elapsed().as_millis() as u64;
@@ -414,8 +409,7 @@ impl TestRunnerTool {
         cmd.stderr(Stdio::piped());
 
         let start = std::time::Instant::now();
-        let output = cmd
-            .output()
+        let output = output_in_group(&mut cmd)
             .await
             .map_err(|e| TestError::ExecutionError(e.to_string()))?;
         let duration_ms = start.elapsed().as_millis() as u64;
@@ -460,8 +454,7 @@ impl TestRunnerTool {
         cmd.stderr(Stdio::piped());
 
         let start = std::time::Instant::now();
-        let output = cmd
-            .output()
+        let output = output_in_group(&mut cmd)
             .await
             .map_err(|e| TestError::ExecutionError(e.to_string()))?;
         let duration_ms = start.elapsed().as_millis() as u64;
@@ -0,0 +1,494 @@
+//! Summarized directory trees
+//!
+//! Listing the root of a big repository entry by entry floods the context.
+//! A [`TreeSummary`] reports each entry of a directory with its file count,
+//! size and languages instead, and any subdirectory can be expanded by
+//! summarizing it in turn. [`TreeSummaries`] caches them in memory and in the
+//! session database, and a file watcher drops a summary as soon as something
+//! below it changes.
+
+use super::filesystem::FileSystemError;
+use super::indexer::{detect_language, format_size};
+use super::search::ALWAYS_IGNORED;
+use crate::db::Database;
+use crate::log_warn;
+use ignore::WalkBuilder;
+use notify::event::ModifyKind;
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::path::{Component, Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+/// Languages shown per entry
+const TOP_LANGUAGES: usize = 3;
+
+/// One entry of a summarized directory
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TreeEntry {
+    pub name: String,
+    pub is_dir: bool,
+    /// Files below this entry (1 for a file)
+    pub files: usize,
+    pub bytes: u64,
+    /// Files per language, most common first
+    pub languages: Vec<(String, usize)>,
+}
+
+/// File counts, sizes and languages of a directory and of each of its entries
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TreeSummary {
+    pub path: PathBuf,
+    pub files: usize,
+    pub bytes: u64,
+    pub languages: Vec<(String, usize)>,
+    /// Directories first, then files, by name
+    pub entries: Vec<TreeEntry>,
+}
+
+impl TreeSummary {
+    /// Walk `path` (honouring ignore files) and aggregate per top-level entry
+    pub fn compute(path: &Path) -> Result<Self, FileSystemError> {
+        if !path.is_dir() {
+            return Err(FileSystemError::InvalidPath(format!(
+                "{} is not a directory",
+                path.display()
+            )));
+        }
+
+        let mut entries: HashMap<String, (TreeEntry, HashMap<String, usize>)> = HashMap::new();
+        let mut languages: HashMap<String, usize> = HashMap::new();
+
+        for entry in walk(path) {
+            let Some(file_type) = entry.file_type() else { continue };
+            let Some(Component::Normal(top)) = entry
+                .path()
+                .strip_prefix(path)
+                .ok()
+                .and_then(|relative| relative.components().next())
+            else {
+                continue;
+            };
+            let (stats, entry_languages) = entries
+                .entry(top.to_string_lossy().to_string())
+                .or_insert_with(|| {
+                    let stats = TreeEntry {
+                        name: top.to_string_lossy().to_string(),
+                        is_dir: entry.depth() > 1 || file_type.is_dir(),
+                        files: 0,
+                        bytes: 0,
+                        languages: Vec::new(),
+                    };
+                    (stats, HashMap::new())
+                });
+            if !file_type.is_file() {
+                continue;
+            }
+
+            stats.files += 1;
+            stats.bytes += entry.metadata().map(|m| m.len()).unwrap_or(0);
+            if let Some(language) = detect_language(entry.path()) {
+                *entry_languages.entry(language.clone()).or_default() += 1;
+                *languages.entry(language).or_default() += 1;
+            }
+        }
+
+        let mut entries: Vec<TreeEntry> = entries
+            .into_values()
+            .map(|(mut stats, entry_languages)| {
+                stats.languages = ranked(entry_languages);
+                stats
+            })
+            .collect();
+        entries.sort_by(|a, b| b.is_dir.cmp(&a.is_dir).then_with(|| a.name.cmp(&b.name)));
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            files: entries.iter().map(|e| e.files).sum(),
+            bytes: entries.iter().map(|e| e.bytes).sum(),
+            languages: ranked(languages),
+            entries,
+        })
+    }
+
+    /// Human-readable summary with paths relative to `root`
+    pub fn format(&self, root: &Path) -> String {
+        let relative = self
+            .path
+            .strip_prefix(root)
+            .ok()
+            .filter(|p| !p.as_os_str().is_empty())
+            .map_or_else(|| ".".to_string(), |p| p.display().to_string());
+
+        let mut output = format!(
+            "{}/ — {} files, {}{}\n\n",
+            relative,
+            self.files,
+            format_size(self.bytes),
+            format_languages(&self.languages)
+        );
+        for entry in &self.entries {
+            if entry.is_dir {
+                output.push_str(&format!(
+                    "📁 {}/ — {} files, {}{}\n",
+                    entry.name,
+                    entry.files,
+                    format_size(entry.bytes),
+                    format_languages(&entry.languages)
+                ));
+            } else {
+                output.push_str(&format!("📄 {} — {}\n", entry.name, format_size(entry.bytes)));
+            }
+        }
+        if let Some(dir) = self.entries.iter().find(|e| e.is_dir) {
+            let example = if relative == "." {
+                dir.name.clone()
+            } else {
+                format!("{}/{}", relative, dir.name)
+            };
+            output.push_str(&format!(
+                "\nExpand a directory with list_directory(path=\"{}\", summary=true); \
+                 summary=false lists every entry.\n",
+                example
+            ));
+        }
+        output
+    }
+}
+
+/// Counts sorted by frequency, then name
+fn ranked(counts: HashMap<String, usize>) -> Vec<(String, usize)> {
+    let mut ranked: Vec<(String, usize)> = counts.into_iter().collect();
+    ranked.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    ranked
+}
+
+fn format_languages(languages: &[(String, usize)]) -> String {
+    if languages.is_empty() {
+        return String::new();
+    }
+    let shown: Vec<String> = languages
+        .iter()
+        .take(TOP_LANGUAGES)
+        .map(|(language, files)| format!("{} {}", language, files))
+        .collect();
+    format!(" ({})", shown.join(", "))
+}
+
+/// Entries below `path` a summary counts: ignore files are honoured and the
+/// always-ignored directories skipped
+fn walk(path: &Path) -> impl Iterator<Item = ignore::DirEntry> {
+    WalkBuilder::new(path)
+        .require_git(false)
+        .filter_entry(|entry| {
+            !ALWAYS_IGNORED
+                .iter()
+                .any(|dir| entry.file_name() == std::ffi::OsStr::new(dir))
+        })
+        .build()
+        .filter_map(|e| e.ok())
+}
+
+/// Relative paths, sizes and modification times of everything a summary of
+/// `path` counts. Used to tell whether a summary stored by an earlier run is
+/// still current; within a run the watcher takes care of invalidation.
+fn fingerprint(path: &Path) -> std::io::Result<String> {
+    let mut entries: Vec<(PathBuf, u64, u128)> = walk(path)
+        .filter_map(|entry| {
+            let metadata = entry.metadata().ok()?;
+            let modified = metadata
+                .modified()
+                .ok()?
+                .duration_since(std::time::UNIX_EPOCH)
+                .ok()?
+                .as_nanos();
+            let relative = entry.path().strip_prefix(path).ok()?.to_path_buf();
+            Some((relative, metadata.len(), modified))
+        })
+        .collect();
+    entries.sort();
+
+    let mut hasher = Sha256::new();
+    for (relative, len, modified) in entries {
+        hasher.update(format!("{}\0{}\0{}\n", relative.display(), len, modified));
+    }
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// Paths inside always-ignored directories never invalidate a summary
+fn is_ignored_change(path: &Path) -> bool {
+    path.components().any(|c| match c {
+        Component::Normal(name) => ALWAYS_IGNORED.iter().any(|dir| name == std::ffi::OsStr::new(dir)),
+        _ => false,
+    })
+}
+
+struct WatchedRoot {
+    root: PathBuf,
+    watcher: RecommendedWatcher,
+    /// Directories created since the last `watch`; the watcher cannot add
+    /// them from its own callback
+    created: Arc<Mutex<Vec<PathBuf>>>,
+}
+
+impl WatchedRoot {
+    /// Watch `dir` and the directories below it a summary counts, so ignored
+    /// trees like `target` or `node_modules` are left alone
+    fn add(&mut self, dir: &Path) -> notify::Result<()> {
+        for entry in walk(dir).filter(|e| e.file_type().is_some_and(|t| t.is_dir())) {
+            self.watcher.watch(entry.path(), RecursiveMode::NonRecursive)?;
+        }
+        Ok(())
+    }
+}
+
+/// Cache of tree summaries, invalidated by a file watcher
+#[derive(Clone, Default)]
+pub struct TreeSummaries {
+    /// `None` marks a summary invalidated during this run, which must not be
+    /// reloaded from the database
+    memory: Arc<Mutex<HashMap<PathBuf, Option<TreeSummary>>>>,
+    db: Option<Database>,
+    watched: Arc<Mutex<Option<WatchedRoot>>>,
+}
+
+impl TreeSummaries {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Persist summaries in the session database
+    pub fn with_database(mut self, db: Database) -> Self {
+        self.db = Some(db);
+        self
+    }
+
+    /// Summary of `path`, from the cache when nothing below it changed
+    pub async fn summarize(&self, path: &Path) -> Result<TreeSummary, FileSystemError> {
+        let path = path.canonicalize()?;
+        self.watch(&path);
+
+        let cached = self.memory.lock().unwrap_or_else(|e| e.into_inner()).get(&path).cloned();
+        let reload = match cached {
+            Some(Some(summary)) => return Ok(summary),
+            Some(None) => false,
+            None => true,
+        };
+
+        let key = path.to_string_lossy().to_string();
+        let fingerprint = {
+            let path = path.clone();
+            tokio::task::spawn_blocking(move || fingerprint(&path))
+                .await
+                .map_err(|e| FileSystemError::Io(std::io::Error::other(e)))??
+        };
+        if let (true, Some(db)) = (reload, &self.db) {
+            if let Ok(Some((stored, json))) = db.get_tree_summary(&key).await {
+                if let Some(summary) = (stored == fingerprint)
+                    .then(|| serde_json::from_str::<TreeSummary>(&json).ok())
+                    .flatten()
+                {
+                    self.remember(&path, &summary);
+                    return Ok(summary);
+                }
+            }
+        }
+
+        let summary = {
+            let path = path.clone();
+            tokio::task::spawn_blocking(move || TreeSummary::compute(&path))
+                .await
+                .map_err(|e| FileSystemError::Io(std::io::Error::other(e)))??
+        };
+        self.remember(&path, &summary);
+        if let Some(db) = &self.db {
+            let json = serde_json::to_string(&summary).unwrap_or_default();
+            if let Err(e) = db.save_tree_summary(&key, &fingerprint, &json).await {
+                log_warn!("[TreeSummary] Failed to cache summary of {}: {}", key, e);
+            }
+        }
+        Ok(summary)
+    }
+
+    /// Forget `moved`, renamed or deleted, with every summary below it, and
+    /// drop the summaries above it
+    pub fn evict(&self, moved: &Path) {
+        self.memory
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .retain(|dir, _| !dir.starts_with(moved));
+        self.invalidate(moved);
+    }
+
+    /// Drop the summaries of `changed` and of every directory above it
+    pub fn invalidate(&self, changed: &Path) {
+        {
+            let mut memory = self.memory.lock().unwrap_or_else(|e| e.into_inner());
+            for (dir, summary) in memory.iter_mut() {
+                if changed.starts_with(dir) {
+                    *summary = None;
+                }
+            }
+        }
+
+        if let (Some(db), Ok(handle)) = (self.db.clone(), tokio::runtime::Handle::try_current()) {
+            let changed = changed.to_string_lossy().to_string();
+            handle.spawn(async move {
+                if let Err(e) = db.invalidate_tree_summaries(&changed).await {
+                    log_warn!("[TreeSummary] Failed to invalidate {}: {}", changed, e);
+                }
+            });
+        }
+    }
+
+    fn remember(&self, path: &Path, summary: &TreeSummary) {
+        self.memory
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(path.to_path_buf(), Some(summary.clone()));
+    }
+
+    /// Make sure a watcher covers `path`, moving it there when it does not
+    fn watch(&self, path: &Path) {
+        let mut watched = self.watched.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(current) = watched.as_mut().filter(|w| path.starts_with(&w.root)) {
+            let created = std::mem::take(&mut *current.created.lock().unwrap_or_else(|e| e.into_inner()));
+            for dir in created {
+                if let Err(e) = current.add(&dir) {
+                    log_warn!("[TreeSummary] Cannot watch {}: {}", dir.display(), e);
+                }
+            }
+            return;
+        }
+        let Ok(handle) = tokio::runtime::Handle::try_current() else {
+            return;
+        };
+
+        let summaries = self.clone_without_watcher();
+        let created: Arc<Mutex<Vec<PathBuf>>> = Arc::default();
+        let pending = created.clone();
+        let watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+            let Ok(event) = event else { return };
+            if event.kind.is_access() {
+                return;
+            }
+            let _guard = handle.enter();
+            let renamed = matches!(event.kind, EventKind::Modify(ModifyKind::Name(_)));
+            let created = renamed || matches!(event.kind, EventKind::Create(_));
+            let moved = renamed || matches!(event.kind, EventKind::Remove(_));
+            for changed in event.paths.iter().filter(|p| !is_ignored_change(p)) {
+                if created && changed.is_dir() {
+                    pending.lock().unwrap_or_else(|e| e.into_inner()).push(changed.clone());
+                }
+                if moved {
+                    summaries.evict(changed);
+                } else {
+                    summaries.invalidate(changed);
+                }
+            }
+        });
+
+        let root = watcher.and_then(|watcher| {
+            let mut root = WatchedRoot { root: path.to_path_buf(), watcher, created };
+            root.add(path)?;
+            Ok(root)
+        });
+        match root {
+            Ok(root) => *watched = Some(root),
+            Err(e) => log_warn!("[TreeSummary] Cannot watch {}: {}", path.display(), e),
+        }
+    }
+
+    /// Handle for the watcher callback; holding the watcher itself would keep
+    /// it alive forever
+    fn clone_without_watcher(&self) -> Self {
+        Self {
+            memory: self.memory.clone(),
+            db: self.db.clone(),
+            watched: Arc::default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn project() -> tempfile::TempDir {
+        let dir = tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("src/nested")).unwrap();
+        std::fs::create_dir_all(dir.path().join("target/debug")).unwrap();
+        std::fs::write(dir.path().join("src/main.rs"), "fn main() {}\n").unwrap();
+        std::fs::write(dir.path().join("src/nested/lib.rs"), "pub fn f() {}\n").unwrap();
+        std::fs::write(dir.path().join("src/nested/notes.md"), "# notes\n").unwrap();
+        std::fs::write(dir.path().join("target/debug/app"), vec![0u8; 4096]).unwrap();
+        std::fs::write(dir.path().join("Cargo.toml"), "[package]\n").unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_compute_aggregates_top_level_entries() {
+        let dir = project();
+        let summary = TreeSummary::compute(dir.path()).unwrap();
+
+        assert_eq!(summary.files, 4);
+        let names: Vec<&str> = summary.entries.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, vec!["src", "Cargo.toml"]);
+
+        let src = &summary.entries[0];
+        assert!(src.is_dir);
+        assert_eq!(src.files, 3);
+        assert_eq!(src.languages[0], ("Rust".to_string(), 2));
+        assert_eq!(summary.languages[0], ("Rust".to_string(), 3));
+
+        let text = summary.format(dir.path());
+        assert!(text.contains("📁 src/ — 3 files"));
+        assert!(text.contains("list_directory(path=\"src\", summary=true)"));
+    }
+
+    #[tokio::test]
+    async fn test_summaries_are_cached_until_invalidated() {
+        let dir = project();
+        let summaries = TreeSummaries::new();
+        let root = dir.path().canonicalize().unwrap();
+
+        assert_eq!(summaries.summarize(&root).await.unwrap().files, 4);
+        summaries.invalidate(&root.join("src/nested/lib.rs"));
+        std::fs::write(root.join("src/nested/extra.rs"), "").unwrap();
+
+        assert_eq!(summaries.summarize(&root).await.unwrap().files, 5);
+
+        summaries.summarize(&root.join("src/nested")).await.unwrap();
+        summaries.evict(&root.join("src"));
+        let memory = summaries.memory.lock().unwrap();
+        assert!(memory.keys().all(|dir| !dir.starts_with(root.join("src"))));
+        assert_eq!(memory.get(&root), Some(&None));
+    }
+
+    #[test]
+    fn test_fingerprint_covers_nested_files_but_not_ignored_ones() {
+        let dir = project();
+        let before = fingerprint(dir.path()).unwrap();
+        std::fs::write(dir.path().join("target/debug/app"), vec![0u8; 8192]).unwrap();
+        assert_eq!(fingerprint(dir.path()).unwrap(), before);
+        std::fs::write(dir.path().join("src/nested/lib.rs"), "pub fn f() { g() }\n").unwrap();
+        assert_ne!(fingerprint(dir.path()).unwrap(), before);
+    }
+
+    #[tokio::test]
+    async fn test_database_copy_is_reused_while_fingerprint_matches() {
+        let dir = project();
+        let root = dir.path().canonicalize().unwrap();
+        let db = Database::in_memory().await.unwrap();
+
+        let first = TreeSummaries::new().with_database(db.clone()).summarize(&root).await.unwrap();
+        let fresh = TreeSummaries::new().with_database(db.clone());
+        assert_eq!(fresh.summarize(&root).await.unwrap(), first);
+
+        std::fs::write(root.join("README.md"), "# readme\n").unwrap();
+        let after_change = TreeSummaries::new().with_database(db).summarize(&root).await.unwrap();
+        assert_eq!(after_change.files, first.files + 1);
+    }
+}
@@ -0,0 +1,408 @@
+//! WASM plugin runtime
+//!
+//! Custom tools compiled to WebAssembly (`wasm32-wasip1`) run in a wasmtime
+//! sandbox, so untrusted or cross-platform plugins can be added without
+//! giving them the machine. A plugin is two files in a plugin directory:
+//!
+//! - `<name>.wasm`: a WASI command that reads the call arguments as JSON on
+//!   stdin and writes its result on stdout; a non-zero exit is an error and
+//!   its stderr the message
+//! - `<name>.json`: `{"name", "description", "parameters"}`, the parameters
+//!   being a JSON Schema as for any other tool
+//!
+//! Plugins start with no capabilities: no files, no environment, no clock
+//! beyond WASI's and no network (WASI preview 1 has no sockets). Directories
+//! are preopened only when the config grants them, read-only unless listed
+//! under `write`, and every call runs with a fuel and memory budget. A plugin
+//! with a `write` grant is gated like the file tools: Build mode only, outside
+//! protected and read-only paths, and after the user approves the call.
+//!
+//! `plugins/template` is a cargo-generate template for authors and
+//! `plugins/examples/word_count` a complete plugin.
+
+use rig::tool::Tool;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use thiserror::Error;
+use wasmtime::{Config, Engine, Linker, Module, Store, StoreLimits, StoreLimitsBuilder, Trap};
+use wasmtime_wasi::pipe::{MemoryInputPipe, MemoryOutputPipe};
+use wasmtime_wasi::preview1::{self, WasiP1Ctx};
+use wasmtime_wasi::{DirPerms, FilePerms, I32Exit, WasiCtxBuilder};
+
+/// Bytes of stdout and stderr kept from a call
+const MAX_OUTPUT_BYTES: usize = 1024 * 1024;
+
+fn default_fuel() -> u64 {
+    5_000_000_000
+}
+
+fn default_memory_mb() -> u64 {
+    64
+}
+
+#[derive(Error, Debug)]
+pub enum WasmPluginError {
+    #[error("Could not read plugin {0}: {1}")]
+    Io(PathBuf, std::io::Error),
+    #[error("Invalid plugin manifest {0}: {1}")]
+    Manifest(PathBuf, String),
+    #[error("Could not compile plugin {0}: {1}")]
+    Compile(PathBuf, String),
+    #[error("Plugin name '{0}' is taken by another tool")]
+    NameTaken(String),
+    #[error("Could not grant {0} to plugin '{1}': {2}")]
+    Grant(String, String, String),
+    #[error("Plugin '{0}' ran out of fuel (raise `fuel` in its grant)")]
+    OutOfFuel(String),
+    #[error("Plugin '{0}' exited with code {1}: {2}")]
+    Exit(String, i32, String),
+    #[error("Plugin '{0}' trapped: {1}")]
+    Trap(String, String),
+    #[error("Plugin task failed: {0}")]
+    Task(String),
+}
+
+/// What one plugin may use; a plugin without a grant gets nothing
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PluginGrant {
+    /// Directories preopened read-only, absolute or relative to the working directory
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub read: Vec<String>,
+
+    /// Directories preopened read-write
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub write: Vec<String>,
+
+    /// Instructions a call may execute, as wasmtime fuel
+    #[serde(default = "default_fuel")]
+    pub fuel: u64,
+
+    /// Linear memory limit of a call
+    #[serde(default = "default_memory_mb")]
+    pub memory_mb: u64,
+}
+
+impl Default for PluginGrant {
+    fn default() -> Self {
+        Self {
+            read: Vec::new(),
+            write: Vec::new(),
+            fuel: default_fuel(),
+            memory_mb: default_memory_mb(),
+        }
+    }
+}
+
+/// Plugin directories and per-plugin grants
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PluginsConfig {
+    /// Directories searched for `<name>.wasm` besides `~/.config/neuro/plugins`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub dirs: Vec<String>,
+
+    /// Capabilities by plugin name
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub grants: HashMap<String, PluginGrant>,
+}
+
+/// `<name>.json` next to the module
+#[derive(Debug, Clone, Deserialize)]
+pub struct PluginManifest {
+    pub name: String,
+    pub description: String,
+    #[serde(default = "empty_object_schema")]
+    pub parameters: serde_json::Value,
+}
+
+fn empty_object_schema() -> serde_json::Value {
+    serde_json::json!({ "type": "object", "properties": {} })
+}
+
+struct PluginState {
+    wasi: WasiP1Ctx,
+    limits: StoreLimits,
+}
+
+/// A loaded plugin, exposed as a tool under its manifest name
+#[derive(Clone)]
+pub struct WasmPlugin {
+    manifest: PluginManifest,
+    engine: Engine,
+    module: Module,
+    linker: Arc<Linker<PluginState>>,
+    grant: PluginGrant,
+    working_dir: PathBuf,
+}
+
+impl WasmPlugin {
+    pub fn manifest(&self) -> &PluginManifest {
+        &self.manifest
+    }
+
+    pub fn grant(&self) -> &PluginGrant {
+        &self.grant
+    }
+
+    /// Directories the plugin may write to, resolved like its preopens
+    pub fn write_dirs(&self) -> Vec<PathBuf> {
+        self.grant.write.iter().map(|dir| self.working_dir.join(dir)).collect()
+    }
+
+    /// Run the module once with `input` on stdin
+    fn run(&self, input: &[u8]) -> Result<String, WasmPluginError> {
+        let name = &self.manifest.name;
+        let stdout = MemoryOutputPipe::new(MAX_OUTPUT_BYTES);
+        let stderr = MemoryOutputPipe::new(MAX_OUTPUT_BYTES);
+
+        let mut builder = WasiCtxBuilder::new();
+        builder
+            .stdin(MemoryInputPipe::new(input.to_vec()))
+            .stdout(stdout.clone())
+            .stderr(stderr.clone())
+            .arg(name);
+        for (dirs, dir_perms, file_perms) in [
+            (&self.grant.read, DirPerms::READ, FilePerms::READ),
+            (&self.grant.write, DirPerms::all(), FilePerms::all()),
+        ] {
+            for dir in dirs {
+                builder
+                    .preopened_dir(self.working_dir.join(dir), dir, dir_perms, file_perms)
+                    .map_err(|e| WasmPluginError::Grant(dir.clone(), name.clone(), e.to_string()))?;
+            }
+        }
+
+        let limits = StoreLimitsBuilder::new()
+            .memory_size(self.grant.memory_mb as usize * 1024 * 1024)
+            .build();
+        let mut store = Store::new(&self.engine, PluginState { wasi: builder.build_p1(), limits });
+        store.limiter(|state| &mut state.limits);
+        store
+            .set_fuel(self.grant.fuel)
+            .map_err(|e| WasmPluginError::Trap(name.clone(), e.to_string()))?;
+
+        let result = self
+            .linker
+            .instantiate(&mut store, &self.module)
+            .and_then(|instance| instance.get_typed_func::<(), ()>(&mut store, "_start"))
+            .and_then(|start| start.call(&mut store, ()));
+
+        let output = String::from_utf8_lossy(&stdout.contents()).trim_end().to_string();
+        let errors = String::from_utf8_lossy(&stderr.contents()).trim_end().to_string();
+        match result {
+            Ok(()) => Ok(output),
+            Err(e) => match (e.downcast_ref::<I32Exit>(), e.downcast_ref::<Trap>()) {
+                (Some(I32Exit(0)), _) => Ok(output),
+                (Some(I32Exit(code)), _) => Err(WasmPluginError::Exit(name.clone(), *code, errors)),
+                (None, Some(Trap::OutOfFuel)) => Err(WasmPluginError::OutOfFuel(name.clone())),
+                _ => Err(WasmPluginError::Trap(name.clone(), format!("{:#}", e))),
+            },
+        }
+    }
+}
+
+impl Tool for WasmPlugin {
+    const NAME: &'static str = "wasm_plugin";
+
+    type Args = serde_json::Value;
+    type Output = String;
+    type Error = WasmPluginError;
+
+    fn name(&self) -> String {
+        self.manifest.name.clone()
+    }
+
+    async fn definition(&self, _prompt: String) -> rig::completion::ToolDefinition {
+        rig::completion::ToolDefinition {
+            name: self.manifest.name.clone(),
+            description: self.manifest.description.clone(),
+            parameters: self.manifest.parameters.clone(),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let input = serde_json::to_vec(&args).unwrap_or_default();
+        // Wasmtime runs synchronously; fuel bounds how long the call blocks its thread
+        let plugin = self.clone();
+        tokio::task::spawn_blocking(move || plugin.run(&input))
+            .await
+            .map_err(|e| WasmPluginError::Task(e.to_string()))?
+    }
+}
+
+/// The loaded plugins
+#[derive(Default)]
+pub struct WasmPluginHost {
+    plugins: Vec<Arc<WasmPlugin>>,
+}
+
+impl WasmPluginHost {
+    /// Directories searched for plugins: the user's and the configured ones
+    pub fn plugin_dirs(config: &PluginsConfig, working_dir: &Path) -> Vec<PathBuf> {
+        let mut dirs: Vec<PathBuf> = crate::config::AppConfig::config_dir()
+            .map(|dir| dir.join("plugins"))
+            .into_iter()
+            .collect();
+        dirs.extend(config.dirs.iter().map(|dir| working_dir.join(dir)));
+        dirs
+    }
+
+    /// Load every plugin of the plugin directories; names in `reserved`
+    /// (the built-in tools) cannot be taken. Plugins that fail to load are
+    /// returned with their error and skipped.
+    pub fn load(
+        config: &PluginsConfig,
+        working_dir: &Path,
+        reserved: &[&str],
+    ) -> (Self, Vec<WasmPluginError>) {
+        let mut host = Self::default();
+        let mut errors = Vec::new();
+        let mut engine_config = Config::new();
+        engine_config.consume_fuel(true);
+        let engine = match Engine::new(&engine_config) {
+            Ok(engine) => engine,
+            Err(e) => {
+                errors.push(WasmPluginError::Task(e.to_string()));
+                return (host, errors);
+            }
+        };
+        let mut linker: Linker<PluginState> = Linker::new(&engine);
+        if let Err(e) = preview1::add_to_linker_sync(&mut linker, |state| &mut state.wasi) {
+            errors.push(WasmPluginError::Task(e.to_string()));
+            return (host, errors);
+        }
+        let linker = Arc::new(linker);
+
+        for dir in Self::plugin_dirs(config, working_dir) {
+            let Ok(entries) = std::fs::read_dir(&dir) else {
+                continue;
+            };
+            let mut modules: Vec<PathBuf> = entries
+                .filter_map(|entry| entry.ok().map(|e| e.path()))
+                .filter(|path| path.extension().is_some_and(|ext| ext == "wasm"))
+                .collect();
+            modules.sort();
+
+            for path in modules {
+                match host.load_one(&engine, &linker, &path, config, working_dir, reserved) {
+                    Ok(plugin) => host.plugins.push(Arc::new(plugin)),
+                    Err(e) => errors.push(e),
+                }
+            }
+        }
+        (host, errors)
+    }
+
+    fn load_one(
+        &self,
+        engine: &Engine,
+        linker: &Arc<Linker<PluginState>>,
+        path: &Path,
+        config: &PluginsConfig,
+        working_dir: &Path,
+        reserved: &[&str],
+    ) -> Result<WasmPlugin, WasmPluginError> {
+        let manifest_path = path.with_extension("json");
+        let text = std::fs::read_to_string(&manifest_path)
+            .map_err(|e| WasmPluginError::Io(manifest_path.clone(), e))?;
+        let manifest: PluginManifest = serde_json::from_str(&text)
+            .map_err(|e| WasmPluginError::Manifest(manifest_path.clone(), e.to_string()))?;
+        if manifest.name.is_empty()
+            || !manifest.name.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+        {
+            return Err(WasmPluginError::Manifest(
+                manifest_path,
+                format!("name '{}' must be lowercase letters, digits and '_'", manifest.name),
+            ));
+        }
+        if reserved.contains(&manifest.name.as_str()) || self.get(&manifest.name).is_some() {
+            return Err(WasmPluginError::NameTaken(manifest.name));
+        }
+
+        let module = Module::from_file(engine, path)
+            .map_err(|e| WasmPluginError::Compile(path.to_path_buf(), e.to_string()))?;
+        let grant = config.grants.get(&manifest.name).cloned().unwrap_or_default();
+        Ok(WasmPlugin {
+            manifest,
+            engine: engine.clone(),
+            module,
+            linker: linker.clone(),
+            grant,
+            working_dir: working_dir.to_path_buf(),
+        })
+    }
+
+    pub fn get(&self, name: &str) -> Option<Arc<WasmPlugin>> {
+        self.plugins.iter().find(|p| p.manifest.name == name).cloned()
+    }
+
+    pub fn plugins(&self) -> &[Arc<WasmPlugin>] {
+        &self.plugins
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.plugins.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const HELLO_WAT: &str = r#"(module
+      (import "wasi_snapshot_preview1" "fd_write" (func $fd_write (param i32 i32 i32 i32) (result i32)))
+      (memory (export "memory") 1)
+      (data (i32.const 16) "hello")
+      (func (export "_start")
+        (i32.store (i32.const 0) (i32.const 16))
+        (i32.store (i32.const 4) (i32.const 5))
+        (drop (call $fd_write (i32.const 1) (i32.const 0) (i32.const 1) (i32.const 8)))))"#;
+
+    const SPIN_WAT: &str = r#"(module
+      (memory (export "memory") 1)
+      (func (export "_start") (loop $spin (br $spin))))"#;
+
+    fn install(dir: &Path, name: &str, wat: &str) {
+        // Module::from_file also accepts the text format
+        std::fs::write(dir.join(format!("{}.wasm", name)), wat).unwrap();
+        std::fs::write(
+            dir.join(format!("{}.json", name)),
+            format!(r#"{{"name": "{}", "description": "test plugin"}}"#, name),
+        )
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_plugin_runs_as_a_tool() {
+        let dir = TempDir::new().unwrap();
+        install(dir.path(), "hello", HELLO_WAT);
+        install(dir.path(), "read_file", HELLO_WAT);
+        let config = PluginsConfig {
+            dirs: vec![dir.path().to_string_lossy().to_string()],
+            grants: HashMap::new(),
+        };
+
+        let (host, errors) = WasmPluginHost::load(&config, dir.path(), &["read_file"]);
+        assert!(matches!(errors.as_slice(), [WasmPluginError::NameTaken(name)] if name == "read_file"));
+        let plugin = host.get("hello").unwrap();
+        assert_eq!(plugin.definition(String::new()).await.name, "hello");
+        assert_eq!(plugin.call(serde_json::json!({})).await.unwrap(), "hello");
+    }
+
+    #[tokio::test]
+    async fn test_fuel_stops_runaway_plugins() {
+        let dir = TempDir::new().unwrap();
+        install(dir.path(), "spin", SPIN_WAT);
+        let mut config = PluginsConfig {
+            dirs: vec![dir.path().to_string_lossy().to_string()],
+            grants: HashMap::new(),
+        };
+        config.grants.insert("spin".to_string(), PluginGrant { fuel: 100_000, ..PluginGrant::default() });
+
+        let (host, _) = WasmPluginHost::load(&config, dir.path(), &[]);
+        let result = host.get("spin").unwrap().call(serde_json::json!({})).await;
+        assert!(matches!(result, Err(WasmPluginError::OutOfFuel(_))));
+    }
+}
@@ -0,0 +1,209 @@
+//! Accessibility hooks
+//!
+//! - `speak_command`: answers are piped, sentence by sentence as they stream,
+//!   to the stdin of a user command (a TTS engine such as `espeak` or `say`).
+//!   Code blocks and markdown markup are left out of what is spoken. The
+//!   command is split into program and arguments and run without a shell;
+//!   only the personal config may set it.
+//! - `high_visibility`: focused borders and selected items are drawn bold
+//!   and in reverse video instead of with a subtle color change.
+//! - `plain`: no box drawing around panels and ASCII separators, for screen
+//!   readers that would otherwise read every border character.
+
+use crate::log_warn;
+use serde::{Deserialize, Serialize};
+use std::process::Stdio;
+use tokio::io::AsyncWriteExt;
+use tokio::sync::mpsc;
+
+/// Accessibility settings of the TUI
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AccessibilityConfig {
+    /// Program and arguments that receive the answer text on stdin, e.g.
+    /// "espeak -s 160" or "say"; quoted as in a shell but not run by one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub speak_command: Option<String>,
+
+    /// Bold, reverse-video focus indicators
+    #[serde(default)]
+    pub high_visibility: bool,
+
+    /// Screen-reader friendly output without box drawing
+    #[serde(default)]
+    pub plain: bool,
+}
+
+/// Pipes the text of one answer to the speak command
+pub struct SpeechHook {
+    command: String,
+    pending: String,
+    in_code_block: bool,
+    sender: Option<mpsc::UnboundedSender<String>>,
+}
+
+impl SpeechHook {
+    pub fn new(command: impl Into<String>) -> Self {
+        Self {
+            command: command.into(),
+            pending: String::new(),
+            in_code_block: false,
+            sender: None,
+        }
+    }
+
+    /// Add streamed text; complete sentences are spoken right away
+    pub fn push(&mut self, text: &str) {
+        self.pending.push_str(text);
+        while let Some(end) = sentence_end(&self.pending) {
+            let sentence: String = self.pending.drain(..end).collect();
+            self.speak(&sentence);
+        }
+    }
+
+    /// Speak what is left of the answer and let the command exit
+    pub fn finish(&mut self) {
+        let rest = std::mem::take(&mut self.pending);
+        self.speak(&rest);
+        self.sender = None;
+        self.in_code_block = false;
+    }
+
+    /// Speak a whole answer that was not streamed
+    pub fn say(&mut self, text: &str) {
+        self.push(text);
+        self.finish();
+    }
+
+    fn speak(&mut self, text: &str) {
+        let mut spoken = String::new();
+        for line in text.split_inclusive('\n') {
+            if line.trim_start().starts_with("```") {
+                self.in_code_block = !self.in_code_block;
+                continue;
+            }
+            if !self.in_code_block {
+                spoken.push_str(line);
+            }
+        }
+        let spoken = speakable(&spoken);
+        if spoken.is_empty() {
+            return;
+        }
+        let sender = self.sender.get_or_insert_with(|| spawn_speaker(&self.command));
+        let _ = sender.send(spoken);
+    }
+}
+
+/// Start the speak command for one answer; it reads lines until the sender is dropped
+fn spawn_speaker(command: &str) -> mpsc::UnboundedSender<String> {
+    let (tx, mut rx) = mpsc::unbounded_channel::<String>();
+    let command = command.to_string();
+    tokio::spawn(async move {
+        let Some((program, args)) = speak_argv(&command) else {
+            log_warn!("⚠️ [A11Y] speak_command '{}' is not a valid command line", command);
+            return;
+        };
+        let child = tokio::process::Command::new(program)
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn();
+        let mut child = match child {
+            Ok(child) => child,
+            Err(e) => {
+                log_warn!("⚠️ [A11Y] speak_command '{}' failed to start: {}", command, e);
+                return;
+            }
+        };
+        let Some(mut stdin) = child.stdin.take() else {
+            return;
+        };
+        while let Some(text) = rx.recv().await {
+            if stdin.write_all(format!("{}\n", text).as_bytes()).await.is_err() {
+                break;
+            }
+        }
+        drop(stdin);
+        let _ = child.wait().await;
+    });
+    tx
+}
+
+/// Program and arguments of the speak command, split with shell quoting rules
+fn speak_argv(command: &str) -> Option<(String, Vec<String>)> {
+    let mut words = shell_words::split(command).ok()?.into_iter();
+    let program = words.next()?;
+    Some((program, words.collect()))
+}
+
+/// Byte index just past the first complete sentence or line of `text`
+fn sentence_end(text: &str) -> Option<usize> {
+    let bytes = text.as_bytes();
+    for (i, &b) in bytes.iter().enumerate() {
+        let next_is_space = bytes.get(i + 1).is_some_and(|c| c.is_ascii_whitespace());
+        if b == b'\n' || (matches!(b, b'.' | b'!' | b'?' | b':') && next_is_space) {
+            return Some(i + 1);
+        }
+    }
+    None
+}
+
+/// `text` without markdown markup, collapsed to one line
+pub fn speakable(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '*' | '_' | '`' | '#' | '>' | '|' => out.push(' '),
+            // [label](url) reads the label only
+            '[' => {
+                let label: String = chars.by_ref().take_while(|&c| c != ']').collect();
+                out.push_str(&label);
+                if chars.peek() == Some(&'(') {
+                    for c in chars.by_ref() {
+                        if c == ')' {
+                            break;
+                        }
+                    }
+                }
+            }
+            _ => out.push(c),
+        }
+    }
+    out.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_speakable_strips_markdown() {
+        assert_eq!(
+            speakable("## Result\n**Done**: see [the docs](https://docs.rs) and `cargo test`"),
+            "Result Done : see the docs and cargo test"
+        );
+    }
+
+    #[test]
+    fn test_sentences_split_while_streaming() {
+        let mut text = String::from("Version 1.2 is out. Next");
+        let end = sentence_end(&text).unwrap();
+        let first: String = text.drain(..end).collect();
+        assert_eq!(first, "Version 1.2 is out.");
+        assert_eq!(sentence_end(&text), None);
+    }
+
+    #[test]
+    fn test_speak_argv_has_no_shell() {
+        assert_eq!(
+            speak_argv("espeak -v 'en-us' -s 160"),
+            Some(("espeak".to_string(), vec!["-v".into(), "en-us".into(), "-s".into(), "160".into()]))
+        );
+        let (program, args) = speak_argv("say hi; rm -rf ~").unwrap();
+        assert_eq!((program.as_str(), args.len()), ("say", 4));
+        assert_eq!(speak_argv("  "), None);
+        assert_eq!(speak_argv("espeak 'unterminated"), None);
+    }
+}
@@ -0,0 +1,159 @@
+//! Tabs for parallel experiments (experimental)
+//!
+//! Tab 0 is the main chat; every experiment of `/try` gets a tab with its own
+//! messages and task queue. The messages of the visible tab live in the
+//! app's message list, so the chat view renders any tab unchanged; the others
+//! are parked here until the user switches to them.
+
+use super::modern_app::{DisplayMessage, MessageSender};
+use crate::agent::experiments::{spawn_runner, ExperimentSet, ExperimentUpdate};
+use crate::agent::translation::Translations;
+use crate::agent::RouterOrchestrator;
+use std::time::Instant;
+use tokio::sync::mpsc;
+
+/// An experiment's conversation and queue
+pub struct ExperimentTab {
+    pub label: String,
+    pub approach: String,
+    messages: Vec<DisplayMessage>,
+    queue: mpsc::UnboundedSender<String>,
+    /// Tasks queued or running
+    pub pending: usize,
+}
+
+/// Experiment tabs next to the main chat
+pub struct ExperimentTabs {
+    pub set: ExperimentSet,
+    pub tabs: Vec<ExperimentTab>,
+    /// Visible tab, 0 being the main chat
+    pub active: usize,
+    /// Main chat messages while an experiment tab is visible
+    parked: Vec<DisplayMessage>,
+    updates: mpsc::UnboundedReceiver<ExperimentUpdate>,
+}
+
+impl ExperimentTabs {
+    /// Start a runner per experiment and queue its first task
+    pub fn start(set: ExperimentSet, routers: Vec<RouterOrchestrator>, task: Option<&str>) -> Self {
+        let (updates_tx, updates) = mpsc::unbounded_channel();
+        let tabs = set
+            .experiments
+            .iter()
+            .zip(routers)
+            .enumerate()
+            .map(|(i, (experiment, router))| ExperimentTab {
+                label: experiment.label.clone(),
+                approach: experiment.approach.clone(),
+                messages: Vec::new(),
+                queue: spawn_runner(i, router, updates_tx.clone()),
+                pending: 0,
+            })
+            .collect();
+        let mut tabs = Self { set, tabs, active: 0, parked: Vec::new(), updates };
+        for tab in 1..=tabs.tabs.len() {
+            let approach = tabs.tabs[tab - 1].approach.clone();
+            let prompt = match task {
+                Some(task) => format!("{}\n\nEnfoque a seguir: {}", task, approach),
+                None => approach,
+            };
+            tabs.enqueue(tab, prompt, &mut Vec::new());
+        }
+        tabs
+    }
+
+    /// Tab titles, the main chat first
+    pub fn titles(&self) -> Vec<String> {
+        std::iter::once("Principal".to_string())
+            .chain(self.tabs.iter().map(|tab| {
+                let approach: String = tab.approach.chars().take(24).collect();
+                match tab.pending {
+                    0 => format!("{}: {}", tab.label, approach),
+                    n => format!("{}: {} ⏳{}", tab.label, approach, n),
+                }
+            }))
+            .collect()
+    }
+
+    /// Queue a task on an experiment tab (1-based)
+    pub fn enqueue(&mut self, tab: usize, prompt: String, live: &mut Vec<DisplayMessage>) {
+        if tab == 0 || tab > self.tabs.len() {
+            return;
+        }
+        if self.tabs[tab - 1].queue.send(prompt.clone()).is_ok() {
+            self.tabs[tab - 1].pending += 1;
+            self.messages_mut(tab, live).push(message(MessageSender::User, prompt));
+        }
+    }
+
+    /// Show another tab: park the visible messages and bring in the new ones
+    pub fn switch(&mut self, to: usize, live: &mut Vec<DisplayMessage>) {
+        let to = to % (self.tabs.len() + 1);
+        if to == self.active {
+            return;
+        }
+        *self.stored_mut(self.active) = std::mem::take(live);
+        *live = std::mem::take(self.stored_mut(to));
+        self.active = to;
+    }
+
+    /// Apply finished and started tasks; true when something changed
+    pub fn poll(&mut self, live: &mut Vec<DisplayMessage>) -> bool {
+        let mut changed = false;
+        while let Ok(update) = self.updates.try_recv() {
+            changed = true;
+            match update {
+                ExperimentUpdate::Started { .. } => {}
+                ExperimentUpdate::Finished { index, result } => {
+                    let tab = index + 1;
+                    self.tabs[index].pending = self.tabs[index].pending.saturating_sub(1);
+                    let reply = match result {
+                        Ok(text) => message(MessageSender::Assistant, text),
+                        Err(e) => message(MessageSender::System, format!("❌ {}", e)),
+                    };
+                    self.messages_mut(tab, live).push(reply);
+                }
+            }
+        }
+        changed
+    }
+
+    /// Whether any experiment still has work queued
+    pub fn busy(&self) -> bool {
+        self.tabs.iter().any(|t| t.pending > 0)
+    }
+
+    /// Back to the main chat; the worktrees are removed
+    pub fn close(mut self, live: &mut Vec<DisplayMessage>) {
+        self.switch(0, live);
+        self.set.discard();
+    }
+
+    fn messages_mut<'a>(&'a mut self, tab: usize, live: &'a mut Vec<DisplayMessage>) -> &'a mut Vec<DisplayMessage> {
+        if tab == self.active {
+            live
+        } else {
+            self.stored_mut(tab)
+        }
+    }
+
+    fn stored_mut(&mut self, tab: usize) -> &mut Vec<DisplayMessage> {
+        if tab == 0 {
+            &mut self.parked
+        } else {
+            &mut self.tabs[tab - 1].messages
+        }
+    }
+}
+
+fn message(sender: MessageSender, content: String) -> DisplayMessage {
+    DisplayMessage {
+        sender,
+        content,
+        timestamp: Instant::now(),
+        is_streaming: false,
+        tool_name: None,
+        reasoning: None,
+        translations: Translations::default(),
+    }
+}
@@ -0,0 +1,231 @@
+//! Chat history archival
+//!
+//! Keeps the in-memory chat list bounded: once it grows past the configured
+//! cap, the oldest messages are written to the session database and dropped
+//! from memory. Scrolling past the top loads them back a page at a time.
+//! Finished messages are also stored as they arrive, so past sessions can be
+//! searched and resumed (see `crate::agent::session_search`).
+
+use crate::db::{Database, DatabaseError, DbMessage};
+
+/// Messages loaded back per scrollback request
+pub const SCROLLBACK_PAGE: usize = 50;
+
+/// Storage-agnostic view of a chat message
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArchivedMessage {
+    pub role: String,
+    pub content: String,
+    pub tool_name: Option<String>,
+}
+
+/// Bookkeeping between the in-memory message list and the session database.
+///
+/// Messages are numbered from the start of the session. `first_index` is the
+/// number of the first message kept in memory; every message below
+/// `persisted` is already stored in the database.
+pub struct HistoryArchive {
+    db: Database,
+    session_id: String,
+    max_messages: usize,
+    first_index: usize,
+    persisted: usize,
+}
+
+impl HistoryArchive {
+    pub fn new(db: Database, session_id: impl Into<String>, max_messages: usize) -> Self {
+        Self {
+            db,
+            session_id: session_id.into(),
+            max_messages: max_messages.max(1),
+            first_index: 0,
+            persisted: 0,
+        }
+    }
+
+    /// Continue a stored session: the newest `max_messages` of it, oldest
+    /// first, go back in memory and the rest stay scrollback
+    pub async fn resume(
+        db: Database,
+        session_id: impl Into<String>,
+        max_messages: usize,
+    ) -> Result<(Self, Vec<ArchivedMessage>), DatabaseError> {
+        let mut archive = Self::new(db, session_id, max_messages);
+        let total = archive.db.count_session_messages(&archive.session_id).await? as usize;
+        archive.first_index = total;
+        archive.persisted = total;
+        let messages = archive.load_older(archive.max_messages).await?;
+        Ok((archive, messages))
+    }
+
+    /// Cap of messages kept in memory
+    pub fn max_messages(&self) -> usize {
+        self.max_messages
+    }
+
+    /// Messages currently out of memory (older than the first one on screen)
+    pub fn archived_count(&self) -> usize {
+        self.first_index
+    }
+
+    /// How many messages must leave memory for `in_memory` to fit the cap
+    pub fn overflow(&self, in_memory: usize) -> usize {
+        in_memory.saturating_sub(self.max_messages)
+    }
+
+    /// Archive the oldest in-memory messages, which the caller then drops.
+    ///
+    /// Messages that were loaded back from the database are not written again.
+    pub async fn archive(&mut self, evicted: Vec<ArchivedMessage>) -> Result<(), DatabaseError> {
+        let count = evicted.len();
+
+        for (offset, message) in evicted.into_iter().enumerate() {
+            let index = self.first_index + offset;
+            if index >= self.persisted {
+                self.store(index, message).await?;
+            }
+        }
+
+        self.first_index += count;
+        Ok(())
+    }
+
+    /// Store the finished in-memory messages not stored yet. `finished` are
+    /// the in-memory messages from the first one up to the last finished one.
+    pub async fn persist(&mut self, finished: Vec<ArchivedMessage>) -> Result<(), DatabaseError> {
+        let skip = self.persisted.saturating_sub(self.first_index);
+        for (offset, message) in finished.into_iter().enumerate().skip(skip) {
+            self.store(self.first_index + offset, message).await?;
+        }
+        Ok(())
+    }
+
+    /// Number of in-memory messages already stored
+    pub fn persisted_in_memory(&self) -> usize {
+        self.persisted.saturating_sub(self.first_index)
+    }
+
+    async fn store(&mut self, index: usize, message: ArchivedMessage) -> Result<(), DatabaseError> {
+        let mut record = DbMessage::new(
+            format!("{}-{}", self.session_id, index),
+            &self.session_id,
+            message.role,
+            message.content,
+        );
+        if let Some(tool) = message.tool_name {
+            record = record.with_tool(tool);
+        }
+        self.db.create_message(&record).await?;
+        self.persisted = index + 1;
+        Ok(())
+    }
+
+    /// Load up to `limit` messages preceding the first in-memory one, oldest first
+    pub async fn load_older(&mut self, limit: usize) -> Result<Vec<ArchivedMessage>, DatabaseError> {
+        let count = limit.min(self.first_index);
+        if count == 0 {
+            return Ok(Vec::new());
+        }
+
+        let offset = self.first_index - count;
+        let records = self
+            .db
+            .get_message_page(&self.session_id, offset as i64, count as i64)
+            .await?;
+
+        self.first_index = offset;
+        Ok(records
+            .into_iter()
+            .map(|record| ArchivedMessage {
+                role: record.role,
+                content: record.content,
+                tool_name: record.tool_name,
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::Session;
+
+    fn message(content: &str) -> ArchivedMessage {
+        ArchivedMessage {
+            role: "user".to_string(),
+            content: content.to_string(),
+            tool_name: None,
+        }
+    }
+
+    async fn archive_with_cap(max_messages: usize) -> HistoryArchive {
+        let db = Database::in_memory().await.unwrap();
+        db.create_session(&Session::new("s1", "fast", "heavy"))
+            .await
+            .unwrap();
+        HistoryArchive::new(db, "s1", max_messages)
+    }
+
+    #[tokio::test]
+    async fn test_archive_and_load_back() {
+        let mut archive = archive_with_cap(2).await;
+        assert_eq!(archive.overflow(5), 3);
+        assert_eq!(archive.overflow(1), 0);
+
+        archive
+            .archive(vec![message("a"), message("b"), message("c")])
+            .await
+            .unwrap();
+        assert_eq!(archive.archived_count(), 3);
+
+        let page = archive.load_older(2).await.unwrap();
+        let contents: Vec<_> = page.iter().map(|m| m.content.as_str()).collect();
+        assert_eq!(contents, vec!["b", "c"]);
+        assert_eq!(archive.archived_count(), 1);
+
+        let page = archive.load_older(10).await.unwrap();
+        assert_eq!(page[0].content, "a");
+        assert_eq!(archive.archived_count(), 0);
+        assert!(archive.load_older(10).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_reloaded_messages_are_not_archived_twice() {
+        let mut archive = archive_with_cap(1).await;
+        archive
+            .archive(vec![message("a"), message("b")])
+            .await
+            .unwrap();
+        let page = archive.load_older(2).await.unwrap();
+
+        // Evicting the reloaded messages plus a new one only writes the new one
+        let mut evicted = page;
+        evicted.push(message("c"));
+        archive.archive(evicted).await.unwrap();
+
+        assert_eq!(archive.archived_count(), 3);
+        let stored = archive.db.get_session_messages("s1").await.unwrap();
+        assert_eq!(stored.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_persist_and_resume() {
+        let mut archive = archive_with_cap(2).await;
+        archive.persist(vec![message("a"), message("b")]).await.unwrap();
+        assert_eq!(archive.persisted_in_memory(), 2);
+        archive
+            .persist(vec![message("a"), message("b"), message("c")])
+            .await
+            .unwrap();
+        // Evicting messages stored already does not write them again
+        archive.archive(vec![message("a")]).await.unwrap();
+        assert_eq!(archive.db.get_session_messages("s1").await.unwrap().len(), 3);
+
+        let (mut resumed, messages) = HistoryArchive::resume(archive.db.clone(), "s1", 2).await.unwrap();
+        let contents: Vec<_> = messages.iter().map(|m| m.content.as_str()).collect();
+        assert_eq!(contents, vec!["b", "c"]);
+        assert_eq!(resumed.archived_count(), 1);
+        assert_eq!(resumed.persisted_in_memory(), 2);
+        assert_eq!(resumed.load_older(10).await.unwrap()[0].content, "a");
+    }
+}
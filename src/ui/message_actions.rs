@@ -0,0 +1,159 @@
+//! Per-message actions (copy, re-run, quote, export)
+//!
+//! Used by the message-selection mode of the chat screen: the user picks a
+//! message with Up/Down and applies one of these actions from a popup menu.
+
+use base64::Engine;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+/// Action that can be applied to a selected chat message
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MessageAction {
+    /// Copy the whole message content to the clipboard
+    Copy,
+    /// Copy only the fenced code blocks of the message
+    CopyCode,
+    /// Send the prompt that originated the message again
+    Rerun,
+    /// Quote the message into the input buffer
+    Quote,
+    /// Write the message to a markdown file
+    Export,
+}
+
+impl MessageAction {
+    pub const ALL: [MessageAction; 5] = [
+        MessageAction::Copy,
+        MessageAction::CopyCode,
+        MessageAction::Rerun,
+        MessageAction::Quote,
+        MessageAction::Export,
+    ];
+
+    pub fn next(self) -> Self {
+        let idx = Self::ALL.iter().position(|a| *a == self).unwrap_or(0);
+        Self::ALL[(idx + 1) % Self::ALL.len()]
+    }
+
+    pub fn prev(self) -> Self {
+        let idx = Self::ALL.iter().position(|a| *a == self).unwrap_or(0);
+        Self::ALL[(idx + Self::ALL.len() - 1) % Self::ALL.len()]
+    }
+
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            MessageAction::Copy => "Copiar contenido",
+            MessageAction::CopyCode => "Copiar solo bloques de código",
+            MessageAction::Rerun => "Re-ejecutar prompt original",
+            MessageAction::Quote => "Citar en el input",
+            MessageAction::Export => "Exportar a archivo",
+        }
+    }
+}
+
+/// Extract the bodies of all fenced (```) code blocks, separated by blank lines
+pub fn extract_code_blocks(content: &str) -> String {
+    let mut blocks: Vec<String> = Vec::new();
+    let mut current: Option<Vec<&str>> = None;
+
+    for line in content.lines() {
+        if line.trim_start().starts_with("```") {
+            match current.take() {
+                Some(lines) => blocks.push(lines.join("\n")),
+                None => current = Some(Vec::new()),
+            }
+        } else if let Some(ref mut lines) = current {
+            lines.push(line);
+        }
+    }
+
+    // Unterminated block (e.g. truncated answer): keep what we have
+    if let Some(lines) = current {
+        blocks.push(lines.join("\n"));
+    }
+
+    blocks.join("\n\n")
+}
+
+/// Quote a message markdown-style ("> " prefix on every line)
+pub fn quote(content: &str) -> String {
+    let mut quoted: String = content
+        .lines()
+        .map(|line| {
+            if line.is_empty() {
+                ">".to_string()
+            } else {
+                format!("> {}", line)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    quoted.push('\n');
+    quoted
+}
+
+/// Build the OSC 52 escape sequence that asks the terminal to set the clipboard.
+/// Works over SSH and without any system clipboard dependency.
+pub fn osc52_sequence(text: &str) -> String {
+    format!(
+        "\x1b]52;c;{}\x07",
+        base64::engine::general_purpose::STANDARD.encode(text.as_bytes())
+    )
+}
+
+/// Copy `text` to the clipboard through the terminal
+pub fn copy_to_clipboard(text: &str) -> io::Result<()> {
+    let mut stdout = io::stdout();
+    stdout.write_all(osc52_sequence(text).as_bytes())?;
+    stdout.flush()
+}
+
+/// Write a message to `<dir>/neuro-message-<timestamp>.md` and return the path
+pub fn export_message(dir: &Path, label: &str, content: &str) -> io::Result<PathBuf> {
+    let stamp = chrono::Local::now().format("%Y%m%d-%H%M%S");
+    let path = dir.join(format!("neuro-message-{}.md", stamp));
+    std::fs::write(&path, format!("## {}\n\n{}\n", label, content))?;
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_action_cycle() {
+        assert_eq!(MessageAction::Copy.next(), MessageAction::CopyCode);
+        assert_eq!(MessageAction::Export.next(), MessageAction::Copy);
+        assert_eq!(MessageAction::Copy.prev(), MessageAction::Export);
+    }
+
+    #[test]
+    fn test_extract_code_blocks() {
+        let content = "Texto\n```rust\nfn a() {}\n```\nmás texto\n```\nls -la\n```";
+        assert_eq!(extract_code_blocks(content), "fn a() {}\n\nls -la");
+        assert_eq!(extract_code_blocks("sin código"), "");
+    }
+
+    #[test]
+    fn test_quote() {
+        assert_eq!(quote("hola\n\nmundo"), "> hola\n>\n> mundo\n");
+    }
+
+    #[test]
+    fn test_osc52_sequence() {
+        assert_eq!(osc52_sequence(""), "\x1b]52;c;\x07");
+        assert_eq!(osc52_sequence("f"), "\x1b]52;c;Zg==\x07");
+        assert_eq!(osc52_sequence("foobar"), "\x1b]52;c;Zm9vYmFy\x07");
+        assert_eq!(osc52_sequence("hi"), "\x1b]52;c;aGk=\x07");
+    }
+
+    #[test]
+    fn test_export_message() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = export_message(dir.path(), "Asistente", "contenido").unwrap();
+        let written = std::fs::read_to_string(path).unwrap();
+        assert!(written.contains("## Asistente"));
+        assert!(written.contains("contenido"));
+    }
+}
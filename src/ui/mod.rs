@@ -1,15 +1,40 @@
 //! UI module - Modern TUI interface using ratatui
+//!
+//! Everything that draws on the terminal needs the `tui` feature; accessibility
+//! settings, animations state, history archival, message actions, response
+//! diffs and the line-based REPL of `--simple` do not.
 
+pub mod accessibility;
 pub mod animations;
+#[cfg(feature = "tui")]
+pub mod experiment_tabs;
+pub mod history_archive;
+#[cfg(feature = "tui")]
 pub mod layout;
+pub mod message_actions;
+pub mod response_diff;
+pub mod simple;
+#[cfg(feature = "tui")]
 pub mod model_config_panel;
+#[cfg(feature = "tui")]
 pub mod modern_app;
+#[cfg(feature = "tui")]
 pub mod settings;
+#[cfg(feature = "tui")]
 pub mod theme;
+#[cfg(feature = "tui")]
+pub mod virtual_list;
+#[cfg(feature = "tui")]
 mod widgets;
 
+pub use accessibility::{AccessibilityConfig, SpeechHook};
 pub use animations::{Spinner, StatusIndicator, StatusState};
+pub use simple::SimpleRepl;
+#[cfg(feature = "tui")]
 pub use model_config_panel::{ButtonAction, ModelConfigPanel};
+#[cfg(feature = "tui")]
 pub use modern_app::ModernApp;
+#[cfg(feature = "tui")]
 pub use settings::SettingsPanel;
+#[cfg(feature = "tui")]
 pub use theme::Theme;
@@ -2,6 +2,7 @@
 
 #![allow(deprecated)]
 
+use std::cell::RefCell;
 use std::io::{self, Stdout};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
@@ -20,15 +21,25 @@ use ratatui::{
     layout::{Alignment, Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, Clear, List, ListItem, Paragraph, Wrap},
+    widgets::{Block, Clear, List, ListItem, Paragraph, Wrap},
     Frame, Terminal,
 };
-use tokio::sync::{mpsc, Mutex};
+use tokio::sync::{mpsc, oneshot, Mutex};
+use tokio_util::sync::CancellationToken;
 
 use crate::agent::{
-    OrchestratorResponse, PlanningOrchestrator, PlanningResponse, RouterOrchestrator,
-    TaskProgressInfo, TaskProgressStatus,
+    context_budget::{self, ContextBudget}, OrchestratorResponse, PlanningOrchestrator,
+    PlanningResponse, RouterOrchestrator, SessionQuery, StructuredResponse, TaskProgressInfo,
+    TaskProgressStatus,
 };
+use crate::agent::translation::{self, Translations};
+use crate::agent::{dry_run, session_search, stream_watchdog};
+use crate::agent::webhooks::{self, TaskEvent, WebhookEventKind};
+use crate::db::SessionSummary;
+use crate::error::NeuroError;
+use crate::config::{ConfigReload, ConfigWatcher};
+use crate::security::network::{self, SendChecked};
+use crate::security::privacy::{self, PrivacyLevel};
 use crate::i18n::{current_locale, init_locale, t, Locale, Text};
 use crate::{log_error, log_debug};
 
@@ -40,10 +51,17 @@ pub enum OrchestratorWrapper {
 use crate::tools::TaskPlan;
 
 use super::animations::{Spinner, StatusIndicator, StatusState};
+use super::experiment_tabs::ExperimentTabs;
+use crate::agent::experiments::{parse_approaches, ExperimentSet};
+use super::history_archive::{ArchivedMessage, HistoryArchive, SCROLLBACK_PAGE};
 use super::layout::centered_rect;
+use super::message_actions::{self, MessageAction};
+use super::response_diff::{LineChange, ResponseDiff};
 use super::model_config_panel::{ButtonAction, ModelConfigPanel};
 use super::settings::{SettingsPanel, ToolConfig};
+use super::accessibility::{AccessibilityConfig, SpeechHook};
 use super::theme::{Icons, Theme};
+use super::virtual_list::{wrapped_rows, MessageLayoutCache};
 // Plan widgets available but not used in modern_app directly
 // use super::widgets::{PlanViewer, PlanSummary};
 
@@ -56,6 +74,82 @@ pub enum AppScreen {
     IndexingPrompt,
     Confirmation,
     Password,
+    /// Actions popup for the message selected in selection mode
+    MessageActions,
+    /// Reloaded config needs a reconnection: apply now or at next restart
+    ConfigReload,
+    /// Full text of a stored tool output (/artifacts open)
+    Pager,
+    /// Past sessions searched by text and tag (/sessions)
+    Sessions,
+}
+
+/// Text shown in the pager screen
+#[derive(Debug, Clone)]
+struct PagerView {
+    title: String,
+    lines: Vec<String>,
+    /// First line shown
+    scroll: usize,
+    /// Lines carry a diff marker and are colored by it
+    diff: bool,
+}
+
+impl PagerView {
+    fn new(title: String, content: &str) -> Self {
+        Self {
+            title,
+            lines: content.lines().map(str::to_string).collect(),
+            scroll: 0,
+            diff: false,
+        }
+    }
+
+    fn diff(title: String, diff: &ResponseDiff) -> Self {
+        Self {
+            diff: true,
+            ..Self::new(title, &diff.render())
+        }
+    }
+
+    fn scroll_by(&mut self, delta: isize) {
+        let last = self.lines.len().saturating_sub(1);
+        self.scroll = self.scroll.saturating_add_signed(delta).min(last);
+    }
+}
+
+/// Past sessions found by the session browser
+#[derive(Debug, Clone, Default)]
+struct SessionBrowser {
+    query: String,
+    results: Vec<SessionSummary>,
+    selected: usize,
+}
+
+impl SessionBrowser {
+    fn current(&self) -> Option<&SessionSummary> {
+        self.results.get(self.selected)
+    }
+
+    fn select_by(&mut self, delta: isize) {
+        let last = self.results.len().saturating_sub(1);
+        self.selected = self.selected.saturating_add_signed(delta).min(last);
+    }
+}
+
+/// The answer a /retry regenerates
+#[derive(Debug, Clone)]
+struct RetrySource {
+    answer: String,
+    started: Instant,
+}
+
+/// A /translate waiting for the fast model
+struct PendingTranslation {
+    /// Timestamp of the message being translated, which identifies it
+    message: Instant,
+    language: String,
+    result: oneshot::Receiver<Result<String, String>>,
 }
 
 /// Indexing options for the prompt
@@ -209,6 +303,17 @@ pub struct DisplayMessage {
     pub timestamp: Instant,
     pub is_streaming: bool,
     pub tool_name: Option<String>,
+    /// Reasoning of a thinking model behind this answer, shown collapsed (Ctrl+R)
+    pub reasoning: Option<String>,
+    /// Translations made with /translate; the content stays the original
+    pub translations: Translations,
+}
+
+impl DisplayMessage {
+    /// Text to draw: the translation being shown, or the original
+    pub fn shown_content(&self) -> &str {
+        self.translations.shown().map_or(&self.content, |(_, text)| text)
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -219,7 +324,14 @@ pub enum MessageSender {
     Tool,
 }
 
-use crate::agent::AgentEvent;
+use crate::agent::{AgentEvent, EventBus, Subscription, Topic};
+
+/// Events the UI mailbox holds between frames; a streamed answer can send
+/// thousands of chunks, and the stream waits when the mailbox is full
+const EVENT_MAILBOX: usize = 5000;
+
+/// Time past the turn budget before a router turn is abandoned
+const TURN_BUDGET_GRACE_SECS: u64 = 30;
 
 /// Main application state
 pub struct ModernApp {
@@ -245,10 +357,23 @@ pub struct ModernApp {
     scroll_offset: usize,
     auto_scroll: bool,
 
+    // Cached wrapped heights per message (virtualized chat rendering)
+    message_layout: RefCell<MessageLayoutCache>,
+
+    // Older messages archived to the session database (bounded history)
+    history_archive: Option<HistoryArchive>,
+    history_load_requested: bool,
+
+    // Message selection mode (Ctrl+S) and its actions popup
+    selected_message: Option<usize>,
+    message_action_selected: MessageAction,
+
     // Status & Animations
     status: StatusIndicator,
     spinner: Spinner,
     status_message: String,
+    /// Records of the latest turn; their summary replaces "Listo" when it ends
+    last_turn: Option<StructuredResponse>,
 
     // Processing state
     is_processing: bool,
@@ -259,10 +384,18 @@ pub struct ModernApp {
     // Streaming optimization: accumulate chunks without rendering
     streaming_buffer: Option<String>,
     streaming_chunks_count: usize,
-
-    // Background task communication
-    response_rx: Option<mpsc::Receiver<AgentEvent>>,
+    /// Reasoning streamed so far for the answer being generated
+    reasoning_buffer: Option<String>,
+    /// Reasoning of answers shown expanded instead of collapsed (Ctrl+R)
+    show_reasoning: bool,
+
+    // Background task communication: the router, the request task and the
+    // RAPTOR indexer publish on `events`, the UI reads its mailbox each frame
+    events: EventBus,
+    event_rx: Subscription,
     background_task_handle: Option<tokio::task::JoinHandle<()>>,
+    /// Cancels the model requests, tools and stream of the running turn
+    turn_cancel: Option<CancellationToken>,
 
     // Settings
     settings_panel: SettingsPanel,
@@ -275,10 +408,10 @@ pub struct ModernApp {
 
     // Background RAPTOR indexing
     raptor_indexing: bool,
+    raptor_cancel: Option<CancellationToken>,
     raptor_status: Option<String>,
     raptor_progress: Option<(usize, usize)>, // (current, total)
     raptor_stage: Option<String>,
-    raptor_rx: Option<mpsc::Receiver<AgentEvent>>,
     raptor_start_time: Option<Instant>,
     raptor_eta: Option<Duration>,
 
@@ -299,6 +432,32 @@ pub struct ModernApp {
     // Command autocomplete
     show_autocomplete: bool,
     autocomplete_selected: usize,
+
+    // Context budget breakdown (Ctrl+B)
+    show_context_budget: bool,
+
+    // Artifact open in the pager screen
+    pager: Option<PagerView>,
+
+    // Session browser screen (/sessions)
+    session_browser: Option<SessionBrowser>,
+
+    // Answer being regenerated (/retry) and the diff of the last regeneration
+    retry_from: Option<RetrySource>,
+    last_retry_diff: Option<ResponseDiff>,
+
+    // Translation of an answer in progress (/translate)
+    translation_task: Option<PendingTranslation>,
+
+    // Parallel experiments in git worktrees (/try, experimental)
+    experiments_enabled: bool,
+    experiments: Option<ExperimentTabs>,
+
+    // Config hot reload and the reload waiting for a reconnection answer
+    config_watcher: Option<ConfigWatcher>,
+    /// Speaks answers through `accessibility.speak_command`
+    speech: Option<SpeechHook>,
+    pending_reload: Option<ConfigReload>,
 }
 
 impl ModernApp {
@@ -397,8 +556,15 @@ impl ModernApp {
         // Initialize locale
         let locale = init_locale();
 
+        let events = match &orchestrator {
+            OrchestratorWrapper::Router(router) => router.event_bus(),
+            OrchestratorWrapper::Planning(_) => EventBus::new(),
+        };
+        let event_rx = events.subscribe("tui", EVENT_MAILBOX, &Topic::ALL);
+
         // Setup terminal
         enable_raw_mode()?;
+        crate::crash::set_tui_active(true);
         let mut stdout = io::stdout();
         execute!(stdout, EnterAlternateScreen, EnableMouseCapture, Hide)?;
         let backend = CrosstermBackend::new(stdout);
@@ -421,6 +587,8 @@ impl ModernApp {
                     timestamp: Instant::now(),
                     is_streaming: false,
                     tool_name: None,
+                    reasoning: None,
+                    translations: Translations::default(),
                 },
             ],
             input_buffer: String::new(),
@@ -432,9 +600,18 @@ impl ModernApp {
             scroll_offset: 0,
             auto_scroll: true,
 
+            message_layout: RefCell::new(MessageLayoutCache::new()),
+
+            history_archive: None,
+            history_load_requested: false,
+
+            selected_message: None,
+            message_action_selected: MessageAction::Copy,
+
             status: StatusIndicator::new(),
             spinner: Spinner::dots(),
             status_message: t(Text::Ready).to_string(),
+            last_turn: None,
 
             is_processing: false,
             processing_start: None,
@@ -443,9 +620,13 @@ impl ModernApp {
 
             streaming_buffer: None,
             streaming_chunks_count: 0,
+            reasoning_buffer: None,
+            show_reasoning: crate::agent::reasoning::expanded(),
 
-            response_rx: None,
+            events,
+            event_rx,
             background_task_handle: None,
+            turn_cancel: None,
 
             settings_panel: SettingsPanel::new(),
             model_config_panel: ModelConfigPanel::new(crate::config::AppConfig::default()),
@@ -455,10 +636,10 @@ impl ModernApp {
             password_error: None,
 
             raptor_indexing: false,
+            raptor_cancel: None,
             raptor_status: None,
             raptor_progress: None,
             raptor_stage: None,
-            raptor_rx: None,
             raptor_start_time: None,
             raptor_eta: None,
 
@@ -472,9 +653,48 @@ impl ModernApp {
 
             show_autocomplete: false,
             autocomplete_selected: 0,
+
+            show_context_budget: false,
+
+            pager: None,
+
+            session_browser: None,
+
+            retry_from: None,
+            last_retry_diff: None,
+
+            translation_task: None,
+
+            experiments_enabled: false,
+            experiments: None,
+
+            config_watcher: None,
+            speech: None,
+            pending_reload: None,
         })
     }
 
+    /// Bound the in-memory chat history, archiving older messages to the session database
+    pub fn enable_history_archive(&mut self, archive: HistoryArchive) {
+        self.history_archive = Some(archive);
+    }
+
+    /// Allow `/try` to run approaches side by side in git worktrees
+    pub fn enable_experiments(&mut self) {
+        self.experiments_enabled = true;
+    }
+
+    /// Apply saved changes of the config file while running
+    pub fn enable_config_reload(&mut self, watcher: ConfigWatcher) {
+        self.config_watcher = Some(watcher);
+    }
+
+    /// Apply the accessibility settings: speech hook, focus indicators, plain output
+    pub fn enable_accessibility(&mut self, config: &AccessibilityConfig) {
+        self.theme = self.theme.clone().with_accessibility(config);
+        self.speech = config.speak_command.as_deref().map(SpeechHook::new);
+    }
+
     /// Check if this project has been indexed before
     fn has_indexed_this_project(&self) -> bool {
         // Check if RAPTOR cache exists
@@ -509,22 +729,21 @@ impl ModernApp {
         self.raptor_eta = None;
 
         let orchestrator = self.orchestrator.clone();
-        let (tx, rx) = mpsc::channel::<AgentEvent>(50);
-        self.raptor_rx = Some(rx);
+        let events = self.events.clone();
+        let cancel = CancellationToken::new();
+        self.raptor_cancel = Some(cancel.clone());
 
         // Spawn background task with two phases
         tokio::spawn(async move {
             use crate::raptor::builder::{has_full_index, quick_index_sync};
 
             // Phase 1: Quick index (very fast - just read files) - run in blocking thread
-            let _ = tx
-                .send(AgentEvent::RaptorProgress {
-                    stage: "Lectura".to_string(),
-                    current: 0,
-                    total: 0,
-                    detail: "Escaneando archivos...".to_string(),
-                })
-                .await;
+            events.publish(AgentEvent::RaptorProgress {
+                stage: "Lectura".to_string(),
+                current: 0,
+                total: 0,
+                detail: "Escaneando archivos...".to_string(),
+            });
 
             let project_path = std::env::current_dir().unwrap_or_default();
             let path_clone = project_path.clone();
@@ -536,28 +755,18 @@ impl ModernApp {
 
             match quick_result {
                 Ok(Ok(Ok(chunks))) => {
-                    let _ = tx
-                        .send(AgentEvent::RaptorProgress {
-                            stage: "Lectura".to_string(),
-                            current: chunks,
-                            total: chunks,
-                            detail: format!("{} archivos leídos", chunks),
-                        })
-                        .await;
+                    events.publish(AgentEvent::RaptorProgress {
+                        stage: "Lectura".to_string(),
+                        current: chunks,
+                        total: chunks,
+                        detail: format!("{} archivos leídos", chunks),
+                    });
                 }
                 Ok(Ok(Err(_))) | Ok(Err(_)) => {
-                    let _ = tx
-                        .send(AgentEvent::RaptorStatus(
-                            "⚠ Error en lectura".to_string(),
-                        ))
-                        .await;
+                    events.publish(AgentEvent::RaptorStatus("⚠ Error en lectura".to_string()));
                 }
                 Err(_) => {
-                    let _ = tx
-                        .send(AgentEvent::RaptorStatus(
-                            "⏱️ Timeout en lectura".to_string(),
-                        ))
-                        .await;
+                    events.publish(AgentEvent::RaptorStatus("⏱️ Timeout en lectura".to_string()));
                 }
             }
 
@@ -578,7 +787,7 @@ impl ModernApp {
                     tokio::sync::mpsc::channel::<crate::agent::TaskProgressInfo>(50);
 
                 // Spawn task to forward progress updates
-                let tx_clone = tx.clone();
+                let progress_events = events.clone();
                 tokio::spawn(async move {
                     while let Some(progress) = progress_rx.recv().await {
                         // Use task_index/total_tasks as current/total for progress
@@ -592,24 +801,20 @@ impl ModernApp {
                             let stage = description[..colon_pos].to_string();
                             let detail = description[colon_pos + 1..].trim().to_string();
                             
-                            let _ = tx_clone
-                                .send(AgentEvent::RaptorProgress {
-                                    stage,
-                                    current,
-                                    total,
-                                    detail,
-                                })
-                                .await;
+                            progress_events.publish(AgentEvent::RaptorProgress {
+                                stage,
+                                current,
+                                total,
+                                detail,
+                            });
                         } else {
                             // No colon, use description as-is
-                            let _ = tx_clone
-                                .send(AgentEvent::RaptorProgress {
-                                    stage: "RAPTOR".to_string(),
-                                    current,
-                                    total,
-                                    detail: description,
-                                })
-                                .await;
+                            progress_events.publish(AgentEvent::RaptorProgress {
+                                stage: "RAPTOR".to_string(),
+                                current,
+                                total,
+                                detail: description,
+                            });
                         }
                     }
                 });
@@ -618,115 +823,95 @@ impl ModernApp {
                 
                 match &mut *orch {
                     OrchestratorWrapper::Planning(planning) => {
-                        match planning.initialize_raptor_with_progress(Some(progress_tx)).await {
+                        match planning.initialize_raptor_with_progress(Some(progress_tx), cancel.clone()).await {
                             Ok(true) => {
-                                let _ = tx
-                                    .send(AgentEvent::RaptorStatus(
-                                        "✓ RAPTOR listo".to_string(),
-                                    ))
-                                    .await;
+                                events.publish(AgentEvent::RaptorStatus("✓ RAPTOR listo".to_string()));
                             }
                             Ok(false) => {
-                                let _ = tx
-                                    .send(AgentEvent::RaptorStatus("📄 Solo texto".to_string()))
-                                    .await;
+                                events.publish(AgentEvent::RaptorStatus("📄 Solo texto".to_string()));
                             }
                             Err(_) => {
-                                let _ = tx
-                                    .send(AgentEvent::RaptorStatus(
-                                        "⚠ Error RAPTOR".to_string(),
-                                    ))
-                                    .await;
+                                events.publish(AgentEvent::RaptorStatus("⚠ Error RAPTOR".to_string()));
                             }
                         }
                     }
                     OrchestratorWrapper::Router(router) => {
                         // RouterOrchestrator: use initialize_raptor_with_progress
-                        match router.initialize_raptor_with_progress(Some(progress_tx)).await {
+                        match router.initialize_raptor_with_progress(Some(progress_tx), cancel.clone()).await {
                             Ok(true) => {
-                                let _ = tx
-                                    .send(AgentEvent::RaptorStatus(
-                                        "✓ RAPTOR listo".to_string(),
-                                    ))
-                                    .await;
+                                events.publish(AgentEvent::RaptorStatus("✓ RAPTOR listo".to_string()));
                             }
                             Ok(false) => {
-                                let _ = tx
-                                    .send(AgentEvent::RaptorStatus("📄 Solo texto".to_string()))
-                                    .await;
+                                events.publish(AgentEvent::RaptorStatus("📄 Solo texto".to_string()));
                             }
                             Err(_) => {
-                                let _ = tx
-                                    .send(AgentEvent::RaptorStatus(
-                                        "⚠ Error RAPTOR".to_string(),
-                                    ))
-                                    .await;
+                                events.publish(AgentEvent::RaptorStatus("⚠ Error RAPTOR".to_string()));
                             }
                         }
                     }
                 }
             } else {
-                let _ = tx
-                    .send(AgentEvent::RaptorStatus(
-                        "✓ RAPTOR listo".to_string(),
-                    ))
-                    .await;
+                events.publish(AgentEvent::RaptorStatus("✓ RAPTOR listo".to_string()));
             }
 
-            let _ = tx.try_send(AgentEvent::RaptorComplete);
+            // A cancelled build was already marked as stopped by the UI
+            if !cancel.is_cancelled() {
+                events.send(AgentEvent::RaptorComplete).await;
+            }
         });
     }
 
-    /// Check for RAPTOR indexing updates
-    fn check_raptor_status(&mut self) {
-        if let Some(ref mut rx) = self.raptor_rx {
-            loop {
-                match rx.try_recv() {
-                    Ok(AgentEvent::RaptorStatus(status)) => {
-                        // Parsear el estado para extraer información de progreso
-                        if status.contains("chunks listos") {
-                            if let Some(num_str) = status.split_whitespace().nth(1) {
-                                if let Ok(num) = num_str.parse::<usize>() {
-                                    self.raptor_progress = Some((num, num));
-                                    self.raptor_stage = Some("Lectura".to_string());
-                                }
-                            }
-                        } else if status.contains("Indexando RAPTOR") {
-                            self.raptor_stage = Some("RAPTOR".to_string());
-                        } else if status.contains("Leyendo archivos") {
+    /// Stop the background RAPTOR build; a partial index is not cached
+    fn cancel_raptor_indexing(&mut self) {
+        if let Some(cancel) = self.raptor_cancel.take() {
+            cancel.cancel();
+        }
+        self.raptor_indexing = false;
+        self.raptor_progress = None;
+        self.raptor_stage = None;
+        self.raptor_status = Some("⏹ Indexado cancelado".to_string());
+    }
+
+    /// Apply a RAPTOR indexing update from the event bus
+    fn handle_raptor_event(&mut self, event: AgentEvent) {
+        if !self.raptor_indexing {
+            return; // Leftovers of a cancelled build
+        }
+        match event {
+            AgentEvent::RaptorStatus(status) => {
+                // Parsear el estado para extraer información de progreso
+                if status.contains("chunks listos") {
+                    if let Some(num_str) = status.split_whitespace().nth(1) {
+                        if let Ok(num) = num_str.parse::<usize>() {
+                            self.raptor_progress = Some((num, num));
                             self.raptor_stage = Some("Lectura".to_string());
                         }
-                        self.raptor_status = Some(status);
-                    }
-                    Ok(AgentEvent::RaptorProgress {
-                        stage,
-                        current,
-                        total,
-                        detail,
-                    }) => {
-                        self.raptor_stage = Some(stage);
-                        self.raptor_progress = Some((current, total));
-                        self.raptor_status = Some(detail);
-                    }
-                    Ok(AgentEvent::RaptorComplete) => {
-                        self.raptor_indexing = false;
-                        self.raptor_status = Some("Índice listo ✓".to_string());
-                        self.raptor_progress = None;
-                        self.raptor_stage = None;
-                        self.raptor_rx = None;
-                        break;
-                    }
-                    Ok(_) => {} // Ignore other messages
-                    Err(mpsc::error::TryRecvError::Empty) => break,
-                    Err(mpsc::error::TryRecvError::Disconnected) => {
-                        self.raptor_indexing = false;
-                        self.raptor_progress = None;
-                        self.raptor_stage = None;
-                        self.raptor_rx = None;
-                        break;
                     }
+                } else if status.contains("Indexando RAPTOR") {
+                    self.raptor_stage = Some("RAPTOR".to_string());
+                } else if status.contains("Leyendo archivos") {
+                    self.raptor_stage = Some("Lectura".to_string());
                 }
+                self.raptor_status = Some(status);
             }
+            AgentEvent::RaptorProgress {
+                stage,
+                current,
+                total,
+                detail,
+            } => {
+                self.raptor_stage = Some(stage);
+                self.raptor_progress = Some((current, total));
+                self.raptor_status = Some(detail);
+            }
+            AgentEvent::RaptorComplete => {
+                self.raptor_indexing = false;
+                self.raptor_cancel = None;
+                self.raptor_status = Some("Índice listo ✓".to_string());
+                self.raptor_progress = None;
+                self.raptor_stage = None;
+            }
+            _ => {}
         }
     }
 
@@ -765,12 +950,21 @@ impl ModernApp {
             // Check for background task completion
             self.check_background_response().await;
 
+            // Collect answers of experiment tabs
+            self.poll_experiments();
+
+            // Keep the in-memory history bounded
+            self.sync_history_archive().await;
+
+            // Apply config file changes
+            self.poll_config_reload().await;
+
+            // Show translations the fast model finished
+            self.poll_translation();
+
             // Yield to runtime after processing events to keep UI responsive
             tokio::task::yield_now().await;
 
-            // Check RAPTOR indexing status
-            self.check_raptor_status();
-
             // Handle events with short timeout for responsive animations
             let timeout = tick_rate.saturating_sub(last_tick.elapsed());
 
@@ -799,15 +993,21 @@ impl ModernApp {
     }
 
     async fn check_background_response(&mut self) {
-        // Early exit if not processing
+        // RAPTOR events arrive at any time; everything else only matters during a turn
+        // (leftovers of a cancelled turn are dropped here)
         if !self.is_processing {
+            while let Ok(event) = self.event_rx.try_recv() {
+                if event.topic() == Topic::Raptor {
+                    self.handle_raptor_event(event);
+                }
+            }
             return;
         }
 
         let _processing_elapsed = self.processing_start.map(|t| t.elapsed().as_secs());
         let mut messages_to_add: Vec<(MessageSender, String, Option<String>)> = Vec::new();
-        let mut final_response: Option<Result<PlanningResponse, String>> = None;
-        let mut orch_response: Option<Result<OrchestratorResponse, String>> = None;
+        let mut final_response: Option<Result<PlanningResponse, NeuroError>> = None;
+        let mut orch_response: Option<Result<OrchestratorResponse, NeuroError>> = None;
         let mut should_close = false;
         let mut new_status: Option<String> = None;
 
@@ -817,9 +1017,12 @@ impl ModernApp {
             let since_last_event = last_event.elapsed().as_secs();
             let since_start = self.processing_start.map(|t| t.elapsed().as_secs()).unwrap_or(0);
 
+            // A stream that stopped sending is the watchdog's to end (StreamAborted)
+            let watched_stream = self.streaming_buffer.is_some() && stream_watchdog::stall_timeout().is_some();
+
             // If no events for 60 seconds AND we've been processing for at least 5 seconds,
             // assume stream ended but StreamEnd was lost or process is stuck
-            if since_last_event >= 60 && since_start >= 5 {
+            if since_last_event >= 60 && since_start >= 5 && !watched_stream {
                 log_debug!("🔧 [TIMEOUT] No events for {}s, assuming stream ended or stuck", since_last_event);
                 self.add_message(MessageSender::System,
                     format!("⚠️ Timeout: Sin eventos por {} segundos. El proceso puede estar bloqueado.", since_last_event),
@@ -829,18 +1032,16 @@ impl ModernApp {
             }
         }
 
-        // Early exit if no channel (nothing to process)
-        if self.response_rx.is_none() {
-            return;
-        }
-
-        if let Some(ref mut rx) = self.response_rx {
+        {
             // Aggressive draining: process ALL available events immediately
             // No yielding - we want to drain the entire channel buffer as fast as possible
             let mut events_count = 0;
 
             loop {
-                match rx.try_recv() {
+                match self.event_rx.try_recv() {
+                    Ok(event) if event.topic() == Topic::Raptor => {
+                        self.handle_raptor_event(event);
+                    }
                     Ok(event) => {
                         events_count += 1;
 
@@ -882,6 +1083,9 @@ impl ModernApp {
                                 messages_to_add.push((MessageSender::System, msg, None));
                             }
                             AgentEvent::Chunk(content) => {
+                                if let Some(speech) = self.speech.as_mut() {
+                                    speech.push(&content);
+                                }
                                 // PERFORMANCE FIX: Accumulate chunks in hidden buffer, don't render
                                 if let Some(ref mut buffer) = self.streaming_buffer {
                                     buffer.push_str(&content);
@@ -897,6 +1101,13 @@ impl ModernApp {
                                     self.status_message = format!("Generando respuesta... {} KB recibidos", kb);
                                 }
                             }
+                            AgentEvent::Thinking(content) => {
+                                let buffer = self.reasoning_buffer.get_or_insert_with(String::new);
+                                buffer.push_str(&content);
+                                if self.streaming_buffer.is_none() {
+                                    self.status_message = format!("Razonando... {} KB", buffer.len() / 1024);
+                                }
+                            }
                             AgentEvent::StreamEnd => {
                                 log_debug!("🏁 [UI] StreamEnd received, creating final message");
 
@@ -904,24 +1115,67 @@ impl ModernApp {
                                 if let Some(buffer) = self.streaming_buffer.take() {
                                     log_debug!("🏁 [UI] Message finalized: {} chars from {} chunks", buffer.len(), self.streaming_chunks_count);
 
+                                    let reasoning = self.reasoning_buffer.take();
                                     let msg = DisplayMessage {
                                         sender: MessageSender::Assistant,
-                                        content: buffer,
+                                        content: buffer.trim_start().to_string(),
                                         timestamp: Instant::now(),
                                         is_streaming: false,
                                         tool_name: None,
+                                        reasoning: reasoning.map(|r| r.trim().to_string()).filter(|r| !r.is_empty()),
+                                        translations: Translations::default(),
                                     };
                                     self.messages.push(msg);
                                     self.auto_scroll = true;
                                 }
 
+                                if let Some(speech) = self.speech.as_mut() {
+                                    speech.finish();
+                                }
+
                                 // Reset streaming state
                                 self.streaming_buffer = None;
+                                self.reasoning_buffer = None;
                                 self.streaming_chunks_count = 0;
 
                                 // Close the channel and reset processing state
                                 should_close = true;
                             }
+                            AgentEvent::StreamAborted { partial, reason } => {
+                                log_debug!("🏁 [UI] StreamAborted received: {}", reason);
+
+                                // Keep what arrived, as the finished answer of the turn
+                                let answer = self.streaming_buffer.take().unwrap_or(partial);
+                                if !answer.trim().is_empty() {
+                                    let reasoning = self.reasoning_buffer.take();
+                                    self.messages.push(DisplayMessage {
+                                        sender: MessageSender::Assistant,
+                                        content: answer.trim_start().to_string(),
+                                        timestamp: Instant::now(),
+                                        is_streaming: false,
+                                        tool_name: None,
+                                        reasoning: reasoning.map(|r| r.trim().to_string()).filter(|r| !r.is_empty()),
+                                        translations: Translations::default(),
+                                    });
+                                    self.auto_scroll = true;
+                                }
+                                messages_to_add.push((
+                                    MessageSender::System,
+                                    format!(
+                                        "⚠️ La respuesta se cortó ({}) y no se pudo retomar; se conserva lo recibido. Usa /retry para regenerarla.",
+                                        reason
+                                    ),
+                                    None,
+                                ));
+
+                                if let Some(speech) = self.speech.as_mut() {
+                                    speech.finish();
+                                }
+                                self.streaming_buffer = None;
+                                self.reasoning_buffer = None;
+                                self.streaming_chunks_count = 0;
+                                should_close = true;
+                            }
                             AgentEvent::TaskProgress(progress) => {
                                 let TaskProgressInfo {
                                     task_index,
@@ -955,27 +1209,18 @@ impl ModernApp {
                                 messages_to_add.push((MessageSender::System, msg, None));
                             }
                             AgentEvent::RaptorStatus(_)
-                            | AgentEvent::RaptorProgress { .. } => {
-                                // Handled by check_raptor_status, ignore here
+                            | AgentEvent::RaptorProgress { .. }
+                            | AgentEvent::RaptorComplete => {}
+                            AgentEvent::TurnDetails(details) => {
+                                self.last_turn = Some(*details);
                             }
-                            AgentEvent::RaptorComplete => {
-                                // Handled by check_raptor_status, ignore here
-                            }
-                            AgentEvent::Error(err_msg) => {
-                                messages_to_add.push((MessageSender::System, format!("Error: {}", err_msg), None));
+                            AgentEvent::Error(err) => {
+                                messages_to_add.push((MessageSender::System, error_message(&err), None));
                                 should_close = true;
                             }
                         }
                     }
-                    Err(mpsc::error::TryRecvError::Empty) => {
-                        break;
-                    }
-                    Err(mpsc::error::TryRecvError::Disconnected) => {
-                        should_close = true;
-                        self.status.set_state(StatusState::Error);
-                        self.status_message = t(Text::Error).to_string();
-                        break;
-                    }
+                    Err(_) => break,
                 }
             }
 
@@ -1007,6 +1252,9 @@ impl ModernApp {
             // Only close if NOT streaming (we need to keep receiving chunks)
             if !is_streaming {
                 self.cleanup_processing();
+                if let Some(summary) = self.last_turn.as_ref().and_then(StructuredResponse::summary) {
+                    self.status_message = summary;
+                }
             }
         } else if let Some(result) = final_response {
             self.handle_planning_response(result);
@@ -1016,12 +1264,16 @@ impl ModernApp {
         }
     }
 
-    fn handle_orchestrator_response(&mut self, result: Result<OrchestratorResponse, String>) {
+    fn handle_orchestrator_response(&mut self, result: Result<OrchestratorResponse, NeuroError>) {
         match result {
             Ok(response) => {
                 match response {
                     OrchestratorResponse::Text(text) => {
+                        if let Some(speech) = self.speech.as_mut() {
+                            speech.say(&text);
+                        }
                         self.add_message(MessageSender::Assistant, text, None);
+                        self.attach_turn_reasoning();
                         self.status.set_state(StatusState::Success);
                     }
                     OrchestratorResponse::ToolResult {
@@ -1039,7 +1291,11 @@ impl ModernApp {
                         self.screen = AppScreen::Confirmation;
                     }
                     OrchestratorResponse::Immediate { content, .. } => {
+                        if let Some(speech) = self.speech.as_mut() {
+                            speech.say(&content);
+                        }
                         self.add_message(MessageSender::Assistant, content, None);
+                        self.attach_turn_reasoning();
                         self.status.set_state(StatusState::Success);
                     }
                     OrchestratorResponse::Delegated { description, .. } => {
@@ -1060,6 +1316,8 @@ impl ModernApp {
                             timestamp: Instant::now(),
                             is_streaming: true,
                             tool_name: None,
+                            reasoning: None,
+                            translations: Translations::default(),
                         };
                         self.messages.push(msg);
                         self.auto_scroll = true;
@@ -1067,17 +1325,23 @@ impl ModernApp {
                 }
             }
             Err(e) => {
-                self.add_message(
-                    MessageSender::System,
-                    format!("{}: {}", t(Text::Error), e),
-                    None,
-                );
+                self.add_message(MessageSender::System, error_message(&e), None);
                 self.status.set_state(StatusState::Error);
             }
         }
     }
 
-    fn handle_planning_response(&mut self, result: Result<PlanningResponse, String>) {
+    /// `plan-finished` webhook for the plan of the request being processed
+    fn notify_plan_finished(&self, success: bool, summary: String) {
+        let title = match &self.active_plan {
+            Some(plan) => format!("Plan {}", plan.goal),
+            None => "Plan".to_string(),
+        };
+        let elapsed = self.processing_start.map(|started| started.elapsed()).unwrap_or_default();
+        webhooks::notify(TaskEvent::new(WebhookEventKind::PlanFinished, success, title, elapsed).with_summary(summary));
+    }
+
+    fn handle_planning_response(&mut self, result: Result<PlanningResponse, NeuroError>) {
         match result {
             Ok(response) => {
                 match response {
@@ -1107,6 +1371,7 @@ impl ModernApp {
                             format!("{}\n", clean_result), // Añadir línea extra al final
                             None,
                         );
+                        self.notify_plan_finished(true, clean_result.lines().next().unwrap_or_default().chars().take(200).collect());
                         self.show_plan_panel = false;
                         self.active_plan = None;
                         self.status.set_state(StatusState::Success);
@@ -1121,6 +1386,7 @@ impl ModernApp {
                             format!("❌ Plan failed after {} tasks: {}", tasks_completed, error),
                             None,
                         );
+                        self.notify_plan_finished(false, error);
                         self.show_plan_panel = false;
                         self.active_plan = None;
                         self.status.set_state(StatusState::Error);
@@ -1136,11 +1402,7 @@ impl ModernApp {
                 }
             }
             Err(e) => {
-                self.add_message(
-                    MessageSender::System,
-                    format!("{}: {}", t(Text::Error), e),
-                    None,
-                );
+                self.add_message(MessageSender::System, error_message(&e), None);
                 self.status.set_state(StatusState::Error);
                 self.show_plan_panel = false;
                 self.active_plan = None;
@@ -1149,6 +1411,14 @@ impl ModernApp {
     }
 
     fn cleanup(&mut self) -> io::Result<()> {
+        // Stop requests, tool subprocesses and index builds still running
+        for cancel in self.turn_cancel.iter().chain(&self.raptor_cancel) {
+            cancel.cancel();
+        }
+        // Experiment worktrees and branches don't outlive the app
+        if let Some(mut tabs) = self.experiments.take() {
+            tabs.set.discard();
+        }
         disable_raw_mode()?;
         execute!(
             self.terminal.backend_mut(),
@@ -1156,6 +1426,7 @@ impl ModernApp {
             DisableMouseCapture,
             Show
         )?;
+        crate::crash::set_tui_active(false);
         Ok(())
     }
 
@@ -1174,6 +1445,13 @@ impl ModernApp {
             status_render: self.status.render(),
             status_message: self.status_message.clone(),
             messages: &self.messages,
+            message_layout: &self.message_layout,
+            archived_messages: self
+                .history_archive
+                .as_ref()
+                .map_or(0, |archive| archive.archived_count()),
+            selected_message: self.selected_message,
+            message_action_selected: self.message_action_selected,
             input_buffer: self.input_buffer.clone(),
             scroll_offset: self.scroll_offset,
             is_processing: self.is_processing,
@@ -1198,6 +1476,22 @@ impl ModernApp {
             show_autocomplete: self.show_autocomplete,
             autocomplete_selected: self.autocomplete_selected,
             auto_scroll: self.auto_scroll,
+            tab_titles: self.experiments.as_ref().map(|tabs| tabs.titles()).unwrap_or_default(),
+            active_tab: self.experiments.as_ref().map_or(0, |tabs| tabs.active),
+            pending_reload_changes: self
+                .pending_reload
+                .as_ref()
+                .map(|reload| reload.changes.reconnect.clone())
+                .unwrap_or_default(),
+            privacy: privacy::privacy_level(),
+            network_strict: network::network_policy().strict,
+            dry_run: dry_run::enabled(),
+            blocked_outbound: network::blocked_count(),
+            context_budget: context_budget::last(),
+            show_context_budget: self.show_context_budget,
+            show_reasoning: self.show_reasoning,
+            pager: self.pager.as_ref(),
+            session_browser: self.session_browser.as_ref(),
         };
 
         self.terminal.draw(|frame| {
@@ -1225,9 +1519,11 @@ impl ModernApp {
             }
             self.last_ctrl_c = Some(now);
 
-            // Cancel processing on first Ctrl+C
+            // Cancel processing on first Ctrl+C, or else the index build
             if self.is_processing {
                 self.cancel_processing();
+            } else if self.raptor_indexing {
+                self.cancel_raptor_indexing();
             }
             return;
         }
@@ -1247,17 +1543,78 @@ impl ModernApp {
 
         match self.screen {
             AppScreen::Chat => self.handle_chat_keys(key).await,
-            AppScreen::Settings => self.handle_settings_keys(key),
+            AppScreen::Settings => self.handle_settings_keys(key).await,
             AppScreen::ModelConfig => self.handle_model_config_keys(key).await,
             AppScreen::IndexingPrompt => self.handle_indexing_prompt_keys(key).await,
             AppScreen::Confirmation => self.handle_confirmation_keys(key).await,
             AppScreen::Password => self.handle_password_keys(key).await,
+            AppScreen::MessageActions => self.handle_message_actions_keys(key).await,
+            AppScreen::ConfigReload => self.handle_config_reload_keys(key).await,
+            AppScreen::Pager => self.handle_pager_keys(key),
+            AppScreen::Sessions => self.handle_sessions_keys(key).await,
         }
     }
 
     async fn handle_chat_keys(&mut self, key: KeyEvent) {
+        // Ctrl+T - next experiment tab
+        if key.code == KeyCode::Char('t') && key.modifiers.contains(KeyModifiers::CONTROL) {
+            if let Some(tabs) = self.experiments.as_mut() {
+                tabs.switch(tabs.active + 1, &mut self.messages);
+                self.message_layout.borrow_mut().invalidate();
+                self.selected_message = None;
+                self.apply_user_scroll_to_end();
+            }
+            return;
+        }
+
+        // Ctrl+R - expand or collapse the reasoning of the answers
+        if key.code == KeyCode::Char('r') && key.modifiers.contains(KeyModifiers::CONTROL) {
+            self.show_reasoning = !self.show_reasoning;
+            self.message_layout.borrow_mut().invalidate();
+            return;
+        }
+
+        // Ctrl+B - context budget breakdown
+        if key.code == KeyCode::Char('b') && key.modifiers.contains(KeyModifiers::CONTROL) {
+            self.show_context_budget = !self.show_context_budget;
+            return;
+        }
+        if self.show_context_budget && key.code == KeyCode::Esc {
+            self.show_context_budget = false;
+            return;
+        }
+
+        // Ctrl+S - toggle message selection mode
+        if key.code == KeyCode::Char('s') && key.modifiers.contains(KeyModifiers::CONTROL) {
+            if self.selected_message.is_some() {
+                self.exit_message_selection();
+            } else if !self.messages.is_empty() {
+                self.select_message(self.messages.len() - 1);
+                self.status_message =
+                    "Selección: ↑↓ elegir mensaje, Enter acciones, Esc salir".to_string();
+            }
+            return;
+        }
+
+        if let Some(selected) = self.selected_message {
+            match key.code {
+                KeyCode::Up if selected > 0 => self.select_message(selected - 1),
+                KeyCode::Down if selected + 1 < self.messages.len() => {
+                    self.select_message(selected + 1)
+                }
+                KeyCode::Enter => {
+                    self.message_action_selected = MessageAction::Copy;
+                    self.screen = AppScreen::MessageActions;
+                }
+                KeyCode::Esc => self.exit_message_selection(),
+                _ => {}
+            }
+            return;
+        }
+
         match key.code {
             KeyCode::Tab if self.input_buffer.is_empty() => {
+                self.sync_settings_tools().await;
                 self.screen = AppScreen::Settings;
             }
             KeyCode::Enter if !self.input_buffer.is_empty() && !self.is_processing => {
@@ -1274,12 +1631,25 @@ impl ModernApp {
 
                 // Check for special commands
                 let input = self.input_buffer.trim();
-                if input == "/reindex" {
+                let on_experiment_tab = self.experiments.as_ref().is_some_and(|tabs| tabs.active != 0);
+                if input == "/try" || input.starts_with("/try ") {
+                    self.handle_try_command().await;
+                } else if on_experiment_tab {
+                    self.queue_experiment_task();
+                } else if input == "/reindex" {
                     self.handle_reindex_command().await;
                 } else if input == "/stats" {
                     self.handle_stats_command().await;
                 } else if input == "/help" {
                     self.handle_help_command().await;
+                } else if input.starts_with("/artifacts open") {
+                    self.handle_artifact_open_command().await;
+                } else if input == "/sessions" || input.starts_with("/sessions ") {
+                    self.handle_sessions_command().await;
+                } else if input == "/retry" || input == "/retry diff" {
+                    self.handle_retry_command().await;
+                } else if input == "/translate" || input.starts_with("/translate ") {
+                    self.handle_translate_command();
                 } else {
                     self.start_processing().await;
                 }
@@ -1374,38 +1744,29 @@ impl ModernApp {
         self.status_message = t(Text::Processing).to_string();
         self.spinner = Spinner::thinking(); // Reset spinner
         self.auto_scroll = true; // Reactivar auto-scroll al empezar a procesar
+        self.last_turn = None;
 
         // Get enabled tools
         let _enabled_tools = self.settings_panel.get_enabled_tool_ids();
 
-        // Create channel for background communication
-        // Large buffer to handle streaming responses with many chunks (e.g., repository analysis)
-        let (tx, rx) = mpsc::channel(5000);
-        self.response_rx = Some(rx);
-
-        // Create channel for progress updates
+        // Create channel for progress updates of the planning orchestrator
         let (progress_tx, mut progress_rx) = mpsc::channel::<TaskProgressInfo>(50);
 
-        // Clone orchestrator for background task
+        // Clone orchestrator and bus for background task
         let orchestrator = Arc::clone(&self.orchestrator);
-        let tx_clone = tx.clone();
+        let events = self.events.clone();
+        let cancel = CancellationToken::new();
+        self.turn_cancel = Some(cancel.clone());
+        let progress_events = events.clone();
 
-        // Spawn task to forward progress updates to main channel
+        // Spawn task to forward progress updates to the bus
         tokio::spawn(async move {
             while let Some(progress) = progress_rx.recv().await {
-                if tx_clone
-                    .send(AgentEvent::TaskProgress(progress))
-                    .await
-                    .is_err()
-                {
-                    break;
-                }
+                progress_events.publish(AgentEvent::TaskProgress(progress));
             }
         });
 
         // Spawn background task based on orchestrator type
-        // NOTE: We keep tx alive even after sending the response because the router
-        // may have spawned internal tasks that will send streaming chunks/events
         let task_handle = tokio::spawn(async move {
             let bg_start = std::time::Instant::now();
             log_debug!("🔧 [BG-TASK] Starting background task for query: '{}'", user_input);
@@ -1417,29 +1778,26 @@ impl ModernApp {
             };
 
             if is_router {
-                // Router orchestrator: configure channel without holding lock
+                // Router orchestrator: its status and stream events already go to the bus
                 log_debug!("🔧 [BG-TASK] Using Router orchestrator");
 
-                // Set event channel BEFORE acquiring lock (set_event_channel now takes &self)
-                {
-                    let orch = orchestrator.lock().await;
-                    if let OrchestratorWrapper::Router(router_orch) = &*orch {
-                        router_orch.set_event_channel_async(tx.clone()).await;
-                        log_debug!("🔧 [BG-TASK] Event channel set at {}ms", bg_start.elapsed().as_millis());
-                    }
-                } // Lock released here
-
                 // Now process WITHOUT holding the orchestrator lock
                 log_debug!("🔧 [BG-TASK] Calling router_orch.process() at {}ms", bg_start.elapsed().as_millis());
                 let process_start = std::time::Instant::now();
 
+                // The turn budget ends turns with a partial answer; this only
+                // catches work that ignores it
+                let mut hard_limit = 0;
                 let result = {
-                    let orch = orchestrator.lock().await;
-                    if let OrchestratorWrapper::Router(router_orch) = &*orch {
+                    let mut orch = orchestrator.lock().await;
+                    if let OrchestratorWrapper::Router(router_orch) = &mut *orch {
+                        hard_limit = router_orch.config().turn_budget_secs + TURN_BUDGET_GRACE_SECS;
                         let timeout_result = tokio::time::timeout(
-                            std::time::Duration::from_secs(120),
-                            router_orch.process(&user_input)
+                            std::time::Duration::from_secs(hard_limit),
+                            router_orch.process_structured(&user_input, cancel)
                         ).await;
+                        // A /cd moves the router-level context too
+                        router_orch.sync_working_dir().await;
                         timeout_result
                     } else {
                         // Wrong orchestrator type - treat as error
@@ -1452,24 +1810,26 @@ impl ModernApp {
                     bg_start.elapsed().as_millis());
 
                 let msg = match result {
-                    Ok(Ok(response)) => {
+                    Ok(Ok(structured)) => {
                         log_debug!("🔧 [BG-TASK] Response received successfully");
+                        let response = structured.response.clone();
+                        events.send(AgentEvent::TurnDetails(Box::new(structured))).await;
                         AgentEvent::Response(Ok(response))
                     },
                     Ok(Err(e)) => {
                         log_error!("Router orchestrator error: {}", e);
-                        AgentEvent::Response(Err(e.to_string()))
+                        AgentEvent::Response(Err(NeuroError::from(e)))
                     }
                     Err(_) => {
-                        let err_msg = "Timeout: El procesamiento tardó más de 120 segundos".to_string();
-                        log_error!("{}", err_msg);
-                        AgentEvent::Response(Err(err_msg))
+                        let err = NeuroError::ProviderTimeout(format!(
+                            "El procesamiento tardó más de {} segundos",
+                            hard_limit
+                        ));
+                        log_error!("{}", err);
+                        AgentEvent::Response(Err(err))
                     }
                 };
-                // Use try_send to avoid blocking if channel is closed
-                if tx.try_send(msg).is_err() {
-                    log_debug!("🔧 [BG-TASK] Failed to send response (channel closed or full)");
-                }
+                events.send(msg).await;
             } else {
                 // Planning orchestrator: needs &mut, keep lock for entire operation
                 let mut orch = orchestrator.lock().await;
@@ -1485,27 +1845,176 @@ impl ModernApp {
                         Ok(response) => AgentEvent::PlanningResponse(Ok(response)),
                         Err(e) => {
                             log_error!("Planning orchestrator error: {}", e);
-                            AgentEvent::PlanningResponse(Err(e.to_string()))
+                            AgentEvent::PlanningResponse(Err(e))
                         }
                     };
-                    // Use try_send to avoid blocking if channel is closed
-                    if tx.try_send(msg).is_err() {
-                        log_debug!("🔧 [BG-TASK] Failed to send planning response (channel closed or full)");
-                    }
+                    events.send(msg).await;
                 }
             } // Lock released here for planning
 
             log_debug!("🔧 [BG-TASK] Background task complete at {}ms", bg_start.elapsed().as_millis());
-
-            // The channel naturally stays alive until the router task completes or
-            // StreamEnd event is sent. No need to artificially keep it alive.
-            // When both sides of the channel are done, it will close automatically.
         });
 
         // Store the task handle so we can cancel it later if needed
         self.background_task_handle = Some(task_handle);
     }
 
+    /// Run the tool call the user approved in the confirmation dialog
+    fn start_confirmed_command(&mut self) {
+        self.is_processing = true;
+        self.processing_start = Some(Instant::now());
+        self.last_event_time = Some(Instant::now());
+        self.status.set_state(StatusState::Working);
+        self.spinner = Spinner::thinking();
+        self.auto_scroll = true;
+
+        let orchestrator = Arc::clone(&self.orchestrator);
+        let events = self.events.clone();
+        let cancel = CancellationToken::new();
+        self.turn_cancel = Some(cancel.clone());
+        let task_handle = tokio::spawn(async move {
+            let orch = orchestrator.lock().await;
+            if let OrchestratorWrapper::Router(router) = &*orch {
+                let result = router.confirm_pending(cancel).await.map_err(NeuroError::from);
+                events.send(AgentEvent::Response(result)).await;
+            }
+        });
+        self.background_task_handle = Some(task_handle);
+    }
+
+    /// Show the answers experiment runners sent since the last frame
+    fn poll_experiments(&mut self) {
+        if let Some(tabs) = self.experiments.as_mut() {
+            if tabs.poll(&mut self.messages) {
+                self.message_layout.borrow_mut().invalidate();
+                if self.auto_scroll {
+                    self.apply_user_scroll_to_end();
+                }
+            }
+        }
+    }
+
+    /// Input typed on an experiment tab goes to that experiment's queue
+    fn queue_experiment_task(&mut self) {
+        let prompt = std::mem::take(&mut self.input_buffer);
+        self.cursor_position = 0;
+        if let Some(tabs) = self.experiments.as_mut() {
+            tabs.enqueue(tabs.active, prompt, &mut self.messages);
+        }
+        self.auto_scroll = true;
+    }
+
+    /// /try: run approaches side by side in git worktrees and compare them
+    async fn handle_try_command(&mut self) {
+        let user_input = std::mem::take(&mut self.input_buffer);
+        self.cursor_position = 0;
+        let args = user_input.trim().trim_start_matches("/try").trim().to_string();
+
+        if !self.experiments_enabled {
+            self.add_message(MessageSender::User, user_input, None);
+            self.add_message(
+                MessageSender::System,
+                "🧪 /try es experimental: actívalo con \"experimental\": { \"parallel_worktrees\": true } en la configuración".to_string(),
+                None,
+            );
+            return;
+        }
+
+        let (action, rest) = args.split_once(' ').unwrap_or((args.as_str(), ""));
+        let reply = match (action, self.experiments.as_mut()) {
+            ("" | "status", Some(tabs)) => Ok(format!(
+                "{}\nCtrl+T cambia de pestaña · /try diff [A] · /try adopt <A> · /try discard",
+                tabs.set.compare()
+            )),
+            ("diff", Some(tabs)) if rest.trim().is_empty() => Ok(tabs.set.compare()),
+            ("diff", Some(tabs)) => tabs
+                .set
+                .get(rest.trim())
+                .and_then(|e| tabs.set.diff(e))
+                .map(|diff| if diff.patch.is_empty() { "Sin cambios".to_string() } else { format!("```diff\n{}```", diff.patch) })
+                .map_err(|e| e.to_string()),
+            ("adopt", Some(tabs)) if tabs.busy() => {
+                Err("Hay tareas en curso en los experimentos; espera a que terminen".to_string())
+            }
+            ("adopt", Some(tabs)) => match tabs.set.adopt(rest.trim()) {
+                Ok(diff) => {
+                    let label = rest.trim().to_uppercase();
+                    if let Some(tabs) = self.experiments.take() {
+                        tabs.close(&mut self.messages);
+                    }
+                    Ok(format!(
+                        "✅ Enfoque {} adoptado: {} archivos preparados (+{} -{}). Revisa y haz commit; los worktrees se eliminaron.",
+                        label,
+                        diff.files.len(),
+                        diff.insertions,
+                        diff.deletions
+                    ))
+                }
+                Err(e) => Err(e.to_string()),
+            },
+            ("discard", Some(_)) => {
+                if let Some(tabs) = self.experiments.take() {
+                    tabs.close(&mut self.messages);
+                }
+                Ok("🗑️ Experimentos descartados".to_string())
+            }
+            ("" | "status" | "diff" | "adopt" | "discard", None) => {
+                Err("No hay experimentos. Uso: /try [tarea ::] enfoque A | enfoque B".to_string())
+            }
+            (_, Some(_)) => Err("Ya hay experimentos en curso: /try adopt <A> o /try discard".to_string()),
+            (_, None) => self.start_experiments(&args).await,
+        };
+
+        self.message_layout.borrow_mut().invalidate();
+        self.add_message(MessageSender::User, user_input, None);
+        match reply {
+            Ok(text) => self.add_message(MessageSender::System, text, None),
+            Err(e) => self.add_message(MessageSender::System, format!("❌ {}", e), None),
+        }
+    }
+
+    /// Create the worktrees and a router per experiment, then open their tabs
+    async fn start_experiments(&mut self, args: &str) -> Result<String, String> {
+        let (task, approaches) = match args.split_once("::") {
+            Some((task, approaches)) => (Some(task.trim().to_string()), approaches),
+            None => (None, args),
+        };
+        let approaches = parse_approaches(approaches).map_err(|e| e.to_string())?;
+        let working_dir = std::env::current_dir().unwrap_or_default();
+        let mut set = ExperimentSet::create(&working_dir, approaches).map_err(|e| e.to_string())?;
+
+        let mut routers = Vec::new();
+        {
+            let orchestrator = self.orchestrator.lock().await;
+            let OrchestratorWrapper::Router(router) = &*orchestrator else {
+                set.discard();
+                return Err("/try necesita el RouterOrchestrator".to_string());
+            };
+            for experiment in set.experiments.clone() {
+                let title = format!("try {}: {}", experiment.label, experiment.approach);
+                match router.fork(&experiment.path, &title).await {
+                    Ok(fork) => routers.push(fork),
+                    Err(e) => {
+                        set.discard();
+                        return Err(format!("No se pudo crear el agente {}: {}", experiment.label, e));
+                    }
+                }
+            }
+        }
+
+        let summary: Vec<String> = set
+            .experiments
+            .iter()
+            .map(|e| format!("  [{}] {} → {}", e.label, e.approach, e.branch))
+            .collect();
+        self.experiments = Some(ExperimentTabs::start(set, routers, task.as_deref()));
+        Ok(format!(
+            "🧪 {} experimentos en marcha, cada uno en su worktree:\n{}\nCtrl+T cambia de pestaña; al terminar compara con /try diff y elige con /try adopt <A>",
+            summary.len(),
+            summary.join("\n")
+        ))
+    }
+
     /// Handle !reindex command to rebuild RAPTOR index
     async fn handle_reindex_command(&mut self) {
         let user_input = std::mem::take(&mut self.input_buffer);
@@ -1536,24 +2045,23 @@ impl ModernApp {
                 self.raptor_eta = None;
                 
                 let orchestrator_clone = Arc::clone(&orchestrator);
-                let (tx, rx) = mpsc::channel(100);
-                self.raptor_rx = Some(rx);
+                let events = self.events.clone();
+                let cancel = CancellationToken::new();
+                self.raptor_cancel = Some(cancel.clone());
                 
                 tokio::spawn(async move {
                     let mut orch = orchestrator_clone.lock().await;
                     if let OrchestratorWrapper::Router(router) = &mut *orch {
-                        match router.rebuild_raptor().await {
-                            Ok(summary) => {
-                                let _ = tx.try_send(AgentEvent::RaptorStatus(summary));
-                                let _ = tx.try_send(AgentEvent::RaptorComplete);
-                            }
-                            Err(e) => {
-                                let _ = tx.try_send(AgentEvent::RaptorStatus(
-                                    format!("❌ Error: {}", e)
-                                ));
-                                let _ = tx.try_send(AgentEvent::RaptorComplete);
-                            }
-                        }
+                        let summary = tokio::select! {
+                            biased;
+                            _ = cancel.cancelled() => return,
+                            result = router.rebuild_raptor() => match result {
+                                Ok(summary) => summary,
+                                Err(e) => format!("❌ Error: {}", e),
+                            },
+                        };
+                        events.send(AgentEvent::RaptorStatus(summary)).await;
+                        events.send(AgentEvent::RaptorComplete).await;
                     }
                 });
             }
@@ -1668,27 +2176,62 @@ impl ModernApp {
             // Code commands
             ("/code-review", "Análisis integral de código (linter + analyzer + deps)"),
             ("/analyze", "Análisis profundo de código y métricas"),
-            ("/refactor", "Refactorización de código (próximamente)"),
+            ("/callgraph", "Quién llama a una función y a qué llama"),
+            ("/deadcode", "Funciones y tipos sin referencias, por lenguaje"),
+            ("/refactor", "Refactorización de código; rename_symbol renombra con el AST"),
+            ("/replace", "Reemplazo en todo el proyecto con checklist y deshacer"),
             ("/format", "Formatear código con formatters automáticos"),
             ("/docs", "Generar documentación del proyecto"),
+            ("/estimate", "Archivos, tests y esfuerzo (S/M/L) de un cambio propuesto"),
             
             // Testing
             ("/test", "Ejecutar tests con detección automática"),
+            ("/profile", "Perfilar un comando y sugerir optimizaciones de sus puntos calientes"),
             
             // Git
             ("/commit", "Crear commit con mensaje auto-generado"),
+            ("/resolve", "Resolver conflictos de merge con propuestas del modelo"),
+            ("/try", "Probar enfoques en paralelo en worktrees (experimental)"),
             ("/commit-push-pr", "Commit, push y crear PR"),
+            ("/owners", "Quién conoce mejor un archivo o directorio; revisores sugeridos"),
             
             // Context
             ("/deps", "Analizar dependencias del proyecto"),
             ("/search", "Buscar en código con regex"),
             ("/context", "Ver información del proyecto"),
+            ("/preview", "Vista previa de CSV/JSONL/Parquet con estadísticas"),
+            ("/schema", "Ingerir el esquema de la base de datos al contexto"),
+            ("/remember", "Recordar un dato del proyecto para futuras sesiones"),
+            ("/memories", "Memorias del proyecto; /memories forget <id> olvida una"),
+            ("/examples", "Ejemplos de respuesta que el modelo imita; /examples save guarda la última"),
+            ("/artifacts", "Salidas largas guardadas; /artifacts open <id> las muestra, @artifact:<id> las adjunta"),
+            ("/why", "Explica la ruta y los filtros de recuperación de la última consulta"),
+            ("/sources", "Fuentes de la última respuesta; /sources irrelevant <n> penaliza una"),
+            ("/tag", "Etiquetar la sesión actual (/tag rm <etiqueta> la quita)"),
+            ("/export", "Exportar la sesión a HTML para compartir (/export md para Markdown)"),
+            ("/sessions", "Buscar sesiones pasadas por texto y #etiqueta"),
+            ("/retry", "Regenerar la última respuesta y ver qué cambió"),
+            ("/translate", "Ver la última respuesta en otro idioma (/translate off la devuelve)"),
             
             // System
             ("/plan", "Generar plan de ejecución (próximamente)"),
             ("/shell", "Ejecutar comando shell con seguridad"),
+            ("/env", "Variables de entorno de la sesión (set/unset/list)"),
+            ("/logs", "Seguir y resumir logs (--diagnose para analizarlos)"),
+            ("/tfplan", "Resumir un plan de Terraform (nunca aplica)"),
+            ("/new", "Crear un proyecto desde una plantilla (compila e indexa)"),
+            ("/recipe", "Ejecutar una receta de varios pasos (.neuro/recipes)"),
+            ("/start", "Empezar desde una plantilla de conversación; /start save guarda la actual"),
+            ("/config", "Capas de configuración; /config doctor revisa conflictos"),
+            ("/network", "Política de red saliente y peticiones bloqueadas"),
+            ("/preferences", "Preferencias de estilo de código y respuestas (globales o --project)"),
+            ("/tools", "Herramientas del modelo; activar o desactivar por proyecto"),
+            ("/cd", "Cambiar el directorio de trabajo de la sesión"),
+            ("/roots", "Raíces del espacio de trabajo; /roots add añade una de solo lectura"),
             ("/reindex", "Reconstruir índice RAPTOR"),
+            ("/continue", "Retomar el turno que se quedó sin tiempo"),
             ("/mode", "Cambiar modo del agente (próximamente)"),
+            ("/dryrun", "Simular: las herramientas describen lo que harían sin ejecutarlo"),
             ("/help", "Mostrar ayuda de comandos"),
             
             // Legacy
@@ -1723,32 +2266,55 @@ impl ModernApp {
 📝 Código:\n\
   /code-review    - Análisis integral (linter + analyzer + deps)\n\
   /analyze <file> - Análisis profundo de código\n\
-  /refactor       - Refactorización (próximamente)\n\
+  /callgraph [fn] - Quién llama a una función\n\
+  /deadcode [path] - Funciones y tipos sin referencias\n\
+  /refactor       - Refactorización (rename_symbol usa el AST)\n\
   /format <path>  - Formatear código\n\
-  /docs [path]    - Generar documentación\n\n\
+  /docs [path]    - Generar documentación\n\
+  /estimate <cambio> - Archivos, tests y esfuerzo (S/M/L) de un cambio\n\n\
 🧪 Testing:\n\
-  /test [pattern] - Ejecutar tests\n\n\
+  /test [pattern] - Ejecutar tests\n\
+  /test --detect-flaky [--runs N] [--parallel N] - Detectar tests inestables\n\
+  /profile <comando> - Perfilar y sugerir optimizaciones\n\n\
 🔧 Git:\n\
   /commit [msg]   - Commit con mensaje auto-generado\n\
-  /commit-push-pr - Commit, push y crear PR\n\n\
+  /commit-push-pr - Commit, push y crear PR\n\
+  /owners [ruta]  - Dueños del código y revisores sugeridos\n\n\
 🔍 Contexto:\n\
   /deps [path]    - Analizar dependencias\n\
   /search <query> - Buscar en código (--regex para regex)\n\
-  /context        - Información del proyecto\n\n\
+  /context        - Información del proyecto\n\
+  /remember <dato> - Recordar un dato del proyecto\n\
+  /memories       - Listar u olvidar memorias\n\
+  /examples       - Ejemplos de respuesta del proyecto\n\
+  /artifacts open <id> - Ver un artefacto completo (@artifact:<id> lo adjunta)\n\
+  /why [rules]    - Por qué la última consulta tomó su ruta y su contexto\n\
+  /sources        - Fuentes citadas; /sources irrelevant <n> penaliza una\n\
+  /tag <etiqueta> - Etiquetar la sesión actual\n\
+  /export [html|md] - Exportar la sesión (HTML con código resaltado y enlaces al repositorio)\n\
+  /sessions [texto] [#etiqueta] - Buscar, abrir, retomar o exportar sesiones\n\
+  /retry [diff]   - Regenerar la última respuesta y comparar con la anterior\n\
+  /translate <idioma> - Ver la última respuesta en otro idioma (off: original)\n\n\
 ⚙️ Sistema:\n\
   /plan <task>    - Generar plan (próximamente)\n\
   /shell <cmd>    - Ejecutar comando shell\n\
+  /start <plantilla> - Empezar desde una plantilla de conversación\n\
+  /preferences    - Preferencias de código y respuestas\n\
   /reindex        - Reconstruir índice RAPTOR\n\
+  /continue       - Retomar el turno que se quedó sin tiempo\n\
   /mode           - Cambiar modo (próximamente)\n\
+  /dryrun [on|off] - Simular cambios y revisar el plan antes de ejecutarlo\n\
   /help           - Mostrar esta ayuda\n\
   /stats          - Estadísticas del índice\n\n\
 🎹 Atajos de Teclado:\n\
   Tab        - Cambiar entre Chat/Settings/ModelConfig\n\
   Esc        - Volver al chat\n\
-  Ctrl+C     - Salir\n\
+  Ctrl+C     - Cancelar la respuesta o el indexado (dos veces: salir)\n\
+  Ctrl+S     - Seleccionar mensaje (copiar, re-ejecutar, citar, exportar)\n\
+  Ctrl+R     - Mostrar/ocultar el razonamiento de las respuestas\n\
   ↑/↓        - Navegar autocompletado / Scroll chat\n\
   PgUp/PgDn  - Scroll página completa\n\
-  Home/End   - Inicio/final del chat\n\n\
+  Home/End   - Inicio/final del chat (en el inicio: cargar historial archivado)\n\n\
 💡 Consejos:\n\
   • Escribe '/' para ver comandos disponibles\n\
   • Usa consultas naturales para análisis del proyecto\n\
@@ -1761,7 +2327,19 @@ impl ModernApp {
         );
     }
 
-    fn handle_settings_keys(&mut self, key: KeyEvent) {
+    /// Mirror the project's tool switches, also changed with /tools, in the Settings list
+    async fn sync_settings_tools(&mut self) {
+        // Busy with a request: keep the list as it is rather than wait
+        let Ok(orch) = self.orchestrator.try_lock() else {
+            return;
+        };
+        if let OrchestratorWrapper::Router(router) = &*orch {
+            let (listing, disabled) = router.tool_states().await;
+            self.settings_panel.sync_tools(&listing, &disabled);
+        }
+    }
+
+    async fn handle_settings_keys(&mut self, key: KeyEvent) {
         match key.code {
             KeyCode::Tab => {
                 // Tab: Settings -> ModelConfig
@@ -1778,7 +2356,17 @@ impl ModernApp {
                 self.settings_panel.move_down();
             }
             KeyCode::Char(' ') | KeyCode::Enter => {
-                self.settings_panel.toggle_selected();
+                if let Some((tool, enabled)) = self.settings_panel.toggle_selected() {
+                    // Saved for the project like /tools, without blocking the UI on a running request
+                    let orchestrator = Arc::clone(&self.orchestrator);
+                    tokio::spawn(async move {
+                        if let OrchestratorWrapper::Router(router) = &*orchestrator.lock().await {
+                            if let Err(e) = router.set_tool_enabled(&tool, enabled).await {
+                                log_error!("Could not save tool state for {}: {}", tool, e);
+                            }
+                        }
+                    });
+                }
             }
             KeyCode::Char('l') | KeyCode::Char('L') => {
                 // Toggle language
@@ -1906,10 +2494,10 @@ impl ModernApp {
                 let url_clone = format!("{}/api/tags", config.fast_model.url);
                 let ollama_url = config.fast_model.url.clone();
                 
-                match reqwest::Client::new()
+                match network::client()
                     .get(&url_clone)
                     .timeout(Duration::from_secs(5))
-                    .send()
+                    .send_checked("model provider")
                     .await
                 {
                     Ok(response) if response.status().is_success() => {
@@ -1945,11 +2533,15 @@ impl ModernApp {
             KeyCode::Char('y') | KeyCode::Char('Y') => {
                 if let Some(cmd) = self.pending_command.take() {
                     self.add_message(MessageSender::System, format!("Executing: {}", cmd), None);
+                    self.start_confirmed_command();
                 }
                 self.screen = AppScreen::Chat;
             }
             KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc => {
                 self.pending_command = None;
+                if let OrchestratorWrapper::Router(router) = &*self.orchestrator.lock().await {
+                    router.cancel_pending().await;
+                }
                 self.add_message(MessageSender::System, t(Text::Cancelled).to_string(), None);
                 self.screen = AppScreen::Chat;
             }
@@ -1957,31 +2549,625 @@ impl ModernApp {
         }
     }
 
-    async fn handle_password_keys(&mut self, key: KeyEvent) {
-        match key.code {
-            KeyCode::Enter => {
-                self.password_input.clear();
-                self.password_error = None;
-                self.screen = AppScreen::Chat;
+    /// Apply live config changes and ask about the ones that need a reconnection.
+    /// The question waits until the user is back in the chat, and reloads that
+    /// arrive meanwhile are merged into the pending one
+    async fn poll_config_reload(&mut self) {
+        if let Some(reload) = self.config_watcher.as_mut().and_then(|w| w.try_recv()) {
+            if let OrchestratorWrapper::Router(router) = &mut *self.orchestrator.lock().await {
+                router.apply_config(&reload).await;
             }
-            KeyCode::Esc => {
-                self.password_input.clear();
-                self.password_error = None;
-                self.pending_command = None;
-                self.screen = AppScreen::Chat;
-            }
-            KeyCode::Char(c) => {
-                self.password_input.push(c);
+            self.enable_accessibility(&reload.config.accessibility);
+            let summary = reload.changes.summary();
+            self.status_message = summary.clone();
+            self.add_message(MessageSender::System, summary, None);
+
+            match &mut self.pending_reload {
+                Some(pending) => pending.merge(reload),
+                None if !reload.changes.reconnect.is_empty() => self.pending_reload = Some(reload),
+                None => {}
             }
-            KeyCode::Backspace => {
-                self.password_input.pop();
-            }
-            _ => {}
+        }
+
+        if self.pending_reload.is_some() && self.screen == AppScreen::Chat {
+            self.screen = AppScreen::ConfigReload;
         }
     }
 
-    async fn handle_indexing_prompt_keys(&mut self, key: KeyEvent) {
-        match key.code {
+    /// Open a stored tool output in the pager
+    async fn handle_artifact_open_command(&mut self) {
+        let user_input = std::mem::take(&mut self.input_buffer);
+        self.cursor_position = 0;
+        self.show_autocomplete = false;
+        self.add_message(MessageSender::User, user_input.clone(), None);
+
+        let id = user_input
+            .trim()
+            .trim_start_matches("/artifacts open")
+            .trim()
+            .trim_start_matches('#')
+            .parse::<i64>();
+        let Ok(id) = id else {
+            self.add_message(MessageSender::System, "Uso: /artifacts open <id>".to_string(), None);
+            return;
+        };
+        let artifact = match &*self.orchestrator.lock().await {
+            OrchestratorWrapper::Router(router) => router.artifact(id).await,
+            OrchestratorWrapper::Planning(_) => Err(anyhow::anyhow!("not supported by the planning orchestrator")),
+        };
+        match artifact {
+            Ok(Some(artifact)) => {
+                let title = format!("#{} {}", artifact.id, artifact.source);
+                self.pager = Some(PagerView::new(title, &artifact.content));
+                self.screen = AppScreen::Pager;
+            }
+            Ok(None) => self.add_message(MessageSender::System, format!("No hay un artefacto #{}", id), None),
+            Err(e) => self.add_message(
+                MessageSender::System,
+                format!("No se pudo abrir el artefacto #{}: {}", id, e),
+                None,
+            ),
+        }
+    }
+
+    /// Regenerate the last answer (/retry) or show the last regeneration diff (/retry diff)
+    async fn handle_retry_command(&mut self) {
+        let user_input = std::mem::take(&mut self.input_buffer);
+        self.cursor_position = 0;
+        self.show_autocomplete = false;
+
+        if user_input.trim() == "/retry diff" {
+            match self.last_retry_diff.clone() {
+                Some(diff) => self.open_response_diff(&diff),
+                None => self.add_message(
+                    MessageSender::System,
+                    "Aún no hay una respuesta regenerada con /retry".to_string(),
+                    None,
+                ),
+            }
+            return;
+        }
+
+        let prompt = self
+            .messages
+            .iter()
+            .rposition(|m| m.sender == MessageSender::User && !m.content.starts_with('/'));
+        match prompt {
+            Some(prompt) => self.retry_prompt(prompt).await,
+            None => self.add_message(
+                MessageSender::System,
+                "No hay un prompt anterior para reintentar".to_string(),
+                None,
+            ),
+        }
+    }
+
+    /// Send the prompt at `index` again, remembering the answer it got
+    async fn retry_prompt(&mut self, index: usize) {
+        // The answer is the last one before the next prompt
+        let answer = self.messages[index + 1..]
+            .iter()
+            .take_while(|m| m.sender != MessageSender::User || m.content.starts_with('/'))
+            .filter(|m| m.sender == MessageSender::Assistant && !m.is_streaming && !m.content.trim().is_empty())
+            .last()
+            .map(|m| m.content.clone());
+        self.retry_from = answer.map(|answer| RetrySource {
+            answer,
+            started: Instant::now(),
+        });
+        self.input_buffer = self.messages[index].content.clone();
+        self.cursor_position = self.input_buffer.len();
+        self.start_processing().await;
+    }
+
+    /// Compare the regenerated answer with the previous one and open the diff
+    fn show_retry_diff(&mut self, source: RetrySource) {
+        let answer = self
+            .messages
+            .iter()
+            .rev()
+            .take_while(|m| m.timestamp >= source.started)
+            .find(|m| m.sender == MessageSender::Assistant && !m.is_streaming && !m.content.trim().is_empty())
+            .map(|m| m.content.clone());
+        // Cancelled or failed: there is nothing to compare
+        let Some(answer) = answer else {
+            return;
+        };
+
+        let diff = ResponseDiff::between(&source.answer, &answer);
+        if diff.is_unchanged() {
+            self.add_message(
+                MessageSender::System,
+                "🔁 La respuesta regenerada es idéntica a la anterior".to_string(),
+                None,
+            );
+        } else {
+            let (added, removed, edited) = diff.counts();
+            self.add_message(
+                MessageSender::System,
+                format!(
+                    "🔁 Respuesta regenerada: {} líneas nuevas, {} quitadas, {} editadas · /retry diff para ver los cambios",
+                    added, removed, edited
+                ),
+                None,
+            );
+            if self.screen == AppScreen::Chat {
+                self.open_response_diff(&diff);
+            }
+        }
+        self.last_retry_diff = Some(diff);
+    }
+
+    fn open_response_diff(&mut self, diff: &ResponseDiff) {
+        self.pager = Some(PagerView::diff(
+            "Cambios respecto a la respuesta anterior".to_string(),
+            diff,
+        ));
+        self.screen = AppScreen::Pager;
+    }
+
+    /// /translate <lang>: show the last answer in another language, cached
+    /// with the message; /translate off goes back to the original
+    fn handle_translate_command(&mut self) {
+        let user_input = std::mem::take(&mut self.input_buffer);
+        self.cursor_position = 0;
+        self.show_autocomplete = false;
+        let arg = user_input.trim().trim_start_matches("/translate").trim();
+
+        let Some(index) = self
+            .messages
+            .iter()
+            .rposition(|m| m.sender == MessageSender::Assistant && !m.is_streaming && !m.content.trim().is_empty())
+        else {
+            self.add_message(MessageSender::System, "No hay una respuesta que traducir".to_string(), None);
+            return;
+        };
+        if arg.is_empty() {
+            self.add_message(
+                MessageSender::System,
+                "Uso: /translate <idioma> (en, es, fr, de, pt...) · /translate off vuelve al original".to_string(),
+                None,
+            );
+            return;
+        }
+        if arg == "off" {
+            self.messages[index].translations.show(None);
+            self.message_layout.borrow_mut().invalidate();
+            self.status_message = "Mostrando la respuesta original".to_string();
+            return;
+        }
+        let Some(language) = translation::language_name(arg) else {
+            self.add_message(MessageSender::System, format!("Idioma no reconocido: {}", arg), None);
+            return;
+        };
+        if self.messages[index].translations.show(Some(&language)) {
+            self.message_layout.borrow_mut().invalidate();
+            self.status_message = format!("🌐 Traducción a {} (guardada)", language);
+            return;
+        }
+        if self.translation_task.is_some() {
+            self.status_message = "Ya hay una traducción en curso".to_string();
+            return;
+        }
+
+        let (tx, rx) = oneshot::channel();
+        let orchestrator = Arc::clone(&self.orchestrator);
+        let text = self.messages[index].content.clone();
+        let target = language.clone();
+        tokio::spawn(async move {
+            let result = match &*orchestrator.lock().await {
+                OrchestratorWrapper::Router(router) => router.translate(&text, &target).await.map_err(|e| e.to_string()),
+                OrchestratorWrapper::Planning(_) => Err("not supported by the planning orchestrator".to_string()),
+            };
+            let _ = tx.send(result);
+        });
+        self.translation_task = Some(PendingTranslation {
+            message: self.messages[index].timestamp,
+            language: language.clone(),
+            result: rx,
+        });
+        self.status_message = format!("🌐 Traduciendo a {}...", language);
+    }
+
+    /// Attach a finished translation to its message and show it
+    fn poll_translation(&mut self) {
+        let Some(task) = self.translation_task.as_mut() else {
+            return;
+        };
+        let result = match task.result.try_recv() {
+            Ok(result) => result,
+            Err(oneshot::error::TryRecvError::Empty) => return,
+            Err(oneshot::error::TryRecvError::Closed) => Err("the translation task stopped".to_string()),
+        };
+        let Some(task) = self.translation_task.take() else {
+            return;
+        };
+        match result {
+            Ok(text) => match self.messages.iter_mut().find(|m| m.timestamp == task.message) {
+                Some(message) => {
+                    message.translations.insert(&task.language, text);
+                    self.message_layout.borrow_mut().invalidate();
+                    self.status_message = format!("🌐 Traducido a {}", task.language);
+                }
+                // Archived meanwhile: the translation has nowhere to go
+                None => self.status_message = "La respuesta ya no está en pantalla".to_string(),
+            },
+            Err(e) => self.add_message(MessageSender::System, format!("No se pudo traducir: {}", e), None),
+        }
+    }
+
+    /// Open the session browser, searching for the text after /sessions
+    async fn handle_sessions_command(&mut self) {
+        let user_input = std::mem::take(&mut self.input_buffer);
+        self.cursor_position = 0;
+        self.show_autocomplete = false;
+
+        if self.history_archive.is_none() {
+            self.add_message(MessageSender::User, user_input, None);
+            self.add_message(
+                MessageSender::System,
+                "El buscador de sesiones necesita la base de datos de sesiones".to_string(),
+                None,
+            );
+            return;
+        }
+        self.session_browser = Some(SessionBrowser {
+            query: user_input.trim().trim_start_matches("/sessions").trim().to_string(),
+            ..SessionBrowser::default()
+        });
+        self.search_sessions().await;
+        self.screen = AppScreen::Sessions;
+    }
+
+    /// Run the search typed in the session browser
+    async fn search_sessions(&mut self) {
+        let Some(query) = self.session_browser.as_ref().map(|browser| SessionQuery::parse(&browser.query)) else {
+            return;
+        };
+        let results = match &*self.orchestrator.lock().await {
+            OrchestratorWrapper::Router(router) => router.search_sessions(&query).await,
+            OrchestratorWrapper::Planning(_) => Err(anyhow::anyhow!("not supported by the planning orchestrator")),
+        };
+        let results = results.unwrap_or_else(|e| {
+            log_error!("Failed to search sessions: {}", e);
+            self.status_message = format!("No se pudieron buscar sesiones: {}", e);
+            Vec::new()
+        });
+        if let Some(browser) = self.session_browser.as_mut() {
+            browser.results = results;
+            browser.selected = 0;
+        }
+    }
+
+    async fn handle_sessions_keys(&mut self, key: KeyEvent) {
+        let Some(browser) = self.session_browser.as_mut() else {
+            self.screen = AppScreen::Chat;
+            return;
+        };
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        match key.code {
+            KeyCode::Esc => {
+                self.session_browser = None;
+                self.screen = AppScreen::Chat;
+            }
+            KeyCode::Up => browser.select_by(-1),
+            KeyCode::Down => browser.select_by(1),
+            KeyCode::PageUp => browser.select_by(-10),
+            KeyCode::PageDown => browser.select_by(10),
+            KeyCode::Enter => self.open_session_transcript().await,
+            KeyCode::Char('r') if ctrl => self.resume_selected_session().await,
+            KeyCode::Char('e') if ctrl => self.export_selected_session().await,
+            KeyCode::Char('w') if ctrl => self.export_selected_session_html().await,
+            KeyCode::Backspace => {
+                if browser.query.pop().is_some() {
+                    self.search_sessions().await;
+                }
+            }
+            KeyCode::Char(c) if !ctrl => {
+                browser.query.push(c);
+                self.search_sessions().await;
+            }
+            _ => {}
+        }
+    }
+
+    /// Transcript of the session selected in the browser, as Markdown
+    async fn selected_session_transcript(&mut self) -> Option<(String, String)> {
+        let id = self.session_browser.as_ref()?.current()?.session.id.clone();
+        let transcript = match &*self.orchestrator.lock().await {
+            OrchestratorWrapper::Router(router) => router.session_transcript(&id).await,
+            OrchestratorWrapper::Planning(_) => Err(anyhow::anyhow!("not supported by the planning orchestrator")),
+        };
+        match transcript {
+            Ok(Some(transcript)) => Some((id, transcript)),
+            Ok(None) => {
+                self.status_message = format!("La sesión {} ya no existe", id);
+                None
+            }
+            Err(e) => {
+                self.status_message = format!("No se pudo leer la sesión {}: {}", id, e);
+                None
+            }
+        }
+    }
+
+    async fn open_session_transcript(&mut self) {
+        if let Some((id, transcript)) = self.selected_session_transcript().await {
+            self.pager = Some(PagerView::new(format!("Sesión {}", id), &transcript));
+            self.screen = AppScreen::Pager;
+        }
+    }
+
+    /// Write the selected session as Markdown in the working directory
+    async fn export_selected_session(&mut self) {
+        let Some((id, transcript)) = self.selected_session_transcript().await else {
+            return;
+        };
+        let working_dir = std::env::current_dir().unwrap_or_default();
+        let path = working_dir.join(format!("neuro-session-{}.md", id.chars().take(8).collect::<String>()));
+        self.status_message = match std::fs::write(&path, transcript) {
+            Ok(()) => format!("📄 Sesión exportada a {}", path.display()),
+            Err(e) => format!("No se pudo exportar la sesión: {}", e),
+        };
+    }
+
+    /// Write the selected session as a standalone HTML page in the working directory
+    async fn export_selected_session_html(&mut self) {
+        let Some(id) = self
+            .session_browser
+            .as_ref()
+            .and_then(|browser| browser.current())
+            .map(|summary| summary.session.id.clone())
+        else {
+            return;
+        };
+        let page = match &*self.orchestrator.lock().await {
+            OrchestratorWrapper::Router(router) => router.session_html(&id).await,
+            OrchestratorWrapper::Planning(_) => Err(anyhow::anyhow!("not supported by the planning orchestrator")),
+        };
+        let working_dir = std::env::current_dir().unwrap_or_default();
+        let path = working_dir.join(format!("neuro-session-{}.html", id.chars().take(8).collect::<String>()));
+        self.status_message = match page {
+            Ok(Some(page)) => match std::fs::write(&path, page) {
+                Ok(()) => format!("🌐 Sesión exportada a {}", path.display()),
+                Err(e) => format!("No se pudo exportar la sesión: {}", e),
+            },
+            Ok(None) => format!("La sesión {} ya no existe", id),
+            Err(e) => format!("No se pudo leer la sesión {}: {}", id, e),
+        };
+    }
+
+    /// Continue the selected session: its messages replace the chat and new
+    /// ones are stored in it
+    async fn resume_selected_session(&mut self) {
+        if self.is_processing {
+            self.status_message = "Espera a que termine la respuesta para retomar otra sesión".to_string();
+            return;
+        }
+        let (Some(id), Some(max_messages)) = (
+            self.session_browser
+                .as_ref()
+                .and_then(|browser| browser.current())
+                .map(|summary| summary.session.id.clone()),
+            self.history_archive.as_ref().map(|archive| archive.max_messages()),
+        ) else {
+            return;
+        };
+
+        let store = match &*self.orchestrator.lock().await {
+            OrchestratorWrapper::Router(router) => router.resume_session(&id).await,
+            OrchestratorWrapper::Planning(_) => Err(anyhow::anyhow!("not supported by the planning orchestrator")),
+        };
+        let resumed = match store {
+            Ok(store) => HistoryArchive::resume(store.db, id.clone(), max_messages)
+                .await
+                .map_err(anyhow::Error::from),
+            Err(e) => Err(e),
+        };
+        let (archive, messages) = match resumed {
+            Ok(resumed) => resumed,
+            Err(e) => {
+                log_error!("Failed to resume session {}: {}", id, e);
+                self.status_message = format!("No se pudo retomar la sesión {}: {}", id, e);
+                return;
+            }
+        };
+
+        self.messages = messages.into_iter().map(display_message).collect();
+        self.message_layout.borrow_mut().invalidate();
+        self.history_archive = Some(archive);
+        self.selected_message = None;
+        self.auto_scroll = true;
+        self.session_browser = None;
+        self.screen = AppScreen::Chat;
+        self.add_message(MessageSender::System, format!("↩️ Sesión {} retomada", id), None);
+    }
+
+    fn handle_pager_keys(&mut self, key: KeyEvent) {
+        let Some(pager) = self.pager.as_mut() else {
+            self.screen = AppScreen::Chat;
+            return;
+        };
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('q') => {
+                self.pager = None;
+                // A transcript opened from the session browser goes back to it
+                self.screen = if self.session_browser.is_some() {
+                    AppScreen::Sessions
+                } else {
+                    AppScreen::Chat
+                };
+            }
+            KeyCode::Up | KeyCode::Char('k') => pager.scroll_by(-1),
+            KeyCode::Down | KeyCode::Char('j') => pager.scroll_by(1),
+            KeyCode::PageUp => pager.scroll_by(-20),
+            KeyCode::PageDown | KeyCode::Char(' ') => pager.scroll_by(20),
+            KeyCode::Home | KeyCode::Char('g') => pager.scroll = 0,
+            KeyCode::End | KeyCode::Char('G') => pager.scroll_by(isize::MAX),
+            _ => {}
+        }
+    }
+
+    async fn handle_config_reload_keys(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Char('y') | KeyCode::Char('Y') => {
+                self.screen = AppScreen::Chat;
+                let Some(reload) = self.pending_reload.take() else {
+                    return;
+                };
+                let result = match &mut *self.orchestrator.lock().await {
+                    OrchestratorWrapper::Router(router) => router.reconnect(&reload.config).await,
+                    OrchestratorWrapper::Planning(_) => Err(anyhow::anyhow!("not supported by the planning orchestrator")),
+                };
+                let message = match result {
+                    Ok(()) => format!(
+                        "Reconectado: {} / {}",
+                        reload.config.fast_model.model, reload.config.heavy_model.model
+                    ),
+                    Err(e) => format!("No se pudo reconectar, se mantiene la conexión actual: {}", e),
+                };
+                self.status_message = message.clone();
+                self.add_message(MessageSender::System, message, None);
+            }
+            KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc => {
+                self.pending_reload = None;
+                self.screen = AppScreen::Chat;
+                self.add_message(
+                    MessageSender::System,
+                    "Los cambios de proveedor/modelo se aplicarán al reiniciar".to_string(),
+                    None,
+                );
+            }
+            _ => {}
+        }
+    }
+
+    async fn handle_message_actions_keys(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Up | KeyCode::Char('k') => {
+                self.message_action_selected = self.message_action_selected.prev();
+            }
+            KeyCode::Down | KeyCode::Char('j') => {
+                self.message_action_selected = self.message_action_selected.next();
+            }
+            KeyCode::Enter => {
+                self.screen = AppScreen::Chat;
+                self.execute_message_action().await;
+            }
+            KeyCode::Esc => {
+                self.screen = AppScreen::Chat;
+            }
+            _ => {}
+        }
+    }
+
+    /// Select a message and scroll the chat so it is visible
+    fn select_message(&mut self, index: usize) {
+        self.selected_message = Some(index);
+        self.auto_scroll = false;
+        self.scroll_offset = self.message_layout.borrow().row_offset(index);
+    }
+
+    fn exit_message_selection(&mut self) {
+        self.selected_message = None;
+        self.auto_scroll = true;
+        self.status_message = t(Text::Ready).to_string();
+    }
+
+    async fn execute_message_action(&mut self) {
+        let Some(index) = self.selected_message else {
+            return;
+        };
+        let Some(msg) = self.messages.get(index).cloned() else {
+            return;
+        };
+
+        match self.message_action_selected {
+            MessageAction::Copy => match message_actions::copy_to_clipboard(&msg.content) {
+                Ok(()) => self.status_message = "📋 Mensaje copiado".to_string(),
+                Err(e) => self.status_message = format!("Error al copiar: {}", e),
+            },
+            MessageAction::CopyCode => {
+                let code = message_actions::extract_code_blocks(&msg.content);
+                if code.is_empty() {
+                    self.status_message = "El mensaje no contiene bloques de código".to_string();
+                } else {
+                    match message_actions::copy_to_clipboard(&code) {
+                        Ok(()) => self.status_message = "📋 Código copiado".to_string(),
+                        Err(e) => self.status_message = format!("Error al copiar: {}", e),
+                    }
+                }
+            }
+            MessageAction::Rerun => {
+                // The originating prompt is the closest user message at or before the selection
+                let prompt = self.messages[..=index]
+                    .iter()
+                    .rposition(|m| m.sender == MessageSender::User);
+
+                match prompt {
+                    Some(prompt) if !self.is_processing => {
+                        self.exit_message_selection();
+                        self.retry_prompt(prompt).await;
+                    }
+                    Some(_) => {
+                        self.status_message = "Espera a que termine la tarea actual".to_string();
+                    }
+                    None => {
+                        self.status_message = "No se encontró el prompt original".to_string();
+                    }
+                }
+            }
+            MessageAction::Quote => {
+                let quoted = message_actions::quote(&msg.content);
+                self.input_buffer.insert_str(0, &quoted);
+                self.cursor_position = self.input_buffer.len();
+                self.exit_message_selection();
+            }
+            MessageAction::Export => {
+                let dir = std::env::current_dir().unwrap_or_default();
+                let label = match msg.sender {
+                    MessageSender::User => "Tú",
+                    MessageSender::Assistant => "Asistente",
+                    MessageSender::System => "Sistema",
+                    MessageSender::Tool => "Tarea",
+                };
+                match message_actions::export_message(&dir, label, &msg.content) {
+                    Ok(path) => {
+                        self.status_message = format!("💾 Exportado a {}", path.display());
+                    }
+                    Err(e) => {
+                        self.status_message = format!("Error al exportar: {}", e);
+                    }
+                }
+            }
+        }
+    }
+
+    async fn handle_password_keys(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Enter => {
+                self.password_input.clear();
+                self.password_error = None;
+                self.screen = AppScreen::Chat;
+            }
+            KeyCode::Esc => {
+                self.password_input.clear();
+                self.password_error = None;
+                self.pending_command = None;
+                self.screen = AppScreen::Chat;
+            }
+            KeyCode::Char(c) => {
+                self.password_input.push(c);
+            }
+            KeyCode::Backspace => {
+                self.password_input.pop();
+            }
+            _ => {}
+        }
+    }
+
+    async fn handle_indexing_prompt_keys(&mut self, key: KeyEvent) {
+        match key.code {
             KeyCode::Up | KeyCode::Char('k') => {
                 self.indexing_prompt_selected = self.indexing_prompt_selected.prev();
             }
@@ -2097,7 +3283,11 @@ impl ModernApp {
     }
 
     fn cancel_processing(&mut self) {
-        // Abort the background task if it's running
+        // Stop what the turn started (requests, tool subprocesses, streams),
+        // then the background task itself
+        if let Some(cancel) = self.turn_cancel.take() {
+            cancel.cancel();
+        }
         if let Some(handle) = self.background_task_handle.take() {
             handle.abort();
         }
@@ -2106,7 +3296,6 @@ impl ModernApp {
         self.processing_start = None;
         self.last_event_time = None;
         self.current_thinking = None;
-        self.response_rx = None;
         self.status.set_state(StatusState::Warning);
         self.status_message = t(Text::Cancelled).to_string();
         self.add_message(MessageSender::System, t(Text::Cancelled).to_string(), None);
@@ -2115,17 +3304,30 @@ impl ModernApp {
     fn cleanup_processing(&mut self) {
         // Clean up background task and processing state
         self.background_task_handle = None;
+        self.turn_cancel = None;
         self.is_processing = false;
         self.processing_start = None;
         self.last_event_time = None;
         self.current_thinking = None;
         self.status_message = t(Text::Ready).to_string();
         self.status.set_state(StatusState::Idle);
-        self.response_rx = None;
+
+        for mailbox in self.events.metrics().mailboxes.iter().filter(|m| m.dropped > 0) {
+            log_debug!(
+                "📥 [BUS] Mailbox '{}' dropped {} events (high water {}/{})",
+                mailbox.name, mailbox.dropped, mailbox.high_water, mailbox.capacity
+            );
+        }
 
         // Clean up streaming buffer
         self.streaming_buffer = None;
+        self.reasoning_buffer = None;
         self.streaming_chunks_count = 0;
+
+        // A regenerated answer is compared with the one it replaces
+        if let Some(source) = self.retry_from.take() {
+            self.show_retry_diff(source);
+        }
     }
 
     fn add_message(&mut self, sender: MessageSender, content: String, tool_name: Option<String>) {
@@ -2135,14 +3337,28 @@ impl ModernApp {
             timestamp: Instant::now(),
             is_streaming: false,
             tool_name,
+            reasoning: None,
+            translations: Translations::default(),
         });
         // Note: auto_scroll is handled dynamically in render_chat_output
         // When auto_scroll=true, it always scrolls to the bottom regardless of scroll_offset
     }
 
+    /// Give the answer just added the reasoning recorded for its turn
+    fn attach_turn_reasoning(&mut self) {
+        let reasoning = self.last_turn.as_ref().and_then(|turn| turn.reasoning.clone());
+        if let (Some(reasoning), Some(message)) = (reasoning, self.messages.last_mut()) {
+            message.reasoning = Some(reasoning);
+        }
+    }
+
     /// Apply a user-initiated scroll. This always disables auto-scroll and makes
     /// sure the view moves at least one line so the first scroll isn't ignored.
     fn apply_user_scroll(&mut self, delta: isize) {
+        if delta < 0 && !self.auto_scroll && self.scroll_offset == 0 {
+            // Already at the top: bring back archived history
+            self.history_load_requested = true;
+        }
         self.auto_scroll = false;
 
         if delta < 0 {
@@ -2158,6 +3374,9 @@ impl ModernApp {
     }
 
     fn apply_user_scroll_to_start(&mut self) {
+        if !self.auto_scroll && self.scroll_offset == 0 {
+            self.history_load_requested = true;
+        }
         self.auto_scroll = false;
         self.scroll_offset = 0;
     }
@@ -2168,6 +3387,73 @@ impl ModernApp {
         self.auto_scroll = true;
     }
 
+    /// Archive messages over the history cap and load older ones on request
+    async fn sync_history_archive(&mut self) {
+        // Only the main chat belongs to the archived session
+        if self.experiments.as_ref().is_some_and(|tabs| tabs.active != 0) {
+            return;
+        }
+        let Some(archive) = self.history_archive.as_mut() else {
+            return;
+        };
+
+        if std::mem::take(&mut self.history_load_requested) {
+            match archive.load_older(SCROLLBACK_PAGE).await {
+                Ok(older) if !older.is_empty() => {
+                    let count = older.len();
+                    self.messages.splice(0..0, older.into_iter().map(display_message));
+                    self.message_layout.borrow_mut().invalidate();
+                    if let Some(selected) = self.selected_message.as_mut() {
+                        *selected += count;
+                    }
+                    self.status_message = format!("📦 {} mensajes recuperados del historial", count);
+                }
+                Ok(_) => {}
+                Err(e) => log_error!("Failed to load archived messages: {}", e),
+            }
+            return;
+        }
+
+        // Store finished messages as they arrive, for /sessions
+        let finished = self.messages.iter().take_while(|m| !m.is_streaming).count();
+        if finished > archive.persisted_in_memory() {
+            let pending = self.messages[..finished].iter().map(archived_message).collect();
+            if let Err(e) = archive.persist(pending).await {
+                log_error!("Failed to store chat history: {}", e);
+            }
+        }
+
+        // Only trim while following the conversation, so the view never jumps
+        if !self.auto_scroll || self.selected_message.is_some() {
+            return;
+        }
+
+        // Never archive a message that is still streaming
+        let overflow = archive.overflow(self.messages.len());
+        let count = self
+            .messages
+            .iter()
+            .take(overflow)
+            .take_while(|m| !m.is_streaming)
+            .count();
+        if count == 0 {
+            return;
+        }
+
+        let evicted = self.messages[..count].iter().map(archived_message).collect();
+
+        match archive.archive(evicted).await {
+            Ok(()) => {
+                self.messages.drain(..count);
+                self.message_layout.borrow_mut().drop_front(count);
+            }
+            Err(e) => {
+                // Keep the messages in memory rather than losing them
+                log_error!("Failed to archive chat history: {}", e);
+            }
+        }
+    }
+
     fn handle_mouse_event(&mut self, mouse: MouseEvent) {
         match mouse.kind {
             MouseEventKind::ScrollUp => {
@@ -2190,14 +3476,56 @@ impl ModernApp {
     }
 }
 
+/// Chat text for an error, followed by the category-specific hint if there is one
+fn error_message(err: &NeuroError) -> String {
+    match err.hint() {
+        Some(hint) => format!("{}: {}\n💡 {}", t(Text::Error), err, hint),
+        None => format!("{}: {}", t(Text::Error), err),
+    }
+}
+
+/// Database role for a message sender
+fn sender_role(sender: &MessageSender) -> &'static str {
+    match sender {
+        MessageSender::User => "user",
+        MessageSender::Assistant => "assistant",
+        MessageSender::System => "system",
+        MessageSender::Tool => "tool",
+    }
+}
+
+fn sender_from_role(role: &str) -> MessageSender {
+    match role {
+        "user" => MessageSender::User,
+        "assistant" => MessageSender::Assistant,
+        "tool" => MessageSender::Tool,
+        _ => MessageSender::System,
+    }
+}
+
+fn archived_message(message: &DisplayMessage) -> ArchivedMessage {
+    ArchivedMessage {
+        role: sender_role(&message.sender).to_string(),
+        content: message.content.clone(),
+        tool_name: message.tool_name.clone(),
+    }
+}
+
+fn display_message(message: ArchivedMessage) -> DisplayMessage {
+    DisplayMessage {
+        sender: sender_from_role(&message.role),
+        content: message.content,
+        timestamp: Instant::now(),
+        is_streaming: false,
+        tool_name: message.tool_name,
+        reasoning: None,
+        translations: Translations::default(),
+    }
+}
+
 impl Drop for ModernApp {
     fn drop(&mut self) {
-        let _ = disable_raw_mode();
-        let _ = execute!(
-            self.terminal.backend_mut(),
-            LeaveAlternateScreen,
-            DisableMouseCapture
-        );
+        crate::crash::restore_terminal();
     }
 }
 
@@ -2211,6 +3539,10 @@ struct RenderData<'a> {
     status_render: (&'static str, (u8, u8, u8)),
     status_message: String,
     messages: &'a [DisplayMessage],
+    message_layout: &'a RefCell<MessageLayoutCache>,
+    archived_messages: usize,
+    selected_message: Option<usize>,
+    message_action_selected: MessageAction,
     input_buffer: String,
     scroll_offset: usize,
     is_processing: bool,
@@ -2235,6 +3567,19 @@ struct RenderData<'a> {
     show_autocomplete: bool,
     autocomplete_selected: usize,
     auto_scroll: bool,
+    tab_titles: Vec<String>,
+    active_tab: usize,
+    pending_reload_changes: Vec<String>,
+    privacy: PrivacyLevel,
+    network_strict: bool,
+    /// Mutating tools only describe their actions (/dryrun)
+    dry_run: bool,
+    blocked_outbound: usize,
+    context_budget: Option<ContextBudget>,
+    show_context_budget: bool,
+    show_reasoning: bool,
+    pager: Option<&'a PagerView>,
+    session_browser: Option<&'a SessionBrowser>,
 }
 
 fn render_ui(frame: &mut Frame, data: &RenderData) {
@@ -2243,7 +3588,7 @@ fn render_ui(frame: &mut Frame, data: &RenderData) {
     frame.render_widget(Block::default().style(data.theme.base_style()), area);
 
     match data.screen {
-        AppScreen::Chat => {
+        AppScreen::Chat | AppScreen::MessageActions => {
             // Two-column layout: main chat + history sidebar
             let columns = Layout::default()
                 .direction(Direction::Horizontal)
@@ -2253,22 +3598,33 @@ fn render_ui(frame: &mut Frame, data: &RenderData) {
                 ])
                 .split(area);
 
-            // Left column: output (top) + input (bottom) + status
+            // Left column: experiment tabs (if any) + output + input + status
+            let tabs_height = if data.tab_titles.is_empty() { 0 } else { 1 };
             let left_chunks = Layout::default()
                 .direction(Direction::Vertical)
                 .constraints([
+                    Constraint::Length(tabs_height), // Experiment tabs
                     Constraint::Min(10),   // Output (scrollable)
                     Constraint::Length(5), // Input (3 lines + borders)
                     Constraint::Length(1), // Status bar
                 ])
                 .split(columns[0]);
 
-            render_chat_output(frame, left_chunks[0], data);
-            render_input(frame, left_chunks[1], data);
-            render_status_bar(frame, left_chunks[2], data);
+            if tabs_height > 0 {
+                render_tab_bar(frame, left_chunks[0], data);
+            }
+            render_chat_output(frame, left_chunks[1], data);
+            render_input(frame, left_chunks[2], data);
+            render_status_bar(frame, left_chunks[3], data);
 
             // Right column: task history
             render_history_sidebar(frame, columns[1], data);
+
+            if data.screen == AppScreen::MessageActions {
+                render_message_actions_modal(frame, area, data);
+            } else if data.show_context_budget {
+                render_context_budget_popup(frame, area, data);
+            }
         }
         AppScreen::Settings => {
             let chunks = Layout::default()
@@ -2335,7 +3691,158 @@ fn render_ui(frame: &mut Frame, data: &RenderData) {
             render_status_bar(frame, chunks[2], data);
             render_password_modal(frame, area, data);
         }
+        AppScreen::ConfigReload => {
+            let chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([
+                    Constraint::Min(10),
+                    Constraint::Length(5),
+                    Constraint::Length(1),
+                ])
+                .split(area);
+
+            render_chat_output(frame, chunks[0], data);
+            render_input(frame, chunks[1], data);
+            render_status_bar(frame, chunks[2], data);
+            render_config_reload_modal(frame, area, data);
+        }
+        AppScreen::Pager => render_pager(frame, area, data),
+        AppScreen::Sessions => render_session_browser(frame, area, data),
+    }
+}
+
+fn render_pager(frame: &mut Frame, area: Rect, data: &RenderData) {
+    let Some(pager) = data.pager else {
+        return;
+    };
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(3), Constraint::Length(1)])
+        .split(area);
+
+    let lines: Vec<Line> = pager
+        .lines
+        .iter()
+        .skip(pager.scroll)
+        .take(chunks[0].height.saturating_sub(2) as usize)
+        .map(|line| {
+            let style = match line.chars().next() {
+                Some(marker) if pager.diff && marker == LineChange::Added.marker() => data.theme.success_style(),
+                Some(marker) if pager.diff && marker == LineChange::Removed.marker() => data.theme.error_style(),
+                Some(marker) if pager.diff && marker == LineChange::Edited.marker() => data.theme.warning_style(),
+                _ => data.theme.base_style(),
+            };
+            Line::from(Span::styled(line.clone(), style))
+        })
+        .collect();
+    let block = Block::default()
+        .borders(data.theme.borders())
+        .border_style(data.theme.primary_style())
+        .border_type(ratatui::widgets::BorderType::Rounded)
+        .title(Span::styled(format!(" 📎 {} ", pager.title), data.theme.primary_style()))
+        .style(data.theme.base_style());
+    frame.render_widget(Paragraph::new(lines).block(block), chunks[0]);
+
+    let footer = format!(
+        " Líneas {}-{} de {} · ↑↓/PgUp/PgDn desplazar · g/G inicio/fin · Esc/q cerrar",
+        (pager.scroll + 1).min(pager.lines.len()),
+        (pager.scroll + chunks[0].height.saturating_sub(2) as usize).min(pager.lines.len()),
+        pager.lines.len()
+    );
+    frame.render_widget(Paragraph::new(footer).style(data.theme.muted_style()), chunks[1]);
+}
+
+fn render_session_browser(frame: &mut Frame, area: Rect, data: &RenderData) {
+    let Some(browser) = data.session_browser else {
+        return;
+    };
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(3), Constraint::Min(5), Constraint::Length(1)])
+        .split(area);
+    let columns = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(55), Constraint::Percentage(45)])
+        .split(rows[1]);
+    let block = |title: String| {
+        Block::default()
+            .borders(data.theme.borders())
+            .border_style(data.theme.primary_style())
+            .border_type(ratatui::widgets::BorderType::Rounded)
+            .title(Span::styled(title, data.theme.primary_style()))
+            .style(data.theme.base_style())
+    };
+
+    let search = Paragraph::new(Line::from(vec![
+        Span::styled(browser.query.clone(), data.theme.base_style()),
+        Span::styled("█", data.theme.primary_style()),
+    ]))
+    .block(block(" 🔎 Buscar sesiones (texto y #etiquetas) ".to_string()));
+    frame.render_widget(search, rows[0]);
+
+    // Keep the selected session in view
+    let height = columns[0].height.saturating_sub(2) as usize;
+    let skip = (browser.selected + 1).saturating_sub(height.max(1));
+    let items: Vec<ListItem> = browser
+        .results
+        .iter()
+        .enumerate()
+        .skip(skip)
+        .take(height)
+        .map(|(i, summary)| {
+            let date = summary.session.updated_at.get(..10).unwrap_or(&summary.session.updated_at);
+            let tags: String = summary.tags.iter().map(|tag| format!(" #{}", tag)).collect();
+            let style = if i == browser.selected {
+                data.theme.selection_style()
+            } else {
+                data.theme.base_style()
+            };
+            ListItem::new(Line::from(vec![
+                Span::styled(format!("{} ", date), data.theme.muted_style()),
+                Span::styled(session_search::describe(summary), style),
+                Span::styled(tags, data.theme.primary_style()),
+            ]))
+        })
+        .collect();
+    let title = format!(" 🗂️ Sesiones ({}) ", browser.results.len());
+    if items.is_empty() {
+        let empty = Paragraph::new("Ninguna sesión coincide con la búsqueda")
+            .style(data.theme.muted_style())
+            .block(block(title));
+        frame.render_widget(empty, columns[0]);
+    } else {
+        frame.render_widget(List::new(items).block(block(title)), columns[0]);
+    }
+
+    let details: Vec<Line> = browser
+        .current()
+        .map(session_search::details)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|line| Line::from(Span::styled(line, data.theme.base_style())))
+        .collect();
+    let details = Paragraph::new(details)
+        .wrap(Wrap { trim: false })
+        .block(block(" Resumen ".to_string()));
+    frame.render_widget(details, columns[1]);
+
+    let footer = " ↑↓ elegir · Enter abrir · Ctrl+R retomar · Ctrl+E exportar a Markdown · Ctrl+W a HTML · Esc cerrar";
+    frame.render_widget(Paragraph::new(footer).style(data.theme.muted_style()), rows[2]);
+}
+
+fn render_tab_bar(frame: &mut Frame, area: Rect, data: &RenderData) {
+    let mut spans = Vec::new();
+    for (i, title) in data.tab_titles.iter().enumerate() {
+        let style = if i == data.active_tab {
+            data.theme.selection_style().add_modifier(Modifier::BOLD)
+        } else {
+            data.theme.muted_style()
+        };
+        spans.push(Span::styled(format!(" {} ", title), style));
+        spans.push(Span::raw(" "));
     }
+    spans.push(Span::styled("Ctrl+T cambia de pestaña", data.theme.muted_style()));
+    frame.render_widget(Paragraph::new(Line::from(spans)), area);
 }
 
 fn render_header(frame: &mut Frame, area: Rect, data: &RenderData) {
@@ -2372,7 +3879,7 @@ fn render_header(frame: &mut Frame, area: Rect, data: &RenderData) {
     let right_info = format!("{} {} ", locale_str, current_locale().display_name());
 
     let block = Block::default()
-        .borders(Borders::ALL)
+        .borders(data.theme.borders())
         .border_style(if data.is_processing {
             data.theme.warning_style()
         } else {
@@ -2392,14 +3899,44 @@ fn render_header(frame: &mut Frame, area: Rect, data: &RenderData) {
     );
 }
 
-/// Parse a line of text with basic markdown support (bold, italic, code)
+/// `src/main.rs:12:5` style citations (search matches, references, errors)
+static CITATION_RE: std::sync::LazyLock<regex::Regex> = std::sync::LazyLock::new(|| {
+    regex::Regex::new(r"(?:^|[\s(\[])((?:\.{0,2}/)?(?:[\w.-]+/)*[\w-][\w.-]*\.\w+:\d+(?::\d+)?)").unwrap()
+});
+
+/// Char ranges of the `path:line[:column]` citations in a line
+fn citation_ranges(text: &str) -> Vec<std::ops::Range<usize>> {
+    CITATION_RE
+        .captures_iter(text)
+        .filter_map(|caps| caps.get(1))
+        .map(|m| {
+            let start = text[..m.start()].chars().count();
+            start..start + m.as_str().chars().count()
+        })
+        .collect()
+}
+
+/// Parse a line of text with basic markdown support (bold, italic, code) and
+/// underline citations so terminals that detect `path:line` make them clickable
 fn parse_markdown_line<'a>(text: &'a str, base_style: Style, accent_style: Style) -> Vec<Span<'a>> {
     let mut spans: Vec<Span> = Vec::new();
     let mut current_pos = 0;
     let chars: Vec<char> = text.chars().collect();
     let len = chars.len();
+    let citations = citation_ranges(text);
 
     while current_pos < len {
+        // Check for path:line[:column] citations
+        if let Some(citation) = citations.iter().find(|r| r.start == current_pos) {
+            let citation_text: String = chars[citation.clone()].iter().collect();
+            spans.push(Span::styled(
+                citation_text,
+                accent_style.add_modifier(Modifier::UNDERLINED),
+            ));
+            current_pos = citation.end;
+            continue;
+        }
+
         // Check for **bold**
         if current_pos + 1 < len && chars[current_pos] == '*' && chars[current_pos + 1] == '*' {
             if let Some(end) = find_closing(&chars, current_pos + 2, "**") {
@@ -2437,39 +3974,182 @@ fn parse_markdown_line<'a>(text: &'a str, base_style: Style, accent_style: Style
             }
         }
 
-        // Regular character - collect until next special char
-        let start = current_pos;
-        while current_pos < len && !matches!(chars[current_pos], '*' | '_' | '`') {
-            current_pos += 1;
+        // Regular character - collect until next special char or citation.
+        // An unmatched marker is plain text.
+        let start = current_pos;
+        if matches!(chars[current_pos], '*' | '_' | '`') {
+            current_pos += 1;
+        }
+        while current_pos < len
+            && !matches!(chars[current_pos], '*' | '_' | '`')
+            && !citations.iter().any(|r| r.start == current_pos)
+        {
+            current_pos += 1;
+        }
+        if start < current_pos {
+            let regular_text: String = chars[start..current_pos].iter().collect();
+            spans.push(Span::styled(regular_text, base_style));
+        }
+    }
+
+    if spans.is_empty() {
+        spans.push(Span::styled(text.to_string(), base_style));
+    }
+
+    spans
+}
+
+fn find_closing(chars: &[char], start: usize, pattern: &str) -> Option<usize> {
+    let pattern_chars: Vec<char> = pattern.chars().collect();
+    let pattern_len = pattern_chars.len();
+
+    (start..chars.len().saturating_sub(pattern_len - 1)).find(|&i| chars[i..i + pattern_len] == pattern_chars[..])
+}
+
+fn find_closing_char(chars: &[char], start: usize, marker: char) -> Option<usize> {
+    (start..chars.len()).find(|&i| chars[i] == marker)
+}
+
+#[cfg(test)]
+mod tests_markdown {
+    use super::*;
+
+    fn texts(spans: &[Span]) -> Vec<String> {
+        spans.iter().map(|s| s.content.to_string()).collect()
+    }
+
+    #[test]
+    fn test_citation_ranges() {
+        let line = "src/tools/file_reader.rs:12:5: let x = 1; see (lib.rs:3) not 10:30";
+        let ranges = citation_ranges(line);
+        let found: Vec<String> = ranges
+            .iter()
+            .map(|r| line.chars().skip(r.start).take(r.len()).collect())
+            .collect();
+        assert_eq!(found, vec!["src/tools/file_reader.rs:12:5", "lib.rs:3"]);
+    }
+
+    #[test]
+    fn test_parse_markdown_line_citations_and_stray_markers() {
+        let style = Style::default();
+        let spans = parse_markdown_line("src/file_reader.rs:4:1: a_b **x**", style, style);
+        assert_eq!(texts(&spans), vec!["src/file_reader.rs:4:1", ": a", "_b ", "x"]);
+        assert!(spans[0].style.add_modifier.contains(Modifier::UNDERLINED));
+    }
+}
+
+/// Build the display lines of a single chat message
+fn message_lines<'a>(msg: &'a DisplayMessage, theme: &Theme, show_reasoning: bool) -> Vec<Line<'a>> {
+    let mut lines: Vec<Line> = Vec::new();
+
+    let (icon, label, style) = match msg.sender {
+        MessageSender::User => (Icons::USER, "Tú", theme.user_style()),
+        MessageSender::Assistant => {
+            (Icons::ASSISTANT, "Asistente", theme.assistant_style())
         }
-        if start < current_pos {
-            let regular_text: String = chars[start..current_pos].iter().collect();
-            spans.push(Span::styled(regular_text, base_style));
+        MessageSender::System => (Icons::SYSTEM, "Sistema", theme.system_style()),
+        MessageSender::Tool => (Icons::TOOL, "Tarea", theme.tool_style()),
+    };
+
+    // Only show header for non-System messages
+    if !matches!(msg.sender, MessageSender::System) {
+        // Header with icon and label
+        let header = if let Some(ref tool) = msg.tool_name {
+            Line::from(vec![
+                Span::styled(format!("{} ", icon), style),
+                Span::styled(label.to_string(), style.add_modifier(Modifier::BOLD)),
+                Span::styled(format!(" [{}]", tool), theme.code_style()),
+            ])
+        } else {
+            Line::from(vec![
+                Span::styled(format!("{} ", icon), style),
+                Span::styled(label.to_string(), style.add_modifier(Modifier::BOLD)),
+            ])
+        };
+        lines.push(header);
+    }
+
+    // Reasoning of thinking models: one line when collapsed, dimmed text when expanded
+    if let Some(reasoning) = &msg.reasoning {
+        let reasoning_style = theme.muted_style().add_modifier(Modifier::ITALIC);
+        let count = reasoning.lines().count();
+        let (marker, hint) = if show_reasoning { ("▾", "ocultar") } else { ("▸", "mostrar") };
+        lines.push(Line::from(vec![
+            Span::raw("   "),
+            Span::styled(
+                format!("{} Razonamiento ({} líneas) · Ctrl+R para {}", marker, count, hint),
+                reasoning_style,
+            ),
+        ]));
+        if show_reasoning {
+            for reasoning_line in reasoning.lines() {
+                lines.push(Line::from(vec![
+                    Span::raw("   │ "),
+                    Span::styled(reasoning_line, reasoning_style),
+                ]));
+            }
         }
     }
 
-    if spans.is_empty() {
-        spans.push(Span::styled(text.to_string(), base_style));
+    // Translations replace the text on screen only
+    if let Some((language, _)) = msg.translations.shown() {
+        lines.push(Line::from(vec![
+            Span::raw("   "),
+            Span::styled(
+                format!("🌐 Traducido a {} · /translate off muestra el original", language),
+                theme.muted_style().add_modifier(Modifier::ITALIC),
+            ),
+        ]));
     }
 
-    spans
-}
+    // Parse content with markdown support
+    // PERFORMANCE FIX: Limit lines rendered during streaming to prevent UI freeze
+    let content_lines: Vec<&str> = msg.shown_content().lines().collect();
+    let lines_to_render = if msg.is_streaming && content_lines.len() > 500 {
+        // During streaming, only show last 500 lines to keep rendering fast
+        &content_lines[content_lines.len() - 500..]
+    } else {
+        // Not streaming or small enough: render everything
+        &content_lines[..]
+    };
 
-fn find_closing(chars: &[char], start: usize, pattern: &str) -> Option<usize> {
-    let pattern_chars: Vec<char> = pattern.chars().collect();
-    let pattern_len = pattern_chars.len();
+    if msg.is_streaming && content_lines.len() > 500 {
+        // Show indicator that we're truncating
+        let truncated_line = Line::from(vec![
+            Span::raw("   "),
+            Span::styled(
+                format!("... (mostrando últimas 500 de {} líneas) ...", content_lines.len()),
+                theme.system_style().add_modifier(Modifier::ITALIC)
+            )
+        ]);
+        lines.push(truncated_line);
+    }
 
-    (start..chars.len().saturating_sub(pattern_len - 1)).find(|&i| chars[i..i + pattern_len] == pattern_chars[..])
-}
+    for content_line in lines_to_render {
+        let spans = parse_markdown_line(content_line, style, theme.accent_style());
+        // For System messages, no indent; for others, 3 spaces alignment
+        let line_spans = if matches!(msg.sender, MessageSender::System) {
+            spans
+        } else {
+            let mut indented = vec![Span::raw("   ")]; // 3 spaces for alignment with icon
+            indented.extend(spans);
+            indented
+        };
+        lines.push(Line::from(line_spans));
+    }
 
-fn find_closing_char(chars: &[char], start: usize, marker: char) -> Option<usize> {
-    (start..chars.len()).find(|&i| chars[i] == marker)
+    // Add blank line only for non-System messages (System messages are compact)
+    if !matches!(msg.sender, MessageSender::System) {
+        lines.push(Line::from(""));
+    }
+
+    lines
 }
 
 fn render_chat_output(frame: &mut Frame, area: Rect, data: &RenderData) {
 
-    let block = Block::default()
-        .borders(Borders::ALL)
+    let mut block = Block::default()
+        .borders(data.theme.borders())
         .border_style(
             data.theme
                 .border_style(data.screen == AppScreen::Chat && !data.is_processing),
@@ -2477,6 +4157,17 @@ fn render_chat_output(frame: &mut Frame, area: Rect, data: &RenderData) {
         .border_type(ratatui::widgets::BorderType::Rounded)
         .title(Span::styled(" Output ", data.theme.primary_style()));
 
+    // Older history lives in the session database until scrolled back into view
+    if data.archived_messages > 0 {
+        block = block.title(
+            Line::from(Span::styled(
+                format!(" 📦 {} mensajes archivados (↑ al inicio para cargar) ", data.archived_messages),
+                data.theme.muted_style(),
+            ))
+            .right_aligned(),
+        );
+    }
+
     let inner = block.inner(area);
     frame.render_widget(block, area);
 
@@ -2488,77 +4179,26 @@ fn render_chat_output(frame: &mut Frame, area: Rect, data: &RenderData) {
         height: inner.height,
     };
 
-    let mut lines: Vec<Line> = Vec::new();
-
-    for msg in data.messages {
-        let (icon, label, style) = match msg.sender {
-            MessageSender::User => (Icons::USER, "Tú", data.theme.user_style()),
-            MessageSender::Assistant => {
-                (Icons::ASSISTANT, "Asistente", data.theme.assistant_style())
-            }
-            MessageSender::System => (Icons::SYSTEM, "Sistema", data.theme.system_style()),
-            MessageSender::Tool => (Icons::TOOL, "Tarea", data.theme.tool_style()),
-        };
-
-        // Only show header for non-System messages
-        if !matches!(msg.sender, MessageSender::System) {
-            // Header with icon and label
-            let header = if let Some(ref tool) = msg.tool_name {
-                Line::from(vec![
-                    Span::styled(format!("{} ", icon), style),
-                    Span::styled(label.to_string(), style.add_modifier(Modifier::BOLD)),
-                    Span::styled(format!(" [{}]", tool), data.theme.code_style()),
-                ])
-            } else {
-                Line::from(vec![
-                    Span::styled(format!("{} ", icon), style),
-                    Span::styled(label.to_string(), style.add_modifier(Modifier::BOLD)),
-                ])
-            };
-            lines.push(header);
-        }
-
-        // Parse content with markdown support
-        // PERFORMANCE FIX: Limit lines rendered during streaming to prevent UI freeze
-        let content_lines: Vec<&str> = msg.content.lines().collect();
-        let lines_to_render = if msg.is_streaming && content_lines.len() > 500 {
-            // During streaming, only show last 500 lines to keep rendering fast
-            &content_lines[content_lines.len() - 500..]
-        } else {
-            // Not streaming or small enough: render everything
-            &content_lines[..]
-        };
-
-        if msg.is_streaming && content_lines.len() > 500 {
-            // Show indicator that we're truncating
-            let truncated_line = Line::from(vec![
-                Span::raw("   "),
-                Span::styled(
-                    format!("... (mostrando últimas 500 de {} líneas) ...", content_lines.len()),
-                    data.theme.system_style().add_modifier(Modifier::ITALIC)
-                )
-            ]);
-            lines.push(truncated_line);
-        }
+    let visible_lines = padded_inner.height as usize;
+    let wrap_width = padded_inner.width as usize;
 
-        for content_line in lines_to_render {
-            let spans = parse_markdown_line(content_line, style, data.theme.accent_style());
-            // For System messages, no indent; for others, 3 spaces alignment
-            let line_spans = if matches!(msg.sender, MessageSender::System) {
-                spans
-            } else {
-                let mut indented = vec![Span::raw("   ")]; // 3 spaces for alignment with icon
-                indented.extend(spans);
-                indented
-            };
-            lines.push(Line::from(line_spans));
-        }
+    // Virtualized layout: only messages that changed since the last frame are
+    // re-wrapped, and only the ones intersecting the viewport are rendered
+    let mut layout = data.message_layout.borrow_mut();
+    layout.update(
+        padded_inner.width,
+        data.messages
+            .iter()
+            .map(|m| (m.timestamp, m.shown_content().len(), m.is_streaming)),
+        |index| {
+            message_lines(&data.messages[index], &data.theme, data.show_reasoning)
+                .iter()
+                .map(|line| wrapped_rows(line, wrap_width))
+                .sum()
+        },
+    );
 
-        // Add blank line only for non-System messages (System messages are compact)
-        if !matches!(msg.sender, MessageSender::System) {
-            lines.push(Line::from(""));
-        }
-    }
+    let mut tail_lines: Vec<Line> = Vec::new();
 
     // Add simple spinner when processing
     if data.is_processing {
@@ -2584,7 +4224,7 @@ fn render_chat_output(frame: &mut Frame, area: Rect, data: &RenderData) {
             " "
         };
 
-        lines.push(Line::from(vec![
+        tail_lines.push(Line::from(vec![
             Span::styled(
                 format!("{:<2}", Icons::ASSISTANT),
                 data.theme.assistant_style(),
@@ -2603,23 +4243,11 @@ fn render_chat_output(frame: &mut Frame, area: Rect, data: &RenderData) {
         ]));
     }
 
-    let visible_lines = padded_inner.height as usize;
-
-    // Calcular líneas reales considerando el wrap
-    // Cada línea puede ocupar más de una fila si es más ancha que el área
-    let wrap_width = padded_inner.width as usize;
-    let mut total_wrapped_lines: usize = 0;
-    for (_idx, line) in lines.iter().enumerate() {
-        let line_width: usize = line.spans.iter().map(|s| s.content.chars().count()).sum();
-        if line_width == 0 {
-            total_wrapped_lines += 1; // Línea vacía
-        } else {
-            // Cuántas líneas ocupa después del wrap
-            total_wrapped_lines += (line_width + wrap_width - 1) / wrap_width.max(1);
-        }
-    }
-
-    let total_lines = total_wrapped_lines;
+    let tail_rows: usize = tail_lines
+        .iter()
+        .map(|line| wrapped_rows(line, wrap_width))
+        .sum();
+    let total_lines = layout.total_rows() + tail_rows;
 
     // Calculate scroll with proper clamping
     // When auto_scroll is true, always scroll to the bottom
@@ -2630,8 +4258,31 @@ fn render_chat_output(frame: &mut Frame, area: Rect, data: &RenderData) {
         data.scroll_offset.min(max_scroll)  // Use manual scroll offset
     };
 
+    let window = layout.window(scroll, visible_lines);
+    let skip_rows = if window.first == data.messages.len() {
+        // Only the trailing spinner is visible
+        scroll - layout.total_rows()
+    } else {
+        window.skip_rows
+    };
+
+    let mut lines: Vec<Line> = Vec::new();
+    for (index, msg) in data.messages[window.first..window.last].iter().enumerate() {
+        let msg_lines = message_lines(msg, &data.theme, data.show_reasoning);
+        if data.selected_message == Some(window.first + index) {
+            // Highlight without changing widths so cached heights stay valid
+            let highlight = Style::default().bg(Color::Rgb(45, 45, 70));
+            lines.extend(msg_lines.into_iter().map(|line| line.patch_style(highlight)));
+        } else {
+            lines.extend(msg_lines);
+        }
+    }
+    if window.last == data.messages.len() {
+        lines.extend(tail_lines);
+    }
+
     let paragraph = Paragraph::new(lines)
-        .scroll((scroll as u16, 0))
+        .scroll((skip_rows as u16, 0))
         .wrap(Wrap { trim: false });
 
     frame.render_widget(paragraph, padded_inner);
@@ -2658,7 +4309,7 @@ fn render_chat_output(frame: &mut Frame, area: Rect, data: &RenderData) {
 
 fn render_history_sidebar(frame: &mut Frame, area: Rect, data: &RenderData) {
     let block = Block::default()
-        .borders(Borders::ALL)
+        .borders(data.theme.borders())
         .border_style(data.theme.border_style(false))
         .border_type(ratatui::widgets::BorderType::Rounded)
         .title(Span::styled(" History ", data.theme.primary_style()));
@@ -2707,7 +4358,7 @@ fn render_input(frame: &mut Frame, area: Rect, data: &RenderData) {
     let is_focused = data.screen == AppScreen::Chat && !data.is_processing;
 
     let block = Block::default()
-        .borders(Borders::ALL)
+        .borders(data.theme.borders())
         .border_style(if data.is_processing {
             data.theme.warning_style()
         } else {
@@ -2810,7 +4461,7 @@ fn render_input(frame: &mut Frame, area: Rect, data: &RenderData) {
 
 fn render_settings(frame: &mut Frame, area: Rect, data: &RenderData) {
     let block = Block::default()
-        .borders(Borders::ALL)
+        .borders(data.theme.borders())
         .border_style(data.theme.border_style(true))
         .border_type(ratatui::widgets::BorderType::Rounded)
         .title(Span::styled(
@@ -2822,7 +4473,7 @@ fn render_settings(frame: &mut Frame, area: Rect, data: &RenderData) {
     frame.render_widget(block, area);
 
     let tools_block = Block::default()
-        .borders(Borders::ALL)
+        .borders(data.theme.borders())
         .border_style(data.theme.border_style(false))
         .title(Span::styled(
             format!(" {} ", t(Text::ToolsTitle)),
@@ -2916,7 +4567,7 @@ fn render_settings_footer(frame: &mut Frame, area: Rect, data: &RenderData) {
     ]);
 
     let block = Block::default()
-        .borders(Borders::ALL)
+        .borders(data.theme.borders())
         .border_style(data.theme.border_style(false))
         .border_type(ratatui::widgets::BorderType::Rounded);
 
@@ -2997,7 +4648,7 @@ fn render_status_bar(frame: &mut Frame, area: Rect, data: &RenderData) {
                 .fg(Color::Magenta)
                 .add_modifier(Modifier::BOLD),
         ),
-        Span::raw("│"),
+        Span::raw(data.theme.separator()),
         Span::styled(
             format!(" {} ", status_text),
             if data.is_processing {
@@ -3006,12 +4657,12 @@ fn render_status_bar(frame: &mut Frame, area: Rect, data: &RenderData) {
                 data.theme.muted_style()
             },
         ),
-        Span::raw("│"),
+        Span::raw(data.theme.separator()),
         Span::styled(format!(" {} ", tools_info), data.theme.muted_style()),
     ];
 
     if !raptor_info.is_empty() {
-        spans.push(Span::raw("│"));
+        spans.push(Span::raw(data.theme.separator()));
         spans.push(Span::styled(
             format!(" {} ", raptor_info),
             if data.raptor_indexing {
@@ -3022,15 +4673,57 @@ fn render_status_bar(frame: &mut Frame, area: Rect, data: &RenderData) {
         ));
     }
 
+    if data.dry_run {
+        spans.push(Span::raw(data.theme.separator()));
+        spans.push(Span::styled(
+            " 🧪 Simulación ",
+            Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+        ));
+    }
+
+    // Privacy level and network policy, when they restrict what leaves the machine
+    if data.privacy != PrivacyLevel::Full || data.network_strict {
+        let mut privacy_info = if data.privacy != PrivacyLevel::Full {
+            data.privacy.label().to_string()
+        } else {
+            "🛡️ strict".to_string()
+        };
+        if data.privacy != PrivacyLevel::Full && data.network_strict {
+            privacy_info.push_str(" 🛡️");
+        }
+        if data.blocked_outbound > 0 {
+            privacy_info.push_str(&format!(" ⛔{}", data.blocked_outbound));
+        }
+        spans.push(Span::raw(data.theme.separator()));
+        spans.push(Span::styled(format!(" {} ", privacy_info), Style::default().fg(Color::Cyan)));
+    }
+
+    // Context window used by the last request
+    if let Some(budget) = &data.context_budget {
+        let percent = budget.percent();
+        let color = if budget.overflow() > 0 || percent >= context_budget::FULL_PERCENT {
+            Color::Red
+        } else if percent >= context_budget::WARN_PERCENT {
+            Color::Yellow
+        } else {
+            Color::Green
+        };
+        spans.push(Span::raw(data.theme.separator()));
+        spans.push(Span::styled(
+            format!(" ctx {} {}% ", budget.meter(6), percent),
+            Style::default().fg(color),
+        ));
+    }
+
     // Show scroll indicator when user has manually scrolled (auto_scroll disabled)
     if !data.auto_scroll {
-        spans.push(Span::raw("│"));
+        spans.push(Span::raw(data.theme.separator()));
         spans.push(Span::styled(
             " Scroll ",
             data.theme.muted_style(),
         ));
         // Add a short hint
-        spans.push(Span::raw("│"));
+        spans.push(Span::raw(data.theme.separator()));
         spans.push(Span::styled(
             "Tip: Use End to resume",
             data.theme.muted_style(),
@@ -3040,7 +4733,7 @@ fn render_status_bar(frame: &mut Frame, area: Rect, data: &RenderData) {
     // Shortcuts hint
     spans.push(Span::raw(" "));
     spans.push(Span::styled(
-        "^N:modo  ^C×2:salir",
+        "^N:modo  ^B:contexto  ^C×2:salir",
         Style::default().fg(Color::DarkGray),
     ));
 
@@ -3049,6 +4742,35 @@ fn render_status_bar(frame: &mut Frame, area: Rect, data: &RenderData) {
     frame.render_widget(Paragraph::new(line).style(data.theme.base_style()), area);
 }
 
+fn render_context_budget_popup(frame: &mut Frame, area: Rect, data: &RenderData) {
+    let modal_area = centered_rect(60, 50, area);
+    frame.render_widget(Clear, modal_area);
+
+    let mut content = vec![Line::from("")];
+    match &data.context_budget {
+        Some(budget) => content.extend(
+            budget
+                .breakdown()
+                .into_iter()
+                .map(|line| Line::from(Span::styled(format!("  {}", line), data.theme.base_style()))),
+        ),
+        None => content.push(Line::from(Span::styled(
+            "  Aún no se ha enviado ninguna petición al modelo",
+            data.theme.muted_style(),
+        ))),
+    }
+    content.push(Line::from(""));
+    content.push(Line::from(Span::styled("  Esc / Ctrl+B: cerrar", data.theme.muted_style())));
+
+    let block = Block::default()
+        .borders(data.theme.borders())
+        .border_style(data.theme.primary_style())
+        .border_type(ratatui::widgets::BorderType::Rounded)
+        .title(Span::styled(" Ventana de contexto ", data.theme.primary_style()))
+        .style(data.theme.base_style());
+    frame.render_widget(Paragraph::new(content).block(block).wrap(Wrap { trim: false }), modal_area);
+}
+
 fn render_indexing_prompt_modal(frame: &mut Frame, area: Rect, data: &RenderData) {
     let modal_area = centered_rect(70, 60, area);
     frame.render_widget(Clear, modal_area);
@@ -3161,7 +4883,7 @@ fn render_indexing_prompt_modal(frame: &mut Frame, area: Rect, data: &RenderData
     ]));
 
     let block = Block::default()
-        .borders(Borders::ALL)
+        .borders(data.theme.borders())
         .border_style(data.theme.primary_style())
         .border_type(ratatui::widgets::BorderType::Rounded)
         .title(Span::styled(
@@ -3220,7 +4942,7 @@ fn render_confirmation_modal(frame: &mut Frame, area: Rect, data: &RenderData) {
     ];
 
     let block = Block::default()
-        .borders(Borders::ALL)
+        .borders(data.theme.borders())
         .border_style(data.theme.warning_style())
         .border_type(ratatui::widgets::BorderType::Double)
         .title(Span::styled(
@@ -3237,6 +4959,100 @@ fn render_confirmation_modal(frame: &mut Frame, area: Rect, data: &RenderData) {
     );
 }
 
+fn render_config_reload_modal(frame: &mut Frame, area: Rect, data: &RenderData) {
+    let modal_area = centered_rect(60, 40, area);
+    frame.render_widget(Clear, modal_area);
+
+    let mut content = vec![
+        Line::from(""),
+        Line::from(Span::styled(
+            "  La configuración cambió y requiere reconectar:",
+            data.theme.base_style(),
+        )),
+        Line::from(""),
+    ];
+    for change in &data.pending_reload_changes {
+        content.push(Line::from(Span::styled(
+            format!("  • {}", change),
+            data.theme.code_style(),
+        )));
+    }
+    content.push(Line::from(""));
+    content.push(Line::from(vec![
+        Span::styled(
+            " [Y] ",
+            data.theme.success_style().add_modifier(Modifier::BOLD),
+        ),
+        Span::styled("Aplicar ahora", data.theme.success_style()),
+        Span::raw("    "),
+        Span::styled(
+            " [N] ",
+            data.theme.muted_style().add_modifier(Modifier::BOLD),
+        ),
+        Span::styled("Al reiniciar", data.theme.muted_style()),
+    ]));
+
+    let block = Block::default()
+        .borders(data.theme.borders())
+        .border_style(data.theme.warning_style())
+        .border_type(ratatui::widgets::BorderType::Rounded)
+        .title(Span::styled(
+            " Configuración recargada ",
+            data.theme.warning_style().add_modifier(Modifier::BOLD),
+        ))
+        .style(data.theme.base_style());
+
+    frame.render_widget(
+        Paragraph::new(content)
+            .block(block)
+            .wrap(Wrap { trim: false }),
+        modal_area,
+    );
+}
+
+fn render_message_actions_modal(frame: &mut Frame, area: Rect, data: &RenderData) {
+    let modal_area = centered_rect(40, 40, area);
+    frame.render_widget(Clear, modal_area);
+
+    let mut content = vec![Line::from("")];
+    for action in MessageAction::ALL {
+        let is_selected = action == data.message_action_selected;
+        let cursor = if is_selected { "▸ " } else { "  " };
+        content.push(Line::from(vec![
+            Span::styled(
+                format!("  {}", cursor),
+                if is_selected {
+                    data.theme.accent_style()
+                } else {
+                    data.theme.muted_style()
+                },
+            ),
+            Span::styled(
+                action.display_name(),
+                if is_selected {
+                    data.theme.primary_style().add_modifier(Modifier::BOLD)
+                } else {
+                    data.theme.base_style()
+                },
+            ),
+        ]));
+    }
+    content.push(Line::from(""));
+    content.push(Line::from(Span::styled(
+        "  ↑↓ elegir · Enter aplicar · Esc cancelar",
+        data.theme.muted_style(),
+    )));
+
+    let block = Block::default()
+        .borders(data.theme.borders())
+        .border_style(data.theme.border_style(true))
+        .border_type(ratatui::widgets::BorderType::Rounded)
+        .title(Span::styled(" Acciones del mensaje ", data.theme.primary_style()))
+        .style(data.theme.base_style());
+
+    frame.render_widget(Paragraph::new(content).block(block), modal_area);
+}
+
 fn render_password_modal(frame: &mut Frame, area: Rect, data: &RenderData) {
     let modal_area = centered_rect(50, 25, area);
     frame.render_widget(Clear, modal_area);
@@ -3272,7 +5088,7 @@ fn render_password_modal(frame: &mut Frame, area: Rect, data: &RenderData) {
     }
 
     let block = Block::default()
-        .borders(Borders::ALL)
+        .borders(data.theme.borders())
         .border_style(data.theme.warning_style())
         .border_type(ratatui::widgets::BorderType::Double)
         .title(Span::styled(
@@ -3295,27 +5111,60 @@ fn render_autocomplete_popup(frame: &mut Frame, input_area: Rect, data: &RenderD
         // Code commands
         ("/code-review", "Análisis integral de código (linter + analyzer + deps)"),
         ("/analyze", "Análisis profundo de código y métricas"),
-        ("/refactor", "Refactorización de código (próximamente)"),
+        ("/callgraph", "Quién llama a una función y a qué llama"),
+        ("/deadcode", "Funciones y tipos sin referencias, por lenguaje"),
+        ("/refactor", "Refactorización de código; rename_symbol renombra con el AST"),
+        ("/replace", "Reemplazo en todo el proyecto con checklist y deshacer"),
         ("/format", "Formatear código con formatters automáticos"),
         ("/docs", "Generar documentación del proyecto"),
+        ("/estimate", "Archivos, tests y esfuerzo (S/M/L) de un cambio propuesto"),
         
         // Testing
         ("/test", "Ejecutar tests con detección automática"),
+        ("/profile", "Perfilar un comando y sugerir optimizaciones de sus puntos calientes"),
         
         // Git
         ("/commit", "Crear commit con mensaje auto-generado"),
         ("/commit-push-pr", "Commit, push y crear PR"),
+        ("/owners", "Quién conoce mejor un archivo o directorio; revisores sugeridos"),
         
         // Context
         ("/deps", "Analizar dependencias del proyecto"),
         ("/search", "Buscar en código con regex"),
         ("/context", "Ver información del proyecto"),
+        ("/preview", "Vista previa de CSV/JSONL/Parquet con estadísticas"),
+        ("/schema", "Ingerir el esquema de la base de datos al contexto"),
+        ("/remember", "Recordar un dato del proyecto para futuras sesiones"),
+        ("/memories", "Memorias del proyecto; /memories forget <id> olvida una"),
+        ("/examples", "Ejemplos de respuesta que el modelo imita; /examples save guarda la última"),
+        ("/artifacts", "Salidas largas guardadas; /artifacts open <id> las muestra, @artifact:<id> las adjunta"),
+        ("/why", "Explica la ruta y los filtros de recuperación de la última consulta"),
+        ("/sources", "Fuentes de la última respuesta; /sources irrelevant <n> penaliza una"),
+        ("/tag", "Etiquetar la sesión actual (/tag rm <etiqueta> la quita)"),
+        ("/export", "Exportar la sesión a HTML para compartir (/export md para Markdown)"),
+        ("/sessions", "Buscar sesiones pasadas por texto y #etiqueta"),
+        ("/retry", "Regenerar la última respuesta y ver qué cambió"),
+        ("/translate", "Ver la última respuesta en otro idioma (/translate off la devuelve)"),
         
         // System
         ("/plan", "Generar plan de ejecución (próximamente)"),
         ("/shell", "Ejecutar comando shell con seguridad"),
+        ("/env", "Variables de entorno de la sesión (set/unset/list)"),
+        ("/logs", "Seguir y resumir logs (--diagnose para analizarlos)"),
+        ("/tfplan", "Resumir un plan de Terraform (nunca aplica)"),
+        ("/new", "Crear un proyecto desde una plantilla (compila e indexa)"),
+        ("/recipe", "Ejecutar una receta de varios pasos (.neuro/recipes)"),
+        ("/start", "Empezar desde una plantilla de conversación; /start save guarda la actual"),
+        ("/config", "Capas de configuración; /config doctor revisa conflictos"),
+        ("/network", "Política de red saliente y peticiones bloqueadas"),
+        ("/preferences", "Preferencias de estilo de código y respuestas (globales o --project)"),
+        ("/tools", "Herramientas del modelo; activar o desactivar por proyecto"),
+        ("/cd", "Cambiar el directorio de trabajo de la sesión"),
+        ("/roots", "Raíces del espacio de trabajo; /roots add añade una de solo lectura"),
         ("/reindex", "Reconstruir índice RAPTOR"),
+        ("/continue", "Retomar el turno que se quedó sin tiempo"),
         ("/mode", "Cambiar modo del agente (próximamente)"),
+        ("/dryrun", "Simular: las herramientas describen lo que harían sin ejecutarlo"),
         ("/help", "Mostrar ayuda de comandos"),
         
         // Legacy
@@ -3401,7 +5250,7 @@ fn render_autocomplete_popup(frame: &mut Frame, input_area: Rect, data: &RenderD
     
     let title = format!(" Comandos ({}/{}) ", selected + 1, total_items);
     let block = Block::default()
-        .borders(Borders::ALL)
+        .borders(data.theme.borders())
         .border_style(data.theme.primary_style())
         .border_type(ratatui::widgets::BorderType::Rounded)
         .title(Span::styled(title, data.theme.primary_style()))
@@ -3415,3 +5264,193 @@ fn render_autocomplete_popup(frame: &mut Frame, input_area: Rect, data: &RenderD
         popup_area,
     );
 }
+
+/// Snapshot tests of full-screen rendering through ratatui's `TestBackend`.
+///
+/// Every scene pins the clock-driven fields (spinner, tick counter, timers) so
+/// the buffers are deterministic; review changes with `cargo insta review`.
+#[cfg(test)]
+mod tests_render {
+    use super::*;
+    use crate::config::AppConfig;
+    use ratatui::backend::TestBackend;
+
+    const WIDTH: u16 = 100;
+    const HEIGHT: u16 = 30;
+
+    fn message(sender: MessageSender, content: &str) -> DisplayMessage {
+        DisplayMessage {
+            sender,
+            content: content.to_string(),
+            timestamp: Instant::now(),
+            is_streaming: false,
+            tool_name: None,
+            reasoning: None,
+            translations: Translations::default(),
+        }
+    }
+
+    fn render_data<'a>(
+        messages: &'a [DisplayMessage],
+        message_layout: &'a RefCell<MessageLayoutCache>,
+        model_config_panel: &'a ModelConfigPanel,
+    ) -> RenderData<'a> {
+        let settings = SettingsPanel::new();
+        RenderData {
+            theme: Theme::default(),
+            screen: AppScreen::Chat,
+            status_render: StatusIndicator::new().render(),
+            status_message: "Ready".to_string(),
+            messages,
+            message_layout,
+            archived_messages: 0,
+            selected_message: None,
+            message_action_selected: MessageAction::Copy,
+            input_buffer: String::new(),
+            scroll_offset: 0,
+            is_processing: false,
+            processing_start: None,
+            spinner_frame: String::new(),
+            settings_tools: settings.tools.clone(),
+            settings_selected: 0,
+            model_config_panel,
+            pending_command: None,
+            password_input_len: 0,
+            password_error: None,
+            enabled_tools_count: settings.get_enabled_tools().len(),
+            raptor_indexing: false,
+            raptor_status: None,
+            raptor_progress: None,
+            raptor_stage: None,
+            raptor_start_time: None,
+            input_mode: InputMode::Question,
+            tick_counter: 0,
+            indexing_prompt_selected: IndexingOption::RagNow,
+            indexing_prompt_dont_ask: false,
+            show_autocomplete: false,
+            autocomplete_selected: 0,
+            auto_scroll: true,
+            tab_titles: Vec::new(),
+            active_tab: 0,
+            pending_reload_changes: Vec::new(),
+            privacy: PrivacyLevel::Full,
+            network_strict: false,
+            dry_run: false,
+            blocked_outbound: 0,
+            context_budget: None,
+            show_context_budget: false,
+            show_reasoning: false,
+            pager: None,
+            session_browser: None,
+        }
+    }
+
+    fn render(data: &RenderData) -> Terminal<TestBackend> {
+        let mut terminal = Terminal::new(TestBackend::new(WIDTH, HEIGHT)).unwrap();
+        terminal.draw(|frame| render_ui(frame, data)).unwrap();
+        terminal
+    }
+
+    fn conversation() -> Vec<DisplayMessage> {
+        vec![
+            message(MessageSender::User, "Explain how the router picks a model for a query"),
+            message(
+                MessageSender::Assistant,
+                &"The router classifies each query before answering. Simple questions go to the fast model, \
+                  while anything that needs tools or long reasoning goes to the heavy model. "
+                    .repeat(3),
+            ),
+            message(MessageSender::System, "Indexed 42 files in 1.2s"),
+        ]
+    }
+
+    #[test]
+    fn snapshot_chat_with_long_messages() {
+        let messages = conversation();
+        let layout = RefCell::new(MessageLayoutCache::new());
+        let panel = ModelConfigPanel::new(AppConfig::default());
+        let data = render_data(&messages, &layout, &panel);
+        insta::assert_snapshot!(render(&data).backend());
+    }
+
+    #[test]
+    fn snapshot_autocomplete_popup() {
+        let messages = conversation();
+        let layout = RefCell::new(MessageLayoutCache::new());
+        let panel = ModelConfigPanel::new(AppConfig::default());
+        let data = RenderData {
+            input_buffer: "/co".to_string(),
+            show_autocomplete: true,
+            autocomplete_selected: 1,
+            ..render_data(&messages, &layout, &panel)
+        };
+        insta::assert_snapshot!(render(&data).backend());
+    }
+
+    #[test]
+    fn snapshot_confirmation_modal() {
+        let messages = conversation();
+        let layout = RefCell::new(MessageLayoutCache::new());
+        let panel = ModelConfigPanel::new(AppConfig::default());
+        let data = RenderData {
+            screen: AppScreen::Confirmation,
+            pending_command: Some("kubectl delete pod api-7d9f --namespace staging".to_string()),
+            ..render_data(&messages, &layout, &panel)
+        };
+        insta::assert_snapshot!(render(&data).backend());
+    }
+
+    #[test]
+    fn snapshot_password_modal() {
+        let messages = conversation();
+        let layout = RefCell::new(MessageLayoutCache::new());
+        let panel = ModelConfigPanel::new(AppConfig::default());
+        let data = RenderData {
+            screen: AppScreen::Password,
+            pending_command: Some("sudo systemctl restart nginx".to_string()),
+            password_input_len: 6,
+            password_error: Some("Incorrect password".to_string()),
+            ..render_data(&messages, &layout, &panel)
+        };
+        insta::assert_snapshot!(render(&data).backend());
+    }
+
+    #[test]
+    fn snapshot_message_actions_modal() {
+        let messages = conversation();
+        let layout = RefCell::new(MessageLayoutCache::new());
+        let panel = ModelConfigPanel::new(AppConfig::default());
+        let data = RenderData {
+            screen: AppScreen::MessageActions,
+            selected_message: Some(1),
+            ..render_data(&messages, &layout, &panel)
+        };
+        insta::assert_snapshot!(render(&data).backend());
+    }
+
+    #[test]
+    fn snapshot_indexing_prompt() {
+        let messages = Vec::new();
+        let layout = RefCell::new(MessageLayoutCache::new());
+        let panel = ModelConfigPanel::new(AppConfig::default());
+        let data = RenderData {
+            screen: AppScreen::IndexingPrompt,
+            indexing_prompt_selected: IndexingOption::RaptorOnly,
+            ..render_data(&messages, &layout, &panel)
+        };
+        insta::assert_snapshot!(render(&data).backend());
+    }
+
+    #[test]
+    fn snapshot_settings_screen() {
+        let messages = Vec::new();
+        let layout = RefCell::new(MessageLayoutCache::new());
+        let panel = ModelConfigPanel::new(AppConfig::default());
+        let data = RenderData {
+            screen: AppScreen::Settings,
+            settings_selected: 2,
+            ..render_data(&messages, &layout, &panel)
+        };
+        insta::assert_snapshot!(render(&data).backend());
+    }
+}
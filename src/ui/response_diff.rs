@@ -0,0 +1,274 @@
+//! Diff between an answer and its regeneration (/retry)
+//!
+//! Lines are compared as a whole, so code blocks show exactly which lines
+//! changed. A prose line replaced by another one is shown once, with the
+//! words that changed marked inline as `[-old-]{+new+}`, since reflowed
+//! paragraphs rarely keep a line intact.
+
+/// What happened to a line of the previous answer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineChange {
+    Same,
+    Removed,
+    Added,
+    /// Prose line rewritten; its text carries the inline word changes
+    Edited,
+}
+
+impl LineChange {
+    /// Prefix of the line in the rendered diff
+    pub fn marker(self) -> char {
+        match self {
+            LineChange::Same => ' ',
+            LineChange::Removed => '-',
+            LineChange::Added => '+',
+            LineChange::Edited => '~',
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiffLine {
+    pub change: LineChange,
+    pub text: String,
+}
+
+/// Line by line changes from one answer to the next
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ResponseDiff {
+    pub lines: Vec<DiffLine>,
+}
+
+impl ResponseDiff {
+    pub fn between(old: &str, new: &str) -> Self {
+        let old_lines: Vec<&str> = old.lines().collect();
+        let new_lines: Vec<&str> = new.lines().collect();
+        let (old_code, new_code) = (code_lines(&old_lines), code_lines(&new_lines));
+
+        let mut lines = Vec::new();
+        let mut removed: Vec<usize> = Vec::new();
+        let mut added: Vec<usize> = Vec::new();
+        for op in edit_script(&old_lines, &new_lines) {
+            match op {
+                Op::Keep(i) => {
+                    flush(
+                        &mut lines,
+                        &mut removed,
+                        &mut added,
+                        &old_lines,
+                        &new_lines,
+                        &old_code,
+                        &new_code,
+                    );
+                    lines.push(DiffLine {
+                        change: LineChange::Same,
+                        text: old_lines[i].to_string(),
+                    });
+                }
+                Op::Remove(i) => removed.push(i),
+                Op::Add(j) => added.push(j),
+            }
+        }
+        flush(
+            &mut lines,
+            &mut removed,
+            &mut added,
+            &old_lines,
+            &new_lines,
+            &old_code,
+            &new_code,
+        );
+        Self { lines }
+    }
+
+    pub fn is_unchanged(&self) -> bool {
+        self.lines
+            .iter()
+            .all(|line| line.change == LineChange::Same)
+    }
+
+    /// Lines added, removed and edited
+    pub fn counts(&self) -> (usize, usize, usize) {
+        let count = |change| {
+            self.lines
+                .iter()
+                .filter(|line| line.change == change)
+                .count()
+        };
+        (
+            count(LineChange::Added),
+            count(LineChange::Removed),
+            count(LineChange::Edited),
+        )
+    }
+
+    /// One line per diff line, prefixed with its marker
+    pub fn render(&self) -> String {
+        self.lines
+            .iter()
+            .map(|line| format!("{} {}", line.change.marker(), line.text))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+/// Turn a run of removed and added lines into diff lines, pairing prose
+/// lines into edits
+fn flush(
+    lines: &mut Vec<DiffLine>,
+    removed: &mut Vec<usize>,
+    added: &mut Vec<usize>,
+    old_lines: &[&str],
+    new_lines: &[&str],
+    old_code: &[bool],
+    new_code: &[bool],
+) {
+    let pairs = removed.len().min(added.len());
+    let mut extra_removed = Vec::new();
+    let mut extra_added = Vec::new();
+    for (&i, &j) in removed.iter().zip(added.iter()) {
+        if old_code[i] || new_code[j] {
+            extra_removed.push(i);
+            extra_added.push(j);
+        } else {
+            lines.push(DiffLine {
+                change: LineChange::Edited,
+                text: word_diff(old_lines[i], new_lines[j]),
+            });
+        }
+    }
+    extra_removed.extend(&removed[pairs..]);
+    extra_added.extend(&added[pairs..]);
+    lines.extend(extra_removed.into_iter().map(|i| DiffLine {
+        change: LineChange::Removed,
+        text: old_lines[i].to_string(),
+    }));
+    lines.extend(extra_added.into_iter().map(|j| DiffLine {
+        change: LineChange::Added,
+        text: new_lines[j].to_string(),
+    }));
+    removed.clear();
+    added.clear();
+}
+
+/// Which lines are inside a fenced code block, fences included
+fn code_lines(lines: &[&str]) -> Vec<bool> {
+    let mut inside = false;
+    lines
+        .iter()
+        .map(|line| {
+            if line.trim_start().starts_with("```") {
+                inside = !inside;
+                true
+            } else {
+                inside
+            }
+        })
+        .collect()
+}
+
+/// `new` with the words of `old` it dropped as `[-…-]` and its new ones as `{+…+}`
+fn word_diff(old: &str, new: &str) -> String {
+    let old_words: Vec<&str> = old.split_whitespace().collect();
+    let new_words: Vec<&str> = new.split_whitespace().collect();
+    let mut parts: Vec<String> = Vec::new();
+    let mut removed: Vec<&str> = Vec::new();
+    let mut added: Vec<&str> = Vec::new();
+    let flush = |parts: &mut Vec<String>, removed: &mut Vec<&str>, added: &mut Vec<&str>| {
+        if !removed.is_empty() {
+            parts.push(format!("[-{}-]", removed.join(" ")));
+            removed.clear();
+        }
+        if !added.is_empty() {
+            parts.push(format!("{{+{}+}}", added.join(" ")));
+            added.clear();
+        }
+    };
+    for op in edit_script(&old_words, &new_words) {
+        match op {
+            Op::Keep(i) => {
+                flush(&mut parts, &mut removed, &mut added);
+                parts.push(old_words[i].to_string());
+            }
+            Op::Remove(i) => removed.push(old_words[i]),
+            Op::Add(j) => added.push(new_words[j]),
+        }
+    }
+    flush(&mut parts, &mut removed, &mut added);
+    parts.join(" ")
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Op {
+    Keep(usize),
+    Remove(usize),
+    Add(usize),
+}
+
+/// Shortest edit script from `old` to `new` (longest common subsequence)
+fn edit_script<T: PartialEq>(old: &[T], new: &[T]) -> Vec<Op> {
+    let (n, m) = (old.len(), new.len());
+    // common[i][j]: length of the LCS of old[i..] and new[j..]
+    let mut common = vec![vec![0usize; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            common[i][j] = if old[i] == new[j] {
+                common[i + 1][j + 1] + 1
+            } else {
+                common[i + 1][j].max(common[i][j + 1])
+            };
+        }
+    }
+
+    let mut ops = Vec::with_capacity(n.max(m));
+    let (mut i, mut j) = (0, 0);
+    while i < n && j < m {
+        if old[i] == new[j] {
+            ops.push(Op::Keep(i));
+            i += 1;
+            j += 1;
+        } else if common[i + 1][j] >= common[i][j + 1] {
+            ops.push(Op::Remove(i));
+            i += 1;
+        } else {
+            ops.push(Op::Add(j));
+            j += 1;
+        }
+    }
+    ops.extend((i..n).map(Op::Remove));
+    ops.extend((j..m).map(Op::Add));
+    ops
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prose_edits_and_code_lines() {
+        let old = "The cache is cleared on startup.\n\n```rust\nlet a = 1;\nlet b = 2;\n```";
+        let new =
+            "The cache is cleared on every startup.\n\n```rust\nlet a = 1;\nlet b = 3;\n```\nDone.";
+        let diff = ResponseDiff::between(old, new);
+
+        assert_eq!(diff.lines[0].change, LineChange::Edited);
+        assert_eq!(
+            diff.lines[0].text,
+            "The cache is cleared on {+every+} startup."
+        );
+        let rendered = diff.render();
+        assert!(rendered.contains("- let b = 2;\n+ let b = 3;"));
+        assert!(rendered.ends_with("+ Done."));
+        assert_eq!(diff.counts(), (2, 1, 1));
+        assert!(!diff.is_unchanged());
+    }
+
+    #[test]
+    fn test_unchanged_and_word_replacements() {
+        assert!(ResponseDiff::between("same\ntext", "same\ntext").is_unchanged());
+        assert_eq!(
+            word_diff("use a mutex here", "use an RwLock here"),
+            "use [-a mutex-] {+an RwLock+} here"
+        );
+    }
+}
@@ -3,6 +3,11 @@
 use super::animations::StatusIndicator;
 use super::theme::Icons;
 use crate::i18n::{t, Text};
+use crate::tools::{ToolListing, ToolOrigin};
+use std::collections::BTreeSet;
+
+/// Icon of the entries added for WASM plugins
+const PLUGIN_ICON: &str = "🧩";
 
 /// Tool configuration for settings panel
 #[derive(Debug, Clone)]
@@ -63,7 +68,7 @@ impl SettingsPanel {
         vec![
             // File System
             ToolConfig {
-                id: "file_read".to_string(),
+                id: "read_file".to_string(),
                 name: t(Text::ToolFileRead).to_string(),
                 description: t(Text::ToolFileReadDesc).to_string(),
                 enabled: true,
@@ -71,7 +76,7 @@ impl SettingsPanel {
                 category: ToolCategory::FileSystem,
             },
             ToolConfig {
-                id: "file_write".to_string(),
+                id: "write_file".to_string(),
                 name: t(Text::ToolFileWrite).to_string(),
                 description: t(Text::ToolFileWriteDesc).to_string(),
                 enabled: true,
@@ -79,7 +84,7 @@ impl SettingsPanel {
                 category: ToolCategory::FileSystem,
             },
             ToolConfig {
-                id: "list_dir".to_string(),
+                id: "list_directory".to_string(),
                 name: t(Text::ToolListDir).to_string(),
                 description: t(Text::ToolListDirDesc).to_string(),
                 enabled: true,
@@ -87,7 +92,7 @@ impl SettingsPanel {
                 category: ToolCategory::FileSystem,
             },
             ToolConfig {
-                id: "indexer".to_string(),
+                id: "index_project".to_string(),
                 name: t(Text::ToolIndexer).to_string(),
                 description: t(Text::ToolIndexerDesc).to_string(),
                 enabled: true,
@@ -95,7 +100,7 @@ impl SettingsPanel {
                 category: ToolCategory::FileSystem,
             },
             ToolConfig {
-                id: "search".to_string(),
+                id: "search_in_files".to_string(),
                 name: t(Text::ToolSearch).to_string(),
                 description: t(Text::ToolSearchDesc).to_string(),
                 enabled: true,
@@ -104,7 +109,7 @@ impl SettingsPanel {
             },
             // Execution
             ToolConfig {
-                id: "shell_exec".to_string(),
+                id: "execute_shell".to_string(),
                 name: t(Text::ToolShellExec).to_string(),
                 description: t(Text::ToolShellExecDesc).to_string(),
                 enabled: true,
@@ -112,7 +117,7 @@ impl SettingsPanel {
                 category: ToolCategory::Execution,
             },
             ToolConfig {
-                id: "shell_advanced".to_string(),
+                id: "execute_shell".to_string(),
                 name: t(Text::ToolShellAdvanced).to_string(),
                 description: t(Text::ToolShellAdvancedDesc).to_string(),
                 enabled: true,
@@ -120,7 +125,7 @@ impl SettingsPanel {
                 category: ToolCategory::Execution,
             },
             ToolConfig {
-                id: "test_runner".to_string(),
+                id: "run_tests".to_string(),
                 name: t(Text::ToolTestRunner).to_string(),
                 description: t(Text::ToolTestRunnerDesc).to_string(),
                 enabled: true,
@@ -129,7 +134,7 @@ impl SettingsPanel {
             },
             // Analysis
             ToolConfig {
-                id: "linter".to_string(),
+                id: "run_linter".to_string(),
                 name: t(Text::ToolLinter).to_string(),
                 description: t(Text::ToolLinterDesc).to_string(),
                 enabled: true,
@@ -137,7 +142,7 @@ impl SettingsPanel {
                 category: ToolCategory::Analysis,
             },
             ToolConfig {
-                id: "analyzer".to_string(),
+                id: "analyze_code".to_string(),
                 name: t(Text::ToolAnalyzer).to_string(),
                 description: t(Text::ToolAnalyzerDesc).to_string(),
                 enabled: true,
@@ -145,7 +150,7 @@ impl SettingsPanel {
                 category: ToolCategory::Analysis,
             },
             ToolConfig {
-                id: "formatter".to_string(),
+                id: "format_code".to_string(),
                 name: t(Text::ToolFormatter).to_string(),
                 description: t(Text::ToolFormatterDesc).to_string(),
                 enabled: true,
@@ -153,7 +158,7 @@ impl SettingsPanel {
                 category: ToolCategory::Analysis,
             },
             ToolConfig {
-                id: "refactor".to_string(),
+                id: "refactor_code".to_string(),
                 name: t(Text::ToolRefactor).to_string(),
                 description: t(Text::ToolRefactorDesc).to_string(),
                 enabled: true,
@@ -162,7 +167,7 @@ impl SettingsPanel {
             },
             // Git
             ToolConfig {
-                id: "git".to_string(),
+                id: "git_tool".to_string(),
                 name: t(Text::ToolGit).to_string(),
                 description: t(Text::ToolGitDesc).to_string(),
                 enabled: true,
@@ -171,7 +176,7 @@ impl SettingsPanel {
             },
             // Project
             ToolConfig {
-                id: "dependencies".to_string(),
+                id: "analyze_dependencies".to_string(),
                 name: t(Text::ToolDependencies).to_string(),
                 description: t(Text::ToolDependenciesDesc).to_string(),
                 enabled: true,
@@ -179,7 +184,7 @@ impl SettingsPanel {
                 category: ToolCategory::Project,
             },
             ToolConfig {
-                id: "documentation".to_string(),
+                id: "generate_documentation".to_string(),
                 name: t(Text::ToolDocumentation).to_string(),
                 description: t(Text::ToolDocumentationDesc).to_string(),
                 enabled: true,
@@ -187,7 +192,7 @@ impl SettingsPanel {
                 category: ToolCategory::Project,
             },
             ToolConfig {
-                id: "context".to_string(),
+                id: "project_context".to_string(),
                 name: t(Text::ToolContext).to_string(),
                 description: t(Text::ToolContextDesc).to_string(),
                 enabled: true,
@@ -196,7 +201,7 @@ impl SettingsPanel {
             },
             // Utilities
             ToolConfig {
-                id: "http".to_string(),
+                id: "http_request".to_string(),
                 name: t(Text::ToolHttp).to_string(),
                 description: t(Text::ToolHttpDesc).to_string(),
                 enabled: true,
@@ -212,7 +217,7 @@ impl SettingsPanel {
                 category: ToolCategory::Utilities,
             },
             ToolConfig {
-                id: "environment".to_string(),
+                id: "environment_info".to_string(),
                 name: t(Text::ToolEnvironment).to_string(),
                 description: t(Text::ToolEnvironmentDesc).to_string(),
                 enabled: true,
@@ -220,7 +225,7 @@ impl SettingsPanel {
                 category: ToolCategory::Utilities,
             },
             ToolConfig {
-                id: "planner".to_string(),
+                id: "task_planner".to_string(),
                 name: t(Text::ToolPlanner).to_string(),
                 description: t(Text::ToolPlannerDesc).to_string(),
                 enabled: true,
@@ -248,10 +253,38 @@ impl SettingsPanel {
         }
     }
 
-    pub fn toggle_selected(&mut self) {
-        if let Some(tool) = self.tools.get_mut(self.selected_index) {
-            tool.enabled = !tool.enabled;
+    /// Toggle the selected tool; returns its id and new state to persist
+    pub fn toggle_selected(&mut self) -> Option<(String, bool)> {
+        let tool = self.tools.get(self.selected_index)?;
+        let (id, enabled) = (tool.id.clone(), !tool.enabled);
+        // Entries that share a tool switch together
+        for tool in self.tools.iter_mut().filter(|t| t.id == id) {
+            tool.enabled = enabled;
+        }
+        Some((id, enabled))
+    }
+
+    /// Match the switches to the tools turned off for the project and add the plugins
+    pub fn sync_tools(&mut self, listing: &[ToolListing], disabled: &BTreeSet<String>) {
+        self.tools = Self::default_tools();
+        self.tools.extend(
+            listing
+                .iter()
+                .filter(|tool| tool.origin == ToolOrigin::Plugin)
+                .map(|tool| ToolConfig {
+                    id: tool.name.clone(),
+                    name: tool.name.clone(),
+                    description: format!("[{}] {}", tool.origin.label(), tool.description),
+                    enabled: true,
+                    icon: PLUGIN_ICON,
+                    category: ToolCategory::Utilities,
+                }),
+        );
+        for tool in &mut self.tools {
+            tool.enabled = !disabled.contains(&tool.id);
         }
+        self.selected_index = self.selected_index.min(self.tools.len().saturating_sub(1));
+        self.scroll_offset = self.scroll_offset.min(self.selected_index);
     }
 
     pub fn get_enabled_tools(&self) -> Vec<&ToolConfig> {
@@ -294,15 +327,15 @@ impl SettingsPanel {
 
     /// Update tool names based on current locale
     pub fn refresh_locale(&mut self) {
-        // Re-create tools with updated locale strings
-        let enabled_states: Vec<bool> = self.tools.iter().map(|t| t.enabled).collect();
+        // Re-create tools with updated locale strings, keeping the plugins
+        let disabled: BTreeSet<String> = self.tools.iter().filter(|t| !t.enabled).map(|t| t.id.clone()).collect();
+        let plugins: Vec<ToolConfig> = self.tools.iter().filter(|t| t.icon == PLUGIN_ICON).cloned().collect();
         self.tools = Self::default_tools();
+        self.tools.extend(plugins);
 
         // Restore enabled states
-        for (i, enabled) in enabled_states.into_iter().enumerate() {
-            if let Some(tool) = self.tools.get_mut(i) {
-                tool.enabled = enabled;
-            }
+        for tool in &mut self.tools {
+            tool.enabled = !disabled.contains(&tool.id);
         }
     }
 }
@@ -368,6 +401,26 @@ mod tests {
         assert_eq!(panel.tools[0].enabled, initial);
     }
 
+    #[test]
+    fn test_sync_tools_mirrors_project_state() {
+        let mut panel = SettingsPanel::new();
+        let listing = vec![ToolListing {
+            name: "word_count".to_string(),
+            description: "Count words".to_string(),
+            origin: ToolOrigin::Plugin,
+        }];
+        let disabled: BTreeSet<String> = ["execute_shell".to_string(), "word_count".to_string()].into();
+
+        panel.sync_tools(&listing, &disabled);
+        assert!(!panel.is_tool_enabled("execute_shell"));
+        assert!(panel.is_tool_enabled("read_file"));
+        let plugin = panel.tools.last().unwrap();
+        assert_eq!((plugin.id.as_str(), plugin.enabled), ("word_count", false));
+
+        panel.selected_index = panel.tools.len() - 1;
+        assert_eq!(panel.toggle_selected(), Some(("word_count".to_string(), true)));
+    }
+
     #[test]
     fn test_get_enabled_tools() {
         let mut panel = SettingsPanel::new();
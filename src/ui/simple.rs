@@ -0,0 +1,388 @@
+//! Simple mode (`neuro --simple`): a line-based REPL on stdin/stdout
+//!
+//! For SSH sessions and CI, where ratatui has no usable terminal. Every line
+//! goes to the router as the TUI would send it, slash commands included;
+//! answers are printed to stdout as they stream in, while status lines and
+//! errors go to stderr, so a script can pipe questions in and keep only the
+//! answers. Input ends at EOF or with `/exit`; Ctrl+C stops the running
+//! request. Commands that need confirmation are only run when someone at a
+//! terminal answers the prompt; with piped input they are cancelled.
+//!
+//! `neuro -p "<question>"` runs a single turn the same way and fails when it
+//! does, for shell pipelines and git hooks. With `--output json` nothing is
+//! printed while the turn runs; stdout gets a single JSON object with the
+//! answer, the tool calls, token usage and timing, for other tools to read.
+//!
+//! `-p` and a `--simple` run fed from a pipe are batch runs: the
+//! `batch-finished` webhook is sent when they end.
+
+use super::history_archive::{ArchivedMessage, HistoryArchive};
+use crate::agent::webhooks::{self, TaskEvent, WebhookEventKind};
+use crate::agent::{
+    AgentEvent, OrchestratorResponse, RouterOrchestrator, StructuredResponse, Subscription, ToolCallRecord, Topic,
+};
+use crate::i18n::{t, Text};
+use crate::log_error;
+use anyhow::anyhow;
+use std::io::{IsTerminal, Write};
+use std::time::Instant;
+use tokio::io::{AsyncBufReadExt, BufReader, Lines, Stdin};
+use tokio_util::sync::CancellationToken;
+
+/// Events queued between the router and the terminal
+const EVENT_BUFFER: usize = 256;
+
+pub struct SimpleRepl {
+    router: RouterOrchestrator,
+    events: Subscription,
+    input: Lines<BufReader<Stdin>>,
+    /// Stdin is a terminal: show a prompt and ask before running commands
+    interactive: bool,
+    /// Print the tool calls of each turn with their output before the answer
+    show_tools: bool,
+    /// Print nothing while the turn runs, then the whole turn as JSON
+    json: bool,
+    archive: Option<HistoryArchive>,
+}
+
+impl SimpleRepl {
+    pub fn new(router: RouterOrchestrator) -> Self {
+        let events = router
+            .event_bus()
+            .subscribe("simple", EVENT_BUFFER, &[Topic::Turn, Topic::Progress, Topic::Stream]);
+        Self {
+            router,
+            events,
+            input: BufReader::new(tokio::io::stdin()).lines(),
+            interactive: std::io::stdin().is_terminal(),
+            show_tools: false,
+            json: false,
+            archive: None,
+        }
+    }
+
+    pub fn show_tools(mut self, show: bool) -> Self {
+        self.show_tools = show;
+        self
+    }
+
+    /// Print the answer of `run_once` as a JSON object
+    pub fn json_output(mut self, json: bool) -> Self {
+        self.json = json;
+        self
+    }
+
+    /// Store the conversation in a session, as the TUI does, for /sessions
+    pub fn with_archive(mut self, archive: Option<HistoryArchive>) -> Self {
+        self.archive = archive;
+        self
+    }
+
+    pub async fn run(mut self) -> anyhow::Result<()> {
+        if self.interactive {
+            eprintln!("neuro (modo simple) · /help lista los comandos, /exit o Ctrl+D para salir");
+        }
+        let started = Instant::now();
+        let (mut requests, mut failed) = (0, 0);
+        loop {
+            // Results of background tasks finished since the last request
+            while let Ok(event) = self.events.try_recv() {
+                if let Err(e) = print_event(event, &mut String::new(), true) {
+                    eprintln!("❌ {}", e);
+                }
+            }
+            if self.interactive {
+                print!("> ");
+                std::io::stdout().flush()?;
+            }
+
+            let line = tokio::select! {
+                line = self.input.next_line() => line?,
+                _ = tokio::signal::ctrl_c() => None,
+            };
+            let Some(line) = line else { break };
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            if matches!(line, "/exit" | "/quit") {
+                break;
+            }
+            requests += 1;
+            if let Err(e) = self.turn(line).await {
+                failed += 1;
+                eprintln!("❌ {}", e);
+            }
+        }
+        if self.interactive {
+            eprintln!();
+        } else if requests > 0 {
+            webhooks::deliver(
+                TaskEvent::new(WebhookEventKind::BatchFinished, failed == 0, "neuro --simple", started.elapsed())
+                    .with_summary(format!("{} peticiones, {} fallidas", requests, failed)),
+            )
+            .await;
+        }
+        Ok(())
+    }
+
+    /// Answer `prompt` and return; `Err` when the turn failed
+    pub async fn run_once(mut self, prompt: &str) -> anyhow::Result<()> {
+        let prompt = prompt.trim();
+        let started = Instant::now();
+        let result = self.turn(prompt).await;
+        if self.json {
+            println!("{}", serde_json::to_string_pretty(&json_output(prompt, &result))?);
+        }
+        let summary = match &result {
+            Ok(_) => prompt.chars().take(200).collect(),
+            Err(e) => e.to_string(),
+        };
+        webhooks::deliver(
+            TaskEvent::new(WebhookEventKind::BatchFinished, result.is_ok(), "neuro -p", started.elapsed())
+                .with_summary(summary),
+        )
+        .await;
+        result.map(|_| ())
+    }
+
+    /// Send `line` to the router and print the answer; the answer text with
+    /// what was recorded while producing it
+    async fn turn(&mut self, line: &str) -> anyhow::Result<(Option<String>, StructuredResponse)> {
+        self.record("user", line).await;
+        self.events.clear();
+
+        let cancel = CancellationToken::new();
+        let mut streamed = String::new();
+        let result = {
+            let request = self.router.process_structured(line, cancel.clone());
+            tokio::pin!(request);
+            loop {
+                tokio::select! {
+                    result = &mut request => break result,
+                    Some(event) = self.events.recv() => print_event(event, &mut streamed, !self.json)?,
+                    _ = tokio::signal::ctrl_c() => cancel.cancel(),
+                }
+            }
+        };
+        // A /cd moves the router-level context too
+        self.router.sync_working_dir().await;
+
+        let structured = result?;
+        if self.show_tools && !self.json {
+            for call in &structured.tool_calls {
+                println!("{}", format_tool_call(call));
+            }
+        }
+        let answer = self.answer(structured.response.clone(), streamed, cancel).await?;
+        if let Some(answer) = &answer {
+            self.record("assistant", answer).await;
+        }
+        Ok((answer, structured))
+    }
+
+    /// Print `response` to the end (the rest of a stream, a confirmed command);
+    /// the answer text, if there is one
+    async fn answer(
+        &mut self,
+        mut response: OrchestratorResponse,
+        mut streamed: String,
+        cancel: CancellationToken,
+    ) -> anyhow::Result<Option<String>> {
+        loop {
+            match response {
+                OrchestratorResponse::Streaming { .. } => {
+                    self.finish_stream(&mut streamed, &cancel).await?;
+                    return Ok(Some(streamed).filter(|text| !text.is_empty()));
+                }
+                OrchestratorResponse::NeedsConfirmation { command, risk_level } => {
+                    eprintln!("⚠ $ {} (riesgo: {})", command, risk_level);
+                    if !self.confirmed().await {
+                        self.router.cancel_pending().await;
+                        return Err(anyhow!("Command not confirmed: {}", command));
+                    }
+                    response = self.router.confirm_pending(cancel.clone()).await?;
+                }
+                response => {
+                    let text = response_text(&response).map_err(|message| anyhow!(message))?;
+                    if text.is_empty() {
+                        return Ok(None);
+                    }
+                    if !self.json {
+                        println!("{}", text);
+                    }
+                    return Ok(Some(text));
+                }
+            }
+        }
+    }
+
+    /// Print chunks until the stream ends, is cut off or Ctrl+C stops it
+    async fn finish_stream(&mut self, streamed: &mut String, cancel: &CancellationToken) -> anyhow::Result<()> {
+        let ended = loop {
+            let event = tokio::select! {
+                event = self.events.recv() => event,
+                _ = tokio::signal::ctrl_c() => {
+                    cancel.cancel();
+                    break Err(anyhow!("Answer interrupted"));
+                }
+            };
+            match event {
+                None | Some(AgentEvent::StreamEnd) => break Ok(()),
+                Some(AgentEvent::StreamAborted { reason, .. }) => break Err(anyhow!("Answer cut off: {}", reason)),
+                Some(event) => {
+                    if let Err(e) = print_event(event, streamed, !self.json) {
+                        break Err(e);
+                    }
+                }
+            }
+        };
+        if !self.json && !streamed.is_empty() && !streamed.ends_with('\n') {
+            println!();
+        }
+        ended
+    }
+
+    /// Ask at the terminal; piped input never confirms
+    async fn confirmed(&mut self) -> bool {
+        if !self.interactive {
+            eprintln!("Sin terminal para confirmar");
+            return false;
+        }
+        eprint!("{} [y/N] ", t(Text::ConfirmCommand));
+        let answer = self.input.next_line().await.ok().flatten().unwrap_or_default();
+        matches!(answer.trim().to_lowercase().as_str(), "y" | "yes" | "s" | "si" | "sí")
+    }
+
+    async fn record(&mut self, role: &str, content: &str) {
+        let Some(archive) = self.archive.as_mut() else { return };
+        let message = ArchivedMessage {
+            role: role.to_string(),
+            content: content.to_string(),
+            tool_name: None,
+        };
+        // Nothing stays in memory: every message is archived as soon as it is complete
+        if let Err(e) = archive.archive(vec![message]).await {
+            log_error!("Failed to store message: {}", e);
+        }
+    }
+}
+
+/// Print an event of the running request: chunks to stdout (collected in
+/// `streamed`; only collected without `echo`) and status lines to stderr;
+/// errors are returned
+fn print_event(event: AgentEvent, streamed: &mut String, echo: bool) -> anyhow::Result<()> {
+    match event {
+        AgentEvent::Chunk(chunk) => {
+            if echo {
+                print!("{}", chunk);
+                // Line buffering would hold tokens back until the next newline
+                let _ = std::io::stdout().flush();
+            }
+            streamed.push_str(&chunk);
+        }
+        AgentEvent::Status(status) | AgentEvent::RaptorStatus(status) => eprintln!("· {}", status),
+        AgentEvent::Error(error) | AgentEvent::Response(Err(error)) => return Err(error.into()),
+        AgentEvent::Response(Ok(response)) => {
+            let text = response_text(&response).map_err(|message| anyhow!(message))?;
+            if !text.is_empty() && echo {
+                println!("{}", text);
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// A tool call of the turn and what it returned, for `--show-tools`
+fn format_tool_call(call: &ToolCallRecord) -> String {
+    let status = if call.success { "✓" } else { "✗" };
+    format!("{} {} {}\n{}\n", status, call.tool, call.args, call.result.trim_end())
+}
+
+/// `--output json` of a single turn
+fn json_output(prompt: &str, result: &anyhow::Result<(Option<String>, StructuredResponse)>) -> serde_json::Value {
+    match result {
+        Ok((answer, structured)) => serde_json::json!({
+            "prompt": prompt,
+            "success": true,
+            "answer": answer,
+            "model": structured.model,
+            "tool_calls": structured.tool_calls,
+            "citations": structured.citations,
+            "usage": structured.usage,
+            "timing": structured.timing,
+            "reasoning": structured.reasoning,
+        }),
+        Err(e) => serde_json::json!({
+            "prompt": prompt,
+            "success": false,
+            "error": e.to_string(),
+        }),
+    }
+}
+
+/// Text printed for a finished response; `Err` for errors
+fn response_text(response: &OrchestratorResponse) -> Result<String, String> {
+    match response {
+        OrchestratorResponse::Immediate { content, .. } => Ok(content.clone()),
+        OrchestratorResponse::Text(text) => Ok(text.clone()),
+        OrchestratorResponse::ToolResult { result, success: true, .. } => Ok(result.clone()),
+        OrchestratorResponse::ToolResult { tool_name, result, .. } => Err(format!("{}: {}", tool_name, result)),
+        OrchestratorResponse::Delegated { description, .. } | OrchestratorResponse::TaskStarted { description, .. } => {
+            Ok(format!("⏳ {}", description))
+        }
+        OrchestratorResponse::NeedsConfirmation { command, risk_level } => {
+            Ok(format!("$ {} (riesgo: {})", command, risk_level))
+        }
+        OrchestratorResponse::Streaming { .. } => Ok(String::new()),
+        OrchestratorResponse::Error(message) => Err(message.clone()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_response_text() {
+        let text = OrchestratorResponse::Immediate {
+            content: "hola".to_string(),
+            model: "qwen3:8b".to_string(),
+        };
+        assert_eq!(response_text(&text), Ok("hola".to_string()));
+
+        let failed = OrchestratorResponse::ToolResult {
+            tool_name: "shell".to_string(),
+            result: "exit 1".to_string(),
+            success: false,
+        };
+        assert_eq!(response_text(&failed), Err("shell: exit 1".to_string()));
+        assert!(response_text(&OrchestratorResponse::Error("timeout".to_string())).is_err());
+    }
+
+    #[test]
+    fn test_format_tool_call() {
+        let call = ToolCallRecord {
+            tool: "read_file".to_string(),
+            args: serde_json::json!({ "path": "src/main.rs" }),
+            result: "fn main() {}\n".to_string(),
+            success: true,
+            duration_ms: 3,
+        };
+        assert_eq!(format_tool_call(&call), "✓ read_file {\"path\":\"src/main.rs\"}\nfn main() {}\n");
+    }
+
+    #[test]
+    fn test_json_output() {
+        let structured = StructuredResponse::plain(OrchestratorResponse::Text("4".to_string()));
+        let value = json_output("2+2", &Ok((Some("4".to_string()), structured)));
+        assert_eq!(value["answer"], "4");
+        assert_eq!(value["usage"]["prompt_tokens"], 0);
+        assert!(value["tool_calls"].as_array().unwrap().is_empty());
+
+        let value = json_output("2+2", &Err(anyhow!("Answer cut off: stalled")));
+        assert_eq!(value["success"], false);
+        assert_eq!(value["error"], "Answer cut off: stalled");
+    }
+}
@@ -1,6 +1,8 @@
 //! Modern theme system for the TUI
 
+use super::accessibility::AccessibilityConfig;
 use ratatui::style::{Color, Modifier, Style};
+use ratatui::widgets::Borders;
 
 /// Color palette for the application
 #[derive(Debug, Clone)]
@@ -32,6 +34,10 @@ pub struct Theme {
     pub assistant_message: Color,
     pub system_message: Color,
     pub tool_message: Color,
+
+    // Accessibility
+    pub high_visibility: bool,
+    pub plain: bool,
 }
 
 impl Theme {
@@ -65,6 +71,9 @@ impl Theme {
             assistant_message: Color::Rgb(220, 220, 240),
             system_message: Color::Rgb(150, 150, 170),
             tool_message: Color::Rgb(180, 255, 200),
+
+            high_visibility: false,
+            plain: false,
         }
     }
 
@@ -93,6 +102,9 @@ impl Theme {
             assistant_message: Color::Rgb(40, 40, 60),
             system_message: Color::Rgb(100, 100, 120),
             tool_message: Color::Rgb(50, 130, 80),
+
+            high_visibility: false,
+            plain: false,
         }
     }
 
@@ -121,6 +133,34 @@ impl Theme {
             assistant_message: Color::White,
             system_message: Color::Gray,
             tool_message: Color::Green,
+
+            high_visibility: false,
+            plain: false,
+        }
+    }
+
+    /// Apply the accessibility settings on top of the palette
+    pub fn with_accessibility(mut self, config: &AccessibilityConfig) -> Self {
+        self.high_visibility = config.high_visibility;
+        self.plain = config.plain;
+        self
+    }
+
+    /// Borders of panels and modals: none in plain mode
+    pub fn borders(&self) -> Borders {
+        if self.plain {
+            Borders::NONE
+        } else {
+            Borders::ALL
+        }
+    }
+
+    /// Separator between status bar segments
+    pub fn separator(&self) -> &'static str {
+        if self.plain {
+            " | "
+        } else {
+            "│"
         }
     }
 
@@ -159,6 +199,11 @@ impl Theme {
     }
 
     pub fn border_style(&self, focused: bool) -> Style {
+        if focused && self.high_visibility {
+            return Style::default()
+                .fg(Color::Yellow)
+                .add_modifier(Modifier::BOLD | Modifier::REVERSED);
+        }
         Style::default().fg(if focused {
             self.border_focused
         } else {
@@ -167,6 +212,9 @@ impl Theme {
     }
 
     pub fn selection_style(&self) -> Style {
+        if self.high_visibility {
+            return Style::default().add_modifier(Modifier::BOLD | Modifier::REVERSED);
+        }
         Style::default().bg(self.selection)
     }
 
@@ -327,6 +375,17 @@ mod tests {
         assert!(style.fg.is_some());
     }
 
+    #[test]
+    fn test_plain_mode_drops_box_drawing() {
+        let theme = Theme::dark().with_accessibility(&AccessibilityConfig {
+            plain: true,
+            ..Default::default()
+        });
+        assert_eq!(theme.borders(), Borders::NONE);
+        assert!(theme.separator().is_ascii());
+        assert_eq!(Theme::dark().borders(), Borders::ALL);
+    }
+
     #[test]
     fn test_all_themes() {
         let _ = Theme::dark();
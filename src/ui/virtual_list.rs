@@ -0,0 +1,257 @@
+//! Virtualized layout for the chat message list
+//!
+//! Long sessions used to re-wrap every message on every frame. This module
+//! caches the wrapped row count of each message for the current width, so a
+//! frame only has to build and wrap the lines of the messages on screen.
+
+use std::time::Instant;
+
+use ratatui::text::Line;
+use ratatui::widgets::{Paragraph, Wrap};
+
+/// Identity of a message layout. A message is re-measured when any of these change.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct LayoutKey {
+    timestamp: Instant,
+    content_len: usize,
+    is_streaming: bool,
+}
+
+/// Portion of the message list that intersects the viewport
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VisibleWindow {
+    /// Index of the first message to render
+    pub first: usize,
+    /// One past the index of the last message to render
+    pub last: usize,
+    /// Rows of `first` that are above the viewport and must be skipped
+    pub skip_rows: usize,
+}
+
+/// Cache of wrapped row counts per message, invalidated on resize
+#[derive(Debug, Default)]
+pub struct MessageLayoutCache {
+    width: u16,
+    keys: Vec<LayoutKey>,
+    rows: Vec<usize>,
+    total_rows: usize,
+}
+
+impl MessageLayoutCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Drop every cached entry
+    pub fn invalidate(&mut self) {
+        self.keys.clear();
+        self.rows.clear();
+        self.total_rows = 0;
+    }
+
+    /// Refresh the cache for `width`, measuring only messages that are new or changed.
+    ///
+    /// `entries` yields `(timestamp, content_len, is_streaming)` for each message and
+    /// `measure(index)` returns the wrapped row count of the message at `index`.
+    pub fn update<I, F>(&mut self, width: u16, entries: I, mut measure: F)
+    where
+        I: IntoIterator<Item = (Instant, usize, bool)>,
+        F: FnMut(usize) -> usize,
+    {
+        if width != self.width {
+            self.width = width;
+            self.invalidate();
+        }
+
+        let mut count = 0;
+        for (index, (timestamp, content_len, is_streaming)) in entries.into_iter().enumerate() {
+            let key = LayoutKey {
+                timestamp,
+                content_len,
+                is_streaming,
+            };
+            count = index + 1;
+
+            if index < self.keys.len() {
+                if self.keys[index] != key {
+                    let rows = measure(index);
+                    self.total_rows = self.total_rows - self.rows[index] + rows;
+                    self.keys[index] = key;
+                    self.rows[index] = rows;
+                }
+            } else {
+                let rows = measure(index);
+                self.total_rows += rows;
+                self.keys.push(key);
+                self.rows.push(rows);
+            }
+        }
+
+        // Messages were removed: forget their entries
+        if count < self.keys.len() {
+            let removed: usize = self.rows[count..].iter().sum();
+            self.total_rows -= removed;
+            self.keys.truncate(count);
+            self.rows.truncate(count);
+        }
+    }
+
+    /// Forget the first `count` entries after those messages left the list,
+    /// so the remaining ones keep their cached heights
+    pub fn drop_front(&mut self, count: usize) {
+        let count = count.min(self.rows.len());
+        let removed: usize = self.rows[..count].iter().sum();
+        self.total_rows -= removed;
+        self.keys.drain(..count);
+        self.rows.drain(..count);
+    }
+
+    /// Total wrapped rows of all cached messages
+    pub fn total_rows(&self) -> usize {
+        self.total_rows
+    }
+
+    /// First row of the message at `index` (or the end of the list if out of range)
+    pub fn row_offset(&self, index: usize) -> usize {
+        self.rows.iter().take(index).sum()
+    }
+
+    /// Find the messages that intersect `[scroll, scroll + viewport)`
+    pub fn window(&self, scroll: usize, viewport: usize) -> VisibleWindow {
+        let mut first = self.rows.len();
+        let mut skip_rows = 0;
+        let mut row = 0;
+
+        for (index, rows) in self.rows.iter().enumerate() {
+            if row + rows > scroll {
+                first = index;
+                skip_rows = scroll - row;
+                break;
+            }
+            row += rows;
+        }
+
+        let end = scroll + viewport;
+        let mut last = first;
+        while last < self.rows.len() && row < end {
+            row += self.rows[last];
+            last += 1;
+        }
+
+        VisibleWindow {
+            first,
+            last,
+            skip_rows,
+        }
+    }
+}
+
+/// Rows a line occupies once wrapped to `width` columns, using the same word
+/// wrapping as the chat paragraph
+pub fn wrapped_rows(line: &Line, width: usize) -> usize {
+    let width = width.clamp(1, u16::MAX as usize) as u16;
+    Paragraph::new(line.clone())
+        .wrap(Wrap { trim: false })
+        .line_count(width)
+        .max(1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entries(lens: &[usize], stamps: &[Instant]) -> Vec<(Instant, usize, bool)> {
+        lens.iter()
+            .zip(stamps)
+            .map(|(len, ts)| (*ts, *len, false))
+            .collect()
+    }
+
+    #[test]
+    fn test_only_changed_messages_are_measured() {
+        let stamps = [Instant::now(), Instant::now(), Instant::now()];
+        let mut cache = MessageLayoutCache::new();
+        let mut measured = Vec::new();
+
+        cache.update(80, entries(&[10, 20, 30], &stamps), |i| {
+            measured.push(i);
+            i + 1
+        });
+        assert_eq!(measured, vec![0, 1, 2]);
+        assert_eq!(cache.total_rows(), 6);
+
+        measured.clear();
+        cache.update(80, entries(&[10, 20, 35], &stamps), |i| {
+            measured.push(i);
+            5
+        });
+        assert_eq!(measured, vec![2]);
+        assert_eq!(cache.total_rows(), 8);
+    }
+
+    #[test]
+    fn test_resize_invalidates_cache() {
+        let stamps = [Instant::now(), Instant::now()];
+        let mut cache = MessageLayoutCache::new();
+        cache.update(80, entries(&[10, 20], &stamps), |_| 1);
+
+        let mut measured = 0;
+        cache.update(40, entries(&[10, 20], &stamps), |_| {
+            measured += 1;
+            2
+        });
+        assert_eq!(measured, 2);
+        assert_eq!(cache.total_rows(), 4);
+    }
+
+    #[test]
+    fn test_drop_front_keeps_remaining_heights() {
+        let stamps = [Instant::now(); 3];
+        let mut cache = MessageLayoutCache::new();
+        cache.update(80, entries(&[1, 2, 3], &stamps), |i| i + 1);
+
+        cache.drop_front(2);
+        assert_eq!(cache.total_rows(), 3);
+
+        let mut measured = 0;
+        cache.update(80, entries(&[3], &stamps), |_| {
+            measured += 1;
+            1
+        });
+        assert_eq!(measured, 0);
+    }
+
+    #[test]
+    fn test_window_skips_offscreen_messages() {
+        let stamps = [Instant::now(); 4];
+        let mut cache = MessageLayoutCache::new();
+        // Rows: [0..3) [3..8) [8..10) [10..14)
+        let heights = [3, 5, 2, 4];
+        cache.update(80, entries(&[1, 2, 3, 4], &stamps), |i| heights[i]);
+
+        let window = cache.window(4, 5);
+        assert_eq!(
+            window,
+            VisibleWindow {
+                first: 1,
+                last: 3,
+                skip_rows: 1
+            }
+        );
+
+        assert_eq!(cache.row_offset(2), 8);
+
+        let window = cache.window(0, 100);
+        assert_eq!(window.first, 0);
+        assert_eq!(window.last, 4);
+    }
+
+    #[test]
+    fn test_wrapped_rows() {
+        assert_eq!(wrapped_rows(&Line::from(""), 10), 1);
+        assert_eq!(wrapped_rows(&Line::from("abcde"), 10), 1);
+        assert_eq!(wrapped_rows(&Line::from("abcdefghijk"), 10), 2);
+        // Words are not split: 16 characters take three rows of 10
+        assert_eq!(wrapped_rows(&Line::from("aaa bbbbbbbb ccc"), 10), 3);
+    }
+}